@@ -0,0 +1,107 @@
+-- Migration: Employer accounts, job ownership, and denormalized application counters
+-- Employer dashboards show per-job applicant counts; computing these with
+-- GROUP BY over application_tracking on every request does not scale, so the
+-- counts are kept in job_application_stats and maintained by triggers.
+-- The scheduler periodically reconciles them against the source table.
+
+-- User roles
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'user_role') THEN
+        CREATE TYPE user_role AS ENUM ('job_seeker', 'employer', 'admin');
+    END IF;
+END $$;
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role user_role NOT NULL DEFAULT 'job_seeker';
+
+-- Job ownership (NULL for seeded/imported jobs)
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS posted_by UUID REFERENCES users(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_posted_by ON jobs(posted_by);
+
+-- Denormalized per-job application counters
+CREATE TABLE IF NOT EXISTS job_application_stats (
+    job_id INTEGER PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+    applicant_count INTEGER NOT NULL DEFAULT 0,
+    new_count INTEGER NOT NULL DEFAULT 0,
+    in_review_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Adjusts the counters for a single application row.
+-- 'applied' counts as new; 'in_review' and 'interview' count as in review.
+CREATE OR REPLACE FUNCTION adjust_job_application_stats(p_job_id INTEGER, p_status TEXT, p_delta INTEGER)
+RETURNS VOID AS $$
+BEGIN
+    -- The job may already be gone when this runs from a cascading delete
+    INSERT INTO job_application_stats (job_id, applicant_count, new_count, in_review_count)
+    SELECT p_job_id, 0, 0, 0 WHERE EXISTS (SELECT 1 FROM jobs WHERE id = p_job_id)
+    ON CONFLICT (job_id) DO NOTHING;
+
+    UPDATE job_application_stats
+    SET applicant_count = applicant_count + p_delta,
+        new_count = new_count + CASE WHEN p_status = 'applied' THEN p_delta ELSE 0 END,
+        in_review_count = in_review_count + CASE WHEN p_status IN ('in_review', 'interview') THEN p_delta ELSE 0 END,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE job_id = p_job_id;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION track_job_application_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM adjust_job_application_stats(NEW.job_id, NEW.status, 1);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF NEW.job_id IS DISTINCT FROM OLD.job_id OR NEW.status IS DISTINCT FROM OLD.status THEN
+            PERFORM adjust_job_application_stats(OLD.job_id, OLD.status, -1);
+            PERFORM adjust_job_application_stats(NEW.job_id, NEW.status, 1);
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM adjust_job_application_stats(OLD.job_id, OLD.status, -1);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS track_job_application_stats ON application_tracking;
+CREATE TRIGGER track_job_application_stats
+    AFTER INSERT OR UPDATE OR DELETE ON application_tracking
+    FOR EACH ROW
+    EXECUTE FUNCTION track_job_application_stats();
+
+-- Recomputes all counters from application_tracking.
+-- Returns the number of jobs whose counters were corrected.
+CREATE OR REPLACE FUNCTION reconcile_job_application_stats()
+RETURNS INTEGER AS $$
+DECLARE
+    corrected INTEGER;
+BEGIN
+    WITH actual AS (
+        SELECT j.id AS job_id,
+               COUNT(a.id)::INTEGER AS applicant_count,
+               COUNT(a.id) FILTER (WHERE a.status = 'applied')::INTEGER AS new_count,
+               COUNT(a.id) FILTER (WHERE a.status IN ('in_review', 'interview'))::INTEGER AS in_review_count
+        FROM jobs j
+        LEFT JOIN application_tracking a ON a.job_id = j.id
+        GROUP BY j.id
+    ),
+    upserted AS (
+        INSERT INTO job_application_stats (job_id, applicant_count, new_count, in_review_count, updated_at)
+        SELECT job_id, applicant_count, new_count, in_review_count, CURRENT_TIMESTAMP FROM actual
+        ON CONFLICT (job_id) DO UPDATE
+        SET applicant_count = EXCLUDED.applicant_count,
+            new_count = EXCLUDED.new_count,
+            in_review_count = EXCLUDED.in_review_count,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE job_application_stats.applicant_count <> EXCLUDED.applicant_count
+           OR job_application_stats.new_count <> EXCLUDED.new_count
+           OR job_application_stats.in_review_count <> EXCLUDED.in_review_count
+        RETURNING 1
+    )
+    SELECT COUNT(*)::INTEGER INTO corrected FROM upserted;
+    RETURN corrected;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Backfill
+SELECT reconcile_job_application_stats();
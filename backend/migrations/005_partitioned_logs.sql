@@ -0,0 +1,88 @@
+-- Migration: Monthly-partitioned tables for high-volume logs
+-- Activity events, AI usage records, and audit logs grow without bound.
+-- Each table is range-partitioned by month on created_at. The scheduler
+-- creates upcoming partitions ahead of time and archives cold ones to
+-- compressed exports before dropping them.
+
+-- Creates the monthly partition containing month_start if it does not exist.
+-- Returns the partition name (<parent>_pYYYYMM).
+CREATE OR REPLACE FUNCTION create_monthly_partition(parent TEXT, month_start DATE)
+RETURNS TEXT AS $$
+DECLARE
+    partition_start DATE := date_trunc('month', month_start)::DATE;
+    partition_name TEXT := parent || '_p' || to_char(partition_start, 'YYYYMM');
+BEGIN
+    EXECUTE format(
+        'CREATE TABLE IF NOT EXISTS %I PARTITION OF %I FOR VALUES FROM (%L) TO (%L)',
+        partition_name,
+        parent,
+        partition_start,
+        (partition_start + INTERVAL '1 month')::DATE
+    );
+    RETURN partition_name;
+END;
+$$ LANGUAGE plpgsql;
+
+-- User-facing activity events (feeds, analytics)
+CREATE TABLE IF NOT EXISTS activity_events (
+    id BIGSERIAL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_activity_events_user_id ON activity_events(user_id, created_at DESC);
+
+-- Per-call AI usage records
+CREATE TABLE IF NOT EXISTS ai_usage_logs (
+    id BIGSERIAL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(100) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    model VARCHAR(100),
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    latency_ms INTEGER,
+    success BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_ai_usage_logs_user_id ON ai_usage_logs(user_id, created_at DESC);
+
+-- Security and administrative audit trail
+CREATE TABLE IF NOT EXISTS audit_logs (
+    id BIGSERIAL,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(100) NOT NULL,
+    target_type VARCHAR(100),
+    target_id VARCHAR(255),
+    metadata JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_actor_id ON audit_logs(actor_id, created_at DESC);
+
+-- Default partitions catch rows outside any monthly range so inserts never fail
+CREATE TABLE IF NOT EXISTS activity_events_default PARTITION OF activity_events DEFAULT;
+CREATE TABLE IF NOT EXISTS ai_usage_logs_default PARTITION OF ai_usage_logs DEFAULT;
+CREATE TABLE IF NOT EXISTS audit_logs_default PARTITION OF audit_logs DEFAULT;
+
+-- Initial partitions: current month and the next two
+DO $$
+DECLARE
+    parent TEXT;
+    offset_months INTEGER;
+BEGIN
+    FOREACH parent IN ARRAY ARRAY['activity_events', 'ai_usage_logs', 'audit_logs'] LOOP
+        FOR offset_months IN 0..2 LOOP
+            PERFORM create_monthly_partition(
+                parent,
+                (date_trunc('month', CURRENT_DATE) + make_interval(months => offset_months))::DATE
+            );
+        END LOOP;
+    END LOOP;
+END $$;
@@ -0,0 +1,15 @@
+-- Migration: Indexes for known hot query paths
+-- Identified from slow query logs and EXPLAIN ANALYZE sampling.
+
+-- Roadmap listing filters by user and orders by newest first.
+-- The composite index supersedes the single-column user_id index.
+CREATE INDEX IF NOT EXISTS idx_roadmaps_user_created ON career_roadmaps(user_id, created_at DESC);
+DROP INDEX IF EXISTS idx_roadmaps_user_id;
+
+-- Skill containment/overlap lookups on user profiles (skills @> / &&)
+CREATE INDEX IF NOT EXISTS idx_users_skills_gin ON users USING GIN (skills);
+
+-- Full-text search over job postings
+CREATE INDEX IF NOT EXISTS idx_jobs_fulltext ON jobs USING GIN (
+    to_tsvector('english', job_title || ' ' || company || ' ' || job_description)
+);
@@ -0,0 +1,39 @@
+-- Migration: Billing plans, Stripe subscriptions, and daily AI quotas
+
+CREATE TABLE IF NOT EXISTS plans (
+    id VARCHAR(50) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    stripe_price_id VARCHAR(255),
+    monthly_price_cents INTEGER NOT NULL DEFAULT 0,
+    ai_daily_quota INTEGER NOT NULL CHECK (ai_daily_quota > 0),
+    premium_templates BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- stripe_price_id is environment-specific; set it per deployment, e.g.
+-- UPDATE plans SET stripe_price_id = 'price_...' WHERE id = 'premium';
+INSERT INTO plans (id, name, monthly_price_cents, ai_daily_quota, premium_templates)
+VALUES
+    ('free', 'Free', 0, 20, FALSE),
+    ('premium', 'Premium', 499, 200, TRUE)
+ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    plan_id VARCHAR(50) NOT NULL REFERENCES plans(id),
+    stripe_customer_id VARCHAR(255),
+    stripe_subscription_id VARCHAR(255) UNIQUE,
+    status VARCHAR(50) NOT NULL,
+    current_period_end TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Per-user AI request counter, one row per day
+CREATE TABLE IF NOT EXISTS ai_daily_usage (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    usage_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, usage_date)
+);
@@ -0,0 +1,42 @@
+-- Migration: Usage-based credits ledger for AI features
+
+-- One row per user; balance is the sum of the user's credit_transactions
+CREATE TABLE IF NOT EXISTS credit_accounts (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    balance INTEGER NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    referral_code VARCHAR(16) UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Append-only history: positive amounts are earnings/refunds, negative are spends
+CREATE TABLE IF NOT EXISTS credit_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount INTEGER NOT NULL CHECK (amount <> 0),
+    reason VARCHAR(50) NOT NULL,
+    reference VARCHAR(255),
+    balance_after INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_credit_transactions_user ON credit_transactions(user_id, created_at DESC);
+
+-- Earnings are granted at most once per (user, reason, reference)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_credit_transactions_once
+    ON credit_transactions(user_id, reason, reference)
+    WHERE reason IN ('signup_bonus', 'referral', 'achievement');
+
+-- Credits charged per AI action
+CREATE TABLE IF NOT EXISTS credit_action_costs (
+    action VARCHAR(50) PRIMARY KEY,
+    cost INTEGER NOT NULL CHECK (cost >= 0)
+);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES
+    ('extract_skills', 2),
+    ('generate_roadmap', 5),
+    ('ask_question', 1),
+    ('generate_content', 2)
+ON CONFLICT (action) DO NOTHING;
@@ -0,0 +1,53 @@
+-- Migration: Company accounts with per-seat recruiter roles
+-- A company groups several employer users around one job pipeline. Each
+-- member holds one seat with a role: owners manage the team, recruiters post
+-- and manage jobs, viewers have read-only access to the dashboard.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'seat_role') THEN
+        CREATE TYPE seat_role AS ENUM ('owner', 'recruiter', 'viewer');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS companies (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A user holds at most one seat
+CREATE TABLE IF NOT EXISTS company_members (
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    role seat_role NOT NULL,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (company_id, user_id)
+);
+
+-- Only the SHA-256 of the invitation token is stored
+CREATE TABLE IF NOT EXISTS company_invitations (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role seat_role NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_company_invitations_pending
+    ON company_invitations(company_id, LOWER(email))
+    WHERE accepted_at IS NULL;
+
+-- Jobs posted from a seat belong to the company pipeline
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_company_id ON jobs(company_id);
+
+-- Per-seat activity attribution: actor_id is the seat holder, company_id the team
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS company_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_audit_logs_company_id ON audit_logs(company_id, created_at DESC);
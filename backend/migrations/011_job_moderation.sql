@@ -0,0 +1,24 @@
+-- Migration: Job posting moderation
+-- Employer posts start as 'pending' and only become visible to job seekers
+-- once an admin approves them. Existing, seeded, and imported jobs default
+-- to 'approved'. Each post is pre-screened for scam indicators and the
+-- result is stored for the moderator.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'moderation_status') THEN
+        CREATE TYPE moderation_status AS ENUM ('pending', 'approved', 'rejected');
+    END IF;
+END $$;
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS moderation_status moderation_status NOT NULL DEFAULT 'approved';
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS moderation_reason TEXT;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS moderated_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS moderated_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS scam_risk_score INTEGER;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS screening JSONB;
+
+-- Moderation queue, riskiest first
+CREATE INDEX IF NOT EXISTS idx_jobs_pending_moderation
+    ON jobs(scam_risk_score DESC NULLS FIRST, created_at)
+    WHERE moderation_status = 'pending';
@@ -0,0 +1,22 @@
+-- Migration: Geocoded job locations for radius search
+-- Coordinates are filled in by the application (on posting and by a
+-- scheduler backfill); geocoded_at marks rows that have been attempted so
+-- unresolvable locations are not retried on every run.
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS geocoded_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS is_remote BOOLEAN
+    GENERATED ALWAYS AS (location ~* '(remote|work from home|anywhere)') STORED;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_coordinates ON jobs(latitude, longitude) WHERE latitude IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_remote ON jobs(is_remote) WHERE is_remote;
+
+-- Provider lookups keyed by normalized place name; NULL coordinates cache a miss
+CREATE TABLE IF NOT EXISTS geocode_cache (
+    query VARCHAR(255) PRIMARY KEY,
+    latitude DOUBLE PRECISION,
+    longitude DOUBLE PRECISION,
+    display_name TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,12 @@
+-- Migration: Job posting expiry
+-- Employer posts get an expires_at (JOB_EXPIRY_DAYS after posting by
+-- default). Expired jobs drop out of search and matching immediately; the
+-- scheduler then marks them closed and notifies the employer to renew.
+-- Closed jobs are kept so existing applications still reference them.
+-- Seeded and imported jobs have no expiry (NULL).
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS closed_at TIMESTAMP WITH TIME ZONE;
+
+-- Finds postings due to be closed
+CREATE INDEX IF NOT EXISTS idx_jobs_expiring ON jobs(expires_at) WHERE closed_at IS NULL;
@@ -0,0 +1,65 @@
+-- Migration: Candidate availability and work authorization
+-- Job seekers record when they can start, which employment types they want,
+-- where they are based and whether they would relocate, plus their right to
+-- work per country. Jobs optionally carry a country, a start date, and
+-- whether they sponsor visas. job_matches_candidate() applies these as hard
+-- filters in job recommendations; anything left unset never excludes a job.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'work_authorization_status') THEN
+        CREATE TYPE work_authorization_status AS ENUM
+            ('citizen', 'permanent_resident', 'work_visa', 'needs_sponsorship');
+    END IF;
+END $$;
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS available_from DATE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS desired_job_types job_type[] NOT NULL DEFAULT '{}';
+ALTER TABLE users ADD COLUMN IF NOT EXISTS willing_to_relocate BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS country_code CHAR(2);
+
+CREATE TABLE IF NOT EXISTS user_work_authorizations (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    country_code CHAR(2) NOT NULL,
+    status work_authorization_status NOT NULL,
+    PRIMARY KEY (user_id, country_code)
+);
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS country_code CHAR(2);
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS start_date DATE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS visa_sponsorship BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_country_code ON jobs(country_code);
+
+-- Whether a job passes a candidate's hard filters:
+-- * job type is one the candidate wants (if they named any)
+-- * the candidate is available by the job's start date
+-- * the candidate may work in the job's country, or the job sponsors visas
+--   (only checked once the candidate has declared any authorization)
+-- * on-site jobs abroad only match candidates willing to relocate
+CREATE OR REPLACE FUNCTION job_matches_candidate(p_job_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT
+        (cardinality(u.desired_job_types) = 0 OR j.job_type = ANY(u.desired_job_types))
+        AND (u.available_from IS NULL OR j.start_date IS NULL OR u.available_from <= j.start_date)
+        AND (
+            j.country_code IS NULL
+            OR j.visa_sponsorship
+            OR NOT EXISTS (SELECT 1 FROM user_work_authorizations a WHERE a.user_id = u.id)
+            OR EXISTS (
+                SELECT 1 FROM user_work_authorizations a
+                WHERE a.user_id = u.id
+                  AND a.country_code = j.country_code
+                  AND a.status <> 'needs_sponsorship'
+            )
+        )
+        AND (
+            u.willing_to_relocate
+            OR j.is_remote
+            OR u.country_code IS NULL
+            OR j.country_code IS NULL
+            OR j.country_code = u.country_code
+        )
+    FROM jobs j, users u
+    WHERE j.id = p_job_id AND u.id = p_user_id
+$$ LANGUAGE sql STABLE;
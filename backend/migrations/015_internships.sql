@@ -0,0 +1,111 @@
+-- Migration: Internship program flows
+-- Internship postings carry their term dates and whether they count for
+-- academic credit. Students list the academic terms they are available
+-- (e.g. '2026-fall'); internships that do not overlap any of them are left
+-- out of recommendations.
+--
+-- Institutions run organizations with cohorts of students. Students join a
+-- cohort with its join code, and the organization's admins can require a
+-- minimum profile completeness before cohort members may apply to jobs.
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS term_start DATE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS term_end DATE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS academic_credit BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_internship_terms
+    ON jobs(term_start, term_end) WHERE job_type = 'internship';
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS available_terms TEXT[] NOT NULL DEFAULT '{}';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'org_role') THEN
+        CREATE TYPE org_role AS ENUM ('admin', 'advisor');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Staff of an institution (students are cohort members instead)
+CREATE TABLE IF NOT EXISTS organization_members (
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role org_role NOT NULL,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (org_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS cohorts (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    join_code VARCHAR(32) NOT NULL UNIQUE,
+    min_profile_completeness INTEGER NOT NULL DEFAULT 0
+        CHECK (min_profile_completeness BETWEEN 0 AND 100),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_cohorts_org_id ON cohorts(org_id);
+
+CREATE TABLE IF NOT EXISTS cohort_members (
+    cohort_id INTEGER NOT NULL REFERENCES cohorts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (cohort_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cohort_members_user_id ON cohort_members(user_id);
+
+-- Date range covered by an academic term written as 'YYYY-season'
+CREATE OR REPLACE FUNCTION academic_term_range(term TEXT)
+RETURNS DATERANGE AS $$
+    SELECT CASE split_part(term, '-', 2)
+        WHEN 'spring' THEN daterange(make_date(y, 1, 1), make_date(y, 6, 1))
+        WHEN 'summer' THEN daterange(make_date(y, 6, 1), make_date(y, 9, 1))
+        WHEN 'fall' THEN daterange(make_date(y, 9, 1), make_date(y + 1, 1, 1))
+    END
+    FROM (SELECT split_part(term, '-', 1)::INTEGER AS y) t
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Adds the internship term check to the hard filters from migration 014
+CREATE OR REPLACE FUNCTION job_matches_candidate(p_job_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT
+        (cardinality(u.desired_job_types) = 0 OR j.job_type = ANY(u.desired_job_types))
+        AND (u.available_from IS NULL OR j.start_date IS NULL OR u.available_from <= j.start_date)
+        AND (
+            j.country_code IS NULL
+            OR j.visa_sponsorship
+            OR NOT EXISTS (SELECT 1 FROM user_work_authorizations a WHERE a.user_id = u.id)
+            OR EXISTS (
+                SELECT 1 FROM user_work_authorizations a
+                WHERE a.user_id = u.id
+                  AND a.country_code = j.country_code
+                  AND a.status <> 'needs_sponsorship'
+            )
+        )
+        AND (
+            u.willing_to_relocate
+            OR j.is_remote
+            OR u.country_code IS NULL
+            OR j.country_code IS NULL
+            OR j.country_code = u.country_code
+        )
+        AND (
+            j.job_type <> 'internship'
+            OR j.term_start IS NULL
+            OR cardinality(u.available_terms) = 0
+            OR EXISTS (
+                SELECT 1 FROM unnest(u.available_terms) AS t(term)
+                WHERE academic_term_range(t.term)
+                    && daterange(j.term_start, COALESCE(j.term_end, j.term_start), '[]')
+            )
+        )
+    FROM jobs j, users u
+    WHERE j.id = p_job_id AND u.id = p_user_id
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,83 @@
+-- Migration: Career-fair events
+-- Organizations run career fairs with sessions and employer booths.
+-- Attendees RSVP (optionally to individual sessions) and receive a badge
+-- code; recruiters at a booth scan the badge to record an interaction,
+-- which becomes a lead in the company's pipeline. Leads move to 'invited'
+-- when the recruiter invites the candidate to apply and to 'applied' once
+-- the candidate applies to the linked job.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'lead_status') THEN
+        CREATE TYPE lead_status AS ENUM ('new', 'invited', 'applied', 'dismissed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS career_events (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    location VARCHAR(255),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL CHECK (ends_at > starts_at),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_career_events_starts_at ON career_events(starts_at);
+CREATE INDEX IF NOT EXISTS idx_career_events_org_id ON career_events(org_id);
+
+CREATE TABLE IF NOT EXISTS event_sessions (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    location VARCHAR(255),
+    speaker VARCHAR(255),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_sessions_event_id ON event_sessions(event_id, starts_at);
+
+CREATE TABLE IF NOT EXISTS event_booths (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    label VARCHAR(100),
+    UNIQUE (event_id, company_id)
+);
+
+CREATE TABLE IF NOT EXISTS event_rsvps (
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    badge_code VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (event_id, user_id),
+    UNIQUE (event_id, badge_code)
+);
+
+CREATE TABLE IF NOT EXISTS event_session_rsvps (
+    session_id INTEGER NOT NULL REFERENCES event_sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS event_interactions (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    recorded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    notes TEXT,
+    rating SMALLINT CHECK (rating BETWEEN 1 AND 5),
+    lead_status lead_status NOT NULL DEFAULT 'new',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_interactions_company
+    ON event_interactions(company_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_event_interactions_candidate_job
+    ON event_interactions(candidate_id, job_id);
@@ -0,0 +1,65 @@
+-- Migration: Job offers and placements
+-- Employers extend offers on applications with a compensation breakdown, a
+-- response deadline, and attached documents (stored via the storage backend;
+-- only the key is kept here). Candidates accept or decline, optionally with
+-- a counter-offer note. A pending offer past its deadline reads as
+-- 'expired'. Accepting an offer records a placement, which cohort and
+-- institution reporting aggregate.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'offer_status') THEN
+        CREATE TYPE offer_status AS ENUM ('pending', 'accepted', 'declined', 'countered', 'withdrawn', 'expired');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS job_offers (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL REFERENCES application_tracking(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    base_salary INTEGER NOT NULL CHECK (base_salary >= 0),
+    currency CHAR(3) NOT NULL DEFAULT 'USD',
+    -- Further components: [{"label": "Signing bonus", "amount": 5000, "period": "one_time"}]
+    compensation JSONB NOT NULL DEFAULT '[]',
+    start_date DATE,
+    deadline TIMESTAMP WITH TIME ZONE NOT NULL,
+    message TEXT,
+    status offer_status NOT NULL DEFAULT 'pending',
+    candidate_note TEXT,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_offers_application_id ON job_offers(application_id);
+
+CREATE TABLE IF NOT EXISTS offer_documents (
+    id SERIAL PRIMARY KEY,
+    offer_id INTEGER NOT NULL REFERENCES job_offers(id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key TEXT NOT NULL UNIQUE,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_offer_documents_offer_id ON offer_documents(offer_id);
+
+-- One row per accepted offer; job details are copied so placements survive
+-- the posting being deleted.
+CREATE TABLE IF NOT EXISTS placements (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    offer_id INTEGER UNIQUE REFERENCES job_offers(id) ON DELETE SET NULL,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    job_title VARCHAR(255) NOT NULL,
+    company VARCHAR(255) NOT NULL,
+    base_salary INTEGER NOT NULL,
+    currency CHAR(3) NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE,
+    offered_at TIMESTAMP WITH TIME ZONE,
+    accepted_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_placements_user_id ON placements(user_id);
+CREATE INDEX IF NOT EXISTS idx_placements_accepted_at ON placements(accepted_at);
@@ -0,0 +1,42 @@
+-- Migration: Resumable multi-step wizards
+-- A wizard is a multi-step flow such as the CV review (upload → extract →
+-- confirm skills → generate summary → build resume). Each step keeps its
+-- status and its intermediate output (extracted skills, generated summary,
+-- ...), so a user can resume on another device without repeating AI calls.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'wizard_kind') THEN
+        CREATE TYPE wizard_kind AS ENUM ('cv_review');
+    END IF;
+END $$;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'wizard_step_status') THEN
+        CREATE TYPE wizard_step_status AS ENUM ('pending', 'in_progress', 'completed', 'skipped', 'failed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS wizards (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind wizard_kind NOT NULL,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_wizards_user ON wizards(user_id, updated_at DESC);
+
+-- One row per step, created with the wizard
+CREATE TABLE IF NOT EXISTS wizard_steps (
+    wizard_id INTEGER NOT NULL REFERENCES wizards(id) ON DELETE CASCADE,
+    step VARCHAR(50) NOT NULL,
+    position SMALLINT NOT NULL,
+    status wizard_step_status NOT NULL DEFAULT 'pending',
+    output JSONB,
+    error TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (wizard_id, step)
+);
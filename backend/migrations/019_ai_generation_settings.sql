@@ -0,0 +1,7 @@
+-- Migration: Per-request AI generation settings
+-- AI actions may set their sampling temperature and output token limit.
+-- Usage logs record the settings each call ran with, for reproducibility.
+-- Adding the columns to the partitioned parent adds them to every partition.
+
+ALTER TABLE ai_usage_logs ADD COLUMN IF NOT EXISTS temperature REAL;
+ALTER TABLE ai_usage_logs ADD COLUMN IF NOT EXISTS max_output_tokens INTEGER;
@@ -0,0 +1,13 @@
+-- Migration: Deterministic AI generation
+-- Requests in deterministic mode run at temperature 0 with a fixed seed.
+-- Usage logs record the seed and prompt version of every call, and roadmaps
+-- are tagged with the model, prompt version, and settings that produced
+-- them, so a roadmap can be regenerated to debug a quality complaint.
+
+ALTER TABLE ai_usage_logs ADD COLUMN IF NOT EXISTS seed INTEGER;
+ALTER TABLE ai_usage_logs ADD COLUMN IF NOT EXISTS prompt_version VARCHAR(50);
+
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS ai_model VARCHAR(100);
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS prompt_version VARCHAR(50);
+-- {"temperature": 0.0, "max_output_tokens": null, "seed": 42}
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS generation_settings JSONB;
@@ -0,0 +1,7 @@
+-- Migration: Roadmap quality self-check
+-- An optional second AI pass critiques a generated roadmap for coverage,
+-- ordering, and realistic durations. Issues it finds (or could not fix) are
+-- stored for the UI to surface; NULL means the roadmap was not checked.
+
+-- [{"criterion": "duration", "message": "Phase 3 packs 8 weeks of topics into 2 weeks", "fixed": false}]
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS quality_warnings JSONB;
@@ -0,0 +1,33 @@
+-- Migration: Resource link validation
+-- AI-suggested resources often point at dead or hallucinated URLs. A
+-- background task collects every link from learning_resources and from
+-- roadmap phase resources, checks each one periodically, and flags links
+-- that are missing or keep failing. Broken links get an AI-suggested
+-- replacement, which is only applied once it passes the same check.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'link_status') THEN
+        CREATE TYPE link_status AS ENUM ('unchecked', 'ok', 'broken');
+    END IF;
+END $$;
+
+-- One row per distinct URL, wherever it is used
+CREATE TABLE IF NOT EXISTS resource_links (
+    url TEXT PRIMARY KEY,
+    status link_status NOT NULL DEFAULT 'unchecked',
+    -- HTTP status of the latest check; NULL if the request failed outright
+    http_status SMALLINT,
+    last_error TEXT,
+    -- Failed checks in a row; a link is flagged broken after several
+    consecutive_failures SMALLINT NOT NULL DEFAULT 0,
+    checked_at TIMESTAMP WITH TIME ZONE,
+    -- Verified replacement, already applied wherever the link was used
+    replacement_url TEXT,
+    replaced_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_links_checked ON resource_links(checked_at NULLS FIRST)
+    WHERE replaced_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resource_links_status ON resource_links(status);
@@ -0,0 +1,16 @@
+-- Migration: Skill proficiency self-assessment
+-- Users rate their proficiency (1-5) in each profile skill and record when
+-- they last used it. Matching and skill-gap analysis lower the effective
+-- proficiency of skills that have not been used for a long time.
+
+CREATE TABLE IF NOT EXISTS user_skill_ratings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    skill VARCHAR(100) NOT NULL,
+    -- 1 = beginner, 3 = intermediate, 5 = expert
+    proficiency SMALLINT NOT NULL CHECK (proficiency BETWEEN 1 AND 5),
+    last_used_on DATE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Skills match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_skill_ratings_skill ON user_skill_ratings(user_id, LOWER(skill));
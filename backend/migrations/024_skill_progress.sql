@@ -0,0 +1,33 @@
+-- Migration: Per-skill learning progress across roadmaps
+-- The same skill often appears in several roadmaps. Progress is unified per
+-- skill: completing a roadmap phase marks the skills it teaches as known
+-- for every roadmap, skill-gap analysis counts them as held, and later
+-- roadmap generations include them in current_skills.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'skill_progress_status') THEN
+        CREATE TYPE skill_progress_status AS ENUM ('learning', 'known');
+    END IF;
+END $$;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'skill_progress_source') THEN
+        CREATE TYPE skill_progress_source AS ENUM ('roadmap', 'self_rating');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS user_skill_progress (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    skill VARCHAR(100) NOT NULL,
+    status skill_progress_status NOT NULL DEFAULT 'learning',
+    source skill_progress_source NOT NULL,
+    -- Roadmap whose completed phase marked the skill known
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    known_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Skills match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_skill_progress_skill ON user_skill_progress(user_id, LOWER(skill));
@@ -0,0 +1,15 @@
+-- Migration: Mentor conversation summaries
+-- Long mentor chats get an AI bullet summary of the advice received, stored
+-- on the conversation so it can be shown (and exported) without re-reading
+-- every message. summary_message_count records how many messages the
+-- summary covered, so a stale summary can be told apart from a current one.
+
+-- {"points": ["Focus on TypeScript before React"], "action_items": ["Build a todo app with tests"]}
+ALTER TABLE mentor_conversations ADD COLUMN IF NOT EXISTS summary JSONB;
+ALTER TABLE mentor_conversations ADD COLUMN IF NOT EXISTS summary_message_count INTEGER;
+ALTER TABLE mentor_conversations ADD COLUMN IF NOT EXISTS summarized_at TIMESTAMP WITH TIME ZONE;
+
+-- Summaries are charged like a mentor question
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('summarize_conversation', 1)
+ON CONFLICT (action) DO NOTHING;
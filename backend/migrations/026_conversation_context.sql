@@ -0,0 +1,8 @@
+-- Migration: Context window management for mentor conversations
+-- Once a conversation's history no longer fits the model's context budget,
+-- its older turns are compressed into a running summary that is sent in
+-- their place. context_summary_through is the id of the last message the
+-- running summary covers; later messages are sent verbatim while they fit.
+
+ALTER TABLE mentor_conversations ADD COLUMN IF NOT EXISTS context_summary TEXT;
+ALTER TABLE mentor_conversations ADD COLUMN IF NOT EXISTS context_summary_through INTEGER;
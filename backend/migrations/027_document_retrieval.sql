@@ -0,0 +1,45 @@
+-- Migration: Retrieval over user documents for grounded mentor answers
+-- The user's CV, roadmaps, and saved documents are cut into chunks and
+-- embedded into pgvector. Mentor questions retrieve the closest chunks and
+-- answer from them, returning the chunks as sources. Chunks are re-embedded
+-- only when the text of their source changes (tracked by source_hash).
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'document_source') THEN
+        CREATE TYPE document_source AS ENUM ('cv', 'roadmap', 'document');
+    END IF;
+END $$;
+
+-- Free-text documents a user saves for the mentor (notes, job descriptions, feedback)
+CREATE TABLE IF NOT EXISTS user_documents (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_documents_user ON user_documents(user_id, updated_at DESC);
+
+CREATE TABLE IF NOT EXISTS document_chunks (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source document_source NOT NULL,
+    -- Roadmap or document id; NULL for the CV
+    source_id INTEGER,
+    source_title VARCHAR(255) NOT NULL,
+    -- SHA-256 of the source text, to skip re-embedding unchanged sources
+    source_hash CHAR(64) NOT NULL,
+    chunk_index SMALLINT NOT NULL,
+    content TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_chunks_source ON document_chunks(user_id, source, source_id);
+CREATE INDEX IF NOT EXISTS idx_document_chunks_embedding ON document_chunks USING hnsw (embedding vector_cosine_ops);
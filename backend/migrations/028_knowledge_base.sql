@@ -0,0 +1,39 @@
+-- Migration: Curated knowledge base
+-- Admins publish career guides and FAQs. Articles are public to browse, and
+-- published ones are chunked and embedded like user documents so mentor
+-- answers can retrieve and cite them. indexed_at is cleared whenever an
+-- article's content changes; the scheduler re-embeds articles until it is set.
+
+-- Knowledge base chunks are cited alongside the user's own documents
+ALTER TYPE document_source ADD VALUE IF NOT EXISTS 'knowledge_base';
+
+CREATE TABLE IF NOT EXISTS kb_articles (
+    id SERIAL PRIMARY KEY,
+    slug VARCHAR(160) NOT NULL UNIQUE,
+    title VARCHAR(255) NOT NULL,
+    -- One or two sentences shown in listings
+    summary TEXT,
+    -- Markdown
+    content TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+    published BOOLEAN NOT NULL DEFAULT FALSE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    indexed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_kb_articles_published ON kb_articles(updated_at DESC) WHERE published;
+CREATE INDEX IF NOT EXISTS idx_kb_articles_tags ON kb_articles USING GIN (tags);
+
+CREATE TABLE IF NOT EXISTS kb_chunks (
+    id BIGSERIAL PRIMARY KEY,
+    article_id INTEGER NOT NULL REFERENCES kb_articles(id) ON DELETE CASCADE,
+    chunk_index SMALLINT NOT NULL,
+    content TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_kb_chunks_article ON kb_chunks(article_id);
+CREATE INDEX IF NOT EXISTS idx_kb_chunks_embedding ON kb_chunks USING hnsw (embedding vector_cosine_ops);
@@ -0,0 +1,20 @@
+-- Migration: Mentor tool calls
+-- The career mentor can call whitelisted internal tools (job search, roadmap
+-- progress, skill gap) before answering. Every call is logged with its
+-- arguments, output or error, and latency for auditing and debugging.
+
+CREATE TABLE IF NOT EXISTS ai_tool_calls (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the call answered a question in a saved conversation
+    conversation_id INTEGER REFERENCES mentor_conversations(id) ON DELETE SET NULL,
+    tool VARCHAR(100) NOT NULL,
+    arguments JSONB NOT NULL DEFAULT '{}'::jsonb,
+    result JSONB,
+    error TEXT,
+    latency_ms INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_tool_calls_user ON ai_tool_calls(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ai_tool_calls_tool ON ai_tool_calls(tool, created_at DESC);
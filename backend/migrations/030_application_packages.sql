@@ -0,0 +1,57 @@
+-- Migration: Application packages
+-- A multi-step AI agent prepares everything for one job application:
+-- analyze the job description → tailor the resume → draft a cover letter →
+-- generate likely interview questions. Packages are queued and run in the
+-- background; the package row is the queue entry and each step stores its
+-- status and artifact, so progress can be polled and a failed run resumes
+-- from the failed step.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'application_package_status') THEN
+        CREATE TYPE application_package_status AS ENUM ('queued', 'running', 'completed', 'failed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS application_packages (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the package was built for a posting on the platform
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    job_title VARCHAR(255) NOT NULL,
+    company VARCHAR(255),
+    job_description TEXT NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    status application_package_status NOT NULL DEFAULT 'queued',
+    -- Runs started, including retries
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_application_packages_user ON application_packages(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_application_packages_queue ON application_packages(updated_at)
+    WHERE status IN ('queued', 'running');
+
+-- One row per step, created with the package
+CREATE TABLE IF NOT EXISTS application_package_steps (
+    package_id INTEGER NOT NULL REFERENCES application_packages(id) ON DELETE CASCADE,
+    step VARCHAR(50) NOT NULL,
+    position SMALLINT NOT NULL,
+    status application_package_status NOT NULL DEFAULT 'queued',
+    -- The step's artifact, e.g. {"content": "Dear hiring manager..."} for the cover letter
+    output JSONB,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (package_id, step)
+);
+
+-- The two new steps are charged like other generations; the resume and
+-- cover letter steps are charged as generate_content
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('analyze_job_description', 1), ('generate_interview_questions', 1)
+ON CONFLICT (action) DO NOTHING;
@@ -0,0 +1,8 @@
+-- Migration: Native PDF analysis
+-- CV uploads can have Gemini read the PDF through its Files API (needed for
+-- scanned PDFs). The transcription is an AI call and is charged like skill
+-- extraction.
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('transcribe_pdf', 2)
+ON CONFLICT (action) DO NOTHING;
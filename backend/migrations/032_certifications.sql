@@ -0,0 +1,32 @@
+-- Migration: Certifications
+-- Profiles list the user's certifications. They are added by hand or read
+-- from a photo or screenshot of a certificate (or a CV's certifications
+-- section) by Gemini's vision model; reading an image is charged like a PDF
+-- transcription.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'certification_source') THEN
+        CREATE TYPE certification_source AS ENUM ('manual', 'image');
+    END IF;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS user_certifications (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    issuer VARCHAR(255),
+    issue_date DATE,
+    expiry_date DATE,
+    credential_id VARCHAR(255),
+    credential_url TEXT,
+    source certification_source NOT NULL DEFAULT 'manual',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_certifications_user ON user_certifications(user_id, issue_date DESC);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('read_image', 2)
+ON CONFLICT (action) DO NOTHING;
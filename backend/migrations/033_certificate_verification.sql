@@ -0,0 +1,24 @@
+-- Migration: Certificate verification
+-- Certifications from issuers with a public verification endpoint (Credly)
+-- are checked against the issuer in the background and earn a verified
+-- badge on profiles and career-fair leads. Verified certifications are
+-- re-checked monthly so revoked ones lose the badge.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'certification_verification_status') THEN
+        CREATE TYPE certification_verification_status AS ENUM ('pending', 'verified', 'failed', 'unsupported');
+    END IF;
+END
+$$;
+
+ALTER TABLE user_certifications
+    ADD COLUMN IF NOT EXISTS verification_status certification_verification_status NOT NULL DEFAULT 'pending',
+    ADD COLUMN IF NOT EXISTS verification_error TEXT,
+    -- Checks in a row that got no usable answer from the issuer
+    ADD COLUMN IF NOT EXISTS verification_attempts SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS verification_checked_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_user_certifications_verification
+    ON user_certifications(verification_status, verification_checked_at);
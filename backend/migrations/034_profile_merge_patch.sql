@@ -0,0 +1,8 @@
+-- Migration: Partial profile updates
+-- Profiles gain social links, and `PATCH /api/v1/users/me` applies JSON
+-- Merge Patch documents to them. Profile changes are audited in
+-- `audit_logs` under the `profile.updated` action.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS social_links JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_target ON audit_logs(target_type, target_id, created_at DESC);
@@ -0,0 +1,17 @@
+-- Migration: Profile snapshots
+-- The profile is snapshotted before AI-driven updates (skills extracted from
+-- a CV, CV uploads) so users can see recent versions and revert to one.
+
+CREATE TABLE IF NOT EXISTS profile_snapshots (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- What was about to change the profile, e.g. `ai_extract_skills`
+    reason VARCHAR(100) NOT NULL,
+    -- Profile fields as returned by GET /api/users/me
+    profile JSONB NOT NULL,
+    raw_cv_text TEXT,
+    reverted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_profile_snapshots_user ON profile_snapshots(user_id, created_at DESC);
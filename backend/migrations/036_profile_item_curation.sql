@@ -0,0 +1,22 @@
+-- Migration: Skill and target role curation
+-- Skills and target roles the user removed or renamed away are remembered
+-- per user, so AI extraction does not add them back.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'profile_item_kind') THEN
+        CREATE TYPE profile_item_kind AS ENUM ('skill', 'target_role');
+    END IF;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS user_rejected_profile_items (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind profile_item_kind NOT NULL,
+    value VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Items match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_rejected_profile_items_value
+    ON user_rejected_profile_items(user_id, kind, LOWER(value));
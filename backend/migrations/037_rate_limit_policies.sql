@@ -0,0 +1,19 @@
+-- Migration: Per-tenant rate limit policies
+-- Plans set each user's daily AI quota (plans.ai_daily_quota). A policy
+-- overrides it for the staff and cohort students of one organization,
+-- either for every plan or for one plan only.
+
+CREATE TABLE IF NOT EXISTS rate_limit_policies (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    -- NULL applies the policy to every plan
+    plan_id VARCHAR(50) REFERENCES plans(id) ON DELETE CASCADE,
+    ai_daily_quota INTEGER NOT NULL CHECK (ai_daily_quota > 0),
+    note VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One policy per organization and plan (or all plans)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rate_limit_policies_scope
+    ON rate_limit_policies(org_id, COALESCE(plan_id, ''));
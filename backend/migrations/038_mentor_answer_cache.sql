@@ -0,0 +1,25 @@
+-- Migration: Semantic cache of mentor answers
+-- Standalone mentor questions are embedded and stored with their answer.
+-- A later question from the same user and profile context whose embedding
+-- is close enough to a recent cached one is answered from the cache
+-- instead of calling the AI provider.
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE IF NOT EXISTS mentor_answer_cache (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 of the profile context the answer was given for
+    context_hash CHAR(64) NOT NULL,
+    question TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL,
+    answer JSONB NOT NULL,
+    sources JSONB NOT NULL DEFAULT '[]'::jsonb,
+    provider VARCHAR(20) NOT NULL,
+    hit_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_answer_cache_lookup
+    ON mentor_answer_cache(user_id, context_hash, created_at DESC);
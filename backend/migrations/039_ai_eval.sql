@@ -0,0 +1,39 @@
+-- Migration: AI evaluation runs against golden fixtures
+-- `cargo run -- eval` sends a fixed set of CVs, roadmap goals, and mentor
+-- questions to each configured provider and scores the outputs against
+-- golden expectations. Results are kept per run so score trends can be
+-- compared across models and prompt versions before a change is rolled out.
+
+CREATE TABLE IF NOT EXISTS ai_eval_runs (
+    id SERIAL PRIMARY KEY,
+    -- Free-form label, e.g. "nightly" or the prompt change under test
+    label VARCHAR(100),
+    case_count INTEGER NOT NULL DEFAULT 0,
+    pass_count INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE IF NOT EXISTS ai_eval_results (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES ai_eval_runs(id) ON DELETE CASCADE,
+    case_name VARCHAR(100) NOT NULL,
+    action VARCHAR(50) NOT NULL,
+    provider VARCHAR(20) NOT NULL,
+    model VARCHAR(100) NOT NULL,
+    prompt_version VARCHAR(100) NOT NULL,
+    schema_valid BOOLEAN NOT NULL,
+    missing_fields TEXT[] NOT NULL DEFAULT '{}',
+    -- Share of expected keywords found in the output, 0 to 1
+    keyword_score DOUBLE PRECISION NOT NULL,
+    -- Mean of the schema, field, and keyword scores, 0 to 1
+    score DOUBLE PRECISION NOT NULL,
+    passed BOOLEAN NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_eval_results_run ON ai_eval_results(run_id);
+CREATE INDEX IF NOT EXISTS idx_ai_eval_results_trend
+    ON ai_eval_results(provider, model, prompt_version, created_at DESC);
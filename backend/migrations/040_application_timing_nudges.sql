@@ -0,0 +1,6 @@
+-- Migration: Job application timing nudges
+-- The scheduler notifies a user, with a shortlist of matching jobs, once a
+-- roadmap's progress reaches the point its job_application_timing says to
+-- start applying. NULL means the roadmap has not been nudged yet.
+
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS application_nudged_at TIMESTAMP WITH TIME ZONE;
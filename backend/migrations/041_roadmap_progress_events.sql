@@ -0,0 +1,15 @@
+-- Migration: Roadmap progress events
+-- Each roadmap progress update is snapshotted so progress can be charted
+-- over time, phase velocity measured, and a completion date projected.
+
+CREATE TABLE IF NOT EXISTS roadmap_progress_events (
+    id BIGSERIAL PRIMARY KEY,
+    roadmap_id INTEGER NOT NULL REFERENCES career_roadmaps(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    progress_percentage INTEGER NOT NULL,
+    completed_phases INTEGER[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_roadmap_progress_events_roadmap
+    ON roadmap_progress_events(roadmap_id, created_at);
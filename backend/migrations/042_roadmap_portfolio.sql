@@ -0,0 +1,13 @@
+-- Migration: Multi-roadmap portfolio
+-- Users state how many hours a week they have for learning, compared with
+-- the combined hours their roadmaps ask for, and can mark one roadmap as
+-- primary. The primary roadmap leads weekly digests and suggested mentor
+-- questions.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS weekly_learning_hours INTEGER
+    CHECK (weekly_learning_hours BETWEEN 1 AND 80);
+
+ALTER TABLE career_roadmaps ADD COLUMN IF NOT EXISTS is_primary BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- At most one primary roadmap per user
+CREATE UNIQUE INDEX IF NOT EXISTS idx_roadmaps_primary ON career_roadmaps(user_id) WHERE is_primary;
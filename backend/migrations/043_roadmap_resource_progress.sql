@@ -0,0 +1,32 @@
+-- Migration: Roadmap resource progress
+-- Beyond checking off phases, users mark individual resources in a roadmap
+-- phase as completed or skipped, with an optional 1-5 rating and a note.
+-- Ratings and skips are aggregated across users by resource (its URL, or
+-- its lowercased text when it has none) to rank catalog recommendations.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'resource_status') THEN
+        CREATE TYPE resource_status AS ENUM ('completed', 'skipped');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS roadmap_resource_progress (
+    roadmap_id INTEGER NOT NULL REFERENCES career_roadmaps(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    phase INTEGER NOT NULL,
+    -- 1-based position of the resource in the phase's resources
+    resource_index INTEGER NOT NULL,
+    -- The resource as listed in the roadmap when it was marked
+    resource TEXT NOT NULL,
+    -- The resource's URL, or its lowercased text when it has none
+    resource_key TEXT NOT NULL,
+    status resource_status NOT NULL,
+    rating SMALLINT CHECK (rating BETWEEN 1 AND 5),
+    note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (roadmap_id, phase, resource_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_roadmap_resource_progress_key ON roadmap_resource_progress(resource_key);
@@ -0,0 +1,22 @@
+-- Migration: Learning resource click tracking
+-- Resource links go through a redirect endpoint that logs each click with
+-- the user and roadmap it came from, for "most used resources" analytics.
+-- Resources can carry affiliate query parameters, added to the URL on
+-- redirect.
+
+ALTER TABLE learning_resources ADD COLUMN IF NOT EXISTS affiliate_params JSONB NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS resource_clicks (
+    id BIGSERIAL PRIMARY KEY,
+    resource_id INTEGER NOT NULL REFERENCES learning_resources(id) ON DELETE CASCADE,
+    -- NULL for anonymous clicks
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- The clicking user's roadmap the link was followed from, if any
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    -- Whether affiliate parameters were added to the redirect
+    affiliate BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_clicks_created ON resource_clicks(created_at);
+CREATE INDEX IF NOT EXISTS idx_resource_clicks_resource ON resource_clicks(resource_id, created_at);
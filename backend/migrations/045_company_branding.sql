@@ -0,0 +1,24 @@
+-- Migration: Employer branding
+-- Companies get profile fields for their public page and AI-generated
+-- branding content: a "why work here" section and a pitch per role. Owners
+-- and recruiters edit a draft, which is shown on the public company page
+-- once published.
+
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS industry VARCHAR(100);
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS company_size VARCHAR(50);
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS website TEXT;
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS headquarters VARCHAR(255);
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS culture_values TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS perks TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS company_branding (
+    company_id INTEGER PRIMARY KEY REFERENCES companies(id) ON DELETE CASCADE,
+    -- {"why_work_here": "...", "role_pitches": [{"role": "...", "pitch": "..."}]}
+    draft JSONB,
+    published JSONB,
+    generated_at TIMESTAMP WITH TIME ZONE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    published_at TIMESTAMP WITH TIME ZONE
+);
@@ -0,0 +1,52 @@
+-- Migration: Screening questions
+-- Employers attach screening questions to a posting, and candidates answer
+-- them when they apply. Yes/no and number answers are scored against the
+-- expected answer on submission; free-text answers are scored by the AI
+-- provider in the background. Knockout questions flag the application when
+-- answered wrongly. Each answer keeps a copy of its question so editing the
+-- questions later does not change what the candidate answered.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'screening_question_type') THEN
+        CREATE TYPE screening_question_type AS ENUM ('yes_no', 'number', 'text');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS screening_questions (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    question TEXT NOT NULL,
+    question_type screening_question_type NOT NULL,
+    -- yes_no: "yes" or "no"; number: the minimum accepted; text: what a
+    -- strong answer covers
+    expected_answer TEXT,
+    required BOOLEAN NOT NULL DEFAULT TRUE,
+    knockout BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_screening_questions_job ON screening_questions(job_id, position);
+
+CREATE TABLE IF NOT EXISTS screening_answers (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL REFERENCES application_tracking(id) ON DELETE CASCADE,
+    question_id INTEGER REFERENCES screening_questions(id) ON DELETE SET NULL,
+    question TEXT NOT NULL,
+    question_type screening_question_type NOT NULL,
+    knockout BOOLEAN NOT NULL DEFAULT FALSE,
+    answer TEXT NOT NULL,
+    -- 0-100; NULL until evaluated, or when there is nothing to score against
+    score SMALLINT CHECK (score BETWEEN 0 AND 100),
+    flagged BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    evaluated_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_screening_answers_application ON screening_answers(application_id);
+
+-- Summary of the answers, shown in the employer's applicant list
+ALTER TABLE application_tracking ADD COLUMN IF NOT EXISTS screening_score SMALLINT;
+ALTER TABLE application_tracking ADD COLUMN IF NOT EXISTS screening_flagged BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,59 @@
+-- Migration: Talent pools
+-- Company teams save candidates into named pools with tags and notes. Each
+-- pool can list skills it looks for; a scheduled check alerts the recruiter
+-- who saved a candidate when the candidate gains one of them or gets a
+-- certification verified. Members remember what was already seen so each
+-- gain is alerted once.
+
+CREATE TABLE IF NOT EXISTS talent_pools (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    -- Skills the pool looks for; gaining one triggers an alert
+    required_skills TEXT[] NOT NULL DEFAULT '{}',
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (company_id, name)
+);
+
+-- Skills (lowercase) a user holds: listed on their profile or marked known
+-- through a roadmap
+CREATE OR REPLACE FUNCTION held_skills(p_user_id UUID)
+RETURNS TEXT[] AS $$
+    SELECT ARRAY(
+        SELECT LOWER(s) FROM users u, unnest(u.skills) s WHERE u.id = p_user_id
+        UNION
+        SELECT LOWER(skill) FROM user_skill_progress WHERE user_id = p_user_id AND status = 'known'
+    )
+$$ LANGUAGE SQL STABLE;
+
+CREATE TABLE IF NOT EXISTS talent_pool_members (
+    pool_id INTEGER NOT NULL REFERENCES talent_pools(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    notes TEXT,
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Required skills (lowercase) the candidate held at the last check
+    seen_skills TEXT[] NOT NULL DEFAULT '{}',
+    -- Verified certifications already alerted on
+    seen_certifications INTEGER[] NOT NULL DEFAULT '{}',
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (pool_id, candidate_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_talent_pool_members_candidate ON talent_pool_members(candidate_id);
+
+CREATE TABLE IF NOT EXISTS talent_pool_alerts (
+    id SERIAL PRIMARY KEY,
+    pool_id INTEGER NOT NULL REFERENCES talent_pools(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'skill' or 'verification'
+    alert_type VARCHAR(20) NOT NULL,
+    detail TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_talent_pool_alerts_pool ON talent_pool_alerts(pool_id, created_at DESC);
@@ -0,0 +1,88 @@
+-- Migration: Blocking and reports
+-- Candidates block employers or whole companies, and employers block
+-- candidates. A block hides the two parties from each other in both
+-- directions: job search, recommendations, and applying on one side,
+-- invitations and talent pools on the other. A block made from a
+-- company seat covers the whole company.
+--
+-- Either side can report a job, a user, a company, or a message it
+-- received. Reports wait in the admin moderation queue until resolved, and
+-- a live job that collects enough open reports goes back to pending review.
+-- Notifications sent by employers record their sender so reported messages
+-- can be traced to who sent them.
+
+CREATE TABLE IF NOT EXISTS user_blocks (
+    id SERIAL PRIMARY KEY,
+    blocker_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the blocker holds a company seat; the block covers the company
+    blocker_company_id INTEGER REFERENCES companies(id) ON DELETE CASCADE,
+    blocked_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    blocked_company_id INTEGER REFERENCES companies(id) ON DELETE CASCADE,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((blocked_user_id IS NULL) <> (blocked_company_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_blocks_user
+    ON user_blocks(COALESCE(blocker_company_id::text, blocker_id::text), blocked_user_id)
+    WHERE blocked_user_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_blocks_company
+    ON user_blocks(blocker_id, blocked_company_id)
+    WHERE blocked_company_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_user_blocks_blocked_user ON user_blocks(blocked_user_id);
+
+-- Whether a candidate and an employer (or their company) have blocked each
+-- other, in either direction
+CREATE OR REPLACE FUNCTION blocked_between(p_candidate_id UUID, p_employer_id UUID, p_company_id INTEGER)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM user_blocks b
+        WHERE (b.blocker_id = p_candidate_id
+               AND (b.blocked_user_id = p_employer_id OR b.blocked_company_id = p_company_id))
+           OR (b.blocked_user_id = p_candidate_id
+               AND (b.blocker_id = p_employer_id OR b.blocker_company_id = p_company_id))
+    )
+$$ LANGUAGE SQL STABLE;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'report_target_type') THEN
+        CREATE TYPE report_target_type AS ENUM ('job', 'user', 'company', 'message');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'report_category') THEN
+        CREATE TYPE report_category AS ENUM ('spam', 'scam', 'harassment', 'inappropriate', 'other');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'report_status') THEN
+        CREATE TYPE report_status AS ENUM ('open', 'resolved', 'dismissed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS reports (
+    id SERIAL PRIMARY KEY,
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_type report_target_type NOT NULL,
+    -- Job or notification ID, user UUID, or company ID
+    target_id TEXT NOT NULL,
+    -- User answerable for the target: the job's poster or the message's sender
+    reported_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    reported_company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL,
+    -- Copy of the reported message, kept if the message is removed
+    excerpt TEXT,
+    category report_category NOT NULL,
+    details TEXT,
+    status report_status NOT NULL DEFAULT 'open',
+    -- `dismiss`, `warn`, or `remove`
+    resolution VARCHAR(20),
+    resolution_note TEXT,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reports_open_once
+    ON reports(reporter_id, target_type, target_id) WHERE status = 'open';
+CREATE INDEX IF NOT EXISTS idx_reports_target ON reports(target_type, target_id, status);
+CREATE INDEX IF NOT EXISTS idx_reports_queue ON reports(status, created_at);
+
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS sender_id UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS sender_company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL;
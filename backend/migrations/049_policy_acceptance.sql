@@ -0,0 +1,42 @@
+-- Migration: Policy acceptance
+-- Terms of service and privacy policy are published as numbered versions.
+-- Once a version is published, users must accept it before they can use
+-- protected endpoints again. Each acceptance records the version, when,
+-- and from which address, and is kept for compliance.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'policy_kind') THEN
+        CREATE TYPE policy_kind AS ENUM ('terms', 'privacy');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS policy_documents (
+    id SERIAL PRIMARY KEY,
+    kind policy_kind NOT NULL,
+    version INTEGER NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- What changed since the previous version, shown when asking to re-accept
+    summary TEXT,
+    published_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (kind, version)
+);
+
+CREATE TABLE IF NOT EXISTS policy_acceptances (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    policy_id INTEGER NOT NULL REFERENCES policy_documents(id) ON DELETE RESTRICT,
+    kind policy_kind NOT NULL,
+    version INTEGER NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Peer address of the connection
+    ip_address TEXT,
+    -- X-Forwarded-For header, when behind a proxy
+    forwarded_for TEXT,
+    user_agent TEXT,
+    UNIQUE (user_id, policy_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_acceptances_policy ON policy_acceptances(policy_id, accepted_at);
@@ -0,0 +1,26 @@
+-- Migration: Impersonation sessions
+-- Admins can act as a user for a limited time to debug reported issues.
+-- Each session records who impersonated whom and why; audit entries made
+-- during a session carry the admin in `impersonated_by`, and the user is
+-- notified once the session ends.
+
+CREATE TABLE IF NOT EXISTS impersonation_sessions (
+    id SERIAL PRIMARY KEY,
+    admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set when an admin ends the session before it expires
+    ended_at TIMESTAMP WITH TIME ZONE,
+    -- Set once the user has been told about the session
+    notified_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_impersonation_sessions_user
+    ON impersonation_sessions(user_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_impersonation_sessions_unnotified
+    ON impersonation_sessions(expires_at) WHERE notified_at IS NULL;
+
+-- Admin who was acting as the actor, for entries made while impersonating
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS impersonated_by UUID REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,13 @@
+-- Migration: Tenant configuration
+-- Per-organization settings. An institution's career services guidance is
+-- added to the mentor and roadmap prompts of its staff and cohort students.
+
+CREATE TABLE IF NOT EXISTS tenant_configs (
+    org_id INTEGER PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    -- Guidance for mentor answers, e.g. tone and services to point students to
+    mentor_persona TEXT,
+    -- Guidance for generated learning roadmaps
+    roadmap_guidance TEXT,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
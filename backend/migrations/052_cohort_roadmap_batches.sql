@@ -0,0 +1,48 @@
+-- Migration: Bulk cohort roadmaps
+-- An org admin generates a roadmap to one target role for every student in a
+-- cohort. The batch row is the task queue entry and each student has an item
+-- row, so progress can be polled, failures are listed per student, and a
+-- stopped run resumes with the students still pending.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'roadmap_batch_status') THEN
+        CREATE TYPE roadmap_batch_status AS ENUM ('queued', 'running', 'completed', 'failed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS cohort_roadmap_batches (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    cohort_id INTEGER NOT NULL REFERENCES cohorts(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    target_role VARCHAR(255) NOT NULL,
+    -- Roadmap the settings were copied from, if any
+    template_roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    timeframe_months INTEGER NOT NULL,
+    learning_hours_per_week INTEGER NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    status roadmap_batch_status NOT NULL DEFAULT 'queued',
+    -- Runs started, including retries
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_cohort_roadmap_batches_cohort ON cohort_roadmap_batches(cohort_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_cohort_roadmap_batches_queue ON cohort_roadmap_batches(updated_at)
+    WHERE status IN ('queued', 'running');
+
+-- One row per student, created with the batch
+CREATE TABLE IF NOT EXISTS cohort_roadmap_items (
+    batch_id INTEGER NOT NULL REFERENCES cohort_roadmap_batches(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status roadmap_batch_status NOT NULL DEFAULT 'queued',
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    error TEXT,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (batch_id, user_id)
+);
@@ -0,0 +1,17 @@
+-- Migration: Student nudges
+-- Advisors nudge at-risk students from the at-risk dashboard. Each nudge is
+-- recorded with the risks it was about, so the dashboard can show when a
+-- student was last nudged and repeat nudges are held back.
+
+CREATE TABLE IF NOT EXISTS student_nudges (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    sent_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Risk flags the student had, e.g. {stalled_roadmap,no_applications}
+    flags TEXT[] NOT NULL DEFAULT '{}',
+    message TEXT NOT NULL,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_student_nudges_org_user ON student_nudges(org_id, user_id, sent_at DESC);
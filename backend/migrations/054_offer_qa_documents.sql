@@ -0,0 +1,28 @@
+-- Migration: Offer document Q&A
+-- Job seekers upload an offer letter or contract and ask questions about it.
+-- The file and the text of every chunk are encrypted at rest; only the
+-- embeddings are stored in the clear. Chunks live apart from
+-- document_chunks so mentor retrieval never sees offer content.
+
+CREATE TABLE IF NOT EXISTS offer_qa_documents (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    -- Key of the encrypted file in storage
+    storage_key VARCHAR(512) NOT NULL,
+    chunk_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_offer_qa_documents_user ON offer_qa_documents(user_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS offer_qa_chunks (
+    document_id INTEGER NOT NULL REFERENCES offer_qa_documents(id) ON DELETE CASCADE,
+    chunk_index SMALLINT NOT NULL,
+    -- Nonce followed by the AES-256-GCM ciphertext of the chunk text
+    content_ciphertext BYTEA NOT NULL,
+    embedding vector(768) NOT NULL,
+    PRIMARY KEY (document_id, chunk_index)
+);
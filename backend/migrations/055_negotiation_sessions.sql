@@ -0,0 +1,33 @@
+-- Migration: Salary negotiation practice
+-- A job seeker practises negotiating one of their offers with the AI playing
+-- the recruiter. The offer and salary benchmarks are captured when the
+-- session starts, so the recruiter stays consistent if the offer changes;
+-- the transcript is kept on the session and a debrief is added when it ends.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'negotiation_status') THEN
+        CREATE TYPE negotiation_status AS ENUM ('active', 'completed');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS negotiation_sessions (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    offer_id INTEGER REFERENCES job_offers(id) ON DELETE SET NULL,
+    -- Offer details and benchmarks the recruiter works from
+    scenario TEXT NOT NULL,
+    -- [{"role": "assistant" | "user", "content": "..."}], recruiter first
+    transcript JSONB NOT NULL DEFAULT '[]',
+    status negotiation_status NOT NULL DEFAULT 'active',
+    debrief JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_negotiation_sessions_user ON negotiation_sessions(user_id, created_at DESC);
+
+-- Recruiter replies are free; the debrief is charged like other generations
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('negotiation_debrief', 1)
+ON CONFLICT (action) DO NOTHING;
@@ -0,0 +1,41 @@
+-- Migration: STAR stories
+-- Behavioral interview stories (Situation, Task, Action, Result) mined from
+-- a job seeker's projects and work history, refined with their input over
+-- several turns, and saved tagged by competency for review before
+-- interviews.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'star_competency') THEN
+        CREATE TYPE star_competency AS ENUM (
+            'leadership', 'teamwork', 'conflict', 'failure',
+            'problem_solving', 'initiative', 'communication', 'adaptability'
+        );
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'star_story_status') THEN
+        CREATE TYPE star_story_status AS ENUM ('draft', 'polished');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS star_stories (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    competencies star_competency[] NOT NULL DEFAULT '{}',
+    -- The project or role the story comes from
+    source VARCHAR(255),
+    situation TEXT NOT NULL,
+    task TEXT NOT NULL,
+    action TEXT NOT NULL,
+    result TEXT NOT NULL,
+    -- Questions whose answers would strengthen the story
+    follow_up_questions TEXT[] NOT NULL DEFAULT '{}',
+    -- The user's refinement inputs, oldest first
+    refinements TEXT[] NOT NULL DEFAULT '{}',
+    status star_story_status NOT NULL DEFAULT 'draft',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_star_stories_user ON star_stories(user_id, status, updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_star_stories_competencies ON star_stories USING GIN (competencies);
@@ -0,0 +1,40 @@
+-- Migration: Code challenge practice tracking
+-- Solved-problem counts imported from coding practice platforms (LeetCode),
+-- either synced on a schedule for a linked handle or uploaded as a progress
+-- export. Shown on the profile and used in readiness for engineering roles.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'code_practice_platform') THEN
+        CREATE TYPE code_practice_platform AS ENUM ('leetcode');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS code_practice_accounts (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    platform code_practice_platform NOT NULL,
+    -- Public handle synced on a schedule; NULL when progress only comes from uploaded exports
+    handle VARCHAR(100),
+    easy_solved INTEGER NOT NULL DEFAULT 0,
+    medium_solved INTEGER NOT NULL DEFAULT 0,
+    hard_solved INTEGER NOT NULL DEFAULT 0,
+    -- Why the last sync failed, and how many failed in a row
+    sync_error TEXT,
+    sync_failures SMALLINT NOT NULL DEFAULT 0,
+    synced_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, platform)
+);
+
+CREATE INDEX IF NOT EXISTS idx_code_practice_accounts_sync
+    ON code_practice_accounts(synced_at NULLS FIRST) WHERE handle IS NOT NULL;
+
+-- Solved problems per topic, replaced on every sync or import
+CREATE TABLE IF NOT EXISTS code_practice_topics (
+    account_id INTEGER NOT NULL REFERENCES code_practice_accounts(id) ON DELETE CASCADE,
+    topic VARCHAR(100) NOT NULL,
+    solved INTEGER NOT NULL,
+    PRIMARY KEY (account_id, topic)
+);
@@ -0,0 +1,17 @@
+-- Migration: Role readiness
+-- Latest readiness score per user and target role, with the components it
+-- was computed from, recalculated in the background. Application timing
+-- nudges are sent once a roadmap's role reaches the nudge score.
+
+CREATE TABLE IF NOT EXISTS role_readiness (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(255) NOT NULL,
+    -- 0-100
+    score DOUBLE PRECISION NOT NULL,
+    -- Name, score, weight, points, and detail of each component
+    components JSONB NOT NULL DEFAULT '[]',
+    calculated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_role_readiness_user_role ON role_readiness(user_id, LOWER(role));
+CREATE INDEX IF NOT EXISTS idx_role_readiness_calculated ON role_readiness(calculated_at);
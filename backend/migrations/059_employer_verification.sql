@@ -0,0 +1,55 @@
+-- Migration: Employer verification
+-- Companies must be verified before their members can post jobs: a work
+-- email on the company's domain, a business registry lookup, and a
+-- registration document, approved by an admin. Verified companies show a
+-- badge on their postings.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'employer_verification_status') THEN
+        CREATE TYPE employer_verification_status AS ENUM ('unverified', 'pending', 'verified', 'rejected');
+    END IF;
+END $$;
+
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS verification_status employer_verification_status NOT NULL DEFAULT 'unverified';
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS verified_at TIMESTAMP WITH TIME ZONE;
+
+-- One row per verification attempt; `unverified` until submitted for review
+CREATE TABLE IF NOT EXISTS employer_verifications (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status employer_verification_status NOT NULL DEFAULT 'unverified',
+    work_email VARCHAR(255),
+    -- SHA-256 of the emailed code, cleared once the email is verified
+    email_code_hash VARCHAR(64),
+    email_code_expires_at TIMESTAMP WITH TIME ZONE,
+    email_code_attempts SMALLINT NOT NULL DEFAULT 0,
+    email_verified_at TIMESTAMP WITH TIME ZONE,
+    registration_number VARCHAR(100),
+    registration_country CHAR(2),
+    -- found, not_found, unavailable, or skipped (no registry configured)
+    registry_status VARCHAR(20),
+    registry_details JSONB,
+    document_file_name VARCHAR(255),
+    document_storage_key TEXT,
+    submitted_at TIMESTAMP WITH TIME ZONE,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- At most one open (not yet reviewed) attempt per company
+CREATE UNIQUE INDEX IF NOT EXISTS idx_employer_verifications_open
+    ON employer_verifications(company_id) WHERE status IN ('unverified', 'pending');
+CREATE INDEX IF NOT EXISTS idx_employer_verifications_queue
+    ON employer_verifications(submitted_at) WHERE status = 'pending';
+
+CREATE OR REPLACE FUNCTION employer_verified(p_company_id INTEGER)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM companies WHERE id = p_company_id AND verification_status = 'verified'
+    )
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,46 @@
+-- Migration: Scam detection
+-- Suspicious employer posts are quarantined by pre-screening, and suspicious
+-- listings from external job feeds are held back until a moderator releases
+-- them. Moderator decisions on screened posts are kept as feedback to tune
+-- the quarantine threshold and the weight of each screening signal.
+
+ALTER TYPE moderation_status ADD VALUE IF NOT EXISTS 'quarantined';
+
+-- Feed listings withheld by screening; `approved` once released
+CREATE TABLE IF NOT EXISTS quarantined_imports (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(100) NOT NULL,
+    external_id VARCHAR(255) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    company VARCHAR(255) NOT NULL,
+    url TEXT NOT NULL,
+    risk_score INTEGER NOT NULL,
+    screening JSONB NOT NULL,
+    status moderation_status NOT NULL DEFAULT 'quarantined',
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (source, external_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_imports_queue
+    ON quarantined_imports(risk_score DESC, created_at)
+    WHERE status = 'quarantined';
+
+-- Moderator verdicts on screened posts and listings
+CREATE TABLE IF NOT EXISTS scam_screening_feedback (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    import_id INTEGER REFERENCES quarantined_imports(id) ON DELETE SET NULL,
+    risk_score INTEGER NOT NULL,
+    -- Screening signals that fired, e.g. pay_to_apply
+    signals TEXT[] NOT NULL DEFAULT '{}',
+    quarantined BOOLEAN NOT NULL,
+    -- The moderator's verdict: FALSE marks a false positive when quarantined
+    confirmed_scam BOOLEAN NOT NULL,
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_scam_screening_feedback_created_at
+    ON scam_screening_feedback(created_at DESC);
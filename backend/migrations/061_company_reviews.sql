@@ -0,0 +1,44 @@
+-- Migration: Company reviews
+-- Candidates who reached at least the interview stage review a company's
+-- hiring process. Reviews are aggregated on the company page, can be
+-- reported (hidden once enough reports are open), and the company may
+-- publish one reply to each.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'company_review_status') THEN
+        CREATE TYPE company_review_status AS ENUM ('published', 'hidden', 'removed');
+    END IF;
+END $$;
+
+ALTER TYPE report_target_type ADD VALUE IF NOT EXISTS 'review';
+
+CREATE TABLE IF NOT EXISTS company_reviews (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- One review per application
+    application_id INTEGER NOT NULL UNIQUE REFERENCES application_tracking(id) ON DELETE CASCADE,
+    job_title VARCHAR(255) NOT NULL,
+    -- Furthest stage the application reached when reviewed
+    stage_reached VARCHAR(50) NOT NULL,
+    overall_rating SMALLINT NOT NULL CHECK (overall_rating BETWEEN 1 AND 5),
+    response_time_rating SMALLINT NOT NULL CHECK (response_time_rating BETWEEN 1 AND 5),
+    transparency_rating SMALLINT NOT NULL CHECK (transparency_rating BETWEEN 1 AND 5),
+    -- Days the company took to first respond
+    response_days SMALLINT CHECK (response_days >= 0),
+    headline VARCHAR(150) NOT NULL,
+    body TEXT NOT NULL,
+    -- hidden while reports are open, removed by a moderator
+    status company_review_status NOT NULL DEFAULT 'published',
+    employer_reply TEXT,
+    replied_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    replied_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_company_reviews_company
+    ON company_reviews(company_id, created_at DESC)
+    WHERE status = 'published';
+CREATE INDEX IF NOT EXISTS idx_company_reviews_user_id ON company_reviews(user_id);
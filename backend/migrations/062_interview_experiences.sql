@@ -0,0 +1,42 @@
+-- Migration: Interview experiences
+-- Candidates share how a company's interviews for a role went: the rounds,
+-- the questions asked, and the outcome. Posts are anonymous to readers and
+-- wait for a moderator before they are published. Published posts also
+-- ground the interview questions generated in application packages.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'interview_outcome') THEN
+        CREATE TYPE interview_outcome AS ENUM ('offer', 'rejected', 'withdrew', 'no_response', 'pending');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS interview_experiences (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(255) NOT NULL,
+    -- Month the interviews took place
+    interviewed_on DATE,
+    rounds SMALLINT NOT NULL CHECK (rounds BETWEEN 1 AND 20),
+    -- One line per round, e.g. "Take-home: build a REST API in 3 days"
+    round_details TEXT[] NOT NULL DEFAULT '{}',
+    questions TEXT[] NOT NULL DEFAULT '{}',
+    summary TEXT NOT NULL,
+    difficulty SMALLINT CHECK (difficulty BETWEEN 1 AND 5),
+    outcome interview_outcome NOT NULL,
+    moderation_status moderation_status NOT NULL DEFAULT 'pending',
+    moderation_reason TEXT,
+    moderated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    moderated_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_interview_experiences_company
+    ON interview_experiences(company_id, created_at DESC)
+    WHERE moderation_status = 'approved';
+CREATE INDEX IF NOT EXISTS idx_interview_experiences_pending
+    ON interview_experiences(created_at)
+    WHERE moderation_status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_interview_experiences_user_id ON interview_experiences(user_id);
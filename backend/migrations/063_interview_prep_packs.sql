@@ -0,0 +1,30 @@
+-- Migration: Interview prep packs
+-- Candidates schedule an interview on an application. 48 hours before it, a
+-- prep pack (likely questions grounded in shared interview experiences, the
+-- STAR stories to rehearse, and facts about the company) is generated and
+-- the candidate is notified. A pack can be regenerated at any time.
+
+ALTER TABLE application_tracking ADD COLUMN IF NOT EXISTS interview_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_application_tracking_interview_at
+    ON application_tracking(interview_at)
+    WHERE interview_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS interview_prep_packs (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL UNIQUE REFERENCES application_tracking(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The interview the pack was prepared for; rescheduling prepares a new pack
+    interview_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- questions, star_stories, and company; NULL until generated
+    content JSONB,
+    provider VARCHAR(20),
+    -- Why the last generation failed
+    error TEXT,
+    generated_at TIMESTAMP WITH TIME ZONE,
+    notified_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_interview_prep_packs_user_id ON interview_prep_packs(user_id);
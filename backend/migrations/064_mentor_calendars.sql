@@ -0,0 +1,89 @@
+-- Migration: Mentor sessions and calendar availability sync
+-- Any user can open a mentor profile and publish session slots that other
+-- users book. Mentors may connect a calendar (Google Calendar or CalDAV);
+-- its busy times are synced on the scheduler, open slots that clash with an
+-- external event are released, and a booking re-checks the calendar before
+-- it is confirmed. Calendar credentials are encrypted at rest.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'mentor_slot_status') THEN
+        CREATE TYPE mentor_slot_status AS ENUM ('open', 'booked', 'released');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'mentor_booking_status') THEN
+        CREATE TYPE mentor_booking_status AS ENUM ('confirmed', 'cancelled', 'completed');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'calendar_provider') THEN
+        CREATE TYPE calendar_provider AS ENUM ('google', 'caldav');
+    END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS mentor_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    headline VARCHAR(150) NOT NULL,
+    bio TEXT,
+    expertise TEXT[] NOT NULL DEFAULT '{}',
+    session_minutes SMALLINT NOT NULL DEFAULT 30 CHECK (session_minutes BETWEEN 15 AND 180),
+    -- Inactive mentors are not listed and their slots cannot be booked
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS mentor_slots (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- released: an event on the mentor's calendar took the time; reopened
+    -- when the event goes away
+    status mentor_slot_status NOT NULL DEFAULT 'open',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at > starts_at),
+    UNIQUE (mentor_id, starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_slots_open ON mentor_slots(mentor_id, starts_at) WHERE status = 'open';
+
+CREATE TABLE IF NOT EXISTS mentor_bookings (
+    id SERIAL PRIMARY KEY,
+    slot_id INTEGER NOT NULL REFERENCES mentor_slots(id) ON DELETE CASCADE,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    topic TEXT NOT NULL,
+    status mentor_booking_status NOT NULL DEFAULT 'confirmed',
+    cancelled_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    cancel_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A slot has at most one live booking
+CREATE UNIQUE INDEX IF NOT EXISTS idx_mentor_bookings_live_slot ON mentor_bookings(slot_id) WHERE status <> 'cancelled';
+CREATE INDEX IF NOT EXISTS idx_mentor_bookings_mentor_id ON mentor_bookings(mentor_id);
+CREATE INDEX IF NOT EXISTS idx_mentor_bookings_mentee_id ON mentor_bookings(mentee_id);
+
+CREATE TABLE IF NOT EXISTS mentor_calendars (
+    mentor_id UUID PRIMARY KEY REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    provider calendar_provider NOT NULL,
+    -- CalDAV calendar collection URL, or the Google calendar ID
+    calendar_url TEXT NOT NULL,
+    -- CalDAV user name; NULL for Google
+    username TEXT,
+    -- Encrypted CalDAV password or Google refresh token
+    credential BYTEA NOT NULL,
+    synced_at TIMESTAMP WITH TIME ZONE,
+    sync_failures SMALLINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Busy times read from the mentor's calendar at the last sync
+CREATE TABLE IF NOT EXISTS mentor_busy_periods (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_busy_periods_mentor ON mentor_busy_periods(mentor_id, starts_at);
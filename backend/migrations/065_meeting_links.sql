@@ -0,0 +1,10 @@
+-- Migration: Video meeting links
+-- Confirmed mentor bookings and scheduled interviews get a video meeting URL
+-- from the configured meeting provider (Jitsi or Zoom). Mentor sessions are
+-- also reminded shortly before they start, with the link.
+
+ALTER TABLE mentor_bookings ADD COLUMN IF NOT EXISTS meeting_url TEXT;
+ALTER TABLE mentor_bookings ADD COLUMN IF NOT EXISTS meeting_provider VARCHAR(20);
+ALTER TABLE mentor_bookings ADD COLUMN IF NOT EXISTS reminded_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE application_tracking ADD COLUMN IF NOT EXISTS meeting_url TEXT;
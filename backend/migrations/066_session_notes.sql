@@ -0,0 +1,28 @@
+-- Migration: Mentor session notes and action items
+-- After a session the mentor records notes, shared with the mentee, and
+-- action items assigned to the mentee. The mentee can turn an action item
+-- into a task on one of their roadmaps, which is remembered so it is only
+-- added once.
+
+CREATE TABLE IF NOT EXISTS mentor_session_notes (
+    booking_id INTEGER PRIMARY KEY REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    notes TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS mentor_action_items (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(300) NOT NULL,
+    details TEXT,
+    due_on DATE,
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    roadmap_resource VARCHAR(20),
+    converted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_action_items_booking ON mentor_action_items(booking_id);
+CREATE INDEX IF NOT EXISTS idx_mentor_action_items_mentee ON mentor_action_items(mentee_id, created_at DESC);
@@ -0,0 +1,65 @@
+-- Migration: Paid mentor sessions
+-- Mentors may charge for sessions. Booking a paid slot waits for payment
+-- through Stripe Checkout; the payment is held until the session is
+-- completed, refunded per the cancellation policy, and otherwise released
+-- to the mentor's balance, which mentors withdraw through payout requests
+-- that admins settle and reconcile.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'mentor_payment_status') THEN
+        CREATE TYPE mentor_payment_status AS ENUM ('pending', 'held', 'released', 'refunded', 'expired');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'mentor_payout_status') THEN
+        CREATE TYPE mentor_payout_status AS ENUM ('requested', 'paid', 'rejected');
+    END IF;
+END $$;
+
+ALTER TYPE mentor_booking_status ADD VALUE IF NOT EXISTS 'pending_payment';
+
+-- Price of one session in the currency's minor unit; 0 means free
+ALTER TABLE mentor_profiles ADD COLUMN IF NOT EXISTS session_price_cents INTEGER NOT NULL DEFAULT 0 CHECK (session_price_cents >= 0);
+ALTER TABLE mentor_profiles ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'usd';
+
+CREATE TABLE IF NOT EXISTS mentor_payouts (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+    currency VARCHAR(3) NOT NULL,
+    status mentor_payout_status NOT NULL DEFAULT 'requested',
+    -- Transfer reference recorded by the admin who paid it
+    reference VARCHAR(255),
+    note TEXT,
+    processed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    processed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_payouts_mentor ON mentor_payouts(mentor_id, requested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_mentor_payouts_requested ON mentor_payouts(requested_at) WHERE status = 'requested';
+
+CREATE TABLE IF NOT EXISTS mentor_payments (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL UNIQUE REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+    -- Platform's share, kept from the mentor's earnings
+    platform_fee_cents INTEGER NOT NULL CHECK (platform_fee_cents >= 0),
+    currency VARCHAR(3) NOT NULL,
+    status mentor_payment_status NOT NULL DEFAULT 'pending',
+    stripe_checkout_session_id VARCHAR(255) UNIQUE,
+    checkout_url TEXT,
+    stripe_payment_intent_id VARCHAR(255),
+    refunded_cents INTEGER NOT NULL DEFAULT 0 CHECK (refunded_cents >= 0),
+    stripe_refund_id VARCHAR(255),
+    -- Payout the mentor's share was claimed by
+    payout_id INTEGER REFERENCES mentor_payouts(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    paid_at TIMESTAMP WITH TIME ZONE,
+    released_at TIMESTAMP WITH TIME ZONE,
+    refunded_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_payments_mentor ON mentor_payments(mentor_id, status);
+CREATE INDEX IF NOT EXISTS idx_mentor_payments_held ON mentor_payments(booking_id) WHERE status = 'held';
@@ -0,0 +1,79 @@
+-- Migration: Disputes
+-- Either side of a mentor session, or a candidate over an application
+-- decision, can open a dispute. Both parties attach evidence, admins take
+-- the dispute under review and arbitrate it, and every status change is
+-- kept. A paid session's payment is held while its dispute is open and
+-- refunded or released per the outcome.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'dispute_subject') THEN
+        CREATE TYPE dispute_subject AS ENUM ('mentor_booking', 'application');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'dispute_status') THEN
+        CREATE TYPE dispute_status AS ENUM ('open', 'under_review', 'resolved', 'withdrawn');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'dispute_outcome') THEN
+        CREATE TYPE dispute_outcome AS ENUM ('upheld', 'partially_upheld', 'dismissed');
+    END IF;
+END $$;
+
+ALTER TYPE mentor_payment_status ADD VALUE IF NOT EXISTS 'disputed';
+
+CREATE TABLE IF NOT EXISTS disputes (
+    id SERIAL PRIMARY KEY,
+    subject dispute_subject NOT NULL,
+    booking_id INTEGER REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    application_id INTEGER REFERENCES application_tracking(id) ON DELETE CASCADE,
+    opened_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The other side: the other party of the session, or the job's poster
+    respondent_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    reason TEXT NOT NULL,
+    status dispute_status NOT NULL DEFAULT 'open',
+    outcome dispute_outcome,
+    -- Refunded to the mentee by the resolution of a paid session's dispute
+    refund_cents INTEGER CHECK (refund_cents >= 0),
+    resolution_note TEXT,
+    assigned_to UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    CHECK ((subject = 'mentor_booking') = (booking_id IS NOT NULL)),
+    CHECK ((subject = 'application') = (application_id IS NOT NULL))
+);
+
+-- At most one live dispute per session or application
+CREATE UNIQUE INDEX IF NOT EXISTS idx_disputes_live_booking
+    ON disputes(booking_id) WHERE status IN ('open', 'under_review');
+CREATE UNIQUE INDEX IF NOT EXISTS idx_disputes_live_application
+    ON disputes(application_id) WHERE status IN ('open', 'under_review');
+CREATE INDEX IF NOT EXISTS idx_disputes_opened_by ON disputes(opened_by, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_disputes_respondent ON disputes(respondent_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_disputes_queue ON disputes(created_at) WHERE status IN ('open', 'under_review');
+
+CREATE TABLE IF NOT EXISTS dispute_evidence (
+    id SERIAL PRIMARY KEY,
+    dispute_id INTEGER NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    submitted_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    note TEXT,
+    file_name VARCHAR(255),
+    storage_key TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (note IS NOT NULL OR storage_key IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dispute_evidence_dispute ON dispute_evidence(dispute_id, created_at);
+
+-- Every status change, with who made it
+CREATE TABLE IF NOT EXISTS dispute_events (
+    id SERIAL PRIMARY KEY,
+    dispute_id INTEGER NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    from_status dispute_status,
+    to_status dispute_status NOT NULL,
+    note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_dispute_events_dispute ON dispute_events(dispute_id, created_at);
@@ -0,0 +1,53 @@
+-- Migration: API keys and sandbox mode
+-- Integrators authenticate with API keys. A key with the `sandbox` scope
+-- creates disposable test users, jobs, and applications in its own sandbox:
+-- production users never see them, analytics leave them out, and they are
+-- purged nightly.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- Start of the key, to tell keys apart; the key itself is shown once
+    key_prefix VARCHAR(16) NOT NULL,
+    -- Hex SHA-256 of the key
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    -- What the key may do, e.g. {sandbox}
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id, created_at DESC);
+
+-- Sandbox a test user or job belongs to; NULL for production data
+ALTER TABLE users ADD COLUMN IF NOT EXISTS sandbox_key_id INTEGER REFERENCES api_keys(id) ON DELETE CASCADE;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS sandbox_key_id INTEGER REFERENCES api_keys(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_users_sandbox ON users(sandbox_key_id) WHERE sandbox_key_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_sandbox ON jobs(sandbox_key_id) WHERE sandbox_key_id IS NOT NULL;
+
+-- Whether a job (by its sandbox) is in the same sandbox as the user:
+-- production jobs for production users, a sandbox's jobs for its test users
+CREATE OR REPLACE FUNCTION same_sandbox(p_sandbox_key_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT p_sandbox_key_id IS NOT DISTINCT FROM (SELECT sandbox_key_id FROM users WHERE id = p_user_id)
+$$ LANGUAGE sql STABLE;
+
+-- Cohorts are production data: sandbox test users never join one, so they
+-- stay out of placement reports, at-risk lists, and cohort analytics
+CREATE OR REPLACE FUNCTION reject_sandbox_cohort_member()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM users WHERE id = NEW.user_id AND sandbox_key_id IS NOT NULL) THEN
+        RAISE EXCEPTION 'sandbox users cannot join cohorts';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cohort_members_no_sandbox ON cohort_members;
+CREATE TRIGGER cohort_members_no_sandbox
+    BEFORE INSERT OR UPDATE ON cohort_members
+    FOR EACH ROW EXECUTE FUNCTION reject_sandbox_cohort_member();
@@ -0,0 +1,43 @@
+-- Migration: Debug captures of AI generations
+-- Users who consent have their AI requests and the providers' raw responses
+-- captured for a day. Flagging a bad generation keeps its capture for
+-- admins, who can replay it against the current prompt version to check a
+-- fix. Unflagged captures are purged, and withdrawing consent deletes all.
+
+-- When the user consented to debug captures; NULL when they have not
+ALTER TABLE users ADD COLUMN IF NOT EXISTS debug_capture_consent_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS debug_captures (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(50) NOT NULL,
+    -- Provider and model that produced the response
+    provider VARCHAR(20) NOT NULL,
+    model VARCHAR(100) NOT NULL,
+    prompt_version VARCHAR(50) NOT NULL,
+    -- The request as the prompt is rendered from it: input, parameters
+    -- (context, history, sampling settings), and the settings applied
+    input TEXT NOT NULL,
+    parameters JSONB,
+    settings JSONB NOT NULL,
+    -- Text the provider returned, before parsing; NULL if the call failed
+    raw_response TEXT,
+    -- Parsed result, or the error
+    response JSONB NOT NULL,
+    success BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    flagged_at TIMESTAMP WITH TIME ZONE,
+    flag_reason TEXT,
+    -- Latest replay by an admin
+    replayed_at TIMESTAMP WITH TIME ZONE,
+    replayed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    replay_provider VARCHAR(20),
+    replay_model VARCHAR(100),
+    replay_prompt_version VARCHAR(50),
+    replay_response JSONB,
+    replay_success BOOLEAN
+);
+
+CREATE INDEX IF NOT EXISTS idx_debug_captures_user ON debug_captures(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_debug_captures_flagged ON debug_captures(flagged_at) WHERE flagged_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_debug_captures_unflagged ON debug_captures(created_at) WHERE flagged_at IS NULL;
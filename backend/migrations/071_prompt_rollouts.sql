@@ -0,0 +1,64 @@
+-- Migration: Prompt rollouts
+-- A new prompt version ships next to the current one and is served to a
+-- share of traffic first. Usage logs record when a provider's output could
+-- not be used, and users rate AI results with a thumbs up or down. A
+-- rollout whose candidate exceeds its parse-failure or thumbs-down rate
+-- threshold is rolled back to the current version automatically.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'prompt_rollout_status') THEN
+        CREATE TYPE prompt_rollout_status AS ENUM ('active', 'promoted', 'rolled_back', 'completed');
+    END IF;
+END $$;
+
+-- The provider answered, but its output was malformed, off-schema, or unsafe
+ALTER TABLE ai_usage_logs ADD COLUMN IF NOT EXISTS parse_failed BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_ai_usage_logs_prompt_version ON ai_usage_logs(prompt_version, created_at);
+
+CREATE TABLE IF NOT EXISTS ai_feedback (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(50) NOT NULL,
+    -- Version of the prompt that produced the rated result
+    prompt_version VARCHAR(50) NOT NULL,
+    -- Thumbs up (TRUE) or down (FALSE)
+    helpful BOOLEAN NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_feedback_prompt_version ON ai_feedback(prompt_version, created_at);
+
+CREATE TABLE IF NOT EXISTS prompt_rollouts (
+    id SERIAL PRIMARY KEY,
+    action VARCHAR(50) NOT NULL,
+    -- Version served outside the rollout, and to everyone after a rollback
+    baseline_version VARCHAR(50) NOT NULL,
+    candidate_version VARCHAR(50) NOT NULL,
+    -- active: the candidate gets traffic_percent of requests; promoted: all
+    -- of them, until a release makes it the current version (completed)
+    status prompt_rollout_status NOT NULL DEFAULT 'active',
+    traffic_percent INTEGER NOT NULL CHECK (traffic_percent BETWEEN 1 AND 100),
+    -- Rolled back when the candidate's rates exceed these, once it has
+    -- min_samples calls (parse failures) or ratings (thumbs down)
+    max_parse_failure_rate REAL NOT NULL CHECK (max_parse_failure_rate BETWEEN 0 AND 1),
+    max_thumbs_down_rate REAL NOT NULL CHECK (max_thumbs_down_rate BETWEEN 0 AND 1),
+    min_samples INTEGER NOT NULL CHECK (min_samples > 0),
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    promoted_at TIMESTAMP WITH TIME ZONE,
+    rolled_back_at TIMESTAMP WITH TIME ZONE,
+    -- NULL for an automatic rollback
+    rolled_back_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    rollback_reason TEXT,
+    -- Candidate and baseline rates when rolled back
+    rollback_metrics JSONB,
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+
+-- One live rollout per action
+CREATE UNIQUE INDEX IF NOT EXISTS idx_prompt_rollouts_live
+    ON prompt_rollouts(action) WHERE status IN ('active', 'promoted');
@@ -0,0 +1,6 @@
+-- Migration: Answer sections
+-- Mentor answers cut off by a provider's output token limit are continued
+-- server-side and stored as one message. The sections it was stitched
+-- from are kept with it as [{start, end, truncated}], in characters.
+
+ALTER TABLE mentor_messages ADD COLUMN IF NOT EXISTS sections JSONB;
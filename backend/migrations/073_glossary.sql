@@ -0,0 +1,36 @@
+-- Migration: Glossary
+-- Beginner-friendly definitions of career and technical jargon. A term is
+-- explained by the AI the first time anyone asks and served from here
+-- after that; roadmap topics and mentor answers are annotated with the
+-- terms they contain. term_key is the term lowercased with single spaces.
+
+CREATE TABLE IF NOT EXISTS glossary (
+    id SERIAL PRIMARY KEY,
+    term_key VARCHAR(100) NOT NULL UNIQUE,
+    -- The term as first explained, e.g. 'CI/CD'
+    term VARCHAR(100) NOT NULL,
+    definition TEXT NOT NULL,
+    example TEXT,
+    -- NULL for the terms seeded below
+    ai_provider VARCHAR(20),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Terms nearly every roadmap uses, so annotations work from the start
+INSERT INTO glossary (term_key, term, definition, example)
+VALUES
+    ('api', 'API', 'A set of rules that lets one program ask another for data or actions, like a menu of requests it accepts.', 'The mobile app calls the weather API to get today''s forecast.'),
+    ('rest api', 'REST API', 'An API that works over the web, where each address stands for a kind of data and requests like GET or POST read or change it.', 'We added a REST API endpoint that returns a user''s orders.'),
+    ('git', 'Git', 'A tool that records every change to a project''s files, so you can go back to earlier versions and combine work from several people.', 'Commit your changes to Git before trying the refactor.'),
+    ('version control', 'Version control', 'Keeping a history of every change to code so changes can be reviewed, shared, and undone.', 'All our projects are under version control on GitHub.'),
+    ('ci/cd', 'CI/CD', 'Continuous integration and delivery: every change is automatically tested and, if it passes, released.', 'The CI/CD pipeline runs the tests on every pull request.'),
+    ('docker', 'Docker', 'A tool that packages an app with everything it needs to run, so it behaves the same on any computer.', 'Run the database locally with Docker instead of installing it.'),
+    ('framework', 'Framework', 'Ready-made code that gives an app its structure, so you only write the parts specific to your project.', 'React is the framework our frontend is built with.'),
+    ('frontend', 'Frontend', 'The part of an app people see and interact with, usually running in the browser or on the phone.', 'She works on the frontend, building the checkout pages.'),
+    ('backend', 'Backend', 'The part of an app that runs on servers: it stores data, applies business rules, and answers the frontend''s requests.', 'The backend checks the payment before confirming the order.'),
+    ('database', 'Database', 'Organized storage for an app''s data that can be searched and updated quickly.', 'User accounts are stored in a PostgreSQL database.'),
+    ('sql', 'SQL', 'The language used to read and change data in most databases.', 'Write a SQL query to find customers who ordered last week.'),
+    ('unit test', 'Unit test', 'A small automated check that one piece of code gives the expected result.', 'Add a unit test for the discount calculation.'),
+    ('deployment', 'Deployment', 'Putting a new version of an app on the servers where users can reach it.', 'The deployment goes out every Tuesday afternoon.')
+ON CONFLICT (term_key) DO NOTHING;
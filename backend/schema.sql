@@ -3,6 +3,7 @@ CREATE TYPE experience_level AS ENUM ('fresher', 'junior', 'mid');
 CREATE TYPE career_track AS ENUM ('web_development', 'data', 'design', 'marketing');
 CREATE TYPE job_type AS ENUM ('internship', 'part_time', 'full_time', 'freelance');
 CREATE TYPE cost_indicator AS ENUM ('free', 'paid');
+CREATE TYPE user_role AS ENUM ('job_seeker', 'employer', 'admin');
 
 -- Create users table
 CREATE TABLE users (
@@ -21,6 +22,7 @@ CREATE TABLE users (
     target_roles TEXT[] NOT NULL DEFAULT '{}',
     profile_completed BOOLEAN DEFAULT FALSE,
     raw_cv_text TEXT,
+    role user_role NOT NULL DEFAULT 'job_seeker',
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
@@ -40,6 +42,7 @@ CREATE TABLE jobs (
     responsibilities TEXT[] NOT NULL DEFAULT '{}',
     requirements TEXT[] NOT NULL DEFAULT '{}',
     benefits TEXT[] NOT NULL DEFAULT '{}',
+    posted_by UUID REFERENCES users(id) ON DELETE SET NULL,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -101,6 +104,11 @@ CREATE INDEX idx_users_email ON users(email);
 CREATE UNIQUE INDEX idx_users_oauth ON users(oauth_provider, oauth_id) WHERE oauth_provider IS NOT NULL;
 CREATE INDEX idx_jobs_experience_level ON jobs(experience_level);
 CREATE INDEX idx_jobs_job_type ON jobs(job_type);
+CREATE INDEX idx_jobs_posted_by ON jobs(posted_by);
+CREATE INDEX idx_jobs_fulltext ON jobs USING GIN (
+    to_tsvector('english', job_title || ' ' || company || ' ' || job_description)
+);
+CREATE INDEX idx_users_skills_gin ON users USING GIN (skills);
 CREATE INDEX idx_application_tracking_user_id ON application_tracking(user_id);
 CREATE INDEX idx_application_tracking_status ON application_tracking(status);
 CREATE INDEX idx_user_progress_user_id ON user_progress(user_id);
@@ -129,7 +137,7 @@ CREATE TABLE career_roadmaps (
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE INDEX idx_roadmaps_user_id ON career_roadmaps(user_id);
+CREATE INDEX idx_roadmaps_user_created ON career_roadmaps(user_id, created_at DESC);
 CREATE INDEX idx_roadmaps_created_at ON career_roadmaps(created_at DESC);
 CREATE INDEX idx_roadmaps_progress ON career_roadmaps(user_id, progress_percentage);
 CREATE INDEX idx_roadmaps_timeframe ON career_roadmaps(timeframe_months);
@@ -148,3 +156,2046 @@ CREATE TRIGGER update_roadmap_timestamp
     BEFORE UPDATE ON career_roadmaps
     FOR EACH ROW
     EXECUTE FUNCTION update_roadmap_timestamp();
+
+-- Employer dashboard read model (see migrations/004_employer_dashboard.sql)
+-- Denormalized per-job application counters
+CREATE TABLE job_application_stats (
+    job_id INTEGER PRIMARY KEY REFERENCES jobs(id) ON DELETE CASCADE,
+    applicant_count INTEGER NOT NULL DEFAULT 0,
+    new_count INTEGER NOT NULL DEFAULT 0,
+    in_review_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Adjusts the counters for a single application row.
+-- 'applied' counts as new; 'in_review' and 'interview' count as in review.
+CREATE OR REPLACE FUNCTION adjust_job_application_stats(p_job_id INTEGER, p_status TEXT, p_delta INTEGER)
+RETURNS VOID AS $$
+BEGIN
+    -- The job may already be gone when this runs from a cascading delete
+    INSERT INTO job_application_stats (job_id, applicant_count, new_count, in_review_count)
+    SELECT p_job_id, 0, 0, 0 WHERE EXISTS (SELECT 1 FROM jobs WHERE id = p_job_id)
+    ON CONFLICT (job_id) DO NOTHING;
+
+    UPDATE job_application_stats
+    SET applicant_count = applicant_count + p_delta,
+        new_count = new_count + CASE WHEN p_status = 'applied' THEN p_delta ELSE 0 END,
+        in_review_count = in_review_count + CASE WHEN p_status IN ('in_review', 'interview') THEN p_delta ELSE 0 END,
+        updated_at = CURRENT_TIMESTAMP
+    WHERE job_id = p_job_id;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION track_job_application_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM adjust_job_application_stats(NEW.job_id, NEW.status, 1);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF NEW.job_id IS DISTINCT FROM OLD.job_id OR NEW.status IS DISTINCT FROM OLD.status THEN
+            PERFORM adjust_job_application_stats(OLD.job_id, OLD.status, -1);
+            PERFORM adjust_job_application_stats(NEW.job_id, NEW.status, 1);
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM adjust_job_application_stats(OLD.job_id, OLD.status, -1);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER track_job_application_stats
+    AFTER INSERT OR UPDATE OR DELETE ON application_tracking
+    FOR EACH ROW
+    EXECUTE FUNCTION track_job_application_stats();
+
+-- Recomputes all counters from application_tracking.
+-- Returns the number of jobs whose counters were corrected.
+CREATE OR REPLACE FUNCTION reconcile_job_application_stats()
+RETURNS INTEGER AS $$
+DECLARE
+    corrected INTEGER;
+BEGIN
+    WITH actual AS (
+        SELECT j.id AS job_id,
+               COUNT(a.id)::INTEGER AS applicant_count,
+               COUNT(a.id) FILTER (WHERE a.status = 'applied')::INTEGER AS new_count,
+               COUNT(a.id) FILTER (WHERE a.status IN ('in_review', 'interview'))::INTEGER AS in_review_count
+        FROM jobs j
+        LEFT JOIN application_tracking a ON a.job_id = j.id
+        GROUP BY j.id
+    ),
+    upserted AS (
+        INSERT INTO job_application_stats (job_id, applicant_count, new_count, in_review_count, updated_at)
+        SELECT job_id, applicant_count, new_count, in_review_count, CURRENT_TIMESTAMP FROM actual
+        ON CONFLICT (job_id) DO UPDATE
+        SET applicant_count = EXCLUDED.applicant_count,
+            new_count = EXCLUDED.new_count,
+            in_review_count = EXCLUDED.in_review_count,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE job_application_stats.applicant_count <> EXCLUDED.applicant_count
+           OR job_application_stats.new_count <> EXCLUDED.new_count
+           OR job_application_stats.in_review_count <> EXCLUDED.in_review_count
+        RETURNING 1
+    )
+    SELECT COUNT(*)::INTEGER INTO corrected FROM upserted;
+    RETURN corrected;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Partitioned high-volume logs (see migrations/005_partitioned_logs.sql)
+-- Creates the monthly partition containing month_start if it does not exist.
+-- Returns the partition name (<parent>_pYYYYMM).
+CREATE OR REPLACE FUNCTION create_monthly_partition(parent TEXT, month_start DATE)
+RETURNS TEXT AS $$
+DECLARE
+    partition_start DATE := date_trunc('month', month_start)::DATE;
+    partition_name TEXT := parent || '_p' || to_char(partition_start, 'YYYYMM');
+BEGIN
+    EXECUTE format(
+        'CREATE TABLE %I PARTITION OF %I FOR VALUES FROM (%L) TO (%L)',
+        partition_name,
+        parent,
+        partition_start,
+        (partition_start + INTERVAL '1 month')::DATE
+    );
+    RETURN partition_name;
+END;
+$$ LANGUAGE plpgsql;
+
+-- User-facing activity events (feeds, analytics)
+CREATE TABLE activity_events (
+    id BIGSERIAL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX idx_activity_events_user_id ON activity_events(user_id, created_at DESC);
+
+-- Per-call AI usage records
+CREATE TABLE ai_usage_logs (
+    id BIGSERIAL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(100) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    model VARCHAR(100),
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    latency_ms INTEGER,
+    success BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX idx_ai_usage_logs_user_id ON ai_usage_logs(user_id, created_at DESC);
+
+-- Security and administrative audit trail
+CREATE TABLE audit_logs (
+    id BIGSERIAL,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(100) NOT NULL,
+    target_type VARCHAR(100),
+    target_id VARCHAR(255),
+    metadata JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX idx_audit_logs_actor_id ON audit_logs(actor_id, created_at DESC);
+
+-- Default partitions catch rows outside any monthly range so inserts never fail
+CREATE TABLE activity_events_default PARTITION OF activity_events DEFAULT;
+CREATE TABLE ai_usage_logs_default PARTITION OF ai_usage_logs DEFAULT;
+CREATE TABLE audit_logs_default PARTITION OF audit_logs DEFAULT;
+
+-- Initial partitions: current month and the next two
+DO $$
+DECLARE
+    parent TEXT;
+    offset_months INTEGER;
+BEGIN
+    FOREACH parent IN ARRAY ARRAY['activity_events', 'ai_usage_logs', 'audit_logs'] LOOP
+        FOR offset_months IN 0..2 LOOP
+            PERFORM create_monthly_partition(
+                parent,
+                (date_trunc('month', CURRENT_DATE) + make_interval(months => offset_months))::DATE
+            );
+        END LOOP;
+    END LOOP;
+END $$;
+
+-- Career mentor conversation history (see migrations/007_mentor_conversations.sql)
+CREATE TABLE mentor_conversations (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE mentor_messages (
+    id SERIAL PRIMARY KEY,
+    conversation_id INTEGER NOT NULL REFERENCES mentor_conversations(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('user', 'assistant')),
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_mentor_conversations_user ON mentor_conversations(user_id, updated_at DESC);
+CREATE INDEX idx_mentor_messages_conversation ON mentor_messages(conversation_id, created_at);
+
+-- Billing plans, Stripe subscriptions, and daily AI quotas (see migrations/008_billing.sql)
+CREATE TABLE plans (
+    id VARCHAR(50) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    stripe_price_id VARCHAR(255),
+    monthly_price_cents INTEGER NOT NULL DEFAULT 0,
+    ai_daily_quota INTEGER NOT NULL CHECK (ai_daily_quota > 0),
+    premium_templates BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO plans (id, name, monthly_price_cents, ai_daily_quota, premium_templates)
+VALUES
+    ('free', 'Free', 0, 20, FALSE),
+    ('premium', 'Premium', 499, 200, TRUE);
+
+CREATE TABLE subscriptions (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    plan_id VARCHAR(50) NOT NULL REFERENCES plans(id),
+    stripe_customer_id VARCHAR(255),
+    stripe_subscription_id VARCHAR(255) UNIQUE,
+    status VARCHAR(50) NOT NULL,
+    current_period_end TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE ai_daily_usage (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    usage_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, usage_date)
+);
+
+-- Usage-based credits ledger for AI features (see migrations/009_credits.sql)
+CREATE TABLE credit_accounts (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    balance INTEGER NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    referral_code VARCHAR(16) UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE credit_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount INTEGER NOT NULL CHECK (amount <> 0),
+    reason VARCHAR(50) NOT NULL,
+    reference VARCHAR(255),
+    balance_after INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_credit_transactions_user ON credit_transactions(user_id, created_at DESC);
+CREATE UNIQUE INDEX idx_credit_transactions_once
+    ON credit_transactions(user_id, reason, reference)
+    WHERE reason IN ('signup_bonus', 'referral', 'achievement');
+
+CREATE TABLE credit_action_costs (
+    action VARCHAR(50) PRIMARY KEY,
+    cost INTEGER NOT NULL CHECK (cost >= 0)
+);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES
+    ('extract_skills', 2),
+    ('generate_roadmap', 5),
+    ('ask_question', 1),
+    ('generate_content', 2);
+
+-- Company accounts with per-seat recruiter roles (see migrations/010_employer_teams.sql)
+CREATE TYPE seat_role AS ENUM ('owner', 'recruiter', 'viewer');
+
+CREATE TABLE companies (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE company_members (
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    role seat_role NOT NULL,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (company_id, user_id)
+);
+
+CREATE TABLE company_invitations (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role seat_role NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_company_invitations_pending
+    ON company_invitations(company_id, LOWER(email))
+    WHERE accepted_at IS NULL;
+
+ALTER TABLE jobs ADD COLUMN company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL;
+CREATE INDEX idx_jobs_company_id ON jobs(company_id);
+
+ALTER TABLE audit_logs ADD COLUMN company_id INTEGER;
+CREATE INDEX idx_audit_logs_company_id ON audit_logs(company_id, created_at DESC);
+
+-- Job posting moderation (see migrations/011_job_moderation.sql)
+CREATE TYPE moderation_status AS ENUM ('pending', 'approved', 'rejected');
+
+ALTER TABLE jobs ADD COLUMN moderation_status moderation_status NOT NULL DEFAULT 'approved';
+ALTER TABLE jobs ADD COLUMN moderation_reason TEXT;
+ALTER TABLE jobs ADD COLUMN moderated_by UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE jobs ADD COLUMN moderated_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN scam_risk_score INTEGER;
+ALTER TABLE jobs ADD COLUMN screening JSONB;
+
+CREATE INDEX idx_jobs_pending_moderation
+    ON jobs(scam_risk_score DESC NULLS FIRST, created_at)
+    WHERE moderation_status = 'pending';
+
+-- Geocoded job locations for radius search (see migrations/012_job_geo.sql)
+ALTER TABLE jobs ADD COLUMN latitude DOUBLE PRECISION;
+ALTER TABLE jobs ADD COLUMN longitude DOUBLE PRECISION;
+ALTER TABLE jobs ADD COLUMN geocoded_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN is_remote BOOLEAN
+    GENERATED ALWAYS AS (location ~* '(remote|work from home|anywhere)') STORED;
+
+CREATE INDEX idx_jobs_coordinates ON jobs(latitude, longitude) WHERE latitude IS NOT NULL;
+CREATE INDEX idx_jobs_remote ON jobs(is_remote) WHERE is_remote;
+
+CREATE TABLE geocode_cache (
+    query VARCHAR(255) PRIMARY KEY,
+    latitude DOUBLE PRECISION,
+    longitude DOUBLE PRECISION,
+    display_name TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Job posting expiry (see migrations/013_job_expiry.sql)
+ALTER TABLE jobs ADD COLUMN expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE jobs ADD COLUMN closed_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_jobs_expiring ON jobs(expires_at) WHERE closed_at IS NULL;
+
+-- Candidate availability and work authorization (see migrations/014_candidate_availability.sql)
+CREATE TYPE work_authorization_status AS ENUM ('citizen', 'permanent_resident', 'work_visa', 'needs_sponsorship');
+
+ALTER TABLE users ADD COLUMN available_from DATE;
+ALTER TABLE users ADD COLUMN desired_job_types job_type[] NOT NULL DEFAULT '{}';
+ALTER TABLE users ADD COLUMN willing_to_relocate BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN country_code CHAR(2);
+
+CREATE TABLE user_work_authorizations (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    country_code CHAR(2) NOT NULL,
+    status work_authorization_status NOT NULL,
+    PRIMARY KEY (user_id, country_code)
+);
+
+ALTER TABLE jobs ADD COLUMN country_code CHAR(2);
+ALTER TABLE jobs ADD COLUMN start_date DATE;
+ALTER TABLE jobs ADD COLUMN visa_sponsorship BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_jobs_country_code ON jobs(country_code);
+
+-- Whether a job passes a candidate's hard filters:
+-- * job type is one the candidate wants (if they named any)
+-- * the candidate is available by the job's start date
+-- * the candidate may work in the job's country, or the job sponsors visas
+--   (only checked once the candidate has declared any authorization)
+-- * on-site jobs abroad only match candidates willing to relocate
+CREATE OR REPLACE FUNCTION job_matches_candidate(p_job_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT
+        (cardinality(u.desired_job_types) = 0 OR j.job_type = ANY(u.desired_job_types))
+        AND (u.available_from IS NULL OR j.start_date IS NULL OR u.available_from <= j.start_date)
+        AND (
+            j.country_code IS NULL
+            OR j.visa_sponsorship
+            OR NOT EXISTS (SELECT 1 FROM user_work_authorizations a WHERE a.user_id = u.id)
+            OR EXISTS (
+                SELECT 1 FROM user_work_authorizations a
+                WHERE a.user_id = u.id
+                  AND a.country_code = j.country_code
+                  AND a.status <> 'needs_sponsorship'
+            )
+        )
+        AND (
+            u.willing_to_relocate
+            OR j.is_remote
+            OR u.country_code IS NULL
+            OR j.country_code IS NULL
+            OR j.country_code = u.country_code
+        )
+    FROM jobs j, users u
+    WHERE j.id = p_job_id AND u.id = p_user_id
+$$ LANGUAGE sql STABLE;
+
+-- Internship terms, institutions, and cohorts (see migrations/015_internships.sql)
+ALTER TABLE jobs ADD COLUMN term_start DATE;
+ALTER TABLE jobs ADD COLUMN term_end DATE;
+ALTER TABLE jobs ADD COLUMN academic_credit BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_jobs_internship_terms
+    ON jobs(term_start, term_end) WHERE job_type = 'internship';
+
+ALTER TABLE users ADD COLUMN available_terms TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TYPE org_role AS ENUM ('admin', 'advisor');
+
+CREATE TABLE organizations (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Staff of an institution (students are cohort members instead)
+CREATE TABLE organization_members (
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role org_role NOT NULL,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (org_id, user_id)
+);
+
+CREATE TABLE cohorts (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    join_code VARCHAR(32) NOT NULL UNIQUE,
+    min_profile_completeness INTEGER NOT NULL DEFAULT 0
+        CHECK (min_profile_completeness BETWEEN 0 AND 100),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_cohorts_org_id ON cohorts(org_id);
+
+CREATE TABLE cohort_members (
+    cohort_id INTEGER NOT NULL REFERENCES cohorts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (cohort_id, user_id)
+);
+
+CREATE INDEX idx_cohort_members_user_id ON cohort_members(user_id);
+
+-- Date range covered by an academic term written as 'YYYY-season'
+CREATE OR REPLACE FUNCTION academic_term_range(term TEXT)
+RETURNS DATERANGE AS $$
+    SELECT CASE split_part(term, '-', 2)
+        WHEN 'spring' THEN daterange(make_date(y, 1, 1), make_date(y, 6, 1))
+        WHEN 'summer' THEN daterange(make_date(y, 6, 1), make_date(y, 9, 1))
+        WHEN 'fall' THEN daterange(make_date(y, 9, 1), make_date(y + 1, 1, 1))
+    END
+    FROM (SELECT split_part(term, '-', 1)::INTEGER AS y) t
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Adds the internship term check to the hard filters above
+CREATE OR REPLACE FUNCTION job_matches_candidate(p_job_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT
+        (cardinality(u.desired_job_types) = 0 OR j.job_type = ANY(u.desired_job_types))
+        AND (u.available_from IS NULL OR j.start_date IS NULL OR u.available_from <= j.start_date)
+        AND (
+            j.country_code IS NULL
+            OR j.visa_sponsorship
+            OR NOT EXISTS (SELECT 1 FROM user_work_authorizations a WHERE a.user_id = u.id)
+            OR EXISTS (
+                SELECT 1 FROM user_work_authorizations a
+                WHERE a.user_id = u.id
+                  AND a.country_code = j.country_code
+                  AND a.status <> 'needs_sponsorship'
+            )
+        )
+        AND (
+            u.willing_to_relocate
+            OR j.is_remote
+            OR u.country_code IS NULL
+            OR j.country_code IS NULL
+            OR j.country_code = u.country_code
+        )
+        AND (
+            j.job_type <> 'internship'
+            OR j.term_start IS NULL
+            OR cardinality(u.available_terms) = 0
+            OR EXISTS (
+                SELECT 1 FROM unnest(u.available_terms) AS t(term)
+                WHERE academic_term_range(t.term)
+                    && daterange(j.term_start, COALESCE(j.term_end, j.term_start), '[]')
+            )
+        )
+    FROM jobs j, users u
+    WHERE j.id = p_job_id AND u.id = p_user_id
+$$ LANGUAGE sql STABLE;
+
+-- Career-fair events, booths, RSVPs, and leads (see migrations/016_career_events.sql)
+CREATE TYPE lead_status AS ENUM ('new', 'invited', 'applied', 'dismissed');
+
+CREATE TABLE career_events (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    location VARCHAR(255),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL CHECK (ends_at > starts_at),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_career_events_starts_at ON career_events(starts_at);
+CREATE INDEX idx_career_events_org_id ON career_events(org_id);
+
+CREATE TABLE event_sessions (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    location VARCHAR(255),
+    speaker VARCHAR(255),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX idx_event_sessions_event_id ON event_sessions(event_id, starts_at);
+
+CREATE TABLE event_booths (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    label VARCHAR(100),
+    UNIQUE (event_id, company_id)
+);
+
+CREATE TABLE event_rsvps (
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    badge_code VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (event_id, user_id),
+    UNIQUE (event_id, badge_code)
+);
+
+CREATE TABLE event_session_rsvps (
+    session_id INTEGER NOT NULL REFERENCES event_sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, user_id)
+);
+
+CREATE TABLE event_interactions (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES career_events(id) ON DELETE CASCADE,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    recorded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    notes TEXT,
+    rating SMALLINT CHECK (rating BETWEEN 1 AND 5),
+    lead_status lead_status NOT NULL DEFAULT 'new',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_event_interactions_company
+    ON event_interactions(company_id, created_at DESC);
+CREATE INDEX idx_event_interactions_candidate_job
+    ON event_interactions(candidate_id, job_id);
+
+-- Job offers and placements (see migrations/017_job_offers.sql)
+CREATE TYPE offer_status AS ENUM ('pending', 'accepted', 'declined', 'countered', 'withdrawn', 'expired');
+
+CREATE TABLE job_offers (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL REFERENCES application_tracking(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    base_salary INTEGER NOT NULL CHECK (base_salary >= 0),
+    currency CHAR(3) NOT NULL DEFAULT 'USD',
+    -- Further components: [{"label": "Signing bonus", "amount": 5000, "period": "one_time"}]
+    compensation JSONB NOT NULL DEFAULT '[]',
+    start_date DATE,
+    deadline TIMESTAMP WITH TIME ZONE NOT NULL,
+    message TEXT,
+    status offer_status NOT NULL DEFAULT 'pending',
+    candidate_note TEXT,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_job_offers_application_id ON job_offers(application_id);
+
+CREATE TABLE offer_documents (
+    id SERIAL PRIMARY KEY,
+    offer_id INTEGER NOT NULL REFERENCES job_offers(id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key TEXT NOT NULL UNIQUE,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_offer_documents_offer_id ON offer_documents(offer_id);
+
+-- One row per accepted offer; job details are copied so placements survive
+-- the posting being deleted.
+CREATE TABLE placements (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    offer_id INTEGER UNIQUE REFERENCES job_offers(id) ON DELETE SET NULL,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    job_title VARCHAR(255) NOT NULL,
+    company VARCHAR(255) NOT NULL,
+    base_salary INTEGER NOT NULL,
+    currency CHAR(3) NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE,
+    offered_at TIMESTAMP WITH TIME ZONE,
+    accepted_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_placements_user_id ON placements(user_id);
+CREATE INDEX idx_placements_accepted_at ON placements(accepted_at);
+
+-- Resumable multi-step wizards (see migrations/018_wizards.sql)
+CREATE TYPE wizard_kind AS ENUM ('cv_review');
+
+CREATE TYPE wizard_step_status AS ENUM ('pending', 'in_progress', 'completed', 'skipped', 'failed');
+
+CREATE TABLE wizards (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind wizard_kind NOT NULL,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_wizards_user ON wizards(user_id, updated_at DESC);
+
+-- One row per step, created with the wizard
+CREATE TABLE wizard_steps (
+    wizard_id INTEGER NOT NULL REFERENCES wizards(id) ON DELETE CASCADE,
+    step VARCHAR(50) NOT NULL,
+    position SMALLINT NOT NULL,
+    status wizard_step_status NOT NULL DEFAULT 'pending',
+    output JSONB,
+    error TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (wizard_id, step)
+);
+
+-- Per-request AI generation settings (see migrations/019_ai_generation_settings.sql)
+ALTER TABLE ai_usage_logs ADD COLUMN temperature REAL;
+ALTER TABLE ai_usage_logs ADD COLUMN max_output_tokens INTEGER;
+
+-- Deterministic AI generation (see migrations/020_deterministic_generation.sql)
+ALTER TABLE ai_usage_logs ADD COLUMN seed INTEGER;
+ALTER TABLE ai_usage_logs ADD COLUMN prompt_version VARCHAR(50);
+
+ALTER TABLE career_roadmaps ADD COLUMN ai_model VARCHAR(100);
+ALTER TABLE career_roadmaps ADD COLUMN prompt_version VARCHAR(50);
+-- {"temperature": 0.0, "max_output_tokens": null, "seed": 42}
+ALTER TABLE career_roadmaps ADD COLUMN generation_settings JSONB;
+
+-- Roadmap quality self-check (see migrations/021_roadmap_quality.sql)
+-- [{"criterion": "duration", "message": "Phase 3 packs 8 weeks of topics into 2 weeks", "fixed": false}]
+ALTER TABLE career_roadmaps ADD COLUMN quality_warnings JSONB;
+
+-- Resource link validation (see migrations/022_resource_links.sql)
+CREATE TYPE link_status AS ENUM ('unchecked', 'ok', 'broken');
+
+-- One row per distinct URL, wherever it is used
+CREATE TABLE resource_links (
+    url TEXT PRIMARY KEY,
+    status link_status NOT NULL DEFAULT 'unchecked',
+    -- HTTP status of the latest check; NULL if the request failed outright
+    http_status SMALLINT,
+    last_error TEXT,
+    -- Failed checks in a row; a link is flagged broken after several
+    consecutive_failures SMALLINT NOT NULL DEFAULT 0,
+    checked_at TIMESTAMP WITH TIME ZONE,
+    -- Verified replacement, already applied wherever the link was used
+    replacement_url TEXT,
+    replaced_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_resource_links_checked ON resource_links(checked_at NULLS FIRST)
+    WHERE replaced_at IS NULL;
+CREATE INDEX idx_resource_links_status ON resource_links(status);
+
+-- Skill proficiency self-assessment (see migrations/023_skill_proficiency.sql)
+CREATE TABLE user_skill_ratings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    skill VARCHAR(100) NOT NULL,
+    -- 1 = beginner, 3 = intermediate, 5 = expert
+    proficiency SMALLINT NOT NULL CHECK (proficiency BETWEEN 1 AND 5),
+    last_used_on DATE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Skills match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX idx_user_skill_ratings_skill ON user_skill_ratings(user_id, LOWER(skill));
+
+-- Per-skill learning progress across roadmaps (see migrations/024_skill_progress.sql)
+CREATE TYPE skill_progress_status AS ENUM ('learning', 'known');
+
+CREATE TYPE skill_progress_source AS ENUM ('roadmap', 'self_rating');
+
+CREATE TABLE user_skill_progress (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    skill VARCHAR(100) NOT NULL,
+    status skill_progress_status NOT NULL DEFAULT 'learning',
+    source skill_progress_source NOT NULL,
+    -- Roadmap whose completed phase marked the skill known
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    known_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Skills match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX idx_user_skill_progress_skill ON user_skill_progress(user_id, LOWER(skill));
+
+-- Mentor conversation summaries (see migrations/025_conversation_summaries.sql)
+-- {"points": ["Focus on TypeScript before React"], "action_items": ["Build a todo app with tests"]}
+ALTER TABLE mentor_conversations ADD COLUMN summary JSONB;
+ALTER TABLE mentor_conversations ADD COLUMN summary_message_count INTEGER;
+ALTER TABLE mentor_conversations ADD COLUMN summarized_at TIMESTAMP WITH TIME ZONE;
+
+-- Summaries are charged like a mentor question
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('summarize_conversation', 1)
+ON CONFLICT (action) DO NOTHING;
+
+-- Context window management for mentor conversations (see migrations/026_conversation_context.sql)
+ALTER TABLE mentor_conversations ADD COLUMN context_summary TEXT;
+ALTER TABLE mentor_conversations ADD COLUMN context_summary_through INTEGER;
+
+-- Retrieval over user documents (see migrations/027_document_retrieval.sql)
+CREATE EXTENSION vector;
+
+CREATE TYPE document_source AS ENUM ('cv', 'roadmap', 'document');
+
+-- Free-text documents a user saves for the mentor (notes, job descriptions, feedback)
+CREATE TABLE user_documents (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_user_documents_user ON user_documents(user_id, updated_at DESC);
+
+CREATE TABLE document_chunks (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source document_source NOT NULL,
+    -- Roadmap or document id; NULL for the CV
+    source_id INTEGER,
+    source_title VARCHAR(255) NOT NULL,
+    -- SHA-256 of the source text, to skip re-embedding unchanged sources
+    source_hash CHAR(64) NOT NULL,
+    chunk_index SMALLINT NOT NULL,
+    content TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_document_chunks_source ON document_chunks(user_id, source, source_id);
+CREATE INDEX idx_document_chunks_embedding ON document_chunks USING hnsw (embedding vector_cosine_ops);
+
+-- Curated knowledge base (see migrations/028_knowledge_base.sql)
+ALTER TYPE document_source ADD VALUE 'knowledge_base';
+
+CREATE TABLE kb_articles (
+    id SERIAL PRIMARY KEY,
+    slug VARCHAR(160) NOT NULL UNIQUE,
+    title VARCHAR(255) NOT NULL,
+    -- One or two sentences shown in listings
+    summary TEXT,
+    -- Markdown
+    content TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+    published BOOLEAN NOT NULL DEFAULT FALSE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    indexed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_kb_articles_published ON kb_articles(updated_at DESC) WHERE published;
+CREATE INDEX idx_kb_articles_tags ON kb_articles USING GIN (tags);
+
+CREATE TABLE kb_chunks (
+    id BIGSERIAL PRIMARY KEY,
+    article_id INTEGER NOT NULL REFERENCES kb_articles(id) ON DELETE CASCADE,
+    chunk_index SMALLINT NOT NULL,
+    content TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL
+);
+
+CREATE INDEX idx_kb_chunks_article ON kb_chunks(article_id);
+CREATE INDEX idx_kb_chunks_embedding ON kb_chunks USING hnsw (embedding vector_cosine_ops);
+
+-- Mentor tool calls (see migrations/029_ai_tool_calls.sql)
+CREATE TABLE ai_tool_calls (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the call answered a question in a saved conversation
+    conversation_id INTEGER REFERENCES mentor_conversations(id) ON DELETE SET NULL,
+    tool VARCHAR(100) NOT NULL,
+    arguments JSONB NOT NULL DEFAULT '{}'::jsonb,
+    result JSONB,
+    error TEXT,
+    latency_ms INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_ai_tool_calls_user ON ai_tool_calls(user_id, created_at DESC);
+CREATE INDEX idx_ai_tool_calls_tool ON ai_tool_calls(tool, created_at DESC);
+
+-- Application packages (see migrations/030_application_packages.sql)
+CREATE TYPE application_package_status AS ENUM ('queued', 'running', 'completed', 'failed');
+
+CREATE TABLE application_packages (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the package was built for a posting on the platform
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    job_title VARCHAR(255) NOT NULL,
+    company VARCHAR(255),
+    job_description TEXT NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    status application_package_status NOT NULL DEFAULT 'queued',
+    -- Runs started, including retries
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_application_packages_user ON application_packages(user_id, created_at DESC);
+CREATE INDEX idx_application_packages_queue ON application_packages(updated_at)
+    WHERE status IN ('queued', 'running');
+
+-- One row per step, created with the package
+CREATE TABLE application_package_steps (
+    package_id INTEGER NOT NULL REFERENCES application_packages(id) ON DELETE CASCADE,
+    step VARCHAR(50) NOT NULL,
+    position SMALLINT NOT NULL,
+    status application_package_status NOT NULL DEFAULT 'queued',
+    -- The step's artifact, e.g. {"content": "Dear hiring manager..."} for the cover letter
+    output JSONB,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (package_id, step)
+);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('analyze_job_description', 1), ('generate_interview_questions', 1)
+ON CONFLICT (action) DO NOTHING;
+
+-- Native PDF analysis (see migrations/031_native_pdf_analysis.sql)
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('transcribe_pdf', 2)
+ON CONFLICT (action) DO NOTHING;
+
+-- Certifications (see migrations/032_certifications.sql)
+CREATE TYPE certification_source AS ENUM ('manual', 'image');
+
+CREATE TABLE user_certifications (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    issuer VARCHAR(255),
+    issue_date DATE,
+    expiry_date DATE,
+    credential_id VARCHAR(255),
+    credential_url TEXT,
+    source certification_source NOT NULL DEFAULT 'manual',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_user_certifications_user ON user_certifications(user_id, issue_date DESC);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('read_image', 2)
+ON CONFLICT (action) DO NOTHING;
+
+-- Certificate verification (see migrations/033_certificate_verification.sql)
+CREATE TYPE certification_verification_status AS ENUM ('pending', 'verified', 'failed', 'unsupported');
+
+ALTER TABLE user_certifications
+    ADD COLUMN verification_status certification_verification_status NOT NULL DEFAULT 'pending',
+    ADD COLUMN verification_error TEXT,
+    -- Checks in a row that got no usable answer from the issuer
+    ADD COLUMN verification_attempts SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN verification_checked_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN verified_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_user_certifications_verification
+    ON user_certifications(verification_status, verification_checked_at);
+
+-- Partial profile updates (see migrations/034_profile_merge_patch.sql)
+ALTER TABLE users ADD COLUMN social_links JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX idx_audit_logs_target ON audit_logs(target_type, target_id, created_at DESC);
+
+-- Profile snapshots (see migrations/035_profile_snapshots.sql)
+CREATE TABLE profile_snapshots (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- What was about to change the profile, e.g. `ai_extract_skills`
+    reason VARCHAR(100) NOT NULL,
+    -- Profile fields as returned by GET /api/users/me
+    profile JSONB NOT NULL,
+    raw_cv_text TEXT,
+    reverted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_profile_snapshots_user ON profile_snapshots(user_id, created_at DESC);
+
+-- Skill and target role curation (see migrations/036_profile_item_curation.sql)
+CREATE TYPE profile_item_kind AS ENUM ('skill', 'target_role');
+
+CREATE TABLE user_rejected_profile_items (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind profile_item_kind NOT NULL,
+    value VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Items match case-insensitively, as in users.skills
+CREATE UNIQUE INDEX idx_user_rejected_profile_items_value
+    ON user_rejected_profile_items(user_id, kind, LOWER(value));
+
+-- Per-tenant rate limit policies (see migrations/037_rate_limit_policies.sql)
+CREATE TABLE rate_limit_policies (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    -- NULL applies the policy to every plan
+    plan_id VARCHAR(50) REFERENCES plans(id) ON DELETE CASCADE,
+    ai_daily_quota INTEGER NOT NULL CHECK (ai_daily_quota > 0),
+    note VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One policy per organization and plan (or all plans)
+CREATE UNIQUE INDEX idx_rate_limit_policies_scope
+    ON rate_limit_policies(org_id, COALESCE(plan_id, ''));
+
+-- Semantic cache of mentor answers (see migrations/038_mentor_answer_cache.sql)
+CREATE TABLE mentor_answer_cache (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- SHA-256 of the profile context the answer was given for
+    context_hash CHAR(64) NOT NULL,
+    question TEXT NOT NULL,
+    -- text-embedding-004
+    embedding vector(768) NOT NULL,
+    answer JSONB NOT NULL,
+    sources JSONB NOT NULL DEFAULT '[]'::jsonb,
+    provider VARCHAR(20) NOT NULL,
+    hit_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_mentor_answer_cache_lookup
+    ON mentor_answer_cache(user_id, context_hash, created_at DESC);
+
+-- AI evaluation runs against golden fixtures (see migrations/039_ai_eval.sql)
+CREATE TABLE ai_eval_runs (
+    id SERIAL PRIMARY KEY,
+    -- Free-form label, e.g. "nightly" or the prompt change under test
+    label VARCHAR(100),
+    case_count INTEGER NOT NULL DEFAULT 0,
+    pass_count INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE ai_eval_results (
+    id BIGSERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES ai_eval_runs(id) ON DELETE CASCADE,
+    case_name VARCHAR(100) NOT NULL,
+    action VARCHAR(50) NOT NULL,
+    provider VARCHAR(20) NOT NULL,
+    model VARCHAR(100) NOT NULL,
+    prompt_version VARCHAR(100) NOT NULL,
+    schema_valid BOOLEAN NOT NULL,
+    missing_fields TEXT[] NOT NULL DEFAULT '{}',
+    -- Share of expected keywords found in the output, 0 to 1
+    keyword_score DOUBLE PRECISION NOT NULL,
+    -- Mean of the schema, field, and keyword scores, 0 to 1
+    score DOUBLE PRECISION NOT NULL,
+    passed BOOLEAN NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_ai_eval_results_run ON ai_eval_results(run_id);
+CREATE INDEX idx_ai_eval_results_trend
+    ON ai_eval_results(provider, model, prompt_version, created_at DESC);
+
+-- Job application timing nudges (see migrations/040_application_timing_nudges.sql)
+ALTER TABLE career_roadmaps ADD COLUMN application_nudged_at TIMESTAMP WITH TIME ZONE;
+
+-- Roadmap progress events (see migrations/041_roadmap_progress_events.sql)
+CREATE TABLE roadmap_progress_events (
+    id BIGSERIAL PRIMARY KEY,
+    roadmap_id INTEGER NOT NULL REFERENCES career_roadmaps(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    progress_percentage INTEGER NOT NULL,
+    completed_phases INTEGER[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_roadmap_progress_events_roadmap ON roadmap_progress_events(roadmap_id, created_at);
+
+-- Multi-roadmap portfolio (see migrations/042_roadmap_portfolio.sql)
+ALTER TABLE users ADD COLUMN weekly_learning_hours INTEGER CHECK (weekly_learning_hours BETWEEN 1 AND 80);
+ALTER TABLE career_roadmaps ADD COLUMN is_primary BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE UNIQUE INDEX idx_roadmaps_primary ON career_roadmaps(user_id) WHERE is_primary;
+
+-- Roadmap resource progress (see migrations/043_roadmap_resource_progress.sql)
+CREATE TYPE resource_status AS ENUM ('completed', 'skipped');
+
+CREATE TABLE roadmap_resource_progress (
+    roadmap_id INTEGER NOT NULL REFERENCES career_roadmaps(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    phase INTEGER NOT NULL,
+    -- 1-based position of the resource in the phase's resources
+    resource_index INTEGER NOT NULL,
+    -- The resource as listed in the roadmap when it was marked
+    resource TEXT NOT NULL,
+    -- The resource's URL, or its lowercased text when it has none
+    resource_key TEXT NOT NULL,
+    status resource_status NOT NULL,
+    rating SMALLINT CHECK (rating BETWEEN 1 AND 5),
+    note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (roadmap_id, phase, resource_index)
+);
+
+CREATE INDEX idx_roadmap_resource_progress_key ON roadmap_resource_progress(resource_key);
+
+-- Learning resource click tracking (see migrations/044_resource_clicks.sql)
+ALTER TABLE learning_resources ADD COLUMN affiliate_params JSONB NOT NULL DEFAULT '{}';
+
+CREATE TABLE resource_clicks (
+    id BIGSERIAL PRIMARY KEY,
+    resource_id INTEGER NOT NULL REFERENCES learning_resources(id) ON DELETE CASCADE,
+    -- NULL for anonymous clicks
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- The clicking user's roadmap the link was followed from, if any
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    -- Whether affiliate parameters were added to the redirect
+    affiliate BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_resource_clicks_created ON resource_clicks(created_at);
+CREATE INDEX idx_resource_clicks_resource ON resource_clicks(resource_id, created_at);
+
+-- Employer branding (see migrations/045_company_branding.sql)
+ALTER TABLE companies ADD COLUMN description TEXT;
+ALTER TABLE companies ADD COLUMN industry VARCHAR(100);
+ALTER TABLE companies ADD COLUMN company_size VARCHAR(50);
+ALTER TABLE companies ADD COLUMN website TEXT;
+ALTER TABLE companies ADD COLUMN headquarters VARCHAR(255);
+ALTER TABLE companies ADD COLUMN culture_values TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE companies ADD COLUMN perks TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE company_branding (
+    company_id INTEGER PRIMARY KEY REFERENCES companies(id) ON DELETE CASCADE,
+    -- {"why_work_here": "...", "role_pitches": [{"role": "...", "pitch": "..."}]}
+    draft JSONB,
+    published JSONB,
+    generated_at TIMESTAMP WITH TIME ZONE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    published_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Screening questions (see migrations/046_screening_questions.sql)
+CREATE TYPE screening_question_type AS ENUM ('yes_no', 'number', 'text');
+
+CREATE TABLE screening_questions (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    question TEXT NOT NULL,
+    question_type screening_question_type NOT NULL,
+    -- yes_no: "yes" or "no"; number: the minimum accepted; text: what a
+    -- strong answer covers
+    expected_answer TEXT,
+    required BOOLEAN NOT NULL DEFAULT TRUE,
+    knockout BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_screening_questions_job ON screening_questions(job_id, position);
+
+CREATE TABLE screening_answers (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL REFERENCES application_tracking(id) ON DELETE CASCADE,
+    question_id INTEGER REFERENCES screening_questions(id) ON DELETE SET NULL,
+    question TEXT NOT NULL,
+    question_type screening_question_type NOT NULL,
+    knockout BOOLEAN NOT NULL DEFAULT FALSE,
+    answer TEXT NOT NULL,
+    -- 0-100; NULL until evaluated, or when there is nothing to score against
+    score SMALLINT CHECK (score BETWEEN 0 AND 100),
+    flagged BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT,
+    evaluated_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_screening_answers_application ON screening_answers(application_id);
+
+ALTER TABLE application_tracking ADD COLUMN screening_score SMALLINT;
+ALTER TABLE application_tracking ADD COLUMN screening_flagged BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Talent pools (see migrations/047_talent_pools.sql)
+CREATE TABLE talent_pools (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    -- Skills the pool looks for; gaining one triggers an alert
+    required_skills TEXT[] NOT NULL DEFAULT '{}',
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (company_id, name)
+);
+
+-- Skills (lowercase) a user holds: listed on their profile or marked known
+-- through a roadmap
+CREATE OR REPLACE FUNCTION held_skills(p_user_id UUID)
+RETURNS TEXT[] AS $$
+    SELECT ARRAY(
+        SELECT LOWER(s) FROM users u, unnest(u.skills) s WHERE u.id = p_user_id
+        UNION
+        SELECT LOWER(skill) FROM user_skill_progress WHERE user_id = p_user_id AND status = 'known'
+    )
+$$ LANGUAGE SQL STABLE;
+
+CREATE TABLE talent_pool_members (
+    pool_id INTEGER NOT NULL REFERENCES talent_pools(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    notes TEXT,
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Required skills (lowercase) the candidate held at the last check
+    seen_skills TEXT[] NOT NULL DEFAULT '{}',
+    -- Verified certifications already alerted on
+    seen_certifications INTEGER[] NOT NULL DEFAULT '{}',
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (pool_id, candidate_id)
+);
+
+CREATE INDEX idx_talent_pool_members_candidate ON talent_pool_members(candidate_id);
+
+CREATE TABLE talent_pool_alerts (
+    id SERIAL PRIMARY KEY,
+    pool_id INTEGER NOT NULL REFERENCES talent_pools(id) ON DELETE CASCADE,
+    candidate_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'skill' or 'verification'
+    alert_type VARCHAR(20) NOT NULL,
+    detail TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_talent_pool_alerts_pool ON talent_pool_alerts(pool_id, created_at DESC);
+
+-- Blocking and reports (see migrations/048_blocks_and_reports.sql)
+CREATE TABLE user_blocks (
+    id SERIAL PRIMARY KEY,
+    blocker_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the blocker holds a company seat; the block covers the company
+    blocker_company_id INTEGER REFERENCES companies(id) ON DELETE CASCADE,
+    blocked_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    blocked_company_id INTEGER REFERENCES companies(id) ON DELETE CASCADE,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((blocked_user_id IS NULL) <> (blocked_company_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_user_blocks_user
+    ON user_blocks(COALESCE(blocker_company_id::text, blocker_id::text), blocked_user_id)
+    WHERE blocked_user_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_user_blocks_company
+    ON user_blocks(blocker_id, blocked_company_id)
+    WHERE blocked_company_id IS NOT NULL;
+CREATE INDEX idx_user_blocks_blocked_user ON user_blocks(blocked_user_id);
+
+-- Whether a candidate and an employer (or their company) have blocked each
+-- other, in either direction
+CREATE OR REPLACE FUNCTION blocked_between(p_candidate_id UUID, p_employer_id UUID, p_company_id INTEGER)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM user_blocks b
+        WHERE (b.blocker_id = p_candidate_id
+               AND (b.blocked_user_id = p_employer_id OR b.blocked_company_id = p_company_id))
+           OR (b.blocked_user_id = p_candidate_id
+               AND (b.blocker_id = p_employer_id OR b.blocker_company_id = p_company_id))
+    )
+$$ LANGUAGE SQL STABLE;
+
+CREATE TYPE report_target_type AS ENUM ('job', 'user', 'company', 'message');
+CREATE TYPE report_category AS ENUM ('spam', 'scam', 'harassment', 'inappropriate', 'other');
+CREATE TYPE report_status AS ENUM ('open', 'resolved', 'dismissed');
+
+CREATE TABLE reports (
+    id SERIAL PRIMARY KEY,
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_type report_target_type NOT NULL,
+    -- Job or notification ID, user UUID, or company ID
+    target_id TEXT NOT NULL,
+    -- User answerable for the target: the job's poster or the message's sender
+    reported_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    reported_company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL,
+    -- Copy of the reported message, kept if the message is removed
+    excerpt TEXT,
+    category report_category NOT NULL,
+    details TEXT,
+    status report_status NOT NULL DEFAULT 'open',
+    -- `dismiss`, `warn`, or `remove`
+    resolution VARCHAR(20),
+    resolution_note TEXT,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_reports_open_once
+    ON reports(reporter_id, target_type, target_id) WHERE status = 'open';
+CREATE INDEX idx_reports_target ON reports(target_type, target_id, status);
+CREATE INDEX idx_reports_queue ON reports(status, created_at);
+
+ALTER TABLE notifications ADD COLUMN sender_id UUID REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE notifications ADD COLUMN sender_company_id INTEGER REFERENCES companies(id) ON DELETE SET NULL;
+
+-- Policy acceptance (see migrations/049_policy_acceptance.sql)
+CREATE TYPE policy_kind AS ENUM ('terms', 'privacy');
+
+CREATE TABLE policy_documents (
+    id SERIAL PRIMARY KEY,
+    kind policy_kind NOT NULL,
+    version INTEGER NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- What changed since the previous version, shown when asking to re-accept
+    summary TEXT,
+    published_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (kind, version)
+);
+
+CREATE TABLE policy_acceptances (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    policy_id INTEGER NOT NULL REFERENCES policy_documents(id) ON DELETE RESTRICT,
+    kind policy_kind NOT NULL,
+    version INTEGER NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Peer address of the connection
+    ip_address TEXT,
+    -- X-Forwarded-For header, when behind a proxy
+    forwarded_for TEXT,
+    user_agent TEXT,
+    UNIQUE (user_id, policy_id)
+);
+
+CREATE INDEX idx_policy_acceptances_policy ON policy_acceptances(policy_id, accepted_at);
+
+-- Impersonation sessions (see migrations/050_impersonation.sql)
+CREATE TABLE impersonation_sessions (
+    id SERIAL PRIMARY KEY,
+    admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set when an admin ends the session before it expires
+    ended_at TIMESTAMP WITH TIME ZONE,
+    -- Set once the user has been told about the session
+    notified_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_impersonation_sessions_user ON impersonation_sessions(user_id, started_at DESC);
+CREATE INDEX idx_impersonation_sessions_unnotified ON impersonation_sessions(expires_at) WHERE notified_at IS NULL;
+
+-- Admin who was acting as the actor, for entries made while impersonating
+ALTER TABLE audit_logs ADD COLUMN impersonated_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- Tenant configuration (see migrations/051_tenant_configs.sql)
+CREATE TABLE tenant_configs (
+    org_id INTEGER PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    -- Guidance for mentor answers, e.g. tone and services to point students to
+    mentor_persona TEXT,
+    -- Guidance for generated learning roadmaps
+    roadmap_guidance TEXT,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Bulk cohort roadmaps (see migrations/052_cohort_roadmap_batches.sql)
+CREATE TYPE roadmap_batch_status AS ENUM ('queued', 'running', 'completed', 'failed');
+
+CREATE TABLE cohort_roadmap_batches (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    cohort_id INTEGER NOT NULL REFERENCES cohorts(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    target_role VARCHAR(255) NOT NULL,
+    -- Roadmap the settings were copied from, if any
+    template_roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    timeframe_months INTEGER NOT NULL,
+    learning_hours_per_week INTEGER NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    status roadmap_batch_status NOT NULL DEFAULT 'queued',
+    -- Runs started, including retries
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_cohort_roadmap_batches_cohort ON cohort_roadmap_batches(cohort_id, created_at DESC);
+CREATE INDEX idx_cohort_roadmap_batches_queue ON cohort_roadmap_batches(updated_at)
+    WHERE status IN ('queued', 'running');
+
+-- One row per student, created with the batch
+CREATE TABLE cohort_roadmap_items (
+    batch_id INTEGER NOT NULL REFERENCES cohort_roadmap_batches(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status roadmap_batch_status NOT NULL DEFAULT 'queued',
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    error TEXT,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (batch_id, user_id)
+);
+
+-- Student nudges (see migrations/053_student_nudges.sql)
+CREATE TABLE student_nudges (
+    id SERIAL PRIMARY KEY,
+    org_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    sent_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Risk flags the student had, e.g. {stalled_roadmap,no_applications}
+    flags TEXT[] NOT NULL DEFAULT '{}',
+    message TEXT NOT NULL,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_student_nudges_org_user ON student_nudges(org_id, user_id, sent_at DESC);
+
+-- Offer document Q&A (see migrations/054_offer_qa_documents.sql)
+CREATE TABLE offer_qa_documents (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    -- Key of the encrypted file in storage
+    storage_key VARCHAR(512) NOT NULL,
+    chunk_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_offer_qa_documents_user ON offer_qa_documents(user_id, created_at DESC);
+
+-- Chunk text is encrypted; only embeddings are stored in the clear
+CREATE TABLE offer_qa_chunks (
+    document_id INTEGER NOT NULL REFERENCES offer_qa_documents(id) ON DELETE CASCADE,
+    chunk_index SMALLINT NOT NULL,
+    content_ciphertext BYTEA NOT NULL,
+    embedding vector(768) NOT NULL,
+    PRIMARY KEY (document_id, chunk_index)
+);
+
+-- Salary negotiation practice (see migrations/055_negotiation_sessions.sql)
+CREATE TYPE negotiation_status AS ENUM ('active', 'completed');
+
+CREATE TABLE negotiation_sessions (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    offer_id INTEGER REFERENCES job_offers(id) ON DELETE SET NULL,
+    -- Offer details and benchmarks the recruiter works from
+    scenario TEXT NOT NULL,
+    -- [{"role": "assistant" | "user", "content": "..."}], recruiter first
+    transcript JSONB NOT NULL DEFAULT '[]',
+    status negotiation_status NOT NULL DEFAULT 'active',
+    debrief JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_negotiation_sessions_user ON negotiation_sessions(user_id, created_at DESC);
+
+INSERT INTO credit_action_costs (action, cost)
+VALUES ('negotiation_debrief', 1)
+ON CONFLICT (action) DO NOTHING;
+
+-- STAR stories (see migrations/056_star_stories.sql)
+CREATE TYPE star_competency AS ENUM (
+    'leadership', 'teamwork', 'conflict', 'failure',
+    'problem_solving', 'initiative', 'communication', 'adaptability'
+);
+CREATE TYPE star_story_status AS ENUM ('draft', 'polished');
+
+CREATE TABLE star_stories (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    competencies star_competency[] NOT NULL DEFAULT '{}',
+    -- The project or role the story comes from
+    source VARCHAR(255),
+    situation TEXT NOT NULL,
+    task TEXT NOT NULL,
+    action TEXT NOT NULL,
+    result TEXT NOT NULL,
+    -- Questions whose answers would strengthen the story
+    follow_up_questions TEXT[] NOT NULL DEFAULT '{}',
+    -- The user's refinement inputs, oldest first
+    refinements TEXT[] NOT NULL DEFAULT '{}',
+    status star_story_status NOT NULL DEFAULT 'draft',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_star_stories_user ON star_stories(user_id, status, updated_at DESC);
+CREATE INDEX idx_star_stories_competencies ON star_stories USING GIN (competencies);
+
+-- Code challenge practice tracking (see migrations/057_code_practice.sql)
+CREATE TYPE code_practice_platform AS ENUM ('leetcode');
+
+CREATE TABLE code_practice_accounts (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    platform code_practice_platform NOT NULL,
+    -- Public handle synced on a schedule; NULL when progress only comes from uploaded exports
+    handle VARCHAR(100),
+    easy_solved INTEGER NOT NULL DEFAULT 0,
+    medium_solved INTEGER NOT NULL DEFAULT 0,
+    hard_solved INTEGER NOT NULL DEFAULT 0,
+    -- Why the last sync failed, and how many failed in a row
+    sync_error TEXT,
+    sync_failures SMALLINT NOT NULL DEFAULT 0,
+    synced_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, platform)
+);
+
+CREATE INDEX idx_code_practice_accounts_sync
+    ON code_practice_accounts(synced_at NULLS FIRST) WHERE handle IS NOT NULL;
+
+-- Solved problems per topic, replaced on every sync or import
+CREATE TABLE code_practice_topics (
+    account_id INTEGER NOT NULL REFERENCES code_practice_accounts(id) ON DELETE CASCADE,
+    topic VARCHAR(100) NOT NULL,
+    solved INTEGER NOT NULL,
+    PRIMARY KEY (account_id, topic)
+);
+
+-- Role readiness (see migrations/058_role_readiness.sql)
+CREATE TABLE role_readiness (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(255) NOT NULL,
+    -- 0-100
+    score DOUBLE PRECISION NOT NULL,
+    -- Name, score, weight, points, and detail of each component
+    components JSONB NOT NULL DEFAULT '[]',
+    calculated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_role_readiness_user_role ON role_readiness(user_id, LOWER(role));
+CREATE INDEX idx_role_readiness_calculated ON role_readiness(calculated_at);
+
+-- Employer verification (see migrations/059_employer_verification.sql)
+CREATE TYPE employer_verification_status AS ENUM ('unverified', 'pending', 'verified', 'rejected');
+
+ALTER TABLE companies ADD COLUMN verification_status employer_verification_status NOT NULL DEFAULT 'unverified';
+ALTER TABLE companies ADD COLUMN verified_at TIMESTAMP WITH TIME ZONE;
+
+-- One row per verification attempt; `unverified` until submitted for review
+CREATE TABLE employer_verifications (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status employer_verification_status NOT NULL DEFAULT 'unverified',
+    work_email VARCHAR(255),
+    -- SHA-256 of the emailed code, cleared once the email is verified
+    email_code_hash VARCHAR(64),
+    email_code_expires_at TIMESTAMP WITH TIME ZONE,
+    email_code_attempts SMALLINT NOT NULL DEFAULT 0,
+    email_verified_at TIMESTAMP WITH TIME ZONE,
+    registration_number VARCHAR(100),
+    registration_country CHAR(2),
+    -- found, not_found, unavailable, or skipped (no registry configured)
+    registry_status VARCHAR(20),
+    registry_details JSONB,
+    document_file_name VARCHAR(255),
+    document_storage_key TEXT,
+    submitted_at TIMESTAMP WITH TIME ZONE,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- At most one open (not yet reviewed) attempt per company
+CREATE UNIQUE INDEX idx_employer_verifications_open
+    ON employer_verifications(company_id) WHERE status IN ('unverified', 'pending');
+CREATE INDEX idx_employer_verifications_queue
+    ON employer_verifications(submitted_at) WHERE status = 'pending';
+
+CREATE OR REPLACE FUNCTION employer_verified(p_company_id INTEGER)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM companies WHERE id = p_company_id AND verification_status = 'verified'
+    )
+$$ LANGUAGE SQL STABLE;
+
+-- Scam detection (see migrations/060_scam_detection.sql)
+ALTER TYPE moderation_status ADD VALUE 'quarantined';
+
+CREATE TABLE quarantined_imports (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(100) NOT NULL,
+    external_id VARCHAR(255) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    company VARCHAR(255) NOT NULL,
+    url TEXT NOT NULL,
+    risk_score INTEGER NOT NULL,
+    screening JSONB NOT NULL,
+    status moderation_status NOT NULL DEFAULT 'quarantined',
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (source, external_id)
+);
+
+CREATE INDEX idx_quarantined_imports_queue
+    ON quarantined_imports(risk_score DESC, created_at)
+    WHERE status = 'quarantined';
+
+CREATE TABLE scam_screening_feedback (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER REFERENCES jobs(id) ON DELETE SET NULL,
+    import_id INTEGER REFERENCES quarantined_imports(id) ON DELETE SET NULL,
+    risk_score INTEGER NOT NULL,
+    signals TEXT[] NOT NULL DEFAULT '{}',
+    quarantined BOOLEAN NOT NULL,
+    confirmed_scam BOOLEAN NOT NULL,
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_scam_screening_feedback_created_at ON scam_screening_feedback(created_at DESC);
+
+-- Company reviews (see migrations/061_company_reviews.sql)
+CREATE TYPE company_review_status AS ENUM ('published', 'hidden', 'removed');
+
+ALTER TYPE report_target_type ADD VALUE 'review';
+
+CREATE TABLE company_reviews (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    application_id INTEGER NOT NULL UNIQUE REFERENCES application_tracking(id) ON DELETE CASCADE,
+    job_title VARCHAR(255) NOT NULL,
+    stage_reached VARCHAR(50) NOT NULL,
+    overall_rating SMALLINT NOT NULL CHECK (overall_rating BETWEEN 1 AND 5),
+    response_time_rating SMALLINT NOT NULL CHECK (response_time_rating BETWEEN 1 AND 5),
+    transparency_rating SMALLINT NOT NULL CHECK (transparency_rating BETWEEN 1 AND 5),
+    response_days SMALLINT CHECK (response_days >= 0),
+    headline VARCHAR(150) NOT NULL,
+    body TEXT NOT NULL,
+    status company_review_status NOT NULL DEFAULT 'published',
+    employer_reply TEXT,
+    replied_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    replied_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_company_reviews_company ON company_reviews(company_id, created_at DESC) WHERE status = 'published';
+CREATE INDEX idx_company_reviews_user_id ON company_reviews(user_id);
+
+-- Interview experiences (see migrations/062_interview_experiences.sql)
+CREATE TYPE interview_outcome AS ENUM ('offer', 'rejected', 'withdrew', 'no_response', 'pending');
+
+CREATE TABLE interview_experiences (
+    id SERIAL PRIMARY KEY,
+    company_id INTEGER NOT NULL REFERENCES companies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(255) NOT NULL,
+    interviewed_on DATE,
+    rounds SMALLINT NOT NULL CHECK (rounds BETWEEN 1 AND 20),
+    round_details TEXT[] NOT NULL DEFAULT '{}',
+    questions TEXT[] NOT NULL DEFAULT '{}',
+    summary TEXT NOT NULL,
+    difficulty SMALLINT CHECK (difficulty BETWEEN 1 AND 5),
+    outcome interview_outcome NOT NULL,
+    moderation_status moderation_status NOT NULL DEFAULT 'pending',
+    moderation_reason TEXT,
+    moderated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    moderated_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_interview_experiences_company ON interview_experiences(company_id, created_at DESC) WHERE moderation_status = 'approved';
+CREATE INDEX idx_interview_experiences_pending ON interview_experiences(created_at) WHERE moderation_status = 'pending';
+CREATE INDEX idx_interview_experiences_user_id ON interview_experiences(user_id);
+
+-- Interview prep packs (see migrations/063_interview_prep_packs.sql)
+ALTER TABLE application_tracking ADD COLUMN interview_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_application_tracking_interview_at ON application_tracking(interview_at) WHERE interview_at IS NOT NULL;
+
+CREATE TABLE interview_prep_packs (
+    id SERIAL PRIMARY KEY,
+    application_id INTEGER NOT NULL UNIQUE REFERENCES application_tracking(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    interview_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    content JSONB,
+    provider VARCHAR(20),
+    error TEXT,
+    generated_at TIMESTAMP WITH TIME ZONE,
+    notified_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_interview_prep_packs_user_id ON interview_prep_packs(user_id);
+
+-- Mentor sessions and calendar sync (see migrations/064_mentor_calendars.sql)
+CREATE TYPE mentor_slot_status AS ENUM ('open', 'booked', 'released');
+CREATE TYPE mentor_booking_status AS ENUM ('confirmed', 'cancelled', 'completed');
+CREATE TYPE calendar_provider AS ENUM ('google', 'caldav');
+
+CREATE TABLE mentor_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    headline VARCHAR(150) NOT NULL,
+    bio TEXT,
+    expertise TEXT[] NOT NULL DEFAULT '{}',
+    session_minutes SMALLINT NOT NULL DEFAULT 30 CHECK (session_minutes BETWEEN 15 AND 180),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE mentor_slots (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status mentor_slot_status NOT NULL DEFAULT 'open',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at > starts_at),
+    UNIQUE (mentor_id, starts_at)
+);
+
+CREATE INDEX idx_mentor_slots_open ON mentor_slots(mentor_id, starts_at) WHERE status = 'open';
+
+CREATE TABLE mentor_bookings (
+    id SERIAL PRIMARY KEY,
+    slot_id INTEGER NOT NULL REFERENCES mentor_slots(id) ON DELETE CASCADE,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    topic TEXT NOT NULL,
+    status mentor_booking_status NOT NULL DEFAULT 'confirmed',
+    cancelled_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    cancel_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_mentor_bookings_live_slot ON mentor_bookings(slot_id) WHERE status <> 'cancelled';
+CREATE INDEX idx_mentor_bookings_mentor_id ON mentor_bookings(mentor_id);
+CREATE INDEX idx_mentor_bookings_mentee_id ON mentor_bookings(mentee_id);
+
+CREATE TABLE mentor_calendars (
+    mentor_id UUID PRIMARY KEY REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    provider calendar_provider NOT NULL,
+    calendar_url TEXT NOT NULL,
+    username TEXT,
+    credential BYTEA NOT NULL,
+    synced_at TIMESTAMP WITH TIME ZONE,
+    sync_failures SMALLINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE mentor_busy_periods (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES mentor_profiles(user_id) ON DELETE CASCADE,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX idx_mentor_busy_periods_mentor ON mentor_busy_periods(mentor_id, starts_at);
+
+-- Video meeting links (see migrations/065_meeting_links.sql)
+ALTER TABLE mentor_bookings ADD COLUMN meeting_url TEXT;
+ALTER TABLE mentor_bookings ADD COLUMN meeting_provider VARCHAR(20);
+ALTER TABLE mentor_bookings ADD COLUMN reminded_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE application_tracking ADD COLUMN meeting_url TEXT;
+
+-- Mentor session notes and action items (see migrations/066_session_notes.sql)
+CREATE TABLE mentor_session_notes (
+    booking_id INTEGER PRIMARY KEY REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    notes TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE mentor_action_items (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(300) NOT NULL,
+    details TEXT,
+    due_on DATE,
+    roadmap_id INTEGER REFERENCES career_roadmaps(id) ON DELETE SET NULL,
+    roadmap_resource VARCHAR(20),
+    converted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_mentor_action_items_booking ON mentor_action_items(booking_id);
+CREATE INDEX idx_mentor_action_items_mentee ON mentor_action_items(mentee_id, created_at DESC);
+
+-- Paid mentor sessions (see migrations/067_mentor_payments.sql)
+CREATE TYPE mentor_payment_status AS ENUM ('pending', 'held', 'released', 'refunded', 'expired');
+CREATE TYPE mentor_payout_status AS ENUM ('requested', 'paid', 'rejected');
+
+ALTER TYPE mentor_booking_status ADD VALUE 'pending_payment';
+
+-- Price of one session in the currency's minor unit; 0 means free
+ALTER TABLE mentor_profiles ADD COLUMN session_price_cents INTEGER NOT NULL DEFAULT 0 CHECK (session_price_cents >= 0);
+ALTER TABLE mentor_profiles ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'usd';
+
+CREATE TABLE mentor_payouts (
+    id SERIAL PRIMARY KEY,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+    currency VARCHAR(3) NOT NULL,
+    status mentor_payout_status NOT NULL DEFAULT 'requested',
+    -- Transfer reference recorded by the admin who paid it
+    reference VARCHAR(255),
+    note TEXT,
+    processed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    processed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_mentor_payouts_mentor ON mentor_payouts(mentor_id, requested_at DESC);
+CREATE INDEX idx_mentor_payouts_requested ON mentor_payouts(requested_at) WHERE status = 'requested';
+
+CREATE TABLE mentor_payments (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL UNIQUE REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    mentor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    mentee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+    -- Platform's share, kept from the mentor's earnings
+    platform_fee_cents INTEGER NOT NULL CHECK (platform_fee_cents >= 0),
+    currency VARCHAR(3) NOT NULL,
+    status mentor_payment_status NOT NULL DEFAULT 'pending',
+    stripe_checkout_session_id VARCHAR(255) UNIQUE,
+    checkout_url TEXT,
+    stripe_payment_intent_id VARCHAR(255),
+    refunded_cents INTEGER NOT NULL DEFAULT 0 CHECK (refunded_cents >= 0),
+    stripe_refund_id VARCHAR(255),
+    -- Payout the mentor's share was claimed by
+    payout_id INTEGER REFERENCES mentor_payouts(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    paid_at TIMESTAMP WITH TIME ZONE,
+    released_at TIMESTAMP WITH TIME ZONE,
+    refunded_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_mentor_payments_mentor ON mentor_payments(mentor_id, status);
+CREATE INDEX idx_mentor_payments_held ON mentor_payments(booking_id) WHERE status = 'held';
+
+-- Disputes (see migrations/068_disputes.sql)
+CREATE TYPE dispute_subject AS ENUM ('mentor_booking', 'application');
+CREATE TYPE dispute_status AS ENUM ('open', 'under_review', 'resolved', 'withdrawn');
+CREATE TYPE dispute_outcome AS ENUM ('upheld', 'partially_upheld', 'dismissed');
+
+ALTER TYPE mentor_payment_status ADD VALUE 'disputed';
+
+CREATE TABLE disputes (
+    id SERIAL PRIMARY KEY,
+    subject dispute_subject NOT NULL,
+    booking_id INTEGER REFERENCES mentor_bookings(id) ON DELETE CASCADE,
+    application_id INTEGER REFERENCES application_tracking(id) ON DELETE CASCADE,
+    opened_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The other side: the other party of the session, or the job's poster
+    respondent_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    reason TEXT NOT NULL,
+    status dispute_status NOT NULL DEFAULT 'open',
+    outcome dispute_outcome,
+    -- Refunded to the mentee by the resolution of a paid session's dispute
+    refund_cents INTEGER CHECK (refund_cents >= 0),
+    resolution_note TEXT,
+    assigned_to UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    CHECK ((subject = 'mentor_booking') = (booking_id IS NOT NULL)),
+    CHECK ((subject = 'application') = (application_id IS NOT NULL))
+);
+
+-- At most one live dispute per session or application
+CREATE UNIQUE INDEX idx_disputes_live_booking
+    ON disputes(booking_id) WHERE status IN ('open', 'under_review');
+CREATE UNIQUE INDEX idx_disputes_live_application
+    ON disputes(application_id) WHERE status IN ('open', 'under_review');
+CREATE INDEX idx_disputes_opened_by ON disputes(opened_by, created_at DESC);
+CREATE INDEX idx_disputes_respondent ON disputes(respondent_id, created_at DESC);
+CREATE INDEX idx_disputes_queue ON disputes(created_at) WHERE status IN ('open', 'under_review');
+
+CREATE TABLE dispute_evidence (
+    id SERIAL PRIMARY KEY,
+    dispute_id INTEGER NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    submitted_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    note TEXT,
+    file_name VARCHAR(255),
+    storage_key TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (note IS NOT NULL OR storage_key IS NOT NULL)
+);
+
+CREATE INDEX idx_dispute_evidence_dispute ON dispute_evidence(dispute_id, created_at);
+
+-- Every status change, with who made it
+CREATE TABLE dispute_events (
+    id SERIAL PRIMARY KEY,
+    dispute_id INTEGER NOT NULL REFERENCES disputes(id) ON DELETE CASCADE,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    from_status dispute_status,
+    to_status dispute_status NOT NULL,
+    note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_dispute_events_dispute ON dispute_events(dispute_id, created_at);
+
+-- API keys and sandbox mode (see migrations/069_sandbox.sql)
+CREATE TABLE api_keys (
+    id SERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- Start of the key, to tell keys apart; the key itself is shown once
+    key_prefix VARCHAR(16) NOT NULL,
+    -- Hex SHA-256 of the key
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    -- What the key may do, e.g. {sandbox}
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_api_keys_user ON api_keys(user_id, created_at DESC);
+
+-- Sandbox a test user or job belongs to; NULL for production data
+ALTER TABLE users ADD COLUMN sandbox_key_id INTEGER REFERENCES api_keys(id) ON DELETE CASCADE;
+ALTER TABLE jobs ADD COLUMN sandbox_key_id INTEGER REFERENCES api_keys(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_users_sandbox ON users(sandbox_key_id) WHERE sandbox_key_id IS NOT NULL;
+CREATE INDEX idx_jobs_sandbox ON jobs(sandbox_key_id) WHERE sandbox_key_id IS NOT NULL;
+
+-- Whether a job (by its sandbox) is in the same sandbox as the user:
+-- production jobs for production users, a sandbox's jobs for its test users
+CREATE OR REPLACE FUNCTION same_sandbox(p_sandbox_key_id INTEGER, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT p_sandbox_key_id IS NOT DISTINCT FROM (SELECT sandbox_key_id FROM users WHERE id = p_user_id)
+$$ LANGUAGE sql STABLE;
+
+-- Cohorts are production data: sandbox test users never join one, so they
+-- stay out of placement reports, at-risk lists, and cohort analytics
+CREATE OR REPLACE FUNCTION reject_sandbox_cohort_member()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM users WHERE id = NEW.user_id AND sandbox_key_id IS NOT NULL) THEN
+        RAISE EXCEPTION 'sandbox users cannot join cohorts';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER cohort_members_no_sandbox
+    BEFORE INSERT OR UPDATE ON cohort_members
+    FOR EACH ROW EXECUTE FUNCTION reject_sandbox_cohort_member();
+
+-- Debug captures (see migrations/070_debug_captures.sql)
+-- When the user consented to debug captures; NULL when they have not
+ALTER TABLE users ADD COLUMN debug_capture_consent_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE debug_captures (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(50) NOT NULL,
+    -- Provider and model that produced the response
+    provider VARCHAR(20) NOT NULL,
+    model VARCHAR(100) NOT NULL,
+    prompt_version VARCHAR(50) NOT NULL,
+    -- The request as the prompt is rendered from it: input, parameters
+    -- (context, history, sampling settings), and the settings applied
+    input TEXT NOT NULL,
+    parameters JSONB,
+    settings JSONB NOT NULL,
+    -- Text the provider returned, before parsing; NULL if the call failed
+    raw_response TEXT,
+    -- Parsed result, or the error
+    response JSONB NOT NULL,
+    success BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    flagged_at TIMESTAMP WITH TIME ZONE,
+    flag_reason TEXT,
+    -- Latest replay by an admin
+    replayed_at TIMESTAMP WITH TIME ZONE,
+    replayed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    replay_provider VARCHAR(20),
+    replay_model VARCHAR(100),
+    replay_prompt_version VARCHAR(50),
+    replay_response JSONB,
+    replay_success BOOLEAN
+);
+
+CREATE INDEX idx_debug_captures_user ON debug_captures(user_id, created_at DESC);
+CREATE INDEX idx_debug_captures_flagged ON debug_captures(flagged_at) WHERE flagged_at IS NOT NULL;
+CREATE INDEX idx_debug_captures_unflagged ON debug_captures(created_at) WHERE flagged_at IS NULL;
+
+-- Prompt rollouts (see migrations/071_prompt_rollouts.sql)
+CREATE TYPE prompt_rollout_status AS ENUM ('active', 'promoted', 'rolled_back', 'completed');
+
+-- The provider answered, but its output was malformed, off-schema, or unsafe
+ALTER TABLE ai_usage_logs ADD COLUMN parse_failed BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_ai_usage_logs_prompt_version ON ai_usage_logs(prompt_version, created_at);
+
+CREATE TABLE ai_feedback (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(50) NOT NULL,
+    -- Version of the prompt that produced the rated result
+    prompt_version VARCHAR(50) NOT NULL,
+    -- Thumbs up (TRUE) or down (FALSE)
+    helpful BOOLEAN NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_ai_feedback_prompt_version ON ai_feedback(prompt_version, created_at);
+
+CREATE TABLE prompt_rollouts (
+    id SERIAL PRIMARY KEY,
+    action VARCHAR(50) NOT NULL,
+    -- Version served outside the rollout, and to everyone after a rollback
+    baseline_version VARCHAR(50) NOT NULL,
+    candidate_version VARCHAR(50) NOT NULL,
+    -- active: the candidate gets traffic_percent of requests; promoted: all
+    -- of them, until a release makes it the current version (completed)
+    status prompt_rollout_status NOT NULL DEFAULT 'active',
+    traffic_percent INTEGER NOT NULL CHECK (traffic_percent BETWEEN 1 AND 100),
+    -- Rolled back when the candidate's rates exceed these, once it has
+    -- min_samples calls (parse failures) or ratings (thumbs down)
+    max_parse_failure_rate REAL NOT NULL CHECK (max_parse_failure_rate BETWEEN 0 AND 1),
+    max_thumbs_down_rate REAL NOT NULL CHECK (max_thumbs_down_rate BETWEEN 0 AND 1),
+    min_samples INTEGER NOT NULL CHECK (min_samples > 0),
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    promoted_at TIMESTAMP WITH TIME ZONE,
+    rolled_back_at TIMESTAMP WITH TIME ZONE,
+    -- NULL for an automatic rollback
+    rolled_back_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    rollback_reason TEXT,
+    -- Candidate and baseline rates when rolled back
+    rollback_metrics JSONB,
+    completed_at TIMESTAMP WITH TIME ZONE
+);
+
+-- One live rollout per action
+CREATE UNIQUE INDEX idx_prompt_rollouts_live
+    ON prompt_rollouts(action) WHERE status IN ('active', 'promoted');
+
+-- Answer sections (see migrations/072_answer_sections.sql)
+ALTER TABLE mentor_messages ADD COLUMN sections JSONB;
+
+-- Glossary (see migrations/073_glossary.sql)
+CREATE TABLE glossary (
+    id SERIAL PRIMARY KEY,
+    term_key VARCHAR(100) NOT NULL UNIQUE,
+    -- The term as first explained, e.g. 'CI/CD'
+    term VARCHAR(100) NOT NULL,
+    definition TEXT NOT NULL,
+    example TEXT,
+    -- NULL for the terms seeded below
+    ai_provider VARCHAR(20),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Terms nearly every roadmap uses, so annotations work from the start
+INSERT INTO glossary (term_key, term, definition, example)
+VALUES
+    ('api', 'API', 'A set of rules that lets one program ask another for data or actions, like a menu of requests it accepts.', 'The mobile app calls the weather API to get today''s forecast.'),
+    ('rest api', 'REST API', 'An API that works over the web, where each address stands for a kind of data and requests like GET or POST read or change it.', 'We added a REST API endpoint that returns a user''s orders.'),
+    ('git', 'Git', 'A tool that records every change to a project''s files, so you can go back to earlier versions and combine work from several people.', 'Commit your changes to Git before trying the refactor.'),
+    ('version control', 'Version control', 'Keeping a history of every change to code so changes can be reviewed, shared, and undone.', 'All our projects are under version control on GitHub.'),
+    ('ci/cd', 'CI/CD', 'Continuous integration and delivery: every change is automatically tested and, if it passes, released.', 'The CI/CD pipeline runs the tests on every pull request.'),
+    ('docker', 'Docker', 'A tool that packages an app with everything it needs to run, so it behaves the same on any computer.', 'Run the database locally with Docker instead of installing it.'),
+    ('framework', 'Framework', 'Ready-made code that gives an app its structure, so you only write the parts specific to your project.', 'React is the framework our frontend is built with.'),
+    ('frontend', 'Frontend', 'The part of an app people see and interact with, usually running in the browser or on the phone.', 'She works on the frontend, building the checkout pages.'),
+    ('backend', 'Backend', 'The part of an app that runs on servers: it stores data, applies business rules, and answers the frontend''s requests.', 'The backend checks the payment before confirming the order.'),
+    ('database', 'Database', 'Organized storage for an app''s data that can be searched and updated quickly.', 'User accounts are stored in a PostgreSQL database.'),
+    ('sql', 'SQL', 'The language used to read and change data in most databases.', 'Write a SQL query to find customers who ordered last week.'),
+    ('unit test', 'Unit test', 'A small automated check that one piece of code gives the expected result.', 'Add a unit test for the discount calculation.'),
+    ('deployment', 'Deployment', 'Putting a new version of an app on the servers where users can reach it.', 'The deployment goes out every Tuesday afternoon.')
+ON CONFLICT (term_key) DO NOTHING;
@@ -0,0 +1,252 @@
+//! User activity feed.
+//!
+//! Meaningful actions (a roadmap created, a roadmap phase completed, an
+//! application submitted, a badge earned, a mentor action item assigned) are
+//! recorded in the monthly-partitioned `activity_events` table. Users read
+//! them newest first through [`feed`], which pages with an opaque cursor so
+//! new activity never shifts the pages being read, and
+//! [`send_weekly_digests`] sums up each active user's week in a
+//! notification.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Activities per page unless the client asks for fewer.
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// Most activities per page.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Days of activity a weekly digest covers, and the least time between two
+/// digests for one user.
+const DIGEST_DAYS: i32 = 7;
+
+/// Notification type of weekly digests.
+const DIGEST_NOTIFICATION_TYPE: &str = "weekly_digest";
+
+/// Kinds of activity shown in the feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityType {
+    RoadmapCreated,
+    PhaseCompleted,
+    ApplicationSubmitted,
+    BadgeEarned,
+    ActionItemAssigned,
+}
+
+impl ActivityType {
+    /// Every activity type, in digest order.
+    pub const ALL: [ActivityType; 5] = [
+        ActivityType::RoadmapCreated,
+        ActivityType::PhaseCompleted,
+        ActivityType::ApplicationSubmitted,
+        ActivityType::BadgeEarned,
+        ActivityType::ActionItemAssigned,
+    ];
+
+    /// The `event_type` stored in `activity_events`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActivityType::RoadmapCreated => "roadmap_created",
+            ActivityType::PhaseCompleted => "phase_completed",
+            ActivityType::ApplicationSubmitted => "application_submitted",
+            ActivityType::BadgeEarned => "badge_earned",
+            ActivityType::ActionItemAssigned => "action_item_assigned",
+        }
+    }
+
+    fn parse(event_type: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == event_type)
+    }
+
+    /// How a weekly digest counts this activity, e.g. "2 phases completed".
+    fn describe(self, count: i64) -> String {
+        let (one, many) = match self {
+            ActivityType::RoadmapCreated => ("roadmap created", "roadmaps created"),
+            ActivityType::PhaseCompleted => ("roadmap phase completed", "roadmap phases completed"),
+            ActivityType::ApplicationSubmitted => ("application submitted", "applications submitted"),
+            ActivityType::BadgeEarned => ("badge earned", "badges earned"),
+            ActivityType::ActionItemAssigned => ("mentor action item assigned", "mentor action items assigned"),
+        };
+        format!("{} {}", count, if count == 1 { one } else { many })
+    }
+}
+
+/// One entry in a user's feed.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Activity {
+    pub id: i64,
+    #[sqlx(rename = "event_type")]
+    #[serde(rename = "type")]
+    pub activity_type: String,
+    /// Details for display, e.g. the roadmap title or job
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A page of the feed.
+#[derive(Debug)]
+pub struct ActivityPage {
+    pub activities: Vec<Activity>,
+    /// Cursor for the next (older) page, if there is one
+    pub next_cursor: Option<String>,
+}
+
+/// Records an activity for the user's feed.
+///
+/// The feed is a side effect, so failures are logged rather than failing
+/// the request that triggered them.
+pub async fn record(db_pool: &PgPool, user_id: Uuid, activity_type: ActivityType, payload: Value) {
+    let result = sqlx::query("INSERT INTO activity_events (user_id, event_type, payload) VALUES ($1, $2, $3)")
+        .bind(user_id)
+        .bind(activity_type.as_str())
+        .bind(&payload)
+        .execute(db_pool)
+        .await;
+
+    if let Err(e) = result {
+        warn!("Failed to record activity {} for {}: {}", activity_type.as_str(), user_id, e);
+    }
+}
+
+/// Loads a page of the user's feed, newest first, starting after `cursor`.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for a malformed cursor, or an error
+/// if the database operation fails.
+pub async fn feed(db_pool: &PgPool, user_id: Uuid, cursor: Option<&str>, limit: i64) -> AppResult<ActivityPage> {
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let (before_at, before_id) = match cursor {
+        Some(cursor) => {
+            let (at, id) = decode_cursor(cursor)
+                .ok_or_else(|| AppError::ValidationError("Invalid activity cursor".to_string()))?;
+            (Some(at), Some(id))
+        }
+        None => (None, None),
+    };
+
+    // One extra row tells whether there is another page
+    let mut activities = sqlx::query_as::<_, Activity>(
+        r#"
+        SELECT id, event_type, payload, created_at
+        FROM activity_events
+        WHERE user_id = $1 AND event_type = ANY($2)
+          AND ($3::TIMESTAMPTZ IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(user_id)
+    .bind(ActivityType::ALL.map(ActivityType::as_str).to_vec())
+    .bind(before_at)
+    .bind(before_id)
+    .bind(limit + 1)
+    .fetch_all(db_pool)
+    .await?;
+
+    let next_cursor = if activities.len() as i64 > limit {
+        activities.truncate(limit as usize);
+        activities.last().map(|a| encode_cursor(a.created_at, a.id))
+    } else {
+        None
+    };
+    Ok(ActivityPage { activities, next_cursor })
+}
+
+fn encode_cursor(created_at: DateTime<Utc>, id: i64) -> String {
+    format!("{}.{}", created_at.timestamp_micros(), id)
+}
+
+fn decode_cursor(cursor: &str) -> Option<(DateTime<Utc>, i64)> {
+    let (micros, id) = cursor.split_once('.')?;
+    let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    Some((created_at, id.parse().ok()?))
+}
+
+/// Sends a weekly digest notification to every user with activity in the
+/// past week who has not had a digest in that time, leading with their
+/// primary roadmap's progress if they have one. Returns how many were sent.
+///
+/// # Errors
+///
+/// Returns an error if the activity counts cannot be loaded; failures
+/// sending single digests are logged and skipped.
+pub async fn send_weekly_digests(db_pool: &PgPool) -> AppResult<usize> {
+    let counts = sqlx::query_as::<_, (Uuid, String, i64)>(
+        r#"
+        SELECT a.user_id, a.event_type, COUNT(*)
+        FROM activity_events a
+        WHERE a.created_at > NOW() - make_interval(days => $1)
+          AND a.user_id IS NOT NULL AND a.event_type = ANY($2)
+          AND NOT EXISTS (
+              SELECT 1 FROM notifications n
+              WHERE n.user_id = a.user_id AND n.type = $3
+                AND n.created_at > NOW() - make_interval(days => $1)
+          )
+        GROUP BY a.user_id, a.event_type
+        "#,
+    )
+    .bind(DIGEST_DAYS)
+    .bind(ActivityType::ALL.map(ActivityType::as_str).to_vec())
+    .bind(DIGEST_NOTIFICATION_TYPE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut digests: BTreeMap<Uuid, BTreeMap<ActivityType, i64>> = BTreeMap::new();
+    for (user_id, event_type, count) in counts {
+        if let Some(activity_type) = ActivityType::parse(&event_type) {
+            digests.entry(user_id).or_default().insert(activity_type, count);
+        }
+    }
+
+    // Each user's primary roadmap leads their digest
+    let user_ids: Vec<Uuid> = digests.keys().copied().collect();
+    let focus: BTreeMap<Uuid, (String, i32)> = sqlx::query_as::<_, (Uuid, String, Option<i32>)>(
+        "SELECT user_id, title, progress_percentage FROM career_roadmaps WHERE is_primary AND user_id = ANY($1)",
+    )
+    .bind(&user_ids)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .map(|(user_id, title, progress)| (user_id, (title, progress.unwrap_or(0))))
+    .collect();
+
+    let mut sent = 0;
+    for (user_id, counts) in &digests {
+        let summary: Vec<String> = counts.iter().map(|(t, count)| t.describe(*count)).collect();
+        let message = match focus.get(user_id) {
+            Some((title, progress)) => format!(
+                "Your focus, {}, is {}% complete. This week: {}. Keep it up!",
+                title,
+                progress,
+                summary.join(", ")
+            ),
+            None => format!("This week: {}. Keep it up!", summary.join(", ")),
+        };
+        let result = sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, $4)")
+            .bind(user_id)
+            .bind("Your week on CareerBridge")
+            .bind(message)
+            .bind(DIGEST_NOTIFICATION_TYPE)
+            .execute(db_pool)
+            .await;
+        match result {
+            Ok(_) => sent += 1,
+            Err(e) => warn!("Failed to send weekly digest to {}: {}", user_id, e),
+        }
+    }
+    if sent > 0 {
+        info!("Sent {} weekly activity digests", sent);
+    }
+    Ok(sent)
+}
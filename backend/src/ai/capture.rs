@@ -0,0 +1,78 @@
+//! Debug captures of AI generations.
+//!
+//! For users who consented (see [`crate::debug_captures`]), each request is
+//! written to `debug_captures` with the provider's raw response, so a bad
+//! generation the user flags can be inspected and replayed.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::types::{AIActionRequest, GenerationSettings};
+
+/// One request and what the provider that answered it returned.
+#[derive(Debug)]
+pub struct CaptureRecord<'a> {
+    pub user_id: Uuid,
+    pub request: &'a AIActionRequest,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub prompt_version: &'a str,
+    pub settings: GenerationSettings,
+    /// Text the provider returned, before parsing
+    pub raw_response: Option<&'a str>,
+    /// Parsed result, or the error
+    pub response: &'a serde_json::Value,
+    pub success: bool,
+}
+
+/// Writes debug captures to the database.
+#[derive(Clone)]
+pub struct CaptureLog {
+    db_pool: PgPool,
+}
+
+impl CaptureLog {
+    /// Create a capture log backed by `db_pool`
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Captures a request if its user consented. Returns the capture's ID.
+    ///
+    /// Errors are logged rather than returned, so a capture failure never
+    /// fails the user's request.
+    pub async fn record(&self, record: CaptureRecord<'_>) -> Option<i64> {
+        let result = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO debug_captures
+                (user_id, action, provider, model, prompt_version, input, parameters, settings,
+                 raw_response, response, success)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+            FROM users
+            WHERE id = $1 AND debug_capture_consent_at IS NOT NULL
+            RETURNING id
+            "#,
+        )
+        .bind(record.user_id)
+        .bind(record.request.action.as_str())
+        .bind(record.provider)
+        .bind(record.model)
+        .bind(record.prompt_version)
+        .bind(&record.request.input)
+        .bind(&record.request.parameters)
+        .bind(sqlx::types::Json(record.settings))
+        .bind(record.raw_response)
+        .bind(record.response)
+        .bind(record.success)
+        .fetch_optional(&self.db_pool)
+        .await;
+
+        match result {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::warn!("Failed to capture AI request {}: {}", record.request.action.as_str(), e);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Circuit breakers around AI providers.
+//!
+//! When a provider is down, every request to it would otherwise wait for
+//! its timeout, and its retries, before failing. Each provider has a
+//! breaker that opens after `AI_CIRCUIT_FAILURE_THRESHOLD` consecutive
+//! failed calls. While it is open, requests to the provider fail at once
+//! with `503 Service Unavailable`, or `auto` requests move on to the next
+//! provider. After `AI_CIRCUIT_COOLDOWN_SECS` it half-opens: one request
+//! is let through as a probe, closing the breaker if it succeeds and
+//! opening it again for another cooldown if it fails.
+//!
+//! Only failures of the provider count: errors and timeouts with no text
+//! back. Unusable output, blocked content, and invalid requests show the
+//! provider is answering, and count as successes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::errors::AppError;
+
+/// Default consecutive failures that open a breaker.
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time a breaker stays open before letting a probe through.
+const DEFAULT_COOLDOWN_SECS: u64 = 30;
+
+/// State of a provider's breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests go through
+    Closed,
+    /// Requests fail at once until the cooldown is over
+    Open,
+    /// The cooldown is over; the next request probes the provider
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<(Instant, DateTime<Utc>)>,
+    /// Set while a half-open probe is in flight
+    probe_started: Option<Instant>,
+    /// Times the breaker opened since the server started
+    trips: u64,
+}
+
+/// A provider's breaker, as reported on the health endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCircuit {
+    pub provider: &'static str,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// When the breaker last opened, while it is open or half-open
+    pub opened_at: Option<DateTime<Utc>>,
+    /// Seconds until an open breaker lets a probe through
+    pub retry_in_secs: Option<u64>,
+    /// Times the breaker opened since the server started
+    pub trips: u64,
+}
+
+/// Per-provider circuit breakers.
+#[derive(Debug)]
+pub struct CircuitBreakers {
+    failure_threshold: u32,
+    cooldown: Duration,
+    breakers: Mutex<HashMap<&'static str, Breaker>>,
+}
+
+impl Default for CircuitBreakers {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, Duration::from_secs(DEFAULT_COOLDOWN_SECS))
+    }
+}
+
+impl CircuitBreakers {
+    /// Breakers that open after `failure_threshold` consecutive failures
+    /// (0 disables them) and half-open after `cooldown`.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads `AI_CIRCUIT_FAILURE_THRESHOLD` (default: 5; 0 disables the
+    /// breakers) and `AI_CIRCUIT_COOLDOWN_SECS` (default: 30).
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        Self::new(
+            var("AI_CIRCUIT_FAILURE_THRESHOLD").map_or(DEFAULT_FAILURE_THRESHOLD, |n| n.min(u64::from(u32::MAX)) as u32),
+            Duration::from_secs(var("AI_CIRCUIT_COOLDOWN_SECS").unwrap_or(DEFAULT_COOLDOWN_SECS)),
+        )
+    }
+
+    fn state_of(&self, breaker: &Breaker, now: Instant) -> CircuitState {
+        match breaker.opened_at {
+            None => CircuitState::Closed,
+            Some((opened, _)) if now.duration_since(opened) < self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Checks that a request may be sent to `provider`. A half-open breaker
+    /// lets one probe through at a time; a probe that never reported back
+    /// (e.g. its request was cancelled) is replaced after another cooldown.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ServiceUnavailable` while the breaker is open, or
+    /// half-open with a probe in flight.
+    pub fn allow(&self, provider: &'static str, label: &str) -> Result<(), AppError> {
+        if self.failure_threshold == 0 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        let breaker = breakers.entry(provider).or_default();
+        let retry_in = match self.state_of(breaker, now) {
+            CircuitState::Closed => return Ok(()),
+            CircuitState::Open => {
+                let (opened, _) = breaker.opened_at.expect("open breakers have an opening time");
+                self.cooldown.saturating_sub(now.duration_since(opened))
+            }
+            CircuitState::HalfOpen => match breaker.probe_started {
+                Some(started) if now.duration_since(started) < self.cooldown => {
+                    self.cooldown.saturating_sub(now.duration_since(started))
+                }
+                _ => {
+                    breaker.probe_started = Some(now);
+                    tracing::info!("{} circuit half-open; probing the provider", label);
+                    return Ok(());
+                }
+            },
+        };
+        Err(AppError::ServiceUnavailable(format!(
+            "{} is temporarily unavailable after repeated failures; try again in {} seconds",
+            label,
+            retry_in.as_secs().max(1)
+        )))
+    }
+
+    /// Records the outcome of a call to `provider`: `failed` if the provider
+    /// did not answer.
+    pub fn record(&self, provider: &'static str, label: &str, failed: bool) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        let breaker = breakers.entry(provider).or_default();
+        breaker.probe_started = None;
+        if !failed {
+            if breaker.opened_at.is_some() {
+                tracing::info!("{} circuit closed; the provider is answering again", label);
+            }
+            breaker.consecutive_failures = 0;
+            breaker.opened_at = None;
+            return;
+        }
+
+        breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+        // A failed probe, or the failure that reaches the threshold, opens
+        // the breaker for a new cooldown
+        if breaker.opened_at.is_some() || breaker.consecutive_failures >= self.failure_threshold {
+            if breaker.opened_at.is_none() {
+                breaker.trips += 1;
+                tracing::warn!(
+                    "{} circuit open after {} consecutive failures; requests fail fast for {} seconds",
+                    label,
+                    breaker.consecutive_failures,
+                    self.cooldown.as_secs()
+                );
+            }
+            breaker.opened_at = Some((Instant::now(), Utc::now()));
+        }
+    }
+
+    /// The breakers of `providers`, in order.
+    pub fn snapshot(&self, providers: &[&'static str]) -> Vec<ProviderCircuit> {
+        let now = Instant::now();
+        let breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        providers
+            .iter()
+            .map(|&provider| {
+                let default = Breaker::default();
+                let breaker = breakers.get(provider).unwrap_or(&default);
+                let state = if self.failure_threshold == 0 {
+                    CircuitState::Closed
+                } else {
+                    self.state_of(breaker, now)
+                };
+                ProviderCircuit {
+                    provider,
+                    state,
+                    consecutive_failures: breaker.consecutive_failures,
+                    opened_at: breaker.opened_at.map(|(_, at)| at),
+                    retry_in_secs: breaker
+                        .opened_at
+                        .filter(|_| state == CircuitState::Open)
+                        .map(|(opened, _)| self.cooldown.saturating_sub(now.duration_since(opened)).as_secs().max(1)),
+                    trips: breaker.trips,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether `error` from a call means the provider did not answer. Output
+/// that came back unusable (`answered`) does not count.
+pub fn is_provider_failure(error: &AppError, answered: bool) -> bool {
+    !answered
+        && matches!(
+            error,
+            AppError::ExternalServiceError(_) | AppError::ServiceUnavailable(_) | AppError::TooManyRequests(_)
+        )
+}
@@ -0,0 +1,437 @@
+//! Mock AI client for local development and load testing.
+//!
+//! Returns canned fixture responses for every action after a simulated
+//! latency, and fails a configurable fraction of calls, so load tests against
+//! the router exercise the same code paths as production without calling real
+//! AI APIs. Latency jitter and failures are drawn from an RNG seeded by
+//! `MOCK_AI_SEED` and a per-client call counter, so a sequential run is
+//! reproducible call for call.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+use super::tools::{ModelTurn, ToolCall, ToolDefinition, ToolRound};
+use super::types::EMBEDDING_DIMENSIONS;
+use crate::errors::AppError;
+
+/// Default base latency for mock responses, in milliseconds.
+const DEFAULT_LATENCY_MS: u64 = 800;
+
+/// Default maximum extra latency added on top of the base, in milliseconds.
+const DEFAULT_JITTER_MS: u64 = 400;
+
+/// Default RNG seed.
+const DEFAULT_SEED: u64 = 42;
+
+/// Simulated latency and failure settings for the mock client.
+#[derive(Debug, Clone, Copy)]
+pub struct MockConfig {
+    /// Base latency applied to every call
+    pub latency: Duration,
+    /// Maximum additional random latency per call
+    pub jitter: Duration,
+    /// Fraction (0.0-1.0) of calls that fail with a simulated upstream error
+    pub failure_rate: f64,
+    /// Seed for latency jitter and failure decisions
+    pub seed: u64,
+}
+
+impl MockConfig {
+    /// Reads `MOCK_AI_LATENCY_MS` (default: 800), `MOCK_AI_JITTER_MS`
+    /// (default: 400), `MOCK_AI_FAILURE_RATE` (default: 0), and
+    /// `MOCK_AI_SEED` (default: 42).
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        Self {
+            latency: Duration::from_millis(var("MOCK_AI_LATENCY_MS").unwrap_or(DEFAULT_LATENCY_MS)),
+            jitter: Duration::from_millis(var("MOCK_AI_JITTER_MS").unwrap_or(DEFAULT_JITTER_MS)),
+            failure_rate: var::<f64>("MOCK_AI_FAILURE_RATE")
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            seed: var("MOCK_AI_SEED").unwrap_or(DEFAULT_SEED),
+        }
+    }
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            latency: Duration::from_millis(DEFAULT_LATENCY_MS),
+            jitter: Duration::from_millis(DEFAULT_JITTER_MS),
+            failure_rate: 0.0,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// AI client that serves fixture responses with simulated latency.
+pub struct MockClient {
+    config: MockConfig,
+    calls: AtomicU64,
+}
+
+impl MockClient {
+    /// Create a new mock client
+    pub fn new(config: MockConfig) -> Self {
+        Self {
+            config,
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    /// Sleeps for the simulated latency and decides whether this call fails.
+    async fn simulate(&self, action: &str) -> Result<(), AppError> {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        let mut rng = StdRng::seed_from_u64(self.config.seed.wrapping_add(call));
+
+        let jitter_ms = self.config.jitter.as_millis() as u64;
+        let extra = if jitter_ms > 0 { rng.random_range(0..=jitter_ms) } else { 0 };
+        let fail = rng.random_bool(self.config.failure_rate);
+
+        tokio::time::sleep(self.config.latency + Duration::from_millis(extra)).await;
+
+        if fail {
+            tracing::debug!("Mock AI call {} ({}) failing by configuration", call, action);
+            return Err(AppError::ExternalServiceError(
+                "Mock AI provider returned 503: simulated failure".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Embed texts as hashed bags of words, so texts sharing words are close
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        self.simulate("embed").await?;
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut vector = vec![0.0f32; EMBEDDING_DIMENSIONS];
+                for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| w.len() > 2) {
+                    let hash = word
+                        .to_lowercase()
+                        .bytes()
+                        .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3));
+                    vector[(hash % EMBEDDING_DIMENSIONS as u64) as usize] += 1.0;
+                }
+                let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt().max(f32::EPSILON);
+                vector.iter_mut().for_each(|v| *v /= norm);
+                vector
+            })
+            .collect())
+    }
+
+    /// Extract skills from CV text
+    pub async fn extract_skills(&self, _cv_text: &str) -> Result<String, AppError> {
+        self.simulate("extract_skills").await?;
+        Ok(json!({
+            "technical_skills": [
+                {"name": "JavaScript", "proficiency": "intermediate", "category": "programming_language"},
+                {"name": "React", "proficiency": "intermediate", "category": "framework"},
+                {"name": "PostgreSQL", "proficiency": "beginner", "category": "database"}
+            ],
+            "soft_skills": ["communication", "teamwork"],
+            "roles": ["Frontend Developer"],
+            "domains": ["Web Development"],
+            "certifications": [],
+            "tools": ["Git", "VS Code"],
+            "years_of_experience": 1.0,
+            "education": ["B.Sc. Computer Science"]
+        })
+        .to_string())
+    }
+
+    /// Generate a learning roadmap
+    pub async fn generate_roadmap(
+        &self,
+        tech_stack: &str,
+        _current_skills: Option<&str>,
+        timeframe_months: Option<u32>,
+        _learning_hours_per_week: Option<u32>,
+    ) -> Result<String, AppError> {
+        self.simulate("generate_roadmap").await?;
+        let timeframe = timeframe_months.unwrap_or(6);
+        Ok(json!({
+            "stack_name": tech_stack,
+            "prerequisites": ["Basic computer literacy"],
+            "estimated_duration": format!("{} months", timeframe),
+            "difficulty": "intermediate",
+            "phases": [
+                {
+                    "phase": 1,
+                    "title": "Fundamentals",
+                    "timeline": "Month 1 (Weeks 1-4)",
+                    "topics": ["Core concepts", "Tooling setup"],
+                    "technologies": ["Git"],
+                    "duration": "4 weeks",
+                    "learning_goals": ["Understand the fundamentals"],
+                    "resources": ["Official documentation"]
+                },
+                {
+                    "phase": 2,
+                    "title": "Building Projects",
+                    "timeline": "Months 2-3 (Weeks 5-12)",
+                    "topics": ["Applied practice", "Testing"],
+                    "technologies": [tech_stack],
+                    "duration": "8 weeks",
+                    "learning_goals": ["Ship a portfolio project"],
+                    "resources": ["Project-based tutorials"]
+                }
+            ],
+            "project_suggestions": [
+                {
+                    "title": "Portfolio Project",
+                    "description": format!("A small end-to-end project using {}", tech_stack),
+                    "technologies": [tech_stack],
+                    "difficulty": "beginner",
+                    "estimated_hours": 20,
+                    "recommended_phase": 2
+                }
+            ],
+            "job_application_timing": "Start applying after completing Phase 2"
+        })
+        .to_string())
+    }
+
+    /// Answer a career-related question
+    pub async fn answer_question(
+        &self,
+        question: &str,
+        _context: Option<&str>,
+    ) -> Result<String, AppError> {
+        self.simulate("answer_question").await?;
+        Ok(json!({
+            "question": question,
+            "answer": "This is a mock answer. Focus on building one strong portfolio project and apply to entry-level roles that match your current skills. This is a suggestion, not a guarantee.",
+            "related_topics": ["Portfolio building", "Interview preparation"]
+        })
+        .to_string())
+    }
+
+    /// Analyze a job description
+    pub async fn analyze_job_description(&self, _description: &str) -> Result<String, AppError> {
+        self.simulate("analyze_job_description").await?;
+        Ok(json!({
+            "role": "Junior Backend Developer",
+            "seniority": "junior",
+            "must_have_skills": ["Python", "SQL", "REST APIs"],
+            "nice_to_have_skills": ["Docker"],
+            "key_responsibilities": ["Build and maintain API endpoints", "Write tests"],
+            "keywords": ["Python", "PostgreSQL", "REST", "unit testing"],
+            "company_values": ["Collaboration"]
+        })
+        .to_string())
+    }
+
+    /// Generate interview questions
+    pub async fn generate_interview_questions(&self, _application: &str) -> Result<String, AppError> {
+        self.simulate("generate_interview_questions").await?;
+        Ok(json!({
+            "questions": [
+                {
+                    "question": "Walk me through a REST API you have built.",
+                    "category": "technical",
+                    "why_asked": "Checks hands-on API experience",
+                    "answer_tips": "Pick one project and explain the endpoints, data model, and one trade-off you made.",
+                    "reported": false
+                },
+                {
+                    "question": "Tell me about a time you had to learn a new tool quickly.",
+                    "category": "behavioral",
+                    "why_asked": "Assesses learning ability",
+                    "answer_tips": "Use the STAR format and end with what you would do differently.",
+                    "reported": false
+                }
+            ]
+        })
+        .to_string())
+    }
+
+    /// Reply as the recruiter in a negotiation
+    pub async fn negotiation_reply(&self, _negotiation: &str) -> Result<String, AppError> {
+        self.simulate("negotiation_reply").await?;
+        Ok(json!({
+            "reply": "I hear you. The base salary is close to the top of our band, but I could look at a signing bonus. What number did you have in mind?",
+            "concluded": false,
+            "revised_offer": null
+        })
+        .to_string())
+    }
+
+    /// Debrief a negotiation
+    pub async fn negotiation_debrief(&self, _negotiation: &str) -> Result<String, AppError> {
+        self.simulate("negotiation_debrief").await?;
+        Ok(json!({
+            "outcome": "The candidate kept the base salary but opened the door to a signing bonus.",
+            "score": 6,
+            "strengths": ["Stayed polite and enthusiastic about the role"],
+            "missed_opportunities": ["Did not mention the posted salary range"],
+            "phrasing_suggestions": [
+                {
+                    "instead_of": "Can you pay more?",
+                    "try": "Based on the posted range and my experience with Python and SQL, I was hoping for a base closer to the top of the band.",
+                    "why": "Anchors the ask in evidence and names a specific target"
+                }
+            ],
+            "next_steps": ["Open with enthusiasm, then name one specific number backed by the benchmarks"]
+        })
+        .to_string())
+    }
+
+    /// Mine STAR stories
+    pub async fn mine_star_stories(&self, _background: &str) -> Result<String, AppError> {
+        self.simulate("mine_star_stories").await?;
+        Ok(json!({
+            "stories": [
+                {
+                    "title": "Shipping the Flask API under a deadline",
+                    "source": "Intern, Example Corp",
+                    "competencies": ["problem_solving", "initiative"],
+                    "situation": "Our team's API release was two weeks away and the endpoints had no tests.",
+                    "task": "I was asked to make the endpoints I had built safe to release.",
+                    "action": "I wrote integration tests for each endpoint and fixed the three bugs they found.",
+                    "result": "The release went out on time with no rollbacks.",
+                    "follow_up_questions": ["How many endpoints did you cover?", "What did you learn from the bugs you found?"]
+                }
+            ]
+        })
+        .to_string())
+    }
+
+    /// Refine a STAR story
+    pub async fn refine_star_story(&self, _story: &str) -> Result<String, AppError> {
+        self.simulate("refine_star_story").await?;
+        Ok(json!({
+            "title": "Shipping the Flask API under a deadline",
+            "competencies": ["problem_solving", "initiative"],
+            "situation": "Our team's API release was two weeks away and none of its twelve endpoints had tests.",
+            "task": "I was asked to make the five endpoints I had built safe to release.",
+            "action": "I wrote integration tests for each endpoint, found three bugs, and fixed them with my mentor's review.",
+            "result": "The release went out on time with no rollbacks, and the team adopted my test setup.",
+            "follow_up_questions": [],
+            "ready": true
+        })
+        .to_string())
+    }
+
+    /// Transcribe a PDF
+    pub async fn transcribe_pdf(&self, _pdf: &[u8]) -> Result<String, AppError> {
+        self.simulate("transcribe_pdf").await?;
+        Ok("Jane Doe\nJunior Software Developer\n\nSkills\n- Python, SQL, Git\n\nExperience\nIntern, Example Corp (2024)\n- Built REST endpoints in Flask".to_string())
+    }
+
+    /// Read a photo of a certificate
+    pub async fn read_image(&self, _image: &[u8]) -> Result<String, AppError> {
+        self.simulate("read_image").await?;
+        Ok(json!({
+            "document_type": "certificate",
+            "text": "Certificate of Completion\nJane Doe\nhas completed Introduction to SQL\nExample Academy\nIssued March 2024\nCredential ID: EX-12345",
+            "certificates": [
+                {
+                    "name": "Introduction to SQL",
+                    "issuer": "Example Academy",
+                    "issue_date": "2024-03-01",
+                    "expiry_date": null,
+                    "credential_id": "EX-12345",
+                    "credential_url": null
+                }
+            ]
+        })
+        .to_string())
+    }
+
+    /// Answer a question with tools: calls the first offered tool once, then
+    /// answers
+    pub async fn answer_with_tools(
+        &self,
+        _question: &str,
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+    ) -> Result<ModelTurn, AppError> {
+        self.simulate("answer_with_tools").await?;
+        if let (true, Some(tool)) = (rounds.is_empty(), tools.first()) {
+            return Ok(ModelTurn::Calls(vec![ToolCall {
+                id: "call_0_0".to_string(),
+                name: tool.name.to_string(),
+                arguments: json!({}),
+            }]));
+        }
+        Ok(ModelTurn::Answer(format!(
+            "This is a mock answer informed by {} tool call(s). Keep working through your roadmap and apply to entry-level roles that match your skills. This is a suggestion, not a guarantee.",
+            rounds.iter().map(|r| r.calls.len()).sum::<usize>()
+        )))
+    }
+
+    /// Generate career-related content
+    pub async fn generate_content(
+        &self,
+        content_type: &str,
+        _input: &str,
+        _parameters: Option<serde_json::Value>,
+    ) -> Result<String, AppError> {
+        self.simulate("generate_content").await?;
+        Ok(json!({
+            "content_type": content_type,
+            "content": format!("Mock {} generated for load testing.", content_type.replace('_', " ")),
+            "metadata": {"word_count": 6, "tone": "professional"}
+        })
+        .to_string())
+    }
+
+    /// Screen a job posting for scam indicators
+    pub async fn screen_job_posting(&self, _posting: &str) -> Result<String, AppError> {
+        self.simulate("screen_job_posting").await?;
+        Ok(json!({
+            "risk_score": 10,
+            "indicators": [],
+            "summary": "Mock screening: no scam indicators found."
+        })
+        .to_string())
+    }
+
+    /// Review a roadmap against the quality rubric
+    pub async fn review_roadmap(&self, _roadmap: &str) -> Result<String, AppError> {
+        self.simulate("review_roadmap").await?;
+        Ok(json!({
+            "issues": [],
+            "revised_roadmap": null
+        })
+        .to_string())
+    }
+
+    /// Suggest a replacement for a broken resource link
+    pub async fn suggest_resource_replacement(&self, _resource: &str) -> Result<String, AppError> {
+        self.simulate("suggest_resource_replacement").await?;
+        Ok(json!({
+            "url": null,
+            "title": null,
+            "reason": "Mock: no replacement suggested."
+        })
+        .to_string())
+    }
+
+    /// Summarize a mentor conversation
+    pub async fn summarize_conversation(&self, _transcript: &str) -> Result<String, AppError> {
+        self.simulate("summarize_conversation").await?;
+        Ok(json!({
+            "points": ["Mock summary: focus on one core skill at a time."],
+            "action_items": ["Mock: pick a project that uses your target stack."]
+        })
+        .to_string())
+    }
+
+    /// Compress older conversation turns into a running summary
+    pub async fn compress_conversation(&self, _turns: &str) -> Result<String, AppError> {
+        self.simulate("compress_conversation").await?;
+        Ok(json!({
+            "summary": "Mock summary: the user is learning web development and was advised to build a portfolio project."
+        })
+        .to_string())
+    }
+}
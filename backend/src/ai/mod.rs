@@ -1,70 +1,871 @@
 //! AI service abstraction layer.
 //!
-//! This module provides AI-powered features using Google Gemini and Groq APIs.
-//! Supports multiple actions: skill extraction, roadmap generation, Q&A, and content generation.
+//! This module provides AI-powered features using Google Gemini and Groq APIs, or
+//! self-hosted models through [`ollama`].
+//! Supports multiple actions: skill extraction, roadmap generation and review, Q&A, content
+//! generation, and job posting screening.
+//! A [`mock::MockClient`] can stand in for every provider during local development and load tests.
+//! User-supplied text is fenced against prompt injection and responses are validated; see [`prompt`].
+//! Stray markdown fences and commentary around JSON responses are removed by [`output`].
+//! Skill extraction, roadmaps, and answers are constrained to the response structs' JSON [`schema`].
+//! Inputs pass a [`preflight`] check before any credits are charged or tokens spent.
+//! Requests may tune sampling through [`types::GenerationSettings`]; each call is recorded by [`usage`].
+//! Requests of users who consented are kept with the raw response for debugging by [`capture`].
+//! New prompt versions are served to a share of traffic while they are rolled out (see [`rollout`]).
+//! Mentor questions can call whitelisted internal [`tools`] before answering,
+//! or have their answer streamed as it is generated (see [`stream`]).
+//! Requests for the `auto` provider fall back through the configured providers
+//! when one is rate limited or failing (see [`AIService::with_fallback_order`]).
+//! Providers that keep failing are skipped for a cooldown by their [`circuit`] breaker.
 
 pub mod types;
+pub mod capture;
+pub mod circuit;
 pub mod gemini;
 pub mod groq;
+pub mod mock;
+pub mod ollama;
+pub mod output;
+pub mod preflight;
+pub mod prompt;
+pub mod retry;
+pub mod rollout;
+pub mod schema;
+pub mod stream;
+pub mod tools;
+pub mod usage;
 
+use crate::credits::CreditLedger;
 use crate::errors::AppError;
 use types::*;
+use capture::{CaptureLog, CaptureRecord};
+use circuit::{CircuitBreakers, ProviderCircuit};
 use gemini::GeminiClient;
 use groq::GroqClient;
+use mock::{MockClient, MockConfig};
+use ollama::OllamaClient;
+use rollout::PromptRouter;
+use stream::{AnswerPart, AnswerStream};
+use tools::{ModelTurn, ToolDefinition, ToolExecutor, ToolInvocation, ToolRound};
+use usage::{UsageLog, UsageRecord};
 
 /// AI service that abstracts over multiple providers
 pub struct AIService {
     gemini_client: Option<GeminiClient>,
     groq_client: Option<GroqClient>,
+    ollama_client: Option<OllamaClient>,
+    /// When set, serves every request regardless of the requested provider
+    mock_client: Option<MockClient>,
+    /// When set, charges each request's action cost to the requesting user
+    credits: Option<CreditLedger>,
+    /// When set, records every provider call
+    usage_log: Option<UsageLog>,
+    /// When set, captures the requests of users who consented
+    capture_log: Option<CaptureLog>,
+    /// When set, sends part of the traffic to prompts being rolled out
+    prompt_router: Option<PromptRouter>,
+    /// Providers `auto` requests try, in order
+    fallback_order: Vec<AIProvider>,
+    /// Breakers of the real providers; `None` in mock mode
+    circuits: Option<CircuitBreakers>,
+}
+
+/// A document Gemini reads through its Files API.
+#[derive(Clone, Copy)]
+enum FileTask<'a> {
+    TranscribePdf { pdf: &'a [u8] },
+    ReadImage { image: &'a [u8], mime_type: &'a str },
 }
 
 impl AIService {
-    /// Create a new AI service with API keys from environment
-    pub fn new(gemini_api_key: Option<String>, groq_api_key: Option<String>) -> Self {
+    /// Create a new AI service with API keys from environment and, for
+    /// self-hosted models, an Ollama client
+    pub fn new(gemini_api_key: Option<String>, groq_api_key: Option<String>, ollama: Option<OllamaClient>) -> Self {
         let gemini_client = gemini_api_key.map(GeminiClient::new);
         let groq_client = groq_api_key.map(GroqClient::new);
 
-        if gemini_client.is_none() && groq_client.is_none() {
+        if gemini_client.is_none() && groq_client.is_none() && ollama.is_none() {
             tracing::warn!("No AI API keys configured. AI features will not be available.");
         }
 
         Self {
             gemini_client,
             groq_client,
+            ollama_client: ollama,
+            mock_client: None,
+            credits: None,
+            usage_log: None,
+            capture_log: None,
+            prompt_router: None,
+            fallback_order: AIProvider::FALLBACK_ORDER.to_vec(),
+            circuits: Some(CircuitBreakers::from_env()),
+        }
+    }
+
+    /// Create an AI service that answers every provider with fixture responses.
+    ///
+    /// Intended for local development and load testing only; no external API
+    /// is ever called.
+    pub fn mock(config: MockConfig) -> Self {
+        tracing::warn!(
+            "AI mock mode enabled: latency={:?} (+{:?} jitter), failure_rate={}, seed={}",
+            config.latency, config.jitter, config.failure_rate, config.seed
+        );
+        Self {
+            gemini_client: None,
+            groq_client: None,
+            ollama_client: None,
+            mock_client: Some(MockClient::new(config)),
+            credits: None,
+            usage_log: None,
+            capture_log: None,
+            prompt_router: None,
+            fallback_order: AIProvider::FALLBACK_ORDER.to_vec(),
+            circuits: None,
+        }
+    }
+
+    /// Charge AI actions against users' credit balances.
+    ///
+    /// Requests without a `user_id` are not charged.
+    pub fn with_credits(mut self, ledger: CreditLedger) -> Self {
+        self.credits = Some(ledger);
+        self
+    }
+
+    /// Record each provider call, with its sampling settings, in `ai_usage_logs`.
+    pub fn with_usage_log(mut self, usage_log: UsageLog) -> Self {
+        self.usage_log = Some(usage_log);
+        self
+    }
+
+    /// Capture the requests of users who consented, with the providers' raw
+    /// responses, in `debug_captures`.
+    pub fn with_capture_log(mut self, capture_log: CaptureLog) -> Self {
+        self.capture_log = Some(capture_log);
+        self
+    }
+
+    /// Send the share of traffic each live prompt rollout sets to the
+    /// action's candidate prompt version.
+    pub fn with_prompt_router(mut self, prompt_router: PromptRouter) -> Self {
+        self.prompt_router = Some(prompt_router);
+        self
+    }
+
+    /// Router of prompt rollouts, if one is set.
+    pub fn prompt_router(&self) -> Option<&PromptRouter> {
+        self.prompt_router.as_ref()
+    }
+
+    /// Try providers in `order` for `auto` requests (default: Gemini, Groq,
+    /// Ollama). Providers left out are never fallen back to.
+    pub fn with_fallback_order(mut self, order: Vec<AIProvider>) -> Self {
+        self.fallback_order = order;
+        self
+    }
+
+    /// Configured providers an `auto` request tries, in order.
+    pub fn fallback_chain(&self) -> Vec<AIProvider> {
+        let configured = self.providers();
+        self.fallback_order
+            .iter()
+            .filter(|p| configured.contains(p))
+            .cloned()
+            .collect()
+    }
+
+    /// Circuit breakers of the configured providers, in fallback order.
+    /// Empty in mock mode.
+    pub fn circuits(&self) -> Vec<ProviderCircuit> {
+        let Some(circuits) = self.circuits.as_ref() else {
+            return Vec::new();
+        };
+        let providers: Vec<&'static str> = self.providers().iter().map(AIProvider::as_str).collect();
+        circuits.snapshot(&providers)
+    }
+
+    /// Whether [`Self::embed`] can run: embeddings need Gemini (or mock mode).
+    pub fn embeddings_available(&self) -> bool {
+        self.mock_client.is_some() || self.gemini_client.is_some()
+    }
+
+    /// Checks each configured provider's API key with a cheap call that
+    /// generates nothing. Mock mode has no keys to check.
+    pub async fn check_providers(&self) -> Vec<(&'static str, Result<(), AppError>)> {
+        let mut results = Vec::new();
+        if let Some(gemini) = self.gemini_client.as_ref() {
+            results.push(("gemini", gemini.check_key().await));
+        }
+        if let Some(groq) = self.groq_client.as_ref() {
+            results.push(("groq", groq.check_key().await));
+        }
+        if let Some(ollama) = self.ollama_client.as_ref() {
+            results.push(("ollama", ollama.check_model().await));
+        }
+        results
+    }
+
+    /// Providers requests can be sent to. In mock mode every provider is
+    /// served by the same fixtures, so only Gemini is listed.
+    pub fn providers(&self) -> Vec<AIProvider> {
+        if self.mock_client.is_some() {
+            return vec![AIProvider::Gemini];
+        }
+        let mut providers = Vec::new();
+        if self.gemini_client.is_some() {
+            providers.push(AIProvider::Gemini);
+        }
+        if self.groq_client.is_some() {
+            providers.push(AIProvider::Groq);
+        }
+        if self.ollama_client.is_some() {
+            providers.push(AIProvider::Ollama);
+        }
+        providers
+    }
+
+    /// Whether every request is served by the mock provider.
+    pub fn is_mock(&self) -> bool {
+        self.mock_client.is_some()
+    }
+
+    /// Embed texts for retrieval, one vector per text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ConfigurationError` if Gemini is not configured, or
+    /// an error if the provider call fails.
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(mock) = self.mock_client.as_ref() {
+            return mock.embed(texts).await;
+        }
+        let client = self.gemini_client.as_ref().ok_or_else(|| {
+            AppError::ConfigurationError("Embeddings require a Gemini API key".to_string())
+        })?;
+        client.embed(texts).await
+    }
+
+    /// Transcribe a PDF CV, including scanned pages, by having Gemini read
+    /// the file natively through its Files API.
+    ///
+    /// The call is charged to `user_id` (when credits are enabled) as
+    /// `transcribe_pdf` and recorded in the usage log.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ConfigurationError` if Gemini is not configured, or
+    /// an error if credits cannot be debited, the upload or transcription
+    /// fails, or the output fails validation.
+    pub async fn transcribe_pdf(
+        &self,
+        pdf: &[u8],
+        file_name: &str,
+        user_id: Option<uuid::Uuid>,
+    ) -> Result<String, AppError> {
+        self.process_file(FileTask::TranscribePdf { pdf }, file_name, user_id).await
+    }
+
+    /// Read a photo or screenshot of a CV or certificate with Gemini's
+    /// vision model: its text, plus the metadata of any certificates shown.
+    ///
+    /// The call is charged to `user_id` (when credits are enabled) as
+    /// `read_image` and recorded in the usage log.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ConfigurationError` if Gemini is not configured, or
+    /// an error if credits cannot be debited, the upload or reading fails, or
+    /// the output is not a valid reading.
+    pub async fn read_image(
+        &self,
+        image: &[u8],
+        mime_type: &str,
+        file_name: &str,
+        user_id: Option<uuid::Uuid>,
+    ) -> Result<ImageReading, AppError> {
+        let reading = self
+            .process_file(FileTask::ReadImage { image, mime_type }, file_name, user_id)
+            .await?;
+        serde_json::from_str(&output::extract_json(&reading))
+            .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse image reading: {}", e)))
+    }
+
+    /// Run a file task with credits and usage logging. Credits are refunded
+    /// if the task fails.
+    async fn process_file(
+        &self,
+        task: FileTask<'_>,
+        file_name: &str,
+        user_id: Option<uuid::Uuid>,
+    ) -> Result<String, AppError> {
+        let (action, prompt_version) = match task {
+            FileTask::TranscribePdf { .. } => ("transcribe_pdf", gemini::TRANSCRIBE_PDF_PROMPT_VERSION),
+            FileTask::ReadImage { .. } => ("read_image", gemini::READ_IMAGE_PROMPT_VERSION),
+        };
+        // Gemini is not called, nor the user charged, while its breaker is open
+        let circuits = self.circuits.as_ref().filter(|_| self.gemini_client.is_some());
+        if let Some(circuits) = circuits {
+            circuits.allow("gemini", "Gemini")?;
+        }
+        let settings = GenerationSettings {
+            temperature: 0.0,
+            max_output_tokens: None,
+            seed: None,
+            prompt_version,
+            reading_level: None,
+            tone: None,
+        };
+
+        let charge = match (&self.credits, user_id) {
+            (Some(ledger), Some(user_id)) => Some((ledger, user_id, ledger.debit_action(user_id, action).await?)),
+            _ => None,
+        };
+
+        let started = std::time::Instant::now();
+        let ((result, provider, model), tokens) = usage::metered(async {
+            match (self.mock_client.as_ref(), self.gemini_client.as_ref()) {
+                (Some(mock), _) => {
+                    let result = match task {
+                        FileTask::TranscribePdf { pdf } => mock.transcribe_pdf(pdf).await,
+                        FileTask::ReadImage { image, .. } => mock.read_image(image).await,
+                    };
+                    (result, "mock", "mock")
+                }
+                (None, Some(client)) => {
+                    let result = match task {
+                        FileTask::TranscribePdf { pdf } => client.transcribe_pdf(pdf, file_name, &settings).await,
+                        FileTask::ReadImage { image, mime_type } => {
+                            client.read_image(image, mime_type, file_name, &settings).await
+                        }
+                    };
+                    (result, "gemini", gemini::DEFAULT_MODEL)
+                }
+                (None, None) => (
+                    Err(AppError::ConfigurationError(
+                        "Document analysis requires a Gemini API key".to_string(),
+                    )),
+                    "gemini",
+                    gemini::DEFAULT_MODEL,
+                ),
+            }
+        })
+        .await;
+        if let Some(circuits) = circuits {
+            circuits.record("gemini", "Gemini", matches!(&result, Err(e) if circuit::is_provider_failure(e, false)));
+        }
+        let answered = result.is_ok();
+        let result = result
+            .and_then(|text| prompt::validate_output(&text).map(|_| text))
+            .and_then(|text| match task {
+                // A reading that does not parse is a failed call, so it is refunded
+                FileTask::ReadImage { .. } => serde_json::from_str::<ImageReading>(&output::extract_json(&text))
+                    .map(|_| text)
+                    .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse image reading: {}", e))),
+                FileTask::TranscribePdf { .. } => Ok(text),
+            });
+
+        if let Some(usage_log) = &self.usage_log {
+            usage_log
+                .record(UsageRecord {
+                    user_id,
+                    action,
+                    provider,
+                    model,
+                    prompt_version,
+                    settings,
+                    latency_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+                    success: result.is_ok(),
+                    parse_failed: answered && result.is_err(),
+                    tokens,
+                })
+                .await;
+        }
+
+        if result.is_err()
+            && let Some((ledger, user_id, cost)) = charge
+            && cost > 0
+        {
+            ledger.refund_action(user_id, action, cost).await;
+        }
+        result
+    }
+
+    /// Model that serves requests for a provider (`"mock"` in mock mode).
+    /// For `auto`, the model of the first provider in the fallback chain.
+    pub fn model_for(&self, provider: &AIProvider) -> &'static str {
+        if self.mock_client.is_some() {
+            return "mock";
+        }
+        match provider {
+            AIProvider::Gemini => gemini::DEFAULT_MODEL,
+            AIProvider::Groq => groq::DEFAULT_MODEL,
+            AIProvider::Ollama => self.ollama_client.as_ref().map_or(ollama::DEFAULT_MODEL, OllamaClient::model),
+            AIProvider::Auto => self
+                .fallback_chain()
+                .first()
+                .map_or(gemini::DEFAULT_MODEL, |p| self.model_for(p)),
         }
     }
 
     /// Process an AI action request
+    ///
+    /// The input is preflighted and the generation settings validated first;
+    /// unusable requests are rejected before anything is charged. When
+    /// credits are enabled, the action cost is debited before the provider is
+    /// called and refunded if the call fails.
+    ///
+    /// An `auto` request tries the [`Self::fallback_chain`] in order, moving
+    /// on when a provider is rate limited, unavailable, or returns unusable
+    /// output; blocked content is returned as it is. The response's
+    /// `provider` is the one that served it, and `fallbacks` the ones that
+    /// failed first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` if the input fails preflight, a
+    /// generation setting is out of bounds, or a question's `history` is not
+    /// a valid chat, `AppError::ConfigurationError` if the requested provider
+    /// (or, for `auto`, every provider) is not configured, or an error if
+    /// credits cannot be debited.
     pub async fn process_action(&self, request: AIActionRequest) -> Result<AIActionResponse, AppError> {
-        // Select the appropriate client based on provider
-        let result = match request.provider {
-            AIProvider::Gemini => {
-                let client = self.gemini_client.as_ref().ok_or_else(|| {
-                    AppError::ConfigurationError("Gemini API key not configured".to_string())
-                })?;
-                self.execute_action(client, &request).await
-            }
-            AIProvider::Groq => {
-                let client = self.groq_client.as_ref().ok_or_else(|| {
-                    AppError::ConfigurationError("Groq API key not configured".to_string())
-                })?;
-                self.execute_action(client, &request).await
+        self.process(request, None, None).await
+    }
+
+    /// Process an AI action request, letting the model call `tools` first.
+    ///
+    /// Only [`ActionType::AskQuestion`] supports tools; other actions run as
+    /// in [`Self::process_action`]. The answer's `related_topics` is empty,
+    /// since function-calling responses are plain text. Calls are reported
+    /// in the response's `tool_calls`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::process_action`].
+    pub async fn process_action_with_tools(
+        &self,
+        request: AIActionRequest,
+        tools: &dyn ToolExecutor,
+    ) -> Result<AIActionResponse, AppError> {
+        self.process(request, Some(tools), None).await
+    }
+
+    /// Process an [`ActionType::AskQuestion`] request, forwarding the answer
+    /// to `stream` as it is generated.
+    ///
+    /// The answer is plain text, so its `related_topics` is empty. An `auto`
+    /// request falls back to the next provider only until the first text
+    /// was forwarded. Providers without a streaming API forward the whole
+    /// answer at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` for other actions; otherwise as
+    /// [`Self::process_action`].
+    pub async fn process_action_streaming(
+        &self,
+        request: AIActionRequest,
+        stream: &AnswerStream,
+    ) -> Result<AIActionResponse, AppError> {
+        if !matches!(request.action, ActionType::AskQuestion) {
+            return Err(AppError::ValidationError(
+                "Only ask_question answers can be streamed".to_string(),
+            ));
+        }
+        self.process(request, None, Some(stream)).await
+    }
+
+    async fn process(
+        &self,
+        request: AIActionRequest,
+        tools: Option<&dyn ToolExecutor>,
+        stream: Option<&AnswerStream>,
+    ) -> Result<AIActionResponse, AppError> {
+        let report = preflight::check(&request.action, &request.input)?;
+        let mut settings = GenerationSettings::from_request(&request)?;
+        if let Some(router) = &self.prompt_router {
+            settings.prompt_version = router.prompt_version(&request.action, request.user_id);
+        }
+        if matches!(request.action, ActionType::AskQuestion) {
+            request_history(&request)?;
+        }
+        if !report.warnings.is_empty() {
+            tracing::info!(
+                action = request.action.as_str(),
+                warnings = ?report.warnings,
+                "AI input preflight warnings"
+            );
+        }
+
+        let candidates = self.candidates(&request.provider)?;
+
+        let charge = match (&self.credits, request.user_id) {
+            (Some(ledger), Some(user_id)) => {
+                let cost = ledger.debit_action(user_id, request.action.as_str()).await?;
+                Some((ledger, user_id, cost))
+            }
+            _ => None,
+        };
+
+        // Fencing neutralizes injected instructions; flagged inputs are logged for review
+        let signals = request_injection_signals(&request);
+        if !signals.is_empty() {
+            tracing::warn!(
+                user_id = ?request.user_id,
+                action = request.action.as_str(),
+                patterns = ?signals,
+                "Suspected prompt injection in AI request"
+            );
+        }
+
+        let mut fallbacks = Vec::new();
+        let mut candidates = candidates.into_iter().peekable();
+        let (result, provider, provider_name, model, raw) = loop {
+            let Some(provider) = candidates.next() else {
+                unreachable!("candidates is never empty");
+            };
+            // An open breaker answers at once, without calling the provider
+            let breaker = self.circuits.as_ref().map(|circuits| {
+                (circuits, circuits.allow(provider.as_str(), provider_label(&provider)))
+            });
+            if let Some((_, Err(e))) = breaker {
+                if candidates.peek().is_some() {
+                    tracing::warn!(
+                        action = request.action.as_str(),
+                        provider = provider.as_str(),
+                        "AI provider circuit open; falling back"
+                    );
+                    fallbacks.push(provider);
+                    continue;
+                }
+                let (provider_name, model) = (provider.as_str(), self.model_for(&provider));
+                break (Err(e), provider, provider_name, model, None);
+            }
+
+            let started = std::time::Instant::now();
+            let mut raw = None;
+            let ((result, provider_name, model), tokens) =
+                usage::metered(self.call(&provider, &request, &settings, tools, stream, &mut raw)).await;
+            if let Some((circuits, _)) = breaker {
+                let failed = matches!(&result, Err(e) if circuit::is_provider_failure(e, raw.is_some()));
+                circuits.record(provider.as_str(), provider_label(&provider), failed);
+            }
+
+            if let Some(usage_log) = &self.usage_log {
+                usage_log
+                    .record(UsageRecord {
+                        user_id: request.user_id,
+                        action: request.action.as_str(),
+                        provider: provider_name,
+                        model,
+                        prompt_version: settings.prompt_version,
+                        settings,
+                        latency_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+                        success: result.is_ok(),
+                        // Text came back but was unusable: malformed, off-schema, or unsafe
+                        parse_failed: result.is_err() && raw.is_some(),
+                        tokens,
+                    })
+                    .await;
+            }
+
+            match result {
+                Err(e) if candidates.peek().is_some()
+                    && should_fall_back(&e)
+                    && !stream.is_some_and(AnswerStream::started) =>
+                {
+                    tracing::warn!(
+                        action = request.action.as_str(),
+                        provider = provider_name,
+                        error = %e,
+                        "AI provider failed; falling back"
+                    );
+                    fallbacks.push(provider);
+                }
+                result => break (result, provider, provider_name, model, raw),
             }
         };
 
-        match result {
-            Ok(data) => Ok(AIActionResponse {
+        if result.is_err()
+            && let Some((ledger, user_id, cost)) = charge
+            && cost > 0
+        {
+            ledger.refund_action(user_id, request.action.as_str(), cost).await;
+        }
+
+        let mut response = match result {
+            Ok((data, tool_calls)) => AIActionResponse {
                 success: true,
                 data,
-                provider: request.provider,
+                provider,
+                fallbacks,
+                model,
+                prompt_version: settings.prompt_version,
+                settings,
                 message: None,
-            }),
-            Err(e) => Ok(AIActionResponse {
+                warnings: report.warnings,
+                tool_calls,
+                capture_id: None,
+            },
+            Err(e) => AIActionResponse {
                 success: false,
-                data: serde_json::json!({"error": e.to_string()}),
-                provider: request.provider,
+                data: match &e {
+                    AppError::ContentBlocked { reason, categories } => serde_json::json!({
+                        "error": e.to_string(),
+                        "block_reason": reason,
+                        "blocked_categories": categories
+                    }),
+                    _ => serde_json::json!({"error": e.to_string()}),
+                },
+                provider,
+                fallbacks,
+                model,
+                prompt_version: settings.prompt_version,
+                settings,
                 message: Some(e.to_string()),
-            }),
+                warnings: report.warnings,
+                tool_calls: Vec::new(),
+                capture_id: None,
+            },
+        };
+
+        if let (Some(capture_log), Some(user_id), true) = (&self.capture_log, request.user_id, request.capture) {
+            response.capture_id = capture_log
+                .record(CaptureRecord {
+                    user_id,
+                    request: &request,
+                    provider: provider_name,
+                    model,
+                    prompt_version: response.prompt_version,
+                    settings,
+                    raw_response: raw.as_deref(),
+                    response: &response.data,
+                    success: response.success,
+                })
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// Providers to try for a request, in order: the requested one, or the
+    /// fallback chain for `auto`. In mock mode the mock serves a single
+    /// attempt.
+    fn candidates(&self, requested: &AIProvider) -> Result<Vec<AIProvider>, AppError> {
+        if self.mock_client.is_some() {
+            return Ok(vec![match requested {
+                AIProvider::Auto => AIProvider::Gemini,
+                provider => provider.clone(),
+            }]);
+        }
+        let configured = match requested {
+            AIProvider::Gemini => self.gemini_client.is_some(),
+            AIProvider::Groq => self.groq_client.is_some(),
+            AIProvider::Ollama => self.ollama_client.is_some(),
+            AIProvider::Auto => {
+                let chain = self.fallback_chain();
+                if chain.is_empty() {
+                    return Err(AppError::ConfigurationError(
+                        "No AI provider in the fallback order is configured".to_string(),
+                    ));
+                }
+                return Ok(chain);
+            }
+        };
+        if !configured {
+            return Err(AppError::ConfigurationError(match requested {
+                AIProvider::Ollama => "Ollama base URL not configured".to_string(),
+                provider => format!("{} API key not configured", provider_label(provider)),
+            }));
+        }
+        Ok(vec![requested.clone()])
+    }
+
+    /// Sends the request to one provider. Returns the result with the
+    /// provider and model names recorded for the call.
+    async fn call(
+        &self,
+        provider: &AIProvider,
+        request: &AIActionRequest,
+        settings: &GenerationSettings,
+        tools: Option<&dyn ToolExecutor>,
+        stream: Option<&AnswerStream>,
+        raw: &mut Option<String>,
+    ) -> (Result<(serde_json::Value, Vec<ToolInvocation>), AppError>, &'static str, &'static str) {
+        if let Some(mock) = self.mock_client.as_ref() {
+            return (self.run(mock, request, settings, tools, stream, raw).await, "mock", "mock");
+        }
+        let unconfigured = || Err(AppError::ConfigurationError(format!("{} is not configured", provider_label(provider))));
+        match provider {
+            AIProvider::Gemini => match self.gemini_client.as_ref() {
+                Some(client) => (self.run(client, request, settings, tools, stream, raw).await, "gemini", gemini::DEFAULT_MODEL),
+                None => (unconfigured(), "gemini", gemini::DEFAULT_MODEL),
+            },
+            AIProvider::Groq => match self.groq_client.as_ref() {
+                Some(client) => (self.run(client, request, settings, tools, stream, raw).await, "groq", groq::DEFAULT_MODEL),
+                None => (unconfigured(), "groq", groq::DEFAULT_MODEL),
+            },
+            AIProvider::Ollama => match self.ollama_client.as_ref() {
+                Some(client) => (self.run(client, request, settings, tools, stream, raw).await, "ollama", client.model()),
+                None => (unconfigured(), "ollama", ollama::DEFAULT_MODEL),
+            },
+            AIProvider::Auto => unreachable!("auto is resolved into providers by candidates"),
+        }
+    }
+
+    /// Runs the action, through the tool loop when tools are offered, or
+    /// streaming the answer when a stream is given. The text the provider
+    /// returned is kept in `raw`, even when it cannot be used.
+    async fn run<T: AIClient>(
+        &self,
+        client: &T,
+        request: &AIActionRequest,
+        settings: &GenerationSettings,
+        tools: Option<&dyn ToolExecutor>,
+        stream: Option<&AnswerStream>,
+        raw: &mut Option<String>,
+    ) -> Result<(serde_json::Value, Vec<ToolInvocation>), AppError> {
+        if let Some(stream) = stream
+            && matches!(request.action, ActionType::AskQuestion)
+        {
+            return Ok((self.answer_in_sections(client, request, settings, stream, raw).await?, Vec::new()));
+        }
+        match tools {
+            Some(tools) if matches!(request.action, ActionType::AskQuestion) => {
+                self.answer_with_tools(client, request, settings, tools, raw).await
+            }
+            _ => Ok((self.execute_action(client, request, settings, raw).await?, Vec::new())),
+        }
+    }
+
+    /// Answers a question in plain text, forwarding it to `stream`. While
+    /// the provider stops at its output token limit, it is asked server-side
+    /// to continue where it stopped, up to [`MAX_ANSWER_SECTIONS`] times, and
+    /// the sections are stitched into one answer. Answers of more than one
+    /// section report them as `sections`.
+    async fn answer_in_sections<T: AIClient>(
+        &self,
+        client: &T,
+        request: &AIActionRequest,
+        settings: &GenerationSettings,
+        stream: &AnswerStream,
+        raw: &mut Option<String>,
+    ) -> Result<serde_json::Value, AppError> {
+        let parameter = |name: &str| {
+            request.parameters.as_ref()
+                .and_then(|p| p.get(name))
+                .and_then(|v| v.as_str())
+        };
+        let mut history = request_history(request)?;
+        let asked = history.len();
+        let mut parts: Vec<AnswerPart> = Vec::new();
+        let mut answer = String::new();
+
+        loop {
+            let question = if parts.is_empty() { request.input.as_str() } else { CONTINUE_INSTRUCTION };
+            let part = match client
+                .stream_answer(question, parameter("context"), &history, parameter("persona"), settings, stream)
+                .await
+            {
+                Ok(part) => part,
+                Err(e) if parts.is_empty() => return Err(e),
+                // The sections so far were already streamed; keep them
+                Err(e) => {
+                    tracing::warn!("Failed to continue answer after {} sections: {}", parts.len(), e);
+                    break;
+                }
+            };
+            answer.push_str(&part.text);
+            let truncated = part.truncated;
+            parts.push(part);
+            if !truncated || parts.len() >= MAX_ANSWER_SECTIONS {
+                break;
+            }
+            // The model sees its answer so far as its reply to the question
+            history.truncate(asked);
+            history.push(ChatMessage::user(request.input.clone()));
+            history.push(ChatMessage::assistant(answer.clone()));
+        }
+
+        *raw = Some(answer.clone());
+        prompt::validate_output(&answer)?;
+        let mut data = serde_json::json!({
+            "question": request.input,
+            "answer": answer.trim(),
+            "related_topics": [],
+        });
+        if parts.len() > 1 {
+            data["sections"] = serde_json::json!(answer_sections(&answer, &parts));
+        }
+        Ok(data)
+    }
+
+    /// Answers a question, running the tools the model calls until it
+    /// answers. Calls to tools that were not offered are refused, and tool
+    /// errors are passed back to the model as the call's output.
+    async fn answer_with_tools<T: AIClient>(
+        &self,
+        client: &T,
+        request: &AIActionRequest,
+        settings: &GenerationSettings,
+        tools: &dyn ToolExecutor,
+        raw: &mut Option<String>,
+    ) -> Result<(serde_json::Value, Vec<ToolInvocation>), AppError> {
+        let parameter = |name: &str| {
+            request.parameters.as_ref()
+                .and_then(|p| p.get(name))
+                .and_then(|v| v.as_str())
+        };
+        let history = request_history(request)?;
+        let definitions = tools.definitions();
+        let mut rounds: Vec<ToolRound> = Vec::new();
+        let mut invocations = Vec::new();
+
+        loop {
+            let offered = if rounds.len() < tools::MAX_TOOL_ROUNDS { definitions.as_slice() } else { &[] };
+            let turn = client
+                .answer_with_tools(&request.input, parameter("context"), &history, &rounds, offered, parameter("persona"), settings)
+                .await?;
+            let calls = match turn {
+                ModelTurn::Answer(answer) => {
+                    *raw = Some(answer.clone());
+                    prompt::validate_output(&answer)?;
+                    let data = serde_json::json!({
+                        "question": request.input,
+                        "answer": answer.trim(),
+                        "related_topics": [],
+                    });
+                    return Ok((data, invocations));
+                }
+                ModelTurn::Calls(_) if offered.is_empty() => {
+                    return Err(AppError::ExternalServiceError(
+                        "Model kept calling tools without answering".to_string(),
+                    ));
+                }
+                ModelTurn::Calls(calls) => calls,
+            };
+
+            let mut outputs = Vec::with_capacity(calls.len());
+            for call in &calls {
+                let started = std::time::Instant::now();
+                let result = if offered.iter().any(|d| d.name == call.name) {
+                    tools.execute(call).await
+                } else {
+                    tracing::warn!(tool = %call.name, "Model called a tool that was not offered");
+                    Err(AppError::BadRequest(format!("Unknown tool '{}'", call.name)))
+                };
+                invocations.push(ToolInvocation {
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
+                    success: result.is_ok(),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                    latency_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+                });
+                outputs.push(result.unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() })));
+            }
+            rounds.push(ToolRound { calls, outputs });
         }
     }
 
@@ -73,13 +874,13 @@ impl AIService {
         &self,
         client: &T,
         request: &AIActionRequest,
+        settings: &GenerationSettings,
+        raw: &mut Option<String>,
     ) -> Result<serde_json::Value, AppError> {
         match request.action {
             ActionType::ExtractSkills => {
-                let result = client.extract_skills(&request.input).await?;
-                let parsed: serde_json::Value = serde_json::from_str(&result)
-                    .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse AI response: {}", e)))?;
-                Ok(parsed)
+                let result = client.extract_skills(&request.input, settings).await?;
+                parse_kept(result, raw)
             }
             ActionType::GenerateRoadmap => {
                 let current_skills = request.parameters.as_ref()
@@ -96,25 +897,41 @@ impl AIService {
                     .and_then(|h| h.as_u64())
                     .map(|h| h as u32);
                 
+                let persona = request.parameters.as_ref()
+                    .and_then(|p| p.get("persona"))
+                    .and_then(|s| s.as_str());
+
                 let result = client.generate_roadmap(
                     &request.input,
                     current_skills,
                     timeframe_months,
-                    learning_hours_per_week
+                    learning_hours_per_week,
+                    persona,
+                    settings,
                 ).await?;
-                let parsed: serde_json::Value = serde_json::from_str(&result)
-                    .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse AI response: {}", e)))?;
-                Ok(parsed)
+                parse_kept(result, raw)
             }
             ActionType::AskQuestion => {
                 let context = request.parameters.as_ref()
                     .and_then(|p| p.get("context"))
                     .and_then(|c| c.as_str());
-                
-                let result = client.answer_question(&request.input, context).await?;
-                let parsed: serde_json::Value = serde_json::from_str(&result)
-                    .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse AI response: {}", e)))?;
-                Ok(parsed)
+                let persona = request.parameters.as_ref()
+                    .and_then(|p| p.get("persona"))
+                    .and_then(|p| p.as_str());
+                let history = request_history(request)?;
+
+                let result = client.answer_question(&request.input, context, &history, persona, settings).await?;
+                match parse_kept(result, raw) {
+                    // Cut off at the output token limit mid-JSON: answer in
+                    // plain text instead, continuing it section by section
+                    Err(AppError::ExternalServiceError(_))
+                        if raw.as_deref().is_some_and(|r| prompt::validate_output(r).is_ok()) =>
+                    {
+                        tracing::warn!("Structured answer could not be parsed; answering in sections");
+                        self.answer_in_sections(client, request, settings, &AnswerStream::discard(), raw).await
+                    }
+                    parsed => parsed,
+                }
             }
             ActionType::GenerateContent => {
                 let content_type = request.parameters.as_ref()
@@ -122,34 +939,267 @@ impl AIService {
                     .and_then(|t| t.as_str())
                     .unwrap_or("generic");
                 
-                let result = client.generate_content(content_type, &request.input, request.parameters.clone()).await?;
-                let parsed: serde_json::Value = serde_json::from_str(&result)
-                    .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse AI response: {}", e)))?;
-                Ok(parsed)
+                // Generation settings are rendered by the client; keep them out of the parameters
+                let parameters = request.parameters.clone().map(|mut p| {
+                    if let Some(p) = p.as_object_mut() {
+                        p.remove("temperature");
+                        p.remove("max_output_tokens");
+                        p.remove("deterministic");
+                        p.remove("reading_level");
+                        p.remove("tone");
+                    }
+                    p
+                });
+                let result = client.generate_content(content_type, &request.input, parameters, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::ScreenJobPosting => {
+                let result = client.screen_job_posting(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::ReviewRoadmap => {
+                let parameter = |name: &str| request.parameters.as_ref().and_then(|p| p.get(name));
+                let target_role = parameter("target_role").and_then(|v| v.as_str()).unwrap_or("");
+                let timeframe_months = parameter("timeframe_months")
+                    .and_then(|v| v.as_u64())
+                    .map(|t| t as u32)
+                    .unwrap_or(6);
+                let learning_hours_per_week = parameter("learning_hours_per_week")
+                    .and_then(|v| v.as_u64())
+                    .map(|h| h as u32)
+                    .unwrap_or(10);
+                let auto_fix = parameter("auto_fix").and_then(|v| v.as_bool()).unwrap_or(false);
+
+                let result = client
+                    .review_roadmap(
+                        &request.input,
+                        target_role,
+                        timeframe_months,
+                        learning_hours_per_week,
+                        auto_fix,
+                        settings,
+                    )
+                    .await?;
+                parse_kept(result, raw)
+            }
+            ActionType::SuggestResourceReplacement => {
+                let result = client.suggest_resource_replacement(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::SummarizeConversation => {
+                let result = client.summarize_conversation(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::CompressConversation => {
+                let result = client.compress_conversation(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::AnalyzeJobDescription => {
+                let result = client.analyze_job_description(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::GenerateInterviewQuestions => {
+                let result = client.generate_interview_questions(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::NegotiationReply => {
+                let result = client.negotiation_reply(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::NegotiationDebrief => {
+                let result = client.negotiation_debrief(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::MineStarStories => {
+                let result = client.mine_star_stories(&request.input, settings).await?;
+                parse_kept(result, raw)
+            }
+            ActionType::RefineStarStory => {
+                let result = client.refine_star_story(&request.input, settings).await?;
+                parse_kept(result, raw)
             }
         }
     }
 }
 
+/// Injection patterns found in a request's input and string parameters.
+fn request_injection_signals(request: &AIActionRequest) -> Vec<&'static str> {
+    let mut signals = prompt::suspected_injection(&request.input);
+    if let Some(serde_json::Value::Object(parameters)) = &request.parameters {
+        for value in parameters.values().filter_map(|v| v.as_str()) {
+            signals.extend(prompt::suspected_injection(value));
+        }
+    }
+    signals.sort_unstable();
+    signals.dedup();
+    signals
+}
+
+/// Validates a provider response and parses the JSON it contains.
+/// Whether an `auto` request should move on to the next provider after
+/// `error`: provider errors, rate limits, and unusable output do; a content
+/// block would most likely recur, so it is answered as is.
+fn should_fall_back(error: &AppError) -> bool {
+    matches!(
+        error,
+        AppError::ExternalServiceError(_)
+            | AppError::ServiceUnavailable(_)
+            | AppError::TooManyRequests(_)
+            | AppError::ConfigurationError(_)
+    )
+}
+
+/// Display name of a provider in error messages.
+fn provider_label(provider: &AIProvider) -> &'static str {
+    match provider {
+        AIProvider::Gemini => "Gemini",
+        AIProvider::Groq => "Groq",
+        AIProvider::Ollama => "Ollama",
+        AIProvider::Auto => "Automatic provider selection",
+    }
+}
+
+/// Parses a provider response as [`parse_response`] does, keeping the text
+/// in `raw`.
+fn parse_kept(result: String, raw: &mut Option<String>) -> Result<serde_json::Value, AppError> {
+    let parsed = parse_response(&result);
+    *raw = Some(result);
+    parsed
+}
+
+fn parse_response(result: &str) -> Result<serde_json::Value, AppError> {
+    prompt::validate_output(result)?;
+    serde_json::from_str(&output::extract_json(result))
+        .map_err(|e| AppError::ExternalServiceError(format!("Failed to parse AI response: {}", e)))
+}
+
+/// Where each part falls in the trimmed `answer` they were stitched into.
+fn answer_sections(answer: &str, parts: &[AnswerPart]) -> Vec<AnswerSection> {
+    let leading = answer.chars().count() - answer.trim_start().chars().count();
+    let length = answer.trim().chars().count();
+    let mut offset = 0;
+    parts
+        .iter()
+        .map(|part| {
+            let start = offset;
+            offset += part.text.chars().count();
+            AnswerSection {
+                start: start.saturating_sub(leading).min(length),
+                end: offset.saturating_sub(leading).min(length),
+                truncated: part.truncated,
+            }
+        })
+        .collect()
+}
+
+/// Conversation history from the request's `history` parameter, as chat
+/// messages with a `system`, `user`, or `assistant` role.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the history is not a list of
+/// messages, or if it and the question do not form a valid chat (see
+/// [`validate_messages`]).
+fn request_history(request: &AIActionRequest) -> Result<Vec<ChatMessage>, AppError> {
+    let Some(history) = request.parameters.as_ref()
+        .and_then(|p| p.get("history"))
+        .filter(|h| !h.is_null())
+    else {
+        return Ok(Vec::new());
+    };
+    let history: Vec<ChatMessage> = serde_json::from_value(history.clone()).map_err(|e| {
+        AppError::ValidationError(format!(
+            "history must be a list of messages with a system, user, or assistant role: {}",
+            e
+        ))
+    })?;
+
+    let mut chat = history.clone();
+    chat.push(ChatMessage::user(request.input.clone()));
+    validate_messages(&chat)?;
+    Ok(history)
+}
+
 /// Trait for AI clients to implement
 #[async_trait::async_trait]
-trait AIClient {
-    async fn extract_skills(&self, cv_text: &str) -> Result<String, AppError>;
+trait AIClient: Sync {
+    async fn extract_skills(&self, cv_text: &str, settings: &GenerationSettings) -> Result<String, AppError>;
     async fn generate_roadmap(
         &self,
         tech_stack: &str,
         current_skills: Option<&str>,
         timeframe_months: Option<u32>,
         learning_hours_per_week: Option<u32>,
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError>;
+    async fn answer_question(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
     ) -> Result<String, AppError>;
-    async fn answer_question(&self, question: &str, context: Option<&str>) -> Result<String, AppError>;
-    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>) -> Result<String, AppError>;
+    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn screen_job_posting(&self, posting: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn review_roadmap(
+        &self,
+        roadmap: &str,
+        target_role: &str,
+        timeframe_months: u32,
+        learning_hours_per_week: u32,
+        auto_fix: bool,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError>;
+    async fn suggest_resource_replacement(&self, resource: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn summarize_conversation(&self, transcript: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn compress_conversation(&self, turns: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn analyze_job_description(&self, description: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn generate_interview_questions(&self, application: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn negotiation_reply(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn negotiation_debrief(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn mine_star_stories(&self, background: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    async fn refine_star_story(&self, story: &str, settings: &GenerationSettings) -> Result<String, AppError>;
+    #[allow(clippy::too_many_arguments)]
+    async fn answer_with_tools(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<ModelTurn, AppError>;
+    /// Answers in plain text, forwarding the answer to `stream`. Clients
+    /// without a streaming API forward the whole answer once it is complete,
+    /// and never report it as cut off.
+    async fn stream_answer(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+        stream: &AnswerStream,
+    ) -> Result<AnswerPart, AppError> {
+        match self.answer_with_tools(question, context, history, &[], &[], persona, settings).await? {
+            ModelTurn::Answer(answer) => {
+                stream.send(&answer);
+                Ok(AnswerPart { text: answer, truncated: false })
+            }
+            ModelTurn::Calls(_) => Err(AppError::ExternalServiceError(
+                "Model called a tool that was not offered".to_string(),
+            )),
+        }
+    }
 }
 
 #[async_trait::async_trait]
 impl AIClient for GeminiClient {
-    async fn extract_skills(&self, cv_text: &str) -> Result<String, AppError> {
-        self.extract_skills(cv_text).await
+    async fn extract_skills(&self, cv_text: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.extract_skills(cv_text, settings).await
     }
 
     async fn generate_roadmap(
@@ -158,22 +1208,318 @@ impl AIClient for GeminiClient {
         current_skills: Option<&str>,
         timeframe_months: Option<u32>,
         learning_hours_per_week: Option<u32>,
+        persona: Option<&str>,
+        settings: &GenerationSettings,
     ) -> Result<String, AppError> {
-        GeminiClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week).await
+        GeminiClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week, persona, settings).await
     }
 
-    async fn answer_question(&self, question: &str, context: Option<&str>) -> Result<String, AppError> {
-        self.answer_question(question, context).await
+    async fn answer_question(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        self.answer_question(question, context, history, persona, settings).await
     }
 
-    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>) -> Result<String, AppError> {
-        self.generate_content(content_type, input, parameters).await
+    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_content(content_type, input, parameters, settings).await
+    }
+
+    async fn screen_job_posting(&self, posting: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.screen_job_posting(posting, settings).await
+    }
+
+    async fn review_roadmap(
+        &self,
+        roadmap: &str,
+        target_role: &str,
+        timeframe_months: u32,
+        learning_hours_per_week: u32,
+        auto_fix: bool,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        GeminiClient::review_roadmap(self, roadmap, target_role, timeframe_months, learning_hours_per_week, auto_fix, settings).await
+    }
+
+    async fn suggest_resource_replacement(&self, resource: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.suggest_resource_replacement(resource, settings).await
+    }
+
+    async fn summarize_conversation(&self, transcript: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.summarize_conversation(transcript, settings).await
+    }
+
+    async fn compress_conversation(&self, turns: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.compress_conversation(turns, settings).await
+    }
+
+    async fn analyze_job_description(&self, description: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.analyze_job_description(description, settings).await
+    }
+
+    async fn generate_interview_questions(&self, application: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_interview_questions(application, settings).await
+    }
+
+    async fn negotiation_reply(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_reply(negotiation, settings).await
+    }
+
+    async fn negotiation_debrief(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_debrief(negotiation, settings).await
+    }
+
+    async fn mine_star_stories(&self, background: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.mine_star_stories(background, settings).await
+    }
+
+    async fn refine_star_story(&self, story: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.refine_star_story(story, settings).await
+    }
+
+    async fn answer_with_tools(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<ModelTurn, AppError> {
+        GeminiClient::answer_with_tools(self, question, context, history, rounds, tools, persona, settings).await
+    }
+
+    async fn stream_answer(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+        stream: &AnswerStream,
+    ) -> Result<AnswerPart, AppError> {
+        GeminiClient::stream_answer(self, question, context, history, persona, settings, stream).await
     }
 }
 
 #[async_trait::async_trait]
 impl AIClient for GroqClient {
-    async fn extract_skills(&self, cv_text: &str) -> Result<String, AppError> {
+    async fn extract_skills(&self, cv_text: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.extract_skills(cv_text, settings).await
+    }
+
+    async fn generate_roadmap(
+        &self,
+        tech_stack: &str,
+        current_skills: Option<&str>,
+        timeframe_months: Option<u32>,
+        learning_hours_per_week: Option<u32>,
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        GroqClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week, persona, settings).await
+    }
+
+    async fn answer_question(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        self.answer_question(question, context, history, persona, settings).await
+    }
+
+    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_content(content_type, input, parameters, settings).await
+    }
+
+    async fn screen_job_posting(&self, posting: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.screen_job_posting(posting, settings).await
+    }
+
+    async fn review_roadmap(
+        &self,
+        roadmap: &str,
+        target_role: &str,
+        timeframe_months: u32,
+        learning_hours_per_week: u32,
+        auto_fix: bool,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        GroqClient::review_roadmap(self, roadmap, target_role, timeframe_months, learning_hours_per_week, auto_fix, settings).await
+    }
+
+    async fn suggest_resource_replacement(&self, resource: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.suggest_resource_replacement(resource, settings).await
+    }
+
+    async fn summarize_conversation(&self, transcript: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.summarize_conversation(transcript, settings).await
+    }
+
+    async fn compress_conversation(&self, turns: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.compress_conversation(turns, settings).await
+    }
+
+    async fn analyze_job_description(&self, description: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.analyze_job_description(description, settings).await
+    }
+
+    async fn generate_interview_questions(&self, application: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_interview_questions(application, settings).await
+    }
+
+    async fn negotiation_reply(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_reply(negotiation, settings).await
+    }
+
+    async fn negotiation_debrief(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_debrief(negotiation, settings).await
+    }
+
+    async fn mine_star_stories(&self, background: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.mine_star_stories(background, settings).await
+    }
+
+    async fn refine_star_story(&self, story: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.refine_star_story(story, settings).await
+    }
+
+    async fn answer_with_tools(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<ModelTurn, AppError> {
+        GroqClient::answer_with_tools(self, question, context, history, rounds, tools, persona, settings).await
+    }
+
+    async fn stream_answer(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+        stream: &AnswerStream,
+    ) -> Result<AnswerPart, AppError> {
+        GroqClient::stream_answer(self, question, context, history, persona, settings, stream).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AIClient for OllamaClient {
+    async fn extract_skills(&self, cv_text: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.extract_skills(cv_text, settings).await
+    }
+
+    async fn generate_roadmap(
+        &self,
+        tech_stack: &str,
+        current_skills: Option<&str>,
+        timeframe_months: Option<u32>,
+        learning_hours_per_week: Option<u32>,
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        OllamaClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week, persona, settings).await
+    }
+
+    async fn answer_question(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        self.answer_question(question, context, history, persona, settings).await
+    }
+
+    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_content(content_type, input, parameters, settings).await
+    }
+
+    async fn screen_job_posting(&self, posting: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.screen_job_posting(posting, settings).await
+    }
+
+    async fn review_roadmap(
+        &self,
+        roadmap: &str,
+        target_role: &str,
+        timeframe_months: u32,
+        learning_hours_per_week: u32,
+        auto_fix: bool,
+        settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        OllamaClient::review_roadmap(self, roadmap, target_role, timeframe_months, learning_hours_per_week, auto_fix, settings).await
+    }
+
+    async fn suggest_resource_replacement(&self, resource: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.suggest_resource_replacement(resource, settings).await
+    }
+
+    async fn summarize_conversation(&self, transcript: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.summarize_conversation(transcript, settings).await
+    }
+
+    async fn compress_conversation(&self, turns: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.compress_conversation(turns, settings).await
+    }
+
+    async fn analyze_job_description(&self, description: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.analyze_job_description(description, settings).await
+    }
+
+    async fn generate_interview_questions(&self, application: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_interview_questions(application, settings).await
+    }
+
+    async fn negotiation_reply(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_reply(negotiation, settings).await
+    }
+
+    async fn negotiation_debrief(&self, negotiation: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_debrief(negotiation, settings).await
+    }
+
+    async fn mine_star_stories(&self, background: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.mine_star_stories(background, settings).await
+    }
+
+    async fn refine_star_story(&self, story: &str, settings: &GenerationSettings) -> Result<String, AppError> {
+        self.refine_star_story(story, settings).await
+    }
+
+    async fn answer_with_tools(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        history: &[ChatMessage],
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+        persona: Option<&str>,
+        settings: &GenerationSettings,
+    ) -> Result<ModelTurn, AppError> {
+        OllamaClient::answer_with_tools(self, question, context, history, rounds, tools, persona, settings).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AIClient for MockClient {
+    async fn extract_skills(&self, cv_text: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
         self.extract_skills(cv_text).await
     }
 
@@ -183,15 +1529,89 @@ impl AIClient for GroqClient {
         current_skills: Option<&str>,
         timeframe_months: Option<u32>,
         learning_hours_per_week: Option<u32>,
+        _persona: Option<&str>,
+        _settings: &GenerationSettings,
     ) -> Result<String, AppError> {
-        GroqClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week).await
+        MockClient::generate_roadmap(self, tech_stack, current_skills, timeframe_months, learning_hours_per_week).await
     }
 
-    async fn answer_question(&self, question: &str, context: Option<&str>) -> Result<String, AppError> {
+    async fn answer_question(
+        &self,
+        question: &str,
+        context: Option<&str>,
+        _history: &[ChatMessage],
+        _persona: Option<&str>,
+        _settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
         self.answer_question(question, context).await
     }
 
-    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>) -> Result<String, AppError> {
+    async fn generate_content(&self, content_type: &str, input: &str, parameters: Option<serde_json::Value>, _settings: &GenerationSettings) -> Result<String, AppError> {
         self.generate_content(content_type, input, parameters).await
     }
+
+    async fn screen_job_posting(&self, posting: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.screen_job_posting(posting).await
+    }
+
+    async fn review_roadmap(
+        &self,
+        roadmap: &str,
+        _target_role: &str,
+        _timeframe_months: u32,
+        _learning_hours_per_week: u32,
+        _auto_fix: bool,
+        _settings: &GenerationSettings,
+    ) -> Result<String, AppError> {
+        self.review_roadmap(roadmap).await
+    }
+
+    async fn suggest_resource_replacement(&self, resource: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.suggest_resource_replacement(resource).await
+    }
+
+    async fn summarize_conversation(&self, transcript: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.summarize_conversation(transcript).await
+    }
+
+    async fn compress_conversation(&self, turns: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.compress_conversation(turns).await
+    }
+
+    async fn analyze_job_description(&self, description: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.analyze_job_description(description).await
+    }
+
+    async fn generate_interview_questions(&self, application: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.generate_interview_questions(application).await
+    }
+
+    async fn negotiation_reply(&self, negotiation: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_reply(negotiation).await
+    }
+
+    async fn negotiation_debrief(&self, negotiation: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.negotiation_debrief(negotiation).await
+    }
+
+    async fn mine_star_stories(&self, background: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.mine_star_stories(background).await
+    }
+
+    async fn refine_star_story(&self, story: &str, _settings: &GenerationSettings) -> Result<String, AppError> {
+        self.refine_star_story(story).await
+    }
+
+    async fn answer_with_tools(
+        &self,
+        question: &str,
+        _context: Option<&str>,
+        _history: &[ChatMessage],
+        rounds: &[ToolRound],
+        tools: &[ToolDefinition],
+        _persona: Option<&str>,
+        _settings: &GenerationSettings,
+    ) -> Result<ModelTurn, AppError> {
+        self.answer_with_tools(question, rounds, tools).await
+    }
 }
@@ -0,0 +1,179 @@
+//! Cleanup of model output before it is parsed as JSON.
+//!
+//! Even in JSON mode, models sometimes wrap their answer in a markdown fence,
+//! add a sentence before or after it, or put raw line breaks inside strings.
+//! [`extract_json`] recovers the JSON value from such output. Output that is
+//! already valid JSON is returned unchanged.
+
+use serde::de::IgnoredAny;
+
+/// Extracts the JSON value from model output.
+///
+/// In order: output that parses as-is is returned trimmed; a surrounding
+/// markdown fence is removed; control characters are escaped inside strings
+/// and dropped outside them; and if the result still does not parse, the
+/// first balanced object or array that does is returned. When nothing
+/// parses, the cleaned text is returned so the parse error names the real
+/// problem.
+///
+/// Outputs seen from the providers:
+///
+/// ```
+/// use backend::ai::output::extract_json;
+///
+/// // A markdown fence with a language tag
+/// assert_eq!(extract_json("```json\n{\"skills\": [\"Rust\"]}\n```"), r#"{"skills": ["Rust"]}"#);
+///
+/// // A fence without a tag, after a lead-in sentence
+/// assert_eq!(extract_json("Sure! Here it is:\n```\n[1, 2]\n```"), "[1, 2]");
+///
+/// // Commentary before and after the object
+/// assert_eq!(
+///     extract_json("Here is the roadmap you asked for:\n{\"phases\": []}\nLet me know if you need changes."),
+///     r#"{"phases": []}"#
+/// );
+///
+/// // Brackets in the prose before the object
+/// assert_eq!(
+///     extract_json("[Note] Based on {your profile}, {\"answer\": \"Learn SQL\"}"),
+///     r#"{"answer": "Learn SQL"}"#
+/// );
+///
+/// // Braces inside strings do not end the object
+/// assert_eq!(
+///     extract_json("Result: {\"answer\": \"Use {braces} and \\\"quotes\\\"\"} Done."),
+///     r#"{"answer": "Use {braces} and \"quotes\""}"#
+/// );
+///
+/// // Raw line breaks and tabs inside strings, a stray NUL outside them
+/// assert_eq!(
+///     extract_json("{\"answer\": \"Line one\nLine two\tend\"}\u{0}"),
+///     r#"{"answer": "Line one\nLine two\tend"}"#
+/// );
+///
+/// // A byte order mark before the object
+/// assert_eq!(extract_json("\u{feff}{\"a\": 1}"), r#"{"a": 1}"#);
+///
+/// // Valid JSON with a fence inside a string is left alone
+/// let content = r#"{"content": "Run:\n```bash\ncargo test\n```"}"#;
+/// assert_eq!(extract_json(content), content);
+///
+/// // Unrecoverable output is returned cleaned, to fail parsing
+/// assert_eq!(extract_json("```json\n{\"phases\": [\n```"), "{\"phases\": [");
+/// ```
+pub fn extract_json(text: &str) -> String {
+    let text = text.trim().trim_start_matches('\u{feff}').trim_start();
+    if parses(text) {
+        return text.to_string();
+    }
+
+    let cleaned = escape_control_characters(strip_fence(text).trim());
+    if parses(&cleaned) {
+        return cleaned;
+    }
+
+    let starts = cleaned.char_indices().filter(|(_, c)| matches!(c, '{' | '['));
+    for (start, _) in starts {
+        if let Some(len) = balanced_len(&cleaned[start..]) {
+            let candidate = &cleaned[start..start + len];
+            if parses(candidate) {
+                return candidate.to_string();
+            }
+        }
+    }
+    cleaned
+}
+
+fn parses(text: &str) -> bool {
+    serde_json::from_str::<IgnoredAny>(text).is_ok()
+}
+
+/// The contents of the first markdown fence, if it opens before any JSON.
+fn strip_fence(text: &str) -> &str {
+    let Some(open) = text.find("```") else {
+        return text;
+    };
+    if text[..open].contains(['{', '[']) {
+        return text;
+    }
+    // Skip the language tag on the opening line
+    let body = &text[open + 3..];
+    let body = body.find('\n').map_or("", |newline| &body[newline + 1..]);
+    match body.find("```") {
+        Some(close) => &body[..close],
+        None => body,
+    }
+}
+
+/// Escapes control characters inside JSON strings and drops them outside,
+/// keeping ordinary whitespace between tokens.
+fn escape_control_characters(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            match c {
+                _ if escaped => {
+                    escaped = false;
+                    out.push(c);
+                }
+                '\\' => {
+                    escaped = true;
+                    out.push(c);
+                }
+                '"' => {
+                    in_string = false;
+                    out.push(c);
+                }
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => {}
+                c => out.push(c),
+            }
+        } else {
+            match c {
+                '"' => {
+                    in_string = true;
+                    out.push(c);
+                }
+                ' ' | '\n' | '\r' | '\t' => out.push(c),
+                c if c.is_control() => {}
+                c => out.push(c),
+            }
+        }
+    }
+    out
+}
+
+/// Byte length of the object or array `text` starts with, up to and
+/// including its closing bracket, or `None` if it is never closed.
+fn balanced_len(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
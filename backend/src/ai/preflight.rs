@@ -0,0 +1,263 @@
+//! Input preflight for AI actions.
+//!
+//! Runs before any credits are charged or provider tokens spent. Input that
+//! is empty, binary (for example PDF bytes pasted as CV text), encoded, or
+//! not readable text is rejected with a message telling the user what to do
+//! instead; input over the action's token budget is rejected too. Softer
+//! problems, such as a question written in a language the prompts are not
+//! tuned for, become warnings returned alongside the AI response.
+
+use serde::Serialize;
+
+use super::types::ActionType;
+use crate::errors::AppError;
+
+/// Endpoint that extracts text from PDF CVs.
+const CV_UPLOAD_ENDPOINT: &str = "POST /api/profile/cv/upload";
+
+/// Inputs with fewer letters than this are too short to classify by language.
+const MIN_LETTERS_FOR_LANGUAGE: usize = 20;
+
+/// Runs of this many characters without whitespace are checked for base64.
+const MIN_ENCODED_RUN: usize = 200;
+
+/// Common English words used to tell English from other Latin-script text.
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "the", "and", "of", "to", "in", "a", "is", "for", "with", "on", "i", "my", "as", "at", "be", "this", "that",
+    "are", "was", "have", "you", "an", "or", "from", "by", "what", "how", "can", "should", "do", "it",
+];
+
+/// Size limits for one action's input.
+#[derive(Debug, Clone, Copy)]
+struct InputLimits {
+    /// What the input is, for messages
+    field: &'static str,
+    min_chars: usize,
+    max_tokens: usize,
+}
+
+fn limits_for(action: &ActionType) -> InputLimits {
+    match action {
+        ActionType::ExtractSkills => InputLimits { field: "CV text", min_chars: 50, max_tokens: 5_000 },
+        ActionType::GenerateRoadmap => InputLimits { field: "Target role", min_chars: 2, max_tokens: 100 },
+        ActionType::AskQuestion => InputLimits { field: "Question", min_chars: 2, max_tokens: 1_000 },
+        ActionType::GenerateContent => InputLimits { field: "Input", min_chars: 2, max_tokens: 5_000 },
+        ActionType::ScreenJobPosting => InputLimits { field: "Job posting", min_chars: 20, max_tokens: 5_000 },
+        ActionType::ReviewRoadmap => InputLimits { field: "Roadmap", min_chars: 20, max_tokens: 8_000 },
+        ActionType::SuggestResourceReplacement => InputLimits { field: "Resource", min_chars: 10, max_tokens: 500 },
+        ActionType::SummarizeConversation => InputLimits { field: "Conversation", min_chars: 20, max_tokens: 12_000 },
+        ActionType::CompressConversation => InputLimits { field: "Conversation", min_chars: 20, max_tokens: 16_000 },
+        ActionType::AnalyzeJobDescription => InputLimits { field: "Job description", min_chars: 50, max_tokens: 5_000 },
+        ActionType::GenerateInterviewQuestions => InputLimits { field: "Job analysis", min_chars: 20, max_tokens: 6_000 },
+        ActionType::NegotiationReply => InputLimits { field: "Negotiation", min_chars: 20, max_tokens: 8_000 },
+        ActionType::NegotiationDebrief => InputLimits { field: "Negotiation", min_chars: 20, max_tokens: 8_000 },
+        ActionType::MineStarStories => InputLimits { field: "Background", min_chars: 50, max_tokens: 6_000 },
+        ActionType::RefineStarStory => InputLimits { field: "Story", min_chars: 50, max_tokens: 6_000 },
+    }
+}
+
+/// What the preflight found about an input.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    /// Characters in the input
+    pub characters: usize,
+    /// Rough token count (about 4 characters per token for Latin script)
+    pub estimated_tokens: usize,
+    /// Token budget for the action
+    pub max_tokens: usize,
+    /// Detected language (ISO 639-1), when there is enough text to tell
+    pub language: Option<&'static str>,
+    /// Problems that do not block the request
+    pub warnings: Vec<String>,
+}
+
+/// Checks an action's input before it is sent to a provider.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` with an actionable message if the
+/// input is empty, binary, encoded, unreadable, too short, or over the
+/// action's token budget.
+pub fn check(action: &ActionType, input: &str) -> Result<PreflightReport, AppError> {
+    let limits = limits_for(action);
+    let text = input.trim();
+
+    if text.is_empty() {
+        return Err(AppError::ValidationError(format!("{} is empty", limits.field)));
+    }
+    if looks_binary(text) {
+        return Err(AppError::ValidationError(match action {
+            ActionType::ExtractSkills => format!(
+                "CV text appears to be binary/PDF bytes — use the upload endpoint ({}) or paste the text instead",
+                CV_UPLOAD_ENDPOINT
+            ),
+            _ => format!("{} appears to be binary data — paste plain text instead", limits.field),
+        }));
+    }
+    if looks_encoded(text) {
+        return Err(AppError::ValidationError(format!(
+            "{} appears to contain base64-encoded data — paste the decoded text instead",
+            limits.field
+        )));
+    }
+
+    let characters = text.chars().count();
+    if characters < limits.min_chars {
+        return Err(AppError::ValidationError(format!(
+            "{} is too short ({} characters; at least {} needed)",
+            limits.field, characters, limits.min_chars
+        )));
+    }
+    if !looks_readable(text) {
+        return Err(AppError::ValidationError(format!(
+            "{} doesn't look like readable text — check that it was copied correctly",
+            limits.field
+        )));
+    }
+
+    let estimated_tokens = estimate_tokens(text);
+    if estimated_tokens > limits.max_tokens {
+        return Err(AppError::ValidationError(format!(
+            "{} is too long (about {} tokens; the limit is {}) — shorten it to the most relevant parts",
+            limits.field, estimated_tokens, limits.max_tokens
+        )));
+    }
+
+    let language = detect_language(text);
+    let mut warnings = Vec::new();
+    if let Some(code) = language.filter(|code| *code != "en") {
+        warnings.push(format!(
+            "{} appears to be in {}; responses are in English and may be less accurate",
+            limits.field,
+            language_name(code)
+        ));
+    }
+    if estimated_tokens * 10 > limits.max_tokens * 9 {
+        warnings.push(format!(
+            "{} is close to the {}-token limit",
+            limits.field, limits.max_tokens
+        ));
+    }
+
+    Ok(PreflightReport {
+        characters,
+        estimated_tokens,
+        max_tokens: limits.max_tokens,
+        language,
+        warnings,
+    })
+}
+
+/// Estimates tokens: about 4 characters per token for ASCII text and 2 for
+/// other scripts, which tokenizers split more finely.
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text
+        .chars()
+        .fold((0usize, 0usize), |(a, o), c| if c.is_ascii() { (a + 1, o) } else { (a, o + 1) });
+    ascii.div_ceil(4) + other.div_ceil(2)
+}
+
+/// Detects the dominant language, or `None` if there is too little text.
+///
+/// Non-Latin scripts map to their most common language on the platform;
+/// Latin-script text counts as English when enough common English words
+/// appear, and as undetermined (`"und"`) otherwise.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut counts = [0usize; 8];
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = match c as u32 {
+            0x0980..=0x09FF => 1, // Bengali
+            0x0900..=0x097F => 2, // Devanagari
+            0x0600..=0x06FF => 3, // Arabic
+            0x0400..=0x04FF => 4, // Cyrillic
+            0x4E00..=0x9FFF => 5, // CJK ideographs
+            0x3040..=0x30FF => 6, // Kana
+            0xAC00..=0xD7AF => 7, // Hangul
+            _ => 0,               // Latin and others
+        };
+        counts[script] += 1;
+    }
+
+    let letters: usize = counts.iter().sum();
+    if letters < MIN_LETTERS_FOR_LANGUAGE {
+        return None;
+    }
+    let (script, count) = counts.iter().enumerate().max_by_key(|(_, n)| **n)?;
+    if count * 2 < letters {
+        return Some("und");
+    }
+
+    Some(match script {
+        1 => "bn",
+        2 => "hi",
+        3 => "ar",
+        4 => "ru",
+        5 => "zh",
+        6 => "ja",
+        7 => "ko",
+        _ => {
+            let lower = text.to_lowercase();
+            let words: Vec<&str> = lower
+                .split(|c: char| !c.is_alphabetic())
+                .filter(|w| !w.is_empty())
+                .collect();
+            let common = words.iter().filter(|w| ENGLISH_STOPWORDS.contains(w)).count();
+            // Terse inputs like skill lists have few stopwords; only flag clear prose
+            if words.len() < 15 || common * 20 >= words.len() {
+                "en"
+            } else {
+                "und"
+            }
+        }
+    })
+}
+
+fn language_name(code: &str) -> &'static str {
+    match code {
+        "bn" => "Bengali",
+        "hi" => "Hindi",
+        "ar" => "Arabic",
+        "ru" => "Russian",
+        "zh" => "Chinese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        _ => "a language other than English",
+    }
+}
+
+/// PDF or Office file signatures, NUL bytes, or many control or
+/// replacement characters.
+fn looks_binary(text: &str) -> bool {
+    if text.starts_with("%PDF") || text.starts_with("PK\u{3}\u{4}") || text.contains('\0') {
+        return true;
+    }
+    let total = text.chars().count();
+    let suspicious = text
+        .chars()
+        .filter(|c| *c == '\u{FFFD}' || (c.is_control() && !matches!(c, '\n' | '\r' | '\t')))
+        .count();
+    suspicious * 20 > total
+}
+
+/// A long unbroken run of base64 characters.
+fn looks_encoded(text: &str) -> bool {
+    text.split_whitespace().any(|run| {
+        run.len() >= MIN_ENCODED_RUN
+            && run
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'))
+    })
+}
+
+/// Mostly letters, and not one or two characters repeated.
+fn looks_readable(text: &str) -> bool {
+    let visible: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if visible.len() < 20 {
+        return true;
+    }
+    let letters = visible.iter().filter(|c| c.is_alphabetic()).count();
+    let mut distinct = visible.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    letters * 10 >= visible.len() * 3 && distinct.len() >= 5
+}
@@ -0,0 +1,113 @@
+//! Retries of transient provider errors.
+//!
+//! Gemini and Groq sometimes answer `429 Too Many Requests`, `500`, or
+//! `503` ("model overloaded") for a request that succeeds moments later.
+//! Their generation requests (plain, structured, streamed, file, and tool
+//! rounds) go through [`send`]; a streamed answer is only retried before
+//! it starts, since these statuses come before any text.
+//! [`send`] retries those statuses with jittered exponential backoff:
+//! before retry `n` it waits a random time of up to
+//! `AI_RETRY_BASE_DELAY_MS * 2^(n-1)`, capped at `AI_RETRY_MAX_DELAY_MS`, or
+//! as long as a `Retry-After` header asks within that cap. Other statuses,
+//! request errors, and the last attempt's response are returned as they
+//! are.
+
+use std::future::Future;
+use std::time::Duration;
+
+use reqwest::{Response, StatusCode, header};
+
+/// How often and how long to retry.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts in all, including the first; 1 disables retries
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Reads `AI_RETRY_MAX_ATTEMPTS` (default 3), `AI_RETRY_BASE_DELAY_MS`
+    /// (default 500), and `AI_RETRY_MAX_DELAY_MS` (default 8000). Invalid
+    /// values keep the default.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        Self {
+            max_attempts: var("AI_RETRY_MAX_ATTEMPTS")
+                .map(|n| n.clamp(1, 10) as u32)
+                .unwrap_or(default.max_attempts),
+            base_delay: var("AI_RETRY_BASE_DELAY_MS").map(Duration::from_millis).unwrap_or(default.base_delay),
+            max_delay: var("AI_RETRY_MAX_DELAY_MS").map(Duration::from_millis).unwrap_or(default.max_delay),
+        }
+    }
+
+    /// Time to wait before retry `retry` (1 for the first), at random up to
+    /// the backoff ceiling.
+    fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        ceiling.mul_f64(rand::random::<f64>())
+    }
+}
+
+/// Whether a status is worth retrying.
+pub fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE
+    )
+}
+
+/// The wait a `Retry-After` header in seconds asks for.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Sends a request built by `request`, retrying retryable statuses under
+/// `policy`. Returns the first response or error that is not retryable, or
+/// the last attempt's response.
+pub async fn send<F, Fut>(provider: &str, policy: &RetryPolicy, request: F) -> reqwest::Result<Response>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = reqwest::Result<Response>>,
+{
+    let mut attempt = 1;
+    loop {
+        let result = request().await;
+        if attempt >= policy.max_attempts {
+            return result;
+        }
+        let wait = match &result {
+            Ok(response) if is_retryable(response.status()) => {
+                let backoff = policy.backoff(attempt);
+                let wait = retry_after(response).map_or(backoff, |after| after.min(policy.max_delay));
+                tracing::warn!(
+                    "{} returned {}; retrying in {} ms (attempt {} of {})",
+                    provider,
+                    response.status(),
+                    wait.as_millis(),
+                    attempt + 1,
+                    policy.max_attempts
+                );
+                wait
+            }
+            _ => return result,
+        };
+        tokio::time::sleep(wait).await;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,108 @@
+//! Routing between an action's current prompt and a candidate being rolled
+//! out.
+//!
+//! [`PromptRouter`] holds the live rollouts (see [`crate::prompt_rollouts`])
+//! in memory and picks the prompt version of each request. A user stays on
+//! the same version for the life of a rollout; requests without a user are
+//! routed at random.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::types::ActionType;
+use crate::errors::AppResult;
+
+/// A live rollout, as routing needs it.
+#[derive(Debug, Clone)]
+struct Route {
+    rollout_id: i32,
+    candidate_version: String,
+    /// Share of requests sent to the candidate, from 0 to 100
+    traffic_percent: u32,
+}
+
+/// Picks the prompt version of each request from the live rollouts.
+#[derive(Clone)]
+pub struct PromptRouter {
+    db_pool: PgPool,
+    /// Live rollouts by action
+    routes: Arc<RwLock<HashMap<String, Route>>>,
+}
+
+impl PromptRouter {
+    /// Create a router backed by `db_pool`, with no rollouts loaded
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            routes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Reloads the live rollouts. Called after every rollout change and on
+    /// each rollout check, so other instances pick changes up within
+    /// `PROMPT_ROLLOUT_CHECK_INTERVAL_SECS`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database operation fails; the routes loaded
+    /// before are kept.
+    pub async fn refresh(&self) -> AppResult<()> {
+        let rows = sqlx::query_as::<_, (i32, String, String, String, i32)>(
+            r#"
+            SELECT id, action, candidate_version, status::TEXT, traffic_percent
+            FROM prompt_rollouts
+            WHERE status IN ('active', 'promoted')
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let routes = rows
+            .into_iter()
+            .map(|(rollout_id, action, candidate_version, status, traffic_percent)| {
+                let route = Route {
+                    rollout_id,
+                    candidate_version,
+                    traffic_percent: if status == "promoted" { 100 } else { traffic_percent.clamp(0, 100) as u32 },
+                };
+                (action, route)
+            })
+            .collect();
+        *self.routes.write().unwrap_or_else(|e| e.into_inner()) = routes;
+        Ok(())
+    }
+
+    /// Prompt version to render for a request: the action's candidate if a
+    /// live rollout sends the request to it, otherwise the current version.
+    ///
+    /// Rollouts of a candidate this build does not ship (e.g. one that has
+    /// since become the current version) are ignored.
+    pub fn prompt_version(&self, action: &ActionType, user_id: Option<Uuid>) -> &'static str {
+        let current = action.prompt_version();
+        let Some(candidate) = action.candidate_prompt_version() else {
+            return current;
+        };
+        let routes = self.routes.read().unwrap_or_else(|e| e.into_inner());
+        let Some(route) = routes.get(action.as_str()).filter(|r| r.candidate_version == candidate) else {
+            return current;
+        };
+        if bucket(route.rollout_id, user_id) < route.traffic_percent {
+            candidate
+        } else {
+            current
+        }
+    }
+}
+
+/// A request's bucket from 0 to 99 in a rollout. Each user keeps their
+/// bucket for the rollout, and different rollouts shift the buckets, so
+/// the same users are not always the first to get a new prompt.
+fn bucket(rollout_id: i32, user_id: Option<Uuid>) -> u32 {
+    match user_id {
+        Some(user_id) => ((user_id.as_u128() % 100 + u128::from(rollout_id.unsigned_abs()) * 37) % 100) as u32,
+        None => rand::random_range(0..100),
+    }
+}
@@ -0,0 +1,147 @@
+//! Response schemas for structured actions.
+//!
+//! Skill extraction, roadmap generation, and mentor answers send the provider
+//! a JSON schema derived from the typed response struct
+//! ([`ExtractedSkills`](super::types::ExtractedSkills),
+//! [`TechStackRoadmap`](super::types::TechStackRoadmap),
+//! [`QuestionResponse`](super::types::QuestionResponse)), so the reply has the fields
+//! those structs need instead of merely being JSON. Groq takes JSON Schema in
+//! `response_format` and Ollama in `format`; Gemini takes the OpenAPI subset in `responseSchema`,
+//! produced by [`OutputSchema::gemini`].
+
+use schemars::JsonSchema;
+use serde_json::{Map, Value, json};
+
+/// Value formats Gemini accepts in `responseSchema`; others are dropped.
+const GEMINI_FORMATS: &[&str] = &["float", "double", "int32", "int64", "enum", "date-time"];
+
+/// A response schema for one action.
+#[derive(Debug, Clone)]
+pub struct OutputSchema {
+    /// Schema name, sent to providers that require one
+    pub name: &'static str,
+    /// Self-contained JSON Schema (references inlined)
+    schema: Value,
+}
+
+impl OutputSchema {
+    /// Derives the schema of `T`, inlining its `$defs`.
+    pub fn of<T: JsonSchema>(name: &'static str) -> Self {
+        let mut root = schemars::schema_for!(T).to_value();
+        let defs = root
+            .as_object_mut()
+            .and_then(|o| o.remove("$defs"))
+            .unwrap_or(Value::Null);
+        if let Some(object) = root.as_object_mut() {
+            object.remove("$schema");
+            object.remove("title");
+        }
+        Self {
+            name,
+            schema: inline_refs(root, &defs),
+        }
+    }
+
+    /// The schema as JSON Schema.
+    pub fn json_schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// The schema in Gemini's OpenAPI subset: upper-case types, `nullable`
+    /// instead of null unions, and no unsupported keywords.
+    pub fn gemini(&self) -> Value {
+        to_gemini(&self.schema)
+    }
+}
+
+/// Replaces `{"$ref": "#/$defs/Name"}` with the definition it points to.
+fn inline_refs(value: Value, defs: &Value) -> Value {
+    match value {
+        Value::Object(mut object) => {
+            if let Some(Value::String(reference)) = object.get("$ref")
+                && let Some(def) = reference
+                    .strip_prefix("#/$defs/")
+                    .and_then(|name| defs.get(name))
+            {
+                let mut inlined = inline_refs(def.clone(), defs);
+                object.remove("$ref");
+                if let Some(target) = inlined.as_object_mut() {
+                    for (key, value) in object {
+                        target.entry(key).or_insert(inline_refs(value, defs));
+                    }
+                }
+                return inlined;
+            }
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, inline_refs(value, defs)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| inline_refs(v, defs)).collect()),
+        other => other,
+    }
+}
+
+/// Converts a JSON Schema node to Gemini's schema format.
+fn to_gemini(schema: &Value) -> Value {
+    let Some(object) = schema.as_object() else {
+        return json!({});
+    };
+
+    // `Option<Struct>` derives as `anyOf: [Struct, {"type": "null"}]`
+    if let Some(variants) = object.get("anyOf").and_then(Value::as_array) {
+        let non_null: Vec<&Value> = variants
+            .iter()
+            .filter(|v| v.get("type").and_then(Value::as_str) != Some("null"))
+            .collect();
+        if let [inner] = non_null.as_slice() {
+            let mut converted = to_gemini(inner);
+            if let Some(target) = converted.as_object_mut() {
+                target.insert("nullable".to_string(), Value::Bool(non_null.len() < variants.len()));
+                if let Some(description) = object.get("description") {
+                    target.insert("description".to_string(), description.clone());
+                }
+            }
+            return converted;
+        }
+    }
+
+    let mut out = Map::new();
+    let (kind, nullable) = match object.get("type") {
+        Some(Value::String(kind)) => (Some(kind.as_str()), false),
+        Some(Value::Array(kinds)) => (
+            kinds.iter().filter_map(Value::as_str).find(|k| *k != "null"),
+            kinds.iter().any(|k| k == "null"),
+        ),
+        _ => (None, false),
+    };
+    if let Some(kind) = kind {
+        out.insert("type".to_string(), Value::String(kind.to_uppercase()));
+    }
+    if nullable {
+        out.insert("nullable".to_string(), Value::Bool(true));
+    }
+    for key in ["description", "enum", "required"] {
+        if let Some(value) = object.get(key) {
+            out.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(format) = object.get("format").and_then(Value::as_str)
+        && GEMINI_FORMATS.contains(&format)
+    {
+        out.insert("format".to_string(), Value::String(format.to_string()));
+    }
+    if let Some(properties) = object.get("properties").and_then(Value::as_object) {
+        let converted: Map<String, Value> = properties
+            .iter()
+            .map(|(name, property)| (name.clone(), to_gemini(property)))
+            .collect();
+        out.insert("properties".to_string(), Value::Object(converted));
+    }
+    if let Some(items) = object.get("items") {
+        out.insert("items".to_string(), to_gemini(items));
+    }
+    Value::Object(out)
+}
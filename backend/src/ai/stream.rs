@@ -0,0 +1,113 @@
+//! Streaming answers.
+//!
+//! Gemini and Groq stream completions as Server-Sent Events; [`SseData`]
+//! reads the `data:` payloads of such a response. An [`AnswerStream`]
+//! forwards the text of an answer, as it is generated, to whoever asked,
+//! and an [`AnswerPart`] reports whether the answer was cut off.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::mpsc;
+
+use crate::errors::AppError;
+
+/// Receives the text of a streamed answer as it is generated.
+pub struct AnswerStream {
+    sender: mpsc::UnboundedSender<String>,
+    started: AtomicBool,
+}
+
+impl AnswerStream {
+    pub fn new(sender: mpsc::UnboundedSender<String>) -> Self {
+        Self {
+            sender,
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// A stream nobody receives, for answers that are only wanted whole.
+    pub(crate) fn discard() -> Self {
+        Self::new(mpsc::unbounded_channel().0)
+    }
+
+    /// Forwards the next piece of the answer. A receiver that went away
+    /// (the client disconnected) is ignored; the answer is still completed.
+    pub(crate) fn send(&self, delta: &str) {
+        if delta.is_empty() {
+            return;
+        }
+        self.started.store(true, Ordering::Relaxed);
+        let _ = self.sender.send(delta.to_string());
+    }
+
+    /// Whether any text was forwarded, after which a failed answer cannot be
+    /// retried with another provider.
+    pub(crate) fn started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+}
+
+/// A plain-text answer, or one section of it.
+#[derive(Debug)]
+pub struct AnswerPart {
+    pub text: String,
+    /// The provider stopped at the output token limit, mid-answer
+    pub truncated: bool,
+}
+
+/// Reads the `data:` payloads of a Server-Sent Events response, one event
+/// at a time. Other fields and comments are skipped.
+pub(crate) struct SseData {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    finished: bool,
+    provider: &'static str,
+}
+
+impl SseData {
+    pub(crate) fn new(response: reqwest::Response, provider: &'static str) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+            finished: false,
+            provider,
+        }
+    }
+
+    /// The next event's data, or `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ExternalServiceError` if the connection fails.
+    pub(crate) async fn next(&mut self) -> Result<Option<String>, AppError> {
+        loop {
+            // Lines are split on bytes so characters split across chunks stay whole
+            if let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                if let Some(data) = line.trim_end_matches(['\r', '\n']).strip_prefix("data:") {
+                    return Ok(Some(data.trim_start().to_string()));
+                }
+                continue;
+            }
+            if self.finished {
+                return Ok(None);
+            }
+
+            let chunk = self.response.chunk().await.map_err(|e| {
+                tracing::error!("{} stream failed: {}", self.provider, e);
+                AppError::ExternalServiceError(format!("{} API stream error: {}", self.provider, e))
+            })?;
+            match chunk {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => {
+                    // The last line may lack its newline
+                    self.finished = true;
+                    if !self.buffer.is_empty() {
+                        self.buffer.push(b'\n');
+                    }
+                }
+            }
+        }
+    }
+}
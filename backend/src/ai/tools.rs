@@ -0,0 +1,72 @@
+//! Function calling: lets the model call internal tools mid-answer.
+//!
+//! The AI layer only knows tool definitions and a [`ToolExecutor`]; the
+//! application supplies the executor (see [`crate::mentor_tools`]), which
+//! decides which tools exist, runs them for the requesting user, and logs
+//! each call. Providers translate [`ToolRound`]s into their own
+//! function-calling wire format.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::errors::AppError;
+
+/// Most rounds of tool calls per answer; the round after that offers no
+/// tools, so the model has to answer.
+pub const MAX_TOOL_ROUNDS: usize = 4;
+
+/// A tool the model may call.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the arguments object
+    pub parameters: Value,
+}
+
+/// A tool call requested by the model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCall {
+    /// Provider-assigned call ID, echoed back with the result
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// One round of tool calls and their outputs, in call order.
+#[derive(Debug, Clone)]
+pub struct ToolRound {
+    pub calls: Vec<ToolCall>,
+    pub outputs: Vec<Value>,
+}
+
+/// What the model did with its turn.
+#[derive(Debug)]
+pub enum ModelTurn {
+    /// A final plain-text answer
+    Answer(String),
+    /// Tools to run before answering
+    Calls(Vec<ToolCall>),
+}
+
+/// A tool call as reported with the answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolInvocation {
+    pub name: String,
+    pub arguments: Value,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub latency_ms: i32,
+}
+
+/// Runs tools on behalf of one request.
+#[async_trait::async_trait]
+pub trait ToolExecutor: Send + Sync {
+    /// Tools offered to the model.
+    fn definitions(&self) -> Vec<ToolDefinition>;
+
+    /// Runs a call and returns its output. Errors are reported back to the
+    /// model rather than failing the answer.
+    async fn execute(&self, call: &ToolCall) -> Result<Value, AppError>;
+}
@@ -1,15 +1,72 @@
 //! AI service types and common structures.
 
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::tools::ToolInvocation;
+use crate::errors::AppError;
+use crate::glossary::TermLink;
 
 /// AI provider to use for processing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AIProvider {
     /// Google Gemini API
     Gemini,
     /// Groq API
     Groq,
+    /// Self-hosted models served by Ollama
+    Ollama,
+    /// The first configured provider in the fallback order that succeeds;
+    /// never reported as the provider that served a response
+    Auto,
+}
+
+impl AIProvider {
+    /// Default fallback order of `auto` requests.
+    pub const FALLBACK_ORDER: [AIProvider; 3] = [AIProvider::Gemini, AIProvider::Groq, AIProvider::Ollama];
+
+    /// Name stored with generated records, e.g. `career_roadmaps.ai_provider`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AIProvider::Gemini => "gemini",
+            AIProvider::Groq => "groq",
+            AIProvider::Ollama => "ollama",
+            AIProvider::Auto => "auto",
+        }
+    }
+
+    /// Provider named by a request or stored record; Gemini unless the name
+    /// is another provider's.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "groq" => AIProvider::Groq,
+            "ollama" => AIProvider::Ollama,
+            "auto" => AIProvider::Auto,
+            _ => AIProvider::Gemini,
+        }
+    }
+
+    /// Parses a fallback order such as `groq,gemini`. Unknown names and
+    /// `auto` are skipped, as are repeats.
+    pub fn parse_order(order: &str) -> Vec<AIProvider> {
+        let mut providers = Vec::new();
+        for name in order.split(',').map(|n| n.trim().to_lowercase()) {
+            let provider = match name.as_str() {
+                "gemini" => AIProvider::Gemini,
+                "groq" => AIProvider::Groq,
+                "ollama" => AIProvider::Ollama,
+                _ => continue,
+            };
+            if !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        providers
+    }
 }
 
 /// Type of AI action to perform
@@ -24,26 +81,420 @@ pub enum ActionType {
     AskQuestion,
     /// Generate career-related content (e.g., cover letters, resume improvements)
     GenerateContent,
+    /// Screen a job posting for scam and fraud indicators
+    ScreenJobPosting,
+    /// Critique a generated roadmap against a quality rubric, optionally fixing it
+    ReviewRoadmap,
+    /// Suggest a working replacement for a broken resource link
+    SuggestResourceReplacement,
+    /// Summarize the advice given in a mentor conversation
+    SummarizeConversation,
+    /// Compress older mentor conversation turns into a running summary
+    CompressConversation,
+    /// Extract the requirements and keywords of a job description
+    AnalyzeJobDescription,
+    /// Generate likely interview questions for a job and candidate
+    GenerateInterviewQuestions,
+    /// Reply as the recruiter in a salary negotiation practice session
+    NegotiationReply,
+    /// Debrief a salary negotiation practice session with phrasing suggestions
+    NegotiationDebrief,
+    /// Find STAR stories in a candidate's projects and work history
+    MineStarStories,
+    /// Revise a STAR story with the candidate's answers and feedback
+    RefineStarStory,
 }
 
+impl ActionType {
+    /// Temperature used when the request does not set one; lower for
+    /// extraction and screening, higher for open-ended writing.
+    pub fn default_temperature(&self) -> f32 {
+        match self {
+            ActionType::ExtractSkills => 0.3,
+            ActionType::GenerateRoadmap => 0.7,
+            ActionType::AskQuestion => 0.8,
+            ActionType::GenerateContent => 0.8,
+            ActionType::ScreenJobPosting => 0.2,
+            ActionType::ReviewRoadmap => 0.2,
+            ActionType::SuggestResourceReplacement => 0.2,
+            ActionType::SummarizeConversation => 0.3,
+            ActionType::CompressConversation => 0.2,
+            ActionType::AnalyzeJobDescription => 0.2,
+            ActionType::GenerateInterviewQuestions => 0.6,
+            ActionType::NegotiationReply => 0.7,
+            ActionType::NegotiationDebrief => 0.3,
+            ActionType::MineStarStories => 0.5,
+            ActionType::RefineStarStory => 0.4,
+        }
+    }
+
+    /// Version of the action's prompt template, stored with generated
+    /// output; bump it whenever the prompt text changes.
+    pub fn prompt_version(&self) -> &'static str {
+        match self {
+            ActionType::ExtractSkills => "extract_skills/v2",
+            ActionType::GenerateRoadmap => "generate_roadmap/v2",
+            ActionType::AskQuestion => "ask_question/v4",
+            ActionType::GenerateContent => "generate_content/v2",
+            ActionType::ScreenJobPosting => "screen_job_posting/v2",
+            ActionType::ReviewRoadmap => "review_roadmap/v1",
+            ActionType::SuggestResourceReplacement => "suggest_resource_replacement/v1",
+            ActionType::SummarizeConversation => "summarize_conversation/v1",
+            ActionType::CompressConversation => "compress_conversation/v1",
+            ActionType::AnalyzeJobDescription => "analyze_job_description/v1",
+            ActionType::GenerateInterviewQuestions => "generate_interview_questions/v2",
+            ActionType::NegotiationReply => "negotiation_reply/v1",
+            ActionType::NegotiationDebrief => "negotiation_debrief/v1",
+            ActionType::MineStarStories => "mine_star_stories/v1",
+            ActionType::RefineStarStory => "refine_star_story/v1",
+        }
+    }
+
+    /// Next version of the action's prompt, shipped next to the current one
+    /// and served only to the traffic a prompt rollout sends to it (see
+    /// [`crate::prompt_rollouts`]); `None` when no new prompt is pending.
+    /// Once it is promoted, it becomes the action's `prompt_version`.
+    pub fn candidate_prompt_version(&self) -> Option<&'static str> {
+        match self {
+            ActionType::ExtractSkills => Some(EXTRACT_SKILLS_V3),
+            _ => None,
+        }
+    }
+
+    /// Stable snake_case name, matching the serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionType::ExtractSkills => "extract_skills",
+            ActionType::GenerateRoadmap => "generate_roadmap",
+            ActionType::AskQuestion => "ask_question",
+            ActionType::GenerateContent => "generate_content",
+            ActionType::ScreenJobPosting => "screen_job_posting",
+            ActionType::ReviewRoadmap => "review_roadmap",
+            ActionType::SuggestResourceReplacement => "suggest_resource_replacement",
+            ActionType::SummarizeConversation => "summarize_conversation",
+            ActionType::CompressConversation => "compress_conversation",
+            ActionType::AnalyzeJobDescription => "analyze_job_description",
+            ActionType::GenerateInterviewQuestions => "generate_interview_questions",
+            ActionType::NegotiationReply => "negotiation_reply",
+            ActionType::NegotiationDebrief => "negotiation_debrief",
+            ActionType::MineStarStories => "mine_star_stories",
+            ActionType::RefineStarStory => "refine_star_story",
+        }
+    }
+}
+
+/// Skill extraction prompt that only lists skills the CV shows in use,
+/// under their canonical names.
+pub const EXTRACT_SKILLS_V3: &str = "extract_skills/v3";
+
+/// Guidelines [`EXTRACT_SKILLS_V3`] adds to the skill extraction prompt.
+pub const EXTRACT_SKILLS_V3_GUIDELINES: &str = "
+- Only list a technical skill when the CV shows it in use, in a role, project, or course; skip bare keyword lists
+- Use each skill's canonical name (\"JavaScript\", not \"JS\" or \"javascript\") and list it once";
+
 /// Request structure for AI actions
 #[derive(Debug, Deserialize)]
 pub struct AIActionRequest {
     /// Type of action to perform
     pub action: ActionType,
-    /// AI provider to use (defaults to Gemini if not specified)
+    /// AI provider to use (defaults to Gemini if not specified); `auto`
+    /// falls back through the configured providers
     #[serde(default = "default_provider")]
     pub provider: AIProvider,
     /// Input text/context for the action
     pub input: String,
     /// Optional additional parameters as JSON
     pub parameters: Option<serde_json::Value>,
+    /// User the request is made for; set by handlers, never read from the body
+    #[serde(skip)]
+    pub user_id: Option<uuid::Uuid>,
+    /// Whether the request may be kept in `debug_captures` for a user who
+    /// consented; `false` for requests carrying data that must stay
+    /// encrypted at rest, such as offer document excerpts
+    #[serde(skip, default = "default_true")]
+    pub capture: bool,
 }
 
 fn default_provider() -> AIProvider {
     AIProvider::Gemini
 }
 
+/// Lowest and highest temperature a request may set.
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+
+/// Lowest and highest output token limit a request may set; below the
+/// minimum, structured responses get cut off mid-JSON.
+pub const MAX_OUTPUT_TOKENS_RANGE: std::ops::RangeInclusive<u32> = 256..=8192;
+
+/// Most sections a plain-text answer is continued to while the provider
+/// keeps stopping at the output token limit.
+pub const MAX_ANSWER_SECTIONS: usize = 4;
+
+/// Question sent in place of the user's to continue an answer the provider
+/// stopped at its output token limit.
+pub const CONTINUE_INSTRUCTION: &str = "continue — your previous answer was cut off. \
+Continue it exactly where it stopped, mid-sentence if need be, without repeating anything or starting over.";
+
+/// One section of an answer continued past the output token limit, in
+/// characters of the stitched answer.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AnswerSection {
+    pub start: usize,
+    pub end: usize,
+    /// The provider stopped at the output token limit at the end of it
+    pub truncated: bool,
+}
+
+/// Seed sent to providers in deterministic mode.
+pub const DETERMINISTIC_SEED: u32 = 42;
+
+/// Length of the embedding vectors stored in `document_chunks`.
+pub const EMBEDDING_DIMENSIONS: usize = 768;
+
+/// How plainly generated text is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingLevel {
+    /// Short sentences and everyday words, for beginners
+    Simple,
+    Intermediate,
+    /// Industry terms used without explanation
+    Professional,
+}
+
+impl ReadingLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Simple => "simple",
+            Self::Intermediate => "intermediate",
+            Self::Professional => "professional",
+        }
+    }
+}
+
+/// Voice of generated text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tone {
+    /// Warm and encouraging
+    Friendly,
+    Neutral,
+    /// Polished, as for a CV or an employer
+    Professional,
+}
+
+/// Sampling settings for one provider call.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GenerationSettings {
+    /// Sampling temperature
+    pub temperature: f32,
+    /// Output token limit; `None` uses the provider's default
+    pub max_output_tokens: Option<u32>,
+    /// Sampling seed, set in deterministic mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    /// Version of the prompt template to render: the action's current
+    /// version, unless a prompt rollout sends the request to its candidate
+    #[serde(skip)]
+    pub prompt_version: &'static str,
+    /// Reading level of the text written; `None` leaves it to the prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reading_level: Option<ReadingLevel>,
+    /// Tone of the text written; `None` leaves it to the prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tone: Option<Tone>,
+}
+
+impl GenerationSettings {
+    /// Reads `temperature`, `max_output_tokens`, `deterministic`,
+    /// `reading_level`, and `tone` from the request's parameters, falling
+    /// back to the action's defaults.
+    ///
+    /// `deterministic: true` sets temperature 0 and [`DETERMINISTIC_SEED`],
+    /// so providers that honor seeds return the same output for the same
+    /// model, prompt version, and input.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ValidationError` if a value has the wrong type, is
+    /// outside [`TEMPERATURE_RANGE`] or [`MAX_OUTPUT_TOKENS_RANGE`], is not
+    /// a [`ReadingLevel`] or [`Tone`], or a non-zero temperature is combined
+    /// with `deterministic`.
+    pub fn from_request(request: &AIActionRequest) -> Result<Self, AppError> {
+        let parameter = |name: &str| {
+            request
+                .parameters
+                .as_ref()
+                .and_then(|p| p.get(name))
+                .filter(|v| !v.is_null())
+        };
+
+        let temperature = parameter("temperature")
+            .map(|value| {
+                value
+                    .as_f64()
+                    .map(|t| t as f32)
+                    .filter(|t| TEMPERATURE_RANGE.contains(t))
+                    .ok_or_else(|| {
+                        AppError::ValidationError(format!(
+                            "temperature must be a number from {} to {}",
+                            TEMPERATURE_RANGE.start(),
+                            TEMPERATURE_RANGE.end()
+                        ))
+                    })
+            })
+            .transpose()?
+            .unwrap_or_else(|| request.action.default_temperature());
+
+        let max_output_tokens = parameter("max_output_tokens")
+            .map(|value| {
+                value
+                    .as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .filter(|n| MAX_OUTPUT_TOKENS_RANGE.contains(n))
+                    .ok_or_else(|| {
+                        AppError::ValidationError(format!(
+                            "max_output_tokens must be a whole number from {} to {}",
+                            MAX_OUTPUT_TOKENS_RANGE.start(),
+                            MAX_OUTPUT_TOKENS_RANGE.end()
+                        ))
+                    })
+            })
+            .transpose()?;
+
+        let deterministic = parameter("deterministic")
+            .map(|value| {
+                value.as_bool().ok_or_else(|| {
+                    AppError::ValidationError("deterministic must be true or false".to_string())
+                })
+            })
+            .transpose()?
+            .unwrap_or(false);
+
+        let reading_level = parameter("reading_level")
+            .map(|value| {
+                serde_json::from_value::<ReadingLevel>(value.clone()).map_err(|_| {
+                    AppError::ValidationError(
+                        "reading_level must be simple, intermediate, or professional".to_string(),
+                    )
+                })
+            })
+            .transpose()?;
+        let tone = parameter("tone")
+            .map(|value| {
+                serde_json::from_value::<Tone>(value.clone()).map_err(|_| {
+                    AppError::ValidationError("tone must be friendly, neutral, or professional".to_string())
+                })
+            })
+            .transpose()?;
+
+        if !deterministic {
+            return Ok(Self {
+                temperature,
+                max_output_tokens,
+                seed: None,
+                prompt_version: request.action.prompt_version(),
+                reading_level,
+                tone,
+            });
+        }
+        if parameter("temperature").is_some() && temperature != 0.0 {
+            return Err(AppError::ValidationError(
+                "temperature cannot be combined with deterministic; deterministic mode uses 0".to_string(),
+            ));
+        }
+        Ok(Self {
+            temperature: 0.0,
+            max_output_tokens,
+            seed: Some(DETERMINISTIC_SEED),
+            prompt_version: request.action.prompt_version(),
+            reading_level,
+            tone,
+        })
+    }
+}
+
+/// Who a chat message is from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    /// Instructions for the model
+    System,
+    /// The person asking
+    User,
+    /// The model's earlier replies
+    Assistant,
+}
+
+impl ChatRole {
+    /// Role name in the OpenAI chat format.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatRole::System => "system",
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+        }
+    }
+}
+
+/// One message of a multi-message prompt or conversation history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::System, content: content.into() }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::User, content: content.into() }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: ChatRole::Assistant, content: content.into() }
+    }
+}
+
+/// Checks that `messages` form a chat a provider accepts: system messages
+/// only at the start, then user and assistant turns alternating from the
+/// user, ending with the user's message, and no empty message.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` describing the first problem found.
+pub fn validate_messages(messages: &[ChatMessage]) -> Result<(), AppError> {
+    let turns_start = messages
+        .iter()
+        .position(|m| m.role != ChatRole::System)
+        .ok_or_else(|| AppError::ValidationError("messages must include a user message".to_string()))?;
+
+    for (index, message) in messages.iter().enumerate() {
+        if message.content.trim().is_empty() {
+            return Err(AppError::ValidationError(format!("message {} is empty", index)));
+        }
+        if index < turns_start {
+            continue;
+        }
+        let expected = if (index - turns_start).is_multiple_of(2) { ChatRole::User } else { ChatRole::Assistant };
+        if message.role != expected {
+            return Err(AppError::ValidationError(format!(
+                "message {} is from {} but must be from {}; system messages come first, then user and assistant turns alternate",
+                index,
+                message.role.as_str(),
+                expected.as_str()
+            )));
+        }
+    }
+
+    if messages.last().map(|m| m.role) != Some(ChatRole::User) {
+        return Err(AppError::ValidationError("the last message must be from the user".to_string()));
+    }
+    Ok(())
+}
+
 /// Response structure for AI actions
 #[derive(Debug, Serialize)]
 pub struct AIActionResponse {
@@ -51,14 +502,34 @@ pub struct AIActionResponse {
     pub success: bool,
     /// The processed result
     pub data: serde_json::Value,
-    /// Provider that was used
+    /// Provider that served the request; for `auto` requests, the one that
+    /// succeeded (or failed last)
     pub provider: AIProvider,
+    /// Providers an `auto` request tried and that failed before `provider`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<AIProvider>,
+    /// Model that produced the result
+    pub model: &'static str,
+    /// Version of the prompt template used
+    pub prompt_version: &'static str,
+    /// Sampling settings the call ran with
+    pub settings: GenerationSettings,
     /// Optional message or explanation
     pub message: Option<String>,
+    /// Input preflight warnings, e.g. text in a language the prompts are not tuned for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Tools the model called before answering
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolInvocation>,
+    /// Debug capture of the request, for users who consented to captures;
+    /// pass it to `POST /api/ai/captures/:id/flag` to report a bad result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_id: Option<i64>,
 }
 
 /// Extracted skills from CV analysis
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ExtractedSkills {
     /// Technical skills (programming languages, frameworks, tools)
     pub technical_skills: Vec<SkillItem>,
@@ -79,7 +550,7 @@ pub struct ExtractedSkills {
 }
 
 /// A skill item with proficiency level
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SkillItem {
     /// Name of the skill
     pub name: String,
@@ -90,7 +561,7 @@ pub struct SkillItem {
 }
 
 /// Roadmap for learning a tech stack
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TechStackRoadmap {
     /// Tech stack name
     pub stack_name: String,
@@ -109,7 +580,7 @@ pub struct TechStackRoadmap {
 }
 
 /// A phase in the learning roadmap
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LearningPhase {
     /// Phase number
     pub phase: u32,
@@ -130,7 +601,7 @@ pub struct LearningPhase {
 }
 
 /// Project suggestion for building during learning
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSuggestion {
     /// Project title
     pub title: String,
@@ -146,8 +617,31 @@ pub struct ProjectSuggestion {
     pub recommended_phase: u32,
 }
 
-/// Generic question-answer response
+/// A problem found by the roadmap self-check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoadmapIssue {
+    /// Rubric criterion: `coverage`, `ordering`, or `duration`
+    pub criterion: String,
+    /// What is wrong, written for the learner
+    pub message: String,
+    /// Whether the revised roadmap fixes it
+    #[serde(default)]
+    pub fixed: bool,
+}
+
+/// Result of the roadmap self-check
 #[derive(Debug, Serialize, Deserialize)]
+pub struct RoadmapReview {
+    /// Problems found in the roadmap
+    #[serde(default)]
+    pub issues: Vec<RoadmapIssue>,
+    /// Corrected roadmap, when fixes were requested and needed
+    #[serde(default)]
+    pub revised_roadmap: Option<serde_json::Value>,
+}
+
+/// Generic question-answer response
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct QuestionResponse {
     /// The original question
     pub question: String,
@@ -155,6 +649,9 @@ pub struct QuestionResponse {
     pub answer: String,
     /// Related suggestions or follow-up topics
     pub related_topics: Option<Vec<String>>,
+    /// Caveat for career advice, absent for simple questions
+    #[serde(default)]
+    pub disclaimer: Option<String>,
 }
 
 /// Generated content response
@@ -167,3 +664,164 @@ pub struct GeneratedContent {
     /// Any additional metadata
     pub metadata: Option<serde_json::Value>,
 }
+
+/// What was read from a photo or screenshot of a CV or certificate
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageReading {
+    /// `cv`, `certificate`, or `other`
+    pub document_type: String,
+    /// All of the text in the image
+    pub text: String,
+    /// Certificates shown in the image
+    #[serde(default)]
+    pub certificates: Vec<ExtractedCertificate>,
+}
+
+/// Certificate metadata read from an image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedCertificate {
+    pub name: String,
+    pub issuer: Option<String>,
+    /// `YYYY-MM-DD`
+    pub issue_date: Option<String>,
+    /// `YYYY-MM-DD`
+    pub expiry_date: Option<String>,
+    pub credential_id: Option<String>,
+    pub credential_url: Option<String>,
+}
+
+/// Request to extract skills from a CV (`POST /api/ai/extract-skills`).
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExtractSkillsRequest {
+    #[validate(length(min = 1, max = 100000, message = "cv_text must be between 1 and 100000 characters"))]
+    pub cv_text: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<AIProvider>,
+    /// Add the extracted skills and roles to the user's profile
+    #[serde(default)]
+    pub update_profile: bool,
+}
+
+/// Skills extracted from a CV.
+#[derive(Debug, Serialize)]
+pub struct ExtractSkillsResult {
+    /// The provider's [`ExtractedSkills`]
+    pub extracted_data: serde_json::Value,
+    pub profile_updated: bool,
+    /// Snapshot to revert the profile update to
+    pub snapshot_id: Option<i32>,
+}
+
+/// Whether a generated roadmap is reviewed by a second AI pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfCheck {
+    #[default]
+    Off,
+    /// Report the issues found
+    Warn,
+    /// Replace the roadmap with a corrected one
+    Fix,
+}
+
+fn default_timeframe_months() -> u32 {
+    6
+}
+
+fn default_learning_hours_per_week() -> u32 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Request to generate a learning roadmap (`POST /api/ai/roadmap`).
+#[derive(Debug, Deserialize, Validate)]
+pub struct GenerateRoadmapRequest {
+    /// Also accepted as `tech_stack`
+    #[serde(alias = "tech_stack")]
+    #[validate(length(min = 1, max = 200, message = "target_role must be between 1 and 200 characters"))]
+    pub target_role: String,
+    #[serde(default = "default_timeframe_months")]
+    #[validate(range(min = 1, max = 36, message = "timeframe_months must be between 1 and 36"))]
+    pub timeframe_months: u32,
+    #[serde(default = "default_learning_hours_per_week")]
+    #[validate(range(min = 1, max = 80, message = "learning_hours_per_week must be between 1 and 80"))]
+    pub learning_hours_per_week: u32,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<AIProvider>,
+    /// Plan around the user's profile skills and skills learned in other
+    /// roadmaps
+    #[serde(default = "default_true")]
+    pub include_current_skills: bool,
+    /// Generate at temperature 0 with a fixed seed
+    pub deterministic: Option<bool>,
+    #[serde(default)]
+    pub self_check: SelfCheck,
+    pub reading_level: Option<ReadingLevel>,
+    pub tone: Option<Tone>,
+}
+
+/// Plan a roadmap was generated for.
+#[derive(Debug, Serialize)]
+pub struct RoadmapMetadata {
+    pub timeframe_months: u32,
+    pub learning_hours_per_week: u32,
+    pub job_application_timing: String,
+}
+
+/// A generated and saved roadmap.
+#[derive(Debug, Serialize)]
+pub struct GeneratedRoadmap {
+    /// The provider's [`TechStackRoadmap`]
+    pub roadmap: serde_json::Value,
+    pub roadmap_id: i32,
+    /// Issues found by the self-check; `None` if it did not run
+    pub quality_warnings: Option<Vec<RoadmapIssue>>,
+    /// Glossary terms in each phase topic, by topic
+    pub glossary: HashMap<String, Vec<TermLink>>,
+    pub metadata: RoadmapMetadata,
+}
+
+/// Request to ask the career mentor (`POST /api/ai/ask-mentor`).
+#[derive(Debug, Deserialize, Validate)]
+pub struct AskMentorRequest {
+    #[validate(length(min = 1, max = 4000, message = "question must be between 1 and 4000 characters"))]
+    pub question: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<AIProvider>,
+    /// Saved conversation to continue
+    pub conversation_id: Option<i32>,
+    /// Start a new saved conversation with this exchange
+    #[serde(default)]
+    pub save_conversation: bool,
+    /// Ground the answer in the user's documents (default: true)
+    #[serde(default = "default_true")]
+    pub use_documents: bool,
+    /// Let the mentor look up the user's data with tools
+    #[serde(default)]
+    pub use_tools: bool,
+    /// Answer from the semantic cache when possible (default: true)
+    #[serde(default = "default_true")]
+    pub use_cache: bool,
+    pub reading_level: Option<ReadingLevel>,
+    pub tone: Option<Tone>,
+}
+
+/// A career mentor answer.
+#[derive(Debug, Serialize)]
+pub struct MentorAnswer {
+    /// The provider's [`QuestionResponse`], with `sections` if the answer
+    /// was continued
+    pub answer: serde_json::Value,
+    /// Set when the exchange was saved to a conversation
+    pub conversation_id: Option<i32>,
+    /// Excerpts the answer cites, as `retrieval::SourceAttribution`
+    pub sources: serde_json::Value,
+    pub tool_calls: Vec<ToolInvocation>,
+    /// Glossary terms in the answer
+    pub glossary: Vec<TermLink>,
+    /// Answered from the semantic cache
+    pub cached: bool,
+}
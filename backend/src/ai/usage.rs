@@ -0,0 +1,119 @@
+//! Per-call AI usage records.
+//!
+//! Each provider call is written to `ai_usage_logs` with the model, prompt
+//! version, and sampling settings it ran with, so a response can be
+//! reproduced later.
+//!
+//! Calls are also recorded with the prompt and completion tokens the
+//! provider reported. An action may take several requests to the provider
+//! (tool rounds, continued answers), so the clients add each request's
+//! usage to a meter scoped to the call ([`metered`], [`count_tokens`]) and
+//! the total is logged. Providers that report no usage (Ollama, the mock)
+//! are logged without token counts.
+
+use std::cell::Cell;
+use std::future::Future;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::types::GenerationSettings;
+
+tokio::task_local! {
+    static METER: Cell<Option<TokenCount>>;
+}
+
+/// Tokens a provider reported for a call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenCount {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+/// Runs `call`, returning its output with the tokens the provider reported
+/// for the requests made during it, if it reported any.
+pub(crate) async fn metered<F: Future>(call: F) -> (F::Output, Option<TokenCount>) {
+    METER
+        .scope(Cell::new(None), async {
+            let output = call.await;
+            (output, METER.with(Cell::get))
+        })
+        .await
+}
+
+/// Adds one provider request's usage to the current call. Requests made
+/// outside [`metered`] (e.g. embeddings or key checks) are not counted.
+pub(crate) fn count_tokens(prompt_tokens: u32, completion_tokens: u32) {
+    let _ = METER.try_with(|meter| {
+        let total = meter.get().unwrap_or_default();
+        meter.set(Some(TokenCount {
+            prompt_tokens: total.prompt_tokens.saturating_add(prompt_tokens),
+            completion_tokens: total.completion_tokens.saturating_add(completion_tokens),
+        }));
+    });
+}
+
+/// One provider call.
+#[derive(Debug)]
+pub struct UsageRecord<'a> {
+    pub user_id: Option<Uuid>,
+    pub action: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub prompt_version: &'a str,
+    pub settings: GenerationSettings,
+    pub latency_ms: i32,
+    pub success: bool,
+    /// The provider answered, but with output that could not be used
+    pub parse_failed: bool,
+    /// `None` when the provider reported no usage
+    pub tokens: Option<TokenCount>,
+}
+
+/// Writes AI usage records to the database.
+#[derive(Clone)]
+pub struct UsageLog {
+    db_pool: PgPool,
+}
+
+impl UsageLog {
+    /// Create a usage log backed by `db_pool`
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Records one provider call.
+    ///
+    /// Errors are logged rather than returned, so a logging failure never
+    /// fails the user's request.
+    pub async fn record(&self, record: UsageRecord<'_>) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO ai_usage_logs
+                (user_id, action, provider, model, latency_ms, success,
+                 temperature, max_output_tokens, seed, prompt_version, parse_failed,
+                 prompt_tokens, completion_tokens)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(record.user_id)
+        .bind(record.action)
+        .bind(record.provider)
+        .bind(record.model)
+        .bind(record.latency_ms)
+        .bind(record.success)
+        .bind(record.settings.temperature)
+        .bind(record.settings.max_output_tokens.map(|n| n as i32))
+        .bind(record.settings.seed.map(|n| n as i32))
+        .bind(record.prompt_version)
+        .bind(record.parse_failed)
+        .bind(record.tokens.map(|t| t.prompt_tokens.min(i32::MAX as u32) as i32))
+        .bind(record.tokens.map(|t| t.completion_tokens.min(i32::MAX as u32) as i32))
+        .execute(&self.db_pool)
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to record AI usage for {}: {}", record.action, e);
+        }
+    }
+}
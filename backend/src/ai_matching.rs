@@ -2,6 +2,9 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 use std::env;
 
+use crate::ai::prompt::{self, UNTRUSTED_INPUT_RULES};
+use crate::skills::SkillWeights;
+
 /// Enhanced match analysis with AI-generated explanations
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EnhancedMatchAnalysis {
@@ -22,16 +25,20 @@ pub struct EnhancedMatchAnalysis {
 }
 
 /// Calculate enhanced match score using heuristic algorithm
+///
+/// Each matched skill counts by its weight in `skill_weights`, so rusty or
+/// beginner-level skills count for less than fresh, practiced ones.
 pub fn calculate_enhanced_match(
     user_skills: &[String],
     job_skills: &[String],
+    skill_weights: &SkillWeights,
     user_experience: Option<&str>,
     job_experience: &str,
     user_track: Option<&str>,
     job_title: &str,
 ) -> EnhancedMatchAnalysis {
     // 1. Skill Overlap (60% weight) - Most important factor
-    let skill_overlap = calculate_skill_overlap(user_skills, job_skills);
+    let skill_overlap = calculate_skill_overlap(user_skills, job_skills, skill_weights);
     
     // 2. Experience Alignment (20% weight)
     let experience_alignment = calculate_experience_alignment(user_experience, job_experience);
@@ -67,8 +74,8 @@ pub fn calculate_enhanced_match(
     }
 }
 
-/// Calculate skill overlap score (0-100)
-fn calculate_skill_overlap(user_skills: &[String], job_skills: &[String]) -> f64 {
+/// Calculate skill overlap score (0-100), weighting each matched skill by proficiency
+fn calculate_skill_overlap(user_skills: &[String], job_skills: &[String], skill_weights: &SkillWeights) -> f64 {
     if job_skills.is_empty() {
         return 100.0; // No skills required = perfect match
     }
@@ -78,8 +85,11 @@ fn calculate_skill_overlap(user_skills: &[String], job_skills: &[String]) -> f64
     let job_skills_set: std::collections::HashSet<_> = 
         job_skills.iter().map(|s| s.to_lowercase()).collect();
     
-    let matched = user_skills_set.intersection(&job_skills_set).count();
-    let overlap = (matched as f64 / job_skills.len() as f64) * 100.0;
+    let matched: f64 = user_skills_set
+        .intersection(&job_skills_set)
+        .map(|skill| skill_weights.weight(skill))
+        .sum();
+    let overlap = (matched / job_skills.len() as f64) * 100.0;
     
     // Bonus for having more skills than required
     let bonus = if user_skills.len() > job_skills.len() {
@@ -264,29 +274,39 @@ pub async fn generate_ai_explanation_hf(
 Analyze the job match between a candidate and a job posting.
 
 Candidate Profile:
-- Skills: {}
-- Experience Level: {}
-- Preferred Track: {}
+{}
 
 Job Requirements:
-- Title: {}
-- Required Skills: {}
-- Experience Level: {}
-- Description: {}
+{}
 
 Match Score: {:.1}%
 
 Provide a concise, professional explanation (2-3 sentences) explaining why this is a good match or what's missing. 
 Focus on specific skills, experience alignment, and career track fit.
-Format: Start with overall assessment, then mention key strengths, then areas for improvement. [/INST]"#,
-        user_skills.join(", "),
-        user_experience.unwrap_or("Not specified"),
-        user_track.unwrap_or("Not specified"),
-        job_title,
-        job_skills.join(", "),
-        job_experience,
-        job_description.chars().take(200).collect::<String>(), // Truncate description
-        match_score
+Format: Start with overall assessment, then mention key strengths, then areas for improvement.
+
+{} [/INST]"#,
+        prompt::fence(
+            "candidate",
+            &format!(
+                "- Skills: {}\n- Experience Level: {}\n- Preferred Track: {}",
+                user_skills.join(", "),
+                user_experience.unwrap_or("Not specified"),
+                user_track.unwrap_or("Not specified"),
+            )
+        ),
+        prompt::fence(
+            "job",
+            &format!(
+                "- Title: {}\n- Required Skills: {}\n- Experience Level: {}\n- Description: {}",
+                job_title,
+                job_skills.join(", "),
+                job_experience,
+                job_description.chars().take(200).collect::<String>(), // Truncate description
+            )
+        ),
+        match_score,
+        UNTRUSTED_INPUT_RULES
     );
     
     let client = reqwest::Client::builder()
@@ -350,6 +370,7 @@ Format: Start with overall assessment, then mention key strengths, then areas fo
         .replace("[/INST]", "")
         .trim()
         .to_string();
+    prompt::validate_output(&cleaned).map_err(|e| e.to_string())?;
     
     Ok(cleaned)
 }
@@ -0,0 +1,115 @@
+//! Token consumption reports for AI calls.
+//!
+//! Every provider call is logged to `ai_usage_logs` with the prompt and
+//! completion tokens Gemini and Groq reported for it (see
+//! [`crate::ai::usage`]). [`report`] totals them over a period for one user
+//! or the whole platform, per provider and model and per action. Calls to
+//! providers that report no usage, and calls logged before tokens were
+//! recorded, count as calls without tokens (`calls` minus
+//! `metered_calls`). Platform totals leave out sandbox test users (see
+//! [`crate::sandbox`]).
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::AppResult;
+
+/// Calls and tokens of a group of calls.
+#[derive(Debug, Serialize, FromRow)]
+pub struct UsageTotals {
+    pub calls: i64,
+    /// Calls the provider reported tokens for
+    pub metered_calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+}
+
+/// Usage of one provider's model.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProviderUsage {
+    pub provider: String,
+    pub model: Option<String>,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage of one action, e.g. `ask_question`.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ActionUsage {
+    pub action: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Token consumption over a period.
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    /// `None` for the whole platform
+    pub user_id: Option<Uuid>,
+    pub since: DateTime<Utc>,
+    pub totals: UsageTotals,
+    /// Most tokens first
+    pub by_provider: Vec<ProviderUsage>,
+    /// Most tokens first
+    pub by_action: Vec<ActionUsage>,
+}
+
+const TOTALS: &str = r#"
+    COUNT(*) AS calls,
+    COUNT(prompt_tokens) AS metered_calls,
+    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+    COALESCE(SUM(prompt_tokens), 0)::BIGINT + COALESCE(SUM(completion_tokens), 0)::BIGINT AS total_tokens
+"#;
+
+const FILTER: &str = r#"
+    WHERE created_at > NOW() - make_interval(days => $1)
+      AND CASE
+          WHEN $2::UUID IS NULL THEN NOT EXISTS (
+              SELECT 1 FROM users u WHERE u.id = ai_usage_logs.user_id AND u.sandbox_key_id IS NOT NULL
+          )
+          ELSE user_id = $2
+      END
+"#;
+
+/// Totals the calls of the last `days` days, of `user_id` or of everyone.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn report(db_pool: &PgPool, user_id: Option<Uuid>, days: i32) -> AppResult<UsageReport> {
+    let totals = sqlx::query_as::<_, UsageTotals>(&format!("SELECT {} FROM ai_usage_logs {}", TOTALS, FILTER))
+        .bind(days)
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+    let by_provider = sqlx::query_as::<_, ProviderUsage>(&format!(
+        "SELECT provider, model, {} FROM ai_usage_logs {} GROUP BY provider, model ORDER BY total_tokens DESC, calls DESC",
+        TOTALS, FILTER
+    ))
+    .bind(days)
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    let by_action = sqlx::query_as::<_, ActionUsage>(&format!(
+        "SELECT action, {} FROM ai_usage_logs {} GROUP BY action ORDER BY total_tokens DESC, calls DESC",
+        TOTALS, FILTER
+    ))
+    .bind(days)
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(UsageReport {
+        user_id,
+        since: Utc::now() - chrono::Duration::days(i64::from(days)),
+        totals,
+        by_provider,
+        by_action,
+    })
+}
@@ -0,0 +1,195 @@
+//! API keys for integrators.
+//!
+//! A user creates keys with a set of scopes and sends one in the
+//! `X-Api-Key` header. Only a hash is stored; the key is shown once, at
+//! creation. The only scope so far is [`SANDBOX_SCOPE`], which opens the
+//! sandbox endpoints (see [`crate::sandbox`]).
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::teams;
+
+/// Header an API key is sent in.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Scope of keys that manage a sandbox.
+pub const SANDBOX_SCOPE: &str = "sandbox";
+
+/// Scopes a key can be given.
+pub const SCOPES: &[&str] = &[SANDBOX_SCOPE];
+
+/// Start of every key, so leaked keys are recognizable.
+const KEY_PREFIX: &str = "cbk_";
+
+/// Characters of a key kept to tell it apart.
+const SHOWN_PREFIX_LEN: usize = 12;
+
+/// Live keys a user can hold.
+const MAX_KEYS_PER_USER: i64 = 10;
+
+/// An API key, without the key itself.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ApiKey {
+    pub id: i32,
+    pub user_id: Uuid,
+    pub name: String,
+    /// Start of the key, e.g. `cbk_3f9a0c1b`
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+const KEY_COLUMNS: &str = "id, user_id, name, key_prefix, scopes, created_at, last_used_at, revoked_at";
+
+/// A newly created key with the key itself, which is not shown again.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Creates a key with the given scopes.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for an unknown scope,
+/// `AppError::Forbidden` for a sandbox test user, `AppError::BadRequest` if
+/// the user holds too many live keys, or an error if the database operation
+/// fails.
+pub async fn create(db_pool: &PgPool, user_id: Uuid, name: &str, scopes: &[String]) -> AppResult<CreatedApiKey> {
+    if let Some(unknown) = scopes.iter().find(|s| !SCOPES.contains(&s.as_str())) {
+        return Err(AppError::ValidationError(format!(
+            "Unknown scope \"{}\"; expected one of: {}",
+            unknown,
+            SCOPES.join(", ")
+        )));
+    }
+    // Test users cannot open sandboxes of their own
+    let sandboxed = sqlx::query_scalar::<_, bool>("SELECT sandbox_key_id IS NOT NULL FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    if sandboxed {
+        return Err(AppError::Forbidden);
+    }
+    let live = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM api_keys WHERE user_id = $1 AND revoked_at IS NULL")
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+    if live >= MAX_KEYS_PER_USER {
+        return Err(AppError::BadRequest(format!(
+            "At most {} API keys can be active; revoke one first",
+            MAX_KEYS_PER_USER
+        )));
+    }
+
+    let mut scopes = scopes.to_vec();
+    scopes.sort();
+    scopes.dedup();
+    let key = format!("{}{}{}", KEY_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+        r#"
+        INSERT INTO api_keys (user_id, name, key_prefix, key_hash, scopes)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        KEY_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(name.trim())
+    .bind(&key[..SHOWN_PREFIX_LEN])
+    .bind(teams::hash_token(&key))
+    .bind(&scopes)
+    .fetch_one(db_pool)
+    .await?;
+    info!("API key {} created by {} with scopes {:?}", api_key.id, user_id, api_key.scopes);
+    Ok(CreatedApiKey { api_key, key })
+}
+
+/// The user's keys, newest first, including revoked ones.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<ApiKey>> {
+    let keys = sqlx::query_as::<_, ApiKey>(&format!(
+        "SELECT {} FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC",
+        KEY_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(keys)
+}
+
+/// Revokes one of the user's keys. A sandbox key's test data stays until
+/// the next purge.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the key is not the user's or already
+/// revoked, or an error if the database operation fails.
+pub async fn revoke(db_pool: &PgPool, user_id: Uuid, key_id: i32) -> AppResult<()> {
+    let revoked = sqlx::query(
+        "UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+    )
+    .bind(key_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+    if revoked == 0 {
+        return Err(AppError::NotFound);
+    }
+    info!("API key {} revoked by {}", key_id, user_id);
+    Ok(())
+}
+
+/// The live key in the request's `X-Api-Key` header, which must have
+/// `scope`. Records that the key was used.
+///
+/// # Errors
+///
+/// Returns `AppError::Unauthorized` if the header is missing or the key is
+/// unknown or revoked, `AppError::Forbidden` if it lacks the scope, or an
+/// error if the database operation fails.
+pub async fn authenticate(db_pool: &PgPool, headers: &HeaderMap, scope: &str) -> AppResult<ApiKey> {
+    let key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| key.starts_with(KEY_PREFIX))
+        .ok_or(AppError::Unauthorized)?;
+    let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+        r#"
+        UPDATE api_keys SET last_used_at = NOW()
+        WHERE key_hash = $1 AND revoked_at IS NULL
+        RETURNING {}
+        "#,
+        KEY_COLUMNS
+    ))
+    .bind(teams::hash_token(key))
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+    if !api_key.has_scope(scope) {
+        return Err(AppError::Forbidden);
+    }
+    Ok(api_key)
+}
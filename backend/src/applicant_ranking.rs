@@ -0,0 +1,376 @@
+//! Explainable applicant scores for the employer's applicant list.
+//!
+//! Each applicant gets a 0-100 score from up to four components, each
+//! reported with what it was computed from so recruiters can audit it:
+//!
+//! | Component | Weight | From |
+//! |-----------|--------|------|
+//! | `skills` | 40 | Share of the job's required skills the candidate holds |
+//! | `assessments` | 20 | Self-assessed proficiency in those skills, after decay |
+//! | `screening` | 30 | The application's screening score |
+//! | `experience` | 10 | Candidate's experience level against the job's |
+//!
+//! Components with nothing to score (no required skills, no ratings, no
+//! scored screening answers, or an unknown experience level) are left out
+//! and the others reweighted. Ranked by score, flagged applications come
+//! after unflagged ones, with applicants without a score last in each group.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::screening::Applicant;
+use crate::skills::{FULL_MATCH_PROFICIENCY, SkillRating};
+
+/// Component weights, out of 100.
+const SKILLS_WEIGHT: f64 = 40.0;
+const ASSESSMENTS_WEIGHT: f64 = 20.0;
+const SCREENING_WEIGHT: f64 = 30.0;
+const EXPERIENCE_WEIGHT: f64 = 10.0;
+
+/// Experience score per level the candidate is below the job's.
+const EXPERIENCE_GAP_SCORES: [f64; 3] = [100.0, 60.0, 20.0];
+
+/// How applicants are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicantSort {
+    /// Newest application first
+    #[default]
+    AppliedAt,
+    /// Highest score first
+    Score,
+}
+
+/// One part of an applicant's (or a role readiness) score.
+#[derive(Debug, Serialize)]
+pub struct ScoreComponent {
+    /// `skills`, `assessments`, `screening`, or `experience`
+    pub name: &'static str,
+    /// The component's own score (0-100)
+    pub score: f64,
+    /// Share of the total after leaving out components with nothing to score
+    pub weight: f64,
+    /// `score * weight`, what the component adds to the total
+    pub points: f64,
+    pub detail: String,
+}
+
+/// A required skill the candidate holds.
+#[derive(Debug, Serialize)]
+pub struct MatchedSkill {
+    pub skill: String,
+    /// Whether the candidate learned it in a roadmap rather than listing it
+    pub from_roadmap: bool,
+}
+
+/// The candidate's rating of a required skill.
+#[derive(Debug, Serialize)]
+pub struct AssessmentResult {
+    pub skill: String,
+    /// Self-assessed, 1 (beginner) to 5 (expert)
+    pub proficiency: i16,
+    /// After decay for time since last use
+    pub effective_proficiency: f64,
+    pub last_used_on: Option<NaiveDate>,
+    pub stale: bool,
+}
+
+/// How one screening answer counted.
+#[derive(Debug, Serialize)]
+pub struct ScreeningResult {
+    pub question: String,
+    pub score: Option<i16>,
+    pub flagged: bool,
+    pub reason: Option<String>,
+}
+
+/// What an applicant's score was computed from.
+#[derive(Debug, Serialize)]
+pub struct ScoreExplanation {
+    pub components: Vec<ScoreComponent>,
+    pub matched_skills: Vec<MatchedSkill>,
+    pub missing_skills: Vec<String>,
+    pub assessments: Vec<AssessmentResult>,
+    pub screening: Vec<ScreeningResult>,
+    pub candidate_experience_level: Option<String>,
+    pub job_experience_level: String,
+}
+
+/// An applicant with their score and its explanation.
+#[derive(Debug, Serialize)]
+pub struct RankedApplicant {
+    #[serde(flatten)]
+    pub applicant: Applicant,
+    /// 0-100, `None` when no component could be scored
+    pub score: Option<f64>,
+    /// 1-based position when sorted by score
+    pub rank: Option<usize>,
+    pub explanation: ScoreExplanation,
+}
+
+#[derive(Debug, FromRow)]
+struct Candidate {
+    id: Uuid,
+    skills: Vec<String>,
+    experience_level: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct UserRating {
+    user_id: Uuid,
+    #[sqlx(flatten)]
+    rating: SkillRating,
+}
+
+fn experience_rank(level: &str) -> Option<usize> {
+    match level {
+        "fresher" => Some(0),
+        "junior" => Some(1),
+        "mid" => Some(2),
+        _ => None,
+    }
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Scores and explains a job's applicants, ordered by `sort`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the job does not exist, or an error if a
+/// database query fails.
+pub async fn rank(
+    db_pool: &PgPool,
+    job_id: i32,
+    applicants: Vec<Applicant>,
+    sort: ApplicantSort,
+) -> AppResult<Vec<RankedApplicant>> {
+    let (required_skills, job_level) = sqlx::query_as::<_, (Vec<String>, String)>(
+        "SELECT required_skills, experience_level::text FROM jobs WHERE id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let user_ids: Vec<Uuid> = applicants.iter().map(|a| a.user_id).collect();
+    let candidates: HashMap<Uuid, Candidate> = sqlx::query_as::<_, Candidate>(
+        "SELECT id, skills, experience_level::text AS experience_level FROM users WHERE id = ANY($1)",
+    )
+    .bind(&user_ids)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .map(|c| (c.id, c))
+    .collect();
+
+    let mut learned: HashMap<Uuid, HashSet<String>> = HashMap::new();
+    for (user_id, skill) in sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT user_id, LOWER(skill) FROM user_skill_progress WHERE user_id = ANY($1) AND status = 'known'",
+    )
+    .bind(&user_ids)
+    .fetch_all(db_pool)
+    .await?
+    {
+        learned.entry(user_id).or_default().insert(skill);
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let mut ratings: HashMap<Uuid, HashMap<String, SkillRating>> = HashMap::new();
+    for row in sqlx::query_as::<_, UserRating>(
+        r#"
+        SELECT user_id, skill, proficiency, last_used_on, updated_at
+        FROM user_skill_ratings
+        WHERE user_id = ANY($1)
+        "#,
+    )
+    .bind(&user_ids)
+    .fetch_all(db_pool)
+    .await?
+    {
+        ratings
+            .entry(row.user_id)
+            .or_default()
+            .insert(row.rating.skill.to_lowercase(), row.rating.with_decay(today));
+    }
+
+    let mut ranked: Vec<RankedApplicant> = applicants
+        .into_iter()
+        .map(|applicant| {
+            let candidate = candidates.get(&applicant.user_id);
+            let (score, explanation) = explain(
+                &applicant,
+                candidate,
+                learned.get(&applicant.user_id),
+                ratings.get(&applicant.user_id),
+                &required_skills,
+                &job_level,
+            );
+            RankedApplicant { applicant, score, rank: None, explanation }
+        })
+        .collect();
+
+    let mut by_score: Vec<usize> = (0..ranked.len()).collect();
+    by_score.sort_by(|&a, &b| compare(&ranked[a], &ranked[b]));
+    for (position, index) in by_score.iter().enumerate() {
+        if ranked[*index].score.is_some() {
+            ranked[*index].rank = Some(position + 1);
+        }
+    }
+    if sort == ApplicantSort::Score {
+        ranked.sort_by(compare);
+    }
+    Ok(ranked)
+}
+
+/// Unflagged before flagged, then by score, with unscored applicants last.
+fn compare(a: &RankedApplicant, b: &RankedApplicant) -> Ordering {
+    a.applicant
+        .screening_flagged
+        .cmp(&b.applicant.screening_flagged)
+        .then_with(|| match (a.score, b.score) {
+            (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+        .then_with(|| b.applicant.applied_at.cmp(&a.applicant.applied_at))
+}
+
+fn explain(
+    applicant: &Applicant,
+    candidate: Option<&Candidate>,
+    learned: Option<&HashSet<String>>,
+    ratings: Option<&HashMap<String, SkillRating>>,
+    required_skills: &[String],
+    job_level: &str,
+) -> (Option<f64>, ScoreExplanation) {
+    let listed: HashSet<String> = candidate
+        .map(|c| c.skills.iter().map(|s| s.to_lowercase()).collect())
+        .unwrap_or_default();
+    let mut components: Vec<(&'static str, f64, f64, String)> = Vec::new();
+
+    // Skills held, whether listed on the profile, rated, or learned in a roadmap
+    let mut matched_skills = Vec::new();
+    let mut missing_skills = Vec::new();
+    let mut assessments = Vec::new();
+    for skill in required_skills {
+        let key = skill.to_lowercase();
+        let rating = ratings.and_then(|r| r.get(&key));
+        if let Some(rating) = rating {
+            assessments.push(AssessmentResult {
+                skill: skill.clone(),
+                proficiency: rating.proficiency,
+                effective_proficiency: rating.effective_proficiency,
+                last_used_on: rating.last_used_on,
+                stale: rating.stale,
+            });
+        }
+        if listed.contains(&key) || rating.is_some() {
+            matched_skills.push(MatchedSkill { skill: skill.clone(), from_roadmap: false });
+        } else if learned.is_some_and(|l| l.contains(&key)) {
+            matched_skills.push(MatchedSkill { skill: skill.clone(), from_roadmap: true });
+        } else {
+            missing_skills.push(skill.clone());
+        }
+    }
+    if !required_skills.is_empty() {
+        components.push((
+            "skills",
+            matched_skills.len() as f64 / required_skills.len() as f64 * 100.0,
+            SKILLS_WEIGHT,
+            format!("Holds {} of {} required skills", matched_skills.len(), required_skills.len()),
+        ));
+    }
+    if !assessments.is_empty() {
+        let average = assessments
+            .iter()
+            .map(|a| (a.effective_proficiency / FULL_MATCH_PROFICIENCY).min(1.0))
+            .sum::<f64>()
+            / assessments.len() as f64;
+        let stale = assessments.iter().filter(|a| a.stale).count();
+        let mut detail = format!(
+            "Rated {} of the required skills; full marks at proficiency {} or above",
+            assessments.len(),
+            FULL_MATCH_PROFICIENCY
+        );
+        if stale > 0 {
+            detail.push_str(&format!(", {} not used recently", stale));
+        }
+        components.push(("assessments", average * 100.0, ASSESSMENTS_WEIGHT, detail));
+    }
+
+    let screening: Vec<ScreeningResult> = applicant
+        .screening_answers
+        .iter()
+        .map(|a| ScreeningResult {
+            question: a.question.clone(),
+            score: a.score,
+            flagged: a.flagged,
+            reason: a.reason.clone(),
+        })
+        .collect();
+    if let Some(score) = applicant.screening_score {
+        let scored = screening.iter().filter(|s| s.score.is_some()).count();
+        let flagged = screening.iter().filter(|s| s.flagged).count();
+        let mut detail = format!("Average of {} scored screening answers", scored);
+        if flagged > 0 {
+            detail.push_str(&format!(", {} flagged", flagged));
+        }
+        components.push(("screening", f64::from(score), SCREENING_WEIGHT, detail));
+    }
+
+    let candidate_level = candidate.and_then(|c| c.experience_level.clone());
+    if let (Some(candidate_rank), Some(job_rank)) =
+        (candidate_level.as_deref().and_then(experience_rank), experience_rank(job_level))
+    {
+        let gap = job_rank.saturating_sub(candidate_rank);
+        let detail = match gap {
+            0 => format!("Meets the {} level asked for", job_level),
+            _ => format!(
+                "Candidate is {}; the job asks for {}",
+                candidate_level.as_deref().unwrap_or_default(),
+                job_level
+            ),
+        };
+        components.push(("experience", EXPERIENCE_GAP_SCORES[gap.min(2)], EXPERIENCE_WEIGHT, detail));
+    }
+
+    let total_weight: f64 = components.iter().map(|(_, _, w, _)| w).sum();
+    let score = (!components.is_empty())
+        .then(|| round1(components.iter().map(|(_, score, w, _)| score * w / total_weight).sum()));
+    let components: Vec<ScoreComponent> = components
+        .into_iter()
+        .map(|(name, score, weight, detail)| {
+            let weight = weight / total_weight;
+            ScoreComponent {
+                name,
+                score: round1(score),
+                weight: (weight * 1000.0).round() / 1000.0,
+                points: round1(score * weight),
+                detail,
+            }
+        })
+        .collect();
+
+    (
+        score,
+        ScoreExplanation {
+            components,
+            matched_skills,
+            missing_skills,
+            assessments,
+            screening,
+            candidate_experience_level: candidate_level,
+            job_experience_level: job_level.to_string(),
+        },
+    )
+}
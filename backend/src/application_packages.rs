@@ -0,0 +1,568 @@
+//! Application packages: a multi-step AI agent that prepares a job
+//! application.
+//!
+//! A package runs four steps in order: analyze the job description →
+//! tailor the resume → draft a cover letter → generate likely interview
+//! questions. Each step feeds the next and saves its artifact on its step
+//! row, so clients can poll progress and read each artifact as soon as it
+//! is ready. The interview questions are grounded in the experiences past
+//! candidates shared about the company, when there are any.
+//!
+//! The package row doubles as the task queue entry. Creating a package
+//! queues it and starts a worker in the background; a worker claims a
+//! package by moving it from `queued` to `running`, so each package runs
+//! once at a time. The scheduler picks up packages left queued (e.g. after a
+//! restart) and requeues runs that stopped reporting progress. A failed
+//! package can be retried; it resumes from the failed step, reusing the
+//! artifacts already saved.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+use crate::interview_experiences;
+use crate::models::ApplicationPackageStatus;
+
+/// Steps of a package, in order.
+pub const STEPS: &[&str] = &["analyze_job", "tailor_resume", "cover_letter", "interview_questions"];
+
+/// Longest stretch of CV text sent with the candidate's profile, in characters.
+const MAX_CV_CHARS: usize = 8_000;
+
+/// Minutes a running package may go without progress before it is requeued.
+const STALE_AFTER_MINUTES: i32 = 15;
+
+/// Runs after which a package that keeps stalling is failed instead of requeued.
+const MAX_ATTEMPTS: i16 = 3;
+
+/// Queued packages run per scheduler pass.
+const RUN_BATCH_SIZE: i64 = 5;
+
+/// One step of a package.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PackageStep {
+    #[serde(skip)]
+    pub package_id: i32,
+    /// Step name (e.g. `cover_letter`)
+    pub step: String,
+    /// Position in the run, starting at 0
+    pub position: i16,
+    pub status: ApplicationPackageStatus,
+    /// The step's artifact
+    pub output: Option<Json<Value>>,
+    /// Why the step failed
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A package with its steps.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ApplicationPackage {
+    pub id: i32,
+    pub job_id: Option<i32>,
+    pub job_title: String,
+    pub company: Option<String>,
+    pub provider: String,
+    pub status: ApplicationPackageStatus,
+    /// Runs started, including retries
+    pub attempts: i16,
+    pub error: Option<String>,
+    /// Step being worked on, or that failed
+    #[sqlx(skip)]
+    pub current_step: Option<String>,
+    /// Percentage of steps completed
+    #[sqlx(skip)]
+    pub progress: u8,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub steps: Vec<PackageStep>,
+}
+
+impl ApplicationPackage {
+    /// Attaches steps (ordered by position) and derives the progress.
+    fn with_steps(mut self, steps: Vec<PackageStep>) -> Self {
+        let completed = steps
+            .iter()
+            .filter(|s| s.status == ApplicationPackageStatus::Completed)
+            .count();
+        self.progress = if steps.is_empty() { 0 } else { (completed * 100 / steps.len()) as u8 };
+        self.current_step = steps
+            .iter()
+            .find(|s| s.status != ApplicationPackageStatus::Completed)
+            .filter(|_| self.status != ApplicationPackageStatus::Queued)
+            .map(|s| s.step.clone());
+        self.steps = steps;
+        self
+    }
+}
+
+/// The job a package is built for.
+#[derive(Debug)]
+pub struct NewPackage {
+    pub job_id: Option<i32>,
+    pub job_title: String,
+    pub company: Option<String>,
+    pub job_description: String,
+    pub provider: AIProvider,
+}
+
+/// A claimed package, as needed to run it.
+#[derive(Debug, FromRow)]
+struct ClaimedPackage {
+    user_id: Uuid,
+    job_id: Option<i32>,
+    job_title: String,
+    company: Option<String>,
+    job_description: String,
+    provider: String,
+}
+
+const PACKAGE_COLUMNS: &str = "id, job_id, job_title, company, provider, status, attempts, error, \
+     started_at, completed_at, created_at, updated_at";
+
+/// Queues a package with all of its steps. Returns the package ID.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn create(db_pool: &PgPool, user_id: Uuid, package: NewPackage) -> AppResult<i32> {
+    let provider = package.provider.as_str();
+
+    let mut tx = db_pool.begin().await?;
+    let package_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO application_packages (user_id, job_id, job_title, company, job_description, provider)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(package.job_id)
+    .bind(&package.job_title)
+    .bind(&package.company)
+    .bind(&package.job_description)
+    .bind(provider)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (position, step) in STEPS.iter().enumerate() {
+        sqlx::query("INSERT INTO application_package_steps (package_id, step, position) VALUES ($1, $2, $3)")
+            .bind(package_id)
+            .bind(step)
+            .bind(position as i16)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(package_id)
+}
+
+/// Loads one of the user's packages with its steps.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such package, or an
+/// error if the database operation fails.
+pub async fn load(db_pool: &PgPool, user_id: Uuid, package_id: i32) -> AppResult<ApplicationPackage> {
+    let package = sqlx::query_as::<_, ApplicationPackage>(&format!(
+        "SELECT {} FROM application_packages WHERE id = $1 AND user_id = $2",
+        PACKAGE_COLUMNS
+    ))
+    .bind(package_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let steps = sqlx::query_as::<_, PackageStep>(
+        r#"
+        SELECT package_id, step, position, status, output, error, started_at, completed_at
+        FROM application_package_steps
+        WHERE package_id = $1
+        ORDER BY position
+        "#,
+    )
+    .bind(package_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(package.with_steps(steps))
+}
+
+/// Lists the user's packages, newest first. Step artifacts are left out;
+/// progress is included.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<ApplicationPackage>> {
+    let packages = sqlx::query_as::<_, ApplicationPackage>(&format!(
+        "SELECT {} FROM application_packages WHERE user_id = $1 ORDER BY created_at DESC LIMIT 100",
+        PACKAGE_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    let ids: Vec<i32> = packages.iter().map(|p| p.id).collect();
+    let mut steps: HashMap<i32, Vec<PackageStep>> = HashMap::new();
+    for step in sqlx::query_as::<_, PackageStep>(
+        r#"
+        SELECT package_id, step, position, status, NULL::jsonb AS output, error, started_at, completed_at
+        FROM application_package_steps
+        WHERE package_id = ANY($1)
+        ORDER BY package_id, position
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(db_pool)
+    .await?
+    {
+        steps.entry(step.package_id).or_default().push(step);
+    }
+
+    Ok(packages
+        .into_iter()
+        .map(|p| {
+            let package_steps = steps.remove(&p.id).unwrap_or_default();
+            p.with_steps(package_steps)
+        })
+        .collect())
+}
+
+/// Requeues a failed package; its completed steps are kept.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such package,
+/// `AppError::BadRequest` if it has not failed, or an error if the database
+/// operation fails.
+pub async fn retry(db_pool: &PgPool, user_id: Uuid, package_id: i32) -> AppResult<()> {
+    let status = sqlx::query_scalar::<_, ApplicationPackageStatus>(
+        "SELECT status FROM application_packages WHERE id = $1 AND user_id = $2",
+    )
+    .bind(package_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if status != ApplicationPackageStatus::Failed {
+        return Err(AppError::BadRequest("Only failed packages can be retried".to_string()));
+    }
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query(
+        "UPDATE application_packages SET status = 'queued', error = NULL, updated_at = NOW() WHERE id = $1 AND status = 'failed'",
+    )
+    .bind(package_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE application_package_steps SET status = 'queued', error = NULL WHERE package_id = $1 AND status <> 'completed'",
+    )
+    .bind(package_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Runs a package in the background. Failures are logged; queued packages
+/// are left for the scheduler.
+pub fn spawn_run(db_pool: PgPool, ai_service: Option<Arc<AIService>>, package_id: i32) {
+    let Some(ai) = ai_service else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = run(&db_pool, &ai, package_id).await {
+            warn!("Application package {} failed to run: {}", package_id, e);
+        }
+    });
+}
+
+/// Claims a queued package and runs its remaining steps. Returns `false`
+/// if the package was not queued, e.g. because another worker claimed it.
+///
+/// A failing step fails the package; its error is saved on the step and the
+/// package.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn run(db_pool: &PgPool, ai: &AIService, package_id: i32) -> AppResult<bool> {
+    let Some(package) = sqlx::query_as::<_, ClaimedPackage>(
+        r#"
+        UPDATE application_packages
+        SET status = 'running', attempts = attempts + 1, error = NULL,
+            started_at = COALESCE(started_at, NOW()), updated_at = NOW()
+        WHERE id = $1 AND status = 'queued'
+        RETURNING user_id, job_id, job_title, company, job_description, provider
+        "#,
+    )
+    .bind(package_id)
+    .fetch_optional(db_pool)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    let profile = candidate_profile(db_pool, package.user_id).await?;
+    let experiences =
+        interview_experiences::grounding(db_pool, package.job_id, package.company.as_deref(), &package.job_title)
+            .await?;
+    let mut outputs: HashMap<String, Value> = sqlx::query_as::<_, (String, Json<Value>)>(
+        "SELECT step, output FROM application_package_steps WHERE package_id = $1 AND status = 'completed' AND output IS NOT NULL",
+    )
+    .bind(package_id)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .map(|(step, output)| (step, output.0))
+    .collect();
+
+    for step in STEPS {
+        if outputs.contains_key(*step) {
+            continue;
+        }
+        sqlx::query(
+            "UPDATE application_package_steps SET status = 'running', error = NULL, started_at = NOW() WHERE package_id = $1 AND step = $2",
+        )
+        .bind(package_id)
+        .bind(step)
+        .execute(db_pool)
+        .await?;
+        touch(db_pool, package_id).await?;
+
+        match run_step(ai, &package, step, &profile, experiences.as_deref(), &outputs).await {
+            Ok(output) => {
+                sqlx::query(
+                    r#"
+                    UPDATE application_package_steps
+                    SET status = 'completed', output = $3, completed_at = NOW()
+                    WHERE package_id = $1 AND step = $2
+                    "#,
+                )
+                .bind(package_id)
+                .bind(step)
+                .bind(Json(&output))
+                .execute(db_pool)
+                .await?;
+                outputs.insert(step.to_string(), output);
+            }
+            Err(e) => {
+                let error = e.to_string();
+                sqlx::query(
+                    "UPDATE application_package_steps SET status = 'failed', error = $3 WHERE package_id = $1 AND step = $2",
+                )
+                .bind(package_id)
+                .bind(step)
+                .bind(&error)
+                .execute(db_pool)
+                .await?;
+                sqlx::query(
+                    "UPDATE application_packages SET status = 'failed', error = $2, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(package_id)
+                .bind(format!("Step {} failed: {}", step, error))
+                .execute(db_pool)
+                .await?;
+                warn!("Application package {} failed at step {}: {}", package_id, step, error);
+                return Ok(true);
+            }
+        }
+    }
+
+    sqlx::query(
+        "UPDATE application_packages SET status = 'completed', completed_at = NOW(), updated_at = NOW() WHERE id = $1",
+    )
+    .bind(package_id)
+    .execute(db_pool)
+    .await?;
+    info!("Application package {} completed", package_id);
+    Ok(true)
+}
+
+/// Requeues stalled runs, then runs a batch of queued packages, oldest
+/// first. Returns the number of packages run.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn run_pending(db_pool: &PgPool, ai: &AIService) -> AppResult<usize> {
+    let stalled = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE application_packages
+        SET status = CASE WHEN attempts >= $2 THEN 'failed'::application_package_status ELSE 'queued' END,
+            error = CASE WHEN attempts >= $2 THEN 'Stopped making progress' END,
+            updated_at = NOW()
+        WHERE status = 'running' AND updated_at < NOW() - make_interval(mins => $1)
+        RETURNING id
+        "#,
+    )
+    .bind(STALE_AFTER_MINUTES)
+    .bind(MAX_ATTEMPTS)
+    .fetch_all(db_pool)
+    .await?;
+    if !stalled.is_empty() {
+        sqlx::query(
+            "UPDATE application_package_steps SET status = 'queued' WHERE package_id = ANY($1) AND status = 'running'",
+        )
+        .bind(&stalled)
+        .execute(db_pool)
+        .await?;
+        warn!("Requeued {} stalled application packages", stalled.len());
+    }
+
+    let queued = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM application_packages WHERE status = 'queued' ORDER BY updated_at LIMIT $1",
+    )
+    .bind(RUN_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut ran = 0;
+    for package_id in queued {
+        if run(db_pool, ai, package_id).await? {
+            ran += 1;
+        }
+    }
+    Ok(ran)
+}
+
+/// Marks a running package as still making progress.
+async fn touch(db_pool: &PgPool, package_id: i32) -> AppResult<()> {
+    sqlx::query("UPDATE application_packages SET updated_at = NOW() WHERE id = $1")
+        .bind(package_id)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// The candidate's profile as plain text for the prompts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn candidate_profile(db_pool: &PgPool, user_id: Uuid) -> AppResult<String> {
+    let (full_name, experience_level, education_level, skills, projects, cv_text) =
+        sqlx::query_as::<_, (String, Option<String>, Option<String>, Vec<String>, Vec<String>, Option<String>)>(
+            r#"
+            SELECT full_name, experience_level::text, education_level, skills, projects, raw_cv_text
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+
+    let mut profile = format!(
+        "Name: {}\nExperience level: {}\nEducation: {}\nSkills: {}\nProjects:\n- {}",
+        full_name,
+        experience_level.as_deref().unwrap_or("not specified"),
+        education_level.as_deref().unwrap_or("not specified"),
+        skills.join(", "),
+        projects.join("\n- "),
+    );
+    if let Some(cv_text) = cv_text.filter(|t| !t.trim().is_empty()) {
+        profile.push_str("\n\nCV:\n");
+        profile.extend(cv_text.chars().take(MAX_CV_CHARS));
+    }
+    Ok(profile)
+}
+
+/// Runs one step through the AI service and returns its artifact. Each step
+/// is charged to the package owner like the matching AI action.
+async fn run_step(
+    ai: &AIService,
+    package: &ClaimedPackage,
+    step: &str,
+    profile: &str,
+    experiences: Option<&str>,
+    outputs: &HashMap<String, Value>,
+) -> AppResult<Value> {
+    let analysis = outputs.get("analyze_job").cloned().unwrap_or(Value::Null);
+    let job = json!({
+        "job_title": package.job_title,
+        "company": package.company,
+        "job_analysis": analysis,
+    });
+
+    let (action, input, parameters) = match step {
+        "analyze_job" => (
+            ActionType::AnalyzeJobDescription,
+            format!(
+                "Job title: {}\nCompany: {}\n\n{}",
+                package.job_title,
+                package.company.as_deref().unwrap_or("not specified"),
+                package.job_description
+            ),
+            None,
+        ),
+        "tailor_resume" => (
+            ActionType::GenerateContent,
+            profile.to_string(),
+            Some(json!({
+                "content_type": "tailored_resume",
+                "job": job,
+                "instructions": "Rewrite the candidate's resume in Markdown for this job: lead with the required skills they have, use the posting's keywords where truthful, and never invent experience.",
+            })),
+        ),
+        "cover_letter" => (
+            ActionType::GenerateContent,
+            profile.to_string(),
+            Some(json!({
+                "content_type": "cover_letter",
+                "job": job,
+                "tailored_resume": outputs.get("tailor_resume").and_then(|o| o.get("content")),
+                "instructions": "Write a cover letter of at most 350 words that connects the candidate's skills and projects to the job's key responsibilities.",
+            })),
+        ),
+        "interview_questions" => (
+            ActionType::GenerateInterviewQuestions,
+            match experiences {
+                Some(experiences) => format!(
+                    "Job:\n{}\n\nCandidate:\n{}\n\nInterview experiences shared by past candidates at this company:\n{}",
+                    job, profile, experiences
+                ),
+                None => format!("Job:\n{}\n\nCandidate:\n{}", job, profile),
+            },
+            None,
+        ),
+        _ => return Err(AppError::InternalServerError),
+    };
+
+    let provider = AIProvider::from_name(&package.provider);
+    let response = ai
+        .process_action(AIActionRequest {
+            action,
+            provider,
+            input,
+            parameters,
+            user_id: Some(package.user_id),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+    Ok(response.data)
+}
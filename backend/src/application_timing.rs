@@ -0,0 +1,254 @@
+//! Job application timing nudges.
+//!
+//! Every generated roadmap carries a `job_application_timing` recommendation
+//! such as "After completing Phase 4, start applying for internships". That
+//! is turned into a readiness threshold: the share of the roadmap's phases
+//! up to the one named, or `APPLICATION_NUDGE_THRESHOLD` percent (default 65)
+//! when the text names no phase. Progress toward it is the roadmap component
+//! of [`readiness`](crate::readiness) for the roadmap's role. Once that role's
+//! readiness reaches the nudge score, [`send_nudges`] notifies the user with
+//! a shortlist of open jobs that currently match their profile. Each roadmap
+//! is nudged at most once, tracked in `career_roadmaps.application_nudged_at`.
+
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ai_matching::calculate_enhanced_match;
+use crate::errors::AppResult;
+use crate::models::SkillProgressStatus;
+use crate::readiness;
+use crate::skills;
+
+/// Default readiness threshold, in percent, for timings that name no phase.
+const DEFAULT_THRESHOLD: i32 = 65;
+
+/// Notification type of application timing nudges.
+const NUDGE_NOTIFICATION_TYPE: &str = "application_timing";
+
+/// Roadmaps checked per run.
+const BATCH_SIZE: i64 = 200;
+
+/// Recent open jobs scored for each shortlist.
+const CANDIDATE_JOBS: i64 = 100;
+
+/// Jobs in a shortlist.
+const SHORTLIST_SIZE: usize = 3;
+
+/// Lowest match score (0-100) for a job to be shortlisted.
+const MIN_MATCH_SCORE: f64 = 60.0;
+
+#[derive(Debug, FromRow)]
+struct PendingRoadmap {
+    id: i32,
+    user_id: Uuid,
+    title: String,
+    target_role: String,
+    job_application_timing: String,
+    readiness: f64,
+}
+
+#[derive(Debug, FromRow)]
+struct Candidate {
+    skills: Vec<String>,
+    experience_level: Option<String>,
+    preferred_track: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct OpenJob {
+    job_title: String,
+    company: String,
+    required_skills: Vec<String>,
+    experience_level: String,
+}
+
+/// Returns the fallback threshold from `APPLICATION_NUDGE_THRESHOLD` (default: 65).
+pub fn default_threshold() -> i32 {
+    std::env::var("APPLICATION_NUDGE_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|t| (1..=100).contains(t))
+        .unwrap_or(DEFAULT_THRESHOLD)
+}
+
+/// The progress, in percent, at which a roadmap's timing says to start
+/// applying: phases up to the first one named in `timing`, out of
+/// `phase_count`, or the default threshold.
+pub fn readiness_threshold(timing: &str, phase_count: usize) -> i32 {
+    let lower = timing.to_lowercase();
+    let phase = lower.match_indices("phase").find_map(|(index, _)| {
+        let digits: String = lower[index + "phase".len()..]
+            .trim_start()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse::<usize>().ok()
+    });
+    match phase {
+        Some(phase) if phase_count > 0 && (1..=phase_count).contains(&phase) => {
+            (phase * 100).div_ceil(phase_count) as i32
+        }
+        _ => default_threshold(),
+    }
+}
+
+/// A roadmap's progress in percent, the larger of `progress_percentage`
+/// and the share of completed phases, and the progress its timing says to
+/// start applying at.
+pub fn progress_and_threshold(
+    roadmap_data: &Value,
+    timing: Option<&str>,
+    progress_percentage: Option<i32>,
+    completed_phases: &[i32],
+) -> (i32, i32) {
+    let phase_count = roadmap_data.get("phases").and_then(Value::as_array).map_or(0, Vec::len);
+    let threshold = match timing {
+        Some(timing) => readiness_threshold(timing, phase_count),
+        None => default_threshold(),
+    };
+    let phase_progress = match phase_count {
+        0 => 0,
+        n => (completed_phases.len() * 100 / n) as i32,
+    };
+    (progress_percentage.unwrap_or(0).max(phase_progress), threshold)
+}
+
+/// Notifies users whose roadmaps' roles have reached the readiness nudge
+/// score since the last run. Returns how many nudges were sent.
+///
+/// Roadmaps whose role is ready while no job matches are still marked as
+/// nudged, with a notification suggesting the user keep an eye on new
+/// postings, so they are not re-checked every run.
+///
+/// # Errors
+///
+/// Returns an error if the pending roadmaps cannot be loaded; failures for
+/// single roadmaps are logged and retried on the next run.
+pub async fn send_nudges(db_pool: &PgPool) -> AppResult<usize> {
+    let roadmaps = sqlx::query_as::<_, PendingRoadmap>(
+        r#"
+        SELECT r.id, r.user_id, r.title, r.target_role, r.job_application_timing, rr.score AS readiness
+        FROM career_roadmaps r
+        JOIN role_readiness rr ON rr.user_id = r.user_id AND LOWER(rr.role) = LOWER(r.target_role)
+        WHERE r.application_nudged_at IS NULL
+          AND r.job_application_timing IS NOT NULL
+          AND rr.score >= $1
+        ORDER BY r.updated_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(readiness::nudge_score())
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut sent = 0;
+    for roadmap in &roadmaps {
+        match nudge(db_pool, roadmap).await {
+            Ok(()) => sent += 1,
+            Err(e) => warn!("Failed to send application timing nudge for roadmap {}: {}", roadmap.id, e),
+        }
+    }
+    if sent > 0 {
+        info!("Sent {} application timing nudges", sent);
+    }
+    Ok(sent)
+}
+
+/// Sends one roadmap's nudge and marks it as sent.
+async fn nudge(db_pool: &PgPool, roadmap: &PendingRoadmap) -> AppResult<()> {
+    let shortlist = shortlist(db_pool, roadmap.user_id).await?;
+    let ready = format!(
+        "You're {:.0}% ready for {} and far enough into \"{}\" to start applying: {}",
+        roadmap.readiness, roadmap.target_role, roadmap.title, roadmap.job_application_timing
+    );
+    let message = if shortlist.is_empty() {
+        format!(
+            "{} No open jobs match your profile yet; we'll show new ones in your recommendations.",
+            ready
+        )
+    } else {
+        format!("{} Jobs that match you now: {}.", ready, shortlist.join("; "))
+    };
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, $4)")
+        .bind(roadmap.user_id)
+        .bind("Time to start applying")
+        .bind(message)
+        .bind(NUDGE_NOTIFICATION_TYPE)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE career_roadmaps SET application_nudged_at = NOW() WHERE id = $1")
+        .bind(roadmap.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// The best-matching open jobs for the user, as "title at company (score%)".
+///
+/// Skills the user has marked as known in their roadmaps count as held,
+/// as in the mentor's skill gap analysis.
+async fn shortlist(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<String>> {
+    let candidate = sqlx::query_as::<_, Candidate>(
+        r#"
+        SELECT skills, experience_level::text AS experience_level, preferred_track::text AS preferred_track
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await?;
+
+    let jobs = sqlx::query_as::<_, OpenJob>(
+        r#"
+        SELECT job_title, company, required_skills, experience_level::text AS experience_level
+        FROM jobs
+        WHERE moderation_status = 'approved' AND same_sandbox(sandbox_key_id, $1) AND (expires_at IS NULL OR expires_at > NOW())
+          AND job_matches_candidate(id, $1)
+        ORDER BY id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(CANDIDATE_JOBS)
+    .fetch_all(db_pool)
+    .await?;
+
+    let known = skills::load_progress(db_pool, user_id)
+        .await?
+        .into_iter()
+        .filter(|p| p.status == SkillProgressStatus::Known)
+        .map(|p| p.skill);
+    let held = skills::merge_skills(&candidate.skills, known);
+    let weights = skills::load_weights(db_pool, user_id).await?;
+
+    let mut scored: Vec<(f64, &OpenJob)> = jobs
+        .iter()
+        .map(|job| {
+            let analysis = calculate_enhanced_match(
+                &held,
+                &job.required_skills,
+                &weights,
+                candidate.experience_level.as_deref(),
+                &job.experience_level,
+                candidate.preferred_track.as_deref(),
+                &job.job_title,
+            );
+            (analysis.match_score, job)
+        })
+        .filter(|(score, _)| *score >= MIN_MATCH_SCORE)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    Ok(scored
+        .into_iter()
+        .take(SHORTLIST_SIZE)
+        .map(|(score, job)| format!("{} at {} ({:.0}% match)", job.job_title, job.company, score))
+        .collect())
+}
@@ -0,0 +1,316 @@
+//! At-risk students for an organization's advisors.
+//!
+//! A student in one of the organization's cohorts is at risk when their
+//! roadmaps have stalled (no progress for a number of weeks, or no roadmap
+//! at all), their profile is less complete than a threshold, or they have
+//! not logged a job application for a number of weeks. Placed students are
+//! left out. The thresholds are chosen per request; [`Thresholds::default`]
+//! has the defaults.
+//!
+//! Advisors can nudge students from the dashboard. A nudge is a notification
+//! saying what to work on next, recorded in `student_nudges`; a student is
+//! nudged by an organization at most once per [`NUDGE_COOLDOWN_HOURS`].
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::cohorts::{PROFILE_FIELDS_SQL, ProfileFields};
+use crate::errors::AppResult;
+
+/// Hours before a student can be nudged by the same organization again.
+pub const NUDGE_COOLDOWN_HOURS: i64 = 24;
+
+/// When a student counts as at risk.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Thresholds {
+    /// Weeks without roadmap progress before a roadmap counts as stalled
+    pub stalled_weeks: i64,
+    /// Profile completeness (0-100) below which a profile counts as incomplete
+    pub min_profile_completeness: i32,
+    /// Weeks without a logged job application
+    pub application_weeks: i64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            stalled_weeks: 3,
+            min_profile_completeness: 60,
+            application_weeks: 8,
+        }
+    }
+}
+
+/// Why a student is at risk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFlag {
+    /// No roadmap yet
+    NoRoadmap,
+    /// No progress on an unfinished roadmap within the threshold
+    StalledRoadmap,
+    /// Profile completeness below the threshold
+    LowProfileCompleteness,
+    /// No job application logged within the threshold
+    NoApplications,
+}
+
+impl RiskFlag {
+    /// Stable name, as stored with nudges.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskFlag::NoRoadmap => "no_roadmap",
+            RiskFlag::StalledRoadmap => "stalled_roadmap",
+            RiskFlag::LowProfileCompleteness => "low_profile_completeness",
+            RiskFlag::NoApplications => "no_applications",
+        }
+    }
+}
+
+/// A student flagged as at risk.
+#[derive(Debug, Serialize)]
+pub struct AtRiskStudent {
+    pub user_id: Uuid,
+    pub full_name: String,
+    pub email: String,
+    pub flags: Vec<RiskFlag>,
+    pub profile_completeness: i32,
+    /// Profile sections still empty
+    pub missing: Vec<&'static str>,
+    /// Latest progress on an unfinished roadmap, or its creation
+    pub last_progress_at: Option<DateTime<Utc>>,
+    pub last_applied_at: Option<DateTime<Utc>>,
+    /// When this organization last nudged the student
+    pub last_nudged_at: Option<DateTime<Utc>>,
+}
+
+/// The organization's at-risk students, most flags first.
+#[derive(Debug, Serialize)]
+pub struct AtRiskReport {
+    pub thresholds: Thresholds,
+    /// Unplaced students checked
+    pub students_checked: usize,
+    pub students: Vec<AtRiskStudent>,
+}
+
+/// Who was nudged.
+#[derive(Debug, Serialize)]
+pub struct NudgeOutcome {
+    pub nudged: Vec<Uuid>,
+    /// Students not in the organization's cohorts, not at risk, or nudged
+    /// within the cooldown
+    pub skipped: Vec<Uuid>,
+}
+
+#[derive(Debug, FromRow)]
+struct StudentRow {
+    user_id: Uuid,
+    full_name: String,
+    email: String,
+    roadmaps: i64,
+    last_progress_at: Option<DateTime<Utc>>,
+    last_applied_at: Option<DateTime<Utc>>,
+    last_nudged_at: Option<DateTime<Utc>>,
+    #[sqlx(flatten)]
+    profile: ProfileFields,
+}
+
+impl StudentRow {
+    fn flags(&self, thresholds: &Thresholds, now: DateTime<Utc>, profile_completeness: i32) -> Vec<RiskFlag> {
+        let mut flags = Vec::new();
+        if self.roadmaps == 0 {
+            flags.push(RiskFlag::NoRoadmap);
+        } else if self
+            .last_progress_at
+            .is_some_and(|at| at < now - Duration::weeks(thresholds.stalled_weeks))
+        {
+            flags.push(RiskFlag::StalledRoadmap);
+        }
+        if profile_completeness < thresholds.min_profile_completeness {
+            flags.push(RiskFlag::LowProfileCompleteness);
+        }
+        if self
+            .last_applied_at
+            .is_none_or(|at| at < now - Duration::weeks(thresholds.application_weeks))
+        {
+            flags.push(RiskFlag::NoApplications);
+        }
+        flags
+    }
+}
+
+/// Finds the at-risk students among the unplaced students of the
+/// organization's cohorts, or of one cohort, optionally only `user_ids`.
+async fn find(
+    db_pool: &PgPool,
+    org_id: i32,
+    cohort_id: Option<i32>,
+    user_ids: Option<&[Uuid]>,
+    thresholds: &Thresholds,
+) -> AppResult<AtRiskReport> {
+    let rows = sqlx::query_as::<_, StudentRow>(&format!(
+        r#"
+        WITH students AS (
+            SELECT DISTINCT cm.user_id
+            FROM cohort_members cm
+            JOIN cohorts c ON c.id = cm.cohort_id
+            WHERE c.org_id = $1 AND ($2::int IS NULL OR c.id = $2)
+        )
+        SELECT
+            u.id AS user_id, u.full_name, u.email,
+            (SELECT COUNT(*) FROM career_roadmaps r WHERE r.user_id = u.id) AS roadmaps,
+            (
+                SELECT MAX(COALESCE(e.last_event, r.created_at))
+                FROM career_roadmaps r
+                LEFT JOIN LATERAL (
+                    SELECT MAX(created_at) AS last_event FROM roadmap_progress_events WHERE roadmap_id = r.id
+                ) e ON TRUE
+                WHERE r.user_id = u.id AND COALESCE(r.progress_percentage, 0) < 100
+            ) AS last_progress_at,
+            (SELECT MAX(a.applied_at) FROM application_tracking a WHERE a.user_id = u.id) AS last_applied_at,
+            (SELECT MAX(n.sent_at) FROM student_nudges n WHERE n.org_id = $1 AND n.user_id = u.id) AS last_nudged_at,
+            {}
+        FROM students s
+        JOIN users u ON u.id = s.user_id
+        WHERE u.sandbox_key_id IS NULL
+          AND NOT EXISTS (SELECT 1 FROM placements p WHERE p.user_id = u.id)
+          AND ($3::uuid[] IS NULL OR u.id = ANY($3))
+        ORDER BY u.full_name
+        "#,
+        PROFILE_FIELDS_SQL
+    ))
+    .bind(org_id)
+    .bind(cohort_id)
+    .bind(user_ids)
+    .fetch_all(db_pool)
+    .await?;
+
+    let now = Utc::now();
+    let students_checked = rows.len();
+    let mut students: Vec<AtRiskStudent> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let profile_completeness = row.profile.score();
+            let flags = row.flags(thresholds, now, profile_completeness);
+            (!flags.is_empty()).then(|| AtRiskStudent {
+                user_id: row.user_id,
+                full_name: row.full_name,
+                email: row.email,
+                flags,
+                profile_completeness,
+                missing: row.profile.missing(),
+                last_progress_at: row.last_progress_at,
+                last_applied_at: row.last_applied_at,
+                last_nudged_at: row.last_nudged_at,
+            })
+        })
+        .collect();
+    // Stable sort keeps students with the same number of flags by name
+    students.sort_by_key(|s| std::cmp::Reverse(s.flags.len()));
+
+    Ok(AtRiskReport {
+        thresholds: *thresholds,
+        students_checked,
+        students,
+    })
+}
+
+/// The at-risk students of the organization, or of one of its cohorts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn report(
+    db_pool: &PgPool,
+    org_id: i32,
+    cohort_id: Option<i32>,
+    thresholds: &Thresholds,
+) -> AppResult<AtRiskReport> {
+    find(db_pool, org_id, cohort_id, None, thresholds).await
+}
+
+/// Nudges the given students who are at risk under `thresholds`. Each gets
+/// a notification listing what to work on, after `note` if one is given.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn nudge(
+    db_pool: &PgPool,
+    org_id: i32,
+    sent_by: Uuid,
+    user_ids: &[Uuid],
+    note: Option<&str>,
+    thresholds: &Thresholds,
+) -> AppResult<NudgeOutcome> {
+    let org_name = sqlx::query_scalar::<_, String>("SELECT name FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .fetch_one(db_pool)
+        .await?;
+    let at_risk = find(db_pool, org_id, None, Some(user_ids), thresholds).await?;
+    let cooldown_start = Utc::now() - Duration::hours(NUDGE_COOLDOWN_HOURS);
+
+    let mut nudged = Vec::new();
+    for student in at_risk.students {
+        if student.last_nudged_at.is_some_and(|at| at > cooldown_start) {
+            continue;
+        }
+
+        let message = nudge_message(&student, note);
+        let flags: Vec<&str> = student.flags.iter().map(RiskFlag::as_str).collect();
+        let mut tx = db_pool.begin().await?;
+        sqlx::query("INSERT INTO student_nudges (org_id, user_id, sent_by, flags, message) VALUES ($1, $2, $3, $4, $5)")
+            .bind(org_id)
+            .bind(student.user_id)
+            .bind(sent_by)
+            .bind(&flags)
+            .bind(&message)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'advisor_nudge')")
+            .bind(student.user_id)
+            .bind(format!("A note from {}", org_name))
+            .bind(&message)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        nudged.push(student.user_id);
+    }
+
+    let sent: HashSet<Uuid> = nudged.iter().copied().collect();
+    let skipped = user_ids.iter().copied().filter(|id| !sent.contains(id)).collect();
+    info!("{} nudged {} students of organization {}", sent_by, nudged.len(), org_id);
+    Ok(NudgeOutcome { nudged, skipped })
+}
+
+/// The nudge text: the advisor's note, then a line per risk.
+fn nudge_message(student: &AtRiskStudent, note: Option<&str>) -> String {
+    let mut lines: Vec<String> = note
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .into_iter()
+        .collect();
+    for flag in &student.flags {
+        lines.push(match flag {
+            RiskFlag::NoRoadmap => "Generate a roadmap for your target role to plan what to learn next.".to_string(),
+            RiskFlag::StalledRoadmap => {
+                "Your roadmap has not moved in a while; pick up the next phase this week.".to_string()
+            }
+            RiskFlag::LowProfileCompleteness => format!(
+                "Your profile is {}% complete; fill in: {}.",
+                student.profile_completeness,
+                student.missing.join(", ")
+            ),
+            RiskFlag::NoApplications => {
+                "You have not logged a job application recently; have a look at your matching jobs.".to_string()
+            }
+        });
+    }
+    lines.join("\n")
+}
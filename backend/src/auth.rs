@@ -6,10 +6,11 @@
 use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
 use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
 use axum::{
-    extract::FromRequestParts,
+    extract::{FromRequestParts, OptionalFromRequestParts},
     http::header,
 };
 use axum::http::request::Parts;
@@ -23,6 +24,12 @@ pub struct Claims {
     pub email: String,
     /// Expiration timestamp (Unix epoch)
     pub exp: i64,
+    /// Admin acting as the user, for impersonation tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imp: Option<Uuid>,
+    /// Impersonation session the token belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<i32>,
 }
 
 impl Claims {
@@ -42,10 +49,17 @@ impl Claims {
             sub: user_id.to_string(),
             email,
             exp: expiration,
+            imp: None,
+            sid: None,
         }
     }
 }
 
+fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET")
+        .unwrap_or_else(|_| "your-secret-key-change-in-production".to_string())
+}
+
 /// Creates a JWT token for a user.
 /// 
 /// # Arguments
@@ -59,13 +73,38 @@ impl Claims {
 /// * `Err(AppError)` - If token creation fails
 pub fn create_jwt(user_id: Uuid, email: String) -> AppResult<String> {
     let claims = Claims::new(user_id, email);
-    let secret = std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
     
     encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
+    )
+    .map_err(|_| AppError::InternalServerError)
+}
+
+/// Creates a JWT token letting an admin act as a user until `expires_at`.
+///
+/// The token is flagged with the admin and the impersonation session, which
+/// [`crate::impersonation::guard`] checks on every request.
+pub fn create_impersonation_jwt(
+    user_id: Uuid,
+    email: String,
+    admin_id: Uuid,
+    session_id: i32,
+    expires_at: DateTime<Utc>,
+) -> AppResult<String> {
+    let claims = Claims {
+        sub: user_id.to_string(),
+        email,
+        exp: expires_at.timestamp(),
+        imp: Some(admin_id),
+        sid: Some(session_id),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
     )
     .map_err(|_| AppError::InternalServerError)
 }
@@ -81,12 +120,9 @@ pub fn create_jwt(user_id: Uuid, email: String) -> AppResult<String> {
 /// * `Ok(Claims)` - The decoded claims if token is valid
 /// * `Err(AppError::Unauthorized)` - If token is invalid or expired
 pub fn verify_jwt(token: &str) -> AppResult<Claims> {
-    let secret = std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
-    
     decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret.as_bytes()),
+        &DecodingKey::from_secret(jwt_secret().as_bytes()),
         &Validation::default(),
     )
     .map(|data| data.claims)
@@ -104,6 +140,45 @@ pub struct AuthUser {
     /// The authenticated user's email
     #[allow(dead_code)]
     pub email: String,
+    /// Set when an admin is acting as this user
+    pub impersonation: Option<Impersonation>,
+}
+
+/// The admin and session behind an impersonation token.
+#[derive(Debug, Clone, Copy)]
+pub struct Impersonation {
+    pub session_id: i32,
+    pub admin_id: Uuid,
+}
+
+impl AuthUser {
+    /// Ensures the authenticated user has one of the given roles.
+    ///
+    /// Roles are looked up from the database rather than the token so that
+    /// role changes take effect immediately.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(UserRole)` - The user's role if it is allowed
+    /// * `Err(AppError::Forbidden)` - If the user's role is not in `allowed`
+    /// * `Err(AppError::Unauthorized)` - If the user no longer exists
+    pub async fn require_role(
+        &self,
+        db_pool: &sqlx::PgPool,
+        allowed: &[UserRole],
+    ) -> AppResult<UserRole> {
+        let role = sqlx::query_scalar::<_, UserRole>("SELECT role FROM users WHERE id = $1")
+            .bind(self.user_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if allowed.contains(&role) {
+            Ok(role)
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
 }
 
 impl<S> FromRequestParts<S> for AuthUser
@@ -135,10 +210,36 @@ where
             let user_id = Uuid::parse_str(&claims.sub)
                 .map_err(|_| AppError::Unauthorized)?;
 
+            let impersonation = match (claims.sid, claims.imp) {
+                (Some(session_id), Some(admin_id)) => Some(Impersonation { session_id, admin_id }),
+                (None, None) => None,
+                _ => return Err(AppError::Unauthorized),
+            };
+
             Ok(AuthUser {
                 user_id,
                 email: claims.email,
+                impersonation,
             })
         }
     }
 }
+
+/// `Option<AuthUser>` is `None` for requests without an `Authorization`
+/// header, for endpoints anonymous users may also call. A header with an
+/// invalid token is still rejected.
+impl<S> OptionalFromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(header::AUTHORIZATION) {
+            return Ok(None);
+        }
+        <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
@@ -0,0 +1,238 @@
+//! Anonymous peer benchmarks.
+//!
+//! A student's roadmap progress, skill count, and recent job applications are
+//! compared with other job seekers targeting the same role, either as one of
+//! their target roles or as a roadmap's role. Only aggregates are returned:
+//! quartiles and the student's percentile rank. When fewer peers than
+//! `BENCHMARK_MIN_PEERS` (default 10) target the role, no figures are
+//! returned at all, so no individual can be singled out.
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Default minimum number of peers before figures are shown.
+const DEFAULT_MIN_PEERS: i64 = 10;
+
+/// Days of job applications counted as recent activity.
+pub const APPLICATION_WINDOW_DAYS: i32 = 90;
+
+/// Returns the minimum peer group size from `BENCHMARK_MIN_PEERS` (default: 10).
+pub fn min_peers() -> i64 {
+    std::env::var("BENCHMARK_MIN_PEERS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|n| *n >= 2)
+        .unwrap_or(DEFAULT_MIN_PEERS)
+}
+
+/// How the user compares with peers on one metric.
+#[derive(Debug, Serialize)]
+pub struct MetricBenchmark {
+    /// The user's own value
+    pub value: f64,
+    /// Share of peers below the user (ties count half), 0-100
+    pub percentile: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+}
+
+/// The user's benchmark against peers targeting a role.
+#[derive(Debug, Serialize)]
+pub struct Benchmark {
+    pub role: String,
+    /// Whether there were too few peers to show figures
+    pub suppressed: bool,
+    /// Minimum number of peers for figures to be shown
+    pub min_peers: i64,
+    /// Number of peers, when not suppressed
+    pub peer_count: Option<i64>,
+    /// Highest progress percentage on a roadmap to the role
+    pub roadmap_progress: Option<MetricBenchmark>,
+    /// Skills on the profile
+    pub skill_count: Option<MetricBenchmark>,
+    /// Job applications logged in the last [`APPLICATION_WINDOW_DAYS`] days
+    pub recent_applications: Option<MetricBenchmark>,
+}
+
+/// One user's metrics.
+#[derive(Debug, FromRow)]
+struct Metrics {
+    progress: f64,
+    skills: f64,
+    applications: f64,
+}
+
+/// Aggregates over the peer group, with ranks of the user's own values.
+#[derive(Debug, FromRow)]
+struct PeerAggregates {
+    peers: i64,
+    progress_p25: Option<f64>,
+    progress_median: Option<f64>,
+    progress_p75: Option<f64>,
+    progress_rank: Option<f64>,
+    skills_p25: Option<f64>,
+    skills_median: Option<f64>,
+    skills_p75: Option<f64>,
+    skills_rank: Option<f64>,
+    applications_p25: Option<f64>,
+    applications_median: Option<f64>,
+    applications_p75: Option<f64>,
+    applications_rank: Option<f64>,
+}
+
+/// Per-user metric columns for the `users` row aliased `u`; `$1` is the role
+/// and `$2` the application window in days.
+const METRICS_SQL: &str = r#"
+    COALESCE((
+        SELECT MAX(r.progress_percentage) FROM career_roadmaps r
+        WHERE r.user_id = u.id AND LOWER(r.target_role) = LOWER($1)
+    ), 0)::float8 AS progress,
+    cardinality(u.skills)::float8 AS skills,
+    (
+        SELECT COUNT(*) FROM application_tracking a
+        WHERE a.user_id = u.id AND a.applied_at > NOW() - make_interval(days => $2)
+    )::float8 AS applications
+"#;
+
+/// The roles the user can be benchmarked on: their target roles, then the
+/// roles of their roadmaps.
+pub async fn user_roles(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<String>> {
+    let roles = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT role FROM (
+            SELECT t.role, 0 AS source, t.position FROM users u, unnest(u.target_roles) WITH ORDINALITY AS t(role, position)
+            WHERE u.id = $1
+            UNION ALL
+            SELECT r.target_role, 1, ROW_NUMBER() OVER (ORDER BY r.created_at DESC) FROM career_roadmaps r
+            WHERE r.user_id = $1
+        ) roles
+        ORDER BY source, position
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(roles)
+}
+
+/// Benchmarks the user against peers targeting `role`, by default their
+/// first target role.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the user has no target role or
+/// roadmap, or `role` is not one of theirs, or an error if a query fails.
+pub async fn benchmark(db_pool: &PgPool, user_id: Uuid, role: Option<&str>) -> AppResult<Benchmark> {
+    let roles = user_roles(db_pool, user_id).await?;
+    let role = match role.map(str::trim) {
+        Some(role) => roles
+            .into_iter()
+            .find(|r| r.eq_ignore_ascii_case(role))
+            .ok_or_else(|| {
+                AppError::ValidationError("role must be one of your target roles or roadmap roles".to_string())
+            })?,
+        None => roles.into_iter().next().ok_or_else(|| {
+            AppError::ValidationError("Add a target role or generate a roadmap to see benchmarks".to_string())
+        })?,
+    };
+
+    let mine = sqlx::query_as::<_, Metrics>(&format!("SELECT {} FROM users u WHERE u.id = $3", METRICS_SQL))
+        .bind(&role)
+        .bind(APPLICATION_WINDOW_DAYS)
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+
+    let aggregates = sqlx::query_as::<_, PeerAggregates>(&format!(
+        r#"
+        WITH peers AS (
+            SELECT {}
+            FROM users u
+            WHERE u.id <> $3 AND u.role = 'job_seeker' AND u.sandbox_key_id IS NULL
+              AND (
+                  EXISTS (SELECT 1 FROM unnest(u.target_roles) t WHERE LOWER(t) = LOWER($1))
+                  OR EXISTS (SELECT 1 FROM career_roadmaps r WHERE r.user_id = u.id AND LOWER(r.target_role) = LOWER($1))
+              )
+        )
+        SELECT
+            COUNT(*) AS peers,
+            percentile_cont(0.25) WITHIN GROUP (ORDER BY progress) AS progress_p25,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY progress) AS progress_median,
+            percentile_cont(0.75) WITHIN GROUP (ORDER BY progress) AS progress_p75,
+            AVG(CASE WHEN progress < $4 THEN 1.0 WHEN progress = $4 THEN 0.5 ELSE 0 END)::float8 * 100 AS progress_rank,
+            percentile_cont(0.25) WITHIN GROUP (ORDER BY skills) AS skills_p25,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY skills) AS skills_median,
+            percentile_cont(0.75) WITHIN GROUP (ORDER BY skills) AS skills_p75,
+            AVG(CASE WHEN skills < $5 THEN 1.0 WHEN skills = $5 THEN 0.5 ELSE 0 END)::float8 * 100 AS skills_rank,
+            percentile_cont(0.25) WITHIN GROUP (ORDER BY applications) AS applications_p25,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY applications) AS applications_median,
+            percentile_cont(0.75) WITHIN GROUP (ORDER BY applications) AS applications_p75,
+            AVG(CASE WHEN applications < $6 THEN 1.0 WHEN applications = $6 THEN 0.5 ELSE 0 END)::float8 * 100
+                AS applications_rank
+        FROM peers
+        "#,
+        METRICS_SQL
+    ))
+    .bind(&role)
+    .bind(APPLICATION_WINDOW_DAYS)
+    .bind(user_id)
+    .bind(mine.progress)
+    .bind(mine.skills)
+    .bind(mine.applications)
+    .fetch_one(db_pool)
+    .await?;
+
+    let min_peers = min_peers();
+    if aggregates.peers < min_peers {
+        return Ok(Benchmark {
+            role,
+            suppressed: true,
+            min_peers,
+            peer_count: None,
+            roadmap_progress: None,
+            skill_count: None,
+            recent_applications: None,
+        });
+    }
+
+    let metric = |value: f64, rank: Option<f64>, p25: Option<f64>, median: Option<f64>, p75: Option<f64>| {
+        MetricBenchmark {
+            value,
+            percentile: (rank.unwrap_or(0.0) * 10.0).round() / 10.0,
+            p25: p25.unwrap_or(0.0),
+            median: median.unwrap_or(0.0),
+            p75: p75.unwrap_or(0.0),
+        }
+    };
+    Ok(Benchmark {
+        role,
+        suppressed: false,
+        min_peers,
+        peer_count: Some(aggregates.peers),
+        roadmap_progress: Some(metric(
+            mine.progress,
+            aggregates.progress_rank,
+            aggregates.progress_p25,
+            aggregates.progress_median,
+            aggregates.progress_p75,
+        )),
+        skill_count: Some(metric(
+            mine.skills,
+            aggregates.skills_rank,
+            aggregates.skills_p25,
+            aggregates.skills_median,
+            aggregates.skills_p75,
+        )),
+        recent_applications: Some(metric(
+            mine.applications,
+            aggregates.applications_rank,
+            aggregates.applications_p25,
+            aggregates.applications_median,
+            aggregates.applications_p75,
+        )),
+    })
+}
@@ -0,0 +1,499 @@
+//! Premium plans, Stripe subscriptions, and plan-aware feature gating.
+//!
+//! Every user is on the `free` plan unless they hold an active Stripe
+//! subscription. Upgrades go through Stripe Checkout
+//! ([`StripeClient::create_checkout_session`]); Stripe then reports the
+//! subscription lifecycle to `/api/hooks/stripe`, which lands in
+//! [`handle_stripe_event`].
+//!
+//! Plans control two features:
+//! - `ai_daily_quota` - AI requests per day, enforced for every AI generation
+//!   route by the [`enforce_ai_quota`] middleware; organizations can override
+//!   it for their members (see [`crate::rate_limits`])
+//! - `premium_templates` - access to premium CV templates, checked with
+//!   [`require_premium_templates`]
+//!
+//! Paid mentor sessions use one-off Checkout payments
+//! ([`StripeClient::create_payment_session`]) and refunds; their lifecycle
+//! lives in [`crate::mentor_payments`].
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::mentor_payments;
+use crate::rate_limits::{self, AiLimit};
+
+/// Plan every user falls back to without an active subscription.
+pub const FREE_PLAN: &str = "free";
+
+/// Subscription statuses that grant the subscribed plan's features.
+const ACTIVE_STATUSES: &[&str] = &["active", "trialing"];
+
+/// A billing plan and the features it grants.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Plan {
+    pub id: String,
+    pub name: String,
+    pub stripe_price_id: Option<String>,
+    pub monthly_price_cents: i32,
+    pub ai_daily_quota: i32,
+    pub premium_templates: bool,
+}
+
+/// Lists all plans, cheapest first.
+pub async fn list_plans(db_pool: &PgPool) -> AppResult<Vec<Plan>> {
+    let plans = sqlx::query_as::<_, Plan>(
+        "SELECT id, name, stripe_price_id, monthly_price_cents, ai_daily_quota, premium_templates \
+         FROM plans ORDER BY monthly_price_cents",
+    )
+    .fetch_all(db_pool)
+    .await?;
+    Ok(plans)
+}
+
+/// Returns the plan currently in effect for `user_id`.
+///
+/// Falls back to the free plan when the user has no subscription, or the
+/// subscription is not active or has lapsed.
+pub async fn current_plan(db_pool: &PgPool, user_id: Uuid) -> AppResult<Plan> {
+    let plan = sqlx::query_as::<_, Plan>(
+        r#"
+        SELECT p.id, p.name, p.stripe_price_id, p.monthly_price_cents, p.ai_daily_quota, p.premium_templates
+        FROM plans p
+        WHERE p.id = COALESCE(
+            (SELECT s.plan_id FROM subscriptions s
+             WHERE s.user_id = $1
+               AND s.status = ANY($2)
+               AND (s.current_period_end IS NULL OR s.current_period_end > NOW())),
+            $3
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(ACTIVE_STATUSES)
+    .bind(FREE_PLAN)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or_else(|| {
+        error!("Plan '{}' missing from plans table", FREE_PLAN);
+        AppError::InternalServerError
+    })?;
+    Ok(plan)
+}
+
+/// Returns how many AI requests `user_id` has made today.
+pub async fn ai_usage_today(db_pool: &PgPool, user_id: Uuid) -> AppResult<i32> {
+    let count = sqlx::query_scalar::<_, i32>(
+        "SELECT request_count FROM ai_daily_usage WHERE user_id = $1 AND usage_date = CURRENT_DATE",
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .unwrap_or(0);
+    Ok(count)
+}
+
+/// Records one AI request against the user's daily quota and returns how
+/// many the user has made today.
+///
+/// The check and increment happen in a single statement, so concurrent
+/// requests cannot overshoot the quota.
+///
+/// # Errors
+///
+/// Returns `AppError::TooManyRequests` once the daily quota is used up.
+pub async fn consume_ai_quota(db_pool: &PgPool, user_id: Uuid, limit: &AiLimit) -> AppResult<i32> {
+    let used = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO ai_daily_usage (user_id, usage_date, request_count)
+        VALUES ($1, CURRENT_DATE, 1)
+        ON CONFLICT (user_id, usage_date)
+        DO UPDATE SET request_count = ai_daily_usage.request_count + 1
+        WHERE ai_daily_usage.request_count < $2
+        RETURNING request_count
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit.ai_daily_quota)
+    .fetch_optional(db_pool)
+    .await?;
+
+    match used {
+        Some(count) => {
+            debug!("AI quota: user={} used {}/{}", user_id, count, limit.ai_daily_quota);
+            Ok(count)
+        }
+        None => {
+            info!("AI quota exhausted for user {} on plan {}", user_id, limit.plan_id);
+            Err(AppError::TooManyRequests(format!(
+                "Daily AI limit of {} requests reached on the {} plan",
+                limit.ai_daily_quota, limit.plan_name
+            )))
+        }
+    }
+}
+
+/// Ensures the user's plan includes premium CV templates.
+///
+/// # Errors
+///
+/// Returns `AppError::PaymentRequired` if it does not.
+pub async fn require_premium_templates(db_pool: &PgPool, user_id: Uuid) -> AppResult<()> {
+    let plan = current_plan(db_pool, user_id).await?;
+    if plan.premium_templates {
+        Ok(())
+    } else {
+        Err(AppError::PaymentRequired(
+            "Premium templates require a premium plan".to_string(),
+        ))
+    }
+}
+
+/// Middleware that charges each request against the caller's daily AI quota.
+///
+/// Applied to every route that calls an AI provider. The quota is resolved
+/// from the caller's plan and tenant policies (see [`crate::rate_limits`]),
+/// and reported in `X-RateLimit-*` headers, including on the `429` sent
+/// once it is used up.
+pub async fn enforce_ai_quota(
+    State(app_state): State<AppState>,
+    auth_user: AuthUser,
+    request: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let limit = app_state.rate_limits.resolve(&app_state.db_pool, auth_user.user_id).await?;
+    match consume_ai_quota(&app_state.db_pool, auth_user.user_id, &limit).await {
+        Ok(used) => {
+            let mut response = next.run(request).await;
+            rate_limits::set_headers(response.headers_mut(), &limit, used);
+            Ok(response)
+        }
+        Err(e @ AppError::TooManyRequests(_)) => {
+            let mut response = e.into_response();
+            rate_limits::set_headers(response.headers_mut(), &limit, limit.ai_daily_quota);
+            let reset = rate_limits::seconds_until_reset(Utc::now());
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(reset));
+            Ok(response)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Minimal Stripe API client for Checkout and refunds.
+pub struct StripeClient {
+    secret_key: String,
+    client: Client,
+    base_url: String,
+}
+
+/// A created Checkout session.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckoutSession {
+    /// Stripe session ID
+    pub id: String,
+    /// Hosted checkout page to redirect the user to
+    pub url: Option<String>,
+}
+
+/// A one-off payment to collect through Checkout.
+#[derive(Debug, Clone, Copy)]
+pub struct PaymentRequest<'a> {
+    /// Line item shown on the checkout page
+    pub description: &'a str,
+    pub amount_cents: i32,
+    pub currency: &'a str,
+    pub customer_email: &'a str,
+    /// What the payment is for, echoed back in webhook metadata as `kind`
+    pub kind: &'a str,
+    /// ID of the paid record, echoed back in metadata as `reference`
+    pub reference: &'a str,
+    /// When the checkout page stops accepting the payment
+    pub expires_at: DateTime<Utc>,
+    pub success_url: &'a str,
+    pub cancel_url: &'a str,
+}
+
+#[derive(Deserialize)]
+struct StripeObject {
+    id: String,
+}
+
+impl StripeClient {
+    /// Create a new Stripe client
+    pub fn new(secret_key: String) -> Self {
+        Self {
+            secret_key,
+            client: Client::new(),
+            base_url: "https://api.stripe.com/v1".to_string(),
+        }
+    }
+
+    /// Creates a subscription Checkout session for `plan`.
+    ///
+    /// The user ID and plan ID are attached as metadata so webhook events can
+    /// be matched back to the user.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::BadRequest` if the plan has no Stripe price, or
+    /// `AppError::ExternalServiceError` if Stripe rejects the request.
+    pub async fn create_checkout_session(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        plan: &Plan,
+        success_url: &str,
+        cancel_url: &str,
+    ) -> AppResult<CheckoutSession> {
+        let price_id = plan.stripe_price_id.as_deref().ok_or_else(|| {
+            AppError::BadRequest(format!("Plan '{}' cannot be purchased", plan.id))
+        })?;
+        let user_id = user_id.to_string();
+
+        let form = [
+            ("mode", "subscription"),
+            ("line_items[0][price]", price_id),
+            ("line_items[0][quantity]", "1"),
+            ("success_url", success_url),
+            ("cancel_url", cancel_url),
+            ("client_reference_id", user_id.as_str()),
+            ("customer_email", email),
+            ("metadata[plan_id]", plan.id.as_str()),
+            ("subscription_data[metadata][user_id]", user_id.as_str()),
+            ("subscription_data[metadata][plan_id]", plan.id.as_str()),
+        ];
+
+        self.post_form("checkout/sessions", &form, None).await
+    }
+
+    /// Creates a one-off payment Checkout session. The payment's `kind` and
+    /// `reference` are attached as metadata for the webhook.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ExternalServiceError` if Stripe rejects the request.
+    pub async fn create_payment_session(&self, payment: &PaymentRequest<'_>) -> AppResult<CheckoutSession> {
+        let amount = payment.amount_cents.to_string();
+        let expires_at = payment.expires_at.timestamp().to_string();
+        let form = [
+            ("mode", "payment"),
+            ("line_items[0][price_data][currency]", payment.currency),
+            ("line_items[0][price_data][unit_amount]", amount.as_str()),
+            ("line_items[0][price_data][product_data][name]", payment.description),
+            ("line_items[0][quantity]", "1"),
+            ("success_url", payment.success_url),
+            ("cancel_url", payment.cancel_url),
+            ("customer_email", payment.customer_email),
+            ("expires_at", expires_at.as_str()),
+            ("metadata[kind]", payment.kind),
+            ("metadata[reference]", payment.reference),
+            ("payment_intent_data[metadata][kind]", payment.kind),
+            ("payment_intent_data[metadata][reference]", payment.reference),
+        ];
+        self.post_form("checkout/sessions", &form, None).await
+    }
+
+    /// Expires an open Checkout session so it can no longer be paid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ExternalServiceError` if Stripe rejects the request.
+    pub async fn expire_checkout_session(&self, session_id: &str) -> AppResult<()> {
+        self.post_form::<StripeObject>(&format!("checkout/sessions/{}/expire", session_id), &[], None)
+            .await?;
+        Ok(())
+    }
+
+    /// Refunds `amount_cents` of a payment and returns the refund's ID.
+    /// `idempotency_key` makes a retried refund return the first one
+    /// instead of refunding twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ExternalServiceError` if Stripe rejects the request.
+    pub async fn refund(&self, payment_intent_id: &str, amount_cents: i32, idempotency_key: &str) -> AppResult<String> {
+        let amount = amount_cents.to_string();
+        let form = [("payment_intent", payment_intent_id), ("amount", amount.as_str())];
+        let refund: StripeObject = self.post_form("refunds", &form, Some(idempotency_key)).await?;
+        Ok(refund.id)
+    }
+
+    async fn post_form<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+        idempotency_key: Option<&str>,
+    ) -> AppResult<T> {
+        let mut request = self
+            .client
+            .post(format!("{}/{}", self.base_url, path))
+            .basic_auth(&self.secret_key, None::<&str>)
+            .form(form);
+        if let Some(key) = idempotency_key {
+            request = request.header("Idempotency-Key", key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Stripe API request failed: {}", e);
+                AppError::ExternalServiceError(format!("Stripe API error: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            error!("Stripe API error {}: {}", status, error_text);
+            return Err(AppError::ExternalServiceError(format!(
+                "Stripe API returned {}",
+                status
+            )));
+        }
+
+        response.json::<T>().await.map_err(|e| {
+            error!("Failed to parse Stripe response: {}", e);
+            AppError::ExternalServiceError(format!("Failed to parse Stripe response: {}", e))
+        })
+    }
+}
+
+/// Applies a verified Stripe webhook event to the `subscriptions` table.
+///
+/// Handles `checkout.session.completed` and the `customer.subscription.*`
+/// lifecycle events; other event types are ignored. Checkout sessions for
+/// mentor sessions are handed to [`crate::mentor_payments`].
+pub async fn handle_stripe_event(
+    db_pool: &PgPool,
+    stripe: Option<&StripeClient>,
+    event_type: &str,
+    event: &Value,
+) -> AppResult<()> {
+    let object = &event["data"]["object"];
+    let mentor_booking = object["metadata"]["kind"].as_str() == Some(mentor_payments::CHECKOUT_KIND);
+
+    match event_type {
+        "checkout.session.completed" if mentor_booking => {
+            mentor_payments::checkout_completed(db_pool, stripe, object).await?;
+        }
+        "checkout.session.expired" if mentor_booking => {
+            mentor_payments::checkout_expired(db_pool, object).await?;
+        }
+        "checkout.session.completed" => {
+            let user_id = parse_user_id(object.get("client_reference_id"))?;
+            let plan_id = object["metadata"]["plan_id"].as_str().ok_or_else(|| {
+                AppError::BadRequest("Checkout session is missing plan metadata".to_string())
+            })?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO subscriptions (user_id, plan_id, stripe_customer_id, stripe_subscription_id, status)
+                VALUES ($1, $2, $3, $4, 'active')
+                ON CONFLICT (user_id) DO UPDATE SET
+                    plan_id = EXCLUDED.plan_id,
+                    stripe_customer_id = EXCLUDED.stripe_customer_id,
+                    stripe_subscription_id = EXCLUDED.stripe_subscription_id,
+                    status = 'active',
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+            )
+            .bind(user_id)
+            .bind(plan_id)
+            .bind(object["customer"].as_str())
+            .bind(object["subscription"].as_str())
+            .execute(db_pool)
+            .await?;
+
+            info!("Subscription started: user={}, plan={}", user_id, plan_id);
+        }
+        "customer.subscription.created"
+        | "customer.subscription.updated"
+        | "customer.subscription.deleted" => {
+            let subscription_id = object["id"].as_str().ok_or_else(|| {
+                AppError::BadRequest("Subscription event is missing an id".to_string())
+            })?;
+            let status = if event_type == "customer.subscription.deleted" {
+                "canceled"
+            } else {
+                object["status"].as_str().unwrap_or("incomplete")
+            };
+            let period_end = object["current_period_end"]
+                .as_i64()
+                .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0));
+
+            let updated = sqlx::query(
+                r#"
+                UPDATE subscriptions
+                SET status = $2,
+                    current_period_end = COALESCE($3, current_period_end),
+                    plan_id = COALESCE($4, plan_id),
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE stripe_subscription_id = $1
+                "#,
+            )
+            .bind(subscription_id)
+            .bind(status)
+            .bind(period_end)
+            .bind(object["metadata"]["plan_id"].as_str())
+            .execute(db_pool)
+            .await?
+            .rows_affected();
+
+            if updated == 0 {
+                // The subscription event can arrive before checkout.session.completed
+                let user_id = parse_user_id(object["metadata"].get("user_id"))?;
+                let plan_id = object["metadata"]["plan_id"].as_str().unwrap_or(FREE_PLAN);
+                sqlx::query(
+                    r#"
+                    INSERT INTO subscriptions
+                        (user_id, plan_id, stripe_customer_id, stripe_subscription_id, status, current_period_end)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (user_id) DO UPDATE SET
+                        plan_id = EXCLUDED.plan_id,
+                        stripe_customer_id = EXCLUDED.stripe_customer_id,
+                        stripe_subscription_id = EXCLUDED.stripe_subscription_id,
+                        status = EXCLUDED.status,
+                        current_period_end = EXCLUDED.current_period_end,
+                        updated_at = CURRENT_TIMESTAMP
+                    "#,
+                )
+                .bind(user_id)
+                .bind(plan_id)
+                .bind(object["customer"].as_str())
+                .bind(subscription_id)
+                .bind(status)
+                .bind(period_end)
+                .execute(db_pool)
+                .await?;
+            }
+
+            info!("Subscription {} is now {}", subscription_id, status);
+        }
+        _ => debug!("Ignoring Stripe event {}", event_type),
+    }
+
+    Ok(())
+}
+
+fn parse_user_id(value: Option<&Value>) -> AppResult<Uuid> {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| {
+            warn!("Stripe event does not reference a CareerBridge user");
+            AppError::BadRequest("Stripe event is missing a user reference".to_string())
+        })
+}
@@ -0,0 +1,227 @@
+//! Blocking between candidates and employers.
+//!
+//! Candidates block an employer or a whole company; employers block
+//! candidates. A block made from a company seat is held by the company, so
+//! it covers every seat and any of its owners and recruiters can lift it.
+//!
+//! Blocks hide the two parties from each other whichever side made them.
+//! Queries check the `blocked_between(candidate, employer, company)` SQL
+//! function: candidates stop seeing the other side's jobs in search,
+//! recommendations, and internships and cannot apply to them, and employers
+//! can no longer invite the candidate or keep them in a talent pool.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+
+/// A block the caller (or their company) holds.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Block {
+    pub id: i32,
+    pub blocked_user_id: Option<Uuid>,
+    pub blocked_user_name: Option<String>,
+    pub blocked_company_id: Option<i32>,
+    pub blocked_company_name: Option<String>,
+    /// Whether the block is held by the blocker's company
+    pub company_wide: bool,
+    pub blocked_by: Uuid,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Who or what to block.
+#[derive(Debug, Clone, Copy)]
+pub enum BlockTarget {
+    User(Uuid),
+    Company(i32),
+}
+
+/// The user making or lifting a block.
+#[derive(Debug, Clone, Copy)]
+pub struct Blocker {
+    pub user_id: Uuid,
+    pub role: UserRole,
+    /// Company of the blocker's seat, for employers on a team
+    pub company_id: Option<i32>,
+}
+
+const BLOCK_COLUMNS: &str = r#"
+    b.id, b.blocked_user_id, u.full_name AS blocked_user_name,
+    b.blocked_company_id, c.name AS blocked_company_name,
+    b.blocker_company_id IS NOT NULL AS company_wide,
+    b.blocker_id AS blocked_by, b.reason, b.created_at
+"#;
+
+const BLOCK_JOINS: &str = r#"
+    FROM user_blocks b
+    LEFT JOIN users u ON u.id = b.blocked_user_id
+    LEFT JOIN companies c ON c.id = b.blocked_company_id
+"#;
+
+/// Blocks held by the blocker: their company's when they have a seat,
+/// otherwise their own.
+const OWNED_BY: &str = r#"
+    (($2::int IS NOT NULL AND b.blocker_company_id = $2)
+     OR ($2::int IS NULL AND b.blocker_company_id IS NULL AND b.blocker_id = $1))
+"#;
+
+/// The blocker's blocks, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, blocker: &Blocker) -> AppResult<Vec<Block>> {
+    let blocks = sqlx::query_as::<_, Block>(&format!(
+        "SELECT {} {} WHERE {} ORDER BY b.created_at DESC",
+        BLOCK_COLUMNS, BLOCK_JOINS, OWNED_BY
+    ))
+    .bind(blocker.user_id)
+    .bind(blocker.company_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(blocks)
+}
+
+/// Blocks a user or company. Candidates may block employers and companies;
+/// employers may block candidates. Blocking again returns the existing
+/// block.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the target does not exist,
+/// `AppError::ValidationError` if the blocker may not block it, or an error
+/// if the database operation fails.
+pub async fn block(
+    db_pool: &PgPool,
+    blocker: &Blocker,
+    target: BlockTarget,
+    reason: Option<&str>,
+) -> AppResult<Block> {
+    match (blocker.role, target) {
+        (_, BlockTarget::User(user_id)) if user_id == blocker.user_id => {
+            return Err(AppError::ValidationError("You cannot block yourself".to_string()));
+        }
+        (UserRole::JobSeeker, BlockTarget::User(user_id)) => {
+            ensure_role(db_pool, user_id, UserRole::Employer).await?;
+        }
+        (UserRole::JobSeeker, BlockTarget::Company(company_id)) => {
+            sqlx::query_scalar::<_, i32>("SELECT id FROM companies WHERE id = $1")
+                .bind(company_id)
+                .fetch_optional(db_pool)
+                .await?
+                .ok_or(AppError::NotFound)?;
+        }
+        (UserRole::Employer, BlockTarget::User(user_id)) => {
+            ensure_role(db_pool, user_id, UserRole::JobSeeker).await?;
+        }
+        (UserRole::Employer, BlockTarget::Company(_)) => {
+            return Err(AppError::ValidationError("Employers can only block candidates".to_string()));
+        }
+        (UserRole::Admin, _) => return Err(AppError::Forbidden),
+    }
+
+    let (blocked_user_id, blocked_company_id) = match target {
+        BlockTarget::User(id) => (Some(id), None),
+        BlockTarget::Company(id) => (None, Some(id)),
+    };
+    let existing = sqlx::query_scalar::<_, i32>(&format!(
+        r#"
+        SELECT b.id FROM user_blocks b
+        WHERE {} AND b.blocked_user_id IS NOT DISTINCT FROM $3 AND b.blocked_company_id IS NOT DISTINCT FROM $4
+        "#,
+        OWNED_BY
+    ))
+    .bind(blocker.user_id)
+    .bind(blocker.company_id)
+    .bind(blocked_user_id)
+    .bind(blocked_company_id)
+    .fetch_optional(db_pool)
+    .await?;
+
+    let block_id = match existing {
+        Some(id) => id,
+        None => {
+            let id = sqlx::query_scalar::<_, i32>(
+                r#"
+                INSERT INTO user_blocks (blocker_id, blocker_company_id, blocked_user_id, blocked_company_id, reason)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id
+                "#,
+            )
+            .bind(blocker.user_id)
+            .bind(blocker.company_id)
+            .bind(blocked_user_id)
+            .bind(blocked_company_id)
+            .bind(reason.map(str::trim).filter(|r| !r.is_empty()))
+            .fetch_one(db_pool)
+            .await?;
+            info!("User {} blocked {:?}", blocker.user_id, target);
+            id
+        }
+    };
+
+    sqlx::query_as::<_, Block>(&format!("SELECT {} {} WHERE b.id = $1", BLOCK_COLUMNS, BLOCK_JOINS))
+        .bind(block_id)
+        .fetch_one(db_pool)
+        .await
+        .map_err(Into::into)
+}
+
+async fn ensure_role(db_pool: &PgPool, user_id: Uuid, role: UserRole) -> AppResult<()> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE id = $1 AND role = $2")
+        .bind(user_id)
+        .bind(role)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(())
+}
+
+/// Lifts one of the blocker's blocks.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the blocker holds no such block, or an
+/// error if the database operation fails.
+pub async fn unblock(db_pool: &PgPool, blocker: &Blocker, block_id: i32) -> AppResult<()> {
+    let removed = sqlx::query(&format!(
+        "DELETE FROM user_blocks b WHERE b.id = $3 AND {}",
+        OWNED_BY
+    ))
+    .bind(blocker.user_id)
+    .bind(blocker.company_id)
+    .bind(block_id)
+    .execute(db_pool)
+    .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    info!("User {} lifted block {}", blocker.user_id, block_id);
+    Ok(())
+}
+
+/// Whether a candidate and an employer or company have blocked each other,
+/// in either direction.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn between(
+    db_pool: &PgPool,
+    candidate_id: Uuid,
+    employer_id: Option<Uuid>,
+    company_id: Option<i32>,
+) -> AppResult<bool> {
+    let blocked = sqlx::query_scalar::<_, bool>("SELECT blocked_between($1, $2, $3)")
+        .bind(candidate_id)
+        .bind(employer_id)
+        .bind(company_id)
+        .fetch_one(db_pool)
+        .await?;
+    Ok(blocked)
+}
@@ -0,0 +1,395 @@
+//! Employer branding for public company pages.
+//!
+//! A company's page shows its profile (description, industry, values, and
+//! perks), a "why work here" section, a pitch for each role it hires for,
+//! its open jobs, and candidates' reviews of its hiring process. [`generate`] drafts the branding content from the
+//! profile through the `employer_branding` content type. Owners and
+//! recruiters edit the draft with [`save_draft`], and [`publish`] copies it
+//! to the public page; the page keeps showing the last published version
+//! while a new draft is being edited.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::company_reviews::{self, CompanyReviews};
+use crate::errors::{AppError, AppResult};
+
+/// Content type the branding is generated with.
+const CONTENT_TYPE: &str = "employer_branding";
+
+/// Role pitches generated when no roles are given.
+const MAX_GENERATED_ROLES: i64 = 5;
+
+/// A company's public profile.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CompanyProfile {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub industry: Option<String>,
+    /// e.g. "11-50 employees"
+    pub company_size: Option<String>,
+    pub website: Option<String>,
+    pub headquarters: Option<String>,
+    pub culture_values: Vec<String>,
+    pub perks: Vec<String>,
+}
+
+/// Profile fields an owner can change; omitted fields are left unchanged.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct CompanyProfileUpdate {
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+    #[validate(length(max = 100))]
+    pub industry: Option<String>,
+    #[validate(length(max = 50))]
+    pub company_size: Option<String>,
+    #[validate(url(message = "website must be a valid URL"))]
+    pub website: Option<String>,
+    #[validate(length(max = 255))]
+    pub headquarters: Option<String>,
+    #[validate(length(max = 20, message = "At most 20 values"))]
+    pub culture_values: Option<Vec<String>>,
+    #[validate(length(max = 30, message = "At most 30 perks"))]
+    pub perks: Option<Vec<String>>,
+}
+
+/// Why a candidate would want one of the company's roles.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RolePitch {
+    #[validate(length(min = 1, max = 255, message = "role is required"))]
+    pub role: String,
+    #[validate(length(min = 1, max = 2000, message = "pitch must be between 1 and 2000 characters"))]
+    pub pitch: String,
+}
+
+/// Branding content shown on the company page.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct BrandingContent {
+    #[validate(length(min = 1, max = 10000, message = "why_work_here must be between 1 and 10000 characters"))]
+    pub why_work_here: String,
+    #[serde(default)]
+    #[validate(length(max = 20, message = "At most 20 role pitches"), nested)]
+    pub role_pitches: Vec<RolePitch>,
+}
+
+/// A company's branding draft and published version.
+#[derive(Debug, Default, Serialize, FromRow)]
+pub struct CompanyBranding {
+    pub draft: Option<Json<BrandingContent>>,
+    pub published: Option<Json<BrandingContent>>,
+    /// When the draft was last generated
+    pub generated_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// An open job on the company page.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CompanyJob {
+    pub id: i32,
+    pub job_title: String,
+    pub location: String,
+    pub job_type: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// The public company page.
+#[derive(Debug, Serialize)]
+pub struct CompanyPage {
+    pub company: CompanyProfile,
+    /// The published branding, if any
+    pub branding: Option<BrandingContent>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub open_jobs: Vec<CompanyJob>,
+    /// Candidates' reviews of the hiring process
+    pub reviews: CompanyReviews,
+}
+
+/// Loads a company's profile.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the company does not exist, or an error
+/// if the database operation fails.
+pub async fn load_profile(db_pool: &PgPool, company_id: i32) -> AppResult<CompanyProfile> {
+    sqlx::query_as::<_, CompanyProfile>(
+        r#"
+        SELECT id, name, description, industry, company_size, website, headquarters,
+               culture_values, perks
+        FROM companies
+        WHERE id = $1
+        "#,
+    )
+    .bind(company_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Updates a company's profile.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn update_profile(
+    db_pool: &PgPool,
+    company_id: i32,
+    update: &CompanyProfileUpdate,
+) -> AppResult<CompanyProfile> {
+    let clean_list = |items: &Option<Vec<String>>| {
+        items.as_ref().map(|items| {
+            items
+                .iter()
+                .map(|i| i.trim().to_string())
+                .filter(|i| !i.is_empty())
+                .collect::<Vec<_>>()
+        })
+    };
+    sqlx::query(
+        r#"
+        UPDATE companies
+        SET description = COALESCE($2, description),
+            industry = COALESCE($3, industry),
+            company_size = COALESCE($4, company_size),
+            website = COALESCE($5, website),
+            headquarters = COALESCE($6, headquarters),
+            culture_values = COALESCE($7, culture_values),
+            perks = COALESCE($8, perks)
+        WHERE id = $1
+        "#,
+    )
+    .bind(company_id)
+    .bind(update.description.as_deref().map(str::trim))
+    .bind(update.industry.as_deref().map(str::trim))
+    .bind(update.company_size.as_deref().map(str::trim))
+    .bind(update.website.as_deref().map(str::trim))
+    .bind(update.headquarters.as_deref().map(str::trim))
+    .bind(clean_list(&update.culture_values))
+    .bind(clean_list(&update.perks))
+    .execute(db_pool)
+    .await?;
+    load_profile(db_pool, company_id).await
+}
+
+/// Loads a company's branding; empty if none was generated or saved.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn load(db_pool: &PgPool, company_id: i32) -> AppResult<CompanyBranding> {
+    let branding = sqlx::query_as::<_, CompanyBranding>(
+        r#"
+        SELECT draft, published, generated_at, updated_at, published_at
+        FROM company_branding
+        WHERE company_id = $1
+        "#,
+    )
+    .bind(company_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(branding.unwrap_or_default())
+}
+
+/// Titles of the company's open jobs, newest first.
+async fn open_roles(db_pool: &PgPool, company_id: i32) -> AppResult<Vec<String>> {
+    let roles = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT job_title
+        FROM jobs
+        WHERE company_id = $1 AND moderation_status = 'approved' AND closed_at IS NULL
+          AND (expires_at IS NULL OR expires_at > NOW())
+        GROUP BY job_title
+        ORDER BY MAX(created_at) DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(company_id)
+    .bind(MAX_GENERATED_ROLES)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(roles)
+}
+
+/// Drafts branding content from the company's profile and saves it as the
+/// draft, replacing any earlier draft. Pitches are written for `roles`, or
+/// for the company's open jobs when none are given.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the profile has no description,
+/// `AppError::ExternalServiceError` if the provider fails or returns no
+/// content, or an error if the database operation fails.
+pub async fn generate(
+    db_pool: &PgPool,
+    ai: &AIService,
+    user_id: Uuid,
+    company_id: i32,
+    roles: Vec<String>,
+    provider: AIProvider,
+) -> AppResult<CompanyBranding> {
+    let profile = load_profile(db_pool, company_id).await?;
+    if profile.description.as_deref().is_none_or(|d| d.trim().is_empty()) {
+        return Err(AppError::ValidationError(
+            "Add a company description to the profile before generating branding".to_string(),
+        ));
+    }
+    let roles = if roles.is_empty() {
+        open_roles(db_pool, company_id).await?
+    } else {
+        roles
+    };
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateContent,
+            provider,
+            input: serde_json::to_string_pretty(&profile).unwrap_or_default(),
+            parameters: Some(json!({
+                "content_type": CONTENT_TYPE,
+                "roles": roles,
+                "instructions": "Write the \"why work here\" section of this company's careers page in Markdown as `content`: \
+                    about 200 words in the second person, grounded in its description, values, and perks, without inventing \
+                    facts. In `metadata.role_pitches`, give one 2-3 sentence pitch per listed role as {\"role\", \"pitch\"} \
+                    objects saying why a candidate would want that role here.",
+            })),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+    let content = parse_generated(&response.data, &roles)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO company_branding (company_id, draft, generated_at, updated_by, updated_at)
+        VALUES ($1, $2, NOW(), $3, NOW())
+        ON CONFLICT (company_id) DO UPDATE
+        SET draft = EXCLUDED.draft, generated_at = NOW(), updated_by = EXCLUDED.updated_by, updated_at = NOW()
+        "#,
+    )
+    .bind(company_id)
+    .bind(Json(&content))
+    .bind(user_id)
+    .execute(db_pool)
+    .await?;
+    load(db_pool, company_id).await
+}
+
+/// Reads the generated content; pitches for roles that were not asked for
+/// are dropped.
+fn parse_generated(data: &Value, roles: &[String]) -> AppResult<BrandingContent> {
+    let why_work_here = data
+        .get("content")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AppError::ExternalServiceError("AI provider returned no branding content".to_string()))?
+        .to_string();
+    let role_pitches = data
+        .pointer("/metadata/role_pitches")
+        .and_then(|p| serde_json::from_value::<Vec<RolePitch>>(p.clone()).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| roles.iter().any(|r| r.eq_ignore_ascii_case(p.role.trim())) && !p.pitch.trim().is_empty())
+        .collect();
+    Ok(BrandingContent {
+        why_work_here,
+        role_pitches,
+    })
+}
+
+/// Replaces the branding draft with edited content.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn save_draft(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    company_id: i32,
+    content: &BrandingContent,
+) -> AppResult<CompanyBranding> {
+    sqlx::query(
+        r#"
+        INSERT INTO company_branding (company_id, draft, updated_by, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (company_id) DO UPDATE
+        SET draft = EXCLUDED.draft, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+        "#,
+    )
+    .bind(company_id)
+    .bind(Json(content))
+    .bind(user_id)
+    .execute(db_pool)
+    .await?;
+    load(db_pool, company_id).await
+}
+
+/// Publishes the branding draft to the public company page.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if there is no draft, or an error if the
+/// database operation fails.
+pub async fn publish(db_pool: &PgPool, user_id: Uuid, company_id: i32) -> AppResult<CompanyBranding> {
+    let updated = sqlx::query(
+        r#"
+        UPDATE company_branding
+        SET published = draft, published_at = NOW(), updated_by = $2, updated_at = NOW()
+        WHERE company_id = $1 AND draft IS NOT NULL
+        "#,
+    )
+    .bind(company_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::BadRequest(
+            "There is no branding draft to publish".to_string(),
+        ));
+    }
+    load(db_pool, company_id).await
+}
+
+/// The public page of a company.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the company does not exist, or an error
+/// if the database operation fails.
+pub async fn page(db_pool: &PgPool, company_id: i32) -> AppResult<CompanyPage> {
+    let company = load_profile(db_pool, company_id).await?;
+    let branding = load(db_pool, company_id).await?;
+    let open_jobs = sqlx::query_as::<_, CompanyJob>(
+        r#"
+        SELECT id, job_title, location, job_type::text AS job_type, created_at
+        FROM jobs
+        WHERE company_id = $1 AND moderation_status = 'approved' AND closed_at IS NULL
+          AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(company_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(CompanyPage {
+        company,
+        branding: branding.published.map(|Json(content)| content),
+        published_at: branding.published_at,
+        open_jobs,
+        reviews: company_reviews::for_page(db_pool, company_id).await?,
+    })
+}
@@ -0,0 +1,99 @@
+//! In-process caching for hot database reads.
+//!
+//! AI handlers load the full `users` row on every call, which adds up quickly
+//! during chat-heavy sessions. This module provides a short-TTL cache keyed by
+//! user ID that sits in front of those reads. Any handler that writes to the
+//! `users` table must call [`UserCache::invalidate`] afterwards so the next
+//! read picks up the change.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use moka::future::Cache;
+use sqlx::PgPool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::User;
+
+/// Default time-to-live for cached user profiles, in seconds.
+const DEFAULT_TTL_SECS: u64 = 60;
+
+/// Default maximum number of cached user profiles.
+const DEFAULT_MAX_CAPACITY: u64 = 10_000;
+
+/// Short-lived cache of user profile rows keyed by user ID.
+///
+/// Cloning is cheap; all clones share the same underlying cache.
+#[derive(Clone)]
+pub struct UserCache {
+    inner: Cache<Uuid, Arc<User>>,
+}
+
+impl UserCache {
+    /// Creates a cache with the given TTL and capacity.
+    pub fn new(ttl: Duration, max_capacity: u64) -> Self {
+        Self {
+            inner: Cache::builder()
+                .time_to_live(ttl)
+                .max_capacity(max_capacity)
+                .build(),
+        }
+    }
+
+    /// Creates a cache configured from the environment.
+    ///
+    /// Reads `USER_CACHE_TTL_SECS` (default: 60) and
+    /// `USER_CACHE_MAX_CAPACITY` (default: 10000).
+    pub fn from_env() -> Self {
+        let ttl_secs = std::env::var("USER_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+        let max_capacity = std::env::var("USER_CACHE_MAX_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_MAX_CAPACITY);
+
+        Self::new(Duration::from_secs(ttl_secs), max_capacity)
+    }
+
+    /// Returns the user profile for `user_id`, loading it from the database
+    /// on a cache miss.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::NotFound` if the user does not exist, or a database
+    /// error if the lookup fails.
+    pub async fn get_user(&self, db_pool: &PgPool, user_id: Uuid) -> AppResult<Arc<User>> {
+        if let Some(user) = self.inner.get(&user_id).await {
+            debug!("User cache hit: {}", user_id);
+            return Ok(user);
+        }
+
+        debug!("User cache miss: {}", user_id);
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let user = Arc::new(user);
+        self.inner.insert(user_id, user.clone()).await;
+        Ok(user)
+    }
+
+    /// Drops the cached profile for `user_id`.
+    ///
+    /// Call this after any write to the user's row.
+    pub async fn invalidate(&self, user_id: Uuid) {
+        self.inner.invalidate(&user_id).await;
+    }
+}
+
+impl Default for UserCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_TTL_SECS), DEFAULT_MAX_CAPACITY)
+    }
+}
@@ -0,0 +1,270 @@
+//! Certificate verification.
+//!
+//! Certifications on a profile start out `pending`. [`verify_pending`] runs
+//! on the scheduler (and [`spawn_verify`] right after a certification is
+//! added): certifications from an issuer with a public verification
+//! endpoint are looked up there, and the rest are marked `unsupported`.
+//! Credly is supported, through the Open Badges assertion of the badge
+//! linked in `credential_url` (or named by `credential_id`).
+//!
+//! A credential the issuer does not know, or has revoked, fails right away;
+//! timeouts and server errors only fail a pending certification after
+//! several checks in a row. Verified certifications are re-checked monthly,
+//! so a revoked badge loses its verified status.
+
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode, Url};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppResult;
+use crate::models::CertificationVerificationStatus;
+
+/// Certifications checked per run.
+const BATCH_SIZE: i64 = 50;
+
+/// Checks in a row without a usable answer before a pending certification
+/// is marked failed.
+const UNAVAILABLE_BEFORE_FAILED: i16 = 3;
+
+/// Days before a verified certification is checked again.
+const RECHECK_AFTER_DAYS: i32 = 30;
+
+/// Credly's Open Badges assertion endpoint.
+const CREDLY_ASSERTIONS_URL: &str = "https://api.credly.com/v1/obi/v2/badge_assertions";
+
+/// What the issuer said about a credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationOutcome {
+    /// The issuer confirmed the credential
+    Verified,
+    /// The issuer does not know the credential, or revoked it
+    Rejected(String),
+    /// The issuer has no public verification endpoint
+    Unsupported,
+    /// No usable answer: timeout, connection error, or server error
+    Unavailable(String),
+}
+
+/// HTTP client for issuer verification endpoints.
+#[derive(Clone)]
+pub struct CertificateVerifier {
+    client: Client,
+}
+
+impl Default for CertificateVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CertificateVerifier {
+    /// Create a verifier with a 10 second timeout per request
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Checks a credential with its issuer.
+    pub async fn verify(
+        &self,
+        credential_url: Option<&str>,
+        credential_id: Option<&str>,
+        issuer: Option<&str>,
+    ) -> VerificationOutcome {
+        match credly_badge_id(credential_url, credential_id, issuer) {
+            Some(badge_id) => self.verify_credly(badge_id).await,
+            None => VerificationOutcome::Unsupported,
+        }
+    }
+
+    async fn verify_credly(&self, badge_id: Uuid) -> VerificationOutcome {
+        let response = match self
+            .client
+            .get(format!("{}/{}", CREDLY_ASSERTIONS_URL, badge_id))
+            .header("Accept", "application/json")
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return VerificationOutcome::Unavailable(e.to_string()),
+        };
+
+        match response.status() {
+            StatusCode::NOT_FOUND | StatusCode::GONE => {
+                return VerificationOutcome::Rejected("Credly has no badge with this ID".to_string());
+            }
+            status if !status.is_success() => {
+                return VerificationOutcome::Unavailable(format!("Credly returned HTTP {}", status));
+            }
+            _ => {}
+        }
+
+        let assertion: Value = match response.json().await {
+            Ok(assertion) => assertion,
+            Err(e) => return VerificationOutcome::Unavailable(format!("Invalid Credly response: {}", e)),
+        };
+        if assertion.get("revoked").and_then(Value::as_bool) == Some(true) {
+            let reason = assertion
+                .get("revocationReason")
+                .and_then(Value::as_str)
+                .map(|r| format!(": {}", r))
+                .unwrap_or_default();
+            return VerificationOutcome::Rejected(format!("The badge was revoked{}", reason));
+        }
+        VerificationOutcome::Verified
+    }
+}
+
+/// The Credly badge a certification refers to: the ID in a
+/// `credly.com/badges/<id>` URL, or a credential ID for a certification
+/// issued through Credly.
+fn credly_badge_id(
+    credential_url: Option<&str>,
+    credential_id: Option<&str>,
+    issuer: Option<&str>,
+) -> Option<Uuid> {
+    if let Some(url) = credential_url.and_then(|u| Url::parse(u.trim()).ok())
+        && url
+            .host_str()
+            .is_some_and(|host| host == "credly.com" || host.ends_with(".credly.com"))
+    {
+        let mut segments = url.path_segments()?;
+        segments.find(|s| *s == "badges")?;
+        return segments.next().and_then(|id| Uuid::parse_str(id).ok());
+    }
+
+    if issuer.is_some_and(|i| i.to_lowercase().contains("credly")) {
+        return credential_id.and_then(|id| Uuid::parse_str(id.trim()).ok());
+    }
+    None
+}
+
+#[derive(Debug, FromRow)]
+struct CertificationCheck {
+    id: i32,
+    issuer: Option<String>,
+    credential_id: Option<String>,
+    credential_url: Option<String>,
+    verification_status: CertificationVerificationStatus,
+    verification_attempts: i16,
+}
+
+/// Columns of [`CertificationCheck`].
+const CHECK_COLUMNS: &str =
+    "id, issuer, credential_id, credential_url, verification_status, verification_attempts";
+
+/// Checks a batch of pending certifications, and verified ones due for a
+/// re-check. Returns how many were checked.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be loaded; failures saving single
+/// results are logged and skipped.
+pub async fn verify_pending(db_pool: &PgPool, verifier: &CertificateVerifier) -> AppResult<usize> {
+    let batch = sqlx::query_as::<_, CertificationCheck>(&format!(
+        r#"
+        SELECT {} FROM user_certifications
+        WHERE verification_status = 'pending'
+           OR (verification_status = 'verified'
+               AND verification_checked_at < NOW() - make_interval(days => $1))
+        ORDER BY verification_checked_at NULLS FIRST
+        LIMIT $2
+        "#,
+        CHECK_COLUMNS
+    ))
+    .bind(RECHECK_AFTER_DAYS)
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    for certification in &batch {
+        if let Err(e) = check(db_pool, verifier, certification).await {
+            warn!("Failed to save verification of certification {}: {}", certification.id, e);
+        }
+    }
+    if !batch.is_empty() {
+        info!("Checked {} certifications with their issuers", batch.len());
+    }
+    Ok(batch.len())
+}
+
+/// Verifies a new or re-queued certification in the background.
+pub fn spawn_verify(db_pool: PgPool, certification_id: i32) {
+    tokio::spawn(async move {
+        let certification = sqlx::query_as::<_, CertificationCheck>(&format!(
+            "SELECT {} FROM user_certifications WHERE id = $1 AND verification_status = 'pending'",
+            CHECK_COLUMNS
+        ))
+        .bind(certification_id)
+        .fetch_optional(&db_pool)
+        .await;
+
+        let result = match certification {
+            Ok(Some(certification)) => check(&db_pool, &CertificateVerifier::new(), &certification).await,
+            Ok(None) => Ok(()),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            warn!("Verifying certification {} failed: {}", certification_id, e);
+        }
+    });
+}
+
+/// Checks one certification and saves the outcome.
+async fn check(
+    db_pool: &PgPool,
+    verifier: &CertificateVerifier,
+    certification: &CertificationCheck,
+) -> AppResult<()> {
+    let outcome = verifier
+        .verify(
+            certification.credential_url.as_deref(),
+            certification.credential_id.as_deref(),
+            certification.issuer.as_deref(),
+        )
+        .await;
+
+    let (status, error, attempts) = match outcome {
+        VerificationOutcome::Verified => (CertificationVerificationStatus::Verified, None, 0),
+        VerificationOutcome::Rejected(reason) => (CertificationVerificationStatus::Failed, Some(reason), 0),
+        VerificationOutcome::Unsupported => (CertificationVerificationStatus::Unsupported, None, 0),
+        VerificationOutcome::Unavailable(reason) => {
+            let attempts = certification.verification_attempts.saturating_add(1);
+            // A verified certification keeps its badge through an issuer outage
+            let status = if certification.verification_status == CertificationVerificationStatus::Pending
+                && attempts >= UNAVAILABLE_BEFORE_FAILED
+            {
+                CertificationVerificationStatus::Failed
+            } else {
+                certification.verification_status
+            };
+            (status, Some(reason), attempts)
+        }
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE user_certifications
+        SET verification_status = $2, verification_error = $3, verification_attempts = $4,
+            verification_checked_at = NOW(),
+            verified_at = CASE WHEN $2 = 'verified' THEN COALESCE(verified_at, NOW()) END
+        WHERE id = $1
+        "#,
+    )
+    .bind(certification.id)
+    .bind(status)
+    .bind(error)
+    .bind(attempts)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
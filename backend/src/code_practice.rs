@@ -0,0 +1,425 @@
+//! Code challenge practice tracking.
+//!
+//! Users link their handle on a coding practice platform (LeetCode for now),
+//! or upload a progress export, and their solved problems by difficulty and
+//! topic are shown on their profile. Linked handles are re-synced on the
+//! scheduler (see [`sync_due`]); a handle that keeps failing stops being
+//! synced until it is linked again. A sync replaces counts from an earlier
+//! export.
+//!
+//! For engineering roles, practice feeds role readiness through
+//! [`practice_readiness`]: volume weighted by difficulty, and breadth over
+//! the core interview topics.
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::integrations::leetcode::{self, LeetCodeClient};
+use crate::integrations::{FetchOutcome, PracticeProgress};
+use crate::models::CodePracticePlatform;
+
+/// Accounts synced per run.
+const BATCH_SIZE: i64 = 100;
+
+/// Hours before a linked handle is synced again.
+const SYNC_AFTER_HOURS: i32 = 24;
+
+/// Failed syncs in a row before a handle stops being synced.
+const MAX_SYNC_FAILURES: i16 = 5;
+
+/// Difficulty-weighted solved problems (easy 1, medium 2, hard 3) that
+/// count as full practice volume.
+const TARGET_WEIGHTED_SOLVED: i32 = 300;
+
+/// Solved problems in a topic for it to count as practised.
+const MIN_TOPIC_SOLVED: i32 = 5;
+
+/// Topics engineering interviews draw on most, by platform tag name.
+const CORE_TOPICS: &[&str] = &[
+    "Array",
+    "String",
+    "Hash Table",
+    "Two Pointers",
+    "Binary Search",
+    "Sorting",
+    "Linked List",
+    "Stack",
+    "Tree",
+    "Graph",
+    "Dynamic Programming",
+    "Greedy",
+];
+
+/// Words in a role title that make it an engineering role.
+const ENGINEERING_ROLE_WORDS: &[&str] = &[
+    "engineer",
+    "developer",
+    "programmer",
+    "software",
+    "backend",
+    "back-end",
+    "frontend",
+    "front-end",
+    "full stack",
+    "full-stack",
+    "devops",
+    "sde",
+];
+
+/// Solved problems in one topic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicCount {
+    pub topic: String,
+    pub solved: i32,
+}
+
+/// A user's progress on one platform.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PracticeAccount {
+    pub platform: CodePracticePlatform,
+    /// Linked handle; `None` when progress comes from an uploaded export
+    pub handle: Option<String>,
+    pub easy_solved: i32,
+    pub medium_solved: i32,
+    pub hard_solved: i32,
+    pub total_solved: i32,
+    /// Topics by solved problems, most first
+    pub topics: Json<Vec<TopicCount>>,
+    /// Why the last sync failed
+    pub sync_error: Option<String>,
+    /// When progress was last synced or imported
+    pub synced_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// How practice counts toward readiness for an engineering role.
+#[derive(Debug, Clone, Serialize)]
+pub struct PracticeReadiness {
+    /// 0-100: 70% volume, 30% core topic breadth
+    pub score: i32,
+    pub total_solved: i32,
+    /// Core topics with at least [`MIN_TOPIC_SOLVED`] solved problems
+    pub core_topics_practised: Vec<String>,
+    pub core_topics_missing: Vec<String>,
+}
+
+const ACCOUNT_COLUMNS: &str = r#"
+    a.platform, a.handle, a.easy_solved, a.medium_solved, a.hard_solved,
+    a.easy_solved + a.medium_solved + a.hard_solved AS total_solved,
+    COALESCE((
+        SELECT jsonb_agg(jsonb_build_object('topic', t.topic, 'solved', t.solved) ORDER BY t.solved DESC, t.topic)
+        FROM code_practice_topics t WHERE t.account_id = a.id
+    ), '[]'::jsonb) AS topics,
+    a.sync_error, a.synced_at
+"#;
+
+#[derive(Debug, FromRow)]
+struct DueAccount {
+    id: i32,
+    platform: CodePracticePlatform,
+    handle: String,
+    sync_failures: i16,
+}
+
+/// Whether readiness for a role takes coding practice into account.
+pub fn is_engineering_role(role: &str) -> bool {
+    let role = role.to_lowercase();
+    ENGINEERING_ROLE_WORDS.iter().any(|word| role.contains(word))
+}
+
+async fn fetch(client: &LeetCodeClient, platform: CodePracticePlatform, handle: &str) -> FetchOutcome {
+    match platform {
+        CodePracticePlatform::Leetcode => client.fetch(handle).await,
+    }
+}
+
+fn platform_name(platform: CodePracticePlatform) -> &'static str {
+    match platform {
+        CodePracticePlatform::Leetcode => "LeetCode",
+    }
+}
+
+/// Replaces an account's counts and topics, and marks it synced.
+async fn store(db_pool: &PgPool, account_id: i32, progress: &PracticeProgress) -> AppResult<()> {
+    let (topics, solved): (Vec<&String>, Vec<i32>) = progress.topics.iter().map(|(t, s)| (t, *s)).unzip();
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE code_practice_accounts
+        SET easy_solved = $2, medium_solved = $3, hard_solved = $4,
+            sync_error = NULL, sync_failures = 0, synced_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(account_id)
+    .bind(progress.easy_solved)
+    .bind(progress.medium_solved)
+    .bind(progress.hard_solved)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM code_practice_topics WHERE account_id = $1")
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO code_practice_topics (account_id, topic, solved)
+        SELECT $1, * FROM UNNEST($2::TEXT[], $3::INTEGER[])
+        "#,
+    )
+    .bind(account_id)
+    .bind(topics)
+    .bind(solved)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn record_failure(db_pool: &PgPool, account_id: i32, error: &str, failures: i16) -> AppResult<()> {
+    sqlx::query(
+        "UPDATE code_practice_accounts SET sync_error = $2, sync_failures = $3, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(account_id)
+    .bind(error)
+    .bind(failures)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+async fn load(db_pool: &PgPool, user_id: Uuid, platform: CodePracticePlatform) -> AppResult<PracticeAccount> {
+    let account = sqlx::query_as::<_, PracticeAccount>(&format!(
+        "SELECT {} FROM code_practice_accounts a WHERE a.user_id = $1 AND a.platform = $2",
+        ACCOUNT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(platform)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(account)
+}
+
+/// The user's practice accounts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<PracticeAccount>> {
+    let accounts = sqlx::query_as::<_, PracticeAccount>(&format!(
+        "SELECT {} FROM code_practice_accounts a WHERE a.user_id = $1 ORDER BY a.platform",
+        ACCOUNT_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(accounts)
+}
+
+/// Links the user's handle on a platform and imports its progress. When the
+/// platform cannot be reached, the handle is still linked and synced on a
+/// later run.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the handle is malformed or the platform
+/// has no such user, or an error if the database operation fails.
+pub async fn link(
+    db_pool: &PgPool,
+    client: &LeetCodeClient,
+    user_id: Uuid,
+    platform: CodePracticePlatform,
+    handle: &str,
+) -> AppResult<PracticeAccount> {
+    let handle = handle.trim();
+    if !leetcode::valid_handle(handle) {
+        return Err(AppError::BadRequest(format!("Invalid {} handle", platform_name(platform))));
+    }
+    let outcome = fetch(client, platform, handle).await;
+    if outcome == FetchOutcome::UnknownHandle {
+        return Err(AppError::BadRequest(format!(
+            "{} has no user named {}",
+            platform_name(platform),
+            handle
+        )));
+    }
+
+    let account_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO code_practice_accounts (user_id, platform, handle)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, platform)
+        DO UPDATE SET handle = EXCLUDED.handle, sync_error = NULL, sync_failures = 0, updated_at = NOW()
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(platform)
+    .bind(handle)
+    .fetch_one(db_pool)
+    .await?;
+
+    match outcome {
+        FetchOutcome::Fetched(progress) => store(db_pool, account_id, &progress).await?,
+        FetchOutcome::Unavailable(reason) => {
+            warn!("Could not fetch {} progress for {}: {}", platform_name(platform), handle, reason);
+            record_failure(db_pool, account_id, &reason, 1).await?;
+        }
+        FetchOutcome::UnknownHandle => {}
+    }
+    info!("User {} linked {} handle {}", user_id, platform_name(platform), handle);
+    load(db_pool, user_id, platform).await
+}
+
+/// Imports progress from an uploaded export, replacing the account's counts
+/// until its linked handle, if any, is next synced.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the export cannot be read, or an error
+/// if the database operation fails.
+pub async fn import(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    platform: CodePracticePlatform,
+    export: &str,
+) -> AppResult<PracticeAccount> {
+    let progress = match platform {
+        CodePracticePlatform::Leetcode => leetcode::parse_export(export),
+    }
+    .map_err(AppError::BadRequest)?;
+
+    let account_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO code_practice_accounts (user_id, platform)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id, platform) DO UPDATE SET updated_at = NOW()
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(platform)
+    .fetch_one(db_pool)
+    .await?;
+    store(db_pool, account_id, &progress).await?;
+
+    info!("User {} imported {} progress", user_id, platform_name(platform));
+    load(db_pool, user_id, platform).await
+}
+
+/// Removes the user's account on a platform and its imported progress.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if there is no such account, or an error if
+/// the database operation fails.
+pub async fn unlink(db_pool: &PgPool, user_id: Uuid, platform: CodePracticePlatform) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM code_practice_accounts WHERE user_id = $1 AND platform = $2")
+        .bind(user_id)
+        .bind(platform)
+        .execute(db_pool)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Syncs a batch of linked handles not synced in the past day. Returns how
+/// many were synced.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be loaded; failures saving single
+/// accounts are logged and skipped.
+pub async fn sync_due(db_pool: &PgPool, client: &LeetCodeClient) -> AppResult<usize> {
+    let batch = sqlx::query_as::<_, DueAccount>(
+        r#"
+        SELECT id, platform, handle, sync_failures FROM code_practice_accounts
+        WHERE handle IS NOT NULL AND sync_failures < $1
+          AND (synced_at IS NULL OR synced_at < NOW() - make_interval(hours => $2))
+        ORDER BY synced_at NULLS FIRST
+        LIMIT $3
+        "#,
+    )
+    .bind(MAX_SYNC_FAILURES)
+    .bind(SYNC_AFTER_HOURS)
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut synced = 0;
+    for account in &batch {
+        let result = match fetch(client, account.platform, &account.handle).await {
+            FetchOutcome::Fetched(progress) => {
+                synced += 1;
+                store(db_pool, account.id, &progress).await
+            }
+            // A renamed or deleted account will not come back; stop syncing it
+            FetchOutcome::UnknownHandle => {
+                let error = format!("{} has no user named {}", platform_name(account.platform), account.handle);
+                record_failure(db_pool, account.id, &error, MAX_SYNC_FAILURES).await
+            }
+            FetchOutcome::Unavailable(reason) => {
+                record_failure(db_pool, account.id, &reason, account.sync_failures.saturating_add(1)).await
+            }
+        };
+        if let Err(e) = result {
+            warn!("Failed to save practice sync of account {}: {}", account.id, e);
+        }
+    }
+    if !batch.is_empty() {
+        info!("Synced {} of {} code practice accounts", synced, batch.len());
+    }
+    Ok(synced)
+}
+
+/// How the user's coding practice counts toward readiness for `role`;
+/// `None` for roles that are not engineering roles.
+///
+/// Volume is solved problems weighted by difficulty against
+/// [`TARGET_WEIGHTED_SOLVED`]; breadth is the share of [`CORE_TOPICS`]
+/// practised, across all the user's platforms.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn practice_readiness(db_pool: &PgPool, user_id: Uuid, role: &str) -> AppResult<Option<PracticeReadiness>> {
+    if !is_engineering_role(role) {
+        return Ok(None);
+    }
+
+    let accounts = list(db_pool, user_id).await?;
+    let total_solved = accounts.iter().map(|a| a.total_solved).sum::<i32>();
+    let weighted = accounts
+        .iter()
+        .map(|a| a.easy_solved + 2 * a.medium_solved + 3 * a.hard_solved)
+        .sum::<i32>();
+
+    let (core_topics_practised, core_topics_missing): (Vec<String>, Vec<String>) = CORE_TOPICS
+        .iter()
+        .map(|core| core.to_string())
+        .partition(|core| {
+            let solved: i32 = accounts
+                .iter()
+                .flat_map(|a| a.topics.iter())
+                .filter(|t| t.topic.eq_ignore_ascii_case(core))
+                .map(|t| t.solved)
+                .sum();
+            solved >= MIN_TOPIC_SOLVED
+        });
+
+    let volume = f64::from(weighted.min(TARGET_WEIGHTED_SOLVED)) / f64::from(TARGET_WEIGHTED_SOLVED);
+    let breadth = core_topics_practised.len() as f64 / CORE_TOPICS.len() as f64;
+    Ok(Some(PracticeReadiness {
+        score: (volume * 70.0 + breadth * 30.0).round() as i32,
+        total_solved,
+        core_topics_practised,
+        core_topics_missing,
+    }))
+}
@@ -0,0 +1,558 @@
+//! Bulk roadmap generation for a cohort.
+//!
+//! An org admin picks a target role, or a staff member's roadmap as a
+//! template, and every student in the cohort gets their own roadmap to it.
+//! Each roadmap starts from that student's skills and follows the
+//! organization's roadmap guidance (see [`crate::tenant_config`]).
+//!
+//! The batch row doubles as the task queue entry, like
+//! [`crate::application_packages`]: creating a batch queues it with one item
+//! per student and starts a worker in the background, and the scheduler picks
+//! up batches left queued and requeues runs that stopped reporting progress.
+//! A student whose roadmap fails does not stop the batch; the failure is
+//! saved on their item, and once the batch completes the failed students can
+//! be retried.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::activity::{self, ActivityType};
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+use crate::models::{RoadmapBatchStatus, SkillProgressStatus};
+use crate::skills;
+use crate::tenant_config;
+
+/// Minutes a running batch may go without progress before it is requeued.
+const STALE_AFTER_MINUTES: i32 = 15;
+
+/// Runs after which a batch that keeps stalling is failed instead of requeued.
+const MAX_ATTEMPTS: i16 = 3;
+
+/// Queued batches run per scheduler pass.
+const RUN_BATCH_SIZE: i64 = 2;
+
+/// A student whose roadmap could not be generated.
+#[derive(Debug, Serialize, FromRow)]
+pub struct StudentFailure {
+    pub user_id: Uuid,
+    pub full_name: String,
+    pub email: String,
+    pub error: Option<String>,
+}
+
+/// A batch with its progress.
+#[derive(Debug, Serialize, FromRow)]
+pub struct RoadmapBatch {
+    pub id: i32,
+    pub org_id: i32,
+    pub cohort_id: i32,
+    pub requested_by: Option<Uuid>,
+    pub target_role: String,
+    pub template_roadmap_id: Option<i32>,
+    pub timeframe_months: i32,
+    pub learning_hours_per_week: i32,
+    pub provider: String,
+    pub status: RoadmapBatchStatus,
+    /// Runs started, including retries
+    pub attempts: i16,
+    pub error: Option<String>,
+    /// Students in the batch
+    pub total: i64,
+    /// Students whose roadmap was generated
+    pub succeeded: i64,
+    /// Students whose roadmap failed
+    pub failed: i64,
+    /// Percentage of students finished, successfully or not
+    #[sqlx(skip)]
+    pub progress: u8,
+    /// Failed students and why; only filled for a single batch
+    #[sqlx(skip)]
+    pub failures: Vec<StudentFailure>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RoadmapBatch {
+    /// Derives the progress from the counts.
+    fn with_progress(mut self) -> Self {
+        self.progress = if self.total == 0 {
+            0
+        } else {
+            ((self.succeeded + self.failed) * 100 / self.total) as u8
+        };
+        self
+    }
+}
+
+/// What to generate for every student.
+#[derive(Debug)]
+pub struct NewBatch {
+    pub target_role: String,
+    pub template_roadmap_id: Option<i32>,
+    pub timeframe_months: i32,
+    pub learning_hours_per_week: i32,
+    pub provider: AIProvider,
+}
+
+/// A claimed batch, as needed to run it.
+#[derive(Debug, FromRow)]
+struct ClaimedBatch {
+    org_id: i32,
+    requested_by: Option<Uuid>,
+    target_role: String,
+    timeframe_months: i32,
+    learning_hours_per_week: i32,
+    provider: String,
+}
+
+const BATCH_SELECT: &str = r#"
+    SELECT b.id, b.org_id, b.cohort_id, b.requested_by, b.target_role, b.template_roadmap_id,
+           b.timeframe_months, b.learning_hours_per_week, b.provider, b.status, b.attempts, b.error,
+           COALESCE(c.total, 0) AS total, COALESCE(c.succeeded, 0) AS succeeded, COALESCE(c.failed, 0) AS failed,
+           b.started_at, b.completed_at, b.created_at, b.updated_at
+    FROM cohort_roadmap_batches b
+    LEFT JOIN LATERAL (
+        SELECT COUNT(*) AS total,
+               COUNT(*) FILTER (WHERE i.status = 'completed') AS succeeded,
+               COUNT(*) FILTER (WHERE i.status = 'failed') AS failed
+        FROM cohort_roadmap_items i
+        WHERE i.batch_id = b.id
+    ) c ON TRUE
+"#;
+
+/// Queues a batch with an item for every student in the cohort. Returns the
+/// batch ID.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the cohort is not one of the
+/// organization's, `AppError::ValidationError` if it has no students, or an
+/// error if the database operation fails.
+pub async fn create(
+    db_pool: &PgPool,
+    org_id: i32,
+    cohort_id: i32,
+    requested_by: Uuid,
+    batch: NewBatch,
+) -> AppResult<i32> {
+    let in_org = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM cohorts WHERE id = $1 AND org_id = $2)",
+    )
+    .bind(cohort_id)
+    .bind(org_id)
+    .fetch_one(db_pool)
+    .await?;
+    if !in_org {
+        return Err(AppError::NotFound);
+    }
+
+    let provider = batch.provider.as_str();
+
+    let mut tx = db_pool.begin().await?;
+    let batch_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO cohort_roadmap_batches
+            (org_id, cohort_id, requested_by, target_role, template_roadmap_id,
+             timeframe_months, learning_hours_per_week, provider)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+    )
+    .bind(org_id)
+    .bind(cohort_id)
+    .bind(requested_by)
+    .bind(&batch.target_role)
+    .bind(batch.template_roadmap_id)
+    .bind(batch.timeframe_months)
+    .bind(batch.learning_hours_per_week)
+    .bind(provider)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let students = sqlx::query(
+        "INSERT INTO cohort_roadmap_items (batch_id, user_id) SELECT $1, user_id FROM cohort_members WHERE cohort_id = $2",
+    )
+    .bind(batch_id)
+    .bind(cohort_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if students == 0 {
+        return Err(AppError::ValidationError("The cohort has no students".to_string()));
+    }
+    tx.commit().await?;
+
+    info!(
+        "Roadmap batch {} queued for {} students of cohort {} by {}",
+        batch_id, students, cohort_id, requested_by
+    );
+    Ok(batch_id)
+}
+
+/// Loads one of the cohort's batches with its failed students.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the cohort has no such batch, or an
+/// error if the database operation fails.
+pub async fn load(db_pool: &PgPool, org_id: i32, cohort_id: i32, batch_id: i32) -> AppResult<RoadmapBatch> {
+    let mut batch = sqlx::query_as::<_, RoadmapBatch>(&format!(
+        "{} WHERE b.id = $1 AND b.org_id = $2 AND b.cohort_id = $3",
+        BATCH_SELECT
+    ))
+    .bind(batch_id)
+    .bind(org_id)
+    .bind(cohort_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    batch.failures = sqlx::query_as::<_, StudentFailure>(
+        r#"
+        SELECT i.user_id, u.full_name, u.email, i.error
+        FROM cohort_roadmap_items i
+        JOIN users u ON u.id = i.user_id
+        WHERE i.batch_id = $1 AND i.status = 'failed'
+        ORDER BY u.full_name
+        "#,
+    )
+    .bind(batch_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(batch.with_progress())
+}
+
+/// Lists the cohort's batches, newest first, without their failed students.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, org_id: i32, cohort_id: i32) -> AppResult<Vec<RoadmapBatch>> {
+    let batches = sqlx::query_as::<_, RoadmapBatch>(&format!(
+        "{} WHERE b.org_id = $1 AND b.cohort_id = $2 ORDER BY b.created_at DESC LIMIT 100",
+        BATCH_SELECT
+    ))
+    .bind(org_id)
+    .bind(cohort_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(batches.into_iter().map(RoadmapBatch::with_progress).collect())
+}
+
+/// Requeues the failed students of a finished batch; students who already
+/// have their roadmap are kept.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the cohort has no such batch,
+/// `AppError::BadRequest` if it is still running or no student failed, or an
+/// error if the database operation fails.
+pub async fn retry(db_pool: &PgPool, org_id: i32, cohort_id: i32, batch_id: i32) -> AppResult<()> {
+    let batch = load(db_pool, org_id, cohort_id, batch_id).await?;
+    if matches!(batch.status, RoadmapBatchStatus::Queued | RoadmapBatchStatus::Running) {
+        return Err(AppError::BadRequest("The batch is still running".to_string()));
+    }
+    if batch.failed == 0 && batch.status != RoadmapBatchStatus::Failed {
+        return Err(AppError::BadRequest("No students failed in this batch".to_string()));
+    }
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE cohort_roadmap_batches
+        SET status = 'queued', attempts = 0, error = NULL, completed_at = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(batch_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE cohort_roadmap_items SET status = 'queued', error = NULL WHERE batch_id = $1 AND status <> 'completed'",
+    )
+    .bind(batch_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Runs a batch in the background. Failures are logged; queued batches are
+/// left for the scheduler.
+pub fn spawn_run(db_pool: PgPool, ai_service: Option<Arc<AIService>>, batch_id: i32) {
+    let Some(ai) = ai_service else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = run(&db_pool, &ai, batch_id).await {
+            warn!("Roadmap batch {} failed to run: {}", batch_id, e);
+        }
+    });
+}
+
+/// Claims a queued batch and generates the roadmaps still pending. Returns
+/// `false` if the batch was not queued, e.g. because another worker claimed
+/// it.
+///
+/// A failing student is recorded on their item and the batch moves on. Each
+/// roadmap is charged to the admin who queued the batch. When the batch
+/// completes, the admin is notified.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn run(db_pool: &PgPool, ai: &AIService, batch_id: i32) -> AppResult<bool> {
+    let Some(batch) = sqlx::query_as::<_, ClaimedBatch>(
+        r#"
+        UPDATE cohort_roadmap_batches
+        SET status = 'running', attempts = attempts + 1, error = NULL,
+            started_at = COALESCE(started_at, NOW()), updated_at = NOW()
+        WHERE id = $1 AND status = 'queued'
+        RETURNING org_id, requested_by, target_role, timeframe_months, learning_hours_per_week, provider
+        "#,
+    )
+    .bind(batch_id)
+    .fetch_optional(db_pool)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    let guidance = tenant_config::get(db_pool, batch.org_id).await?.roadmap_guidance;
+    let students = sqlx::query_scalar::<_, Uuid>(
+        "SELECT user_id FROM cohort_roadmap_items WHERE batch_id = $1 AND status IN ('queued', 'running') ORDER BY user_id",
+    )
+    .bind(batch_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    for user_id in students {
+        sqlx::query("UPDATE cohort_roadmap_items SET status = 'running', error = NULL WHERE batch_id = $1 AND user_id = $2")
+            .bind(batch_id)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+        touch(db_pool, batch_id).await?;
+
+        match generate(db_pool, ai, &batch, guidance.as_deref(), user_id).await {
+            Ok(roadmap_id) => {
+                sqlx::query(
+                    r#"
+                    UPDATE cohort_roadmap_items
+                    SET status = 'completed', roadmap_id = $3, completed_at = NOW()
+                    WHERE batch_id = $1 AND user_id = $2
+                    "#,
+                )
+                .bind(batch_id)
+                .bind(user_id)
+                .bind(roadmap_id)
+                .execute(db_pool)
+                .await?;
+                activity::record(
+                    db_pool,
+                    user_id,
+                    ActivityType::RoadmapCreated,
+                    json!({ "roadmap_id": roadmap_id, "target_role": batch.target_role, "batch_id": batch_id }),
+                )
+                .await;
+                sqlx::query(
+                    "INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'roadmap_batch')",
+                )
+                .bind(user_id)
+                .bind("A new roadmap for you")
+                .bind(format!("Your career services team created a roadmap to {} for you.", batch.target_role))
+                .execute(db_pool)
+                .await?;
+            }
+            Err(e) => {
+                let error = e.to_string();
+                sqlx::query(
+                    "UPDATE cohort_roadmap_items SET status = 'failed', error = $3 WHERE batch_id = $1 AND user_id = $2",
+                )
+                .bind(batch_id)
+                .bind(user_id)
+                .bind(&error)
+                .execute(db_pool)
+                .await?;
+                warn!("Roadmap batch {} failed for user {}: {}", batch_id, user_id, error);
+            }
+        }
+    }
+
+    let (succeeded, failed) = sqlx::query_as::<_, (i64, i64)>(
+        r#"
+        UPDATE cohort_roadmap_batches b
+        SET status = 'completed', completed_at = NOW(), updated_at = NOW()
+        WHERE b.id = $1
+        RETURNING (SELECT COUNT(*) FROM cohort_roadmap_items WHERE batch_id = b.id AND status = 'completed'),
+                  (SELECT COUNT(*) FROM cohort_roadmap_items WHERE batch_id = b.id AND status = 'failed')
+        "#,
+    )
+    .bind(batch_id)
+    .fetch_one(db_pool)
+    .await?;
+    info!("Roadmap batch {} completed: {} generated, {} failed", batch_id, succeeded, failed);
+
+    if let Some(admin_id) = batch.requested_by {
+        let mut message = format!("{} roadmaps to {} were generated.", succeeded, batch.target_role);
+        if failed > 0 {
+            message.push_str(&format!(" {} students failed and can be retried.", failed));
+        }
+        sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'roadmap_batch')")
+            .bind(admin_id)
+            .bind("Cohort roadmaps ready")
+            .bind(&message)
+            .execute(db_pool)
+            .await?;
+    }
+    Ok(true)
+}
+
+/// Requeues stalled runs, then runs a batch of queued batches, oldest
+/// first. Returns the number of batches run.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn run_pending(db_pool: &PgPool, ai: &AIService) -> AppResult<usize> {
+    let stalled = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE cohort_roadmap_batches
+        SET status = CASE WHEN attempts >= $2 THEN 'failed'::roadmap_batch_status ELSE 'queued' END,
+            error = CASE WHEN attempts >= $2 THEN 'Stopped making progress' END,
+            updated_at = NOW()
+        WHERE status = 'running' AND updated_at < NOW() - make_interval(mins => $1)
+        RETURNING id
+        "#,
+    )
+    .bind(STALE_AFTER_MINUTES)
+    .bind(MAX_ATTEMPTS)
+    .fetch_all(db_pool)
+    .await?;
+    if !stalled.is_empty() {
+        sqlx::query(
+            "UPDATE cohort_roadmap_items SET status = 'queued' WHERE batch_id = ANY($1) AND status = 'running'",
+        )
+        .bind(&stalled)
+        .execute(db_pool)
+        .await?;
+        warn!("Requeued {} stalled roadmap batches", stalled.len());
+    }
+
+    let queued = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM cohort_roadmap_batches WHERE status = 'queued' ORDER BY updated_at LIMIT $1",
+    )
+    .bind(RUN_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut ran = 0;
+    for batch_id in queued {
+        if run(db_pool, ai, batch_id).await? {
+            ran += 1;
+        }
+    }
+    Ok(ran)
+}
+
+/// Marks a running batch as still making progress.
+async fn touch(db_pool: &PgPool, batch_id: i32) -> AppResult<()> {
+    sqlx::query("UPDATE cohort_roadmap_batches SET updated_at = NOW() WHERE id = $1")
+        .bind(batch_id)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// Generates and saves one student's roadmap. Returns the roadmap ID.
+async fn generate(
+    db_pool: &PgPool,
+    ai: &AIService,
+    batch: &ClaimedBatch,
+    guidance: Option<&str>,
+    user_id: Uuid,
+) -> AppResult<i32> {
+    let profile_skills = sqlx::query_scalar::<_, Vec<String>>("SELECT skills FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+    let learned = skills::load_progress(db_pool, user_id)
+        .await?
+        .into_iter()
+        .filter(|p| p.status == SkillProgressStatus::Known)
+        .map(|p| p.skill);
+    let current_skills = skills::merge_skills(&profile_skills, learned);
+
+    let provider = AIProvider::from_name(&batch.provider);
+    let response = ai
+        .process_action(AIActionRequest {
+            action: ActionType::GenerateRoadmap,
+            provider,
+            input: batch.target_role.clone(),
+            parameters: Some(json!({
+                "current_skills": current_skills.join(", "),
+                "timeframe_months": batch.timeframe_months,
+                "learning_hours_per_week": batch.learning_hours_per_week,
+                "persona": guidance,
+            })),
+            user_id: batch.requested_by,
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "Roadmap generation failed".to_string()),
+        ));
+    }
+
+    let roadmap_data = response.data;
+    let project_suggestions = roadmap_data.get("project_suggestions").cloned().unwrap_or(json!([]));
+    let job_application_timing = roadmap_data
+        .get("job_application_timing")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Apply after completing 60-70% of the roadmap");
+
+    let roadmap_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO career_roadmaps (
+            user_id, title, target_role, roadmap_data, ai_provider,
+            timeframe_months, learning_hours_per_week, current_skills,
+            project_suggestions, job_application_timing,
+            ai_model, prompt_version, generation_settings
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(format!("Roadmap to {}", batch.target_role))
+    .bind(&batch.target_role)
+    .bind(&roadmap_data)
+    .bind(&batch.provider)
+    .bind(batch.timeframe_months)
+    .bind(batch.learning_hours_per_week)
+    .bind(json!(current_skills))
+    .bind(&project_suggestions)
+    .bind(job_application_timing)
+    .bind(response.model)
+    .bind(response.prompt_version)
+    .bind(sqlx::types::Json(&response.settings))
+    .fetch_one(db_pool)
+    .await?;
+
+    if let Err(e) = skills::sync_roadmap_progress(db_pool, user_id).await {
+        warn!("Failed to sync skill progress for user {}: {}", user_id, e);
+    }
+    Ok(roadmap_id)
+}
@@ -0,0 +1,184 @@
+//! Institutions, student cohorts, and profile completeness.
+//!
+//! An organization represents an institution such as a university career
+//! office. Its staff are `organization_members` with an [`OrgRole`]; admins
+//! manage cohorts and advisors follow students. Students join a cohort with
+//! the cohort's join code, so nobody is enrolled without consent.
+//!
+//! A cohort can require a minimum profile completeness (0-100). Members below
+//! the highest requirement among their cohorts cannot apply to jobs through
+//! the platform until they fill in their profile; see [`ensure_may_apply`].
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::OrgRole;
+
+/// Columns of the `users` row aliased `u` that make up [`ProfileFields`].
+pub const PROFILE_FIELDS_SQL: &str = r#"
+    COALESCE(u.education_level, '') <> '' AS has_education,
+    u.experience_level IS NOT NULL AS has_experience_level,
+    u.preferred_track IS NOT NULL AS has_preferred_track,
+    cardinality(u.skills) > 0 AS has_skills,
+    cardinality(u.projects) > 0 AS has_projects,
+    cardinality(u.target_roles) > 0 AS has_target_roles,
+    COALESCE(u.raw_cv_text, '') <> '' AS has_cv,
+    (u.available_from IS NOT NULL OR cardinality(u.available_terms) > 0) AS has_availability
+"#;
+
+/// Which parts of a profile are filled in.
+#[derive(Debug, Clone, FromRow)]
+pub struct ProfileFields {
+    pub has_education: bool,
+    pub has_experience_level: bool,
+    pub has_preferred_track: bool,
+    pub has_skills: bool,
+    pub has_projects: bool,
+    pub has_target_roles: bool,
+    pub has_cv: bool,
+    pub has_availability: bool,
+}
+
+impl ProfileFields {
+    fn checks(&self) -> [(bool, &'static str); 8] {
+        [
+            (self.has_education, "education_level"),
+            (self.has_experience_level, "experience_level"),
+            (self.has_preferred_track, "preferred_track"),
+            (self.has_skills, "skills"),
+            (self.has_projects, "projects"),
+            (self.has_target_roles, "target_roles"),
+            (self.has_cv, "cv"),
+            (self.has_availability, "availability"),
+        ]
+    }
+
+    /// Percentage of profile sections filled in, rounded down.
+    pub fn score(&self) -> i32 {
+        let checks = self.checks();
+        let filled = checks.iter().filter(|(filled, _)| *filled).count();
+        (filled * 100 / checks.len()) as i32
+    }
+
+    /// Profile sections still empty.
+    pub fn missing(&self) -> Vec<&'static str> {
+        self.checks()
+            .into_iter()
+            .filter(|(filled, _)| !filled)
+            .map(|(_, name)| name)
+            .collect()
+    }
+}
+
+/// A user's profile completeness and the strictest cohort requirement.
+#[derive(Debug, Serialize)]
+pub struct ProfileCompleteness {
+    /// 0-100
+    pub score: i32,
+    /// Profile sections still empty
+    pub missing: Vec<&'static str>,
+    /// Highest minimum completeness among the user's cohorts
+    pub required: Option<i32>,
+    /// Cohort that sets `required`
+    pub required_by: Option<String>,
+    /// Whether the user may apply to jobs through the platform
+    pub may_apply: bool,
+}
+
+/// Computes a user's profile completeness against their cohorts' requirements.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user does not exist, or an error if a
+/// query fails.
+pub async fn profile_completeness(db_pool: &PgPool, user_id: Uuid) -> AppResult<ProfileCompleteness> {
+    let fields = sqlx::query_as::<_, ProfileFields>(&format!(
+        "SELECT {} FROM users u WHERE u.id = $1",
+        PROFILE_FIELDS_SQL
+    ))
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let requirement = sqlx::query_as::<_, (String, i32)>(
+        r#"
+        SELECT c.name, c.min_profile_completeness
+        FROM cohort_members m
+        JOIN cohorts c ON c.id = m.cohort_id
+        WHERE m.user_id = $1 AND c.min_profile_completeness > 0
+        ORDER BY c.min_profile_completeness DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?;
+
+    let score = fields.score();
+    let (required_by, required) = requirement.unzip();
+    Ok(ProfileCompleteness {
+        score,
+        missing: fields.missing(),
+        may_apply: required.is_none_or(|min| score >= min),
+        required,
+        required_by,
+    })
+}
+
+/// Rejects applications from cohort members whose profile is below their
+/// cohort's minimum completeness.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` naming the missing sections when the
+/// user may not apply yet, or an error if a query fails.
+pub async fn ensure_may_apply(db_pool: &PgPool, user_id: Uuid) -> AppResult<()> {
+    let completeness = profile_completeness(db_pool, user_id).await?;
+    if completeness.may_apply {
+        return Ok(());
+    }
+
+    Err(AppError::ValidationError(format!(
+        "Your profile is {}% complete; {} requires at least {}% before applying. Missing: {}",
+        completeness.score,
+        completeness.required_by.unwrap_or_default(),
+        completeness.required.unwrap_or_default(),
+        completeness.missing.join(", ")
+    )))
+}
+
+/// Returns the user's role in an organization if it is one of `allowed`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user is not on the organization's
+/// staff and `AppError::Forbidden` if their role is not allowed.
+pub async fn require_org_role(
+    db_pool: &PgPool,
+    org_id: i32,
+    user_id: Uuid,
+    allowed: &[OrgRole],
+) -> AppResult<OrgRole> {
+    let role = sqlx::query_scalar::<_, OrgRole>(
+        "SELECT role FROM organization_members WHERE org_id = $1 AND user_id = $2",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if allowed.contains(&role) {
+        Ok(role)
+    } else {
+        Err(AppError::Forbidden)
+    }
+}
+
+/// Generates a cohort join code students can type in.
+pub fn new_join_code() -> String {
+    Uuid::new_v4().simple().to_string()[..10].to_uppercase()
+}
@@ -0,0 +1,393 @@
+//! Candidate reviews of companies' hiring processes.
+//!
+//! A candidate whose application reached at least the interview stage can
+//! review the company once per application, rating the process overall, how
+//! quickly the company responded, and how transparent it was. Published
+//! reviews are shown on the company page without the reviewer's identity,
+//! with a [`ReviewSummary`] of the ratings. Anyone can report a review
+//! through [`crate::reports`]; enough open reports hide it until a moderator
+//! decides. The company may publish one reply to each review.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::CompanyReviewStatus;
+use crate::teams;
+
+/// Application statuses that show the candidate reached the interview stage.
+const REVIEWABLE_STATUSES: &[&str] = &["interview", "offer", "accepted", "declined"];
+
+/// Published reviews shown on the company page.
+const PAGE_REVIEWS: i64 = 10;
+
+/// A company review.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CompanyReview {
+    pub id: i32,
+    pub company_id: i32,
+    pub company_name: String,
+    pub job_title: String,
+    /// Furthest stage the application reached when reviewed
+    pub stage_reached: String,
+    pub overall_rating: i16,
+    pub response_time_rating: i16,
+    pub transparency_rating: i16,
+    /// Days the company took to first respond
+    pub response_days: Option<i16>,
+    pub headline: String,
+    pub body: String,
+    pub status: CompanyReviewStatus,
+    /// The company's reply
+    pub employer_reply: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Averages of a company's published reviews.
+#[derive(Debug, Default, Serialize, Deserialize, FromRow)]
+pub struct ReviewSummary {
+    pub reviews: i64,
+    pub overall_rating: Option<f64>,
+    pub response_time_rating: Option<f64>,
+    pub transparency_rating: Option<f64>,
+    /// Average days to first response, over reviews that gave one
+    pub response_days: Option<f64>,
+    /// Share of reviews the company replied to
+    pub reply_rate: Option<f64>,
+}
+
+/// A company's published reviews with their summary.
+#[derive(Debug, Serialize)]
+pub struct CompanyReviews {
+    pub summary: ReviewSummary,
+    pub reviews: Vec<CompanyReview>,
+}
+
+/// What the candidate writes in a review.
+#[derive(Debug)]
+pub struct ReviewDraft<'a> {
+    pub overall_rating: i16,
+    pub response_time_rating: i16,
+    pub transparency_rating: i16,
+    pub response_days: Option<i16>,
+    pub headline: &'a str,
+    pub body: &'a str,
+}
+
+const REVIEW_COLUMNS: &str = r#"
+    r.id, r.company_id, c.name AS company_name, r.job_title, r.stage_reached,
+    r.overall_rating, r.response_time_rating, r.transparency_rating, r.response_days,
+    r.headline, r.body, r.status, r.employer_reply, r.replied_at, r.created_at, r.updated_at
+"#;
+
+async fn fetch(db_pool: &PgPool, review_id: i32) -> AppResult<CompanyReview> {
+    sqlx::query_as::<_, CompanyReview>(&format!(
+        "SELECT {} FROM company_reviews r JOIN companies c ON c.id = r.company_id WHERE r.id = $1",
+        REVIEW_COLUMNS
+    ))
+    .bind(review_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Reviews the company behind one of the candidate's applications, and
+/// tells the company's owners.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the application is not the candidate's,
+/// `AppError::ValidationError` if the job has no company, the application
+/// did not reach the interview stage, or it was already reviewed, or an
+/// error if the database operation fails.
+pub async fn create(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    application_id: i32,
+    draft: &ReviewDraft<'_>,
+) -> AppResult<CompanyReview> {
+    let (company_id, job_title, status, has_offer) =
+        sqlx::query_as::<_, (Option<i32>, String, String, bool)>(
+            r#"
+            SELECT j.company_id, j.job_title, a.status,
+                   EXISTS (SELECT 1 FROM job_offers o WHERE o.application_id = a.id)
+            FROM application_tracking a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1 AND a.user_id = $2
+            "#,
+        )
+        .bind(application_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let company_id = company_id.ok_or_else(|| {
+        AppError::ValidationError("This job is not linked to a company on CareerBridge".to_string())
+    })?;
+    let stage_reached = if REVIEWABLE_STATUSES.contains(&status.as_str()) {
+        status
+    } else if has_offer {
+        "offer".to_string()
+    } else {
+        return Err(AppError::ValidationError(
+            "You can review a company once your application reaches the interview stage".to_string(),
+        ));
+    };
+
+    let review_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO company_reviews
+            (company_id, user_id, application_id, job_title, stage_reached, overall_rating,
+             response_time_rating, transparency_rating, response_days, headline, body)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (application_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(company_id)
+    .bind(user_id)
+    .bind(application_id)
+    .bind(&job_title)
+    .bind(&stage_reached)
+    .bind(draft.overall_rating)
+    .bind(draft.response_time_rating)
+    .bind(draft.transparency_rating)
+    .bind(draft.response_days)
+    .bind(draft.headline.trim())
+    .bind(draft.body.trim())
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or_else(|| AppError::ValidationError("You have already reviewed this application".to_string()))?;
+    info!("Review {} of company {} written by {}", review_id, company_id, user_id);
+
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, title, message, type)
+        SELECT m.user_id, 'New company review', $2, 'company_review'
+        FROM company_members m
+        WHERE m.company_id = $1 AND m.role = 'owner'
+        "#,
+    )
+    .bind(company_id)
+    .bind(format!(
+        "A candidate for {} reviewed your hiring process ({}/5). You can reply from your reviews page.",
+        job_title, draft.overall_rating
+    ))
+    .execute(db_pool)
+    .await
+    {
+        warn!("Failed to notify company {} about review {}: {}", company_id, review_id, e);
+    }
+
+    fetch(db_pool, review_id).await
+}
+
+/// Rewrites one of the candidate's reviews. A hidden review stays hidden
+/// until a moderator decides.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the review is not the candidate's or was
+/// removed, or an error if the database operation fails.
+pub async fn update(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    review_id: i32,
+    draft: &ReviewDraft<'_>,
+) -> AppResult<CompanyReview> {
+    let updated = sqlx::query(
+        r#"
+        UPDATE company_reviews
+        SET overall_rating = $3, response_time_rating = $4, transparency_rating = $5,
+            response_days = $6, headline = $7, body = $8, updated_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND status <> 'removed'
+        "#,
+    )
+    .bind(review_id)
+    .bind(user_id)
+    .bind(draft.overall_rating)
+    .bind(draft.response_time_rating)
+    .bind(draft.transparency_rating)
+    .bind(draft.response_days)
+    .bind(draft.headline.trim())
+    .bind(draft.body.trim())
+    .execute(db_pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    fetch(db_pool, review_id).await
+}
+
+/// Deletes one of the candidate's reviews.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the review is not the candidate's, or an
+/// error if the database operation fails.
+pub async fn delete(db_pool: &PgPool, user_id: Uuid, review_id: i32) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM company_reviews WHERE id = $1 AND user_id = $2")
+        .bind(review_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// The candidate's reviews, newest first, whatever their status.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_for_user(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<CompanyReview>> {
+    let reviews = sqlx::query_as::<_, CompanyReview>(&format!(
+        r#"
+        SELECT {} FROM company_reviews r JOIN companies c ON c.id = r.company_id
+        WHERE r.user_id = $1
+        ORDER BY r.created_at DESC
+        "#,
+        REVIEW_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(reviews)
+}
+
+/// A company's reviews, newest first. The public page shows published ones
+/// only; the company's team also sees reviews hidden by reports.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_for_company(
+    db_pool: &PgPool,
+    company_id: i32,
+    include_hidden: bool,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<CompanyReview>> {
+    let reviews = sqlx::query_as::<_, CompanyReview>(&format!(
+        r#"
+        SELECT {} FROM company_reviews r JOIN companies c ON c.id = r.company_id
+        WHERE r.company_id = $1
+          AND (r.status = 'published' OR ($2 AND r.status = 'hidden'))
+        ORDER BY r.created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        REVIEW_COLUMNS
+    ))
+    .bind(company_id)
+    .bind(include_hidden)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(reviews)
+}
+
+/// Averages of a company's published reviews.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn summary(db_pool: &PgPool, company_id: i32) -> AppResult<ReviewSummary> {
+    let summary = sqlx::query_as::<_, ReviewSummary>(
+        r#"
+        SELECT COUNT(*) AS reviews,
+               ROUND(AVG(overall_rating), 2)::float8 AS overall_rating,
+               ROUND(AVG(response_time_rating), 2)::float8 AS response_time_rating,
+               ROUND(AVG(transparency_rating), 2)::float8 AS transparency_rating,
+               ROUND(AVG(response_days), 1)::float8 AS response_days,
+               ROUND(AVG(CASE WHEN employer_reply IS NOT NULL THEN 1.0 ELSE 0.0 END), 2)::float8 AS reply_rate
+        FROM company_reviews
+        WHERE company_id = $1 AND status = 'published'
+        "#,
+    )
+    .bind(company_id)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(summary)
+}
+
+/// A company's summary and latest published reviews, as shown on its page.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn for_page(db_pool: &PgPool, company_id: i32) -> AppResult<CompanyReviews> {
+    Ok(CompanyReviews {
+        summary: summary(db_pool, company_id).await?,
+        reviews: list_for_company(db_pool, company_id, false, PAGE_REVIEWS, 0).await?,
+    })
+}
+
+/// Publishes or replaces the company's reply to a review, and tells the
+/// reviewer.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the review is not of the company or was
+/// removed, or an error if the database operation fails.
+pub async fn reply(
+    db_pool: &PgPool,
+    company_id: i32,
+    replier_id: Uuid,
+    review_id: i32,
+    reply: &str,
+) -> AppResult<CompanyReview> {
+    let (reviewer_id, first_reply) = sqlx::query_as::<_, (Uuid, bool)>(
+        r#"
+        UPDATE company_reviews r
+        SET employer_reply = $3, replied_by = $4, replied_at = NOW()
+        FROM company_reviews prev
+        WHERE r.id = $1 AND r.company_id = $2 AND r.status <> 'removed' AND prev.id = r.id
+        RETURNING r.user_id, prev.employer_reply IS NULL
+        "#,
+    )
+    .bind(review_id)
+    .bind(company_id)
+    .bind(reply.trim())
+    .bind(replier_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    teams::record_activity(
+        db_pool,
+        replier_id,
+        company_id,
+        "review.reply",
+        "company_review",
+        &review_id.to_string(),
+        json!({ "first_reply": first_reply }),
+    )
+    .await;
+
+    if first_reply
+        && let Err(e) = sqlx::query(
+            r#"
+            INSERT INTO notifications (user_id, title, message, type)
+            SELECT $1, 'The company replied to your review', c.name || ' replied to your review of its hiring process.', 'company_review'
+            FROM companies c WHERE c.id = $2
+            "#,
+        )
+        .bind(reviewer_id)
+        .bind(company_id)
+        .execute(db_pool)
+        .await
+    {
+        warn!("Failed to notify reviewer {} about reply to review {}: {}", reviewer_id, review_id, e);
+    }
+
+    fetch(db_pool, review_id).await
+}
@@ -0,0 +1,624 @@
+//! Mentor conversations: history, context windows, export, and summaries.
+//!
+//! A conversation is started by saving a mentor question, or created empty
+//! and then continued one message at a time. Questions asked with a
+//! `conversation_id` are answered with the conversation's history. The history sent is budgeted in tokens per model
+//! (see [`context_tokens`]): recent turns go verbatim while they fit, and
+//! once they no longer do, the older ones are compressed by the AI into a
+//! running summary stored on the conversation and sent in their place.
+//!
+//! Long mentor chats are also hard to navigate, so a conversation can be
+//! exported as Markdown (or JSON) and summarized by the AI into bullet
+//! points of the advice received. The summary is stored on the
+//! conversation along with the number of messages it covered; once new
+//! messages arrive it is reported as stale until summarized again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::preflight::estimate_tokens;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType, ChatMessage, ChatRole};
+use crate::errors::{AppError, AppResult};
+
+/// Most transcript characters sent for summarization; older messages are
+/// dropped first.
+const MAX_TRANSCRIPT_CHARS: usize = 40_000;
+
+/// Context budget in tokens per model, unless `MENTOR_CONTEXT_TOKENS`
+/// overrides it. Well under the models' limits, to keep calls fast and cheap.
+const DEFAULT_CONTEXT_TOKENS: &[(&str, usize)] = &[
+    ("gemini-2.0-flash", 32_000),
+    ("llama-3.3-70b-versatile", 8_000),
+    ("mock", 4_000),
+];
+
+/// Context budget for models without a default.
+const FALLBACK_CONTEXT_TOKENS: usize = 8_000;
+
+/// Tokens of the budget kept for the prompt template and the answer.
+const RESERVED_TOKENS: usize = 2_500;
+
+/// Most tokens of older turns folded into the running summary at once;
+/// beyond that the oldest are dropped.
+const MAX_COMPRESS_TOKENS: usize = 12_000;
+
+/// Longest title of a conversation started from a question, in characters.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Title of a conversation created without one.
+const DEFAULT_TITLE: &str = "New conversation";
+
+/// AI summary of the advice given in a conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    /// One bullet per piece of advice, in the order it was given
+    pub points: Vec<String>,
+    /// Concrete next steps the user was advised to take
+    #[serde(default)]
+    pub action_items: Vec<String>,
+}
+
+impl ConversationSummary {
+    /// Parses the summarize action's response, dropping empty bullets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response has no advice points.
+    pub fn from_response(data: &Value) -> AppResult<Self> {
+        let bullets = |key: &str| -> Vec<String> {
+            data.get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let summary = Self {
+            points: bullets("points"),
+            action_items: bullets("action_items"),
+        };
+        if summary.points.is_empty() {
+            return Err(AppError::ExternalServiceError(
+                "AI returned an empty conversation summary".to_string(),
+            ));
+        }
+        Ok(summary)
+    }
+}
+
+/// One message of a conversation.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MentorMessage {
+    pub id: i32,
+    /// `user` or `assistant`
+    pub role: String,
+    pub content: String,
+    /// Sections of an answer continued past the provider's output limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sections: Option<Value>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl MentorMessage {
+    fn speaker(&self) -> &'static str {
+        if self.role == "assistant" { "Mentor" } else { "You" }
+    }
+
+    /// The message as one transcript line, e.g. `Mentor: ...`.
+    fn to_turn(&self) -> String {
+        format!("{}: {}", self.speaker(), self.content.trim())
+    }
+}
+
+/// A conversation in the user's list, without its messages.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ConversationListing {
+    pub id: i32,
+    pub title: String,
+    pub message_count: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A mentor conversation with its messages, oldest first.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Conversation {
+    pub id: i32,
+    pub title: String,
+    pub summary: Option<sqlx::types::Json<ConversationSummary>>,
+    /// Messages the summary covered
+    pub summary_message_count: Option<i32>,
+    pub summarized_at: Option<DateTime<Utc>>,
+    /// Whether messages were added since the summary was made
+    #[sqlx(skip)]
+    pub summary_stale: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub messages: Vec<MentorMessage>,
+}
+
+impl Conversation {
+    /// Renders the conversation as Markdown, with the summary (if any)
+    /// ahead of the messages.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title.trim());
+        if let Some(created_at) = self.created_at {
+            out.push_str(&format!("_Started {}_\n\n", created_at.format("%Y-%m-%d %H:%M UTC")));
+        }
+
+        if let Some(summary) = &self.summary {
+            out.push_str("## Summary\n\n");
+            if self.summary_stale {
+                out.push_str("_Summary predates the latest messages._\n\n");
+            }
+            for point in &summary.points {
+                out.push_str(&format!("- {}\n", point));
+            }
+            if !summary.action_items.is_empty() {
+                out.push_str("\n### Next steps\n\n");
+                for item in &summary.action_items {
+                    out.push_str(&format!("- [ ] {}\n", item));
+                }
+            }
+            out.push('\n');
+        }
+
+        out.push_str("## Conversation\n");
+        for message in &self.messages {
+            out.push_str(&format!("\n### {}", message.speaker()));
+            if let Some(created_at) = message.created_at {
+                out.push_str(&format!(" ({})", created_at.format("%Y-%m-%d %H:%M")));
+            }
+            out.push_str(&format!("\n\n{}\n", message.content.trim()));
+        }
+        out
+    }
+
+    /// Builds the transcript sent for summarization. Keeps the most recent
+    /// messages that fit in [`MAX_TRANSCRIPT_CHARS`], noting any omitted.
+    pub fn transcript(&self) -> String {
+        let mut blocks = Vec::new();
+        let mut length = 0;
+        for message in self.messages.iter().rev() {
+            let block = message.to_turn();
+            length += block.chars().count() + 2;
+            if length > MAX_TRANSCRIPT_CHARS && !blocks.is_empty() {
+                break;
+            }
+            blocks.push(block);
+        }
+
+        let omitted = self.messages.len() - blocks.len();
+        if omitted > 0 {
+            blocks.push(format!("[{} earlier messages omitted]", omitted));
+        }
+        blocks.reverse();
+        blocks.join("\n\n")
+    }
+}
+
+/// Loads one of the user's conversations with its messages.
+///
+/// # Errors
+///
+/// Returns `NotFound` if the conversation does not exist or belongs to
+/// another user, or an error if the database operation fails.
+pub async fn load(db_pool: &PgPool, user_id: Uuid, conversation_id: i32) -> AppResult<Conversation> {
+    let mut conversation = sqlx::query_as::<_, Conversation>(
+        r#"
+        SELECT id, title, summary, summary_message_count, summarized_at, created_at, updated_at
+        FROM mentor_conversations
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(conversation_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    conversation.messages = sqlx::query_as::<_, MentorMessage>(
+        r#"
+        SELECT id, role, content, sections, created_at
+        FROM mentor_messages
+        WHERE conversation_id = $1
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(conversation_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    conversation.summary_stale = conversation.summary.is_some()
+        && conversation.summary_message_count != Some(conversation.messages.len() as i32);
+    Ok(conversation)
+}
+
+/// Stores a summary covering the conversation's current messages.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn save_summary(
+    db_pool: &PgPool,
+    conversation: &mut Conversation,
+    summary: ConversationSummary,
+) -> AppResult<()> {
+    let message_count = conversation.messages.len() as i32;
+    let summarized_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+        r#"
+        UPDATE mentor_conversations
+        SET summary = $2, summary_message_count = $3, summarized_at = NOW()
+        WHERE id = $1
+        RETURNING summarized_at
+        "#,
+    )
+    .bind(conversation.id)
+    .bind(sqlx::types::Json(&summary))
+    .bind(message_count)
+    .fetch_one(db_pool)
+    .await?;
+
+    conversation.summary = Some(sqlx::types::Json(summary));
+    conversation.summary_message_count = Some(message_count);
+    conversation.summarized_at = Some(summarized_at);
+    conversation.summary_stale = false;
+    Ok(())
+}
+
+/// Context budget in tokens for a model.
+///
+/// `MENTOR_CONTEXT_TOKENS` overrides the defaults per model, as
+/// comma-separated `model=tokens` pairs (e.g.
+/// `gemini-2.0-flash=64000,llama-3.3-70b-versatile=6000`).
+pub fn context_tokens(model: &str) -> usize {
+    std::env::var("MENTOR_CONTEXT_TOKENS")
+        .ok()
+        .and_then(|pairs| {
+            pairs.split(',').find_map(|pair| {
+                let (name, tokens) = pair.split_once('=')?;
+                if name.trim() == model { tokens.trim().parse().ok() } else { None }
+            })
+        })
+        .or_else(|| {
+            DEFAULT_CONTEXT_TOKENS
+                .iter()
+                .find(|(name, _)| *name == model)
+                .map(|(_, tokens)| *tokens)
+        })
+        .unwrap_or(FALLBACK_CONTEXT_TOKENS)
+}
+
+/// Tokens left for conversation history once the question and profile
+/// context are sent.
+pub fn history_budget(model: &str, question: &str, context: &str) -> usize {
+    context_tokens(model)
+        .saturating_sub(RESERVED_TOKENS + estimate_tokens(question) + estimate_tokens(context))
+}
+
+/// Conversation history to send with the next question.
+#[derive(Debug, Default)]
+pub struct MentorContext {
+    /// Running summary of turns no longer sent verbatim
+    pub summary: Option<String>,
+    /// Most recent turns, oldest first
+    pub turns: Vec<MentorMessage>,
+}
+
+impl MentorContext {
+    /// The history as chat messages: the summary as a system message, then
+    /// the turns. Empty for a new conversation.
+    ///
+    /// Turns are trimmed to the budget one message at a time, so the oldest
+    /// kept turn may be a reply whose question was cut; it is dropped, and
+    /// consecutive messages from the same side are merged, so the turns
+    /// alternate from the user as providers require. A question left
+    /// unanswered at the end is left out, since the new question follows.
+    pub fn to_messages(&self) -> Vec<ChatMessage> {
+        let mut messages = Vec::new();
+        if let Some(summary) = &self.summary {
+            messages.push(ChatMessage::system(format!("Summary of earlier turns: {}", summary)));
+        }
+        let mut turns: Vec<ChatMessage> = Vec::new();
+        for turn in self.turns.iter().skip_while(|t| t.role == "assistant") {
+            let role = if turn.role == "assistant" { ChatRole::Assistant } else { ChatRole::User };
+            match turns.last_mut() {
+                Some(last) if last.role == role => {
+                    last.content.push_str("\n\n");
+                    last.content.push_str(turn.content.trim());
+                }
+                _ => turns.push(ChatMessage { role, content: turn.content.trim().to_string() }),
+            }
+        }
+        if turns.last().is_some_and(|t| t.role == ChatRole::User) {
+            turns.pop();
+        }
+        messages.extend(turns);
+        messages
+    }
+}
+
+/// Starts a conversation for the user, titled after its first question.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn create(db_pool: &PgPool, user_id: Uuid, first_question: &str) -> AppResult<i32> {
+    let question = first_question.trim();
+    let mut title: String = question.chars().take(MAX_TITLE_CHARS).collect();
+    if question.chars().count() > MAX_TITLE_CHARS {
+        title.push('…');
+    }
+
+    let id = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO mentor_conversations (user_id, title) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(title)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(id)
+}
+
+/// Creates an empty conversation for the user.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn create_empty(db_pool: &PgPool, user_id: Uuid, title: Option<&str>) -> AppResult<Conversation> {
+    let title = title.map(str::trim).filter(|t| !t.is_empty()).unwrap_or(DEFAULT_TITLE);
+    let conversation = sqlx::query_as::<_, Conversation>(
+        r#"
+        INSERT INTO mentor_conversations (user_id, title)
+        VALUES ($1, $2)
+        RETURNING id, title, summary, summary_message_count, summarized_at, created_at, updated_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(title)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(conversation)
+}
+
+/// The user's conversations, most recently active first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<ConversationListing>> {
+    let conversations = sqlx::query_as::<_, ConversationListing>(
+        r#"
+        SELECT c.id, c.title, COUNT(m.id) AS message_count, c.created_at, c.updated_at
+        FROM mentor_conversations c
+        LEFT JOIN mentor_messages m ON m.conversation_id = c.id
+        WHERE c.user_id = $1
+        GROUP BY c.id
+        ORDER BY c.updated_at DESC NULLS LAST, c.id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(conversations)
+}
+
+/// Deletes one of the user's conversations with its messages.
+///
+/// # Errors
+///
+/// Returns `NotFound` if the conversation does not exist or belongs to
+/// another user, or an error if the database operation fails.
+pub async fn delete(db_pool: &PgPool, user_id: Uuid, conversation_id: i32) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM mentor_conversations WHERE id = $1 AND user_id = $2")
+        .bind(conversation_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+    info!("User {} deleted conversation {}", user_id, conversation_id);
+    Ok(())
+}
+
+/// Checks that a conversation exists and belongs to the user.
+///
+/// # Errors
+///
+/// Returns `NotFound` if it does not, or an error if the database
+/// operation fails.
+pub async fn ensure_owned(db_pool: &PgPool, user_id: Uuid, conversation_id: i32) -> AppResult<()> {
+    let owned = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM mentor_conversations WHERE id = $1 AND user_id = $2)",
+    )
+    .bind(conversation_id)
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await?;
+    if !owned {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Appends a question and the mentor's answer to a conversation, with the
+/// answer's `sections` when it was continued past the provider's output
+/// limit.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn append_exchange(
+    db_pool: &PgPool,
+    conversation_id: i32,
+    question: &str,
+    answer: &str,
+    sections: Option<&Value>,
+) -> AppResult<()> {
+    let mut tx = db_pool.begin().await?;
+    for (role, content, sections) in [("user", question, None), ("assistant", answer, sections)] {
+        sqlx::query("INSERT INTO mentor_messages (conversation_id, role, content, sections) VALUES ($1, $2, $3, $4)")
+            .bind(conversation_id)
+            .bind(role)
+            .bind(content)
+            .bind(sections)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query("UPDATE mentor_conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(conversation_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Assembles the history that fits in `budget_tokens`.
+///
+/// Turns after the running summary are sent verbatim while they fit. When
+/// they no longer do, all but the most recent turns filling half the budget
+/// are folded into the running summary, so the next few questions fit
+/// without compressing again. If compression fails, the older turns are
+/// left out of this request and compression is retried next time.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn assemble_context(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    conversation_id: i32,
+    budget_tokens: usize,
+) -> AppResult<MentorContext> {
+    let (summary, summary_through) = sqlx::query_as::<_, (Option<String>, Option<i32>)>(
+        "SELECT context_summary, context_summary_through FROM mentor_conversations WHERE id = $1",
+    )
+    .bind(conversation_id)
+    .fetch_one(db_pool)
+    .await?;
+
+    let mut turns = sqlx::query_as::<_, MentorMessage>(
+        r#"
+        SELECT id, role, content, sections, created_at
+        FROM mentor_messages
+        WHERE conversation_id = $1 AND id > COALESCE($2, 0)
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(conversation_id)
+    .bind(summary_through)
+    .fetch_all(db_pool)
+    .await?;
+
+    let available = budget_tokens.saturating_sub(summary.as_deref().map_or(0, estimate_tokens));
+    if recent_turns(&turns, available) == turns.len() {
+        return Ok(MentorContext { summary, turns });
+    }
+
+    let kept = recent_turns(&turns, available / 2);
+    let recent = turns.split_off(turns.len() - kept);
+    let older = turns;
+
+    match compress(ai, provider, summary.as_deref(), &older).await {
+        Some(compressed) => {
+            let through = older.last().map(|m| m.id);
+            sqlx::query(
+                "UPDATE mentor_conversations SET context_summary = $2, context_summary_through = $3 WHERE id = $1",
+            )
+            .bind(conversation_id)
+            .bind(&compressed)
+            .bind(through)
+            .execute(db_pool)
+            .await?;
+            info!(
+                "Compressed {} turns of conversation {} into its running summary",
+                older.len(),
+                conversation_id
+            );
+            Ok(MentorContext { summary: Some(compressed), turns: recent })
+        }
+        None => {
+            // Send what fits; the older turns stay uncompressed for a retry
+            let mut turns = older;
+            turns.extend(recent);
+            let fits = recent_turns(&turns, available);
+            let turns = turns.split_off(turns.len() - fits);
+            Ok(MentorContext { summary, turns })
+        }
+    }
+}
+
+/// Number of most recent turns that fit in `budget_tokens`.
+fn recent_turns(turns: &[MentorMessage], budget_tokens: usize) -> usize {
+    let mut used = 0;
+    turns
+        .iter()
+        .rev()
+        .take_while(|turn| {
+            used += estimate_tokens(&turn.content) + 4;
+            used <= budget_tokens
+        })
+        .count()
+}
+
+/// Folds older turns into the running summary, or `None` if the AI call
+/// fails.
+async fn compress(
+    ai: &AIService,
+    provider: AIProvider,
+    summary: Option<&str>,
+    older: &[MentorMessage],
+) -> Option<String> {
+    let kept = recent_turns(older, MAX_COMPRESS_TOKENS);
+    let mut blocks = Vec::new();
+    if let Some(summary) = summary {
+        blocks.push(format!("Summary so far: {}", summary));
+    }
+    if kept < older.len() {
+        blocks.push(format!("[{} earlier messages omitted]", older.len() - kept));
+    }
+    blocks.extend(older[older.len() - kept..].iter().map(MentorMessage::to_turn));
+
+    let request = AIActionRequest {
+        action: ActionType::CompressConversation,
+        provider,
+        input: blocks.join("\n\n"),
+        parameters: None,
+        user_id: None,
+        capture: true,
+    };
+
+    let response = match ai.process_action(request).await {
+        Ok(response) if response.success => response.data,
+        Ok(response) => {
+            debug!("Conversation compression unavailable: {:?}", response.message);
+            return None;
+        }
+        Err(e) => {
+            debug!("Conversation compression unavailable: {}", e);
+            return None;
+        }
+    };
+    response
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|summary| !summary.is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,328 @@
+//! Usage-based credits ledger for AI features.
+//!
+//! Each user has a `credit_accounts` balance backed by an append-only
+//! `credit_transactions` history. Credits are earned through a signup bonus,
+//! referrals, and achievements, and spent on AI actions at the per-action
+//! cost in `credit_action_costs`.
+//!
+//! When `CREDITS_ENABLED` is set, [`crate::ai::AIService`] debits the action
+//! cost through a [`CreditLedger`] before calling the provider and refunds it
+//! if the call fails. Debits are a single conditional `UPDATE`, so concurrent
+//! requests can never drive a balance below zero.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::activity::{self, ActivityType};
+use crate::errors::{AppError, AppResult};
+
+/// Default credits granted when a user's account is first created.
+const DEFAULT_SIGNUP_BONUS: i32 = 50;
+
+/// Credits granted to a referrer for each new user who signs up with their code.
+pub const REFERRAL_REWARD: i32 = 25;
+
+/// Milestones that earn credits, each granted at most once.
+#[derive(Debug, Clone, Copy)]
+pub enum Achievement {
+    /// Completed onboarding
+    ProfileCompleted,
+    /// Marked every phase of the given roadmap completed. Worth less than
+    /// generating a roadmap, so generating and completing roadmaps cannot
+    /// farm credits
+    RoadmapCompleted(i32),
+}
+
+impl Achievement {
+    /// Credits granted for the achievement.
+    pub fn reward(self) -> i32 {
+        match self {
+            Achievement::ProfileCompleted => 10,
+            Achievement::RoadmapCompleted(_) => 3,
+        }
+    }
+
+    fn reference(self) -> String {
+        match self {
+            Achievement::ProfileCompleted => "profile_completed".to_string(),
+            Achievement::RoadmapCompleted(id) => format!("roadmap_completed:{}", id),
+        }
+    }
+}
+
+/// A single ledger entry.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CreditTransaction {
+    pub id: i64,
+    pub amount: i32,
+    pub reason: String,
+    pub reference: Option<String>,
+    pub balance_after: i32,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A user's balance, referral code, and recent transactions.
+#[derive(Debug, Serialize)]
+pub struct CreditSummary {
+    pub balance: i32,
+    pub referral_code: String,
+    pub transactions: Vec<CreditTransaction>,
+}
+
+/// Reads `CREDITS_SIGNUP_BONUS` (default: 50).
+fn signup_bonus() -> i32 {
+    std::env::var("CREDITS_SIGNUP_BONUS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|v| *v >= 0)
+        .unwrap_or(DEFAULT_SIGNUP_BONUS)
+}
+
+/// Creates the user's account (with the signup bonus) if it does not exist yet.
+async fn ensure_account(conn: &mut PgConnection, user_id: Uuid) -> AppResult<()> {
+    let referral_code = Uuid::new_v4().simple().to_string()[..8].to_uppercase();
+
+    let created = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO credit_accounts (user_id, balance, referral_code)
+        VALUES ($1, 0, $2)
+        ON CONFLICT (user_id) DO NOTHING
+        RETURNING user_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(&referral_code)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let bonus = signup_bonus();
+    if created.is_some() && bonus > 0 {
+        apply(conn, user_id, bonus, "signup_bonus", Some("signup")).await?;
+    }
+    Ok(())
+}
+
+/// Adjusts the balance by `amount` and records the transaction.
+///
+/// Returns the new balance, or `None` if a debit would overdraw the account.
+async fn apply(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    amount: i32,
+    reason: &str,
+    reference: Option<&str>,
+) -> AppResult<Option<i32>> {
+    let balance = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE credit_accounts
+        SET balance = balance + $2, updated_at = CURRENT_TIMESTAMP
+        WHERE user_id = $1 AND balance + $2 >= 0
+        RETURNING balance
+        "#,
+    )
+    .bind(user_id)
+    .bind(amount)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(balance_after) = balance {
+        sqlx::query(
+            r#"
+            INSERT INTO credit_transactions (user_id, amount, reason, reference, balance_after)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(amount)
+        .bind(reason)
+        .bind(reference)
+        .bind(balance_after)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(balance)
+}
+
+/// Grants `amount` credits to a user.
+///
+/// Signup bonuses, referrals, and achievements are granted at most once per
+/// `reference`; repeat grants are ignored.
+///
+/// # Returns
+///
+/// `true` if the credits were granted, `false` if this grant was already made.
+pub async fn grant(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    amount: i32,
+    reason: &str,
+    reference: &str,
+) -> AppResult<bool> {
+    let mut tx = db_pool.begin().await?;
+    ensure_account(&mut tx, user_id).await?;
+
+    match apply(&mut tx, user_id, amount, reason, Some(reference)).await {
+        Ok(_) => {
+            tx.commit().await?;
+            info!("Granted {} credits to {} ({}: {})", amount, user_id, reason, reference);
+            Ok(true)
+        }
+        Err(AppError::DatabaseError(e))
+            if e.as_database_error().is_some_and(|d| d.is_unique_violation()) =>
+        {
+            debug!("Credits already granted to {} ({}: {})", user_id, reason, reference);
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Grants the reward for an achievement, logging rather than failing on errors.
+///
+/// Achievements are a side effect of other requests, so a ledger problem
+/// must not fail the request that earned them.
+pub async fn award_achievement(db_pool: &PgPool, user_id: Uuid, achievement: Achievement) {
+    let reference = achievement.reference();
+    match grant(db_pool, user_id, achievement.reward(), "achievement", &reference).await {
+        Ok(true) => {
+            activity::record(
+                db_pool,
+                user_id,
+                ActivityType::BadgeEarned,
+                json!({ "badge": reference, "credits": achievement.reward() }),
+            )
+            .await;
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Failed to award achievement {} to {}: {}", reference, user_id, e),
+    }
+}
+
+/// Rewards the owner of `referral_code` for referring `new_user_id`.
+///
+/// Unknown codes are ignored. Errors are logged rather than returned so a
+/// bad code never blocks registration.
+pub async fn redeem_referral(db_pool: &PgPool, new_user_id: Uuid, referral_code: &str) {
+    let referrer = sqlx::query_scalar::<_, Uuid>(
+        "SELECT user_id FROM credit_accounts WHERE referral_code = $1",
+    )
+    .bind(referral_code.trim().to_uppercase())
+    .fetch_optional(db_pool)
+    .await;
+
+    match referrer {
+        Ok(Some(referrer)) if referrer != new_user_id => {
+            let reference = new_user_id.to_string();
+            if let Err(e) = grant(db_pool, referrer, REFERRAL_REWARD, "referral", &reference).await {
+                warn!("Failed to reward referral of {} by {}: {}", new_user_id, referrer, e);
+            }
+        }
+        Ok(_) => debug!("Ignoring unknown referral code {}", referral_code),
+        Err(e) => warn!("Referral lookup failed: {}", e),
+    }
+}
+
+/// Returns the user's balance, referral code, and latest `limit` transactions.
+pub async fn summary(db_pool: &PgPool, user_id: Uuid, limit: i64) -> AppResult<CreditSummary> {
+    let mut conn = db_pool.acquire().await?;
+    ensure_account(&mut conn, user_id).await?;
+
+    let (balance, referral_code) = sqlx::query_as::<_, (i32, String)>(
+        "SELECT balance, referral_code FROM credit_accounts WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let transactions = sqlx::query_as::<_, CreditTransaction>(
+        r#"
+        SELECT id, amount, reason, reference, balance_after, created_at
+        FROM credit_transactions
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(CreditSummary {
+        balance,
+        referral_code,
+        transactions,
+    })
+}
+
+/// Charges and refunds AI actions against users' credit balances.
+#[derive(Clone)]
+pub struct CreditLedger {
+    db_pool: PgPool,
+}
+
+impl CreditLedger {
+    /// Create a ledger backed by `db_pool`
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Debits the cost of `action` from the user's balance.
+    ///
+    /// Actions without a configured cost are free.
+    ///
+    /// # Returns
+    ///
+    /// The number of credits charged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::PaymentRequired` if the balance is too low.
+    pub async fn debit_action(&self, user_id: Uuid, action: &str) -> AppResult<i32> {
+        let cost = sqlx::query_scalar::<_, i32>("SELECT cost FROM credit_action_costs WHERE action = $1")
+            .bind(action)
+            .fetch_optional(&self.db_pool)
+            .await?
+            .unwrap_or(0);
+        if cost == 0 {
+            return Ok(0);
+        }
+
+        let mut tx = self.db_pool.begin().await?;
+        ensure_account(&mut tx, user_id).await?;
+        let balance = apply(&mut tx, user_id, -cost, "ai_action", Some(action)).await?;
+        tx.commit().await?;
+
+        match balance {
+            Some(balance) => {
+                debug!("Charged {} credits to {} for {} (balance {})", cost, user_id, action, balance);
+                Ok(cost)
+            }
+            None => Err(AppError::PaymentRequired(format!(
+                "Not enough credits: {} costs {} credits",
+                action, cost
+            ))),
+        }
+    }
+
+    /// Returns credits charged for an action whose provider call failed.
+    ///
+    /// Errors are logged rather than returned, since the caller is already
+    /// handling the provider failure.
+    pub async fn refund_action(&self, user_id: Uuid, action: &str, cost: i32) {
+        let result = async {
+            let mut conn = self.db_pool.acquire().await?;
+            apply(&mut conn, user_id, cost, "refund", Some(action)).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to refund {} credits to {} for {}: {}", cost, user_id, action, e);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Hand curation of profile skills and target roles.
+//!
+//! AI extraction only ever adds to `users.skills` and `users.target_roles`,
+//! so users remove and rename items here. Removed items, and the old names
+//! of renamed ones, are remembered in `user_rejected_profile_items`; AI
+//! merges drop rejected items through [`RejectedItems`], so junk removed
+//! once does not come back with the next extraction. Adding an item by hand
+//! (renaming to it, or rating a skill) takes it off the rejected list.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileItemKind;
+
+/// Longest skill or target role.
+pub const MAX_ITEM_CHARS: usize = 100;
+
+/// Changes to one curated list.
+#[derive(Debug, Default)]
+pub struct Curation {
+    /// Items to remove, matched case-insensitively
+    pub remove: Vec<String>,
+    /// `(from, to)` renames, matched case-insensitively on `from`
+    pub rename: Vec<(String, String)>,
+    /// Whether removed items and old names are rejected for AI merges
+    pub reject: bool,
+}
+
+/// A curated list after a change.
+#[derive(Debug, Serialize)]
+pub struct CuratedItems {
+    pub kind: ProfileItemKind,
+    /// The list as saved
+    pub items: Vec<String>,
+    /// Removed or renamed items that were not on the list
+    pub not_found: Vec<String>,
+}
+
+/// An item the user removed, kept out of AI merges.
+#[derive(Debug, Serialize, FromRow)]
+pub struct RejectedItem {
+    pub kind: ProfileItemKind,
+    pub value: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user's rejected items, for filtering AI merges.
+#[derive(Debug, Default)]
+pub struct RejectedItems {
+    skills: HashSet<String>,
+    target_roles: HashSet<String>,
+}
+
+impl RejectedItems {
+    /// Drops rejected items from `items`, matching case-insensitively.
+    pub fn filter(&self, kind: ProfileItemKind, items: Vec<String>) -> Vec<String> {
+        let rejected = match kind {
+            ProfileItemKind::Skill => &self.skills,
+            ProfileItemKind::TargetRole => &self.target_roles,
+        };
+        items
+            .into_iter()
+            .filter(|item| !rejected.contains(&item.trim().to_lowercase()))
+            .collect()
+    }
+}
+
+fn column(kind: ProfileItemKind) -> &'static str {
+    match kind {
+        ProfileItemKind::Skill => "skills",
+        ProfileItemKind::TargetRole => "target_roles",
+    }
+}
+
+/// A trimmed item, checked for length.
+pub fn clean_item(value: &str) -> AppResult<String> {
+    let value = value.trim();
+    if value.is_empty() || value.chars().count() > MAX_ITEM_CHARS {
+        return Err(AppError::ValidationError(format!(
+            "Skills and target roles must be between 1 and {} characters",
+            MAX_ITEM_CHARS
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Applies removals and renames to one of the user's lists.
+///
+/// Removals run first, then renames; a rename onto an item already on the
+/// list merges the two. For skills, removed skills lose their proficiency
+/// rating and renamed ones keep it. Call inside a transaction: the user's
+/// row stays locked until it ends.
+pub async fn curate(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    kind: ProfileItemKind,
+    curation: &Curation,
+) -> AppResult<CuratedItems> {
+    let current = sqlx::query_scalar::<_, Vec<String>>(&format!(
+        "SELECT {} FROM users WHERE id = $1 FOR UPDATE",
+        column(kind)
+    ))
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let held: HashSet<String> = current.iter().map(|i| i.to_lowercase()).collect();
+    let removed: HashSet<String> = curation.remove.iter().map(|i| i.to_lowercase()).collect();
+    let renames: HashMap<String, &str> = curation
+        .rename
+        .iter()
+        .map(|(from, to)| (from.to_lowercase(), to.as_str()))
+        .collect();
+    let not_found: Vec<String> = curation
+        .remove
+        .iter()
+        .chain(curation.rename.iter().map(|(from, _)| from))
+        .filter(|item| !held.contains(&item.to_lowercase()))
+        .cloned()
+        .collect();
+
+    let mut seen = HashSet::new();
+    let items: Vec<String> = current
+        .iter()
+        .filter(|item| !removed.contains(&item.to_lowercase()))
+        .map(|item| renames.get(&item.to_lowercase()).map_or_else(|| item.clone(), |to| to.to_string()))
+        .filter(|item| seen.insert(item.to_lowercase()))
+        .collect();
+
+    sqlx::query(&format!(
+        "UPDATE users SET {} = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        column(kind)
+    ))
+    .bind(user_id)
+    .bind(&items)
+    .execute(&mut *conn)
+    .await?;
+
+    // A case-only rename keeps the item, so it is not rejected
+    let targets: Vec<String> = curation.rename.iter().map(|(_, to)| to.clone()).collect();
+    accept(&mut *conn, user_id, kind, &targets).await?;
+    if curation.reject {
+        let rejected: Vec<String> = curation
+            .remove
+            .iter()
+            .chain(
+                curation
+                    .rename
+                    .iter()
+                    .filter(|(from, to)| from.to_lowercase() != to.to_lowercase())
+                    .map(|(from, _)| from),
+            )
+            .cloned()
+            .collect();
+        sqlx::query(
+            r#"
+            INSERT INTO user_rejected_profile_items (user_id, kind, value)
+            SELECT $1, $2, value FROM UNNEST($3::TEXT[]) AS r(value)
+            ON CONFLICT (user_id, kind, LOWER(value)) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(&rejected)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    if kind == ProfileItemKind::Skill {
+        for (from, to) in &curation.rename {
+            sqlx::query(
+                r#"
+                UPDATE user_skill_ratings SET skill = $3, updated_at = NOW()
+                WHERE user_id = $1 AND LOWER(skill) = LOWER($2)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM user_skill_ratings
+                      WHERE user_id = $1 AND LOWER(skill) = LOWER($3) AND LOWER(skill) <> LOWER($2)
+                  )
+                "#,
+            )
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .execute(&mut *conn)
+            .await?;
+        }
+        let dropped: Vec<String> = curation
+            .remove
+            .iter()
+            .chain(curation.rename.iter().map(|(from, _)| from))
+            .map(|item| item.to_lowercase())
+            .filter(|item| !seen.contains(item))
+            .collect();
+        sqlx::query("DELETE FROM user_skill_ratings WHERE user_id = $1 AND LOWER(skill) = ANY($2)")
+            .bind(user_id)
+            .bind(&dropped)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(CuratedItems { kind, items, not_found })
+}
+
+/// Takes items the user added by hand off the rejected list.
+pub async fn accept(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    kind: ProfileItemKind,
+    items: &[String],
+) -> AppResult<()> {
+    let items: Vec<String> = items.iter().map(|i| i.to_lowercase()).collect();
+    sqlx::query(
+        "DELETE FROM user_rejected_profile_items WHERE user_id = $1 AND kind = $2 AND LOWER(value) = ANY($3)",
+    )
+    .bind(user_id)
+    .bind(kind)
+    .bind(&items)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Lists the user's rejected items, alphabetically per kind.
+pub async fn list_rejected(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<RejectedItem>> {
+    let items = sqlx::query_as::<_, RejectedItem>(
+        r#"
+        SELECT kind, value, created_at
+        FROM user_rejected_profile_items
+        WHERE user_id = $1
+        ORDER BY kind, LOWER(value)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(items)
+}
+
+/// Loads the user's rejected items for filtering an AI merge.
+pub async fn load_rejected(db_pool: &PgPool, user_id: Uuid) -> AppResult<RejectedItems> {
+    let mut rejected = RejectedItems::default();
+    for item in list_rejected(db_pool, user_id).await? {
+        let value = item.value.to_lowercase();
+        match item.kind {
+            ProfileItemKind::Skill => rejected.skills.insert(value),
+            ProfileItemKind::TargetRole => rejected.target_roles.insert(value),
+        };
+    }
+    Ok(rejected)
+}
@@ -0,0 +1,110 @@
+//! Database connection setup and query instrumentation.
+//!
+//! Slow statements are logged by sqlx at `WARN` level once they exceed
+//! `SLOW_QUERY_THRESHOLD_MS`. When `QUERY_EXPLAIN_SAMPLE_RATE` is above zero,
+//! each connection also enables Postgres' `auto_explain` module so that a
+//! sample of slow statements is logged server-side with an
+//! `EXPLAIN ANALYZE` plan and the statement's query identifier, which serves
+//! as a stable fingerprint for grouping plans of the same query shape.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::LevelFilter;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{ConnectOptions, Executor, PgPool};
+use tracing::{info, warn};
+
+/// Default threshold above which a statement is considered slow.
+const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 500;
+
+/// Query instrumentation settings read from the environment.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryInstrumentation {
+    /// Statements slower than this are logged and eligible for plan sampling
+    pub slow_threshold: Duration,
+    /// Fraction (0.0-1.0) of slow statements to capture `EXPLAIN ANALYZE` plans for
+    pub explain_sample_rate: f64,
+}
+
+impl QueryInstrumentation {
+    /// Reads `SLOW_QUERY_THRESHOLD_MS` (default: 500) and
+    /// `QUERY_EXPLAIN_SAMPLE_RATE` (default: 0, disabled).
+    pub fn from_env() -> Self {
+        let threshold_ms = std::env::var("SLOW_QUERY_THRESHOLD_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_SLOW_QUERY_THRESHOLD_MS);
+        let explain_sample_rate = std::env::var("QUERY_EXPLAIN_SAMPLE_RATE")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+            .clamp(0.0, 1.0);
+
+        Self {
+            slow_threshold: Duration::from_millis(threshold_ms),
+            explain_sample_rate,
+        }
+    }
+}
+
+/// Creates the application connection pool with query instrumentation enabled.
+///
+/// # Errors
+///
+/// Returns an error if the URL is invalid or the database is unreachable.
+pub async fn connect(database_url: &str) -> Result<PgPool, sqlx::Error> {
+    let instrumentation = QueryInstrumentation::from_env();
+
+    let connect_options = PgConnectOptions::from_str(database_url)?
+        .log_statements(LevelFilter::Debug)
+        .log_slow_statements(LevelFilter::Warn, instrumentation.slow_threshold);
+
+    info!(
+        "Slow query threshold: {:?}, EXPLAIN sample rate: {}",
+        instrumentation.slow_threshold, instrumentation.explain_sample_rate
+    );
+
+    PgPoolOptions::new()
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                if instrumentation.explain_sample_rate > 0.0 {
+                    enable_auto_explain(conn, instrumentation).await;
+                }
+                Ok(())
+            })
+        })
+        .connect_with(connect_options)
+        .await
+}
+
+/// Enables `auto_explain` sampling on a single connection.
+///
+/// Failures are logged and ignored: loading the module requires it to be
+/// installed (and, for non-superusers, listed in `session_preload_libraries`
+/// or placed under `$libdir/plugins`), and a missing module must not prevent
+/// the application from connecting.
+async fn enable_auto_explain(conn: &mut sqlx::PgConnection, instrumentation: QueryInstrumentation) {
+    let statements = [
+        "LOAD 'auto_explain'".to_string(),
+        "SET compute_query_id = on".to_string(),
+        format!(
+            "SET auto_explain.log_min_duration = {}",
+            instrumentation.slow_threshold.as_millis()
+        ),
+        "SET auto_explain.log_analyze = on".to_string(),
+        "SET auto_explain.log_verbose = on".to_string(),
+        "SET auto_explain.log_format = 'json'".to_string(),
+        format!(
+            "SET auto_explain.sample_rate = {}",
+            instrumentation.explain_sample_rate
+        ),
+    ];
+
+    for statement in &statements {
+        if let Err(e) = conn.execute(statement.as_str()).await {
+            warn!("Could not enable auto_explain ({}): {}", statement, e);
+            return;
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Replayable debug captures of AI generations.
+//!
+//! Users opt in to captures. While they are opted in, each of their AI
+//! requests is recorded with the provider, model, prompt version, and the
+//! provider's raw response (see [`crate::ai::capture`]); the capture's ID is
+//! returned with the result as `capture_id`. Flagging a bad generation keeps
+//! its capture for admins, who can replay the request against the current
+//! prompt version to check that a fix works.
+//!
+//! The prompt is not stored verbatim: it is rendered from the captured
+//! request (input, context, history, and sampling settings) by the prompt
+//! version recorded with it, so a replay under the same version sends the
+//! same prompt. Unflagged captures are purged after
+//! `DEBUG_CAPTURE_RETENTION_HOURS` (a day by default), and withdrawing
+//! consent deletes all of the user's captures.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+
+/// Hours an unflagged capture is kept, unless
+/// `DEBUG_CAPTURE_RETENTION_HOURS` overrides it.
+const DEFAULT_RETENTION_HOURS: i32 = 24;
+
+/// Hours an unflagged capture is kept.
+pub fn retention_hours() -> i32 {
+    std::env::var("DEBUG_CAPTURE_RETENTION_HOURS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_RETENTION_HOURS)
+}
+
+/// Whether a user consented to debug captures.
+#[derive(Debug, Serialize)]
+pub struct CaptureConsent {
+    pub enabled: bool,
+    pub consented_at: Option<DateTime<Utc>>,
+    /// Hours unflagged captures are kept
+    pub retention_hours: i32,
+}
+
+/// A capture in a list, without the request and responses.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CaptureListing {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub action: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_version: String,
+    pub success: bool,
+    pub created_at: DateTime<Utc>,
+    pub flagged_at: Option<DateTime<Utc>>,
+    pub flag_reason: Option<String>,
+    pub replayed_at: Option<DateTime<Utc>>,
+    pub replay_success: Option<bool>,
+}
+
+const LISTING_COLUMNS: &str = r#"
+    id, user_id, action, provider, model, prompt_version, success, created_at,
+    flagged_at, flag_reason, replayed_at, replay_success
+"#;
+
+/// A capture with the request, the raw and parsed response, and the latest
+/// replay.
+#[derive(Debug, Serialize, FromRow)]
+pub struct DebugCapture {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub action: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_version: String,
+    pub input: String,
+    pub parameters: Option<Value>,
+    pub settings: Value,
+    /// Text the provider returned, before parsing
+    pub raw_response: Option<String>,
+    /// Parsed result, or the error
+    pub response: Value,
+    pub success: bool,
+    pub created_at: DateTime<Utc>,
+    pub flagged_at: Option<DateTime<Utc>>,
+    pub flag_reason: Option<String>,
+    pub replayed_at: Option<DateTime<Utc>>,
+    pub replayed_by: Option<Uuid>,
+    pub replay_provider: Option<String>,
+    pub replay_model: Option<String>,
+    pub replay_prompt_version: Option<String>,
+    pub replay_response: Option<Value>,
+    pub replay_success: Option<bool>,
+}
+
+/// Whether the user consented to debug captures.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user does not exist, or an error if
+/// the database operation fails.
+pub async fn consent(db_pool: &PgPool, user_id: Uuid) -> AppResult<CaptureConsent> {
+    let consented_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        "SELECT debug_capture_consent_at FROM users WHERE id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(CaptureConsent {
+        enabled: consented_at.is_some(),
+        consented_at,
+        retention_hours: retention_hours(),
+    })
+}
+
+/// Gives or withdraws consent to debug captures. Withdrawing deletes all of
+/// the user's captures, flagged or not.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user does not exist, or an error if
+/// the database operation fails.
+pub async fn set_consent(db_pool: &PgPool, user_id: Uuid, enabled: bool) -> AppResult<CaptureConsent> {
+    let mut tx = db_pool.begin().await?;
+    let consented_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        UPDATE users
+        SET debug_capture_consent_at = CASE
+            WHEN $2 THEN COALESCE(debug_capture_consent_at, NOW())
+            ELSE NULL
+        END
+        WHERE id = $1
+        RETURNING debug_capture_consent_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(enabled)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if !enabled {
+        let deleted = sqlx::query("DELETE FROM debug_captures WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if deleted > 0 {
+            info!("Deleted {} debug captures of user {} on withdrawn consent", deleted, user_id);
+        }
+    }
+    tx.commit().await?;
+    Ok(CaptureConsent {
+        enabled,
+        consented_at,
+        retention_hours: retention_hours(),
+    })
+}
+
+/// The user's captures, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_mine(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<CaptureListing>> {
+    let captures = sqlx::query_as::<_, CaptureListing>(&format!(
+        "SELECT {} FROM debug_captures WHERE user_id = $1 ORDER BY created_at DESC LIMIT 100",
+        LISTING_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(captures)
+}
+
+/// Flags one of the user's captures as a bad generation, keeping it for
+/// admins. Flagging again replaces the reason.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the capture is not the user's (or was
+/// purged), or an error if the database operation fails.
+pub async fn flag(db_pool: &PgPool, user_id: Uuid, capture_id: i64, reason: &str) -> AppResult<CaptureListing> {
+    let capture = sqlx::query_as::<_, CaptureListing>(&format!(
+        r#"
+        UPDATE debug_captures
+        SET flagged_at = COALESCE(flagged_at, NOW()), flag_reason = $3
+        WHERE id = $1 AND user_id = $2
+        RETURNING {}
+        "#,
+        LISTING_COLUMNS
+    ))
+    .bind(capture_id)
+    .bind(user_id)
+    .bind(reason.trim())
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    info!("User {} flagged AI capture {} ({})", user_id, capture_id, capture.action);
+    Ok(capture)
+}
+
+/// Captures for admins, newest first: flagged ones, or all when
+/// `include_unflagged`.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn queue(db_pool: &PgPool, include_unflagged: bool) -> AppResult<Vec<CaptureListing>> {
+    let captures = sqlx::query_as::<_, CaptureListing>(&format!(
+        r#"
+        SELECT {} FROM debug_captures
+        WHERE flagged_at IS NOT NULL OR $1
+        ORDER BY COALESCE(flagged_at, created_at) DESC
+        LIMIT 200
+        "#,
+        LISTING_COLUMNS
+    ))
+    .bind(include_unflagged)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(captures)
+}
+
+/// A capture with its request and responses.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if it does not exist, or an error if the
+/// database operation fails.
+pub async fn get(db_pool: &PgPool, capture_id: i64) -> AppResult<DebugCapture> {
+    let capture = sqlx::query_as::<_, DebugCapture>("SELECT * FROM debug_captures WHERE id = $1")
+        .bind(capture_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(capture)
+}
+
+/// Re-runs a captured request against the current prompt version, with the
+/// captured provider, input, context, history, and sampling settings, and
+/// stores the result as the capture's latest replay.
+///
+/// Replays are not charged to the user and are not captured themselves.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the capture does not exist, or an error
+/// if the request cannot be rebuilt, the provider is not configured, or the
+/// database operation fails. A failed generation is stored, not returned as
+/// an error.
+pub async fn replay(db_pool: &PgPool, ai: &AIService, admin_id: Uuid, capture_id: i64) -> AppResult<DebugCapture> {
+    let capture = get(db_pool, capture_id).await?;
+    let action: ActionType = serde_json::from_value(Value::String(capture.action.clone())).map_err(|_| {
+        AppError::BadRequest(format!("Action '{}' can no longer be replayed", capture.action))
+    })?;
+    let request = AIActionRequest {
+        action,
+        provider: AIProvider::from_name(&capture.provider),
+        input: capture.input,
+        parameters: capture.parameters,
+        user_id: None,
+        capture: true,
+    };
+    let response = ai.process_action(request).await?;
+
+    let replayed = sqlx::query_as::<_, DebugCapture>(
+        r#"
+        UPDATE debug_captures
+        SET replayed_at = NOW(), replayed_by = $2, replay_provider = $3, replay_model = $4,
+            replay_prompt_version = $5, replay_response = $6, replay_success = $7
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(capture_id)
+    .bind(admin_id)
+    .bind(response.provider.as_str())
+    .bind(response.model)
+    .bind(response.prompt_version)
+    .bind(&response.data)
+    .bind(response.success)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    info!(
+        "Admin {} replayed AI capture {} with {} (success: {})",
+        admin_id, capture_id, response.prompt_version, response.success
+    );
+    Ok(replayed)
+}
+
+/// Deletes a capture once it has been dealt with.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if it does not exist, or an error if the
+/// database operation fails.
+pub async fn delete(db_pool: &PgPool, capture_id: i64) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM debug_captures WHERE id = $1")
+        .bind(capture_id)
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Deletes unflagged captures older than the retention period. Returns how
+/// many were deleted.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn purge_expired(db_pool: &PgPool) -> AppResult<u64> {
+    let deleted = sqlx::query(
+        r#"
+        DELETE FROM debug_captures
+        WHERE flagged_at IS NULL AND created_at < NOW() - make_interval(hours => $1)
+        "#,
+    )
+    .bind(retention_hours())
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+    if deleted > 0 {
+        info!("Purged {} expired debug captures", deleted);
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,483 @@
+//! Startup self-check and configuration diagnostics.
+//!
+//! [`run`] reports the resolved configuration, with secrets redacted, and
+//! checks that this deployment can serve traffic: the database is reachable
+//! with every migration applied, AI provider keys are accepted, and file
+//! storage is writable. It backs `GET /api/admin/diagnostics` and the
+//! server's `--check` mode, which exits non-zero on a failed check before
+//! serving any traffic.
+//!
+//! Migrations are applied by hand with `psql`, so there is no migrations
+//! table to read; each migration is instead recognized by an object it
+//! creates, listed in [`MIGRATION_MARKERS`].
+
+use std::time::Instant;
+
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::ai::{AIService, gemini};
+use crate::storage::Storage;
+
+/// Shortest `JWT_SECRET` not flagged as weak.
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Settings reported by diagnostics: name, default, and whether the value
+/// is a secret that must never be shown.
+const SETTINGS: &[(&str, Option<&str>, bool)] = &[
+    ("RUST_ENV", Some("development"), false),
+    ("PORT", Some("3000"), false),
+    ("DATABASE_URL", None, true),
+    ("SLOW_QUERY_THRESHOLD_MS", Some("500"), false),
+    ("QUERY_EXPLAIN_SAMPLE_RATE", Some("0"), false),
+    ("JWT_SECRET", None, true),
+    ("FRONTEND_URL", Some("http://localhost:3001"), false),
+    ("LEGACY_API_SUNSET", Some("2027-06-30"), false),
+    ("DEMO_MODE", Some("false"), false),
+    ("GEMINI_API_KEY", None, true),
+    ("GEMINI_SAFETY_HARASSMENT", None, false),
+    ("GEMINI_SAFETY_HATE_SPEECH", None, false),
+    ("GEMINI_SAFETY_SEXUALLY_EXPLICIT", None, false),
+    ("GEMINI_SAFETY_DANGEROUS_CONTENT", None, false),
+    ("GROQ_API_KEY", None, true),
+    ("OLLAMA_BASE_URL", None, false),
+    ("OLLAMA_MODEL", Some("llama3.1"), false),
+    ("AI_PROVIDER_FALLBACK_ORDER", Some("gemini,groq,ollama"), false),
+    ("AI_RETRY_MAX_ATTEMPTS", Some("3"), false),
+    ("AI_RETRY_BASE_DELAY_MS", Some("500"), false),
+    ("AI_RETRY_MAX_DELAY_MS", Some("8000"), false),
+    ("AI_CIRCUIT_FAILURE_THRESHOLD", Some("5"), false),
+    ("AI_CIRCUIT_COOLDOWN_SECS", Some("30"), false),
+    ("HUGGINGFACE_API_KEY", None, true),
+    ("AI_MOCK_MODE", Some("false"), false),
+    ("MENTOR_CONTEXT_TOKENS", None, false),
+    ("MENTOR_CACHE_SIMILARITY", Some("0.95"), false),
+    ("MENTOR_CACHE_TTL_SECS", Some("86400"), false),
+    ("EVAL_MIN_PASS_RATE", Some("0.8"), false),
+    ("CREDITS_ENABLED", Some("false"), false),
+    ("CREDITS_SIGNUP_BONUS", Some("50"), false),
+    ("STRIPE_SECRET_KEY", None, true),
+    ("WEBHOOK_SECRET_SMS", None, true),
+    ("WEBHOOK_SECRET_PAYMENTS", None, true),
+    ("WEBHOOK_SECRET_ATS", None, true),
+    ("WEBHOOK_SECRET_STRIPE", None, true),
+    ("WEBHOOK_TOLERANCE_SECS", Some("300"), false),
+    ("JOB_EXPIRY_DAYS", Some("30"), false),
+    ("SCAM_QUARANTINE_SCORE", Some("70"), false),
+    ("EMAIL_WEBHOOK_URL", None, false),
+    ("EMAIL_WEBHOOK_TOKEN", None, true),
+    ("BUSINESS_REGISTRY_URL", None, false),
+    ("BUSINESS_REGISTRY_API_KEY", None, true),
+    ("GEOCODING_API_URL", None, false),
+    ("GEOCODING_API_KEY", None, true),
+    ("PLACEMENT_MIN_GROUP_SIZE", Some("5"), false),
+    ("BENCHMARK_MIN_PEERS", Some("10"), false),
+    ("APPLICATION_NUDGE_THRESHOLD", Some("65"), false),
+    ("READINESS_NUDGE_SCORE", Some("65"), false),
+    ("STORAGE_DIR", Some("storage"), false),
+    ("DOCUMENT_ENCRYPTION_KEY", None, true),
+    ("USER_CACHE_TTL_SECS", Some("60"), false),
+    ("USER_CACHE_MAX_CAPACITY", Some("10000"), false),
+    ("RATE_LIMIT_POLICY_TTL_SECS", Some("30"), false),
+    ("TENANT_CONFIG_TTL_SECS", Some("30"), false),
+    ("LOAD_SHED_AI_CONCURRENCY", Some("16"), false),
+    ("LOAD_SHED_UPLOAD_CONCURRENCY", Some("8"), false),
+    ("LOAD_SHED_READ_CONCURRENCY", Some("64"), false),
+    ("LOAD_SHED_QUEUE_TIMEOUT_MS", Some("2000"), false),
+    ("LOAD_SHED_RETRY_AFTER_SECS", Some("5"), false),
+    ("STATS_RECONCILE_INTERVAL_SECS", Some("3600"), false),
+    ("PARTITION_MAINTENANCE_INTERVAL_SECS", Some("86400"), false),
+    ("ARCHIVE_DIR", Some("./archive"), false),
+    ("ARCHIVE_RETENTION_MONTHS", Some("6"), false),
+    ("JOB_EXPIRY_INTERVAL_SECS", Some("3600"), false),
+    ("GEOCODE_BACKFILL_INTERVAL_SECS", Some("600"), false),
+    ("LINK_CHECK_INTERVAL_SECS", Some("3600"), false),
+    ("KB_INDEX_INTERVAL_SECS", Some("300"), false),
+    ("APPLICATION_PACKAGE_INTERVAL_SECS", Some("60"), false),
+    ("COHORT_ROADMAP_INTERVAL_SECS", Some("60"), false),
+    ("CERTIFICATE_VERIFY_INTERVAL_SECS", Some("3600"), false),
+    ("CODE_PRACTICE_SYNC_INTERVAL_SECS", Some("3600"), false),
+    ("WEEKLY_DIGEST_INTERVAL_SECS", Some("21600"), false),
+    ("READINESS_INTERVAL_SECS", Some("3600"), false),
+    ("APPLICATION_NUDGE_INTERVAL_SECS", Some("3600"), false),
+    ("INTERVIEW_PREP_INTERVAL_SECS", Some("900"), false),
+    ("MENTOR_CALENDAR_SYNC_INTERVAL_SECS", Some("300"), false),
+    ("MENTOR_REMINDER_INTERVAL_SECS", Some("300"), false),
+    ("MENTOR_PAYMENT_SETTLE_INTERVAL_SECS", Some("900"), false),
+    ("SANDBOX_PURGE_INTERVAL_SECS", Some("86400"), false),
+    ("DEBUG_CAPTURE_PURGE_INTERVAL_SECS", Some("3600"), false),
+    ("DEBUG_CAPTURE_RETENTION_HOURS", Some("24"), false),
+    ("PROMPT_ROLLOUT_CHECK_INTERVAL_SECS", Some("300"), false),
+    ("TALENT_POOL_ALERT_INTERVAL_SECS", Some("3600"), false),
+    ("IMPERSONATION_NOTICE_INTERVAL_SECS", Some("300"), false),
+    ("GOOGLE_CLIENT_ID", None, false),
+    ("GOOGLE_CLIENT_SECRET", None, true),
+    ("GOOGLE_REDIRECT_URI", None, false),
+    ("GOOGLE_CALENDAR_REDIRECT_URI", None, false),
+    ("MEETING_PROVIDER", Some("jitsi"), false),
+    ("JITSI_BASE_URL", Some("https://meet.jit.si"), false),
+    ("ZOOM_ACCOUNT_ID", None, false),
+    ("ZOOM_CLIENT_ID", None, false),
+    ("ZOOM_CLIENT_SECRET", None, true),
+    ("GITHUB_CLIENT_ID", None, false),
+    ("GITHUB_CLIENT_SECRET", None, true),
+    ("GITHUB_REDIRECT_URI", None, false),
+    ("EXPORT_FONT_SANS", Some("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf"), false),
+    ("EXPORT_FONT_SANS_BOLD", Some("/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf"), false),
+    ("EXPORT_FONT_SERIF", Some("/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf"), false),
+    ("EXPORT_FONT_SERIF_BOLD", Some("/usr/share/fonts/truetype/dejavu/DejaVuSerif-Bold.ttf"), false),
+    ("EXPORT_FONT_FALLBACKS", None, false),
+];
+
+/// Each migration with an object it creates: a table or index name, or
+/// `table.column`. Data-only migrations have no marker and are not listed.
+/// Add new migrations here.
+pub const MIGRATION_MARKERS: &[(&str, &str)] = &[
+    ("003_add_job_details_columns", "jobs.responsibilities"),
+    ("004_employer_dashboard", "users.role"),
+    ("005_partitioned_logs", "activity_events"),
+    ("006_hot_path_indexes", "idx_jobs_fulltext"),
+    ("007_mentor_conversations", "mentor_messages"),
+    ("008_billing", "ai_daily_usage"),
+    ("009_credits", "credit_transactions"),
+    ("010_employer_teams", "company_members"),
+    ("011_job_moderation", "jobs.moderation_status"),
+    ("012_job_geo", "jobs.latitude"),
+    ("013_job_expiry", "jobs.expires_at"),
+    ("014_candidate_availability", "users.available_from"),
+    ("015_internships", "jobs.term_start"),
+    ("016_career_events", "career_events"),
+    ("017_job_offers", "job_offers"),
+    ("018_wizards", "wizards"),
+    ("019_ai_generation_settings", "ai_usage_logs.temperature"),
+    ("020_deterministic_generation", "ai_usage_logs.seed"),
+    ("021_roadmap_quality", "career_roadmaps.quality_warnings"),
+    ("022_resource_links", "resource_links"),
+    ("023_skill_proficiency", "user_skill_ratings"),
+    ("024_skill_progress", "user_skill_progress"),
+    ("025_conversation_summaries", "mentor_conversations.summary"),
+    ("026_conversation_context", "mentor_conversations.context_summary"),
+    ("027_document_retrieval", "user_documents"),
+    ("028_knowledge_base", "kb_articles"),
+    ("029_ai_tool_calls", "ai_tool_calls"),
+    ("030_application_packages", "application_packages"),
+    ("032_certifications", "user_certifications"),
+    ("033_certificate_verification", "user_certifications.verification_status"),
+    ("034_profile_merge_patch", "users.social_links"),
+    ("035_profile_snapshots", "profile_snapshots"),
+    ("036_profile_item_curation", "user_rejected_profile_items"),
+    ("037_rate_limit_policies", "rate_limit_policies"),
+    ("038_mentor_answer_cache", "mentor_answer_cache"),
+    ("039_ai_eval", "ai_eval_results"),
+    ("040_application_timing_nudges", "career_roadmaps.application_nudged_at"),
+    ("041_roadmap_progress_events", "roadmap_progress_events"),
+    ("042_roadmap_portfolio", "career_roadmaps.is_primary"),
+    ("043_roadmap_resource_progress", "roadmap_resource_progress"),
+    ("044_resource_clicks", "resource_clicks"),
+    ("045_company_branding", "company_branding"),
+    ("046_screening_questions", "screening_questions"),
+    ("047_talent_pools", "talent_pools"),
+    ("048_blocks_and_reports", "user_blocks"),
+    ("049_policy_acceptance", "policy_acceptances"),
+    ("050_impersonation", "impersonation_sessions"),
+    ("051_tenant_configs", "tenant_configs"),
+    ("052_cohort_roadmap_batches", "cohort_roadmap_items"),
+    ("053_student_nudges", "student_nudges"),
+    ("054_offer_qa_documents", "offer_qa_documents"),
+    ("055_negotiation_sessions", "negotiation_sessions"),
+    ("056_star_stories", "star_stories"),
+    ("057_code_practice", "code_practice_accounts"),
+    ("058_role_readiness", "role_readiness"),
+    ("059_employer_verification", "employer_verifications"),
+    ("060_scam_detection", "scam_screening_feedback"),
+    ("061_company_reviews", "company_reviews"),
+    ("062_interview_experiences", "interview_experiences"),
+    ("063_interview_prep_packs", "interview_prep_packs"),
+    ("064_mentor_calendars", "mentor_calendars"),
+    ("065_meeting_links", "mentor_bookings.meeting_url"),
+    ("066_session_notes", "mentor_action_items"),
+    ("067_mentor_payments", "mentor_payments"),
+    ("068_disputes", "disputes"),
+    ("069_sandbox", "api_keys"),
+    ("070_debug_captures", "debug_captures"),
+    ("071_prompt_rollouts", "prompt_rollouts"),
+    ("072_answer_sections", "mentor_messages.sections"),
+    ("073_glossary", "glossary"),
+];
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but something is off or disabled
+    Warning,
+    /// The deployment should not serve traffic
+    Failed,
+}
+
+/// One check and what it found.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A setting as the server resolves it.
+#[derive(Debug, Serialize)]
+pub struct ConfigValue {
+    pub name: &'static str,
+    /// The value in effect; secrets show only whether they are set
+    pub value: Option<String>,
+    /// Whether the value is the built-in default rather than set
+    pub is_default: bool,
+}
+
+/// The full diagnostics report.
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    /// Whether no check failed
+    pub healthy: bool,
+    pub config: Vec<ConfigValue>,
+    pub checks: Vec<Check>,
+}
+
+/// Resolves the configuration and runs every check.
+///
+/// Provider checks call each configured AI provider once, so this is not
+/// meant for frequent polling.
+pub async fn run(db_pool: &PgPool, ai_service: Option<&AIService>, storage: &Storage) -> Diagnostics {
+    let mut checks = check_config(ai_service);
+    checks.push(check_database(db_pool).await);
+    checks.push(check_migrations(db_pool).await);
+    checks.extend(check_providers(ai_service).await);
+    checks.push(check_storage(storage).await);
+    checks.push(Check::new(
+        "scheduler",
+        CheckStatus::Ok,
+        "Background tasks run in every server process; there is no leader election, \
+         so tasks run once per instance",
+    ));
+
+    Diagnostics {
+        healthy: checks.iter().all(|c| c.status != CheckStatus::Failed),
+        config: resolved_config(),
+        checks,
+    }
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn resolved_config() -> Vec<ConfigValue> {
+    SETTINGS
+        .iter()
+        .map(|&(name, default, secret)| {
+            let set = env(name);
+            let is_default = set.is_none();
+            let value = match set {
+                Some(value) if name == "DATABASE_URL" => Some(redact_url(&value)),
+                Some(_) if secret => Some("[redacted]".to_string()),
+                Some(value) => Some(value),
+                None => default.map(str::to_string),
+            };
+            ConfigValue { name, value, is_default }
+        })
+        .collect()
+}
+
+/// A connection URL with its password masked.
+fn redact_url(value: &str) -> String {
+    match url::Url::parse(value) {
+        Ok(mut url) => {
+            if url.password().is_some() {
+                let _ = url.set_password(Some("redacted"));
+            }
+            url.to_string()
+        }
+        Err(_) => "[redacted]".to_string(),
+    }
+}
+
+fn check_config(ai_service: Option<&AIService>) -> Vec<Check> {
+    let production = env("RUST_ENV").as_deref() == Some("production");
+    let mut checks = Vec::new();
+
+    if env("DATABASE_URL").is_none() {
+        checks.push(Check::new("config", CheckStatus::Failed, "DATABASE_URL is not set"));
+    }
+    if env("PORT").is_some_and(|p| p.parse::<u16>().is_err()) {
+        checks.push(Check::new("config", CheckStatus::Failed, "PORT is not a valid port number"));
+    }
+    match env("JWT_SECRET") {
+        None if production => checks.push(Check::new(
+            "config",
+            CheckStatus::Failed,
+            "JWT_SECRET is not set, so tokens are signed with the public development key",
+        )),
+        None => checks.push(Check::new(
+            "config",
+            CheckStatus::Warning,
+            "JWT_SECRET is not set; using the development key",
+        )),
+        Some(secret) if secret.len() < MIN_JWT_SECRET_LEN => checks.push(Check::new(
+            "config",
+            CheckStatus::Warning,
+            format!("JWT_SECRET is shorter than {} characters", MIN_JWT_SECRET_LEN),
+        )),
+        Some(_) => {}
+    }
+
+    // Numeric settings that do not parse silently fall back to their default
+    for &(name, default, _) in SETTINGS {
+        if let (Some(value), Some(default)) = (env(name), default)
+            && default.parse::<f64>().is_ok()
+            && value.parse::<f64>().is_err()
+        {
+            checks.push(Check::new(
+                "config",
+                CheckStatus::Warning,
+                format!("{} is not a number; the default {} is used", name, default),
+            ));
+        }
+    }
+
+    if ai_service.is_none() {
+        let detail = if env("AI_MOCK_MODE").is_some_and(|v| v == "true" || v == "1") && production {
+            "AI_MOCK_MODE is ignored in production and no AI provider is configured"
+        } else {
+            "No AI provider is configured (set GEMINI_API_KEY, GROQ_API_KEY, or OLLAMA_BASE_URL); AI features are disabled"
+        };
+        checks.push(Check::new("config", CheckStatus::Warning, detail));
+    }
+
+    for &(name, _) in gemini::SAFETY_CATEGORIES {
+        if let Some(value) = env(name)
+            && !gemini::SAFETY_THRESHOLDS.contains(&value.trim().to_uppercase().as_str())
+        {
+            checks.push(Check::new(
+                "config",
+                CheckStatus::Warning,
+                format!(
+                    "{} is not one of {}; Gemini's default threshold is used",
+                    name,
+                    gemini::SAFETY_THRESHOLDS.join(", ")
+                ),
+            ));
+        }
+    }
+
+    for provider in ["GOOGLE", "GITHUB"] {
+        let missing: Vec<String> = ["CLIENT_SECRET", "REDIRECT_URI"]
+            .iter()
+            .map(|suffix| format!("{}_{}", provider, suffix))
+            .filter(|name| env(name).is_none())
+            .collect();
+        if env(&format!("{}_CLIENT_ID", provider)).is_some() && !missing.is_empty() {
+            checks.push(Check::new(
+                "config",
+                CheckStatus::Warning,
+                format!("{}_CLIENT_ID is set but {} is not; OAuth sign-in will fail", provider, missing.join(" and ")),
+            ));
+        }
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::new("config", CheckStatus::Ok, "Configuration is complete"));
+    }
+    checks
+}
+
+async fn check_database(db_pool: &PgPool) -> Check {
+    let started = Instant::now();
+    match sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(db_pool).await {
+        Ok(_) => Check::new(
+            "database",
+            CheckStatus::Ok,
+            format!("Reachable in {} ms", started.elapsed().as_millis()),
+        ),
+        Err(e) => Check::new("database", CheckStatus::Failed, format!("Unreachable: {}", e)),
+    }
+}
+
+async fn check_migrations(db_pool: &PgPool) -> Check {
+    let (migrations, markers): (Vec<&str>, Vec<&str>) = MIGRATION_MARKERS.iter().copied().unzip();
+    let pending = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT m.migration
+        FROM UNNEST($1::TEXT[], $2::TEXT[]) AS m(migration, marker)
+        WHERE CASE
+            WHEN position('.' IN m.marker) > 0 THEN NOT EXISTS (
+                SELECT 1 FROM information_schema.columns c
+                WHERE c.table_schema = current_schema()
+                  AND c.table_name = split_part(m.marker, '.', 1)
+                  AND c.column_name = split_part(m.marker, '.', 2)
+            )
+            ELSE to_regclass(m.marker) IS NULL
+        END
+        ORDER BY m.migration
+        "#,
+    )
+    .bind(&migrations)
+    .bind(&markers)
+    .fetch_all(db_pool)
+    .await;
+
+    match pending {
+        Ok(pending) if pending.is_empty() => Check::new(
+            "migrations",
+            CheckStatus::Ok,
+            format!("All {} migrations are applied", migrations.len()),
+        ),
+        Ok(pending) => Check::new(
+            "migrations",
+            CheckStatus::Failed,
+            format!("Not applied: {}", pending.join(", ")),
+        ),
+        Err(e) => Check::new("migrations", CheckStatus::Failed, format!("Could not inspect the schema: {}", e)),
+    }
+}
+
+async fn check_providers(ai_service: Option<&AIService>) -> Vec<Check> {
+    let Some(ai) = ai_service else {
+        return Vec::new();
+    };
+    if ai.is_mock() {
+        return vec![Check::new(
+            "ai_provider",
+            CheckStatus::Warning,
+            "Mock mode: fixture responses, no provider is called",
+        )];
+    }
+    ai.check_providers()
+        .await
+        .into_iter()
+        .map(|(provider, result)| {
+            let name = format!("ai_provider.{}", provider);
+            match result {
+                Ok(()) => Check::new(name, CheckStatus::Ok, "API key accepted"),
+                Err(e) => Check::new(name, CheckStatus::Failed, e.to_string()),
+            }
+        })
+        .collect()
+}
+
+async fn check_storage(storage: &Storage) -> Check {
+    let root = storage.root().display();
+    match storage.check().await {
+        Ok(()) => Check::new("storage", CheckStatus::Ok, format!("Writable at {}", root)),
+        Err(_) => Check::new("storage", CheckStatus::Failed, format!("Cannot write to {}", root)),
+    }
+}
@@ -0,0 +1,739 @@
+//! Disputes over mentor sessions and application decisions.
+//!
+//! Either side of a mentor session can dispute it once it has started or
+//! been cancelled, up to [`DISPUTE_WINDOW_DAYS`] after it was due to end. A
+//! candidate can dispute a rejected application, or an offer the employer
+//! withdrew. Both parties attach evidence - notes and files - while the
+//! dispute is live.
+//!
+//! Lifecycle: `open` -> `under_review` when an admin takes it, then
+//! `resolved` with an outcome, or `withdrawn` by the party who opened it.
+//! Every status change is kept in `dispute_events` and both parties are
+//! notified.
+//!
+//! Opening a dispute over a paid session freezes its payment (see
+//! [`mentor_payments::hold_for_dispute`]). The resolution refunds the
+//! mentee - in full by default when a mentee's dispute is upheld, an amount
+//! the admin sets when partially upheld - and releases the rest to the
+//! mentor. A withdrawn dispute unfreezes the payment as it was.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::billing::StripeClient;
+use crate::errors::{AppError, AppResult};
+use crate::mentor_payments;
+use crate::mentorship;
+use crate::models::{DisputeOutcome, DisputeStatus, DisputeSubject, MentorBookingStatus, MentorPaymentStatus, UserRole};
+use crate::storage::Storage;
+
+/// Days after a session was due to end it can still be disputed.
+pub const DISPUTE_WINDOW_DAYS: i64 = 14;
+
+/// Evidence file types: extension and content type.
+pub const EVIDENCE_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("txt", "text/plain"),
+];
+
+/// Largest evidence file accepted, in bytes.
+pub const MAX_EVIDENCE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Evidence items each party can attach to a dispute.
+const MAX_EVIDENCE_PER_PARTY: i64 = 20;
+
+/// What a dispute is opened against.
+#[derive(Debug, Clone, Copy)]
+pub enum DisputeTarget {
+    Booking(i32),
+    Application(i32),
+}
+
+/// A dispute.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Dispute {
+    pub id: i32,
+    pub subject: DisputeSubject,
+    pub booking_id: Option<i32>,
+    pub application_id: Option<i32>,
+    pub opened_by: Uuid,
+    pub opener_name: String,
+    /// The other party of the session, or the job's poster
+    pub respondent_id: Option<Uuid>,
+    pub respondent_name: Option<String>,
+    pub reason: String,
+    pub status: DisputeStatus,
+    pub outcome: Option<DisputeOutcome>,
+    /// Refunded to the mentee by the resolution
+    pub refund_cents: Option<i32>,
+    pub resolution_note: Option<String>,
+    /// Admin arbitrating the dispute
+    pub assigned_to: Option<Uuid>,
+    /// Payment of a paid session
+    pub payment_status: Option<MentorPaymentStatus>,
+    pub payment_cents: Option<i32>,
+    pub currency: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+const DISPUTE_SELECT: &str = r#"
+    SELECT d.id, d.subject, d.booking_id, d.application_id, d.opened_by, o.full_name AS opener_name,
+           d.respondent_id, r.full_name AS respondent_name, d.reason, d.status, d.outcome,
+           d.refund_cents, d.resolution_note, d.assigned_to, p.status AS payment_status,
+           p.amount_cents AS payment_cents, p.currency, d.created_at, d.updated_at, d.resolved_at
+    FROM disputes d
+    JOIN users o ON o.id = d.opened_by
+    LEFT JOIN users r ON r.id = d.respondent_id
+    LEFT JOIN mentor_payments p ON p.booking_id = d.booking_id
+"#;
+
+/// A note or file attached to a dispute.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Evidence {
+    pub id: i32,
+    pub dispute_id: i32,
+    pub submitted_by: Uuid,
+    pub submitter_name: String,
+    pub note: Option<String>,
+    /// Name of the attached file, downloadable by the parties and admins
+    pub file_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const EVIDENCE_SELECT: &str = r#"
+    SELECT e.id, e.dispute_id, e.submitted_by, u.full_name AS submitter_name, e.note,
+           e.file_name, e.created_at
+    FROM dispute_evidence e
+    JOIN users u ON u.id = e.submitted_by
+"#;
+
+/// A status change of a dispute.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DisputeEvent {
+    pub id: i32,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    /// `None` for the dispute being opened
+    pub from_status: Option<DisputeStatus>,
+    pub to_status: DisputeStatus,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A dispute with its evidence and history.
+#[derive(Debug, Serialize)]
+pub struct DisputeDetail {
+    #[serde(flatten)]
+    pub dispute: Dispute,
+    pub evidence: Vec<Evidence>,
+    pub events: Vec<DisputeEvent>,
+}
+
+/// An admin's ruling on a dispute.
+#[derive(Debug, Clone, Copy)]
+pub struct Resolution<'a> {
+    pub outcome: DisputeOutcome,
+    /// Refund to the mentee of a paid session; see [`resolve`] for the default
+    pub refund_cents: Option<i32>,
+    pub note: &'a str,
+}
+
+fn is_live(status: DisputeStatus) -> bool {
+    matches!(status, DisputeStatus::Open | DisputeStatus::UnderReview)
+}
+
+fn status_label(status: DisputeStatus) -> &'static str {
+    match status {
+        DisputeStatus::Open => "open",
+        DisputeStatus::UnderReview => "under review",
+        DisputeStatus::Resolved => "resolved",
+        DisputeStatus::Withdrawn => "withdrawn",
+    }
+}
+
+fn outcome_label(outcome: DisputeOutcome) -> &'static str {
+    match outcome {
+        DisputeOutcome::Upheld => "upheld",
+        DisputeOutcome::PartiallyUpheld => "partially upheld",
+        DisputeOutcome::Dismissed => "dismissed",
+    }
+}
+
+async fn record_event(
+    conn: &mut PgConnection,
+    dispute_id: i32,
+    actor_id: Uuid,
+    from_status: Option<DisputeStatus>,
+    to_status: DisputeStatus,
+    note: Option<&str>,
+) -> AppResult<()> {
+    sqlx::query(
+        "INSERT INTO dispute_events (dispute_id, actor_id, from_status, to_status, note) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(dispute_id)
+    .bind(actor_id)
+    .bind(from_status)
+    .bind(to_status)
+    .bind(note)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Notifies the dispute's parties other than `except`.
+async fn notify_parties(db_pool: &PgPool, dispute: &Dispute, except: Option<Uuid>, title: &str, message: &str) {
+    for user_id in [Some(dispute.opened_by), dispute.respondent_id].into_iter().flatten() {
+        if Some(user_id) == except {
+            continue;
+        }
+        if let Err(e) = sqlx::query(
+            "INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'dispute')",
+        )
+        .bind(user_id)
+        .bind(title)
+        .bind(message)
+        .execute(db_pool)
+        .await
+        {
+            warn!("Failed to notify user {} about dispute {}: {}", user_id, dispute.id, e);
+        }
+    }
+}
+
+async fn fetch(db_pool: &PgPool, dispute_id: i32) -> AppResult<Dispute> {
+    sqlx::query_as::<_, Dispute>(&format!("{} WHERE d.id = $1", DISPUTE_SELECT))
+        .bind(dispute_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// The dispute if the caller is one of its parties or an admin.
+async fn visible(db_pool: &PgPool, user_id: Uuid, dispute_id: i32) -> AppResult<Dispute> {
+    let dispute = fetch(db_pool, dispute_id).await?;
+    if dispute.opened_by == user_id || dispute.respondent_id == Some(user_id) {
+        return Ok(dispute);
+    }
+    let role = sqlx::query_scalar::<_, UserRole>("SELECT role FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?;
+    if role == Some(UserRole::Admin) {
+        Ok(dispute)
+    } else {
+        Err(AppError::NotFound)
+    }
+}
+
+/// Who a booking dispute is against, once the session can be disputed.
+async fn booking_respondent(db_pool: &PgPool, user_id: Uuid, booking_id: i32) -> AppResult<Uuid> {
+    let booking = mentorship::booking(db_pool, user_id, booking_id).await?;
+    let now = Utc::now();
+    match booking.status {
+        MentorBookingStatus::PendingPayment => {
+            return Err(AppError::BadRequest("A booking awaiting payment cannot be disputed".to_string()));
+        }
+        MentorBookingStatus::Confirmed | MentorBookingStatus::Completed if booking.starts_at > now => {
+            return Err(AppError::BadRequest(
+                "A session can be disputed once it has started or been cancelled".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    if booking.ends_at + Duration::days(DISPUTE_WINDOW_DAYS) < now {
+        return Err(AppError::BadRequest(format!(
+            "Sessions can only be disputed within {} days",
+            DISPUTE_WINDOW_DAYS
+        )));
+    }
+    Ok(if booking.mentor_id == user_id { booking.mentee_id } else { booking.mentor_id })
+}
+
+/// Who an application dispute is against, if the employer decided against
+/// the candidate: the poster of a withdrawn offer, else the job's poster.
+async fn application_respondent(db_pool: &PgPool, user_id: Uuid, application_id: i32) -> AppResult<Option<Uuid>> {
+    let (status, offer_withdrawn_by, offer_withdrawn, posted_by) =
+        sqlx::query_as::<_, (String, Option<Uuid>, bool, Option<Uuid>)>(
+            r#"
+            SELECT a.status,
+                   (SELECT o.created_by FROM job_offers o
+                    WHERE o.application_id = a.id AND o.status = 'withdrawn'
+                    ORDER BY o.created_at DESC LIMIT 1),
+                   EXISTS (SELECT 1 FROM job_offers o WHERE o.application_id = a.id AND o.status = 'withdrawn'),
+                   j.posted_by
+            FROM application_tracking a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1 AND a.user_id = $2
+            "#,
+        )
+        .bind(application_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if status != "rejected" && !offer_withdrawn {
+        return Err(AppError::BadRequest(
+            "Only a rejected application or a withdrawn offer can be disputed".to_string(),
+        ));
+    }
+    Ok(offer_withdrawn_by.or(posted_by))
+}
+
+/// Opens a dispute. A paid session's payment is frozen until it is settled.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the booking or application is not the
+/// caller's, `AppError::BadRequest` if it cannot be disputed or already has
+/// a live dispute, or an error if the database operation fails.
+pub async fn open(db_pool: &PgPool, user_id: Uuid, target: DisputeTarget, reason: &str) -> AppResult<Dispute> {
+    let (subject, booking_id, application_id, respondent_id) = match target {
+        DisputeTarget::Booking(id) => (
+            DisputeSubject::MentorBooking,
+            Some(id),
+            None,
+            Some(booking_respondent(db_pool, user_id, id).await?),
+        ),
+        DisputeTarget::Application(id) => (
+            DisputeSubject::Application,
+            None,
+            Some(id),
+            application_respondent(db_pool, user_id, id).await?,
+        ),
+    };
+
+    let mut tx = db_pool.begin().await?;
+    let dispute_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO disputes (subject, booking_id, application_id, opened_by, respondent_id, reason)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(subject)
+    .bind(booking_id)
+    .bind(application_id)
+    .bind(user_id)
+    .bind(respondent_id)
+    .bind(reason.trim())
+    .fetch_one(&mut *tx)
+    .await;
+    let dispute_id = match dispute_id {
+        Ok(id) => id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(AppError::BadRequest("A dispute over this is already open".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    record_event(&mut tx, dispute_id, user_id, None, DisputeStatus::Open, None).await?;
+    tx.commit().await?;
+
+    if let Some(booking_id) = booking_id {
+        mentor_payments::hold_for_dispute(db_pool, booking_id).await?;
+    }
+    let dispute = fetch(db_pool, dispute_id).await?;
+    info!("Dispute {} opened by {} over {:?}", dispute.id, user_id, target);
+    notify_parties(
+        db_pool,
+        &dispute,
+        Some(user_id),
+        "A dispute was opened",
+        &format!("{} opened a dispute: {}", dispute.opener_name, dispute.reason),
+    )
+    .await;
+    Ok(dispute)
+}
+
+/// Disputes the caller opened or is the respondent of, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_mine(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<Dispute>> {
+    let disputes = sqlx::query_as::<_, Dispute>(&format!(
+        "{} WHERE d.opened_by = $1 OR d.respondent_id = $1 ORDER BY d.created_at DESC LIMIT 200",
+        DISPUTE_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(disputes)
+}
+
+/// A dispute with its evidence and history, for its parties and admins.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the dispute does not exist or the caller
+/// may not see it, or an error if the database operation fails.
+pub async fn detail(db_pool: &PgPool, user_id: Uuid, dispute_id: i32) -> AppResult<DisputeDetail> {
+    let dispute = visible(db_pool, user_id, dispute_id).await?;
+    let evidence = sqlx::query_as::<_, Evidence>(&format!(
+        "{} WHERE e.dispute_id = $1 ORDER BY e.created_at",
+        EVIDENCE_SELECT
+    ))
+    .bind(dispute_id)
+    .fetch_all(db_pool)
+    .await?;
+    let events = sqlx::query_as::<_, DisputeEvent>(
+        r#"
+        SELECT e.id, e.actor_id, u.full_name AS actor_name, e.from_status, e.to_status, e.note, e.created_at
+        FROM dispute_events e
+        LEFT JOIN users u ON u.id = e.actor_id
+        WHERE e.dispute_id = $1
+        ORDER BY e.created_at, e.id
+        "#,
+    )
+    .bind(dispute_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(DisputeDetail { dispute, evidence, events })
+}
+
+/// Attaches a note, a file (`(file_name, extension, data)`), or both to a
+/// live dispute, by one of its parties.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller is not a party,
+/// `AppError::BadRequest` if the dispute is closed, there is nothing to
+/// attach, or the caller's evidence limit is reached, or an error if
+/// storage or the database operation fails.
+pub async fn add_evidence(
+    db_pool: &PgPool,
+    storage: &Storage,
+    user_id: Uuid,
+    dispute_id: i32,
+    note: Option<&str>,
+    file: Option<(&str, &str, &[u8])>,
+) -> AppResult<Evidence> {
+    let dispute = fetch(db_pool, dispute_id).await?;
+    if dispute.opened_by != user_id && dispute.respondent_id != Some(user_id) {
+        return Err(AppError::NotFound);
+    }
+    if !is_live(dispute.status) {
+        return Err(AppError::BadRequest(format!(
+            "Evidence cannot be added to a {} dispute",
+            status_label(dispute.status)
+        )));
+    }
+    let note = note.map(str::trim).filter(|n| !n.is_empty());
+    if note.is_none() && file.is_none() {
+        return Err(AppError::BadRequest("Provide a note or a file".to_string()));
+    }
+    let submitted = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM dispute_evidence WHERE dispute_id = $1 AND submitted_by = $2",
+    )
+    .bind(dispute_id)
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await?;
+    if submitted >= MAX_EVIDENCE_PER_PARTY {
+        return Err(AppError::BadRequest(format!(
+            "At most {} pieces of evidence can be attached per party",
+            MAX_EVIDENCE_PER_PARTY
+        )));
+    }
+
+    let (file_name, storage_key) = match file {
+        Some((file_name, extension, data)) => {
+            let storage_key = format!("disputes/{}/{}.{}", dispute_id, Uuid::new_v4(), extension);
+            storage.put(&storage_key, data).await?;
+            (Some(file_name), Some(storage_key))
+        }
+        None => (None, None),
+    };
+    let evidence_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO dispute_evidence (dispute_id, submitted_by, note, file_name, storage_key)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(dispute_id)
+    .bind(user_id)
+    .bind(note)
+    .bind(file_name)
+    .bind(&storage_key)
+    .fetch_one(db_pool)
+    .await?;
+    sqlx::query("UPDATE disputes SET updated_at = NOW() WHERE id = $1")
+        .bind(dispute_id)
+        .execute(db_pool)
+        .await?;
+
+    let evidence = sqlx::query_as::<_, Evidence>(&format!("{} WHERE e.id = $1", EVIDENCE_SELECT))
+        .bind(evidence_id)
+        .fetch_one(db_pool)
+        .await?;
+    notify_parties(
+        db_pool,
+        &dispute,
+        Some(user_id),
+        "New evidence in a dispute",
+        &format!("{} added evidence to dispute #{}", evidence.submitter_name, dispute_id),
+    )
+    .await;
+    Ok(evidence)
+}
+
+/// An evidence file: its name, content type, and contents.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller may not see the dispute or the
+/// evidence has no file, or an error if storage or the database operation
+/// fails.
+pub async fn evidence_file(
+    db_pool: &PgPool,
+    storage: &Storage,
+    user_id: Uuid,
+    dispute_id: i32,
+    evidence_id: i32,
+) -> AppResult<(String, &'static str, Vec<u8>)> {
+    visible(db_pool, user_id, dispute_id).await?;
+    let (file_name, storage_key) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT file_name, storage_key FROM dispute_evidence WHERE id = $1 AND dispute_id = $2",
+    )
+    .bind(evidence_id)
+    .bind(dispute_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let (Some(file_name), Some(storage_key)) = (file_name, storage_key) else {
+        return Err(AppError::NotFound);
+    };
+    let extension = storage_key.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    let content_type = EVIDENCE_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, content_type)| *content_type)
+        .unwrap_or("application/octet-stream");
+    let bytes = storage.get(&storage_key).await?;
+    Ok((file_name, content_type, bytes))
+}
+
+/// Moves a live dispute to `to`, recording the change. Returns the dispute
+/// as it was.
+async fn transition(
+    conn: &mut PgConnection,
+    dispute_id: i32,
+    actor_id: Uuid,
+    to: DisputeStatus,
+    note: Option<&str>,
+) -> AppResult<DisputeStatus> {
+    let from = sqlx::query_scalar::<_, DisputeStatus>("SELECT status FROM disputes WHERE id = $1 FOR UPDATE")
+        .bind(dispute_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if !is_live(from) || from == to {
+        return Err(AppError::BadRequest(format!(
+            "The dispute is already {}",
+            status_label(from)
+        )));
+    }
+    sqlx::query("UPDATE disputes SET status = $2, updated_at = NOW() WHERE id = $1")
+        .bind(dispute_id)
+        .bind(to)
+        .execute(&mut *conn)
+        .await?;
+    record_event(conn, dispute_id, actor_id, Some(from), to, note).await?;
+    Ok(from)
+}
+
+/// Withdraws a live dispute, by the party who opened it. A frozen payment
+/// is unfrozen as it was.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller did not open the dispute,
+/// `AppError::BadRequest` if it is closed, or an error if the database
+/// operation fails.
+pub async fn withdraw(db_pool: &PgPool, user_id: Uuid, dispute_id: i32, note: Option<&str>) -> AppResult<Dispute> {
+    let dispute = fetch(db_pool, dispute_id).await?;
+    if dispute.opened_by != user_id {
+        return Err(AppError::NotFound);
+    }
+    let mut tx = db_pool.begin().await?;
+    transition(&mut tx, dispute_id, user_id, DisputeStatus::Withdrawn, note).await?;
+    tx.commit().await?;
+
+    if let Some(booking_id) = dispute.booking_id {
+        mentor_payments::lift_dispute_hold(db_pool, booking_id).await?;
+    }
+    info!("Dispute {} withdrawn", dispute_id);
+    notify_parties(
+        db_pool,
+        &dispute,
+        Some(user_id),
+        "A dispute was withdrawn",
+        &format!("{} withdrew dispute #{}", dispute.opener_name, dispute_id),
+    )
+    .await;
+    fetch(db_pool, dispute_id).await
+}
+
+/// Disputes for admins, oldest first: live ones, or those in `status`.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn queue(db_pool: &PgPool, status: Option<DisputeStatus>) -> AppResult<Vec<Dispute>> {
+    let disputes = sqlx::query_as::<_, Dispute>(&format!(
+        r#"
+        {}
+        WHERE ($1::dispute_status IS NULL AND d.status IN ('open', 'under_review')) OR d.status = $1
+        ORDER BY d.created_at
+        LIMIT 200
+        "#,
+        DISPUTE_SELECT
+    ))
+    .bind(status)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(disputes)
+}
+
+/// Takes an open dispute under review, assigning it to the admin.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the dispute does not exist,
+/// `AppError::BadRequest` if it is not open, or an error if the database
+/// operation fails.
+pub async fn start_review(db_pool: &PgPool, admin_id: Uuid, dispute_id: i32) -> AppResult<Dispute> {
+    let mut tx = db_pool.begin().await?;
+    transition(&mut tx, dispute_id, admin_id, DisputeStatus::UnderReview, None).await?;
+    sqlx::query("UPDATE disputes SET assigned_to = $2 WHERE id = $1")
+        .bind(dispute_id)
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let dispute = fetch(db_pool, dispute_id).await?;
+    info!("Dispute {} under review by {}", dispute_id, admin_id);
+    notify_parties(
+        db_pool,
+        &dispute,
+        None,
+        "Your dispute is under review",
+        &format!("An admin is now reviewing dispute #{}", dispute_id),
+    )
+    .await;
+    Ok(dispute)
+}
+
+/// Resolves a live dispute. For a paid session whose payment is frozen,
+/// `refund_cents` go back to the mentee and the rest is released to the
+/// mentor. Without an amount, a mentee's upheld dispute is refunded in full
+/// and any other ruling refunds nothing; a partially upheld one needs an
+/// amount.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the dispute does not exist,
+/// `AppError::BadRequest` if it is closed, the refund amount is missing or
+/// more than is left of the payment, or an error if the refund or the
+/// database operation fails.
+pub async fn resolve(
+    db_pool: &PgPool,
+    stripe: Option<&StripeClient>,
+    admin_id: Uuid,
+    dispute_id: i32,
+    resolution: Resolution<'_>,
+) -> AppResult<Dispute> {
+    let dispute = fetch(db_pool, dispute_id).await?;
+    let frozen = match dispute.booking_id {
+        Some(booking_id) => mentor_payments::disputed_amount(db_pool, booking_id).await?,
+        None => None,
+    };
+    let refund_cents = match (&frozen, resolution.refund_cents) {
+        (None, Some(cents)) if cents > 0 => {
+            return Err(AppError::BadRequest("The dispute has no frozen payment to refund".to_string()));
+        }
+        (None, _) => None,
+        (Some(_), Some(cents)) => Some(cents),
+        (Some(_), None) if resolution.outcome == DisputeOutcome::PartiallyUpheld => {
+            return Err(AppError::BadRequest(
+                "A partially upheld dispute needs the amount to refund".to_string(),
+            ));
+        }
+        (Some((remaining, _)), None) => {
+            let mentee_opened = sqlx::query_scalar::<_, bool>(
+                "SELECT mentee_id = $2 FROM mentor_bookings WHERE id = $1",
+            )
+            .bind(dispute.booking_id)
+            .bind(dispute.opened_by)
+            .fetch_one(db_pool)
+            .await?;
+            Some(if resolution.outcome == DisputeOutcome::Upheld && mentee_opened { *remaining } else { 0 })
+        }
+    };
+
+    // Hold the dispute while settling, so a concurrent ruling waits and
+    // then finds it resolved
+    let mut tx = db_pool.begin().await?;
+    transition(&mut tx, dispute_id, admin_id, DisputeStatus::Resolved, Some(resolution.note)).await?;
+    let refunded = match (dispute.booking_id, refund_cents) {
+        (Some(booking_id), Some(cents)) => Some(
+            mentor_payments::settle_dispute(db_pool, stripe, booking_id, dispute_id, cents).await?,
+        ),
+        _ => None,
+    };
+    sqlx::query(
+        r#"
+        UPDATE disputes
+        SET outcome = $2, refund_cents = $3, resolution_note = $4, resolved_by = $5, resolved_at = NOW(),
+            assigned_to = COALESCE(assigned_to, $5)
+        WHERE id = $1
+        "#,
+    )
+    .bind(dispute_id)
+    .bind(resolution.outcome)
+    .bind(refunded)
+    .bind(resolution.note.trim())
+    .bind(admin_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let dispute = fetch(db_pool, dispute_id).await?;
+    info!(
+        "Dispute {} resolved by {}: {:?}, refunded {:?}",
+        dispute_id, admin_id, resolution.outcome, refunded
+    );
+    let refund_note = match (refunded, &frozen) {
+        (Some(cents), Some((_, currency))) if cents > 0 => format!(
+            " {}.{:02} {} is refunded to the mentee.",
+            cents / 100,
+            cents % 100,
+            currency.to_uppercase()
+        ),
+        _ => String::new(),
+    };
+    notify_parties(
+        db_pool,
+        &dispute,
+        None,
+        "Your dispute was resolved",
+        &format!(
+            "Dispute #{} was {}: {}{}",
+            dispute_id,
+            outcome_label(resolution.outcome),
+            resolution.note.trim(),
+            refund_note
+        ),
+    )
+    .await;
+    Ok(dispute)
+}
@@ -0,0 +1,56 @@
+//! Outbound email.
+//!
+//! Messages are handed to a mail relay: `EMAIL_WEBHOOK_URL` receives a JSON
+//! `POST` of `{"to", "subject", "text"}`, with `EMAIL_WEBHOOK_TOKEN` as a
+//! bearer token when set. Most transactional email services accept this
+//! through a small relay function. Without a relay outside production,
+//! messages are logged instead so flows that email a code can be tried
+//! locally.
+
+use std::time::Duration;
+
+use reqwest::Client;
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::errors::{AppError, AppResult};
+
+/// Sends a plain-text email through the configured relay.
+///
+/// # Errors
+///
+/// Returns `AppError::ConfigurationError` if no relay is configured in
+/// production, or `AppError::ExternalServiceError` if the relay rejects the
+/// message or cannot be reached.
+pub async fn send(to: &str, subject: &str, text: &str) -> AppResult<()> {
+    let Some(url) = std::env::var("EMAIL_WEBHOOK_URL").ok().filter(|u| !u.trim().is_empty()) else {
+        if std::env::var("RUST_ENV").is_ok_and(|v| v == "production") {
+            return Err(AppError::ConfigurationError("Email delivery not configured".to_string()));
+        }
+        warn!("EMAIL_WEBHOOK_URL not set; email to {} not sent. {}: {}", to, subject, text);
+        return Ok(());
+    };
+
+    let client = Client::builder()
+        .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    let mut request = client.post(url.trim()).json(&json!({ "to": to, "subject": subject, "text": text }));
+    if let Ok(token) = std::env::var("EMAIL_WEBHOOK_TOKEN")
+        && !token.is_empty()
+    {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        error!("Email relay unreachable: {}", e);
+        AppError::ExternalServiceError("Email could not be sent".to_string())
+    })?;
+    if !response.status().is_success() {
+        error!("Email relay returned HTTP {}", response.status());
+        return Err(AppError::ExternalServiceError("Email could not be sent".to_string()));
+    }
+    info!("Email \"{}\" sent to {}", subject, to);
+    Ok(())
+}
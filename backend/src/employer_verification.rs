@@ -0,0 +1,696 @@
+//! Employer verification.
+//!
+//! A company must be verified before its members can post jobs. The team
+//! owner opens a verification attempt and completes three steps:
+//! - confirms a work email on a company domain with an emailed code,
+//! - uploads a registration document (certificate of incorporation, trade
+//!   license, or similar),
+//! - submits the company's registration number, which is looked up in a
+//!   business registry when `BUSINESS_REGISTRY_URL` is configured.
+//!
+//! Submitted attempts wait in an admin queue with the registry result
+//! attached. Approval marks the company verified, which unlocks posting
+//! (see [`require_may_post`]) and shows a "verified employer" badge on its
+//! jobs (`employer_verified` on [`crate::models::Job`]). A rejected attempt
+//! keeps its reason; the owner may start a new one.
+
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::email;
+use crate::errors::{AppError, AppResult};
+use crate::models::{EmployerVerificationStatus, UserRole};
+use crate::storage::Storage;
+use crate::teams;
+
+/// Email domains of free mail providers, which do not show that the sender
+/// works at a company.
+pub const PERSONAL_EMAIL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "yahoo.com",
+    "outlook.com",
+    "hotmail.com",
+    "live.com",
+    "msn.com",
+    "icloud.com",
+    "me.com",
+    "aol.com",
+    "proton.me",
+    "protonmail.com",
+    "gmx.com",
+    "gmx.net",
+    "mail.com",
+    "yandex.com",
+    "yandex.ru",
+    "zoho.com",
+];
+
+/// Registration document types: extension and content type.
+pub const DOCUMENT_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+];
+
+/// Largest registration document accepted.
+pub const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Minutes an emailed code stays valid.
+const EMAIL_CODE_TTL_MINUTES: i32 = 30;
+
+/// Wrong codes before a new code must be requested.
+const MAX_EMAIL_CODE_ATTEMPTS: i16 = 5;
+
+/// A verification attempt, without its secrets.
+#[derive(Debug, Serialize, FromRow)]
+pub struct EmployerVerification {
+    pub id: i32,
+    pub company_id: i32,
+    pub status: EmployerVerificationStatus,
+    pub work_email: Option<String>,
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub registration_number: Option<String>,
+    pub registration_country: Option<String>,
+    /// `found`, `not_found`, `unavailable`, or `skipped` when no registry is
+    /// configured
+    pub registry_status: Option<String>,
+    /// What the registry returned, such as the registered name
+    pub registry_details: Option<Value>,
+    pub document_file_name: Option<String>,
+    pub submitted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Reason given when the attempt was rejected
+    pub review_note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The caller's company verification status and its latest attempt.
+#[derive(Debug, Serialize)]
+pub struct VerificationOverview {
+    pub company_id: i32,
+    pub status: EmployerVerificationStatus,
+    pub verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub latest: Option<EmployerVerification>,
+}
+
+/// A submitted attempt in the admin queue.
+#[derive(Debug, Serialize, FromRow)]
+pub struct QueuedVerification {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub verification: EmployerVerification,
+    pub company_name: String,
+    pub company_website: Option<String>,
+    pub requested_by_email: Option<String>,
+}
+
+const VERIFICATION_COLUMNS: &str = "v.id, v.company_id, v.status, v.work_email, v.email_verified_at, \
+     v.registration_number, v.registration_country, v.registry_status, v.registry_details, \
+     v.document_file_name, v.submitted_at, v.reviewed_at, v.review_note, v.created_at, v.updated_at";
+
+/// What the business registry said about a registration number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryOutcome {
+    /// The registry knows the company; carries its answer
+    Found(Value),
+    /// The registry has no company with this number
+    NotFound,
+    /// No usable answer: timeout, connection error, or server error
+    Unavailable(String),
+}
+
+/// Client for the business registry lookup hook.
+///
+/// `BUSINESS_REGISTRY_URL` is called with `GET ?country=<ISO code>&number=<registration number>`
+/// and `BUSINESS_REGISTRY_API_KEY` as a bearer token when set. It answers
+/// `{"found": bool, "name": ..., "status": ...}`, so any registry (or an
+/// aggregator in front of several) can be plugged in.
+#[derive(Clone)]
+pub struct BusinessRegistry {
+    client: Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+impl BusinessRegistry {
+    /// Returns the configured registry, or `None` when
+    /// `BUSINESS_REGISTRY_URL` is not set.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("BUSINESS_REGISTRY_URL").ok().filter(|u| !u.trim().is_empty())?;
+        Some(Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url: url.trim().to_string(),
+            api_key: std::env::var("BUSINESS_REGISTRY_API_KEY").ok().filter(|k| !k.is_empty()),
+        })
+    }
+
+    /// Looks up a registration number.
+    pub async fn lookup(&self, country: &str, number: &str) -> RegistryOutcome {
+        let mut request = self
+            .client
+            .get(&self.url)
+            .query(&[("country", country), ("number", number)])
+            .header("Accept", "application/json");
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => return RegistryOutcome::Unavailable(e.to_string()),
+        };
+
+        match response.status() {
+            StatusCode::NOT_FOUND => return RegistryOutcome::NotFound,
+            status if !status.is_success() => {
+                return RegistryOutcome::Unavailable(format!("The registry returned HTTP {}", status));
+            }
+            _ => {}
+        }
+        match response.json::<Value>().await {
+            Ok(answer) if answer.get("found").and_then(Value::as_bool) == Some(false) => RegistryOutcome::NotFound,
+            Ok(answer) => RegistryOutcome::Found(answer),
+            Err(e) => RegistryOutcome::Unavailable(format!("Invalid registry response: {}", e)),
+        }
+    }
+}
+
+/// The domain of a work email, lowercased, if it may be used for
+/// verification: not a free mail provider, and on the company's website
+/// domain (or a subdomain of it) when the company lists a website.
+fn work_email_domain(email: &str, website: Option<&str>) -> Result<String, String> {
+    let domain = email
+        .trim()
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.to_lowercase())
+        .filter(|domain| domain.contains('.'))
+        .ok_or_else(|| "Enter a valid work email".to_string())?;
+    if PERSONAL_EMAIL_DOMAINS.contains(&domain.as_str()) {
+        return Err("Use an email address on your company's domain, not a personal mailbox".to_string());
+    }
+
+    let site_domain = website
+        .and_then(|w| reqwest::Url::parse(w.trim()).ok())
+        .and_then(|url| url.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()));
+    if let Some(site_domain) = site_domain
+        && domain != site_domain
+        && !domain.ends_with(&format!(".{}", site_domain))
+    {
+        return Err(format!("Use an email address on {}, your company's website domain", site_domain));
+    }
+    Ok(domain)
+}
+
+/// The company's open attempt, started if there is none.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the company is already verified or its
+/// attempt is awaiting review.
+async fn open_attempt(db_pool: &PgPool, company_id: i32, user_id: Uuid) -> AppResult<EmployerVerification> {
+    let company_status =
+        sqlx::query_scalar::<_, EmployerVerificationStatus>("SELECT verification_status FROM companies WHERE id = $1")
+            .bind(company_id)
+            .fetch_one(db_pool)
+            .await?;
+    if company_status == EmployerVerificationStatus::Verified {
+        return Err(AppError::BadRequest("Your company is already verified".to_string()));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO employer_verifications (company_id, requested_by)
+        VALUES ($1, $2)
+        ON CONFLICT (company_id) WHERE status IN ('unverified', 'pending') DO NOTHING
+        "#,
+    )
+    .bind(company_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await?;
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        "SELECT {} FROM employer_verifications v WHERE v.company_id = $1 AND v.status IN ('unverified', 'pending')",
+        VERIFICATION_COLUMNS
+    ))
+    .bind(company_id)
+    .fetch_one(db_pool)
+    .await?;
+    if attempt.status == EmployerVerificationStatus::Pending {
+        return Err(AppError::BadRequest(
+            "Your verification is awaiting review and can no longer be changed".to_string(),
+        ));
+    }
+    Ok(attempt)
+}
+
+/// The company's verification status and latest attempt.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn overview(db_pool: &PgPool, company_id: i32) -> AppResult<VerificationOverview> {
+    let (status, verified_at) = sqlx::query_as::<_, (EmployerVerificationStatus, Option<chrono::DateTime<chrono::Utc>>)>(
+        "SELECT verification_status, verified_at FROM companies WHERE id = $1",
+    )
+    .bind(company_id)
+    .fetch_one(db_pool)
+    .await?;
+    let latest = sqlx::query_as::<_, EmployerVerification>(&format!(
+        "SELECT {} FROM employer_verifications v WHERE v.company_id = $1 ORDER BY v.created_at DESC, v.id DESC LIMIT 1",
+        VERIFICATION_COLUMNS
+    ))
+    .bind(company_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(VerificationOverview {
+        company_id,
+        status,
+        verified_at,
+        latest,
+    })
+}
+
+/// Emails a six-digit code to a work email on the company's domain.
+/// Requesting a new code replaces the previous one.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the email is not on a company
+/// domain, `AppError::BadRequest` if the company is verified or awaiting
+/// review, or an error if sending the email or the database operation fails.
+pub async fn request_email_code(
+    db_pool: &PgPool,
+    company_id: i32,
+    user_id: Uuid,
+    work_email: &str,
+) -> AppResult<EmployerVerification> {
+    let website = sqlx::query_scalar::<_, Option<String>>("SELECT website FROM companies WHERE id = $1")
+        .bind(company_id)
+        .fetch_one(db_pool)
+        .await?;
+    work_email_domain(work_email, website.as_deref()).map_err(AppError::ValidationError)?;
+    let work_email = work_email.trim().to_lowercase();
+    let attempt = open_attempt(db_pool, company_id, user_id).await?;
+
+    let code = format!("{:06}", rand::random_range(0..1_000_000u32));
+    email::send(
+        &work_email,
+        "Your CareerBridge employer verification code",
+        &format!(
+            "Your code is {}. It expires in {} minutes. If you did not ask to verify your company on CareerBridge, ignore this email.",
+            code, EMAIL_CODE_TTL_MINUTES
+        ),
+    )
+    .await?;
+
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        r#"
+        UPDATE employer_verifications v
+        SET work_email = $2, email_code_hash = $3,
+            email_code_expires_at = NOW() + make_interval(mins => $4),
+            email_code_attempts = 0, email_verified_at = NULL, updated_at = NOW()
+        WHERE v.id = $1
+        RETURNING {}
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .bind(attempt.id)
+    .bind(&work_email)
+    .bind(teams::hash_token(&code))
+    .bind(EMAIL_CODE_TTL_MINUTES)
+    .fetch_one(db_pool)
+    .await?;
+
+    info!("Verification code sent for company {}", company_id);
+    Ok(attempt)
+}
+
+/// Confirms the work email with the emailed code.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if no code is outstanding, it expired, too
+/// many wrong codes were tried, or the code is wrong, or an error if the
+/// database operation fails.
+pub async fn confirm_email(db_pool: &PgPool, company_id: i32, user_id: Uuid, code: &str) -> AppResult<EmployerVerification> {
+    let attempt = open_attempt(db_pool, company_id, user_id).await?;
+    let (code_hash, expired, attempts) = sqlx::query_as::<_, (Option<String>, bool, i16)>(
+        r#"
+        SELECT email_code_hash, COALESCE(email_code_expires_at < NOW(), TRUE), email_code_attempts
+        FROM employer_verifications
+        WHERE id = $1
+        "#,
+    )
+    .bind(attempt.id)
+    .fetch_one(db_pool)
+    .await?;
+
+    let Some(code_hash) = code_hash else {
+        return Err(AppError::BadRequest("Request a verification code first".to_string()));
+    };
+    if expired || attempts >= MAX_EMAIL_CODE_ATTEMPTS {
+        return Err(AppError::BadRequest(
+            "This code has expired; request a new one".to_string(),
+        ));
+    }
+    if teams::hash_token(code.trim()) != code_hash {
+        sqlx::query("UPDATE employer_verifications SET email_code_attempts = email_code_attempts + 1 WHERE id = $1")
+            .bind(attempt.id)
+            .execute(db_pool)
+            .await?;
+        return Err(AppError::BadRequest("The code is incorrect".to_string()));
+    }
+
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        r#"
+        UPDATE employer_verifications v
+        SET email_code_hash = NULL, email_code_expires_at = NULL,
+            email_verified_at = NOW(), updated_at = NOW()
+        WHERE v.id = $1
+        RETURNING {}
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .bind(attempt.id)
+    .fetch_one(db_pool)
+    .await?;
+    info!("Work email verified for company {}", company_id);
+    Ok(attempt)
+}
+
+/// Stores the registration document, replacing an earlier upload.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the company is verified or awaiting
+/// review, or an error if storage or the database operation fails.
+pub async fn upload_document(
+    db_pool: &PgPool,
+    storage: &Storage,
+    company_id: i32,
+    user_id: Uuid,
+    file_name: &str,
+    extension: &str,
+    data: &[u8],
+) -> AppResult<EmployerVerification> {
+    let attempt = open_attempt(db_pool, company_id, user_id).await?;
+    let storage_key = format!("employer-verification/{}/{}.{}", company_id, Uuid::new_v4(), extension);
+    storage.put(&storage_key, data).await?;
+
+    let previous_key = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT document_storage_key FROM employer_verifications WHERE id = $1",
+    )
+    .bind(attempt.id)
+    .fetch_one(db_pool)
+    .await?;
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        r#"
+        UPDATE employer_verifications v
+        SET document_file_name = $2, document_storage_key = $3, updated_at = NOW()
+        WHERE v.id = $1
+        RETURNING {}
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .bind(attempt.id)
+    .bind(file_name)
+    .bind(&storage_key)
+    .fetch_one(db_pool)
+    .await?;
+
+    if let Some(previous_key) = previous_key
+        && let Err(e) = storage.delete(&previous_key).await
+    {
+        warn!("Failed to delete replaced verification document {}: {}", previous_key, e);
+    }
+    info!("Verification document uploaded for company {}", company_id);
+    Ok(attempt)
+}
+
+/// Submits the attempt for admin review with the company's registration
+/// number, looking it up in the business registry first.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the work email is not confirmed or no
+/// document was uploaded, or the company is verified or awaiting review, or
+/// an error if the database operation fails.
+pub async fn submit(
+    db_pool: &PgPool,
+    company_id: i32,
+    user_id: Uuid,
+    registration_number: &str,
+    registration_country: &str,
+) -> AppResult<EmployerVerification> {
+    let attempt = open_attempt(db_pool, company_id, user_id).await?;
+    if attempt.email_verified_at.is_none() {
+        return Err(AppError::BadRequest("Confirm a work email before submitting".to_string()));
+    }
+    if attempt.document_file_name.is_none() {
+        return Err(AppError::BadRequest(
+            "Upload a registration document before submitting".to_string(),
+        ));
+    }
+
+    let registration_number = registration_number.trim();
+    let registration_country = registration_country.trim().to_uppercase();
+    let (registry_status, registry_details) = match BusinessRegistry::from_env() {
+        Some(registry) => match registry.lookup(&registration_country, registration_number).await {
+            RegistryOutcome::Found(answer) => ("found", Some(answer)),
+            RegistryOutcome::NotFound => ("not_found", None),
+            RegistryOutcome::Unavailable(reason) => {
+                warn!("Business registry lookup for company {} failed: {}", company_id, reason);
+                ("unavailable", Some(json!({ "error": reason })))
+            }
+        },
+        None => ("skipped", None),
+    };
+
+    let mut tx = db_pool.begin().await?;
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        r#"
+        UPDATE employer_verifications v
+        SET status = 'pending', registration_number = $2, registration_country = $3,
+            registry_status = $4, registry_details = $5, submitted_at = NOW(), updated_at = NOW()
+        WHERE v.id = $1
+        RETURNING {}
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .bind(attempt.id)
+    .bind(registration_number)
+    .bind(&registration_country)
+    .bind(registry_status)
+    .bind(registry_details)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE companies SET verification_status = 'pending' WHERE id = $1")
+        .bind(company_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("Company {} submitted for verification (registry: {})", company_id, registry_status);
+    teams::record_activity(
+        db_pool,
+        user_id,
+        company_id,
+        "verification.submit",
+        "employer_verification",
+        &attempt.id.to_string(),
+        json!({ "registry_status": registry_status }),
+    )
+    .await;
+    Ok(attempt)
+}
+
+/// Submitted attempts awaiting review, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_pending(db_pool: &PgPool) -> AppResult<Vec<QueuedVerification>> {
+    let queue = sqlx::query_as::<_, QueuedVerification>(&format!(
+        r#"
+        SELECT {}, c.name AS company_name, c.website AS company_website, u.email AS requested_by_email
+        FROM employer_verifications v
+        JOIN companies c ON c.id = v.company_id
+        LEFT JOIN users u ON u.id = v.requested_by
+        WHERE v.status = 'pending'
+        ORDER BY v.submitted_at
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .fetch_all(db_pool)
+    .await?;
+    Ok(queue)
+}
+
+/// The registration document of an attempt: file name, content type, and
+/// bytes.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the attempt does not exist or has no
+/// document, or an error if storage or the database operation fails.
+pub async fn document(db_pool: &PgPool, storage: &Storage, verification_id: i32) -> AppResult<(String, &'static str, Vec<u8>)> {
+    let (file_name, storage_key) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT document_file_name, document_storage_key FROM employer_verifications WHERE id = $1",
+    )
+    .bind(verification_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let (Some(file_name), Some(storage_key)) = (file_name, storage_key) else {
+        return Err(AppError::NotFound);
+    };
+
+    let extension = storage_key.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    let content_type = DOCUMENT_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, content_type)| *content_type)
+        .unwrap_or("application/octet-stream");
+    let bytes = storage.get(&storage_key).await?;
+    Ok((file_name, content_type, bytes))
+}
+
+/// Approves or rejects a submitted attempt, updates the company's status,
+/// and notifies the employer who requested it.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the attempt does not exist or is not
+/// awaiting review, or an error if the database operation fails.
+pub async fn review(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    verification_id: i32,
+    decision: EmployerVerificationStatus,
+    note: Option<&str>,
+) -> AppResult<EmployerVerification> {
+    let mut tx = db_pool.begin().await?;
+    let attempt = sqlx::query_as::<_, EmployerVerification>(&format!(
+        r#"
+        UPDATE employer_verifications v
+        SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_note = $4, updated_at = NOW()
+        WHERE v.id = $1 AND v.status = 'pending'
+        RETURNING {}
+        "#,
+        VERIFICATION_COLUMNS
+    ))
+    .bind(verification_id)
+    .bind(decision)
+    .bind(admin_id)
+    .bind(note)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    sqlx::query(
+        r#"
+        UPDATE companies
+        SET verification_status = $2,
+            verified_at = CASE WHEN $2 = 'verified'::employer_verification_status THEN NOW() ELSE NULL END
+        WHERE id = $1
+        "#,
+    )
+    .bind(attempt.company_id)
+    .bind(decision)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, company_id, action, target_type, target_id, metadata)
+        VALUES ($1, $2, $3, 'employer_verification', $4, $5)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(attempt.company_id)
+    .bind(match decision {
+        EmployerVerificationStatus::Verified => "verification.approve",
+        _ => "verification.reject",
+    })
+    .bind(verification_id.to_string())
+    .bind(json!({ "reason": note }))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    info!("Employer verification {} {:?} by admin {}", verification_id, decision, admin_id);
+
+    let (title, message) = match decision {
+        EmployerVerificationStatus::Verified => (
+            "Your company is verified".to_string(),
+            "Your company is now a verified employer. You can post jobs, and they show a verified badge.".to_string(),
+        ),
+        _ => (
+            "Company verification rejected".to_string(),
+            format!(
+                "Your company verification was not approved: {}. You can start a new verification.",
+                note.unwrap_or("no reason given")
+            ),
+        ),
+    };
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, title, message, type)
+        SELECT requested_by, $2, $3, 'employer_verification'
+        FROM employer_verifications
+        WHERE id = $1 AND requested_by IS NOT NULL
+        "#,
+    )
+    .bind(verification_id)
+    .bind(&title)
+    .bind(&message)
+    .execute(db_pool)
+    .await
+    {
+        warn!("Failed to notify employer about verification {}: {}", verification_id, e);
+    }
+    Ok(attempt)
+}
+
+/// Checks that a user may publish job postings: admins always may, and
+/// employers only through a seat at a verified company.
+///
+/// # Errors
+///
+/// Returns `AppError::PreconditionFailed` if the user's company is not
+/// verified or they hold no seat, or an error if the database operation
+/// fails.
+pub async fn require_may_post(db_pool: &PgPool, user_id: Uuid, role: UserRole) -> AppResult<()> {
+    if role == UserRole::Admin {
+        return Ok(());
+    }
+    let verified = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT employer_verified(m.company_id)
+        FROM company_members m
+        WHERE m.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?;
+    match verified {
+        Some(true) => Ok(()),
+        Some(false) => Err(AppError::PreconditionFailed(
+            "Your company must be a verified employer to post jobs; complete verification at /api/employer/verification".to_string(),
+        )),
+        None => Err(AppError::PreconditionFailed(
+            "Create or join a company and verify it before posting jobs".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,103 @@
+//! Encryption at rest for sensitive uploads.
+//!
+//! Documents such as job offer letters are encrypted with AES-256-GCM before
+//! they are written to storage or the database. Each value gets a fresh
+//! random 96-bit nonce, stored in front of its ciphertext. The key comes from
+//! `DOCUMENT_ENCRYPTION_KEY` (64 hex characters); without it, features that
+//! need encryption at rest are unavailable rather than storing plaintext.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use tracing::error;
+
+use crate::errors::{AppError, AppResult};
+
+/// Bytes of nonce stored in front of each ciphertext.
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM cipher for documents encrypted at rest.
+#[derive(Clone)]
+pub struct DocumentCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for DocumentCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DocumentCipher").finish_non_exhaustive()
+    }
+}
+
+impl DocumentCipher {
+    /// Creates a cipher from a 32-byte key.
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(key.into()),
+        }
+    }
+
+    /// Creates a cipher from `DOCUMENT_ENCRYPTION_KEY`, or `None` if it is
+    /// unset or not 64 hex characters.
+    pub fn from_env() -> Option<Self> {
+        let hex_key = std::env::var("DOCUMENT_ENCRYPTION_KEY").ok()?;
+        let key: Option<[u8; 32]> = hex::decode(hex_key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok());
+        if key.is_none() {
+            error!("DOCUMENT_ENCRYPTION_KEY must be 64 hex characters; document encryption is disabled");
+        }
+        key.map(|key| Self::new(&key))
+    }
+
+    /// Encrypts `plaintext`, returning the nonce followed by the ciphertext.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if encryption fails.
+    pub fn encrypt(&self, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| {
+                error!("Document encryption failed");
+                AppError::InternalServerError
+            })?;
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypts a value produced by [`DocumentCipher::encrypt`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if the value is truncated or
+    /// was not encrypted with this key.
+    pub fn decrypt(&self, sealed: &[u8]) -> AppResult<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            error!("Encrypted document is truncated");
+            return Err(AppError::InternalServerError);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                error!("Document decryption failed");
+                AppError::InternalServerError
+            })
+    }
+
+    /// Decrypts a value encrypted from UTF-8 text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if decryption fails or the
+    /// plaintext is not UTF-8.
+    pub fn decrypt_text(&self, sealed: &[u8]) -> AppResult<String> {
+        String::from_utf8(self.decrypt(sealed)?).map_err(|_| {
+            error!("Decrypted document text is not UTF-8");
+            AppError::InternalServerError
+        })
+    }
+}
@@ -26,6 +26,21 @@ pub enum AppError {
     /// Validation errors from validator crate
     #[error("Validation failed")]
     ValidatorErrors(#[from] ValidationErrors),
+
+    /// Validation errors of a typed request body, answered with
+    /// `422 Unprocessable Entity`
+    #[error("Validation failed")]
+    UnprocessableFields(ValidationErrors),
+
+    /// A JSON request body that could not be read, or did not match the
+    /// expected shape
+    #[error("{message}")]
+    InvalidJson {
+        status: StatusCode,
+        message: String,
+        /// Path of the offending field, e.g. `provider`
+        field: Option<String>,
+    },
     
     /// Database operation error
     #[error("Database error")]
@@ -35,6 +50,10 @@ pub enum AppError {
     #[error("Unauthorized")]
     Unauthorized,
     
+    /// Authenticated user lacks permission for the resource
+    #[error("Forbidden")]
+    Forbidden,
+    
     /// Requested resource not found
     #[error("Not found")]
     NotFound,
@@ -50,6 +69,38 @@ pub enum AppError {
     /// External service error (e.g., AI API)
     #[error("External service error: {0}")]
     ExternalServiceError(String),
+    
+    /// Feature requires a paid plan
+    #[error("{0}")]
+    PaymentRequired(String),
+    
+    /// Usage quota or rate limit exceeded
+    #[error("{0}")]
+    TooManyRequests(String),
+
+    /// A conditional request's precondition (e.g. `If-Match`) failed
+    #[error("{0}")]
+    PreconditionFailed(String),
+
+    /// The server is too busy to take the request right now
+    #[error("{0}")]
+    ServiceUnavailable(String),
+
+    /// An AI provider's safety filters blocked the prompt or the response
+    #[error("The AI provider's safety filters blocked this request ({reason})")]
+    ContentBlocked {
+        /// Block or finish reason reported by the provider, e.g. `safety`
+        reason: String,
+        /// Harm categories that triggered the block, e.g. `harassment`
+        categories: Vec<String>,
+    },
+
+    /// The user must accept policy versions in force before continuing
+    #[error("Please accept the updated policies to continue")]
+    PolicyAcceptanceRequired {
+        /// Kind and version of each policy awaiting acceptance
+        pending: Vec<(String, i32)>,
+    },
 }
 
 impl IntoResponse for AppError {
@@ -59,12 +110,26 @@ impl IntoResponse for AppError {
         // Log appropriately based on error type
         match &self {
             AppError::ValidationError(msg) => debug!("Validation error: {}", msg),
-            AppError::ValidatorErrors(_) => debug!("Validation errors: {:?}", self),
+            AppError::ValidatorErrors(_) | AppError::UnprocessableFields(_) => {
+                debug!("Validation errors: {:?}", self)
+            }
+            AppError::InvalidJson { message, .. } => debug!("Invalid JSON body: {}", message),
             AppError::Unauthorized => debug!("Unauthorized access attempt"),
+            AppError::Forbidden => warn!("Forbidden access attempt"),
             AppError::NotFound => debug!("Resource not found"),
             AppError::BadRequest(msg) => warn!("Bad request: {}", msg),
             AppError::ConfigurationError(msg) => error!("Configuration error: {}", msg),
             AppError::ExternalServiceError(msg) => error!("External service error: {}", msg),
+            AppError::PaymentRequired(msg) => debug!("Payment required: {}", msg),
+            AppError::TooManyRequests(msg) => debug!("Too many requests: {}", msg),
+            AppError::PreconditionFailed(msg) => debug!("Precondition failed: {}", msg),
+            AppError::ServiceUnavailable(msg) => debug!("Service unavailable: {}", msg),
+            AppError::ContentBlocked { reason, categories } => {
+                warn!("AI content blocked ({}): {:?}", reason, categories)
+            }
+            AppError::PolicyAcceptanceRequired { pending } => {
+                debug!("Policy acceptance required: {:?}", pending)
+            }
             AppError::DatabaseError(err) => {
                 // Check if it's a user error (like duplicate key) vs system error
                 if let Some(db_err) = err.as_database_error() {
@@ -80,7 +145,7 @@ impl IntoResponse for AppError {
             AppError::InternalServerError => error!("Internal server error: {:?}", self),
         }
 
-        let (status, error_message) = match self {
+        let (status, mut error_message) = match self {
             AppError::InternalServerError => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 json!({"error": "Internal Server Error"}),
@@ -95,6 +160,16 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_REQUEST,
                 json!({"errors": errors})
             ),
+
+            AppError::UnprocessableFields(errors) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                json!({"errors": errors})
+            ),
+
+            AppError::InvalidJson { status, message, field } => (
+                status,
+                json!({"error": message, "field": field})
+            ),
             
             AppError::DatabaseError(err) => {
                 if let Some(db_err) = err.as_database_error() {
@@ -109,7 +184,7 @@ impl IntoResponse for AppError {
                         
                         return (
                             StatusCode::CONFLICT,
-                            Json(json!({"error": message}))
+                            Json(json!({"success": false, "error": message}))
                         ).into_response();
                     }
                 }
@@ -125,6 +200,11 @@ impl IntoResponse for AppError {
                 json!({"error": "Unauthorized"})
             ),
             
+            AppError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                json!({"error": "Forbidden"})
+            ),
+            
             AppError::NotFound => (
                 StatusCode::NOT_FOUND,
                 json!({"error": "Not found"})
@@ -143,9 +223,54 @@ impl IntoResponse for AppError {
             AppError::ExternalServiceError(msg) => (
                 StatusCode::BAD_GATEWAY,
                 json!({"error": msg})
+            ),
+            
+            AppError::PaymentRequired(msg) => (
+                StatusCode::PAYMENT_REQUIRED,
+                json!({"error": msg})
+            ),
+            
+            AppError::TooManyRequests(msg) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                json!({"error": msg})
+            ),
+
+            AppError::PreconditionFailed(msg) => (
+                StatusCode::PRECONDITION_FAILED,
+                json!({"error": msg})
+            ),
+
+            AppError::ServiceUnavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                json!({"error": msg})
+            ),
+
+            AppError::ContentBlocked { ref reason, ref categories } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                json!({
+                    "error": self.to_string(),
+                    "block_reason": reason,
+                    "blocked_categories": categories
+                })
+            ),
+
+            AppError::PolicyAcceptanceRequired { ref pending } => (
+                StatusCode::FORBIDDEN,
+                json!({
+                    "error": self.to_string(),
+                    "pending_policies": pending
+                        .iter()
+                        .map(|(kind, version)| json!({"kind": kind, "version": version}))
+                        .collect::<Vec<_>>()
+                })
             )
         };
 
+        // Errors share the response envelope's `success` flag
+        if let Some(body) = error_message.as_object_mut() {
+            body.insert("success".to_string(), json!(false));
+        }
+
         (status, Json(error_message)).into_response()
     }
 }
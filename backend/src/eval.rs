@@ -0,0 +1,468 @@
+//! Evaluation of AI outputs against golden fixtures.
+//!
+//! [`run`] sends a fixed set of cases — CVs for skill extraction, goals for
+//! roadmap generation, and mentor questions — to every configured provider
+//! with deterministic sampling, and scores each output on three checks:
+//!
+//! - schema: the output parses into the action's response type
+//! - fields: the expected fields are present and not empty
+//! - keywords: the expected keywords appear in the output
+//!
+//! Each result is stored in `ai_eval_results` with the model and prompt
+//! version that produced it, so a prompt or model change can be compared with
+//! the trend of earlier runs before it is rolled out. Run it with
+//! `cargo run -- eval [label]`; it is scheduled nightly on Render.
+//!
+//! Eval requests have no user, so they are never charged credits; they are
+//! recorded in `ai_usage_logs` like any other provider call.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+
+use crate::ai::AIService;
+use crate::ai::types::{
+    AIActionRequest, AIProvider, ActionType, ExtractedSkills, QuestionResponse, TechStackRoadmap,
+};
+use crate::errors::AppResult;
+
+/// Lowest share of expected keywords for a case to pass.
+const KEYWORD_PASS_SCORE: f64 = 0.5;
+
+/// Default lowest pass rate for `cargo run -- eval` to succeed.
+const DEFAULT_MIN_PASS_RATE: f64 = 0.8;
+
+/// A fixed input and what a good output must contain.
+#[derive(Debug)]
+pub struct EvalCase {
+    pub name: &'static str,
+    pub action: ActionType,
+    pub input: &'static str,
+    pub parameters: Value,
+    /// Dotted paths into the output, e.g. `phases.0.topics`
+    pub required_fields: &'static [&'static str],
+    /// Matched case-insensitively anywhere in the output
+    pub keywords: &'static [&'static str],
+}
+
+/// The golden cases every run evaluates.
+pub fn cases() -> Vec<EvalCase> {
+    vec![
+        EvalCase {
+            name: "extract_skills_backend_cv",
+            action: ActionType::ExtractSkills,
+            input: "Jane Doe - Backend Developer. 3 years building REST APIs in Python with Django and \
+                    FastAPI. Designed PostgreSQL schemas and Redis caching for a payments service. \
+                    Containerized services with Docker and deployed them to AWS (ECS, S3). Mentored two \
+                    junior developers and led sprint planning. B.Sc. in Computer Science. AWS Certified \
+                    Developer - Associate.",
+            parameters: json!({}),
+            required_fields: &["technical_skills", "soft_skills", "roles", "certifications"],
+            keywords: &["python", "django", "postgresql", "docker", "aws"],
+        },
+        EvalCase {
+            name: "extract_skills_data_analyst_cv",
+            action: ActionType::ExtractSkills,
+            input: "Data Analyst with 2 years of experience. Wrote SQL queries against a Snowflake \
+                    warehouse, built Tableau and Power BI dashboards for the sales team, and automated \
+                    weekly Excel reports with Python (pandas). Presented findings to leadership. \
+                    B.A. in Economics.",
+            parameters: json!({}),
+            required_fields: &["technical_skills", "roles", "education"],
+            keywords: &["sql", "tableau", "power bi", "excel", "pandas"],
+        },
+        EvalCase {
+            name: "roadmap_frontend_developer",
+            action: ActionType::GenerateRoadmap,
+            input: "Frontend Developer",
+            parameters: json!({
+                "current_skills": "HTML, CSS",
+                "timeframe_months": 6,
+                "learning_hours_per_week": 10
+            }),
+            required_fields: &[
+                "stack_name",
+                "phases",
+                "phases.0.topics",
+                "phases.0.learning_goals",
+                "estimated_duration",
+                "project_suggestions",
+            ],
+            keywords: &["javascript", "react", "git"],
+        },
+        EvalCase {
+            name: "roadmap_data_engineer",
+            action: ActionType::GenerateRoadmap,
+            input: "Data Engineer",
+            parameters: json!({
+                "current_skills": "Python, SQL",
+                "timeframe_months": 9,
+                "learning_hours_per_week": 8
+            }),
+            required_fields: &["stack_name", "phases", "phases.0.topics", "project_suggestions"],
+            keywords: &["spark", "airflow", "pipeline", "sql"],
+        },
+        EvalCase {
+            name: "question_backend_path",
+            action: ActionType::AskQuestion,
+            input: "What should I learn to become a backend developer?",
+            parameters: json!({
+                "context": "User's current skills: Python\nTarget roles: Backend Developer\nExperience level: Some(Fresher)"
+            }),
+            required_fields: &["answer"],
+            keywords: &["api", "database", "sql"],
+        },
+        EvalCase {
+            name: "question_system_design_prep",
+            action: ActionType::AskQuestion,
+            input: "How do I prepare for a system design interview?",
+            parameters: json!({
+                "context": "User's current skills: Java, Spring, MySQL\nTarget roles: Software Engineer\nExperience level: Some(Mid)"
+            }),
+            required_fields: &["answer", "related_topics"],
+            keywords: &["scal", "cach", "load balanc", "trade-off"],
+        },
+    ]
+}
+
+/// Score of one case from one provider.
+#[derive(Debug, Serialize)]
+pub struct CaseResult {
+    pub case_name: &'static str,
+    pub action: &'static str,
+    pub provider: &'static str,
+    pub model: &'static str,
+    pub prompt_version: &'static str,
+    pub schema_valid: bool,
+    pub missing_fields: Vec<String>,
+    pub keyword_score: f64,
+    pub score: f64,
+    pub passed: bool,
+    pub latency_ms: i32,
+    pub error: Option<String>,
+}
+
+/// A stored eval run.
+#[derive(Debug, Serialize, FromRow)]
+pub struct EvalRun {
+    pub id: i32,
+    pub label: Option<String>,
+    pub case_count: i32,
+    pub pass_count: i32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl EvalRun {
+    /// Share of cases that passed.
+    pub fn pass_rate(&self) -> f64 {
+        if self.case_count == 0 {
+            return 0.0;
+        }
+        f64::from(self.pass_count) / f64::from(self.case_count)
+    }
+}
+
+/// Results of one run for one provider, model, action, and prompt version.
+#[derive(Debug, Serialize, FromRow)]
+pub struct RunSummary {
+    pub provider: String,
+    pub model: String,
+    pub action: String,
+    pub prompt_version: String,
+    pub cases: i64,
+    pub passed: i64,
+    pub avg_score: f64,
+    /// Average score for the provider and action in the latest earlier run,
+    /// whatever its model or prompt version
+    pub previous_score: Option<f64>,
+}
+
+/// Daily results for one provider, model, action, and prompt version.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TrendPoint {
+    pub day: NaiveDate,
+    pub provider: String,
+    pub model: String,
+    pub action: String,
+    pub prompt_version: String,
+    pub cases: i64,
+    pub pass_rate: f64,
+    pub avg_score: f64,
+    pub avg_latency_ms: f64,
+}
+
+/// Returns the lowest passing rate from `EVAL_MIN_PASS_RATE` (default: 0.8).
+pub fn min_pass_rate() -> f64 {
+    std::env::var("EVAL_MIN_PASS_RATE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|r| (0.0..=1.0).contains(r))
+        .unwrap_or(DEFAULT_MIN_PASS_RATE)
+}
+
+/// Runs every case against every configured provider and stores the results.
+///
+/// A failing provider call fails its case, not the run.
+///
+/// # Errors
+///
+/// Returns an error if the results cannot be stored.
+pub async fn run(db_pool: &PgPool, ai: &AIService, label: Option<&str>) -> AppResult<EvalRun> {
+    let run_id = sqlx::query_scalar::<_, i32>("INSERT INTO ai_eval_runs (label) VALUES ($1) RETURNING id")
+        .bind(label)
+        .fetch_one(db_pool)
+        .await?;
+
+    let cases = cases();
+    let mut case_count = 0;
+    let mut pass_count = 0;
+    for provider in ai.providers() {
+        for case in &cases {
+            let result = evaluate(ai, provider.clone(), case).await;
+            if let Some(error) = &result.error {
+                warn!("Eval case {} failed on {}: {}", result.case_name, result.provider, error);
+            }
+            store_result(db_pool, run_id, &result).await?;
+            case_count += 1;
+            if result.passed {
+                pass_count += 1;
+            }
+        }
+    }
+
+    let run = sqlx::query_as::<_, EvalRun>(
+        r#"
+        UPDATE ai_eval_runs
+        SET case_count = $2, pass_count = $3, finished_at = NOW()
+        WHERE id = $1
+        RETURNING id, label, case_count, pass_count, started_at, finished_at
+        "#,
+    )
+    .bind(run_id)
+    .bind(case_count)
+    .bind(pass_count)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(run)
+}
+
+/// Sends one case to `provider` and scores the output.
+async fn evaluate(ai: &AIService, provider: AIProvider, case: &EvalCase) -> CaseResult {
+    let provider_name = provider.as_str();
+    let mut parameters = case.parameters.clone();
+    if let Some(p) = parameters.as_object_mut() {
+        p.insert("deterministic".to_string(), json!(true));
+    }
+    let request = AIActionRequest {
+        user_id: None,
+        action: case.action.clone(),
+        provider: provider.clone(),
+        input: case.input.to_string(),
+        parameters: Some(parameters),
+        capture: true,
+    };
+
+    let started = std::time::Instant::now();
+    let response = ai.process_action(request).await;
+    let latency_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+
+    let failed = |model: &'static str, error: String| CaseResult {
+        case_name: case.name,
+        action: case.action.as_str(),
+        provider: provider_name,
+        model,
+        prompt_version: case.action.prompt_version(),
+        schema_valid: false,
+        missing_fields: case.required_fields.iter().map(|f| f.to_string()).collect(),
+        keyword_score: 0.0,
+        score: 0.0,
+        passed: false,
+        latency_ms,
+        error: Some(error),
+    };
+    let response = match response {
+        Ok(response) if response.success => response,
+        Ok(response) => {
+            let error = response.message.unwrap_or_else(|| "provider call failed".to_string());
+            return failed(response.model, error);
+        }
+        Err(e) => return failed(ai.model_for(&provider), e.to_string()),
+    };
+
+    let schema_valid = schema_valid(&case.action, &response.data);
+    let missing_fields: Vec<String> = case
+        .required_fields
+        .iter()
+        .filter(|path| !field_present(&response.data, path))
+        .map(|path| path.to_string())
+        .collect();
+    let keyword_score = keyword_score(&response.data, case.keywords);
+
+    let field_score = if case.required_fields.is_empty() {
+        1.0
+    } else {
+        1.0 - missing_fields.len() as f64 / case.required_fields.len() as f64
+    };
+    let score = (f64::from(u8::from(schema_valid)) + field_score + keyword_score) / 3.0;
+    let passed = schema_valid && missing_fields.is_empty() && keyword_score >= KEYWORD_PASS_SCORE;
+
+    CaseResult {
+        case_name: case.name,
+        action: case.action.as_str(),
+        provider: response.provider.as_str(),
+        model: response.model,
+        prompt_version: response.prompt_version,
+        schema_valid,
+        missing_fields,
+        keyword_score,
+        score,
+        passed,
+        latency_ms,
+        error: None,
+    }
+}
+
+/// Whether the output parses into the action's response type.
+fn schema_valid(action: &ActionType, data: &Value) -> bool {
+    match action {
+        ActionType::ExtractSkills => serde_json::from_value::<ExtractedSkills>(data.clone()).is_ok(),
+        ActionType::GenerateRoadmap => serde_json::from_value::<TechStackRoadmap>(data.clone()).is_ok(),
+        ActionType::AskQuestion => serde_json::from_value::<QuestionResponse>(data.clone()).is_ok(),
+        _ => data.is_object(),
+    }
+}
+
+/// Whether the dotted `path` leads to a value that is not null or empty.
+fn field_present(data: &Value, path: &str) -> bool {
+    let value = path.split('.').try_fold(data, |value, segment| match segment.parse::<usize>() {
+        Ok(index) => value.get(index),
+        Err(_) => value.get(segment),
+    });
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::String(s)) => !s.trim().is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Object(fields)) => !fields.is_empty(),
+        Some(_) => true,
+    }
+}
+
+/// Share of `keywords` found in the output, case-insensitively.
+fn keyword_score(data: &Value, keywords: &[&str]) -> f64 {
+    if keywords.is_empty() {
+        return 1.0;
+    }
+    let text = data.to_string().to_lowercase();
+    let found = keywords.iter().filter(|k| text.contains(&k.to_lowercase())).count();
+    found as f64 / keywords.len() as f64
+}
+
+async fn store_result(db_pool: &PgPool, run_id: i32, result: &CaseResult) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO ai_eval_results
+            (run_id, case_name, action, provider, model, prompt_version, schema_valid,
+             missing_fields, keyword_score, score, passed, latency_ms, error)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#,
+    )
+    .bind(run_id)
+    .bind(result.case_name)
+    .bind(result.action)
+    .bind(result.provider)
+    .bind(result.model)
+    .bind(result.prompt_version)
+    .bind(result.schema_valid)
+    .bind(&result.missing_fields)
+    .bind(result.keyword_score)
+    .bind(result.score)
+    .bind(result.passed)
+    .bind(result.latency_ms)
+    .bind(&result.error)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+/// Summarizes a run per provider, model, action, and prompt version, with the
+/// previous run's score for comparison.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn summarize(db_pool: &PgPool, run_id: i32) -> AppResult<Vec<RunSummary>> {
+    let summary = sqlx::query_as::<_, RunSummary>(
+        r#"
+        SELECT r.provider, r.model, r.action, r.prompt_version,
+               COUNT(*) AS cases,
+               COUNT(*) FILTER (WHERE r.passed) AS passed,
+               AVG(r.score) AS avg_score,
+               (
+                   SELECT AVG(p.score)
+                   FROM ai_eval_results p
+                   WHERE p.provider = r.provider AND p.action = r.action
+                     AND p.run_id = (
+                         SELECT MAX(q.run_id) FROM ai_eval_results q
+                         WHERE q.run_id < $1 AND q.provider = r.provider AND q.action = r.action
+                     )
+               ) AS previous_score
+        FROM ai_eval_results r
+        WHERE r.run_id = $1
+        GROUP BY r.provider, r.model, r.action, r.prompt_version
+        ORDER BY r.provider, r.action
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(summary)
+}
+
+/// Lists the most recent runs, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn recent_runs(db_pool: &PgPool, limit: i64) -> AppResult<Vec<EvalRun>> {
+    let runs = sqlx::query_as::<_, EvalRun>(
+        r#"
+        SELECT id, label, case_count, pass_count, started_at, finished_at
+        FROM ai_eval_runs
+        ORDER BY started_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(runs)
+}
+
+/// Daily pass rates and scores over the last `days` days, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn trends(db_pool: &PgPool, days: i32) -> AppResult<Vec<TrendPoint>> {
+    let points = sqlx::query_as::<_, TrendPoint>(
+        r#"
+        SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day,
+               provider, model, action, prompt_version,
+               COUNT(*) AS cases,
+               AVG(CASE WHEN passed THEN 1.0 ELSE 0.0 END)::DOUBLE PRECISION AS pass_rate,
+               AVG(score) AS avg_score,
+               AVG(latency_ms)::DOUBLE PRECISION AS avg_latency_ms
+        FROM ai_eval_results
+        WHERE created_at > NOW() - make_interval(days => $1)
+        GROUP BY day, provider, model, action, prompt_version
+        ORDER BY day DESC, provider, action
+        "#,
+    )
+    .bind(days)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(points)
+}
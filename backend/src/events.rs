@@ -0,0 +1,115 @@
+//! Career-fair events: attendee badges and iCalendar schedules.
+//!
+//! Organizations create career fairs with sessions and employer booths.
+//! Attendees RSVP to a fair, optionally picking sessions, and receive a badge
+//! code (shown as a QR code by the frontend). Recruiters at a booth scan the
+//! badge to record an interaction; each interaction is a lead in the
+//! company's pipeline that can be linked to a job and turned into an
+//! invitation to apply.
+//!
+//! Schedules are exported as iCalendar (RFC 5545) so attendees can add them
+//! to any calendar app.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Product identifier written to exported calendars.
+const PRODID: &str = "-//CareerBridge//Career Fairs//EN";
+
+/// Maximum line length in octets before folding, per RFC 5545.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// One event in an exported calendar.
+#[derive(Debug, Clone)]
+pub struct CalendarEntry {
+    /// Globally unique identifier, stable across exports
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    /// Link to the event, such as a video meeting
+    pub url: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Generates a badge code for an attendee.
+pub fn new_badge_code() -> String {
+    Uuid::new_v4().simple().to_string()[..8].to_uppercase()
+}
+
+/// Renders entries as an iCalendar document with CRLF line endings.
+pub fn to_ics(calendar_name: &str, entries: &[CalendarEntry]) -> String {
+    let stamp = format_timestamp(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    ];
+
+    for entry in entries {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", entry.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_timestamp(entry.starts_at)));
+        lines.push(format!("DTEND:{}", format_timestamp(entry.ends_at)));
+        lines.push(format!("SUMMARY:{}", escape_text(&entry.summary)));
+        if let Some(description) = entry.description.as_deref().filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = entry.location.as_deref().filter(|l| !l.is_empty()) {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some(url) = entry.url.as_deref().filter(|u| !u.is_empty()) {
+            lines.push(format!("URL:{}", url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold_line(&line));
+        ics.push_str("\r\n");
+    }
+    ics
+}
+
+fn format_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line at 75 octets without splitting UTF-8 characters.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts toward the limit
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
@@ -0,0 +1,305 @@
+//! Right-to-left text in PDFs.
+//!
+//! PDF text is drawn glyph by glyph, left to right, with no shaping.
+//! Arabic-script text (Arabic, Urdu, Persian) is first [`shape`]d into the
+//! presentation forms of its letters: initial, medial, final, or isolated,
+//! and the lam-alef ligatures. Each wrapped line is then put in display
+//! order by the Unicode Bidirectional Algorithm ([`reorder`]), with brackets
+//! mirrored in right-to-left runs and combining marks kept after their
+//! letter. HTML and plain text exports keep the logical order; browsers and
+//! editors lay them out.
+//!
+//! ```
+//! use backend::export::bidi::{self, Direction};
+//!
+//! // "Salam" in Arabic: seen joins the lam-alef ligature, meem stands alone
+//! assert_eq!(bidi::shape("سلام", |_| true), "\u{FEB3}\u{FEFC}\u{FEE1}");
+//! // Urdu letters too, unless the font lacks their forms
+//! assert_eq!(bidi::shape("پاک", |_| true), "\u{FB58}\u{FE8E}\u{FB8E}");
+//! assert_eq!(bidi::shape("پاک", |c| c < '\u{FB50}'), "پاک");
+//!
+//! // Hebrew runs are reversed, brackets mirrored, and Latin left in order
+//! let text = "שלום (Rust)";
+//! let lines = bidi::reorder(text, &[0..text.len()], Direction::Ltr);
+//! assert_eq!(lines.direction, Direction::Rtl);
+//! assert_eq!(lines.lines, ["(Rust) םולש"]);
+//! ```
+
+use std::ops::Range;
+
+use unicode_bidi::{BidiClass, Level, ParagraphBidiInfo, bidi_class};
+
+/// Direction a paragraph is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// Lines of a paragraph in display order.
+#[derive(Debug)]
+pub struct Reordered {
+    /// The paragraph's direction: right-to-left lines are aligned right
+    pub direction: Direction,
+    pub lines: Vec<String>,
+}
+
+fn strong(c: char) -> Option<Direction> {
+    match bidi_class(c) {
+        BidiClass::L => Some(Direction::Ltr),
+        BidiClass::R | BidiClass::AL => Some(Direction::Rtl),
+        _ => None,
+    }
+}
+
+/// Direction of the first letter of `text` with a strong direction, as
+/// HTML's `dir="auto"` picks it.
+pub fn first_strong(text: &str) -> Option<Direction> {
+    text.chars().find_map(strong)
+}
+
+/// Direction most letters of `texts` are written in; left-to-right on a tie.
+pub fn dominant<'a>(texts: impl IntoIterator<Item = &'a str>) -> Direction {
+    let (mut ltr, mut rtl) = (0usize, 0usize);
+    for c in texts.into_iter().flat_map(str::chars) {
+        match strong(c) {
+            Some(Direction::Ltr) => ltr += 1,
+            Some(Direction::Rtl) => rtl += 1,
+            None => {}
+        }
+    }
+    if rtl > ltr { Direction::Rtl } else { Direction::Ltr }
+}
+
+/// Whether `c` is a combining mark drawn over or under the letter before
+/// it: Arabic vowel signs and Quranic marks, Hebrew points, and general
+/// diacritics.
+pub fn is_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{0591}'..='\u{05BD}'
+            | '\u{05BF}'
+            | '\u{05C1}'..='\u{05C2}'
+            | '\u{05C4}'..='\u{05C5}'
+            | '\u{05C7}'
+            | '\u{0610}'..='\u{061A}'
+            | '\u{064B}'..='\u{065F}'
+            | '\u{0670}'
+            | '\u{06D6}'..='\u{06DC}'
+            | '\u{06DF}'..='\u{06E4}'
+            | '\u{06E7}'..='\u{06E8}'
+            | '\u{06EA}'..='\u{06ED}'
+    )
+}
+
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        c => c,
+    }
+}
+
+/// Puts the `lines` of single-line `text` (byte ranges, as wrapped in
+/// logical order) in display order, left to right. The paragraph takes the
+/// direction of its first strong letter, or `fallback` if it has none.
+pub fn reorder(text: &str, lines: &[Range<usize>], fallback: Direction) -> Reordered {
+    let direction = first_strong(text).unwrap_or(fallback);
+    let level = match direction {
+        Direction::Ltr => Level::ltr(),
+        Direction::Rtl => Level::rtl(),
+    };
+    let info = ParagraphBidiInfo::new(text, Some(level));
+    let lines = lines
+        .iter()
+        .map(|line| {
+            let (levels, runs) = info.visual_runs(line.clone());
+            let mut visual = String::with_capacity(line.len());
+            for run in runs {
+                if !levels[run.start].is_rtl() {
+                    visual.push_str(&text[run]);
+                    continue;
+                }
+                // Reversed a letter at a time, each followed by its marks
+                let mut clusters: Vec<String> = Vec::new();
+                for c in text[run].chars() {
+                    match clusters.last_mut() {
+                        Some(cluster) if is_mark(c) => cluster.push(c),
+                        _ => clusters.push(mirror(c).to_string()),
+                    }
+                }
+                visual.extend(clusters.into_iter().rev());
+            }
+            visual
+        })
+        .collect();
+    Reordered { direction, lines }
+}
+
+const TATWEEL: char = '\u{0640}';
+const ZWJ: char = '\u{200D}';
+const LAM: char = '\u{0644}';
+
+/// Isolated, final, and (for letters that join on both sides) initial and
+/// medial forms of a letter.
+type Forms = (char, char, Option<(char, char)>);
+
+/// Presentation forms of Arabic-script letters: the letter and its
+/// [`Forms`].
+const FORMS: &[(char, Forms)] = &[
+    ('\u{0622}', ('\u{FE81}', '\u{FE82}', None)), // alef with madda above
+    ('\u{0623}', ('\u{FE83}', '\u{FE84}', None)), // alef with hamza above
+    ('\u{0624}', ('\u{FE85}', '\u{FE86}', None)), // waw with hamza above
+    ('\u{0625}', ('\u{FE87}', '\u{FE88}', None)), // alef with hamza below
+    ('\u{0626}', ('\u{FE89}', '\u{FE8A}', Some(('\u{FE8B}', '\u{FE8C}')))), // yeh with hamza above
+    ('\u{0627}', ('\u{FE8D}', '\u{FE8E}', None)), // alef
+    ('\u{0628}', ('\u{FE8F}', '\u{FE90}', Some(('\u{FE91}', '\u{FE92}')))), // beh
+    ('\u{0629}', ('\u{FE93}', '\u{FE94}', None)), // teh marbuta
+    ('\u{062A}', ('\u{FE95}', '\u{FE96}', Some(('\u{FE97}', '\u{FE98}')))), // teh
+    ('\u{062B}', ('\u{FE99}', '\u{FE9A}', Some(('\u{FE9B}', '\u{FE9C}')))), // theh
+    ('\u{062C}', ('\u{FE9D}', '\u{FE9E}', Some(('\u{FE9F}', '\u{FEA0}')))), // jeem
+    ('\u{062D}', ('\u{FEA1}', '\u{FEA2}', Some(('\u{FEA3}', '\u{FEA4}')))), // hah
+    ('\u{062E}', ('\u{FEA5}', '\u{FEA6}', Some(('\u{FEA7}', '\u{FEA8}')))), // khah
+    ('\u{062F}', ('\u{FEA9}', '\u{FEAA}', None)), // dal
+    ('\u{0630}', ('\u{FEAB}', '\u{FEAC}', None)), // thal
+    ('\u{0631}', ('\u{FEAD}', '\u{FEAE}', None)), // reh
+    ('\u{0632}', ('\u{FEAF}', '\u{FEB0}', None)), // zain
+    ('\u{0633}', ('\u{FEB1}', '\u{FEB2}', Some(('\u{FEB3}', '\u{FEB4}')))), // seen
+    ('\u{0634}', ('\u{FEB5}', '\u{FEB6}', Some(('\u{FEB7}', '\u{FEB8}')))), // sheen
+    ('\u{0635}', ('\u{FEB9}', '\u{FEBA}', Some(('\u{FEBB}', '\u{FEBC}')))), // sad
+    ('\u{0636}', ('\u{FEBD}', '\u{FEBE}', Some(('\u{FEBF}', '\u{FEC0}')))), // dad
+    ('\u{0637}', ('\u{FEC1}', '\u{FEC2}', Some(('\u{FEC3}', '\u{FEC4}')))), // tah
+    ('\u{0638}', ('\u{FEC5}', '\u{FEC6}', Some(('\u{FEC7}', '\u{FEC8}')))), // zah
+    ('\u{0639}', ('\u{FEC9}', '\u{FECA}', Some(('\u{FECB}', '\u{FECC}')))), // ain
+    ('\u{063A}', ('\u{FECD}', '\u{FECE}', Some(('\u{FECF}', '\u{FED0}')))), // ghain
+    ('\u{0641}', ('\u{FED1}', '\u{FED2}', Some(('\u{FED3}', '\u{FED4}')))), // feh
+    ('\u{0642}', ('\u{FED5}', '\u{FED6}', Some(('\u{FED7}', '\u{FED8}')))), // qaf
+    ('\u{0643}', ('\u{FED9}', '\u{FEDA}', Some(('\u{FEDB}', '\u{FEDC}')))), // kaf
+    ('\u{0644}', ('\u{FEDD}', '\u{FEDE}', Some(('\u{FEDF}', '\u{FEE0}')))), // lam
+    ('\u{0645}', ('\u{FEE1}', '\u{FEE2}', Some(('\u{FEE3}', '\u{FEE4}')))), // meem
+    ('\u{0646}', ('\u{FEE5}', '\u{FEE6}', Some(('\u{FEE7}', '\u{FEE8}')))), // noon
+    ('\u{0647}', ('\u{FEE9}', '\u{FEEA}', Some(('\u{FEEB}', '\u{FEEC}')))), // heh
+    ('\u{0648}', ('\u{FEED}', '\u{FEEE}', None)), // waw
+    ('\u{0649}', ('\u{FEEF}', '\u{FEF0}', Some(('\u{FBE8}', '\u{FBE9}')))), // alef maksura
+    ('\u{064A}', ('\u{FEF1}', '\u{FEF2}', Some(('\u{FEF3}', '\u{FEF4}')))), // yeh
+    ('\u{0671}', ('\u{FB50}', '\u{FB51}', None)), // alef wasla
+    ('\u{0679}', ('\u{FB66}', '\u{FB67}', Some(('\u{FB68}', '\u{FB69}')))), // tteh
+    ('\u{067A}', ('\u{FB5E}', '\u{FB5F}', Some(('\u{FB60}', '\u{FB61}')))), // tteheh
+    ('\u{067B}', ('\u{FB52}', '\u{FB53}', Some(('\u{FB54}', '\u{FB55}')))), // beeh
+    ('\u{067E}', ('\u{FB56}', '\u{FB57}', Some(('\u{FB58}', '\u{FB59}')))), // peh
+    ('\u{067F}', ('\u{FB62}', '\u{FB63}', Some(('\u{FB64}', '\u{FB65}')))), // teheh
+    ('\u{0680}', ('\u{FB5A}', '\u{FB5B}', Some(('\u{FB5C}', '\u{FB5D}')))), // beheh
+    ('\u{0683}', ('\u{FB76}', '\u{FB77}', Some(('\u{FB78}', '\u{FB79}')))), // nyeh
+    ('\u{0684}', ('\u{FB72}', '\u{FB73}', Some(('\u{FB74}', '\u{FB75}')))), // dyeh
+    ('\u{0686}', ('\u{FB7A}', '\u{FB7B}', Some(('\u{FB7C}', '\u{FB7D}')))), // tcheh
+    ('\u{0687}', ('\u{FB7E}', '\u{FB7F}', Some(('\u{FB80}', '\u{FB81}')))), // tcheheh
+    ('\u{0688}', ('\u{FB88}', '\u{FB89}', None)), // ddal
+    ('\u{068C}', ('\u{FB84}', '\u{FB85}', None)), // dahal
+    ('\u{068D}', ('\u{FB82}', '\u{FB83}', None)), // ddahal
+    ('\u{068E}', ('\u{FB86}', '\u{FB87}', None)), // dul
+    ('\u{0691}', ('\u{FB8C}', '\u{FB8D}', None)), // rreh
+    ('\u{0698}', ('\u{FB8A}', '\u{FB8B}', None)), // jeh
+    ('\u{06A4}', ('\u{FB6A}', '\u{FB6B}', Some(('\u{FB6C}', '\u{FB6D}')))), // veh
+    ('\u{06A6}', ('\u{FB6E}', '\u{FB6F}', Some(('\u{FB70}', '\u{FB71}')))), // peheh
+    ('\u{06A9}', ('\u{FB8E}', '\u{FB8F}', Some(('\u{FB90}', '\u{FB91}')))), // keheh
+    ('\u{06AD}', ('\u{FBD3}', '\u{FBD4}', Some(('\u{FBD5}', '\u{FBD6}')))), // ng
+    ('\u{06AF}', ('\u{FB92}', '\u{FB93}', Some(('\u{FB94}', '\u{FB95}')))), // gaf
+    ('\u{06B1}', ('\u{FB9A}', '\u{FB9B}', Some(('\u{FB9C}', '\u{FB9D}')))), // ngoeh
+    ('\u{06B3}', ('\u{FB96}', '\u{FB97}', Some(('\u{FB98}', '\u{FB99}')))), // gueh
+    ('\u{06BA}', ('\u{FB9E}', '\u{FB9F}', None)), // noon ghunna
+    ('\u{06BB}', ('\u{FBA0}', '\u{FBA1}', Some(('\u{FBA2}', '\u{FBA3}')))), // rnoon
+    ('\u{06BE}', ('\u{FBAA}', '\u{FBAB}', Some(('\u{FBAC}', '\u{FBAD}')))), // heh doachashmee
+    ('\u{06C0}', ('\u{FBA4}', '\u{FBA5}', None)), // heh with yeh above
+    ('\u{06C1}', ('\u{FBA6}', '\u{FBA7}', Some(('\u{FBA8}', '\u{FBA9}')))), // heh goal
+    ('\u{06C5}', ('\u{FBE0}', '\u{FBE1}', None)), // kirghiz oe
+    ('\u{06C6}', ('\u{FBD9}', '\u{FBDA}', None)), // oe
+    ('\u{06C7}', ('\u{FBD7}', '\u{FBD8}', None)), // u
+    ('\u{06C8}', ('\u{FBDB}', '\u{FBDC}', None)), // yu
+    ('\u{06C9}', ('\u{FBE2}', '\u{FBE3}', None)), // kirghiz yu
+    ('\u{06CB}', ('\u{FBDE}', '\u{FBDF}', None)), // ve
+    ('\u{06CC}', ('\u{FBFC}', '\u{FBFD}', Some(('\u{FBFE}', '\u{FBFF}')))), // farsi yeh
+    ('\u{06D0}', ('\u{FBE4}', '\u{FBE5}', Some(('\u{FBE6}', '\u{FBE7}')))), // e
+    ('\u{06D2}', ('\u{FBAE}', '\u{FBAF}', None)), // yeh barree
+    ('\u{06D3}', ('\u{FBB0}', '\u{FBB1}', None)), // yeh barree with hamza above
+];
+
+/// Isolated and final forms of lam followed by each alef.
+const LAM_ALEF: &[(char, char, char)] = &[
+    ('\u{0622}', '\u{FEF5}', '\u{FEF6}'),
+    ('\u{0623}', '\u{FEF7}', '\u{FEF8}'),
+    ('\u{0625}', '\u{FEF9}', '\u{FEFA}'),
+    ('\u{0627}', '\u{FEFB}', '\u{FEFC}'),
+];
+
+fn forms(c: char) -> Option<Forms> {
+    FORMS
+        .binary_search_by_key(&c, |&(letter, _)| letter)
+        .ok()
+        .map(|index| FORMS[index].1)
+}
+
+/// Whether `c` joins the letter after it.
+fn joins_next(c: char) -> bool {
+    c == TATWEEL || c == ZWJ || forms(c).is_some_and(|(_, _, middle)| middle.is_some())
+}
+
+/// Whether `c` joins the letter before it.
+fn joins_previous(c: char) -> bool {
+    c == TATWEEL || c == ZWJ || forms(c).is_some()
+}
+
+/// Replaces Arabic-script letters with their presentation forms, as they
+/// join their neighbours. Forms `supported` rejects (the font has no glyph
+/// for them) are left as the plain letter.
+pub fn shape(text: &str, supported: impl Fn(char) -> bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let letter_before = |index: usize| chars[..index].iter().rev().copied().find(|&c| !is_mark(c));
+    let letter_after = |index: usize| chars[index + 1..].iter().copied().find(|&c| !is_mark(c));
+
+    let mut shaped = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let Some((isolated, last, middle)) = forms(c) else {
+            shaped.push(c);
+            index += 1;
+            continue;
+        };
+        let joined_before = letter_before(index).is_some_and(joins_next);
+
+        if c == LAM
+            && let Some(&(_, isolated, last)) = chars
+                .get(index + 1)
+                .and_then(|&alef| LAM_ALEF.iter().find(|&&(a, ..)| a == alef))
+        {
+            let ligature = if joined_before { last } else { isolated };
+            if supported(ligature) {
+                shaped.push(ligature);
+                index += 2;
+                continue;
+            }
+        }
+
+        let joined_after = middle.is_some() && letter_after(index).is_some_and(joins_previous);
+        let form = match (joined_before, joined_after, middle) {
+            (true, true, Some((_, medial))) => medial,
+            (false, true, Some((initial, _))) => initial,
+            (true, _, _) => last,
+            _ => isolated,
+        };
+        shaped.push(if supported(form) { form } else { c });
+        index += 1;
+    }
+    shaped
+}
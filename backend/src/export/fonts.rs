@@ -0,0 +1,141 @@
+//! Fonts embedded in PDF exports.
+//!
+//! The standard PDF fonts only cover Western European text, and printpdf
+//! leaves out characters a font has no glyph for, so PDFs with other text
+//! are written in TrueType fonts embedded in the file. Each typeface has a
+//! regular and a bold font, read from `EXPORT_FONT_SANS`,
+//! `EXPORT_FONT_SANS_BOLD`, `EXPORT_FONT_SERIF`, and
+//! `EXPORT_FONT_SERIF_BOLD` (default: DejaVu, as most Linux distributions
+//! install it, which covers Latin, Greek, Cyrillic, Arabic, Urdu, and
+//! Hebrew). A character the typeface has no glyph for is written in the
+//! other typeface, or else in the first of `EXPORT_FONT_FALLBACKS`
+//! (comma-separated paths, e.g. Noto Sans Bengali) that has one. Only the
+//! fonts a document uses are embedded, each whole, adding a few hundred
+//! kilobytes. Fonts are read on the first PDF that needs them and kept for
+//! the life of the process.
+
+use std::sync::OnceLock;
+
+use tracing::warn;
+use ttf_parser::Face;
+
+use super::Typeface;
+
+const DEJAVU: &str = "/usr/share/fonts/truetype/dejavu";
+
+/// A TrueType font read from disk.
+pub struct Font {
+    path: String,
+    data: Vec<u8>,
+}
+
+impl Font {
+    /// Reads the font named by `var`, or at `default` if it is unset.
+    /// Unreadable fonts are logged when configured and skipped.
+    fn from_env(var: &str, default: &str) -> Option<Font> {
+        let configured = std::env::var(var).ok().filter(|path| !path.trim().is_empty());
+        let path = configured.as_deref().map_or(default, str::trim);
+        let font = Self::read(path);
+        if let (Err(e), Some(_)) = (&font, &configured) {
+            warn!("{} ({}) cannot be used for PDF exports: {}", var, path, e);
+        }
+        font.ok()
+    }
+
+    fn read(path: &str) -> Result<Font, String> {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        Face::parse(&data, 0).map_err(|e| e.to_string())?;
+        Ok(Font {
+            path: path.to_string(),
+            data,
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, 0).expect("fonts are parsed when they are read")
+    }
+}
+
+/// The fonts of a PDF, with the order they are tried in for a character
+/// of regular and of bold text.
+pub struct FontSet {
+    pub fonts: Vec<Font>,
+    /// Indexes into `fonts`
+    pub regular: Vec<usize>,
+    pub bold: Vec<usize>,
+}
+
+impl FontSet {
+    /// The fonts of `typeface`, read on first use. `None` if the typeface's
+    /// regular font cannot be read.
+    pub fn get(typeface: Typeface) -> Option<&'static FontSet> {
+        static SANS: OnceLock<Option<FontSet>> = OnceLock::new();
+        static SERIF: OnceLock<Option<FontSet>> = OnceLock::new();
+        let cell = match typeface {
+            Typeface::Sans => &SANS,
+            Typeface::Serif => &SERIF,
+        };
+        cell.get_or_init(|| Self::load(typeface)).as_ref()
+    }
+
+    /// Reads the fonts of `typeface`, followed by those of the other
+    /// typeface and the fallbacks.
+    fn load(typeface: Typeface) -> Option<FontSet> {
+        let sans = || {
+            (
+                Font::from_env("EXPORT_FONT_SANS", &format!("{}/DejaVuSans.ttf", DEJAVU)),
+                Font::from_env("EXPORT_FONT_SANS_BOLD", &format!("{}/DejaVuSans-Bold.ttf", DEJAVU)),
+            )
+        };
+        let serif = || {
+            (
+                Font::from_env("EXPORT_FONT_SERIF", &format!("{}/DejaVuSerif.ttf", DEJAVU)),
+                Font::from_env("EXPORT_FONT_SERIF_BOLD", &format!("{}/DejaVuSerif-Bold.ttf", DEJAVU)),
+            )
+        };
+        let ((regular, bold), (other_regular, other_bold)) = match typeface {
+            Typeface::Sans => (sans(), serif()),
+            Typeface::Serif => (serif(), sans()),
+        };
+        let regular = regular?;
+
+        let mut set = FontSet {
+            fonts: Vec::new(),
+            regular: Vec::new(),
+            bold: Vec::new(),
+        };
+        let regular = set.push(regular);
+        let bold = bold.map(|font| set.push(font));
+        let other_regular = other_regular.map(|font| set.push(font));
+        let other_bold = other_bold.map(|font| set.push(font));
+        set.regular.extend([Some(regular), other_regular].into_iter().flatten());
+        // Covering the script matters more than the weight
+        set.bold.extend([bold, other_bold, Some(regular), other_regular].into_iter().flatten());
+
+        let fallbacks = std::env::var("EXPORT_FONT_FALLBACKS").unwrap_or_default();
+        for path in fallbacks.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match Font::read(path) {
+                Ok(font) => {
+                    let index = set.push(font);
+                    set.regular.push(index);
+                    set.bold.push(index);
+                }
+                Err(e) => warn!("EXPORT_FONT_FALLBACKS font {} cannot be used: {}", path, e),
+            }
+        }
+        Some(set)
+    }
+
+    fn push(&mut self, font: Font) -> usize {
+        self.fonts.push(font);
+        self.fonts.len() - 1
+    }
+}
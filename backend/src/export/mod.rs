@@ -0,0 +1,664 @@
+//! Exports of CVs and roadmaps in accessible formats.
+//!
+//! A CV or roadmap is first laid out as a [`Document`]: a title and a flat
+//! list of headings, paragraphs, and lists. [`render`] then writes it in the
+//! [`ExportFormat`] asked for:
+//!
+//! - `pdf`: A4 pages in the document's typeface (the default)
+//! - `large_print_pdf`: 18pt sans-serif body text with wide line spacing
+//! - `html`: a standalone page with one `h1`, a `section` per `h2`, and real
+//!   lists, for screen readers and browser zoom
+//! - `text`: plain UTF-8 text with underlined headings
+//!
+//! PDFs are written in the standard PDF fonts when all their text is
+//! Western European, and otherwise in embedded TrueType [`fonts`], so that
+//! any script the fonts cover is written. Arabic-script letters are joined and right-to-left lines
+//! put in display order and aligned right (see [`bidi`]). Lines wrap on
+//! word boundaries and continue on new pages as needed. HTML pages take the
+//! direction most of their text is written in, and every heading,
+//! paragraph, and list item its own (`dir="auto"`).
+//!
+//! ```
+//! use backend::export::{self, Block, Document, ExportFormat, Typeface, bidi::Direction};
+//!
+//! // An Urdu CV with an English skill
+//! let cv = Document {
+//!     title: "محمد علی".to_string(),
+//!     subtitle: Some("ali@example.com".to_string()),
+//!     blocks: vec![
+//!         Block::Heading("مہارتیں".to_string()),
+//!         Block::List(vec!["Rust".to_string(), "ڈیٹا کا تجزیہ".to_string()]),
+//!     ],
+//! };
+//! assert_eq!(cv.direction(), Direction::Rtl);
+//! let html = export::render(&cv, ExportFormat::Html, Typeface::Sans).unwrap().body;
+//! let html = String::from_utf8(html).unwrap();
+//! assert!(html.contains("<html dir=\"rtl\">"));
+//! assert!(html.contains("<li dir=\"auto\">ڈیٹا کا تجزیہ</li>"));
+//!
+//! // Russian and Greek read left to right
+//! let cv = Document {
+//!     title: "Анна Иванова".to_string(),
+//!     subtitle: None,
+//!     blocks: vec![Block::Paragraph("Αναλύτρια δεδομένων".to_string())],
+//! };
+//! assert_eq!(cv.direction(), Direction::Ltr);
+//! let text = export::render(&cv, ExportFormat::Text, Typeface::Serif).unwrap().body;
+//! assert_eq!(String::from_utf8(text).unwrap(), "Анна Иванова\n============\n\nΑναλύτρια δεδομένων\n");
+//! ```
+
+pub mod bidi;
+pub mod fonts;
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use serde::Deserialize;
+use tokio::task::spawn_blocking;
+use tracing::{error, warn};
+use ttf_parser::Face;
+
+use crate::errors::{AppError, AppResult};
+use bidi::Direction;
+use fonts::{Font, FontSet};
+
+/// Format of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Pdf,
+    /// PDF with large type
+    #[serde(alias = "large_print")]
+    LargePrintPdf,
+    /// Semantic HTML
+    Html,
+    /// Plain text
+    #[serde(alias = "txt")]
+    Text,
+}
+
+/// Typeface of a PDF export. Large print is always sans-serif.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Typeface {
+    Sans,
+    Serif,
+}
+
+/// A part of a document, in reading order.
+#[derive(Debug, Clone)]
+pub enum Block {
+    /// Starts a section (`h2`)
+    Heading(String),
+    /// A heading within a section (`h3`)
+    Subheading(String),
+    Paragraph(String),
+    List(Vec<String>),
+    OrderedList(Vec<String>),
+}
+
+/// A document to export.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub title: String,
+    /// A line under the title, e.g. contact details
+    pub subtitle: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+impl Document {
+    /// Every piece of text in the document, in reading order.
+    fn texts(&self) -> impl Iterator<Item = &str> {
+        let blocks = self.blocks.iter().flat_map(|block| match block {
+            Block::Heading(text) | Block::Subheading(text) | Block::Paragraph(text) => std::slice::from_ref(text),
+            Block::List(items) | Block::OrderedList(items) => items.as_slice(),
+        });
+        std::iter::once(&self.title).chain(&self.subtitle).chain(blocks).map(String::as_str)
+    }
+
+    /// Direction most of the document is written in.
+    pub fn direction(&self) -> Direction {
+        bidi::dominant(self.texts())
+    }
+}
+
+/// A rendered export.
+#[derive(Debug)]
+pub struct Export {
+    pub content_type: &'static str,
+    /// File extension, without the dot
+    pub extension: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Export {
+    /// Serves the export as a download named `file_stem` plus its extension.
+    /// Names outside ASCII, e.g. an Arabic name, are sent in `filename*`,
+    /// with an ASCII `filename` for older clients.
+    pub fn into_download(self, file_stem: &str) -> Response {
+        let ascii: String = file_stem
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            .collect();
+        let ascii = match ascii.trim_matches(|c| matches!(c, '-' | '_' | '.')) {
+            "" => "export",
+            stem => stem,
+        };
+        let file_name = format!("{}.{}", file_stem, self.extension);
+        (
+            [
+                (header::CONTENT_TYPE, self.content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"{}.{}\"; filename*=UTF-8''{}",
+                        ascii,
+                        self.extension,
+                        urlencoding::encode(&file_name)
+                    ),
+                ),
+            ],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+/// Renders `document` in `format`. `typeface` applies to standard PDFs.
+///
+/// # Errors
+///
+/// Returns `AppError::InternalServerError` if the PDF cannot be written.
+pub fn render(document: &Document, format: ExportFormat, typeface: Typeface) -> AppResult<Export> {
+    Ok(match format {
+        ExportFormat::Pdf => Export {
+            content_type: "application/pdf",
+            extension: "pdf",
+            body: pdf(document, &PdfStyle::STANDARD, typeface)?,
+        },
+        ExportFormat::LargePrintPdf => Export {
+            content_type: "application/pdf",
+            extension: "pdf",
+            body: pdf(document, &PdfStyle::LARGE_PRINT, Typeface::Sans)?,
+        },
+        ExportFormat::Html => Export {
+            content_type: "text/html; charset=utf-8",
+            extension: "html",
+            body: html(document).into_bytes(),
+        },
+        ExportFormat::Text => Export {
+            content_type: "text/plain; charset=utf-8",
+            extension: "txt",
+            body: text(document).into_bytes(),
+        },
+    })
+}
+
+/// Renders `document` like [`render`], on the blocking thread pool: PDFs
+/// with embedded fonts take long enough to stall other requests.
+///
+/// # Errors
+///
+/// Returns `AppError::InternalServerError` if the export cannot be written.
+pub async fn render_in_background(document: Document, format: ExportFormat, typeface: Typeface) -> AppResult<Export> {
+    spawn_blocking(move || render(&document, format, typeface))
+        .await
+        .map_err(|e| {
+            error!("Task join error during export: {}", e);
+            AppError::InternalServerError
+        })?
+}
+
+/// Type sizes in points and spacing of a PDF.
+struct PdfStyle {
+    title: f32,
+    heading: f32,
+    subheading: f32,
+    body: f32,
+    /// Line height as a multiple of the type size
+    line_spacing: f32,
+    margin: f32,
+}
+
+impl PdfStyle {
+    const STANDARD: PdfStyle = PdfStyle {
+        title: 16.0,
+        heading: 14.0,
+        subheading: 12.0,
+        body: 11.0,
+        line_spacing: 1.5,
+        margin: 20.0,
+    };
+
+    const LARGE_PRINT: PdfStyle = PdfStyle {
+        title: 28.0,
+        heading: 24.0,
+        subheading: 20.0,
+        body: 18.0,
+        line_spacing: 1.5,
+        margin: 18.0,
+    };
+}
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MM_PER_PT: f32 = 0.3528;
+
+/// Fonts text is written in.
+enum PdfFonts<'a> {
+    /// The standard fonts, for Western European text
+    Builtin { regular: IndirectFontRef, bold: IndirectFontRef },
+    /// Font files, embedded in the document when first used
+    Embedded {
+        set: &'a FontSet,
+        faces: Vec<Face<'a>>,
+        added: Vec<Option<IndirectFontRef>>,
+    },
+}
+
+/// Writes lines top to bottom, starting a new page when one is full.
+struct PdfWriter<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    style: &'a PdfStyle,
+    fonts: PdfFonts<'a>,
+    /// Direction of paragraphs with no letters, e.g. a date
+    direction: Direction,
+    /// Characters no font has a glyph for
+    missing: BTreeSet<char>,
+    y: f32,
+}
+
+impl PdfWriter<'_> {
+    fn line_height(&self, size: f32) -> f32 {
+        size * MM_PER_PT * self.style.line_spacing
+    }
+
+    fn space(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+
+    fn chain(&self, bold: bool) -> &[usize] {
+        match &self.fonts {
+            PdfFonts::Embedded { set, .. } if bold => &set.bold,
+            PdfFonts::Embedded { set, .. } => &set.regular,
+            PdfFonts::Builtin { .. } => &[],
+        }
+    }
+
+    /// Embedded font `c` is written in: the first with a glyph for it, or
+    /// the first of all if none has one.
+    fn font_for(&self, c: char, bold: bool) -> usize {
+        let chain = self.chain(bold);
+        match &self.fonts {
+            PdfFonts::Embedded { faces, .. } => chain
+                .iter()
+                .copied()
+                .find(|&font| faces[font].glyph_index(c).is_some())
+                .unwrap_or(chain[0]),
+            PdfFonts::Builtin { .. } => 0,
+        }
+    }
+
+    /// Whether some font has a glyph for `c`.
+    fn supports(&self, c: char, bold: bool) -> bool {
+        match &self.fonts {
+            PdfFonts::Embedded { faces, .. } => {
+                self.chain(bold).iter().any(|&font| faces[font].glyph_index(c).is_some())
+            }
+            PdfFonts::Builtin { .. } => is_win_ansi(c),
+        }
+    }
+
+    /// Width of `text` in millimetres at `size`. The standard fonts are
+    /// taken to average glyphs of about half the type size.
+    fn measure(&self, text: &str, size: f32, bold: bool) -> f32 {
+        let PdfFonts::Embedded { faces, .. } = &self.fonts else {
+            return text.chars().count() as f32 * size * MM_PER_PT * 0.52;
+        };
+        text.chars()
+            .map(|c| {
+                let face = &faces[self.font_for(c, bold)];
+                let advance = face.glyph_index(c).and_then(|glyph| face.glyph_hor_advance(glyph)).unwrap_or(0);
+                f32::from(advance) / f32::from(face.units_per_em())
+            })
+            .sum::<f32>()
+            * size
+            * MM_PER_PT
+    }
+
+    /// Writes `text` wrapped to the page width, aligned to the side its
+    /// direction starts from. Lines after the first start at `hanging` past
+    /// `indent`.
+    fn write(&mut self, text: &str, size: f32, bold: bool, indent: f32, hanging: f32) -> AppResult<()> {
+        let text = bidi::shape(&text.split_whitespace().collect::<Vec<_>>().join(" "), |c| {
+            self.supports(c, bold)
+        });
+        let missing: Vec<char> = text.chars().filter(|&c| !c.is_whitespace() && !self.supports(c, bold)).collect();
+        self.missing.extend(missing);
+
+        let width = PAGE_WIDTH - 2.0 * self.style.margin - indent;
+        let lines = wrap(&text, |line| self.measure(line, size, bold), width, width - hanging);
+        let paragraph = bidi::reorder(&text, &lines, self.direction);
+        for (index, line) in paragraph.lines.iter().enumerate() {
+            let height = self.line_height(size);
+            if self.y - height < self.style.margin {
+                let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+                self.layer = self.doc.get_page(page).get_layer(layer);
+                self.y = PAGE_HEIGHT - self.style.margin;
+            }
+            self.y -= height;
+            let offset = indent + if index == 0 { 0.0 } else { hanging };
+            let x = match paragraph.direction {
+                Direction::Ltr => self.style.margin + offset,
+                Direction::Rtl => PAGE_WIDTH - self.style.margin - offset - self.measure(line, size, bold),
+            };
+            self.draw(line, size, bold, x)?;
+        }
+        Ok(())
+    }
+
+    /// Draws a line in display order from `x`, switching fonts where the
+    /// current one has no glyph for a character.
+    fn draw(&mut self, line: &str, size: f32, bold: bool, x: f32) -> AppResult<()> {
+        if let PdfFonts::Builtin { regular, bold: bold_font } = &self.fonts {
+            let font = if bold { bold_font } else { regular };
+            self.layer.use_text(line, size, Mm(x), Mm(self.y), font);
+            return Ok(());
+        }
+
+        // Runs of characters in one font; spaces stay in the run they are in
+        let mut runs: Vec<(usize, String)> = Vec::new();
+        for c in line.chars() {
+            let font = match runs.last() {
+                Some(&(font, _)) if c.is_whitespace() => font,
+                _ => self.font_for(c, bold),
+            };
+            match runs.last_mut() {
+                Some((current, run)) if *current == font => run.push(c),
+                _ => runs.push((font, c.to_string())),
+            }
+        }
+
+        let mut x = x;
+        for (font, run) in runs {
+            let PdfFonts::Embedded { set, added, .. } = &mut self.fonts else {
+                unreachable!("the standard fonts are drawn above");
+            };
+            let font_ref = match &added[font] {
+                Some(font_ref) => font_ref.clone(),
+                None => {
+                    let font_ref = self.doc.add_external_font(set.fonts[font].data()).map_err(|e| {
+                        error!("Failed to embed font {}: {}", set.fonts[font].path(), e);
+                        AppError::InternalServerError
+                    })?;
+                    added[font] = Some(font_ref.clone());
+                    font_ref
+                }
+            };
+            self.layer.use_text(run.as_str(), size, Mm(x), Mm(self.y), &font_ref);
+            x += self.measure(&run, size, bold);
+        }
+        Ok(())
+    }
+}
+
+/// Whether the standard fonts can write `c`: it is in Windows-1252.
+fn is_win_ansi(c: char) -> bool {
+    matches!(c, ' '..='~' | '\u{A0}'..='\u{FF}') || "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ".contains(c)
+}
+
+/// Splits single-spaced text into lines no wider than `first` for the
+/// first line and `rest` after, on spaces where possible. Returns the byte
+/// range of each line.
+fn wrap(text: &str, measure: impl Fn(&str) -> f32, first: f32, rest: f32) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let (mut start, mut end) = (0, 0);
+    let mut width = first;
+    let mut offset = 0;
+    for word in text.split(' ') {
+        let (mut word_start, word_end) = (offset, offset + word.len());
+        offset = word_end + 1;
+        if end > start && measure(&text[start..word_end]) > width {
+            lines.push(start..end);
+            start = word_start;
+            width = rest;
+        }
+        // Words longer than a line are broken
+        while start == word_start && measure(&text[word_start..word_end]) > width {
+            let fits = text[word_start..word_end]
+                .char_indices()
+                .map(|(index, c)| word_start + index + c.len_utf8())
+                .take_while(|&split| measure(&text[word_start..split]) <= width)
+                .last();
+            let split = fits.unwrap_or_else(|| {
+                word_start + text[word_start..].chars().next().map_or(0, char::len_utf8)
+            });
+            if split >= word_end {
+                break;
+            }
+            lines.push(word_start..split);
+            word_start = split;
+            start = split;
+            width = rest;
+        }
+        end = word_end;
+    }
+    if end > start || lines.is_empty() {
+        lines.push(start..end);
+    }
+    lines
+}
+
+fn pdf(document: &Document, style: &PdfStyle, typeface: Typeface) -> AppResult<Vec<u8>> {
+    let (doc, page, layer) = PdfDocument::new(&document.title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    // The standard fonts keep Western European documents small
+    let western = document.texts().flat_map(str::chars).all(|c| c.is_whitespace() || is_win_ansi(c));
+    let font_set = if western { None } else { FontSet::get(typeface) };
+    let fonts = match font_set {
+        Some(set) => PdfFonts::Embedded {
+            set,
+            faces: set.fonts.iter().map(Font::face).collect(),
+            added: vec![None; set.fonts.len()],
+        },
+        None if western => {
+            let (regular, bold) = match typeface {
+                Typeface::Sans => (BuiltinFont::Helvetica, BuiltinFont::HelveticaBold),
+                Typeface::Serif => (BuiltinFont::TimesRoman, BuiltinFont::TimesBold),
+            };
+            let font_error = |e| {
+                error!("Failed to add font: {}", e);
+                AppError::InternalServerError
+            };
+            PdfFonts::Builtin {
+                regular: doc.add_builtin_font(regular).map_err(font_error)?,
+                bold: doc.add_builtin_font(bold).map_err(font_error)?,
+            }
+        }
+        None => {
+            return Err(AppError::ConfigurationError(
+                "PDF export of text outside Western European scripts needs a Unicode font; set EXPORT_FONT_SANS \
+                 (or export as HTML or text)"
+                    .to_string(),
+            ));
+        }
+    };
+
+    let mut writer = PdfWriter {
+        doc: &doc,
+        layer: doc.get_page(page).get_layer(layer),
+        style,
+        fonts,
+        direction: document.direction(),
+        missing: BTreeSet::new(),
+        y: PAGE_HEIGHT - style.margin,
+    };
+    writer.write(&document.title, style.title, true, 0.0, 0.0)?;
+    if let Some(subtitle) = &document.subtitle {
+        writer.write(subtitle, style.body, false, 0.0, 0.0)?;
+    }
+    for block in &document.blocks {
+        match block {
+            Block::Heading(heading) => {
+                writer.space(style.heading * MM_PER_PT);
+                writer.write(heading, style.heading, true, 0.0, 0.0)?;
+            }
+            Block::Subheading(heading) => {
+                writer.space(style.subheading * MM_PER_PT * 0.5);
+                writer.write(heading, style.subheading, true, 0.0, 0.0)?;
+            }
+            Block::Paragraph(paragraph) => writer.write(paragraph, style.body, false, 0.0, 0.0)?,
+            Block::List(items) => {
+                let hanging = style.body * MM_PER_PT * 1.5;
+                for item in items {
+                    writer.write(&format!("• {}", item), style.body, false, 0.0, hanging)?;
+                }
+            }
+            Block::OrderedList(items) => {
+                let hanging = style.body * MM_PER_PT * 2.0;
+                for (index, item) in items.iter().enumerate() {
+                    writer.write(&format!("{}. {}", index + 1, item), style.body, false, 0.0, hanging)?;
+                }
+            }
+        }
+    }
+    if !writer.missing.is_empty() {
+        warn!(
+            "PDF export has characters no font covers, left out: {:?}; add a font with them to EXPORT_FONT_FALLBACKS",
+            writer.missing.iter().collect::<String>()
+        );
+    }
+
+    doc.save_to_bytes().map_err(|e| {
+        error!("Failed to generate PDF bytes: {}", e);
+        AppError::InternalServerError
+    })
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,-apple-system,\"Segoe UI\",Arial,sans-serif;font-size:1.125rem;\
+line-height:1.6;max-width:42em;margin:0 auto;padding:1.5rem;color:#1a1a1a;background:#fff}\
+h1{font-size:2rem;line-height:1.25}h2{font-size:1.5rem;margin-top:2rem}h3{font-size:1.25rem}\
+li{margin-bottom:.25rem}";
+
+fn html(document: &Document) -> String {
+    let mut out = String::new();
+    let title = escape(&document.title);
+    // The language of right-to-left documents is not known; readers detect it
+    let root = match document.direction() {
+        Direction::Ltr => "lang=\"en\" dir=\"ltr\"",
+        Direction::Rtl => "dir=\"rtl\"",
+    };
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html {}>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n<article>\n<header>\n<h1 dir=\"auto\">{}</h1>\n",
+        root, title, HTML_STYLE, title
+    );
+    if let Some(subtitle) = &document.subtitle {
+        let _ = writeln!(out, "<p dir=\"auto\">{}</p>", escape(subtitle));
+    }
+    out.push_str("</header>\n");
+
+    let mut sections = 0;
+    for block in &document.blocks {
+        match block {
+            Block::Heading(heading) => {
+                if sections > 0 {
+                    out.push_str("</section>\n");
+                }
+                sections += 1;
+                let _ = writeln!(
+                    out,
+                    "<section aria-labelledby=\"section-{0}\">\n<h2 id=\"section-{0}\" dir=\"auto\">{1}</h2>",
+                    sections,
+                    escape(heading)
+                );
+            }
+            Block::Subheading(heading) => {
+                let _ = writeln!(out, "<h3 dir=\"auto\">{}</h3>", escape(heading));
+            }
+            Block::Paragraph(paragraph) => {
+                let _ = writeln!(out, "<p dir=\"auto\">{}</p>", escape(paragraph));
+            }
+            Block::List(items) | Block::OrderedList(items) => {
+                let tag = if matches!(block, Block::List(_)) { "ul" } else { "ol" };
+                let _ = writeln!(out, "<{}>", tag);
+                for item in items {
+                    let _ = writeln!(out, "<li dir=\"auto\">{}</li>", escape(item));
+                }
+                let _ = writeln!(out, "</{}>", tag);
+            }
+        }
+    }
+    if sections > 0 {
+        out.push_str("</section>\n");
+    }
+    out.push_str("</article>\n</main>\n</body>\n</html>\n");
+    out
+}
+
+/// A heading followed by a line of `underline` as long as it. Combining
+/// marks take no room of their own.
+fn underlined(out: &mut String, heading: &str, underline: char) {
+    let _ = writeln!(out, "{}", heading);
+    let width = heading.chars().filter(|&c| !bidi::is_mark(c)).count();
+    let _ = writeln!(out, "{}", underline.to_string().repeat(width));
+}
+
+fn text(document: &Document) -> String {
+    let mut out = String::new();
+    underlined(&mut out, &document.title, '=');
+    if let Some(subtitle) = &document.subtitle {
+        let _ = writeln!(out, "{}", subtitle);
+    }
+    let mut after_subheading = false;
+    for block in &document.blocks {
+        // Subheadings sit right above what they introduce
+        if !after_subheading {
+            out.push('\n');
+        }
+        after_subheading = matches!(block, Block::Subheading(_));
+        match block {
+            Block::Heading(heading) => underlined(&mut out, heading, '-'),
+            Block::Subheading(heading) => {
+                let _ = writeln!(out, "{}:", heading.trim_end_matches(':'));
+            }
+            Block::Paragraph(paragraph) => {
+                let _ = writeln!(out, "{}", paragraph);
+            }
+            Block::List(items) => {
+                for item in items {
+                    let _ = writeln!(out, "- {}", item);
+                }
+            }
+            Block::OrderedList(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let _ = writeln!(out, "{}. {}", index + 1, item);
+                }
+            }
+        }
+    }
+    out
+}
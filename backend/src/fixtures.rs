@@ -0,0 +1,166 @@
+//! Load-test fixture generation.
+//!
+//! Creates thousands of synthetic job seekers with varied profiles and writes
+//! a CSV of `user_id,email,token` rows that load-testing tools can use to
+//! authenticate requests. Run it with:
+//!
+//! ```text
+//! cargo run -- fixtures [count]
+//! ```
+//!
+//! Profiles are generated from a seeded RNG (`FIXTURE_SEED`, default 42) so
+//! the same count and seed always produce the same dataset. Fixture accounts
+//! live under [`FIXTURE_EMAIL_DOMAIN`] and are replaced on every run. Pair
+//! this with `AI_MOCK_MODE=true` so AI endpoints do not call real providers.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::auth::create_jwt;
+use crate::errors::{AppError, AppResult};
+use crate::models::{CareerTrack, ExperienceLevel};
+use crate::security::hash_password;
+
+/// Email domain shared by every fixture account.
+pub const FIXTURE_EMAIL_DOMAIN: &str = "loadtest.careerbridge.dev";
+
+/// Password for every fixture account.
+pub const FIXTURE_PASSWORD: &str = "LoadTest123!";
+
+/// Default number of fixture users.
+pub const DEFAULT_FIXTURE_USERS: usize = 1000;
+
+/// Rows per multi-row insert statement.
+const BATCH_SIZE: usize = 500;
+
+const FIRST_NAMES: &[&str] = &[
+    "Ayesha", "Tanvir", "Nusrat", "Rafi", "Sadia", "Imran", "Farhana", "Arif", "Mim", "Sakib",
+];
+const LAST_NAMES: &[&str] = &[
+    "Rahman", "Hasan", "Jahan", "Ahmed", "Islam", "Chowdhury", "Karim", "Hossain",
+];
+const TRACKS: &[(CareerTrack, &[&str], &str)] = &[
+    (
+        CareerTrack::WebDevelopment,
+        &["HTML", "CSS", "JavaScript", "React", "Node.js", "Git", "TypeScript", "SQL"],
+        "Frontend Developer",
+    ),
+    (
+        CareerTrack::Data,
+        &["Python", "SQL", "Excel", "Power BI", "Statistics", "Pandas", "Tableau"],
+        "Data Analyst",
+    ),
+    (
+        CareerTrack::Design,
+        &["Figma", "UI Design", "Prototyping", "User Research", "Adobe XD"],
+        "UI/UX Designer",
+    ),
+    (
+        CareerTrack::Marketing,
+        &["SEO", "Content Writing", "Google Analytics", "Social Media", "Copywriting"],
+        "Digital Marketer",
+    ),
+];
+const LEVELS: &[ExperienceLevel] = &[
+    ExperienceLevel::Fresher,
+    ExperienceLevel::Junior,
+    ExperienceLevel::Mid,
+];
+
+struct FixtureUser {
+    full_name: String,
+    email: String,
+    experience_level: &'static ExperienceLevel,
+    preferred_track: &'static CareerTrack,
+    skills: Vec<&'static str>,
+    target_role: &'static str,
+}
+
+fn generate_user(rng: &mut StdRng, index: usize) -> FixtureUser {
+    let first = FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())];
+    let last = LAST_NAMES[rng.random_range(0..LAST_NAMES.len())];
+    let (track, pool, target_role) = &TRACKS[rng.random_range(0..TRACKS.len())];
+
+    let skill_count = rng.random_range(2..=pool.len());
+    let skills = pool.choose_multiple(rng, skill_count).copied().collect();
+
+    FixtureUser {
+        full_name: format!("{} {}", first, last),
+        email: format!("user{:06}@{}", index, FIXTURE_EMAIL_DOMAIN),
+        experience_level: &LEVELS[rng.random_range(0..LEVELS.len())],
+        preferred_track: track,
+        skills,
+        target_role,
+    }
+}
+
+/// Generates `count` fixture users and writes their credentials to `output`.
+///
+/// Existing fixture accounts are deleted first. The CSV contains one
+/// `user_id,email,token` row per user; tokens expire after 24 hours like any
+/// other login token, so re-run the generator before each load test session.
+///
+/// # Errors
+///
+/// Returns an error if hashing, a database operation, or writing the CSV fails.
+pub async fn generate(db_pool: &PgPool, count: usize, seed: u64, output: &Path) -> AppResult<PathBuf> {
+    let password_hash = hash_password(FIXTURE_PASSWORD.to_string()).await?;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let removed = sqlx::query("DELETE FROM users WHERE email LIKE $1")
+        .bind(format!("%@{}", FIXTURE_EMAIL_DOMAIN))
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+    if removed > 0 {
+        info!("Removed {} existing fixture users", removed);
+    }
+
+    let io_err = |e: std::io::Error| {
+        warn!("Failed to write fixture credentials to {:?}: {}", output, e);
+        AppError::InternalServerError
+    };
+    let mut csv = std::io::BufWriter::new(std::fs::File::create(output).map_err(io_err)?);
+    writeln!(csv, "user_id,email,token").map_err(io_err)?;
+
+    let users: Vec<FixtureUser> = (0..count).map(|i| generate_user(&mut rng, i)).collect();
+
+    for batch in users.chunks(BATCH_SIZE) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO users (full_name, email, password_hash, experience_level, \
+             preferred_track, skills, target_roles, profile_completed) ",
+        );
+        builder.push_values(batch, |mut row, user| {
+            row.push_bind(&user.full_name)
+                .push_bind(&user.email)
+                .push_bind(&password_hash)
+                .push_bind(user.experience_level)
+                .push_bind(user.preferred_track)
+                .push_bind(&user.skills)
+                .push_bind(vec![user.target_role])
+                .push_bind(true);
+        });
+        builder.push(" RETURNING id, email");
+
+        let rows = builder
+            .build_query_as::<(Uuid, String)>()
+            .fetch_all(db_pool)
+            .await?;
+
+        for (id, email) in rows {
+            let token = create_jwt(id, email.clone())?;
+            writeln!(csv, "{},{},{}", id, email, token).map_err(io_err)?;
+        }
+    }
+
+    csv.flush().map_err(io_err)?;
+    info!("Generated {} fixture users, credentials written to {:?}", count, output);
+    Ok(output.to_path_buf())
+}
@@ -0,0 +1,284 @@
+//! Geocoding of job locations and distance calculations.
+//!
+//! Place names are resolved through a Nominatim-compatible search API
+//! (`GEOCODING_API_URL`, e.g. `https://nominatim.openstreetmap.org/search`
+//! or LocationIQ with `GEOCODING_API_KEY`). Every lookup, including misses,
+//! is cached in `geocode_cache` so each distinct place is only sent to the
+//! provider once.
+//!
+//! Job coordinates are filled in when a job is posted and by a scheduler
+//! task that backfills older rows. Radius filtering uses the haversine
+//! distance computed in SQL; see [`DISTANCE_KM_SQL`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tracing::{debug, error, info, warn};
+
+use crate::errors::{AppError, AppResult};
+
+/// Haversine distance in kilometres between the row's `latitude`/`longitude`
+/// and the point bound to `$1` (latitude) and `$2` (longitude).
+pub const DISTANCE_KM_SQL: &str = "6371 * 2 * ASIN(SQRT(\
+    POWER(SIN(RADIANS(latitude - $1) / 2), 2) + \
+    COS(RADIANS($1)) * COS(RADIANS(latitude)) * POWER(SIN(RADIANS(longitude - $2) / 2), 2)))";
+
+/// Maximum jobs geocoded per backfill run.
+const BACKFILL_BATCH_SIZE: i64 = 50;
+
+/// Pause between provider calls during backfill, to respect public API rate limits.
+const BACKFILL_DELAY: Duration = Duration::from_millis(1100);
+
+/// A latitude/longitude pair in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinates {
+    /// Parses a `"lat,lng"` pair, rejecting out-of-range values.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (lat, lng) = value.split_once(',')?;
+        let latitude = lat.trim().parse::<f64>().ok()?;
+        let longitude = lng.trim().parse::<f64>().ok()?;
+        ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude))
+            .then_some(Self { latitude, longitude })
+    }
+}
+
+/// Whether a free-text job location describes a remote position.
+pub fn is_remote(location: &str) -> bool {
+    let lower = location.to_lowercase();
+    lower.contains("remote") || lower.contains("work from home") || lower.contains("anywhere")
+}
+
+/// Normalizes a two-letter ISO 3166-1 country code to upper case.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if `code` is not two ASCII letters.
+pub fn normalize_country_code(code: &str) -> AppResult<String> {
+    let code = code.trim();
+    if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code.to_ascii_uppercase())
+    } else {
+        Err(AppError::ValidationError(format!(
+            "'{}' is not a two-letter ISO country code",
+            code
+        )))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+/// Client for a Nominatim-compatible geocoding API.
+pub struct Geocoder {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Geocoder {
+    /// Create a geocoder for the search endpoint at `base_url`
+    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            base_url,
+            api_key,
+        }
+    }
+
+    /// Creates a geocoder from `GEOCODING_API_URL` and `GEOCODING_API_KEY`.
+    ///
+    /// Returns `None` when no API URL is configured.
+    pub fn from_env() -> Option<Self> {
+        let base_url = std::env::var("GEOCODING_API_URL").ok().filter(|u| !u.is_empty())?;
+        let api_key = std::env::var("GEOCODING_API_KEY").ok().filter(|k| !k.is_empty());
+        Some(Self::new(base_url, api_key))
+    }
+
+    /// Looks a place up with the provider, bypassing the cache.
+    async fn search(&self, place: &str) -> AppResult<Option<(Coordinates, Option<String>)>> {
+        let mut query = vec![("q", place), ("format", "json"), ("limit", "1")];
+        if let Some(key) = &self.api_key {
+            query.push(("key", key));
+        }
+
+        let response = self
+            .client
+            .get(&self.base_url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Geocoding request failed: {}", e);
+                AppError::ExternalServiceError(format!("Geocoding API error: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            error!("Geocoding API returned {} for '{}'", status, place);
+            return Err(AppError::ExternalServiceError(format!(
+                "Geocoding API returned {}",
+                status
+            )));
+        }
+
+        let results = response.json::<Vec<SearchResult>>().await.map_err(|e| {
+            error!("Failed to parse geocoding response: {}", e);
+            AppError::ExternalServiceError(format!("Failed to parse geocoding response: {}", e))
+        })?;
+
+        Ok(results.into_iter().next().and_then(|r| {
+            let latitude = r.lat.parse().ok()?;
+            let longitude = r.lon.parse().ok()?;
+            Some((Coordinates { latitude, longitude }, r.display_name))
+        }))
+    }
+}
+
+/// Normalizes a place name into its cache key.
+fn cache_key(place: &str) -> String {
+    place.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Resolves a place name to coordinates, consulting `geocode_cache` first.
+///
+/// Without a geocoder only cached places resolve. Places the provider could
+/// not find are cached too, so they are not looked up again.
+///
+/// # Errors
+///
+/// Returns an error if the database or the geocoding provider fails.
+pub async fn geocode(
+    db_pool: &PgPool,
+    geocoder: Option<&Geocoder>,
+    place: &str,
+) -> AppResult<Option<Coordinates>> {
+    let key = cache_key(place);
+    if key.is_empty() {
+        return Ok(None);
+    }
+
+    let cached = sqlx::query_as::<_, (Option<f64>, Option<f64>)>(
+        "SELECT latitude, longitude FROM geocode_cache WHERE query = $1",
+    )
+    .bind(&key)
+    .fetch_optional(db_pool)
+    .await?;
+    if let Some((latitude, longitude)) = cached {
+        debug!("Geocode cache hit for '{}'", key);
+        return Ok(latitude
+            .zip(longitude)
+            .map(|(latitude, longitude)| Coordinates { latitude, longitude }));
+    }
+
+    let Some(geocoder) = geocoder else {
+        return Ok(None);
+    };
+    let found = geocoder.search(&key).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO geocode_cache (query, latitude, longitude, display_name)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (query) DO NOTHING
+        "#,
+    )
+    .bind(&key)
+    .bind(found.as_ref().map(|(c, _)| c.latitude))
+    .bind(found.as_ref().map(|(c, _)| c.longitude))
+    .bind(found.as_ref().and_then(|(_, name)| name.as_deref()))
+    .execute(db_pool)
+    .await?;
+
+    Ok(found.map(|(coordinates, _)| coordinates))
+}
+
+/// Geocodes one job's location and stores the coordinates on the job.
+///
+/// Remote jobs are skipped. Returns whether coordinates were stored.
+pub async fn geocode_job(db_pool: &PgPool, geocoder: Option<&Geocoder>, job_id: i32) -> AppResult<bool> {
+    let Some(location) = sqlx::query_scalar::<_, String>("SELECT location FROM jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_optional(db_pool)
+        .await?
+    else {
+        return Ok(false);
+    };
+    if is_remote(&location) {
+        return Ok(false);
+    }
+
+    let coordinates = geocode(db_pool, geocoder, &location).await?;
+    if coordinates.is_none() && geocoder.is_none() {
+        // Not attempted yet; leave it for the backfill once a provider is configured
+        return Ok(false);
+    }
+    sqlx::query("UPDATE jobs SET latitude = $2, longitude = $3, geocoded_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(job_id)
+        .bind(coordinates.map(|c| c.latitude))
+        .bind(coordinates.map(|c| c.longitude))
+        .execute(db_pool)
+        .await?;
+    Ok(coordinates.is_some())
+}
+
+/// Geocodes a newly posted job in the background, logging failures.
+pub fn spawn_geocode_job(db_pool: PgPool, geocoder: Option<Arc<Geocoder>>, job_id: i32) {
+    tokio::spawn(async move {
+        if let Err(e) = geocode_job(&db_pool, geocoder.as_deref(), job_id).await {
+            warn!("Geocoding failed for job {}: {}", job_id, e);
+        }
+    });
+}
+
+/// Geocodes jobs that have never been geocoded, oldest first.
+///
+/// Stops at the first provider error so a failing API is not hammered;
+/// remaining jobs are picked up on the next run.
+pub async fn backfill_job_coordinates(db_pool: &PgPool, geocoder: &Geocoder) -> AppResult<usize> {
+    let job_ids = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT id FROM jobs
+        WHERE geocoded_at IS NULL AND NOT is_remote
+        ORDER BY created_at
+        LIMIT $1
+        "#,
+    )
+    .bind(BACKFILL_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut geocoded = 0;
+    for job_id in job_ids {
+        match geocode_job(db_pool, Some(geocoder), job_id).await {
+            Ok(true) => geocoded += 1,
+            Ok(false) => {}
+            Err(e) => {
+                warn!("Geocoding backfill stopped at job {}: {}", job_id, e);
+                break;
+            }
+        }
+        tokio::time::sleep(BACKFILL_DELAY).await;
+    }
+
+    if geocoded > 0 {
+        info!("Geocoded {} jobs", geocoded);
+    }
+    Ok(geocoded)
+}
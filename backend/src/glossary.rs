@@ -0,0 +1,310 @@
+//! Glossary of career and technical jargon.
+//!
+//! [`explain`] returns a beginner-friendly definition of a term, generated
+//! by the AI the first time anyone asks (`glossary_term` content type) and
+//! served from the `glossary` table after that. Roadmap topics and mentor
+//! answers are annotated with the glossary terms they contain
+//! ([`link_terms`], [`link_roadmap_terms`]), so clients can make them
+//! tappable. Annotation only looks terms up; it never calls the AI.
+//!
+//! Terms are matched on whole words, case-insensitively, keeping the
+//! punctuation of names like `C++`, `Node.js`, or `CI/CD`:
+//!
+//! ```
+//! use backend::glossary::term_key;
+//!
+//! assert_eq!(term_key("  CI/CD  Pipelines. "), "ci/cd pipelines");
+//! assert_eq!(term_key("Node.js"), "node.js");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+
+/// Content type definitions are generated with.
+const CONTENT_TYPE: &str = "glossary_term";
+
+/// Longest term, in words.
+pub const MAX_TERM_WORDS: usize = 4;
+
+/// A glossary term and its definition.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct GlossaryEntry {
+    /// The term as first explained, e.g. `CI/CD`
+    pub term: String,
+    /// One or two plain sentences
+    pub definition: String,
+    /// A short example of the term in use
+    pub example: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A glossary term found in a text.
+#[derive(Debug, Clone, Serialize)]
+pub struct TermLink {
+    pub term: String,
+    pub definition: String,
+    pub example: Option<String>,
+    /// Where the term first appears, in characters of the text
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A word of a text, lowercased, with its character offsets.
+struct Word {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Splits text into words, keeping the `+`, `#`, `.`, `/`, and `-` inside
+/// names like `C#` or `CI/CD` but not at the end of a sentence.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut current: Vec<char> = Vec::new();
+    let mut start = 0;
+    for (index, c) in text.chars().chain(std::iter::once(' ')).enumerate() {
+        if c.is_alphanumeric() || "+#./-".contains(c) {
+            if current.is_empty() {
+                start = index;
+            }
+            current.push(c);
+            continue;
+        }
+        while current.last().is_some_and(|c| ".-/".contains(*c)) {
+            current.pop();
+        }
+        let leading = current.iter().take_while(|c| "-/".contains(**c)).count();
+        if current.len() > leading {
+            words.push(Word {
+                text: current[leading..].iter().collect::<String>().to_lowercase(),
+                start: start + leading,
+                end: start + current.len(),
+            });
+        }
+        current.clear();
+    }
+    words
+}
+
+/// The key a term is stored and matched under: its words, lowercased and
+/// separated by single spaces.
+pub fn term_key(term: &str) -> String {
+    words(term).into_iter().map(|w| w.text).collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the glossary entry for a term, generating and storing it the
+/// first time. `context` (e.g. the sentence the term was seen in) helps
+/// pick the right meaning; it is only used when the term is first
+/// explained. Returns whether the entry was already in the glossary.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the term is empty, longer than
+/// [`MAX_TERM_WORDS`] words, or not jargon the AI can explain;
+/// `AppError::ExternalServiceError` if the provider fails; or an error if
+/// a database query fails.
+pub async fn explain(
+    db_pool: &PgPool,
+    ai: &AIService,
+    user_id: Uuid,
+    term: &str,
+    context: Option<&str>,
+    provider: AIProvider,
+) -> AppResult<(GlossaryEntry, bool)> {
+    let key = term_key(term);
+    if key.is_empty() {
+        return Err(AppError::ValidationError("term must contain a word".to_string()));
+    }
+    if key.split(' ').count() > MAX_TERM_WORDS {
+        return Err(AppError::ValidationError(format!(
+            "term must be at most {} words",
+            MAX_TERM_WORDS
+        )));
+    }
+    if let Some(entry) = find(db_pool, &key).await? {
+        return Ok((entry, true));
+    }
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateContent,
+            provider: provider.clone(),
+            input: term.trim().to_string(),
+            parameters: Some(json!({
+                "content_type": CONTENT_TYPE,
+                "context": context,
+                "instructions": "Explain this term to someone new to tech careers. Put a definition of one or two \
+                    plain sentences in `content`, without jargon of its own. In `metadata`, give `example` (one \
+                    short sentence using the term as it is used at work) and `is_term` (false if this is not a \
+                    technical or career term worth explaining, e.g. an everyday word or a sentence).",
+            })),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+
+    let data = &response.data;
+    let metadata = data.get("metadata").cloned().unwrap_or(Value::Null);
+    if metadata.get("is_term").and_then(Value::as_bool) == Some(false) {
+        return Err(AppError::ValidationError(format!("'{}' is not a term the glossary explains", term.trim())));
+    }
+    let definition = data
+        .get("content")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AppError::ExternalServiceError("AI provider returned no definition".to_string()))?;
+    let example = metadata
+        .get("example")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|e| !e.is_empty());
+
+    // Another request may have explained the term meanwhile; keep the first
+    sqlx::query(
+        r#"
+        INSERT INTO glossary (term_key, term, definition, example, ai_provider, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (term_key) DO NOTHING
+        "#,
+    )
+    .bind(&key)
+    .bind(term.split_whitespace().collect::<Vec<_>>().join(" "))
+    .bind(definition)
+    .bind(example)
+    .bind(response.provider.as_str())
+    .bind(user_id)
+    .execute(db_pool)
+    .await?;
+    let entry = find(db_pool, &key).await?.ok_or(AppError::NotFound)?;
+    Ok((entry, false))
+}
+
+async fn find(db_pool: &PgPool, key: &str) -> AppResult<Option<GlossaryEntry>> {
+    Ok(sqlx::query_as::<_, GlossaryEntry>(
+        "SELECT term, definition, example, created_at FROM glossary WHERE term_key = $1",
+    )
+    .bind(key)
+    .fetch_optional(db_pool)
+    .await?)
+}
+
+/// Entries for the terms among `keys`, by key.
+async fn lookup(db_pool: &PgPool, keys: Vec<String>) -> AppResult<HashMap<String, GlossaryEntry>> {
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let rows = sqlx::query_as::<_, (String, String, String, Option<String>, Option<DateTime<Utc>>)>(
+        "SELECT term_key, term, definition, example, created_at FROM glossary WHERE term_key = ANY($1)",
+    )
+    .bind(keys)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(key, term, definition, example, created_at)| {
+            (key, GlossaryEntry { term, definition, example, created_at })
+        })
+        .collect())
+}
+
+/// Every run of up to [`MAX_TERM_WORDS`] words, as a term key.
+fn candidate_keys(words: &[Word], keys: &mut HashSet<String>) {
+    for start in 0..words.len() {
+        let mut key = String::new();
+        for word in words.iter().skip(start).take(MAX_TERM_WORDS) {
+            if !key.is_empty() {
+                key.push(' ');
+            }
+            key.push_str(&word.text);
+            keys.insert(key.clone());
+        }
+    }
+}
+
+/// Links the glossary terms in `text`, in order, each at its first
+/// appearance. Where terms overlap, the longest wins ("machine learning"
+/// rather than "learning").
+fn link(words: &[Word], entries: &HashMap<String, GlossaryEntry>) -> Vec<TermLink> {
+    let mut links = Vec::new();
+    let mut linked = HashSet::new();
+    let mut index = 0;
+    while index < words.len() {
+        let longest = (1..=MAX_TERM_WORDS.min(words.len() - index)).rev().find_map(|len| {
+            let key = words[index..index + len].iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+            entries.get(&key).map(|entry| (len, key, entry))
+        });
+        let Some((len, key, entry)) = longest else {
+            index += 1;
+            continue;
+        };
+        if linked.insert(key) {
+            links.push(TermLink {
+                term: entry.term.clone(),
+                definition: entry.definition.clone(),
+                example: entry.example.clone(),
+                start: words[index].start,
+                end: words[index + len - 1].end,
+            });
+        }
+        index += len;
+    }
+    links
+}
+
+/// The glossary terms in a mentor answer.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn link_terms(db_pool: &PgPool, text: &str) -> AppResult<Vec<TermLink>> {
+    let words = words(text);
+    let mut keys = HashSet::new();
+    candidate_keys(&words, &mut keys);
+    let entries = lookup(db_pool, keys.into_iter().collect()).await?;
+    Ok(link(&words, &entries))
+}
+
+/// The glossary terms in each topic of a roadmap's phases, by topic.
+/// Topics without terms are left out.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn link_roadmap_terms(db_pool: &PgPool, roadmap: &Value) -> AppResult<HashMap<String, Vec<TermLink>>> {
+    let topics: HashSet<&str> = roadmap
+        .get("phases")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|phase| phase.get("topics").and_then(Value::as_array))
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let topics: Vec<(&str, Vec<Word>)> = topics.into_iter().map(|topic| (topic, words(topic))).collect();
+
+    let mut keys = HashSet::new();
+    for (_, words) in &topics {
+        candidate_keys(words, &mut keys);
+    }
+    let entries = lookup(db_pool, keys.into_iter().collect()).await?;
+    Ok(topics
+        .into_iter()
+        .map(|(topic, words)| (topic.to_string(), link(&words, &entries)))
+        .filter(|(_, links)| !links.is_empty())
+        .collect())
+}
@@ -0,0 +1,29 @@
+//! Activity feed handlers.
+
+use axum::extract::{Query, State};
+
+use super::types::ActivityFeedParams;
+use crate::AppState;
+use crate::activity::{self, Activity};
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+
+/// Returns a page of the caller's activity, newest first.
+///
+/// Pass `meta.next_cursor` back as `cursor` for the next (older) page; it is
+/// `null` on the last page.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated, the cursor is
+/// malformed, or a database query fails.
+pub async fn get_my_activity(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ActivityFeedParams>,
+) -> AppResult<ApiResponse<Vec<Activity>>> {
+    let limit = params.limit.unwrap_or(activity::DEFAULT_PAGE_SIZE);
+    let page = activity::feed(&app_state.db_pool, auth_user.user_id, params.cursor.as_deref(), limit).await?;
+    Ok(ApiResponse::ok(page.activities).meta("next_cursor", page.next_cursor))
+}
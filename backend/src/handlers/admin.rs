@@ -0,0 +1,642 @@
+//! Admin moderation queues for employer job postings, quarantined feed
+//! listings, and user reports, scam screening tuning, resource link health,
+//! resource usage and affiliate parameters, tenant rate limit policies,
+//! deployment diagnostics, current load, and AI evaluation trends.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    AffiliateParamsPayload, EvalTrendParams, ImpersonatePayload, ImpersonationQueryParams, ModerationDecisionPayload,
+    PendingJob, RateLimitPolicyPayload, ReportQueueParams, ResolveReportPayload, ResourceLinkQueryParams,
+    ResourceUsageParams,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::diagnostics::{self, Diagnostics};
+use crate::errors::{AppError, AppResult};
+use crate::employer_verification;
+use crate::eval;
+use crate::impersonation::{self, ImpersonationSession, StartedImpersonation};
+use crate::load_shedding::ClassLoad;
+use crate::rate_limits::{self, RateLimitPolicy};
+use crate::resource_clicks::{self, AffiliateParams, ResourceUsage};
+use crate::response::ApiResponse;
+use crate::links::ResourceLink;
+use crate::models::{ModerationStatus, UserRole};
+use crate::moderation::{self, QuarantinedImport, Tuning};
+use crate::reports::{self, QueuedReport};
+
+/// Lists job postings awaiting moderation, riskiest first.
+///
+/// Includes posts quarantined by scam screening. Posts whose pre-screening
+/// has not finished yet are listed first, and among equally risky posts the
+/// most reported. Live jobs return here once they collect enough user
+/// reports.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_pending_jobs(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PendingJob>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let jobs = sqlx::query_as::<_, PendingJob>(
+        r#"
+        SELECT
+            j.id, j.job_title, j.company, j.location, j.job_description,
+            j.salary_min, j.salary_max, j.posted_by, u.email AS poster_email,
+            j.moderation_status, j.scam_risk_score, j.screening,
+            (SELECT COUNT(*) FROM reports r
+             WHERE r.target_type = 'job' AND r.target_id = j.id::text AND r.status = 'open') AS open_reports,
+            j.created_at
+        FROM jobs j
+        LEFT JOIN users u ON u.id = j.posted_by
+        WHERE j.moderation_status IN ('pending', 'quarantined')
+        ORDER BY j.scam_risk_score DESC NULLS FIRST, open_reports DESC, j.created_at
+        "#,
+    )
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(jobs))
+}
+
+/// Approves a pending job posting, making it visible to job seekers.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - The poster's company is no longer a verified employer (412)
+/// - The job does not exist or is not pending (404)
+/// - Database operation fails
+pub async fn approve_job(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+    payload: Option<Json<ModerationDecisionPayload>>,
+) -> AppResult<ApiResponse<Value>> {
+    let reason = payload.and_then(|Json(p)| p.reason);
+    decide(auth_user, app_state, job_id, ModerationStatus::Approved, reason).await
+}
+
+/// Rejects a pending job posting with a reason shown to the employer.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - No reason is given
+/// - The job does not exist or is not pending (404)
+/// - Database operation fails
+pub async fn reject_job(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+    Json(payload): Json<ModerationDecisionPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    if payload.reason.as_deref().is_none_or(|r| r.trim().is_empty()) {
+        return Err(AppError::ValidationError(
+            "A reason is required when rejecting a job".to_string(),
+        ));
+    }
+    decide(auth_user, app_state, job_id, ModerationStatus::Rejected, payload.reason).await
+}
+
+/// Records a moderation decision, audits it, keeps it as screening
+/// feedback, and notifies the employer.
+async fn decide(
+    auth_user: AuthUser,
+    app_state: AppState,
+    job_id: i32,
+    decision: ModerationStatus,
+    reason: Option<String>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    // A company that lost its verification after posting cannot go live
+    if decision == ModerationStatus::Approved
+        && let Some((poster_id, poster_role)) = sqlx::query_as::<_, (Uuid, UserRole)>(
+            "SELECT u.id, u.role FROM jobs j JOIN users u ON u.id = j.posted_by WHERE j.id = $1",
+        )
+        .bind(job_id)
+        .fetch_optional(&app_state.db_pool)
+        .await?
+    {
+        employer_verification::require_may_post(&app_state.db_pool, poster_id, poster_role).await?;
+    }
+
+    let (job_title, posted_by, company_id, screening) =
+        sqlx::query_as::<_, (String, Option<Uuid>, Option<i32>, Option<Value>)>(
+            r#"
+            UPDATE jobs
+            SET moderation_status = $2, moderation_reason = $3,
+                moderated_by = $4, moderated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND moderation_status IN ('pending', 'quarantined')
+            RETURNING job_title, posted_by, company_id, screening
+            "#,
+        )
+    .bind(job_id)
+    .bind(decision)
+    .bind(&reason)
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    info!("Job {} {:?} by admin {}", job_id, decision, auth_user.user_id);
+    moderation::record_feedback(
+        &app_state.db_pool,
+        auth_user.user_id,
+        Some(job_id),
+        None,
+        screening.as_ref(),
+        decision == ModerationStatus::Rejected,
+    )
+    .await;
+
+    let action = match decision {
+        ModerationStatus::Approved => "job.approve",
+        _ => "job.reject",
+    };
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, company_id, action, target_type, target_id, metadata)
+        VALUES ($1, $2, $3, 'job', $4, $5)
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(company_id)
+    .bind(action)
+    .bind(job_id.to_string())
+    .bind(json!({ "reason": reason }))
+    .execute(&app_state.db_pool)
+    .await?;
+
+    // The decision settles any user reports that sent the job here
+    reports::close_for_job(&app_state.db_pool, auth_user.user_id, job_id, decision, reason.as_deref()).await?;
+
+    if let Some(employer_id) = posted_by
+        && let Err(e) = moderation::notify_employer(
+            &app_state.db_pool,
+            employer_id,
+            &job_title,
+            decision,
+            reason.as_deref(),
+        )
+        .await
+    {
+        warn!("Failed to notify employer {} about job {}: {}", employer_id, job_id, e);
+    }
+
+    Ok(ApiResponse::ok(json!({
+        "job_id": job_id,
+        "moderation_status": decision,
+        "reason": reason
+    })))
+}
+
+/// Lists feed listings quarantined by scam screening, riskiest first.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_quarantined_imports(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<QuarantinedImport>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(moderation::list_quarantined_imports(&app_state.db_pool).await?))
+}
+
+/// Releases a quarantined feed listing into the feed, recording a false
+/// positive.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the listing is not
+/// quarantined (404), or the database operation fails.
+pub async fn release_import(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(import_id): Path<i32>,
+) -> AppResult<ApiResponse<QuarantinedImport>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let import =
+        moderation::decide_import(&app_state.db_pool, auth_user.user_id, import_id, ModerationStatus::Approved).await?;
+    Ok(ApiResponse::ok(import))
+}
+
+/// Confirms a quarantined feed listing as a scam; it stays out of the feed.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the listing is not
+/// quarantined (404), or the database operation fails.
+pub async fn reject_import(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(import_id): Path<i32>,
+) -> AppResult<ApiResponse<QuarantinedImport>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let import =
+        moderation::decide_import(&app_state.db_pool, auth_user.user_id, import_id, ModerationStatus::Rejected).await?;
+    Ok(ApiResponse::ok(import))
+}
+
+/// Returns the scam screening threshold and signal weights as tuned by
+/// moderator feedback, with each signal's precision.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn get_scam_screening(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Tuning>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(moderation::load_tuning(&app_state.db_pool).await?))
+}
+
+/// Lists user reports, open reports first with the most reported targets
+/// at the top.
+///
+/// # Query Parameters
+///
+/// - `status` - Only reports with this status (`open`, `resolved`, or `dismissed`)
+/// - `limit` - Maximum reports to return (default: 50, max: 200)
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_reports(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ReportQueueParams>,
+) -> AppResult<ApiResponse<Vec<QueuedReport>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    Ok(ApiResponse::ok(
+        reports::queue(&app_state.db_pool, params.status, limit).await?,
+    ))
+}
+
+/// Resolves a report by dismissing it, warning the reported party, or
+/// removing the reported job or message. Every open report on the same
+/// target is closed with it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - The report does not exist (404)
+/// - The report is closed, a note is missing, or the action does not apply to the target
+/// - Database operation fails
+pub async fn resolve_report(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(report_id): Path<i32>,
+    Json(payload): Json<ResolveReportPayload>,
+) -> AppResult<ApiResponse<QueuedReport>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+
+    let report = reports::resolve(
+        &app_state.db_pool,
+        auth_user.user_id,
+        report_id,
+        payload.action,
+        payload.note.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(report))
+}
+
+/// Starts impersonating a user for support. The returned token acts as the
+/// user until the session expires or is ended; destructive requests made
+/// with it are refused, every request is audited, and the user is notified
+/// when it finishes (see [`crate::impersonation`]).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin, or the target is an admin
+/// - The target user does not exist (404)
+/// - The reason is missing or the session length is out of range
+/// - Database operation fails
+pub async fn impersonate_user(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<ImpersonatePayload>,
+) -> AppResult<ApiResponse<StartedImpersonation>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+
+    let started = impersonation::start(
+        &app_state.db_pool,
+        auth_user.user_id,
+        user_id,
+        &payload.reason,
+        payload.minutes,
+    )
+    .await?;
+    Ok(ApiResponse::ok(started))
+}
+
+/// Lists impersonation sessions, newest first, with how many requests each
+/// made.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_impersonations(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ImpersonationQueryParams>,
+) -> AppResult<ApiResponse<Vec<ImpersonationSession>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    Ok(ApiResponse::ok(
+        impersonation::list(&app_state.db_pool, params.user_id, limit).await?,
+    ))
+}
+
+/// Ends an impersonation session before it expires and notifies the user.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, there is no such open
+/// session (404), or the query fails.
+pub async fn end_impersonation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(session_id): Path<i32>,
+) -> AppResult<ApiResponse<ImpersonationSession>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(
+        impersonation::end(&app_state.db_pool, auth_user.user_id, session_id).await?,
+    ))
+}
+
+/// Lists checked resource links, most recently checked first.
+///
+/// # Query Parameters
+///
+/// - `status` - Only links with this status (`unchecked`, `ok`, or `broken`)
+/// - `include_replaced` - Include broken links that were already replaced (default: false)
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_resource_links(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ResourceLinkQueryParams>,
+) -> AppResult<ApiResponse<Vec<ResourceLink>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let links = sqlx::query_as::<_, ResourceLink>(
+        r#"
+        SELECT url, status, http_status, last_error, consecutive_failures,
+               checked_at, replacement_url, replaced_at
+        FROM resource_links
+        WHERE ($1::link_status IS NULL OR status = $1)
+          AND ($2 OR replaced_at IS NULL)
+        ORDER BY checked_at DESC NULLS LAST
+        LIMIT 200
+        "#,
+    )
+    .bind(params.status)
+    .bind(params.include_replaced)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(links))
+}
+
+/// Lists the most clicked learning resources.
+///
+/// # Query Parameters
+///
+/// - `days` - Days of clicks to include (default: 30, at most 365)
+/// - `limit` - Resources to return (default: 20, at most 100)
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_resource_usage(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ResourceUsageParams>,
+) -> AppResult<ApiResponse<Vec<ResourceUsage>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let usage = resource_clicks::most_used(&app_state.db_pool, days, limit).await?;
+    Ok(ApiResponse::ok(usage).meta("days", days))
+}
+
+/// Sets the affiliate query parameters added to a learning resource's URL
+/// when users follow it, replacing any earlier ones.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - Validation fails
+/// - The resource does not exist (404)
+/// - Database operation fails
+pub async fn set_resource_affiliate_params(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(resource_id): Path<i32>,
+    Json(payload): Json<AffiliateParamsPayload>,
+) -> AppResult<ApiResponse<AffiliateParams>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let params = resource_clicks::set_affiliate_params(&app_state.db_pool, resource_id, payload.params).await?;
+    info!(
+        "Affiliate parameters of resource {} set by {}: {:?}",
+        resource_id,
+        auth_user.user_id,
+        params.params.keys().collect::<Vec<_>>()
+    );
+    Ok(ApiResponse::ok(params))
+}
+
+/// Reports the resolved configuration (secrets redacted) and the results of
+/// the startup self-checks: database, migrations, AI provider keys, storage,
+/// and the scheduler.
+///
+/// Failed checks are reported in the body with `healthy: false`; the request
+/// itself still succeeds.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin.
+pub async fn get_diagnostics(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Diagnostics>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let report = diagnostics::run(
+        &app_state.db_pool,
+        app_state.ai_service.as_deref(),
+        &app_state.storage,
+    )
+    .await;
+    Ok(ApiResponse::ok(report))
+}
+
+/// Reports in-flight and queued requests per traffic class, and how many
+/// each has shed since the server started.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin.
+pub async fn get_load(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ClassLoad>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(app_state.load_shedder.load()))
+}
+
+/// Lists tenant rate limit policies.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_rate_limit_policies(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<RateLimitPolicy>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(rate_limits::list(&app_state.db_pool).await?))
+}
+
+/// Sets an organization's rate limit policy for one plan, or for every plan
+/// when `plan_id` is omitted, replacing any policy with the same scope.
+///
+/// Takes effect at once on this instance and within
+/// `RATE_LIMIT_POLICY_TTL_SECS` on others.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - Validation fails or the plan does not exist
+/// - The organization does not exist (404)
+/// - Database operation fails
+pub async fn set_rate_limit_policy(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<RateLimitPolicyPayload>,
+) -> AppResult<ApiResponse<RateLimitPolicy>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+
+    let org_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM organizations WHERE id = $1)")
+        .bind(payload.org_id)
+        .fetch_one(&app_state.db_pool)
+        .await?;
+    if !org_exists {
+        return Err(AppError::NotFound);
+    }
+    if let Some(plan_id) = payload.plan_id.as_deref() {
+        let plan_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM plans WHERE id = $1)")
+            .bind(plan_id)
+            .fetch_one(&app_state.db_pool)
+            .await?;
+        if !plan_exists {
+            return Err(AppError::ValidationError(format!("Unknown plan '{}'", plan_id)));
+        }
+    }
+
+    let policy = sqlx::query_as::<_, RateLimitPolicy>(
+        r#"
+        INSERT INTO rate_limit_policies (org_id, plan_id, ai_daily_quota, note)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (org_id, (COALESCE(plan_id, '')))
+        DO UPDATE SET ai_daily_quota = EXCLUDED.ai_daily_quota, note = EXCLUDED.note, updated_at = NOW()
+        RETURNING id, org_id, plan_id, ai_daily_quota, note, updated_at
+        "#,
+    )
+    .bind(payload.org_id)
+    .bind(&payload.plan_id)
+    .bind(payload.ai_daily_quota)
+    .bind(&payload.note)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    app_state.rate_limits.invalidate().await;
+
+    info!(
+        "Rate limit policy {} set by {}: org={} plan={:?} quota={}",
+        policy.id, auth_user.user_id, policy.org_id, policy.plan_id, policy.ai_daily_quota
+    );
+    Ok(ApiResponse::ok(policy))
+}
+
+/// Deletes a rate limit policy; its members fall back to their plan quota.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the policy does not exist
+/// (404), or the database operation fails.
+pub async fn delete_rate_limit_policy(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(policy_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let result = sqlx::query("DELETE FROM rate_limit_policies WHERE id = $1")
+        .bind(policy_id)
+        .execute(&app_state.db_pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    app_state.rate_limits.invalidate().await;
+
+    info!("Rate limit policy {} deleted by {}", policy_id, auth_user.user_id);
+    Ok(ApiResponse::ok(json!({ "message": "Rate limit policy deleted" })))
+}
+
+/// Reports recent AI evaluation runs and daily pass rates and scores per
+/// provider, model, action, and prompt version.
+///
+/// # Query Parameters
+///
+/// - `days` - Days of results to include (default: 30, at most 365)
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn get_eval_trends(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<EvalTrendParams>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let runs = eval::recent_runs(&app_state.db_pool, 20).await?;
+    let trends = eval::trends(&app_state.db_pool, days).await?;
+    Ok(ApiResponse::ok(json!({ "runs": runs, "trends": trends })).meta("days", days))
+}
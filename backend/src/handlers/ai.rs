@@ -3,16 +3,46 @@
 //! Provides endpoints for AI-powered features like skill extraction,
 //! roadmap generation, and more.
 
-use axum::{Json, extract::State};
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, stream};
 use serde_json::json;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
     AppState,
-    ai::types::{AIActionRequest, AIActionResponse},
+    ai::{
+        AIService,
+        stream::AnswerStream,
+        types::{
+            AIActionRequest, AIActionResponse, AIProvider, AskMentorRequest, ChatMessage, ExtractSkillsRequest,
+            ExtractSkillsResult, GenerateRoadmapRequest, GeneratedRoadmap, MentorAnswer, ReadingLevel, RoadmapMetadata,
+            SelfCheck,
+        },
+    },
     auth::AuthUser,
+    conversations,
     errors::AppError,
+    export::{Block, Document, Typeface},
+    mentor_tools::MentorTools,
+    response::ApiResponse,
+    retrieval,
+    glossary::{self, GlossaryEntry, TermLink},
+    semantic_cache,
+    tenant_config::PersonaKind,
+    validated_json::ValidatedJson,
 };
 
+use super::types::{AiUsageParams, AskMentorStreamPayload, ExplainTermPayload, GenerateBrandingPayload, GenerateScreeningQuestionsPayload, JobDescriptionBriefPayload, PersonaPreviewPayload, RoadmapExportParams, RoadmapGeneration, SimplifyPayload, UpdateRoadmapResourcePayload};
+
 /// Process an AI action
 ///
 /// # Endpoint
@@ -39,11 +69,15 @@ use crate::{
 /// # Providers
 /// - `gemini`: Google Gemini API (default)
 /// - `groq`: Groq API
+/// - `ollama`: Self-hosted models through Ollama
+/// - `auto`: The first configured provider, falling back to the next when one fails
 pub async fn process_ai_action(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
-    Json(request): Json<AIActionRequest>,
-) -> Result<Json<AIActionResponse>, AppError> {
+    Json(mut request): Json<AIActionRequest>,
+) -> Result<ApiResponse<AIActionResponse>, AppError> {
+    request.user_id = Some(auth_user.user_id);
+
     tracing::info!(
         "Processing AI action: {:?} with provider: {:?}",
         request.action,
@@ -57,7 +91,25 @@ pub async fn process_ai_action(
 
     let response = ai_service.process_action(request).await?;
 
-    Ok(Json(response))
+    Ok(ApiResponse::new(response.success, response))
+}
+
+/// Check an AI action's input without running it
+///
+/// # Endpoint
+/// `POST /api/ai/preflight`
+///
+/// # Request Body
+/// Same as `POST /api/ai/action`; `provider` and `parameters` are ignored.
+///
+/// Nothing is charged and no provider is called. Unusable input returns 400
+/// with the same message the action would; otherwise the response reports
+/// the estimated tokens, detected language, and any warnings.
+pub async fn preflight_ai_action(
+    _auth_user: AuthUser,
+    Json(request): Json<AIActionRequest>,
+) -> Result<ApiResponse<crate::ai::preflight::PreflightReport>, AppError> {
+    Ok(ApiResponse::ok(crate::ai::preflight::check(&request.action, &request.input)?))
 }
 
 /// Extract skills from CV and update user profile
@@ -73,36 +125,27 @@ pub async fn process_ai_action(
 ///   "update_profile": true
 /// }
 /// ```
+///
+/// With `update_profile`, extracted skills and roles the user removed from
+/// their profile before are left out, and the profile is snapshotted before
+/// it changes: the response's `snapshot_id` can be passed to
+/// `POST /api/users/me/profile/revert/{snapshot_id}` to undo the update.
 pub async fn extract_and_save_skills(
     auth_user: AuthUser,
     State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let cv_text = payload
-        .get("cv_text")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| AppError::ValidationError("cv_text is required".to_string()))?;
-
-    let provider_str = payload
-        .get("provider")
-        .and_then(|v| v.as_str())
-        .unwrap_or("gemini");
-
-    let update_profile = payload
-        .get("update_profile")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+    ValidatedJson(payload): ValidatedJson<ExtractSkillsRequest>,
+) -> Result<ApiResponse<ExtractSkillsResult>, AppError> {
+    let cv_text = payload.cv_text.as_str();
+    let update_profile = payload.update_profile;
 
     // Create AI action request
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::ExtractSkills,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider: payload.provider.unwrap_or(AIProvider::Gemini),
         input: cv_text.to_string(),
         parameters: None,
+        capture: true,
     };
 
     let ai_service = state
@@ -125,10 +168,11 @@ pub async fn extract_and_save_skills(
     }
 
     // Extract the skills from the response
-    let extracted_data = &response.data;
-    tracing::info!("Full AI response data: {}", serde_json::to_string_pretty(extracted_data).unwrap_or_default());
+    let extracted_data = response.data;
+    tracing::info!("Full AI response data: {}", serde_json::to_string_pretty(&extracted_data).unwrap_or_default());
 
     // If update_profile is true, update the user's profile
+    let mut snapshot_id = None;
     if update_profile {
         tracing::info!("Starting profile update with extracted data");
         
@@ -170,13 +214,17 @@ pub async fn extract_and_save_skills(
 
         tracing::info!("Extracted {} roles: {:?}", roles.len(), roles);
 
+        // Leave out items the user removed from their profile before
+        let rejected = crate::curation::load_rejected(&state.db_pool, auth_user.user_id).await?;
+        let technical_skills = rejected.filter(crate::models::ProfileItemKind::Skill, technical_skills);
+        let roles = rejected.filter(crate::models::ProfileItemKind::TargetRole, roles);
+
         // Combine existing skills with new ones (avoid duplicates)
         let user_id = auth_user.user_id;
-        let existing_user =
-            sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-                .bind(&user_id)
-                .fetch_one(&state.db_pool)
-                .await?;
+        let existing_user = state
+            .user_cache
+            .get_user(&state.db_pool, user_id)
+            .await?;
 
         tracing::info!("Existing user skills before update: {:?}", existing_user.skills);
         tracing::info!("Existing user roles before update: {:?}", existing_user.target_roles);
@@ -198,6 +246,10 @@ pub async fn extract_and_save_skills(
         tracing::info!("Combined skills to save: {:?} (total: {})", combined_skills, combined_skills.len());
         tracing::info!("Combined roles to save: {:?} (total: {})", combined_roles, combined_roles.len());
 
+        // Snapshot the profile first so the update can be reverted
+        let mut tx = state.db_pool.begin().await?;
+        snapshot_id = Some(super::users::snapshot_profile(&mut tx, user_id, "ai_extract_skills").await?);
+
         // Update user profile with extracted skills and roles
         let result = sqlx::query(
             "UPDATE users 
@@ -211,8 +263,11 @@ pub async fn extract_and_save_skills(
         .bind(&combined_roles)
         .bind(cv_text)
         .bind(&user_id)
-        .execute(&state.db_pool)
+        .execute(&mut *tx)
         .await?;
+        tx.commit().await?;
+
+        state.user_cache.invalidate(user_id).await;
 
         tracing::info!(
             "Updated user profile with extracted skills for user: {}. Rows affected: {}",
@@ -221,12 +276,13 @@ pub async fn extract_and_save_skills(
         );
     }
 
-    Ok(Json(json!({
-        "success": true,
-        "extracted_data": extracted_data,
-        "profile_updated": update_profile,
-        "message": "Skills extracted successfully"
-    })))
+    Ok(ApiResponse::ok(ExtractSkillsResult {
+        extracted_data,
+        profile_updated: update_profile,
+        snapshot_id,
+    })
+    .meta("message", "Skills extracted successfully")
+    .meta("warnings", response.warnings))
 }
 
 /// Generate a personalized learning roadmap
@@ -241,51 +297,50 @@ pub async fn extract_and_save_skills(
 ///   "timeframe_months": 6,
 ///   "learning_hours_per_week": 10,
 ///   "provider": "gemini",
-///   "include_current_skills": true
+///   "include_current_skills": true,
+///   "deterministic": false,
+///   "self_check": "warn"
 /// }
 /// ```
+///
+/// With `deterministic: true` the roadmap is generated at temperature 0 with
+/// a fixed seed. Every saved roadmap is tagged with the model, prompt
+/// version, and settings that produced it.
+///
+/// `self_check` runs a second AI pass that critiques the roadmap for
+/// coverage, ordering, and realistic durations: `warn` stores the issues as
+/// `quality_warnings`, `fix` also replaces the roadmap with a corrected one
+/// and keeps only the issues it could not fix. The default, `off`, skips it.
+/// A failed self-check never fails the roadmap.
 pub async fn generate_roadmap(
     auth_user: AuthUser,
     State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let target_role = payload
-        .get("target_role")
-        .or_else(|| payload.get("tech_stack"))
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| AppError::ValidationError("target_role is required".to_string()))?;
-
-    let timeframe_months = payload
-        .get("timeframe_months")
-        .and_then(|v| v.as_u64())
-        .map(|t| t as u32)
-        .unwrap_or(6);
-
-    let learning_hours_per_week = payload
-        .get("learning_hours_per_week")
-        .and_then(|v| v.as_u64())
-        .map(|h| h as u32)
-        .unwrap_or(10);
-
-    let provider_str = payload
-        .get("provider")
-        .and_then(|v| v.as_str())
-        .unwrap_or("gemini");
-
-    let include_current_skills = payload
-        .get("include_current_skills")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+    ValidatedJson(payload): ValidatedJson<GenerateRoadmapRequest>,
+) -> Result<ApiResponse<GeneratedRoadmap>, AppError> {
+    let target_role = payload.target_role.as_str();
+    let timeframe_months = payload.timeframe_months;
+    let learning_hours_per_week = payload.learning_hours_per_week;
+    let self_check = match payload.self_check {
+        SelfCheck::Off => None,
+        SelfCheck::Warn => Some(false),
+        SelfCheck::Fix => Some(true),
+    };
 
-    // Get user's current skills if requested
-    let (current_skills, user_skills_json) = if include_current_skills {
-        let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-            .bind(auth_user.user_id)
-            .fetch_one(&state.db_pool)
+    // Get user's current skills if requested, including skills learned in other roadmaps
+    let (current_skills, user_skills_json) = if payload.include_current_skills {
+        let user = state
+            .user_cache
+            .get_user(&state.db_pool, auth_user.user_id)
             .await?;
+        let learned = crate::skills::load_progress(&state.db_pool, auth_user.user_id)
+            .await?
+            .into_iter()
+            .filter(|p| p.status == crate::models::SkillProgressStatus::Known)
+            .map(|p| p.skill);
+        let skills = crate::skills::merge_skills(&user.skills, learned);
 
-        let skills_str = user.skills.join(", ");
-        let skills_json = serde_json::to_value(&user.skills).unwrap_or(json!([]));
+        let skills_str = skills.join(", ");
+        let skills_json = serde_json::to_value(&skills).unwrap_or(json!([]));
         (Some(skills_str), skills_json)
     } else {
         (None, json!([]))
@@ -298,16 +353,31 @@ pub async fn generate_roadmap(
     }
     parameters.insert("timeframe_months".to_string(), json!(timeframe_months));
     parameters.insert("learning_hours_per_week".to_string(), json!(learning_hours_per_week));
+    if let Some(persona) = state
+        .tenant_configs
+        .persona_for(&state.db_pool, auth_user.user_id, PersonaKind::Roadmap)
+        .await
+    {
+        parameters.insert("persona".to_string(), json!(persona));
+    }
+    if let Some(deterministic) = payload.deterministic {
+        parameters.insert("deterministic".to_string(), json!(deterministic));
+    }
+    if let Some(reading_level) = payload.reading_level {
+        parameters.insert("reading_level".to_string(), json!(reading_level));
+    }
+    if let Some(tone) = payload.tone {
+        parameters.insert("tone".to_string(), json!(tone));
+    }
 
+    let provider = payload.provider.clone().unwrap_or(AIProvider::Gemini);
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::GenerateRoadmap,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider: provider.clone(),
         input: target_role.to_string(),
         parameters: Some(serde_json::Value::Object(parameters)),
+        capture: true,
     };
 
     let ai_service = state
@@ -323,56 +393,154 @@ pub async fn generate_roadmap(
         ));
     }
 
+    let mut roadmap_data = response.data;
+    let mut quality_warnings = None;
+    if let Some(auto_fix) = self_check {
+        let mut review_parameters = serde_json::Map::new();
+        review_parameters.insert("target_role".to_string(), json!(target_role));
+        review_parameters.insert("timeframe_months".to_string(), json!(timeframe_months));
+        review_parameters.insert("learning_hours_per_week".to_string(), json!(learning_hours_per_week));
+        review_parameters.insert("auto_fix".to_string(), json!(auto_fix));
+        if let Some(deterministic) = payload.deterministic {
+            review_parameters.insert("deterministic".to_string(), json!(deterministic));
+        }
+        let review_request = AIActionRequest {
+            user_id: Some(auth_user.user_id),
+            action: crate::ai::types::ActionType::ReviewRoadmap,
+            provider,
+            input: roadmap_data.to_string(),
+            parameters: Some(serde_json::Value::Object(review_parameters)),
+            capture: true,
+        };
+        let review = review_roadmap(ai_service, review_request, auto_fix).await;
+        match review {
+            Ok((revised, warnings)) => {
+                if let Some(revised) = revised {
+                    roadmap_data = revised;
+                }
+                quality_warnings = Some(warnings);
+            }
+            Err(e) => tracing::warn!("Roadmap self-check failed for user {}: {}", auth_user.user_id, e),
+        }
+    }
+
     // Extract project suggestions and job application timing from AI response
-    let project_suggestions = response.data.get("project_suggestions")
+    let project_suggestions = roadmap_data.get("project_suggestions")
         .cloned()
         .unwrap_or(json!([]));
     
-    let job_application_timing = response.data.get("job_application_timing")
+    let job_application_timing = roadmap_data.get("job_application_timing")
         .and_then(|v| v.as_str())
-        .unwrap_or("Apply after completing 60-70% of the roadmap");
+        .unwrap_or("Apply after completing 60-70% of the roadmap")
+        .to_string();
 
     // Save roadmap to database with enhanced fields
-    let provider_string = match response.provider {
-        crate::ai::types::AIProvider::Gemini => "gemini",
-        crate::ai::types::AIProvider::Groq => "groq",
-    };
+    let provider_string = response.provider.as_str();
 
     let roadmap_id = sqlx::query_scalar::<_, i32>(
         "INSERT INTO career_roadmaps (
             user_id, title, target_role, roadmap_data, ai_provider,
             timeframe_months, learning_hours_per_week, current_skills,
-            project_suggestions, job_application_timing
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
+            project_suggestions, job_application_timing,
+            ai_model, prompt_version, generation_settings, quality_warnings
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         RETURNING id",
     )
     .bind(auth_user.user_id)
     .bind(format!("Roadmap to {}", target_role))
     .bind(target_role)
-    .bind(&response.data)
+    .bind(&roadmap_data)
     .bind(provider_string)
     .bind(timeframe_months as i32)
     .bind(learning_hours_per_week as i32)
     .bind(&user_skills_json)
     .bind(&project_suggestions)
-    .bind(job_application_timing)
+    .bind(&job_application_timing)
+    .bind(response.model)
+    .bind(response.prompt_version)
+    .bind(sqlx::types::Json(&response.settings))
+    .bind(quality_warnings.as_ref().map(sqlx::types::Json))
     .fetch_one(&state.db_pool)
     .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "roadmap": response.data,
-        "roadmap_id": roadmap_id,
-        "provider": response.provider,
-        "message": "Roadmap generated and saved successfully",
-        "metadata": {
-            "timeframe_months": timeframe_months,
-            "learning_hours_per_week": learning_hours_per_week,
-            "job_application_timing": job_application_timing
-        }
+    if let Err(e) = crate::skills::sync_roadmap_progress(&state.db_pool, auth_user.user_id).await {
+        tracing::warn!("Failed to sync skill progress for user {}: {}", auth_user.user_id, e);
+    }
+    crate::activity::record(
+        &state.db_pool,
+        auth_user.user_id,
+        crate::activity::ActivityType::RoadmapCreated,
+        json!({ "roadmap_id": roadmap_id, "target_role": target_role }),
+    )
+    .await;
+
+    let glossary = roadmap_glossary(&state, &roadmap_data).await;
+    Ok(ApiResponse::ok(GeneratedRoadmap {
+        roadmap: roadmap_data,
+        roadmap_id,
+        quality_warnings,
+        glossary,
+        metadata: RoadmapMetadata {
+            timeframe_months,
+            learning_hours_per_week,
+            job_application_timing,
+        },
+    })
+    .meta("provider", response.provider)
+    .meta("message", "Roadmap generated and saved successfully")
+    .meta("warnings", response.warnings)
+    .meta("generation", json!({
+        "model": response.model,
+        "prompt_version": response.prompt_version,
+        "settings": response.settings
     })))
 }
 
+/// Adds the `reading_level` and `tone` the request body asked for to the AI
+/// parameters, over any defaults. They are validated with the other
+/// generation settings.
+fn with_style(payload: &serde_json::Value, mut parameters: serde_json::Value) -> serde_json::Value {
+    if let Some(parameters) = parameters.as_object_mut() {
+        for name in ["reading_level", "tone"] {
+            if let Some(value) = payload.get(name).filter(|v| !v.is_null()) {
+                parameters.insert(name.to_string(), value.clone());
+            }
+        }
+    }
+    parameters
+}
+
+/// Runs the roadmap self-check.
+///
+/// Returns the corrected roadmap, if `auto_fix` is set and the reviewer
+/// produced a valid one, and the issues left to show the learner.
+async fn review_roadmap(
+    ai_service: &crate::ai::AIService,
+    request: AIActionRequest,
+    auto_fix: bool,
+) -> Result<(Option<serde_json::Value>, Vec<crate::ai::types::RoadmapIssue>), AppError> {
+    let response = ai_service.process_action(request).await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "Roadmap review failed".to_string()),
+        ));
+    }
+    let review: crate::ai::types::RoadmapReview = serde_json::from_value(response.data)
+        .map_err(|e| AppError::ExternalServiceError(format!("Invalid roadmap review: {}", e)))?;
+
+    // Only accept a revision that still has the roadmap structure
+    let revised = review.revised_roadmap.filter(|revised| {
+        auto_fix && serde_json::from_value::<crate::ai::types::TechStackRoadmap>(revised.clone()).is_ok()
+    });
+    let warnings = review
+        .issues
+        .into_iter()
+        .filter(|issue| revised.is_none() || !issue.fixed)
+        .map(|issue| crate::ai::types::RoadmapIssue { fixed: false, ..issue })
+        .collect();
+    Ok((revised, warnings))
+}
+
 /// Generate professional summary for CV/profile
 ///
 /// # Endpoint
@@ -393,16 +561,16 @@ pub async fn generate_professional_summary(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let provider_str = payload
         .get("provider")
         .and_then(|v| v.as_str())
         .unwrap_or("gemini");
 
     // Get user profile
-    let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-        .bind(auth_user.user_id)
-        .fetch_one(&state.db_pool)
+    let user = state
+        .user_cache
+        .get_user(&state.db_pool, auth_user.user_id)
         .await?;
 
     // Build context from user profile
@@ -421,18 +589,16 @@ pub async fn generate_professional_summary(
     );
 
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::GenerateContent,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider: crate::ai::types::AIProvider::from_name(provider_str),
         input: prompt,
-        parameters: Some(json!({
+        parameters: Some(with_style(&payload, json!({
             "content_type": "professional_summary",
             "tone": "professional",
             "length": "short"
-        })),
+        }))),
+        capture: true,
     };
 
     let ai_service = state
@@ -442,11 +608,8 @@ pub async fn generate_professional_summary(
 
     let response = ai_service.process_action(ai_request).await?;
 
-    Ok(Json(json!({
-        "success": response.success,
-        "summary": response.data,
-        "provider": response.provider
-    })))
+    Ok(ApiResponse::new(response.success, json!({ "summary": response.data }))
+        .meta("provider", response.provider))
 }
 
 /// Improve project descriptions with AI
@@ -465,7 +628,7 @@ pub async fn improve_project_descriptions(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let projects = payload
         .get("projects")
         .and_then(|v| v.as_array())
@@ -477,9 +640,9 @@ pub async fn improve_project_descriptions(
         .unwrap_or("gemini");
 
     // Get user skills for context
-    let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-        .bind(auth_user.user_id)
-        .fetch_one(&state.db_pool)
+    let user = state
+        .user_cache
+        .get_user(&state.db_pool, auth_user.user_id)
         .await?;
 
     let projects_text = projects
@@ -495,30 +658,243 @@ pub async fn improve_project_descriptions(
     );
 
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::GenerateContent,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider: crate::ai::types::AIProvider::from_name(provider_str),
         input: prompt,
-        parameters: Some(json!({
+        parameters: Some(with_style(&payload, json!({
             "content_type": "project_descriptions",
             "format": "bullet_points"
-        })),
+        }))),
+        capture: true,
+    };
+
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    let response = ai_service.process_action(ai_request).await?;
+
+    Ok(ApiResponse::new(response.success, json!({ "improved_projects": response.data }))
+        .meta("provider", response.provider))
+}
+
+/// Draft the employer's company branding: a "why work here" section and a
+/// pitch per role, generated from the company profile
+///
+/// The draft replaces any earlier one and is published to the public
+/// company page with `POST /api/employer/company/branding/publish`.
+///
+/// # Endpoint
+/// `POST /api/ai/employer/branding`
+///
+/// # Request Body
+/// ```json
+/// {
+///   "roles": ["Backend Engineer", "Product Designer"],
+///   "provider": "gemini"
+/// }
+/// ```
+pub async fn generate_employer_branding(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateBrandingPayload>,
+) -> Result<ApiResponse<crate::branding::CompanyBranding>, AppError> {
+    let seat = crate::teams::require_seat(&state.db_pool, auth_user.user_id, crate::teams::PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    let roles = payload
+        .roles
+        .iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    let branding = crate::branding::generate(
+        &state.db_pool,
+        ai_service,
+        auth_user.user_id,
+        seat.company_id,
+        roles,
+        payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+    )
+    .await?;
+    Ok(ApiResponse::ok(branding))
+}
+
+/// Draft a structured job posting from a short brief
+///
+/// Returns the posting fields `POST /api/employer/jobs` accepts, plus
+/// nice-to-haves, a salary range suggested from similar approved postings,
+/// and exclusionary phrases to fix before publishing. Nothing is posted.
+///
+/// # Endpoint
+/// `POST /api/ai/employer/job-description`
+///
+/// # Request Body
+/// ```json
+/// {
+///   "brief": "Backend developer to build our payments API in Rust and PostgreSQL, working with two seniors",
+///   "experience_level": "junior",
+///   "location": "Dhaka"
+/// }
+/// ```
+pub async fn draft_job_description(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<JobDescriptionBriefPayload>,
+) -> Result<ApiResponse<crate::job_writer::JobDraft>, AppError> {
+    auth_user
+        .require_role(&state.db_pool, &[crate::models::UserRole::Employer, crate::models::UserRole::Admin])
+        .await?;
+    let seat = crate::teams::seat_for(&state.db_pool, auth_user.user_id).await?;
+    if let Some(seat) = &seat
+        && !crate::teams::PIPELINE_ROLES.contains(&seat.role)
+    {
+        return Err(AppError::Forbidden);
+    }
+    payload.validate()?;
+
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    let brief = crate::job_writer::Brief {
+        brief: payload.brief.trim().to_string(),
+        job_title: payload.job_title,
+        experience_level: payload.experience_level,
+        job_type: payload.job_type,
+        location: payload.location,
     };
+    let draft = crate::job_writer::draft(
+        &state.db_pool,
+        ai_service,
+        auth_user.user_id,
+        seat.map(|s| s.company_id),
+        brief,
+        payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+    )
+    .await?;
+    let flags = draft.language_flags.len();
+    Ok(ApiResponse::ok(draft).meta("language_flags", flags))
+}
+
+/// Suggest screening questions for a job posting
+///
+/// Suggestions are drafted from the posting's description and requirements
+/// and returned for the employer to edit; save them with
+/// `PUT /api/employer/jobs/:id/screening-questions`.
+///
+/// # Endpoint
+/// `POST /api/ai/employer/jobs/:id/screening-questions`
+///
+/// # Request Body
+/// ```json
+/// { "count": 5, "provider": "gemini" }
+/// ```
+pub async fn generate_screening_questions(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(job_id): axum::extract::Path<i32>,
+    payload: Option<Json<GenerateScreeningQuestionsPayload>>,
+) -> Result<ApiResponse<Vec<crate::screening::QuestionInput>>, AppError> {
+    super::screening::require_job_access(&state, &auth_user, job_id, true).await?;
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
 
     let ai_service = state
         .ai_service
         .as_ref()
         .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
 
+    let questions = crate::screening::generate(
+        &state.db_pool,
+        ai_service,
+        auth_user.user_id,
+        job_id,
+        payload.count.unwrap_or(5),
+        payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+    )
+    .await?;
+    Ok(ApiResponse::ok(questions))
+}
+
+/// Preview an organization's career services guidance (org admins only)
+///
+/// Runs a sample mentor question or roadmap target role with the saved
+/// guidance, or with draft `persona` text, so admins can check the result
+/// before saving it with `PUT /api/orgs/:id/persona`. Nothing is stored.
+///
+/// # Endpoint
+/// `POST /api/ai/orgs/:id/persona/preview`
+///
+/// # Request Body
+/// ```json
+/// {
+///   "kind": "mentor",
+///   "input": "How should I prepare for campus placements?",
+///   "persona": "Refer students to the Career Centre's weekly drop-in sessions.",
+///   "provider": "gemini"
+/// }
+/// ```
+pub async fn preview_org_persona(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(org_id): axum::extract::Path<i32>,
+    Json(payload): Json<PersonaPreviewPayload>,
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
+    crate::cohorts::require_org_role(&state.db_pool, org_id, auth_user.user_id, &[crate::models::OrgRole::Admin])
+        .await?;
+    payload.validate()?;
+
+    let persona = match payload.persona.as_deref().map(str::trim) {
+        Some(draft) => Some(draft.to_string()).filter(|d| !d.is_empty()),
+        None => crate::tenant_config::get(&state.db_pool, org_id)
+            .await?
+            .persona(payload.kind)
+            .map(str::to_string),
+    };
+
+    let (action, parameters) = match payload.kind {
+        PersonaKind::Mentor => (
+            crate::ai::types::ActionType::AskQuestion,
+            json!({ "persona": persona }),
+        ),
+        PersonaKind::Roadmap => (
+            crate::ai::types::ActionType::GenerateRoadmap,
+            json!({ "timeframe_months": 6, "learning_hours_per_week": 10, "persona": persona }),
+        ),
+    };
+    let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
+        action,
+        provider: payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+        input: payload.input.trim().to_string(),
+        parameters: Some(parameters),
+        capture: true,
+    };
+
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
     let response = ai_service.process_action(ai_request).await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "Persona preview failed".to_string()),
+        ));
+    }
 
-    Ok(Json(json!({
-        "success": response.success,
-        "improved_projects": response.data,
-        "provider": response.provider
+    Ok(ApiResponse::ok(json!({
+        "kind": payload.kind,
+        "persona": persona,
+        "output": response.data,
     })))
 }
 
@@ -538,7 +914,7 @@ pub async fn get_profile_suggestions(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let platform = payload
         .get("platform")
         .and_then(|v| v.as_str())
@@ -550,9 +926,9 @@ pub async fn get_profile_suggestions(
         .unwrap_or("gemini");
 
     // Get user profile
-    let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-        .bind(auth_user.user_id)
-        .fetch_one(&state.db_pool)
+    let user = state
+        .user_cache
+        .get_user(&state.db_pool, auth_user.user_id)
         .await?;
 
     let prompt = format!(
@@ -565,17 +941,15 @@ pub async fn get_profile_suggestions(
     );
 
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::GenerateContent,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider: crate::ai::types::AIProvider::from_name(provider_str),
         input: prompt,
         parameters: Some(json!({
             "content_type": "profile_suggestions",
             "platform": platform
         })),
+        capture: true,
     };
 
     let ai_service = state
@@ -585,16 +959,149 @@ pub async fn get_profile_suggestions(
 
     let response = ai_service.process_action(ai_request).await?;
 
-    Ok(Json(json!({
-        "success": response.success,
+    Ok(ApiResponse::new(response.success, json!({
         "suggestions": response.data,
-        "platform": platform,
-        "provider": response.provider
-    })))
+        "platform": platform
+    }))
+    .meta("provider", response.provider))
+}
+
+/// Explain a jargon term for beginners
+///
+/// Definitions come from the glossary; a term nobody asked about before is
+/// explained by the AI once and stored, so `cached` is false only then.
+/// Roadmap topics and mentor answers link the glossary terms they contain.
+///
+/// # Endpoint
+/// `POST /api/ai/explain-term`
+///
+/// # Request Body
+/// ```json
+/// { "term": "CI/CD", "context": "Set up a CI/CD pipeline for your project", "provider": "gemini" }
+/// ```
+pub async fn explain_term(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<ExplainTermPayload>,
+) -> Result<ApiResponse<GlossaryEntry>, AppError> {
+    payload.validate()?;
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let (entry, cached) = glossary::explain(
+        &state.db_pool,
+        ai_service,
+        auth_user.user_id,
+        &payload.term,
+        payload.context.as_deref(),
+        payload.provider.unwrap_or(AIProvider::Gemini),
+    )
+    .await?;
+    Ok(ApiResponse::ok(entry).meta("cached", cached))
+}
+
+/// Rewrite a previously generated document at a simpler reading level
+///
+/// Text keeps its headings and lists; a JSON document (e.g. a roadmap)
+/// comes back with the same keys and items, only its prose rewritten.
+///
+/// # Endpoint
+/// `POST /api/ai/simplify`
+///
+/// # Request Body
+/// ```json
+/// { "content": "## Phase 1\n- Learn idempotent REST semantics", "reading_level": "simple", "provider": "gemini" }
+/// ```
+pub async fn simplify_content(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<SimplifyPayload>,
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
+    payload.validate()?;
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let reading_level = payload.reading_level.unwrap_or(ReadingLevel::Simple);
+    let simplified = crate::simplify::simplify(
+        ai_service,
+        auth_user.user_id,
+        &payload.content,
+        reading_level,
+        payload.tone,
+        payload.provider.unwrap_or(AIProvider::Gemini),
+    )
+    .await?;
+    Ok(ApiResponse::ok(simplified).meta("reading_level", reading_level))
+}
+
+/// AI token usage per provider and per action
+///
+/// Users see their own calls. Admins may pass `user_id` for another user's
+/// or `all=true` for the whole platform's.
+///
+/// # Endpoint
+/// `GET /api/ai/usage?days=30`
+pub async fn get_ai_usage(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Query(params): Query<AiUsageParams>,
+) -> Result<ApiResponse<crate::ai_usage::UsageReport>, AppError> {
+    let user_id = if params.all || params.user_id.is_some() {
+        auth_user.require_role(&state.db_pool, &[crate::models::UserRole::Admin]).await?;
+        params.user_id.filter(|_| !params.all)
+    } else {
+        Some(auth_user.user_id)
+    };
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let report = crate::ai_usage::report(&state.db_pool, user_id, days).await?;
+    Ok(ApiResponse::ok(report).meta("days", days))
+}
+
+/// Glossary terms in a mentor answer; annotation is best-effort.
+pub(super) async fn answer_glossary(state: &AppState, answer: &serde_json::Value) -> Vec<TermLink> {
+    let Some(text) = answer.get("answer").and_then(|a| a.as_str()) else {
+        return Vec::new();
+    };
+    glossary::link_terms(&state.db_pool, text).await.unwrap_or_else(|e| {
+        tracing::warn!("Linking glossary terms failed: {}", e);
+        Vec::new()
+    })
+}
+
+/// Glossary terms in a roadmap's topics; annotation is best-effort.
+async fn roadmap_glossary(state: &AppState, roadmap: &serde_json::Value) -> HashMap<String, Vec<TermLink>> {
+    glossary::link_roadmap_terms(&state.db_pool, roadmap).await.unwrap_or_else(|e| {
+        tracing::warn!("Linking glossary terms failed: {}", e);
+        HashMap::new()
+    })
 }
 
 /// Career chatbot - ask career-related questions
 ///
+/// Pass `conversation_id` to continue a saved conversation: the answer takes
+/// its history into account, within the model's context budget, and the
+/// exchange is appended to it. `save_conversation: true` starts a new saved
+/// conversation instead. The response includes the `conversation_id` when
+/// the exchange was saved.
+///
+/// Answers are grounded in the user's CV, roadmaps, and saved documents,
+/// and in the knowledge base: the most relevant excerpts are retrieved and
+/// sent as context, and
+/// returned as numbered `sources`. Pass `use_documents: false` to skip it.
+///
+/// With `use_tools: true` the mentor may call tools (job search, roadmap
+/// progress, skill gap) to look up the user's data before answering; the
+/// calls are listed in `tool_calls`. Tool answers are plain text, so their
+/// `related_topics` is empty.
+///
+/// Standalone questions (no `conversation_id`, no tools) are answered from
+/// the semantic cache when the user recently asked a near-identical question
+/// with the same profile; the response then has `cached: true` and the
+/// original question in `meta.cached_question`. Pass `use_cache: false` to
+/// always ask the provider.
+///
 /// # Endpoint
 /// `POST /api/ai/ask-mentor`
 ///
@@ -602,60 +1109,375 @@ pub async fn get_profile_suggestions(
 /// ```json
 /// {
 ///   "question": "What should I learn to become a backend developer?",
-///   "provider": "gemini"
+///   "provider": "gemini",
+///   "conversation_id": 12
 /// }
 /// ```
 pub async fn ask_career_mentor(
     auth_user: AuthUser,
     State(state): State<AppState>,
-    Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let question = payload
-        .get("question")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| AppError::ValidationError("question is required".to_string()))?;
+    ValidatedJson(payload): ValidatedJson<AskMentorRequest>,
+) -> Result<ApiResponse<MentorAnswer>, AppError> {
+    let question = payload.question.as_str();
+    let conversation_id = payload.conversation_id;
+    let save_conversation = payload.save_conversation;
+    let use_documents = payload.use_documents;
+    let use_tools = payload.use_tools;
 
-    let provider_str = payload
-        .get("provider")
-        .and_then(|v| v.as_str())
-        .unwrap_or("gemini");
+    let (mut context, persona) = mentor_context(&state, auth_user.user_id).await?;
 
-    // Get user context
-    let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-        .bind(auth_user.user_id)
-        .fetch_one(&state.db_pool)
-        .await?;
+    let provider = payload.provider.clone().unwrap_or(AIProvider::Gemini);
 
-    let context = format!(
-        "User's current skills: {}\nTarget roles: {}\nExperience level: {:?}",
-        user.skills.join(", "),
-        user.target_roles.join(", "),
-        user.experience_level
-    );
+    let ai_service = state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    // Only standalone questions are cached; answers within a conversation or
+    // from tools depend on more than the question.
+    let cache_key = if payload.use_cache && conversation_id.is_none() && !use_tools && semantic_cache::enabled(ai_service) {
+        let cache_context = format!(
+            "{}\nDocuments: {}\nPersona: {}\nReading level: {}\nTone: {}",
+            context,
+            use_documents,
+            persona.as_deref().unwrap_or_default(),
+            json!(payload.reading_level),
+            json!(payload.tone)
+        );
+        match semantic_cache::key(ai_service, &cache_context, question).await {
+            Ok(key) => key,
+            Err(e) => {
+                tracing::warn!("Embedding mentor question failed for user {}: {}", auth_user.user_id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let Some(key) = &cache_key {
+        match semantic_cache::lookup(&state.db_pool, auth_user.user_id, key).await {
+            Ok(Some(cached)) => {
+                let conversation_id = if save_conversation {
+                    let id = conversations::create(&state.db_pool, auth_user.user_id, question).await?;
+                    let answer = cached
+                        .answer
+                        .get("answer")
+                        .and_then(|a| a.as_str())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| cached.answer.to_string());
+                    conversations::append_exchange(&state.db_pool, id, question, &answer, cached.answer.get("sections"))
+                        .await?;
+                    Some(id)
+                } else {
+                    None
+                };
+                let glossary = answer_glossary(&state, &cached.answer).await;
+                return Ok(ApiResponse::ok(MentorAnswer {
+                    answer: cached.answer,
+                    conversation_id,
+                    sources: cached.sources,
+                    tool_calls: Vec::new(),
+                    glossary,
+                    cached: true,
+                })
+                .meta("provider", cached.provider)
+                .meta("cached_question", cached.question)
+                .meta("similarity", cached.similarity)
+                .meta("cached_at", cached.created_at));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Mentor cache lookup failed for user {}: {}", auth_user.user_id, e),
+        }
+    }
+
+    let sources = if use_documents {
+        ground_in_documents(&state, ai_service, auth_user.user_id, question, &mut context).await
+    } else {
+        Vec::new()
+    };
+    let history =
+        mentor_history(&state, ai_service, auth_user.user_id, conversation_id, &provider, question, &context).await?;
 
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::AskQuestion,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
+        provider,
         input: question.to_string(),
-        parameters: Some(json!({ "context": context })),
+        parameters: Some(json!({
+            "context": context,
+            "history": history,
+            "persona": persona,
+            "reading_level": payload.reading_level,
+            "tone": payload.tone
+        })),
+        capture: true,
     };
 
+    let response = if use_tools {
+        let tools = MentorTools::new(state.db_pool.clone(), auth_user.user_id, conversation_id);
+        ai_service.process_action_with_tools(ai_request, &tools).await?
+    } else {
+        ai_service.process_action(ai_request).await?
+    };
+
+    let conversation_id = match (response.success, conversation_id) {
+        (true, Some(id)) => Some(id),
+        (true, None) if save_conversation => {
+            Some(conversations::create(&state.db_pool, auth_user.user_id, question).await?)
+        }
+        _ => None,
+    };
+    if let Some(id) = conversation_id {
+        let answer = response
+            .data
+            .get("answer")
+            .and_then(|a| a.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| response.data.to_string());
+        conversations::append_exchange(&state.db_pool, id, question, &answer, response.data.get("sections")).await?;
+    }
+
+    if let Some(key) = &cache_key
+        && response.success
+    {
+        let provider = response.provider.as_str();
+        if let Err(e) = semantic_cache::store(
+            &state.db_pool,
+            auth_user.user_id,
+            key,
+            question,
+            &response.data,
+            &json!(sources),
+            provider,
+        )
+        .await
+        {
+            tracing::warn!("Caching mentor answer failed for user {}: {}", auth_user.user_id, e);
+        }
+    }
+
+    let glossary = answer_glossary(&state, &response.data).await;
+    Ok(ApiResponse::new(response.success, MentorAnswer {
+        answer: response.data,
+        conversation_id,
+        sources: json!(sources),
+        tool_calls: response.tool_calls,
+        glossary,
+        cached: false,
+    })
+    .meta("provider", response.provider)
+    .meta("warnings", response.warnings))
+}
+
+/// Career chatbot, streaming the answer as Server-Sent Events
+///
+/// Takes the same `question`, `provider`, `conversation_id`,
+/// `save_conversation`, and `use_documents` as `POST /api/ai/ask-mentor`.
+/// Answers are plain text, streamed from Gemini or Groq as they are
+/// generated; tools and the semantic cache are not used.
+///
+/// Events:
+/// - `delta`: `{"text": "..."}`, the next piece of the answer
+/// - `done`: the answer as from `ask-mentor`, with its `conversation_id`,
+///   `sources`, `provider`, and `warnings`
+/// - `error`: `{"error": "..."}` if the answer failed, possibly after some
+///   deltas were sent
+///
+/// The answer is completed and the exchange saved even if the client
+/// disconnects early.
+///
+/// # Endpoint
+/// `POST /api/ai/ask-mentor/stream`
+///
+/// # Errors
+///
+/// Returns an error before the stream starts if validation fails, the
+/// conversation is not the user's (404), or the AI service is not
+/// configured.
+pub async fn ask_career_mentor_stream(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<AskMentorStreamPayload>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    payload.validate()?;
+    let question = payload.question.trim().to_string();
+    let provider = payload.provider.unwrap_or(AIProvider::Gemini);
     let ai_service = state
         .ai_service
-        .as_ref()
+        .clone()
         .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
 
-    let response = ai_service.process_action(ai_request).await?;
+    let (mut context, persona) = mentor_context(&state, auth_user.user_id).await?;
+    let sources = if payload.use_documents.unwrap_or(true) {
+        ground_in_documents(&state, &ai_service, auth_user.user_id, &question, &mut context).await
+    } else {
+        Vec::new()
+    };
+    let history = mentor_history(
+        &state,
+        &ai_service,
+        auth_user.user_id,
+        payload.conversation_id,
+        &provider,
+        &question,
+        &context,
+    )
+    .await?;
 
-    Ok(Json(json!({
-        "success": response.success,
-        "answer": response.data,
-        "provider": response.provider
-    })))
+    let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
+        action: crate::ai::types::ActionType::AskQuestion,
+        provider,
+        input: question.clone(),
+        parameters: Some(json!({
+            "context": context,
+            "history": history,
+            "persona": persona,
+            "reading_level": payload.reading_level,
+            "tone": payload.tone
+        })),
+        capture: true,
+    };
+
+    let (deltas_tx, deltas) = mpsc::unbounded_channel();
+    let user_id = auth_user.user_id;
+    let conversation_id = payload.conversation_id;
+    let save_conversation = payload.save_conversation;
+    // The answer runs apart from the response so a disconnect does not cut it short
+    let answer = tokio::spawn(async move {
+        let stream = AnswerStream::new(deltas_tx);
+        let response = match ai_service.process_action_streaming(ai_request, &stream).await {
+            Ok(response) if response.success => response,
+            Ok(response) => {
+                let message = response.message.unwrap_or_else(|| "The answer failed".to_string());
+                return Event::default().event("error").data(json!({ "error": message }).to_string());
+            }
+            Err(e) => {
+                return Event::default().event("error").data(json!({ "error": e.to_string() }).to_string());
+            }
+        };
+
+        let saved = match conversation_id {
+            Some(id) => Ok(Some(id)),
+            None if save_conversation => conversations::create(&state.db_pool, user_id, &question).await.map(Some),
+            None => Ok(None),
+        };
+        let answer = response.data.get("answer").and_then(|a| a.as_str()).unwrap_or_default();
+        let conversation_id = match saved {
+            Ok(Some(id)) => match conversations::append_exchange(
+                &state.db_pool,
+                id,
+                &question,
+                answer,
+                response.data.get("sections"),
+            )
+            .await
+            {
+                Ok(()) => Some(id),
+                Err(e) => {
+                    tracing::error!("Saving streamed mentor answer failed for user {}: {}", user_id, e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::error!("Creating mentor conversation failed for user {}: {}", user_id, e);
+                None
+            }
+        };
+
+        let glossary = answer_glossary(&state, &response.data).await;
+        Event::default().event("done").data(
+            json!({
+                "answer": response.data,
+                "conversation_id": conversation_id,
+                "sources": sources,
+                "glossary": glossary,
+                "provider": response.provider,
+                "warnings": response.warnings
+            })
+            .to_string(),
+        )
+    });
+
+    // Deltas until the answer is complete, then its outcome
+    let events = stream::unfold((deltas, Some(answer)), |(mut deltas, answer)| async move {
+        if let Some(text) = deltas.recv().await {
+            let event = Event::default().event("delta").data(json!({ "text": text }).to_string());
+            return Some((Ok(event), (deltas, answer)));
+        }
+        let event = answer?.await.unwrap_or_else(|e| {
+            tracing::error!("Streamed mentor answer task failed: {}", e);
+            Event::default().event("error").data(json!({ "error": "The answer failed" }).to_string())
+        });
+        Some((Ok(event), (deltas, None)))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Profile context and organization persona for the mentor.
+pub(super) async fn mentor_context(state: &AppState, user_id: Uuid) -> Result<(String, Option<String>), AppError> {
+    let user = state.user_cache.get_user(&state.db_pool, user_id).await?;
+    let context = format!(
+        "User's current skills: {}\nTarget roles: {}\nExperience level: {:?}",
+        user.skills.join(", "),
+        user.target_roles.join(", "),
+        user.experience_level
+    );
+    let persona = state
+        .tenant_configs
+        .persona_for(&state.db_pool, user_id, PersonaKind::Mentor)
+        .await;
+    Ok((context, persona))
+}
+
+/// Adds the excerpts of the user's documents and the knowledge base most
+/// relevant to the question to `context`, returning their attributions.
+/// Retrieval failures leave the context as it was.
+pub(super) async fn ground_in_documents(
+    state: &AppState,
+    ai_service: &AIService,
+    user_id: Uuid,
+    question: &str,
+    context: &mut String,
+) -> Vec<retrieval::SourceAttribution> {
+    if !ai_service.embeddings_available() {
+        return Vec::new();
+    }
+    match retrieval::retrieve(&state.db_pool, ai_service, user_id, question, retrieval::DEFAULT_LIMIT).await {
+        Ok(chunks) if !chunks.is_empty() => {
+            context.push_str("\n\n");
+            context.push_str(&retrieval::to_context(&chunks));
+            retrieval::attributions(&chunks)
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Document retrieval failed for user {}: {}", user_id, e);
+            Vec::new()
+        }
+    }
+}
+
+/// History of the user's saved conversation, if continuing one, within the
+/// model's context budget.
+pub(super) async fn mentor_history(
+    state: &AppState,
+    ai_service: &AIService,
+    user_id: Uuid,
+    conversation_id: Option<i32>,
+    provider: &AIProvider,
+    question: &str,
+    context: &str,
+) -> Result<Vec<ChatMessage>, AppError> {
+    let Some(id) = conversation_id else {
+        return Ok(Vec::new());
+    };
+    conversations::ensure_owned(&state.db_pool, user_id, id).await?;
+    let budget = conversations::history_budget(ai_service.model_for(provider), question, context);
+    Ok(conversations::assemble_context(&state.db_pool, ai_service, provider.clone(), id, budget)
+        .await?
+        .to_messages())
 }
 
 /// Enhanced career mentor with intelligent context awareness
@@ -678,7 +1500,7 @@ pub async fn enhanced_career_mentor(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let question = payload
         .get("question")
         .and_then(|v| v.as_str())
@@ -737,9 +1559,9 @@ pub async fn enhanced_career_mentor(
     );
 
     // Get user profile
-    let user = sqlx::query_as::<_, crate::models::User>("SELECT * FROM users WHERE id = $1")
-        .bind(auth_user.user_id)
-        .fetch_one(&state.db_pool)
+    let user = state
+        .user_cache
+        .get_user(&state.db_pool, auth_user.user_id)
         .await?;
 
     let mut context_parts = vec![
@@ -801,15 +1623,16 @@ pub async fn enhanced_career_mentor(
             
         tracing::info!("Searching for jobs with pattern: '{}'", search_pattern);
             
-        let jobs = sqlx::query!(
+        let jobs = sqlx::query_scalar::<_, Vec<String>>(
             r#"
             SELECT required_skills
             FROM jobs
             WHERE LOWER(job_title) LIKE LOWER($1)
+              AND moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
             LIMIT 10
             "#,
-            format!("%{}%", search_pattern)
         )
+        .bind(format!("%{}%", search_pattern))
         .fetch_all(&state.db_pool)
         .await?;
         
@@ -818,8 +1641,8 @@ pub async fn enhanced_career_mentor(
         if !jobs.is_empty() {
             // Aggregate required skills
             let mut all_required_skills = std::collections::HashSet::new();
-            for job in &jobs {
-                for skill in &job.required_skills {
+            for required in &jobs {
+                for skill in required {
                     all_required_skills.insert(skill.clone());
                 }
             }
@@ -876,7 +1699,11 @@ pub async fn enhanced_career_mentor(
             
             // Suggest available job titles
             let sample_titles = sqlx::query_scalar::<_, String>(
-                "SELECT DISTINCT job_title FROM jobs LIMIT 10"
+                r#"
+                SELECT DISTINCT job_title FROM jobs
+                WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+                LIMIT 10
+                "#
             )
             .fetch_all(&state.db_pool)
             .await?;
@@ -895,9 +1722,11 @@ pub async fn enhanced_career_mentor(
         tracing::info!("Performing market analysis");
 
         // Get total job count
-        let total_jobs = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM jobs")
-            .fetch_one(&state.db_pool)
-            .await?;
+        let total_jobs = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM jobs WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())",
+        )
+        .fetch_one(&state.db_pool)
+        .await?;
 
         // Get total learning resources count
         let total_resources = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM learning_resources")
@@ -905,7 +1734,7 @@ pub async fn enhanced_career_mentor(
             .await?;
 
         // Get total users count
-        let total_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+        let total_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE sandbox_key_id IS NULL")
             .fetch_one(&state.db_pool)
             .await?;
 
@@ -942,17 +1771,20 @@ pub async fn enhanced_career_mentor(
         }
 
         // Get unique companies count
-        let total_companies = sqlx::query_scalar::<_, i64>("SELECT COUNT(DISTINCT company) FROM jobs")
-            .fetch_one(&state.db_pool)
-            .await?;
+        let total_companies = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(DISTINCT company) FROM jobs WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())",
+        )
+        .fetch_one(&state.db_pool)
+        .await?;
 
         context_parts.push(format!("- Unique Companies: {}", total_companies));
 
         // Get experience level breakdown
-        let exp_breakdown = sqlx::query!(
+        let exp_breakdown = sqlx::query_as::<_, (crate::models::ExperienceLevel, i64)>(
             r#"
-            SELECT experience_level as "experience_level: crate::models::ExperienceLevel", COUNT(*) as count
+            SELECT experience_level, COUNT(*) as count
             FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
             GROUP BY experience_level
             ORDER BY count DESC
             "#
@@ -963,17 +1795,18 @@ pub async fn enhanced_career_mentor(
         if !exp_breakdown.is_empty() {
             let exp_dist: Vec<String> = exp_breakdown
                 .iter()
-                .map(|row| format!("{:?}: {}", row.experience_level, row.count.unwrap_or(0)))
+                .map(|(experience_level, count)| format!("{:?}: {}", experience_level, count))
                 .collect();
             
             context_parts.push(format!("- By Experience Level: {}", exp_dist.join(", ")));
         }
 
         // Get most in-demand skills from all jobs
-        let skill_demand = sqlx::query!(
+        let skill_demand = sqlx::query_as::<_, (String, i64)>(
             r#"
             SELECT unnest(required_skills) as skill, COUNT(*) as demand_count
             FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
             GROUP BY skill
             ORDER BY demand_count DESC
             LIMIT 15
@@ -985,7 +1818,7 @@ pub async fn enhanced_career_mentor(
         if !skill_demand.is_empty() {
             let top_skills: Vec<String> = skill_demand
                 .iter()
-                .map(|row| format!("{} ({} jobs)", row.skill.as_deref().unwrap_or("Unknown"), row.demand_count.unwrap_or(0)))
+                .map(|(skill, demand_count)| format!("{} ({} jobs)", skill, demand_count))
                 .collect();
 
             context_parts.push(format!(
@@ -1001,10 +1834,9 @@ pub async fn enhanced_career_mentor(
 
             let missing_trending_skills: Vec<String> = skill_demand
                 .iter()
-                .filter_map(|row| {
-                    let skill = row.skill.as_deref()?;
+                .filter_map(|(skill, demand_count)| {
                     if !user_skills_lower.contains(&skill.to_lowercase()) {
-                        Some(format!("{} ({} jobs)", skill, row.demand_count.unwrap_or(0)))
+                        Some(format!("{} ({} jobs)", skill, demand_count))
                     } else {
                         None
                     }
@@ -1021,10 +1853,11 @@ pub async fn enhanced_career_mentor(
         }
 
         // Get job type distribution
-        let job_types = sqlx::query!(
+        let job_types = sqlx::query_as::<_, (crate::models::JobType, i64)>(
             r#"
-            SELECT job_type as "job_type: crate::models::JobType", COUNT(*) as count
+            SELECT job_type, COUNT(*) as count
             FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
             GROUP BY job_type
             ORDER BY count DESC
             "#
@@ -1035,7 +1868,7 @@ pub async fn enhanced_career_mentor(
         if !job_types.is_empty() {
             let type_dist: Vec<String> = job_types
                 .iter()
-                .map(|row| format!("{:?}: {}", row.job_type, row.count.unwrap_or(0)))
+                .map(|(job_type, count)| format!("{:?}: {}", job_type, count))
                 .collect();
 
             context_parts.push(format!(
@@ -1083,39 +1916,36 @@ pub async fn enhanced_career_mentor(
 
     tracing::debug!("Generated context for AI:\n{}", full_context);
 
-    // Build enhanced prompt that forces AI to use exact numbers from context
-    let enhanced_prompt = if include_skill_gap {
-        format!(
-            "IMPORTANT: The context below contains the user's ACTUAL skill gap analysis with match percentage. Use these EXACT numbers.\n\n{}\n\nQuestion: {}\n\nAnswer with the specific match percentage and skill gaps shown in the context above. Be direct and cite the exact numbers provided.",
-            full_context, question
-        )
+    // Guidance that makes the AI use exact numbers from the context. The
+    // question goes in separately so the prompt builder can fence it as
+    // user input.
+    let guidance = if include_skill_gap {
+        "IMPORTANT: The context below contains the user's ACTUAL skill gap analysis with match percentage. Use these EXACT numbers. Answer with the specific match percentage and skill gaps shown, and cite the exact numbers provided."
     } else if include_market_analysis {
-        format!(
-            "IMPORTANT: The context below contains ACTUAL DATA from our database. Use ONLY these exact numbers and resource names. Do NOT make up or estimate any statistics.\n\nWhen the user asks about resources, jobs, or statistics, refer to the specific data provided below.\n\n{}\n\nQuestion: {}\n\nAnswer based on the data above. If asking about learning resources, reference the actual resource titles and platforms shown above.",
-            full_context, question
-        )
+        "IMPORTANT: The context below contains ACTUAL DATA from our database. Use ONLY these exact numbers and resource names. Do NOT make up or estimate any statistics. If the user asks about learning resources, reference the actual resource titles and platforms shown."
     } else {
-        format!(
-            "Based on the following user context, answer their question:\n\n{}\n\nQuestion: {}",
-            full_context, question
-        )
+        "Answer the user's question based on the following user context."
     };
+    let enhanced_context = format!("{}\n\n{}", guidance, full_context);
+    let persona = state
+        .tenant_configs
+        .persona_for(&state.db_pool, auth_user.user_id, PersonaKind::Mentor)
+        .await;
 
     // Call AI service with enhanced context
     let ai_request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
         action: crate::ai::types::ActionType::AskQuestion,
-        provider: if provider_str == "groq" {
-            crate::ai::types::AIProvider::Groq
-        } else {
-            crate::ai::types::AIProvider::Gemini
-        },
-        input: enhanced_prompt,
+        provider: crate::ai::types::AIProvider::from_name(provider_str),
+        input: question.to_string(),
         parameters: Some(json!({
-            "context": full_context,
+            "context": enhanced_context,
+            "persona": persona,
             "enhanced": true,
             "include_skill_gap": include_skill_gap,
             "include_market_analysis": include_market_analysis
         })),
+        capture: true,
     };
 
     let ai_service = state
@@ -1135,16 +1965,16 @@ pub async fn enhanced_career_mentor(
             .unwrap_or_else(|| serde_json::to_string_pretty(&response.data).unwrap_or_default())
     };
 
-    Ok(Json(json!({
-        "success": response.success,
+    Ok(ApiResponse::new(response.success, json!({
         "answer": answer_text,
-        "provider": response.provider,
         "context_included": {
             "skill_gap": include_skill_gap,
             "market_analysis": include_market_analysis,
             "cv_data": include_cv_data
         }
-    })))
+    }))
+    .meta("provider", response.provider)
+    .meta("warnings", response.warnings))
 }
 
 /// Get all saved roadmaps for the logged-in user
@@ -1154,7 +1984,7 @@ pub async fn enhanced_career_mentor(
 pub async fn get_my_roadmaps(
     auth_user: AuthUser,
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let roadmaps = sqlx::query!(
         "SELECT id, title, target_role, roadmap_data, ai_provider, 
                 timeframe_months, learning_hours_per_week, current_skills,
@@ -1192,11 +2022,107 @@ pub async fn get_my_roadmaps(
         })
         .collect();
 
-    Ok(Json(json!({
-        "success": true,
-        "roadmaps": roadmaps_json,
-        "count": roadmaps_json.len()
-    })))
+    let count = roadmaps_json.len();
+    Ok(ApiResponse::ok(json!({ "roadmaps": roadmaps_json })).meta("count", count))
+}
+
+/// Get the user's unfinished roadmaps taken together: weekly hours needed
+/// against availability, conflict warnings, and questions for the primary
+/// roadmap
+///
+/// # Endpoint
+/// `GET /api/ai/roadmaps/summary`
+pub async fn get_roadmap_summary(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<ApiResponse<crate::portfolio::PortfolioSummary>, AppError> {
+    let summary = crate::portfolio::summary(&state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(summary))
+}
+
+/// Mark a roadmap as the user's primary roadmap, replacing any other
+///
+/// # Endpoint
+/// `PUT /api/ai/roadmaps/:id/primary`
+pub async fn set_primary_roadmap(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
+) -> Result<ApiResponse<crate::portfolio::PortfolioSummary>, AppError> {
+    crate::portfolio::set_primary(&state.db_pool, auth_user.user_id, roadmap_id).await?;
+    let summary = crate::portfolio::summary(&state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(summary))
+}
+
+/// Stop treating a roadmap as primary
+///
+/// # Endpoint
+/// `DELETE /api/ai/roadmaps/:id/primary`
+pub async fn clear_primary_roadmap(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
+) -> Result<ApiResponse<crate::portfolio::PortfolioSummary>, AppError> {
+    crate::portfolio::clear_primary(&state.db_pool, auth_user.user_id, roadmap_id).await?;
+    let summary = crate::portfolio::summary(&state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(summary))
+}
+
+/// Get progress analytics for a roadmap
+///
+/// Available to the roadmap's owner and to staff of organizations with a
+/// cohort the owner belongs to.
+///
+/// # Endpoint
+/// `GET /api/ai/roadmaps/:id/analytics`
+pub async fn get_roadmap_analytics(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
+) -> Result<ApiResponse<crate::roadmap_analytics::RoadmapAnalytics>, AppError> {
+    let analytics = crate::roadmap_analytics::analytics(&state.db_pool, auth_user.user_id, roadmap_id).await?;
+    Ok(ApiResponse::ok(analytics))
+}
+
+/// List the resources in each phase of a roadmap with the user's progress
+///
+/// # Endpoint
+/// `GET /api/ai/roadmaps/:id/resources`
+pub async fn get_roadmap_resources(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
+) -> Result<ApiResponse<Vec<crate::resource_progress::RoadmapResource>>, AppError> {
+    let resources = crate::resource_progress::list(&state.db_pool, auth_user.user_id, roadmap_id).await?;
+    let count = resources.len();
+    Ok(ApiResponse::ok(resources).meta("count", count))
+}
+
+/// Mark a resource in a roadmap phase completed or skipped, with an
+/// optional rating and note
+///
+/// Ratings are aggregated across users to rank learning recommendations.
+///
+/// # Endpoint
+/// `PUT /api/ai/roadmaps/:id/resources/:rid`
+pub async fn update_roadmap_resource(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path((roadmap_id, rid)): axum::extract::Path<(i32, String)>,
+    Json(payload): Json<UpdateRoadmapResourcePayload>,
+) -> Result<ApiResponse<crate::resource_progress::RoadmapResource>, AppError> {
+    payload.validate()?;
+    let resource = crate::resource_progress::update(
+        &state.db_pool,
+        auth_user.user_id,
+        roadmap_id,
+        &rid,
+        payload.status,
+        payload.rating,
+        payload.note.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(resource))
 }
 
 /// Get a specific roadmap by ID
@@ -1207,7 +2133,7 @@ pub async fn get_roadmap_by_id(
     auth_user: AuthUser,
     State(state): State<AppState>,
     axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let roadmap = sqlx::query!(
         "SELECT id, title, target_role, roadmap_data, ai_provider,
                 timeframe_months, learning_hours_per_week, current_skills,
@@ -1222,31 +2148,179 @@ pub async fn get_roadmap_by_id(
     .fetch_optional(&state.db_pool)
     .await?;
 
+    // Model, prompt version, and settings that produced the roadmap, and self-check
+    // results; NULL for older or unchecked roadmaps
+    let generation = sqlx::query_as::<_, RoadmapGeneration>(
+        "SELECT ai_model, prompt_version, generation_settings, quality_warnings
+         FROM career_roadmaps WHERE id = $1 AND user_id = $2",
+    )
+    .bind(roadmap_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db_pool)
+    .await?
+    .unwrap_or_default();
+
     match roadmap {
-        Some(r) => Ok(Json(json!({
-            "success": true,
-            "roadmap": {
-                "id": r.id,
-                "title": r.title,
-                "target_role": r.target_role,
-                "roadmap": r.roadmap_data,
-                "ai_provider": r.ai_provider,
-                "timeframe_months": r.timeframe_months,
-                "learning_hours_per_week": r.learning_hours_per_week,
-                "current_skills": r.current_skills,
-                "project_suggestions": r.project_suggestions,
-                "job_application_timing": r.job_application_timing,
-                "progress_percentage": r.progress_percentage,
-                "completed_phases": r.completed_phases,
-                "notes": r.notes,
-                "created_at": r.created_at,
-                "updated_at": r.updated_at
-            }
-        }))),
+        Some(r) => {
+            let glossary = roadmap_glossary(&state, &r.roadmap_data).await;
+            Ok(ApiResponse::ok(json!({
+                "roadmap": {
+                    "id": r.id,
+                    "title": r.title,
+                    "target_role": r.target_role,
+                    "roadmap": r.roadmap_data,
+                    "ai_provider": r.ai_provider,
+                    "timeframe_months": r.timeframe_months,
+                    "learning_hours_per_week": r.learning_hours_per_week,
+                    "current_skills": r.current_skills,
+                    "project_suggestions": r.project_suggestions,
+                    "job_application_timing": r.job_application_timing,
+                    "progress_percentage": r.progress_percentage,
+                    "completed_phases": r.completed_phases,
+                    "notes": r.notes,
+                    "created_at": r.created_at,
+                    "updated_at": r.updated_at,
+                    "generation": {
+                        "model": generation.ai_model,
+                        "prompt_version": generation.prompt_version,
+                        "settings": generation.generation_settings
+                    },
+                    "quality_warnings": generation.quality_warnings
+                },
+                "glossary": glossary
+            })))
+        }
         None => Err(AppError::NotFound),
     }
 }
 
+/// Export a roadmap as a download
+///
+/// `format` is `pdf` (the default), `large_print_pdf`, `html` (semantic
+/// headings and lists for screen readers), or `text`.
+///
+/// # Endpoint
+/// `GET /api/ai/roadmaps/:id/export?format=html`
+pub async fn export_roadmap(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
+    Query(params): Query<RoadmapExportParams>,
+) -> Result<axum::response::Response, AppError> {
+    let roadmap = sqlx::query_as::<_, (String, String, serde_json::Value, Option<i32>, Option<i32>, Option<String>)>(
+        "SELECT title, target_role, roadmap_data, timeframe_months, learning_hours_per_week, job_application_timing
+         FROM career_roadmaps
+         WHERE id = $1 AND user_id = $2",
+    )
+    .bind(roadmap_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let (title, target_role, roadmap_data, timeframe_months, learning_hours_per_week, job_application_timing) =
+        roadmap;
+    let mut plan = vec![format!("Target role: {}", target_role)];
+    if let Some(months) = timeframe_months {
+        plan.push(format!("{} months", months));
+    }
+    if let Some(hours) = learning_hours_per_week {
+        plan.push(format!("{} hours a week", hours));
+    }
+    let document = Document {
+        title,
+        subtitle: Some(plan.join(" · ")),
+        blocks: roadmap_blocks(&roadmap_data, job_application_timing.as_deref()),
+    };
+
+    let export = crate::export::render_in_background(document, params.format, Typeface::Sans).await?;
+    Ok(export.into_download(&format!("roadmap-{}", roadmap_id)))
+}
+
+/// Lays out a roadmap's phases, projects, and application timing for
+/// export. Fields the provider left out are skipped.
+fn roadmap_blocks(roadmap: &serde_json::Value, job_application_timing: Option<&str>) -> Vec<Block> {
+    let text = |value: &serde_json::Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let list = |value: &serde_json::Value, key: &str| -> Vec<String> {
+        value
+            .get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let mut blocks = Vec::new();
+    let phases = roadmap.get("phases").and_then(|p| p.as_array()).into_iter().flatten();
+    for (index, phase) in phases.enumerate() {
+        let number = phase.get("phase").and_then(|p| p.as_u64()).unwrap_or(index as u64 + 1);
+        blocks.push(Block::Heading(match text(phase, "title") {
+            Some(title) => format!("Phase {}: {}", number, title),
+            None => format!("Phase {}", number),
+        }));
+        let when: Vec<String> = [text(phase, "timeline"), text(phase, "duration")].into_iter().flatten().collect();
+        if !when.is_empty() {
+            blocks.push(Block::Paragraph(when.join(" · ")));
+        }
+        for (heading, key) in [("Learning goals", "learning_goals"), ("Topics", "topics"), ("Resources", "resources")] {
+            let items = list(phase, key);
+            if !items.is_empty() {
+                blocks.push(Block::Subheading(heading.to_string()));
+                blocks.push(Block::List(items));
+            }
+        }
+        let technologies = list(phase, "technologies");
+        if !technologies.is_empty() {
+            blocks.push(Block::Subheading("Technologies".to_string()));
+            blocks.push(Block::Paragraph(technologies.join(", ")));
+        }
+    }
+
+    let projects: Vec<&serde_json::Value> =
+        roadmap.get("project_suggestions").and_then(|p| p.as_array()).into_iter().flatten().collect();
+    if !projects.is_empty() {
+        blocks.push(Block::Heading("Projects".to_string()));
+        for project in projects {
+            let Some(title) = text(project, "title") else {
+                continue;
+            };
+            blocks.push(Block::Subheading(title));
+            if let Some(description) = text(project, "description") {
+                blocks.push(Block::Paragraph(description));
+            }
+            let mut details = Vec::new();
+            if let Some(difficulty) = text(project, "difficulty") {
+                details.push(format!("Difficulty: {}", difficulty));
+            }
+            if let Some(hours) = project.get("estimated_hours").and_then(|h| h.as_u64()) {
+                details.push(format!("About {} hours", hours));
+            }
+            let technologies = list(project, "technologies");
+            if !technologies.is_empty() {
+                details.push(format!("Technologies: {}", technologies.join(", ")));
+            }
+            if !details.is_empty() {
+                blocks.push(Block::Paragraph(details.join(" · ")));
+            }
+        }
+    }
+
+    if let Some(timing) = job_application_timing.map(str::trim).filter(|t| !t.is_empty()) {
+        blocks.push(Block::Heading("When to apply".to_string()));
+        blocks.push(Block::Paragraph(timing.to_string()));
+    }
+    blocks
+}
+
 /// Delete a roadmap by ID
 ///
 /// # Endpoint
@@ -1255,7 +2329,7 @@ pub async fn delete_roadmap(
     auth_user: AuthUser,
     State(state): State<AppState>,
     axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let result = sqlx::query!(
         "DELETE FROM career_roadmaps WHERE id = $1 AND user_id = $2",
         roadmap_id,
@@ -1268,10 +2342,7 @@ pub async fn delete_roadmap(
         return Err(AppError::NotFound);
     }
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Roadmap deleted successfully"
-    })))
+    Ok(ApiResponse::ok(json!({ "message": "Roadmap deleted successfully" })))
 }
 
 /// Update roadmap progress
@@ -1292,7 +2363,7 @@ pub async fn update_roadmap_progress(
     State(state): State<AppState>,
     axum::extract::Path(roadmap_id): axum::extract::Path<i32>,
     Json(payload): Json<serde_json::Value>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<ApiResponse<serde_json::Value>, AppError> {
     let progress_percentage = payload
         .get("progress_percentage")
         .and_then(|v| v.as_i64())
@@ -1312,6 +2383,22 @@ pub async fn update_roadmap_progress(
         .get("notes")
         .and_then(|v| v.as_str());
 
+    // Phases completed before this update, to report only new ones
+    let (roadmap_title, roadmap_data, previous_phases) = sqlx::query_as::<_, (String, serde_json::Value, Option<Vec<i32>>)>(
+        "SELECT title, roadmap_data, completed_phases FROM career_roadmaps WHERE id = $1 AND user_id = $2",
+    )
+    .bind(roadmap_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let new_phases: Vec<i32> = completed_phases
+        .iter()
+        .flatten()
+        .filter(|p| !previous_phases.as_deref().unwrap_or_default().contains(p))
+        .copied()
+        .collect();
+
     // Build dynamic update query
     let mut update_fields = Vec::new();
     let mut query = String::from("UPDATE career_roadmaps SET updated_at = CURRENT_TIMESTAMP");
@@ -1339,6 +2426,19 @@ pub async fn update_roadmap_progress(
     query.push_str(" WHERE id = $1 AND user_id = $2 RETURNING id");
 
     // Execute update
+    let phases_changed = completed_phases.is_some();
+    // The achievement follows phase tracking, not the percentage, which
+    // clients can set to 100 directly
+    let all_phases_completed = completed_phases.as_deref().is_some_and(|completed| {
+        let mut numbers = roadmap_data
+            .get("phases")
+            .and_then(|p| p.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|phase| phase.get("phase")?.as_i64())
+            .peekable();
+        numbers.peek().is_some() && numbers.all(|n| completed.iter().any(|&c| i64::from(c) == n))
+    });
     let result = if let Some(phases) = completed_phases {
         if let Some(note_text) = notes {
             sqlx::query_scalar::<_, i32>(&query)
@@ -1377,10 +2477,33 @@ pub async fn update_roadmap_progress(
     };
 
     match result {
-        Some(_) => Ok(Json(json!({
-            "success": true,
-            "message": "Roadmap progress updated successfully"
-        }))),
+        Some(_) => {
+            crate::roadmap_analytics::record(&state.db_pool, roadmap_id).await;
+            // Completed phases mark their skills known across all roadmaps
+            if phases_changed
+                && let Err(e) = crate::skills::sync_roadmap_progress(&state.db_pool, auth_user.user_id).await
+            {
+                tracing::warn!("Failed to sync skill progress for user {}: {}", auth_user.user_id, e);
+            }
+            for phase in new_phases {
+                crate::activity::record(
+                    &state.db_pool,
+                    auth_user.user_id,
+                    crate::activity::ActivityType::PhaseCompleted,
+                    json!({ "roadmap_id": roadmap_id, "roadmap_title": roadmap_title, "phase": phase }),
+                )
+                .await;
+            }
+            if all_phases_completed {
+                crate::credits::award_achievement(
+                    &state.db_pool,
+                    auth_user.user_id,
+                    crate::credits::Achievement::RoadmapCompleted(roadmap_id),
+                )
+                .await;
+            }
+            Ok(ApiResponse::ok(json!({ "message": "Roadmap progress updated successfully" })))
+        }
         None => Err(AppError::NotFound),
     }
 }
@@ -0,0 +1,60 @@
+//! API key handlers: creating, listing, and revoking the caller's keys.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use serde_json::{Value, json};
+use validator::Validate;
+
+use super::types::CreateApiKeyPayload;
+use crate::AppState;
+use crate::api_keys::{self, ApiKey, CreatedApiKey};
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+
+/// Creates an API key with the given scopes. The key is only returned
+/// here; store it safely.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, a scope is unknown, the caller is
+/// a sandbox test user or holds too many keys, or the database operation
+/// fails.
+pub async fn create_api_key(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateApiKeyPayload>,
+) -> AppResult<ApiResponse<CreatedApiKey>> {
+    payload.validate()?;
+    let key = api_keys::create(&app_state.db_pool, auth_user.user_id, &payload.name, &payload.scopes).await?;
+    Ok(ApiResponse::ok(key))
+}
+
+/// Lists the caller's API keys, newest first, without the keys themselves.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_api_keys(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ApiKey>>> {
+    Ok(ApiResponse::ok(api_keys::list(&app_state.db_pool, auth_user.user_id).await?))
+}
+
+/// Revokes one of the caller's API keys.
+///
+/// # Errors
+///
+/// Returns an error if the key is not the caller's or already revoked
+/// (404), or the database operation fails.
+pub async fn revoke_api_key(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(key_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    api_keys::revoke(&app_state.db_pool, auth_user.user_id, key_id).await?;
+    Ok(ApiResponse::ok(json!({ "message": "API key revoked" })))
+}
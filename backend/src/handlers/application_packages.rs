@@ -0,0 +1,152 @@
+//! Application package handlers: queue the multi-step application agent and
+//! follow its progress.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use tracing::info;
+use validator::Validate;
+
+use super::types::ApplicationPackagePayload;
+use crate::AppState;
+use crate::ai::types::AIProvider;
+use crate::application_packages::{self, ApplicationPackage, NewPackage};
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+
+/// Queues an application package for a job and starts it in the
+/// background. Poll `GET /api/ai/application-packages/{id}` for progress.
+///
+/// Pass `job_id` for a posting on the platform, or `job_description` (with
+/// an optional `job_title` and `company`) for any other job.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Validation fails, or neither `job_id` nor `job_description` is given
+/// - The job does not exist or is not open (404)
+/// - The AI service is not configured
+/// - Database operation fails
+pub async fn create_application_package(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<ApplicationPackagePayload>,
+) -> AppResult<ApiResponse<ApplicationPackage>> {
+    payload.validate()?;
+    if app_state.ai_service.is_none() {
+        return Err(AppError::ConfigurationError("AI service not configured".to_string()));
+    }
+
+    let package = match (payload.job_id, payload.job_description) {
+        (Some(job_id), _) => {
+            let (job_title, company, description, requirements) =
+                sqlx::query_as::<_, (String, String, String, Vec<String>)>(
+                    r#"
+                    SELECT job_title, company, job_description, requirements
+                    FROM jobs
+                    WHERE id = $1
+                      AND moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+                    "#,
+                )
+                .bind(job_id)
+                .fetch_optional(&app_state.db_pool)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            let job_description = if requirements.is_empty() {
+                description
+            } else {
+                format!("{}\n\nRequirements:\n- {}", description, requirements.join("\n- "))
+            };
+            NewPackage {
+                job_id: Some(job_id),
+                job_title,
+                company: Some(company),
+                job_description,
+                provider: provider(payload.provider.as_deref()),
+            }
+        }
+        (None, Some(job_description)) => NewPackage {
+            job_id: None,
+            job_title: payload
+                .job_title
+                .map(|t| t.trim().to_string())
+                .unwrap_or_else(|| "Untitled role".to_string()),
+            company: payload.company.map(|c| c.trim().to_string()),
+            job_description,
+            provider: provider(payload.provider.as_deref()),
+        },
+        (None, None) => {
+            return Err(AppError::ValidationError(
+                "Either job_id or job_description is required".to_string(),
+            ));
+        }
+    };
+
+    let package_id = application_packages::create(&app_state.db_pool, auth_user.user_id, package).await?;
+    application_packages::spawn_run(app_state.db_pool.clone(), app_state.ai_service.clone(), package_id);
+    info!("User {} queued application package {}", auth_user.user_id, package_id);
+
+    Ok(ApiResponse::ok(
+        application_packages::load(&app_state.db_pool, auth_user.user_id, package_id).await?,
+    ))
+}
+
+/// Lists the user's application packages with their progress, newest first.
+/// Step artifacts are only returned by the single-package endpoint.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_application_packages(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ApplicationPackage>>> {
+    Ok(ApiResponse::ok(
+        application_packages::list(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Returns an application package with each step's status and artifact.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such package, or an
+/// error if the database operation fails.
+pub async fn get_application_package(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(package_id): Path<i32>,
+) -> AppResult<ApiResponse<ApplicationPackage>> {
+    Ok(ApiResponse::ok(
+        application_packages::load(&app_state.db_pool, auth_user.user_id, package_id).await?,
+    ))
+}
+
+/// Retries a failed application package from the step that failed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The user has no such package (404)
+/// - The package has not failed (400)
+/// - Database operation fails
+pub async fn retry_application_package(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(package_id): Path<i32>,
+) -> AppResult<ApiResponse<ApplicationPackage>> {
+    application_packages::retry(&app_state.db_pool, auth_user.user_id, package_id).await?;
+    application_packages::spawn_run(app_state.db_pool.clone(), app_state.ai_service.clone(), package_id);
+    info!("User {} retried application package {}", auth_user.user_id, package_id);
+
+    Ok(ApiResponse::ok(
+        application_packages::load(&app_state.db_pool, auth_user.user_id, package_id).await?,
+    ))
+}
+
+fn provider(name: Option<&str>) -> AIProvider {
+    name.map_or(AIProvider::Gemini, AIProvider::from_name)
+}
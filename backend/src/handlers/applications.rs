@@ -1,31 +1,63 @@
 //! Job application tracking handlers.
 
-use axum::{extract::{State, Path}, Json};
+use axum::{extract::{State, Path}, http::header, response::{IntoResponse, Response}, Json};
 use tracing::{info, debug};
 use crate::models::ApplicationTracking;
-use crate::errors::AppResult;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
 use crate::auth::AuthUser;
 use crate::AppState;
-use super::types::{CreateApplicationPayload, UpdateApplicationPayload};
+use crate::cohorts;
+use crate::screening;
+use crate::activity::{self, ActivityType};
+use crate::events::{self, CalendarEntry};
+use crate::interview_prep::{self, ScheduledInterview};
+use super::types::{CreateApplicationPayload, ScheduleInterviewPayload, UpdateApplicationPayload};
 
 /// Creates a new job application record.
 /// 
-/// Tracks when a user applies to a job with optional notes.
+/// Tracks when a user applies to a job with optional notes and answers to
+/// the job's screening questions. Yes/no and number answers are scored
+/// right away; text answers are scored in the background.
 /// 
 /// # Errors
 /// 
 /// Returns an error if:
 /// - User is not authenticated
-/// - Job ID doesn't exist
+/// - Job ID doesn't exist, the job is not approved or has expired, or a block hides it
+/// - The user's profile is below their cohort's minimum completeness
+/// - A required screening question is unanswered or an answer is invalid
 /// - Database operation fails
 pub async fn create_application(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Json(payload): Json<CreateApplicationPayload>,
-) -> AppResult<Json<ApplicationTracking>> {
+) -> AppResult<ApiResponse<ApplicationTracking>> {
     info!("Creating application for user: {}, job_id: {}", 
           auth_user.user_id, payload.job_id);
-    
+
+    // Pending, rejected, and expired postings are not open for applications,
+    // nor are postings hidden by a block between the candidate and employer
+    let (job_title, company) = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT job_title, company FROM jobs
+        WHERE id = $1 AND moderation_status = 'approved' AND same_sandbox(sandbox_key_id, $2) AND (expires_at IS NULL OR expires_at > NOW())
+          AND NOT blocked_between($2, posted_by, company_id)
+        "#,
+    )
+    .bind(payload.job_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    // Cohorts may require a minimum profile completeness before applying
+    cohorts::ensure_may_apply(&app_state.db_pool, auth_user.user_id).await?;
+
+    let questions = screening::list(&app_state.db_pool, payload.job_id).await?;
+    screening::check_answers(&questions, &payload.screening_answers)?;
+
+    let mut tx = app_state.db_pool.begin().await?;
     let application = sqlx::query_as!(
         ApplicationTracking,
         r#"
@@ -37,13 +69,46 @@ pub async fn create_application(
         payload.job_id,
         payload.notes
     )
-    .fetch_one(&app_state.db_pool)
+    .fetch_one(&mut *tx)
     .await?;
+    let application_id = application.id.unwrap_or_default();
+    let text_answers =
+        screening::record_answers(&mut tx, application_id, &questions, &payload.screening_answers).await?;
+    tx.commit().await?;
+
+    if text_answers {
+        screening::spawn_evaluation(app_state.db_pool.clone(), app_state.ai_service.clone(), application_id);
+    }
+
+    // Career-fair leads for this job are converted once the candidate applies
+    sqlx::query(
+        r#"
+        UPDATE event_interactions SET lead_status = 'applied'
+        WHERE candidate_id = $1 AND job_id = $2 AND lead_status IN ('new', 'invited')
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.job_id)
+    .execute(&app_state.db_pool)
+    .await?;
+
+    activity::record(
+        &app_state.db_pool,
+        auth_user.user_id,
+        ActivityType::ApplicationSubmitted,
+        serde_json::json!({
+            "application_id": application.id,
+            "job_id": payload.job_id,
+            "job_title": job_title,
+            "company": company
+        }),
+    )
+    .await;
 
     info!("Application created successfully: application_id={}, user_id={}, job_id={}",
           application.id.unwrap_or(0), auth_user.user_id, payload.job_id);
     
-    Ok(Json(application))
+    Ok(ApiResponse::ok(application))
 }
 
 /// Retrieves all applications for the authenticated user.
@@ -58,7 +123,7 @@ pub async fn create_application(
 pub async fn get_my_applications(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
-) -> AppResult<Json<Vec<ApplicationTracking>>> {
+) -> AppResult<ApiResponse<Vec<ApplicationTracking>>> {
     info!("Fetching applications for user: {}", auth_user.user_id);
     
     let applications = sqlx::query_as!(
@@ -76,7 +141,7 @@ pub async fn get_my_applications(
 
     debug!("Retrieved {} applications for user: {}", applications.len(), auth_user.user_id);
     
-    Ok(Json(applications))
+    Ok(ApiResponse::ok(applications))
 }
 
 /// Updates an existing application.
@@ -99,7 +164,7 @@ pub async fn update_application(
     State(app_state): State<AppState>,
     Path(application_id): Path<i32>,
     Json(payload): Json<UpdateApplicationPayload>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Updating application: application_id={}, user_id={}, new_status={}",
           application_id, auth_user.user_id, payload.status);
     
@@ -119,7 +184,79 @@ pub async fn update_application(
 
     info!("Application updated successfully: application_id={}", application_id);
     
-    Ok(Json(serde_json::json!({
+    Ok(ApiResponse::ok(serde_json::json!({
         "message": "Application updated successfully"
     })))
 }
+
+/// Schedules (or reschedules) the interview of one of the caller's
+/// applications and moves it to the `interview` stage. The interview gets a
+/// meeting link unless the company's own is given, and an interview prep
+/// pack is delivered 48 hours before it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The interview time is in the past
+/// - The meeting link is not an http(s) URL
+/// - Application doesn't exist or doesn't belong to user
+/// - Database operation fails
+pub async fn schedule_interview(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+    Json(payload): Json<ScheduleInterviewPayload>,
+) -> AppResult<ApiResponse<ScheduledInterview>> {
+    let meeting_url = payload.meeting_url.as_deref().map(str::trim).filter(|u| !u.is_empty());
+    if let Some(url) = meeting_url
+        && !url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+    {
+        return Err(AppError::ValidationError("meeting_url must be an http(s) URL".to_string()));
+    }
+    let scheduled = interview_prep::schedule(
+        &app_state.db_pool,
+        &app_state.meetings,
+        auth_user.user_id,
+        application_id,
+        payload.interview_at,
+        meeting_url,
+    )
+    .await?;
+    Ok(ApiResponse::ok(scheduled))
+}
+
+/// Downloads a calendar invite for the scheduled interview of one of the
+/// caller's applications, with its meeting link.
+///
+/// # Errors
+///
+/// Returns an error if the application is not the caller's or has no
+/// interview scheduled (404), or the database operation fails.
+pub async fn get_interview_invite(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+) -> AppResult<Response> {
+    let interview = interview_prep::scheduled(&app_state.db_pool, auth_user.user_id, application_id).await?;
+    let entry = CalendarEntry {
+        uid: format!("interview-{}@careerbridge", interview.application_id),
+        summary: format!("Interview: {} at {}", interview.job_title, interview.company),
+        description: None,
+        location: interview.meeting_url.clone(),
+        url: interview.meeting_url,
+        starts_at: interview.interview_at,
+        ends_at: interview.interview_at + chrono::Duration::minutes(interview_prep::INTERVIEW_MINUTES),
+    };
+    let ics = events::to_ics("Interview", &[entry]);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"interview-{}.ics\"", application_id),
+            ),
+        ],
+        ics,
+    )
+        .into_response())
+}
@@ -3,8 +3,9 @@
 use axum::{extract::State, Json};
 use validator::Validate;
 use tracing::{info, warn, error, debug};
-use crate::models::{User, ExperienceLevel, CareerTrack};
+use crate::models::{User, ExperienceLevel, CareerTrack, UserRole};
 use crate::errors::{AppResult, AppError};
+use crate::response::ApiResponse;
 use crate::security::{hash_password, verify_password};
 use crate::auth::create_jwt;
 use crate::AppState;
@@ -26,7 +27,7 @@ use super::types::{RegisterPayload, LoginPayload, LoginResponse, UserProfile};
 pub async fn register(
     State(app_state): State<AppState>,
     Json(payload): Json<RegisterPayload>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Received registration request for email: {}", payload.email);
     
     payload.validate().map_err(|e| {
@@ -37,16 +38,23 @@ pub async fn register(
     debug!("Hashing password for user: {}", payload.email);
     let hashed_password = hash_password(payload.password).await?;
     
-    let user_id = sqlx::query_scalar!(
+    let role = payload.role.unwrap_or(UserRole::JobSeeker);
+    if role == UserRole::Admin {
+        warn!("Registration rejected: admin role requested by {}", payload.email);
+        return Err(AppError::Forbidden);
+    }
+
+    let user_id = sqlx::query_scalar::<_, uuid::Uuid>(
         r#"
-        INSERT INTO users (full_name, email, password_hash)
-        VALUES ($1, $2, $3)
+        INSERT INTO users (full_name, email, password_hash, role)
+        VALUES ($1, $2, $3, $4)
         RETURNING id
         "#,
-        payload.full_name,
-        payload.email,
-        hashed_password
     )
+    .bind(&payload.full_name)
+    .bind(&payload.email)
+    .bind(&hashed_password)
+    .bind(role)
     .fetch_one(&app_state.db_pool)
     .await
     .map_err(|e| {
@@ -62,6 +70,10 @@ pub async fn register(
     })?;
 
     info!("User created successfully: user_id={}, email={}", user_id, payload.email);
+
+    if let Some(code) = payload.referral_code.as_deref() {
+        crate::credits::redeem_referral(&app_state.db_pool, user_id, code).await;
+    }
     
     // Generate JWT token for immediate login
     debug!("Generating JWT token for user: {}", user_id);
@@ -69,10 +81,11 @@ pub async fn register(
     
     info!("Registration successful for user: {}", user_id);
 
-    Ok(Json(serde_json::json!({
+    Ok(ApiResponse::ok(serde_json::json!({
         "message": "User registered successfully",
         "token": token,
-        "user_id": user_id
+        "user_id": user_id,
+        "role": role
     })))
 }
 
@@ -92,7 +105,7 @@ pub async fn register(
 pub async fn login(
     State(app_state): State<AppState>,
     Json(payload): Json<LoginPayload>,
-) -> AppResult<Json<LoginResponse>> {
+) -> AppResult<ApiResponse<LoginResponse>> {
     info!("Login attempt received for: {}", payload.email);
     
     payload.validate().map_err(|e| {
@@ -141,7 +154,7 @@ pub async fn login(
     
     info!("Login successful for user: user_id={}, email={}", user.id, user.email);
 
-    Ok(Json(LoginResponse {
+    Ok(ApiResponse::ok(LoginResponse {
         token,
         user: UserProfile {
             id: user.id,
@@ -154,6 +167,8 @@ pub async fn login(
             skills: user.skills,
             projects: user.projects,
             target_roles: user.target_roles,
+            availability: None,
+            code_practice: None,
         },
     }))
 }
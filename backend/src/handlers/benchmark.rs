@@ -0,0 +1,27 @@
+//! Anonymous peer benchmark handlers.
+
+use axum::extract::{Query, State};
+
+use super::types::BenchmarkParams;
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::benchmarking::{self, Benchmark};
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+
+/// Compares the caller's roadmap progress, skill count, and recent job
+/// applications with anonymized percentiles of peers targeting the same
+/// role. Figures are suppressed when the peer group is too small.
+///
+/// # Errors
+///
+/// Returns an error if the user has no target role or roadmap, `role` is
+/// not one of theirs, or a database query fails.
+pub async fn get_my_benchmark(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<BenchmarkParams>,
+) -> AppResult<ApiResponse<Benchmark>> {
+    let benchmark = benchmarking::benchmark(&app_state.db_pool, auth_user.user_id, params.role.as_deref()).await?;
+    Ok(ApiResponse::ok(benchmark))
+}
@@ -0,0 +1,100 @@
+//! Billing plan, subscription, and Stripe Checkout handlers.
+
+use axum::{Json, extract::State};
+use tracing::info;
+
+use super::types::{CheckoutPayload, SubscriptionSummary};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::billing::{self, Plan};
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+
+/// Lists available billing plans.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn list_plans(
+    _auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<Plan>>> {
+    Ok(ApiResponse::ok(billing::list_plans(&app_state.db_pool).await?))
+}
+
+/// Returns the caller's current plan, subscription status, and AI usage today.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or a database query fails.
+pub async fn get_subscription(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<SubscriptionSummary>> {
+    let plan = billing::current_plan(&app_state.db_pool, auth_user.user_id).await?;
+    let limit = app_state.rate_limits.resolve(&app_state.db_pool, auth_user.user_id).await?;
+    let used = billing::ai_usage_today(&app_state.db_pool, auth_user.user_id).await?;
+
+    let (status, current_period_end) = sqlx::query_as::<_, (String, Option<chrono::DateTime<chrono::Utc>>)>(
+        "SELECT status, current_period_end FROM subscriptions WHERE user_id = $1",
+    )
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .map(|(status, end)| (Some(status), end))
+    .unwrap_or((None, None));
+
+    Ok(ApiResponse::ok(SubscriptionSummary {
+        ai_requests_today: used,
+        ai_requests_remaining: (limit.ai_daily_quota - used).max(0),
+        ai_daily_quota: limit.ai_daily_quota,
+        rate_limit_policy_id: limit.policy_id,
+        plan,
+        status,
+        current_period_end,
+    }))
+}
+
+/// Starts a Stripe Checkout session for upgrading to a paid plan.
+///
+/// Returns the hosted checkout URL to redirect the user to. The subscription
+/// is activated once Stripe confirms payment via `/api/hooks/stripe`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Stripe is not configured
+/// - The plan does not exist or cannot be purchased
+/// - The Stripe API call fails
+pub async fn create_checkout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CheckoutPayload>,
+) -> AppResult<ApiResponse<billing::CheckoutSession>> {
+    let stripe = app_state
+        .stripe
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("Billing not configured".to_string()))?;
+
+    let plan = billing::list_plans(&app_state.db_pool)
+        .await?
+        .into_iter()
+        .find(|p| p.id == payload.plan_id)
+        .ok_or(AppError::NotFound)?;
+
+    let frontend_url = std::env::var("FRONTEND_URL")
+        .unwrap_or_else(|_| "http://localhost:3001".to_string());
+
+    let session = stripe
+        .create_checkout_session(
+            auth_user.user_id,
+            &auth_user.email,
+            &plan,
+            &format!("{}/billing/success?session_id={{CHECKOUT_SESSION_ID}}", frontend_url),
+            &format!("{}/billing/cancel", frontend_url),
+        )
+        .await?;
+
+    info!("Checkout session {} created for user {} (plan {})", session.id, auth_user.user_id, plan.id);
+    Ok(ApiResponse::ok(session))
+}
@@ -0,0 +1,302 @@
+//! Certification handlers: the profile's certifications list, added by hand
+//! or read from a photo or screenshot of a certificate, and verified with
+//! the issuer in the background.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+};
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{CertificateScan, Certification, CertificationPayload};
+use crate::AppState;
+use crate::ai::types::ExtractedCertificate;
+use crate::auth::AuthUser;
+use crate::certifications;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+
+/// Image types Gemini can read, by file extension.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("webp", "image/webp"),
+    ("heic", "image/heic"),
+];
+
+const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+/// Columns of [`Certification`].
+const CERTIFICATION_COLUMNS: &str = r#"
+    id, name, issuer, issue_date, expiry_date, credential_id, credential_url, source,
+    verification_status, verification_error, verified_at, created_at
+"#;
+
+/// Content type of an image file, if it is one Gemini can read.
+pub(super) fn image_content_type(file_name: &str) -> Option<&'static str> {
+    let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase())?;
+    IMAGE_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, content_type)| *content_type)
+}
+
+/// Adds certificates read from an image to the user's profile, skipping
+/// any already listed (same credential ID, or same name and issuer), and
+/// starts verifying them. Returns the ones added.
+pub(super) async fn save_extracted(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    certificates: &[ExtractedCertificate],
+) -> AppResult<Vec<Certification>> {
+    let mut saved = Vec::new();
+    for certificate in certificates {
+        let Some(name) = clean(Some(&certificate.name)) else {
+            continue;
+        };
+        let credential_url = clean(certificate.credential_url.as_ref())
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+
+        let inserted = sqlx::query_as::<_, Certification>(&format!(
+            r#"
+            INSERT INTO user_certifications
+                (user_id, name, issuer, issue_date, expiry_date, credential_id, credential_url, source)
+            SELECT $1, $2, $3, $4, $5, $6, $7, 'image'
+            WHERE NOT EXISTS (
+                SELECT 1 FROM user_certifications
+                WHERE user_id = $1
+                  AND (credential_id = $6 OR (LOWER(name) = LOWER($2) AND LOWER(issuer) IS NOT DISTINCT FROM LOWER($3)))
+            )
+            RETURNING {}
+            "#,
+            CERTIFICATION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&name)
+        .bind(clean(certificate.issuer.as_ref()))
+        .bind(parse_date(certificate.issue_date.as_deref()))
+        .bind(parse_date(certificate.expiry_date.as_deref()))
+        .bind(clean(certificate.credential_id.as_ref()))
+        .bind(credential_url)
+        .fetch_optional(db_pool)
+        .await?;
+        if let Some(certification) = inserted {
+            certifications::spawn_verify(db_pool.clone(), certification.id);
+            saved.push(certification);
+        }
+    }
+    Ok(saved)
+}
+
+/// A trimmed, non-empty value of at most 255 characters.
+fn clean(value: Option<&String>) -> Option<String> {
+    value
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("null"))
+        .map(|v| v.chars().take(255).collect())
+}
+
+/// A `YYYY-MM-DD` date; anything else reads as unknown.
+fn parse_date(value: Option<&str>) -> Option<NaiveDate> {
+    value.and_then(|v| NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok())
+}
+
+/// Lists the user's certifications, most recently issued first.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_certifications(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<Certification>>> {
+    let certifications = sqlx::query_as::<_, Certification>(&format!(
+        "SELECT {} FROM user_certifications WHERE user_id = $1 ORDER BY issue_date DESC NULLS LAST, id DESC",
+        CERTIFICATION_COLUMNS
+    ))
+    .bind(auth_user.user_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(certifications))
+}
+
+/// Adds a certification by hand and starts verifying it with the issuer.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Validation fails, or the expiry date is before the issue date
+/// - Database operation fails
+pub async fn create_certification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CertificationPayload>,
+) -> AppResult<ApiResponse<Certification>> {
+    payload.validate()?;
+    if let (Some(issued), Some(expires)) = (payload.issue_date, payload.expiry_date)
+        && expires < issued
+    {
+        return Err(AppError::ValidationError(
+            "Expiry date cannot be before the issue date".to_string(),
+        ));
+    }
+
+    let certification = sqlx::query_as::<_, Certification>(&format!(
+        r#"
+        INSERT INTO user_certifications
+            (user_id, name, issuer, issue_date, expiry_date, credential_id, credential_url)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}
+        "#,
+        CERTIFICATION_COLUMNS
+    ))
+    .bind(auth_user.user_id)
+    .bind(payload.name.trim())
+    .bind(clean(payload.issuer.as_ref()))
+    .bind(payload.issue_date)
+    .bind(payload.expiry_date)
+    .bind(clean(payload.credential_id.as_ref()))
+    .bind(payload.credential_url.as_deref().map(str::trim))
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    certifications::spawn_verify(app_state.db_pool.clone(), certification.id);
+    info!("User {} added certification {}", auth_user.user_id, certification.id);
+    Ok(ApiResponse::ok(certification))
+}
+
+/// Checks a certification with its issuer again, e.g. after a failed check
+/// during an issuer outage. The check runs in the background.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such certification, or
+/// an error if the database operation fails.
+pub async fn verify_certification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(certification_id): Path<i32>,
+) -> AppResult<ApiResponse<Certification>> {
+    let certification = sqlx::query_as::<_, Certification>(&format!(
+        r#"
+        UPDATE user_certifications
+        SET verification_status = 'pending', verification_error = NULL, verification_attempts = 0
+        WHERE id = $1 AND user_id = $2
+        RETURNING {}
+        "#,
+        CERTIFICATION_COLUMNS
+    ))
+    .bind(certification_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    certifications::spawn_verify(app_state.db_pool.clone(), certification.id);
+    Ok(ApiResponse::ok(certification))
+}
+
+/// Removes a certification from the user's profile.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such certification, or
+/// an error if the database operation fails.
+pub async fn delete_certification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(certification_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let deleted = sqlx::query("DELETE FROM user_certifications WHERE id = $1 AND user_id = $2")
+        .bind(certification_id)
+        .bind(auth_user.user_id)
+        .execute(&app_state.db_pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(ApiResponse::ok(json!({ "message": "Certification deleted" })))
+}
+
+/// Reads a photo or screenshot of a certificate (PNG, JPEG, WebP, or HEIC,
+/// up to 10MB) with Gemini's vision model and adds the certificates it
+/// shows to the user's profile. Charged as an AI action.
+///
+/// Expects a multipart form with the image in the `image` field.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No image is provided, or it has an unsupported type or is too large
+/// - The AI service is not configured, credits run out, or reading fails
+/// - Database operation fails
+pub async fn scan_certificate(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<CertificateScan>> {
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        if field.name() != Some("image") {
+            continue;
+        }
+
+        let file_name = field
+            .file_name()
+            .and_then(|name| name.rsplit(['/', '\\']).next())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?
+            .to_string();
+        let content_type = image_content_type(&file_name).ok_or_else(|| {
+            AppError::BadRequest("Only PNG, JPEG, WebP, and HEIC images are supported".to_string())
+        })?;
+
+        let data = field.bytes().await.map_err(|e| {
+            error!("Failed to read image data: {}", e);
+            AppError::BadRequest("Failed to read file data".to_string())
+        })?;
+        if data.len() > MAX_IMAGE_SIZE {
+            warn!("Certificate image too large ({} bytes) for user {}", data.len(), auth_user.user_id);
+            return Err(AppError::BadRequest(
+                "File size exceeds 10MB limit".to_string(),
+            ));
+        }
+
+        let reading = ai_service
+            .read_image(&data, content_type, &file_name, Some(auth_user.user_id))
+            .await?;
+        let added = save_extracted(&app_state.db_pool, auth_user.user_id, &reading.certificates).await?;
+
+        info!(
+            "User {} scanned a certificate image: {} found, {} added",
+            auth_user.user_id,
+            reading.certificates.len(),
+            added.len()
+        );
+        return Ok(ApiResponse::ok(CertificateScan {
+            document_type: reading.document_type,
+            text: reading.text,
+            found: reading.certificates.len(),
+            added,
+        }));
+    }
+
+    Err(AppError::BadRequest("No image provided".to_string()))
+}
@@ -0,0 +1,115 @@
+//! Code practice handlers: linking a coding practice handle, or uploading a
+//! progress export, to show solved problems on the profile.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+};
+use serde_json::{Value, json};
+use tracing::{error, warn};
+use validator::Validate;
+
+use super::types::LinkCodePracticePayload;
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::code_practice::{self, PracticeAccount};
+use crate::errors::{AppError, AppResult};
+use crate::integrations::leetcode::LeetCodeClient;
+use crate::models::CodePracticePlatform;
+use crate::response::ApiResponse;
+
+/// Maximum progress export size.
+const MAX_EXPORT_SIZE: usize = 2 * 1024 * 1024;
+
+/// Lists the caller's practice accounts with solved problems by difficulty
+/// and topic.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_code_practice(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PracticeAccount>>> {
+    let accounts = code_practice::list(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(accounts))
+}
+
+/// Links the caller's handle on a platform and imports its progress, which
+/// is then re-synced daily.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the platform has no such
+/// user, or the database operation fails.
+pub async fn link_code_practice(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(platform): Path<CodePracticePlatform>,
+    Json(payload): Json<LinkCodePracticePayload>,
+) -> AppResult<ApiResponse<PracticeAccount>> {
+    payload.validate()?;
+    let account = code_practice::link(
+        &app_state.db_pool,
+        &LeetCodeClient::new(),
+        auth_user.user_id,
+        platform,
+        &payload.handle,
+    )
+    .await?;
+    Ok(ApiResponse::ok(account))
+}
+
+/// Imports progress from an uploaded CSV export (up to 2MB) in the `export`
+/// field of a multipart form.
+///
+/// # Errors
+///
+/// Returns an error if no export is provided, it is too large or cannot be
+/// read, or the database operation fails.
+pub async fn import_code_practice(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(platform): Path<CodePracticePlatform>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<PracticeAccount>> {
+    let mut export = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        if field.name() != Some("export") {
+            continue;
+        }
+        let data = field.bytes().await.map_err(|e| {
+            error!("Failed to read export data: {}", e);
+            AppError::BadRequest("Failed to read file data".to_string())
+        })?;
+        if data.len() > MAX_EXPORT_SIZE {
+            warn!("Practice export too large ({} bytes) for user {}", data.len(), auth_user.user_id);
+            return Err(AppError::BadRequest("File size exceeds 2MB limit".to_string()));
+        }
+        let text = String::from_utf8(data.to_vec())
+            .map_err(|_| AppError::BadRequest("The export must be a UTF-8 CSV file".to_string()))?;
+        export = Some(text);
+    }
+
+    let export = export.ok_or_else(|| AppError::BadRequest("No export provided".to_string()))?;
+    let account = code_practice::import(&app_state.db_pool, auth_user.user_id, platform, &export).await?;
+    Ok(ApiResponse::ok(account))
+}
+
+/// Unlinks the caller's account on a platform and removes its progress.
+///
+/// # Errors
+///
+/// Returns an error if there is no such account (404) or the database
+/// operation fails.
+pub async fn unlink_code_practice(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(platform): Path<CodePracticePlatform>,
+) -> AppResult<ApiResponse<Value>> {
+    code_practice::unlink(&app_state.db_pool, auth_user.user_id, platform).await?;
+    Ok(ApiResponse::ok(json!({ "unlinked": platform })))
+}
@@ -0,0 +1,112 @@
+//! Company profile, employer branding, and public company page handlers.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use tracing::info;
+use validator::Validate;
+
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::branding::{self, BrandingContent, CompanyBranding, CompanyPage, CompanyProfile, CompanyProfileUpdate};
+use crate::errors::AppResult;
+use crate::models::SeatRole;
+use crate::response::ApiResponse;
+use crate::teams::{self, PIPELINE_ROLES};
+
+/// Returns a company's public page: its profile, published branding, open
+/// jobs, and candidates' reviews. No sign-in required.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the company does not exist, or an error
+/// if a database query fails.
+pub async fn get_company_page(
+    State(app_state): State<AppState>,
+    Path(company_id): Path<i32>,
+) -> AppResult<ApiResponse<CompanyPage>> {
+    Ok(ApiResponse::ok(branding::page(&app_state.db_pool, company_id).await?))
+}
+
+/// Updates the profile of the caller's company (owners only). Omitted
+/// fields are left unchanged.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - Validation fails
+/// - Database operation fails
+pub async fn update_company_profile(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CompanyProfileUpdate>,
+) -> AppResult<ApiResponse<CompanyProfile>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    payload.validate()?;
+
+    let profile = branding::update_profile(&app_state.db_pool, seat.company_id, &payload).await?;
+    info!("Company {} profile updated by {}", seat.company_id, auth_user.user_id);
+    Ok(ApiResponse::ok(profile))
+}
+
+/// Returns the caller's company branding: the draft and the published
+/// version.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if a
+/// database query fails.
+pub async fn get_branding(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<CompanyBranding>> {
+    let seat = teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await?;
+    Ok(ApiResponse::ok(branding::load(&app_state.db_pool, seat.company_id).await?))
+}
+
+/// Replaces the branding draft with edited content (owners and
+/// recruiters). The public page is unchanged until the draft is published.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - Validation fails
+/// - Database operation fails
+pub async fn save_branding_draft(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<BrandingContent>,
+) -> AppResult<ApiResponse<CompanyBranding>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let branding = branding::save_draft(&app_state.db_pool, auth_user.user_id, seat.company_id, &payload).await?;
+    Ok(ApiResponse::ok(branding))
+}
+
+/// Publishes the branding draft to the public company page (owners only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - There is no draft to publish
+/// - Database operation fails
+pub async fn publish_branding(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<CompanyBranding>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+
+    let branding = branding::publish(&app_state.db_pool, auth_user.user_id, seat.company_id).await?;
+    info!("Company {} branding published by {}", seat.company_id, auth_user.user_id);
+    Ok(ApiResponse::ok(branding))
+}
@@ -0,0 +1,170 @@
+//! Company review handlers: candidates reviewing a company's hiring
+//! process, the public review list, and the company's replies.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use validator::Validate;
+
+use super::types::{CompanyReviewParams, CompanyReviewPayload, ReviewReplyPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::company_reviews::{self, CompanyReview, CompanyReviews, ReviewDraft};
+use crate::errors::AppResult;
+use crate::models::SeatRole;
+use crate::response::ApiResponse;
+use crate::teams;
+
+fn draft(payload: &CompanyReviewPayload) -> ReviewDraft<'_> {
+    ReviewDraft {
+        overall_rating: payload.overall_rating,
+        response_time_rating: payload.response_time_rating,
+        transparency_rating: payload.transparency_rating,
+        response_days: payload.response_days,
+        headline: &payload.headline,
+        body: &payload.body,
+    }
+}
+
+/// Reviews the company behind one of the caller's applications. The
+/// application must have reached at least the interview stage.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Validation fails
+/// - The application is not the caller's (404)
+/// - The application did not reach the interview stage or was already reviewed
+/// - Database operation fails
+pub async fn create_review(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+    Json(payload): Json<CompanyReviewPayload>,
+) -> AppResult<ApiResponse<CompanyReview>> {
+    payload.validate()?;
+    let review =
+        company_reviews::create(&app_state.db_pool, auth_user.user_id, application_id, &draft(&payload)).await?;
+    Ok(ApiResponse::ok(review))
+}
+
+/// Lists the caller's reviews, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_my_reviews(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<CompanyReview>>> {
+    Ok(ApiResponse::ok(
+        company_reviews::list_for_user(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Rewrites one of the caller's reviews.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the review is not the caller's or
+/// was removed (404), or the database operation fails.
+pub async fn update_review(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(review_id): Path<i32>,
+    Json(payload): Json<CompanyReviewPayload>,
+) -> AppResult<ApiResponse<CompanyReview>> {
+    payload.validate()?;
+    let review =
+        company_reviews::update(&app_state.db_pool, auth_user.user_id, review_id, &draft(&payload)).await?;
+    Ok(ApiResponse::ok(review))
+}
+
+/// Deletes one of the caller's reviews.
+///
+/// # Errors
+///
+/// Returns an error if the review is not the caller's (404) or the database
+/// operation fails.
+pub async fn delete_review(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(review_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    company_reviews::delete(&app_state.db_pool, auth_user.user_id, review_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": review_id })))
+}
+
+/// Returns a company's published reviews, newest first, with the summary of
+/// their ratings. Reviewers are not identified. No sign-in required.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_company_reviews(
+    State(app_state): State<AppState>,
+    Path(company_id): Path<i32>,
+    Query(params): Query<CompanyReviewParams>,
+) -> AppResult<ApiResponse<CompanyReviews>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    Ok(ApiResponse::ok(CompanyReviews {
+        summary: company_reviews::summary(&app_state.db_pool, company_id).await?,
+        reviews: company_reviews::list_for_company(&app_state.db_pool, company_id, false, limit, offset).await?,
+    }))
+}
+
+/// Returns the reviews of the caller's company, including ones hidden while
+/// reports on them are open.
+///
+/// # Errors
+///
+/// Returns an error if the caller has no seat or the database operation
+/// fails.
+pub async fn list_employer_reviews(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<CompanyReviewParams>,
+) -> AppResult<ApiResponse<CompanyReviews>> {
+    let seat = teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await?;
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    Ok(ApiResponse::ok(CompanyReviews {
+        summary: company_reviews::summary(&app_state.db_pool, seat.company_id).await?,
+        reviews: company_reviews::list_for_company(&app_state.db_pool, seat.company_id, true, limit, offset)
+            .await?,
+    }))
+}
+
+/// Publishes or replaces the company's reply to a review (owners only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a team owner, validation fails,
+/// the review is not of the caller's company or was removed (404), or the
+/// database operation fails.
+pub async fn reply_to_review(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(review_id): Path<i32>,
+    Json(payload): Json<ReviewReplyPayload>,
+) -> AppResult<ApiResponse<CompanyReview>> {
+    payload.validate()?;
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    let review = company_reviews::reply(
+        &app_state.db_pool,
+        seat.company_id,
+        auth_user.user_id,
+        review_id,
+        &payload.reply,
+    )
+    .await?;
+    Ok(ApiResponse::ok(review))
+}
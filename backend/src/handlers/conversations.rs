@@ -0,0 +1,287 @@
+//! Mentor conversation handlers: creating and continuing conversations,
+//! export, and AI summaries.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Value, json};
+use tracing::info;
+
+use validator::Validate;
+
+use super::ai::{answer_glossary, ground_in_documents, mentor_context, mentor_history};
+use super::types::{ConversationExportParams, ConversationMessagePayload, CreateConversationPayload};
+use crate::AppState;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::auth::AuthUser;
+use crate::conversations::{self, Conversation, ConversationListing, ConversationSummary};
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+
+/// Creates an empty mentor conversation, to be continued through
+/// `POST /api/ai/conversations/{id}/messages`.
+///
+/// # Errors
+///
+/// Returns an error if validation or the database operation fails.
+pub async fn create_conversation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    payload: Option<Json<CreateConversationPayload>>,
+) -> AppResult<ApiResponse<Conversation>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+    let conversation =
+        conversations::create_empty(&app_state.db_pool, auth_user.user_id, payload.title.as_deref()).await?;
+    info!("User {} created conversation {}", auth_user.user_id, conversation.id);
+    Ok(ApiResponse::ok(conversation))
+}
+
+/// Lists the user's mentor conversations, most recently active first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_conversations(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ConversationListing>>> {
+    Ok(ApiResponse::ok(conversations::list(&app_state.db_pool, auth_user.user_id).await?))
+}
+
+/// Returns a mentor conversation with its messages, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the conversation does not exist or is not the
+/// user's (404), or the database operation fails.
+pub async fn get_conversation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(conversation_id): Path<i32>,
+) -> AppResult<ApiResponse<Conversation>> {
+    Ok(ApiResponse::ok(
+        conversations::load(&app_state.db_pool, auth_user.user_id, conversation_id).await?,
+    ))
+}
+
+/// Deletes a mentor conversation with its messages.
+///
+/// # Errors
+///
+/// Returns an error if the conversation does not exist or is not the
+/// user's (404), or the database operation fails.
+pub async fn delete_conversation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(conversation_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    conversations::delete(&app_state.db_pool, auth_user.user_id, conversation_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": true })))
+}
+
+/// Sends the mentor the next message of a conversation. The mentor answers
+/// with the conversation's history as chat context, within the model's
+/// context budget, and both messages are appended to it.
+///
+/// The answer is grounded in the user's profile and documents as in
+/// `POST /api/ai/ask-mentor`; pass `use_documents: false` to skip the
+/// documents.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Validation fails
+/// - The conversation does not exist or is not the user's (404)
+/// - AI service is not configured or the AI call fails
+/// - Database operation fails
+pub async fn send_message(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(conversation_id): Path<i32>,
+    Json(payload): Json<ConversationMessagePayload>,
+) -> AppResult<ApiResponse<Value>> {
+    payload.validate()?;
+    let message = payload.message.trim();
+    let provider = payload.provider.unwrap_or(AIProvider::Gemini);
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+    let (mut context, persona) = mentor_context(&app_state, auth_user.user_id).await?;
+    let sources = if payload.use_documents.unwrap_or(true) {
+        ground_in_documents(&app_state, ai_service, auth_user.user_id, message, &mut context).await
+    } else {
+        Vec::new()
+    };
+    let history = mentor_history(
+        &app_state,
+        ai_service,
+        auth_user.user_id,
+        Some(conversation_id),
+        &provider,
+        message,
+        &context,
+    )
+    .await?;
+
+    let request = AIActionRequest {
+        user_id: Some(auth_user.user_id),
+        action: ActionType::AskQuestion,
+        provider,
+        input: message.to_string(),
+        parameters: Some(json!({
+            "context": context,
+            "history": history,
+            "persona": persona,
+            "reading_level": payload.reading_level,
+            "tone": payload.tone
+        })),
+        capture: true,
+    };
+    let response = ai_service.process_action(request).await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "The mentor could not answer".to_string()),
+        ));
+    }
+
+    let answer = response
+        .data
+        .get("answer")
+        .and_then(|a| a.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| response.data.to_string());
+    conversations::append_exchange(&app_state.db_pool, conversation_id, message, &answer, response.data.get("sections"))
+        .await?;
+
+    let glossary = answer_glossary(&app_state, &response.data).await;
+    Ok(ApiResponse::ok(json!({
+        "conversation_id": conversation_id,
+        "answer": response.data,
+        "sources": sources,
+        "glossary": glossary
+    }))
+    .meta("provider", response.provider)
+    .meta("warnings", response.warnings))
+}
+
+/// Exports a mentor conversation, with its summary if one was made.
+///
+/// Markdown (the default) is served as a download; pass `format=json` for
+/// the conversation and messages as JSON.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - The conversation does not exist or is not the user's (404)
+/// - The format is unknown
+/// - Database operation fails
+pub async fn export_conversation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(conversation_id): Path<i32>,
+    Query(params): Query<ConversationExportParams>,
+) -> AppResult<Response> {
+    let markdown = match params.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("markdown") | Some("md") => true,
+        Some("json") => false,
+        Some(other) => {
+            return Err(AppError::ValidationError(format!(
+                "Unknown format '{}'; use markdown or json",
+                other
+            )));
+        }
+    };
+
+    let conversation =
+        conversations::load(&app_state.db_pool, auth_user.user_id, conversation_id).await?;
+    if !markdown {
+        return Ok(ApiResponse::ok(conversation).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/markdown; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"conversation-{}.md\"", conversation.id),
+            ),
+        ],
+        conversation.to_markdown(),
+    )
+        .into_response())
+}
+
+/// Summarizes the advice received in a mentor conversation as bullet
+/// points and stores the summary on the conversation.
+///
+/// If the stored summary already covers every message it is returned
+/// without another AI call.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - The conversation does not exist or is not the user's (404)
+/// - The conversation has no messages
+/// - AI service is not configured or the AI call fails
+/// - Database operation fails
+pub async fn summarize_conversation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(conversation_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let mut conversation =
+        conversations::load(&app_state.db_pool, auth_user.user_id, conversation_id).await?;
+    if conversation.messages.is_empty() {
+        return Err(AppError::ValidationError(
+            "Conversation has no messages to summarize".to_string(),
+        ));
+    }
+
+    if conversation.summary.is_none() || conversation.summary_stale {
+        let ai_service = app_state
+            .ai_service
+            .as_ref()
+            .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+
+        let request = AIActionRequest {
+            action: ActionType::SummarizeConversation,
+            provider: AIProvider::Gemini,
+            input: conversation.transcript(),
+            parameters: None,
+            user_id: Some(auth_user.user_id),
+            capture: true,
+        };
+        let response = ai_service.process_action(request).await?;
+        if !response.success {
+            return Err(AppError::ExternalServiceError(
+                response
+                    .message
+                    .unwrap_or_else(|| "Conversation summary failed".to_string()),
+            ));
+        }
+
+        let summary = ConversationSummary::from_response(&response.data)?;
+        conversations::save_summary(&app_state.db_pool, &mut conversation, summary).await?;
+        info!(
+            "User {} summarized conversation {} ({} messages)",
+            auth_user.user_id,
+            conversation.id,
+            conversation.messages.len()
+        );
+    }
+
+    Ok(ApiResponse::ok(json!({
+        "conversation_id": conversation.id,
+        "summary": conversation.summary,
+        "summary_message_count": conversation.summary_message_count,
+        "summarized_at": conversation.summarized_at
+    })))
+}
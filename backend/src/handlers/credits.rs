@@ -0,0 +1,30 @@
+//! Credits balance and history handlers.
+
+use axum::{
+    extract::{Query, State},
+};
+
+use super::types::CreditHistoryParams;
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::credits::{self, CreditSummary};
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+
+/// Default number of transactions returned.
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+/// Returns the caller's credit balance, referral code, and recent transactions.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or a database query fails.
+pub async fn get_my_credits(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<CreditHistoryParams>,
+) -> AppResult<ApiResponse<CreditSummary>> {
+    let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, 500);
+    let summary = credits::summary(&app_state.db_pool, auth_user.user_id, limit).await?;
+    Ok(ApiResponse::ok(summary))
+}
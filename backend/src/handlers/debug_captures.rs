@@ -0,0 +1,144 @@
+//! Debug capture handlers: consent, flagging bad generations, and admin
+//! inspection and replay.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use validator::Validate;
+
+use super::types::{DebugCaptureConsentPayload, DebugCaptureQueueParams, FlagCapturePayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::debug_captures::{self, CaptureConsent, CaptureListing, DebugCapture};
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+use crate::response::ApiResponse;
+
+/// Returns whether the caller consented to debug captures.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn get_capture_consent(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<CaptureConsent>> {
+    Ok(ApiResponse::ok(debug_captures::consent(&app_state.db_pool, auth_user.user_id).await?))
+}
+
+/// Gives or withdraws consent to debug captures of the caller's AI
+/// requests. Withdrawing deletes the captures already made.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn set_capture_consent(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<DebugCaptureConsentPayload>,
+) -> AppResult<ApiResponse<CaptureConsent>> {
+    let consent = debug_captures::set_consent(&app_state.db_pool, auth_user.user_id, payload.enabled).await?;
+    Ok(ApiResponse::ok(consent))
+}
+
+/// Lists the caller's debug captures, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_my_captures(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<CaptureListing>>> {
+    Ok(ApiResponse::ok(debug_captures::list_mine(&app_state.db_pool, auth_user.user_id).await?))
+}
+
+/// Flags one of the caller's captures as a bad generation, so admins can
+/// inspect and replay it.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the capture is not the caller's
+/// or was purged (404), or the database operation fails.
+pub async fn flag_capture(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(capture_id): Path<i64>,
+    Json(payload): Json<FlagCapturePayload>,
+) -> AppResult<ApiResponse<CaptureListing>> {
+    payload.validate()?;
+    let capture = debug_captures::flag(&app_state.db_pool, auth_user.user_id, capture_id, &payload.reason).await?;
+    Ok(ApiResponse::ok(capture))
+}
+
+/// Lists flagged captures, newest first; `all=true` includes unflagged ones.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_capture_queue(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<DebugCaptureQueueParams>,
+) -> AppResult<ApiResponse<Vec<CaptureListing>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let captures = debug_captures::queue(&app_state.db_pool, params.all.unwrap_or(false)).await?;
+    Ok(ApiResponse::ok(captures))
+}
+
+/// Returns a capture with its request, raw and parsed response, and latest
+/// replay.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the capture does not exist
+/// (404), or the database operation fails.
+pub async fn get_capture(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(capture_id): Path<i64>,
+) -> AppResult<ApiResponse<DebugCapture>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(debug_captures::get(&app_state.db_pool, capture_id).await?))
+}
+
+/// Re-runs a captured request against the current prompt version and
+/// returns the capture with the replay next to the original response.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the capture does not exist
+/// (404), the AI service or the captured provider is not configured, or the
+/// database operation fails.
+pub async fn replay_capture(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(capture_id): Path<i64>,
+) -> AppResult<ApiResponse<DebugCapture>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let capture = debug_captures::replay(&app_state.db_pool, ai_service, auth_user.user_id, capture_id).await?;
+    let prompt_changed = capture.replay_prompt_version.as_deref() != Some(capture.prompt_version.as_str());
+    Ok(ApiResponse::ok(capture).meta("prompt_version_changed", prompt_changed))
+}
+
+/// Deletes a capture once it has been dealt with.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the capture does not exist
+/// (404), or the database operation fails.
+pub async fn delete_capture(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(capture_id): Path<i64>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    debug_captures::delete(&app_state.db_pool, capture_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": true })))
+}
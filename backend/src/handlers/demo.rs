@@ -0,0 +1,69 @@
+//! One-click demo login, available only when `DEMO_MODE` is enabled.
+
+use axum::{Json, extract::State};
+use tracing::{info, warn};
+
+use super::types::{DemoLoginPayload, LoginResponse, UserProfile};
+use crate::AppState;
+use crate::auth::create_jwt;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::models::{User, UserRole};
+use crate::seed::{self, DEMO_EMPLOYER_EMAIL, DEMO_JOB_SEEKER_EMAIL};
+
+/// Signs in as a seeded demo persona without a password.
+///
+/// Defaults to the demo job seeker; pass `{"persona": "employer"}` for the
+/// demo employer. Run `cargo run -- seed` first to create the accounts.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if `DEMO_MODE` is disabled or the demo data
+/// has not been seeded, and `AppError::Forbidden` for the admin persona.
+pub async fn demo_login(
+    State(app_state): State<AppState>,
+    payload: Option<Json<DemoLoginPayload>>,
+) -> AppResult<ApiResponse<LoginResponse>> {
+    if !seed::demo_mode_enabled() {
+        return Err(AppError::NotFound);
+    }
+
+    let persona = payload
+        .and_then(|Json(p)| p.persona)
+        .unwrap_or(UserRole::JobSeeker);
+    let email = match persona {
+        UserRole::JobSeeker => DEMO_JOB_SEEKER_EMAIL,
+        UserRole::Employer => DEMO_EMPLOYER_EMAIL,
+        UserRole::Admin => return Err(AppError::Forbidden),
+    };
+
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(&app_state.db_pool)
+        .await?
+        .ok_or_else(|| {
+            warn!("Demo login failed: {} not seeded (run `cargo run -- seed`)", email);
+            AppError::NotFound
+        })?;
+
+    let token = create_jwt(user.id, user.email.clone())?;
+    info!("Demo login: user_id={}, persona={:?}", user.id, persona);
+
+    Ok(ApiResponse::ok(LoginResponse {
+        token,
+        user: UserProfile {
+            id: user.id,
+            full_name: user.full_name,
+            email: user.email,
+            profile_completed: user.profile_completed,
+            education_level: user.education_level,
+            experience_level: user.experience_level,
+            preferred_track: user.preferred_track,
+            skills: user.skills,
+            projects: user.projects,
+            target_roles: user.target_roles,
+            availability: None,
+            code_practice: None,
+        },
+    }))
+}
@@ -0,0 +1,270 @@
+//! Dispute handlers: opening disputes over mentor sessions and application
+//! decisions, evidence, and admin arbitration.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+use validator::Validate;
+
+use super::types::{DisputeQueueParams, OpenDisputePayload, ResolveDisputePayload, WithdrawDisputePayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::disputes::{
+    self, Dispute, DisputeDetail, DisputeTarget, EVIDENCE_TYPES, Evidence, MAX_EVIDENCE_SIZE, Resolution,
+};
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+use crate::response::ApiResponse;
+
+/// Opens a dispute over one of the caller's mentor bookings or a decision
+/// on their application. A paid session's payment is held until resolved.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Validation fails, or neither or both of `booking_id` and
+///   `application_id` are given
+/// - The booking or application is not the caller's (404)
+/// - It cannot be disputed (yet, or any longer) or already has a live dispute
+/// - Database operation fails
+pub async fn open_dispute(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<OpenDisputePayload>,
+) -> AppResult<ApiResponse<Dispute>> {
+    payload.validate()?;
+    let target = match (payload.booking_id, payload.application_id) {
+        (Some(booking_id), None) => DisputeTarget::Booking(booking_id),
+        (None, Some(application_id)) => DisputeTarget::Application(application_id),
+        _ => {
+            return Err(AppError::ValidationError(
+                "Provide either booking_id or application_id".to_string(),
+            ));
+        }
+    };
+    let dispute = disputes::open(&app_state.db_pool, auth_user.user_id, target, &payload.reason).await?;
+    Ok(ApiResponse::ok(dispute))
+}
+
+/// Lists disputes the caller opened or responds to, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_disputes(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<Dispute>>> {
+    Ok(ApiResponse::ok(disputes::list_mine(&app_state.db_pool, auth_user.user_id).await?))
+}
+
+/// Returns a dispute with its evidence and status history, to its parties
+/// and admins.
+///
+/// # Errors
+///
+/// Returns an error if the caller may not see the dispute (404) or the
+/// database operation fails.
+pub async fn get_dispute(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(dispute_id): Path<i32>,
+) -> AppResult<ApiResponse<DisputeDetail>> {
+    Ok(ApiResponse::ok(
+        disputes::detail(&app_state.db_pool, auth_user.user_id, dispute_id).await?,
+    ))
+}
+
+/// Adds evidence to a live dispute from multipart fields `note` and/or
+/// `file` (PDF, PNG, JPG, or TXT up to 10MB; parties only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a party (404), the dispute is
+/// closed, nothing is attached, the file is of another type or too large,
+/// or storage or the database operation fails.
+pub async fn add_evidence(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(dispute_id): Path<i32>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<Evidence>> {
+    let mut note = None;
+    let mut file = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        match field.name() {
+            Some("note") => {
+                let text = field.text().await.map_err(|e| {
+                    error!("Failed to read evidence note: {}", e);
+                    AppError::BadRequest("Failed to read note".to_string())
+                })?;
+                if text.chars().count() > 5000 {
+                    return Err(AppError::ValidationError("Note must be at most 5000 characters".to_string()));
+                }
+                note = Some(text);
+            }
+            Some("file") => {
+                // Keep only the final path component of the client's file name
+                let file_name = field
+                    .file_name()
+                    .and_then(|name| name.rsplit(['/', '\\']).next())
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?
+                    .chars()
+                    .take(255)
+                    .collect::<String>();
+                let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+                let (extension, _) = EVIDENCE_TYPES
+                    .iter()
+                    .find(|(ext, _)| Some(*ext) == extension.as_deref())
+                    .ok_or_else(|| {
+                        AppError::BadRequest("Only PDF, PNG, JPG, and TXT files are supported".to_string())
+                    })?;
+
+                let data = field.bytes().await.map_err(|e| {
+                    error!("Failed to read evidence data: {}", e);
+                    AppError::BadRequest("Failed to read file data".to_string())
+                })?;
+                if data.len() > MAX_EVIDENCE_SIZE {
+                    warn!("Dispute evidence too large ({} bytes) for dispute {}", data.len(), dispute_id);
+                    return Err(AppError::BadRequest("File size exceeds 10MB limit".to_string()));
+                }
+                file = Some((file_name, *extension, data));
+            }
+            _ => {}
+        }
+    }
+
+    let evidence = disputes::add_evidence(
+        &app_state.db_pool,
+        &app_state.storage,
+        auth_user.user_id,
+        dispute_id,
+        note.as_deref(),
+        file.as_ref().map(|(name, ext, data)| (name.as_str(), *ext, data.as_ref())),
+    )
+    .await?;
+    Ok(ApiResponse::ok(evidence))
+}
+
+/// Downloads an evidence file.
+///
+/// # Errors
+///
+/// Returns an error if the caller may not see the dispute or the evidence
+/// has no file (404), or storage fails.
+pub async fn download_evidence(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((dispute_id, evidence_id)): Path<(i32, i32)>,
+) -> AppResult<Response> {
+    let (file_name, content_type, bytes) = disputes::evidence_file(
+        &app_state.db_pool,
+        &app_state.storage,
+        auth_user.user_id,
+        dispute_id,
+        evidence_id,
+    )
+    .await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name.replace(['"', '\\'], "")),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Withdraws a live dispute the caller opened, releasing any held payment
+/// as it was.
+///
+/// # Errors
+///
+/// Returns an error if the caller did not open the dispute (404), it is
+/// closed, or the database operation fails.
+pub async fn withdraw_dispute(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(dispute_id): Path<i32>,
+    payload: Option<Json<WithdrawDisputePayload>>,
+) -> AppResult<ApiResponse<Dispute>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+    let note = payload.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    let dispute = disputes::withdraw(&app_state.db_pool, auth_user.user_id, dispute_id, note).await?;
+    Ok(ApiResponse::ok(dispute))
+}
+
+/// Lists disputes for arbitration, oldest first: open and under review, or
+/// those in `status`.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_dispute_queue(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<DisputeQueueParams>,
+) -> AppResult<ApiResponse<Vec<Dispute>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(disputes::queue(&app_state.db_pool, params.status).await?))
+}
+
+/// Takes an open dispute under review, assigned to the caller.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the dispute does not exist
+/// (404) or is not open, or the database operation fails.
+pub async fn review_dispute(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(dispute_id): Path<i32>,
+) -> AppResult<ApiResponse<Dispute>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let dispute = disputes::start_review(&app_state.db_pool, auth_user.user_id, dispute_id).await?;
+    Ok(ApiResponse::ok(dispute))
+}
+
+/// Resolves a live dispute with an outcome, refunding the mentee of a paid
+/// session as ruled and releasing the rest to the mentor.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, validation fails, the
+/// dispute does not exist (404) or is closed, the refund amount is missing
+/// or too large, or the refund or database operation fails.
+pub async fn resolve_dispute(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(dispute_id): Path<i32>,
+    Json(payload): Json<ResolveDisputePayload>,
+) -> AppResult<ApiResponse<Dispute>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+    let dispute = disputes::resolve(
+        &app_state.db_pool,
+        app_state.stripe.as_deref(),
+        auth_user.user_id,
+        dispute_id,
+        Resolution {
+            outcome: payload.outcome,
+            refund_cents: payload.refund_cents,
+            note: &payload.note,
+        },
+    )
+    .await?;
+    Ok(ApiResponse::ok(dispute))
+}
@@ -0,0 +1,105 @@
+//! Saved document handlers: documents the mentor draws on when answering.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use serde_json::{Value, json};
+use tracing::info;
+use validator::Validate;
+
+use super::types::CreateDocumentPayload;
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::retrieval::UserDocument;
+
+/// Lists the user's saved documents, most recently updated first.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_documents(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<UserDocument>>> {
+    let documents = sqlx::query_as::<_, UserDocument>(
+        r#"
+        SELECT id, title, content, created_at, updated_at
+        FROM user_documents
+        WHERE user_id = $1
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(documents))
+}
+
+/// Saves a document. It is indexed for the mentor on the next question.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - Title or content is empty or too long
+/// - Database operation fails
+pub async fn create_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateDocumentPayload>,
+) -> AppResult<ApiResponse<UserDocument>> {
+    payload.validate()?;
+
+    let document = sqlx::query_as::<_, UserDocument>(
+        r#"
+        INSERT INTO user_documents (user_id, title, content)
+        VALUES ($1, $2, $3)
+        RETURNING id, title, content, created_at, updated_at
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.title.trim())
+    .bind(&payload.content)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    info!("User {} saved document {}", auth_user.user_id, document.id);
+    Ok(ApiResponse::ok(document))
+}
+
+/// Deletes a saved document and its indexed chunks.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no document with this ID,
+/// or an error if the database operation fails.
+pub async fn delete_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(document_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let mut tx = app_state.db_pool.begin().await?;
+
+    let deleted = sqlx::query("DELETE FROM user_documents WHERE id = $1 AND user_id = $2")
+        .bind(document_id)
+        .bind(auth_user.user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+    sqlx::query("DELETE FROM document_chunks WHERE user_id = $1 AND source = 'document' AND source_id = $2")
+        .bind(auth_user.user_id)
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(ApiResponse::ok(json!({ "message": "Document deleted" })))
+}
@@ -0,0 +1,331 @@
+//! Employer job posting and dashboard handlers.
+//!
+//! Employers on a company team (see [`crate::teams`]) share one pipeline:
+//! jobs they post belong to the company and appear on every member's
+//! dashboard. Viewer seats cannot post jobs.
+//!
+//! Only members of a verified company (see [`crate::employer_verification`])
+//! may post or renew jobs. New posts wait in the moderation queue (see
+//! [`crate::moderation`]) until an admin approves them. Posts expire after
+//! `JOB_EXPIRY_DAYS` (default 30) unless the employer picks another
+//! duration, and can be renewed.
+
+use axum::{extract::{Path, State}, Json};
+use tracing::{info, debug, warn};
+use validator::Validate;
+use crate::models::{JobType, ModerationStatus, UserRole};
+use crate::geo;
+use crate::inclusive_language;
+use crate::employer_verification;
+use crate::moderation;
+use crate::teams;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::auth::AuthUser;
+use crate::AppState;
+use super::types::{CreateJobPayload, EmployerDashboard, EmployerJobSummary, RenewJobPayload};
+
+/// Default posting lifetime in days.
+const DEFAULT_JOB_EXPIRY_DAYS: i64 = 30;
+
+/// Reads `JOB_EXPIRY_DAYS` (default: 30).
+fn default_expiry_days() -> i64 {
+    std::env::var("JOB_EXPIRY_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_JOB_EXPIRY_DAYS)
+}
+
+/// Creates a job posting owned by the authenticated employer.
+/// 
+/// Posts start as `pending` and are pre-screened for scam indicators in the
+/// background; admin posts are approved immediately. If the employer holds a
+/// seat, the job joins the company pipeline and the posting is recorded in
+/// the team's activity log.
+/// 
+/// # Errors
+/// 
+/// Returns an error if:
+/// - User is not authenticated
+/// - User is not an employer
+/// - User holds a viewer seat
+/// - User's company is not a verified employer (412)
+/// - Validation fails
+/// - Database operation fails
+pub async fn create_job(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateJobPayload>,
+) -> AppResult<ApiResponse<serde_json::Value>> {
+    let role = auth_user
+        .require_role(&app_state.db_pool, &[UserRole::Employer, UserRole::Admin])
+        .await?;
+
+    let seat = teams::seat_for(&app_state.db_pool, auth_user.user_id).await?;
+    if let Some(seat) = &seat
+        && !teams::PIPELINE_ROLES.contains(&seat.role)
+    {
+        return Err(AppError::Forbidden);
+    }
+    employer_verification::require_may_post(&app_state.db_pool, auth_user.user_id, role).await?;
+
+    payload.validate().map_err(|e| {
+        warn!("Job posting validation failed for user {}: {}", auth_user.user_id, e);
+        e
+    })?;
+
+    if let (Some(min), Some(max)) = (payload.salary_min, payload.salary_max)
+        && min > max
+    {
+        return Err(AppError::ValidationError(
+            "salary_min cannot be greater than salary_max".to_string(),
+        ));
+    }
+    if payload.job_type != JobType::Internship
+        && (payload.term_start.is_some() || payload.term_end.is_some() || payload.academic_credit)
+    {
+        return Err(AppError::ValidationError(
+            "Term dates and academic credit only apply to internships".to_string(),
+        ));
+    }
+    if let (Some(start), Some(end)) = (payload.term_start, payload.term_end)
+        && end < start
+    {
+        return Err(AppError::ValidationError(
+            "term_end cannot be before term_start".to_string(),
+        ));
+    }
+
+    let country_code = payload
+        .country_code
+        .as_deref()
+        .map(geo::normalize_country_code)
+        .transpose()?;
+
+    info!("Creating job posting for employer: {}", auth_user.user_id);
+
+    let status = if role == UserRole::Admin {
+        ModerationStatus::Approved
+    } else {
+        ModerationStatus::Pending
+    };
+
+    let job_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO jobs (
+            job_title, company, location, job_description, required_skills,
+            experience_level, job_type, salary_min, salary_max,
+            responsibilities, requirements, benefits, posted_by, company_id,
+            moderation_status, expires_at, country_code, start_date, visa_sponsorship,
+            term_start, term_end, academic_credit
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+            NOW() + make_interval(days => $16), $17, $18, $19, $20, $21, $22
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(&payload.job_title)
+    .bind(&payload.company)
+    .bind(&payload.location)
+    .bind(&payload.job_description)
+    .bind(&payload.required_skills)
+    .bind(&payload.experience_level)
+    .bind(payload.job_type)
+    .bind(payload.salary_min)
+    .bind(payload.salary_max)
+    .bind(&payload.responsibilities)
+    .bind(&payload.requirements)
+    .bind(&payload.benefits)
+    .bind(auth_user.user_id)
+    .bind(seat.as_ref().map(|s| s.company_id))
+    .bind(status)
+    .bind(payload.expires_in_days.unwrap_or_else(default_expiry_days) as i32)
+    .bind(&country_code)
+    .bind(payload.start_date)
+    .bind(payload.visa_sponsorship)
+    .bind(payload.term_start)
+    .bind(payload.term_end)
+    .bind(payload.academic_credit)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    info!("Job posting created: job_id={}, employer={}, status={:?}", job_id, auth_user.user_id, status);
+
+    if status == ModerationStatus::Pending {
+        moderation::spawn_prescreen(app_state.db_pool.clone(), app_state.ai_service.clone(), job_id);
+    }
+    geo::spawn_geocode_job(app_state.db_pool.clone(), app_state.geocoder.clone(), job_id);
+
+    if let Some(seat) = &seat {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            seat.company_id,
+            "job.create",
+            "job",
+            &job_id.to_string(),
+            serde_json::json!({ "job_title": payload.job_title }),
+        )
+        .await;
+    }
+
+    let message = match status {
+        ModerationStatus::Pending => "Job submitted for review",
+        _ => "Job posted successfully",
+    };
+
+    // Advice only; the posting is already created
+    let mut fields = vec![
+        ("job_title", payload.job_title.as_str()),
+        ("job_description", payload.job_description.as_str()),
+    ];
+    for (field, items) in [
+        ("responsibilities", &payload.responsibilities),
+        ("requirements", &payload.requirements),
+        ("benefits", &payload.benefits),
+    ] {
+        fields.extend(items.iter().map(|item| (field, item.as_str())));
+    }
+    let language_flags = inclusive_language::lint_posting(fields);
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "message": message,
+        "job_id": job_id,
+        "moderation_status": status,
+        "language_flags": language_flags
+    })))
+}
+
+/// Returns the employer dashboard with per-job applicant counters.
+/// 
+/// Counts are read from the `job_application_stats` read model rather than
+/// aggregated from `application_tracking` on each request. Team members see
+/// every job in their company's pipeline.
+/// 
+/// # Errors
+/// 
+/// Returns an error if:
+/// - User is not authenticated
+/// - User is not an employer
+/// - Database operation fails
+pub async fn get_dashboard(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<EmployerDashboard>> {
+    auth_user
+        .require_role(&app_state.db_pool, &[UserRole::Employer, UserRole::Admin])
+        .await?;
+
+    info!("Fetching employer dashboard for user: {}", auth_user.user_id);
+
+    let company_id = teams::seat_for(&app_state.db_pool, auth_user.user_id)
+        .await?
+        .map(|seat| seat.company_id);
+
+    let jobs = sqlx::query_as::<_, EmployerJobSummary>(
+        r#"
+        SELECT
+            j.id AS job_id, j.posted_by, j.job_title, j.location, j.created_at,
+            j.moderation_status, j.moderation_reason, j.expires_at, j.closed_at,
+            COALESCE(s.applicant_count, 0) AS applicant_count,
+            COALESCE(s.new_count, 0) AS new_count,
+            COALESCE(s.in_review_count, 0) AS in_review_count
+        FROM jobs j
+        LEFT JOIN job_application_stats s ON s.job_id = j.id
+        WHERE j.posted_by = $1 OR j.company_id = $2
+        ORDER BY j.created_at DESC
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(company_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let total_applicants = jobs.iter().map(|j| j.applicant_count as i64).sum();
+    let total_new = jobs.iter().map(|j| j.new_count as i64).sum();
+    let total_in_review = jobs.iter().map(|j| j.in_review_count as i64).sum();
+
+    debug!("Employer {} has {} jobs, {} applicants", auth_user.user_id, jobs.len(), total_applicants);
+
+    Ok(ApiResponse::ok(EmployerDashboard {
+        total_jobs: jobs.len(),
+        total_applicants,
+        total_new,
+        total_in_review,
+        jobs,
+    }))
+}
+
+/// Renews a job posting, reopening it if it has expired.
+///
+/// The poster, any owner or recruiter on the job's company team, and admins
+/// may renew. Applications made before the posting expired are unaffected.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an employer
+/// - User's company is not a verified employer (412)
+/// - The job does not exist or the user may not manage it (404)
+/// - Validation fails
+/// - Database operation fails
+pub async fn renew_job(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+    payload: Option<Json<RenewJobPayload>>,
+) -> AppResult<ApiResponse<serde_json::Value>> {
+    let role = auth_user
+        .require_role(&app_state.db_pool, &[UserRole::Employer, UserRole::Admin])
+        .await?;
+    employer_verification::require_may_post(&app_state.db_pool, auth_user.user_id, role).await?;
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+
+    let company_id = teams::seat_for(&app_state.db_pool, auth_user.user_id)
+        .await?
+        .filter(|seat| teams::PIPELINE_ROLES.contains(&seat.role))
+        .map(|seat| seat.company_id);
+    let days = payload.expires_in_days.unwrap_or_else(default_expiry_days) as i32;
+
+    let expires_at = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
+        r#"
+        UPDATE jobs
+        SET expires_at = NOW() + make_interval(days => $4), closed_at = NULL
+        WHERE id = $1 AND ($2 OR posted_by = $3 OR company_id = $5)
+        RETURNING expires_at
+        "#,
+    )
+    .bind(job_id)
+    .bind(role == UserRole::Admin)
+    .bind(auth_user.user_id)
+    .bind(days)
+    .bind(company_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    info!("Job {} renewed by {} until {}", job_id, auth_user.user_id, expires_at);
+
+    if let Some(company_id) = company_id {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            company_id,
+            "job.renew",
+            "job",
+            &job_id.to_string(),
+            serde_json::json!({ "expires_at": expires_at }),
+        )
+        .await;
+    }
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "message": "Job renewed",
+        "job_id": job_id,
+        "expires_at": expires_at
+    })))
+}
@@ -0,0 +1,278 @@
+//! Employer verification handlers: the team owner's verification steps and
+//! the admin review queue.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+use validator::Validate;
+
+use super::types::{
+    ModerationDecisionPayload, SubmitVerificationPayload, VerificationCodePayload, VerificationEmailPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::employer_verification::{
+    self, DOCUMENT_TYPES, EmployerVerification, MAX_DOCUMENT_SIZE, QueuedVerification, VerificationOverview,
+};
+use crate::errors::{AppError, AppResult};
+use crate::models::{EmployerVerificationStatus, SeatRole, UserRole};
+use crate::response::ApiResponse;
+use crate::teams;
+
+/// Returns the caller's company verification status and its latest attempt.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if
+/// the database operation fails.
+pub async fn get_verification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<VerificationOverview>> {
+    let seat = teams::seat_for(&app_state.db_pool, auth_user.user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let overview = employer_verification::overview(&app_state.db_pool, seat.company_id).await?;
+    Ok(ApiResponse::ok(overview))
+}
+
+/// Emails a verification code to a work email on the company's domain
+/// (owners only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - The email is on a personal or unrelated domain
+/// - The company is verified or awaiting review
+/// - Sending the email or the database operation fails
+pub async fn request_verification_email(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<VerificationEmailPayload>,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    payload.validate()?;
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    let attempt = employer_verification::request_email_code(
+        &app_state.db_pool,
+        seat.company_id,
+        auth_user.user_id,
+        &payload.work_email,
+    )
+    .await?;
+    Ok(ApiResponse::ok(attempt))
+}
+
+/// Confirms the work email with the emailed code (owners only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a team owner, the code is wrong or
+/// expired, or the database operation fails.
+pub async fn confirm_verification_email(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<VerificationCodePayload>,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    payload.validate()?;
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    let attempt =
+        employer_verification::confirm_email(&app_state.db_pool, seat.company_id, auth_user.user_id, &payload.code)
+            .await?;
+    Ok(ApiResponse::ok(attempt))
+}
+
+/// Uploads the company's registration document as the multipart field
+/// `document` (PDF, PNG, or JPG up to 10MB; owners only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a team owner, the file is missing,
+/// of another type, or too large, or storage or the database operation
+/// fails.
+pub async fn upload_verification_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        if field.name() != Some("document") {
+            continue;
+        }
+
+        // Keep only the final path component of the client's file name
+        let file_name = field
+            .file_name()
+            .and_then(|name| name.rsplit(['/', '\\']).next())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?
+            .chars()
+            .take(255)
+            .collect::<String>();
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+        let (extension, _) = DOCUMENT_TYPES
+            .iter()
+            .find(|(ext, _)| Some(*ext) == extension.as_deref())
+            .ok_or_else(|| AppError::BadRequest("Only PDF, PNG, and JPG documents are supported".to_string()))?;
+
+        let data = field.bytes().await.map_err(|e| {
+            error!("Failed to read document data: {}", e);
+            AppError::BadRequest("Failed to read file data".to_string())
+        })?;
+        if data.len() > MAX_DOCUMENT_SIZE {
+            warn!("Verification document too large ({} bytes) for company {}", data.len(), seat.company_id);
+            return Err(AppError::BadRequest("File size exceeds 10MB limit".to_string()));
+        }
+
+        let attempt = employer_verification::upload_document(
+            &app_state.db_pool,
+            &app_state.storage,
+            seat.company_id,
+            auth_user.user_id,
+            &file_name,
+            extension,
+            &data,
+        )
+        .await?;
+        return Ok(ApiResponse::ok(attempt));
+    }
+
+    Err(AppError::BadRequest("No document provided".to_string()))
+}
+
+/// Submits the verification for admin review with the company's
+/// registration number, which is looked up in the business registry
+/// (owners only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - The work email is not confirmed or no document was uploaded
+/// - The company is verified or awaiting review
+/// - Database operation fails
+pub async fn submit_verification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SubmitVerificationPayload>,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    payload.validate()?;
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    let attempt = employer_verification::submit(
+        &app_state.db_pool,
+        seat.company_id,
+        auth_user.user_id,
+        &payload.registration_number,
+        &payload.registration_country,
+    )
+    .await?;
+    Ok(ApiResponse::ok(attempt))
+}
+
+/// Lists employer verifications awaiting review, oldest first, with their
+/// registry results.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_pending_verifications(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<QueuedVerification>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(employer_verification::list_pending(&app_state.db_pool).await?))
+}
+
+/// Downloads the registration document of a verification.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the verification has no
+/// document (404), or storage fails.
+pub async fn download_verification_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(verification_id): Path<i32>,
+) -> AppResult<Response> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let (file_name, content_type, bytes) =
+        employer_verification::document(&app_state.db_pool, &app_state.storage, verification_id).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name.replace(['"', '\\'], "")),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Approves a verification, making the company a verified employer.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the verification is not
+/// awaiting review (404), or the database operation fails.
+pub async fn approve_verification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(verification_id): Path<i32>,
+    payload: Option<Json<ModerationDecisionPayload>>,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let note = payload
+        .and_then(|Json(p)| p.reason)
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let attempt = employer_verification::review(
+        &app_state.db_pool,
+        auth_user.user_id,
+        verification_id,
+        EmployerVerificationStatus::Verified,
+        note.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(attempt))
+}
+
+/// Rejects a verification with a reason shown to the employer.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, no reason is given, the
+/// verification is not awaiting review (404), or the database operation
+/// fails.
+pub async fn reject_verification(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(verification_id): Path<i32>,
+    Json(payload): Json<ModerationDecisionPayload>,
+) -> AppResult<ApiResponse<EmployerVerification>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let Some(reason) = payload.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()) else {
+        return Err(AppError::ValidationError(
+            "A reason is required when rejecting a verification".to_string(),
+        ));
+    };
+    let attempt = employer_verification::review(
+        &app_state.db_pool,
+        auth_user.user_id,
+        verification_id,
+        EmployerVerificationStatus::Rejected,
+        Some(&reason),
+    )
+    .await?;
+    Ok(ApiResponse::ok(attempt))
+}
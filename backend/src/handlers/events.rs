@@ -0,0 +1,782 @@
+//! Career-fair event handlers: organizing, RSVPs, schedules, and booth leads.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Value, json};
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    CareerEvent, CreateBoothPayload, CreateEventPayload, CreateEventSessionPayload, EventBooth,
+    EventDetails, EventLead, EventRsvp, EventSession, InviteLeadPayload, RecordInteractionPayload,
+    RsvpPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::blocks;
+use crate::cohorts;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::events::{self, CalendarEntry};
+use crate::models::{LeadStatus, OrgRole, SeatRole};
+use crate::teams;
+
+/// Columns of [`CareerEvent`] for the `career_events` row aliased `e`.
+const EVENT_COLUMNS: &str = r#"
+    e.id, e.org_id, o.name AS org_name, e.name, e.description, e.location,
+    e.starts_at, e.ends_at,
+    (SELECT COUNT(*) FROM event_rsvps r WHERE r.event_id = e.id) AS attendee_count
+"#;
+
+/// Columns of [`EventLead`] for the `event_interactions` row aliased `i`.
+const LEAD_COLUMNS: &str = r#"
+    i.id, i.event_id, e.name AS event_name,
+    i.candidate_id, u.full_name AS candidate_name, u.email AS candidate_email,
+    i.job_id, j.job_title,
+    ARRAY(
+        SELECT c.name FROM user_certifications c
+        WHERE c.user_id = i.candidate_id AND c.verification_status = 'verified'
+        ORDER BY c.issue_date DESC NULLS LAST
+    ) AS verified_certifications,
+    i.notes, i.rating, i.lead_status, i.recorded_by, i.created_at
+"#;
+
+async fn fetch_event(app_state: &AppState, event_id: i32) -> AppResult<CareerEvent> {
+    sqlx::query_as::<_, CareerEvent>(&format!(
+        "SELECT {} FROM career_events e JOIN organizations o ON o.id = e.org_id WHERE e.id = $1",
+        EVENT_COLUMNS
+    ))
+    .bind(event_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Ensures the event exists and belongs to the organization.
+async fn require_org_event(app_state: &AppState, org_id: i32, event_id: i32) -> AppResult<CareerEvent> {
+    let event = fetch_event(app_state, event_id).await?;
+    if event.org_id != org_id {
+        return Err(AppError::NotFound);
+    }
+    Ok(event)
+}
+
+fn ensure_time_range(
+    starts_at: chrono::DateTime<chrono::Utc>,
+    ends_at: chrono::DateTime<chrono::Utc>,
+) -> AppResult<()> {
+    if ends_at <= starts_at {
+        return Err(AppError::ValidationError(
+            "ends_at must be after starts_at".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a career fair for an organization (org admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails or the event ends before it starts
+/// - Database operation fails
+pub async fn create_event(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Json(payload): Json<CreateEventPayload>,
+) -> AppResult<ApiResponse<CareerEvent>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+    ensure_time_range(payload.starts_at, payload.ends_at)?;
+
+    let event_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO career_events (org_id, name, description, location, starts_at, ends_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(org_id)
+    .bind(payload.name.trim())
+    .bind(&payload.description)
+    .bind(&payload.location)
+    .bind(payload.starts_at)
+    .bind(payload.ends_at)
+    .bind(auth_user.user_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    info!("Career event {} created in organization {} by {}", event_id, org_id, auth_user.user_id);
+    Ok(ApiResponse::ok(fetch_event(&app_state, event_id).await?))
+}
+
+/// Adds a session to a career fair (org admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - The event does not belong to the organization (404)
+/// - Validation fails or the session falls outside the event
+/// - Database operation fails
+pub async fn create_session(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, event_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateEventSessionPayload>,
+) -> AppResult<ApiResponse<EventSession>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    let event = require_org_event(&app_state, org_id, event_id).await?;
+    payload.validate()?;
+    ensure_time_range(payload.starts_at, payload.ends_at)?;
+    if payload.starts_at < event.starts_at || payload.ends_at > event.ends_at {
+        return Err(AppError::ValidationError(
+            "Sessions must take place during the event".to_string(),
+        ));
+    }
+
+    let session = sqlx::query_as::<_, EventSession>(
+        r#"
+        INSERT INTO event_sessions (event_id, title, description, location, speaker, starts_at, ends_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, title, description, location, speaker, starts_at, ends_at
+        "#,
+    )
+    .bind(event_id)
+    .bind(payload.title.trim())
+    .bind(&payload.description)
+    .bind(&payload.location)
+    .bind(&payload.speaker)
+    .bind(payload.starts_at)
+    .bind(payload.ends_at)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(session))
+}
+
+/// Gives a company team a booth at a career fair (org admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - The event does not belong to the organization, or the company does not exist (404)
+/// - The company already has a booth at this event
+/// - Database operation fails
+pub async fn add_booth(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, event_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateBoothPayload>,
+) -> AppResult<ApiResponse<EventBooth>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    require_org_event(&app_state, org_id, event_id).await?;
+    payload.validate()?;
+
+    let booth = sqlx::query_as::<_, EventBooth>(
+        r#"
+        INSERT INTO event_booths (event_id, company_id, label)
+        SELECT $1, c.id, $3 FROM companies c WHERE c.id = $2
+        ON CONFLICT (event_id, company_id) DO NOTHING
+        RETURNING id, company_id,
+            (SELECT name FROM companies WHERE id = company_id) AS company_name, label
+        "#,
+    )
+    .bind(event_id)
+    .bind(payload.company_id)
+    .bind(&payload.label)
+    .fetch_optional(&app_state.db_pool)
+    .await?;
+
+    match booth {
+        Some(booth) => {
+            info!("Company {} given booth {} at event {}", payload.company_id, booth.id, event_id);
+            Ok(ApiResponse::ok(booth))
+        }
+        None => {
+            let company_exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM companies WHERE id = $1)",
+            )
+            .bind(payload.company_id)
+            .fetch_one(&app_state.db_pool)
+            .await?;
+            if company_exists {
+                Err(AppError::BadRequest(
+                    "This company already has a booth at the event".to_string(),
+                ))
+            } else {
+                Err(AppError::NotFound)
+            }
+        }
+    }
+}
+
+/// Lists upcoming and ongoing career fairs, soonest first.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn list_events(
+    _auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<CareerEvent>>> {
+    let events = sqlx::query_as::<_, CareerEvent>(&format!(
+        r#"
+        SELECT {} FROM career_events e
+        JOIN organizations o ON o.id = e.org_id
+        WHERE e.ends_at > NOW()
+        ORDER BY e.starts_at
+        "#,
+        EVENT_COLUMNS
+    ))
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(events))
+}
+
+async fn fetch_rsvp(app_state: &AppState, event_id: i32, user_id: Uuid) -> AppResult<Option<EventRsvp>> {
+    let badge_code = sqlx::query_scalar::<_, String>(
+        "SELECT badge_code FROM event_rsvps WHERE event_id = $1 AND user_id = $2",
+    )
+    .bind(event_id)
+    .bind(user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?;
+    let Some(badge_code) = badge_code else {
+        return Ok(None);
+    };
+
+    let session_ids = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT s.id FROM event_session_rsvps sr
+        JOIN event_sessions s ON s.id = sr.session_id
+        WHERE s.event_id = $1 AND sr.user_id = $2
+        ORDER BY s.starts_at
+        "#,
+    )
+    .bind(event_id)
+    .bind(user_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(Some(EventRsvp { badge_code, session_ids }))
+}
+
+/// Returns a career fair with its sessions, booths, and the caller's RSVP.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the event does not exist, or an error if
+/// a query fails.
+pub async fn get_event(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(event_id): Path<i32>,
+) -> AppResult<ApiResponse<EventDetails>> {
+    let event = fetch_event(&app_state, event_id).await?;
+
+    let sessions = sqlx::query_as::<_, EventSession>(
+        r#"
+        SELECT id, title, description, location, speaker, starts_at, ends_at
+        FROM event_sessions WHERE event_id = $1
+        ORDER BY starts_at
+        "#,
+    )
+    .bind(event_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let booths = sqlx::query_as::<_, EventBooth>(
+        r#"
+        SELECT b.id, b.company_id, c.name AS company_name, b.label
+        FROM event_booths b
+        JOIN companies c ON c.id = b.company_id
+        WHERE b.event_id = $1
+        ORDER BY b.label NULLS LAST, c.name
+        "#,
+    )
+    .bind(event_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let rsvp = fetch_rsvp(&app_state, event_id, auth_user.user_id).await?;
+
+    Ok(ApiResponse::ok(EventDetails {
+        event,
+        sessions,
+        booths,
+        rsvp,
+    }))
+}
+
+/// RSVPs the caller to a career fair, or updates their session choices.
+///
+/// The badge code is kept across updates so printed badges stay valid.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The event does not exist or has ended (404)
+/// - A session ID does not belong to the event
+/// - Database operation fails
+pub async fn rsvp_event(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(event_id): Path<i32>,
+    payload: Option<Json<RsvpPayload>>,
+) -> AppResult<ApiResponse<EventRsvp>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let event = fetch_event(&app_state, event_id).await?;
+    if event.ends_at <= chrono::Utc::now() {
+        return Err(AppError::NotFound);
+    }
+
+    let valid_sessions = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM event_sessions WHERE event_id = $1 AND id = ANY($2)",
+    )
+    .bind(event_id)
+    .bind(&payload.session_ids)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    let mut requested = payload.session_ids.clone();
+    requested.sort_unstable();
+    requested.dedup();
+    if valid_sessions as usize != requested.len() {
+        return Err(AppError::ValidationError(
+            "One or more sessions do not belong to this event".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO event_rsvps (event_id, user_id, badge_code)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (event_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(event_id)
+    .bind(auth_user.user_id)
+    .bind(events::new_badge_code())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM event_session_rsvps sr
+        USING event_sessions s
+        WHERE sr.session_id = s.id AND s.event_id = $1 AND sr.user_id = $2
+        "#,
+    )
+    .bind(event_id)
+    .bind(auth_user.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO event_session_rsvps (session_id, user_id) SELECT UNNEST($1::int[]), $2",
+    )
+    .bind(&requested)
+    .bind(auth_user.user_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    info!("User {} RSVPed to event {} ({} sessions)", auth_user.user_id, event_id, requested.len());
+
+    let rsvp = fetch_rsvp(&app_state, event_id, auth_user.user_id)
+        .await?
+        .ok_or(AppError::InternalServerError)?;
+    Ok(ApiResponse::ok(rsvp))
+}
+
+/// Cancels the caller's RSVP and session sign-ups.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has not RSVPed.
+pub async fn cancel_rsvp(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(event_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let mut tx = app_state.db_pool.begin().await?;
+    let removed = sqlx::query("DELETE FROM event_rsvps WHERE event_id = $1 AND user_id = $2")
+        .bind(event_id)
+        .bind(auth_user.user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if removed == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    sqlx::query(
+        r#"
+        DELETE FROM event_session_rsvps sr
+        USING event_sessions s
+        WHERE sr.session_id = s.id AND s.event_id = $1 AND sr.user_id = $2
+        "#,
+    )
+    .bind(event_id)
+    .bind(auth_user.user_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(ApiResponse::ok(json!({ "message": "RSVP cancelled" })))
+}
+
+/// Downloads the caller's schedule for a career fair as an `.ics` file.
+///
+/// Includes the fair itself plus the sessions the caller signed up for, or
+/// every session if they did not pick any.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the event does not exist or the caller
+/// has not RSVPed.
+pub async fn get_schedule(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(event_id): Path<i32>,
+) -> AppResult<Response> {
+    let event = fetch_event(&app_state, event_id).await?;
+    let rsvp = fetch_rsvp(&app_state, event_id, auth_user.user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let sessions = sqlx::query_as::<_, EventSession>(
+        r#"
+        SELECT id, title, description, location, speaker, starts_at, ends_at
+        FROM event_sessions
+        WHERE event_id = $1 AND (cardinality($2::int[]) = 0 OR id = ANY($2))
+        ORDER BY starts_at
+        "#,
+    )
+    .bind(event_id)
+    .bind(&rsvp.session_ids)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let mut entries = vec![CalendarEntry {
+        uid: format!("event-{}@careerbridge", event.id),
+        summary: event.name.clone(),
+        description: Some(format!(
+            "{}\n\nBadge code: {}",
+            event.description.as_deref().unwrap_or_default(),
+            rsvp.badge_code
+        )),
+        location: event.location.clone(),
+        url: None,
+        starts_at: event.starts_at,
+        ends_at: event.ends_at,
+    }];
+    entries.extend(sessions.into_iter().map(|session| CalendarEntry {
+        uid: format!("event-session-{}@careerbridge", session.id),
+        summary: match &session.speaker {
+            Some(speaker) => format!("{} ({})", session.title, speaker),
+            None => session.title,
+        },
+        description: session.description,
+        location: session.location.or_else(|| event.location.clone()),
+        url: None,
+        starts_at: session.starts_at,
+        ends_at: session.ends_at,
+    }));
+
+    let ics = events::to_ics(&event.name, &entries);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"event-{}.ics\"", event.id),
+            ),
+        ],
+        ics,
+    )
+        .into_response())
+}
+
+/// Records a booth interaction by scanning an attendee's badge.
+///
+/// The caller must hold a recruiter or owner seat on a company with a booth
+/// at the event. The interaction becomes a lead on the company's pipeline.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller has no pipeline seat, or their company has no booth at the event (403)
+/// - The badge code is unknown for this event (404)
+/// - `job_id` is not one of the company's jobs
+/// - Database operation fails
+pub async fn record_interaction(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(event_id): Path<i32>,
+    Json(payload): Json<RecordInteractionPayload>,
+) -> AppResult<ApiResponse<EventLead>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, teams::PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let has_booth = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM event_booths WHERE event_id = $1 AND company_id = $2)",
+    )
+    .bind(event_id)
+    .bind(seat.company_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    if !has_booth {
+        warn!("User {} scanned a badge at event {} without a booth", auth_user.user_id, event_id);
+        return Err(AppError::Forbidden);
+    }
+
+    let candidate_id = sqlx::query_scalar::<_, Uuid>(
+        "SELECT user_id FROM event_rsvps WHERE event_id = $1 AND badge_code = $2",
+    )
+    .bind(event_id)
+    .bind(payload.badge_code.trim().to_uppercase())
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if let Some(job_id) = payload.job_id {
+        ensure_company_job(&app_state, seat.company_id, job_id).await?;
+    }
+
+    let lead_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO event_interactions
+            (event_id, company_id, candidate_id, recorded_by, job_id, notes, rating)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(event_id)
+    .bind(seat.company_id)
+    .bind(candidate_id)
+    .bind(auth_user.user_id)
+    .bind(payload.job_id)
+    .bind(&payload.notes)
+    .bind(payload.rating)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    info!("Lead {} recorded at event {} by {}", lead_id, event_id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "lead.record",
+        "lead",
+        &lead_id.to_string(),
+        json!({ "event_id": event_id, "candidate_id": candidate_id, "job_id": payload.job_id }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(fetch_lead(&app_state, seat.company_id, lead_id).await?))
+}
+
+async fn ensure_company_job(app_state: &AppState, company_id: i32, job_id: i32) -> AppResult<()> {
+    let owned = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM jobs WHERE id = $1 AND company_id = $2)",
+    )
+    .bind(job_id)
+    .bind(company_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    if owned {
+        Ok(())
+    } else {
+        Err(AppError::ValidationError(
+            "job_id must be one of your company's jobs".to_string(),
+        ))
+    }
+}
+
+async fn fetch_lead(app_state: &AppState, company_id: i32, lead_id: i32) -> AppResult<EventLead> {
+    sqlx::query_as::<_, EventLead>(&format!(
+        r#"
+        SELECT {} FROM event_interactions i
+        JOIN career_events e ON e.id = i.event_id
+        JOIN users u ON u.id = i.candidate_id
+        LEFT JOIN jobs j ON j.id = i.job_id
+        WHERE i.id = $1 AND i.company_id = $2
+        "#,
+        LEAD_COLUMNS
+    ))
+    .bind(lead_id)
+    .bind(company_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Lists the leads recorded at career fairs by the caller's company, newest first,
+/// leaving out candidates hidden by a block.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if
+/// the query fails.
+pub async fn list_leads(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<EventLead>>> {
+    let seat = teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await?;
+
+    let leads = sqlx::query_as::<_, EventLead>(&format!(
+        r#"
+        SELECT {} FROM event_interactions i
+        JOIN career_events e ON e.id = i.event_id
+        JOIN users u ON u.id = i.candidate_id
+        LEFT JOIN jobs j ON j.id = i.job_id
+        WHERE i.company_id = $1 AND NOT blocked_between(i.candidate_id, i.recorded_by, i.company_id)
+        ORDER BY i.created_at DESC
+        "#,
+        LEAD_COLUMNS
+    ))
+    .bind(seat.company_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(leads))
+}
+
+/// Invites a lead to apply to a job and notifies the candidate.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller has no pipeline seat
+/// - The lead does not belong to the caller's company, or a block hides the candidate (404)
+/// - Neither the lead nor the payload names a job, or the job is not the company's
+/// - Database operation fails
+pub async fn invite_lead(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(lead_id): Path<i32>,
+    payload: Option<Json<InviteLeadPayload>>,
+) -> AppResult<ApiResponse<EventLead>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, teams::PIPELINE_ROLES).await?;
+    let lead = fetch_lead(&app_state, seat.company_id, lead_id).await?;
+    if blocks::between(
+        &app_state.db_pool,
+        lead.candidate_id,
+        Some(auth_user.user_id),
+        Some(seat.company_id),
+    )
+    .await?
+    {
+        return Err(AppError::NotFound);
+    }
+
+    let job_id = payload.job_id.or(lead.job_id).ok_or_else(|| {
+        AppError::ValidationError("Choose a job to invite the candidate to".to_string())
+    })?;
+    ensure_company_job(&app_state, seat.company_id, job_id).await?;
+
+    let job_title = sqlx::query_scalar::<_, String>(
+        r#"
+        UPDATE event_interactions i
+        SET job_id = $2, lead_status = 'invited'
+        FROM jobs j
+        WHERE i.id = $1 AND j.id = $2
+        RETURNING j.job_title
+        "#,
+    )
+    .bind(lead_id)
+    .bind(job_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    let mut message = format!(
+        "{} met you at {} and invites you to apply for \"{}\".",
+        seat.company_name, lead.event_name, job_title
+    );
+    if let Some(note) = payload.message.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        message.push_str(&format!(" Message: {}", note));
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, title, message, type, sender_id, sender_company_id)
+        VALUES ($1, $2, $3, 'lead_invitation', $4, $5)
+        "#,
+    )
+    .bind(lead.candidate_id)
+    .bind("You're invited to apply")
+    .bind(message)
+    .bind(auth_user.user_id)
+    .bind(seat.company_id)
+    .execute(&app_state.db_pool)
+    .await?;
+
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "lead.invite",
+        "lead",
+        &lead_id.to_string(),
+        json!({ "job_id": job_id }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(fetch_lead(&app_state, seat.company_id, lead_id).await?))
+}
+
+/// Marks a lead as not being pursued.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the lead does not belong to the caller's
+/// company, or an error if the caller has no pipeline seat.
+pub async fn dismiss_lead(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(lead_id): Path<i32>,
+) -> AppResult<ApiResponse<EventLead>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, teams::PIPELINE_ROLES).await?;
+
+    let updated = sqlx::query(
+        "UPDATE event_interactions SET lead_status = $3 WHERE id = $1 AND company_id = $2",
+    )
+    .bind(lead_id)
+    .bind(seat.company_id)
+    .bind(LeadStatus::Dismissed)
+    .execute(&app_state.db_pool)
+    .await?
+    .rows_affected();
+    if updated == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "lead.dismiss",
+        "lead",
+        &lead_id.to_string(),
+        json!({}),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(fetch_lead(&app_state, seat.company_id, lead_id).await?))
+}
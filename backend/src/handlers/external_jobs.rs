@@ -4,11 +4,17 @@
 //! - ReliefWeb API (UN/NGO jobs)
 //! - Bangladesh government portals
 //! - Local job boards
+//!
+//! Every listing is screened for scam indicators before it is returned;
+//! suspicious listings are quarantined for moderators (see
+//! [`crate::moderation::screen_import`]).
 
 use crate::AppState;
 use crate::auth::AuthUser;
 use crate::errors::AppResult;
-use axum::{Json, extract::State};
+use crate::moderation;
+use crate::response::ApiResponse;
+use axum::extract::State;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
@@ -366,11 +372,44 @@ fn get_sample_local_jobs() -> Vec<ExternalJob> {
     ]
 }
 
+/// Drops listings that scam screening quarantined, unless a moderator has
+/// released them.
+async fn screen(app_state: &AppState, jobs: Vec<ExternalJob>) -> AppResult<Vec<ExternalJob>> {
+    let tuning = moderation::load_tuning(&app_state.db_pool).await?;
+    let mut screened = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let text = format!(
+            "Title: {}\nCompany: {}\nSalary: {}\n\n{}",
+            job.title,
+            job.company,
+            job.salary.as_deref().unwrap_or("-"),
+            job.description
+        );
+        if moderation::screen_import(
+            &app_state.db_pool,
+            &tuning,
+            &job.source,
+            &job.id,
+            &job.title,
+            &job.company,
+            &job.url,
+            &text,
+        )
+        .await?
+        {
+            screened.push(job);
+        } else {
+            warn!("Withholding quarantined listing {} from {}", job.id, job.source);
+        }
+    }
+    Ok(screened)
+}
+
 /// Get external jobs from all sources
 pub async fn get_external_jobs(
     _auth_user: AuthUser,
-    State(_app_state): State<AppState>,
-) -> AppResult<Json<Vec<ExternalJob>>> {
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ExternalJob>>> {
     info!("Fetching external jobs from multiple sources");
 
     let mut all_jobs = Vec::new();
@@ -397,50 +436,52 @@ pub async fn get_external_jobs(
     debug!("Added {} local job board listings", local_jobs.len());
     all_jobs.append(&mut local_jobs);
 
+    let all_jobs = screen(&app_state, all_jobs).await?;
     info!(
         "Returning {} total external jobs from all sources",
         all_jobs.len()
     );
 
-    Ok(Json(all_jobs))
+    Ok(ApiResponse::ok(all_jobs))
 }
 
 /// Get only ReliefWeb NGO jobs
 pub async fn get_ngo_jobs(
     _auth_user: AuthUser,
-    State(_app_state): State<AppState>,
-) -> AppResult<Json<Vec<ExternalJob>>> {
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ExternalJob>>> {
     info!("Fetching NGO jobs from ReliefWeb");
 
-    match fetch_reliefweb_jobs().await {
+    let jobs = match fetch_reliefweb_jobs().await {
         Ok(jobs) => {
             info!("Successfully fetched {} NGO jobs", jobs.len());
-            Ok(Json(jobs))
+            jobs
         }
         Err(e) => {
             error!("Failed to fetch NGO jobs: {}", e);
             // Return empty list on error
-            Ok(Json(Vec::new()))
+            return Ok(ApiResponse::ok(Vec::new()));
         }
-    }
+    };
+    Ok(ApiResponse::ok(screen(&app_state, jobs).await?))
 }
 
 /// Get government job portal listings
 pub async fn get_govt_jobs(
     _auth_user: AuthUser,
-    State(_app_state): State<AppState>,
-) -> AppResult<Json<Vec<ExternalJob>>> {
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ExternalJob>>> {
     info!("Fetching government job portal listings");
     let jobs = get_sample_govt_jobs();
-    Ok(Json(jobs))
+    Ok(ApiResponse::ok(screen(&app_state, jobs).await?))
 }
 
 /// Get local job board listings
 pub async fn get_local_jobs(
     _auth_user: AuthUser,
-    State(_app_state): State<AppState>,
-) -> AppResult<Json<Vec<ExternalJob>>> {
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ExternalJob>>> {
     info!("Fetching local job board listings");
     let jobs = get_sample_local_jobs();
-    Ok(Json(jobs))
+    Ok(ApiResponse::ok(screen(&app_state, jobs).await?))
 }
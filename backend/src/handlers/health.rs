@@ -0,0 +1,41 @@
+//! Health check handlers.
+
+use axum::extract::State;
+
+use super::types::HealthResponse;
+use crate::AppState;
+use crate::ai::circuit::CircuitState;
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+
+/// Reports whether the service can answer AI requests, with the circuit
+/// breaker of each configured provider. Answers `200 OK` whatever the
+/// status, so monitors read the status from the body.
+///
+/// # Endpoint
+/// `GET /api/health`
+pub async fn get_health(State(app_state): State<AppState>) -> AppResult<ApiResponse<HealthResponse>> {
+    let Some(ai) = app_state.ai_service.as_ref() else {
+        return Ok(ApiResponse::ok(HealthResponse {
+            status: "unavailable",
+            ai_mock_mode: false,
+            ai_providers: Vec::new(),
+        }));
+    };
+    let ai_providers = ai.circuits();
+    let open = ai_providers.iter().filter(|p| p.state == CircuitState::Open).count();
+    let status = if ai.is_mock() {
+        "ok"
+    } else if ai_providers.is_empty() || open == ai_providers.len() {
+        "unavailable"
+    } else if open == 0 {
+        "ok"
+    } else {
+        "degraded"
+    };
+    Ok(ApiResponse::ok(HealthResponse {
+        status,
+        ai_mock_mode: ai.is_mock(),
+        ai_providers,
+    }))
+}
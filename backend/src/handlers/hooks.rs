@@ -0,0 +1,102 @@
+//! Inbound webhook receiver and per-integration dispatch.
+//!
+//! Every integration posts to `/api/hooks/{integration}`. Requests are
+//! verified by [`crate::webhooks::WebhookVerifier`] before the body is parsed,
+//! then recorded in `audit_logs` and dispatched to the integration's handler.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use serde_json::{Value, json};
+use tracing::{debug, info};
+
+use crate::AppState;
+use crate::billing;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::webhooks::SignatureScheme;
+
+/// Integrations accepted by the webhook router, with the payload field that
+/// names the event type and the integration's signature format.
+const INTEGRATIONS: &[(&str, &str, SignatureScheme)] = &[
+    ("sms", "status", SignatureScheme::Standard),
+    ("payments", "type", SignatureScheme::Standard),
+    ("ats", "event", SignatureScheme::Standard),
+    ("stripe", "type", SignatureScheme::Stripe),
+];
+
+/// Receives a signed webhook from an external integration.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The integration is unknown or has no secret configured (404)
+/// - Signature, timestamp, or replay checks fail (401)
+/// - The body is not valid JSON (400)
+/// - Database operation fails
+pub async fn receive_webhook(
+    State(app_state): State<AppState>,
+    Path(integration): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<ApiResponse<Value>> {
+    let &(_, event_field, scheme) = INTEGRATIONS
+        .iter()
+        .find(|(name, _, _)| *name == integration)
+        .ok_or(AppError::NotFound)?;
+
+    app_state
+        .webhooks
+        .verify(&integration, scheme, &headers, &body)
+        .await?;
+
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+    let event = payload
+        .get(event_field)
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    info!("Webhook received: integration={}, event={}", integration, event);
+
+    sqlx::query(
+        "INSERT INTO audit_logs (action, target_type, target_id, metadata) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(format!("webhook.{}", integration))
+    .bind(&integration)
+    .bind(&event)
+    .bind(&payload)
+    .execute(&app_state.db_pool)
+    .await?;
+
+    match integration.as_str() {
+        "sms" => handle_sms_delivery(&event, &payload),
+        "payments" => handle_payment_event(&event, &payload),
+        "ats" => handle_ats_event(&event, &payload),
+        "stripe" => billing::handle_stripe_event(&app_state.db_pool, app_state.stripe.as_deref(), &event, &payload).await?,
+        _ => unreachable!("integration validated against INTEGRATIONS"),
+    }
+
+    Ok(ApiResponse::ok(json!({ "received": true, "event": event })))
+}
+
+/// SMS delivery receipts (`delivered`, `failed`, ...).
+fn handle_sms_delivery(status: &str, payload: &Value) {
+    let message_id = payload.get("message_id").and_then(|v| v.as_str()).unwrap_or("-");
+    debug!("SMS {} delivery status: {}", message_id, status);
+}
+
+/// Payment provider confirmations.
+fn handle_payment_event(event_type: &str, payload: &Value) {
+    let payment_id = payload.get("payment_id").and_then(|v| v.as_str()).unwrap_or("-");
+    debug!("Payment {} event: {}", payment_id, event_type);
+}
+
+/// Applicant tracking system events.
+fn handle_ats_event(event: &str, payload: &Value) {
+    let reference = payload.get("reference").and_then(|v| v.as_str()).unwrap_or("-");
+    debug!("ATS event {} for {}", event, reference);
+}
@@ -0,0 +1,73 @@
+//! Internship browsing for students.
+
+use axum::{
+    extract::{Query, State},
+};
+use tracing::info;
+
+use super::types::{InternshipListing, InternshipQueryParams};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::response::ApiResponse;
+use crate::internships;
+
+/// Lists open internship postings, soonest term first, leaving out those
+/// hidden by a block between the caller and the employer.
+///
+/// # Query Parameters
+///
+/// - `term` - Only internships whose term dates overlap this academic term
+///   (e.g. `2026-fall`); postings without term dates are excluded
+/// - `academic_credit` - Filter on the academic-credit flag
+/// - `limit` - Maximum results to return (default: 20, max: 100)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - `term` is not a valid academic term
+/// - Database operation fails
+pub async fn list_internships(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<InternshipQueryParams>,
+) -> AppResult<ApiResponse<Vec<InternshipListing>>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let term = params
+        .term
+        .as_deref()
+        .map(internships::normalize_term)
+        .transpose()?;
+
+    let listings = sqlx::query_as::<_, InternshipListing>(
+        r#"
+        SELECT
+            id, job_title, company, location, job_description, required_skills,
+            experience_level, job_type,
+            salary_min, salary_max, responsibilities, requirements, benefits,
+            employer_verified(company_id) AS employer_verified,
+            term_start, term_end, academic_credit, is_remote
+        FROM jobs
+        WHERE job_type = 'internship'
+          AND moderation_status = 'approved' AND same_sandbox(sandbox_key_id, $4) AND (expires_at IS NULL OR expires_at > NOW())
+          AND ($1::text IS NULL OR (
+              term_start IS NOT NULL
+              AND academic_term_range($1) && daterange(term_start, COALESCE(term_end, term_start), '[]')
+          ))
+          AND ($2::boolean IS NULL OR academic_credit = $2)
+          AND NOT blocked_between($4, posted_by, company_id)
+        ORDER BY term_start NULLS LAST, id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(&term)
+    .bind(params.academic_credit)
+    .bind(limit)
+    .bind(auth_user.user_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    info!("Internship search (term={:?}) returned {} results", term, listings.len());
+    Ok(ApiResponse::ok(listings))
+}
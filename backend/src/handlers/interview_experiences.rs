@@ -0,0 +1,211 @@
+//! Interview experience handlers: candidates sharing how a company's
+//! interviews went, the public search, and the admin moderation queue.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use validator::Validate;
+
+use super::types::{InterviewExperienceParams, InterviewExperiencePayload, ModerationDecisionPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::interview_experiences::{
+    self, CompanyExperiences, ExperienceDraft, ExperienceSearch, InterviewExperience, QueuedExperience,
+};
+use crate::models::{ModerationStatus, UserRole};
+use crate::response::ApiResponse;
+
+/// Longest round or question line, in characters.
+const MAX_ITEM_CHARS: usize = 1000;
+
+fn draft(payload: &InterviewExperiencePayload) -> AppResult<ExperienceDraft<'_>> {
+    if payload
+        .round_details
+        .iter()
+        .chain(&payload.questions)
+        .any(|item| item.chars().count() > MAX_ITEM_CHARS)
+    {
+        return Err(AppError::ValidationError(format!(
+            "Rounds and questions must be at most {} characters each",
+            MAX_ITEM_CHARS
+        )));
+    }
+    Ok(ExperienceDraft {
+        role: &payload.role,
+        interviewed_on: payload.interviewed_on,
+        rounds: payload.rounds,
+        round_details: &payload.round_details,
+        questions: &payload.questions,
+        summary: &payload.summary,
+        difficulty: payload.difficulty,
+        outcome: payload.outcome,
+    })
+}
+
+/// Shares an interview experience at a company. It is published once a
+/// moderator approves it.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, no `company_id` is given, the
+/// company does not exist (404), or the database operation fails.
+pub async fn create_interview_experience(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<InterviewExperiencePayload>,
+) -> AppResult<ApiResponse<InterviewExperience>> {
+    payload.validate()?;
+    let company_id = payload
+        .company_id
+        .ok_or_else(|| AppError::ValidationError("company_id is required".to_string()))?;
+    let experience =
+        interview_experiences::submit(&app_state.db_pool, auth_user.user_id, company_id, &draft(&payload)?).await?;
+    Ok(ApiResponse::ok(experience))
+}
+
+/// Lists the caller's interview experiences with their moderation status.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_my_interview_experiences(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<InterviewExperience>>> {
+    Ok(ApiResponse::ok(
+        interview_experiences::list_for_user(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Rewrites one of the caller's interview experiences and sends it back to
+/// moderation.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the post is not the caller's
+/// (404), or the database operation fails.
+pub async fn update_interview_experience(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(experience_id): Path<i32>,
+    Json(payload): Json<InterviewExperiencePayload>,
+) -> AppResult<ApiResponse<InterviewExperience>> {
+    payload.validate()?;
+    let experience =
+        interview_experiences::update(&app_state.db_pool, auth_user.user_id, experience_id, &draft(&payload)?)
+            .await?;
+    Ok(ApiResponse::ok(experience))
+}
+
+/// Deletes one of the caller's interview experiences.
+///
+/// # Errors
+///
+/// Returns an error if the post is not the caller's (404) or the database
+/// operation fails.
+pub async fn delete_interview_experience(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(experience_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    interview_experiences::delete(&app_state.db_pool, auth_user.user_id, experience_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": experience_id })))
+}
+
+/// Searches a company's published interview experiences, newest first,
+/// with a summary of all of them. Authors are not identified. No sign-in
+/// required.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_company_interview_experiences(
+    State(app_state): State<AppState>,
+    Path(company_id): Path<i32>,
+    Query(params): Query<InterviewExperienceParams>,
+) -> AppResult<ApiResponse<CompanyExperiences>> {
+    let filter = ExperienceSearch {
+        query: params.q.as_deref(),
+        role: params.role.as_deref(),
+        outcome: params.outcome,
+    };
+    let experiences = interview_experiences::search(
+        &app_state.db_pool,
+        company_id,
+        &filter,
+        params.limit.unwrap_or(20).clamp(1, 100),
+        params.offset.unwrap_or(0).max(0),
+    )
+    .await?;
+    Ok(ApiResponse::ok(experiences))
+}
+
+/// Lists interview experiences awaiting moderation, oldest first, with
+/// their authors.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_pending_interview_experiences(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<QueuedExperience>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(interview_experiences::list_pending(&app_state.db_pool).await?))
+}
+
+/// Publishes a pending interview experience.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the post is not pending
+/// (404), or the database operation fails.
+pub async fn approve_interview_experience(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(experience_id): Path<i32>,
+) -> AppResult<ApiResponse<InterviewExperience>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let experience = interview_experiences::moderate(
+        &app_state.db_pool,
+        auth_user.user_id,
+        experience_id,
+        ModerationStatus::Approved,
+        None,
+    )
+    .await?;
+    Ok(ApiResponse::ok(experience))
+}
+
+/// Rejects a pending interview experience with a reason shown to its
+/// author.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, no reason is given, the
+/// post is not pending (404), or the database operation fails.
+pub async fn reject_interview_experience(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(experience_id): Path<i32>,
+    Json(payload): Json<ModerationDecisionPayload>,
+) -> AppResult<ApiResponse<InterviewExperience>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let Some(reason) = payload.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()) else {
+        return Err(AppError::ValidationError(
+            "A reason is required when rejecting an interview experience".to_string(),
+        ));
+    };
+    let experience = interview_experiences::moderate(
+        &app_state.db_pool,
+        auth_user.user_id,
+        experience_id,
+        ModerationStatus::Rejected,
+        Some(&reason),
+    )
+    .await?;
+    Ok(ApiResponse::ok(experience))
+}
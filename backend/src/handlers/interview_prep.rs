@@ -0,0 +1,57 @@
+//! Interview prep pack handlers: reading the pack for an application's
+//! scheduled interview and regenerating it on demand.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+use super::types::PrepPackPayload;
+use crate::AppState;
+use crate::ai::types::AIProvider;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::interview_prep::{self, PrepPack};
+use crate::response::ApiResponse;
+
+/// Returns the prep pack for the interview of one of the caller's
+/// applications.
+///
+/// # Errors
+///
+/// Returns an error if the application is not the caller's or has no pack
+/// yet (404), or the database operation fails.
+pub async fn get_prep_pack(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+) -> AppResult<ApiResponse<PrepPack>> {
+    Ok(ApiResponse::ok(
+        interview_prep::load(&app_state.db_pool, auth_user.user_id, application_id).await?,
+    ))
+}
+
+/// Regenerates the prep pack for the scheduled interview of one of the
+/// caller's applications. A pack regenerated before its delivery time is
+/// the one delivered.
+///
+/// # Errors
+///
+/// Returns an error if the application is not the caller's (404), no
+/// interview is scheduled, the AI service is not configured, or the AI call
+/// fails.
+pub async fn regenerate_prep_pack(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+    payload: Option<Json<PrepPackPayload>>,
+) -> AppResult<ApiResponse<PrepPack>> {
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let provider = payload.and_then(|Json(p)| p.provider).unwrap_or(AIProvider::Gemini);
+    let pack =
+        interview_prep::generate(&app_state.db_pool, ai_service, provider, auth_user.user_id, application_id).await?;
+    Ok(ApiResponse::ok(pack))
+}
@@ -1,13 +1,15 @@
 //! Job recommendation handlers.
 
-use super::types::{JobQueryParams, JobRecommendation, PlatformLinks};
+use super::types::{JobQueryParams, JobRecommendation, JobSearchParams, JobSearchResult, PlatformLinks};
 use crate::AppState;
 use crate::ai_matching::{calculate_enhanced_match, generate_ai_explanation};
 use crate::auth::AuthUser;
-use crate::errors::AppResult;
-use crate::models::{CareerTrack, ExperienceLevel, Job, JobType, User};
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::geo::{self, Coordinates};
+use crate::skills;
+use crate::models::{CareerTrack, ExperienceLevel, Job, User};
 use axum::{
-    Json,
     extract::{Query, State},
 };
 use tracing::{debug, info};
@@ -15,7 +17,15 @@ use tracing::{debug, info};
 /// Gets job recommendations for the authenticated user.
 ///
 /// Retrieves jobs matching user's experience level (or specified level) and
-/// calculates match scores based on skill overlap.
+/// calculates match scores based on skill overlap. Matched skills are
+/// weighted by the user's proficiency ratings, decayed for time since last
+/// use (see [`crate::skills`]).
+///
+/// Jobs that fail the user's hard filters are never recommended: employment
+/// type, start date versus availability, work authorization for the job's
+/// country, and relocation for on-site jobs abroad (see
+/// `job_matches_candidate` in `schema.sql`). Jobs from employers or
+/// companies the candidate blocked, or who blocked them, are left out.
 ///
 /// # Query Parameters
 ///
@@ -41,7 +51,7 @@ pub async fn get_job_recommendations(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Query(params): Query<JobQueryParams>,
-) -> AppResult<Json<Vec<JobRecommendation>>> {
+) -> AppResult<ApiResponse<Vec<JobRecommendation>>> {
     info!(
         "Fetching job recommendations for user: {}",
         auth_user.user_id
@@ -76,44 +86,51 @@ pub async fn get_job_recommendations(
     // Match scoring will prioritize jobs that match user's experience level
     let jobs = if let Some(exp_level) = params.experience_level {
         // If explicitly filtered by experience level, honor that filter
-        sqlx::query_as!(
-            Job,
+        sqlx::query_as::<_, Job>(
             r#"
             SELECT 
                 id, job_title, company, location, job_description, required_skills,
-                experience_level as "experience_level: ExperienceLevel",
-                job_type as "job_type: JobType",
-                salary_min, salary_max, responsibilities, requirements, benefits
+                experience_level, job_type,
+                salary_min, salary_max, responsibilities, requirements, benefits,
+                employer_verified(company_id) AS employer_verified
             FROM jobs 
             WHERE experience_level = $1
+              AND moderation_status = 'approved' AND same_sandbox(sandbox_key_id, $3) AND (expires_at IS NULL OR expires_at > NOW())
+              AND job_matches_candidate(id, $3)
+              AND NOT blocked_between($3, posted_by, company_id)
             LIMIT $2
             "#,
-            exp_level as _,
-            limit
         )
+        .bind(exp_level)
+        .bind(limit)
+        .bind(auth_user.user_id)
         .fetch_all(&app_state.db_pool)
         .await?
     } else {
-        // Otherwise, return all jobs regardless of user's experience level
+        // Otherwise, return all open jobs regardless of user's experience level
         // Match scoring will rank them appropriately
-        sqlx::query_as!(
-            Job,
+        sqlx::query_as::<_, Job>(
             r#"
             SELECT 
                 id, job_title, company, location, job_description, required_skills,
-                experience_level as "experience_level: ExperienceLevel",
-                job_type as "job_type: JobType",
-                salary_min, salary_max, responsibilities, requirements, benefits
+                experience_level, job_type,
+                salary_min, salary_max, responsibilities, requirements, benefits,
+                employer_verified(company_id) AS employer_verified
             FROM jobs 
+            WHERE moderation_status = 'approved' AND same_sandbox(sandbox_key_id, $2) AND (expires_at IS NULL OR expires_at > NOW())
+              AND job_matches_candidate(id, $2)
+              AND NOT blocked_between($2, posted_by, company_id)
             LIMIT $1
             "#,
-            limit
         )
+        .bind(limit)
+        .bind(auth_user.user_id)
         .fetch_all(&app_state.db_pool)
         .await?
     };
 
-    // Calculate match scores
+    // Calculate match scores, weighting skills by self-assessed proficiency
+    let skill_weights = skills::load_weights(&app_state.db_pool, auth_user.user_id).await?;
     let mut recommendations: Vec<JobRecommendation> = Vec::new();
 
     for job in jobs {
@@ -134,6 +151,7 @@ pub async fn get_job_recommendations(
         let enhanced = calculate_enhanced_match(
             &user.skills,
             &job.required_skills,
+            &skill_weights,
             user.experience_level.as_ref().map(|e| match e {
                 ExperienceLevel::Fresher => "fresher",
                 ExperienceLevel::Junior => "junior",
@@ -236,5 +254,99 @@ pub async fn get_job_recommendations(
         debug!("Top match score: {:.1}%", recommendations[0].match_score);
     }
 
-    Ok(Json(recommendations))
+    Ok(ApiResponse::ok(recommendations))
+}
+
+/// Default search radius in kilometres.
+const DEFAULT_RADIUS_KM: f64 = 25.0;
+
+/// Largest accepted search radius in kilometres.
+const MAX_RADIUS_KM: f64 = 500.0;
+
+/// Searches open (approved, unexpired) jobs, optionally within a radius of a location.
+///
+/// `near` accepts a place name, which is geocoded (and cached), or a
+/// `lat,lng` pair. With `include_remote=true`, remote jobs are returned
+/// alongside those within `radius_km`. Results are ordered nearest first.
+/// Jobs hidden by a block between the caller and the employer are left out.
+///
+/// # Query Parameters
+///
+/// - `near` - Place name or `lat,lng` to search around
+/// - `radius_km` - Radius around `near` (default: 25, max: 500)
+/// - `include_remote` - Also include remote jobs (default: false)
+/// - `experience_level`, `job_type` - Optional filters
+/// - `limit` - Maximum results to return (default: 20, max: 100)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - `near` cannot be resolved to a location
+/// - The geocoding provider or a database operation fails
+pub async fn search_jobs(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<JobSearchParams>,
+) -> AppResult<ApiResponse<Vec<JobSearchResult>>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let radius_km = params.radius_km.unwrap_or(DEFAULT_RADIUS_KM).clamp(0.0, MAX_RADIUS_KM);
+
+    let origin = match params.near.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(near) => {
+            let coordinates = match Coordinates::parse(near) {
+                Some(coordinates) => Some(coordinates),
+                None => geo::geocode(&app_state.db_pool, app_state.geocoder.as_deref(), near).await?,
+            };
+            Some(coordinates.ok_or_else(|| {
+                AppError::BadRequest(format!("Could not find location '{}'", near))
+            })?)
+        }
+        None => None,
+    };
+
+    debug!(
+        "Job search: origin={:?}, radius_km={}, include_remote={}",
+        origin, radius_km, params.include_remote
+    );
+
+    let query = format!(
+        r#"
+        SELECT * FROM (
+            SELECT
+                id, job_title, company, location, job_description, required_skills,
+                experience_level, job_type,
+                salary_min, salary_max, responsibilities, requirements, benefits,
+                employer_verified(company_id) AS employer_verified,
+                is_remote,
+                ROUND(({distance})::numeric, 1)::float8 AS distance_km
+            FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+              AND ($3::experience_level IS NULL OR experience_level = $3)
+              AND ($4::job_type IS NULL OR job_type = $4)
+              AND NOT blocked_between($8, posted_by, company_id)
+        ) j
+        WHERE $1::float8 IS NULL
+           OR distance_km <= $5
+           OR ($6 AND is_remote)
+        ORDER BY distance_km NULLS LAST, id DESC
+        LIMIT $7
+        "#,
+        distance = geo::DISTANCE_KM_SQL
+    );
+
+    let jobs = sqlx::query_as::<_, JobSearchResult>(&query)
+        .bind(origin.map(|o| o.latitude))
+        .bind(origin.map(|o| o.longitude))
+        .bind(params.experience_level)
+        .bind(params.job_type)
+        .bind(radius_km)
+        .bind(params.include_remote)
+        .bind(limit)
+        .bind(auth_user.user_id)
+        .fetch_all(&app_state.db_pool)
+        .await?;
+
+    info!("Job search returned {} results", jobs.len());
+    Ok(ApiResponse::ok(jobs))
 }
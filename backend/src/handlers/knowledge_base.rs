@@ -0,0 +1,233 @@
+//! Knowledge base handlers: public browsing and admin article management.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use tracing::info;
+use validator::Validate;
+
+use super::types::{KbArticlePayload, KbArticleQueryParams};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::knowledge_base::{self, KbArticle, KbArticleSummary};
+use crate::models::UserRole;
+
+/// Columns of [`KbArticle`].
+const ARTICLE_COLUMNS: &str =
+    "id, slug, title, summary, content, tags, published, indexed_at, created_at, updated_at";
+
+/// Lists published articles, most recently updated first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_articles(
+    State(app_state): State<AppState>,
+    Query(params): Query<KbArticleQueryParams>,
+) -> AppResult<ApiResponse<Vec<KbArticleSummary>>> {
+    let search = params
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+
+    let articles = sqlx::query_as::<_, KbArticleSummary>(
+        r#"
+        SELECT id, slug, title, summary, tags, updated_at
+        FROM kb_articles
+        WHERE published
+          AND ($1::text IS NULL OR EXISTS (SELECT 1 FROM unnest(tags) t WHERE LOWER(t) = LOWER($1)))
+          AND ($2::text IS NULL OR title ILIKE $2 OR summary ILIKE $2)
+        ORDER BY updated_at DESC
+        LIMIT 200
+        "#,
+    )
+    .bind(params.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()))
+    .bind(search)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(articles))
+}
+
+/// Returns a published article by slug, or by ID as cited in mentor
+/// answers.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no published article matches, or an
+/// error if the database operation fails.
+pub async fn get_article(
+    State(app_state): State<AppState>,
+    Path(slug_or_id): Path<String>,
+) -> AppResult<ApiResponse<KbArticle>> {
+    let article = sqlx::query_as::<_, KbArticle>(&format!(
+        "SELECT {} FROM kb_articles WHERE published AND (slug = $1 OR id::text = $1)",
+        ARTICLE_COLUMNS
+    ))
+    .bind(slug_or_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(ApiResponse::ok(article))
+}
+
+/// Lists every article, drafts included (admins only).
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the query fails.
+pub async fn list_all_articles(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<KbArticle>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let articles = sqlx::query_as::<_, KbArticle>(&format!(
+        "SELECT {} FROM kb_articles ORDER BY updated_at DESC",
+        ARTICLE_COLUMNS
+    ))
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(articles))
+}
+
+/// Validates a payload and returns its normalized slug and tags.
+fn normalize(payload: &KbArticlePayload) -> AppResult<(String, Vec<String>)> {
+    payload.validate()?;
+
+    let slug = knowledge_base::slugify(payload.slug.as_deref().unwrap_or(&payload.title));
+    if slug.is_empty() {
+        return Err(AppError::ValidationError(
+            "Slug must contain at least one letter or digit".to_string(),
+        ));
+    }
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in payload.tags.iter().map(|t| t.trim().to_lowercase()) {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok((slug, tags))
+}
+
+/// Creates an article (admins only). Published articles are indexed for
+/// mentor answers in the background.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - Validation fails
+/// - The slug is already taken (409)
+/// - Database operation fails
+pub async fn create_article(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<KbArticlePayload>,
+) -> AppResult<ApiResponse<KbArticle>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let (slug, tags) = normalize(&payload)?;
+
+    let article = sqlx::query_as::<_, KbArticle>(&format!(
+        r#"
+        INSERT INTO kb_articles (slug, title, summary, content, tags, published, author_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}
+        "#,
+        ARTICLE_COLUMNS
+    ))
+    .bind(&slug)
+    .bind(payload.title.trim())
+    .bind(payload.summary.as_deref().map(str::trim).filter(|s| !s.is_empty()))
+    .bind(&payload.content)
+    .bind(&tags)
+    .bind(payload.published)
+    .bind(auth_user.user_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    knowledge_base::spawn_index(app_state.db_pool.clone(), app_state.ai_service.clone(), article.id);
+    info!("Admin {} created knowledge base article {}", auth_user.user_id, article.slug);
+    Ok(ApiResponse::ok(article))
+}
+
+/// Replaces an article (admins only) and re-indexes it in the background.
+/// Unpublishing removes it from mentor answers.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an admin
+/// - The article does not exist (404)
+/// - Validation fails
+/// - The slug is already taken (409)
+/// - Database operation fails
+pub async fn update_article(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(article_id): Path<i32>,
+    Json(payload): Json<KbArticlePayload>,
+) -> AppResult<ApiResponse<KbArticle>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let (slug, tags) = normalize(&payload)?;
+
+    let article = sqlx::query_as::<_, KbArticle>(&format!(
+        r#"
+        UPDATE kb_articles
+        SET slug = $2, title = $3, summary = $4, content = $5, tags = $6, published = $7,
+            indexed_at = NULL, updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        ARTICLE_COLUMNS
+    ))
+    .bind(article_id)
+    .bind(&slug)
+    .bind(payload.title.trim())
+    .bind(payload.summary.as_deref().map(str::trim).filter(|s| !s.is_empty()))
+    .bind(&payload.content)
+    .bind(&tags)
+    .bind(payload.published)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    knowledge_base::spawn_index(app_state.db_pool.clone(), app_state.ai_service.clone(), article.id);
+    info!("Admin {} updated knowledge base article {}", auth_user.user_id, article.slug);
+    Ok(ApiResponse::ok(article))
+}
+
+/// Deletes an article and its indexed chunks (admins only).
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the article does not exist, or an error
+/// if the user is not an admin or the database operation fails.
+pub async fn delete_article(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(article_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let deleted = sqlx::query("DELETE FROM kb_articles WHERE id = $1")
+        .bind(article_id)
+        .execute(&app_state.db_pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    info!("Admin {} deleted knowledge base article {}", auth_user.user_id, article_id);
+    Ok(ApiResponse::ok(json!({ "message": "Article deleted" })))
+}
@@ -1,17 +1,26 @@
 //! Learning resource and skill gap analysis handlers.
 
-use axum::{extract::{State, Path}, Json};
+use axum::extract::{State, Path, Query};
+use axum::response::Redirect;
 use tracing::{info, debug};
-use crate::models::{User, Job, LearningResource, ExperienceLevel, CareerTrack, JobType, CostIndicator};
+use crate::models::{User, Job, LearningResource, ExperienceLevel, CareerTrack, CostIndicator, SkillProgressStatus};
 use crate::errors::AppResult;
+use crate::response::ApiResponse;
 use crate::auth::AuthUser;
 use crate::AppState;
-use super::types::{ResourceRecommendation, SkillGapAnalysis};
+use crate::links;
+use crate::resource_clicks;
+use crate::resource_progress;
+use crate::skills;
+use super::types::{ResourceGoParams, ResourceRecommendation, SkillGapAnalysis};
 
 /// Gets learning resource recommendations for the authenticated user.
 /// 
 /// Recommends resources that teach skills the user doesn't currently have.
-/// Resources are scored based on how many new skills they offer.
+/// Resources are scored based on how many new skills they offer, then ranked
+/// by that score weighted with the ratings and skips of users who followed
+/// the same resource in a roadmap. Resources whose link is flagged broken
+/// are left out.
 /// 
 /// # Returns
 /// 
@@ -20,6 +29,7 @@ use super::types::{ResourceRecommendation, SkillGapAnalysis};
 /// - The resource details
 /// - Relevance score
 /// - Skills the resource can teach
+/// - Aggregated ratings and skip rate, if users have marked it
 /// 
 /// # Errors
 /// 
@@ -29,7 +39,7 @@ use super::types::{ResourceRecommendation, SkillGapAnalysis};
 pub async fn get_learning_recommendations(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
-) -> AppResult<Json<Vec<ResourceRecommendation>>> {
+) -> AppResult<ApiResponse<Vec<ResourceRecommendation>>> {
     info!("Fetching learning recommendations for user: {}", auth_user.user_id);
     
     // Get user profile
@@ -64,13 +74,25 @@ pub async fn get_learning_recommendations(
     .fetch_all(&app_state.db_pool)
     .await?;
 
+    let urls: Vec<String> = resources.iter().map(|r| r.url.clone()).collect();
+    let broken = links::broken_urls(&app_state.db_pool, &urls).await?;
+    let resources: Vec<_> = resources.into_iter().filter(|r| !broken.contains(&r.url)).collect();
+
+    // Roadmap marks are keyed by URL, or by text for resources without one
+    let keys: Vec<String> = resources
+        .iter()
+        .flat_map(|r| [r.url.clone(), r.title.trim().to_lowercase()])
+        .collect();
+    let mut quality = resource_progress::quality_by_key(&app_state.db_pool, &keys).await?;
+
     // Calculate relevance scores (case-insensitive comparison)
     let user_skills_lower: std::collections::HashSet<String> = user.skills
         .iter()
         .map(|s| s.to_lowercase())
         .collect();
 
-    let mut recommendations: Vec<ResourceRecommendation> = resources.into_iter()
+    let mut recommendations: Vec<ResourceRecommendation> = resources
+        .into_iter()
         .map(|resource| {
             // Create case-insensitive mapping for resource skills
             let resource_skills_lower: std::collections::HashMap<String, String> = resource.related_skills
@@ -92,21 +114,58 @@ pub async fn get_learning_recommendations(
                 0.0
             };
 
+            let quality = quality
+                .remove(&resource.url)
+                .or_else(|| quality.remove(&resource.title.trim().to_lowercase()));
+
             ResourceRecommendation {
                 resource,
                 relevance_score,
                 target_skills: new_skills,
+                quality,
             }
         })
         .filter(|r| r.relevance_score > 0.0) // Only show resources that teach new skills
         .collect();
 
-    recommendations.sort_by(|a, b| b.relevance_score.partial_cmp(&a.relevance_score).unwrap());
+    let ranking = |r: &ResourceRecommendation| r.relevance_score * r.quality.as_ref().map_or(1.0, |q| q.factor());
+    recommendations.sort_by(|a, b| ranking(b).total_cmp(&ranking(a)));
 
     let result: Vec<_> = recommendations.into_iter().take(10).collect();
     info!("Returning {} learning recommendations for user: {}", result.len(), auth_user.user_id);
     
-    Ok(Json(result))
+    Ok(ApiResponse::ok(result))
+}
+
+/// Follows a learning resource link: logs the click, then redirects to the
+/// resource with any affiliate parameters added.
+///
+/// Signing in is optional; clicks without a token are logged anonymously.
+///
+/// # Query Parameters
+///
+/// - `roadmap_id` - The user's roadmap the link was followed from
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The token is invalid
+/// - The resource does not exist (404)
+/// - Database operation fails
+pub async fn follow_resource(
+    auth_user: Option<AuthUser>,
+    State(app_state): State<AppState>,
+    Path(resource_id): Path<i32>,
+    Query(params): Query<ResourceGoParams>,
+) -> AppResult<Redirect> {
+    let destination = resource_clicks::follow(
+        &app_state.db_pool,
+        resource_id,
+        auth_user.map(|u| u.user_id),
+        params.roadmap_id,
+    )
+    .await?;
+    Ok(Redirect::temporary(&destination))
 }
 
 /// Analyzes skill gaps for a target role.
@@ -124,9 +183,11 @@ pub async fn get_learning_recommendations(
 /// - User's current skills
 /// - All required skills for the role
 /// - Skill gaps (what's missing)
-/// - Matching skills (what user already has)
-/// - Match percentage
-/// - Recommended learning resources to close gaps
+/// - Matching skills (what user already has, including skills completed in any roadmap)
+/// - Learning skills (gaps the user is working on in a roadmap)
+/// - Stale skills (matching skills unused long enough to need a refresher)
+/// - Match percentage, weighting matching skills by proficiency
+/// - Recommended learning resources to close gaps and refresh stale skills
 /// 
 /// # Errors
 /// 
@@ -137,7 +198,7 @@ pub async fn analyze_skill_gap(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Path(target_role): Path<String>,
-) -> AppResult<Json<SkillGapAnalysis>> {
+) -> AppResult<ApiResponse<SkillGapAnalysis>> {
     info!("Analyzing skill gap for user: {}, target_role: {}", 
           auth_user.user_id, target_role);
     
@@ -161,20 +222,20 @@ pub async fn analyze_skill_gap(
 
     // Find jobs matching the target role
     // Find learning resources that teach skills the user doesn't have yet
-    let jobs = sqlx::query_as!(
-        Job,
+    let jobs = sqlx::query_as::<_, Job>(
         r#"
         SELECT 
             id, job_title, company, location, job_description, required_skills,
-            experience_level as "experience_level: ExperienceLevel",
-            job_type as "job_type: JobType",
-            salary_min, salary_max, responsibilities, requirements, benefits
+            experience_level, job_type,
+            salary_min, salary_max, responsibilities, requirements, benefits,
+            employer_verified(company_id) AS employer_verified
         FROM jobs 
         WHERE LOWER(job_title) LIKE LOWER($1)
+          AND moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
         LIMIT 5
         "#,
-        format!("%{}%", target_role)
     )
+    .bind(format!("%{}%", target_role))
     .fetch_all(&app_state.db_pool)
     .await?;
 
@@ -187,9 +248,18 @@ pub async fn analyze_skill_gap(
     }
 
     let required_skills: Vec<String> = all_required_skills.into_iter().collect();
+
+    // Skills completed in any roadmap count as held; ones still being learned are reported
+    let progress = skills::load_progress(&app_state.db_pool, auth_user.user_id).await?;
+    let (known, learning): (Vec<_>, Vec<_>) = progress
+        .into_iter()
+        .partition(|p| p.status == SkillProgressStatus::Known);
+    let held_skills = skills::merge_skills(&user.skills, known.into_iter().map(|p| p.skill));
+    let learning_lower: std::collections::HashSet<String> =
+        learning.iter().map(|p| p.skill.to_lowercase()).collect();
     
     // Create case-insensitive skill sets for comparison
-    let user_skills_lower: std::collections::HashMap<String, String> = user.skills
+    let user_skills_lower: std::collections::HashMap<String, String> = held_skills
         .iter()
         .map(|s| (s.to_lowercase(), s.clone()))
         .collect();
@@ -213,14 +283,30 @@ pub async fn analyze_skill_gap(
         .filter_map(|lower_key| required_skills_lower.get(lower_key).cloned())
         .collect();
 
+    let learning_skills: Vec<String> = skill_gaps
+        .iter()
+        .filter(|s| learning_lower.contains(&s.to_lowercase()))
+        .cloned()
+        .collect();
+
+    // Rusty or beginner-level skills count as partial matches
+    let skill_weights = skills::load_weights(&app_state.db_pool, auth_user.user_id).await?;
+    let stale_skills: Vec<String> = matching_skills
+        .iter()
+        .filter(|s| skill_weights.is_stale(s))
+        .cloned()
+        .collect();
+
     let match_percentage = if !required_skills.is_empty() {
-        (matching_skills.len() as f64 / required_skills.len() as f64) * 100.0
+        let matched: f64 = matching_skills.iter().map(|s| skill_weights.weight(s)).sum();
+        (matched / required_skills.len() as f64) * 100.0
     } else {
         0.0
     };
 
-    // Find resources for skill gaps
-    let recommended_resources = if !skill_gaps.is_empty() {
+    // Find resources for skill gaps and stale skills
+    let refresh_skills: Vec<String> = skill_gaps.iter().chain(&stale_skills).cloned().collect();
+    let recommended_resources = if !refresh_skills.is_empty() {
         sqlx::query_as!(
             LearningResource,
             r#"
@@ -231,25 +317,33 @@ pub async fn analyze_skill_gap(
             WHERE related_skills && $1
             LIMIT 10
             "#,
-            &skill_gaps
+            &refresh_skills
         )
         .fetch_all(&app_state.db_pool)
         .await?
     } else {
         vec![]
     };
+    let urls: Vec<String> = recommended_resources.iter().map(|r| r.url.clone()).collect();
+    let broken = links::broken_urls(&app_state.db_pool, &urls).await?;
+    let recommended_resources: Vec<_> = recommended_resources
+        .into_iter()
+        .filter(|r| !broken.contains(&r.url))
+        .collect();
 
     info!("Skill gap analysis complete for user {}: {}/{} skills matched ({:.1}%), {} gaps identified",
           auth_user.user_id, matching_skills.len(), required_skills.len(), 
           match_percentage, skill_gaps.len());
     debug!("Skill gaps: {:?}", skill_gaps);
     
-    Ok(Json(SkillGapAnalysis {
-        user_skills: user.skills,
+    Ok(ApiResponse::ok(SkillGapAnalysis {
+        user_skills: held_skills,
         target_role,
         required_skills,
         skill_gaps,
         matching_skills,
+        learning_skills,
+        stale_skills,
         match_percentage,
         recommended_resources,
     }))
@@ -0,0 +1,549 @@
+//! Mentor session handlers: mentor profiles and slots, bookings, session
+//! notes and action items, the mentor's calendar connection, and payments
+//! and payouts for paid sessions.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    ActionItemListParams, ActionItemRoadmapPayload, ActionItemsPayload, CancelBookingPayload, MentorBookingPayload,
+    MentorCalendarPayload, MentorListParams, MentorProfilePayload, MentorSlotsPayload, PayoutRequestPayload,
+    ProcessPayoutPayload, ReconciliationParams, SessionNotesPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::events;
+use crate::mentor_calendars::{self, CalendarConnection, CalendarDraft};
+use crate::mentor_payments::{self, MentorBalance, MentorPayout, ReconciliationReport};
+use crate::mentorship::{self, MentorBooking, MentorProfile, MentorProfileDraft, MentorSlot};
+use crate::models::{CalendarProvider, UserRole};
+use crate::response::ApiResponse;
+use crate::session_notes::{self, ActionItem, ActionItemDraft, SessionNotes};
+
+/// Creates or updates the caller's mentor profile.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, a price is set without billing
+/// configured, or the database operation fails.
+pub async fn save_profile(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MentorProfilePayload>,
+) -> AppResult<ApiResponse<MentorProfile>> {
+    payload.validate()?;
+    let currency = payload.currency.as_deref().unwrap_or("usd");
+    if !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::ValidationError("Currency must be a 3-letter ISO code".to_string()));
+    }
+    let session_price_cents = payload.session_price_cents.unwrap_or(0);
+    if session_price_cents > 0 && app_state.stripe.is_none() {
+        return Err(AppError::ConfigurationError(
+            "Paid mentor sessions need billing to be configured".to_string(),
+        ));
+    }
+    let expertise: Vec<String> = payload
+        .expertise
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    let draft = MentorProfileDraft {
+        headline: &payload.headline,
+        bio: payload.bio.as_deref(),
+        expertise: &expertise,
+        session_minutes: payload.session_minutes.unwrap_or(30),
+        session_price_cents,
+        currency,
+        active: payload.active.unwrap_or(true),
+    };
+    let profile = mentorship::save_profile(&app_state.db_pool, auth_user.user_id, &draft).await?;
+    Ok(ApiResponse::ok(profile))
+}
+
+/// Lists active mentors, optionally by expertise.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_mentors(
+    _auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<MentorListParams>,
+) -> AppResult<ApiResponse<Vec<MentorProfile>>> {
+    let mentors = mentorship::list_mentors(
+        &app_state.db_pool,
+        params.expertise.as_deref(),
+        params.limit.unwrap_or(20).clamp(1, 100),
+        params.offset.unwrap_or(0).max(0),
+    )
+    .await?;
+    Ok(ApiResponse::ok(mentors))
+}
+
+/// A mentor's profile with their open slots.
+///
+/// # Errors
+///
+/// Returns an error if the user is not a mentor (404) or the database
+/// operation fails.
+pub async fn get_mentor(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(mentor_id): Path<Uuid>,
+) -> AppResult<ApiResponse<Value>> {
+    let profile = mentorship::profile(&app_state.db_pool, mentor_id).await?;
+    let own = mentor_id == auth_user.user_id;
+    if !profile.active && !own {
+        return Err(AppError::NotFound);
+    }
+    let slots = mentorship::upcoming_slots(&app_state.db_pool, mentor_id, own).await?;
+    Ok(ApiResponse::ok(json!({ "mentor": profile, "slots": slots })))
+}
+
+/// The caller's mentor profile with all their upcoming slots.
+///
+/// # Errors
+///
+/// Returns an error if the caller has no mentor profile (404) or the
+/// database operation fails.
+pub async fn get_my_profile(auth_user: AuthUser, State(app_state): State<AppState>) -> AppResult<ApiResponse<Value>> {
+    let profile = mentorship::profile(&app_state.db_pool, auth_user.user_id).await?;
+    let slots = mentorship::upcoming_slots(&app_state.db_pool, auth_user.user_id, true).await?;
+    Ok(ApiResponse::ok(json!({ "mentor": profile, "slots": slots })))
+}
+
+/// Publishes session slots for the caller.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the caller has no mentor profile
+/// (404), or the database operation fails.
+pub async fn add_slots(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MentorSlotsPayload>,
+) -> AppResult<ApiResponse<Vec<MentorSlot>>> {
+    payload.validate()?;
+    let slots = mentorship::add_slots(&app_state.db_pool, auth_user.user_id, &payload.starts).await?;
+    Ok(ApiResponse::ok(slots))
+}
+
+/// Removes one of the caller's slots that is not booked.
+///
+/// # Errors
+///
+/// Returns an error if the slot is not the caller's (404), is booked, or
+/// the database operation fails.
+pub async fn delete_slot(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(slot_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    mentorship::delete_slot(&app_state.db_pool, auth_user.user_id, slot_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": true })))
+}
+
+/// Books a mentor session slot.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the slot cannot be found (404) or
+/// booked, the mentor's calendar shows them busy, or the database operation
+/// fails.
+pub async fn book_session(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MentorBookingPayload>,
+) -> AppResult<ApiResponse<MentorBooking>> {
+    payload.validate()?;
+    let booking = mentorship::book(
+        &app_state.db_pool,
+        &app_state.calendars,
+        &app_state.meetings,
+        app_state.stripe.as_deref(),
+        app_state.document_cipher.as_deref(),
+        auth_user.user_id,
+        payload.slot_id,
+        &payload.topic,
+    )
+    .await?;
+    Ok(ApiResponse::ok(booking))
+}
+
+/// Lists the caller's sessions as mentor and mentee.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_bookings(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<MentorBooking>>> {
+    Ok(ApiResponse::ok(
+        mentorship::list_bookings(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// One of the caller's sessions.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404) or the
+/// database operation fails.
+pub async fn get_booking(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+) -> AppResult<ApiResponse<MentorBooking>> {
+    Ok(ApiResponse::ok(
+        mentorship::booking(&app_state.db_pool, auth_user.user_id, booking_id).await?,
+    ))
+}
+
+/// Downloads a calendar invite for one of the caller's sessions, with its
+/// meeting link.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404) or the
+/// database operation fails.
+pub async fn get_booking_invite(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+) -> AppResult<Response> {
+    let booking = mentorship::booking(&app_state.db_pool, auth_user.user_id, booking_id).await?;
+    let ics = events::to_ics("Mentor session", &[mentorship::calendar_entry(&booking)]);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"mentor-session-{}.ics\"", booking.id),
+            ),
+        ],
+        ics,
+    )
+        .into_response())
+}
+
+/// Cancels one of the caller's sessions before it starts, refunding a paid
+/// session per the cancellation policy.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the booking is not the caller's
+/// (404) or can no longer be cancelled, the refund fails, or the database
+/// operation fails.
+pub async fn cancel_booking(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+    payload: Option<Json<CancelBookingPayload>>,
+) -> AppResult<ApiResponse<MentorBooking>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+    let booking =
+        mentorship::cancel(
+            &app_state.db_pool,
+            app_state.stripe.as_deref(),
+            auth_user.user_id,
+            booking_id,
+            payload.reason.as_deref(),
+        )
+        .await?;
+    Ok(ApiResponse::ok(booking))
+}
+
+/// Notes and action items of one of the caller's sessions.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404) or the
+/// database operation fails.
+pub async fn get_session_notes(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+) -> AppResult<ApiResponse<SessionNotes>> {
+    Ok(ApiResponse::ok(
+        session_notes::session_notes(&app_state.db_pool, auth_user.user_id, booking_id).await?,
+    ))
+}
+
+/// Records the caller's notes for a session they mentored and marks it
+/// completed.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the booking is not the caller's
+/// (404), the caller is not its mentor (403), the session has not started,
+/// or the database operation fails.
+pub async fn save_session_notes(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+    Json(payload): Json<SessionNotesPayload>,
+) -> AppResult<ApiResponse<SessionNotes>> {
+    payload.validate()?;
+    let notes = session_notes::save_notes(&app_state.db_pool, auth_user.user_id, booking_id, &payload.notes).await?;
+    Ok(ApiResponse::ok(notes))
+}
+
+/// Assigns action items to the mentee of a session the caller mentored.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the booking is not the caller's
+/// (404), the caller is not its mentor (403), the session has not started,
+/// or the database operation fails.
+pub async fn add_action_items(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(booking_id): Path<i32>,
+    Json(payload): Json<ActionItemsPayload>,
+) -> AppResult<ApiResponse<Vec<ActionItem>>> {
+    payload.validate()?;
+    if payload.items.is_empty() || payload.items.len() > 20 {
+        return Err(AppError::ValidationError(
+            "Between 1 and 20 action items can be added at once".to_string(),
+        ));
+    }
+    let drafts: Vec<ActionItemDraft> = payload
+        .items
+        .iter()
+        .map(|item| ActionItemDraft {
+            title: &item.title,
+            details: item.details.as_deref(),
+            due_on: item.due_on,
+        })
+        .collect();
+    let items = session_notes::add_action_items(&app_state.db_pool, auth_user.user_id, booking_id, &drafts).await?;
+    Ok(ApiResponse::ok(items))
+}
+
+/// Removes an action item the caller assigned.
+///
+/// # Errors
+///
+/// Returns an error if the item is not one the caller assigned (404), the
+/// mentee already added it to a roadmap, or the database operation fails.
+pub async fn delete_action_item(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    session_notes::delete_action_item(&app_state.db_pool, auth_user.user_id, item_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": true })))
+}
+
+/// Action items assigned to the caller.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_action_items(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ActionItemListParams>,
+) -> AppResult<ApiResponse<Vec<ActionItem>>> {
+    Ok(ApiResponse::ok(
+        session_notes::action_items(&app_state.db_pool, auth_user.user_id, params.open.unwrap_or(false)).await?,
+    ))
+}
+
+/// Adds one of the caller's action items to a roadmap as a task.
+///
+/// # Errors
+///
+/// Returns an error if the item or roadmap is not the caller's (404), the
+/// item is already on a roadmap, the phase does not exist, or the database
+/// operation fails.
+pub async fn add_action_item_to_roadmap(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(item_id): Path<i32>,
+    payload: Option<Json<ActionItemRoadmapPayload>>,
+) -> AppResult<ApiResponse<ActionItem>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let item = session_notes::add_to_roadmap(
+        &app_state.db_pool,
+        auth_user.user_id,
+        item_id,
+        payload.roadmap_id,
+        payload.phase,
+    )
+    .await?;
+    Ok(ApiResponse::ok(item))
+}
+
+/// The caller's connected calendar and its sync state.
+///
+/// # Errors
+///
+/// Returns an error if no calendar is connected (404) or the database
+/// operation fails.
+pub async fn get_calendar(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<CalendarConnection>> {
+    Ok(ApiResponse::ok(
+        mentor_calendars::connection(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Connects the caller's Google or CalDAV calendar so their slots follow it.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the caller has no mentor profile
+/// (404), encryption at rest is not configured, the calendar cannot be read
+/// with the given credential, or the database operation fails.
+pub async fn connect_calendar(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MentorCalendarPayload>,
+) -> AppResult<ApiResponse<CalendarConnection>> {
+    payload.validate()?;
+    let calendar_url = match (payload.provider, payload.calendar_url.as_deref()) {
+        (_, Some(url)) if !url.trim().is_empty() => url,
+        (CalendarProvider::Google, _) => "primary",
+        (CalendarProvider::Caldav, _) => {
+            return Err(AppError::ValidationError(
+                "CalDAV calendars need a calendar_url".to_string(),
+            ));
+        }
+    };
+    let draft = CalendarDraft {
+        provider: payload.provider,
+        calendar_url,
+        username: payload.username.as_deref(),
+        secret: &payload.secret,
+    };
+    let connection = mentor_calendars::connect(
+        &app_state.db_pool,
+        &app_state.calendars,
+        app_state.document_cipher.as_deref(),
+        auth_user.user_id,
+        &draft,
+    )
+    .await?;
+    Ok(ApiResponse::ok(connection))
+}
+
+/// Disconnects the caller's calendar.
+///
+/// # Errors
+///
+/// Returns an error if no calendar is connected (404) or the database
+/// operation fails.
+pub async fn disconnect_calendar(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Value>> {
+    mentor_calendars::disconnect(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(json!({ "disconnected": true })))
+}
+
+/// The caller's mentor earnings per currency and their payouts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn get_payouts(auth_user: AuthUser, State(app_state): State<AppState>) -> AppResult<ApiResponse<Value>> {
+    let balances: Vec<MentorBalance> = mentor_payments::balances(&app_state.db_pool, auth_user.user_id).await?;
+    let payouts: Vec<MentorPayout> = mentor_payments::payouts(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(json!({ "balances": balances, "payouts": payouts })))
+}
+
+/// Requests a payout of the caller's available mentor earnings.
+///
+/// # Errors
+///
+/// Returns an error if validation fails, the balance is ambiguous or below
+/// the minimum payout, or the database operation fails.
+pub async fn request_payout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    payload: Option<Json<PayoutRequestPayload>>,
+) -> AppResult<ApiResponse<MentorPayout>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    payload.validate()?;
+    let payout =
+        mentor_payments::request_payout(&app_state.db_pool, auth_user.user_id, payload.currency.as_deref()).await?;
+    Ok(ApiResponse::ok(payout))
+}
+
+/// Lists mentor payouts awaiting an admin (admin only).
+///
+/// # Errors
+///
+/// Returns `AppError::Forbidden` for non-admins, or an error if the
+/// database operation fails.
+pub async fn list_pending_payouts(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<MentorPayout>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(mentor_payments::pending_payouts(&app_state.db_pool).await?))
+}
+
+/// Marks a mentor payout paid or rejects it (admin only).
+///
+/// # Errors
+///
+/// Returns `AppError::Forbidden` for non-admins, or an error if validation
+/// fails, the payout does not exist (404) or was already processed, or the
+/// database operation fails.
+pub async fn process_payout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(payout_id): Path<i32>,
+    Json(payload): Json<ProcessPayoutPayload>,
+) -> AppResult<ApiResponse<MentorPayout>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+    let payout = mentor_payments::process_payout(
+        &app_state.db_pool,
+        auth_user.user_id,
+        payout_id,
+        payload.status,
+        payload.reference.as_deref(),
+        payload.note.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(payout))
+}
+
+/// Reconciles mentor session payments, refunds, and payouts for a period
+/// (admin only).
+///
+/// # Errors
+///
+/// Returns `AppError::Forbidden` for non-admins, `AppError::BadRequest` if
+/// the period ends before it starts, or an error if the database operation
+/// fails.
+pub async fn payment_reconciliation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ReconciliationParams>,
+) -> AppResult<ApiResponse<ReconciliationReport>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let to = params.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = params.from.unwrap_or(to - chrono::Duration::days(30));
+    if from > to {
+        return Err(AppError::BadRequest("'from' must not be after 'to'".to_string()));
+    }
+    Ok(ApiResponse::ok(
+        mentor_payments::reconciliation(&app_state.db_pool, from, to).await?,
+    ))
+}
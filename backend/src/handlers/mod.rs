@@ -2,37 +2,128 @@
 //!
 //! This module contains all endpoint handlers organized by feature:
 //! - `auth` - Authentication and registration
+//! - `demo` - One-click demo login (`DEMO_MODE` only)
+//! - `health` - Service health with the AI providers' circuit breakers
+//! - `hooks` - Signed inbound webhooks from external integrations
+//! - `api_keys` - Integrators' API keys and their scopes
+//! - `sandbox` - Sandbox test users, jobs, and applications for integrators, behind a `sandbox`-scoped API key
 //! - `profile` - User profile management
+//! - `users` - Current user's profile as a resource, edited with JSON Merge Patch, with snapshots to revert AI-driven updates
+//! - `certifications` - Profile certifications, including ones read from certificate images, and issuer verification
 //! - `jobs` - Job recommendations
-//! - `learning` - Learning resources and skill gap analysis
+//! - `learning` - Learning resources, tracked resource links, and skill gap analysis
+//! - `skills` - Skill proficiency ratings, decay, per-skill learning progress, and skill/target role curation
+//! - `code_practice` - Linked coding practice handles and uploaded progress exports
 //! - `applications` - Application tracking
+//! - `policies` - Versioned terms of service and privacy policy, and their acceptance
+//! - `reports` - Blocking between candidates and employers, and reports of abuse
 //! - `progress` - Learning progress tracking
+//! - `employer` - Employer job postings and dashboard
+//! - `employer_verification` - Employer verification steps and the admin review queue
+//! - `screening` - Screening questions on postings and the employer's ranked applicant list
+//! - `talent_pools` - Saved candidate pools with tags, notes, alerts, and bulk invitations
+//! - `admin` - Job posting, quarantined feed listing, and user report moderation queues, scam screening tuning, support impersonation, resource link health, resource usage and affiliate parameters, rate limit policies, diagnostics, load, and AI eval trends
+//! - `team` - Company recruiter seats, invitations, and team activity
+//! - `companies` - Company profiles, employer branding drafts, and public company pages
+//! - `company_reviews` - Candidates' reviews of companies' hiring processes and the companies' replies
+//! - `interview_experiences` - Moderated, anonymous interview experiences per company and role
+//! - `interview_prep` - Interview prep packs delivered before a scheduled interview and regenerated on demand
+//! - `mentorship` - Mentor profiles, session slots and bookings with meeting links and invites, session notes and action items, mentors' calendar sync, and paid-session payouts
+//! - `disputes` - Disputes over mentor sessions and application decisions, their evidence, and admin arbitration
+//! - `debug_captures` - Consent to AI debug captures, flagging bad generations, and admin replay
+//! - `prompt_rollouts` - Thumbs up or down on AI results, and admin rollouts of new prompt versions
+//! - `internships` - Internship browsing by academic term
+//! - `orgs` - Institution organizations, cohorts, cohort membership, placement reports, career services guidance, bulk cohort roadmaps, and at-risk students
+//! - `events` - Career fairs, RSVPs, schedules, and booth leads
+//! - `offers` - Job offers, offer documents, and candidate responses
+//! - `offer_qa` - Questions about an uploaded offer letter or contract, answered from it alone
+//! - `negotiation` - Salary negotiation practice against an AI recruiter, with a debrief
+//! - `star_stories` - Behavioral interview STAR stories mined from the user's background, refined, and saved by competency
+//! - `conversations` - Mentor conversations, export, and AI summaries
+//! - `application_packages` - Multi-step agent preparing a full job application
+//! - `documents` - Saved documents the mentor retrieves from when answering
+//! - `knowledge_base` - Public career guides and FAQs, managed by admins
+//! - `wizards` - Resumable multi-step wizards such as the CV review
+//! - `billing` - Plans, subscriptions, and Stripe Checkout
+//! - `credits` - Credit balance and transaction history
+//! - `activity` - The current user's activity feed
+//! - `benchmark` - The current user's anonymous comparison with peers targeting the same role
+//! - `readiness` - The current user's readiness score for a target role, with its components
 //! - `types` - Shared request/response types
 
+mod activity;
+mod admin;
 mod ai;
+mod api_keys;
+mod application_packages;
 mod applications;
 mod auth;
+mod benchmark;
+mod billing;
+mod certifications;
+mod code_practice;
+mod companies;
+mod company_reviews;
+mod conversations;
+mod credits;
+mod demo;
+mod debug_captures;
+mod disputes;
+mod documents;
+mod employer;
+mod employer_verification;
+mod events;
 mod external_jobs;
+mod health;
+mod hooks;
+mod internships;
+mod interview_experiences;
+mod interview_prep;
 mod jobs;
+mod mentorship;
+mod knowledge_base;
 mod learning;
+mod negotiation;
 mod oauth;
+mod offer_qa;
+mod offers;
+mod orgs;
+mod policies;
 mod profile;
 mod progress;
+mod prompt_rollouts;
+mod readiness;
+mod reports;
+mod sandbox;
+mod screening;
+mod skills;
+mod star_stories;
+mod talent_pools;
+mod team;
 mod types;
+mod users;
+mod wizards;
 
 #[allow(unused_imports)]
 pub use types::*;
 
+use std::sync::Arc;
+
 use crate::AppState;
 use crate::errors::AppResult;
+use crate::load_shedding;
+use crate::versioning::{self, LegacyApi};
 use axum::{
-    Router,
-    routing::{get, post, put},
+    Router, middleware,
+    routing::{delete, get, post, put},
 };
 use tower_http::cors::{Any, CorsLayer};
 
 /// Creates the application router with all routes configured.
 ///
+/// API routes are served under `/api/v1`, and under `/api` as a deprecated
+/// alias of v1 (see [`crate::versioning`]).
+///
 /// # Arguments
 ///
 /// * `app_state` - The shared application state
@@ -44,104 +135,719 @@ pub fn create_router(app_state: AppState) -> Router {
     use tracing::info;
 
     info!("Setting up API routes:");
+    info!("  ✓ API versions: /api/v1 (current), /api (deprecated alias of v1)");
     info!("  ✓ Public routes: /, /api/register, /api/login");
+    info!("  ✓ Webhook routes: /api/hooks/{{integration}}");
+    info!("  ✓ API key and sandbox routes: /api/api-keys, /api/api-keys/{{id}}, /api/sandbox, /api/sandbox/users, /api/sandbox/jobs, /api/sandbox/applications");
     info!("  ✓ OAuth routes: /api/auth/google, /api/auth/github");
     info!("  ✓ Protected routes: profile (+ CV upload), jobs, learning, applications, progress");
+    info!("  ✓ Resource links: /api/resources/{{id}}/go");
+    info!("  ✓ Job search: /api/jobs?near=&radius_km=&include_remote=");
     info!("  ✓ External jobs: ReliefWeb NGO, govt portals, local boards");
-    info!("  ✓ AI routes: /api/ai/assist, /api/roadmaps");
+    info!("  ✓ AI routes: /api/ai/assist, /api/ai/preflight, /api/roadmaps, /api/ai/roadmaps/summary, /api/ai/roadmaps/{{id}}/analytics, /api/ai/roadmaps/{{id}}/primary, /api/ai/roadmaps/{{id}}/resources, /api/ai/roadmaps/{{id}}/export");
+    if crate::seed::demo_mode_enabled() {
+        info!("  ✓ Demo mode: /api/demo/login");
+    }
+    info!("  ✓ Billing routes: /api/billing/plans, /api/billing/subscription, /api/billing/checkout");
+    info!("  ✓ Current user routes: /api/users/me (GET, PATCH with JSON Merge Patch), /api/users/me/profile/history, /api/users/me/profile/revert/{{snapshot_id}}");
+    info!("  ✓ Credits routes: /api/users/me/credits");
+    info!("  ✓ Activity routes: /api/users/me/activity");
+    info!("  ✓ Benchmark routes: /api/users/me/benchmark");
+    info!("  ✓ Readiness routes: /api/users/me/readiness");
+    info!("  ✓ Skill routes: /api/users/me/skills, /api/users/me/skills/{{skill}}, /api/users/me/skill-progress");
+    info!("  ✓ Curation routes: /api/users/me/skills/bulk, /api/users/me/target-roles/{{role}}, /api/users/me/target-roles/bulk, /api/users/me/rejected-items");
+    info!("  ✓ Document routes: /api/users/me/documents, /api/users/me/documents/{{id}}");
+    info!("  ✓ Code practice routes: /api/users/me/code-practice, /api/users/me/code-practice/{{platform}}, /api/users/me/code-practice/{{platform}}/import");
+    info!("  ✓ Offer document Q&A routes: /api/offer-documents, /api/offer-documents/{{id}}, /api/ai/offer-documents/{{id}}/ask");
+    info!("  ✓ Negotiation practice routes: /api/ai/negotiation/start, /api/ai/negotiation/reply");
+    info!("  ✓ STAR story routes: /api/ai/star-stories, /api/ai/star-stories/{{id}}/refine, /api/star-stories, /api/star-stories/{{id}}");
+    info!("  ✓ Employer routes: /api/employer/jobs, /api/employer/jobs/{{id}}/renew, /api/employer/dashboard");
+    info!("  ✓ Policy routes: /api/policies, /api/policies/pending, /api/policies/acceptances, /api/policies/{{kind}}, /api/policies/{{kind}}/accept");
+    info!("  ✓ Blocking and report routes: /api/blocks, /api/blocks/{{id}}, /api/reports");
+    info!("  ✓ Admin routes: /api/admin/jobs/pending, /api/admin/jobs/{{id}}/approve, /api/admin/jobs/{{id}}/reject, /api/admin/imports/quarantine, /api/admin/imports/quarantine/{{id}}/release, /api/admin/imports/quarantine/{{id}}/reject, /api/admin/scam-screening, /api/admin/reports, /api/admin/reports/{{id}}/resolve, /api/admin/users/{{id}}/impersonate, /api/admin/impersonations, /api/admin/impersonations/{{id}}/end, /api/admin/policies, /api/admin/policies/{{id}}/acceptances, /api/admin/resource-links, /api/admin/resources/usage, /api/admin/resources/{{id}}/affiliate, /api/admin/kb/articles, /api/admin/diagnostics, /api/admin/load, /api/admin/rate-limits, /api/admin/evals");
+    info!("  ✓ Knowledge base routes: /api/kb/articles, /api/kb/articles/{{slug}}");
+    info!("  ✓ Employer verification routes: /api/employer/verification, /api/employer/verification/email, /api/employer/verification/email/confirm, /api/employer/verification/document, /api/employer/verification/submit, /api/admin/employer-verifications, /api/admin/employer-verifications/{{id}}/document, /api/admin/employer-verifications/{{id}}/approve, /api/admin/employer-verifications/{{id}}/reject");
+    info!("  ✓ Team routes: /api/employer/company, /api/employer/team (members, invitations, activity)");
+    info!("  ✓ Company routes: /api/companies/{{id}}, /api/employer/company/profile, /api/employer/company/branding, /api/ai/employer/branding");
+    info!("  ✓ Company review routes: /api/companies/{{id}}/reviews, /api/applications/{{id}}/review, /api/users/me/reviews, /api/reviews/{{id}}, /api/employer/reviews, /api/employer/reviews/{{id}}/reply");
+    info!("  ✓ Interview experience routes: /api/companies/{{id}}/interview-experiences, /api/interview-experiences, /api/interview-experiences/{{id}}, /api/users/me/interview-experiences, /api/admin/interview-experiences, /api/admin/interview-experiences/{{id}}/approve, /api/admin/interview-experiences/{{id}}/reject");
+    info!("  ✓ Mentor session routes: /api/mentors, /api/mentors/{{id}}, /api/mentors/me, /api/mentors/me/slots, /api/mentors/me/slots/{{id}}, /api/mentors/me/calendar, /api/mentor-bookings, /api/mentor-bookings/{{id}}, /api/mentor-bookings/{{id}}/cancel, /api/mentor-bookings/{{id}}/invite.ics, /api/mentor-bookings/{{id}}/notes, /api/mentor-bookings/{{id}}/action-items, /api/action-items, /api/action-items/{{id}}, /api/action-items/{{id}}/roadmap, /api/mentors/me/payouts, /api/admin/mentor-payouts, /api/admin/mentor-payouts/{{id}}, /api/admin/mentor-payments/reconciliation");
+    info!("  ✓ Prompt rollout routes: /api/ai/feedback, /api/admin/prompt-rollouts, /api/admin/prompt-rollouts/{{id}}, /api/admin/prompt-rollouts/{{id}}/promote, /api/admin/prompt-rollouts/{{id}}/rollback");
+    info!("  ✓ Debug capture routes: /api/users/me/debug-capture, /api/ai/captures, /api/ai/captures/{{id}}/flag, /api/admin/debug-captures, /api/admin/debug-captures/{{id}}, /api/admin/debug-captures/{{id}}/replay");
+    info!("  ✓ Dispute routes: /api/disputes, /api/disputes/{{id}}, /api/disputes/{{id}}/evidence, /api/disputes/{{id}}/evidence/{{evidence_id}}, /api/disputes/{{id}}/withdraw, /api/admin/disputes, /api/admin/disputes/{{id}}/review, /api/admin/disputes/{{id}}/resolve");
+    info!("  ✓ Interview prep routes: /api/applications/{{id}}/interview, /api/applications/{{id}}/interview.ics, /api/applications/{{id}}/prep-pack, /api/ai/applications/{{id}}/prep-pack");
+    info!("  ✓ Job description assistant: /api/ai/employer/job-description");
+    info!("  ✓ Screening routes: /api/jobs/{{id}}/screening-questions, /api/employer/jobs/{{id}}/screening-questions, /api/employer/jobs/{{id}}/applicants, /api/ai/employer/jobs/{{id}}/screening-questions");
+    info!("  ✓ Talent pool routes: /api/employer/talent-pools, /api/employer/talent-pools/alerts, /api/employer/talent-pools/{{id}}, /api/employer/talent-pools/{{id}}/members, /api/employer/talent-pools/{{id}}/invite");
+    info!("  ✓ Certification routes: /api/profile/certifications, /api/profile/certifications/scan, /api/profile/certifications/{{id}}/verify");
+    info!("  ✓ Internship routes: /api/internships, /api/profile/completeness");
+    info!("  ✓ Organization routes: /api/orgs, /api/orgs/{{id}}/cohorts, /api/orgs/{{id}}/placements, /api/orgs/{{id}}/persona, /api/orgs/{{id}}/cohorts/{{cid}}/roadmaps/bulk, /api/orgs/{{id}}/at-risk, /api/ai/orgs/{{id}}/persona/preview, /api/cohorts/join");
+    info!("  ✓ Career fair routes: /api/events, /api/orgs/{{id}}/events, /api/employer/leads");
+    info!("  ✓ Offer routes: /api/employer/applications/{{id}}/offers, /api/employer/offers, /api/offers/{{id}}/accept, /api/offers/{{id}}/decline");
+    info!("  ✓ Wizard routes: /api/wizards, /api/wizards/{{id}}");
+    info!("  ✓ Mentor conversation routes: /api/ai/ask-mentor/stream, /api/ai/conversations, /api/ai/conversations/{{id}}, /api/ai/conversations/{{id}}/messages, /api/ai/conversations/{{id}}/export, /api/ai/conversations/{{id}}/summarize");
+    info!("  ✓ Glossary route: /api/ai/explain-term");
+    info!("  ✓ AI usage route: /api/ai/usage");
+    info!("  ✓ Simplify route: /api/ai/simplify");
+    info!("  ✓ Health route: /api/health");
+    info!("  ✓ Application package routes: /api/ai/application-package, /api/ai/application-packages/{{id}}");
+
+    let legacy = Arc::new(LegacyApi::from_env());
+    // Hold signed-in users until they accept the policies in force, then shed
+    // uploads and reads when saturated; AI routes are limited in `ai_generation_routes`
+    let api = api_routes(app_state.clone())
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::policies::require_acceptance,
+        ))
+        // Check, restrict, and audit requests made with impersonation tokens
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::impersonation::guard,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.load_shedder.clone(),
+            load_shedding::shed,
+        ));
 
     Router::new()
-        // Public routes
         .route("/", get(root))
-        .route("/api/register", post(auth::register))
-        .route("/api/login", post(auth::login))
+        // Current version, and the unversioned alias kept for existing clients
+        .nest(
+            versioning::V1_PREFIX,
+            api.clone().layer(middleware::from_fn(versioning::v1)),
+        )
+        .nest(
+            versioning::LEGACY_PREFIX,
+            api.layer(middleware::from_fn_with_state(legacy, versioning::legacy)),
+        )
+        // Add CORS middleware
+        .layer(
+            CorsLayer::new()
+                .allow_origin(Any)
+                .allow_methods(Any)
+                .allow_headers(Any)
+                .allow_credentials(false),
+        )
+        .with_state(app_state)
+}
+
+/// Every API route, relative to the version prefix.
+fn api_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        // Public routes
+        .route("/register", post(auth::register))
+        .route("/login", post(auth::login))
+        .route("/demo/login", post(demo::demo_login))
+        .route("/health", get(health::get_health))
+        // Inbound webhooks (signature-verified, no JWT)
+        .route("/hooks/{integration}", post(hooks::receive_webhook))
+        // Sandbox for integrators (X-Api-Key with the sandbox scope, no JWT)
+        .route("/sandbox", get(sandbox::get_sandbox).delete(sandbox::purge_sandbox))
+        .route("/sandbox/users", post(sandbox::create_sandbox_user))
+        .route("/sandbox/jobs", post(sandbox::create_sandbox_job))
+        .route("/sandbox/applications", post(sandbox::create_sandbox_application))
+        // Public company pages
+        .route("/companies/{id}", get(companies::get_company_page))
+        .route("/companies/{id}/reviews", get(company_reviews::list_company_reviews))
+        .route(
+            "/companies/{id}/interview-experiences",
+            get(interview_experiences::list_company_interview_experiences),
+        )
+        // Knowledge base (public browsing)
+        .route("/kb/articles", get(knowledge_base::list_articles))
+        .route("/kb/articles/{slug}", get(knowledge_base::get_article))
         // OAuth routes
-        .route("/api/auth/google", get(oauth::google_login))
-        .route("/api/auth/google/callback", get(oauth::google_callback))
-        .route("/api/auth/github", get(oauth::github_login))
-        .route("/api/auth/github/callback", get(oauth::github_callback))
+        .route("/auth/google", get(oauth::google_login))
+        .route("/auth/google/callback", get(oauth::google_callback))
+        .route("/auth/github", get(oauth::github_login))
+        .route("/auth/github/callback", get(oauth::github_callback))
         // Protected routes - Profile
-        .route("/api/profile", get(profile::get_profile))
-        .route("/api/profile/complete", post(profile::complete_profile))
-        .route("/api/profile", put(profile::update_profile))
-        .route("/api/profile/cv/upload", post(profile::upload_cv))
-        .route("/api/profile/generate-cv", get(profile::generate_cv))
+        .route("/profile", get(profile::get_profile))
+        .route("/profile/complete", post(profile::complete_profile))
+        .route("/profile", put(profile::update_profile))
+        .route("/users/me", get(users::get_me).patch(users::patch_me))
+        .route("/users/me/profile/history", get(users::get_profile_history))
+        .route("/users/me/profile/revert/{snapshot_id}", post(users::revert_profile))
+        .route("/profile/cv/upload", post(profile::upload_cv))
+        .route("/profile/generate-cv", get(profile::generate_cv))
+        .route("/profile/completeness", get(profile::get_profile_completeness))
+        .route(
+            "/profile/certifications",
+            get(certifications::list_certifications).post(certifications::create_certification),
+        )
+        .route("/profile/certifications/scan", post(certifications::scan_certificate))
+        .route(
+            "/profile/certifications/{id}",
+            axum::routing::delete(certifications::delete_certification),
+        )
+        .route(
+            "/profile/certifications/{id}/verify",
+            post(certifications::verify_certification),
+        )
         // Protected routes - Job Recommendations
         .route(
-            "/api/jobs/recommendations",
+            "/jobs/recommendations",
             get(jobs::get_job_recommendations),
         )
         // Protected routes - External Jobs Integration
-        .route("/api/jobs/external", get(external_jobs::get_external_jobs))
-        .route("/api/jobs/ngo", get(external_jobs::get_ngo_jobs))
-        .route("/api/jobs/govt", get(external_jobs::get_govt_jobs))
-        .route("/api/jobs/local", get(external_jobs::get_local_jobs))
+        .route("/jobs", get(jobs::search_jobs))
+        .route(
+            "/jobs/{id}/screening-questions",
+            get(screening::get_job_screening_questions),
+        )
+        .route("/jobs/external", get(external_jobs::get_external_jobs))
+        .route("/jobs/ngo", get(external_jobs::get_ngo_jobs))
+        .route("/jobs/govt", get(external_jobs::get_govt_jobs))
+        .route("/jobs/local", get(external_jobs::get_local_jobs))
+        // Protected routes - Internships
+        .route("/internships", get(internships::list_internships))
         // Protected routes - Learning Resources
         .route(
-            "/api/learning/recommendations",
+            "/learning/recommendations",
             get(learning::get_learning_recommendations),
         )
+        // Resource links (sign-in optional), logged before redirecting
+        .route("/resources/{id}/go", get(learning::follow_resource))
         // Protected routes - Skill Gap Analysis
         .route(
-            "/api/skill-gap/{target_role}",
+            "/skill-gap/{target_role}",
             get(learning::analyze_skill_gap),
         )
+        // Protected routes - Skill Proficiency
+        .route("/users/me/skills", get(skills::list_skill_ratings))
+        .route(
+            "/users/me/skills/{skill}",
+            put(skills::rate_skill).delete(skills::remove_skill),
+        )
+        .route("/users/me/skills/bulk", post(skills::curate_skills))
+        .route("/users/me/target-roles/{role}", delete(skills::remove_target_role))
+        .route("/users/me/target-roles/bulk", post(skills::curate_target_roles))
+        .route("/users/me/rejected-items", get(skills::list_rejected_items))
+        .route("/users/me/rejected-items/{kind}/{value}", delete(skills::unreject_item))
+        .route("/users/me/skill-progress", get(skills::list_skill_progress))
+        // Protected routes - Saved documents for the mentor
+        .route(
+            "/users/me/documents",
+            get(documents::list_documents).post(documents::create_document),
+        )
+        .route(
+            "/users/me/documents/{id}",
+            axum::routing::delete(documents::delete_document),
+        )
+        // Protected routes - Coding practice progress
+        .route("/users/me/code-practice", get(code_practice::list_code_practice))
+        .route(
+            "/users/me/code-practice/{platform}",
+            put(code_practice::link_code_practice).delete(code_practice::unlink_code_practice),
+        )
+        .route(
+            "/users/me/code-practice/{platform}/import",
+            post(code_practice::import_code_practice),
+        )
+        // Protected routes - Saved STAR stories for behavioral interviews
+        .route("/star-stories", get(star_stories::list_star_stories))
+        .route(
+            "/star-stories/{id}",
+            put(star_stories::update_star_story).delete(star_stories::delete_star_story),
+        )
+        // Protected routes - Offer documents to ask questions about
+        .route(
+            "/offer-documents",
+            get(offer_qa::list_offer_documents).post(offer_qa::upload_offer_document),
+        )
+        .route(
+            "/offer-documents/{id}",
+            axum::routing::delete(offer_qa::delete_offer_document),
+        )
         // Protected routes - Application Tracking
-        .route("/api/applications", post(applications::create_application))
-        .route("/api/applications", get(applications::get_my_applications))
+        .route("/applications", post(applications::create_application))
+        .route("/applications", get(applications::get_my_applications))
         .route(
-            "/api/applications/{id}",
+            "/applications/{id}",
             put(applications::update_application),
         )
+        .route("/applications/{id}/interview", put(applications::schedule_interview))
+        .route("/applications/{id}/interview.ics", get(applications::get_interview_invite))
+        .route("/applications/{id}/prep-pack", get(interview_prep::get_prep_pack))
+        // Protected routes - Company reviews by candidates
+        .route("/applications/{id}/review", post(company_reviews::create_review))
+        .route("/users/me/reviews", get(company_reviews::list_my_reviews))
+        .route(
+            "/reviews/{id}",
+            put(company_reviews::update_review).delete(company_reviews::delete_review),
+        )
+        // Protected routes - Interview experiences
+        .route("/interview-experiences", post(interview_experiences::create_interview_experience))
+        .route(
+            "/interview-experiences/{id}",
+            put(interview_experiences::update_interview_experience)
+                .delete(interview_experiences::delete_interview_experience),
+        )
+        .route(
+            "/users/me/interview-experiences",
+            get(interview_experiences::list_my_interview_experiences),
+        )
+        .route(
+            "/admin/interview-experiences",
+            get(interview_experiences::list_pending_interview_experiences),
+        )
+        .route(
+            "/admin/interview-experiences/{id}/approve",
+            post(interview_experiences::approve_interview_experience),
+        )
+        .route(
+            "/admin/interview-experiences/{id}/reject",
+            post(interview_experiences::reject_interview_experience),
+        )
+        // Protected routes - Mentor sessions
+        .route("/mentors", get(mentorship::list_mentors))
+        .route("/mentors/me", get(mentorship::get_my_profile).put(mentorship::save_profile))
+        .route("/mentors/me/slots", post(mentorship::add_slots))
+        .route("/mentors/me/slots/{id}", delete(mentorship::delete_slot))
+        .route(
+            "/mentors/me/calendar",
+            get(mentorship::get_calendar)
+                .put(mentorship::connect_calendar)
+                .delete(mentorship::disconnect_calendar),
+        )
+        .route(
+            "/mentors/me/payouts",
+            get(mentorship::get_payouts).post(mentorship::request_payout),
+        )
+        .route("/mentors/{id}", get(mentorship::get_mentor))
+        .route(
+            "/mentor-bookings",
+            get(mentorship::list_bookings).post(mentorship::book_session),
+        )
+        .route("/mentor-bookings/{id}", get(mentorship::get_booking))
+        .route("/mentor-bookings/{id}/cancel", post(mentorship::cancel_booking))
+        .route("/mentor-bookings/{id}/invite.ics", get(mentorship::get_booking_invite))
+        .route(
+            "/mentor-bookings/{id}/notes",
+            get(mentorship::get_session_notes).put(mentorship::save_session_notes),
+        )
+        .route("/mentor-bookings/{id}/action-items", post(mentorship::add_action_items))
+        .route("/action-items", get(mentorship::list_action_items))
+        .route("/action-items/{id}", delete(mentorship::delete_action_item))
+        .route("/action-items/{id}/roadmap", post(mentorship::add_action_item_to_roadmap))
+        .route("/admin/mentor-payouts", get(mentorship::list_pending_payouts))
+        .route("/admin/mentor-payouts/{id}", post(mentorship::process_payout))
+        .route("/admin/mentor-payments/reconciliation", get(mentorship::payment_reconciliation))
+        // Protected routes - API keys
+        .route("/api-keys", get(api_keys::list_api_keys).post(api_keys::create_api_key))
+        .route("/api-keys/{id}", delete(api_keys::revoke_api_key))
+        // Protected routes - Disputes
+        .route("/disputes", get(disputes::list_disputes).post(disputes::open_dispute))
+        .route("/disputes/{id}", get(disputes::get_dispute))
+        .route("/disputes/{id}/evidence", post(disputes::add_evidence))
+        .route("/disputes/{id}/evidence/{evidence_id}", get(disputes::download_evidence))
+        .route("/disputes/{id}/withdraw", post(disputes::withdraw_dispute))
+        .route("/admin/disputes", get(disputes::list_dispute_queue))
+        .route("/admin/disputes/{id}/review", post(disputes::review_dispute))
+        .route("/admin/disputes/{id}/resolve", post(disputes::resolve_dispute))
+        // Protected routes - AI debug captures (replays are not metered)
+        .route(
+            "/users/me/debug-capture",
+            get(debug_captures::get_capture_consent).put(debug_captures::set_capture_consent),
+        )
+        .route("/ai/captures", get(debug_captures::list_my_captures))
+        .route("/ai/captures/{id}/flag", post(debug_captures::flag_capture))
+        .route("/admin/debug-captures", get(debug_captures::list_capture_queue))
+        .route(
+            "/admin/debug-captures/{id}",
+            get(debug_captures::get_capture).delete(debug_captures::delete_capture),
+        )
+        .route("/admin/debug-captures/{id}/replay", post(debug_captures::replay_capture))
+        // Protected routes - AI feedback and prompt rollouts
+        .route("/ai/feedback", post(prompt_rollouts::submit_ai_feedback))
+        .route(
+            "/admin/prompt-rollouts",
+            get(prompt_rollouts::list_prompt_rollouts).post(prompt_rollouts::start_prompt_rollout),
+        )
+        .route(
+            "/admin/prompt-rollouts/{id}",
+            get(prompt_rollouts::get_prompt_rollout).put(prompt_rollouts::update_prompt_rollout),
+        )
+        .route("/admin/prompt-rollouts/{id}/promote", post(prompt_rollouts::promote_prompt_rollout))
+        .route("/admin/prompt-rollouts/{id}/rollback", post(prompt_rollouts::roll_back_prompt_rollout))
+        // Policies (reading is public; acceptance requires auth)
+        .route("/policies", get(policies::list_policies))
+        .route("/policies/pending", get(policies::list_pending))
+        .route("/policies/acceptances", get(policies::list_my_acceptances))
+        .route("/policies/{kind}", get(policies::get_policy))
+        .route("/policies/{kind}/accept", post(policies::accept_policy))
+        // Protected routes - Blocking and reports
+        .route("/blocks", get(reports::list_blocks).post(reports::create_block))
+        .route("/blocks/{id}", delete(reports::delete_block))
+        .route("/reports", get(reports::list_my_reports).post(reports::create_report))
         // Protected routes - Progress Tracking
         .route(
-            "/api/progress/resource/{id}/start",
+            "/progress/resource/{id}/start",
             post(progress::start_resource),
         )
         .route(
-            "/api/progress/resource/{id}",
+            "/progress/resource/{id}",
             put(progress::update_resource_progress),
         )
-        .route("/api/progress", get(progress::get_my_progress))
-        // Protected routes - AI Actions
-        .route("/api/ai/action", post(ai::process_ai_action))
-        .route("/api/ai/extract-skills", post(ai::extract_and_save_skills))
-        .route("/api/ai/roadmap", post(ai::generate_roadmap))
-        .route("/api/ai/roadmaps", get(ai::get_my_roadmaps))
-        .route("/api/ai/roadmaps/{id}", get(ai::get_roadmap_by_id))
+        .route("/progress", get(progress::get_my_progress))
+        // Protected routes - Employer
+        .route("/employer/jobs", post(employer::create_job))
+        .route("/employer/dashboard", get(employer::get_dashboard))
+        .route("/employer/jobs/{id}/renew", post(employer::renew_job))
+        .route(
+            "/employer/jobs/{id}/screening-questions",
+            get(screening::get_screening_questions).put(screening::set_screening_questions),
+        )
+        .route("/employer/jobs/{id}/applicants", get(screening::list_applicants))
+        .route(
+            "/employer/talent-pools",
+            get(talent_pools::list_pools).post(talent_pools::create_pool),
+        )
+        .route("/employer/talent-pools/alerts", get(talent_pools::list_alerts))
+        .route(
+            "/employer/talent-pools/{id}",
+            get(talent_pools::get_pool)
+                .put(talent_pools::update_pool)
+                .delete(talent_pools::delete_pool),
+        )
+        .route("/employer/talent-pools/{id}/members", post(talent_pools::add_member))
+        .route(
+            "/employer/talent-pools/{id}/members/{candidate_id}",
+            put(talent_pools::update_member).delete(talent_pools::remove_member),
+        )
+        .route("/employer/talent-pools/{id}/invite", post(talent_pools::invite_pool))
+        // Protected routes - Admin moderation
+        .route("/admin/jobs/pending", get(admin::list_pending_jobs))
+        .route("/admin/jobs/{id}/approve", post(admin::approve_job))
+        .route("/admin/jobs/{id}/reject", post(admin::reject_job))
+        .route("/admin/imports/quarantine", get(admin::list_quarantined_imports))
+        .route("/admin/imports/quarantine/{id}/release", post(admin::release_import))
+        .route("/admin/imports/quarantine/{id}/reject", post(admin::reject_import))
+        .route("/admin/scam-screening", get(admin::get_scam_screening))
+        .route("/admin/reports", get(admin::list_reports))
+        .route("/admin/reports/{id}/resolve", post(admin::resolve_report))
+        .route("/admin/users/{id}/impersonate", post(admin::impersonate_user))
+        .route("/admin/impersonations", get(admin::list_impersonations))
+        .route("/admin/impersonations/{id}/end", post(admin::end_impersonation))
+        .route(
+            "/admin/policies",
+            get(policies::list_versions).post(policies::publish_policy),
+        )
+        .route("/admin/policies/{id}/acceptances", get(policies::list_acceptances))
+        .route("/admin/resource-links", get(admin::list_resource_links))
+        .route("/admin/resources/usage", get(admin::list_resource_usage))
+        .route(
+            "/admin/resources/{id}/affiliate",
+            put(admin::set_resource_affiliate_params),
+        )
+        .route("/admin/diagnostics", get(admin::get_diagnostics))
+        .route("/admin/load", get(admin::get_load))
+        .route("/admin/evals", get(admin::get_eval_trends))
+        .route(
+            "/admin/rate-limits",
+            get(admin::list_rate_limit_policies).put(admin::set_rate_limit_policy),
+        )
+        .route("/admin/rate-limits/{id}", delete(admin::delete_rate_limit_policy))
+        .route(
+            "/admin/kb/articles",
+            get(knowledge_base::list_all_articles).post(knowledge_base::create_article),
+        )
+        .route(
+            "/admin/kb/articles/{id}",
+            put(knowledge_base::update_article).delete(knowledge_base::delete_article),
+        )
+        // Protected routes - Employer teams
+        .route("/employer/company", post(team::create_company))
+        .route("/employer/company/profile", put(companies::update_company_profile))
+        .route(
+            "/employer/company/branding",
+            get(companies::get_branding).put(companies::save_branding_draft),
+        )
+        .route("/employer/company/branding/publish", post(companies::publish_branding))
+        .route("/employer/reviews", get(company_reviews::list_employer_reviews))
+        .route("/employer/reviews/{id}/reply", put(company_reviews::reply_to_review))
+        .route("/employer/team", get(team::get_team))
+        .route("/employer/team/activity", get(team::get_team_activity))
+        .route("/employer/team/invitations", post(team::invite_member))
+        .route(
+            "/employer/team/invitations/accept",
+            post(team::accept_invitation),
+        )
+        // Protected routes - Employer verification
+        .route("/employer/verification", get(employer_verification::get_verification))
         .route(
-            "/api/ai/roadmaps/{id}",
+            "/employer/verification/email",
+            post(employer_verification::request_verification_email),
+        )
+        .route(
+            "/employer/verification/email/confirm",
+            post(employer_verification::confirm_verification_email),
+        )
+        .route(
+            "/employer/verification/document",
+            post(employer_verification::upload_verification_document),
+        )
+        .route("/employer/verification/submit", post(employer_verification::submit_verification))
+        .route(
+            "/admin/employer-verifications",
+            get(employer_verification::list_pending_verifications),
+        )
+        .route(
+            "/admin/employer-verifications/{id}/document",
+            get(employer_verification::download_verification_document),
+        )
+        .route(
+            "/admin/employer-verifications/{id}/approve",
+            post(employer_verification::approve_verification),
+        )
+        .route(
+            "/admin/employer-verifications/{id}/reject",
+            post(employer_verification::reject_verification),
+        )
+        .route(
+            "/employer/team/invitations/{id}",
+            axum::routing::delete(team::revoke_invitation),
+        )
+        .route(
+            "/employer/team/members/{user_id}",
+            put(team::update_member_role).delete(team::remove_member),
+        )
+        // Protected routes - Institutions and cohorts
+        .route("/orgs", post(orgs::create_organization))
+        .route("/orgs/{id}/members", post(orgs::add_org_member))
+        .route(
+            "/orgs/{id}/cohorts",
+            get(orgs::list_cohorts).post(orgs::create_cohort),
+        )
+        .route("/orgs/{id}/cohorts/{cid}", put(orgs::update_cohort))
+        .route(
+            "/orgs/{id}/cohorts/{cid}/members",
+            get(orgs::list_cohort_members),
+        )
+        .route(
+            "/orgs/{id}/cohorts/{cid}/members/{user_id}",
+            axum::routing::delete(orgs::remove_cohort_member),
+        )
+        .route("/orgs/{id}/placements", get(orgs::get_placement_report))
+        .route(
+            "/orgs/{id}/cohorts/{cid}/roadmaps/bulk",
+            get(orgs::list_cohort_roadmap_batches).post(orgs::create_cohort_roadmaps),
+        )
+        .route(
+            "/orgs/{id}/cohorts/{cid}/roadmaps/bulk/{bid}",
+            get(orgs::get_cohort_roadmap_batch),
+        )
+        .route(
+            "/orgs/{id}/cohorts/{cid}/roadmaps/bulk/{bid}/retry",
+            post(orgs::retry_cohort_roadmap_batch),
+        )
+        .route("/orgs/{id}/at-risk", get(orgs::get_at_risk_students))
+        .route("/orgs/{id}/at-risk/nudge", post(orgs::nudge_at_risk_students))
+        .route(
+            "/orgs/{id}/persona",
+            get(orgs::get_org_persona).put(orgs::update_org_persona),
+        )
+        .route("/cohorts/join", post(orgs::join_cohort))
+        // Protected routes - Career fairs
+        .route("/orgs/{id}/events", post(events::create_event))
+        .route(
+            "/orgs/{id}/events/{eid}/sessions",
+            post(events::create_session),
+        )
+        .route("/orgs/{id}/events/{eid}/booths", post(events::add_booth))
+        .route("/events", get(events::list_events))
+        .route("/events/{id}", get(events::get_event))
+        .route(
+            "/events/{id}/rsvp",
+            post(events::rsvp_event).delete(events::cancel_rsvp),
+        )
+        .route("/events/{id}/schedule.ics", get(events::get_schedule))
+        .route(
+            "/events/{id}/interactions",
+            post(events::record_interaction),
+        )
+        .route("/employer/leads", get(events::list_leads))
+        .route("/employer/leads/{id}/invite", post(events::invite_lead))
+        .route("/employer/leads/{id}/dismiss", post(events::dismiss_lead))
+        // Protected routes - Offers
+        .route(
+            "/employer/applications/{id}/offers",
+            post(offers::create_offer),
+        )
+        .route("/employer/offers", get(offers::list_employer_offers))
+        .route(
+            "/employer/offers/{id}/documents",
+            post(offers::upload_offer_document),
+        )
+        .route(
+            "/applications/{id}/offers",
+            get(offers::get_application_offers),
+        )
+        .route("/offers/{id}/accept", post(offers::accept_offer))
+        .route("/offers/{id}/decline", post(offers::decline_offer))
+        .route(
+            "/offers/{id}/documents/{doc_id}",
+            get(offers::download_offer_document),
+        )
+        // Protected routes - Wizards
+        .route("/wizards", post(wizards::create_wizard))
+        .route("/wizards", get(wizards::list_wizards))
+        .route("/wizards/{id}", get(wizards::get_wizard))
+        .route("/wizards/{id}", put(wizards::update_wizard))
+        // Protected routes - Billing
+        .route("/billing/plans", get(billing::list_plans))
+        .route("/billing/subscription", get(billing::get_subscription))
+        .route("/billing/checkout", post(billing::create_checkout))
+        // Protected routes - Credits
+        .route("/users/me/credits", get(credits::get_my_credits))
+        .route("/users/me/activity", get(activity::get_my_activity))
+        .route("/users/me/benchmark", get(benchmark::get_my_benchmark))
+        .route("/users/me/readiness", get(readiness::get_my_readiness))
+        // Protected routes - AI generation (quota-metered)
+        .merge(ai_generation_routes(app_state.clone()))
+        // Protected routes - AI Roadmaps
+        // Protected routes - AI input preflight (not metered)
+        .route("/ai/preflight", post(ai::preflight_ai_action))
+        .route("/ai/roadmaps", get(ai::get_my_roadmaps))
+        .route("/ai/usage", get(ai::get_ai_usage))
+        .route("/ai/roadmaps/summary", get(ai::get_roadmap_summary))
+        .route("/ai/roadmaps/{id}", get(ai::get_roadmap_by_id))
+        .route(
+            "/ai/roadmaps/{id}",
             axum::routing::delete(ai::delete_roadmap),
         )
         .route(
-            "/api/ai/roadmaps/{id}/progress",
+            "/ai/roadmaps/{id}/progress",
             put(ai::update_roadmap_progress),
         )
-        // Protected routes - CV/Profile Assistant (Point 6)
         .route(
-            "/api/ai/generate-summary",
+            "/ai/roadmaps/{id}/analytics",
+            get(ai::get_roadmap_analytics),
+        )
+        .route(
+            "/ai/roadmaps/{id}/primary",
+            put(ai::set_primary_roadmap).delete(ai::clear_primary_roadmap),
+        )
+        .route("/ai/roadmaps/{id}/resources", get(ai::get_roadmap_resources))
+        .route("/ai/roadmaps/{id}/export", get(ai::export_roadmap))
+        .route(
+            "/ai/roadmaps/{id}/resources/{rid}",
+            put(ai::update_roadmap_resource),
+        )
+        // Protected routes - Mentor conversations and export (not metered)
+        .route(
+            "/ai/conversations",
+            get(conversations::list_conversations).post(conversations::create_conversation),
+        )
+        .route(
+            "/ai/conversations/{id}",
+            get(conversations::get_conversation).delete(conversations::delete_conversation),
+        )
+        .route(
+            "/ai/conversations/{id}/export",
+            get(conversations::export_conversation),
+        )
+        // Protected routes - Application package progress (steps are metered as they run)
+        .route(
+            "/ai/application-packages",
+            get(application_packages::list_application_packages),
+        )
+        .route(
+            "/ai/application-packages/{id}",
+            get(application_packages::get_application_package),
+        )
+}
+
+/// Routes that call an AI provider.
+///
+/// Each request is charged against the caller's daily plan quota before the
+/// handler runs (see [`crate::billing::enforce_ai_quota`]), once it has a
+/// slot under the AI concurrency limit (see [`crate::load_shedding`]).
+fn ai_generation_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        // AI Actions
+        .route("/ai/action", post(ai::process_ai_action))
+        .route("/ai/extract-skills", post(ai::extract_and_save_skills))
+        .route("/ai/roadmap", post(ai::generate_roadmap))
+        // CV/Profile Assistant (Point 6)
+        .route(
+            "/ai/generate-summary",
             post(ai::generate_professional_summary),
         )
         .route(
-            "/api/ai/improve-projects",
+            "/ai/improve-projects",
             post(ai::improve_project_descriptions),
         )
         .route(
-            "/api/ai/profile-suggestions",
+            "/ai/profile-suggestions",
             post(ai::get_profile_suggestions),
         )
-        // Protected routes - Career Mentor Chatbot (Point 5)
-        .route("/api/ai/ask-mentor", post(ai::ask_career_mentor))
-        .route("/api/ai/enhanced-mentor", post(ai::enhanced_career_mentor))
-        // Add CORS middleware
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any)
-                .allow_credentials(false),
+        .route(
+            "/ai/employer/branding",
+            post(ai::generate_employer_branding),
         )
-        .with_state(app_state)
+        .route(
+            "/ai/employer/job-description",
+            post(ai::draft_job_description),
+        )
+        .route(
+            "/ai/employer/jobs/{id}/screening-questions",
+            post(ai::generate_screening_questions),
+        )
+        .route(
+            "/ai/orgs/{id}/persona/preview",
+            post(ai::preview_org_persona),
+        )
+        // Career Mentor Chatbot (Point 5)
+        .route("/ai/ask-mentor", post(ai::ask_career_mentor))
+        .route("/ai/ask-mentor/stream", post(ai::ask_career_mentor_stream))
+        .route("/ai/explain-term", post(ai::explain_term))
+        .route("/ai/simplify", post(ai::simplify_content))
+        .route("/ai/enhanced-mentor", post(ai::enhanced_career_mentor))
+        .route(
+            "/ai/conversations/{id}/messages",
+            post(conversations::send_message),
+        )
+        .route(
+            "/ai/conversations/{id}/summarize",
+            post(conversations::summarize_conversation),
+        )
+        .route(
+            "/ai/offer-documents/{id}/ask",
+            post(offer_qa::ask_offer_document),
+        )
+        // Salary negotiation practice
+        .route("/ai/negotiation/start", post(negotiation::start_negotiation))
+        .route("/ai/negotiation/reply", post(negotiation::reply_negotiation))
+        // STAR story builder
+        .route("/ai/star-stories", post(star_stories::mine_star_stories))
+        .route(
+            "/ai/star-stories/{id}/refine",
+            post(star_stories::refine_star_story),
+        )
+        // Interview prep packs
+        .route(
+            "/ai/applications/{id}/prep-pack",
+            post(interview_prep::regenerate_prep_pack),
+        )
+        // Application package agent
+        .route(
+            "/ai/application-package",
+            post(application_packages::create_application_package),
+        )
+        .route(
+            "/ai/application-packages/{id}/retry",
+            post(application_packages::retry_application_package),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::billing::enforce_ai_quota,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.load_shedder,
+            load_shedding::shed_ai,
+        ))
 }
 
 /// Root endpoint handler.
@@ -0,0 +1,72 @@
+//! Salary negotiation practice handlers: the AI plays the recruiter behind
+//! one of the caller's offers, then debriefs the negotiation.
+
+use axum::{Json, extract::State};
+use validator::Validate;
+
+use super::types::{NegotiationReplyPayload, NegotiationStartPayload};
+use crate::AppState;
+use crate::ai::types::AIProvider;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::negotiation::{self, NegotiationTurn};
+use crate::response::ApiResponse;
+
+/// Starts a negotiation practice session on one of the caller's offers. The
+/// recruiter opens by presenting the offer.
+///
+/// # Errors
+///
+/// Returns an error if the offer is not on one of the caller's applications
+/// (404), the AI service is not configured, or the AI call fails.
+pub async fn start_negotiation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<NegotiationStartPayload>,
+) -> AppResult<ApiResponse<NegotiationTurn>> {
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let turn = negotiation::start(
+        &app_state.db_pool,
+        ai_service,
+        payload.provider.unwrap_or(AIProvider::Gemini),
+        auth_user.user_id,
+        payload.offer_id,
+    )
+    .await?;
+    Ok(ApiResponse::ok(turn))
+}
+
+/// Sends the caller's message to the recruiter and returns the reply. The
+/// session ends with a debrief when `end` is set, the recruiter concludes,
+/// or the turn limit is reached.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the session is not the
+/// caller's (404) or has ended, the AI service is not configured, or the AI
+/// call fails.
+pub async fn reply_negotiation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<NegotiationReplyPayload>,
+) -> AppResult<ApiResponse<NegotiationTurn>> {
+    payload.validate()?;
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let turn = negotiation::reply(
+        &app_state.db_pool,
+        ai_service,
+        payload.provider.unwrap_or(AIProvider::Gemini),
+        auth_user.user_id,
+        payload.session_id,
+        payload.message.as_deref(),
+        payload.end,
+    )
+    .await?;
+    Ok(ApiResponse::ok(turn))
+}
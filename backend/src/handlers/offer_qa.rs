@@ -0,0 +1,173 @@
+//! Offer document Q&A handlers: questions about an uploaded offer letter or
+//! contract, answered from that document alone.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+};
+use serde_json::{Value, json};
+use tracing::{error, warn};
+use validator::Validate;
+
+use super::profile::extract_pdf_text;
+use super::types::OfferQuestionPayload;
+use crate::AppState;
+use crate::ai::AIService;
+use crate::auth::AuthUser;
+use crate::encryption::DocumentCipher;
+use crate::errors::{AppError, AppResult};
+use crate::offer_qa::{self, OfferAnswer, OfferQaDocument};
+use crate::response::ApiResponse;
+
+/// Maximum offer document size.
+const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// The cipher and AI service offer Q&A needs.
+fn services(app_state: &AppState) -> AppResult<(&DocumentCipher, &AIService)> {
+    let cipher = app_state.document_cipher.as_deref().ok_or_else(|| {
+        AppError::ConfigurationError("Document encryption key not configured".to_string())
+    })?;
+    let ai = app_state
+        .ai_service
+        .as_deref()
+        .filter(|ai| ai.embeddings_available())
+        .ok_or_else(|| AppError::ConfigurationError("AI embeddings not configured".to_string()))?;
+    Ok((cipher, ai))
+}
+
+/// Uploads an offer letter or contract (PDF, up to 10MB) to ask questions
+/// about. The file and its text are encrypted at rest.
+///
+/// Expects a multipart form with the file in the `document` field and an
+/// optional `title` field (default: the file name).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Document encryption or embeddings are not configured
+/// - No document is provided, it is not a PDF, is too large, or has no text
+/// - Embedding, storing the file, or the database operation fails
+pub async fn upload_offer_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<OfferQaDocument>> {
+    let (cipher, ai) = services(&app_state)?;
+
+    let mut title = None;
+    let mut document = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        match field.name() {
+            Some("title") => {
+                let text = field.text().await.map_err(|e| {
+                    error!("Failed to read title field: {}", e);
+                    AppError::BadRequest("Invalid title".to_string())
+                })?;
+                title = Some(text.trim().to_string()).filter(|t| !t.is_empty());
+            }
+            Some("document") => {
+                // Keep only the final path component of the client's file name
+                let file_name = field
+                    .file_name()
+                    .and_then(|name| name.rsplit(['/', '\\']).next())
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?
+                    .chars()
+                    .take(255)
+                    .collect::<String>();
+                if !file_name.to_lowercase().ends_with(".pdf") {
+                    return Err(AppError::BadRequest("Only PDF documents are supported".to_string()));
+                }
+
+                let data = field.bytes().await.map_err(|e| {
+                    error!("Failed to read document data: {}", e);
+                    AppError::BadRequest("Failed to read file data".to_string())
+                })?;
+                if data.len() > MAX_DOCUMENT_SIZE {
+                    warn!("Offer document too large ({} bytes) for user {}", data.len(), auth_user.user_id);
+                    return Err(AppError::BadRequest("File size exceeds 10MB limit".to_string()));
+                }
+                document = Some((file_name, data));
+            }
+            _ => {}
+        }
+    }
+
+    let (file_name, data) = document.ok_or_else(|| AppError::BadRequest("No document provided".to_string()))?;
+    let text = extract_pdf_text(&data, auth_user.user_id)?;
+    let title = title.unwrap_or_else(|| file_name.clone());
+    let document = offer_qa::upload(
+        &app_state.db_pool,
+        &app_state.storage,
+        cipher,
+        ai,
+        auth_user.user_id,
+        &title,
+        &file_name,
+        &data,
+        &text,
+    )
+    .await?;
+    Ok(ApiResponse::ok(document))
+}
+
+/// Lists the caller's offer documents, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_offer_documents(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<OfferQaDocument>>> {
+    let documents = offer_qa::list(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(documents))
+}
+
+/// Deletes one of the caller's offer documents, its chunks, and its file.
+///
+/// # Errors
+///
+/// Returns an error if the document is not the caller's (404), or storage
+/// or the database operation fails.
+pub async fn delete_offer_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(document_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    offer_qa::delete(&app_state.db_pool, &app_state.storage, auth_user.user_id, document_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": document_id })))
+}
+
+/// Answers a question from one of the caller's offer documents only, with
+/// the passages the answer is based on. Neither the question nor the answer
+/// is stored.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the document is not the
+/// caller's (404), encryption or embeddings are not configured, or the AI
+/// call fails.
+pub async fn ask_offer_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(document_id): Path<i32>,
+    Json(payload): Json<OfferQuestionPayload>,
+) -> AppResult<ApiResponse<OfferAnswer>> {
+    payload.validate()?;
+    let (cipher, ai) = services(&app_state)?;
+    let answer = offer_qa::ask(
+        &app_state.db_pool,
+        cipher,
+        ai,
+        payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+        auth_user.user_id,
+        document_id,
+        payload.question.trim(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(answer))
+}
@@ -0,0 +1,717 @@
+//! Job offer handlers: extending offers, offer documents, and candidate responses.
+//!
+//! Employers (the job's poster or a pipeline seat at its company) extend an
+//! offer on an application. Making a new offer replaces any open one, so a
+//! counter-offer is answered with a revised offer. Accepting an offer records
+//! a placement and can withdraw the candidate's other active applications.
+
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    AcceptOfferPayload, CreateOfferPayload, DeclineOfferPayload, JobOffer, OfferDocument,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::models::{CompensationPeriod, OfferStatus};
+use crate::teams;
+
+/// Application statuses that count as still in progress.
+const ACTIVE_APPLICATION_STATUSES: &[&str] = &["applied", "in_review", "interview", "offer"];
+
+/// Document types accepted for offers, by file extension.
+const DOCUMENT_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+];
+
+const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+/// Columns of [`JobOffer`]; pending offers past their deadline read as expired.
+const OFFER_COLUMNS: &str = r#"
+    o.id, o.application_id, a.job_id, j.job_title, j.company,
+    a.user_id AS candidate_id, u.full_name AS candidate_name,
+    o.base_salary, o.currency, o.compensation, o.start_date, o.deadline, o.message,
+    CASE WHEN o.status = 'pending' AND o.deadline <= NOW() THEN 'expired'::offer_status ELSE o.status END AS status,
+    o.candidate_note, o.responded_at, o.created_at
+"#;
+
+const OFFER_JOINS: &str = r#"
+    FROM job_offers o
+    JOIN application_tracking a ON a.id = o.application_id
+    JOIN jobs j ON j.id = a.job_id
+    JOIN users u ON u.id = a.user_id
+"#;
+
+/// Who may see or manage an application and its offers.
+#[derive(sqlx::FromRow)]
+struct OfferAccess {
+    candidate_id: Uuid,
+    application_status: Option<String>,
+    job_title: String,
+    company: String,
+    posted_by: Option<Uuid>,
+    company_id: Option<i32>,
+}
+
+impl OfferAccess {
+    /// Whether the caller posted the job or holds a seat at its company.
+    fn is_employer(&self, user_id: Uuid, seat_company_id: Option<i32>) -> bool {
+        self.posted_by == Some(user_id)
+            || (seat_company_id.is_some() && self.company_id == seat_company_id)
+    }
+}
+
+const ACCESS_COLUMNS: &str = r#"
+    a.user_id AS candidate_id, a.status AS application_status,
+    j.job_title, j.company, j.posted_by, j.company_id
+"#;
+
+async fn application_access(app_state: &AppState, application_id: i32) -> AppResult<OfferAccess> {
+    sqlx::query_as::<_, OfferAccess>(&format!(
+        r#"
+        SELECT {} FROM application_tracking a
+        JOIN jobs j ON j.id = a.job_id
+        WHERE a.id = $1
+        "#,
+        ACCESS_COLUMNS
+    ))
+    .bind(application_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+async fn offer_access(app_state: &AppState, offer_id: i32) -> AppResult<OfferAccess> {
+    sqlx::query_as::<_, OfferAccess>(&format!(
+        r#"
+        SELECT {} FROM job_offers o
+        JOIN application_tracking a ON a.id = o.application_id
+        JOIN jobs j ON j.id = a.job_id
+        WHERE o.id = $1
+        "#,
+        ACCESS_COLUMNS
+    ))
+    .bind(offer_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Company of the caller's seat, optionally only for pipeline roles.
+async fn seat_company(app_state: &AppState, user_id: Uuid, pipeline_only: bool) -> AppResult<Option<i32>> {
+    Ok(teams::seat_for(&app_state.db_pool, user_id)
+        .await?
+        .filter(|seat| !pipeline_only || teams::PIPELINE_ROLES.contains(&seat.role))
+        .map(|seat| seat.company_id))
+}
+
+/// Attaches documents and first-year totals to offers.
+async fn complete_offers(app_state: &AppState, mut offers: Vec<JobOffer>) -> AppResult<Vec<JobOffer>> {
+    let ids: Vec<i32> = offers.iter().map(|o| o.id).collect();
+    let mut documents = sqlx::query_as::<_, OfferDocument>(
+        r#"
+        SELECT id, offer_id, file_name, content_type, size_bytes, created_at
+        FROM offer_documents
+        WHERE offer_id = ANY($1)
+        ORDER BY created_at
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    for offer in &mut offers {
+        offer.first_year_total = i64::from(offer.base_salary)
+            + offer
+                .compensation
+                .iter()
+                .map(|c| match c.period {
+                    CompensationPeriod::Annual | CompensationPeriod::OneTime => c.amount,
+                    CompensationPeriod::Monthly => c.amount * 12,
+                })
+                .sum::<i64>();
+        let (own, rest): (Vec<_>, Vec<_>) = documents.into_iter().partition(|d| d.offer_id == offer.id);
+        offer.documents = own;
+        documents = rest;
+    }
+    Ok(offers)
+}
+
+async fn fetch_offer(app_state: &AppState, offer_id: i32) -> AppResult<JobOffer> {
+    let offer = sqlx::query_as::<_, JobOffer>(&format!(
+        "SELECT {} {} WHERE o.id = $1",
+        OFFER_COLUMNS, OFFER_JOINS
+    ))
+    .bind(offer_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    complete_offers(app_state, vec![offer])
+        .await?
+        .pop()
+        .ok_or(AppError::NotFound)
+}
+
+async fn notify(app_state: &AppState, user_id: Uuid, title: &str, message: &str, kind: &str) -> AppResult<()> {
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, $4)")
+        .bind(user_id)
+        .bind(title)
+        .bind(message)
+        .bind(kind)
+        .execute(&app_state.db_pool)
+        .await?;
+    Ok(())
+}
+
+/// Extends an offer on an application and notifies the candidate.
+///
+/// Any open offer on the application is withdrawn and replaced, and the
+/// application moves to `offer`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The caller neither posted the job nor holds a pipeline seat at its company (404)
+/// - Validation fails, the deadline has passed, or the application is closed
+/// - Database operation fails
+pub async fn create_offer(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+    Json(payload): Json<CreateOfferPayload>,
+) -> AppResult<ApiResponse<JobOffer>> {
+    let access = application_access(&app_state, application_id).await?;
+    let company_id = seat_company(&app_state, auth_user.user_id, true).await?;
+    if !access.is_employer(auth_user.user_id, company_id) {
+        return Err(AppError::NotFound);
+    }
+
+    payload.validate()?;
+    if payload.deadline <= Utc::now() {
+        return Err(AppError::ValidationError(
+            "The offer deadline must be in the future".to_string(),
+        ));
+    }
+    if matches!(access.application_status.as_deref(), Some("accepted" | "withdrawn")) {
+        return Err(AppError::ValidationError(
+            "This application is closed".to_string(),
+        ));
+    }
+    let currency = payload.currency.as_deref().unwrap_or("USD").to_uppercase();
+    if !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::ValidationError(
+            "Currency must be a 3-letter ISO code".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE job_offers SET status = 'withdrawn'
+        WHERE application_id = $1 AND status IN ('pending', 'countered')
+        "#,
+    )
+    .bind(application_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let offer_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO job_offers
+            (application_id, created_by, base_salary, currency, compensation, start_date, deadline, message)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+    )
+    .bind(application_id)
+    .bind(auth_user.user_id)
+    .bind(payload.base_salary)
+    .bind(&currency)
+    .bind(sqlx::types::Json(&payload.compensation))
+    .bind(payload.start_date)
+    .bind(payload.deadline)
+    .bind(&payload.message)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE application_tracking SET status = 'offer' WHERE id = $1")
+        .bind(application_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("Offer {} made on application {} by {}", offer_id, application_id, auth_user.user_id);
+
+    notify(
+        &app_state,
+        access.candidate_id,
+        &format!("Offer from {}", access.company),
+        &format!(
+            "You have an offer for {} at {}. Please respond by {}.",
+            access.job_title,
+            access.company,
+            payload.deadline.format("%Y-%m-%d %H:%M UTC")
+        ),
+        "job_offer",
+    )
+    .await?;
+
+    if let Some(company_id) = access.company_id {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            company_id,
+            "offer.create",
+            "offer",
+            &offer_id.to_string(),
+            json!({ "application_id": application_id, "base_salary": payload.base_salary }),
+        )
+        .await;
+    }
+
+    Ok(ApiResponse::ok(fetch_offer(&app_state, offer_id).await?))
+}
+
+/// Lists offers on the caller's job postings and company jobs.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn list_employer_offers(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<JobOffer>>> {
+    let company_id = seat_company(&app_state, auth_user.user_id, false).await?;
+
+    let offers = sqlx::query_as::<_, JobOffer>(&format!(
+        "SELECT {} {} WHERE j.posted_by = $1 OR j.company_id = $2 ORDER BY o.created_at DESC",
+        OFFER_COLUMNS, OFFER_JOINS
+    ))
+    .bind(auth_user.user_id)
+    .bind(company_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(complete_offers(&app_state, offers).await?))
+}
+
+/// Attaches a document (PDF, DOC, or DOCX, up to 10MB) to an offer.
+///
+/// Expects a multipart form with the file in the `document` field.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The caller may not manage the offer (404)
+/// - No document is provided, or it has an unsupported type or is too large
+/// - Storing the file or the database operation fails
+pub async fn upload_offer_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(offer_id): Path<i32>,
+    mut multipart: Multipart,
+) -> AppResult<ApiResponse<OfferDocument>> {
+    let access = offer_access(&app_state, offer_id).await?;
+    let company_id = seat_company(&app_state, auth_user.user_id, true).await?;
+    if !access.is_employer(auth_user.user_id, company_id) {
+        return Err(AppError::NotFound);
+    }
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        AppError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        if field.name() != Some("document") {
+            continue;
+        }
+
+        // Keep only the final path component of the client's file name
+        let file_name = field
+            .file_name()
+            .and_then(|name| name.rsplit(['/', '\\']).next())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AppError::BadRequest("No filename provided".to_string()))?
+            .chars()
+            .take(255)
+            .collect::<String>();
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+        let (extension, content_type) = DOCUMENT_TYPES
+            .iter()
+            .find(|(ext, _)| Some(*ext) == extension.as_deref())
+            .ok_or_else(|| {
+                AppError::BadRequest("Only PDF, DOC, and DOCX documents are supported".to_string())
+            })?;
+
+        let data = field.bytes().await.map_err(|e| {
+            error!("Failed to read document data: {}", e);
+            AppError::BadRequest("Failed to read file data".to_string())
+        })?;
+        if data.len() > MAX_DOCUMENT_SIZE {
+            warn!("Offer document too large ({} bytes) for offer {}", data.len(), offer_id);
+            return Err(AppError::BadRequest(
+                "File size exceeds 10MB limit".to_string(),
+            ));
+        }
+
+        let storage_key = format!("offers/{}/{}.{}", offer_id, Uuid::new_v4(), extension);
+        app_state.storage.put(&storage_key, &data).await?;
+
+        let document = sqlx::query_as::<_, OfferDocument>(
+            r#"
+            INSERT INTO offer_documents
+                (offer_id, file_name, content_type, size_bytes, storage_key, uploaded_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, offer_id, file_name, content_type, size_bytes, created_at
+            "#,
+        )
+        .bind(offer_id)
+        .bind(&file_name)
+        .bind(content_type)
+        .bind(data.len() as i64)
+        .bind(&storage_key)
+        .bind(auth_user.user_id)
+        .fetch_one(&app_state.db_pool)
+        .await?;
+
+        info!("Document {} attached to offer {} by {}", document.id, offer_id, auth_user.user_id);
+        if let Some(company_id) = access.company_id {
+            teams::record_activity(
+                &app_state.db_pool,
+                auth_user.user_id,
+                company_id,
+                "offer.document",
+                "offer",
+                &offer_id.to_string(),
+                json!({ "document_id": document.id, "file_name": file_name }),
+            )
+            .await;
+        }
+        return Ok(ApiResponse::ok(document));
+    }
+
+    Err(AppError::BadRequest("No document provided".to_string()))
+}
+
+/// Downloads an offer document (the candidate or the employer).
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the document does not exist or the caller
+/// may not see the offer, or an error if reading it fails.
+pub async fn download_offer_document(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((offer_id, document_id)): Path<(i32, i32)>,
+) -> AppResult<Response> {
+    let access = offer_access(&app_state, offer_id).await?;
+    if access.candidate_id != auth_user.user_id {
+        let company_id = seat_company(&app_state, auth_user.user_id, false).await?;
+        if !access.is_employer(auth_user.user_id, company_id) {
+            return Err(AppError::NotFound);
+        }
+    }
+
+    let (file_name, content_type, storage_key) = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT file_name, content_type, storage_key FROM offer_documents WHERE id = $1 AND offer_id = $2",
+    )
+    .bind(document_id)
+    .bind(offer_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let bytes = app_state.storage.get(&storage_key).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name.replace(['"', '\\'], "")),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Lists the offers on one of the caller's applications.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the application is not the caller's, or an
+/// error if a query fails.
+pub async fn get_application_offers(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(application_id): Path<i32>,
+) -> AppResult<ApiResponse<Vec<JobOffer>>> {
+    let access = application_access(&app_state, application_id).await?;
+    if access.candidate_id != auth_user.user_id {
+        return Err(AppError::NotFound);
+    }
+
+    let offers = sqlx::query_as::<_, JobOffer>(&format!(
+        "SELECT {} {} WHERE o.application_id = $1 ORDER BY o.created_at DESC",
+        OFFER_COLUMNS, OFFER_JOINS
+    ))
+    .bind(application_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(complete_offers(&app_state, offers).await?))
+}
+
+/// An offer the candidate is answering, locked for the response.
+#[derive(sqlx::FromRow)]
+struct OpenOffer {
+    application_id: i32,
+    job_id: i32,
+    job_title: String,
+    company: String,
+    base_salary: i32,
+    currency: String,
+    status: OfferStatus,
+    deadline: DateTime<Utc>,
+    created_by: Option<Uuid>,
+    applied_at: Option<DateTime<Utc>>,
+    offered_at: Option<DateTime<Utc>>,
+}
+
+async fn lock_open_offer(
+    conn: &mut sqlx::PgConnection,
+    offer_id: i32,
+    user_id: Uuid,
+) -> AppResult<OpenOffer> {
+    let offer = sqlx::query_as::<_, OpenOffer>(
+        r#"
+        SELECT
+            o.application_id, a.job_id, j.job_title, j.company, o.base_salary, o.currency,
+            o.status, o.deadline, o.created_by, a.applied_at, o.created_at AS offered_at
+        FROM job_offers o
+        JOIN application_tracking a ON a.id = o.application_id
+        JOIN jobs j ON j.id = a.job_id
+        WHERE o.id = $1 AND a.user_id = $2
+        FOR UPDATE OF o
+        "#,
+    )
+    .bind(offer_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if offer.status != OfferStatus::Pending {
+        return Err(AppError::ValidationError(
+            "This offer is no longer open".to_string(),
+        ));
+    }
+    if offer.deadline <= Utc::now() {
+        return Err(AppError::ValidationError(format!(
+            "This offer expired on {}",
+            offer.deadline.format("%Y-%m-%d %H:%M UTC")
+        )));
+    }
+    Ok(offer)
+}
+
+/// Accepts an offer, records the placement, and notifies the employer.
+///
+/// With `close_other_applications`, the caller's other active applications
+/// are withdrawn and any open offers on them declined.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The offer is not on one of the caller's applications (404)
+/// - The offer is no longer pending or its deadline has passed
+/// - Database operation fails
+pub async fn accept_offer(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(offer_id): Path<i32>,
+    payload: Option<Json<AcceptOfferPayload>>,
+) -> AppResult<ApiResponse<Value>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let offer = lock_open_offer(&mut tx, offer_id, auth_user.user_id).await?;
+
+    sqlx::query(
+        "UPDATE job_offers SET status = 'accepted', candidate_note = $2, responded_at = NOW() WHERE id = $1",
+    )
+    .bind(offer_id)
+    .bind(&payload.note)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE application_tracking SET status = 'accepted' WHERE id = $1")
+        .bind(offer.application_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let placement_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO placements
+            (user_id, offer_id, job_id, job_title, company, base_salary, currency, applied_at, offered_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(offer_id)
+    .bind(offer.job_id)
+    .bind(&offer.job_title)
+    .bind(&offer.company)
+    .bind(offer.base_salary)
+    .bind(&offer.currency)
+    .bind(offer.applied_at)
+    .bind(offer.offered_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let mut closed_applications = Vec::new();
+    if payload.close_other_applications {
+        closed_applications = sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE application_tracking SET status = 'withdrawn'
+            WHERE user_id = $1 AND id <> $2 AND status = ANY($3)
+            RETURNING id
+            "#,
+        )
+        .bind(auth_user.user_id)
+        .bind(offer.application_id)
+        .bind(ACTIVE_APPLICATION_STATUSES)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE job_offers
+            SET status = 'declined', responded_at = NOW(),
+                candidate_note = COALESCE(candidate_note, 'Accepted another offer')
+            WHERE application_id = ANY($1) AND status IN ('pending', 'countered')
+            "#,
+        )
+        .bind(&closed_applications)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    info!(
+        "Offer {} accepted by {}; {} other applications closed",
+        offer_id,
+        auth_user.user_id,
+        closed_applications.len()
+    );
+
+    if let Some(employer_id) = offer.created_by {
+        let mut message = format!("Your offer for {} was accepted.", offer.job_title);
+        if let Some(note) = payload.note.as_deref().filter(|n| !n.trim().is_empty()) {
+            message.push_str(&format!(" Note: {}", note));
+        }
+        notify(&app_state, employer_id, "Offer accepted", &message, "offer_response").await?;
+    }
+
+    Ok(ApiResponse::ok(json!({
+        "message": "Offer accepted",
+        "offer_id": offer_id,
+        "placement_id": placement_id,
+        "closed_applications": closed_applications
+    })))
+}
+
+/// Declines an offer, optionally with a counter-offer, and notifies the
+/// employer.
+///
+/// A counter-offer leaves the application open so the employer can respond
+/// with a revised offer; a plain decline closes it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The offer is not on one of the caller's applications (404)
+/// - The offer is no longer pending or its deadline has passed
+/// - Database operation fails
+pub async fn decline_offer(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(offer_id): Path<i32>,
+    payload: Option<Json<DeclineOfferPayload>>,
+) -> AppResult<ApiResponse<Value>> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let counter_offer = payload
+        .counter_offer
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let (status, note) = match counter_offer {
+        Some(counter) => (OfferStatus::Countered, Some(counter)),
+        None => (OfferStatus::Declined, payload.reason.as_deref()),
+    };
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let offer = lock_open_offer(&mut tx, offer_id, auth_user.user_id).await?;
+
+    sqlx::query(
+        "UPDATE job_offers SET status = $2, candidate_note = $3, responded_at = NOW() WHERE id = $1",
+    )
+    .bind(offer_id)
+    .bind(status)
+    .bind(note)
+    .execute(&mut *tx)
+    .await?;
+
+    if status == OfferStatus::Declined {
+        sqlx::query("UPDATE application_tracking SET status = 'declined' WHERE id = $1")
+            .bind(offer.application_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    info!("Offer {} {:?} by {}", offer_id, status, auth_user.user_id);
+
+    if let Some(employer_id) = offer.created_by {
+        let (title, mut message) = match status {
+            OfferStatus::Countered => (
+                "Counter-offer received",
+                format!("The candidate countered your offer for {}.", offer.job_title),
+            ),
+            _ => (
+                "Offer declined",
+                format!("Your offer for {} was declined.", offer.job_title),
+            ),
+        };
+        if let Some(note) = note {
+            message.push_str(&format!(" Note: {}", note));
+        }
+        notify(&app_state, employer_id, title, &message, "offer_response").await?;
+    }
+
+    Ok(ApiResponse::ok(json!({
+        "message": match status {
+            OfferStatus::Countered => "Counter-offer sent",
+            _ => "Offer declined",
+        },
+        "offer_id": offer_id,
+        "status": status
+    })))
+}
@@ -0,0 +1,712 @@
+//! Institution organizations, cohorts, cohort membership, placement reports,
+//! career services guidance for AI prompts, bulk cohort roadmaps, and
+//! at-risk students.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    AddOrgMemberPayload, AtRiskParams, BulkRoadmapPayload, CohortMember, CohortSummary, CreateCohortPayload, CreateOrganizationPayload,
+    JoinCohortPayload, NudgeStudentsPayload, OrgPersonaPayload, PlacementReportParams, UpdateCohortPayload,
+};
+use crate::AppState;
+use crate::at_risk::{self, AtRiskReport, NudgeOutcome, Thresholds};
+use crate::auth::AuthUser;
+use crate::cohort_roadmaps::{self, NewBatch, RoadmapBatch};
+use crate::cohorts::{self, PROFILE_FIELDS_SQL, ProfileFields};
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::models::OrgRole;
+use crate::reporting::{self, ReportScope};
+use crate::tenant_config::{self, TenantConfig};
+
+/// Staff roles that can view cohorts and their students.
+const STAFF_ROLES: &[OrgRole] = &[OrgRole::Admin, OrgRole::Advisor];
+
+/// Creates an organization with the caller as its first admin.
+///
+/// # Errors
+///
+/// Returns an error if validation or the database operation fails.
+pub async fn create_organization(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateOrganizationPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    payload.validate()?;
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let org_id = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO organizations (name, created_by) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(payload.name.trim())
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO organization_members (org_id, user_id, role) VALUES ($1, $2, 'admin')")
+        .bind(org_id)
+        .bind(auth_user.user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("Organization {} created by {}", org_id, auth_user.user_id);
+
+    Ok(ApiResponse::ok(json!({
+        "message": "Organization created successfully",
+        "org_id": org_id
+    })))
+}
+
+/// Adds an existing user to the organization's staff, or changes their role
+/// (admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails
+/// - No user has the given email (404)
+/// - Database operation fails
+pub async fn add_org_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Json(payload): Json<AddOrgMemberPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+
+    let user_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE LOWER(email) = LOWER($1)")
+        .bind(payload.email.trim())
+        .fetch_optional(&app_state.db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (org_id, user_id, role)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (org_id, user_id) DO UPDATE SET role = EXCLUDED.role
+        "#,
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .bind(payload.role)
+    .execute(&app_state.db_pool)
+    .await?;
+
+    info!("User {} added to organization {} as {:?}", user_id, org_id, payload.role);
+
+    Ok(ApiResponse::ok(json!({
+        "user_id": user_id,
+        "role": payload.role
+    })))
+}
+
+/// Lists the organization's cohorts with member and placement counts (staff only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not on the organization's staff or the
+/// query fails.
+pub async fn list_cohorts(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+) -> AppResult<ApiResponse<Vec<CohortSummary>>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+
+    let cohorts = sqlx::query_as::<_, CohortSummary>(
+        r#"
+        SELECT
+            c.id, c.org_id, c.name, c.join_code, c.min_profile_completeness,
+            COUNT(m.user_id) AS member_count,
+            COUNT(m.user_id) FILTER (
+                WHERE EXISTS (SELECT 1 FROM placements p WHERE p.user_id = m.user_id)
+            ) AS placed_count,
+            c.created_at
+        FROM cohorts c
+        LEFT JOIN cohort_members m ON m.cohort_id = c.id
+        WHERE c.org_id = $1
+        GROUP BY c.id
+        ORDER BY c.created_at DESC
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(cohorts))
+}
+
+/// Creates a cohort with a fresh join code (admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails
+/// - Database operation fails
+pub async fn create_cohort(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Json(payload): Json<CreateCohortPayload>,
+) -> AppResult<ApiResponse<CohortSummary>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+
+    let cohort = sqlx::query_as::<_, CohortSummary>(
+        r#"
+        INSERT INTO cohorts (org_id, name, join_code, min_profile_completeness)
+        VALUES ($1, $2, $3, $4)
+        RETURNING
+            id, org_id, name, join_code, min_profile_completeness,
+            0::BIGINT AS member_count, 0::BIGINT AS placed_count, created_at
+        "#,
+    )
+    .bind(org_id)
+    .bind(payload.name.trim())
+    .bind(cohorts::new_join_code())
+    .bind(payload.min_profile_completeness.unwrap_or(0))
+    .fetch_one(&app_state.db_pool)
+    .await?;
+
+    info!("Cohort {} created in organization {} by {}", cohort.id, org_id, auth_user.user_id);
+    Ok(ApiResponse::ok(cohort))
+}
+
+/// Renames a cohort or changes its minimum profile completeness (admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails
+/// - The cohort does not belong to the organization (404)
+/// - Database operation fails
+pub async fn update_cohort(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateCohortPayload>,
+) -> AppResult<ApiResponse<CohortSummary>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+
+    let cohort = sqlx::query_as::<_, CohortSummary>(
+        r#"
+        UPDATE cohorts c
+        SET name = COALESCE($3, c.name),
+            min_profile_completeness = COALESCE($4, c.min_profile_completeness)
+        WHERE c.id = $1 AND c.org_id = $2
+        RETURNING
+            c.id, c.org_id, c.name, c.join_code, c.min_profile_completeness,
+            (SELECT COUNT(*) FROM cohort_members m WHERE m.cohort_id = c.id) AS member_count,
+            (
+                SELECT COUNT(*) FROM cohort_members m
+                WHERE m.cohort_id = c.id
+                  AND EXISTS (SELECT 1 FROM placements p WHERE p.user_id = m.user_id)
+            ) AS placed_count,
+            c.created_at
+        "#,
+    )
+    .bind(cohort_id)
+    .bind(org_id)
+    .bind(payload.name.as_deref().map(str::trim))
+    .bind(payload.min_profile_completeness)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    info!(
+        "Cohort {} updated by {}: min_profile_completeness={}",
+        cohort_id, auth_user.user_id, cohort.min_profile_completeness
+    );
+    Ok(ApiResponse::ok(cohort))
+}
+
+#[derive(sqlx::FromRow)]
+struct CohortMemberRow {
+    user_id: Uuid,
+    full_name: String,
+    email: String,
+    joined_at: Option<DateTime<Utc>>,
+    placed: bool,
+    #[sqlx(flatten)]
+    profile: ProfileFields,
+}
+
+/// Lists a cohort's students with their profile completeness and placement
+/// (staff only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not on the organization's staff
+/// - The cohort does not belong to the organization (404)
+/// - Database operation fails
+pub async fn list_cohort_members(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id)): Path<(i32, i32)>,
+) -> AppResult<ApiResponse<Vec<CohortMember>>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+
+    let min_completeness = sqlx::query_scalar::<_, i32>(
+        "SELECT min_profile_completeness FROM cohorts WHERE id = $1 AND org_id = $2",
+    )
+    .bind(cohort_id)
+    .bind(org_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let rows = sqlx::query_as::<_, CohortMemberRow>(&format!(
+        r#"
+        SELECT
+            u.id AS user_id, u.full_name, u.email, m.joined_at,
+            EXISTS (SELECT 1 FROM placements p WHERE p.user_id = u.id) AS placed,
+            {}
+        FROM cohort_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.cohort_id = $1
+        ORDER BY u.full_name
+        "#,
+        PROFILE_FIELDS_SQL
+    ))
+    .bind(cohort_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let members = rows
+        .into_iter()
+        .map(|row| {
+            let score = row.profile.score();
+            CohortMember {
+                user_id: row.user_id,
+                full_name: row.full_name,
+                email: row.email,
+                joined_at: row.joined_at,
+                profile_completeness: score,
+                missing: row.profile.missing(),
+                meets_requirement: score >= min_completeness,
+                placed: row.placed,
+            }
+        })
+        .collect();
+
+    Ok(ApiResponse::ok(members))
+}
+
+/// Removes a student from a cohort (admins only).
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the student is not in a cohort of this
+/// organization, or an error if the caller is not an admin.
+pub async fn remove_cohort_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id, user_id)): Path<(i32, i32, Uuid)>,
+) -> AppResult<ApiResponse<Value>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+
+    let removed = sqlx::query(
+        r#"
+        DELETE FROM cohort_members m
+        USING cohorts c
+        WHERE m.cohort_id = c.id AND c.id = $1 AND c.org_id = $2 AND m.user_id = $3
+        "#,
+    )
+    .bind(cohort_id)
+    .bind(org_id)
+    .bind(user_id)
+    .execute(&app_state.db_pool)
+    .await?
+    .rows_affected();
+    if removed == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    info!("User {} removed from cohort {} by {}", user_id, cohort_id, auth_user.user_id);
+    Ok(ApiResponse::ok(json!({ "message": "Student removed from cohort" })))
+}
+
+/// Joins the caller to the cohort with the given join code.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` for an unknown join code, or an error if the
+/// database operation fails.
+pub async fn join_cohort(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<JoinCohortPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    // Cohorts are production data, so sandbox test users cannot find them
+    let (cohort_id, name, min_profile_completeness) = sqlx::query_as::<_, (i32, String, i32)>(
+        "SELECT id, name, min_profile_completeness FROM cohorts WHERE join_code = $1 AND same_sandbox(NULL, $2)",
+    )
+    .bind(payload.join_code.trim().to_uppercase())
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    sqlx::query(
+        "INSERT INTO cohort_members (cohort_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(cohort_id)
+    .bind(auth_user.user_id)
+    .execute(&app_state.db_pool)
+    .await?;
+
+    info!("User {} joined cohort {}", auth_user.user_id, cohort_id);
+
+    Ok(ApiResponse::ok(json!({
+        "cohort_id": cohort_id,
+        "name": name,
+        "min_profile_completeness": min_profile_completeness
+    })))
+}
+
+/// Reports placement outcomes across the organization's cohorts (staff only).
+///
+/// Figures for groups below the minimum group size are suppressed. Pass
+/// `format=csv` to download the report as CSV.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not on the organization's staff
+/// - `cohort_id` is not a cohort of the organization (404)
+/// - The date range or format is invalid
+/// - Database operation fails
+pub async fn get_placement_report(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Query(params): Query<PlacementReportParams>,
+) -> AppResult<Response> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+
+    let csv = match params.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => {
+            return Err(AppError::ValidationError(format!(
+                "Unknown format '{}'; use json or csv",
+                other
+            )));
+        }
+    };
+    if let (Some(from), Some(to)) = (params.from, params.to)
+        && to < from
+    {
+        return Err(AppError::ValidationError(
+            "to must not be before from".to_string(),
+        ));
+    }
+
+    let org_name = sqlx::query_scalar::<_, String>("SELECT name FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .fetch_one(&app_state.db_pool)
+        .await?;
+    if let Some(cohort_id) = params.cohort_id {
+        let in_org = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM cohorts WHERE id = $1 AND org_id = $2)",
+        )
+        .bind(cohort_id)
+        .bind(org_id)
+        .fetch_one(&app_state.db_pool)
+        .await?;
+        if !in_org {
+            return Err(AppError::NotFound);
+        }
+    }
+
+    let scope = ReportScope {
+        org_id,
+        cohort_id: params.cohort_id,
+        from: params.from,
+        to: params.to,
+    };
+    let report =
+        reporting::placement_report(&app_state.db_pool, org_name, scope, reporting::min_group_size()).await?;
+
+    info!("Placement report for organization {} generated by {}", org_id, auth_user.user_id);
+
+    if csv {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"placements-org-{}.csv\"", org_id),
+                ),
+            ],
+            reporting::to_csv(&report),
+        )
+            .into_response());
+    }
+    Ok(ApiResponse::ok(report).into_response())
+}
+
+/// Returns the organization's career services guidance for mentor and
+/// roadmap prompts (staff only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not on the organization's staff or the
+/// query fails.
+pub async fn get_org_persona(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+) -> AppResult<ApiResponse<TenantConfig>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+    Ok(ApiResponse::ok(tenant_config::get(&app_state.db_pool, org_id).await?))
+}
+
+/// Saves the organization's career services guidance (admins only). It
+/// applies to its members' next mentor and roadmap prompts without a restart.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails
+/// - Database operation fails
+pub async fn update_org_persona(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Json(payload): Json<OrgPersonaPayload>,
+) -> AppResult<ApiResponse<TenantConfig>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+
+    let config = tenant_config::save(
+        &app_state.db_pool,
+        org_id,
+        auth_user.user_id,
+        payload.mentor_persona.as_deref(),
+        payload.roadmap_guidance.as_deref(),
+    )
+    .await?;
+    app_state.tenant_configs.invalidate().await;
+    Ok(ApiResponse::ok(config))
+}
+
+/// Queues a roadmap to one role for every student in the cohort and starts it
+/// in the background (admins only). Each roadmap starts from the student's
+/// own skills and follows the organization's roadmap guidance. Poll
+/// `GET /api/orgs/{id}/cohorts/{cid}/roadmaps/bulk/{bid}` for progress.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - Validation fails, or neither `target_role` nor `template_roadmap_id` is given
+/// - The cohort or template roadmap is not the organization's (404)
+/// - The cohort has no students
+/// - The AI service is not configured
+/// - Database operation fails
+pub async fn create_cohort_roadmaps(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id)): Path<(i32, i32)>,
+    Json(payload): Json<BulkRoadmapPayload>,
+) -> AppResult<ApiResponse<RoadmapBatch>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    payload.validate()?;
+    if app_state.ai_service.is_none() {
+        return Err(AppError::ConfigurationError("AI service not configured".to_string()));
+    }
+
+    let template = match payload.template_roadmap_id {
+        Some(roadmap_id) => Some(
+            sqlx::query_as::<_, (String, Option<i32>, Option<i32>)>(
+                r#"
+                SELECT r.target_role, r.timeframe_months, r.learning_hours_per_week
+                FROM career_roadmaps r
+                JOIN organization_members m ON m.user_id = r.user_id AND m.org_id = $2
+                WHERE r.id = $1
+                "#,
+            )
+            .bind(roadmap_id)
+            .bind(org_id)
+            .fetch_optional(&app_state.db_pool)
+            .await?
+            .ok_or(AppError::NotFound)?,
+        ),
+        None => None,
+    };
+    let target_role = payload
+        .target_role
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .or_else(|| template.as_ref().map(|(role, _, _)| role.clone()))
+        .ok_or_else(|| {
+            AppError::ValidationError("Either target_role or template_roadmap_id is required".to_string())
+        })?;
+
+    let batch = NewBatch {
+        target_role,
+        template_roadmap_id: payload.template_roadmap_id,
+        timeframe_months: payload
+            .timeframe_months
+            .or_else(|| template.as_ref().and_then(|(_, months, _)| *months))
+            .unwrap_or(6),
+        learning_hours_per_week: payload
+            .learning_hours_per_week
+            .or_else(|| template.as_ref().and_then(|(_, _, hours)| *hours))
+            .unwrap_or(10),
+        provider: payload.provider.unwrap_or(crate::ai::types::AIProvider::Gemini),
+    };
+    let batch_id = cohort_roadmaps::create(&app_state.db_pool, org_id, cohort_id, auth_user.user_id, batch).await?;
+    cohort_roadmaps::spawn_run(app_state.db_pool.clone(), app_state.ai_service.clone(), batch_id);
+
+    Ok(ApiResponse::ok(
+        cohort_roadmaps::load(&app_state.db_pool, org_id, cohort_id, batch_id).await?,
+    ))
+}
+
+/// Lists the cohort's bulk roadmap batches with their progress, newest first
+/// (staff only). Failed students are only listed by the single-batch endpoint.
+///
+/// # Errors
+///
+/// Returns an error if the caller is not on the organization's staff or the
+/// query fails.
+pub async fn list_cohort_roadmap_batches(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id)): Path<(i32, i32)>,
+) -> AppResult<ApiResponse<Vec<RoadmapBatch>>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+    Ok(ApiResponse::ok(
+        cohort_roadmaps::list(&app_state.db_pool, org_id, cohort_id).await?,
+    ))
+}
+
+/// Returns a bulk roadmap batch's progress and its failed students with
+/// their errors (staff only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not on the organization's staff, the
+/// cohort has no such batch (404), or the query fails.
+pub async fn get_cohort_roadmap_batch(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id, batch_id)): Path<(i32, i32, i32)>,
+) -> AppResult<ApiResponse<RoadmapBatch>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+    Ok(ApiResponse::ok(
+        cohort_roadmaps::load(&app_state.db_pool, org_id, cohort_id, batch_id).await?,
+    ))
+}
+
+/// Retries the failed students of a finished batch (admins only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin of the organization
+/// - The cohort has no such batch (404)
+/// - The batch is still running or no student failed (400)
+/// - Database operation fails
+pub async fn retry_cohort_roadmap_batch(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((org_id, cohort_id, batch_id)): Path<(i32, i32, i32)>,
+) -> AppResult<ApiResponse<RoadmapBatch>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, &[OrgRole::Admin]).await?;
+    cohort_roadmaps::retry(&app_state.db_pool, org_id, cohort_id, batch_id).await?;
+    cohort_roadmaps::spawn_run(app_state.db_pool.clone(), app_state.ai_service.clone(), batch_id);
+    info!("Roadmap batch {} retried by {}", batch_id, auth_user.user_id);
+
+    Ok(ApiResponse::ok(
+        cohort_roadmaps::load(&app_state.db_pool, org_id, cohort_id, batch_id).await?,
+    ))
+}
+
+/// Thresholds from the query, with defaults for those left out.
+fn thresholds(params: &AtRiskParams) -> Thresholds {
+    let defaults = Thresholds::default();
+    Thresholds {
+        stalled_weeks: params.stalled_weeks.unwrap_or(defaults.stalled_weeks),
+        min_profile_completeness: params.min_completeness.unwrap_or(defaults.min_profile_completeness),
+        application_weeks: params.application_weeks.unwrap_or(defaults.application_weeks),
+    }
+}
+
+/// Lists the unplaced students with a stalled or missing roadmap, a profile
+/// below the completeness threshold, or no recent job application, most
+/// flags first (staff only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not on the organization's staff
+/// - A threshold is out of range
+/// - Database operation fails
+pub async fn get_at_risk_students(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Query(params): Query<AtRiskParams>,
+) -> AppResult<ApiResponse<AtRiskReport>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+    params.validate()?;
+
+    let report = at_risk::report(&app_state.db_pool, org_id, params.cohort_id, &thresholds(&params)).await?;
+    Ok(ApiResponse::ok(report))
+}
+
+/// Sends each listed student who is at risk a notification with their next
+/// steps (staff only). Pass the dashboard's query so the same thresholds
+/// apply. Students nudged by the organization within the last day are
+/// skipped.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not on the organization's staff
+/// - Validation fails
+/// - Database operation fails
+pub async fn nudge_at_risk_students(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(org_id): Path<i32>,
+    Query(params): Query<AtRiskParams>,
+    Json(payload): Json<NudgeStudentsPayload>,
+) -> AppResult<ApiResponse<NudgeOutcome>> {
+    cohorts::require_org_role(&app_state.db_pool, org_id, auth_user.user_id, STAFF_ROLES).await?;
+    params.validate()?;
+    payload.validate()?;
+
+    let outcome = at_risk::nudge(
+        &app_state.db_pool,
+        org_id,
+        auth_user.user_id,
+        &payload.user_ids,
+        payload.note.as_deref(),
+        &thresholds(&params),
+    )
+    .await?;
+    Ok(ApiResponse::ok(outcome))
+}
@@ -0,0 +1,172 @@
+//! Policy handlers: reading and accepting the terms of service and privacy
+//! policy, and publishing new versions (admins).
+
+use std::net::SocketAddr;
+
+use axum::{
+    Extension, Json,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, header},
+};
+use validator::Validate;
+
+use super::types::{AcceptPolicyPayload, PolicyAcceptanceParams, PolicyQueryParams, PublishPolicyPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::models::{PolicyKind, UserRole};
+use crate::policies::{self, AcceptanceSource, PendingPolicy, PolicyAcceptance, PolicyDocument, PolicyVersion};
+use crate::response::ApiResponse;
+
+/// Default and maximum page size for acceptance exports.
+const DEFAULT_EXPORT_LIMIT: i64 = 500;
+const MAX_EXPORT_LIMIT: i64 = 5000;
+
+/// Lists the policies in force.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_policies(State(app_state): State<AppState>) -> AppResult<ApiResponse<Vec<PolicyDocument>>> {
+    Ok(ApiResponse::ok(policies::current(&app_state.db_pool).await?))
+}
+
+/// Returns a policy's version in force, or `?version=` for an earlier one.
+///
+/// # Errors
+///
+/// Returns an error if no such version is published (404) or the query fails.
+pub async fn get_policy(
+    State(app_state): State<AppState>,
+    Path(kind): Path<PolicyKind>,
+    Query(params): Query<PolicyQueryParams>,
+) -> AppResult<ApiResponse<PolicyDocument>> {
+    Ok(ApiResponse::ok(
+        policies::get(&app_state.db_pool, kind, params.version).await?,
+    ))
+}
+
+/// Lists the policy versions the caller still has to accept.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_pending(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PendingPolicy>>> {
+    Ok(ApiResponse::ok(
+        policies::pending(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Accepts the version of a policy in force, recording the caller's address
+/// and user agent.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The policy has no published version (404)
+/// - `version` is not the version in force
+/// - Database operation fails
+pub async fn accept_policy(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(kind): Path<PolicyKind>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    Json(payload): Json<AcceptPolicyPayload>,
+) -> AppResult<ApiResponse<PolicyAcceptance>> {
+    let header_value = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.chars().take(500).collect::<String>())
+    };
+    let source = AcceptanceSource {
+        ip_address: connect_info.map(|Extension(ConnectInfo(addr))| addr.ip().to_string()),
+        forwarded_for: header_value(header::HeaderName::from_static("x-forwarded-for")),
+        user_agent: header_value(header::USER_AGENT),
+    };
+
+    let acceptance = policies::accept(&app_state.db_pool, auth_user.user_id, kind, payload.version, source).await?;
+    Ok(ApiResponse::ok(acceptance))
+}
+
+/// Lists the caller's acceptances, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_my_acceptances(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PolicyAcceptance>>> {
+    Ok(ApiResponse::ok(
+        policies::acceptances(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Lists every published version with acceptance counts (admin only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not an admin or the query fails.
+pub async fn list_versions(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PolicyVersion>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(policies::versions(&app_state.db_pool).await?))
+}
+
+/// Publishes the next version of a policy (admin only). Every user must
+/// accept it before using the API again.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an admin
+/// - Validation fails
+/// - Database operation fails
+pub async fn publish_policy(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<PublishPolicyPayload>,
+) -> AppResult<ApiResponse<PolicyDocument>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+
+    let document = policies::publish(
+        &app_state.db_pool,
+        auth_user.user_id,
+        payload.kind,
+        &payload.title,
+        &payload.body,
+        payload.summary.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(document))
+}
+
+/// Exports the acceptances of one policy version, oldest first (admin only).
+///
+/// # Errors
+///
+/// Returns an error if the caller is not an admin, the version does not
+/// exist (404), or the query fails.
+pub async fn list_acceptances(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(policy_id): Path<i32>,
+    Query(params): Query<PolicyAcceptanceParams>,
+) -> AppResult<ApiResponse<Vec<PolicyAcceptance>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+
+    let limit = params.limit.unwrap_or(DEFAULT_EXPORT_LIMIT).clamp(1, MAX_EXPORT_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let acceptances = policies::acceptances_of(&app_state.db_pool, policy_id, limit, offset).await?;
+    Ok(ApiResponse::ok(acceptances)
+        .meta("limit", limit)
+        .meta("offset", offset))
+}
@@ -1,17 +1,26 @@
 //! User profile management handlers.
 
-use super::types::{UpdateProfilePayload, UserProfile};
+use super::certifications;
+use super::users;
+use super::types::{
+    AvailabilityPayload, CvQueryParams, CvTemplate, CvUploadParams, UpdateProfilePayload, UserProfile,
+};
 use crate::AppState;
 use crate::auth::AuthUser;
+use crate::code_practice;
+use crate::cohorts::{self, ProfileCompleteness};
 use crate::errors::{AppError, AppResult};
-use crate::models::{CareerTrack, ExperienceLevel, User};
+use crate::export::{self, Block, Document, Typeface};
+use crate::response::ApiResponse;
+use crate::geo;
+use crate::internships;
+use crate::models::{CandidateAvailability, CareerTrack, ExperienceLevel, User, WorkAuthorization};
 use axum::{
     Json,
-    extract::{Multipart, State},
-    http::{StatusCode, header},
-    response::{IntoResponse, Response},
+    extract::{Multipart, Query, State},
+    response::Response,
 };
-use printpdf::*;
+use sqlx::PgPool;
 use std::io::Write;
 use tempfile::NamedTempFile;
 use tracing::{debug, error, info, warn};
@@ -31,7 +40,7 @@ use validator::Validate;
 pub async fn get_profile(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
-) -> AppResult<Json<UserProfile>> {
+) -> AppResult<ApiResponse<UserProfile>> {
     info!("Fetching profile for user: {}", auth_user.user_id);
 
     let user = sqlx::query_as!(
@@ -60,7 +69,10 @@ pub async fn get_profile(
         user.id, user.profile_completed
     );
 
-    Ok(Json(UserProfile {
+    let availability = load_availability(&app_state.db_pool, user.id).await?;
+    let code_practice = code_practice::list(&app_state.db_pool, user.id).await?;
+
+    Ok(ApiResponse::ok(UserProfile {
         id: user.id,
         full_name: user.full_name,
         email: user.email,
@@ -71,6 +83,8 @@ pub async fn get_profile(
         skills: user.skills,
         projects: user.projects,
         target_roles: user.target_roles,
+        availability: Some(availability),
+        code_practice: Some(code_practice),
     }))
 }
 
@@ -95,7 +109,7 @@ pub async fn complete_profile(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Json(payload): Json<super::types::CompleteProfilePayload>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Completing profile for user: {}", auth_user.user_id);
 
     payload.validate().map_err(|e| {
@@ -141,13 +155,22 @@ pub async fn complete_profile(
         );
         e
     })?;
+    save_availability(&app_state.db_pool, auth_user.user_id, payload.availability).await?;
+
+    app_state.user_cache.invalidate(auth_user.user_id).await;
+    crate::credits::award_achievement(
+        &app_state.db_pool,
+        auth_user.user_id,
+        crate::credits::Achievement::ProfileCompleted,
+    )
+    .await;
 
     info!(
         "Profile completed successfully for user: {}",
         auth_user.user_id
     );
 
-    Ok(Json(serde_json::json!({
+    Ok(ApiResponse::ok(serde_json::json!({
         "message": "Profile completed successfully"
     })))
 }
@@ -168,7 +191,7 @@ pub async fn update_profile(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Json(payload): Json<UpdateProfilePayload>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Updating profile for user: {}", auth_user.user_id);
 
     payload.validate().map_err(|e| {
@@ -264,24 +287,187 @@ pub async fn update_profile(
         .await?;
     }
 
+    updated_fields.extend(
+        save_availability(&app_state.db_pool, auth_user.user_id, payload.availability).await?,
+    );
+
+    if !updated_fields.is_empty() {
+        app_state.user_cache.invalidate(auth_user.user_id).await;
+    }
+
     info!(
         "Profile updated successfully for user {}: fields updated: {:?}",
         auth_user.user_id, updated_fields
     );
 
-    Ok(Json(serde_json::json!({
+    Ok(ApiResponse::ok(serde_json::json!({
         "message": "Profile updated successfully"
     })))
 }
 
-/// Uploads and processes a CV/resume PDF file.
+/// Returns how complete the user's profile is and whether any of their
+/// cohorts requires more before they may apply to jobs.
+///
+/// # Errors
+///
+/// Returns an error if the user is not found or a query fails.
+pub async fn get_profile_completeness(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<ProfileCompleteness>> {
+    let completeness = cohorts::profile_completeness(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(completeness))
+}
+
+/// Loads a user's availability preferences and work authorizations.
+///
+/// # Errors
+///
+/// Returns an error if the user does not exist or the query fails.
+pub async fn load_availability(db_pool: &PgPool, user_id: uuid::Uuid) -> AppResult<CandidateAvailability> {
+    let mut availability = sqlx::query_as::<_, CandidateAvailability>(
+        r#"
+        SELECT available_from, desired_job_types, willing_to_relocate, country_code, available_terms,
+               weekly_learning_hours
+        FROM users
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    availability.work_authorizations = sqlx::query_as::<_, WorkAuthorization>(
+        "SELECT country_code, status FROM user_work_authorizations WHERE user_id = $1 ORDER BY country_code",
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(availability)
+}
+
+/// Stores the availability fields present in `payload`.
+///
+/// A provided `work_authorizations` list replaces the stored one. Returns
+/// the names of the fields that were updated.
+async fn save_availability(
+    db_pool: &PgPool,
+    user_id: uuid::Uuid,
+    payload: AvailabilityPayload,
+) -> AppResult<Vec<&'static str>> {
+    let mut updated_fields = Vec::new();
+
+    if let Some(available_from) = payload.available_from {
+        updated_fields.push("available_from");
+        sqlx::query("UPDATE users SET available_from = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(available_from)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(desired_job_types) = payload.desired_job_types {
+        updated_fields.push("desired_job_types");
+        sqlx::query("UPDATE users SET desired_job_types = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(desired_job_types)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(willing_to_relocate) = payload.willing_to_relocate {
+        updated_fields.push("willing_to_relocate");
+        sqlx::query("UPDATE users SET willing_to_relocate = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(willing_to_relocate)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(country_code) = payload.country_code {
+        updated_fields.push("country_code");
+        sqlx::query("UPDATE users SET country_code = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(geo::normalize_country_code(&country_code)?)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(terms) = payload.available_terms {
+        updated_fields.push("available_terms");
+        let mut normalized: Vec<String> = Vec::with_capacity(terms.len());
+        for term in &terms {
+            let term = internships::normalize_term(term)?;
+            if !normalized.contains(&term) {
+                normalized.push(term);
+            }
+        }
+        sqlx::query("UPDATE users SET available_terms = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(&normalized)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(hours) = payload.weekly_learning_hours {
+        updated_fields.push("weekly_learning_hours");
+        sqlx::query("UPDATE users SET weekly_learning_hours = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(hours)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+    }
+    if let Some(authorizations) = payload.work_authorizations {
+        updated_fields.push("work_authorizations");
+        let mut countries = Vec::with_capacity(authorizations.len());
+        let mut statuses = Vec::with_capacity(authorizations.len());
+        for authorization in authorizations {
+            let country = geo::normalize_country_code(&authorization.country_code)?;
+            if countries.contains(&country) {
+                return Err(AppError::ValidationError(format!(
+                    "Work authorization for {} is listed more than once",
+                    country
+                )));
+            }
+            countries.push(country);
+            statuses.push(authorization.status);
+        }
+
+        let mut tx = db_pool.begin().await?;
+        sqlx::query("DELETE FROM user_work_authorizations WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_work_authorizations (user_id, country_code, status)
+            SELECT $1, * FROM UNNEST($2::text[], $3::work_authorization_status[])
+            "#,
+        )
+        .bind(user_id)
+        .bind(&countries)
+        .bind(&statuses)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+    }
+
+    Ok(updated_fields)
+}
+
+/// Uploads and processes a CV/resume PDF file or a photo of one.
 ///
 /// Accepts a PDF file via multipart form upload, extracts the text content,
 /// and saves it to the user's profile as `raw_cv_text`.
 ///
+/// With `?native_pdf=true` the PDF is uploaded to Gemini's Files API and
+/// read natively instead, which also works for scanned PDFs; the uploaded
+/// file is deleted afterwards. This is charged as an AI action.
+///
+/// Photos and screenshots are always read by Gemini's vision model (also
+/// charged); certificates listed on them are added to the profile's
+/// certifications.
+///
 /// # File Requirements
 ///
-/// - Format: PDF only
+/// - Format: PDF, PNG, JPEG, WebP, or HEIC
 /// - Max size: 10MB
 /// - Field name: `cv_file`
 ///
@@ -289,15 +475,18 @@ pub async fn update_profile(
 ///
 /// Returns an error if:
 /// - User is not authenticated
-/// - File is not a PDF
+/// - File is not a PDF or supported image
 /// - File exceeds size limit
 /// - PDF text extraction fails
+/// - `native_pdf` is set or the file is an image, and Gemini is not
+///   configured or the call fails
 /// - Database operation fails
 pub async fn upload_cv(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
+    Query(params): Query<CvUploadParams>,
     mut multipart: Multipart,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Processing CV upload for user: {}", auth_user.user_id);
 
     const MAX_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
@@ -327,14 +516,15 @@ pub async fn upload_cv(
 
         debug!("Uploaded file: {}", file_name);
 
-        // Verify it's a PDF file
-        if !file_name.to_lowercase().ends_with(".pdf") {
+        // Verify it's a PDF file or an image
+        let image_type = certifications::image_content_type(&file_name);
+        if image_type.is_none() && !file_name.to_lowercase().ends_with(".pdf") {
             warn!(
                 "CV upload failed: invalid file type '{}' for user {}",
                 file_name, auth_user.user_id
             );
             return Err(AppError::BadRequest(
-                "Only PDF files are supported".to_string(),
+                "Only PDF files and PNG, JPEG, WebP, or HEIC images are supported".to_string(),
             ));
         }
 
@@ -358,56 +548,28 @@ pub async fn upload_cv(
             ));
         }
 
-        // Verify PDF header (should start with %PDF)
-        if file_data.len() < 5 || !file_data.starts_with(b"%PDF") {
-            error!(
-                "Invalid PDF header for user {}. First bytes: {:?}",
-                auth_user.user_id,
-                &file_data.get(0..10)
-            );
-            return Err(AppError::BadRequest(
-                "Invalid PDF file. The file may be corrupted or not a valid PDF.".to_string(),
-            ));
-        }
-
-        // Create a temporary file to write the PDF data
-        let mut temp_file = NamedTempFile::new().map_err(|e| {
-            error!(
-                "Failed to create temporary file for user {}: {}",
-                auth_user.user_id, e
-            );
-            AppError::InternalServerError
-        })?;
-
-        temp_file.write_all(&file_data).map_err(|e| {
-            error!(
-                "Failed to write PDF data for user {}: {}",
-                auth_user.user_id, e
-            );
-            AppError::InternalServerError
-        })?;
-
-        // Flush to ensure all data is written
-        temp_file.flush().map_err(|e| {
-            error!(
-                "Failed to flush PDF data for user {}: {}",
-                auth_user.user_id, e
-            );
-            AppError::InternalServerError
-        })?;
-
-        let temp_path = temp_file.path();
-
-        // Extract text from PDF
-        let extracted_text = pdf_extract::extract_text(temp_path).map_err(|e| {
-            error!(
-                "PDF text extraction failed for user {}: {}",
-                auth_user.user_id, e
-            );
-            AppError::BadRequest(format!("Failed to extract text from PDF: {}", e))
-        })?;
-
-        debug!("Extracted {} characters from PDF", extracted_text.len());
+        let mut certifications_added = 0;
+        let (extracted_text, extraction) = if let Some(content_type) = image_type {
+            let ai_service = app_state.ai_service.as_ref().ok_or_else(|| {
+                AppError::ConfigurationError("AI service not configured".to_string())
+            })?;
+            let reading = ai_service
+                .read_image(&file_data, content_type, &file_name, Some(auth_user.user_id))
+                .await
+                .map_err(|e| {
+                    error!("CV image reading failed for user {}: {}", auth_user.user_id, e);
+                    e
+                })?;
+            certifications_added =
+                certifications::save_extracted(&app_state.db_pool, auth_user.user_id, &reading.certificates)
+                    .await?
+                    .len();
+            (reading.text, "gemini_vision")
+        } else {
+            read_pdf(&app_state, &file_data, &file_name, params.native_pdf, auth_user.user_id).await?
+        };
+
+        debug!("Extracted {} characters from CV ({})", extracted_text.len(), extraction);
 
         if extracted_text.trim().is_empty() {
             warn!(
@@ -415,17 +577,20 @@ pub async fn upload_cv(
                 auth_user.user_id
             );
             return Err(AppError::BadRequest(
-                "PDF appears to be empty or contains no extractable text".to_string(),
+                "CV appears to be empty or contains no extractable text. For a scanned PDF, upload it again with ?native_pdf=true".to_string(),
             ));
         }
 
-        // Save extracted text to database
+        // Save extracted text to database, snapshotting the profile first so
+        // the previous CV text can be restored
+        let mut tx = app_state.db_pool.begin().await?;
+        let snapshot_id = users::snapshot_profile(&mut tx, auth_user.user_id, "cv_upload").await?;
         sqlx::query!(
             "UPDATE users SET raw_cv_text = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
             extracted_text,
             auth_user.user_id
         )
-        .execute(&app_state.db_pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| {
             error!(
@@ -434,15 +599,21 @@ pub async fn upload_cv(
             );
             e
         })?;
+        tx.commit().await?;
+
+        app_state.user_cache.invalidate(auth_user.user_id).await;
 
         info!(
             "CV uploaded and processed successfully for user: {}",
             auth_user.user_id
         );
 
-        return Ok(Json(serde_json::json!({
+        return Ok(ApiResponse::ok(serde_json::json!({
             "message": "CV uploaded and processed successfully",
-            "extracted_length": extracted_text.len()
+            "extracted_length": extracted_text.len(),
+            "extraction": extraction,
+            "certifications_added": certifications_added,
+            "snapshot_id": snapshot_id
         })));
     }
 
@@ -456,6 +627,70 @@ pub async fn upload_cv(
     ))
 }
 
+/// Checks a PDF's header and extracts its text, locally or (with
+/// `native_pdf`) through Gemini's Files API. Returns the text and the
+/// extraction method.
+async fn read_pdf(
+    app_state: &AppState,
+    file_data: &[u8],
+    file_name: &str,
+    native_pdf: bool,
+    user_id: uuid::Uuid,
+) -> AppResult<(String, &'static str)> {
+    // Verify PDF header (should start with %PDF)
+    if file_data.len() < 5 || !file_data.starts_with(b"%PDF") {
+        error!(
+            "Invalid PDF header for user {}. First bytes: {:?}",
+            user_id,
+            &file_data.get(0..10)
+        );
+        return Err(AppError::BadRequest(
+            "Invalid PDF file. The file may be corrupted or not a valid PDF.".to_string(),
+        ));
+    }
+
+    if !native_pdf {
+        return Ok((extract_pdf_text(file_data, user_id)?, "local"));
+    }
+
+    let ai_service = app_state.ai_service.as_ref().ok_or_else(|| {
+        AppError::ConfigurationError("AI service not configured".to_string())
+    })?;
+    let text = ai_service
+        .transcribe_pdf(file_data, file_name, Some(user_id))
+        .await
+        .map_err(|e| {
+            error!("Native PDF analysis failed for user {}: {}", user_id, e);
+            e
+        })?;
+    Ok((text, "gemini_files"))
+}
+
+/// Extracts the text layer of a PDF locally.
+pub(super) fn extract_pdf_text(file_data: &[u8], user_id: uuid::Uuid) -> AppResult<String> {
+    // Create a temporary file to write the PDF data
+    let mut temp_file = NamedTempFile::new().map_err(|e| {
+        error!("Failed to create temporary file for user {}: {}", user_id, e);
+        AppError::InternalServerError
+    })?;
+
+    temp_file.write_all(file_data).map_err(|e| {
+        error!("Failed to write PDF data for user {}: {}", user_id, e);
+        AppError::InternalServerError
+    })?;
+
+    // Flush to ensure all data is written
+    temp_file.flush().map_err(|e| {
+        error!("Failed to flush PDF data for user {}: {}", user_id, e);
+        AppError::InternalServerError
+    })?;
+
+    pdf_extract::extract_text(temp_file.path()).map_err(|e| {
+        error!("PDF text extraction failed for user {}: {}", user_id, e);
+        AppError::BadRequest(format!("Failed to extract text from PDF: {}", e))
+    })
+}
+
 /// Generates a PDF CV based on the user's profile data.
 ///
 /// Creates a professional CV document using the user's profile information including:
@@ -466,19 +701,27 @@ pub async fn upload_cv(
 /// - Projects
 /// - Target roles
 ///
-/// Returns the PDF as a downloadable file.
+/// Returns the PDF as a downloadable file. Pass `?template=executive` for the
+/// premium serif layout. `?format=` selects an accessible alternative:
+/// `large_print_pdf`, `html` (semantic headings and lists), or `text`.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - User is not authenticated
+/// - A premium template is requested without a premium plan
 /// - User profile not found
 /// - PDF generation fails
 pub async fn generate_cv(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
+    Query(params): Query<CvQueryParams>,
 ) -> AppResult<Response> {
-    info!("Generating CV for user: {}", auth_user.user_id);
+    info!("Generating CV for user: {} (template: {:?})", auth_user.user_id, params.template);
+
+    if params.template.is_premium() {
+        crate::billing::require_premium_templates(&app_state.db_pool, auth_user.user_id).await?;
+    }
 
     // Fetch user profile
     let user = sqlx::query_as!(
@@ -504,153 +747,67 @@ pub async fn generate_cv(
 
     debug!("Generating CV for: {}", user.full_name);
 
-    // Create PDF document
-    let (doc, page1, layer1) = PdfDocument::new(
-        &format!("{}_CV", user.full_name.replace(' ', "_")),
-        Mm(210.0), // A4 width
-        Mm(297.0), // A4 height
-        "Layer 1",
-    );
-
-    let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| {
-        error!("Failed to add font: {}", e);
-        AppError::InternalServerError
-    })?;
-    let font_bold = doc
-        .add_builtin_font(BuiltinFont::HelveticaBold)
-        .map_err(|e| {
-            error!("Failed to add bold font: {}", e);
-            AppError::InternalServerError
-        })?;
-
-    let current_layer = doc.get_page(page1).get_layer(layer1);
-
-    let mut y_position = 270.0; // Start from top (A4 is 297mm high)
-    let left_margin = 20.0;
-    let line_height = 6.0;
-
-    // Helper function to add text
-    let add_text = |text: &str, font_ref: &IndirectFontRef, size: f32, y: &mut f32| {
-        current_layer.use_text(text, size, Mm(left_margin), Mm(*y), font_ref);
-        *y -= line_height;
-    };
-
-    // Helper function to add bold heading
-    let add_heading = |text: &str, y: &mut f32| {
-        current_layer.use_text(text, 16.0, Mm(left_margin), Mm(*y), &font_bold);
-        *y -= line_height * 1.5;
-    };
-
-    let add_section_title = |text: &str, y: &mut f32| {
-        *y -= 3.0; // Extra spacing before sections
-        current_layer.use_text(text, 14.0, Mm(left_margin), Mm(*y), &font_bold);
-        *y -= line_height * 1.2;
+    let typeface = match params.template {
+        CvTemplate::Classic => Typeface::Sans,
+        CvTemplate::Executive => Typeface::Serif,
     };
+    let export = export::render_in_background(cv_document(&user), params.format, typeface).await?;
 
-    // Title - Name
-    add_heading(&user.full_name, &mut y_position);
+    info!(
+        "CV generated successfully for user: {} ({:?}, {} bytes)",
+        auth_user.user_id,
+        params.format,
+        export.body.len()
+    );
 
-    // Contact Information
-    add_text(&user.email, &font, 11.0, &mut y_position);
+    // Return the CV as a downloadable file
+    Ok(export.into_download(&format!("{}_CV", user.full_name.replace(' ', "_"))))
+}
 
-    // Education & Experience
-    add_section_title("Education & Experience", &mut y_position);
+/// Lays out a user's CV for export.
+fn cv_document(user: &User) -> Document {
+    let mut blocks = vec![Block::Heading("Education & Experience".to_string())];
     if let Some(edu) = &user.education_level {
-        add_text(&format!("Education: {}", edu), &font, 11.0, &mut y_position);
+        blocks.push(Block::Paragraph(format!("Education: {}", edu)));
     }
-    add_text(
-        &format!(
-            "Experience Level: {}",
-            match &user.experience_level {
-                Some(ExperienceLevel::Fresher) => "Fresher (0-1 years)",
-                Some(ExperienceLevel::Junior) => "Junior (1-3 years)",
-                Some(ExperienceLevel::Mid) => "Mid-level (3-5 years)",
-                None => "Not specified",
-            }
-        ),
-        &font,
-        11.0,
-        &mut y_position,
-    );
-
-    // Career Track
-    add_section_title("Career Track", &mut y_position);
-    add_text(
-        &format!(
-            "Preferred Track: {}",
-            match &user.preferred_track {
-                Some(CareerTrack::WebDevelopment) => "Web Development",
-                Some(CareerTrack::Data) => "Data Science & Analytics",
-                Some(CareerTrack::Design) => "UI/UX Design",
-                Some(CareerTrack::Marketing) => "Digital Marketing",
-                None => "Not specified",
-            }
-        ),
-        &font,
-        11.0,
-        &mut y_position,
-    );
+    blocks.push(Block::Paragraph(format!(
+        "Experience Level: {}",
+        match &user.experience_level {
+            Some(ExperienceLevel::Fresher) => "Fresher (0-1 years)",
+            Some(ExperienceLevel::Junior) => "Junior (1-3 years)",
+            Some(ExperienceLevel::Mid) => "Mid-level (3-5 years)",
+            None => "Not specified",
+        }
+    )));
+
+    blocks.push(Block::Heading("Career Track".to_string()));
+    blocks.push(Block::Paragraph(format!(
+        "Preferred Track: {}",
+        match &user.preferred_track {
+            Some(CareerTrack::WebDevelopment) => "Web Development",
+            Some(CareerTrack::Data) => "Data Science & Analytics",
+            Some(CareerTrack::Design) => "UI/UX Design",
+            Some(CareerTrack::Marketing) => "Digital Marketing",
+            None => "Not specified",
+        }
+    )));
 
-    // Skills
     if !user.skills.is_empty() {
-        add_section_title("Skills", &mut y_position);
-        let skills_text = user.skills.join(", ");
-        // Word wrap for long skills list
-        let max_chars_per_line = 80;
-        for chunk in skills_text.as_bytes().chunks(max_chars_per_line) {
-            if let Ok(chunk_str) = std::str::from_utf8(chunk) {
-                add_text(chunk_str, &font, 11.0, &mut y_position);
-            }
-        }
+        blocks.push(Block::Heading("Skills".to_string()));
+        blocks.push(Block::Paragraph(user.skills.join(", ")));
     }
-
-    // Projects
     if !user.projects.is_empty() {
-        add_section_title("Projects", &mut y_position);
-        for (i, project) in user.projects.iter().enumerate() {
-            add_text(
-                &format!("{}. {}", i + 1, project),
-                &font,
-                11.0,
-                &mut y_position,
-            );
-        }
+        blocks.push(Block::Heading("Projects".to_string()));
+        blocks.push(Block::OrderedList(user.projects.clone()));
     }
-
-    // Target Roles
     if !user.target_roles.is_empty() {
-        add_section_title("Target Roles", &mut y_position);
-        for role in &user.target_roles {
-            add_text(&format!("• {}", role), &font, 11.0, &mut y_position);
-        }
+        blocks.push(Block::Heading("Target Roles".to_string()));
+        blocks.push(Block::List(user.target_roles.clone()));
     }
 
-    // Save PDF to bytes
-    let pdf_bytes = doc.save_to_bytes().map_err(|e| {
-        error!("Failed to generate PDF bytes: {}", e);
-        AppError::InternalServerError
-    })?;
-
-    info!(
-        "CV generated successfully for user: {} ({} bytes)",
-        auth_user.user_id,
-        pdf_bytes.len()
-    );
-
-    // Return PDF as downloadable file
-    Ok((
-        StatusCode::OK,
-        [
-            (header::CONTENT_TYPE, "application/pdf"),
-            (
-                header::CONTENT_DISPOSITION,
-                &format!(
-                    "attachment; filename=\"{}_CV.pdf\"",
-                    user.full_name.replace(' ', "_")
-                ),
-            ),
-        ],
-        pdf_bytes,
-    )
-        .into_response())
+    Document {
+        title: user.full_name.clone(),
+        subtitle: Some(user.email.clone()),
+        blocks,
+    }
 }
@@ -4,6 +4,7 @@ use axum::{extract::{State, Path}, Json};
 use tracing::{info, debug};
 use crate::models::UserProgress;
 use crate::errors::AppResult;
+use crate::response::ApiResponse;
 use crate::auth::AuthUser;
 use crate::AppState;
 use super::types::UpdateProgressPayload;
@@ -27,7 +28,7 @@ pub async fn start_resource(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
     Path(resource_id): Path<i32>,
-) -> AppResult<Json<UserProgress>> {
+) -> AppResult<ApiResponse<UserProgress>> {
     info!("Starting resource tracking: user_id={}, resource_id={}", 
           auth_user.user_id, resource_id);
     
@@ -47,7 +48,7 @@ pub async fn start_resource(
 
     info!("Resource tracking started: progress_id={}", progress.id.unwrap_or(0));
     
-    Ok(Json(progress))
+    Ok(ApiResponse::ok(progress))
 }
 
 /// Updates progress for a learning resource.
@@ -70,7 +71,7 @@ pub async fn update_resource_progress(
     State(app_state): State<AppState>,
     Path(resource_id): Path<i32>,
     Json(payload): Json<UpdateProgressPayload>,
-) -> AppResult<Json<serde_json::Value>> {
+) -> AppResult<ApiResponse<serde_json::Value>> {
     info!("Updating progress: user_id={}, resource_id={}, completion={}%",
           auth_user.user_id, resource_id, payload.completion_percentage);
     
@@ -101,7 +102,7 @@ pub async fn update_resource_progress(
         debug!("Progress updated to {}%", payload.completion_percentage);
     }
     
-    Ok(Json(serde_json::json!({
+    Ok(ApiResponse::ok(serde_json::json!({
         "message": "Progress updated successfully"
     })))
 }
@@ -118,7 +119,7 @@ pub async fn update_resource_progress(
 pub async fn get_my_progress(
     auth_user: AuthUser,
     State(app_state): State<AppState>,
-) -> AppResult<Json<Vec<UserProgress>>> {
+) -> AppResult<ApiResponse<Vec<UserProgress>>> {
     info!("Fetching progress for user: {}", auth_user.user_id);
     
     let progress = sqlx::query_as!(
@@ -136,5 +137,5 @@ pub async fn get_my_progress(
 
     debug!("Retrieved {} progress records for user: {}", progress.len(), auth_user.user_id);
     
-    Ok(Json(progress))
+    Ok(ApiResponse::ok(progress))
 }
@@ -0,0 +1,180 @@
+//! Prompt rollout handlers: thumbs up or down on AI results, and admin
+//! control of rollouts of new prompt versions.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use tracing::warn;
+use validator::Validate;
+
+use super::types::{
+    AiFeedbackPayload, RollBackPromptRolloutPayload, StartPromptRolloutPayload, UpdatePromptRolloutPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::models::UserRole;
+use crate::prompt_rollouts::{self, AiFeedback, PromptRollout, RolloutReport, RolloutThresholds};
+use crate::response::ApiResponse;
+
+/// Rates an AI result with a thumbs up or down, under the `prompt_version`
+/// it reported. Thumbs down count against prompts being rolled out.
+///
+/// # Errors
+///
+/// Returns an error if validation fails or the database operation fails.
+pub async fn submit_ai_feedback(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<AiFeedbackPayload>,
+) -> AppResult<ApiResponse<AiFeedback>> {
+    payload.validate()?;
+    let feedback = prompt_rollouts::record_feedback(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &payload.action,
+        &payload.prompt_version,
+        payload.helpful,
+        payload.comment.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(feedback))
+}
+
+/// Lists prompt rollouts, newest first, with the parse-failure and
+/// thumbs-down rates of their candidate and baseline versions.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin or the database operation
+/// fails.
+pub async fn list_prompt_rollouts(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<RolloutReport>>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(prompt_rollouts::list(&app_state.db_pool).await?))
+}
+
+/// Starts sending a share of an action's requests to the candidate prompt
+/// shipped in this release.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, validation fails, no
+/// candidate ships for the action, it already has a live rollout, or the
+/// database operation fails.
+pub async fn start_prompt_rollout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<StartPromptRolloutPayload>,
+) -> AppResult<ApiResponse<PromptRollout>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+    let thresholds = RolloutThresholds {
+        max_parse_failure_rate: payload.max_parse_failure_rate,
+        max_thumbs_down_rate: payload.max_thumbs_down_rate,
+        min_samples: payload.min_samples,
+    };
+    let rollout = prompt_rollouts::start(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &payload.action,
+        payload.traffic_percent,
+        thresholds,
+    )
+    .await?;
+    refresh_routes(&app_state).await;
+    Ok(ApiResponse::ok(rollout))
+}
+
+/// Returns a prompt rollout with its metrics.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the rollout does not exist
+/// (404), or the database operation fails.
+pub async fn get_prompt_rollout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(rollout_id): Path<i32>,
+) -> AppResult<ApiResponse<RolloutReport>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    Ok(ApiResponse::ok(prompt_rollouts::get(&app_state.db_pool, rollout_id).await?))
+}
+
+/// Changes an active rollout's share of traffic or thresholds.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, validation fails, the
+/// rollout does not exist (404) or is no longer active, or the database
+/// operation fails.
+pub async fn update_prompt_rollout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(rollout_id): Path<i32>,
+    Json(payload): Json<UpdatePromptRolloutPayload>,
+) -> AppResult<ApiResponse<PromptRollout>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+    let thresholds = RolloutThresholds {
+        max_parse_failure_rate: payload.max_parse_failure_rate,
+        max_thumbs_down_rate: payload.max_thumbs_down_rate,
+        min_samples: payload.min_samples,
+    };
+    let rollout =
+        prompt_rollouts::update(&app_state.db_pool, rollout_id, payload.traffic_percent, thresholds).await?;
+    refresh_routes(&app_state).await;
+    Ok(ApiResponse::ok(rollout))
+}
+
+/// Sends all of the action's requests to the rollout's candidate.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, the rollout does not exist
+/// (404) or is not active, or the database operation fails.
+pub async fn promote_prompt_rollout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(rollout_id): Path<i32>,
+) -> AppResult<ApiResponse<PromptRollout>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    let rollout = prompt_rollouts::promote(&app_state.db_pool, auth_user.user_id, rollout_id).await?;
+    refresh_routes(&app_state).await;
+    Ok(ApiResponse::ok(rollout))
+}
+
+/// Sends everyone back to the rollout's baseline version.
+///
+/// # Errors
+///
+/// Returns an error if the user is not an admin, validation fails, the
+/// rollout does not exist (404) or is no longer live, or the database
+/// operation fails.
+pub async fn roll_back_prompt_rollout(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(rollout_id): Path<i32>,
+    Json(payload): Json<RollBackPromptRolloutPayload>,
+) -> AppResult<ApiResponse<PromptRollout>> {
+    auth_user.require_role(&app_state.db_pool, &[UserRole::Admin]).await?;
+    payload.validate()?;
+    let rollout =
+        prompt_rollouts::roll_back(&app_state.db_pool, rollout_id, Some(auth_user.user_id), &payload.reason).await?;
+    refresh_routes(&app_state).await;
+    Ok(ApiResponse::ok(rollout))
+}
+
+/// Applies a rollout change to this instance's routing right away; other
+/// instances pick it up on their next rollout check.
+async fn refresh_routes(app_state: &AppState) {
+    let Some(router) = app_state.ai_service.as_deref().and_then(|ai| ai.prompt_router()) else {
+        return;
+    };
+    if let Err(e) = router.refresh().await {
+        warn!("Failed to reload prompt rollouts: {}", e);
+    }
+}
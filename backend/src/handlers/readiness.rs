@@ -0,0 +1,27 @@
+//! Role readiness handlers.
+
+use axum::extract::{Query, State};
+
+use super::types::ReadinessParams;
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::readiness::{self, RoleReadiness};
+use crate::response::ApiResponse;
+
+/// Scores how ready the caller is to apply for one of their roles, from
+/// roadmap progress, verified certifications, projects, and (for
+/// engineering roles) coding practice, with each component's share.
+///
+/// # Errors
+///
+/// Returns an error if the user has no target role or roadmap, `role` is
+/// not one of theirs, or a database operation fails.
+pub async fn get_my_readiness(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ReadinessParams>,
+) -> AppResult<ApiResponse<RoleReadiness>> {
+    let readiness = readiness::for_user(&app_state.db_pool, auth_user.user_id, params.role.as_deref()).await?;
+    Ok(ApiResponse::ok(readiness))
+}
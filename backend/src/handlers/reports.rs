@@ -0,0 +1,205 @@
+//! Blocking and report handlers for candidates and employers.
+//!
+//! Employers on a team block on behalf of their company, so only owners and
+//! recruiters may add or lift blocks; viewers can see them. See
+//! [`crate::blocks`] for what a block hides and [`crate::reports`] for how
+//! reports reach the moderation queue.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use serde_json::{Value, json};
+use tracing::warn;
+use validator::Validate;
+
+use super::types::{BlockPayload, ReportPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::blocks::{self, Block, BlockTarget, Blocker};
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+use crate::reports::{self, Report};
+use crate::response::ApiResponse;
+use crate::teams;
+
+/// Resolves the caller as a blocker. When `changing`, an employer's seat
+/// must be allowed to change the company's pipeline.
+async fn blocker(app_state: &AppState, auth_user: &AuthUser, changing: bool) -> AppResult<Blocker> {
+    let role = auth_user
+        .require_role(&app_state.db_pool, &[UserRole::JobSeeker, UserRole::Employer])
+        .await?;
+    let seat = match role {
+        UserRole::Employer => teams::seat_for(&app_state.db_pool, auth_user.user_id).await?,
+        _ => None,
+    };
+    if changing
+        && let Some(seat) = &seat
+        && !teams::PIPELINE_ROLES.contains(&seat.role)
+    {
+        return Err(AppError::Forbidden);
+    }
+    Ok(Blocker {
+        user_id: auth_user.user_id,
+        role,
+        company_id: seat.map(|s| s.company_id),
+    })
+}
+
+/// Lists the caller's blocks, or their company's when they hold a seat.
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a candidate or employer, or the
+/// query fails.
+pub async fn list_blocks(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<Block>>> {
+    let blocker = blocker(&app_state, &auth_user, false).await?;
+    Ok(ApiResponse::ok(blocks::list(&app_state.db_pool, &blocker).await?))
+}
+
+/// Blocks an employer or company (candidates) or a candidate (employers).
+/// Blocking the same party again returns the existing block.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a candidate or employer, or holds a viewer seat
+/// - Not exactly one of `user_id` and `company_id` is given, or the caller may not block it
+/// - The user or company does not exist (404)
+/// - Database operation fails
+pub async fn create_block(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<BlockPayload>,
+) -> AppResult<ApiResponse<Block>> {
+    let blocker = blocker(&app_state, &auth_user, true).await?;
+    payload.validate()?;
+
+    let target = match (payload.user_id, payload.company_id) {
+        (Some(user_id), None) => BlockTarget::User(user_id),
+        (None, Some(company_id)) => BlockTarget::Company(company_id),
+        _ => {
+            return Err(AppError::ValidationError(
+                "Give either user_id or company_id".to_string(),
+            ));
+        }
+    };
+    let block = blocks::block(&app_state.db_pool, &blocker, target, payload.reason.as_deref()).await?;
+
+    if let Some(company_id) = blocker.company_id {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            company_id,
+            "candidate.block",
+            "user",
+            &payload.user_id.map(|u| u.to_string()).unwrap_or_default(),
+            json!({ "block_id": block.id }),
+        )
+        .await;
+    }
+    Ok(ApiResponse::ok(block))
+}
+
+/// Lifts a block.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a candidate or employer, or holds a viewer seat
+/// - The caller (or their company) holds no such block (404)
+/// - Database operation fails
+pub async fn delete_block(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(block_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let blocker = blocker(&app_state, &auth_user, true).await?;
+
+    blocks::unblock(&app_state.db_pool, &blocker, block_id).await?;
+    if let Some(company_id) = blocker.company_id {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            company_id,
+            "candidate.unblock",
+            "block",
+            &block_id.to_string(),
+            json!({}),
+        )
+        .await;
+    }
+    Ok(ApiResponse::ok(json!({ "message": "Block removed" })))
+}
+
+/// Reports a job, user, company, or a message the caller received. With
+/// `block`, the reported party is blocked too where the caller may block
+/// it; `meta.blocked` tells whether it was.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target does not exist, or the message was not sent to the caller (404)
+/// - Validation fails, or the caller already has an open report on the target
+/// - Database operation fails
+pub async fn create_report(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<ReportPayload>,
+) -> AppResult<ApiResponse<Report>> {
+    payload.validate()?;
+
+    let report = reports::file(
+        &app_state.db_pool,
+        auth_user.user_id,
+        payload.target_type,
+        &payload.target_id,
+        payload.category,
+        payload.details.as_deref(),
+    )
+    .await?;
+
+    let mut blocked = false;
+    if payload.block {
+        blocked = block_reported(&app_state, &auth_user, report.id).await;
+    }
+    Ok(ApiResponse::ok(report).meta("blocked", blocked))
+}
+
+/// Blocks the party answerable for a report: candidates block the company
+/// when there is one, employers block the reported candidate.
+async fn block_reported(app_state: &AppState, auth_user: &AuthUser, report_id: i32) -> bool {
+    let result = async {
+        let blocker = blocker(app_state, auth_user, true).await?;
+        let (user_id, company_id) = reports::reported_party_of(&app_state.db_pool, report_id).await?;
+        let target = match (blocker.role, user_id, company_id) {
+            (UserRole::JobSeeker, _, Some(company_id)) => BlockTarget::Company(company_id),
+            (_, Some(user_id), _) => BlockTarget::User(user_id),
+            _ => return Ok(false),
+        };
+        blocks::block(&app_state.db_pool, &blocker, target, Some("Reported")).await?;
+        Ok::<_, AppError>(true)
+    }
+    .await;
+    result.unwrap_or_else(|e| {
+        warn!("Could not block the party of report {}: {}", report_id, e);
+        false
+    })
+}
+
+/// Lists the caller's reports and how they were resolved, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn list_my_reports(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<Report>>> {
+    Ok(ApiResponse::ok(
+        reports::list_for_reporter(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
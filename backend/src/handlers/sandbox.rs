@@ -0,0 +1,154 @@
+//! Sandbox handlers: test users, jobs, and applications for API
+//! integrators, authenticated with a `sandbox`-scoped key in `X-Api-Key`.
+
+use axum::{Json, extract::State, http::HeaderMap};
+use validator::Validate;
+
+use super::types::{
+    LoginResponse, SandboxApplicationPayload, SandboxJobPayload, SandboxUserPayload, UserProfile,
+};
+use crate::AppState;
+use crate::api_keys::{self, SANDBOX_SCOPE};
+use crate::auth::create_jwt;
+use crate::errors::{AppError, AppResult};
+use crate::models::{ApplicationTracking, Job, UserRole};
+use crate::response::ApiResponse;
+use crate::sandbox::{self, SandboxJobDraft, SandboxSummary, SandboxUserDraft};
+
+/// Returns what the key's sandbox holds.
+///
+/// # Errors
+///
+/// Returns an error if the key is missing, unknown, or lacks the `sandbox`
+/// scope, or the database operation fails.
+pub async fn get_sandbox(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<ApiResponse<SandboxSummary>> {
+    let key = api_keys::authenticate(&app_state.db_pool, &headers, SANDBOX_SCOPE).await?;
+    Ok(ApiResponse::ok(sandbox::summary(&app_state.db_pool, key.id).await?))
+}
+
+/// Creates a test user and returns a token to call the API as them.
+///
+/// # Errors
+///
+/// Returns an error if the key is invalid, validation fails, the role is
+/// admin, the sandbox is full, or the database operation fails.
+pub async fn create_sandbox_user(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<SandboxUserPayload>,
+) -> AppResult<ApiResponse<LoginResponse>> {
+    let key = api_keys::authenticate(&app_state.db_pool, &headers, SANDBOX_SCOPE).await?;
+    payload.validate()?;
+    let user = sandbox::create_user(
+        &app_state.db_pool,
+        key.id,
+        SandboxUserDraft {
+            role: payload.role.unwrap_or(UserRole::JobSeeker),
+            full_name: payload.full_name.as_deref(),
+            skills: &payload.skills,
+            experience_level: payload.experience_level,
+        },
+    )
+    .await?;
+
+    let token = create_jwt(user.id, user.email.clone())?;
+    Ok(ApiResponse::ok(LoginResponse {
+        token,
+        user: UserProfile {
+            id: user.id,
+            full_name: user.full_name,
+            email: user.email,
+            profile_completed: user.profile_completed,
+            education_level: user.education_level,
+            experience_level: user.experience_level,
+            preferred_track: user.preferred_track,
+            skills: user.skills,
+            projects: user.projects,
+            target_roles: user.target_roles,
+            availability: None,
+            code_practice: None,
+        },
+    }))
+}
+
+/// Creates an approved test job, seen only by the sandbox's test users.
+///
+/// # Errors
+///
+/// Returns an error if the key is invalid, validation fails, the salary
+/// range is inverted, the poster is not a test employer of the sandbox, the
+/// sandbox is full, or the database operation fails.
+pub async fn create_sandbox_job(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<SandboxJobPayload>,
+) -> AppResult<ApiResponse<Job>> {
+    let key = api_keys::authenticate(&app_state.db_pool, &headers, SANDBOX_SCOPE).await?;
+    payload.validate()?;
+    if let (Some(min), Some(max)) = (payload.salary_min, payload.salary_max)
+        && min > max
+    {
+        return Err(AppError::ValidationError("salary_min cannot exceed salary_max".to_string()));
+    }
+    let job = sandbox::create_job(
+        &app_state.db_pool,
+        key.id,
+        SandboxJobDraft {
+            posted_by: payload.posted_by,
+            job_title: &payload.job_title,
+            company: &payload.company,
+            location: &payload.location,
+            job_description: &payload.job_description,
+            required_skills: &payload.required_skills,
+            experience_level: payload.experience_level,
+            job_type: payload.job_type,
+            salary_min: payload.salary_min,
+            salary_max: payload.salary_max,
+        },
+    )
+    .await?;
+    Ok(ApiResponse::ok(job))
+}
+
+/// Records a test user's application to a sandbox job, in any status.
+///
+/// # Errors
+///
+/// Returns an error if the key is invalid, validation fails, the user or
+/// job is not in the sandbox, the user already applied, or the database
+/// operation fails.
+pub async fn create_sandbox_application(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<SandboxApplicationPayload>,
+) -> AppResult<ApiResponse<ApplicationTracking>> {
+    let key = api_keys::authenticate(&app_state.db_pool, &headers, SANDBOX_SCOPE).await?;
+    payload.validate()?;
+    let application = sandbox::create_application(
+        &app_state.db_pool,
+        key.id,
+        payload.user_id,
+        payload.job_id,
+        payload.status.as_deref().unwrap_or("applied"),
+        payload.notes.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(application))
+}
+
+/// Deletes everything in the key's sandbox now, without waiting for the
+/// nightly purge. Returns what was deleted.
+///
+/// # Errors
+///
+/// Returns an error if the key is invalid or the database operation fails.
+pub async fn purge_sandbox(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<ApiResponse<SandboxSummary>> {
+    let key = api_keys::authenticate(&app_state.db_pool, &headers, SANDBOX_SCOPE).await?;
+    Ok(ApiResponse::ok(sandbox::purge(&app_state.db_pool, Some(key.id)).await?))
+}
@@ -0,0 +1,151 @@
+//! Screening question and applicant list handlers.
+//!
+//! The job's poster, seats at its company, and admins manage a posting's
+//! screening questions and see its applicants; viewer seats can read but
+//! not change the questions. See [`crate::screening`] for how answers are
+//! scored and [`crate::applicant_ranking`] for how applicants are ranked.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use tracing::info;
+
+use super::types::{ApplicantListParams, SetScreeningQuestionsPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+use crate::response::ApiResponse;
+use crate::applicant_ranking::{self, RankedApplicant};
+use crate::screening::{self, CandidateQuestion, ScreeningQuestion};
+use crate::teams;
+
+/// Ensures the caller may manage the job: an admin, its poster, or a seat
+/// at its company (only owners and recruiters when `pipeline_only`).
+/// Returns the company of the caller's seat, if any.
+pub(super) async fn require_job_access(
+    app_state: &AppState,
+    auth_user: &AuthUser,
+    job_id: i32,
+    pipeline_only: bool,
+) -> AppResult<Option<i32>> {
+    let role = auth_user
+        .require_role(&app_state.db_pool, &[UserRole::Employer, UserRole::Admin])
+        .await?;
+    let seat = teams::seat_for(&app_state.db_pool, auth_user.user_id).await?;
+    if pipeline_only
+        && let Some(seat) = &seat
+        && !teams::PIPELINE_ROLES.contains(&seat.role)
+    {
+        return Err(AppError::Forbidden);
+    }
+    let company_id = seat.map(|s| s.company_id);
+    screening::ensure_job_access(
+        &app_state.db_pool,
+        job_id,
+        auth_user.user_id,
+        role == UserRole::Admin,
+        company_id,
+    )
+    .await?;
+    Ok(company_id)
+}
+
+/// Returns the screening questions candidates answer when applying, without
+/// expected answers.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the job is not open for applications, or
+/// an error if the database operation fails.
+pub async fn get_job_screening_questions(
+    _auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+) -> AppResult<ApiResponse<Vec<CandidateQuestion>>> {
+    Ok(ApiResponse::ok(screening::for_candidates(&app_state.db_pool, job_id).await?))
+}
+
+/// Returns a posting's screening questions with their expected answers.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an employer
+/// - The job does not exist or the user may not manage it (404)
+/// - Database operation fails
+pub async fn get_screening_questions(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+) -> AppResult<ApiResponse<Vec<ScreeningQuestion>>> {
+    require_job_access(&app_state, &auth_user, job_id, false).await?;
+    Ok(ApiResponse::ok(screening::list(&app_state.db_pool, job_id).await?))
+}
+
+/// Replaces a posting's screening questions. Applications already made
+/// keep the questions they answered.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an employer, or holds a viewer seat
+/// - The job does not exist or the user may not manage it (404)
+/// - Validation fails
+/// - Database operation fails
+pub async fn set_screening_questions(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+    Json(payload): Json<SetScreeningQuestionsPayload>,
+) -> AppResult<ApiResponse<Vec<ScreeningQuestion>>> {
+    let company_id = require_job_access(&app_state, &auth_user, job_id, true).await?;
+
+    let questions = screening::replace(&app_state.db_pool, job_id, payload.questions).await?;
+    info!(
+        "Screening questions set for job {} by {}: {}",
+        job_id,
+        auth_user.user_id,
+        questions.len()
+    );
+
+    if let Some(company_id) = company_id {
+        teams::record_activity(
+            &app_state.db_pool,
+            auth_user.user_id,
+            company_id,
+            "job.screening_questions",
+            "job",
+            &job_id.to_string(),
+            serde_json::json!({ "questions": questions.len() }),
+        )
+        .await;
+    }
+
+    Ok(ApiResponse::ok(questions))
+}
+
+/// Lists a posting's applicants with their screening results and an
+/// explained score. `?sort=score` ranks them by score; the default is
+/// newest first.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an employer
+/// - The job does not exist or the user may not manage it (404)
+/// - Database operation fails
+pub async fn list_applicants(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(job_id): Path<i32>,
+    Query(params): Query<ApplicantListParams>,
+) -> AppResult<ApiResponse<Vec<RankedApplicant>>> {
+    require_job_access(&app_state, &auth_user, job_id, false).await?;
+
+    let applicants = screening::applicants(&app_state.db_pool, job_id).await?;
+    let flagged = applicants.iter().filter(|a| a.screening_flagged).count();
+    let ranked = applicant_ranking::rank(&app_state.db_pool, job_id, applicants, params.sort.unwrap_or_default()).await?;
+    Ok(ApiResponse::ok(ranked).meta("flagged", flagged))
+}
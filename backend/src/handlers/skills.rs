@@ -0,0 +1,294 @@
+//! Skill proficiency handlers: rate skills, see their effective proficiency,
+//! and see learning progress per skill across roadmaps. Also curation of
+//! the profile's skills and target roles: removing, renaming, and the list
+//! of rejected items that AI extraction leaves out.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use tracing::info;
+use validator::Validate;
+
+use super::types::{CurateItemsPayload, RateSkillPayload};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::curation::{self, CuratedItems, Curation, RejectedItem};
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileItemKind;
+use crate::response::ApiResponse;
+use crate::skills::{self, SkillProgress, SkillRating};
+
+/// Lists the user's skill ratings with decay applied.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_skill_ratings(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<SkillRating>>> {
+    let ratings = skills::load_ratings(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(ratings))
+}
+
+/// Rates the user's proficiency in a skill and when they last used it.
+///
+/// Replaces any earlier rating of the skill (matched case-insensitively).
+/// A skill not yet on the user's profile is added to it. The rating also
+/// sets the skill's learning progress: known at 3 or above, otherwise
+/// still being learned.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - The skill name is empty or longer than 100 characters
+/// - Proficiency is outside 1-5, or `last_used_on` is in the future
+/// - Database operation fails
+pub async fn rate_skill(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(skill): Path<String>,
+    Json(payload): Json<RateSkillPayload>,
+) -> AppResult<ApiResponse<SkillRating>> {
+    payload.validate()?;
+
+    let skill = skill.trim();
+    if skill.is_empty() || skill.chars().count() > 100 {
+        return Err(AppError::ValidationError(
+            "Skill name must be between 1 and 100 characters".to_string(),
+        ));
+    }
+    let today = chrono::Utc::now().date_naive();
+    if payload.last_used_on.is_some_and(|d| d > today) {
+        return Err(AppError::ValidationError(
+            "last_used_on cannot be in the future".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+
+    let rating = sqlx::query_as::<_, SkillRating>(
+        r#"
+        INSERT INTO user_skill_ratings (user_id, skill, proficiency, last_used_on)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, LOWER(skill)) DO UPDATE
+        SET skill = EXCLUDED.skill,
+            proficiency = EXCLUDED.proficiency,
+            last_used_on = EXCLUDED.last_used_on,
+            updated_at = NOW()
+        RETURNING skill, proficiency, last_used_on, updated_at
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(skill)
+    .bind(payload.proficiency)
+    .bind(payload.last_used_on)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    skills::record_rating_progress(&mut tx, auth_user.user_id, skill, payload.proficiency).await?;
+    curation::accept(&mut tx, auth_user.user_id, ProfileItemKind::Skill, &[skill.to_string()]).await?;
+
+    let added = sqlx::query(
+        r#"
+        UPDATE users
+        SET skills = array_append(skills, $2), updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND NOT EXISTS (SELECT 1 FROM unnest(skills) s WHERE LOWER(s) = LOWER($2))
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(skill)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    tx.commit().await?;
+    if added {
+        app_state.user_cache.invalidate(auth_user.user_id).await;
+    }
+
+    info!(
+        "User {} rated {} at {}/{}",
+        auth_user.user_id,
+        rating.skill,
+        rating.proficiency,
+        skills::MAX_PROFICIENCY
+    );
+    Ok(ApiResponse::ok(rating.with_decay(today)))
+}
+
+/// Lists the user's learning progress per skill, across all roadmaps.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_skill_progress(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<SkillProgress>>> {
+    let progress = skills::load_progress(&app_state.db_pool, auth_user.user_id).await?;
+    Ok(ApiResponse::ok(progress))
+}
+
+/// Removes a skill from the profile, along with its proficiency rating.
+/// The skill is rejected, so AI extraction will not add it back.
+///
+/// # Errors
+///
+/// Returns an error if the skill name is invalid or the database operation
+/// fails.
+pub async fn remove_skill(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(skill): Path<String>,
+) -> AppResult<ApiResponse<CuratedItems>> {
+    let curation = Curation {
+        remove: vec![curation::clean_item(&skill)?],
+        reject: true,
+        ..Curation::default()
+    };
+    apply(&app_state, auth_user, ProfileItemKind::Skill, curation).await
+}
+
+/// Removes a target role from the profile and rejects it, so AI extraction
+/// will not add it back.
+///
+/// # Errors
+///
+/// Returns an error if the role is invalid or the database operation fails.
+pub async fn remove_target_role(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(role): Path<String>,
+) -> AppResult<ApiResponse<CuratedItems>> {
+    let curation = Curation {
+        remove: vec![curation::clean_item(&role)?],
+        reject: true,
+        ..Curation::default()
+    };
+    apply(&app_state, auth_user, ProfileItemKind::TargetRole, curation).await
+}
+
+/// Removes and renames several skills at once (see
+/// [`curation::curate`]). Removed skills lose their proficiency rating;
+/// renamed ones keep it.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid or the database operation
+/// fails.
+pub async fn curate_skills(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CurateItemsPayload>,
+) -> AppResult<ApiResponse<CuratedItems>> {
+    apply(&app_state, auth_user, ProfileItemKind::Skill, curation_from(payload)?).await
+}
+
+/// Removes and renames several target roles at once (see
+/// [`curation::curate`]).
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid or the database operation
+/// fails.
+pub async fn curate_target_roles(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CurateItemsPayload>,
+) -> AppResult<ApiResponse<CuratedItems>> {
+    apply(&app_state, auth_user, ProfileItemKind::TargetRole, curation_from(payload)?).await
+}
+
+/// Lists the skills and target roles AI extraction leaves out.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_rejected_items(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<RejectedItem>>> {
+    Ok(ApiResponse::ok(
+        curation::list_rejected(&app_state.db_pool, auth_user.user_id).await?,
+    ))
+}
+
+/// Takes an item off the rejected list, so AI extraction may add it again.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the item was not rejected, or an error
+/// if the database operation fails.
+pub async fn unreject_item(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((kind, value)): Path<(ProfileItemKind, String)>,
+) -> AppResult<ApiResponse<serde_json::Value>> {
+    let deleted = sqlx::query(
+        "DELETE FROM user_rejected_profile_items WHERE user_id = $1 AND kind = $2 AND LOWER(value) = LOWER($3)",
+    )
+    .bind(auth_user.user_id)
+    .bind(kind)
+    .bind(value.trim())
+    .execute(&app_state.db_pool)
+    .await?
+    .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(ApiResponse::ok(serde_json::json!({ "message": "Item is no longer rejected" })))
+}
+
+/// Validates a bulk payload. Removed items and old names are rejected
+/// unless `reject` is false.
+fn curation_from(payload: CurateItemsPayload) -> AppResult<Curation> {
+    payload.validate()?;
+    if payload.remove.is_empty() && payload.rename.is_empty() {
+        return Err(AppError::ValidationError(
+            "Nothing to change: give items to remove or rename".to_string(),
+        ));
+    }
+
+    Ok(Curation {
+        remove: payload
+            .remove
+            .iter()
+            .map(|item| curation::clean_item(item))
+            .collect::<AppResult<_>>()?,
+        rename: payload
+            .rename
+            .iter()
+            .map(|r| Ok((curation::clean_item(&r.from)?, curation::clean_item(&r.to)?)))
+            .collect::<AppResult<_>>()?,
+        reject: payload.reject.unwrap_or(true),
+    })
+}
+
+/// Applies a curation in its own transaction.
+async fn apply(
+    app_state: &AppState,
+    auth_user: AuthUser,
+    kind: ProfileItemKind,
+    curation: Curation,
+) -> AppResult<ApiResponse<CuratedItems>> {
+    let mut tx = app_state.db_pool.begin().await?;
+    let curated = curation::curate(&mut tx, auth_user.user_id, kind, &curation).await?;
+    tx.commit().await?;
+    app_state.user_cache.invalidate(auth_user.user_id).await;
+
+    info!(
+        "User {} curated {:?}: removed {}, renamed {}",
+        auth_user.user_id,
+        kind,
+        curation.remove.len(),
+        curation.rename.len()
+    );
+    Ok(ApiResponse::ok(curated))
+}
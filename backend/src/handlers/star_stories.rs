@@ -0,0 +1,132 @@
+//! STAR story handlers: behavioral interview stories mined from the user's
+//! background, refined over several turns, and saved by competency.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use validator::Validate;
+
+use super::types::{MineStarStoriesPayload, RefineStarStoryPayload, StarStoryParams, UpdateStarStoryPayload};
+use crate::AppState;
+use crate::ai::types::AIProvider;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::star_stories::{self, RefinedStory, StarStory, StoryEdit};
+
+/// Mines the caller's projects and CV for STAR stories and saves them as
+/// drafts, each with follow-up questions to refine it.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the caller has neither
+/// projects nor a CV, the AI service is not configured, or the AI call
+/// fails.
+pub async fn mine_star_stories(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MineStarStoriesPayload>,
+) -> AppResult<ApiResponse<Vec<StarStory>>> {
+    payload.validate()?;
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let stories = star_stories::mine(
+        &app_state.db_pool,
+        ai_service,
+        payload.provider.unwrap_or(AIProvider::Gemini),
+        auth_user.user_id,
+        &payload.competencies,
+    )
+    .await?;
+    Ok(ApiResponse::ok(stories))
+}
+
+/// Revises one of the caller's stories with their answers or feedback.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the story is not the
+/// caller's (404), the AI service is not configured, or the AI call fails.
+pub async fn refine_star_story(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(story_id): Path<i32>,
+    Json(payload): Json<RefineStarStoryPayload>,
+) -> AppResult<ApiResponse<RefinedStory>> {
+    payload.validate()?;
+    let ai_service = app_state
+        .ai_service
+        .as_ref()
+        .ok_or_else(|| AppError::ConfigurationError("AI service not configured".to_string()))?;
+    let refined = star_stories::refine(
+        &app_state.db_pool,
+        ai_service,
+        payload.provider.unwrap_or(AIProvider::Gemini),
+        auth_user.user_id,
+        story_id,
+        payload.input.trim(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(refined))
+}
+
+/// Lists the caller's stories, polished first, optionally by competency or
+/// status.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_star_stories(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<StarStoryParams>,
+) -> AppResult<ApiResponse<Vec<StarStory>>> {
+    let stories = star_stories::list(&app_state.db_pool, auth_user.user_id, params.competency, params.status).await?;
+    Ok(ApiResponse::ok(stories))
+}
+
+/// Edits one of the caller's stories; `"status": "polished"` saves it as
+/// ready for interviews.
+///
+/// # Errors
+///
+/// Returns an error if the payload is invalid, the story is not the
+/// caller's (404), or the database operation fails.
+pub async fn update_star_story(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(story_id): Path<i32>,
+    Json(payload): Json<UpdateStarStoryPayload>,
+) -> AppResult<ApiResponse<StarStory>> {
+    payload.validate()?;
+    let edit = StoryEdit {
+        title: payload.title.as_deref().map(str::trim),
+        competencies: payload.competencies.as_deref(),
+        situation: payload.situation.as_deref().map(str::trim),
+        task: payload.task.as_deref().map(str::trim),
+        action: payload.action.as_deref().map(str::trim),
+        result: payload.result.as_deref().map(str::trim),
+        status: payload.status,
+    };
+    let story = star_stories::update(&app_state.db_pool, auth_user.user_id, story_id, edit).await?;
+    Ok(ApiResponse::ok(story))
+}
+
+/// Deletes one of the caller's stories.
+///
+/// # Errors
+///
+/// Returns an error if the story is not the caller's (404) or the database
+/// operation fails.
+pub async fn delete_star_story(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(story_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    star_stories::delete(&app_state.db_pool, auth_user.user_id, story_id).await?;
+    Ok(ApiResponse::ok(json!({ "deleted": story_id })))
+}
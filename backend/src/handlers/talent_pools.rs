@@ -0,0 +1,342 @@
+//! Talent pool handlers.
+//!
+//! Any seat at a company can browse its pools and alerts; owners and
+//! recruiters create pools, save candidates, and invite pools to postings.
+//! See [`crate::talent_pools`] for how alerts are raised.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Value, json};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    AddPoolMemberPayload, CreateTalentPoolPayload, InviteTalentPoolPayload, TalentPoolParams,
+    UpdatePoolMemberPayload, UpdateTalentPoolPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::AppResult;
+use crate::models::SeatRole;
+use crate::response::ApiResponse;
+use crate::talent_pools::{self, PoolAlert, PoolChanges, PoolDetail, PoolInvitation, TalentPool};
+use crate::teams::{self, PIPELINE_ROLES, Seat};
+
+/// Alerts returned by the alert feed
+const ALERT_LIMIT: i64 = 100;
+
+async fn any_seat(app_state: &AppState, auth_user: &AuthUser) -> AppResult<Seat> {
+    teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await
+}
+
+/// Lists the caller's company's talent pools.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if a
+/// database query fails.
+pub async fn list_pools(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<TalentPool>>> {
+    let seat = any_seat(&app_state, &auth_user).await?;
+    Ok(ApiResponse::ok(talent_pools::list(&app_state.db_pool, seat.company_id).await?))
+}
+
+/// Creates a talent pool (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - Validation fails, or the name is taken
+/// - Database operation fails
+pub async fn create_pool(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateTalentPoolPayload>,
+) -> AppResult<ApiResponse<TalentPool>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let pool = talent_pools::create(
+        &app_state.db_pool,
+        seat.company_id,
+        auth_user.user_id,
+        &payload.name,
+        payload.description.as_deref(),
+        payload.required_skills,
+    )
+    .await?;
+    info!("Talent pool {} created by {}", pool.id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.created",
+        "talent_pool",
+        &pool.id.to_string(),
+        json!({ "name": pool.name }),
+    )
+    .await;
+    Ok(ApiResponse::ok(pool))
+}
+
+/// Returns a pool and its members, optionally only those with `?tag=`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat or the pool is not
+/// their company's, or an error if a database query fails.
+pub async fn get_pool(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(pool_id): Path<i32>,
+    Query(params): Query<TalentPoolParams>,
+) -> AppResult<ApiResponse<PoolDetail>> {
+    let seat = any_seat(&app_state, &auth_user).await?;
+    let tag = params.tag.as_deref().filter(|t| !t.trim().is_empty());
+    Ok(ApiResponse::ok(
+        talent_pools::get(&app_state.db_pool, seat.company_id, pool_id, tag).await?,
+    ))
+}
+
+/// Updates a pool's name, description, or skills (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool is not the company's (404)
+/// - Validation fails, or the name is taken
+/// - Database operation fails
+pub async fn update_pool(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(pool_id): Path<i32>,
+    Json(payload): Json<UpdateTalentPoolPayload>,
+) -> AppResult<ApiResponse<TalentPool>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let changes = PoolChanges {
+        name: payload.name,
+        description: payload.description,
+        required_skills: payload.required_skills,
+    };
+    let pool = talent_pools::update(&app_state.db_pool, seat.company_id, pool_id, changes).await?;
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.updated",
+        "talent_pool",
+        &pool_id.to_string(),
+        json!({ "name": pool.name }),
+    )
+    .await;
+    Ok(ApiResponse::ok(pool))
+}
+
+/// Deletes a pool with its members and alerts (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool is not the company's (404)
+/// - Database operation fails
+pub async fn delete_pool(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(pool_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+
+    talent_pools::delete(&app_state.db_pool, seat.company_id, pool_id).await?;
+    info!("Talent pool {} deleted by {}", pool_id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.deleted",
+        "talent_pool",
+        &pool_id.to_string(),
+        json!({}),
+    )
+    .await;
+    Ok(ApiResponse::ok(json!({ "message": "Talent pool deleted" })))
+}
+
+/// Saves a candidate in a pool with tags and notes, replacing them if the
+/// candidate is already in it (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool is not the company's, or the candidate is not a job seeker (404)
+/// - Validation fails
+/// - Database operation fails
+pub async fn add_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(pool_id): Path<i32>,
+    Json(payload): Json<AddPoolMemberPayload>,
+) -> AppResult<ApiResponse<PoolDetail>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    talent_pools::add_member(
+        &app_state.db_pool,
+        seat.company_id,
+        pool_id,
+        payload.candidate_id,
+        payload.tags,
+        payload.notes.as_deref(),
+        auth_user.user_id,
+    )
+    .await?;
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.member_added",
+        "talent_pool",
+        &pool_id.to_string(),
+        json!({ "candidate_id": payload.candidate_id }),
+    )
+    .await;
+    Ok(ApiResponse::ok(
+        talent_pools::get(&app_state.db_pool, seat.company_id, pool_id, None).await?,
+    ))
+}
+
+/// Changes a pool member's tags or notes (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool is not the company's, or the candidate is not in it (404)
+/// - Validation fails
+/// - Database operation fails
+pub async fn update_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((pool_id, candidate_id)): Path<(i32, Uuid)>,
+    Json(payload): Json<UpdatePoolMemberPayload>,
+) -> AppResult<ApiResponse<PoolDetail>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    talent_pools::update_member(
+        &app_state.db_pool,
+        seat.company_id,
+        pool_id,
+        candidate_id,
+        payload.tags,
+        payload.notes.as_deref(),
+    )
+    .await?;
+    Ok(ApiResponse::ok(
+        talent_pools::get(&app_state.db_pool, seat.company_id, pool_id, None).await?,
+    ))
+}
+
+/// Removes a candidate from a pool (owners and recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool is not the company's, or the candidate is not in it (404)
+/// - Database operation fails
+pub async fn remove_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path((pool_id, candidate_id)): Path<(i32, Uuid)>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+
+    talent_pools::remove_member(&app_state.db_pool, seat.company_id, pool_id, candidate_id).await?;
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.member_removed",
+        "talent_pool",
+        &pool_id.to_string(),
+        json!({ "candidate_id": candidate_id }),
+    )
+    .await;
+    Ok(ApiResponse::ok(json!({ "message": "Candidate removed from pool" })))
+}
+
+/// Invites every member of a pool to apply to one of the company's open
+/// postings; members who already applied are skipped (owners and
+/// recruiters).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller's seat cannot change the pipeline
+/// - The pool or job is not the company's (404)
+/// - The job is not open for applications, or validation fails
+/// - Database operation fails
+pub async fn invite_pool(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(pool_id): Path<i32>,
+    Json(payload): Json<InviteTalentPoolPayload>,
+) -> AppResult<ApiResponse<PoolInvitation>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, PIPELINE_ROLES).await?;
+    payload.validate()?;
+
+    let invitation = talent_pools::invite(
+        &app_state.db_pool,
+        seat.company_id,
+        &seat.company_name,
+        pool_id,
+        payload.job_id,
+        payload.message.as_deref(),
+        auth_user.user_id,
+    )
+    .await?;
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "talent_pool.invited",
+        "talent_pool",
+        &pool_id.to_string(),
+        json!({ "job_id": invitation.job_id, "invited": invitation.invited }),
+    )
+    .await;
+    Ok(ApiResponse::ok(invitation))
+}
+
+/// Lists recent alerts about pooled candidates gaining a pool's skills or
+/// verified certifications, newest first.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if a
+/// database query fails.
+pub async fn list_alerts(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<PoolAlert>>> {
+    let seat = any_seat(&app_state, &auth_user).await?;
+    Ok(ApiResponse::ok(
+        talent_pools::alerts(&app_state.db_pool, seat.company_id, ALERT_LIMIT).await?,
+    ))
+}
@@ -0,0 +1,525 @@
+//! Company team handlers: seats, invitations, and per-seat activity.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{
+    AcceptInvitationPayload, CreateCompanyPayload, InviteMemberPayload, PendingInvitation,
+    TeamActivity, TeamActivityParams, TeamMember, TeamOverview, UpdateSeatPayload,
+};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::models::{SeatRole, UserRole};
+use crate::teams::{self, INVITATION_TTL_DAYS};
+
+/// Default number of activity entries returned.
+const DEFAULT_ACTIVITY_LIMIT: i64 = 50;
+
+/// Creates a company team with the caller as its first owner.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not an employer
+/// - User already holds a seat on a team
+/// - Validation fails
+/// - Database operation fails
+pub async fn create_company(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateCompanyPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    auth_user
+        .require_role(&app_state.db_pool, &[UserRole::Employer, UserRole::Admin])
+        .await?;
+    payload.validate()?;
+
+    if teams::seat_for(&app_state.db_pool, auth_user.user_id).await?.is_some() {
+        return Err(AppError::BadRequest(
+            "You already belong to a company team".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let company_id = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO companies (name, created_by) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(payload.name.trim())
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO company_members (company_id, user_id, role) VALUES ($1, $2, 'owner')")
+        .bind(company_id)
+        .bind(auth_user.user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("Company {} created by {}", company_id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        company_id,
+        "team.create",
+        "company",
+        &company_id.to_string(),
+        json!({ "name": payload.name.trim() }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({
+        "message": "Company created successfully",
+        "company_id": company_id
+    })))
+}
+
+/// Returns the caller's team: their seat, the members, and pending invitations.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the caller has no seat, or an error if a
+/// database query fails.
+pub async fn get_team(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<TeamOverview>> {
+    let seat = teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await?;
+
+    let members = sqlx::query_as::<_, TeamMember>(
+        r#"
+        SELECT u.id AS user_id, u.full_name, u.email, m.role, m.invited_by, m.joined_at
+        FROM company_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.company_id = $1
+        ORDER BY m.role, m.joined_at
+        "#,
+    )
+    .bind(seat.company_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let invitations = sqlx::query_as::<_, PendingInvitation>(
+        r#"
+        SELECT id, email, role, invited_by, expires_at
+        FROM company_invitations
+        WHERE company_id = $1 AND accepted_at IS NULL AND expires_at > NOW()
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(seat.company_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(TeamOverview {
+        seat,
+        members,
+        invitations,
+    }))
+}
+
+/// Invites a user to the caller's team (owners only).
+///
+/// Re-inviting an email replaces its pending invitation. The token is only
+/// returned here; deliver it to the invitee out of band.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - Validation fails
+/// - The email already belongs to a team member
+/// - Database operation fails
+pub async fn invite_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<InviteMemberPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    payload.validate()?;
+    let email = payload.email.trim().to_lowercase();
+
+    let already_member = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM company_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.company_id = $1 AND LOWER(u.email) = $2
+        )
+        "#,
+    )
+    .bind(seat.company_id)
+    .bind(&email)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    if already_member {
+        return Err(AppError::BadRequest(format!("{} is already on the team", email)));
+    }
+
+    let (token, token_hash) = teams::new_invitation_token();
+    let expires_at = Utc::now() + Duration::days(INVITATION_TTL_DAYS);
+
+    let mut tx = app_state.db_pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM company_invitations WHERE company_id = $1 AND LOWER(email) = $2 AND accepted_at IS NULL",
+    )
+    .bind(seat.company_id)
+    .bind(&email)
+    .execute(&mut *tx)
+    .await?;
+
+    let invitation_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO company_invitations (company_id, email, role, token_hash, invited_by, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(seat.company_id)
+    .bind(&email)
+    .bind(payload.role)
+    .bind(&token_hash)
+    .bind(auth_user.user_id)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    info!("Invitation {} to company {} sent by {}", invitation_id, seat.company_id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "team.invite",
+        "invitation",
+        &invitation_id.to_string(),
+        json!({ "email": email, "role": payload.role }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({
+        "invitation_id": invitation_id,
+        "token": token,
+        "expires_at": expires_at
+    })))
+}
+
+/// Revokes a pending invitation (owners only).
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no pending invitation with this ID exists
+/// on the caller's team.
+pub async fn revoke_invitation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(invitation_id): Path<i32>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+
+    let revoked = sqlx::query(
+        "DELETE FROM company_invitations WHERE id = $1 AND company_id = $2 AND accepted_at IS NULL",
+    )
+    .bind(invitation_id)
+    .bind(seat.company_id)
+    .execute(&app_state.db_pool)
+    .await?
+    .rows_affected();
+    if revoked == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "team.invite_revoked",
+        "invitation",
+        &invitation_id.to_string(),
+        json!({}),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({ "message": "Invitation revoked" })))
+}
+
+/// Accepts an invitation addressed to the caller's email.
+///
+/// Job seekers who accept are promoted to the `employer` account role.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The token is unknown, already used, or expired (404)
+/// - The invitation was sent to a different email (403)
+/// - Caller already holds a seat on a team
+/// - Database operation fails
+pub async fn accept_invitation(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<AcceptInvitationPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    if teams::seat_for(&app_state.db_pool, auth_user.user_id).await?.is_some() {
+        return Err(AppError::BadRequest(
+            "You already belong to a company team".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let (invitation_id, company_id, email, role, invited_by) =
+        sqlx::query_as::<_, (i32, i32, String, SeatRole, Option<Uuid>)>(
+            r#"
+            SELECT id, company_id, email, role, invited_by
+            FROM company_invitations
+            WHERE token_hash = $1 AND accepted_at IS NULL AND expires_at > NOW()
+            FOR UPDATE
+            "#,
+        )
+        .bind(teams::hash_token(payload.token.trim()))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let user_email = sqlx::query_scalar::<_, String>("SELECT email FROM users WHERE id = $1")
+        .bind(auth_user.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if !user_email.eq_ignore_ascii_case(&email) {
+        warn!("User {} tried to accept invitation {} for another email", auth_user.user_id, invitation_id);
+        return Err(AppError::Forbidden);
+    }
+
+    sqlx::query(
+        "INSERT INTO company_members (company_id, user_id, role, invited_by) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(company_id)
+    .bind(auth_user.user_id)
+    .bind(role)
+    .bind(invited_by)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE company_invitations SET accepted_at = NOW() WHERE id = $1")
+        .bind(invitation_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE users SET role = 'employer', updated_at = NOW() WHERE id = $1 AND role = 'job_seeker'")
+        .bind(auth_user.user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    app_state.user_cache.invalidate(auth_user.user_id).await;
+
+    info!("User {} joined company {} as {:?}", auth_user.user_id, company_id, role);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        company_id,
+        "team.join",
+        "invitation",
+        &invitation_id.to_string(),
+        json!({ "role": role, "invited_by": invited_by }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({
+        "message": "Invitation accepted",
+        "company_id": company_id,
+        "role": role
+    })))
+}
+
+/// Changes a member's seat role (owners only).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not a team owner
+/// - The user is not on the caller's team (404)
+/// - The change would leave the team without an owner
+/// - Database operation fails
+pub async fn update_member_role(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(member_id): Path<Uuid>,
+    Json(payload): Json<UpdateSeatPayload>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let current = member_role_for_update(&mut tx, seat.company_id, member_id).await?;
+    if current == SeatRole::Owner && payload.role != SeatRole::Owner {
+        ensure_other_owner(&mut tx, seat.company_id, member_id).await?;
+    }
+
+    sqlx::query("UPDATE company_members SET role = $3 WHERE company_id = $1 AND user_id = $2")
+        .bind(seat.company_id)
+        .bind(member_id)
+        .bind(payload.role)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        "team.role_change",
+        "user",
+        &member_id.to_string(),
+        json!({ "from": current, "to": payload.role }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({
+        "message": "Seat role updated",
+        "user_id": member_id,
+        "role": payload.role
+    })))
+}
+
+/// Removes a member from the team.
+///
+/// Owners can remove anyone; other members can only remove themselves.
+/// Jobs the member posted stay in the company pipeline.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Caller is not an owner and is removing someone else (403)
+/// - The user is not on the caller's team (404)
+/// - The removal would leave the team without an owner
+/// - Database operation fails
+pub async fn remove_member(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(member_id): Path<Uuid>,
+) -> AppResult<ApiResponse<Value>> {
+    let seat = teams::require_seat(
+        &app_state.db_pool,
+        auth_user.user_id,
+        &[SeatRole::Owner, SeatRole::Recruiter, SeatRole::Viewer],
+    )
+    .await?;
+    let leaving = member_id == auth_user.user_id;
+    if !leaving && seat.role != SeatRole::Owner {
+        return Err(AppError::Forbidden);
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let role = member_role_for_update(&mut tx, seat.company_id, member_id).await?;
+    if role == SeatRole::Owner {
+        ensure_other_owner(&mut tx, seat.company_id, member_id).await?;
+    }
+
+    sqlx::query("DELETE FROM company_members WHERE company_id = $1 AND user_id = $2")
+        .bind(seat.company_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("User {} removed from company {} by {}", member_id, seat.company_id, auth_user.user_id);
+    teams::record_activity(
+        &app_state.db_pool,
+        auth_user.user_id,
+        seat.company_id,
+        if leaving { "team.leave" } else { "team.remove" },
+        "user",
+        &member_id.to_string(),
+        json!({ "role": role }),
+    )
+    .await;
+
+    Ok(ApiResponse::ok(json!({ "message": "Member removed" })))
+}
+
+/// Returns the team's audit trail, newest first (owners only).
+///
+/// Pass `actor_id` to see the activity of a single seat.
+///
+/// # Errors
+///
+/// Returns an error if the caller is not a team owner or the query fails.
+pub async fn get_team_activity(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<TeamActivityParams>,
+) -> AppResult<ApiResponse<Vec<TeamActivity>>> {
+    let seat = teams::require_seat(&app_state.db_pool, auth_user.user_id, &[SeatRole::Owner]).await?;
+    let limit = params.limit.unwrap_or(DEFAULT_ACTIVITY_LIMIT).clamp(1, 500);
+
+    let activity = sqlx::query_as::<_, TeamActivity>(
+        r#"
+        SELECT a.id, a.actor_id, u.full_name AS actor_name, a.action,
+               a.target_type, a.target_id, a.metadata, a.impersonated_by, a.created_at
+        FROM audit_logs a
+        LEFT JOIN users u ON u.id = a.actor_id
+        WHERE a.company_id = $1 AND ($2::uuid IS NULL OR a.actor_id = $2)
+        ORDER BY a.created_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(seat.company_id)
+    .bind(params.actor_id)
+    .bind(limit)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(activity))
+}
+
+/// Locks and returns a member's seat role.
+async fn member_role_for_update(
+    conn: &mut sqlx::PgConnection,
+    company_id: i32,
+    user_id: Uuid,
+) -> AppResult<SeatRole> {
+    sqlx::query_scalar::<_, SeatRole>(
+        "SELECT role FROM company_members WHERE company_id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(company_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Fails unless the team has an owner other than `user_id`.
+async fn ensure_other_owner(
+    conn: &mut sqlx::PgConnection,
+    company_id: i32,
+    user_id: Uuid,
+) -> AppResult<()> {
+    // Lock every owner seat so two owners cannot demote each other concurrently
+    let owners = sqlx::query_scalar::<_, Uuid>(
+        "SELECT user_id FROM company_members WHERE company_id = $1 AND role = 'owner' FOR UPDATE",
+    )
+    .bind(company_id)
+    .fetch_all(conn)
+    .await?;
+
+    if owners.iter().any(|owner| *owner != user_id) {
+        Ok(())
+    } else {
+        Err(AppError::BadRequest(
+            "A team must keep at least one owner".to_string(),
+        ))
+    }
+}
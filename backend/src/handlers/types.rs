@@ -20,6 +20,10 @@ pub struct RegisterPayload {
     /// Password (minimum 8 characters)
     #[validate(length(min = 8, message = "Password must be at least 8 characters long"))]
     pub password: String,
+    /// Account role (`job_seeker` or `employer`, defaults to `job_seeker`)
+    pub role: Option<UserRole>,
+    /// Referral code of the user who invited this one
+    pub referral_code: Option<String>,
 }
 
 /// User login payload.
@@ -33,6 +37,13 @@ pub struct LoginPayload {
     pub password: String,
 }
 
+/// Demo login payload (only honoured when `DEMO_MODE` is enabled).
+#[derive(Debug, Deserialize)]
+pub struct DemoLoginPayload {
+    /// Demo persona to sign in as (`job_seeker` or `employer`, defaults to `job_seeker`)
+    pub persona: Option<UserRole>,
+}
+
 /// Login response containing JWT token and user profile.
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
@@ -65,6 +76,12 @@ pub struct UserProfile {
     pub projects: Vec<String>,
     /// Target job roles
     pub target_roles: Vec<String>,
+    /// Availability and work authorization (only on the profile endpoint)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<CandidateAvailability>,
+    /// Solved coding practice problems by platform (only on the profile endpoint)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_practice: Option<Vec<crate::code_practice::PracticeAccount>>,
 }
 
 /// Profile completion payload for onboarding.
@@ -82,6 +99,32 @@ pub struct CompleteProfilePayload {
     pub projects: Option<Vec<String>>,
     /// Target job roles (optional)
     pub target_roles: Option<Vec<String>>,
+    /// Availability and work authorization
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub availability: AvailabilityPayload,
+}
+
+/// Availability and work authorization fields accepted by the profile
+/// payloads. Omitted fields are left unchanged.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct AvailabilityPayload {
+    /// Earliest date the user can start
+    pub available_from: Option<chrono::NaiveDate>,
+    /// Employment types the user wants (empty means any)
+    pub desired_job_types: Option<Vec<JobType>>,
+    /// Whether the user would relocate for an on-site job
+    pub willing_to_relocate: Option<bool>,
+    /// Two-letter code of the country the user is based in
+    #[validate(length(equal = 2, message = "country_code must be a two-letter ISO country code"))]
+    pub country_code: Option<String>,
+    /// Right to work per country (replaces the stored list)
+    pub work_authorizations: Option<Vec<WorkAuthorization>>,
+    /// Academic terms available for internships, e.g. `2026-fall` (replaces the stored list)
+    pub available_terms: Option<Vec<String>>,
+    /// Hours a week the user has for learning
+    #[validate(range(min = 1, max = 80, message = "weekly_learning_hours must be between 1 and 80"))]
+    pub weekly_learning_hours: Option<i32>,
 }
 
 /// Profile update payload (all fields optional).
@@ -103,6 +146,85 @@ pub struct UpdateProfilePayload {
     pub target_roles: Option<Vec<String>>,
     /// Raw CV/resume text
     pub raw_cv_text: Option<String>,
+    /// Availability and work authorization
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub availability: AvailabilityPayload,
+}
+
+/// The profile fields `PATCH /api/users/me` edits. A merge patch is applied
+/// to this document and the result must deserialize and validate again, so
+/// unknown members and removing `full_name` are rejected.
+#[derive(Debug, Serialize, Deserialize, Validate, sqlx::FromRow)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileDocument {
+    /// Full name
+    #[validate(length(min = 1, max = 255, message = "Full name is required"))]
+    pub full_name: String,
+    /// Educational background
+    #[validate(length(max = 255))]
+    pub education_level: Option<String>,
+    /// Experience level
+    pub experience_level: Option<ExperienceLevel>,
+    /// Preferred career track
+    pub preferred_track: Option<CareerTrack>,
+    /// Skills
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 skills are allowed"))]
+    pub skills: Vec<String>,
+    /// Projects
+    #[serde(default)]
+    #[validate(length(max = 50, message = "At most 50 projects are allowed"))]
+    pub projects: Vec<String>,
+    /// Target job roles
+    #[serde(default)]
+    #[validate(length(max = 20, message = "At most 20 target roles are allowed"))]
+    pub target_roles: Vec<String>,
+    /// Links to the user's profiles elsewhere
+    #[serde(default)]
+    #[validate(nested)]
+    #[sqlx(json)]
+    pub social_links: SocialLinks,
+}
+
+/// A profile snapshot taken before an AI-driven update or a revert.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ProfileSnapshot {
+    /// Snapshot ID, used to revert to it
+    pub id: i32,
+    /// What was about to change the profile, e.g. `ai_extract_skills`
+    pub reason: String,
+    /// Profile fields at the time
+    #[sqlx(json)]
+    pub profile: ProfileDocument,
+    /// Whether CV text was stored with the snapshot
+    pub has_cv_text: bool,
+    /// When the profile was last reverted to this snapshot
+    pub reverted_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the snapshot was taken
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Social and portfolio links on a profile.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct SocialLinks {
+    /// LinkedIn profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(url(message = "Invalid LinkedIn URL"))]
+    pub linkedin: Option<String>,
+    /// GitHub profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(url(message = "Invalid GitHub URL"))]
+    pub github: Option<String>,
+    /// Portfolio or personal website
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(url(message = "Invalid portfolio URL"))]
+    pub portfolio: Option<String>,
+    /// X (Twitter) profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(url(message = "Invalid Twitter URL"))]
+    pub twitter: Option<String>,
 }
 
 /// Query parameters for job recommendations.
@@ -117,6 +239,112 @@ pub struct JobQueryParams {
     pub limit: Option<i64>,
 }
 
+/// Query parameters for location-aware job search.
+#[derive(Debug, Deserialize)]
+pub struct JobSearchParams {
+    /// Place name (e.g. `Dhaka`) or `lat,lng` to search around
+    pub near: Option<String>,
+    /// Search radius around `near` in kilometres (default 25)
+    pub radius_km: Option<f64>,
+    /// Also return remote jobs, regardless of distance
+    #[serde(default)]
+    pub include_remote: bool,
+    /// Filter by experience level
+    pub experience_level: Option<ExperienceLevel>,
+    /// Filter by job type
+    pub job_type: Option<JobType>,
+    /// Maximum number of results to return (default 20)
+    pub limit: Option<i64>,
+}
+
+/// A job search hit with its distance from the search location.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JobSearchResult {
+    /// The job listing
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub job: Job,
+    /// Whether the job is remote
+    pub is_remote: bool,
+    /// Distance from the search location in kilometres (`None` without `near`
+    /// or when the job has not been geocoded)
+    pub distance_km: Option<f64>,
+}
+
+/// Query parameters for browsing internships.
+#[derive(Debug, Deserialize)]
+pub struct InternshipQueryParams {
+    /// Only internships overlapping this academic term, e.g. `2026-fall`
+    pub term: Option<String>,
+    /// Only internships that do (or do not) offer academic credit
+    pub academic_credit: Option<bool>,
+    /// Maximum number of results to return (default 20)
+    pub limit: Option<i64>,
+}
+
+/// An open internship posting with its term details.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct InternshipListing {
+    /// The job listing
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub job: Job,
+    /// First day of the internship term
+    pub term_start: Option<chrono::NaiveDate>,
+    /// Last day of the internship term
+    pub term_end: Option<chrono::NaiveDate>,
+    /// Whether the internship can count for academic credit
+    pub academic_credit: bool,
+    /// Whether the internship is remote
+    pub is_remote: bool,
+}
+
+/// CV layout templates for PDF generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CvTemplate {
+    /// Clean sans-serif layout (all plans)
+    #[default]
+    Classic,
+    /// Serif executive layout (premium plans)
+    Executive,
+}
+
+impl CvTemplate {
+    /// Whether the template requires a plan with premium templates.
+    pub fn is_premium(self) -> bool {
+        matches!(self, CvTemplate::Executive)
+    }
+}
+
+/// Query parameters for CV generation.
+#[derive(Debug, Deserialize)]
+pub struct CvQueryParams {
+    /// Template to render (defaults to `classic`)
+    #[serde(default)]
+    pub template: CvTemplate,
+    /// `pdf` (default), `large_print_pdf`, `html`, or `text`
+    #[serde(default)]
+    pub format: crate::export::ExportFormat,
+}
+
+/// Query parameters for exporting a roadmap.
+#[derive(Debug, Deserialize)]
+pub struct RoadmapExportParams {
+    /// `pdf` (default), `large_print_pdf`, `html`, or `text`
+    #[serde(default)]
+    pub format: crate::export::ExportFormat,
+}
+
+/// Query parameters for CV upload.
+#[derive(Debug, Default, Deserialize)]
+pub struct CvUploadParams {
+    /// Have Gemini read the PDF natively (Files API) instead of extracting
+    /// its text locally; needed for scanned PDFs
+    #[serde(default)]
+    pub native_pdf: bool,
+}
+
 /// Job recommendation with match analysis.
 #[derive(Debug, Serialize)]
 pub struct JobRecommendation {
@@ -162,12 +390,14 @@ pub struct ResourceRecommendation {
     pub relevance_score: f64,
     /// Skills this resource can help develop
     pub target_skills: Vec<String>,
+    /// Ratings and skips from users who followed it in a roadmap, if any
+    pub quality: Option<crate::resource_progress::ResourceQuality>,
 }
 
 /// Skill gap analysis for a target role.
 #[derive(Debug, Serialize)]
 pub struct SkillGapAnalysis {
-    /// User's current skills
+    /// User's current skills, including skills completed in any roadmap
     pub user_skills: Vec<String>,
     /// Target role being analyzed
     pub target_role: String,
@@ -175,9 +405,13 @@ pub struct SkillGapAnalysis {
     pub required_skills: Vec<String>,
     /// Skills the user needs to acquire
     pub skill_gaps: Vec<String>,
-    /// Skills the user already has
+    /// Skills the user already has, from the profile or completed roadmap phases
     pub matching_skills: Vec<String>,
-    /// Percentage of required skills the user has
+    /// Skill gaps the user is working on in a roadmap
+    pub learning_skills: Vec<String>,
+    /// Matching skills unused long enough to need a refresher
+    pub stale_skills: Vec<String>,
+    /// Percentage of required skills the user has, weighted by proficiency
     pub match_percentage: f64,
     /// Recommended resources to close skill gaps
     pub recommended_resources: Vec<LearningResource>,
@@ -190,6 +424,9 @@ pub struct CreateApplicationPayload {
     pub job_id: i32,
     /// Optional notes about the application
     pub notes: Option<String>,
+    /// Answers to the job's screening questions
+    #[serde(default)]
+    pub screening_answers: Vec<crate::screening::AnswerInput>,
 }
 
 /// Payload for updating an existing application.
@@ -201,6 +438,261 @@ pub struct UpdateApplicationPayload {
     pub notes: Option<String>,
 }
 
+/// Payload for scheduling an application's interview.
+#[derive(Debug, Deserialize)]
+pub struct ScheduleInterviewPayload {
+    /// When the interview starts
+    pub interview_at: chrono::DateTime<chrono::Utc>,
+    /// The company's own meeting link; one is provisioned when omitted
+    pub meeting_url: Option<String>,
+}
+
+/// Payload for regenerating an interview prep pack.
+#[derive(Debug, Default, Deserialize)]
+pub struct PrepPackPayload {
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for creating or updating the caller's mentor profile.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MentorProfilePayload {
+    #[validate(length(min = 1, max = 150, message = "Headline must be between 1 and 150 characters"))]
+    pub headline: String,
+    #[validate(length(max = 5000, message = "Bio must be at most 5000 characters"))]
+    pub bio: Option<String>,
+    /// Topics the mentor can help with
+    #[validate(length(max = 20, message = "At most 20 expertise tags"))]
+    #[serde(default)]
+    pub expertise: Vec<String>,
+    /// Length of a session in minutes (default: 30)
+    #[validate(range(min = 15, max = 180, message = "Sessions must be between 15 and 180 minutes"))]
+    pub session_minutes: Option<i16>,
+    /// Price of a session in the currency's minor unit (default: 0, free)
+    #[validate(range(min = 0, max = 100000, message = "Session price must be between 0 and 100000"))]
+    pub session_price_cents: Option<i32>,
+    /// ISO 4217 currency code of the price (default: usd)
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter ISO code"))]
+    pub currency: Option<String>,
+    /// Whether the mentor is listed and bookable (default: true)
+    pub active: Option<bool>,
+}
+
+/// Query parameters for listing mentors.
+#[derive(Debug, Deserialize)]
+pub struct MentorListParams {
+    /// Expertise tag to filter by
+    pub expertise: Option<String>,
+    /// Maximum mentors to return (default: 20, max: 100)
+    pub limit: Option<i64>,
+    /// Mentors to skip
+    pub offset: Option<i64>,
+}
+
+/// Payload for publishing mentor session slots.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MentorSlotsPayload {
+    /// Start of each slot; each lasts the mentor's session length
+    #[validate(length(min = 1, max = 50, message = "Add between 1 and 50 slots at a time"))]
+    pub starts: Vec<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Payload for booking a mentor session.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MentorBookingPayload {
+    pub slot_id: i32,
+    /// What the mentee wants to discuss
+    #[validate(length(min = 1, max = 1000, message = "Topic must be between 1 and 1000 characters"))]
+    pub topic: String,
+}
+
+/// Payload for cancelling a mentor session.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct CancelBookingPayload {
+    #[validate(length(max = 1000, message = "Reason must be at most 1000 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Payload for connecting a mentor's calendar.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MentorCalendarPayload {
+    pub provider: crate::models::CalendarProvider,
+    /// CalDAV calendar collection URL, or Google calendar ID (default: primary)
+    #[validate(length(max = 2000, message = "Calendar URL must be at most 2000 characters"))]
+    pub calendar_url: Option<String>,
+    /// CalDAV user name
+    #[validate(length(max = 255, message = "Username must be at most 255 characters"))]
+    pub username: Option<String>,
+    /// CalDAV (app) password, or the authorization code from Google's consent screen
+    #[validate(length(min = 1, max = 4096, message = "Secret must be between 1 and 4096 characters"))]
+    pub secret: String,
+}
+
+/// Payload for recording a mentor session's notes.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SessionNotesPayload {
+    #[validate(length(min = 1, max = 20000, message = "Notes must be between 1 and 20000 characters"))]
+    pub notes: String,
+}
+
+/// Payload for assigning action items from a mentor session.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ActionItemsPayload {
+    /// Between 1 and 20 items
+    #[validate(nested)]
+    pub items: Vec<ActionItemPayload>,
+}
+
+/// One action item for the mentee.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ActionItemPayload {
+    #[validate(length(min = 1, max = 300, message = "Title must be between 1 and 300 characters"))]
+    pub title: String,
+    #[validate(length(max = 2000, message = "Details must be at most 2000 characters"))]
+    pub details: Option<String>,
+    pub due_on: Option<chrono::NaiveDate>,
+}
+
+/// Query parameters for listing the caller's action items.
+#[derive(Debug, Deserialize)]
+pub struct ActionItemListParams {
+    /// Only items not yet added to a roadmap (default: false)
+    pub open: Option<bool>,
+}
+
+/// Payload for requesting a mentor payout.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct PayoutRequestPayload {
+    /// Currency to pay out (default: the only one the mentor was paid in)
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter ISO code"))]
+    pub currency: Option<String>,
+}
+
+/// Payload for settling a mentor payout.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ProcessPayoutPayload {
+    /// `paid` or `rejected`
+    pub status: crate::models::MentorPayoutStatus,
+    /// Transfer reference, required when paid
+    #[validate(length(max = 255, message = "Reference must be at most 255 characters"))]
+    pub reference: Option<String>,
+    /// Note for the mentor, such as why it was rejected
+    #[validate(length(max = 2000, message = "Note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Query parameters for the mentor payment reconciliation report.
+#[derive(Debug, Deserialize)]
+pub struct ReconciliationParams {
+    /// First day of the period (default: 30 days before `to`)
+    pub from: Option<chrono::NaiveDate>,
+    /// Last day of the period (default: today)
+    pub to: Option<chrono::NaiveDate>,
+}
+
+/// Payload for adding an action item to a roadmap.
+#[derive(Debug, Default, Deserialize)]
+pub struct ActionItemRoadmapPayload {
+    /// Roadmap to add to (default: the primary roadmap, else the newest)
+    pub roadmap_id: Option<i32>,
+    /// Phase to add to (default: the first phase not completed)
+    pub phase: Option<i32>,
+}
+
+/// Payload for creating an API key.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyPayload {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    /// What the key may do, e.g. `["sandbox"]`
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<String>,
+}
+
+/// Payload for creating a sandbox test user.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SandboxUserPayload {
+    /// `job_seeker` (default) or `employer`
+    pub role: Option<UserRole>,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1-255 characters"))]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    #[validate(length(max = 50, message = "At most 50 skills"))]
+    pub skills: Vec<String>,
+    pub experience_level: Option<ExperienceLevel>,
+}
+
+/// Payload for creating a sandbox job.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SandboxJobPayload {
+    /// Test employer posting the job
+    pub posted_by: Option<Uuid>,
+    #[validate(length(min = 1, max = 255, message = "Job title must be 1-255 characters"))]
+    pub job_title: String,
+    #[validate(length(min = 1, max = 255, message = "Company must be 1-255 characters"))]
+    pub company: String,
+    #[validate(length(min = 1, max = 255, message = "Location must be 1-255 characters"))]
+    pub location: String,
+    #[validate(length(min = 1, max = 10000, message = "Description must be 1-10000 characters"))]
+    pub job_description: String,
+    #[serde(default)]
+    #[validate(length(max = 50, message = "At most 50 skills"))]
+    pub required_skills: Vec<String>,
+    pub experience_level: ExperienceLevel,
+    pub job_type: JobType,
+    pub salary_min: Option<i32>,
+    pub salary_max: Option<i32>,
+}
+
+/// Payload for creating a sandbox application.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SandboxApplicationPayload {
+    pub user_id: Uuid,
+    pub job_id: i32,
+    /// Application status (default: `applied`)
+    #[validate(length(min = 1, max = 50, message = "Status must be 1-50 characters"))]
+    pub status: Option<String>,
+    #[validate(length(max = 2000, message = "Notes must be at most 2000 characters"))]
+    pub notes: Option<String>,
+}
+
+/// Payload for opening a dispute over a mentor booking or an application.
+#[derive(Debug, Deserialize, Validate)]
+pub struct OpenDisputePayload {
+    /// Mentor booking to dispute
+    pub booking_id: Option<i32>,
+    /// Application to dispute, instead of a booking
+    pub application_id: Option<i32>,
+    #[validate(length(min = 10, max = 5000, message = "Reason must be 10-5000 characters"))]
+    pub reason: String,
+}
+
+/// Payload for withdrawing a dispute.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct WithdrawDisputePayload {
+    #[validate(length(max = 2000, message = "Note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Query parameters for the admin dispute queue.
+#[derive(Debug, Deserialize)]
+pub struct DisputeQueueParams {
+    /// Only disputes in this status (default: open and under review)
+    pub status: Option<crate::models::DisputeStatus>,
+}
+
+/// Payload for resolving a dispute.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResolveDisputePayload {
+    pub outcome: crate::models::DisputeOutcome,
+    /// Refund to the mentee of a paid session, in minor units; required
+    /// when partially upheld
+    #[validate(range(min = 0, message = "Refund cannot be negative"))]
+    pub refund_cents: Option<i32>,
+    #[validate(length(min = 1, max = 5000, message = "Resolution note must be 1-5000 characters"))]
+    pub note: String,
+}
+
 /// Payload for updating learning resource progress.
 #[derive(Debug, Deserialize)]
 pub struct UpdateProgressPayload {
@@ -227,3 +719,1631 @@ pub struct OAuthLoginResponse {
     /// Whether this is a newly created user
     pub is_new_user: bool,
 }
+
+/// Payload for an employer creating a job posting.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateJobPayload {
+    /// Job title or position name
+    #[validate(length(min = 1, message = "Job title is required"))]
+    pub job_title: String,
+    /// Company offering the job
+    #[validate(length(min = 1, message = "Company is required"))]
+    pub company: String,
+    /// Job location
+    #[validate(length(min = 1, message = "Location is required"))]
+    pub location: String,
+    /// Detailed job description
+    #[validate(length(min = 1, message = "Job description is required"))]
+    pub job_description: String,
+    /// Skills required for the job
+    #[serde(default)]
+    pub required_skills: Vec<String>,
+    /// Required experience level
+    pub experience_level: ExperienceLevel,
+    /// Type of employment
+    pub job_type: JobType,
+    /// Minimum salary offered
+    pub salary_min: Option<i32>,
+    /// Maximum salary offered
+    pub salary_max: Option<i32>,
+    /// List of job responsibilities
+    #[serde(default)]
+    pub responsibilities: Vec<String>,
+    /// List of job requirements
+    #[serde(default)]
+    pub requirements: Vec<String>,
+    /// List of benefits offered
+    #[serde(default)]
+    pub benefits: Vec<String>,
+    /// Days until the posting expires (defaults to `JOB_EXPIRY_DAYS`)
+    #[validate(range(min = 1, max = 365, message = "expires_in_days must be between 1 and 365"))]
+    pub expires_in_days: Option<i64>,
+    /// Two-letter code of the country the job is in, used for work authorization matching
+    #[validate(length(equal = 2, message = "country_code must be a two-letter ISO country code"))]
+    pub country_code: Option<String>,
+    /// Expected start date
+    pub start_date: Option<chrono::NaiveDate>,
+    /// Whether the employer sponsors work visas
+    #[serde(default)]
+    pub visa_sponsorship: bool,
+    /// First day of an internship's term
+    pub term_start: Option<chrono::NaiveDate>,
+    /// Last day of an internship's term
+    pub term_end: Option<chrono::NaiveDate>,
+    /// Whether an internship can count for academic credit
+    #[serde(default)]
+    pub academic_credit: bool,
+}
+
+/// Payload for replacing a posting's screening questions.
+#[derive(Debug, Deserialize)]
+pub struct SetScreeningQuestionsPayload {
+    pub questions: Vec<crate::screening::QuestionInput>,
+}
+
+/// Query parameters for a posting's applicant list.
+#[derive(Debug, Deserialize)]
+pub struct ApplicantListParams {
+    /// `applied_at` (default, newest first) or `score` (highest first)
+    pub sort: Option<crate::applicant_ranking::ApplicantSort>,
+}
+
+/// Payload for creating a talent pool.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTalentPoolPayload {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// Skills the pool looks for; a member gaining one raises an alert
+    #[serde(default)]
+    #[validate(length(max = 30, message = "At most 30 skills"))]
+    pub required_skills: Vec<String>,
+}
+
+/// Payload for updating a talent pool; omitted fields are left unchanged
+/// and an empty description clears it.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTalentPoolPayload {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    #[validate(length(max = 30, message = "At most 30 skills"))]
+    pub required_skills: Option<Vec<String>>,
+}
+
+/// Query parameters for a talent pool's members.
+#[derive(Debug, Deserialize)]
+pub struct TalentPoolParams {
+    /// Only members with this tag
+    pub tag: Option<String>,
+}
+
+/// Payload for saving a candidate in a talent pool.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddPoolMemberPayload {
+    pub candidate_id: uuid::Uuid,
+    #[serde(default)]
+    #[validate(length(max = 20, message = "At most 20 tags"))]
+    pub tags: Vec<String>,
+    #[validate(length(max = 2000, message = "notes must be at most 2000 characters"))]
+    pub notes: Option<String>,
+}
+
+/// Payload for changing a pool member's tags and notes; an empty note
+/// clears it.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePoolMemberPayload {
+    #[validate(length(max = 20, message = "At most 20 tags"))]
+    pub tags: Option<Vec<String>>,
+    #[validate(length(max = 2000, message = "notes must be at most 2000 characters"))]
+    pub notes: Option<String>,
+}
+
+/// Payload for inviting a talent pool to apply to a posting.
+#[derive(Debug, Deserialize, Validate)]
+pub struct InviteTalentPoolPayload {
+    pub job_id: i32,
+    /// Added to the invitation
+    #[validate(length(max = 1000, message = "message must be at most 1000 characters"))]
+    pub message: Option<String>,
+}
+
+/// Payload for suggesting screening questions from a posting.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct GenerateScreeningQuestionsPayload {
+    /// Number of questions to suggest (default: 5)
+    #[validate(range(min = 1, max = 10))]
+    pub count: Option<usize>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for drafting a job posting from a brief.
+#[derive(Debug, Deserialize, Validate)]
+pub struct JobDescriptionBriefPayload {
+    /// A few sentences about the role
+    #[validate(length(min = 20, max = 4000, message = "brief must be between 20 and 4000 characters"))]
+    pub brief: String,
+    /// Job title (default: suggested by the AI)
+    #[validate(length(min = 1, max = 255))]
+    pub job_title: Option<String>,
+    /// Experience level (default: suggested by the AI)
+    pub experience_level: Option<ExperienceLevel>,
+    /// Type of employment (default: suggested by the AI)
+    pub job_type: Option<JobType>,
+    /// Job location
+    #[validate(length(max = 255))]
+    pub location: Option<String>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for renewing an expired or expiring job posting.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct RenewJobPayload {
+    /// Days from now until the posting expires (defaults to `JOB_EXPIRY_DAYS`)
+    #[validate(range(min = 1, max = 365, message = "expires_in_days must be between 1 and 365"))]
+    pub expires_in_days: Option<i64>,
+}
+
+/// Per-job row on the employer dashboard.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EmployerJobSummary {
+    /// Job ID
+    pub job_id: i32,
+    /// Seat holder who posted the job
+    pub posted_by: Option<Uuid>,
+    /// Job title
+    pub job_title: String,
+    /// Job location
+    pub location: String,
+    /// When the job was posted
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the job is live, awaiting review, or rejected
+    pub moderation_status: ModerationStatus,
+    /// Moderator's reason for the decision, if any
+    pub moderation_reason: Option<String>,
+    /// When the posting expires (`None` for postings that never expire)
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the posting was closed after expiring
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Total number of applications
+    pub applicant_count: i32,
+    /// Applications not yet reviewed
+    pub new_count: i32,
+    /// Applications under review or at interview stage
+    pub in_review_count: i32,
+}
+
+/// Employer dashboard response with per-job counters and totals.
+#[derive(Debug, Serialize)]
+pub struct EmployerDashboard {
+    /// Number of jobs posted by the employer or their company team
+    pub total_jobs: usize,
+    /// Total applications across all jobs
+    pub total_applicants: i64,
+    /// Total unreviewed applications across all jobs
+    pub total_new: i64,
+    /// Total applications under review across all jobs
+    pub total_in_review: i64,
+    /// Per-job breakdown, newest first
+    pub jobs: Vec<EmployerJobSummary>,
+}
+
+/// Payload for starting a Stripe Checkout session.
+#[derive(Debug, Deserialize)]
+pub struct CheckoutPayload {
+    /// Plan to subscribe to (e.g. `premium`)
+    pub plan_id: String,
+}
+
+/// Current plan, subscription state, and today's AI usage for a user.
+#[derive(Debug, Serialize)]
+pub struct SubscriptionSummary {
+    /// Plan currently in effect
+    pub plan: crate::billing::Plan,
+    /// Stripe subscription status, if the user has ever subscribed
+    pub status: Option<String>,
+    /// End of the current paid period
+    pub current_period_end: Option<chrono::DateTime<chrono::Utc>>,
+    /// AI requests made today
+    pub ai_requests_today: i32,
+    /// AI requests left today
+    pub ai_requests_remaining: i32,
+    /// AI requests allowed per day: the plan's quota, or a tenant policy's
+    pub ai_daily_quota: i32,
+    /// The tenant policy that sets the quota, if any
+    pub rate_limit_policy_id: Option<i32>,
+}
+
+/// Query parameters for credit history.
+#[derive(Debug, Deserialize)]
+pub struct CreditHistoryParams {
+    /// Maximum number of transactions to return (default 50)
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for the peer benchmark.
+#[derive(Debug, Deserialize)]
+pub struct BenchmarkParams {
+    /// One of the user's target roles or roadmap roles (default: first target role)
+    pub role: Option<String>,
+}
+
+/// Query parameters for role readiness.
+#[derive(Debug, Deserialize)]
+pub struct ReadinessParams {
+    /// One of the user's target roles or roadmap roles (default: first target role)
+    pub role: Option<String>,
+}
+
+/// Query parameters for the activity feed.
+#[derive(Debug, Deserialize)]
+pub struct ActivityFeedParams {
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Maximum number of activities to return (default 20, at most 100)
+    pub limit: Option<i64>,
+}
+
+/// Payload for creating a company team.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCompanyPayload {
+    /// Company name
+    #[validate(length(min = 1, max = 255, message = "Company name is required"))]
+    pub name: String,
+}
+
+/// Payload for inviting a user to a company team.
+#[derive(Debug, Deserialize, Validate)]
+pub struct InviteMemberPayload {
+    /// Email address the invitation is bound to
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    /// Seat role granted on acceptance
+    pub role: SeatRole,
+}
+
+/// Payload for accepting a team invitation.
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitationPayload {
+    /// Token from the invitation
+    pub token: String,
+}
+
+/// Payload for changing a member's seat role.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSeatPayload {
+    /// New seat role
+    pub role: SeatRole,
+}
+
+/// Payload for generating a company's branding content.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct GenerateBrandingPayload {
+    /// Roles to write pitches for (default: the company's open jobs)
+    #[serde(default)]
+    #[validate(length(max = 10, message = "At most 10 roles"))]
+    pub roles: Vec<String>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// A member of a company team.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TeamMember {
+    /// Member's user ID
+    pub user_id: Uuid,
+    /// Member's full name
+    pub full_name: String,
+    /// Member's email
+    pub email: String,
+    /// Seat role
+    pub role: SeatRole,
+    /// Who invited the member (`None` for the founding owner)
+    pub invited_by: Option<Uuid>,
+    /// When the member joined
+    pub joined_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// An invitation that has not been accepted yet.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PendingInvitation {
+    /// Invitation ID
+    pub id: i32,
+    /// Invited email address
+    pub email: String,
+    /// Seat role granted on acceptance
+    pub role: SeatRole,
+    /// Who sent the invitation
+    pub invited_by: Option<Uuid>,
+    /// When the invitation stops being valid
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A company team with its members and pending invitations.
+#[derive(Debug, Serialize)]
+pub struct TeamOverview {
+    /// The caller's seat
+    pub seat: crate::teams::Seat,
+    /// Current members
+    pub members: Vec<TeamMember>,
+    /// Unaccepted, unexpired invitations
+    pub invitations: Vec<PendingInvitation>,
+}
+
+/// A team or pipeline action attributed to a seat.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TeamActivity {
+    /// Audit log entry ID
+    pub id: i64,
+    /// Seat holder who performed the action
+    pub actor_id: Option<Uuid>,
+    /// Actor's full name, if the account still exists
+    pub actor_name: Option<String>,
+    /// Action name (e.g. `team.invite`, `job.create`)
+    pub action: String,
+    /// Kind of object acted on
+    pub target_type: Option<String>,
+    /// ID of the object acted on
+    pub target_id: Option<String>,
+    /// Action details
+    pub metadata: serde_json::Value,
+    /// Support admin acting as the seat holder, when impersonated
+    pub impersonated_by: Option<Uuid>,
+    /// When the action happened
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Query parameters for team activity.
+#[derive(Debug, Deserialize)]
+pub struct TeamActivityParams {
+    /// Only return actions by this seat holder
+    pub actor_id: Option<Uuid>,
+    /// Maximum number of entries to return (default 50)
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for listing resource links.
+#[derive(Debug, Deserialize)]
+pub struct ResourceLinkQueryParams {
+    /// Only links with this status (e.g. `broken`)
+    pub status: Option<LinkStatus>,
+    /// Include links that were already replaced (default: false)
+    #[serde(default)]
+    pub include_replaced: bool,
+}
+
+/// Query parameters for following a learning resource link.
+#[derive(Debug, Deserialize)]
+pub struct ResourceGoParams {
+    /// Roadmap the link was followed from
+    pub roadmap_id: Option<i32>,
+}
+
+/// Query parameters for resource usage analytics.
+#[derive(Debug, Deserialize)]
+pub struct ResourceUsageParams {
+    /// Days of clicks to include (default: 30, at most 365)
+    pub days: Option<i32>,
+    /// Resources to return (default: 20, at most 100)
+    pub limit: Option<i64>,
+}
+
+/// Payload for setting a learning resource's affiliate parameters.
+#[derive(Debug, Deserialize)]
+pub struct AffiliateParamsPayload {
+    /// Query parameters added to the resource URL on redirect (empty to remove)
+    #[serde(default)]
+    pub params: std::collections::BTreeMap<String, String>,
+}
+
+/// Query parameters for AI evaluation trends.
+#[derive(Debug, Deserialize)]
+pub struct EvalTrendParams {
+    /// Days of results to include (default: 30, at most 365)
+    pub days: Option<i32>,
+}
+
+/// A job posting awaiting moderation.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PendingJob {
+    /// Job ID
+    pub id: i32,
+    /// Job title
+    pub job_title: String,
+    /// Company offering the job
+    pub company: String,
+    /// Job location
+    pub location: String,
+    /// Detailed job description
+    pub job_description: String,
+    /// Minimum salary offered
+    pub salary_min: Option<i32>,
+    /// Maximum salary offered
+    pub salary_max: Option<i32>,
+    /// Employer who posted the job
+    pub posted_by: Option<Uuid>,
+    /// Email of the posting employer
+    pub poster_email: Option<String>,
+    /// `pending`, or `quarantined` by scam screening
+    pub moderation_status: ModerationStatus,
+    /// Pre-screening risk score, `None` while screening is still running
+    pub scam_risk_score: Option<i32>,
+    /// Pre-screening indicators, risk reasons, and summary
+    pub screening: Option<serde_json::Value>,
+    /// Open user reports on the job
+    pub open_reports: i64,
+    /// When the job was posted
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Payload for blocking a user or a company; give exactly one.
+#[derive(Debug, Deserialize, Validate)]
+pub struct BlockPayload {
+    /// Employer (blocked by a candidate) or candidate (blocked by an employer)
+    pub user_id: Option<Uuid>,
+    /// Company to block (candidates only)
+    pub company_id: Option<i32>,
+    #[validate(length(max = 500, message = "reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Payload for reporting a job, user, company, received message, or company
+/// review.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReportPayload {
+    pub target_type: ReportTargetType,
+    /// Job, message, or review ID, user UUID, or company ID
+    #[validate(length(min = 1, max = 64, message = "target_id is required"))]
+    pub target_id: String,
+    pub category: ReportCategory,
+    #[validate(length(max = 2000, message = "details must be at most 2000 characters"))]
+    pub details: Option<String>,
+    /// Also block the reported party, where the reporter may block it
+    #[serde(default)]
+    pub block: bool,
+}
+
+/// Query parameters for the admin report queue.
+#[derive(Debug, Deserialize)]
+pub struct ReportQueueParams {
+    /// Only reports with this status (default: all, open first)
+    pub status: Option<ReportStatus>,
+    /// Maximum reports to return (default: 50, max: 200)
+    pub limit: Option<i64>,
+}
+
+/// Payload for resolving a report.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ResolveReportPayload {
+    pub action: crate::reports::ReportAction,
+    /// Shown to the reported party when warning or removing; required unless dismissing
+    #[validate(length(max = 2000, message = "note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Query parameters for reading a policy.
+#[derive(Debug, Deserialize)]
+pub struct PolicyQueryParams {
+    /// An earlier version; the version in force by default
+    pub version: Option<i32>,
+}
+
+/// Payload for accepting a policy.
+#[derive(Debug, Deserialize)]
+pub struct AcceptPolicyPayload {
+    /// The version being accepted; must be the one in force
+    pub version: i32,
+}
+
+/// Payload for publishing a policy version.
+#[derive(Debug, Deserialize, Validate)]
+pub struct PublishPolicyPayload {
+    pub kind: crate::models::PolicyKind,
+    #[validate(length(min = 1, max = 255, message = "Title must be between 1 and 255 characters"))]
+    pub title: String,
+    #[validate(length(min = 1, message = "Body is required"))]
+    pub body: String,
+    /// What changed since the previous version, shown when asking users to accept
+    #[validate(length(max = 2000, message = "Summary must be at most 2000 characters"))]
+    pub summary: Option<String>,
+}
+
+/// Query parameters for exporting a policy version's acceptances.
+#[derive(Debug, Deserialize)]
+pub struct PolicyAcceptanceParams {
+    /// Maximum number of records to return (default: 500, max: 5000)
+    pub limit: Option<i64>,
+    /// Records to skip
+    pub offset: Option<i64>,
+}
+
+/// Payload for starting an impersonation session.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ImpersonatePayload {
+    /// Why support needs to act as the user; shown to them afterwards
+    #[validate(length(min = 1, max = 1000, message = "Reason must be between 1 and 1000 characters"))]
+    pub reason: String,
+    /// Session length in minutes (default: 15, max: 60)
+    pub minutes: Option<i64>,
+}
+
+/// Query parameters for listing impersonation sessions.
+#[derive(Debug, Deserialize)]
+pub struct ImpersonationQueryParams {
+    /// Only sessions on this user
+    pub user_id: Option<Uuid>,
+    /// Maximum number of sessions to return (default: 50)
+    pub limit: Option<i64>,
+}
+
+/// Payload for approving or rejecting a job posting.
+#[derive(Debug, Default, Deserialize)]
+pub struct ModerationDecisionPayload {
+    /// Reason shown to the employer (required when rejecting)
+    pub reason: Option<String>,
+}
+
+/// Payload for setting an organization's rate limit policy.
+#[derive(Debug, Deserialize, Validate)]
+pub struct RateLimitPolicyPayload {
+    /// Organization whose staff and students the policy applies to
+    pub org_id: i32,
+    /// Plan the policy applies to (omit for every plan)
+    pub plan_id: Option<String>,
+    /// AI requests allowed per day
+    #[validate(range(min = 1, max = 100000, message = "ai_daily_quota must be between 1 and 100000"))]
+    pub ai_daily_quota: i32,
+    /// Why the override exists
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
+
+/// Payload for creating an institution's organization.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationPayload {
+    /// Organization name
+    #[validate(length(min = 1, max = 255, message = "Organization name is required"))]
+    pub name: String,
+}
+
+/// Payload for adding a staff member to an organization.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddOrgMemberPayload {
+    /// Email of an existing user
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    /// Staff role
+    pub role: OrgRole,
+}
+
+/// Payload for creating a cohort.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCohortPayload {
+    /// Cohort name, e.g. `CSE Class of 2027`
+    #[validate(length(min = 1, max = 255, message = "Cohort name is required"))]
+    pub name: String,
+    /// Minimum profile completeness (0-100) before members may apply to jobs
+    #[validate(range(min = 0, max = 100, message = "min_profile_completeness must be between 0 and 100"))]
+    pub min_profile_completeness: Option<i32>,
+}
+
+/// Payload for updating a cohort (all fields optional).
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCohortPayload {
+    /// New cohort name
+    #[validate(length(min = 1, max = 255, message = "Cohort name cannot be empty"))]
+    pub name: Option<String>,
+    /// New minimum profile completeness (0-100)
+    #[validate(range(min = 0, max = 100, message = "min_profile_completeness must be between 0 and 100"))]
+    pub min_profile_completeness: Option<i32>,
+}
+
+/// Payload for a student joining a cohort.
+#[derive(Debug, Deserialize)]
+pub struct JoinCohortPayload {
+    /// Join code shared by the institution
+    pub join_code: String,
+}
+
+/// Payload for saving an organization's career services guidance. Both
+/// fields are replaced; omit or blank one to clear it.
+#[derive(Debug, Deserialize, Validate)]
+pub struct OrgPersonaPayload {
+    /// Guidance added to mentor prompts, e.g. the tone and services to mention
+    #[validate(length(max = 4000, message = "mentor_persona must be at most 4000 characters"))]
+    pub mentor_persona: Option<String>,
+    /// Guidance added to roadmap prompts
+    #[validate(length(max = 4000, message = "roadmap_guidance must be at most 4000 characters"))]
+    pub roadmap_guidance: Option<String>,
+}
+
+/// Payload for generating a roadmap for every student in a cohort. Give a
+/// `target_role`, or a staff member's roadmap as `template_roadmap_id` to
+/// copy its role, timeframe, and weekly hours; other fields override the
+/// template.
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkRoadmapPayload {
+    /// Role every student works toward
+    #[validate(length(min = 1, max = 255, message = "target_role must be between 1 and 255 characters"))]
+    pub target_role: Option<String>,
+    /// Roadmap of an organization staff member to use as the template
+    pub template_roadmap_id: Option<i32>,
+    /// Months to reach the role (default: 6)
+    #[validate(range(min = 1, max = 36, message = "timeframe_months must be between 1 and 36"))]
+    pub timeframe_months: Option<i32>,
+    /// Weekly learning hours (default: 10)
+    #[validate(range(min = 1, max = 80, message = "learning_hours_per_week must be between 1 and 80"))]
+    pub learning_hours_per_week: Option<i32>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for starting a salary negotiation practice session.
+#[derive(Debug, Deserialize)]
+pub struct NegotiationStartPayload {
+    /// One of your offers; the recruiter negotiates its terms
+    pub offer_id: i32,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for replying in a salary negotiation practice session.
+#[derive(Debug, Deserialize, Validate)]
+pub struct NegotiationReplyPayload {
+    pub session_id: i32,
+    /// What you say to the recruiter; optional when ending the session
+    #[validate(length(min = 1, max = 2000, message = "Message must be between 1 and 2000 characters"))]
+    pub message: Option<String>,
+    /// End the session after this message and get the debrief (default: false)
+    #[serde(default)]
+    pub end: bool,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for mining STAR stories from the user's projects and CV.
+#[derive(Debug, Deserialize, Validate)]
+pub struct MineStarStoriesPayload {
+    /// Competencies to focus on; any when empty
+    #[serde(default)]
+    #[validate(length(max = 8, message = "At most 8 competencies"))]
+    pub competencies: Vec<crate::models::StarCompetency>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for refining a STAR story.
+#[derive(Debug, Deserialize, Validate)]
+pub struct RefineStarStoryPayload {
+    /// Answers to the follow-up questions, or feedback on the story
+    #[validate(length(min = 1, max = 4000, message = "Input must be between 1 and 4000 characters"))]
+    pub input: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for editing a STAR story; omitted fields are left as they are.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateStarStoryPayload {
+    #[validate(length(min = 1, max = 255, message = "Title must be between 1 and 255 characters"))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 8, message = "Between 1 and 8 competencies"))]
+    pub competencies: Option<Vec<crate::models::StarCompetency>>,
+    #[validate(length(min = 1, max = 4000, message = "Situation must be between 1 and 4000 characters"))]
+    pub situation: Option<String>,
+    #[validate(length(min = 1, max = 4000, message = "Task must be between 1 and 4000 characters"))]
+    pub task: Option<String>,
+    #[validate(length(min = 1, max = 4000, message = "Action must be between 1 and 4000 characters"))]
+    pub action: Option<String>,
+    #[validate(length(min = 1, max = 4000, message = "Result must be between 1 and 4000 characters"))]
+    pub result: Option<String>,
+    /// `polished` saves the story as ready for interviews
+    pub status: Option<crate::models::StarStoryStatus>,
+}
+
+/// Query parameters for listing STAR stories.
+#[derive(Debug, Deserialize)]
+pub struct StarStoryParams {
+    /// Only stories showing this competency
+    pub competency: Option<crate::models::StarCompetency>,
+    /// Only stories with this status
+    pub status: Option<crate::models::StarStoryStatus>,
+}
+
+/// Payload for linking a coding practice handle.
+#[derive(Debug, Deserialize, Validate)]
+pub struct LinkCodePracticePayload {
+    /// Public username on the platform
+    #[validate(length(min = 1, max = 100, message = "Handle is required"))]
+    pub handle: String,
+}
+
+/// Payload for emailing an employer verification code.
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerificationEmailPayload {
+    /// Work email on the company's domain
+    #[validate(email(message = "Invalid email format"))]
+    pub work_email: String,
+}
+
+/// Payload for confirming an employer verification code.
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerificationCodePayload {
+    #[validate(length(min = 6, max = 6, message = "The code has 6 digits"))]
+    pub code: String,
+}
+
+/// Payload for submitting an employer verification for review.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitVerificationPayload {
+    /// Company registration or incorporation number
+    #[validate(length(min = 1, max = 100, message = "Registration number is required"))]
+    pub registration_number: String,
+    /// ISO 3166-1 alpha-2 code of the country the company is registered in
+    #[validate(length(equal = 2, message = "Registration country must be a 2-letter country code"))]
+    pub registration_country: String,
+}
+
+/// Payload for writing or editing a company review.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CompanyReviewPayload {
+    /// The hiring process as a whole, 1-5
+    #[validate(range(min = 1, max = 5, message = "Ratings must be between 1 and 5"))]
+    pub overall_rating: i16,
+    /// How quickly the company responded, 1-5
+    #[validate(range(min = 1, max = 5, message = "Ratings must be between 1 and 5"))]
+    pub response_time_rating: i16,
+    /// How clearly the company explained its process and decisions, 1-5
+    #[validate(range(min = 1, max = 5, message = "Ratings must be between 1 and 5"))]
+    pub transparency_rating: i16,
+    /// Days the company took to first respond
+    #[validate(range(min = 0, max = 365, message = "Response days must be between 0 and 365"))]
+    pub response_days: Option<i16>,
+    #[validate(length(min = 1, max = 150, message = "Headline must be between 1 and 150 characters"))]
+    pub headline: String,
+    #[validate(length(min = 20, max = 5000, message = "Review must be between 20 and 5000 characters"))]
+    pub body: String,
+}
+
+/// Query parameters for listing a company's reviews.
+#[derive(Debug, Deserialize)]
+pub struct CompanyReviewParams {
+    /// Maximum reviews to return (default: 20, max: 100)
+    pub limit: Option<i64>,
+    /// Reviews to skip
+    pub offset: Option<i64>,
+}
+
+/// Payload for sharing or editing an interview experience.
+#[derive(Debug, Deserialize, Validate)]
+pub struct InterviewExperiencePayload {
+    /// Company interviewed with; ignored when editing
+    pub company_id: Option<i32>,
+    #[validate(length(min = 1, max = 255, message = "Role must be between 1 and 255 characters"))]
+    pub role: String,
+    /// When the interviews took place
+    pub interviewed_on: Option<chrono::NaiveDate>,
+    #[validate(range(min = 1, max = 20, message = "Rounds must be between 1 and 20"))]
+    pub rounds: i16,
+    /// One line per round, e.g. "Take-home: build a REST API in 3 days"
+    #[serde(default)]
+    #[validate(length(max = 20, message = "At most 20 rounds"))]
+    pub round_details: Vec<String>,
+    /// Questions asked
+    #[serde(default)]
+    #[validate(length(max = 30, message = "At most 30 questions"))]
+    pub questions: Vec<String>,
+    #[validate(length(min = 20, max = 5000, message = "Summary must be between 20 and 5000 characters"))]
+    pub summary: String,
+    /// 1 (easy) to 5 (very hard)
+    #[validate(range(min = 1, max = 5, message = "Difficulty must be between 1 and 5"))]
+    pub difficulty: Option<i16>,
+    pub outcome: crate::models::InterviewOutcome,
+}
+
+/// Query parameters for searching a company's interview experiences.
+#[derive(Debug, Deserialize)]
+pub struct InterviewExperienceParams {
+    /// Text to find in the role, summary, rounds, or questions
+    pub q: Option<String>,
+    /// Only posts for roles matching this
+    pub role: Option<String>,
+    pub outcome: Option<crate::models::InterviewOutcome>,
+    /// Maximum posts to return (default: 20, max: 100)
+    pub limit: Option<i64>,
+    /// Posts to skip
+    pub offset: Option<i64>,
+}
+
+/// Payload for the company's reply to a review.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReviewReplyPayload {
+    #[validate(length(min = 1, max = 3000, message = "Reply must be between 1 and 3000 characters"))]
+    pub reply: String,
+}
+
+/// Payload for asking the career mentor with a streamed answer.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AskMentorStreamPayload {
+    #[validate(length(min = 1, max = 4000, message = "question must be between 1 and 4000 characters"))]
+    pub question: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+    /// Saved conversation to continue
+    pub conversation_id: Option<i32>,
+    /// Start a new saved conversation with this exchange
+    #[serde(default)]
+    pub save_conversation: bool,
+    /// Ground the answer in the user's documents (default: true)
+    pub use_documents: Option<bool>,
+    /// How plainly to answer
+    pub reading_level: Option<crate::ai::types::ReadingLevel>,
+    pub tone: Option<crate::ai::types::Tone>,
+}
+
+/// Payload for previewing an organization's guidance on a sample prompt.
+#[derive(Debug, Deserialize, Validate)]
+pub struct PersonaPreviewPayload {
+    /// Which prompt to preview
+    pub kind: crate::tenant_config::PersonaKind,
+    /// A mentor question, or a target role for a roadmap
+    #[validate(length(min = 1, max = 2000, message = "input must be between 1 and 2000 characters"))]
+    pub input: String,
+    /// Draft guidance to try instead of the saved one
+    #[validate(length(max = 4000, message = "persona must be at most 4000 characters"))]
+    pub persona: Option<String>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// A cohort with its member count.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CohortSummary {
+    /// Cohort ID
+    pub id: i32,
+    /// Owning organization
+    pub org_id: i32,
+    /// Cohort name
+    pub name: String,
+    /// Code students use to join
+    pub join_code: String,
+    /// Minimum profile completeness before members may apply to jobs
+    pub min_profile_completeness: i32,
+    /// Number of students in the cohort
+    pub member_count: i64,
+    /// Number of students who accepted an offer
+    pub placed_count: i64,
+    /// When the cohort was created
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A student in a cohort with their profile completeness.
+#[derive(Debug, Serialize)]
+pub struct CohortMember {
+    /// Student's user ID
+    pub user_id: Uuid,
+    /// Student's full name
+    pub full_name: String,
+    /// Student's email
+    pub email: String,
+    /// When the student joined the cohort
+    pub joined_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Profile completeness (0-100)
+    pub profile_completeness: i32,
+    /// Profile sections still empty
+    pub missing: Vec<&'static str>,
+    /// Whether the student meets the cohort's completeness requirement
+    pub meets_requirement: bool,
+    /// Whether the student has accepted an offer
+    pub placed: bool,
+}
+
+/// Query parameters for an organization's placement report.
+#[derive(Debug, Deserialize)]
+pub struct PlacementReportParams {
+    /// Only this cohort
+    pub cohort_id: Option<i32>,
+    /// Only placements accepted on or after this date
+    pub from: Option<chrono::NaiveDate>,
+    /// Only placements accepted on or before this date
+    pub to: Option<chrono::NaiveDate>,
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+/// Query parameters for an organization's at-risk students. Omitted
+/// thresholds use the defaults.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AtRiskParams {
+    /// Only this cohort
+    pub cohort_id: Option<i32>,
+    /// Weeks without roadmap progress (default: 3)
+    #[validate(range(min = 1, max = 52, message = "stalled_weeks must be between 1 and 52"))]
+    pub stalled_weeks: Option<i64>,
+    /// Minimum profile completeness, 0-100 (default: 60)
+    #[validate(range(min = 0, max = 100, message = "min_completeness must be between 0 and 100"))]
+    pub min_completeness: Option<i32>,
+    /// Weeks without a logged job application (default: 8)
+    #[validate(range(min = 1, max = 52, message = "application_weeks must be between 1 and 52"))]
+    pub application_weeks: Option<i64>,
+}
+
+/// Payload for nudging at-risk students.
+#[derive(Debug, Deserialize, Validate)]
+pub struct NudgeStudentsPayload {
+    /// Students to nudge
+    #[validate(length(min = 1, max = 200, message = "user_ids must list between 1 and 200 students"))]
+    pub user_ids: Vec<uuid::Uuid>,
+    /// Note shown before the suggested next steps
+    #[validate(length(max = 1000, message = "note must be at most 1000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Payload for creating a career fair.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEventPayload {
+    /// Event name
+    #[validate(length(min = 1, max = 255, message = "Event name is required"))]
+    pub name: String,
+    /// What the event is about
+    pub description: Option<String>,
+    /// Venue
+    #[validate(length(max = 255))]
+    pub location: Option<String>,
+    /// When the event starts
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// When the event ends
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Payload for adding a session to a career fair.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEventSessionPayload {
+    /// Session title
+    #[validate(length(min = 1, max = 255, message = "Session title is required"))]
+    pub title: String,
+    /// What the session covers
+    pub description: Option<String>,
+    /// Room or stage
+    #[validate(length(max = 255))]
+    pub location: Option<String>,
+    /// Speaker or host
+    #[validate(length(max = 255))]
+    pub speaker: Option<String>,
+    /// When the session starts
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// When the session ends
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Payload for giving a company a booth at a career fair.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateBoothPayload {
+    /// Company team that staffs the booth
+    pub company_id: i32,
+    /// Booth number or label shown to attendees
+    #[validate(length(max = 100))]
+    pub label: Option<String>,
+}
+
+/// Payload for RSVPing to a career fair.
+#[derive(Debug, Default, Deserialize)]
+pub struct RsvpPayload {
+    /// Sessions to attend (replaces earlier choices)
+    #[serde(default)]
+    pub session_ids: Vec<i32>,
+}
+
+/// Payload for recording a booth interaction from a scanned badge.
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordInteractionPayload {
+    /// Badge code scanned from the attendee
+    #[validate(length(min = 1, max = 16, message = "Badge code is required"))]
+    pub badge_code: String,
+    /// Recruiter's notes
+    pub notes: Option<String>,
+    /// Recruiter's rating of the conversation (1-5)
+    #[validate(range(min = 1, max = 5, message = "rating must be between 1 and 5"))]
+    pub rating: Option<i16>,
+    /// Job the candidate is a lead for
+    pub job_id: Option<i32>,
+}
+
+/// Payload for inviting a lead to apply.
+#[derive(Debug, Default, Deserialize)]
+pub struct InviteLeadPayload {
+    /// Job to invite the candidate to (defaults to the lead's job)
+    pub job_id: Option<i32>,
+    /// Personal message included in the notification
+    pub message: Option<String>,
+}
+
+/// A career fair.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CareerEvent {
+    /// Event ID
+    pub id: i32,
+    /// Organizing institution
+    pub org_id: i32,
+    /// Organizing institution's name
+    pub org_name: String,
+    /// Event name
+    pub name: String,
+    /// What the event is about
+    pub description: Option<String>,
+    /// Venue
+    pub location: Option<String>,
+    /// When the event starts
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// When the event ends
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+    /// Number of attendees who RSVPed
+    pub attendee_count: i64,
+}
+
+/// A session within a career fair.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EventSession {
+    /// Session ID
+    pub id: i32,
+    /// Session title
+    pub title: String,
+    /// What the session covers
+    pub description: Option<String>,
+    /// Room or stage
+    pub location: Option<String>,
+    /// Speaker or host
+    pub speaker: Option<String>,
+    /// When the session starts
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// When the session ends
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An employer booth at a career fair.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EventBooth {
+    /// Booth ID
+    pub id: i32,
+    /// Company staffing the booth
+    pub company_id: i32,
+    /// Company name
+    pub company_name: String,
+    /// Booth number or label
+    pub label: Option<String>,
+}
+
+/// The caller's RSVP to a career fair.
+#[derive(Debug, Serialize)]
+pub struct EventRsvp {
+    /// Code recruiters scan at booths
+    pub badge_code: String,
+    /// Sessions the caller signed up for
+    pub session_ids: Vec<i32>,
+}
+
+/// A career fair with its sessions, booths, and the caller's RSVP.
+#[derive(Debug, Serialize)]
+pub struct EventDetails {
+    /// The event
+    #[serde(flatten)]
+    pub event: CareerEvent,
+    /// Sessions in start order
+    pub sessions: Vec<EventSession>,
+    /// Employer booths
+    pub booths: Vec<EventBooth>,
+    /// The caller's RSVP, if any
+    pub rsvp: Option<EventRsvp>,
+}
+
+/// A candidate lead recorded at a career-fair booth.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EventLead {
+    /// Lead ID
+    pub id: i32,
+    /// Event where the interaction happened
+    pub event_id: i32,
+    /// Event name
+    pub event_name: String,
+    /// Candidate's user ID
+    pub candidate_id: Uuid,
+    /// Candidate's full name
+    pub candidate_name: String,
+    /// Candidate's email
+    pub candidate_email: String,
+    /// Job the candidate is a lead for
+    pub job_id: Option<i32>,
+    /// Title of that job
+    pub job_title: Option<String>,
+    /// Candidate's certifications verified with their issuers
+    pub verified_certifications: Vec<String>,
+    /// Recruiter's notes
+    pub notes: Option<String>,
+    /// Recruiter's rating (1-5)
+    pub rating: Option<i16>,
+    /// Pipeline state
+    pub lead_status: LeadStatus,
+    /// Recruiter who recorded the interaction
+    pub recorded_by: Option<Uuid>,
+    /// When the interaction was recorded
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// One component of an offer's compensation beyond the base salary.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CompensationComponent {
+    /// What the component is, e.g. "Signing bonus" or "Housing allowance"
+    #[validate(length(min = 1, max = 100, message = "Each compensation component needs a label"))]
+    pub label: String,
+    /// Amount per period, in the offer's currency
+    #[validate(range(min = 0, message = "Compensation amounts cannot be negative"))]
+    pub amount: i64,
+    /// How often it is paid
+    pub period: CompensationPeriod,
+}
+
+/// Payload for extending an offer on an application.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOfferPayload {
+    /// Annual base salary
+    #[validate(range(min = 0, message = "Base salary cannot be negative"))]
+    pub base_salary: i32,
+    /// ISO 4217 currency code (default: USD)
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter ISO code"))]
+    pub currency: Option<String>,
+    /// Bonuses, allowances, equity, and other components
+    #[serde(default)]
+    #[validate(nested)]
+    pub compensation: Vec<CompensationComponent>,
+    /// Proposed start date
+    pub start_date: Option<chrono::NaiveDate>,
+    /// When the offer lapses if the candidate has not responded
+    pub deadline: chrono::DateTime<chrono::Utc>,
+    /// Message to the candidate
+    pub message: Option<String>,
+}
+
+/// Payload for accepting an offer.
+#[derive(Debug, Default, Deserialize)]
+pub struct AcceptOfferPayload {
+    /// Note to the employer
+    pub note: Option<String>,
+    /// Withdraw the candidate's other active applications
+    #[serde(default)]
+    pub close_other_applications: bool,
+}
+
+/// Payload for declining an offer.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeclineOfferPayload {
+    /// Why the candidate is declining
+    pub reason: Option<String>,
+    /// Terms the candidate would accept; marks the offer as countered
+    pub counter_offer: Option<String>,
+}
+
+/// A document attached to an offer.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct OfferDocument {
+    /// Document ID
+    pub id: i32,
+    /// Offer the document belongs to
+    pub offer_id: i32,
+    /// Original file name
+    pub file_name: String,
+    /// MIME type
+    pub content_type: String,
+    /// File size in bytes
+    pub size_bytes: i64,
+    /// When the document was uploaded
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A job offer with its compensation breakdown.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JobOffer {
+    /// Offer ID
+    pub id: i32,
+    /// Application the offer was made on
+    pub application_id: i32,
+    /// Job offered
+    pub job_id: i32,
+    /// Job title
+    pub job_title: String,
+    /// Company name
+    pub company: String,
+    /// Candidate's user ID
+    pub candidate_id: Uuid,
+    /// Candidate's full name
+    pub candidate_name: String,
+    /// Annual base salary
+    pub base_salary: i32,
+    /// ISO 4217 currency code
+    pub currency: String,
+    /// Components beyond the base salary
+    pub compensation: sqlx::types::Json<Vec<CompensationComponent>>,
+    /// Base salary plus all components over the first year
+    #[sqlx(skip)]
+    pub first_year_total: i64,
+    /// Proposed start date
+    pub start_date: Option<chrono::NaiveDate>,
+    /// Response deadline
+    pub deadline: chrono::DateTime<chrono::Utc>,
+    /// Message to the candidate
+    pub message: Option<String>,
+    /// Offer state
+    pub status: OfferStatus,
+    /// Candidate's note, decline reason, or counter-offer
+    pub candidate_note: Option<String>,
+    /// When the candidate responded
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the offer was made
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Attached documents
+    #[sqlx(skip)]
+    pub documents: Vec<OfferDocument>,
+}
+
+/// Payload for starting a wizard.
+#[derive(Debug, Deserialize)]
+pub struct CreateWizardPayload {
+    /// Which flow to start
+    pub kind: WizardKind,
+}
+
+/// Query parameters for listing wizards.
+#[derive(Debug, Deserialize)]
+pub struct WizardQueryParams {
+    /// Only wizards of this kind
+    pub kind: Option<WizardKind>,
+    /// Include finished wizards (default: false)
+    #[serde(default)]
+    pub include_completed: bool,
+}
+
+/// New state for one wizard step.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WizardStepUpdate {
+    /// Step name (e.g. `confirm_skills`)
+    pub step: String,
+    /// New status
+    pub status: WizardStepStatus,
+    /// Intermediate result to store; omit to keep the stored one
+    pub output: Option<serde_json::Value>,
+    /// Why the step failed (kept only for `failed`)
+    pub error: Option<String>,
+}
+
+/// Payload for updating a wizard's steps, applied in order.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWizardPayload {
+    #[validate(length(min = 1, max = 20, message = "Provide between 1 and 20 step updates"))]
+    pub steps: Vec<WizardStepUpdate>,
+}
+
+/// How a roadmap was generated and what its self-check found.
+#[derive(Debug, Default, sqlx::FromRow)]
+pub struct RoadmapGeneration {
+    /// Model that generated the roadmap
+    pub ai_model: Option<String>,
+    /// Prompt template version
+    pub prompt_version: Option<String>,
+    /// Temperature, output token limit, and seed
+    pub generation_settings: Option<serde_json::Value>,
+    /// Issues found by the self-check; `None` if it did not run
+    pub quality_warnings: Option<serde_json::Value>,
+}
+
+/// Payload for rating proficiency in a skill.
+#[derive(Debug, Deserialize, Validate)]
+pub struct RateSkillPayload {
+    /// 1 (beginner) to 5 (expert)
+    #[validate(range(min = 1, max = 5, message = "proficiency must be between 1 and 5"))]
+    pub proficiency: i16,
+    /// When the skill was last used; omit if in current use or unknown
+    pub last_used_on: Option<chrono::NaiveDate>,
+}
+
+/// Payload for marking a resource in a roadmap phase.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRoadmapResourcePayload {
+    /// `completed` or `skipped`
+    pub status: ResourceStatus,
+    /// 1 (poor) to 5 (excellent); omit to leave unrated
+    #[validate(range(min = 1, max = 5, message = "rating must be between 1 and 5"))]
+    pub rating: Option<i16>,
+    /// Private note about the resource
+    #[validate(length(max = 2000, message = "note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Bulk changes to the user's skills or target roles.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CurateItemsPayload {
+    /// Items to remove
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 items can be removed at once"))]
+    pub remove: Vec<String>,
+    /// Items to rename
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 items can be renamed at once"))]
+    pub rename: Vec<RenameItemPayload>,
+    /// Keep removed items and old names out of AI extraction (default true)
+    pub reject: Option<bool>,
+}
+
+/// One rename in a [`CurateItemsPayload`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RenameItemPayload {
+    /// Current name, matched case-insensitively
+    pub from: String,
+    /// New name
+    pub to: String,
+}
+
+/// Query parameters for exporting a mentor conversation.
+#[derive(Debug, Deserialize)]
+pub struct ConversationExportParams {
+    /// `markdown` (default) or `json`
+    pub format: Option<String>,
+}
+
+/// Payload for creating an empty mentor conversation.
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct CreateConversationPayload {
+    /// Title (default: "New conversation")
+    #[validate(length(max = 255, message = "title must be at most 255 characters"))]
+    pub title: Option<String>,
+}
+
+/// Payload for sending the mentor the next message of a conversation.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConversationMessagePayload {
+    #[validate(length(min = 1, max = 4000, message = "message must be between 1 and 4000 characters"))]
+    pub message: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+    /// Ground the answer in the user's documents (default: true)
+    pub use_documents: Option<bool>,
+    /// How plainly to answer
+    pub reading_level: Option<crate::ai::types::ReadingLevel>,
+    pub tone: Option<crate::ai::types::Tone>,
+}
+
+/// Payload for giving or withdrawing consent to debug captures.
+#[derive(Debug, Deserialize)]
+pub struct DebugCaptureConsentPayload {
+    pub enabled: bool,
+}
+
+/// Payload for flagging a captured AI generation as bad.
+#[derive(Debug, Deserialize, Validate)]
+pub struct FlagCapturePayload {
+    /// What was wrong with the result
+    #[validate(length(min = 1, max = 2000, message = "reason must be between 1 and 2000 characters"))]
+    pub reason: String,
+}
+
+/// Query parameters for the admin debug capture queue.
+#[derive(Debug, Deserialize)]
+pub struct DebugCaptureQueueParams {
+    /// Include unflagged captures (default: false)
+    pub all: Option<bool>,
+}
+
+/// Payload for rating an AI result with a thumbs up or down.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AiFeedbackPayload {
+    pub action: crate::ai::types::ActionType,
+    /// `prompt_version` of the rated result
+    #[validate(length(min = 1, max = 50, message = "prompt_version must be 1-50 characters"))]
+    pub prompt_version: String,
+    /// Thumbs up (true) or down (false)
+    pub helpful: bool,
+    #[validate(length(max = 2000, message = "Comment must be at most 2000 characters"))]
+    pub comment: Option<String>,
+}
+
+/// Payload for starting a prompt rollout.
+#[derive(Debug, Deserialize, Validate)]
+pub struct StartPromptRolloutPayload {
+    /// Action whose candidate prompt is rolled out
+    pub action: crate::ai::types::ActionType,
+    /// Share of requests sent to the candidate (1-100)
+    #[validate(range(min = 1, max = 100, message = "traffic_percent must be from 1 to 100"))]
+    pub traffic_percent: i32,
+    /// Parse-failure rate (0-1) above which it is rolled back (default: 0.1)
+    pub max_parse_failure_rate: Option<f32>,
+    /// Thumbs-down rate (0-1) above which it is rolled back (default: 0.3)
+    pub max_thumbs_down_rate: Option<f32>,
+    /// Calls or ratings needed before a rate is judged (default: 30)
+    pub min_samples: Option<i32>,
+}
+
+/// Payload for changing an active prompt rollout; unset fields are kept.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePromptRolloutPayload {
+    #[validate(range(min = 1, max = 100, message = "traffic_percent must be from 1 to 100"))]
+    pub traffic_percent: Option<i32>,
+    pub max_parse_failure_rate: Option<f32>,
+    pub max_thumbs_down_rate: Option<f32>,
+    pub min_samples: Option<i32>,
+}
+
+/// Payload for rolling a prompt rollout back.
+#[derive(Debug, Deserialize, Validate)]
+pub struct RollBackPromptRolloutPayload {
+    #[validate(length(min = 1, max = 2000, message = "Reason must be 1-2000 characters"))]
+    pub reason: String,
+}
+
+/// Payload for explaining a jargon term.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExplainTermPayload {
+    /// The term, e.g. "CI/CD"
+    #[validate(length(min = 1, max = 100, message = "term must be between 1 and 100 characters"))]
+    pub term: String,
+    /// Where the term was seen, to pick the right meaning
+    #[validate(length(max = 1000))]
+    pub context: Option<String>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for rewriting a generated document more simply.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SimplifyPayload {
+    /// The document: text, or JSON such as a roadmap
+    pub content: serde_json::Value,
+    /// Level to rewrite at (default: simple)
+    pub reading_level: Option<crate::ai::types::ReadingLevel>,
+    pub tone: Option<crate::ai::types::Tone>,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Query parameters for AI token usage.
+#[derive(Debug, Deserialize)]
+pub struct AiUsageParams {
+    /// Days of calls to include (default: 30, at most 365)
+    pub days: Option<i32>,
+    /// Another user's usage (admins only)
+    pub user_id: Option<Uuid>,
+    /// The whole platform's usage (admins only)
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Payload for saving a document for the mentor to draw on.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDocumentPayload {
+    #[validate(length(min = 1, max = 255, message = "Title must be between 1 and 255 characters"))]
+    pub title: String,
+    /// Plain text, e.g. notes, a job description, or interview feedback
+    #[validate(length(min = 1, max = 100000, message = "Content must be between 1 and 100000 characters"))]
+    pub content: String,
+}
+
+/// Payload for asking a question about an uploaded offer document.
+#[derive(Debug, Deserialize, Validate)]
+pub struct OfferQuestionPayload {
+    #[validate(length(min = 1, max = 2000, message = "Question must be between 1 and 2000 characters"))]
+    pub question: String,
+    /// AI provider to use (default: gemini)
+    pub provider: Option<crate::ai::types::AIProvider>,
+}
+
+/// Payload for creating or replacing a knowledge base article.
+#[derive(Debug, Deserialize, Validate)]
+pub struct KbArticlePayload {
+    #[validate(length(min = 1, max = 255, message = "Title must be between 1 and 255 characters"))]
+    pub title: String,
+    /// URL slug; derived from the title if omitted
+    #[validate(length(min = 1, max = 160, message = "Slug must be between 1 and 160 characters"))]
+    pub slug: Option<String>,
+    /// One or two sentences shown in listings
+    #[validate(length(max = 500))]
+    pub summary: Option<String>,
+    /// Markdown
+    #[validate(length(min = 1, max = 200000, message = "Content must be between 1 and 200000 characters"))]
+    pub content: String,
+    #[serde(default)]
+    #[validate(length(max = 20, message = "At most 20 tags"))]
+    pub tags: Vec<String>,
+    /// Published articles are public and feed mentor answers (default: false)
+    #[serde(default)]
+    pub published: bool,
+}
+
+/// Query parameters for browsing knowledge base articles.
+#[derive(Debug, Deserialize)]
+pub struct KbArticleQueryParams {
+    /// Only articles with this tag
+    pub tag: Option<String>,
+    /// Only articles whose title or summary contains this text
+    pub q: Option<String>,
+}
+
+/// Payload for building an application package. Either `job_id` (a posting
+/// on the platform) or `job_description` is required.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApplicationPackagePayload {
+    pub job_id: Option<i32>,
+    #[validate(length(min = 1, max = 255, message = "Job title must be between 1 and 255 characters"))]
+    pub job_title: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Company must be between 1 and 255 characters"))]
+    pub company: Option<String>,
+    #[validate(length(min = 50, max = 20000, message = "Job description must be between 50 and 20000 characters"))]
+    pub job_description: Option<String>,
+    /// `gemini` (default), `groq`, or `ollama`
+    pub provider: Option<String>,
+}
+
+/// A certification on the user's profile.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Certification {
+    pub id: i32,
+    /// Certificate or course name
+    pub name: String,
+    /// Issuing organization
+    pub issuer: Option<String>,
+    pub issue_date: Option<chrono::NaiveDate>,
+    pub expiry_date: Option<chrono::NaiveDate>,
+    /// The issuer's credential or certificate ID
+    pub credential_id: Option<String>,
+    /// Where the credential can be verified
+    pub credential_url: Option<String>,
+    pub source: CertificationSource,
+    /// Whether the issuer confirmed the credential; `verified` earns a badge
+    pub verification_status: CertificationVerificationStatus,
+    /// Why verification failed or could not reach the issuer
+    pub verification_error: Option<String>,
+    /// When the issuer first confirmed the credential
+    pub verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Payload for adding a certification by hand.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CertificationPayload {
+    #[validate(length(min = 1, max = 255, message = "Name must be between 1 and 255 characters"))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub issuer: Option<String>,
+    pub issue_date: Option<chrono::NaiveDate>,
+    pub expiry_date: Option<chrono::NaiveDate>,
+    #[validate(length(max = 255))]
+    pub credential_id: Option<String>,
+    #[validate(url(message = "Invalid credential URL"))]
+    pub credential_url: Option<String>,
+}
+
+/// Result of reading a certificate image.
+#[derive(Debug, Serialize)]
+pub struct CertificateScan {
+    /// `cv`, `certificate`, or `other`
+    pub document_type: String,
+    /// All of the text read from the image
+    pub text: String,
+    /// Certificates shown in the image
+    pub found: usize,
+    /// Certificates added to the profile; ones already listed are skipped
+    pub added: Vec<Certification>,
+}
+
+/// Service health, as reported by `GET /api/health`.
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    /// `ok` when every configured AI provider accepts requests, `degraded`
+    /// when some have an open circuit breaker, `unavailable` when none
+    /// accepts requests or none is configured
+    pub status: &'static str,
+    /// Whether AI requests are served by the mock provider
+    pub ai_mock_mode: bool,
+    /// Circuit breakers of the configured AI providers, in fallback order
+    pub ai_providers: Vec<crate::ai::circuit::ProviderCircuit>,
+}
@@ -0,0 +1,390 @@
+//! Current-user handlers: read the editable profile fields and change them
+//! with JSON Merge Patch (RFC 7396).
+//!
+//! Responses carry an `ETag` for the profile. Sending it back in `If-Match`
+//! makes the patch fail with 412 if the profile changed in the meantime,
+//! so two clients editing at once cannot silently overwrite each other.
+//!
+//! AI-driven updates snapshot the profile first (see [`snapshot_profile`]),
+//! and the user can revert to any recent snapshot.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, header},
+};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value, json};
+use sqlx::{FromRow, PgConnection};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{ProfileDocument, ProfileSnapshot};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::merge_patch;
+use crate::response::ApiResponse;
+
+/// Audit action recorded for profile changes.
+const AUDIT_ACTION: &str = "profile.updated";
+
+/// Audit action recorded when a snapshot is restored.
+const REVERT_AUDIT_ACTION: &str = "profile.reverted";
+
+/// Snapshot reason for the state a revert replaced, so reverts can be undone.
+const REVERT_REASON: &str = "revert";
+
+/// Snapshots kept per user; older ones are pruned.
+const MAX_SNAPSHOTS: i64 = 20;
+
+/// Response headers sent with a profile.
+type ProfileHeaders = [(header::HeaderName, HeaderValue); 1];
+
+#[derive(Debug, FromRow)]
+struct StoredProfile {
+    #[sqlx(flatten)]
+    document: ProfileDocument,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// Columns of [`StoredProfile`].
+const PROFILE_COLUMNS: &str = "full_name, education_level, experience_level, preferred_track, \
+                               skills, projects, target_roles, social_links, updated_at";
+
+/// Returns the profile fields that `PATCH /api/users/me` edits, with the
+/// profile's `ETag`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user no longer exists, or an error if
+/// the database operation fails.
+pub async fn get_me(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<(ProfileHeaders, ApiResponse<ProfileDocument>)> {
+    let profile = sqlx::query_as::<_, StoredProfile>(&format!(
+        "SELECT {} FROM users WHERE id = $1",
+        PROFILE_COLUMNS
+    ))
+    .bind(auth_user.user_id)
+    .fetch_optional(&app_state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok((etag_header(profile.updated_at), ApiResponse::ok(profile.document)))
+}
+
+/// Applies a JSON Merge Patch to the user's profile.
+///
+/// Members in the patch replace the stored values, `null` clears a field
+/// (lists become empty), and `social_links` is merged link by link. Lists
+/// are replaced as a whole, as RFC 7396 specifies. The patched profile is
+/// validated before anything is saved, and the changed fields are recorded
+/// in the audit log with their old and new values.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The body is not a JSON object sent as `application/merge-patch+json`
+///   or `application/json` (400)
+/// - The patched profile has unknown fields or fails validation (400)
+/// - `If-Match` is given and does not match the profile's `ETag` (412)
+/// - Database operation fails
+pub async fn patch_me(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<(ProfileHeaders, ApiResponse<ProfileDocument>)> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with(merge_patch::CONTENT_TYPE) && !content_type.starts_with("application/json") {
+        return Err(AppError::BadRequest(format!(
+            "Send the patch as {}",
+            merge_patch::CONTENT_TYPE
+        )));
+    }
+    let patch: Value = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+    if !patch.is_object() {
+        return Err(AppError::ValidationError(
+            "The patch must be a JSON object".to_string(),
+        ));
+    }
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let stored = lock_profile(&mut tx, auth_user.user_id).await?;
+
+    if let Some(if_match) = headers.get(header::IF_MATCH)
+        && !etag_matches(if_match, &etag(stored.updated_at))
+    {
+        return Err(AppError::PreconditionFailed(
+            "The profile changed since it was read; fetch it again and reapply the patch".to_string(),
+        ));
+    }
+
+    let before = serde_json::to_value(&stored.document).map_err(|_| AppError::InternalServerError)?;
+    let mut patched = before.clone();
+    merge_patch::apply(&mut patched, &patch);
+    let mut document: ProfileDocument =
+        serde_json::from_value(patched).map_err(|e| AppError::ValidationError(e.to_string()))?;
+    normalize(&mut document);
+    document.validate()?;
+
+    let after = serde_json::to_value(&document).map_err(|_| AppError::InternalServerError)?;
+    let changed = merge_patch::changed_members(&before, &after);
+    if changed.is_empty() {
+        tx.commit().await?;
+        return Ok((etag_header(stored.updated_at), ApiResponse::ok(stored.document)));
+    }
+
+    let updated_at = save_profile(&mut tx, auth_user.user_id, &document).await?;
+    record_change(
+        &mut tx,
+        auth_user.user_id,
+        AUDIT_ACTION,
+        change_metadata(&changed, &before, &after),
+    )
+    .await?;
+    tx.commit().await?;
+    app_state.user_cache.invalidate(auth_user.user_id).await;
+
+    info!("User {} patched profile fields {:?}", auth_user.user_id, changed);
+    Ok((etag_header(updated_at), ApiResponse::ok(document)))
+}
+
+/// Lists the user's recent profile snapshots, newest first. Snapshots are
+/// taken before AI-driven updates and before reverts; the stored CV text is
+/// not returned, only whether there is any.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn get_profile_history(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+) -> AppResult<ApiResponse<Vec<ProfileSnapshot>>> {
+    let snapshots = sqlx::query_as::<_, ProfileSnapshot>(
+        r#"
+        SELECT id, reason, profile, raw_cv_text IS NOT NULL AS has_cv_text, reverted_at, created_at
+        FROM profile_snapshots
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    Ok(ApiResponse::ok(snapshots))
+}
+
+/// Restores the profile fields and CV text from a snapshot.
+///
+/// The profile being replaced is snapshotted first; its ID is returned as
+/// `meta.undo_snapshot_id`, so reverting to it undoes the revert.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such snapshot, or an
+/// error if the database operation fails.
+pub async fn revert_profile(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(snapshot_id): Path<i32>,
+) -> AppResult<(ProfileHeaders, ApiResponse<ProfileDocument>)> {
+    let mut tx = app_state.db_pool.begin().await?;
+    let (sqlx::types::Json(document), raw_cv_text) =
+        sqlx::query_as::<_, (sqlx::types::Json<ProfileDocument>, Option<String>)>(
+            "SELECT profile, raw_cv_text FROM profile_snapshots WHERE id = $1 AND user_id = $2",
+        )
+        .bind(snapshot_id)
+        .bind(auth_user.user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let stored = lock_profile(&mut tx, auth_user.user_id).await?;
+    let undo_snapshot_id = insert_snapshot(&mut tx, auth_user.user_id, REVERT_REASON, &stored.document).await?;
+
+    let updated_at = save_profile(&mut tx, auth_user.user_id, &document).await?;
+    sqlx::query("UPDATE users SET raw_cv_text = $2 WHERE id = $1")
+        .bind(auth_user.user_id)
+        .bind(raw_cv_text)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE profile_snapshots SET reverted_at = NOW() WHERE id = $1")
+        .bind(snapshot_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let before = serde_json::to_value(&stored.document).map_err(|_| AppError::InternalServerError)?;
+    let after = serde_json::to_value(&document).map_err(|_| AppError::InternalServerError)?;
+    let mut metadata = change_metadata(&merge_patch::changed_members(&before, &after), &before, &after);
+    metadata["snapshot_id"] = json!(snapshot_id);
+    metadata["undo_snapshot_id"] = json!(undo_snapshot_id);
+    record_change(&mut tx, auth_user.user_id, REVERT_AUDIT_ACTION, metadata).await?;
+    tx.commit().await?;
+    app_state.user_cache.invalidate(auth_user.user_id).await;
+
+    info!("User {} reverted their profile to snapshot {}", auth_user.user_id, snapshot_id);
+    Ok((
+        etag_header(updated_at),
+        ApiResponse::ok(document).meta("undo_snapshot_id", undo_snapshot_id),
+    ))
+}
+
+/// Snapshots the user's profile and CV text before an AI-driven update.
+/// Call it inside the transaction that makes the update; it locks the
+/// user's row until the transaction ends. Returns the snapshot ID.
+pub(super) async fn snapshot_profile(conn: &mut PgConnection, user_id: Uuid, reason: &str) -> AppResult<i32> {
+    let stored = lock_profile(conn, user_id).await?;
+    insert_snapshot(conn, user_id, reason, &stored.document).await
+}
+
+/// Saves a snapshot of `document` with the user's current CV text, pruning
+/// snapshots beyond [`MAX_SNAPSHOTS`].
+async fn insert_snapshot(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    reason: &str,
+    document: &ProfileDocument,
+) -> AppResult<i32> {
+    let snapshot_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO profile_snapshots (user_id, reason, profile, raw_cv_text)
+        SELECT id, $2, $3, raw_cv_text FROM users WHERE id = $1
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(reason)
+    .bind(sqlx::types::Json(document))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM profile_snapshots
+        WHERE user_id = $1
+          AND id NOT IN (
+              SELECT id FROM profile_snapshots
+              WHERE user_id = $1
+              ORDER BY created_at DESC, id DESC
+              LIMIT $2
+          )
+        "#,
+    )
+    .bind(user_id)
+    .bind(MAX_SNAPSHOTS)
+    .execute(conn)
+    .await?;
+    Ok(snapshot_id)
+}
+
+/// Trims list entries, dropping blank ones and case-insensitive duplicates.
+fn normalize(document: &mut ProfileDocument) {
+    for list in [&mut document.skills, &mut document.projects, &mut document.target_roles] {
+        let mut seen = std::collections::HashSet::new();
+        *list = list
+            .iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty() && seen.insert(item.to_lowercase()))
+            .collect();
+    }
+    document.full_name = document.full_name.trim().to_string();
+}
+
+/// Locks the user's row for the rest of the transaction and loads the profile.
+async fn lock_profile(conn: &mut PgConnection, user_id: Uuid) -> AppResult<StoredProfile> {
+    sqlx::query_as::<_, StoredProfile>(&format!(
+        "SELECT {} FROM users WHERE id = $1 FOR UPDATE",
+        PROFILE_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Saves every profile field and returns the new `updated_at`.
+async fn save_profile(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    document: &ProfileDocument,
+) -> AppResult<Option<DateTime<Utc>>> {
+    let updated_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        UPDATE users
+        SET full_name = $2, education_level = $3, experience_level = $4, preferred_track = $5,
+            skills = $6, projects = $7, target_roles = $8, social_links = $9,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING updated_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(&document.full_name)
+    .bind(&document.education_level)
+    .bind(document.experience_level.as_ref())
+    .bind(document.preferred_track.as_ref())
+    .bind(&document.skills)
+    .bind(&document.projects)
+    .bind(&document.target_roles)
+    .bind(sqlx::types::Json(&document.social_links))
+    .fetch_one(conn)
+    .await?;
+    Ok(updated_at)
+}
+
+/// Audit metadata for a change: the changed fields with their old and new
+/// values.
+fn change_metadata(changed: &[String], before: &Value, after: &Value) -> Value {
+    let pick = |document: &Value| -> Map<String, Value> {
+        changed
+            .iter()
+            .map(|field| (field.clone(), document.get(field).cloned().unwrap_or(Value::Null)))
+            .collect()
+    };
+    json!({ "fields": changed, "before": pick(before), "after": pick(after) })
+}
+
+/// Records a profile change in `audit_logs`.
+async fn record_change(conn: &mut PgConnection, user_id: Uuid, action: &str, metadata: Value) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, action, target_type, target_id, metadata, impersonated_by)
+        VALUES ($1, $2, 'user', $3, $4, $5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(action)
+    .bind(user_id.to_string())
+    .bind(metadata)
+    .bind(crate::impersonation::current())
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// The profile's entity tag, derived from when it last changed.
+fn etag(updated_at: Option<DateTime<Utc>>) -> String {
+    format!("\"{:x}\"", updated_at.map(|t| t.timestamp_micros()).unwrap_or_default())
+}
+
+fn etag_header(updated_at: Option<DateTime<Utc>>) -> ProfileHeaders {
+    let value = HeaderValue::from_str(&etag(updated_at)).unwrap_or(HeaderValue::from_static("\"0\""));
+    [(header::ETAG, value)]
+}
+
+/// Strong `If-Match` comparison: `*` or any listed tag equal to `current`.
+fn etag_matches(if_match: &HeaderValue, current: &str) -> bool {
+    if_match
+        .to_str()
+        .map(|value| value.split(',').map(str::trim).any(|tag| tag == "*" || tag == current))
+        .unwrap_or(false)
+}
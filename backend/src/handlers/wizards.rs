@@ -0,0 +1,238 @@
+//! Resumable wizard handlers: start a flow, list flows to resume, and save step progress.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sqlx::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::types::{CreateWizardPayload, UpdateWizardPayload, WizardQueryParams};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::response::ApiResponse;
+use crate::wizards::{self, Wizard, WizardStep};
+
+const WIZARD_COLUMNS: &str = "id, kind, completed_at, created_at, updated_at";
+
+const STEP_COLUMNS: &str = "wizard_id, step, position, status, output, error, updated_at";
+
+/// Loads one of the user's wizards with its steps, optionally locking it.
+async fn load_wizard(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    wizard_id: i32,
+    for_update: bool,
+) -> AppResult<Wizard> {
+    let wizard = sqlx::query_as::<_, Wizard>(&format!(
+        "SELECT {} FROM wizards WHERE id = $1 AND user_id = $2 {}",
+        WIZARD_COLUMNS,
+        if for_update { "FOR UPDATE" } else { "" }
+    ))
+    .bind(wizard_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let steps = sqlx::query_as::<_, WizardStep>(&format!(
+        "SELECT {} FROM wizard_steps WHERE wizard_id = $1 ORDER BY position",
+        STEP_COLUMNS
+    ))
+    .bind(wizard_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(wizard.with_steps(steps))
+}
+
+/// Starts a wizard with all of its steps pending.
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn create_wizard(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateWizardPayload>,
+) -> AppResult<ApiResponse<Wizard>> {
+    let mut tx = app_state.db_pool.begin().await?;
+
+    let wizard_id: i32 = sqlx::query_scalar(
+        "INSERT INTO wizards (user_id, kind) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.kind)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let steps = wizards::steps_for(payload.kind);
+    let positions: Vec<i16> = (0..steps.len() as i16).collect();
+    sqlx::query(
+        r#"
+        INSERT INTO wizard_steps (wizard_id, step, position)
+        SELECT $1, step, position FROM UNNEST($2::TEXT[], $3::SMALLINT[]) AS s(step, position)
+        "#,
+    )
+    .bind(wizard_id)
+    .bind(steps)
+    .bind(&positions)
+    .execute(&mut *tx)
+    .await?;
+
+    let wizard = load_wizard(&mut tx, auth_user.user_id, wizard_id, false).await?;
+    tx.commit().await?;
+
+    info!("Wizard {} ({:?}) started by {}", wizard_id, payload.kind, auth_user.user_id);
+    Ok(ApiResponse::ok(wizard))
+}
+
+/// Lists the user's wizards, most recently updated first.
+///
+/// # Query Parameters
+///
+/// - `kind` - Only wizards of this kind (e.g. `cv_review`)
+/// - `include_completed` - Include finished wizards (default: false)
+///
+/// # Errors
+///
+/// Returns an error if the user is not authenticated or the database
+/// operation fails.
+pub async fn list_wizards(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<WizardQueryParams>,
+) -> AppResult<ApiResponse<Vec<Wizard>>> {
+    let wizards = sqlx::query_as::<_, Wizard>(&format!(
+        r#"
+        SELECT {} FROM wizards
+        WHERE user_id = $1
+          AND ($2::wizard_kind IS NULL OR kind = $2)
+          AND ($3 OR completed_at IS NULL)
+        ORDER BY updated_at DESC
+        LIMIT 20
+        "#,
+        WIZARD_COLUMNS
+    ))
+    .bind(auth_user.user_id)
+    .bind(params.kind)
+    .bind(params.include_completed)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let ids: Vec<i32> = wizards.iter().map(|w| w.id).collect();
+    let steps = sqlx::query_as::<_, WizardStep>(&format!(
+        "SELECT {} FROM wizard_steps WHERE wizard_id = ANY($1) ORDER BY wizard_id, position",
+        STEP_COLUMNS
+    ))
+    .bind(&ids)
+    .fetch_all(&app_state.db_pool)
+    .await?;
+
+    let wizards = wizards
+        .into_iter()
+        .map(|wizard| {
+            let steps = steps
+                .iter()
+                .filter(|s| s.wizard_id == wizard.id)
+                .cloned()
+                .collect();
+            wizard.with_steps(steps)
+        })
+        .collect();
+    Ok(ApiResponse::ok(wizards))
+}
+
+/// Gets a wizard with each step's status and stored output.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - The wizard does not exist or belongs to another user
+/// - Database operation fails
+pub async fn get_wizard(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(wizard_id): Path<i32>,
+) -> AppResult<ApiResponse<Wizard>> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let wizard = load_wizard(&mut conn, auth_user.user_id, wizard_id, false).await?;
+    Ok(ApiResponse::ok(wizard))
+}
+
+/// Saves progress on a wizard's steps.
+///
+/// Updates are applied in order. Changing a finished step's output, or
+/// reopening it, resets the steps after it to `pending`. The wizard is
+/// marked completed once every step is completed or skipped.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - User is not authenticated
+/// - The wizard does not exist or belongs to another user
+/// - A step is unknown, started before the steps ahead of it, or its output is too large
+/// - Database operation fails
+pub async fn update_wizard(
+    auth_user: AuthUser,
+    State(app_state): State<AppState>,
+    Path(wizard_id): Path<i32>,
+    Json(payload): Json<UpdateWizardPayload>,
+) -> AppResult<ApiResponse<Wizard>> {
+    payload.validate()?;
+
+    let mut tx = app_state.db_pool.begin().await?;
+    let mut wizard = load_wizard(&mut tx, auth_user.user_id, wizard_id, true).await?;
+
+    for update in payload.steps {
+        wizards::apply_update(
+            &mut wizard.steps,
+            &update.step,
+            update.status,
+            update.output,
+            update.error,
+        )?;
+    }
+
+    for step in wizard.steps.iter().filter(|s| s.changed) {
+        sqlx::query(
+            r#"
+            UPDATE wizard_steps
+            SET status = $3, output = $4, error = $5, updated_at = NOW()
+            WHERE wizard_id = $1 AND step = $2
+            "#,
+        )
+        .bind(wizard_id)
+        .bind(&step.step)
+        .bind(step.status)
+        .bind(&step.output)
+        .bind(&step.error)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let finished = wizards::current_step(&wizard.steps).is_none();
+    sqlx::query(
+        r#"
+        UPDATE wizards
+        SET updated_at = NOW(),
+            completed_at = CASE WHEN $2 THEN COALESCE(completed_at, NOW()) END
+        WHERE id = $1
+        "#,
+    )
+    .bind(wizard_id)
+    .bind(finished)
+    .execute(&mut *tx)
+    .await?;
+
+    let wizard = load_wizard(&mut tx, auth_user.user_id, wizard_id, false).await?;
+    tx.commit().await?;
+
+    info!("Wizard {} updated by {}", wizard_id, auth_user.user_id);
+    Ok(ApiResponse::ok(wizard))
+}
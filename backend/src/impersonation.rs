@@ -0,0 +1,397 @@
+//! Impersonation of users by support admins.
+//!
+//! An admin starts a session with a reason and gets a token that acts as the
+//! user until it expires (at most [`MAX_MINUTES`]) or an admin ends it.
+//! Impersonation tokens carry the admin and session (see
+//! [`crate::auth::create_impersonation_jwt`]), and [`guard`] checks every
+//! request made with one:
+//!
+//! - The session must still be open and the admin still an admin.
+//! - Destructive requests are refused: every `DELETE`, and writes that
+//!   spend the user's money or credits, accept offers or policies, revert
+//!   their profile, change a team, or message other users.
+//! - Every request is written to `audit_logs` as `impersonation.request`,
+//!   and audit entries made by handlers during it carry the admin in
+//!   `impersonated_by` (see [`current`]).
+//! - Responses carry `X-Impersonated-By` with the admin's ID.
+//!
+//! Once a session ends or expires, [`notify_finished`] tells the user who
+//! accessed their account, why, and how many requests changed data.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::auth::{self, AuthUser};
+use crate::errors::{AppError, AppResult};
+use crate::models::UserRole;
+
+/// Session length when none is requested, in minutes.
+pub const DEFAULT_MINUTES: i64 = 15;
+
+/// Longest session an admin may start, in minutes.
+pub const MAX_MINUTES: i64 = 60;
+
+/// Writes refused while impersonating, by path prefix. `DELETE` requests
+/// and invitations (paths ending in `/invite`) are refused everywhere.
+const BLOCKED_WRITE_PREFIXES: &[&str] = &[
+    "/ai",
+    "/api-keys",
+    "/billing",
+    "/offers",
+    "/policies",
+    "/sandbox",
+    "/users/me/profile/revert",
+    "/employer/team",
+];
+
+/// Sessions notified per [`notify_finished`] run.
+const NOTIFY_BATCH: i64 = 100;
+
+tokio::task_local! {
+    static IMPERSONATOR: Uuid;
+}
+
+/// The admin impersonating the user of the request being handled, if any.
+/// Audit log writes store it in `impersonated_by`.
+pub fn current() -> Option<Uuid> {
+    IMPERSONATOR.try_with(|admin_id| *admin_id).ok()
+}
+
+/// An impersonation session.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ImpersonationSession {
+    pub id: i32,
+    pub admin_id: Option<Uuid>,
+    pub admin_name: Option<String>,
+    pub user_id: Uuid,
+    pub user_name: Option<String>,
+    pub reason: String,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// When an admin ended the session early
+    pub ended_at: Option<DateTime<Utc>>,
+    /// When the user was told about the session
+    pub notified_at: Option<DateTime<Utc>>,
+    /// Requests made with the session's token
+    pub requests: i64,
+    /// Of those, requests that were not reads
+    pub changes: i64,
+}
+
+/// A newly started session and its token.
+#[derive(Debug, Serialize)]
+pub struct StartedImpersonation {
+    pub token: String,
+    pub session: ImpersonationSession,
+}
+
+const SESSION_QUERY: &str = r#"
+    SELECT s.id, s.admin_id, a.full_name AS admin_name, s.user_id, u.full_name AS user_name,
+           s.reason, s.started_at, s.expires_at, s.ended_at, s.notified_at,
+           COALESCE(r.requests, 0) AS requests, COALESCE(r.changes, 0) AS changes
+    FROM impersonation_sessions s
+    LEFT JOIN users a ON a.id = s.admin_id
+    LEFT JOIN users u ON u.id = s.user_id
+    LEFT JOIN LATERAL (
+        SELECT COUNT(*) AS requests,
+               COUNT(*) FILTER (WHERE l.metadata->>'method' NOT IN ('GET', 'HEAD', 'OPTIONS')) AS changes
+        FROM audit_logs l
+        WHERE l.action = 'impersonation.request'
+          AND l.target_type = 'impersonation'
+          AND l.target_id = s.id::text
+          AND l.created_at >= s.started_at
+    ) r ON true
+"#;
+
+async fn fetch(db_pool: &PgPool, session_id: i32) -> AppResult<ImpersonationSession> {
+    sqlx::query_as::<_, ImpersonationSession>(&format!("{} WHERE s.id = $1", SESSION_QUERY))
+        .bind(session_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Starts a session letting `admin_id` act as `user_id` for `minutes`
+/// (default [`DEFAULT_MINUTES`]). The admin's other open sessions on the
+/// same user are ended.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user does not exist,
+/// `AppError::ValidationError` for a missing reason, a session length out of
+/// range, or an attempt to impersonate oneself, `AppError::Forbidden` for an
+/// admin target, or an error if the database operation fails.
+pub async fn start(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    user_id: Uuid,
+    reason: &str,
+    minutes: Option<i64>,
+) -> AppResult<StartedImpersonation> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::ValidationError("A reason is required".to_string()));
+    }
+    let minutes = minutes.unwrap_or(DEFAULT_MINUTES);
+    if !(1..=MAX_MINUTES).contains(&minutes) {
+        return Err(AppError::ValidationError(format!(
+            "Sessions last between 1 and {} minutes",
+            MAX_MINUTES
+        )));
+    }
+    if user_id == admin_id {
+        return Err(AppError::ValidationError("You cannot impersonate yourself".to_string()));
+    }
+
+    let (email, role) = sqlx::query_as::<_, (String, UserRole)>("SELECT email, role FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if role == UserRole::Admin {
+        return Err(AppError::Forbidden);
+    }
+
+    let expires_at = Utc::now() + Duration::minutes(minutes);
+    let mut tx = db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE impersonation_sessions SET ended_at = NOW()
+        WHERE admin_id = $1 AND user_id = $2 AND ended_at IS NULL AND expires_at > NOW()
+        "#,
+    )
+    .bind(admin_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    let session_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO impersonation_sessions (admin_id, user_id, reason, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(admin_id)
+    .bind(user_id)
+    .bind(reason)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, action, target_type, target_id, metadata)
+        VALUES ($1, 'impersonation.start', 'user', $2, $3)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(user_id.to_string())
+    .bind(json!({ "session_id": session_id, "reason": reason, "expires_at": expires_at }))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let token = auth::create_impersonation_jwt(user_id, email, admin_id, session_id, expires_at)?;
+    info!(
+        "Admin {} started impersonating user {} (session {}, {} minutes)",
+        admin_id, user_id, session_id, minutes
+    );
+    Ok(StartedImpersonation {
+        token,
+        session: fetch(db_pool, session_id).await?,
+    })
+}
+
+/// Ends an open session at once and notifies its user.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if there is no such open session, or an
+/// error if the database operation fails.
+pub async fn end(db_pool: &PgPool, admin_id: Uuid, session_id: i32) -> AppResult<ImpersonationSession> {
+    let ended = sqlx::query(
+        "UPDATE impersonation_sessions SET ended_at = NOW() WHERE id = $1 AND ended_at IS NULL AND expires_at > NOW()",
+    )
+    .bind(session_id)
+    .execute(db_pool)
+    .await?;
+    if ended.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, action, target_type, target_id, metadata)
+        SELECT $1, 'impersonation.end', 'user', user_id::text, jsonb_build_object('session_id', id)
+        FROM impersonation_sessions WHERE id = $2
+        "#,
+    )
+    .bind(admin_id)
+    .bind(session_id)
+    .execute(db_pool)
+    .await?;
+    info!("Admin {} ended impersonation session {}", admin_id, session_id);
+
+    notify_finished(db_pool).await?;
+    fetch(db_pool, session_id).await
+}
+
+/// Sessions newest first, optionally only those on one user.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Option<Uuid>, limit: i64) -> AppResult<Vec<ImpersonationSession>> {
+    let sessions = sqlx::query_as::<_, ImpersonationSession>(&format!(
+        "{} WHERE ($1::uuid IS NULL OR s.user_id = $1) ORDER BY s.started_at DESC LIMIT $2",
+        SESSION_QUERY
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(sessions)
+}
+
+/// Notifies users whose impersonation sessions ended or expired and who
+/// have not been told yet. Returns how many were notified.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn notify_finished(db_pool: &PgPool) -> AppResult<u64> {
+    let sessions = sqlx::query_as::<_, ImpersonationSession>(&format!(
+        r#"
+        {} WHERE s.notified_at IS NULL AND (s.ended_at IS NOT NULL OR s.expires_at <= NOW())
+        ORDER BY s.started_at
+        LIMIT $1
+        "#,
+        SESSION_QUERY
+    ))
+    .bind(NOTIFY_BATCH)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut notified = 0;
+    for session in sessions {
+        let finished_at = session.ended_at.unwrap_or(session.expires_at);
+        let message = format!(
+            "A CareerBridge support admin accessed your account from {} to {} UTC to investigate: {}. \
+             {} requests were made, {} of which changed data. Contact support if you have questions.",
+            session.started_at.format("%Y-%m-%d %H:%M"),
+            finished_at.format("%Y-%m-%d %H:%M"),
+            session.reason,
+            session.requests,
+            session.changes,
+        );
+
+        let mut tx = db_pool.begin().await?;
+        let claimed = sqlx::query(
+            "UPDATE impersonation_sessions SET notified_at = NOW() WHERE id = $1 AND notified_at IS NULL",
+        )
+        .bind(session.id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            continue;
+        }
+        sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'impersonation')")
+            .bind(session.user_id)
+            .bind("Support accessed your account")
+            .bind(&message)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        notified += 1;
+    }
+
+    if notified > 0 {
+        info!("Notified {} users of finished impersonation sessions", notified);
+    }
+    Ok(notified)
+}
+
+/// Whether a request may not be made while impersonating.
+fn is_destructive(method: &Method, path: &str) -> bool {
+    if *method == Method::DELETE {
+        return true;
+    }
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return false;
+    }
+    path.ends_with("/invite") || BLOCKED_WRITE_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Middleware checking, restricting, and auditing requests made with
+/// impersonation tokens. Other requests pass through untouched.
+pub async fn guard(
+    State(app_state): State<AppState>,
+    auth_user: Option<AuthUser>,
+    request: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let Some((user_id, impersonation)) = auth_user.and_then(|u| u.impersonation.map(|i| (u.user_id, i))) else {
+        return Ok(next.run(request).await);
+    };
+
+    let open = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM impersonation_sessions s
+            JOIN users a ON a.id = s.admin_id AND a.role = 'admin'
+            WHERE s.id = $1 AND s.admin_id = $2 AND s.user_id = $3
+              AND s.ended_at IS NULL AND s.expires_at > NOW()
+        )
+        "#,
+    )
+    .bind(impersonation.session_id)
+    .bind(impersonation.admin_id)
+    .bind(user_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
+    if !open {
+        return Err(AppError::Unauthorized);
+    }
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    if is_destructive(&method, &path) {
+        warn!(
+            "Impersonation session {} refused {} {}",
+            impersonation.session_id, method, path
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let mut response = IMPERSONATOR.scope(impersonation.admin_id, next.run(request)).await;
+
+    let logged = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, action, target_type, target_id, metadata, impersonated_by)
+        VALUES ($1, 'impersonation.request', 'impersonation', $2, $3, $4)
+        "#,
+    )
+    .bind(user_id)
+    .bind(impersonation.session_id.to_string())
+    .bind(json!({ "method": method.as_str(), "path": path, "status": response.status().as_u16() }))
+    .bind(impersonation.admin_id)
+    .execute(&app_state.db_pool)
+    .await;
+    if let Err(e) = logged {
+        warn!("Could not audit impersonated request {} {}: {}", method, path, e);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&impersonation.admin_id.to_string()) {
+        response.headers_mut().insert("x-impersonated-by", value);
+    }
+    Ok(response)
+}
@@ -0,0 +1,138 @@
+//! Exclusionary language linting for job postings.
+//!
+//! [`lint`] flags phrasing that discourages or excludes qualified
+//! candidates: gendered wording, age, appearance, marital status, and
+//! nationality requirements, and jargon like "rockstar" that reads as
+//! coded. Matching is on whole words, case-insensitively. Flags are advice
+//! for the employer; nothing is blocked.
+//!
+//! ```
+//! use backend::inclusive_language::{lint, BiasCategory};
+//!
+//! let flags = lint("requirements", "Young, energetic rockstar. He must be a native English speaker.");
+//! let phrases: Vec<_> = flags.iter().map(|f| f.phrase.as_str()).collect();
+//! assert_eq!(phrases, ["young", "energetic", "rockstar", "he", "native english speaker"]);
+//! assert_eq!(flags[0].category, BiasCategory::Age);
+//!
+//! // Inclusive pronoun pairs are fine
+//! assert!(lint("job_description", "He or she will own the API.").is_empty());
+//! ```
+
+use serde::Serialize;
+
+/// Kinds of exclusionary language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BiasCategory {
+    Gender,
+    Age,
+    Appearance,
+    MaritalStatus,
+    Nationality,
+    Disability,
+    /// Jargon that reads as coded for a narrow group
+    Jargon,
+}
+
+/// One flagged phrase.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageFlag {
+    /// The posting field the phrase is in
+    pub field: String,
+    /// The phrase as listed in the rules (lowercase)
+    pub phrase: String,
+    pub category: BiasCategory,
+    pub suggestion: &'static str,
+}
+
+/// Phrase (lowercase words), category, suggestion.
+const RULES: &[(&str, BiasCategory, &str)] = &[
+    ("only male", BiasCategory::Gender, "Open the role to all genders"),
+    ("only female", BiasCategory::Gender, "Open the role to all genders"),
+    ("male candidates", BiasCategory::Gender, "Say \"candidates\""),
+    ("female candidates", BiasCategory::Gender, "Say \"candidates\""),
+    ("he", BiasCategory::Gender, "Use \"you\" or \"they\""),
+    ("him", BiasCategory::Gender, "Use \"you\" or \"them\""),
+    ("his", BiasCategory::Gender, "Use \"your\" or \"their\""),
+    ("manpower", BiasCategory::Gender, "Say \"staff\" or \"workforce\""),
+    ("salesman", BiasCategory::Gender, "Say \"salesperson\""),
+    ("chairman", BiasCategory::Gender, "Say \"chair\""),
+    ("aggressive", BiasCategory::Gender, "Say \"ambitious\" or \"driven\""),
+    ("dominant", BiasCategory::Gender, "Say \"leading\""),
+    ("young", BiasCategory::Age, "Describe the skills needed, not age"),
+    ("age limit", BiasCategory::Age, "Remove the age requirement"),
+    ("years old", BiasCategory::Age, "Remove the age requirement"),
+    ("digital native", BiasCategory::Age, "Say \"comfortable with digital tools\""),
+    ("fresh blood", BiasCategory::Age, "Describe the skills needed, not age"),
+    ("energetic", BiasCategory::Age, "Say \"motivated\""),
+    ("attractive", BiasCategory::Appearance, "Remove appearance requirements"),
+    ("good looking", BiasCategory::Appearance, "Remove appearance requirements"),
+    ("fair complexion", BiasCategory::Appearance, "Remove appearance requirements"),
+    ("height", BiasCategory::Appearance, "Only keep if the job truly requires it"),
+    ("unmarried", BiasCategory::MaritalStatus, "Remove marital status requirements"),
+    ("married", BiasCategory::MaritalStatus, "Remove marital status requirements"),
+    ("no family commitments", BiasCategory::MaritalStatus, "Describe the schedule instead"),
+    ("native english speaker", BiasCategory::Nationality, "Say \"fluent in English\""),
+    ("native speaker", BiasCategory::Nationality, "Say \"fluent in\" the language"),
+    ("culture fit", BiasCategory::Jargon, "Name the values or working style you mean"),
+    ("able bodied", BiasCategory::Disability, "Describe the physical tasks, if essential"),
+    ("rockstar", BiasCategory::Jargon, "Say what the person will do"),
+    ("ninja", BiasCategory::Jargon, "Say what the person will do"),
+    ("guru", BiasCategory::Jargon, "Say \"expert\""),
+    ("work hard play hard", BiasCategory::Jargon, "Describe the actual schedule and culture"),
+];
+
+/// Pronouns that make a gendered pronoun next to them inclusive ("he or she").
+const INCLUSIVE_PAIRS: &[&str] = &["she", "her", "hers", "they", "them", "their"];
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Flags exclusionary phrases in one field of a posting, in order of first
+/// appearance; each phrase is flagged once per field.
+pub fn lint(field: &str, text: &str) -> Vec<LanguageFlag> {
+    let words = words(text);
+    let mut found: Vec<(usize, &(&str, BiasCategory, &str))> = Vec::new();
+    for rule in RULES {
+        let phrase: Vec<&str> = rule.0.split(' ').collect();
+        let position = words.windows(phrase.len()).enumerate().find_map(|(index, window)| {
+            (window.iter().zip(&phrase).all(|(word, p)| word == p) && !inclusive_pair(&words, index, phrase.len()))
+                .then_some(index)
+        });
+        if let Some(position) = position {
+            found.push((position, rule));
+        }
+    }
+    found.sort_by_key(|(position, _)| *position);
+    found
+        .into_iter()
+        .map(|(_, (phrase, category, suggestion))| LanguageFlag {
+            field: field.to_string(),
+            phrase: phrase.to_string(),
+            category: *category,
+            suggestion,
+        })
+        .collect()
+}
+
+/// Whether a single gendered pronoun is paired with another ("he or she",
+/// "he/she", "his or her").
+fn inclusive_pair(words: &[String], index: usize, len: usize) -> bool {
+    if len != 1 || !matches!(words[index].as_str(), "he" | "him" | "his") {
+        return false;
+    }
+    let near = |i: Option<usize>| i.and_then(|i| words.get(i)).is_some_and(|w| INCLUSIVE_PAIRS.contains(&w.as_str()));
+    near(index.checked_sub(1))
+        || near(index.checked_sub(2))
+        || near(Some(index + 1))
+        || near(Some(index + 2))
+}
+
+/// Lints every field of a posting.
+pub fn lint_posting<'a>(fields: impl IntoIterator<Item = (&'a str, &'a str)>) -> Vec<LanguageFlag> {
+    fields.into_iter().flat_map(|(field, text)| lint(field, text)).collect()
+}
@@ -0,0 +1,358 @@
+//! Calendar free/busy.
+//!
+//! Mentors' busy times are read from Google Calendar's free/busy API, with a
+//! refresh token from the mentor's consent to the `calendar.freebusy` scope,
+//! or from any CalDAV server through a `free-busy-query` report (RFC 4791).
+//! Only busy intervals are read; event titles and attendees never leave the
+//! calendar.
+//!
+//! Google consent uses the OAuth client of Google sign-in
+//! (`GOOGLE_CLIENT_ID`, `GOOGLE_CLIENT_SECRET`) with its own redirect URI,
+//! `GOOGLE_CALENDAR_REDIRECT_URI`. The client app runs the consent screen
+//! and hands the authorization code to [`CalendarClient::exchange_google_code`].
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use reqwest::{Client, Method, StatusCode};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Google's OAuth token endpoint.
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+/// Google Calendar's free/busy endpoint.
+const GOOGLE_FREEBUSY_URL: &str = "https://www.googleapis.com/calendar/v3/freeBusy";
+
+/// Date-time format of iCalendar UTC values.
+const ICAL_UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A span of time the calendar is busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BusyPeriod {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+impl BusyPeriod {
+    /// Whether the period overlaps `[starts_at, ends_at)`.
+    pub fn overlaps(&self, starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> bool {
+        self.starts_at < ends_at && starts_at < self.ends_at
+    }
+}
+
+/// Where a calendar is read from, with its decrypted credential.
+#[derive(Debug, Clone, Copy)]
+pub enum CalendarSource<'a> {
+    Google { calendar_id: &'a str, refresh_token: &'a str },
+    Caldav { url: &'a str, username: &'a str, password: &'a str },
+}
+
+/// What a calendar said about a time range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FreeBusyOutcome {
+    /// Busy periods in the range
+    Fetched(Vec<BusyPeriod>),
+    /// The calendar rejected the credential; it has to be connected again
+    Unauthorized(String),
+    /// No usable answer: timeout, connection error, or server error
+    Unavailable(String),
+}
+
+#[derive(Clone)]
+struct GoogleOAuth {
+    client_id: String,
+    client_secret: String,
+    redirect_uri: Option<String>,
+}
+
+/// HTTP client for calendar free/busy lookups.
+#[derive(Clone)]
+pub struct CalendarClient {
+    client: Client,
+    google: Option<GoogleOAuth>,
+}
+
+impl CalendarClient {
+    /// Creates a client with a 10 second timeout per request. Google
+    /// calendars are available when `GOOGLE_CLIENT_ID` and
+    /// `GOOGLE_CLIENT_SECRET` are set.
+    pub fn from_env() -> Self {
+        let google = match (std::env::var("GOOGLE_CLIENT_ID"), std::env::var("GOOGLE_CLIENT_SECRET")) {
+            (Ok(client_id), Ok(client_secret)) => Some(GoogleOAuth {
+                client_id,
+                client_secret,
+                redirect_uri: std::env::var("GOOGLE_CALENDAR_REDIRECT_URI").ok(),
+            }),
+            _ => None,
+        };
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            google,
+        }
+    }
+
+    /// Exchanges an authorization code from Google's consent screen for a
+    /// refresh token.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if Google sign-in or `GOOGLE_CALENDAR_REDIRECT_URI`
+    /// is not configured, or Google rejects the code.
+    pub async fn exchange_google_code(&self, code: &str) -> Result<String, String> {
+        let google = self.google.as_ref().ok_or("Google Calendar is not configured")?;
+        let redirect_uri = google
+            .redirect_uri
+            .as_deref()
+            .ok_or("GOOGLE_CALENDAR_REDIRECT_URI is not configured")?;
+        let body = self
+            .token_request(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("client_id", &google.client_id),
+                ("client_secret", &google.client_secret),
+                ("redirect_uri", redirect_uri),
+            ])
+            .await?;
+        body.get("refresh_token")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| "Google granted no refresh token; request offline access".to_string())
+    }
+
+    /// Reads the busy periods of a calendar between `from` and `to`.
+    pub async fn free_busy(&self, source: CalendarSource<'_>, from: DateTime<Utc>, to: DateTime<Utc>) -> FreeBusyOutcome {
+        match source {
+            CalendarSource::Google {
+                calendar_id,
+                refresh_token,
+            } => self.google_free_busy(calendar_id, refresh_token, from, to).await,
+            CalendarSource::Caldav { url, username, password } => {
+                self.caldav_free_busy(url, username, password, from, to).await
+            }
+        }
+    }
+
+    async fn token_request(&self, form: &[(&str, &str)]) -> Result<Value, String> {
+        let response = self
+            .client
+            .post(GOOGLE_TOKEN_URL)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Google token response: {}", e))?;
+        if !status.is_success() {
+            let reason = body.get("error").and_then(Value::as_str).unwrap_or("unknown error");
+            return Err(format!("Google token request failed: {}", reason));
+        }
+        Ok(body)
+    }
+
+    async fn google_free_busy(
+        &self,
+        calendar_id: &str,
+        refresh_token: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> FreeBusyOutcome {
+        let Some(google) = &self.google else {
+            return FreeBusyOutcome::Unavailable("Google Calendar is not configured".to_string());
+        };
+        let token = match self
+            .token_request(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", &google.client_id),
+                ("client_secret", &google.client_secret),
+            ])
+            .await
+        {
+            Ok(body) => body.get("access_token").and_then(Value::as_str).map(str::to_string),
+            // A revoked or expired grant will not come back
+            Err(e) if e.contains("invalid_grant") => return FreeBusyOutcome::Unauthorized(e),
+            Err(e) => return FreeBusyOutcome::Unavailable(e),
+        };
+        let Some(token) = token else {
+            return FreeBusyOutcome::Unavailable("Google token response has no access token".to_string());
+        };
+
+        let response = match self
+            .client
+            .post(GOOGLE_FREEBUSY_URL)
+            .bearer_auth(token)
+            .json(&json!({
+                "timeMin": from.to_rfc3339(),
+                "timeMax": to.to_rfc3339(),
+                "items": [{ "id": calendar_id }],
+            }))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return FreeBusyOutcome::Unavailable(e.to_string()),
+        };
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return FreeBusyOutcome::Unauthorized(format!("Google Calendar returned HTTP {}", response.status()));
+            }
+            status if !status.is_success() => {
+                return FreeBusyOutcome::Unavailable(format!("Google Calendar returned HTTP {}", status));
+            }
+            _ => {}
+        }
+        let body: Value = match response.json().await {
+            Ok(body) => body,
+            Err(e) => return FreeBusyOutcome::Unavailable(format!("Invalid Google Calendar response: {}", e)),
+        };
+        let Some(calendar) = body.get("calendars").and_then(|c| c.get(calendar_id)) else {
+            return FreeBusyOutcome::Unavailable("Google Calendar response has no calendar".to_string());
+        };
+        // Errors are per calendar, e.g. notFound for an ID the token cannot see
+        if let Some(reason) = calendar.pointer("/errors/0/reason").and_then(Value::as_str) {
+            return FreeBusyOutcome::Unauthorized(format!("Google Calendar: {}", reason));
+        }
+
+        let busy = calendar
+            .get("busy")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|period| {
+                let start = period.get("start").and_then(Value::as_str)?;
+                let end = period.get("end").and_then(Value::as_str)?;
+                Some(BusyPeriod {
+                    starts_at: DateTime::parse_from_rfc3339(start).ok()?.with_timezone(&Utc),
+                    ends_at: DateTime::parse_from_rfc3339(end).ok()?.with_timezone(&Utc),
+                })
+            })
+            .collect();
+        FreeBusyOutcome::Fetched(busy)
+    }
+
+    async fn caldav_free_busy(
+        &self,
+        url: &str,
+        username: &str,
+        password: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> FreeBusyOutcome {
+        let query = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <C:time-range start="{}" end="{}"/>
+</C:free-busy-query>"#,
+            from.format(ICAL_UTC_FORMAT),
+            to.format(ICAL_UTC_FORMAT)
+        );
+        let method = Method::from_bytes(b"REPORT").expect("REPORT is a valid method");
+        let response = match self
+            .client
+            .request(method, url)
+            .basic_auth(username, Some(password))
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(query)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return FreeBusyOutcome::Unavailable(e.to_string()),
+        };
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND => {
+                return FreeBusyOutcome::Unauthorized(format!("CalDAV server returned HTTP {}", response.status()));
+            }
+            status if !status.is_success() => {
+                return FreeBusyOutcome::Unavailable(format!("CalDAV server returned HTTP {}", status));
+            }
+            _ => {}
+        }
+        match response.text().await {
+            Ok(body) => FreeBusyOutcome::Fetched(parse_freebusy(&body)),
+            Err(e) => FreeBusyOutcome::Unavailable(format!("Invalid CalDAV response: {}", e)),
+        }
+    }
+}
+
+/// Reads the busy periods of a `VFREEBUSY` component. `FREE` periods are
+/// skipped; tentative and unavailable ones count as busy.
+pub fn parse_freebusy(ical: &str) -> Vec<BusyPeriod> {
+    // Long lines are folded onto following lines that start with whitespace
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.trim_end_matches('\r').to_string()),
+        }
+    }
+
+    let mut busy = Vec::new();
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = name.split(';');
+        if !params.next().is_some_and(|n| n.eq_ignore_ascii_case("FREEBUSY")) {
+            continue;
+        }
+        if params.any(|p| p.eq_ignore_ascii_case("FBTYPE=FREE")) {
+            continue;
+        }
+        busy.extend(value.split(',').filter_map(parse_period));
+    }
+    busy
+}
+
+/// Parses an iCalendar period: `start/end` or `start/duration`.
+fn parse_period(period: &str) -> Option<BusyPeriod> {
+    let (start, end) = period.trim().split_once('/')?;
+    let starts_at = parse_utc(start)?;
+    let ends_at = if end.starts_with('P') {
+        starts_at + parse_duration(end)?
+    } else {
+        parse_utc(end)?
+    };
+    (ends_at > starts_at).then_some(BusyPeriod { starts_at, ends_at })
+}
+
+fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, ICAL_UTC_FORMAT)
+        .ok()
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+/// Parses an iCalendar duration such as `PT1H30M`, `P1D`, or `P2W`.
+fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                seconds += n * match (unit, in_time) {
+                    ('W', false) => 604_800,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3_600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then(|| chrono::Duration::seconds(seconds))
+}
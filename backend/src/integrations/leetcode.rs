@@ -0,0 +1,224 @@
+//! LeetCode progress.
+//!
+//! A linked handle's progress is read from LeetCode's public GraphQL API,
+//! the same one its profile pages use: accepted submissions by difficulty
+//! and solved problems by topic tag. Users who keep their profile private,
+//! or track progress elsewhere, can upload a CSV export instead (see
+//! [`parse_export`]).
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::{FetchOutcome, PracticeProgress};
+
+/// LeetCode's GraphQL endpoint.
+const GRAPHQL_URL: &str = "https://leetcode.com/graphql";
+
+const PROGRESS_QUERY: &str = r#"
+query userProgress($username: String!) {
+  matchedUser(username: $username) {
+    submitStatsGlobal { acSubmissionNum { difficulty count } }
+    tagProblemCounts {
+      advanced { tagName problemsSolved }
+      intermediate { tagName problemsSolved }
+      fundamental { tagName problemsSolved }
+    }
+  }
+}
+"#;
+
+/// Rows read from an export.
+const MAX_EXPORT_ROWS: usize = 10_000;
+
+/// Longest topic name kept.
+const MAX_TOPIC_CHARS: usize = 100;
+
+/// HTTP client for the LeetCode API.
+#[derive(Clone)]
+pub struct LeetCodeClient {
+    client: Client,
+}
+
+impl Default for LeetCodeClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LeetCodeClient {
+    /// Create a client with a 10 second timeout per request
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Reads a handle's solved problems.
+    pub async fn fetch(&self, handle: &str) -> FetchOutcome {
+        let response = match self
+            .client
+            .post(GRAPHQL_URL)
+            .header("Referer", "https://leetcode.com")
+            .json(&json!({ "query": PROGRESS_QUERY, "variables": { "username": handle } }))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return FetchOutcome::Unavailable(e.to_string()),
+        };
+        if !response.status().is_success() {
+            return FetchOutcome::Unavailable(format!("LeetCode returned HTTP {}", response.status()));
+        }
+
+        let body: Value = match response.json().await {
+            Ok(body) => body,
+            Err(e) => return FetchOutcome::Unavailable(format!("Invalid LeetCode response: {}", e)),
+        };
+        // An unknown handle comes back as a null user, usually with an error
+        let user = match body.pointer("/data/matchedUser") {
+            Some(Value::Null) => return FetchOutcome::UnknownHandle,
+            Some(user) => user,
+            None => return FetchOutcome::Unavailable("LeetCode response has no user data".to_string()),
+        };
+
+        let mut progress = PracticeProgress::default();
+        for entry in user
+            .pointer("/submitStatsGlobal/acSubmissionNum")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let count = entry.get("count").and_then(Value::as_i64).unwrap_or(0) as i32;
+            match entry.get("difficulty").and_then(Value::as_str) {
+                Some("Easy") => progress.easy_solved = count,
+                Some("Medium") => progress.medium_solved = count,
+                Some("Hard") => progress.hard_solved = count,
+                _ => {}
+            }
+        }
+        for level in ["advanced", "intermediate", "fundamental"] {
+            for tag in user
+                .pointer(&format!("/tagProblemCounts/{}", level))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let solved = tag.get("problemsSolved").and_then(Value::as_i64).unwrap_or(0) as i32;
+                if let Some(name) = tag.get("tagName").and_then(Value::as_str)
+                    && solved > 0
+                {
+                    progress.topics.insert(topic_name(name), solved);
+                }
+            }
+        }
+        FetchOutcome::Fetched(progress)
+    }
+}
+
+/// Whether a handle is one LeetCode could have issued.
+pub fn valid_handle(handle: &str) -> bool {
+    (1..=50).contains(&handle.len())
+        && handle
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Reads a progress export: a CSV with a header row and one row per
+/// problem, as produced by common LeetCode export extensions.
+///
+/// A `difficulty` column is required. Topics are read from a `topics` or
+/// `tags` column, separated by `;`, `|`, or `,`. When there is a `status`
+/// column, only accepted or solved rows count; when there is a `title` or
+/// `slug` column, a problem listed twice counts once.
+///
+/// # Errors
+///
+/// Returns a message for the user if the export has no header, no
+/// `difficulty` column, or no solved problems.
+pub fn parse_export(csv: &str) -> Result<PracticeProgress, String> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .map(|line| csv_fields(line.trim_start_matches('\u{feff}')))
+        .ok_or("The export is empty")?
+        .into_iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let difficulty_column = column(&["difficulty", "level"]).ok_or("The export has no difficulty column")?;
+    let topics_column = column(&["topics", "tags", "topic tags", "topic_tags"]);
+    let status_column = column(&["status", "result"]);
+    let title_column = column(&["title", "slug", "title_slug", "problem", "question"]);
+
+    let mut progress = PracticeProgress::default();
+    let mut seen = HashSet::new();
+    let mut topics: BTreeMap<String, i32> = BTreeMap::new();
+    for line in lines.take(MAX_EXPORT_ROWS) {
+        let fields = csv_fields(line);
+        let field = |index: Option<usize>| index.and_then(|i| fields.get(i)).map(String::as_str);
+
+        if let Some(status) = field(status_column)
+            && !matches!(status.to_lowercase().as_str(), "ac" | "accepted" | "solved")
+        {
+            continue;
+        }
+        if let Some(title) = field(title_column).filter(|t| !t.is_empty())
+            && !seen.insert(title.to_lowercase())
+        {
+            continue;
+        }
+        match field(Some(difficulty_column)).map(str::to_lowercase).as_deref() {
+            Some("easy") => progress.easy_solved += 1,
+            Some("medium") => progress.medium_solved += 1,
+            Some("hard") => progress.hard_solved += 1,
+            _ => continue,
+        }
+        for topic in field(topics_column)
+            .unwrap_or_default()
+            .split([';', '|', ','])
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            *topics.entry(topic_name(topic)).or_default() += 1;
+        }
+    }
+
+    if progress.easy_solved + progress.medium_solved + progress.hard_solved == 0 {
+        return Err("The export has no solved problems".to_string());
+    }
+    progress.topics = topics;
+    Ok(progress)
+}
+
+fn topic_name(name: &str) -> String {
+    name.trim().chars().take(MAX_TOPIC_CHARS).collect()
+}
+
+/// Splits a CSV line into its fields, handling quoted fields and `""`
+/// escapes.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
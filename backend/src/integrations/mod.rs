@@ -0,0 +1,33 @@
+//! Clients for outside platforms that user activity is imported from.
+//!
+//! - [`leetcode`] - Solved LeetCode problems, from the public API or a progress export
+//! - [`calendar`] - Busy times from mentors' Google or CalDAV calendars
+//!
+//! Storing and scoring the imported progress is up to [`crate::code_practice`];
+//! busy times are applied to session slots by [`crate::mentor_calendars`].
+
+use std::collections::BTreeMap;
+
+pub mod calendar;
+pub mod leetcode;
+
+/// Solved-problem counts on a coding practice platform.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PracticeProgress {
+    pub easy_solved: i32,
+    pub medium_solved: i32,
+    pub hard_solved: i32,
+    /// Solved problems by topic; a problem counts toward each of its topics
+    pub topics: BTreeMap<String, i32>,
+}
+
+/// What a platform said about a handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchOutcome {
+    /// The handle's current progress
+    Fetched(PracticeProgress),
+    /// The platform has no user with this handle
+    UnknownHandle,
+    /// No usable answer: timeout, connection error, or server error
+    Unavailable(String),
+}
@@ -0,0 +1,48 @@
+//! Academic terms for internship postings and student availability.
+//!
+//! Terms are written `YYYY-season` with seasons `spring` (January-May),
+//! `summer` (June-August), and `fall` (September-December). The database
+//! maps a term to its date range with `academic_term_range()`, which job
+//! matching uses to check that an internship's term dates overlap one of the
+//! student's available terms.
+
+use crate::errors::{AppError, AppResult};
+
+/// Seasons a term can name.
+pub const SEASONS: &[&str] = &["spring", "summer", "fall"];
+
+/// Normalizes an academic term such as `"Fall 2026"` or `"2026-fall"` to
+/// the stored `2026-fall` form.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the term cannot be parsed.
+pub fn normalize_term(term: &str) -> AppResult<String> {
+    let lower = term.trim().to_lowercase();
+    let parts: Vec<&str> = lower
+        .split(['-', ' ', '_'])
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let (year, season) = match parts.as_slice() {
+        [a, b] if a.parse::<i32>().is_ok() => (*a, *b),
+        [a, b] if b.parse::<i32>().is_ok() => (*b, *a),
+        _ => return Err(invalid_term(term)),
+    };
+    let year = year.parse::<i32>().map_err(|_| invalid_term(term))?;
+    let season = if season == "autumn" { "fall" } else { season };
+
+    if (2000..=2100).contains(&year) && SEASONS.contains(&season) {
+        Ok(format!("{}-{}", year, season))
+    } else {
+        Err(invalid_term(term))
+    }
+}
+
+fn invalid_term(term: &str) -> AppError {
+    AppError::ValidationError(format!(
+        "'{}' is not an academic term; use e.g. '2026-fall' (seasons: {})",
+        term,
+        SEASONS.join(", ")
+    ))
+}
@@ -0,0 +1,496 @@
+//! Interview experiences shared by candidates.
+//!
+//! A candidate describes how a company's interviews for a role went: the
+//! rounds, the questions asked, how hard it was, and the outcome. Posts are
+//! anonymous to readers, and email addresses and phone numbers are redacted
+//! when they are saved. Every new or edited post waits for a moderator
+//! before it is published; published posts are searchable on the company
+//! and summarized by outcome, rounds, and difficulty.
+//!
+//! [`grounding`] turns a company's published posts into context for the
+//! interview questions generated in application packages, so candidates
+//! prepare for the questions the company actually asks.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{InterviewOutcome, ModerationStatus};
+
+/// Published posts used to ground generated interview questions.
+const MAX_GROUNDING_POSTS: i64 = 5;
+
+/// Questions taken from each post when grounding.
+const MAX_GROUNDING_QUESTIONS: usize = 8;
+
+/// Shortest run of digits treated as a phone number.
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// An interview experience post.
+#[derive(Debug, Serialize, FromRow)]
+pub struct InterviewExperience {
+    pub id: i32,
+    pub company_id: i32,
+    pub company_name: String,
+    pub role: String,
+    pub interviewed_on: Option<NaiveDate>,
+    pub rounds: i16,
+    /// One line per round
+    pub round_details: Vec<String>,
+    pub questions: Vec<String>,
+    pub summary: String,
+    /// 1 (easy) to 5 (very hard)
+    pub difficulty: Option<i16>,
+    pub outcome: InterviewOutcome,
+    pub moderation_status: ModerationStatus,
+    /// Why the post was rejected
+    pub moderation_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Aggregates of a company's published posts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExperienceSummary {
+    pub experiences: i64,
+    pub average_rounds: Option<f64>,
+    pub average_difficulty: Option<f64>,
+    /// Posts by outcome
+    pub outcomes: BTreeMap<String, i64>,
+}
+
+/// A company's published posts matching a search, with the summary of all
+/// of its published posts.
+#[derive(Debug, Serialize)]
+pub struct CompanyExperiences {
+    pub summary: ExperienceSummary,
+    pub experiences: Vec<InterviewExperience>,
+}
+
+/// A post in the moderation queue, with its author.
+#[derive(Debug, Serialize, FromRow)]
+pub struct QueuedExperience {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub experience: InterviewExperience,
+    pub author_id: Uuid,
+    pub author_email: String,
+}
+
+/// What the candidate writes in a post.
+#[derive(Debug)]
+pub struct ExperienceDraft<'a> {
+    pub role: &'a str,
+    pub interviewed_on: Option<NaiveDate>,
+    pub rounds: i16,
+    pub round_details: &'a [String],
+    pub questions: &'a [String],
+    pub summary: &'a str,
+    pub difficulty: Option<i16>,
+    pub outcome: InterviewOutcome,
+}
+
+/// Filters for searching a company's published posts.
+#[derive(Debug, Default)]
+pub struct ExperienceSearch<'a> {
+    /// Matched against the role, summary, rounds, and questions
+    pub query: Option<&'a str>,
+    /// Matched against the role
+    pub role: Option<&'a str>,
+    pub outcome: Option<InterviewOutcome>,
+}
+
+const EXPERIENCE_COLUMNS: &str = r#"
+    e.id, e.company_id, c.name AS company_name, e.role, e.interviewed_on, e.rounds,
+    e.round_details, e.questions, e.summary, e.difficulty, e.outcome,
+    e.moderation_status, e.moderation_reason, e.created_at, e.updated_at
+"#;
+
+/// Replaces email addresses and phone numbers with `[redacted]`.
+fn anonymize(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|word| {
+                    let digits = word.chars().filter(char::is_ascii_digit).count();
+                    let is_email = word.contains('@') && word.contains('.');
+                    let is_phone = digits >= MIN_PHONE_DIGITS
+                        && word.chars().all(|c| c.is_ascii_digit() || "+-().".contains(c));
+                    if is_email || is_phone { "[redacted]" } else { word }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Trims and anonymizes list items, dropping empty ones.
+fn clean_list(items: &[String]) -> Vec<String> {
+    items.iter().map(|i| anonymize(i)).filter(|i| !i.is_empty()).collect()
+}
+
+/// Escapes `%`, `_`, and `\` and wraps the text for an `ILIKE` match.
+fn like_pattern(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("%{}%", t.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")))
+}
+
+async fn fetch(db_pool: &PgPool, experience_id: i32) -> AppResult<InterviewExperience> {
+    sqlx::query_as::<_, InterviewExperience>(&format!(
+        "SELECT {} FROM interview_experiences e JOIN companies c ON c.id = e.company_id WHERE e.id = $1",
+        EXPERIENCE_COLUMNS
+    ))
+    .bind(experience_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Submits a post for moderation.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the company does not exist, or an error
+/// if the database operation fails.
+pub async fn submit(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    company_id: i32,
+    draft: &ExperienceDraft<'_>,
+) -> AppResult<InterviewExperience> {
+    sqlx::query_scalar::<_, i32>("SELECT id FROM companies WHERE id = $1")
+        .bind(company_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let experience_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO interview_experiences
+            (company_id, user_id, role, interviewed_on, rounds, round_details, questions, summary, difficulty, outcome)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id
+        "#,
+    )
+    .bind(company_id)
+    .bind(user_id)
+    .bind(draft.role.trim())
+    .bind(draft.interviewed_on)
+    .bind(draft.rounds)
+    .bind(clean_list(draft.round_details))
+    .bind(clean_list(draft.questions))
+    .bind(anonymize(draft.summary))
+    .bind(draft.difficulty)
+    .bind(draft.outcome)
+    .fetch_one(db_pool)
+    .await?;
+    info!("Interview experience {} at company {} submitted by {}", experience_id, company_id, user_id);
+    fetch(db_pool, experience_id).await
+}
+
+/// Rewrites one of the candidate's posts and sends it back to moderation.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the post is not the candidate's, or an
+/// error if the database operation fails.
+pub async fn update(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    experience_id: i32,
+    draft: &ExperienceDraft<'_>,
+) -> AppResult<InterviewExperience> {
+    let updated = sqlx::query(
+        r#"
+        UPDATE interview_experiences
+        SET role = $3, interviewed_on = $4, rounds = $5, round_details = $6, questions = $7,
+            summary = $8, difficulty = $9, outcome = $10,
+            moderation_status = 'pending', moderation_reason = NULL, moderated_by = NULL, moderated_at = NULL,
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(experience_id)
+    .bind(user_id)
+    .bind(draft.role.trim())
+    .bind(draft.interviewed_on)
+    .bind(draft.rounds)
+    .bind(clean_list(draft.round_details))
+    .bind(clean_list(draft.questions))
+    .bind(anonymize(draft.summary))
+    .bind(draft.difficulty)
+    .bind(draft.outcome)
+    .execute(db_pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    fetch(db_pool, experience_id).await
+}
+
+/// Deletes one of the candidate's posts.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the post is not the candidate's, or an
+/// error if the database operation fails.
+pub async fn delete(db_pool: &PgPool, user_id: Uuid, experience_id: i32) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM interview_experiences WHERE id = $1 AND user_id = $2")
+        .bind(experience_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// The candidate's posts, newest first, whatever their moderation status.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_for_user(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<InterviewExperience>> {
+    let experiences = sqlx::query_as::<_, InterviewExperience>(&format!(
+        r#"
+        SELECT {} FROM interview_experiences e JOIN companies c ON c.id = e.company_id
+        WHERE e.user_id = $1
+        ORDER BY e.created_at DESC
+        "#,
+        EXPERIENCE_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(experiences)
+}
+
+/// Searches a company's published posts, newest first, and summarizes all
+/// of its published posts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn search(
+    db_pool: &PgPool,
+    company_id: i32,
+    filter: &ExperienceSearch<'_>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<CompanyExperiences> {
+    let experiences = sqlx::query_as::<_, InterviewExperience>(&format!(
+        r#"
+        SELECT {} FROM interview_experiences e JOIN companies c ON c.id = e.company_id
+        WHERE e.company_id = $1 AND e.moderation_status = 'approved'
+          AND ($2::text IS NULL OR e.role ILIKE $2 OR e.summary ILIKE $2
+               OR EXISTS (SELECT 1 FROM unnest(e.questions || e.round_details) t WHERE t ILIKE $2))
+          AND ($3::text IS NULL OR e.role ILIKE $3)
+          AND ($4::interview_outcome IS NULL OR e.outcome = $4)
+        ORDER BY e.created_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+        EXPERIENCE_COLUMNS
+    ))
+    .bind(company_id)
+    .bind(like_pattern(filter.query))
+    .bind(like_pattern(filter.role))
+    .bind(filter.outcome)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db_pool)
+    .await?;
+
+    let (count, average_rounds, average_difficulty) = sqlx::query_as::<_, (i64, Option<f64>, Option<f64>)>(
+        r#"
+        SELECT COUNT(*), ROUND(AVG(rounds), 1)::float8, ROUND(AVG(difficulty), 1)::float8
+        FROM interview_experiences
+        WHERE company_id = $1 AND moderation_status = 'approved'
+        "#,
+    )
+    .bind(company_id)
+    .fetch_one(db_pool)
+    .await?;
+    let outcomes = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT outcome::text, COUNT(*)
+        FROM interview_experiences
+        WHERE company_id = $1 AND moderation_status = 'approved'
+        GROUP BY outcome
+        "#,
+    )
+    .bind(company_id)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    Ok(CompanyExperiences {
+        summary: ExperienceSummary {
+            experiences: count,
+            average_rounds,
+            average_difficulty,
+            outcomes,
+        },
+        experiences,
+    })
+}
+
+/// Posts awaiting moderation, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_pending(db_pool: &PgPool) -> AppResult<Vec<QueuedExperience>> {
+    let experiences = sqlx::query_as::<_, QueuedExperience>(&format!(
+        r#"
+        SELECT {}, e.user_id AS author_id, u.email AS author_email
+        FROM interview_experiences e
+        JOIN companies c ON c.id = e.company_id
+        JOIN users u ON u.id = e.user_id
+        WHERE e.moderation_status = 'pending'
+        ORDER BY e.created_at
+        "#,
+        EXPERIENCE_COLUMNS
+    ))
+    .fetch_all(db_pool)
+    .await?;
+    Ok(experiences)
+}
+
+/// Approves or rejects a pending post, audits the decision, and tells the
+/// author.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the post is not pending, or an error if
+/// the database operation fails.
+pub async fn moderate(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    experience_id: i32,
+    decision: ModerationStatus,
+    reason: Option<&str>,
+) -> AppResult<InterviewExperience> {
+    let (author_id, company_id, role) = sqlx::query_as::<_, (Uuid, i32, String)>(
+        r#"
+        UPDATE interview_experiences
+        SET moderation_status = $2, moderation_reason = $3, moderated_by = $4, moderated_at = NOW()
+        WHERE id = $1 AND moderation_status = 'pending'
+        RETURNING user_id, company_id, role
+        "#,
+    )
+    .bind(experience_id)
+    .bind(decision)
+    .bind(reason)
+    .bind(admin_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let approved = decision == ModerationStatus::Approved;
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, company_id, action, target_type, target_id, metadata)
+        VALUES ($1, $2, $3, 'interview_experience', $4, $5)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(company_id)
+    .bind(if approved { "interview_experience.approve" } else { "interview_experience.reject" })
+    .bind(experience_id.to_string())
+    .bind(json!({ "reason": reason }))
+    .execute(db_pool)
+    .await?;
+
+    let (title, message) = if approved {
+        (
+            "Your interview experience is published",
+            format!("Your interview experience for {} is now visible to other candidates.", role),
+        )
+    } else {
+        (
+            "Your interview experience was not published",
+            format!(
+                "Your interview experience for {} was not published: {}",
+                role,
+                reason.unwrap_or("it does not meet our guidelines")
+            ),
+        )
+    };
+    if let Err(e) = sqlx::query(
+        "INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'interview_experience')",
+    )
+    .bind(author_id)
+    .bind(title)
+    .bind(message)
+    .execute(db_pool)
+    .await
+    {
+        warn!("Failed to notify {} about interview experience {}: {}", author_id, experience_id, e);
+    }
+
+    info!("Interview experience {} {:?} by admin {}", experience_id, decision, admin_id);
+    fetch(db_pool, experience_id).await
+}
+
+/// Published posts about a company as prompt context, the ones for the
+/// same role first. The company is the job's, or else the company of that
+/// name. Returns `None` when there are no posts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn grounding(
+    db_pool: &PgPool,
+    job_id: Option<i32>,
+    company: Option<&str>,
+    role: &str,
+) -> AppResult<Option<String>> {
+    let posts = sqlx::query_as::<_, (String, i16, Vec<String>, Vec<String>, String)>(
+        r#"
+        SELECT e.role, e.rounds, e.round_details, e.questions, e.outcome::text
+        FROM interview_experiences e
+        WHERE e.moderation_status = 'approved'
+          AND e.company_id = COALESCE(
+              (SELECT company_id FROM jobs WHERE id = $1),
+              (SELECT id FROM companies WHERE LOWER(name) = LOWER(TRIM($2)) ORDER BY id LIMIT 1))
+        ORDER BY (e.role ILIKE '%' || $3 || '%' OR $3 ILIKE '%' || e.role || '%') DESC, e.created_at DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(job_id)
+    .bind(company)
+    .bind(role.trim())
+    .bind(MAX_GROUNDING_POSTS)
+    .fetch_all(db_pool)
+    .await?;
+    if posts.is_empty() {
+        return Ok(None);
+    }
+
+    let context = posts
+        .iter()
+        .map(|(role, rounds, round_details, questions, outcome)| {
+            let mut post = format!("Role: {} ({} rounds, outcome: {})", role, rounds, outcome.replace('_', " "));
+            for round in round_details {
+                post.push_str(&format!("\nRound: {}", round));
+            }
+            for question in questions.iter().take(MAX_GROUNDING_QUESTIONS) {
+                post.push_str(&format!("\nAsked: {}", question));
+            }
+            post
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(Some(context))
+}
@@ -0,0 +1,492 @@
+//! Interview prep packs.
+//!
+//! A candidate schedules an interview on one of their applications with
+//! [`schedule`]. [`DELIVERY_LEAD_HOURS`] before it, [`deliver_due`] prepares a
+//! prep pack and notifies them: the questions they are likely to be asked,
+//! generated from their profile and the job and grounded in the interview
+//! experiences past candidates shared about the company; the STAR stories
+//! to rehearse; and facts about the company from its page, reviews, and
+//! shared interviews. The candidate can regenerate the pack at any time.
+//!
+//! A pack belongs to the interview it was prepared for, so rescheduling
+//! prepares and delivers a new one.
+//!
+//! Scheduling also provisions a video meeting (see [`crate::meetings`])
+//! unless the candidate passes the link the company sent; the link goes into
+//! the prep pack notification and the interview's calendar invite.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::application_packages;
+use crate::branding::{self, CompanyProfile};
+use crate::company_reviews::{self, ReviewSummary};
+use crate::errors::{AppError, AppResult};
+use crate::interview_experiences::{self, ExperienceSearch, ExperienceSummary};
+use crate::meetings::MeetingProvider;
+use crate::models::StarCompetency;
+
+/// Length of an interview's meeting and calendar entry.
+pub const INTERVIEW_MINUTES: i64 = 60;
+
+/// Hours before an interview its prep pack is delivered.
+pub const DELIVERY_LEAD_HOURS: i64 = 48;
+
+/// Packs prepared per scheduler pass.
+const DELIVERY_BATCH_SIZE: i64 = 10;
+
+/// Minutes before a pack that failed to generate is tried again.
+const RETRY_AFTER_MINUTES: i32 = 60;
+
+/// STAR stories suggested for rehearsal.
+const MAX_STORIES: i64 = 4;
+
+/// A question the candidate is likely to be asked.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrepQuestion {
+    pub question: String,
+    /// `technical`, `behavioral`, `role_specific`, or `culture`
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub why_asked: String,
+    #[serde(default)]
+    pub answer_tips: String,
+    /// Past candidates report being asked this
+    #[serde(default)]
+    pub reported: bool,
+}
+
+/// A STAR story to rehearse.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RehearsalStory {
+    pub id: i32,
+    pub title: String,
+    pub competencies: Vec<StarCompetency>,
+    pub situation: String,
+    pub task: String,
+    pub action: String,
+    pub result: String,
+}
+
+/// What candidates should know about the company.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanyFacts {
+    pub profile: CompanyProfile,
+    /// The company's published "why work here"
+    pub why_work_here: Option<String>,
+    pub reviews: ReviewSummary,
+    pub interviews: ExperienceSummary,
+}
+
+/// The contents of a prep pack.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackContent {
+    pub questions: Vec<PrepQuestion>,
+    pub star_stories: Vec<RehearsalStory>,
+    /// `None` when the job is not linked to a company on CareerBridge
+    pub company: Option<CompanyFacts>,
+}
+
+/// A prep pack for an application's interview.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PrepPack {
+    pub application_id: i32,
+    pub job_title: String,
+    pub company: String,
+    pub interview_at: DateTime<Utc>,
+    /// `None` until generated
+    pub content: Option<Json<PackContent>>,
+    pub provider: Option<String>,
+    /// Why the last generation failed
+    pub error: Option<String>,
+    pub generated_at: Option<DateTime<Utc>>,
+    /// When the candidate was notified of the pack
+    pub notified_at: Option<DateTime<Utc>>,
+}
+
+/// An application's interview.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScheduledInterview {
+    pub application_id: i32,
+    pub job_title: String,
+    pub company: String,
+    pub status: String,
+    pub interview_at: DateTime<Utc>,
+    /// Video meeting to join; `None` if provisioning failed
+    pub meeting_url: Option<String>,
+    /// When the prep pack will be delivered
+    pub prep_pack_at: DateTime<Utc>,
+}
+
+/// Questions as the model returns them.
+#[derive(Debug, Deserialize)]
+struct GeneratedQuestions {
+    #[serde(default)]
+    questions: Vec<PrepQuestion>,
+}
+
+const PACK_COLUMNS: &str = r#"
+    p.application_id, j.job_title, j.company, p.interview_at, p.content, p.provider,
+    p.error, p.generated_at, p.notified_at
+"#;
+
+const INTERVIEW_COLUMNS: &str = r#"
+    a.id AS application_id, j.job_title, j.company, a.status, a.interview_at, a.meeting_url,
+    a.interview_at - make_interval(hours => $3) AS prep_pack_at
+"#;
+
+/// Schedules (or reschedules) the interview of one of the candidate's
+/// applications and moves the application to the `interview` stage. A new
+/// meeting is provisioned unless `meeting_url` (the company's own link) is
+/// given; if provisioning fails, the interview is scheduled without one.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the time is in the past,
+/// `AppError::NotFound` if the application is not the candidate's, or an
+/// error if the database operation fails.
+pub async fn schedule(
+    db_pool: &PgPool,
+    meetings: &MeetingProvider,
+    user_id: Uuid,
+    application_id: i32,
+    interview_at: DateTime<Utc>,
+    meeting_url: Option<&str>,
+) -> AppResult<ScheduledInterview> {
+    if interview_at <= Utc::now() {
+        return Err(AppError::ValidationError("The interview must be in the future".to_string()));
+    }
+    let (job_title, company) = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT j.job_title, j.company FROM application_tracking a
+        JOIN jobs j ON j.id = a.job_id
+        WHERE a.id = $1 AND a.user_id = $2
+        "#,
+    )
+    .bind(application_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let meeting_url = match meeting_url.map(str::trim).filter(|u| !u.is_empty()) {
+        Some(url) => Some(url.to_string()),
+        None => match meetings
+            .create(
+                &format!("Interview: {} at {}", job_title, company),
+                interview_at,
+                INTERVIEW_MINUTES,
+            )
+            .await
+        {
+            Ok(room) => Some(room.join_url),
+            Err(e) => {
+                warn!("No meeting for the interview of application {}: {}", application_id, e);
+                None
+            }
+        },
+    };
+
+    let scheduled = sqlx::query_as::<_, ScheduledInterview>(&format!(
+        r#"
+        UPDATE application_tracking a
+        SET interview_at = $4, status = 'interview', meeting_url = $5
+        FROM jobs j
+        WHERE a.id = $1 AND a.user_id = $2 AND j.id = a.job_id
+        RETURNING {}
+        "#,
+        INTERVIEW_COLUMNS
+    ))
+    .bind(application_id)
+    .bind(user_id)
+    .bind(DELIVERY_LEAD_HOURS as i32)
+    .bind(interview_at)
+    .bind(meeting_url)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    info!("Interview for application {} scheduled at {}", application_id, interview_at);
+    Ok(scheduled)
+}
+
+/// The scheduled interview of one of the candidate's applications.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the application is not the candidate's
+/// or has no interview scheduled, or an error if the database operation
+/// fails.
+pub async fn scheduled(db_pool: &PgPool, user_id: Uuid, application_id: i32) -> AppResult<ScheduledInterview> {
+    sqlx::query_as::<_, ScheduledInterview>(&format!(
+        r#"
+        SELECT {}
+        FROM application_tracking a
+        JOIN jobs j ON j.id = a.job_id
+        WHERE a.id = $1 AND a.user_id = $2 AND a.interview_at IS NOT NULL
+        "#,
+        INTERVIEW_COLUMNS
+    ))
+    .bind(application_id)
+    .bind(user_id)
+    .bind(DELIVERY_LEAD_HOURS as i32)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Loads the prep pack of one of the candidate's applications.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the application is not the candidate's
+/// or has no pack yet, or an error if the database operation fails.
+pub async fn load(db_pool: &PgPool, user_id: Uuid, application_id: i32) -> AppResult<PrepPack> {
+    sqlx::query_as::<_, PrepPack>(&format!(
+        r#"
+        SELECT {} FROM interview_prep_packs p
+        JOIN application_tracking a ON a.id = p.application_id
+        JOIN jobs j ON j.id = a.job_id
+        WHERE p.application_id = $1 AND p.user_id = $2
+        "#,
+        PACK_COLUMNS
+    ))
+    .bind(application_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Prepares the pack for the scheduled interview of one of the candidate's
+/// applications, replacing any earlier pack. The question generation is
+/// charged to the candidate like the matching AI action.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the application is not the candidate's,
+/// `AppError::ValidationError` if no interview is scheduled,
+/// `AppError::ExternalServiceError` if the model's questions cannot be
+/// read, or an error if the AI call or database operation fails.
+pub async fn generate(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    application_id: i32,
+) -> AppResult<PrepPack> {
+    let (job_id, job_title, company, job_description, company_id, interview_at) =
+        sqlx::query_as::<_, (i32, String, String, String, Option<i32>, Option<DateTime<Utc>>)>(
+            r#"
+            SELECT j.id, j.job_title, j.company, j.job_description, j.company_id, a.interview_at
+            FROM application_tracking a
+            JOIN jobs j ON j.id = a.job_id
+            WHERE a.id = $1 AND a.user_id = $2
+            "#,
+        )
+        .bind(application_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let interview_at = interview_at.ok_or_else(|| {
+        AppError::ValidationError("Schedule the interview before preparing for it".to_string())
+    })?;
+
+    let profile = application_packages::candidate_profile(db_pool, user_id).await?;
+    let experiences = interview_experiences::grounding(db_pool, Some(job_id), Some(&company), &job_title).await?;
+    let job = serde_json::json!({
+        "job_title": job_title,
+        "company": company,
+        "job_description": job_description,
+    });
+    let input = match experiences {
+        Some(experiences) => format!(
+            "Job:\n{}\n\nCandidate:\n{}\n\nInterview experiences shared by past candidates at this company:\n{}",
+            job, profile, experiences
+        ),
+        None => format!("Job:\n{}\n\nCandidate:\n{}", job, profile),
+    };
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateInterviewQuestions,
+            provider,
+            input,
+            parameters: None,
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+    let generated: GeneratedQuestions = serde_json::from_value(response.data)
+        .map_err(|e| AppError::ExternalServiceError(format!("Could not read the interview questions: {}", e)))?;
+
+    let star_stories = sqlx::query_as::<_, RehearsalStory>(
+        r#"
+        SELECT id, title, competencies, situation, task, action, result
+        FROM star_stories
+        WHERE user_id = $1
+        ORDER BY status = 'polished' DESC, cardinality(competencies) DESC, updated_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(MAX_STORIES)
+    .fetch_all(db_pool)
+    .await?;
+
+    let company_facts = match company_id {
+        Some(company_id) => Some(company_facts(db_pool, company_id).await?),
+        None => None,
+    };
+
+    let content = PackContent {
+        questions: generated.questions,
+        star_stories,
+        company: company_facts,
+    };
+    sqlx::query(
+        r#"
+        INSERT INTO interview_prep_packs (application_id, user_id, interview_at, content, provider, generated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW())
+        ON CONFLICT (application_id) DO UPDATE
+        SET interview_at = EXCLUDED.interview_at, content = EXCLUDED.content, provider = EXCLUDED.provider,
+            error = NULL, generated_at = NOW(), updated_at = NOW(),
+            notified_at = CASE WHEN interview_prep_packs.interview_at = EXCLUDED.interview_at
+                               THEN interview_prep_packs.notified_at END
+        "#,
+    )
+    .bind(application_id)
+    .bind(user_id)
+    .bind(interview_at)
+    .bind(Json(&content))
+    .bind(response.provider.as_str())
+    .execute(db_pool)
+    .await?;
+    info!("Interview prep pack generated for application {}", application_id);
+
+    load(db_pool, user_id, application_id).await
+}
+
+/// The company's profile, published branding, and the summaries of its
+/// reviews and shared interviews.
+async fn company_facts(db_pool: &PgPool, company_id: i32) -> AppResult<CompanyFacts> {
+    let profile = branding::load_profile(db_pool, company_id).await?;
+    let why_work_here = branding::load(db_pool, company_id)
+        .await?
+        .published
+        .map(|Json(content)| content.why_work_here);
+    let reviews = company_reviews::summary(db_pool, company_id).await?;
+    let interviews = interview_experiences::search(db_pool, company_id, &ExperienceSearch::default(), 1, 0)
+        .await?
+        .summary;
+    Ok(CompanyFacts {
+        profile,
+        why_work_here,
+        reviews,
+        interviews,
+    })
+}
+
+/// Prepares and delivers the packs of interviews starting within
+/// [`DELIVERY_LEAD_HOURS`], soonest first. A pack already delivered for the
+/// same interview is not sent again; one that failed to generate is retried
+/// after [`RETRY_AFTER_MINUTES`]. Returns the number of packs delivered.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn deliver_due(db_pool: &PgPool, ai: &AIService) -> AppResult<usize> {
+    let due = sqlx::query_as::<_, (i32, Uuid, String, String, DateTime<Utc>, Option<String>)>(
+        r#"
+        SELECT a.id, a.user_id, j.job_title, j.company, a.interview_at, a.meeting_url
+        FROM application_tracking a
+        JOIN jobs j ON j.id = a.job_id
+        LEFT JOIN interview_prep_packs p ON p.application_id = a.id
+        WHERE a.status = 'interview'
+          AND a.interview_at > NOW() AND a.interview_at <= NOW() + make_interval(hours => $1)
+          AND (p.id IS NULL
+               OR p.interview_at <> a.interview_at
+               OR (p.notified_at IS NULL
+                   AND (p.error IS NULL OR p.updated_at < NOW() - make_interval(mins => $2))))
+        ORDER BY a.interview_at
+        LIMIT $3
+        "#,
+    )
+    .bind(DELIVERY_LEAD_HOURS as i32)
+    .bind(RETRY_AFTER_MINUTES)
+    .bind(DELIVERY_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut delivered = 0;
+    for (application_id, user_id, job_title, company, interview_at, meeting_url) in due {
+        // A pack regenerated on demand for this interview is delivered as is
+        let ready = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM interview_prep_packs WHERE application_id = $1 AND interview_at = $2 AND content IS NOT NULL)",
+        )
+        .bind(application_id)
+        .bind(interview_at)
+        .fetch_one(db_pool)
+        .await?;
+        if !ready && let Err(e) = generate(db_pool, ai, AIProvider::Gemini, user_id, application_id).await {
+            warn!("Interview prep pack for application {} failed: {}", application_id, e);
+            sqlx::query(
+                r#"
+                INSERT INTO interview_prep_packs (application_id, user_id, interview_at, error)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (application_id) DO UPDATE
+                SET interview_at = EXCLUDED.interview_at, content = NULL, error = EXCLUDED.error,
+                    notified_at = NULL, updated_at = NOW()
+                "#,
+            )
+            .bind(application_id)
+            .bind(user_id)
+            .bind(interview_at)
+            .bind(e.to_string())
+            .execute(db_pool)
+            .await?;
+            continue;
+        }
+
+        let hours = (interview_at - Utc::now()).num_hours().max(1);
+        sqlx::query(
+            r#"
+            WITH sent AS (
+                UPDATE interview_prep_packs SET notified_at = NOW()
+                WHERE application_id = $1 AND notified_at IS NULL
+                RETURNING user_id
+            )
+            INSERT INTO notifications (user_id, title, message, type)
+            SELECT user_id, 'Your interview prep pack is ready', $2, 'interview_prep'
+            FROM sent
+            "#,
+        )
+        .bind(application_id)
+        .bind(format!(
+            "Your interview for {} at {} is in about {} hours. Your prep pack has the questions you are likely to be asked, the STAR stories to rehearse, and facts about the company.{}",
+            job_title,
+            company,
+            hours.min(DELIVERY_LEAD_HOURS),
+            meeting_url.map(|url| format!(" Join the interview at {}", url)).unwrap_or_default()
+        ))
+        .execute(db_pool)
+        .await?;
+        delivered += 1;
+    }
+
+    if delivered > 0 {
+        info!("Delivered {} interview prep packs", delivered);
+    }
+    Ok(delivered)
+}
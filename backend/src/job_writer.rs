@@ -0,0 +1,273 @@
+//! Job description writing assistant for employers.
+//!
+//! [`draft`] turns a short brief into a structured posting through the
+//! `job_description` content type: a description, responsibilities,
+//! requirements, nice-to-haves, and required skills, in the shape
+//! `POST /api/employer/jobs` accepts. The draft's benefits default to the
+//! company's perks, and its salary range is suggested from approved
+//! postings for similar titles ([`benchmark`]) rather than by the AI. Every
+//! field is linted for exclusionary language before the employer publishes.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+use crate::inclusive_language::{self, LanguageFlag};
+use crate::models::{ExperienceLevel, JobType};
+
+/// Content type the posting is generated with.
+const CONTENT_TYPE: &str = "job_description";
+
+/// Postings with a salary needed before a range is suggested.
+const MIN_BENCHMARK_POSTINGS: i64 = 3;
+
+/// Seniority words left out of the title when finding similar postings;
+/// the experience level covers seniority.
+const SENIORITY_WORDS: &[&str] = &["senior", "sr", "junior", "jr", "lead", "principal", "intern", "trainee", "associate"];
+
+/// What the employer tells the assistant about the role.
+#[derive(Debug)]
+pub struct Brief {
+    pub brief: String,
+    pub job_title: Option<String>,
+    pub experience_level: Option<ExperienceLevel>,
+    pub job_type: Option<JobType>,
+    pub location: Option<String>,
+}
+
+/// A salary range suggested from similar postings.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SalaryBenchmark {
+    /// Approved postings from the last year the range is based on
+    pub sample_size: i64,
+    /// 25th percentile of their minimum salaries
+    pub salary_min: i32,
+    /// Median of their midpoints
+    pub median: i32,
+    /// 75th percentile of their maximum salaries
+    pub salary_max: i32,
+    /// Whether only postings at the same experience level were used
+    pub same_experience_level: bool,
+}
+
+/// A drafted job posting.
+#[derive(Debug, Serialize)]
+pub struct JobDraft {
+    pub job_title: String,
+    pub job_description: String,
+    pub responsibilities: Vec<String>,
+    pub requirements: Vec<String>,
+    /// Not required; list them in the description or requirements as optional
+    pub nice_to_haves: Vec<String>,
+    pub required_skills: Vec<String>,
+    pub benefits: Vec<String>,
+    pub experience_level: Option<ExperienceLevel>,
+    pub job_type: Option<JobType>,
+    pub location: Option<String>,
+    /// `None` when too few similar postings list a salary
+    pub salary_suggestion: Option<SalaryBenchmark>,
+    /// Exclusionary phrases to fix before publishing
+    pub language_flags: Vec<LanguageFlag>,
+}
+
+#[derive(Debug, FromRow)]
+struct Company {
+    name: String,
+    description: Option<String>,
+    perks: Vec<String>,
+}
+
+/// Drafts a posting from a brief for the user's company, if they have one.
+///
+/// # Errors
+///
+/// Returns `AppError::ExternalServiceError` if the provider fails or returns
+/// no description, or an error if a database query fails.
+pub async fn draft(
+    db_pool: &PgPool,
+    ai: &AIService,
+    user_id: Uuid,
+    company_id: Option<i32>,
+    brief: Brief,
+    provider: AIProvider,
+) -> AppResult<JobDraft> {
+    let company = match company_id {
+        Some(id) => {
+            sqlx::query_as::<_, Company>("SELECT name, description, perks FROM companies WHERE id = $1")
+                .bind(id)
+                .fetch_optional(db_pool)
+                .await?
+        }
+        None => None,
+    };
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateContent,
+            provider,
+            input: brief.brief.clone(),
+            parameters: Some(json!({
+                "content_type": CONTENT_TYPE,
+                "job_title": brief.job_title,
+                "experience_level": brief.experience_level,
+                "job_type": brief.job_type,
+                "location": brief.location,
+                "company": company.as_ref().map(|c| json!({ "name": c.name, "description": c.description })),
+                "instructions": "Write a job posting from this brief. Put a 2-3 paragraph overview of the role in `content`. \
+                    In `metadata`, give `job_title`, `responsibilities` (5-8 items), `requirements` (4-6 must-haves), \
+                    `nice_to_haves` (2-4 items), `required_skills` (technologies and skills, short names), and \
+                    `experience_level` (fresher, junior, or mid) and `job_type` (full_time, part_time, internship, or \
+                    freelance) when the brief does not fix them. Ask only for what the role needs; no age, \
+                    gender, appearance, or marital status requirements, no degree requirement unless essential, and \
+                    no jargon like \"rockstar\" or \"ninja\". Do not mention salary.",
+            })),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+
+    let data = &response.data;
+    let job_description = data
+        .get("content")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AppError::ExternalServiceError("AI provider returned no job description".to_string()))?
+        .to_string();
+    let metadata = data.get("metadata").cloned().unwrap_or(Value::Null);
+    let list = |name: &str| -> Vec<String> {
+        metadata
+            .get(name)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::trim)
+                    .filter(|i| !i.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let job_title = brief
+        .job_title
+        .or_else(|| metadata.get("job_title").and_then(Value::as_str).map(str::to_string))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "Untitled role".to_string());
+    let experience_level = brief.experience_level.or_else(|| {
+        metadata
+            .get("experience_level")
+            .and_then(Value::as_str)
+            .and_then(|l| l.parse().ok())
+    });
+    let job_type = brief
+        .job_type
+        .or_else(|| metadata.get("job_type").and_then(Value::as_str).and_then(|t| t.parse().ok()));
+
+    let mut draft = JobDraft {
+        salary_suggestion: benchmark(db_pool, &job_title, experience_level.as_ref()).await?,
+        job_title,
+        job_description,
+        responsibilities: list("responsibilities"),
+        requirements: list("requirements"),
+        nice_to_haves: list("nice_to_haves"),
+        required_skills: list("required_skills"),
+        benefits: company.map(|c| c.perks).unwrap_or_default(),
+        experience_level,
+        job_type,
+        location: brief.location,
+        language_flags: Vec::new(),
+    };
+    draft.language_flags = lint(&draft);
+    Ok(draft)
+}
+
+/// Lints every text field of a draft.
+fn lint(draft: &JobDraft) -> Vec<LanguageFlag> {
+    let mut fields = vec![("job_title", draft.job_title.as_str()), ("job_description", draft.job_description.as_str())];
+    for (field, items) in [
+        ("responsibilities", &draft.responsibilities),
+        ("requirements", &draft.requirements),
+        ("nice_to_haves", &draft.nice_to_haves),
+        ("benefits", &draft.benefits),
+    ] {
+        fields.extend(items.iter().map(|item| (field, item.as_str())));
+    }
+    dedup(inclusive_language::lint_posting(fields))
+}
+
+/// Keeps the first flag of each phrase per field.
+fn dedup(flags: Vec<LanguageFlag>) -> Vec<LanguageFlag> {
+    let mut seen = std::collections::HashSet::new();
+    flags
+        .into_iter()
+        .filter(|f| seen.insert((f.field.clone(), f.phrase.clone())))
+        .collect()
+}
+
+/// A salary range from approved postings of the last year with a similar
+/// title, at the same experience level when enough exist.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn benchmark(
+    db_pool: &PgPool,
+    job_title: &str,
+    experience_level: Option<&ExperienceLevel>,
+) -> AppResult<Option<SalaryBenchmark>> {
+    let core: Vec<String> = job_title
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty() && !SENIORITY_WORDS.contains(&w.as_str()))
+        .collect();
+    if core.is_empty() {
+        return Ok(None);
+    }
+    let pattern = format!(
+        "%{}%",
+        core.join(" ").replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+
+    let levels: &[Option<&ExperienceLevel>] = match experience_level {
+        Some(level) => &[Some(level), None],
+        None => &[None],
+    };
+    for level in levels {
+        let benchmark = sqlx::query_as::<_, SalaryBenchmark>(
+            r#"
+            SELECT COUNT(*) AS sample_size,
+                   COALESCE(percentile_cont(0.25) WITHIN GROUP (ORDER BY salary_min), 0)::int AS salary_min,
+                   COALESCE(percentile_cont(0.5) WITHIN GROUP (ORDER BY (salary_min + salary_max) / 2.0), 0)::int AS median,
+                   COALESCE(percentile_cont(0.75) WITHIN GROUP (ORDER BY salary_max), 0)::int AS salary_max,
+                   $2::experience_level IS NOT NULL AS same_experience_level
+            FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL
+              AND salary_min IS NOT NULL AND salary_max IS NOT NULL
+              AND created_at > NOW() - INTERVAL '1 year'
+              AND job_title ILIKE $1
+              AND ($2::experience_level IS NULL OR experience_level = $2)
+            "#,
+        )
+        .bind(&pattern)
+        .bind(*level)
+        .fetch_one(db_pool)
+        .await?;
+        if benchmark.sample_size >= MIN_BENCHMARK_POSTINGS {
+            return Ok(Some(benchmark));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,187 @@
+//! Curated knowledge base of career guides and FAQs.
+//!
+//! Admins write articles in Markdown; published ones are public to browse
+//! and are chunked and embedded like user documents (see [`crate::retrieval`]),
+//! so mentor answers can retrieve and cite them. Saving an article clears
+//! its `indexed_at` and indexes it in the background; the scheduler retries
+//! any article still unindexed, e.g. after an embedding failure.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+
+use crate::ai::AIService;
+use crate::errors::AppResult;
+use crate::retrieval::{self, RetrievedChunk};
+
+/// Articles indexed per scheduler run.
+const INDEX_BATCH_SIZE: i64 = 20;
+
+/// Longest slug, in characters.
+const MAX_SLUG_CHARS: usize = 160;
+
+/// An article as listed for browsing.
+#[derive(Debug, Serialize, FromRow)]
+pub struct KbArticleSummary {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A full article.
+#[derive(Debug, Serialize, FromRow)]
+pub struct KbArticle {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Markdown
+    pub content: String,
+    pub tags: Vec<String>,
+    pub published: bool,
+    /// When the article was last embedded; `None` while indexing is pending
+    pub indexed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Derives a URL slug from a title, e.g. `Writing Your First CV` →
+/// `writing-your-first-cv`.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_end_matches('-').chars().take(MAX_SLUG_CHARS).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Indexes an article in the background. Failures are logged and left for
+/// the scheduler to retry.
+pub fn spawn_index(db_pool: PgPool, ai_service: Option<Arc<AIService>>, article_id: i32) {
+    let Some(ai) = ai_service.filter(|ai| ai.embeddings_available()) else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = index_article(&db_pool, &ai, article_id).await {
+            warn!("Indexing knowledge base article {} failed: {}", article_id, e);
+        }
+    });
+}
+
+/// Replaces an article's chunks: embeds it if published, or just drops
+/// its chunks if not. Returns the number of chunks embedded.
+///
+/// `indexed_at` is only set if the article was not edited meanwhile, so an
+/// edit during embedding is indexed again.
+///
+/// # Errors
+///
+/// Returns an error if embedding or the database operation fails.
+pub async fn index_article(db_pool: &PgPool, ai: &AIService, article_id: i32) -> AppResult<usize> {
+    let Some((title, content, published, updated_at)) =
+        sqlx::query_as::<_, (String, String, bool, Option<DateTime<Utc>>)>(
+            "SELECT title, content, published, updated_at FROM kb_articles WHERE id = $1",
+        )
+        .bind(article_id)
+        .fetch_optional(db_pool)
+        .await?
+    else {
+        return Ok(0);
+    };
+
+    let chunks = if published { retrieval::chunk_text(&content) } else { Vec::new() };
+    let inputs: Vec<String> = chunks.iter().map(|c| format!("{}\n{}", title, c)).collect();
+    let vectors = ai.embed(&inputs).await?;
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query("DELETE FROM kb_chunks WHERE article_id = $1")
+        .bind(article_id)
+        .execute(&mut *tx)
+        .await?;
+    for (index, (chunk, vector)) in chunks.iter().zip(&vectors).enumerate() {
+        sqlx::query(
+            "INSERT INTO kb_chunks (article_id, chunk_index, content, embedding) VALUES ($1, $2, $3, $4::vector)",
+        )
+        .bind(article_id)
+        .bind(index as i16)
+        .bind(chunk)
+        .bind(retrieval::vector_literal(vector))
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(
+        "UPDATE kb_articles SET indexed_at = NOW() WHERE id = $1 AND updated_at IS NOT DISTINCT FROM $2",
+    )
+    .bind(article_id)
+    .bind(updated_at)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(chunks.len())
+}
+
+/// Indexes articles whose indexing is pending, oldest edit first.
+/// Returns the number of articles indexed.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails; a failing article is
+/// logged and skipped.
+pub async fn index_pending(db_pool: &PgPool, ai: &AIService) -> AppResult<usize> {
+    let pending = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM kb_articles WHERE indexed_at IS NULL ORDER BY updated_at LIMIT $1",
+    )
+    .bind(INDEX_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut indexed = 0;
+    for article_id in pending {
+        match index_article(db_pool, ai, article_id).await {
+            Ok(_) => indexed += 1,
+            Err(e) => warn!("Indexing knowledge base article {} failed: {}", article_id, e),
+        }
+    }
+    if indexed > 0 {
+        info!("Indexed {} knowledge base articles", indexed);
+    }
+    Ok(indexed)
+}
+
+/// Returns the published article chunks closest to a query vector.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn nearest(db_pool: &PgPool, query_vector: &str, limit: i64) -> AppResult<Vec<RetrievedChunk>> {
+    let chunks = sqlx::query_as::<_, RetrievedChunk>(
+        r#"
+        SELECT 'knowledge_base'::document_source AS source, a.id AS source_id,
+               a.title AS source_title, c.chunk_index, c.content,
+               1 - (c.embedding <=> $1::vector) AS similarity
+        FROM kb_chunks c
+        JOIN kb_articles a ON a.id = c.article_id
+        WHERE a.published
+        ORDER BY c.embedding <=> $1::vector
+        LIMIT $2
+        "#,
+    )
+    .bind(query_vector)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(chunks)
+}
@@ -20,6 +20,88 @@
 //! - [`auth`] - JWT authentication and authorization
 //! - [`security`] - Password hashing and verification
 //! - [`errors`] - Error types and HTTP response conversions
+//! - [`db`] - Connection pool setup and slow query instrumentation
+//! - [`cache`] - In-process caching for hot database reads
+//! - [`scheduler`] - Periodic background maintenance tasks
+//! - [`partitioning`] - Monthly partitioning and archival of log tables
+//! - [`seed`] - Demo dataset seeding and demo mode
+//! - [`fixtures`] - Bulk load-test user generation
+//! - [`webhooks`] - Signature verification for inbound webhooks
+//! - [`billing`] - Premium plans, Stripe subscriptions, and plan-aware quotas
+//! - [`credits`] - Credits ledger for AI usage, referrals, and achievements
+//! - [`teams`] - Company recruiter seats and per-seat activity attribution
+//! - [`employer_verification`] - Work email, registry, and document checks an admin approves before a company may post jobs
+//! - [`email`] - Outbound email through a relay webhook
+//! - [`branding`] - Company profiles, AI-drafted employer branding, and public company pages
+//! - [`company_reviews`] - Candidates' reviews of companies' hiring processes, hidden on reports, with company replies
+//! - [`interview_experiences`] - Moderated, anonymized interview experiences that ground generated interview questions
+//! - [`interview_prep`] - Prep packs for scheduled interviews, delivered as notifications 48 hours before
+//! - [`mentorship`] - Mentor profiles, session slots, and bookings
+//! - [`mentor_payments`] - Payments for paid mentor sessions: held until completion, refunds, and mentor payouts
+//! - [`mentor_calendars`] - Mentors' Google or CalDAV calendars keeping their session slots free of conflicts
+//! - [`meetings`] - Video meeting links (Jitsi or Zoom) for mentor sessions and interviews
+//! - [`session_notes`] - Mentors' notes and action items after sessions, convertible into roadmap tasks
+//! - [`api_keys`] - Integrators' API keys, stored hashed, with scopes
+//! - [`sandbox`] - Sandbox test users, jobs, and applications for API integrators, kept out of production views and analytics and purged nightly
+//! - [`disputes`] - Disputes over mentor sessions and application decisions, with evidence, arbitration, and payment holds
+//! - [`debug_captures`] - Consented captures of AI requests and raw responses, flagged by users and replayed by admins
+//! - [`prompt_rollouts`] - Blue/green rollouts of new prompt versions, rolled back when parse failures or thumbs down exceed thresholds
+//! - [`ai_usage`] - Prompt and completion token totals of AI calls per provider and per action
+//! - [`simplify`] - Rewriting generated documents at a simpler reading level, keeping their structure
+//! - [`export`] - CV and roadmap exports as PDF, large-print PDF, semantic HTML, or plain text, in any script and direction
+//! - [`glossary`] - Jargon definitions generated once and cached, linked from roadmap topics and mentor answers
+//! - [`job_writer`] - AI job description drafting with salary benchmarks from similar postings
+//! - [`inclusive_language`] - Exclusionary language linting for job postings
+//! - [`screening`] - Screening questions on postings, answer scoring, and the applicant list
+//! - [`applicant_ranking`] - Explainable applicant scores from skills, assessments, screening, and experience
+//! - [`talent_pools`] - Saved candidate pools, skill and verification alerts, and pool invitations
+//! - [`moderation`] - Scam screening and quarantine of postings and feed listings, tuned by moderation decisions
+//! - [`blocks`] - Blocking between candidates and employers, hiding each from the other
+//! - [`reports`] - Reports of abusive jobs, accounts, messages, and company reviews, and their resolution
+//! - [`impersonation`] - Time-limited, audited support sessions acting as a user, with destructive requests refused
+//! - [`policies`] - Versioned terms of service and privacy policy, with acceptance records and a middleware requiring the versions in force
+//! - [`geo`] - Geocoding of job locations and radius search
+//! - [`internships`] - Academic terms for internship postings and availability
+//! - [`cohorts`] - Institutions, student cohorts, and profile completeness
+//! - [`events`] - Career-fair badges and iCalendar schedules
+//! - [`storage`] - Local-disk storage for uploaded documents
+//! - [`reporting`] - Anonymized placement outcome reports for institutions
+//! - [`wizards`] - Resumable multi-step flows such as the CV review
+//! - [`links`] - Background validation of resource links with AI replacements
+//! - [`skills`] - Skill proficiency ratings, decay for stale skills, and per-skill learning progress
+//! - [`conversations`] - Mentor conversation history within per-model context budgets, export, and AI summaries
+//! - [`retrieval`] - Embedding the user's CV, roadmaps, and saved documents (pgvector) to ground mentor answers
+//! - [`knowledge_base`] - Admin-curated career guides and FAQs, public to browse and cited in mentor answers
+//! - [`mentor_tools`] - Whitelisted tools (job search, roadmap progress, skill gap) the mentor can call, with call logging
+//! - [`application_packages`] - Queued multi-step agent that builds a job application: job analysis, tailored resume, cover letter, interview questions
+//! - [`cohort_roadmaps`] - Queued bulk roadmap generation for every student in a cohort, with per-student failures
+//! - [`at_risk`] - Students with stalled roadmaps, incomplete profiles, or no applications, and advisor nudges
+//! - [`benchmarking`] - Anonymous percentiles of peers targeting the same role, suppressed for small groups
+//! - [`encryption`] - AES-256-GCM encryption at rest for sensitive uploads
+//! - [`offer_qa`] - Questions answered from one uploaded offer letter or contract, encrypted at rest
+//! - [`negotiation`] - Salary negotiation practice with an AI recruiter working from the user's offer, and a debrief
+//! - [`star_stories`] - Behavioral interview STAR stories mined from projects and the CV, refined over turns, tagged by competency
+//! - [`certifications`] - Background verification of profile certifications with their issuers (Credly)
+//! - [`integrations`] - Clients for outside platforms user activity is imported from (LeetCode, calendars)
+//! - [`code_practice`] - Solved coding problems by topic from linked handles or exports, and their weight in engineering readiness
+//! - [`readiness`] - Explainable role readiness from roadmap progress, verified certifications, projects, and practice
+//! - [`versioning`] - `/api/v1` routing, the deprecated `/api` alias, and legacy response shims
+//! - [`response`] - The `ApiResponse` envelope (success, data, meta, pagination) returned by every JSON endpoint
+//! - [`merge_patch`] - JSON Merge Patch (RFC 7396) for partial updates
+//! - [`validated_json`] - Typed, validated JSON request bodies with JSON errors naming the offending field
+//! - [`curation`] - Removing and renaming profile skills and target roles, and keeping rejected ones out of AI merges
+//! - [`activity`] - Per-user activity feed (roadmaps, phases, applications, badges) and weekly digests
+//! - [`diagnostics`] - Startup self-check and configuration report behind `--check` and the admin diagnostics endpoint
+//! - [`rate_limits`] - Daily AI quotas resolved from the plan and tenant policies, with `X-RateLimit-*` headers
+//! - [`tenant_config`] - Organizations' career services guidance added to their members' mentor and roadmap prompts
+//! - [`semantic_cache`] - Mentor answers reused for near-identical questions by embedding similarity
+//! - [`eval`] - Nightly evaluation of AI outputs against golden fixtures, with score trends
+//! - [`application_timing`] - Notifications with matching jobs once roadmap progress reaches its application timing
+//! - [`roadmap_analytics`] - Roadmap progress over time, phase velocity, and projected completion against the target
+//! - [`portfolio`] - Several roadmaps at once: weekly hours against availability, conflicts, and a primary roadmap
+//! - [`resource_progress`] - Completed/skipped marks, ratings, and notes on roadmap phase resources, aggregated to rank recommendations
+//! - [`resource_clicks`] - Tracked resource links with click analytics and per-resource affiliate parameters
+//! - [`load_shedding`] - Per-class concurrency limits (AI, uploads, reads) that reject with `503` when saturated
 //!
 //! ## Example Usage
 //!
@@ -33,7 +115,21 @@
 //!         .await
 //!         .expect("Failed to connect to database");
 //!     
-//!     let app_state = AppState { db_pool };
+//!     let app_state = AppState {
+//!         db_pool,
+//!         ai_service: None,
+//!         user_cache: backend::cache::UserCache::from_env(),
+//!         webhooks: backend::webhooks::WebhookVerifier::from_env(),
+//!         stripe: None,
+//!         geocoder: None,
+//!         storage: backend::storage::Storage::from_env(),
+//!         document_cipher: None,
+//!         load_shedder: backend::load_shedding::LoadShedder::from_env(),
+//!         rate_limits: backend::rate_limits::RateLimitPolicies::from_env(),
+//!         tenant_configs: backend::tenant_config::TenantConfigs::from_env(),
+//!         calendars: backend::integrations::calendar::CalendarClient::from_env(),
+//!         meetings: backend::meetings::MeetingProvider::from_env(),
+//!     };
 //!     let app = backend::handlers::create_router(app_state);
 //!     
 //!     // Server setup...
@@ -44,32 +140,302 @@
 //!
 //! The application uses PostgreSQL with the following main tables:
 //!
-//! - `users` - User accounts and profiles
-//! - `jobs` - Job listings
+//! - `users` - User accounts and profiles, including social links, availability, and relocation preferences
+//! - `user_work_authorizations` - Per-country right to work, used as a job matching filter
+//! - `jobs` - Job listings (employer posts start `pending`, or `quarantined` by scam screening, until moderated and expire after `JOB_EXPIRY_DAYS`)
+//! - `quarantined_imports`, `scam_screening_feedback` - Feed listings held back by screening, and moderator verdicts that tune it
 //! - `learning_resources` - Educational content
 //! - `application_tracking` - Job application history
 //! - `user_progress` - Learning progress tracking
+//! - `activity_events`, `ai_usage_logs`, `audit_logs` - Monthly-partitioned logs (`activity_events` backs the activity feed; `ai_usage_logs` carries token counts)
+//! - `mentor_conversations`, `mentor_messages` - Career mentor chat history and stored summaries
+//! - `plans`, `subscriptions`, `ai_daily_usage` - Billing and AI quotas
+//! - `rate_limit_policies` - Organization overrides of plan AI quotas
+//! - `mentor_answer_cache` - Cached mentor answers with question embeddings
+//! - `glossary` - Definitions of jargon terms, by lowercased term
+//! - `ai_eval_runs`, `ai_eval_results` - AI evaluation runs and per-case scores
+//! - `roadmap_progress_events` - Roadmap progress snapshots behind roadmap analytics
+//! - `roadmap_resource_progress` - Per-resource completed/skipped marks with ratings and notes
+//! - `credit_accounts`, `credit_transactions`, `credit_action_costs` - Credits ledger
+//! - `geocode_cache` - Cached geocoding lookups for job locations
+//! - `companies`, `company_members`, `company_invitations` - Employer teams and seats
+//! - `employer_verifications` - Companies' verification attempts: work email, registry result, document, and review
+//! - `company_branding` - Draft and published "why work here" content and role pitches for company pages
+//! - `company_reviews` - Candidates' ratings and reviews of companies' hiring processes, with the company's reply
+//! - `interview_experiences` - Candidates' rounds, questions, and outcomes at a company, published after moderation
+//! - `interview_prep_packs` - Prep packs for the interviews scheduled on `application_tracking.interview_at`
+//! - `mentor_profiles`, `mentor_slots`, `mentor_bookings` - Mentors, their session slots, and booked sessions
+//! - `mentor_calendars`, `mentor_busy_periods` - Mentors' connected calendars and their synced busy times
+//! - `mentor_session_notes`, `mentor_action_items` - Notes from mentor sessions and action items assigned to mentees
+//! - `mentor_payments`, `mentor_payouts` - Payments for paid mentor sessions and mentors' payout requests
+//! - `api_keys` - Integrators' hashed API keys and their scopes; `users.sandbox_key_id` and `jobs.sandbox_key_id` tag sandbox test data
+//! - `disputes`, `dispute_evidence`, `dispute_events` - Disputes over sessions and application decisions, their evidence, and status history
+//! - `debug_captures` - AI requests with raw responses and replays, for users who consented (`users.debug_capture_consent_at`)
+//! - `ai_feedback`, `prompt_rollouts` - Thumbs up or down on AI results by prompt version; rollouts of candidate prompts (`ai_usage_logs.parse_failed`)
+//! - `screening_questions` - Questions employers attach to postings
+//! - `screening_answers` - Candidates' answers with their scores and flags
+//! - `talent_pools` - Companies' named candidate pools and the skills they look for
+//! - `talent_pool_members` - Pooled candidates with tags, notes, and what was last seen
+//! - `talent_pool_alerts` - Pooled candidates gaining a pool's skill or a verified certification
+//! - `user_blocks` - Blocks between candidates and employers or companies
+//! - `reports` - User reports awaiting or closed by moderation
+//! - `impersonation_sessions` - Support admins acting as users, and whether the user was told
+//! - `policy_documents` - Published terms of service and privacy policy versions
+//! - `policy_acceptances` - Who accepted which policy version, when, and from where
+//! - `organizations`, `organization_members`, `cohorts`, `cohort_members` - Institutions and student cohorts
+//! - `tenant_configs` - Organizations' mentor persona and roadmap guidance
+//! - `cohort_roadmap_batches`, `cohort_roadmap_items` - Bulk cohort roadmap runs (also their queue) and each student's result
+//! - `student_nudges` - Advisor nudges to at-risk students and the risks they were about
+//! - `career_events`, `event_sessions`, `event_booths`, `event_rsvps`, `event_interactions` - Career fairs and booth leads
+//! - `job_offers`, `offer_documents`, `placements` - Offers on applications and accepted placements
+//! - `offer_qa_documents`, `offer_qa_chunks` - Uploaded offer documents and their encrypted, embedded chunks
+//! - `negotiation_sessions` - Negotiation practice scenarios, transcripts, and debriefs
+//! - `star_stories` - Behavioral interview stories by competency, with refinement inputs
+//! - `wizards`, `wizard_steps` - Resumable multi-step flows with per-step status and outputs
+//! - `resource_links` - Link check status and replacements for learning and roadmap resources
+//! - `resource_clicks` - Followed learning resource links with user and roadmap context
+//! - `user_skill_ratings` - Self-assessed skill proficiency and last-used dates
+//! - `profile_snapshots` - Profile versions saved before AI-driven updates, for reverting
+//! - `user_rejected_profile_items` - Skills and target roles the user removed, kept out of AI merges
+//! - `user_skill_progress` - Learning progress per skill, shared across roadmaps
+//! - `user_documents`, `document_chunks` - Saved documents and embedded chunks of each user's CV, roadmaps, and documents
+//! - `kb_articles`, `kb_chunks` - Knowledge base articles and their embedded chunks
+//! - `ai_tool_calls` - Log of tools called by the mentor, with arguments, results, and latency
+//! - `application_packages`, `application_package_steps` - Application agent runs (also their queue) and each step's artifact
+//! - `user_certifications` - Certifications on a profile, entered by hand or read from an image, with issuer verification status
+//! - `code_practice_accounts`, `code_practice_topics` - Linked coding practice handles and solved problems by difficulty and topic
+//! - `role_readiness` - Latest readiness score per user and role, with its components
 //!
 //! ## API Endpoints
 //!
+//! Endpoints are listed under `/api`, the deprecated alias of `/api/v1`;
+//! every one is also served under `/api/v1`.
+//!
 //! ### Public Endpoints
 //!
 //! - `POST /api/register` - Register a new user
 //! - `POST /api/login` - Authenticate and receive JWT token
+//! - `POST /api/demo/login` - Sign in as a seeded demo persona (`DEMO_MODE` only)
+//! - `GET /api/health` - Service health and the circuit breaker state of each AI provider
+//! - `POST /api/hooks/:integration` - Signed inbound webhooks (`sms`, `payments`, `ats`)
+//! - `GET /api/kb/articles?tag=&q=` - Browse published knowledge base articles
+//! - `GET /api/kb/articles/:slug` - Read a knowledge base article (by slug or id)
+//! - `GET /api/companies/:id` - Public company page with published branding, open jobs, and reviews
+//! - `GET /api/companies/:id/reviews` - A company's published reviews with their rating summary
+//! - `GET /api/companies/:id/interview-experiences?q=&role=&outcome=` - Search a company's published interview experiences
+//! - `GET /api/resources/:id/go?roadmap_id=` - Log a click on a learning resource and redirect to it (JWT optional)
 //!
 //! ### Protected Endpoints (require JWT)
 //!
 //! - `GET /api/profile` - Get user profile
-//! - `PUT /api/profile` - Update user profile
+//! - `PUT /api/profile` - Update user profile (superseded by `PATCH /api/users/me` for profile fields)
+//! - `GET /api/users/me` - Editable profile fields, with an `ETag`
+//! - `PATCH /api/users/me` - Partial profile update with JSON Merge Patch (`If-Match` for concurrency checks)
+//! - `GET /api/users/me/profile/history` - Profile snapshots taken before AI-driven updates
+//! - `POST /api/users/me/profile/revert/:snapshot_id` - Restore the profile from a snapshot
+//! - `GET /api/profile/completeness` - Profile completeness and cohort requirement
+//! - `GET /api/profile/certifications` - List certifications (`POST` to add one, `DELETE /:id` to remove)
+//! - `POST /api/profile/certifications/scan` - Read certificates from a photo or screenshot
+//! - `POST /api/profile/certifications/:id/verify` - Check a certification with its issuer again
+//! - `GET /api/users/me/code-practice` - Your solved coding problems by platform, difficulty, and topic
+//! - `PUT|DELETE /api/users/me/code-practice/:platform` - Link a handle (synced daily), or unlink it
+//! - `POST /api/users/me/code-practice/:platform/import` - Upload a CSV progress export
+//! - `GET /api/users/me/readiness?role=` - Your readiness for a target role, with each component's share
+//! - `GET /api/jobs?near=Dhaka&radius_km=25` - Search jobs by distance (optionally including remote jobs)
 //! - `GET /api/jobs/recommendations` - Get job recommendations
+//! - `GET /api/jobs/:id/screening-questions` - Screening questions to answer when applying
+//! - `GET /api/internships?term=2026-fall` - Browse internships by academic term and credit
 //! - `GET /api/learning/recommendations` - Get learning resource recommendations
 //! - `GET /api/skill-gap/:target_role` - Analyze skill gaps for a role
-//! - `POST /api/applications` - Create job application
+//! - `GET /api/users/me/skills` - Skill proficiency ratings with decay applied
+//! - `PUT /api/users/me/skills/:skill` - Rate proficiency in a skill and when it was last used
+//! - `DELETE /api/users/me/skills/:skill` - Remove a skill and keep it out of AI extraction
+//! - `DELETE /api/users/me/target-roles/:role` - Remove a target role and keep it out of AI extraction
+//! - `POST /api/users/me/skills/bulk`, `POST /api/users/me/target-roles/bulk` - Remove and rename several items
+//! - `GET /api/users/me/rejected-items` - Rejected skills and target roles (`DELETE /:kind/:value` to allow one again)
+//! - `GET /api/users/me/skill-progress` - Learning progress per skill across roadmaps
+//! - `GET|POST /api/users/me/documents` - List or save documents the mentor draws on
+//! - `DELETE /api/users/me/documents/:id` - Delete a saved document
+//! - `POST /api/applications` - Create job application, with answers to the job's screening questions
 //! - `GET /api/applications` - List user's applications
 //! - `PUT /api/applications/:id` - Update application status
+//! - `PUT /api/applications/:id/interview` - Schedule the application's interview, with a meeting link
+//! - `GET /api/applications/:id/interview.ics` - Calendar invite for the interview
+//! - `GET /api/applications/:id/prep-pack` - The interview prep pack, delivered 48 hours before the interview
+//! - `POST /api/ai/applications/:id/prep-pack` - Regenerate the interview prep pack
+//! - `GET|POST /api/blocks` - List or add blocks; candidates block employers and companies, employers block candidates
+//! - `DELETE /api/blocks/:id` - Lift a block
+//! - `GET|POST /api/reports` - The caller's reports, or report a job, user, company, received message, or company review
+//! - `GET /api/policies` - Terms of service and privacy policy in force (public)
+//! - `GET /api/policies/:kind?version=` - A policy's version in force or an earlier one (public)
+//! - `GET /api/policies/pending` - Policy versions the caller must accept before using other endpoints
+//! - `POST /api/policies/:kind/accept` - Accept the version in force, recording address and user agent
+//! - `GET /api/policies/acceptances` - The caller's acceptances
 //! - `POST /api/progress/resource/:id/start` - Start tracking resource
 //! - `PUT /api/progress/resource/:id` - Update progress
 //! - `GET /api/progress` - Get all progress records
+//! - `POST /api/employer/jobs` - Post a job; the response flags exclusionary language (employers at verified companies)
+//! - `GET /api/employer/dashboard` - Per-job applicant counters (employers)
+//! - `POST /api/employer/jobs/:id/renew` - Extend or reopen an expiring job posting (employers)
+//! - `GET /api/admin/jobs/pending` - Job posting moderation queue, including quarantined posts (admins)
+//! - `POST /api/admin/jobs/:id/approve` - Approve a pending job (admins)
+//! - `POST /api/admin/jobs/:id/reject` - Reject a pending job with a reason (admins)
+//! - `GET /api/admin/imports/quarantine` - Feed listings held back by scam screening (admins)
+//! - `POST /api/admin/imports/quarantine/:id/release|reject` - Release a false positive, or confirm a scam (admins)
+//! - `GET /api/admin/scam-screening` - Quarantine threshold and signal weights tuned from feedback (admins)
+//! - `GET /api/admin/reports?status=open` - User report queue, most reported targets first (admins)
+//! - `POST /api/admin/reports/:id/resolve` - Dismiss, warn the reported party, or remove the job, message, or review (admins)
+//! - `POST /api/admin/users/:id/impersonate` - Start a time-limited session acting as a user (admins)
+//! - `GET /api/admin/impersonations?user_id=` - Impersonation sessions with request counts (admins)
+//! - `POST /api/admin/impersonations/:id/end` - End a session early and notify the user (admins)
+//! - `GET|POST /api/admin/policies` - Policy versions with acceptance counts, or publish the next version (admins)
+//! - `GET /api/admin/policies/:id/acceptances` - Acceptance records of a version for compliance (admins)
+//! - `GET /api/admin/resource-links` - Checked resource links, e.g. broken ones (admins)
+//! - `GET /api/admin/resources/usage?days=30` - Most clicked learning resources (admins)
+//! - `PUT /api/admin/resources/:id/affiliate` - Set affiliate parameters added to a resource's URL on redirect (admins)
+//! - `GET|POST /api/admin/kb/articles` - All knowledge base articles, or create one (admins)
+//! - `PUT|DELETE /api/admin/kb/articles/:id` - Replace or delete a knowledge base article (admins)
+//! - `GET|PUT /api/admin/rate-limits` - List tenant rate limit policies, or set one for an organization and plan (admins)
+//! - `DELETE /api/admin/rate-limits/:id` - Remove a rate limit policy (admins)
+//! - `GET /api/admin/load` - In-flight, queued, and shed requests per traffic class (admins)
+//! - `GET /api/admin/evals` - Recent AI evaluation runs and daily score trends per provider and prompt version (admins)
+//! - `GET /api/admin/diagnostics` - Resolved config (secrets redacted) and self-checks of the database, migrations, AI keys, and storage (admins)
+//! - `POST /api/employer/company` - Create a company team (employers)
+//! - `GET /api/employer/team` - Team members and pending invitations
+//! - `POST /api/employer/team/invitations` - Invite a recruiter or viewer (owners)
+//! - `POST /api/employer/team/invitations/accept` - Join a team with an invitation token
+//! - `PUT /api/employer/team/members/:user_id` - Change a seat role (owners)
+//! - `DELETE /api/employer/team/members/:user_id` - Remove a member or leave the team
+//! - `GET /api/employer/team/activity` - Per-seat audit trail (owners)
+//! - `PUT /api/employer/company/profile` - Update the company profile shown on its page (owners)
+//! - `POST /api/ai/employer/branding` - Draft "why work here" content and role pitches with AI (owners, recruiters)
+//! - `GET|PUT /api/employer/company/branding` - Read or edit the branding draft (`PUT`: owners, recruiters)
+//! - `POST /api/employer/company/branding/publish` - Publish the branding draft to the company page (owners)
+//! - `POST /api/applications/:id/review` - Review the company once the application reached the interview stage
+//! - `GET /api/users/me/reviews` - Your company reviews
+//! - `PUT|DELETE /api/reviews/:id` - Edit or delete your review
+//! - `GET /api/employer/reviews` - Your company's reviews, including ones hidden by reports
+//! - `PUT /api/employer/reviews/:id/reply` - Reply to a review of your company (owners)
+//! - `POST /api/interview-experiences` - Share an interview experience; published after moderation
+//! - `PUT|DELETE /api/interview-experiences/:id` - Edit (back to moderation) or delete your interview experience
+//! - `GET /api/users/me/interview-experiences` - Your interview experiences with their moderation status
+//! - `GET /api/admin/interview-experiences` - Interview experiences awaiting moderation (admins)
+//! - `POST /api/admin/interview-experiences/:id/approve|reject` - Publish or reject an interview experience (admins)
+//! - `GET /api/mentors?expertise=` - Active mentors; `GET /api/mentors/:id` - A mentor with their open slots
+//! - `GET|PUT /api/mentors/me` - Your mentor profile and slots
+//! - `POST /api/mentors/me/slots`, `DELETE /api/mentors/me/slots/:id` - Publish or remove session slots
+//! - `GET|PUT|DELETE /api/mentors/me/calendar` - Connect a Google or CalDAV calendar your slots follow
+//! - `GET|POST /api/mentor-bookings` - Your mentor sessions; book a slot (re-checks the mentor's calendar; paid sessions return a checkout URL)
+//! - `GET /api/mentor-bookings/:id`, `POST /api/mentor-bookings/:id/cancel` - A session; cancel it before it starts (refunded per policy)
+//! - `GET /api/mentor-bookings/:id/invite.ics` - Calendar invite for a session, with its meeting link
+//! - `GET|PUT /api/mentor-bookings/:id/notes` - A session's notes and action items; the mentor records notes
+//! - `POST /api/mentor-bookings/:id/action-items` - Assign action items to the session's mentee
+//! - `GET /api/action-items`, `DELETE /api/action-items/:id` - Your action items; remove one you assigned
+//! - `POST /api/action-items/:id/roadmap` - Add an action item to a roadmap phase as a task
+//! - `GET|POST /api/mentors/me/payouts` - Your mentor earnings and payouts; request a payout
+//! - `GET /api/admin/mentor-payouts`, `POST /api/admin/mentor-payouts/:id` - Payouts awaiting an admin; mark one paid or rejected (admin)
+//! - `GET /api/admin/mentor-payments/reconciliation` - Mentor payment, refund, and payout totals with discrepancies (admin)
+//! - `GET|POST /api/api-keys`, `DELETE /api/api-keys/:id` - Your API keys; create one (shown once) or revoke it
+//! - `GET|DELETE /api/sandbox` - What your sandbox holds; purge it now (`X-Api-Key` with the `sandbox` scope)
+//! - `POST /api/sandbox/users`, `POST /api/sandbox/jobs`, `POST /api/sandbox/applications` - Create test users (with a token), jobs, and applications in your sandbox
+//! - `GET|POST /api/disputes` - Disputes you opened or respond to; dispute a mentor session or an application decision
+//! - `GET /api/disputes/:id` - A dispute with its evidence and status history
+//! - `POST /api/disputes/:id/evidence`, `GET /api/disputes/:id/evidence/:evidence_id` - Attach a note or file; download a file
+//! - `POST /api/disputes/:id/withdraw` - Withdraw a dispute you opened
+//! - `GET /api/admin/disputes`, `POST /api/admin/disputes/:id/review`, `POST /api/admin/disputes/:id/resolve` - Dispute queue; take one under review; rule on it, refunding paid sessions as ruled (admin)
+//! - `GET|PUT /api/users/me/debug-capture` - Your consent to debug captures of your AI requests; withdrawing deletes them
+//! - `GET /api/ai/captures`, `POST /api/ai/captures/:id/flag` - Your debug captures; flag a bad generation
+//! - `GET /api/admin/debug-captures`, `GET|DELETE /api/admin/debug-captures/:id` - Flagged captures; inspect or delete one (admin)
+//! - `POST /api/admin/debug-captures/:id/replay` - Re-run a capture against the current prompt version (admin)
+//! - `POST /api/ai/feedback` - Rate an AI result with a thumbs up or down
+//! - `GET|POST /api/admin/prompt-rollouts`, `GET|PUT /api/admin/prompt-rollouts/:id` - Prompt rollouts with candidate and baseline rates; start one; change its traffic share or thresholds (admin)
+//! - `POST /api/admin/prompt-rollouts/:id/promote`, `POST /api/admin/prompt-rollouts/:id/rollback` - Send all or none of the traffic to the candidate (admin)
+//! - `GET /api/employer/verification` - The company's verification status and latest attempt
+//! - `POST /api/employer/verification/email`, `POST /api/employer/verification/email/confirm` - Confirm a work email with an emailed code (owners)
+//! - `POST /api/employer/verification/document` - Upload a registration document (owners)
+//! - `POST /api/employer/verification/submit` - Look up the registration number and submit for review (owners)
+//! - `GET /api/admin/employer-verifications` - Employer verification queue with registry results (admins)
+//! - `GET /api/admin/employer-verifications/:id/document` - Download a registration document (admins)
+//! - `POST /api/admin/employer-verifications/:id/approve|reject` - Verify a company, or reject with a reason (admins)
+//! - `POST /api/ai/employer/job-description` - Draft a posting from a brief with a salary suggestion and language flags (employers)
+//! - `GET|PUT /api/employer/jobs/:id/screening-questions` - Read or replace a posting's screening questions (`PUT`: not viewers)
+//! - `POST /api/ai/employer/jobs/:id/screening-questions` - Suggest screening questions from the posting (not viewers)
+//! - `GET /api/employer/jobs/:id/applicants?sort=score` - Applicants with screening results and explained scores, optionally ranked
+//! - `GET|POST /api/employer/talent-pools` - List or create the company's talent pools (`POST`: not viewers)
+//! - `GET|PUT|DELETE /api/employer/talent-pools/:id` - A pool and its members, filtered with `?tag=` (`PUT`/`DELETE`: not viewers)
+//! - `POST /api/employer/talent-pools/:id/members` - Save a candidate with tags and notes (not viewers)
+//! - `PUT|DELETE /api/employer/talent-pools/:id/members/:candidate_id` - Change or remove a pooled candidate (not viewers)
+//! - `POST /api/employer/talent-pools/:id/invite` - Invite a pool to apply to an open posting (not viewers)
+//! - `GET /api/employer/talent-pools/alerts` - Pooled candidates who gained a pool's skill or a verification
+//! - `POST /api/orgs` - Create an institution organization
+//! - `POST /api/orgs/:id/members` - Add an admin or advisor (org admins)
+//! - `GET|POST /api/orgs/:id/cohorts` - List or create cohorts
+//! - `PUT /api/orgs/:id/cohorts/:cid` - Rename a cohort or set its minimum profile completeness (org admins)
+//! - `GET /api/orgs/:id/cohorts/:cid/members` - Students with profile completeness (org staff)
+//! - `DELETE /api/orgs/:id/cohorts/:cid/members/:user_id` - Remove a student (org admins)
+//! - `GET /api/orgs/:id/placements` - Anonymized placement outcomes, `?format=csv` to export (org staff)
+//! - `GET|PUT /api/orgs/:id/persona` - Career services guidance for mentor and roadmap prompts (staff read, admins write)
+//! - `POST /api/ai/orgs/:id/persona/preview` - Try saved or draft guidance on a sample question or role (org admins)
+//! - `POST /api/orgs/:id/cohorts/:cid/roadmaps/bulk` - Queue a roadmap to one role for every student (org admins)
+//! - `GET /api/orgs/:id/cohorts/:cid/roadmaps/bulk[/:bid]` - Bulk roadmap progress and failed students (org staff)
+//! - `POST /api/orgs/:id/cohorts/:cid/roadmaps/bulk/:bid/retry` - Retry a batch's failed students (org admins)
+//! - `GET /api/orgs/:id/at-risk` - Students at risk under configurable thresholds (org staff)
+//! - `POST /api/orgs/:id/at-risk/nudge` - Notify at-risk students of their next steps (org staff)
+//! - `POST /api/cohorts/join` - Join a cohort with its join code
+//! - `POST /api/orgs/:id/events` - Create a career fair; add `sessions` and `booths` under it (org admins)
+//! - `GET /api/events` - Upcoming career fairs
+//! - `GET /api/events/:id` - Event with sessions, booths, and your RSVP
+//! - `POST|DELETE /api/events/:id/rsvp` - RSVP (with session choices) or cancel
+//! - `GET /api/events/:id/schedule.ics` - Your event schedule as iCalendar
+//! - `POST /api/events/:id/interactions` - Record a booth interaction from a badge scan (recruiters)
+//! - `GET /api/employer/leads` - Career-fair leads for your company
+//! - `POST /api/employer/leads/:id/invite` - Invite a lead to apply; `.../dismiss` to drop it
+//! - `POST /api/employer/applications/:id/offers` - Extend an offer on an application (employers)
+//! - `GET /api/employer/offers` - Offers on your jobs with candidate responses (employers)
+//! - `POST /api/employer/offers/:id/documents` - Attach an offer document (employers)
+//! - `GET /api/applications/:id/offers` - Offers on your application
+//! - `POST /api/offers/:id/accept` - Accept an offer, optionally closing your other applications
+//! - `POST /api/offers/:id/decline` - Decline an offer, optionally with a counter-offer
+//! - `GET /api/offers/:id/documents/:doc_id` - Download an offer document
+//! - `POST /api/offer-documents` - Upload an offer letter or contract (PDF) to ask questions about
+//! - `GET /api/offer-documents` - Your uploaded offer documents
+//! - `DELETE /api/offer-documents/:id` - Delete an offer document and its file
+//! - `POST /api/ai/offer-documents/:id/ask` - Ask a question answered only from that document
+//! - `POST /api/ai/negotiation/start` - Start practising a salary negotiation on one of your offers
+//! - `POST /api/ai/negotiation/reply` - Reply to the recruiter, or end the session for a debrief
+//! - `POST /api/wizards` - Start a multi-step wizard (e.g. `cv_review`)
+//! - `GET /api/wizards` - Your unfinished wizards, to resume on any device
+//! - `GET|PUT /api/wizards/:id` - Wizard with step statuses and outputs, or save step progress
+//! - `POST /api/ai/ask-mentor/stream` - Ask the career mentor, with the answer streamed as Server-Sent Events
+//! - `POST /api/ai/explain-term` - Beginner-friendly definition of a jargon term, from the glossary
+//! - `GET /api/ai/usage` - AI calls and tokens per provider and per action (your own; admins any user's or everyone's)
+//! - `POST /api/ai/simplify` - Rewrite generated text or JSON at a simpler reading level
+//! - `GET /api/ai/conversations` - List your mentor conversations
+//! - `POST /api/ai/conversations` - Start an empty mentor conversation
+//! - `GET /api/ai/conversations/:id` - A mentor conversation with its messages
+//! - `DELETE /api/ai/conversations/:id` - Delete a mentor conversation
+//! - `POST /api/ai/conversations/:id/messages` - Send the next message; the mentor answers with the conversation's history
+//! - `GET /api/ai/conversations/:id/export?format=markdown` - Download a mentor conversation with its summary
+//! - `POST /api/ai/conversations/:id/summarize` - Summarize the advice in a mentor conversation as bullets
+//! - `POST /api/ai/application-package` - Queue an agent that builds a tailored resume, cover letter, and interview questions for a job
+//! - `GET /api/ai/application-packages/:id` - Application package progress and artifacts
+//! - `POST /api/ai/star-stories` - Mine your projects and CV for behavioral interview STAR stories
+//! - `POST /api/ai/star-stories/:id/refine` - Revise a STAR story with your answers or feedback
+//! - `GET /api/star-stories?competency=&status=` - Your STAR stories, polished first
+//! - `PUT|DELETE /api/star-stories/:id` - Edit a story (or save it as polished), or delete it
+//! - `POST /api/ai/preflight` - Check an AI action's input (length, language, token estimate) without running it
+//! - `GET /api/ai/roadmaps/summary` - Unfinished roadmaps with combined weekly hours, availability conflicts, and suggested questions
+//! - `PUT|DELETE /api/ai/roadmaps/:id/primary` - Mark a roadmap primary (it leads digests and suggested questions) or unmark it
+//! - `GET /api/ai/roadmaps/:id/analytics` - Progress over time, phase velocity, and projected vs. target completion (owner or cohort staff)
+//! - `GET /api/ai/roadmaps/:id/resources` - Each phase resource with the user's completed/skipped mark, rating, and note
+//! - `PUT /api/ai/roadmaps/:id/resources/:rid` - Mark a phase resource completed or skipped, with an optional 1-5 rating and note
+//! - `GET /api/ai/roadmaps/:id/export?format=` - Download a roadmap as PDF, large-print PDF, semantic HTML, or plain text
+//! - `GET /api/billing/plans` - List billing plans
+//! - `GET /api/billing/subscription` - Current plan and AI usage
+//! - `POST /api/billing/checkout` - Start a Stripe Checkout session
+//! - `GET /api/users/me/credits` - Credit balance, referral code, and history
+//! - `GET /api/users/me/activity?cursor=&limit=` - Activity feed, newest first, with cursor pagination
+//! - `GET /api/users/me/benchmark?role=` - Anonymous percentiles against peers targeting the same role
 //!
 //! ## Environment Variables
 //!
@@ -87,6 +453,88 @@ pub mod models;
 pub mod security;
 pub mod auth;
 pub mod ai_matching;
+pub mod cache;
+pub mod db;
+pub mod partitioning;
+pub mod scheduler;
+pub mod seed;
+pub mod fixtures;
+pub mod webhooks;
+pub mod billing;
+pub mod credits;
+pub mod teams;
+pub mod email;
+pub mod employer_verification;
+pub mod moderation;
+pub mod geo;
+pub mod internships;
+pub mod cohorts;
+pub mod events;
+pub mod storage;
+pub mod reporting;
+pub mod wizards;
+pub mod links;
+pub mod skills;
+pub mod conversations;
+pub mod retrieval;
+pub mod knowledge_base;
+pub mod mentor_tools;
+pub mod application_packages;
+pub mod cohort_roadmaps;
+pub mod at_risk;
+pub mod benchmarking;
+pub mod encryption;
+pub mod offer_qa;
+pub mod negotiation;
+pub mod star_stories;
+pub mod certifications;
+pub mod integrations;
+pub mod code_practice;
+pub mod readiness;
+pub mod versioning;
+pub mod response;
+pub mod merge_patch;
+pub mod validated_json;
+pub mod curation;
+pub mod activity;
+pub mod diagnostics;
+pub mod load_shedding;
+pub mod rate_limits;
+pub mod tenant_config;
+pub mod semantic_cache;
+pub mod eval;
+pub mod application_timing;
+pub mod roadmap_analytics;
+pub mod portfolio;
+pub mod resource_progress;
+pub mod resource_clicks;
+pub mod branding;
+pub mod company_reviews;
+pub mod interview_experiences;
+pub mod interview_prep;
+pub mod meetings;
+pub mod mentor_calendars;
+pub mod mentor_payments;
+pub mod mentorship;
+pub mod session_notes;
+pub mod disputes;
+pub mod debug_captures;
+pub mod prompt_rollouts;
+pub mod glossary;
+pub mod ai_usage;
+pub mod simplify;
+pub mod export;
+pub mod api_keys;
+pub mod sandbox;
+pub mod inclusive_language;
+pub mod job_writer;
+pub mod screening;
+pub mod applicant_ranking;
+pub mod talent_pools;
+pub mod blocks;
+pub mod reports;
+pub mod policies;
+pub mod impersonation;
 
 /// Application state shared across all request handlers.
 /// 
@@ -97,4 +545,26 @@ pub struct AppState {
     pub db_pool: PgPool,
     /// AI service for intelligent features (optional)
     pub ai_service: Option<std::sync::Arc<ai::AIService>>,
+    /// Short-TTL cache for user profile reads
+    pub user_cache: cache::UserCache,
+    /// Signature and replay verification for inbound webhooks
+    pub webhooks: webhooks::WebhookVerifier,
+    /// Stripe client for premium checkout (optional)
+    pub stripe: Option<std::sync::Arc<billing::StripeClient>>,
+    /// Geocoding provider for job location search (optional)
+    pub geocoder: Option<std::sync::Arc<geo::Geocoder>>,
+    /// File storage for uploaded documents
+    pub storage: storage::Storage,
+    /// Encryption at rest for sensitive uploads (optional)
+    pub document_cipher: Option<std::sync::Arc<encryption::DocumentCipher>>,
+    /// Per-class concurrency limits that shed load when saturated
+    pub load_shedder: load_shedding::LoadShedder,
+    /// Tenant rate limit policies, reloaded when stale
+    pub rate_limits: rate_limits::RateLimitPolicies,
+    /// Tenant configuration such as mentor personas, reloaded when stale
+    pub tenant_configs: tenant_config::TenantConfigs,
+    /// Free/busy lookups on mentors' connected calendars
+    pub calendars: integrations::calendar::CalendarClient,
+    /// Video meeting provisioning for mentor sessions and interviews
+    pub meetings: meetings::MeetingProvider,
 }
@@ -0,0 +1,517 @@
+//! Resource link validation.
+//!
+//! Roadmap resources and learning resources are often AI-suggested, and
+//! their URLs are sometimes dead or hallucinated. [`validate_links`] runs on
+//! the scheduler: it collects every URL from `learning_resources` and from
+//! roadmap phase resources into `resource_links`, then checks a batch of the
+//! least recently checked ones with a HEAD request (falling back to GET for
+//! servers that reject HEAD).
+//!
+//! A 404 or 410 flags a link broken right away; timeouts, connection errors,
+//! and server errors only do after several failed checks in a row, so a
+//! brief outage does not flag a good link. When a link becomes broken and an
+//! AI provider is configured, the AI is asked for a replacement. The
+//! replacement is checked the same way and, if it works, swapped in
+//! everywhere the broken link was used.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{debug, info, warn};
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::AppResult;
+use crate::models::LinkStatus;
+
+/// Links checked per run.
+const BATCH_SIZE: i64 = 100;
+
+/// Failed checks in a row before an unreachable link is flagged broken.
+const FAILURES_BEFORE_BROKEN: i16 = 3;
+
+/// Characters that end a URL embedded in free text.
+const URL_DELIMITERS: &[char] = &['"', '<', '>', '(', ')'];
+
+/// Punctuation dropped from the end of a URL embedded in free text.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?'];
+
+/// Postgres equivalent of [`extract_urls`], used to collect roadmap links.
+const URL_PATTERN: &str = r#"https?://[^\s"<>()]*[^\s"<>().,;:!?]"#;
+
+/// What a check found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkOutcome {
+    /// The server answered; the page exists (possibly behind a login)
+    Reachable,
+    /// The page is gone (404 or 410)
+    Missing,
+    /// No usable answer: timeout, connection error, or server error
+    Failed,
+}
+
+/// Result of checking one link.
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    pub outcome: LinkOutcome,
+    /// Final HTTP status, after redirects
+    pub http_status: Option<i16>,
+    pub error: Option<String>,
+}
+
+impl LinkCheck {
+    fn from_status(status: StatusCode) -> Self {
+        let outcome = match status {
+            s if s.is_success() || s.is_redirection() => LinkOutcome::Reachable,
+            // Bot protection and logins hide pages that exist
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+                LinkOutcome::Reachable
+            }
+            StatusCode::NOT_FOUND | StatusCode::GONE => LinkOutcome::Missing,
+            _ => LinkOutcome::Failed,
+        };
+        Self {
+            outcome,
+            http_status: Some(status.as_u16() as i16),
+            error: (outcome != LinkOutcome::Reachable).then(|| format!("HTTP {}", status)),
+        }
+    }
+}
+
+/// HTTP client for link checks.
+#[derive(Clone)]
+pub struct LinkChecker {
+    client: Client,
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkChecker {
+    /// Create a link checker with a 10 second timeout per request
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Checks one URL, following redirects.
+    ///
+    /// Tries HEAD first and falls back to GET when HEAD is not answered with
+    /// a success, since some servers reject or mishandle HEAD.
+    pub async fn check(&self, url: &str) -> LinkCheck {
+        if let Ok(response) = self.client.head(url).send().await
+            && response.status().is_success()
+        {
+            return LinkCheck::from_status(response.status());
+        }
+
+        match self.client.get(url).send().await {
+            Ok(response) => LinkCheck::from_status(response.status()),
+            Err(e) => LinkCheck {
+                outcome: LinkOutcome::Failed,
+                http_status: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// A tracked link, as listed for admins.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ResourceLink {
+    pub url: String,
+    pub status: LinkStatus,
+    pub http_status: Option<i16>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: i16,
+    pub checked_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Working link that replaced this one
+    pub replacement_url: Option<String>,
+    pub replaced_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Counts from one validation run.
+#[derive(Debug, Default)]
+pub struct ValidationRun {
+    pub checked: usize,
+    /// Links newly flagged broken
+    pub broken: usize,
+    /// Broken links swapped for a working replacement
+    pub replaced: usize,
+}
+
+/// Byte ranges of the `http(s)://` URLs in `text`.
+fn url_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find("http") {
+        let start = offset + found;
+        let candidate = &text[start..];
+        let scheme_len = if candidate.starts_with("https://") {
+            8
+        } else if candidate.starts_with("http://") {
+            7
+        } else {
+            offset = start + 4;
+            continue;
+        };
+
+        let len = candidate
+            .find(|c: char| c.is_whitespace() || URL_DELIMITERS.contains(&c))
+            .unwrap_or(candidate.len());
+        let url = candidate[..len].trim_end_matches(TRAILING_PUNCTUATION);
+        if url.len() > scheme_len {
+            spans.push((start, start + url.len()));
+        }
+        offset = start + len;
+    }
+    spans
+}
+
+/// Finds the `http(s)://` URLs in free text such as a roadmap resource
+/// ("React docs - https://react.dev/learn").
+pub fn extract_urls(text: &str) -> Vec<&str> {
+    url_spans(text).into_iter().map(|(start, end)| &text[start..end]).collect()
+}
+
+/// Replaces every occurrence of the URL `from` in `text`; `None` if absent.
+fn replace_url(text: &str, from: &str, to: &str) -> Option<String> {
+    let spans: Vec<_> = url_spans(text)
+        .into_iter()
+        .filter(|&(start, end)| &text[start..end] == from)
+        .collect();
+    if spans.is_empty() {
+        return None;
+    }
+
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in spans {
+        replaced.push_str(&text[last..start]);
+        replaced.push_str(to);
+        last = end;
+    }
+    replaced.push_str(&text[last..]);
+    Some(replaced)
+}
+
+/// Replaces the URL `from` in a roadmap's phase resources; returns whether
+/// anything changed.
+fn replace_in_roadmap(roadmap: &mut Value, from: &str, to: &str) -> bool {
+    let mut changed = false;
+    let Some(phases) = roadmap.get_mut("phases").and_then(|p| p.as_array_mut()) else {
+        return false;
+    };
+    for phase in phases {
+        let Some(resources) = phase.get_mut("resources").and_then(|r| r.as_array_mut()) else {
+            continue;
+        };
+        for resource in resources {
+            if let Some(text) = resource.as_str()
+                && let Some(replaced) = replace_url(text, from, to)
+            {
+                *resource = Value::String(replaced);
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Which of `urls` are currently flagged broken.
+pub async fn broken_urls(db_pool: &PgPool, urls: &[String]) -> AppResult<HashSet<String>> {
+    if urls.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let broken = sqlx::query_scalar::<_, String>(
+        "SELECT url FROM resource_links WHERE status = 'broken' AND url = ANY($1)",
+    )
+    .bind(urls)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(broken.into_iter().collect())
+}
+
+/// Records links used by learning resources and roadmaps that are not
+/// tracked yet.
+async fn discover_links(db_pool: &PgPool) -> AppResult<u64> {
+    let added = sqlx::query(
+        r#"
+        INSERT INTO resource_links (url)
+        SELECT url FROM learning_resources WHERE url ~ '^https?://'
+        UNION
+        SELECT (regexp_matches(r.resource, $1, 'g'))[1]
+        FROM career_roadmaps cr,
+             jsonb_array_elements(CASE WHEN jsonb_typeof(cr.roadmap_data->'phases') = 'array'
+                                       THEN cr.roadmap_data->'phases' ELSE '[]'::jsonb END) AS p(phase),
+             jsonb_array_elements_text(CASE WHEN jsonb_typeof(p.phase->'resources') = 'array'
+                                            THEN p.phase->'resources' ELSE '[]'::jsonb END) AS r(resource)
+        ON CONFLICT (url) DO NOTHING
+        "#,
+    )
+    .bind(URL_PATTERN)
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+    Ok(added)
+}
+
+/// Checks a batch of links that are due and asks for replacements of links
+/// that became broken.
+///
+/// Unchecked links go first; working links are rechecked weekly and
+/// failing ones daily. Links that were replaced are no longer checked.
+pub async fn validate_links(
+    db_pool: &PgPool,
+    ai_service: Option<&AIService>,
+    checker: &LinkChecker,
+) -> AppResult<ValidationRun> {
+    let discovered = discover_links(db_pool).await?;
+    if discovered > 0 {
+        debug!("Discovered {} new resource links", discovered);
+    }
+
+    let due = sqlx::query_as::<_, (String, LinkStatus)>(
+        r#"
+        SELECT url, status FROM resource_links
+        WHERE replaced_at IS NULL
+          AND (checked_at IS NULL
+               OR checked_at < NOW() - CASE WHEN status = 'ok' THEN INTERVAL '7 days'
+                                            ELSE INTERVAL '1 day' END)
+        ORDER BY checked_at NULLS FIRST
+        LIMIT $1
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut run = ValidationRun::default();
+    for (url, previous) in due {
+        let check = checker.check(&url).await;
+        let status = sqlx::query_scalar::<_, LinkStatus>(
+            r#"
+            UPDATE resource_links
+            SET http_status = $2,
+                last_error = $3,
+                checked_at = NOW(),
+                consecutive_failures = CASE WHEN $4 THEN 0 ELSE consecutive_failures + 1 END,
+                status = CASE
+                    WHEN $4 THEN 'ok'::link_status
+                    WHEN $5 OR consecutive_failures + 1 >= $6 THEN 'broken'::link_status
+                    ELSE status
+                END
+            WHERE url = $1
+            RETURNING status
+            "#,
+        )
+        .bind(&url)
+        .bind(check.http_status)
+        .bind(&check.error)
+        .bind(check.outcome == LinkOutcome::Reachable)
+        .bind(check.outcome == LinkOutcome::Missing)
+        .bind(FAILURES_BEFORE_BROKEN)
+        .fetch_one(db_pool)
+        .await?;
+        run.checked += 1;
+
+        if status != LinkStatus::Broken || previous == LinkStatus::Broken {
+            continue;
+        }
+        run.broken += 1;
+        info!("Resource link flagged broken: {} ({})", url, check.error.as_deref().unwrap_or("unknown error"));
+
+        if let Some(ai) = ai_service
+            && let Some(replacement) = suggest_replacement(db_pool, ai, checker, &url).await?
+        {
+            apply_replacement(db_pool, &url, &replacement).await?;
+            info!("Replaced broken resource link {} with {}", url, replacement.url);
+            run.replaced += 1;
+        }
+    }
+
+    if run.broken > 0 || run.replaced > 0 {
+        info!(
+            "Link validation: {} checked, {} newly broken, {} replaced",
+            run.checked, run.broken, run.replaced
+        );
+    }
+    Ok(run)
+}
+
+/// A replacement link that passed a check.
+struct Replacement {
+    url: String,
+    http_status: Option<i16>,
+}
+
+/// Asks the AI for a replacement and checks it; `None` if the AI has none
+/// or its suggestion does not work.
+async fn suggest_replacement(
+    db_pool: &PgPool,
+    ai: &AIService,
+    checker: &LinkChecker,
+    url: &str,
+) -> AppResult<Option<Replacement>> {
+    // Where the link is used tells the AI what the resource teaches
+    let usage = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT format('Title: %s%sSkills: %s', title, E'\n', array_to_string(related_skills, ', '))
+        FROM learning_resources WHERE url = $1
+        UNION ALL
+        SELECT format('Listed as: %s%sRoadmap for: %s', r.resource, E'\n', cr.target_role)
+        FROM career_roadmaps cr,
+             jsonb_array_elements(CASE WHEN jsonb_typeof(cr.roadmap_data->'phases') = 'array'
+                                       THEN cr.roadmap_data->'phases' ELSE '[]'::jsonb END) AS p(phase),
+             jsonb_array_elements_text(CASE WHEN jsonb_typeof(p.phase->'resources') = 'array'
+                                            THEN p.phase->'resources' ELSE '[]'::jsonb END) AS r(resource)
+        WHERE strpos(r.resource, $1) > 0
+        LIMIT 1
+        "#,
+    )
+    .bind(url)
+    .fetch_optional(db_pool)
+    .await?
+    .unwrap_or_default();
+
+    let request = AIActionRequest {
+        action: ActionType::SuggestResourceReplacement,
+        provider: AIProvider::Gemini,
+        input: format!("URL: {}\n{}", url, usage),
+        parameters: None,
+        user_id: None,
+        capture: true,
+    };
+
+    let response = match ai.process_action(request).await {
+        Ok(response) if response.success => response.data,
+        Ok(response) => {
+            debug!("AI link replacement unavailable: {:?}", response.message);
+            return Ok(None);
+        }
+        Err(e) => {
+            debug!("AI link replacement unavailable: {}", e);
+            return Ok(None);
+        }
+    };
+
+    let Some(candidate) = response.get("url").and_then(|v| v.as_str()).map(str::trim) else {
+        return Ok(None);
+    };
+    // A single bare URL only, so it can be substituted into free text
+    if candidate == url || extract_urls(candidate) != [candidate] {
+        debug!("Rejected AI link replacement for {}: {:?}", url, candidate);
+        return Ok(None);
+    }
+
+    let check = checker.check(candidate).await;
+    if check.outcome != LinkOutcome::Reachable {
+        warn!(
+            "AI replacement for {} does not work either: {} ({})",
+            url,
+            candidate,
+            check.error.as_deref().unwrap_or("unknown error")
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(Replacement {
+        url: candidate.to_string(),
+        http_status: check.http_status,
+    }))
+}
+
+/// Swaps a broken link for its replacement in learning resources and
+/// roadmaps, and records the replacement.
+async fn apply_replacement(db_pool: &PgPool, broken: &str, replacement: &Replacement) -> AppResult<()> {
+    let mut tx = db_pool.begin().await?;
+
+    sqlx::query("UPDATE learning_resources SET url = $2 WHERE url = $1")
+        .bind(broken)
+        .bind(&replacement.url)
+        .execute(&mut *tx)
+        .await?;
+
+    let roadmaps = sqlx::query_as::<_, (i32, Value)>(
+        "SELECT id, roadmap_data FROM career_roadmaps WHERE strpos(roadmap_data::text, $1) > 0 FOR UPDATE",
+    )
+    .bind(broken)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (id, mut roadmap) in roadmaps {
+        if replace_in_roadmap(&mut roadmap, broken, &replacement.url) {
+            sqlx::query("UPDATE career_roadmaps SET roadmap_data = $2 WHERE id = $1")
+                .bind(id)
+                .bind(&roadmap)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    // Keep resource marks, and their aggregated ratings, with the resource
+    let marks = sqlx::query_as::<_, (i32, i32, i32, String)>(
+        r#"
+        SELECT roadmap_id, phase, resource_index, resource
+        FROM roadmap_resource_progress
+        WHERE strpos(resource, $1) > 0
+        FOR UPDATE
+        "#,
+    )
+    .bind(broken)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (roadmap_id, phase, index, resource) in marks {
+        if let Some(resource) = replace_url(&resource, broken, &replacement.url) {
+            sqlx::query(
+                r#"
+                UPDATE roadmap_resource_progress SET resource = $4, resource_key = $5
+                WHERE roadmap_id = $1 AND phase = $2 AND resource_index = $3
+                "#,
+            )
+            .bind(roadmap_id)
+            .bind(phase)
+            .bind(index)
+            .bind(&resource)
+            .bind(crate::resource_progress::resource_key(&resource))
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO resource_links (url, status, http_status, checked_at)
+        VALUES ($1, 'ok', $2, NOW())
+        ON CONFLICT (url) DO UPDATE
+        SET status = 'ok', http_status = EXCLUDED.http_status, last_error = NULL,
+            consecutive_failures = 0, checked_at = NOW()
+        "#,
+    )
+    .bind(&replacement.url)
+    .bind(replacement.http_status)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE resource_links SET replacement_url = $2, replaced_at = NOW() WHERE url = $1")
+        .bind(broken)
+        .bind(&replacement.url)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Backpressure and load shedding.
+//!
+//! Requests are grouped into traffic classes, each with its own concurrency
+//! limit: AI generation (which holds a provider call open for seconds),
+//! multipart uploads, and reads. Other writes are not limited. When a class
+//! is at its limit, requests wait in a short queue; once the queue is full,
+//! or a request has waited `LOAD_SHED_QUEUE_TIMEOUT_MS`, it is rejected at
+//! once with `503 Service Unavailable` and a `Retry-After` header instead of
+//! piling onto the database pool until everything times out.
+//!
+//! The AI class is applied to the AI generation routes ahead of the quota
+//! charge, so shed requests are not charged; uploads and reads are
+//! classified for every other route by [`shed`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::debug;
+
+use crate::errors::AppError;
+
+/// Default concurrent AI generation requests.
+const DEFAULT_AI_CONCURRENCY: usize = 16;
+
+/// Default concurrent multipart uploads.
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 8;
+
+/// Default concurrent reads.
+const DEFAULT_READ_CONCURRENCY: usize = 64;
+
+/// Default longest wait in a class's queue before the request is shed.
+const DEFAULT_QUEUE_TIMEOUT_MS: u64 = 2000;
+
+/// Default `Retry-After` sent with shed requests.
+const DEFAULT_RETRY_AFTER_SECS: u64 = 5;
+
+/// A group of requests sharing a concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficClass {
+    /// Routes that call an AI provider
+    Ai,
+    /// `multipart/form-data` requests, e.g. CV and document uploads
+    Upload,
+    /// `GET` and `HEAD` requests
+    Read,
+}
+
+impl TrafficClass {
+    const ALL: [TrafficClass; 3] = [TrafficClass::Ai, TrafficClass::Upload, TrafficClass::Read];
+
+    fn index(self) -> usize {
+        match self {
+            TrafficClass::Ai => 0,
+            TrafficClass::Upload => 1,
+            TrafficClass::Read => 2,
+        }
+    }
+
+    /// The class of a request outside the AI routes, if it is limited.
+    fn of(request: &Request) -> Option<Self> {
+        let multipart = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("multipart/form-data"));
+        if multipart {
+            Some(TrafficClass::Upload)
+        } else if request.method() == Method::GET || request.method() == Method::HEAD {
+            Some(TrafficClass::Read)
+        } else {
+            None
+        }
+    }
+}
+
+/// Concurrency limit and queue of one class.
+#[derive(Debug)]
+struct ClassLimiter {
+    /// `None` when the class is not limited
+    permits: Option<Semaphore>,
+    limit: usize,
+    /// Most requests waiting for a permit at once
+    max_queue: usize,
+    queued: AtomicUsize,
+    shed: AtomicU64,
+}
+
+impl ClassLimiter {
+    fn new(limit: usize) -> Self {
+        Self {
+            permits: (limit > 0).then(|| Semaphore::new(limit)),
+            limit,
+            max_queue: limit,
+            queued: AtomicUsize::new(0),
+            shed: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Current load of one class.
+#[derive(Debug, Serialize)]
+pub struct ClassLoad {
+    pub class: TrafficClass,
+    /// Concurrency limit (0 means unlimited)
+    pub limit: usize,
+    pub in_flight: usize,
+    pub queued: usize,
+    pub max_queue: usize,
+    /// Requests shed since the server started
+    pub shed_total: u64,
+}
+
+/// Per-class concurrency limits shared by every request.
+///
+/// Cloning is cheap; all clones share the same limits and counters.
+#[derive(Debug, Clone)]
+pub struct LoadShedder {
+    classes: Arc<[ClassLimiter; 3]>,
+    queue_timeout: Duration,
+    retry_after_secs: u64,
+}
+
+impl LoadShedder {
+    /// Creates limits from the environment.
+    ///
+    /// Reads `LOAD_SHED_AI_CONCURRENCY` (default: 16),
+    /// `LOAD_SHED_UPLOAD_CONCURRENCY` (default: 8), and
+    /// `LOAD_SHED_READ_CONCURRENCY` (default: 64), where 0 disables the limit;
+    /// `LOAD_SHED_QUEUE_TIMEOUT_MS` (default: 2000); and
+    /// `LOAD_SHED_RETRY_AFTER_SECS` (default: 5). Each class queues at most as
+    /// many requests as its limit.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str, default: T) -> T {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        }
+
+        Self {
+            classes: Arc::new([
+                ClassLimiter::new(var("LOAD_SHED_AI_CONCURRENCY", DEFAULT_AI_CONCURRENCY)),
+                ClassLimiter::new(var("LOAD_SHED_UPLOAD_CONCURRENCY", DEFAULT_UPLOAD_CONCURRENCY)),
+                ClassLimiter::new(var("LOAD_SHED_READ_CONCURRENCY", DEFAULT_READ_CONCURRENCY)),
+            ]),
+            queue_timeout: Duration::from_millis(var("LOAD_SHED_QUEUE_TIMEOUT_MS", DEFAULT_QUEUE_TIMEOUT_MS)),
+            retry_after_secs: var("LOAD_SHED_RETRY_AFTER_SECS", DEFAULT_RETRY_AFTER_SECS),
+        }
+    }
+
+    /// Current load of every class.
+    pub fn load(&self) -> Vec<ClassLoad> {
+        TrafficClass::ALL
+            .into_iter()
+            .map(|class| {
+                let limiter = &self.classes[class.index()];
+                ClassLoad {
+                    class,
+                    limit: limiter.limit,
+                    in_flight: limiter
+                        .permits
+                        .as_ref()
+                        .map_or(0, |p| limiter.limit - p.available_permits()),
+                    queued: limiter.queued.load(Ordering::Relaxed),
+                    max_queue: limiter.max_queue,
+                    shed_total: limiter.shed.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    /// Waits for a slot in `permits`, or returns `None` if the request
+    /// should be shed.
+    async fn acquire<'a>(&self, limiter: &ClassLimiter, permits: &'a Semaphore) -> Option<SemaphorePermit<'a>> {
+        if let Ok(permit) = permits.try_acquire() {
+            return Some(permit);
+        }
+
+        if limiter.queued.fetch_add(1, Ordering::AcqRel) >= limiter.max_queue {
+            limiter.queued.fetch_sub(1, Ordering::AcqRel);
+            limiter.shed.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        let permit = tokio::time::timeout(self.queue_timeout, permits.acquire()).await;
+        limiter.queued.fetch_sub(1, Ordering::AcqRel);
+        match permit {
+            Ok(Ok(permit)) => Some(permit),
+            _ => {
+                limiter.shed.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Runs the request within `class`'s limit, or sheds it.
+    async fn run(&self, class: TrafficClass, request: Request, next: Next) -> Response {
+        let limiter = &self.classes[class.index()];
+        let Some(permits) = limiter.permits.as_ref() else {
+            return next.run(request).await;
+        };
+        match self.acquire(limiter, permits).await {
+            Some(_permit) => next.run(request).await,
+            None => {
+                debug!("Shedding {:?} request to {}", class, request.uri().path());
+                let mut response =
+                    AppError::ServiceUnavailable("The server is busy, please retry shortly".to_string()).into_response();
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(self.retry_after_secs));
+                response
+            }
+        }
+    }
+}
+
+/// Middleware limiting uploads and reads; other writes pass through.
+pub async fn shed(State(shedder): State<LoadShedder>, request: Request, next: Next) -> Response {
+    match TrafficClass::of(&request) {
+        Some(class) => shedder.run(class, request, next).await,
+        None => next.run(request).await,
+    }
+}
+
+/// Middleware limiting the AI generation routes.
+pub async fn shed_ai(State(shedder): State<LoadShedder>, request: Request, next: Next) -> Response {
+    shedder.run(TrafficClass::Ai, request, next).await
+}
@@ -4,9 +4,8 @@
 //! Sets up database connection, logging, and HTTP server.
 
 use std::net::SocketAddr;
-use tracing::{info, error, Level};
+use tracing::{info, warn, error, Level};
 use tracing_subscriber::FmtSubscriber;
-use sqlx::PgPool; 
 use dotenvy::dotenv; 
 use std::env;
 use backend::{AppState, handlers};
@@ -14,6 +13,10 @@ use backend::{AppState, handlers};
 /// Main application entry point.
 /// 
 /// Initializes the database connection, sets up tracing, and starts the HTTP server.
+/// Run with the `seed` argument to populate demo data, or `fixtures [count]`
+/// to generate load-test users, instead of serving. Run with `--check` to
+/// run the self-checks and exit non-zero if any fails, or `eval [label]` to
+/// score the AI providers against the golden fixtures.
 #[tokio::main]
 async fn main() {
     // Load environment variables
@@ -40,18 +43,66 @@ async fn main() {
     }
     
     // Create database connection pool
-    let db_pool = PgPool::connect(&database_url)
+    let db_pool = backend::db::connect(&database_url)
         .await
         .expect("Failed to create database connection pool");
     
     info!("✓ Database connection pool created successfully");
+
+    // `cargo run -- seed` populates the demo dataset and exits
+    if env::args().nth(1).as_deref() == Some("seed") {
+        match backend::seed::run(&db_pool).await {
+            Ok(_) => {
+                info!("✓ Demo data seeded. Log in as {} / {}",
+                    backend::seed::DEMO_JOB_SEEKER_EMAIL, backend::seed::DEMO_PASSWORD);
+                return;
+            }
+            Err(e) => {
+                error!("Seeding failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
     
+    // `cargo run -- fixtures [count]` generates load-test users and exits
+    if env::args().nth(1).as_deref() == Some("fixtures") {
+        let count = env::args()
+            .nth(2)
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(backend::fixtures::DEFAULT_FIXTURE_USERS);
+        let seed = env::var("FIXTURE_SEED").ok().and_then(|s| s.parse().ok()).unwrap_or(42);
+        let output = env::var("FIXTURE_OUTPUT").unwrap_or_else(|_| "loadtest_users.csv".to_string());
+        match backend::fixtures::generate(&db_pool, count, seed, std::path::Path::new(&output)).await {
+            Ok(path) => {
+                info!("✓ Fixture credentials written to {}", path.display());
+                return;
+            }
+            Err(e) => {
+                error!("Fixture generation failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Initialize AI service
     info!("Initializing AI services...");
     let gemini_api_key = env::var("GEMINI_API_KEY").ok();
     let groq_api_key = env::var("GROQ_API_KEY").ok();
-    
-    let ai_service = if gemini_api_key.is_some() || groq_api_key.is_some() {
+    let ollama = backend::ai::ollama::OllamaClient::from_env();
+    let mock_mode = env::var("AI_MOCK_MODE").is_ok_and(|v| v == "true" || v == "1");
+    let is_production = env::var("RUST_ENV").is_ok_and(|v| v == "production");
+    // A stray flag in a production env file must not switch off the real providers
+    if mock_mode && is_production {
+        warn!("AI_MOCK_MODE is ignored in production; using the configured providers");
+    }
+    let mock_mode = mock_mode && !is_production;
+
+    let ai_service = if mock_mode {
+        info!("✓ AI service initialized in mock mode (no external API calls)");
+        Some(backend::ai::AIService::mock(
+            backend::ai::mock::MockConfig::from_env(),
+        ))
+    } else if gemini_api_key.is_some() || groq_api_key.is_some() || ollama.is_some() {
         info!("✓ AI service initialized with available providers");
         if gemini_api_key.is_some() {
             info!("  - Gemini API: enabled");
@@ -59,20 +110,140 @@ async fn main() {
         if groq_api_key.is_some() {
             info!("  - Groq API: enabled");
         }
-        Some(std::sync::Arc::new(backend::ai::AIService::new(
+        if let Some(ollama) = &ollama {
+            info!("  - Ollama: enabled ({})", ollama.model());
+        }
+        Some(backend::ai::AIService::new(
             gemini_api_key,
             groq_api_key,
-        )))
+            ollama,
+        ))
     } else {
         info!("⚠ AI service not configured (no API keys found)");
-        info!("  Set GEMINI_API_KEY or GROQ_API_KEY to enable AI features");
+        info!("  Set GEMINI_API_KEY, GROQ_API_KEY, or OLLAMA_BASE_URL to enable AI features");
         None
     };
-    
+
+    // Charge AI actions against the credits ledger when enabled; always record usage and consented captures,
+    // and route requests to prompts being rolled out
+    let credits_enabled = env::var("CREDITS_ENABLED").is_ok_and(|v| v == "true" || v == "1");
+    let prompt_router = backend::ai::rollout::PromptRouter::new(db_pool.clone());
+    if let Err(e) = prompt_router.refresh().await {
+        error!("Failed to load prompt rollouts: {}", e);
+    }
+    let ai_service = ai_service.map(|service| {
+        let service = if credits_enabled {
+            info!("✓ AI credits ledger enabled");
+            service.with_credits(backend::credits::CreditLedger::new(db_pool.clone()))
+        } else {
+            service
+        };
+        let service = service.with_usage_log(backend::ai::usage::UsageLog::new(db_pool.clone()));
+        let service = service.with_capture_log(backend::ai::capture::CaptureLog::new(db_pool.clone()));
+        let service = service.with_prompt_router(prompt_router.clone());
+        let service = match env::var("AI_PROVIDER_FALLBACK_ORDER") {
+            Ok(order) => service.with_fallback_order(backend::ai::types::AIProvider::parse_order(&order)),
+            Err(_) => service,
+        };
+        info!(
+            "  - Fallback order for auto requests: {:?}",
+            service.fallback_chain()
+        );
+        std::sync::Arc::new(service)
+    });
+
+    // Initialize billing
+    let stripe = env::var("STRIPE_SECRET_KEY").ok().map(|key| {
+        info!("✓ Stripe billing enabled");
+        std::sync::Arc::new(backend::billing::StripeClient::new(key))
+    });
+
+    // Initialize geocoding
+    let geocoder = backend::geo::Geocoder::from_env().map(|geocoder| {
+        info!("✓ Geocoding enabled");
+        std::sync::Arc::new(geocoder)
+    });
+
+    // `cargo run -- --check` runs the self-checks and exits, non-zero on failure
+    if env::args().nth(1).as_deref() == Some("--check") {
+        let storage = backend::storage::Storage::from_env();
+        let report = backend::diagnostics::run(&db_pool, ai_service.as_deref(), &storage).await;
+        for check in &report.checks {
+            match check.status {
+                backend::diagnostics::CheckStatus::Ok => info!("✓ {}: {}", check.name, check.detail),
+                backend::diagnostics::CheckStatus::Warning => warn!("⚠ {}: {}", check.name, check.detail),
+                backend::diagnostics::CheckStatus::Failed => error!("✗ {}: {}", check.name, check.detail),
+            }
+        }
+        if report.healthy {
+            info!("✓ Self-check passed");
+            return;
+        }
+        error!("Self-check failed");
+        std::process::exit(1);
+    }
+
+    // `cargo run -- eval [label]` scores the AI providers and exits, non-zero below the pass rate
+    if env::args().nth(1).as_deref() == Some("eval") {
+        let Some(ai_service) = ai_service.as_deref() else {
+            error!("AI evaluation needs GEMINI_API_KEY, GROQ_API_KEY, OLLAMA_BASE_URL, or AI_MOCK_MODE");
+            std::process::exit(1);
+        };
+        let label = env::args().nth(2);
+        let run = match backend::eval::run(&db_pool, ai_service, label.as_deref()).await {
+            Ok(run) => run,
+            Err(e) => {
+                error!("AI evaluation failed: {}", e);
+                std::process::exit(1);
+            }
+        };
+        match backend::eval::summarize(&db_pool, run.id).await {
+            Ok(summary) => {
+                for row in summary {
+                    let line = format!(
+                        "{} {} {} ({}): {}/{} passed, score {:.2}",
+                        row.provider, row.model, row.action, row.prompt_version, row.passed, row.cases, row.avg_score
+                    );
+                    match row.previous_score {
+                        Some(previous) if row.avg_score < previous => {
+                            warn!("⚠ {} (was {:.2})", line, previous)
+                        }
+                        _ => info!("✓ {}", line),
+                    }
+                }
+            }
+            Err(e) => warn!("Could not summarize eval run {}: {}", run.id, e),
+        }
+        let min_pass_rate = backend::eval::min_pass_rate();
+        if run.pass_rate() >= min_pass_rate {
+            info!("✓ Eval run {} passed {}/{} cases", run.id, run.pass_count, run.case_count);
+            return;
+        }
+        error!(
+            "Eval run {} passed {}/{} cases, below the {:.0}% required",
+            run.id, run.pass_count, run.case_count, min_pass_rate * 100.0
+        );
+        std::process::exit(1);
+    }
+
+    // Start background maintenance tasks
+    backend::scheduler::spawn(db_pool.clone(), geocoder.clone(), ai_service.clone());
+
     // Create application state
     let app_state = AppState { 
         db_pool,
         ai_service,
+        user_cache: backend::cache::UserCache::from_env(),
+        webhooks: backend::webhooks::WebhookVerifier::from_env(),
+        stripe,
+        geocoder,
+        storage: backend::storage::Storage::from_env(),
+        document_cipher: backend::encryption::DocumentCipher::from_env().map(std::sync::Arc::new),
+        load_shedder: backend::load_shedding::LoadShedder::from_env(),
+        rate_limits: backend::rate_limits::RateLimitPolicies::from_env(),
+        tenant_configs: backend::tenant_config::TenantConfigs::from_env(),
+        calendars: backend::integrations::calendar::CalendarClient::from_env(),
+        meetings: backend::meetings::MeetingProvider::from_env(),
     };
 
     // Create router
@@ -97,8 +268,8 @@ async fn main() {
     info!("📚 API Documentation: http://{}/api/", addr);
     info!("Press Ctrl+C to stop the server");
 
-    // Start serving
-    if let Err(e) = axum::serve(listener, app).await {
+    // Start serving; the peer address is recorded with policy acceptances
+    if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
         error!("Server error: {}", e);
     }
     
@@ -0,0 +1,187 @@
+//! Video meeting links for mentor sessions and interviews.
+//!
+//! Confirmed mentor bookings and scheduled interviews get a meeting URL from
+//! the configured provider (`MEETING_PROVIDER`):
+//! - `jitsi` (default) - A room on a Jitsi Meet server (`JITSI_BASE_URL`,
+//!   default `https://meet.jit.si`) with an unguessable name; no API call
+//! - `zoom` - A scheduled meeting created through Zoom's API with a
+//!   Server-to-Server OAuth app (`ZOOM_ACCOUNT_ID`, `ZOOM_CLIENT_ID`,
+//!   `ZOOM_CLIENT_SECRET`)
+//!
+//! The URL is stored on the booking or application and goes into calendar
+//! invites and reminders. Any Zoom-compatible service can be used by
+//! pointing `ZOOM_API_URL` and `ZOOM_OAUTH_URL` at it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Default Jitsi Meet server.
+const DEFAULT_JITSI_BASE_URL: &str = "https://meet.jit.si";
+
+/// Default Zoom REST API base.
+const DEFAULT_ZOOM_API_URL: &str = "https://api.zoom.us/v2";
+
+/// Default Zoom OAuth token endpoint.
+const DEFAULT_ZOOM_OAUTH_URL: &str = "https://zoom.us/oauth/token";
+
+/// A provisioned meeting.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingRoom {
+    pub join_url: String,
+    /// `jitsi` or `zoom`
+    pub provider: &'static str,
+}
+
+#[derive(Clone)]
+struct ZoomCredentials {
+    api_url: String,
+    oauth_url: String,
+    account_id: String,
+    client_id: String,
+    client_secret: String,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Jitsi { base_url: String },
+    Zoom(ZoomCredentials),
+}
+
+/// Creates meeting rooms with the configured provider.
+#[derive(Clone)]
+pub struct MeetingProvider {
+    client: Client,
+    backend: Backend,
+}
+
+impl MeetingProvider {
+    /// A provider creating Jitsi rooms on `base_url`.
+    pub fn jitsi(base_url: impl Into<String>) -> Self {
+        Self::with_backend(Backend::Jitsi {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent(concat!("CareerBridge/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            backend,
+        }
+    }
+
+    /// Creates the provider named by `MEETING_PROVIDER`. Zoom without its
+    /// credentials, or an unknown provider, falls back to Jitsi.
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let jitsi = || Self::jitsi(var("JITSI_BASE_URL").unwrap_or_else(|| DEFAULT_JITSI_BASE_URL.to_string()));
+        match var("MEETING_PROVIDER").as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("jitsi") => jitsi(),
+            Some("zoom") => match (var("ZOOM_ACCOUNT_ID"), var("ZOOM_CLIENT_ID"), var("ZOOM_CLIENT_SECRET")) {
+                (Some(account_id), Some(client_id), Some(client_secret)) => Self::with_backend(Backend::Zoom(ZoomCredentials {
+                    api_url: var("ZOOM_API_URL").unwrap_or_else(|| DEFAULT_ZOOM_API_URL.to_string()),
+                    oauth_url: var("ZOOM_OAUTH_URL").unwrap_or_else(|| DEFAULT_ZOOM_OAUTH_URL.to_string()),
+                    account_id,
+                    client_id,
+                    client_secret,
+                })),
+                _ => {
+                    error!("MEETING_PROVIDER=zoom needs ZOOM_ACCOUNT_ID, ZOOM_CLIENT_ID, and ZOOM_CLIENT_SECRET; using Jitsi");
+                    jitsi()
+                }
+            },
+            Some(other) => {
+                error!("Unknown MEETING_PROVIDER '{}'; using Jitsi", other);
+                jitsi()
+            }
+        }
+    }
+
+    /// Creates a meeting for `topic` starting at `starts_at`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::ExternalServiceError` if the provider's API fails.
+    pub async fn create(&self, topic: &str, starts_at: DateTime<Utc>, minutes: i64) -> AppResult<MeetingRoom> {
+        match &self.backend {
+            Backend::Jitsi { base_url } => Ok(MeetingRoom {
+                join_url: format!("{}/CareerBridge-{}", base_url, Uuid::new_v4().simple()),
+                provider: "jitsi",
+            }),
+            Backend::Zoom(zoom) => {
+                let join_url = self.create_zoom(zoom, topic, starts_at, minutes).await?;
+                info!("Created Zoom meeting for {}", starts_at);
+                Ok(MeetingRoom {
+                    join_url,
+                    provider: "zoom",
+                })
+            }
+        }
+    }
+
+    async fn create_zoom(
+        &self,
+        zoom: &ZoomCredentials,
+        topic: &str,
+        starts_at: DateTime<Utc>,
+        minutes: i64,
+    ) -> AppResult<String> {
+        let failed = |e: String| {
+            error!("Zoom meeting creation failed: {}", e);
+            AppError::ExternalServiceError(format!("Meeting provider error: {}", e))
+        };
+
+        let token: Value = self
+            .client
+            .post(&zoom.oauth_url)
+            .basic_auth(&zoom.client_id, Some(&zoom.client_secret))
+            .query(&[("grant_type", "account_credentials"), ("account_id", zoom.account_id.as_str())])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| failed(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| failed(e.to_string()))?;
+        let access_token = token
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| failed("no access token in OAuth response".to_string()))?;
+
+        let meeting: Value = self
+            .client
+            .post(format!("{}/users/me/meetings", zoom.api_url))
+            .bearer_auth(access_token)
+            .json(&json!({
+                "topic": topic.chars().take(200).collect::<String>(),
+                "type": 2,
+                "start_time": starts_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                "duration": minutes,
+                "timezone": "UTC",
+                "settings": { "waiting_room": true },
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| failed(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| failed(e.to_string()))?;
+        meeting
+            .get("join_url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| failed("no join_url in meeting response".to_string()))
+    }
+}
@@ -0,0 +1,442 @@
+//! Mentor calendar availability sync.
+//!
+//! A mentor connects a Google or CalDAV calendar (see
+//! [`crate::integrations::calendar`]); the credential, a Google refresh
+//! token or a CalDAV app password, is encrypted at rest, so connecting needs
+//! `DOCUMENT_ENCRYPTION_KEY`. [`sync_due`] runs on the scheduler and reads
+//! the calendar's busy times for the coming weeks into
+//! `mentor_busy_periods`: open slots an external event overlaps are
+//! released, and released slots whose event went away open again. Booked
+//! slots are never released; the mentor decides whether to cancel.
+//!
+//! Booking re-checks the calendar live through [`recheck_slot`], falling
+//! back to the last synced busy times when the calendar cannot be reached.
+//! A calendar that rejects its credential stops being synced and the mentor
+//! is asked to connect it again.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::encryption::DocumentCipher;
+use crate::errors::{AppError, AppResult};
+use crate::integrations::calendar::{BusyPeriod, CalendarClient, CalendarSource, FreeBusyOutcome};
+use crate::mentorship::MentorSlot;
+use crate::models::CalendarProvider;
+
+/// Calendars synced per run.
+const BATCH_SIZE: i64 = 50;
+
+/// Minutes before a calendar is synced again.
+const SYNC_AFTER_MINUTES: i32 = 15;
+
+/// Days ahead busy times are read; matches how far ahead slots can start.
+const SYNC_WINDOW_DAYS: i64 = 90;
+
+/// Failed syncs in a row before a calendar stops being synced.
+const MAX_SYNC_FAILURES: i16 = 5;
+
+/// A mentor's connected calendar, without its credential.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CalendarConnection {
+    pub provider: CalendarProvider,
+    pub calendar_url: String,
+    pub username: Option<String>,
+    pub synced_at: Option<DateTime<Utc>>,
+    /// Failed syncs in a row; at 5 the calendar must be connected again
+    pub sync_failures: i16,
+    pub last_error: Option<String>,
+    /// Upcoming busy periods from the last sync
+    pub busy_periods: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a mentor connects.
+#[derive(Debug, Clone, Copy)]
+pub struct CalendarDraft<'a> {
+    pub provider: CalendarProvider,
+    /// CalDAV calendar collection URL, or Google calendar ID
+    pub calendar_url: &'a str,
+    /// CalDAV user name
+    pub username: Option<&'a str>,
+    /// CalDAV password, or the authorization code from Google's consent screen
+    pub secret: &'a str,
+}
+
+#[derive(FromRow)]
+struct StoredCalendar {
+    mentor_id: Uuid,
+    provider: CalendarProvider,
+    calendar_url: String,
+    username: Option<String>,
+    credential: Vec<u8>,
+    sync_failures: i16,
+}
+
+impl StoredCalendar {
+    async fn free_busy(
+        &self,
+        client: &CalendarClient,
+        cipher: &DocumentCipher,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> AppResult<FreeBusyOutcome> {
+        let secret = String::from_utf8(cipher.decrypt(&self.credential)?).map_err(|_| AppError::InternalServerError)?;
+        let source = match self.provider {
+            CalendarProvider::Google => CalendarSource::Google {
+                calendar_id: &self.calendar_url,
+                refresh_token: &secret,
+            },
+            CalendarProvider::Caldav => CalendarSource::Caldav {
+                url: &self.calendar_url,
+                username: self.username.as_deref().unwrap_or_default(),
+                password: &secret,
+            },
+        };
+        Ok(client.free_busy(source, from, to).await)
+    }
+}
+
+const CONNECTION_SELECT: &str = r#"
+    SELECT c.provider, c.calendar_url, c.username, c.synced_at, c.sync_failures, c.last_error,
+           (SELECT COUNT(*) FROM mentor_busy_periods b
+            WHERE b.mentor_id = c.mentor_id AND b.ends_at > NOW()) AS busy_periods,
+           c.created_at
+    FROM mentor_calendars c
+    WHERE c.mentor_id = $1
+"#;
+
+/// The mentor's connected calendar.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no calendar is connected, or an error if
+/// the database operation fails.
+pub async fn connection(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<CalendarConnection> {
+    sqlx::query_as::<_, CalendarConnection>(CONNECTION_SELECT)
+        .bind(mentor_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Connects (or replaces) the mentor's calendar. The calendar is read once
+/// before it is saved, so a wrong URL or credential is reported right away,
+/// and the slots are brought in line with it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The caller has no mentor profile (404)
+/// - Encryption at rest is not configured
+/// - A CalDAV calendar has no user name or a URL that is not HTTPS
+/// - The calendar rejects the credential or cannot be reached
+/// - Database operation fails
+pub async fn connect(
+    db_pool: &PgPool,
+    client: &CalendarClient,
+    cipher: Option<&DocumentCipher>,
+    mentor_id: Uuid,
+    draft: &CalendarDraft<'_>,
+) -> AppResult<CalendarConnection> {
+    let is_mentor = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM mentor_profiles WHERE user_id = $1)")
+        .bind(mentor_id)
+        .fetch_one(db_pool)
+        .await?;
+    if !is_mentor {
+        return Err(AppError::NotFound);
+    }
+    let cipher = cipher.ok_or_else(|| {
+        AppError::ConfigurationError("Calendar sync requires DOCUMENT_ENCRYPTION_KEY".to_string())
+    })?;
+
+    let calendar_url = draft.calendar_url.trim();
+    let (username, credential) = match draft.provider {
+        CalendarProvider::Google => {
+            let refresh_token = client
+                .exchange_google_code(draft.secret.trim())
+                .await
+                .map_err(AppError::BadRequest)?;
+            (None, refresh_token)
+        }
+        CalendarProvider::Caldav => {
+            let username = draft
+                .username
+                .map(str::trim)
+                .filter(|u| !u.is_empty())
+                .ok_or_else(|| AppError::ValidationError("CalDAV calendars need a username".to_string()))?;
+            if url::Url::parse(calendar_url).map(|u| u.scheme() != "https").unwrap_or(true) {
+                return Err(AppError::ValidationError(
+                    "The CalDAV calendar URL must be an https:// URL".to_string(),
+                ));
+            }
+            (Some(username.to_string()), draft.secret.to_string())
+        }
+    };
+
+    let stored = StoredCalendar {
+        mentor_id,
+        provider: draft.provider,
+        calendar_url: calendar_url.to_string(),
+        username,
+        credential: cipher.encrypt(credential.as_bytes())?,
+        sync_failures: 0,
+    };
+    let now = Utc::now();
+    let busy = match stored
+        .free_busy(client, cipher, now, now + Duration::days(SYNC_WINDOW_DAYS))
+        .await?
+    {
+        FreeBusyOutcome::Fetched(busy) => busy,
+        FreeBusyOutcome::Unauthorized(reason) | FreeBusyOutcome::Unavailable(reason) => {
+            return Err(AppError::BadRequest(format!("Could not read the calendar: {}", reason)));
+        }
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_calendars (mentor_id, provider, calendar_url, username, credential)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (mentor_id) DO UPDATE
+        SET provider = EXCLUDED.provider, calendar_url = EXCLUDED.calendar_url,
+            username = EXCLUDED.username, credential = EXCLUDED.credential,
+            sync_failures = 0, last_error = NULL, created_at = NOW()
+        "#,
+    )
+    .bind(mentor_id)
+    .bind(stored.provider)
+    .bind(&stored.calendar_url)
+    .bind(&stored.username)
+    .bind(&stored.credential)
+    .execute(db_pool)
+    .await?;
+    apply(db_pool, mentor_id, &busy).await?;
+    info!("Mentor {} connected a {:?} calendar", mentor_id, draft.provider);
+    connection(db_pool, mentor_id).await
+}
+
+/// Disconnects the mentor's calendar, forgets its busy times, and reopens
+/// the upcoming slots it had released.
+///
+/// # Errors
+///
+/// Returns an error if no calendar is connected (404) or the database
+/// operation fails.
+pub async fn disconnect(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<()> {
+    let mut tx = db_pool.begin().await?;
+    let deleted = sqlx::query("DELETE FROM mentor_calendars WHERE mentor_id = $1")
+        .bind(mentor_id)
+        .execute(&mut *tx)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    sqlx::query("DELETE FROM mentor_busy_periods WHERE mentor_id = $1")
+        .bind(mentor_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "UPDATE mentor_slots SET status = 'open' WHERE mentor_id = $1 AND status = 'released' AND starts_at > NOW()",
+    )
+    .bind(mentor_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Replaces the mentor's busy times and releases or reopens slots to match.
+async fn apply(db_pool: &PgPool, mentor_id: Uuid, busy: &[BusyPeriod]) -> AppResult<()> {
+    let starts: Vec<DateTime<Utc>> = busy.iter().map(|b| b.starts_at).collect();
+    let ends: Vec<DateTime<Utc>> = busy.iter().map(|b| b.ends_at).collect();
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query("DELETE FROM mentor_busy_periods WHERE mentor_id = $1")
+        .bind(mentor_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_busy_periods (mentor_id, starts_at, ends_at)
+        SELECT $1, t.starts_at, t.ends_at FROM unnest($2::timestamptz[], $3::timestamptz[]) AS t(starts_at, ends_at)
+        "#,
+    )
+    .bind(mentor_id)
+    .bind(&starts)
+    .bind(&ends)
+    .execute(&mut *tx)
+    .await?;
+    let changed = sqlx::query(
+        r#"
+        UPDATE mentor_slots s
+        SET status = CASE WHEN s.status = 'open' THEN 'released'::mentor_slot_status
+                          ELSE 'open'::mentor_slot_status END
+        WHERE s.mentor_id = $1 AND s.starts_at > NOW() AND s.status IN ('open', 'released')
+          AND (s.status = 'open') = EXISTS (
+              SELECT 1 FROM mentor_busy_periods b
+              WHERE b.mentor_id = s.mentor_id AND b.starts_at < s.ends_at AND s.starts_at < b.ends_at
+          )
+        "#,
+    )
+    .bind(mentor_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE mentor_calendars SET synced_at = NOW(), sync_failures = 0, last_error = NULL WHERE mentor_id = $1",
+    )
+    .bind(mentor_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    if changed.rows_affected() > 0 {
+        info!("Calendar sync changed {} slots of mentor {}", changed.rows_affected(), mentor_id);
+    }
+    Ok(())
+}
+
+async fn record_failure(db_pool: &PgPool, calendar: &StoredCalendar, error: &str, failures: i16) -> AppResult<()> {
+    sqlx::query("UPDATE mentor_calendars SET synced_at = NOW(), sync_failures = $2, last_error = $3 WHERE mentor_id = $1")
+        .bind(calendar.mentor_id)
+        .bind(failures)
+        .bind(error)
+        .execute(db_pool)
+        .await?;
+    if failures >= MAX_SYNC_FAILURES && calendar.sync_failures < MAX_SYNC_FAILURES {
+        sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_calendar')")
+            .bind(calendar.mentor_id)
+            .bind("Reconnect your calendar")
+            .bind(format!(
+                "We could not read your calendar ({}), so your session slots no longer follow it. Connect it again to resume syncing.",
+                error
+            ))
+            .execute(db_pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Whether the mentor is still free for a slot about to be booked. With a
+/// calendar connected, it is read live for the slot's time; if it cannot be
+/// read, the busy times of the last sync decide. A slot found busy is
+/// released.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn recheck_slot(
+    db_pool: &PgPool,
+    client: &CalendarClient,
+    cipher: Option<&DocumentCipher>,
+    slot: &MentorSlot,
+) -> AppResult<bool> {
+    let calendar = sqlx::query_as::<_, StoredCalendar>(
+        r#"
+        SELECT mentor_id, provider, calendar_url, username, credential, sync_failures
+        FROM mentor_calendars WHERE mentor_id = $1 AND sync_failures < $2
+        "#,
+    )
+    .bind(slot.mentor_id)
+    .bind(MAX_SYNC_FAILURES)
+    .fetch_optional(db_pool)
+    .await?;
+
+    let live = match (&calendar, cipher) {
+        (Some(calendar), Some(cipher)) => {
+            match calendar.free_busy(client, cipher, slot.starts_at, slot.ends_at).await {
+                Ok(FreeBusyOutcome::Fetched(busy)) => Some(busy.iter().any(|b| b.overlaps(slot.starts_at, slot.ends_at))),
+                Ok(FreeBusyOutcome::Unauthorized(reason) | FreeBusyOutcome::Unavailable(reason)) => {
+                    warn!("Booking-time calendar check for mentor {} failed: {}", slot.mentor_id, reason);
+                    None
+                }
+                Err(e) => {
+                    warn!("Booking-time calendar check for mentor {} failed: {}", slot.mentor_id, e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let busy = match live {
+        Some(busy) => busy,
+        None => {
+            sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM mentor_busy_periods
+                    WHERE mentor_id = $1 AND starts_at < $3 AND $2 < ends_at
+                )
+                "#,
+            )
+            .bind(slot.mentor_id)
+            .bind(slot.starts_at)
+            .bind(slot.ends_at)
+            .fetch_one(db_pool)
+            .await?
+        }
+    };
+
+    if busy {
+        sqlx::query("UPDATE mentor_slots SET status = 'released' WHERE id = $1 AND status = 'open'")
+            .bind(slot.id)
+            .execute(db_pool)
+            .await?;
+        info!("Released slot {} of mentor {}: busy on their calendar", slot.id, slot.mentor_id);
+    }
+    Ok(!busy)
+}
+
+/// Syncs a batch of calendars not synced in the last 15 minutes. Returns
+/// how many were synced.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be loaded. Failures for a single
+/// calendar are recorded on it and logged.
+pub async fn sync_due(db_pool: &PgPool, client: &CalendarClient, cipher: &DocumentCipher) -> AppResult<usize> {
+    let batch = sqlx::query_as::<_, StoredCalendar>(
+        r#"
+        SELECT mentor_id, provider, calendar_url, username, credential, sync_failures
+        FROM mentor_calendars
+        WHERE sync_failures < $1
+          AND (synced_at IS NULL OR synced_at < NOW() - make_interval(mins => $2))
+        ORDER BY synced_at NULLS FIRST
+        LIMIT $3
+        "#,
+    )
+    .bind(MAX_SYNC_FAILURES)
+    .bind(SYNC_AFTER_MINUTES)
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut synced = 0;
+    let now = Utc::now();
+    for calendar in &batch {
+        let outcome = calendar
+            .free_busy(client, cipher, now, now + Duration::days(SYNC_WINDOW_DAYS))
+            .await;
+        let result = match outcome {
+            Ok(FreeBusyOutcome::Fetched(busy)) => {
+                synced += 1;
+                apply(db_pool, calendar.mentor_id, &busy).await
+            }
+            // A revoked grant or changed password will not come back
+            Ok(FreeBusyOutcome::Unauthorized(reason)) => {
+                record_failure(db_pool, calendar, &reason, MAX_SYNC_FAILURES).await
+            }
+            Ok(FreeBusyOutcome::Unavailable(reason)) => {
+                record_failure(db_pool, calendar, &reason, calendar.sync_failures.saturating_add(1)).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Failed to sync calendar of mentor {}: {}", calendar.mentor_id, e);
+        }
+    }
+    if !batch.is_empty() {
+        info!("Synced {} of {} mentor calendars", synced, batch.len());
+    }
+    Ok(synced)
+}
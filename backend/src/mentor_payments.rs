@@ -0,0 +1,1023 @@
+//! Payments for paid mentor sessions.
+//!
+//! A mentor with a session price gets paid through Stripe Checkout when a
+//! mentee books: the booking waits in `pending_payment` until the checkout
+//! completes, and is cancelled and its slot reopened if the checkout
+//! expires. The payment is then held until the session is completed -
+//! recorded by the mentor's session notes, or [`RELEASE_AFTER_HOURS`] after
+//! it ended without a cancellation - and released to the mentor's balance,
+//! less the platform fee.
+//!
+//! Cancellation policy for a held payment:
+//! - Cancelled by the mentor, or by the mentee at least
+//!   [`FULL_REFUND_HOURS`] before the session - refunded in full
+//! - Cancelled by the mentee later - [`LATE_REFUND_PERCENT`] refunded, the
+//!   rest released to the mentor
+//!
+//! A dispute over a session (see [`crate::disputes`]) freezes its payment,
+//! held or released but not yet paid out, until an admin rules on it.
+//!
+//! Mentors withdraw their released balance through payout requests, which
+//! admins mark paid (with the transfer reference) or reject. [`reconciliation`]
+//! totals the money flows for a period and lists records whose payment,
+//! booking, and payout states disagree.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgExecutor, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::billing::{PaymentRequest, StripeClient};
+use crate::errors::{AppError, AppResult};
+use crate::mentorship::{self, MentorBooking};
+use crate::models::{MentorPaymentStatus, MentorPayoutStatus};
+
+/// Checkout metadata `kind` of mentor session payments.
+pub const CHECKOUT_KIND: &str = "mentor_booking";
+
+/// Share of each payment the platform keeps.
+const PLATFORM_FEE_PERCENT: i64 = 15;
+
+/// Smallest balance a payout can be requested for, in minor units.
+const MIN_PAYOUT_CENTS: i64 = 2000;
+
+/// Hours before a session a mentee can cancel with a full refund.
+pub const FULL_REFUND_HOURS: i64 = 24;
+
+/// Share refunded when a mentee cancels later than that.
+pub const LATE_REFUND_PERCENT: i64 = 50;
+
+/// Hours after a session ends its payment is released without notes.
+pub const RELEASE_AFTER_HOURS: i32 = 24;
+
+/// Minutes a checkout page stays payable (Stripe's minimum).
+const CHECKOUT_EXPIRY_MINUTES: i64 = 30;
+
+/// Minutes after its checkout expired a payment still pending is expired
+/// here, in case Stripe's webhook never arrived.
+const STALE_PENDING_GRACE_MINUTES: i32 = 30;
+
+/// A payment for a mentor session.
+#[derive(Debug, Clone, FromRow)]
+struct MentorPayment {
+    id: i32,
+    booking_id: i32,
+    mentee_id: Uuid,
+    amount_cents: i32,
+    refunded_cents: i32,
+    currency: String,
+    status: MentorPaymentStatus,
+    released_at: Option<DateTime<Utc>>,
+    stripe_checkout_session_id: Option<String>,
+    stripe_payment_intent_id: Option<String>,
+}
+
+const PAYMENT_SELECT: &str = r#"
+    SELECT id, booking_id, mentee_id, amount_cents, refunded_cents, currency, status, released_at,
+           stripe_checkout_session_id, stripe_payment_intent_id
+    FROM mentor_payments
+"#;
+
+/// A mentor's money in one currency, in minor units.
+#[derive(Debug, Serialize, FromRow)]
+pub struct MentorBalance {
+    pub currency: String,
+    /// Earnings from paid sessions not yet completed, or under dispute
+    pub held_cents: i64,
+    /// Released earnings not yet claimed by a payout
+    pub available_cents: i64,
+    /// Claimed by payouts awaiting an admin
+    pub requested_cents: i64,
+    pub paid_out_cents: i64,
+}
+
+/// A mentor's request to withdraw their balance.
+#[derive(Debug, Serialize, FromRow)]
+pub struct MentorPayout {
+    pub id: i32,
+    pub mentor_id: Uuid,
+    pub mentor_name: String,
+    pub mentor_email: String,
+    pub amount_cents: i32,
+    pub currency: String,
+    pub status: MentorPayoutStatus,
+    /// Transfer reference, once paid
+    pub reference: Option<String>,
+    pub note: Option<String>,
+    /// Session payments the payout covers
+    pub payments: i64,
+    pub requested_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+const PAYOUT_SELECT: &str = r#"
+    SELECT o.id, o.mentor_id, u.full_name AS mentor_name, u.email AS mentor_email, o.amount_cents,
+           o.currency, o.status, o.reference, o.note,
+           (SELECT COUNT(*) FROM mentor_payments p WHERE p.payout_id = o.id) AS payments,
+           o.requested_at, o.processed_at
+    FROM mentor_payouts o
+    JOIN users u ON u.id = o.mentor_id
+"#;
+
+/// Money flows in one currency over a reconciliation period, in minor units.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CurrencyTotals {
+    pub currency: String,
+    /// Payments collected from mentees
+    pub payments: i64,
+    pub collected_cents: i64,
+    pub refunded_cents: i64,
+    /// Collected and still held for upcoming or disputed sessions
+    pub held_cents: i64,
+    pub platform_fee_cents: i64,
+    /// Released to mentors' balances
+    pub mentor_earnings_cents: i64,
+    /// Payouts paid in the period
+    pub paid_out_cents: i64,
+    /// Payouts awaiting an admin, whenever requested
+    pub pending_payout_cents: i64,
+}
+
+/// A record whose states disagree.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Discrepancy {
+    /// `held_on_cancelled_booking`, `released_on_cancelled_booking`,
+    /// `refunded_on_live_booking`, `paid_without_payment_intent`,
+    /// `stale_pending_payment`, `disputed_without_open_dispute`, or
+    /// `payout_amount_mismatch`
+    pub kind: String,
+    pub payment_id: Option<i32>,
+    pub payout_id: Option<i32>,
+    pub booking_id: Option<i32>,
+    pub detail: String,
+}
+
+/// Reconciliation of mentor session payments for a period.
+#[derive(Debug, Serialize)]
+pub struct ReconciliationReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub totals: Vec<CurrencyTotals>,
+    /// Current inconsistencies, regardless of the period
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// The platform's share of a payment.
+fn platform_fee(amount_cents: i32) -> i32 {
+    (i64::from(amount_cents) * PLATFORM_FEE_PERCENT / 100) as i32
+}
+
+/// An amount in minor units as `12.50 USD`.
+fn format_amount(cents: i64, currency: &str) -> String {
+    format!("{}.{:02} {}", cents / 100, cents % 100, currency.to_uppercase())
+}
+
+async fn notify(db_pool: &PgPool, user_id: Uuid, title: &str, message: &str, kind: &str) -> AppResult<()> {
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, $4)")
+        .bind(user_id)
+        .bind(title)
+        .bind(message)
+        .bind(kind)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// The mentor's session price and currency, if their sessions are paid.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn session_price(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<Option<(i32, String)>> {
+    let price = sqlx::query_as::<_, (i32, String)>(
+        "SELECT session_price_cents, currency FROM mentor_profiles WHERE user_id = $1 AND session_price_cents > 0",
+    )
+    .bind(mentor_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(price)
+}
+
+/// Starts checkout for a booking awaiting payment and records the pending
+/// payment with its checkout page.
+///
+/// # Errors
+///
+/// Returns an error if the mentor's sessions are free, Stripe rejects the
+/// checkout, or the database operation fails.
+pub(crate) async fn open_checkout(db_pool: &PgPool, stripe: &StripeClient, booking: &MentorBooking) -> AppResult<()> {
+    let (amount_cents, currency) = session_price(db_pool, booking.mentor_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("This mentor's sessions are free".to_string()))?;
+    let email = sqlx::query_scalar::<_, String>("SELECT email FROM users WHERE id = $1")
+        .bind(booking.mentee_id)
+        .fetch_one(db_pool)
+        .await?;
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3001".to_string());
+
+    let session = stripe
+        .create_payment_session(&PaymentRequest {
+            description: &format!(
+                "Mentor session with {} on {} UTC",
+                booking.mentor_name,
+                booking.starts_at.format("%Y-%m-%d %H:%M")
+            ),
+            amount_cents,
+            currency: &currency,
+            customer_email: &email,
+            kind: CHECKOUT_KIND,
+            reference: &booking.id.to_string(),
+            expires_at: Utc::now() + Duration::minutes(CHECKOUT_EXPIRY_MINUTES),
+            success_url: &format!("{}/mentor-bookings/{}?payment=success", frontend_url, booking.id),
+            cancel_url: &format!("{}/mentor-bookings/{}?payment=cancelled", frontend_url, booking.id),
+        })
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_payments
+            (booking_id, mentor_id, mentee_id, amount_cents, platform_fee_cents, currency,
+             stripe_checkout_session_id, checkout_url)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(booking.id)
+    .bind(booking.mentor_id)
+    .bind(booking.mentee_id)
+    .bind(amount_cents)
+    .bind(platform_fee(amount_cents))
+    .bind(&currency)
+    .bind(&session.id)
+    .bind(&session.url)
+    .execute(db_pool)
+    .await?;
+    info!("Opened checkout {} for mentor booking {}", session.id, booking.id);
+    Ok(())
+}
+
+/// Records a completed checkout: the payment is held and the booking
+/// confirmed. A payment arriving after its booking was cancelled is
+/// refunded in full; if the refund fails, nothing is recorded, so the
+/// retried webhook tries again.
+///
+/// # Errors
+///
+/// Returns an error if the session is unknown, a refund fails, or the
+/// database operation fails.
+pub async fn checkout_completed(db_pool: &PgPool, stripe: Option<&StripeClient>, session: &Value) -> AppResult<()> {
+    let session_id = session["id"]
+        .as_str()
+        .ok_or_else(|| AppError::BadRequest("Checkout session is missing an id".to_string()))?;
+    if session["payment_status"].as_str() != Some("paid") {
+        info!("Checkout {} completed without payment yet", session_id);
+        return Ok(());
+    }
+
+    // The payment and the booking change together. If the booking was
+    // cancelled and the refund fails, nothing is committed: the payment stays
+    // pending or expired, and Stripe's retry of the webhook refunds it.
+    // Redeliveries wait on the payment's row lock until this one is done.
+    let mut tx = db_pool.begin().await?;
+    let payment = sqlx::query_as::<_, MentorPayment>(
+        r#"
+        UPDATE mentor_payments
+        SET status = 'held', stripe_payment_intent_id = $2, paid_at = NOW()
+        WHERE stripe_checkout_session_id = $1 AND status IN ('pending', 'expired')
+        RETURNING id, booking_id, mentee_id, amount_cents, refunded_cents, currency, status, released_at,
+                  stripe_checkout_session_id, stripe_payment_intent_id
+        "#,
+    )
+    .bind(session_id)
+    .bind(session["payment_intent"].as_str())
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(payment) = payment else {
+        info!("Checkout {} already recorded", session_id);
+        return Ok(());
+    };
+
+    let confirmed = sqlx::query(
+        "UPDATE mentor_bookings SET status = 'confirmed', updated_at = NOW() WHERE id = $1 AND status = 'pending_payment'",
+    )
+    .bind(payment.booking_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    if !confirmed {
+        warn!("Mentor booking {} was paid after it was cancelled; refunding", payment.booking_id);
+        let refunded = refund(&mut *tx, stripe, &payment, payment.amount_cents, "refund").await?;
+        sqlx::query("UPDATE mentor_payments SET status = 'refunded' WHERE id = $1")
+            .bind(payment.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return notify(
+            db_pool,
+            payment.mentee_id,
+            "Mentor session payment refunded",
+            &format!(
+                "Your booking had already been cancelled when your payment arrived, so {} was refunded",
+                format_amount(refunded.into(), &payment.currency)
+            ),
+            "mentor_booking",
+        )
+        .await;
+    }
+    tx.commit().await?;
+
+    let booking = mentorship::booking(db_pool, payment.mentee_id, payment.booking_id).await?;
+    mentorship::notify_booked(db_pool, &booking).await?;
+    notify(
+        db_pool,
+        payment.mentee_id,
+        "Mentor session confirmed",
+        &format!(
+            "Your payment of {} was received and your session with {} on {} UTC is confirmed",
+            format_amount(payment.amount_cents.into(), &payment.currency),
+            booking.mentor_name,
+            booking.starts_at.format("%Y-%m-%d %H:%M")
+        ),
+        "mentor_booking",
+    )
+    .await?;
+    info!("Mentor booking {} paid", payment.booking_id);
+    Ok(())
+}
+
+/// Records an expired checkout: the booking is cancelled and its slot
+/// reopened.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn checkout_expired(db_pool: &PgPool, session: &Value) -> AppResult<()> {
+    let booking_id = sqlx::query_scalar::<_, i32>(
+        "UPDATE mentor_payments SET status = 'expired' WHERE stripe_checkout_session_id = $1 AND status = 'pending' RETURNING booking_id",
+    )
+    .bind(session["id"].as_str())
+    .fetch_optional(db_pool)
+    .await?;
+    if let Some(booking_id) = booking_id {
+        release_unpaid_booking(db_pool, booking_id).await?;
+    }
+    Ok(())
+}
+
+/// Cancels a booking whose payment expired and reopens its slot.
+async fn release_unpaid_booking(db_pool: &PgPool, booking_id: i32) -> AppResult<()> {
+    let mut tx = db_pool.begin().await?;
+    let released = sqlx::query_as::<_, (i32, Uuid)>(
+        r#"
+        UPDATE mentor_bookings
+        SET status = 'cancelled', cancel_reason = 'Payment was not completed', updated_at = NOW()
+        WHERE id = $1 AND status = 'pending_payment'
+        RETURNING slot_id, mentee_id
+        "#,
+    )
+    .bind(booking_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((slot_id, mentee_id)) = released else {
+        return Ok(());
+    };
+    mentorship::reopen_slot(&mut tx, slot_id).await?;
+    tx.commit().await?;
+
+    notify(
+        db_pool,
+        mentee_id,
+        "Mentor session not booked",
+        "Checkout was not completed in time, so the session was released. Book it again if it is still open.",
+        "mentor_booking",
+    )
+    .await?;
+    info!("Released unpaid mentor booking {}", booking_id);
+    Ok(())
+}
+
+/// Refunds `amount_cents` of a payment and records it. Returns the amount.
+/// `purpose` tells refunds of the same payment apart, so a retry of one is
+/// not mistaken for another.
+async fn refund(
+    executor: impl PgExecutor<'_>,
+    stripe: Option<&StripeClient>,
+    payment: &MentorPayment,
+    amount_cents: i32,
+    purpose: &str,
+) -> AppResult<i32> {
+    let stripe = stripe.ok_or_else(|| {
+        AppError::ConfigurationError("Refunds need billing to be configured".to_string())
+    })?;
+    let intent = payment.stripe_payment_intent_id.as_deref().ok_or_else(|| {
+        warn!("Mentor payment {} has no payment intent to refund", payment.id);
+        AppError::InternalServerError
+    })?;
+    let refund_id = stripe
+        .refund(intent, amount_cents, &format!("mentor-payment-{}-{}", payment.id, purpose))
+        .await?;
+    sqlx::query(
+        "UPDATE mentor_payments SET refunded_cents = refunded_cents + $2, stripe_refund_id = $3, refunded_at = NOW() WHERE id = $1",
+    )
+    .bind(payment.id)
+    .bind(amount_cents)
+    .bind(&refund_id)
+    .execute(executor)
+    .await?;
+    info!("Refunded {} of mentor payment {}", amount_cents, payment.id);
+    Ok(amount_cents)
+}
+
+/// Settles the payment of a booking being cancelled, per the cancellation
+/// policy. An unpaid checkout is expired; a held payment is refunded in full
+/// or in part, and the mentee told how much.
+///
+/// # Errors
+///
+/// Returns an error if the refund fails or the database operation fails.
+pub(crate) async fn settle_cancellation(
+    db_pool: &PgPool,
+    stripe: Option<&StripeClient>,
+    booking: &MentorBooking,
+    by_mentor: bool,
+) -> AppResult<()> {
+    let payment = sqlx::query_as::<_, MentorPayment>(&format!("{} WHERE booking_id = $1", PAYMENT_SELECT))
+        .bind(booking.id)
+        .fetch_optional(db_pool)
+        .await?;
+    let Some(payment) = payment else {
+        return Ok(());
+    };
+
+    match payment.status {
+        MentorPaymentStatus::Pending => {
+            sqlx::query("UPDATE mentor_payments SET status = 'expired' WHERE id = $1 AND status = 'pending'")
+                .bind(payment.id)
+                .execute(db_pool)
+                .await?;
+            // A payment completed anyway is refunded when its webhook arrives
+            if let (Some(stripe), Some(session_id)) = (stripe, payment.stripe_checkout_session_id.as_deref())
+                && let Err(e) = stripe.expire_checkout_session(session_id).await
+            {
+                warn!("Could not expire checkout {}: {}", session_id, e);
+            }
+        }
+        MentorPaymentStatus::Held => {
+            let full = by_mentor || booking.starts_at - Utc::now() >= Duration::hours(FULL_REFUND_HOURS);
+            let amount = if full {
+                payment.amount_cents
+            } else {
+                (i64::from(payment.amount_cents) * LATE_REFUND_PERCENT / 100) as i32
+            };
+            refund(db_pool, stripe, &payment, amount, "refund").await?;
+            if full {
+                sqlx::query("UPDATE mentor_payments SET status = 'refunded' WHERE id = $1")
+                    .bind(payment.id)
+                    .execute(db_pool)
+                    .await?;
+            } else {
+                // The mentor keeps the rest of a late cancellation
+                sqlx::query(
+                    "UPDATE mentor_payments SET status = 'released', platform_fee_cents = $2, released_at = NOW() WHERE id = $1",
+                )
+                .bind(payment.id)
+                .bind(platform_fee(payment.amount_cents - amount))
+                .execute(db_pool)
+                .await?;
+            }
+            let policy = if full {
+                String::new()
+            } else {
+                format!(
+                    " ({}% for cancellations less than {} hours ahead)",
+                    LATE_REFUND_PERCENT, FULL_REFUND_HOURS
+                )
+            };
+            notify(
+                db_pool,
+                payment.mentee_id,
+                "Mentor session refund",
+                &format!(
+                    "{} was refunded for your cancelled session with {}{}",
+                    format_amount(amount.into(), &payment.currency),
+                    booking.mentor_name,
+                    policy
+                ),
+                "mentor_booking",
+            )
+            .await?;
+        }
+        MentorPaymentStatus::Released
+        | MentorPaymentStatus::Refunded
+        | MentorPaymentStatus::Expired
+        | MentorPaymentStatus::Disputed => {}
+    }
+    Ok(())
+}
+
+/// Freezes the payment of a disputed session, if it is held or released
+/// but not yet claimed by a payout. Returns whether there was one to freeze.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub(crate) async fn hold_for_dispute(db_pool: &PgPool, booking_id: i32) -> AppResult<bool> {
+    let held = sqlx::query(
+        r#"
+        UPDATE mentor_payments SET status = 'disputed'
+        WHERE booking_id = $1 AND (status = 'held' OR (status = 'released' AND payout_id IS NULL))
+        "#,
+    )
+    .bind(booking_id)
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+    if held > 0 {
+        info!("Froze payment of disputed mentor booking {}", booking_id);
+    }
+    Ok(held > 0)
+}
+
+/// Unfreezes a disputed session's payment as it was: released if it had
+/// been, else held until the session completes.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub(crate) async fn lift_dispute_hold(db_pool: &PgPool, booking_id: i32) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE mentor_payments
+        SET status = CASE WHEN released_at IS NULL THEN 'held' ELSE 'released' END::mentor_payment_status
+        WHERE booking_id = $1 AND status = 'disputed'
+        "#,
+    )
+    .bind(booking_id)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+/// Settles a disputed session's payment per the ruling: `refund_cents` go
+/// back to the mentee and the rest is released to the mentor. Returns the
+/// amount refunded, 0 without a frozen payment.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the refund exceeds what is left of the
+/// payment, or an error if the refund or the database operation fails.
+pub(crate) async fn settle_dispute(
+    db_pool: &PgPool,
+    stripe: Option<&StripeClient>,
+    booking_id: i32,
+    dispute_id: i32,
+    refund_cents: i32,
+) -> AppResult<i32> {
+    let payment = sqlx::query_as::<_, MentorPayment>(&format!(
+        "{} WHERE booking_id = $1 AND status = 'disputed'",
+        PAYMENT_SELECT
+    ))
+    .bind(booking_id)
+    .fetch_optional(db_pool)
+    .await?;
+    let Some(payment) = payment else {
+        return Ok(0);
+    };
+    let remaining = payment.amount_cents - payment.refunded_cents;
+    if refund_cents > remaining {
+        return Err(AppError::BadRequest(format!(
+            "At most {} is left to refund",
+            format_amount(remaining.into(), &payment.currency)
+        )));
+    }
+
+    if refund_cents > 0 {
+        refund(db_pool, stripe, &payment, refund_cents, &format!("dispute-{}", dispute_id)).await?;
+    }
+    if refund_cents == remaining {
+        sqlx::query("UPDATE mentor_payments SET status = 'refunded', platform_fee_cents = 0 WHERE id = $1")
+            .bind(payment.id)
+            .execute(db_pool)
+            .await?;
+    } else {
+        sqlx::query(
+            r#"
+            UPDATE mentor_payments
+            SET status = 'released', platform_fee_cents = $2, released_at = COALESCE(released_at, NOW())
+            WHERE id = $1
+            "#,
+        )
+        .bind(payment.id)
+        .bind(platform_fee(remaining - refund_cents))
+        .execute(db_pool)
+        .await?;
+    }
+    info!(
+        "Settled disputed mentor payment {}: {} refunded, released before: {}",
+        payment.id,
+        refund_cents,
+        payment.released_at.is_some()
+    );
+    Ok(refund_cents)
+}
+
+/// What is left of a disputed session's payment to refund, and its
+/// currency, if the payment is frozen.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub(crate) async fn disputed_amount(db_pool: &PgPool, booking_id: i32) -> AppResult<Option<(i32, String)>> {
+    let amount = sqlx::query_as::<_, (i32, String)>(
+        "SELECT amount_cents - refunded_cents, currency FROM mentor_payments WHERE booking_id = $1 AND status = 'disputed'",
+    )
+    .bind(booking_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(amount)
+}
+
+/// Releases held payments of completed sessions, and of sessions that
+/// ended [`RELEASE_AFTER_HOURS`] ago without a cancellation (marking those
+/// completed), and expires checkouts whose webhook never arrived. Returns
+/// the number of payments settled.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn settle_due(db_pool: &PgPool) -> AppResult<usize> {
+    let released = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE mentor_payments p
+        SET status = 'released', released_at = NOW()
+        FROM mentor_bookings b
+        JOIN mentor_slots s ON s.id = b.slot_id
+        WHERE p.booking_id = b.id AND p.status = 'held'
+          AND (b.status = 'completed'
+               OR (b.status = 'confirmed' AND s.ends_at <= NOW() - make_interval(hours => $1)))
+        RETURNING b.id
+        "#,
+    )
+    .bind(RELEASE_AFTER_HOURS)
+    .fetch_all(db_pool)
+    .await?;
+    sqlx::query("UPDATE mentor_bookings SET status = 'completed', updated_at = NOW() WHERE id = ANY($1) AND status = 'confirmed'")
+        .bind(&released)
+        .execute(db_pool)
+        .await?;
+
+    let stale = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE mentor_payments
+        SET status = 'expired'
+        WHERE status = 'pending' AND created_at <= NOW() - make_interval(mins => $1)
+        RETURNING booking_id
+        "#,
+    )
+    .bind(CHECKOUT_EXPIRY_MINUTES as i32 + STALE_PENDING_GRACE_MINUTES)
+    .fetch_all(db_pool)
+    .await?;
+    for booking_id in &stale {
+        release_unpaid_booking(db_pool, *booking_id).await?;
+    }
+
+    let settled = released.len() + stale.len();
+    if settled > 0 {
+        info!(
+            "Released {} mentor payments and expired {} unpaid checkouts",
+            released.len(),
+            stale.len()
+        );
+    }
+    Ok(settled)
+}
+
+/// The mentor's balances, one per currency they were paid in.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn balances(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<Vec<MentorBalance>> {
+    let balances = sqlx::query_as::<_, MentorBalance>(
+        r#"
+        WITH earnings AS (
+            SELECT currency,
+                   SUM(amount_cents - refunded_cents - platform_fee_cents)
+                       FILTER (WHERE status IN ('held', 'disputed')) AS held_cents,
+                   SUM(amount_cents - refunded_cents - platform_fee_cents)
+                       FILTER (WHERE status = 'released' AND payout_id IS NULL) AS available_cents
+            FROM mentor_payments
+            WHERE mentor_id = $1
+            GROUP BY currency
+        ), payouts AS (
+            SELECT currency,
+                   SUM(amount_cents) FILTER (WHERE status = 'requested') AS requested_cents,
+                   SUM(amount_cents) FILTER (WHERE status = 'paid') AS paid_out_cents
+            FROM mentor_payouts
+            WHERE mentor_id = $1
+            GROUP BY currency
+        )
+        SELECT COALESCE(e.currency, o.currency) AS currency,
+               COALESCE(e.held_cents, 0)::BIGINT AS held_cents,
+               COALESCE(e.available_cents, 0)::BIGINT AS available_cents,
+               COALESCE(o.requested_cents, 0)::BIGINT AS requested_cents,
+               COALESCE(o.paid_out_cents, 0)::BIGINT AS paid_out_cents
+        FROM earnings e
+        FULL JOIN payouts o ON o.currency = e.currency
+        ORDER BY 1
+        "#,
+    )
+    .bind(mentor_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(balances)
+}
+
+/// The mentor's payouts, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn payouts(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<Vec<MentorPayout>> {
+    let payouts = sqlx::query_as::<_, MentorPayout>(&format!(
+        "{} WHERE o.mentor_id = $1 ORDER BY o.requested_at DESC LIMIT 100",
+        PAYOUT_SELECT
+    ))
+    .bind(mentor_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(payouts)
+}
+
+async fn payout(db_pool: &PgPool, payout_id: i32) -> AppResult<MentorPayout> {
+    sqlx::query_as::<_, MentorPayout>(&format!("{} WHERE o.id = $1", PAYOUT_SELECT))
+        .bind(payout_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Requests a payout of the mentor's available balance in `currency`,
+/// which may be omitted if they were only paid in one.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the currency is ambiguous or the
+/// balance is below the minimum payout, or an error if the database
+/// operation fails.
+pub async fn request_payout(db_pool: &PgPool, mentor_id: Uuid, currency: Option<&str>) -> AppResult<MentorPayout> {
+    let currency = currency.map(str::to_lowercase);
+    let mut tx = db_pool.begin().await?;
+    let available = sqlx::query_as::<_, (i32, String, i32)>(
+        r#"
+        SELECT id, currency, amount_cents - refunded_cents - platform_fee_cents
+        FROM mentor_payments
+        WHERE mentor_id = $1 AND status = 'released' AND payout_id IS NULL
+          AND ($2::TEXT IS NULL OR currency = $2)
+        FOR UPDATE
+        "#,
+    )
+    .bind(mentor_id)
+    .bind(&currency)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let Some((_, currency, _)) = available.first() else {
+        return Err(AppError::BadRequest("There is no available balance to pay out".to_string()));
+    };
+    if available.iter().any(|(_, c, _)| c != currency) {
+        return Err(AppError::BadRequest(
+            "Your balance is in several currencies; choose which to pay out".to_string(),
+        ));
+    }
+    let total: i64 = available.iter().map(|(_, _, cents)| i64::from(*cents)).sum();
+    if total < MIN_PAYOUT_CENTS {
+        return Err(AppError::BadRequest(format!(
+            "Payouts start at {}; your available balance is {}",
+            format_amount(MIN_PAYOUT_CENTS, currency),
+            format_amount(total, currency)
+        )));
+    }
+
+    let payout_id = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO mentor_payouts (mentor_id, amount_cents, currency) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(mentor_id)
+    .bind(total as i32)
+    .bind(currency)
+    .fetch_one(&mut *tx)
+    .await?;
+    let ids: Vec<i32> = available.iter().map(|(id, _, _)| *id).collect();
+    sqlx::query("UPDATE mentor_payments SET payout_id = $1 WHERE id = ANY($2)")
+        .bind(payout_id)
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    info!("Mentor {} requested payout {} of {}", mentor_id, payout_id, total);
+    payout(db_pool, payout_id).await
+}
+
+/// Payouts awaiting an admin, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn pending_payouts(db_pool: &PgPool) -> AppResult<Vec<MentorPayout>> {
+    let payouts = sqlx::query_as::<_, MentorPayout>(&format!(
+        "{} WHERE o.status = 'requested' ORDER BY o.requested_at LIMIT 200",
+        PAYOUT_SELECT
+    ))
+    .fetch_all(db_pool)
+    .await?;
+    Ok(payouts)
+}
+
+/// Marks a requested payout paid, with the transfer reference, or rejects
+/// it, returning its payments to the mentor's available balance. The mentor
+/// is notified either way.
+///
+/// # Errors
+///
+/// Returns an error if the payout does not exist (404), was already
+/// processed, is paid without a reference, or the database operation fails.
+pub async fn process_payout(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    payout_id: i32,
+    status: MentorPayoutStatus,
+    reference: Option<&str>,
+    note: Option<&str>,
+) -> AppResult<MentorPayout> {
+    let reference = reference.map(str::trim).filter(|r| !r.is_empty());
+    match status {
+        MentorPayoutStatus::Requested => {
+            return Err(AppError::BadRequest("A payout can only be marked paid or rejected".to_string()));
+        }
+        MentorPayoutStatus::Paid if reference.is_none() => {
+            return Err(AppError::BadRequest("A paid payout needs its transfer reference".to_string()));
+        }
+        _ => {}
+    }
+
+    let current = payout(db_pool, payout_id).await?;
+    if current.status != MentorPayoutStatus::Requested {
+        return Err(AppError::BadRequest("This payout was already processed".to_string()));
+    }
+
+    let mut tx = db_pool.begin().await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE mentor_payouts
+        SET status = $2, reference = $3, note = $4, processed_by = $5, processed_at = NOW()
+        WHERE id = $1 AND status = 'requested'
+        "#,
+    )
+    .bind(payout_id)
+    .bind(status)
+    .bind(reference)
+    .bind(note.map(str::trim).filter(|n| !n.is_empty()))
+    .bind(admin_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if updated == 0 {
+        return Err(AppError::BadRequest("This payout was already processed".to_string()));
+    }
+    if status == MentorPayoutStatus::Rejected {
+        sqlx::query("UPDATE mentor_payments SET payout_id = NULL WHERE payout_id = $1")
+            .bind(payout_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    let amount = format_amount(current.amount_cents.into(), &current.currency);
+    let (title, message) = if status == MentorPayoutStatus::Paid {
+        ("Payout sent", format!("Your payout of {} was sent", amount))
+    } else {
+        (
+            "Payout rejected",
+            format!(
+                "Your payout of {} was rejected and returned to your balance{}",
+                amount,
+                note.map(|n| format!(": {}", n.trim())).unwrap_or_default()
+            ),
+        )
+    };
+    notify(db_pool, current.mentor_id, title, &message, "mentor_payout").await?;
+    info!("Admin {} marked mentor payout {} {:?}", admin_id, payout_id, status);
+    payout(db_pool, payout_id).await
+}
+
+/// Totals of payments made and payouts paid between `from` and `to`
+/// (inclusive), per currency, with the current discrepancies.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn reconciliation(db_pool: &PgPool, from: NaiveDate, to: NaiveDate) -> AppResult<ReconciliationReport> {
+    let totals = sqlx::query_as::<_, CurrencyTotals>(
+        r#"
+        WITH payments AS (
+            SELECT currency,
+                   COUNT(*) AS payments,
+                   SUM(amount_cents) AS collected_cents,
+                   SUM(refunded_cents) AS refunded_cents,
+                   SUM(amount_cents - refunded_cents) FILTER (WHERE status IN ('held', 'disputed')) AS held_cents,
+                   SUM(platform_fee_cents) FILTER (WHERE status = 'released') AS platform_fee_cents,
+                   SUM(amount_cents - refunded_cents - platform_fee_cents)
+                       FILTER (WHERE status = 'released') AS mentor_earnings_cents
+            FROM mentor_payments
+            WHERE paid_at::DATE BETWEEN $1 AND $2
+            GROUP BY currency
+        ), payouts AS (
+            SELECT currency,
+                   SUM(amount_cents) FILTER (WHERE status = 'paid' AND processed_at::DATE BETWEEN $1 AND $2)
+                       AS paid_out_cents,
+                   SUM(amount_cents) FILTER (WHERE status = 'requested') AS pending_payout_cents
+            FROM mentor_payouts
+            GROUP BY currency
+        )
+        SELECT COALESCE(p.currency, o.currency) AS currency,
+               COALESCE(p.payments, 0) AS payments,
+               COALESCE(p.collected_cents, 0)::BIGINT AS collected_cents,
+               COALESCE(p.refunded_cents, 0)::BIGINT AS refunded_cents,
+               COALESCE(p.held_cents, 0)::BIGINT AS held_cents,
+               COALESCE(p.platform_fee_cents, 0)::BIGINT AS platform_fee_cents,
+               COALESCE(p.mentor_earnings_cents, 0)::BIGINT AS mentor_earnings_cents,
+               COALESCE(o.paid_out_cents, 0)::BIGINT AS paid_out_cents,
+               COALESCE(o.pending_payout_cents, 0)::BIGINT AS pending_payout_cents
+        FROM payments p
+        FULL JOIN payouts o ON o.currency = p.currency
+        WHERE p.currency IS NOT NULL OR o.paid_out_cents IS NOT NULL OR o.pending_payout_cents IS NOT NULL
+        ORDER BY 1
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(db_pool)
+    .await?;
+
+    let discrepancies = sqlx::query_as::<_, Discrepancy>(
+        r#"
+        SELECT 'held_on_cancelled_booking' AS kind, p.id AS payment_id, NULL::INTEGER AS payout_id,
+               p.booking_id, 'Payment is held for a cancelled session' AS detail
+        FROM mentor_payments p JOIN mentor_bookings b ON b.id = p.booking_id
+        WHERE p.status = 'held' AND b.status = 'cancelled'
+        UNION ALL
+        SELECT 'released_on_cancelled_booking', p.id, NULL, p.booking_id,
+               'Payment was released for a cancelled session without a refund'
+        FROM mentor_payments p JOIN mentor_bookings b ON b.id = p.booking_id
+        WHERE p.status = 'released' AND b.status = 'cancelled' AND p.refunded_cents = 0
+        UNION ALL
+        SELECT 'refunded_on_live_booking', p.id, NULL, p.booking_id,
+               'Payment was refunded but the session is ' || b.status::TEXT
+        FROM mentor_payments p JOIN mentor_bookings b ON b.id = p.booking_id
+        WHERE p.status = 'refunded' AND b.status <> 'cancelled'
+          AND NOT EXISTS (
+              SELECT 1 FROM disputes d
+              WHERE d.booking_id = p.booking_id AND d.status = 'resolved' AND d.refund_cents > 0
+          )
+        UNION ALL
+        SELECT 'paid_without_payment_intent', p.id, NULL, p.booking_id,
+               'Payment is ' || p.status::TEXT || ' but has no Stripe payment intent'
+        FROM mentor_payments p
+        WHERE p.status IN ('held', 'released', 'refunded') AND p.stripe_payment_intent_id IS NULL
+        UNION ALL
+        SELECT 'stale_pending_payment', p.id, NULL, p.booking_id,
+               'Checkout has been pending since ' || TO_CHAR(p.created_at, 'YYYY-MM-DD HH24:MI')
+        FROM mentor_payments p
+        WHERE p.status = 'pending' AND p.created_at <= NOW() - INTERVAL '2 hours'
+        UNION ALL
+        SELECT 'disputed_without_open_dispute', p.id, NULL, p.booking_id,
+               'Payment is frozen but no dispute over the session is open'
+        FROM mentor_payments p
+        WHERE p.status = 'disputed'
+          AND NOT EXISTS (
+              SELECT 1 FROM disputes d
+              WHERE d.booking_id = p.booking_id AND d.status IN ('open', 'under_review')
+          )
+        UNION ALL
+        SELECT 'payout_amount_mismatch', NULL, o.id, NULL,
+               'Payout of ' || o.amount_cents || ' covers payments totalling '
+                   || COALESCE(SUM(p.amount_cents - p.refunded_cents - p.platform_fee_cents), 0)
+        FROM mentor_payouts o LEFT JOIN mentor_payments p ON p.payout_id = o.id
+        WHERE o.status <> 'rejected'
+        GROUP BY o.id
+        HAVING o.amount_cents <> COALESCE(SUM(p.amount_cents - p.refunded_cents - p.platform_fee_cents), 0)
+        "#,
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(ReconciliationReport {
+        from,
+        to,
+        totals,
+        discrepancies,
+    })
+}
@@ -0,0 +1,277 @@
+//! Tools the career mentor can call while answering.
+//!
+//! [`MentorTools`] is the registry of whitelisted tools handed to
+//! [`AIService::process_action_with_tools`](crate::ai::AIService::process_action_with_tools):
+//! only the tools in [`definitions`] are offered to the model, each one runs
+//! scoped to the requesting user, and every call is logged to `ai_tool_calls`.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::ai::tools::{ToolCall, ToolDefinition, ToolExecutor};
+use crate::errors::{AppError, AppResult};
+use crate::models::SkillProgressStatus;
+use crate::skills;
+
+/// Jobs returned by `search_jobs` unless the model asks for fewer.
+const DEFAULT_JOB_RESULTS: i64 = 5;
+
+/// Most jobs `search_jobs` returns.
+const MAX_JOB_RESULTS: i64 = 10;
+
+/// Jobs whose required skills make up the `analyze_skill_gap` target.
+const SKILL_GAP_JOBS: i64 = 5;
+
+/// Tools offered to the mentor. `get_roadmap_progress` comes first as the
+/// one tool that needs no arguments.
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "get_roadmap_progress",
+            description: "Get the user's career roadmaps with their completion progress, and the skills they are currently learning.",
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        ToolDefinition {
+            name: "search_jobs",
+            description: "Search open job postings on the platform by job title, company, or required skill.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Job title, company, or skill keyword" },
+                    "limit": { "type": "integer", "description": "Maximum results, 1-10 (default 5)" }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "analyze_skill_gap",
+            description: "Compare the user's skills with the skills required by job postings for a target role.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "target_role": { "type": "string", "description": "Role to compare against, e.g. Backend Developer" }
+                },
+                "required": ["target_role"]
+            }),
+        },
+    ]
+}
+
+/// Runs mentor tools for one user.
+pub struct MentorTools {
+    db_pool: PgPool,
+    user_id: Uuid,
+    /// Saved conversation the answer belongs to, recorded with each call
+    conversation_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct RoadmapProgress {
+    id: i32,
+    title: String,
+    target_role: String,
+    progress_percentage: Option<i32>,
+    completed_phases: Option<Vec<i32>>,
+    total_phases: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct JobMatch {
+    id: i32,
+    job_title: String,
+    company: String,
+    location: String,
+    is_remote: Option<bool>,
+    experience_level: String,
+    job_type: String,
+    required_skills: Vec<String>,
+    salary_min: Option<i32>,
+    salary_max: Option<i32>,
+}
+
+impl MentorTools {
+    pub fn new(db_pool: PgPool, user_id: Uuid, conversation_id: Option<i32>) -> Self {
+        Self {
+            db_pool,
+            user_id,
+            conversation_id,
+        }
+    }
+
+    async fn roadmap_progress(&self) -> AppResult<Value> {
+        let roadmaps = sqlx::query_as::<_, RoadmapProgress>(
+            r#"
+            SELECT id, title, target_role, progress_percentage, completed_phases,
+                   CASE WHEN jsonb_typeof(roadmap_data->'phases') = 'array'
+                        THEN jsonb_array_length(roadmap_data->'phases') END AS total_phases
+            FROM career_roadmaps
+            WHERE user_id = $1
+            ORDER BY updated_at DESC
+            LIMIT 10
+            "#,
+        )
+        .bind(self.user_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let learning: Vec<String> = skills::load_progress(&self.db_pool, self.user_id)
+            .await?
+            .into_iter()
+            .filter(|p| p.status != SkillProgressStatus::Known)
+            .map(|p| p.skill)
+            .collect();
+
+        Ok(json!({ "roadmaps": roadmaps, "skills_learning": learning }))
+    }
+
+    async fn search_jobs(&self, arguments: &Value) -> AppResult<Value> {
+        let query = required_str(arguments, "query")?;
+        let limit = arguments
+            .get("limit")
+            .and_then(|l| l.as_i64())
+            .unwrap_or(DEFAULT_JOB_RESULTS)
+            .clamp(1, MAX_JOB_RESULTS);
+        let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+
+        let jobs = sqlx::query_as::<_, JobMatch>(
+            r#"
+            SELECT id, job_title, company, location, is_remote,
+                   experience_level::text AS experience_level, job_type::text AS job_type,
+                   required_skills, salary_min, salary_max
+            FROM jobs
+            WHERE moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+              AND (job_title ILIKE $1 OR company ILIKE $1
+                   OR EXISTS (SELECT 1 FROM unnest(required_skills) s WHERE s ILIKE $1))
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(json!({ "jobs": jobs }))
+    }
+
+    /// A compact version of the skill gap endpoint's analysis: known roadmap
+    /// skills count as held, and rusty skills as partial matches.
+    async fn skill_gap(&self, arguments: &Value) -> AppResult<Value> {
+        let target_role = required_str(arguments, "target_role")?;
+
+        let user_skills = sqlx::query_scalar::<_, Vec<String>>("SELECT skills FROM users WHERE id = $1")
+            .bind(self.user_id)
+            .fetch_one(&self.db_pool)
+            .await?;
+        let required_lists = sqlx::query_scalar::<_, Vec<String>>(
+            r#"
+            SELECT required_skills
+            FROM jobs
+            WHERE job_title ILIKE $1
+              AND moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+            LIMIT $2
+            "#,
+        )
+        .bind(format!("%{}%", target_role))
+        .bind(SKILL_GAP_JOBS)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let progress = skills::load_progress(&self.db_pool, self.user_id).await?;
+        let (known, learning): (Vec<_>, Vec<_>) = progress
+            .into_iter()
+            .partition(|p| p.status == SkillProgressStatus::Known);
+        let held: HashSet<String> = skills::merge_skills(&user_skills, known.into_iter().map(|p| p.skill))
+            .iter()
+            .map(|s| s.to_lowercase())
+            .collect();
+        let learning: HashSet<String> = learning.iter().map(|p| p.skill.to_lowercase()).collect();
+
+        let mut required: HashMap<String, String> = HashMap::new();
+        for skill in required_lists.into_iter().flatten() {
+            required.entry(skill.to_lowercase()).or_insert(skill);
+        }
+        let mut matching: Vec<&String> = required.iter().filter(|(k, _)| held.contains(*k)).map(|(_, s)| s).collect();
+        let mut gaps: Vec<&String> = required.iter().filter(|(k, _)| !held.contains(*k)).map(|(_, s)| s).collect();
+        matching.sort();
+        gaps.sort();
+        let learning_gaps: Vec<&&String> = gaps.iter().filter(|s| learning.contains(&s.to_lowercase())).collect();
+
+        let weights = skills::load_weights(&self.db_pool, self.user_id).await?;
+        let match_percentage = if required.is_empty() {
+            0.0
+        } else {
+            let matched: f64 = matching.iter().map(|s| weights.weight(s)).sum();
+            (matched / required.len() as f64 * 1000.0).round() / 10.0
+        };
+
+        Ok(json!({
+            "target_role": target_role,
+            "matching_skills": matching,
+            "skill_gaps": gaps,
+            "gaps_being_learned": learning_gaps,
+            "match_percentage": match_percentage,
+        }))
+    }
+
+    /// Records a call in `ai_tool_calls`. Failures are logged, never raised.
+    async fn log(&self, call: &ToolCall, result: &AppResult<Value>, latency: Duration) {
+        let (output, error) = match result {
+            Ok(output) => (Some(output), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let logged = sqlx::query(
+            r#"
+            INSERT INTO ai_tool_calls (user_id, conversation_id, tool, arguments, result, error, latency_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(self.user_id)
+        .bind(self.conversation_id)
+        .bind(&call.name)
+        .bind(&call.arguments)
+        .bind(output)
+        .bind(error)
+        .bind(latency.as_millis().min(i32::MAX as u128) as i32)
+        .execute(&self.db_pool)
+        .await;
+        if let Err(e) = logged {
+            warn!("Failed to log mentor tool call {}: {}", call.name, e);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolExecutor for MentorTools {
+    fn definitions(&self) -> Vec<ToolDefinition> {
+        definitions()
+    }
+
+    async fn execute(&self, call: &ToolCall) -> AppResult<Value> {
+        let started = std::time::Instant::now();
+        let result = match call.name.as_str() {
+            "get_roadmap_progress" => self.roadmap_progress().await,
+            "search_jobs" => self.search_jobs(&call.arguments).await,
+            "analyze_skill_gap" => self.skill_gap(&call.arguments).await,
+            other => Err(AppError::BadRequest(format!("Unknown tool '{}'", other))),
+        };
+        self.log(call, &result, started.elapsed()).await;
+        result
+    }
+}
+
+/// A required, non-blank string argument.
+fn required_str<'a>(arguments: &'a Value, name: &str) -> AppResult<&'a str> {
+    arguments
+        .get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::ValidationError(format!("{} is required", name)))
+}
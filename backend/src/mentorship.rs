@@ -0,0 +1,668 @@
+//! Human mentor sessions.
+//!
+//! Any user can open a mentor profile and publish session slots; other
+//! users book an open slot with the topic they want to discuss. A slot
+//! holds at most one live booking, and either side can cancel a booking
+//! before it starts, which reopens the slot.
+//!
+//! Mentors who connect a calendar (see [`crate::mentor_calendars`]) only
+//! offer slots their calendar leaves free: the scheduler releases open
+//! slots an external event takes, and [`book`] checks the calendar again
+//! before confirming.
+//!
+//! Mentors may charge for sessions: a paid slot's booking waits for the
+//! mentee's payment (see [`crate::mentor_payments`]) and is confirmed once
+//! it is collected.
+//!
+//! A booking gets a video meeting from the configured provider
+//! (see [`crate::meetings`]). [`send_reminders`] reminds both sides with
+//! the link shortly before the session, provisioning the meeting then if it
+//! failed at booking time.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::billing::StripeClient;
+use crate::encryption::DocumentCipher;
+use crate::errors::{AppError, AppResult};
+use crate::events::CalendarEntry;
+use crate::integrations::calendar::CalendarClient;
+use crate::meetings::MeetingProvider;
+use crate::mentor_calendars;
+use crate::mentor_payments;
+use crate::models::{MentorBookingStatus, MentorPaymentStatus, MentorSlotStatus};
+
+/// Furthest ahead a slot can start.
+const MAX_DAYS_AHEAD: i64 = 90;
+
+/// Minutes before a session its reminder is sent.
+const REMINDER_LEAD_MINUTES: i32 = 60;
+
+/// Reminders sent per run.
+const REMINDER_BATCH_SIZE: i64 = 100;
+
+/// A mentor's public profile.
+#[derive(Debug, Serialize, FromRow)]
+pub struct MentorProfile {
+    pub user_id: Uuid,
+    pub name: String,
+    pub avatar_url: Option<String>,
+    pub headline: String,
+    pub bio: Option<String>,
+    pub expertise: Vec<String>,
+    pub session_minutes: i16,
+    /// Price of a session in the currency's minor unit; 0 for free sessions
+    pub session_price_cents: i32,
+    /// ISO 4217 code, lower case
+    pub currency: String,
+    pub active: bool,
+    /// Whether slots follow the mentor's calendar
+    pub calendar_connected: bool,
+    /// Open slots from now on
+    pub open_slots: i64,
+}
+
+/// Fields of a mentor profile.
+#[derive(Debug, Clone, Copy)]
+pub struct MentorProfileDraft<'a> {
+    pub headline: &'a str,
+    pub bio: Option<&'a str>,
+    pub expertise: &'a [String],
+    pub session_minutes: i16,
+    pub session_price_cents: i32,
+    pub currency: &'a str,
+    pub active: bool,
+}
+
+/// A session slot.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MentorSlot {
+    pub id: i32,
+    pub mentor_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: MentorSlotStatus,
+}
+
+/// A booked session, as either side sees it.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MentorBooking {
+    pub id: i32,
+    pub slot_id: i32,
+    pub mentor_id: Uuid,
+    pub mentor_name: String,
+    pub mentee_id: Uuid,
+    pub mentee_name: String,
+    pub topic: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: MentorBookingStatus,
+    /// Video meeting to join; provisioned when the booking is confirmed
+    pub meeting_url: Option<String>,
+    pub cancelled_by: Option<Uuid>,
+    pub cancel_reason: Option<String>,
+    /// Price paid for the session, for paid sessions
+    pub price_cents: Option<i32>,
+    pub currency: Option<String>,
+    pub payment_status: Option<MentorPaymentStatus>,
+    /// Checkout page the mentee pays on while the booking awaits payment
+    pub checkout_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const PROFILE_SELECT: &str = r#"
+    SELECT p.user_id, u.full_name AS name, u.avatar_url, p.headline, p.bio, p.expertise,
+           p.session_minutes, p.session_price_cents, p.currency, p.active,
+           EXISTS (SELECT 1 FROM mentor_calendars c WHERE c.mentor_id = p.user_id) AS calendar_connected,
+           (SELECT COUNT(*) FROM mentor_slots s
+            WHERE s.mentor_id = p.user_id AND s.status = 'open' AND s.starts_at > NOW()) AS open_slots
+    FROM mentor_profiles p
+    JOIN users u ON u.id = p.user_id
+"#;
+
+const BOOKING_SELECT: &str = r#"
+    SELECT b.id, b.slot_id, b.mentor_id, m.full_name AS mentor_name, b.mentee_id,
+           e.full_name AS mentee_name, b.topic, s.starts_at, s.ends_at, b.status,
+           b.meeting_url, b.cancelled_by, b.cancel_reason, pay.amount_cents AS price_cents,
+           pay.currency, pay.status AS payment_status,
+           CASE WHEN pay.status = 'pending' THEN pay.checkout_url END AS checkout_url, b.created_at
+    FROM mentor_bookings b
+    JOIN mentor_slots s ON s.id = b.slot_id
+    JOIN users m ON m.id = b.mentor_id
+    JOIN users e ON e.id = b.mentee_id
+    LEFT JOIN mentor_payments pay ON pay.booking_id = b.id
+"#;
+
+/// Creates or updates the caller's mentor profile.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn save_profile(db_pool: &PgPool, user_id: Uuid, draft: &MentorProfileDraft<'_>) -> AppResult<MentorProfile> {
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_profiles (user_id, headline, bio, expertise, session_minutes, session_price_cents, currency, active)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id) DO UPDATE
+        SET headline = EXCLUDED.headline, bio = EXCLUDED.bio, expertise = EXCLUDED.expertise,
+            session_minutes = EXCLUDED.session_minutes, session_price_cents = EXCLUDED.session_price_cents,
+            currency = EXCLUDED.currency, active = EXCLUDED.active, updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(draft.headline.trim())
+    .bind(draft.bio.map(str::trim).filter(|b| !b.is_empty()))
+    .bind(draft.expertise)
+    .bind(draft.session_minutes)
+    .bind(draft.session_price_cents)
+    .bind(draft.currency.to_lowercase())
+    .bind(draft.active)
+    .execute(db_pool)
+    .await?;
+    profile(db_pool, user_id).await
+}
+
+/// A mentor's profile.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no mentor profile, or an
+/// error if the database operation fails.
+pub async fn profile(db_pool: &PgPool, mentor_id: Uuid) -> AppResult<MentorProfile> {
+    sqlx::query_as::<_, MentorProfile>(&format!("{} WHERE p.user_id = $1", PROFILE_SELECT))
+        .bind(mentor_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Active mentors, those with open slots first. `expertise` matches a
+/// mentor's expertise tags case-insensitively.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_mentors(
+    db_pool: &PgPool,
+    expertise: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<MentorProfile>> {
+    let mentors = sqlx::query_as::<_, MentorProfile>(&format!(
+        r#"
+        {}
+        WHERE p.active
+          AND ($1::text IS NULL OR EXISTS (
+              SELECT 1 FROM unnest(p.expertise) tag WHERE LOWER(tag) = LOWER($1)))
+        ORDER BY open_slots > 0 DESC, p.updated_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        PROFILE_SELECT
+    ))
+    .bind(expertise.map(str::trim).filter(|e| !e.is_empty()))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(mentors)
+}
+
+/// Adds session slots of the mentor's session length at each of `starts`.
+/// Slots that clash with the mentor's calendar are added as released;
+/// starts that already have a slot are skipped.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The caller has no mentor profile (404)
+/// - A start is in the past or more than 90 days ahead
+/// - Database operation fails
+pub async fn add_slots(db_pool: &PgPool, mentor_id: Uuid, starts: &[DateTime<Utc>]) -> AppResult<Vec<MentorSlot>> {
+    let minutes = sqlx::query_scalar::<_, i16>("SELECT session_minutes FROM mentor_profiles WHERE user_id = $1")
+        .bind(mentor_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let now = Utc::now();
+    if starts
+        .iter()
+        .any(|start| *start <= now || *start > now + Duration::days(MAX_DAYS_AHEAD))
+    {
+        return Err(AppError::ValidationError(format!(
+            "Slots must start in the future and at most {} days ahead",
+            MAX_DAYS_AHEAD
+        )));
+    }
+
+    let ends: Vec<DateTime<Utc>> = starts
+        .iter()
+        .map(|start| *start + Duration::minutes(i64::from(minutes)))
+        .collect();
+    let slots = sqlx::query_as::<_, MentorSlot>(
+        r#"
+        INSERT INTO mentor_slots (mentor_id, starts_at, ends_at, status)
+        SELECT $1, t.starts_at, t.ends_at,
+               CASE WHEN EXISTS (
+                   SELECT 1 FROM mentor_busy_periods b
+                   WHERE b.mentor_id = $1 AND b.starts_at < t.ends_at AND t.starts_at < b.ends_at
+               ) THEN 'released'::mentor_slot_status ELSE 'open'::mentor_slot_status END
+        FROM unnest($2::timestamptz[], $3::timestamptz[]) AS t(starts_at, ends_at)
+        ON CONFLICT (mentor_id, starts_at) DO NOTHING
+        RETURNING id, mentor_id, starts_at, ends_at, status
+        "#,
+    )
+    .bind(mentor_id)
+    .bind(starts)
+    .bind(&ends)
+    .fetch_all(db_pool)
+    .await?;
+    info!("Mentor {} added {} session slots", mentor_id, slots.len());
+    Ok(slots)
+}
+
+/// Removes one of the mentor's slots that is not booked.
+///
+/// # Errors
+///
+/// Returns an error if the slot is not the mentor's (404), is booked, or
+/// the database operation fails.
+pub async fn delete_slot(db_pool: &PgPool, mentor_id: Uuid, slot_id: i32) -> AppResult<()> {
+    let status = sqlx::query_scalar::<_, MentorSlotStatus>(
+        "SELECT status FROM mentor_slots WHERE id = $1 AND mentor_id = $2",
+    )
+    .bind(slot_id)
+    .bind(mentor_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if status == MentorSlotStatus::Booked {
+        return Err(AppError::BadRequest(
+            "This slot is booked; cancel the booking first".to_string(),
+        ));
+    }
+    sqlx::query("DELETE FROM mentor_slots WHERE id = $1 AND status <> 'booked'")
+        .bind(slot_id)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// Upcoming slots of a mentor. Others only see open slots of active
+/// mentors; the mentor sees all of their own.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn upcoming_slots(db_pool: &PgPool, mentor_id: Uuid, own: bool) -> AppResult<Vec<MentorSlot>> {
+    let slots = sqlx::query_as::<_, MentorSlot>(
+        r#"
+        SELECT s.id, s.mentor_id, s.starts_at, s.ends_at, s.status
+        FROM mentor_slots s
+        JOIN mentor_profiles p ON p.user_id = s.mentor_id
+        WHERE s.mentor_id = $1 AND s.starts_at > NOW()
+          AND ($2 OR (p.active AND s.status = 'open'))
+        ORDER BY s.starts_at
+        "#,
+    )
+    .bind(mentor_id)
+    .bind(own)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(slots)
+}
+
+/// Books an open slot. If the mentor has a calendar connected, it is
+/// checked again first; a slot an external event took meanwhile is released
+/// instead of booked. The booking gets a meeting link. A free session is
+/// confirmed and the mentor notified at once; a paid one awaits payment
+/// through the returned booking's `checkout_url`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The slot does not exist or its mentor is inactive (404)
+/// - The slot is the caller's own, already started, or no longer open
+/// - The mentor's calendar shows them busy at that time
+/// - The session is paid and billing is not configured, or checkout cannot be started
+/// - Database operation fails
+#[allow(clippy::too_many_arguments)]
+pub async fn book(
+    db_pool: &PgPool,
+    calendars: &CalendarClient,
+    meetings: &MeetingProvider,
+    stripe: Option<&StripeClient>,
+    cipher: Option<&DocumentCipher>,
+    mentee_id: Uuid,
+    slot_id: i32,
+    topic: &str,
+) -> AppResult<MentorBooking> {
+    let slot = sqlx::query_as::<_, MentorSlot>(
+        r#"
+        SELECT s.id, s.mentor_id, s.starts_at, s.ends_at, s.status
+        FROM mentor_slots s
+        JOIN mentor_profiles p ON p.user_id = s.mentor_id
+        WHERE s.id = $1 AND p.active
+        "#,
+    )
+    .bind(slot_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if slot.mentor_id == mentee_id {
+        return Err(AppError::BadRequest("You cannot book your own slot".to_string()));
+    }
+    if slot.status != MentorSlotStatus::Open || slot.starts_at <= Utc::now() {
+        return Err(AppError::BadRequest("This slot is no longer available".to_string()));
+    }
+    let paid = mentor_payments::session_price(db_pool, slot.mentor_id).await?.is_some();
+    if paid && stripe.is_none() {
+        return Err(AppError::ConfigurationError(
+            "Paid mentor sessions need billing to be configured".to_string(),
+        ));
+    }
+    if !mentor_calendars::recheck_slot(db_pool, calendars, cipher, &slot).await? {
+        return Err(AppError::BadRequest(
+            "The mentor is no longer free at this time; pick another slot".to_string(),
+        ));
+    }
+
+    let mut tx = db_pool.begin().await?;
+    // Guards against a concurrent booking or release of the same slot
+    let taken = sqlx::query(
+        "UPDATE mentor_slots SET status = 'booked' WHERE id = $1 AND status = 'open' AND starts_at > NOW()",
+    )
+    .bind(slot_id)
+    .execute(&mut *tx)
+    .await?;
+    if taken.rows_affected() == 0 {
+        return Err(AppError::BadRequest("This slot is no longer available".to_string()));
+    }
+    let booking_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO mentor_bookings (slot_id, mentor_id, mentee_id, topic, status)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(slot_id)
+    .bind(slot.mentor_id)
+    .bind(mentee_id)
+    .bind(topic.trim())
+    .bind(if paid { MentorBookingStatus::PendingPayment } else { MentorBookingStatus::Confirmed })
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let mut booking = booking(db_pool, mentee_id, booking_id).await?;
+    if let Some(stripe) = stripe.filter(|_| paid) {
+        // The slot is held only while checkout can still be completed
+        if let Err(e) = mentor_payments::open_checkout(db_pool, stripe, &booking).await {
+            let mut tx = db_pool.begin().await?;
+            sqlx::query("UPDATE mentor_bookings SET status = 'cancelled', cancel_reason = $2, updated_at = NOW() WHERE id = $1")
+                .bind(booking_id)
+                .bind("Checkout could not be started")
+                .execute(&mut *tx)
+                .await?;
+            reopen_slot(&mut tx, slot_id).await?;
+            tx.commit().await?;
+            return Err(e);
+        }
+    }
+    if let Err(e) = attach_meeting(db_pool, meetings, &mut booking).await {
+        warn!("No meeting yet for mentor booking {}: {}", booking_id, e);
+    }
+    if paid {
+        info!("User {} started checkout for mentor slot {}", mentee_id, slot_id);
+        return self::booking(db_pool, mentee_id, booking_id).await;
+    }
+    notify_booked(db_pool, &booking).await?;
+    info!("User {} booked mentor slot {}", mentee_id, slot_id);
+    Ok(booking)
+}
+
+/// Tells the mentor about a newly confirmed booking.
+pub(crate) async fn notify_booked(db_pool: &PgPool, booking: &MentorBooking) -> AppResult<()> {
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_booking')")
+        .bind(booking.mentor_id)
+        .bind("New mentor session booked")
+        .bind(format!(
+            "{} booked a session on {} UTC: {}{}",
+            booking.mentee_name,
+            booking.starts_at.format("%Y-%m-%d %H:%M"),
+            booking.topic,
+            join_line(booking)
+        ))
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// Opens a slot whose booking went away again, or marks it released if the
+/// mentor's calendar is busy then.
+pub(crate) async fn reopen_slot(conn: &mut PgConnection, slot_id: i32) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE mentor_slots s
+        SET status = CASE WHEN EXISTS (
+                SELECT 1 FROM mentor_busy_periods b
+                WHERE b.mentor_id = s.mentor_id AND b.starts_at < s.ends_at AND s.starts_at < b.ends_at
+            ) THEN 'released'::mentor_slot_status ELSE 'open'::mentor_slot_status END
+        WHERE s.id = $1
+        "#,
+    )
+    .bind(slot_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// A booking the caller is the mentor or mentee of.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the booking is not the caller's, or an
+/// error if the database operation fails.
+pub async fn booking(db_pool: &PgPool, user_id: Uuid, booking_id: i32) -> AppResult<MentorBooking> {
+    sqlx::query_as::<_, MentorBooking>(&format!(
+        "{} WHERE b.id = $1 AND (b.mentor_id = $2 OR b.mentee_id = $2)",
+        BOOKING_SELECT
+    ))
+    .bind(booking_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// The caller's bookings as mentor and as mentee, upcoming first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_bookings(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<MentorBooking>> {
+    let bookings = sqlx::query_as::<_, MentorBooking>(&format!(
+        r#"
+        {}
+        WHERE b.mentor_id = $1 OR b.mentee_id = $1
+        ORDER BY s.starts_at < NOW(), ABS(EXTRACT(EPOCH FROM s.starts_at - NOW()))
+        LIMIT 200
+        "#,
+        BOOKING_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(bookings)
+}
+
+/// Cancels a confirmed or unpaid booking before it starts. The slot opens
+/// again unless the mentor's calendar is busy then, and the other side is
+/// notified. A paid session is refunded per the cancellation policy first.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404), is not
+/// confirmed or already started, the refund fails, or the database
+/// operation fails.
+pub async fn cancel(
+    db_pool: &PgPool,
+    stripe: Option<&StripeClient>,
+    user_id: Uuid,
+    booking_id: i32,
+    reason: Option<&str>,
+) -> AppResult<MentorBooking> {
+    let current = booking(db_pool, user_id, booking_id).await?;
+    let cancellable = matches!(
+        current.status,
+        MentorBookingStatus::Confirmed | MentorBookingStatus::PendingPayment
+    );
+    if !cancellable || current.starts_at <= Utc::now() {
+        return Err(AppError::BadRequest(
+            "Only confirmed sessions that have not started can be cancelled".to_string(),
+        ));
+    }
+    mentor_payments::settle_cancellation(db_pool, stripe, &current, user_id == current.mentor_id).await?;
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE mentor_bookings
+        SET status = 'cancelled', cancelled_by = $2, cancel_reason = $3, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(booking_id)
+    .bind(user_id)
+    .bind(reason.map(str::trim).filter(|r| !r.is_empty()))
+    .execute(&mut *tx)
+    .await?;
+    reopen_slot(&mut tx, current.slot_id).await?;
+    tx.commit().await?;
+    if current.status == MentorBookingStatus::PendingPayment {
+        // The mentor was never told about an unpaid booking
+        info!("User {} abandoned unpaid mentor booking {}", user_id, booking_id);
+        return booking(db_pool, user_id, booking_id).await;
+    }
+
+    let (recipient, canceller) = if user_id == current.mentor_id {
+        (current.mentee_id, &current.mentor_name)
+    } else {
+        (current.mentor_id, &current.mentee_name)
+    };
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_booking')")
+        .bind(recipient)
+        .bind("Mentor session cancelled")
+        .bind(format!(
+            "{} cancelled the session on {} UTC{}",
+            canceller,
+            current.starts_at.format("%Y-%m-%d %H:%M"),
+            reason.map(|r| format!(": {}", r.trim())).unwrap_or_default()
+        ))
+        .execute(db_pool)
+        .await?;
+    info!("User {} cancelled mentor booking {}", user_id, booking_id);
+    booking(db_pool, user_id, booking_id).await
+}
+
+/// Provisions the booking's meeting and stores its link.
+async fn attach_meeting(db_pool: &PgPool, meetings: &MeetingProvider, booking: &mut MentorBooking) -> AppResult<()> {
+    let room = meetings
+        .create(
+            &format!("Mentor session: {} and {}", booking.mentor_name, booking.mentee_name),
+            booking.starts_at,
+            (booking.ends_at - booking.starts_at).num_minutes(),
+        )
+        .await?;
+    sqlx::query("UPDATE mentor_bookings SET meeting_url = $2, meeting_provider = $3 WHERE id = $1")
+        .bind(booking.id)
+        .bind(&room.join_url)
+        .bind(room.provider)
+        .execute(db_pool)
+        .await?;
+    booking.meeting_url = Some(room.join_url);
+    Ok(())
+}
+
+/// The sentence pointing to a booking's meeting, if it has one.
+fn join_line(booking: &MentorBooking) -> String {
+    booking
+        .meeting_url
+        .as_deref()
+        .map(|url| format!(". Join at {}", url))
+        .unwrap_or_default()
+}
+
+/// A booking as a calendar entry, with its meeting link as the location.
+pub fn calendar_entry(booking: &MentorBooking) -> CalendarEntry {
+    CalendarEntry {
+        uid: format!("mentor-booking-{}@careerbridge", booking.id),
+        summary: format!("Mentor session: {} and {}", booking.mentor_name, booking.mentee_name),
+        description: Some(booking.topic.clone()),
+        location: booking.meeting_url.clone(),
+        url: booking.meeting_url.clone(),
+        starts_at: booking.starts_at,
+        ends_at: booking.ends_at,
+    }
+}
+
+/// Reminds both sides of confirmed sessions starting within the hour, with
+/// the meeting link. A booking whose meeting could not be provisioned when
+/// it was confirmed gets one now. Returns the number of sessions reminded.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn send_reminders(db_pool: &PgPool, meetings: &MeetingProvider) -> AppResult<usize> {
+    let due = sqlx::query_as::<_, MentorBooking>(&format!(
+        r#"
+        {}
+        WHERE b.status = 'confirmed' AND b.reminded_at IS NULL
+          AND s.starts_at > NOW() AND s.starts_at <= NOW() + make_interval(mins => $1)
+        ORDER BY s.starts_at
+        LIMIT $2
+        "#,
+        BOOKING_SELECT
+    ))
+    .bind(REMINDER_LEAD_MINUTES)
+    .bind(REMINDER_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut reminded = 0;
+    for mut booking in due {
+        if booking.meeting_url.is_none()
+            && let Err(e) = attach_meeting(db_pool, meetings, &mut booking).await
+        {
+            warn!("No meeting for mentor booking {} before its reminder: {}", booking.id, e);
+        }
+        let minutes = (booking.starts_at - Utc::now()).num_minutes().max(1);
+        for (user_id, other) in [
+            (booking.mentor_id, &booking.mentee_name),
+            (booking.mentee_id, &booking.mentor_name),
+        ] {
+            sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_booking')")
+                .bind(user_id)
+                .bind("Mentor session starting soon")
+                .bind(format!(
+                    "Your session with {} starts in {} minutes{}",
+                    other,
+                    minutes,
+                    join_line(&booking)
+                ))
+                .execute(db_pool)
+                .await?;
+        }
+        sqlx::query("UPDATE mentor_bookings SET reminded_at = NOW() WHERE id = $1")
+            .bind(booking.id)
+            .execute(db_pool)
+            .await?;
+        reminded += 1;
+    }
+    if reminded > 0 {
+        info!("Sent reminders for {} mentor sessions", reminded);
+    }
+    Ok(reminded)
+}
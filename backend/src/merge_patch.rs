@@ -0,0 +1,51 @@
+//! JSON Merge Patch (RFC 7396).
+//!
+//! A merge patch is a JSON document shaped like the resource it changes:
+//! members it names replace the target's, `null` removes a member, nested
+//! objects are merged recursively, and anything else (arrays included)
+//! replaces the target value as a whole.
+
+use serde_json::{Map, Value};
+
+/// Media type of merge patch request bodies.
+pub const CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Applies `patch` to `target` in place.
+pub fn apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            apply(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Top-level members whose value differs between `before` and `after`,
+/// sorted by name.
+pub fn changed_members(before: &Value, after: &Value) -> Vec<String> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut changed: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
@@ -6,7 +6,7 @@
 use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// Custom deserializer for case-insensitive enum matching
 fn deserialize_case_insensitive<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -97,36 +97,780 @@ impl<'de> Deserialize<'de> for CareerTrack {
 }
 
 /// Type of job or employment arrangement.
-#[derive(Debug, Serialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
 #[sqlx(type_name = "job_type")]
 #[sqlx(rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
-pub enum JobType {
-    /// Internship position
-    Internship,
-    /// Part-time employment
-    PartTime,
-    /// Full-time employment
-    FullTime,
-    /// Freelance or contract work
-    Freelance,
+pub enum JobType {
+    /// Internship position
+    Internship,
+    /// Part-time employment
+    PartTime,
+    /// Full-time employment
+    FullTime,
+    /// Freelance or contract work
+    Freelance,
+}
+
+impl std::str::FromStr for JobType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").replace(' ', "_").as_str() {
+            "internship" => Ok(JobType::Internship),
+            "part_time" | "parttime" => Ok(JobType::PartTime),
+            "full_time" | "fulltime" => Ok(JobType::FullTime),
+            "freelance" => Ok(JobType::Freelance),
+            _ => Err(format!("Unknown job type: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JobType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Cost indicator for learning resources.
+#[derive(Debug, Serialize, sqlx::Type)]
+#[sqlx(type_name = "cost_indicator")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CostIndicator {
+    /// Free resource
+    Free,
+    /// Paid resource
+    Paid,
+}
+
+impl std::str::FromStr for CostIndicator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "free" => Ok(CostIndicator::Free),
+            "paid" => Ok(CostIndicator::Paid),
+            _ => Err(format!("Unknown cost indicator: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CostIndicator {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Account role controlling access to role-specific endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "user_role")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    /// Student or job seeker (default)
+    JobSeeker,
+    /// Employer who posts jobs and reviews applicants
+    Employer,
+    /// Platform administrator
+    Admin,
+}
+
+impl std::str::FromStr for UserRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "job_seeker" | "jobseeker" | "student" => Ok(UserRole::JobSeeker),
+            "employer" => Ok(UserRole::Employer),
+            "admin" => Ok(UserRole::Admin),
+            _ => Err(format!("Unknown user role: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for UserRole {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Role of a seat on a company's recruiting team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "seat_role")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SeatRole {
+    /// Manages the team and billing; can do everything a recruiter can
+    Owner,
+    /// Posts jobs and works the applicant pipeline
+    Recruiter,
+    /// Read-only access to the company's jobs and dashboard
+    Viewer,
+}
+
+impl std::str::FromStr for SeatRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "owner" => Ok(SeatRole::Owner),
+            "recruiter" => Ok(SeatRole::Recruiter),
+            "viewer" => Ok(SeatRole::Viewer),
+            _ => Err(format!("Unknown seat role: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SeatRole {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Moderation state of a job posting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "moderation_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStatus {
+    /// Awaiting admin review; hidden from job seekers
+    Pending,
+    /// Visible to job seekers
+    Approved,
+    /// Rejected by a moderator; hidden from job seekers
+    Rejected,
+    /// Held back by scam screening until a moderator decides; hidden from
+    /// job seekers
+    Quarantined,
+}
+
+/// A policy users must accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "policy_kind")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyKind {
+    /// Terms of service
+    Terms,
+    /// Privacy policy
+    Privacy,
+}
+
+impl PolicyKind {
+    /// Name used in URLs and API responses.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PolicyKind::Terms => "terms",
+            PolicyKind::Privacy => "privacy",
+        }
+    }
+}
+
+/// What a report is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "report_target_type")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportTargetType {
+    /// A job posting
+    Job,
+    /// A candidate or employer account
+    User,
+    /// A company
+    Company,
+    /// A message the reporter received, such as an invitation to apply
+    Message,
+    /// A candidate's review of a company
+    Review,
+}
+
+/// Why something was reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "report_category")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportCategory {
+    Spam,
+    Scam,
+    Harassment,
+    Inappropriate,
+    Other,
+}
+
+/// Where a report is in the moderation queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "report_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    /// Awaiting an admin
+    Open,
+    /// Acted on by an admin
+    Resolved,
+    /// Closed without action
+    Dismissed,
+}
+
+/// Role of a staff member in an institution's organization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "org_role")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    /// Manages the organization and its cohorts
+    Admin,
+    /// Follows the progress of cohort students
+    Advisor,
+}
+
+impl std::str::FromStr for OrgRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "admin" => Ok(OrgRole::Admin),
+            "advisor" => Ok(OrgRole::Advisor),
+            _ => Err(format!("Unknown organization role: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OrgRole {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Pipeline state of a lead recorded at a career-fair booth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "lead_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LeadStatus {
+    /// Recorded at the booth, not yet followed up
+    New,
+    /// Candidate was invited to apply
+    Invited,
+    /// Candidate applied to the linked job
+    Applied,
+    /// Recruiter decided not to pursue
+    Dismissed,
+}
+
+impl std::str::FromStr for LeadStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "new" => Ok(LeadStatus::New),
+            "invited" => Ok(LeadStatus::Invited),
+            "applied" => Ok(LeadStatus::Applied),
+            "dismissed" => Ok(LeadStatus::Dismissed),
+            _ => Err(format!("Unknown lead status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LeadStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// State of a job offer.
+///
+/// A pending offer past its deadline is reported as `Expired`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "offer_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OfferStatus {
+    /// Awaiting the candidate's response
+    Pending,
+    /// Candidate accepted
+    Accepted,
+    /// Candidate declined
+    Declined,
+    /// Candidate declined with a counter-offer
+    Countered,
+    /// Employer withdrew or replaced the offer
+    Withdrawn,
+    /// Deadline passed without a response
+    Expired,
+}
+
+impl std::str::FromStr for OfferStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(OfferStatus::Pending),
+            "accepted" => Ok(OfferStatus::Accepted),
+            "declined" => Ok(OfferStatus::Declined),
+            "countered" => Ok(OfferStatus::Countered),
+            "withdrawn" => Ok(OfferStatus::Withdrawn),
+            "expired" => Ok(OfferStatus::Expired),
+            _ => Err(format!("Unknown offer status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OfferStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// How often a compensation component is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompensationPeriod {
+    /// Paid yearly
+    Annual,
+    /// Paid monthly
+    Monthly,
+    /// Paid once, e.g. a signing bonus
+    OneTime,
+}
+
+/// A kind of multi-step wizard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "wizard_kind")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WizardKind {
+    /// Upload a CV, extract and confirm skills, generate a summary, build a resume
+    CvReview,
+}
+
+impl std::str::FromStr for WizardKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cv_review" => Ok(WizardKind::CvReview),
+            _ => Err(format!("Unknown wizard kind: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WizardKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Progress of one wizard step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "wizard_step_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WizardStepStatus {
+    /// Not started
+    Pending,
+    /// Started but not finished
+    InProgress,
+    /// Finished; its output is kept
+    Completed,
+    /// Passed over by the user
+    Skipped,
+    /// Attempted and failed; can be retried
+    Failed,
+}
+
+impl WizardStepStatus {
+    /// Whether later steps may start after a step with this status.
+    pub fn is_done(self) -> bool {
+        matches!(self, WizardStepStatus::Completed | WizardStepStatus::Skipped)
+    }
+}
+
+impl std::str::FromStr for WizardStepStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(WizardStepStatus::Pending),
+            "in_progress" => Ok(WizardStepStatus::InProgress),
+            "completed" => Ok(WizardStepStatus::Completed),
+            "skipped" => Ok(WizardStepStatus::Skipped),
+            "failed" => Ok(WizardStepStatus::Failed),
+            _ => Err(format!("Unknown wizard step status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WizardStepStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Result of the latest check of a resource link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "link_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// Discovered but not yet checked
+    Unchecked,
+    /// Reachable on the latest check
+    Ok,
+    /// Missing (404/410) or unreachable on several checks in a row
+    Broken,
+}
+
+impl std::str::FromStr for LinkStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unchecked" => Ok(LinkStatus::Unchecked),
+            "ok" => Ok(LinkStatus::Ok),
+            "broken" => Ok(LinkStatus::Broken),
+            _ => Err(format!("Unknown link status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LinkStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// What a user did with one resource in a roadmap phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "resource_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceStatus {
+    /// Worked through the resource
+    Completed,
+    /// Passed over it
+    Skipped,
+}
+
+impl std::str::FromStr for ResourceStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "completed" => Ok(ResourceStatus::Completed),
+            "skipped" => Ok(ResourceStatus::Skipped),
+            _ => Err(format!("Unknown resource status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// How a screening question is answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "screening_question_type")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningQuestionType {
+    /// "yes" or "no", checked against the expected answer
+    YesNo,
+    /// A number, checked against the minimum expected
+    Number,
+    /// Free text, scored by the AI provider
+    Text,
+}
+
+impl std::str::FromStr for ScreeningQuestionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yes_no" => Ok(ScreeningQuestionType::YesNo),
+            "number" => Ok(ScreeningQuestionType::Number),
+            "text" => Ok(ScreeningQuestionType::Text),
+            _ => Err(format!("Unknown screening question type: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ScreeningQuestionType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// How far a user has got with a skill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "skill_progress_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SkillProgressStatus {
+    /// Part of a roadmap the user is working through
+    Learning,
+    /// Completed in a roadmap or rated at intermediate or above
+    Known,
+}
+
+impl std::str::FromStr for SkillProgressStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "learning" => Ok(SkillProgressStatus::Learning),
+            "known" => Ok(SkillProgressStatus::Known),
+            _ => Err(format!("Unknown skill progress status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SkillProgressStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// What last set a skill's progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "skill_progress_source")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SkillProgressSource {
+    /// A roadmap phase teaching the skill
+    Roadmap,
+    /// The user's proficiency rating
+    SelfRating,
+}
+
+impl std::str::FromStr for SkillProgressSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "roadmap" => Ok(SkillProgressSource::Roadmap),
+            "self_rating" => Ok(SkillProgressSource::SelfRating),
+            _ => Err(format!("Unknown skill progress source: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SkillProgressSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Where an indexed document chunk came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, sqlx::Type)]
+#[sqlx(type_name = "document_source")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSource {
+    /// Text of the user's uploaded CV
+    Cv,
+    /// One of the user's career roadmaps
+    Roadmap,
+    /// A document the user saved
+    Document,
+    /// A published knowledge base article
+    KnowledgeBase,
+}
+
+impl std::str::FromStr for DocumentSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cv" => Ok(DocumentSource::Cv),
+            "roadmap" => Ok(DocumentSource::Roadmap),
+            "document" => Ok(DocumentSource::Document),
+            "knowledge_base" => Ok(DocumentSource::KnowledgeBase),
+            _ => Err(format!("Unknown document source: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DocumentSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Status of an application package, and of each of its steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "application_package_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationPackageStatus {
+    /// Waiting for a worker
+    Queued,
+    /// Being worked on
+    Running,
+    /// Finished; artifacts are saved
+    Completed,
+    /// Stopped on an error; can be retried from the failed step
+    Failed,
+}
+
+impl std::str::FromStr for ApplicationPackageStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "queued" => Ok(ApplicationPackageStatus::Queued),
+            "running" => Ok(ApplicationPackageStatus::Running),
+            "completed" => Ok(ApplicationPackageStatus::Completed),
+            "failed" => Ok(ApplicationPackageStatus::Failed),
+            _ => Err(format!("Unknown application package status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ApplicationPackageStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Status of a bulk cohort roadmap batch, and of each student's roadmap in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "roadmap_batch_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RoadmapBatchStatus {
+    /// Waiting for a worker
+    Queued,
+    /// Being worked on
+    Running,
+    /// Finished; a batch is completed even if some students failed
+    Completed,
+    /// Stopped on an error
+    Failed,
+}
+
+impl std::str::FromStr for RoadmapBatchStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "queued" => Ok(RoadmapBatchStatus::Queued),
+            "running" => Ok(RoadmapBatchStatus::Running),
+            "completed" => Ok(RoadmapBatchStatus::Completed),
+            "failed" => Ok(RoadmapBatchStatus::Failed),
+            _ => Err(format!("Unknown roadmap batch status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RoadmapBatchStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// State of a salary negotiation practice session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "negotiation_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NegotiationStatus {
+    /// The candidate can still reply
+    Active,
+    /// Ended, with a debrief
+    Completed,
 }
 
-impl std::str::FromStr for JobType {
+/// Competency a behavioral interview story demonstrates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "star_competency")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StarCompetency {
+    Leadership,
+    Teamwork,
+    /// Handling disagreement
+    Conflict,
+    /// A mistake or setback and what was learned
+    Failure,
+    ProblemSolving,
+    Initiative,
+    Communication,
+    Adaptability,
+}
+
+impl std::str::FromStr for StarCompetency {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().replace('-', "_").replace(' ', "_").as_str() {
-            "internship" => Ok(JobType::Internship),
-            "part_time" | "parttime" => Ok(JobType::PartTime),
-            "full_time" | "fulltime" => Ok(JobType::FullTime),
-            "freelance" => Ok(JobType::Freelance),
-            _ => Err(format!("Unknown job type: {}", s)),
+        match s.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "leadership" => Ok(StarCompetency::Leadership),
+            "teamwork" => Ok(StarCompetency::Teamwork),
+            "conflict" => Ok(StarCompetency::Conflict),
+            "failure" => Ok(StarCompetency::Failure),
+            "problem_solving" => Ok(StarCompetency::ProblemSolving),
+            "initiative" => Ok(StarCompetency::Initiative),
+            "communication" => Ok(StarCompetency::Communication),
+            "adaptability" => Ok(StarCompetency::Adaptability),
+            _ => Err(format!("Unknown competency: {}", s)),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for JobType {
+impl<'de> Deserialize<'de> for StarCompetency {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -135,31 +879,441 @@ impl<'de> Deserialize<'de> for JobType {
     }
 }
 
-/// Cost indicator for learning resources.
-#[derive(Debug, Serialize, sqlx::Type)]
-#[sqlx(type_name = "cost_indicator")]
-#[sqlx(rename_all = "lowercase")]
-#[serde(rename_all = "lowercase")]
-pub enum CostIndicator {
-    /// Free resource
-    Free,
-    /// Paid resource
+/// Whether a STAR story is still being worked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "star_story_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StarStoryStatus {
+    /// Mined or being refined
+    Draft,
+    /// Saved by the user as ready for interviews
+    Polished,
+}
+
+impl std::str::FromStr for StarStoryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "draft" => Ok(StarStoryStatus::Draft),
+            "polished" => Ok(StarStoryStatus::Polished),
+            _ => Err(format!("Unknown story status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StarStoryStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// A coding practice platform solved problems are imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "code_practice_platform")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CodePracticePlatform {
+    Leetcode,
+}
+
+impl std::str::FromStr for CodePracticePlatform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "leetcode" => Ok(CodePracticePlatform::Leetcode),
+            _ => Err(format!("Unknown practice platform: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CodePracticePlatform {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Where a company, or one of its verification attempts, stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "employer_verification_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EmployerVerificationStatus {
+    /// Not verified; for an attempt, not yet submitted for review
+    Unverified,
+    /// Waiting in the admin approval queue
+    Pending,
+    /// Approved by an admin; the company may post jobs
+    Verified,
+    /// Turned down by an admin, with a note
+    Rejected,
+}
+
+impl std::str::FromStr for EmployerVerificationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unverified" => Ok(EmployerVerificationStatus::Unverified),
+            "pending" => Ok(EmployerVerificationStatus::Pending),
+            "verified" => Ok(EmployerVerificationStatus::Verified),
+            "rejected" => Ok(EmployerVerificationStatus::Rejected),
+            _ => Err(format!("Unknown verification status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EmployerVerificationStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Visibility of a company review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "company_review_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CompanyReviewStatus {
+    /// Shown on the company page
+    Published,
+    /// Hidden while enough reports on it are open
+    Hidden,
+    /// Taken down by a moderator
+    Removed,
+}
+
+/// How a candidate's interviews with a company ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "interview_outcome")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum InterviewOutcome {
+    Offer,
+    Rejected,
+    /// The candidate withdrew
+    Withdrew,
+    /// The company stopped responding
+    NoResponse,
+    /// Still waiting for a decision
+    Pending,
+}
+
+impl std::str::FromStr for InterviewOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "offer" => Ok(InterviewOutcome::Offer),
+            "rejected" => Ok(InterviewOutcome::Rejected),
+            "withdrew" => Ok(InterviewOutcome::Withdrew),
+            "no_response" => Ok(InterviewOutcome::NoResponse),
+            "pending" => Ok(InterviewOutcome::Pending),
+            _ => Err(format!("Unknown interview outcome: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for InterviewOutcome {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Availability of a mentor's session slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "mentor_slot_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MentorSlotStatus {
+    /// Free to book
+    Open,
+    /// Taken by a live booking
+    Booked,
+    /// An event on the mentor's calendar took the time
+    Released,
+}
+
+/// Lifecycle of a mentor session booking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "mentor_booking_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MentorBookingStatus {
+    /// A paid session waiting for the mentee to complete checkout
+    PendingPayment,
+    Confirmed,
+    Cancelled,
+    Completed,
+}
+
+/// Where the money for a paid mentor session is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "mentor_payment_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MentorPaymentStatus {
+    /// Checkout started but not completed
+    Pending,
+    /// Paid and held until the session is completed
+    Held,
+    /// Credited to the mentor's balance, less any partial refund
+    Released,
+    /// Refunded to the mentee in full
+    Refunded,
+    /// Checkout was abandoned
+    Expired,
+    /// Frozen while a dispute over the session is open
+    Disputed,
+}
+
+/// Lifecycle of a mentor's payout request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "mentor_payout_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MentorPayoutStatus {
+    Requested,
     Paid,
+    Rejected,
 }
 
-impl std::str::FromStr for CostIndicator {
+/// What a dispute is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "dispute_subject")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeSubject {
+    MentorBooking,
+    /// An employer's decision on an application
+    Application,
+}
+
+/// Lifecycle of a dispute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "dispute_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    /// Waiting for an admin; both parties can add evidence
+    Open,
+    /// An admin is arbitrating; both parties can still add evidence
+    UnderReview,
+    Resolved,
+    /// Withdrawn by the party who opened it
+    Withdrawn,
+}
+
+/// An admin's ruling on a dispute, from the opener's side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "dispute_outcome")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Upheld,
+    PartiallyUpheld,
+    Dismissed,
+}
+
+/// Lifecycle of a prompt rollout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "prompt_rollout_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PromptRolloutStatus {
+    /// The candidate gets the rollout's share of traffic
+    Active,
+    /// The candidate gets all traffic until a release makes it current
+    Promoted,
+    /// Everyone is back on the baseline version
+    RolledBack,
+    /// The candidate became the action's current version
+    Completed,
+}
+
+/// Calendar service a mentor's availability is synced from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "calendar_provider")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarProvider {
+    /// Google Calendar free/busy API
+    Google,
+    /// Any CalDAV server (Nextcloud, iCloud, Fastmail, ...)
+    Caldav,
+}
+
+impl std::str::FromStr for CalendarProvider {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "free" => Ok(CostIndicator::Free),
-            "paid" => Ok(CostIndicator::Paid),
-            _ => Err(format!("Unknown cost indicator: {}", s)),
+            "google" => Ok(CalendarProvider::Google),
+            "caldav" => Ok(CalendarProvider::Caldav),
+            _ => Err(format!("Unknown calendar provider: {}", s)),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for CostIndicator {
+impl<'de> Deserialize<'de> for CalendarProvider {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// How a certification was added to a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "certification_source")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CertificationSource {
+    /// Entered by the user
+    Manual,
+    /// Read from a photo or screenshot
+    Image,
+}
+
+impl std::str::FromStr for CertificationSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "manual" => Ok(CertificationSource::Manual),
+            "image" => Ok(CertificationSource::Image),
+            _ => Err(format!("Unknown certification source: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CertificationSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// Whether a certification has been confirmed with its issuer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "certification_verification_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CertificationVerificationStatus {
+    /// Waiting for the verifier
+    Pending,
+    /// The issuer confirmed the credential
+    Verified,
+    /// The issuer does not know the credential, or revoked it
+    Failed,
+    /// The issuer has no public verification endpoint
+    Unsupported,
+}
+
+impl std::str::FromStr for CertificationVerificationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(CertificationVerificationStatus::Pending),
+            "verified" => Ok(CertificationVerificationStatus::Verified),
+            "failed" => Ok(CertificationVerificationStatus::Failed),
+            "unsupported" => Ok(CertificationVerificationStatus::Unsupported),
+            _ => Err(format!("Unknown certification verification status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CertificationVerificationStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// A list on the profile that users curate by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "profile_item_kind")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileItemKind {
+    /// `users.skills`
+    Skill,
+    /// `users.target_roles`
+    TargetRole,
+}
+
+impl std::str::FromStr for ProfileItemKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skill" => Ok(ProfileItemKind::Skill),
+            "target_role" => Ok(ProfileItemKind::TargetRole),
+            _ => Err(format!("Unknown profile item kind: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ProfileItemKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_case_insensitive(deserializer)
+    }
+}
+
+/// A candidate's right to work in a country.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "work_authorization_status")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WorkAuthorizationStatus {
+    /// Citizen of the country
+    Citizen,
+    /// Permanent resident
+    PermanentResident,
+    /// Holds a visa that allows work
+    WorkVisa,
+    /// Can only work there with employer visa sponsorship
+    NeedsSponsorship,
+}
+
+impl std::str::FromStr for WorkAuthorizationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "citizen" => Ok(WorkAuthorizationStatus::Citizen),
+            "permanent_resident" => Ok(WorkAuthorizationStatus::PermanentResident),
+            "work_visa" => Ok(WorkAuthorizationStatus::WorkVisa),
+            "needs_sponsorship" => Ok(WorkAuthorizationStatus::NeedsSponsorship),
+            _ => Err(format!("Unknown work authorization status: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WorkAuthorizationStatus {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -168,6 +1322,38 @@ impl<'de> Deserialize<'de> for CostIndicator {
     }
 }
 
+/// Work authorization for one country, keyed by ISO 3166-1 alpha-2 code.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WorkAuthorization {
+    /// Two-letter country code, e.g. `BD`
+    pub country_code: String,
+    /// Right to work in that country
+    pub status: WorkAuthorizationStatus,
+}
+
+/// A job seeker's availability and where they can work.
+///
+/// Used as hard filters by job recommendations; unset values never exclude
+/// a job.
+#[derive(Debug, Clone, Default, FromRow, Serialize)]
+pub struct CandidateAvailability {
+    /// Earliest start date
+    pub available_from: Option<NaiveDate>,
+    /// Employment types the candidate wants; empty means any
+    pub desired_job_types: Vec<JobType>,
+    /// Whether the candidate would move for an on-site job
+    pub willing_to_relocate: bool,
+    /// Country the candidate is based in
+    pub country_code: Option<String>,
+    /// Academic terms a student is available for internships, e.g. `2026-fall`
+    pub available_terms: Vec<String>,
+    /// Hours a week the user has for learning, compared with their roadmaps
+    pub weekly_learning_hours: Option<i32>,
+    /// Right to work per country
+    #[sqlx(skip)]
+    pub work_authorizations: Vec<WorkAuthorization>,
+}
+
 /// User account with profile and career information.
 #[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct User {
@@ -238,6 +1424,9 @@ pub struct Job {
     pub requirements: Vec<String>,
     /// List of benefits offered
     pub benefits: Vec<String>,
+    /// Whether the posting company is a verified employer
+    #[serde(default)]
+    pub employer_verified: bool,
 }
 
 /// Learning resource for skill development.
@@ -375,3 +1564,21 @@ pub struct UserWithOAuth {
     /// Raw CV text
     pub raw_cv_text: Option<String>,
 }
+
+/// Denormalized per-job application counters for employer dashboards.
+///
+/// Maintained by database triggers on `application_tracking` and
+/// periodically reconciled by the scheduler.
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct JobApplicationStats {
+    /// Job the counters belong to
+    pub job_id: i32,
+    /// Total number of applications
+    pub applicant_count: i32,
+    /// Applications still in the initial `applied` status
+    pub new_count: i32,
+    /// Applications in `in_review` or `interview` status
+    pub in_review_count: i32,
+    /// When the counters last changed
+    pub updated_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,686 @@
+//! Job posting moderation.
+//!
+//! Employer posts are created with `moderation_status = 'pending'` and stay
+//! hidden from job seekers until an admin approves them. Right after posting,
+//! [`spawn_prescreen`] scores the post for scam indicators in the background:
+//! heuristics always run (pay-to-apply language, personal email addresses,
+//! crypto payments, pressure tactics), and when an AI provider is configured
+//! its classification is merged in. The score and the reasons behind it are
+//! stored on the job so the moderation queue can be sorted riskiest first,
+//! and posts scoring at or above the quarantine threshold are moved to
+//! `quarantined`.
+//!
+//! Listings from external job feeds are screened with the heuristics when
+//! they are fetched (see [`screen_import`]); suspicious ones are held in
+//! `quarantined_imports` and left out of the feed until a moderator releases
+//! them.
+//!
+//! Every moderator decision on a screened post or listing is kept in
+//! `scam_screening_feedback`. [`load_tuning`] raises the threshold when too
+//! many quarantined posts turn out to be legitimate (and lowers it when few
+//! do), and halves the weight of signals that mostly fire on legitimate
+//! posts.
+//!
+//! Moderators approve or reject posts with a reason; the employer is told
+//! about the decision through a row in `notifications`.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::employer_verification::PERSONAL_EMAIL_DOMAINS;
+use crate::errors::{AppError, AppResult};
+use crate::models::ModerationStatus;
+
+/// A kind of scam indicator; the unit feedback is tracked and tuned by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskSignal {
+    /// Fees or deposits asked of applicants
+    PayToApply,
+    /// Bank or identity details asked for up front
+    FinancialDetails,
+    /// Easy money, guaranteed income, no experience needed
+    UnrealisticPromise,
+    /// Contact only through a messaging app
+    OffPlatformContact,
+    /// Pressure to act immediately
+    Pressure,
+    /// Contact address on a free mail provider
+    PersonalEmail,
+    /// Payment in cryptocurrency
+    CryptoPayment,
+    /// The AI classifier's assessment
+    AiClassifier,
+}
+
+impl RiskSignal {
+    /// Every signal, in the order tuning reports them.
+    pub const ALL: [RiskSignal; 8] = [
+        RiskSignal::PayToApply,
+        RiskSignal::FinancialDetails,
+        RiskSignal::UnrealisticPromise,
+        RiskSignal::OffPlatformContact,
+        RiskSignal::Pressure,
+        RiskSignal::PersonalEmail,
+        RiskSignal::CryptoPayment,
+        RiskSignal::AiClassifier,
+    ];
+
+    /// Name stored in `scam_screening_feedback.signals`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RiskSignal::PayToApply => "pay_to_apply",
+            RiskSignal::FinancialDetails => "financial_details",
+            RiskSignal::UnrealisticPromise => "unrealistic_promise",
+            RiskSignal::OffPlatformContact => "off_platform_contact",
+            RiskSignal::Pressure => "pressure",
+            RiskSignal::PersonalEmail => "personal_email",
+            RiskSignal::CryptoPayment => "crypto_payment",
+            RiskSignal::AiClassifier => "ai_classifier",
+        }
+    }
+}
+
+/// Phrases that commonly appear in fraudulent job posts, with their signal
+/// and the indicator reported when found.
+const SCAM_PHRASES: &[(&str, RiskSignal, &str)] = &[
+    ("registration fee", RiskSignal::PayToApply, "Asks applicants to pay a registration fee"),
+    ("application fee", RiskSignal::PayToApply, "Asks applicants to pay an application fee"),
+    ("pay to apply", RiskSignal::PayToApply, "Asks applicants to pay to apply"),
+    ("training fee", RiskSignal::PayToApply, "Asks applicants to pay for training"),
+    ("security deposit", RiskSignal::PayToApply, "Asks applicants for a security deposit"),
+    ("refundable deposit", RiskSignal::PayToApply, "Asks applicants for a security deposit"),
+    ("processing fee", RiskSignal::PayToApply, "Asks applicants to pay a processing fee"),
+    ("bank account details", RiskSignal::FinancialDetails, "Requests bank account details up front"),
+    ("no experience needed", RiskSignal::UnrealisticPromise, "Promises work with no experience required"),
+    ("earn money fast", RiskSignal::UnrealisticPromise, "Promises fast or easy money"),
+    ("easy money", RiskSignal::UnrealisticPromise, "Promises fast or easy money"),
+    ("guaranteed income", RiskSignal::UnrealisticPromise, "Guarantees income"),
+    ("whatsapp only", RiskSignal::OffPlatformContact, "Contact only through a messaging app"),
+    ("telegram", RiskSignal::OffPlatformContact, "Contact only through a messaging app"),
+    ("limited slots", RiskSignal::Pressure, "Pressures applicants to act immediately"),
+];
+
+/// Words that mention paying or being paid in cryptocurrency.
+const CRYPTO_TERMS: &[&str] = &[
+    "bitcoin", "btc", "usdt", "tether", "ethereum", "crypto", "cryptocurrency", "binance",
+];
+
+/// Risk added per heuristic indicator at full weight.
+const KEYWORD_RISK: i32 = 25;
+
+/// Default score at which a post is quarantined.
+const DEFAULT_QUARANTINE_SCORE: i32 = 70;
+
+/// Bounds of the tuned quarantine threshold.
+const MIN_QUARANTINE_SCORE: i32 = 40;
+const MAX_QUARANTINE_SCORE: i32 = 95;
+
+/// Share of quarantined posts allowed to be legitimate before the threshold
+/// is raised.
+const TARGET_FALSE_POSITIVE_RATE: f64 = 0.2;
+
+/// Threshold points moved per unit of false positive rate off the target.
+const THRESHOLD_ADJUSTMENT: f64 = 50.0;
+
+/// Days of feedback used for tuning.
+const FEEDBACK_WINDOW_DAYS: i32 = 90;
+
+/// Decisions needed before the threshold, or a signal's weight, is tuned.
+const MIN_THRESHOLD_FEEDBACK: i64 = 10;
+const MIN_SIGNAL_FEEDBACK: i64 = 5;
+
+/// Share of a signal's decisions that must be confirmed scams for it to keep
+/// full weight.
+const MIN_SIGNAL_PRECISION: f64 = 0.5;
+
+/// A reason a post looks suspicious, shown to moderators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskReason {
+    pub signal: RiskSignal,
+    pub detail: String,
+    /// Risk points the reason added
+    pub points: i32,
+}
+
+/// Result of pre-screening a job posting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Screening {
+    /// 0 (clearly legitimate) to 100 (almost certainly a scam)
+    pub risk_score: i32,
+    /// Specific red flags found in the post
+    pub indicators: Vec<String>,
+    /// The red flags with their signal and weight
+    #[serde(default)]
+    pub reasons: Vec<RiskReason>,
+    /// Short explanation for the moderator
+    pub summary: Option<String>,
+    /// Whether an AI provider contributed to the result
+    pub ai_assisted: bool,
+    /// Score at which posts were quarantined when this one was screened
+    #[serde(default)]
+    pub threshold: Option<i32>,
+    /// Whether the post was quarantined
+    #[serde(default)]
+    pub quarantined: bool,
+}
+
+impl Screening {
+    /// Signals that fired, without duplicates.
+    pub fn signals(&self) -> Vec<RiskSignal> {
+        let mut signals: Vec<RiskSignal> = Vec::new();
+        for reason in &self.reasons {
+            if !signals.contains(&reason.signal) {
+                signals.push(reason.signal);
+            }
+        }
+        signals
+    }
+
+    fn add(&mut self, signal: RiskSignal, detail: String, points: i32) {
+        if self.indicators.contains(&detail) {
+            return;
+        }
+        self.indicators.push(detail.clone());
+        self.reasons.push(RiskReason { signal, detail, points });
+    }
+}
+
+/// Screening thresholds and weights tuned from moderator feedback.
+#[derive(Debug, Clone, Serialize)]
+pub struct Tuning {
+    /// Configured threshold (`SCAM_QUARANTINE_SCORE`, default 70)
+    pub base_threshold: i32,
+    /// Threshold in use after tuning
+    pub threshold: i32,
+    /// Decided quarantines in the feedback window
+    pub quarantined_decisions: i64,
+    /// Share of those that moderators found legitimate
+    pub false_positive_rate: Option<f64>,
+    pub signals: Vec<SignalTuning>,
+}
+
+/// How one signal performed and the weight it is given.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalTuning {
+    pub signal: RiskSignal,
+    /// Decisions on posts where the signal fired
+    pub decisions: i64,
+    /// How many of those were confirmed scams
+    pub confirmed: i64,
+    pub precision: Option<f64>,
+    /// 1.0, or 0.5 while the signal mostly fires on legitimate posts
+    pub weight: f64,
+}
+
+impl Tuning {
+    /// Untuned thresholds and weights.
+    pub fn untuned() -> Self {
+        let base_threshold = base_threshold();
+        Self {
+            base_threshold,
+            threshold: base_threshold,
+            quarantined_decisions: 0,
+            false_positive_rate: None,
+            signals: Vec::new(),
+        }
+    }
+
+    fn weight(&self, signal: RiskSignal) -> f64 {
+        self.signals
+            .iter()
+            .find(|s| s.signal == signal)
+            .map_or(1.0, |s| s.weight)
+    }
+}
+
+/// Reads `SCAM_QUARANTINE_SCORE` (default: 70).
+fn base_threshold() -> i32 {
+    std::env::var("SCAM_QUARANTINE_SCORE")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|v| (1..=100).contains(v))
+        .unwrap_or(DEFAULT_QUARANTINE_SCORE)
+}
+
+#[derive(Debug, FromRow)]
+struct SignalCounts {
+    signal: String,
+    decisions: i64,
+    confirmed: i64,
+}
+
+/// Tunes the quarantine threshold and signal weights from recent moderator
+/// feedback.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn load_tuning(db_pool: &PgPool) -> AppResult<Tuning> {
+    let mut tuning = Tuning::untuned();
+
+    let (decisions, false_positives) = sqlx::query_as::<_, (i64, i64)>(
+        r#"
+        SELECT COUNT(*), COUNT(*) FILTER (WHERE NOT confirmed_scam)
+        FROM scam_screening_feedback
+        WHERE quarantined AND created_at > NOW() - make_interval(days => $1)
+        "#,
+    )
+    .bind(FEEDBACK_WINDOW_DAYS)
+    .fetch_one(db_pool)
+    .await?;
+    tuning.quarantined_decisions = decisions;
+    if decisions > 0 {
+        let rate = false_positives as f64 / decisions as f64;
+        tuning.false_positive_rate = Some(rate);
+        if decisions >= MIN_THRESHOLD_FEEDBACK {
+            let adjustment = ((rate - TARGET_FALSE_POSITIVE_RATE) * THRESHOLD_ADJUSTMENT).round() as i32;
+            tuning.threshold = (tuning.base_threshold + adjustment).clamp(MIN_QUARANTINE_SCORE, MAX_QUARANTINE_SCORE);
+        }
+    }
+
+    let counts = sqlx::query_as::<_, SignalCounts>(
+        r#"
+        SELECT signal, COUNT(*) AS decisions, COUNT(*) FILTER (WHERE confirmed_scam) AS confirmed
+        FROM scam_screening_feedback, UNNEST(signals) AS signal
+        WHERE created_at > NOW() - make_interval(days => $1)
+        GROUP BY signal
+        "#,
+    )
+    .bind(FEEDBACK_WINDOW_DAYS)
+    .fetch_all(db_pool)
+    .await?;
+    tuning.signals = RiskSignal::ALL
+        .iter()
+        .map(|&signal| {
+            let (decisions, confirmed) = counts
+                .iter()
+                .find(|c| c.signal == signal.as_str())
+                .map_or((0, 0), |c| (c.decisions, c.confirmed));
+            let precision = (decisions > 0).then(|| confirmed as f64 / decisions as f64);
+            let weight = match precision {
+                Some(precision) if decisions >= MIN_SIGNAL_FEEDBACK && precision < MIN_SIGNAL_PRECISION => 0.5,
+                _ => 1.0,
+            };
+            SignalTuning {
+                signal,
+                decisions,
+                confirmed,
+                precision,
+                weight,
+            }
+        })
+        .collect();
+    Ok(tuning)
+}
+
+/// Email domains in the text that belong to free mail providers.
+fn personal_email_domains(text: &str) -> Vec<String> {
+    let mut domains: Vec<String> = Vec::new();
+    for token in text.split_whitespace() {
+        let token = token.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+        if let Some((local, domain)) = token.rsplit_once('@')
+            && !local.is_empty()
+            && PERSONAL_EMAIL_DOMAINS.contains(&domain)
+            && !domains.iter().any(|d| d == domain)
+        {
+            domains.push(domain.to_string());
+        }
+    }
+    domains
+}
+
+/// Scores a posting for scam indicators using the heuristics only.
+pub fn heuristic_screen(text: &str, tuning: &Tuning) -> Screening {
+    let lower = text.to_lowercase();
+    let mut screening = Screening::default();
+    let points = |signal: RiskSignal| (KEYWORD_RISK as f64 * tuning.weight(signal)).round() as i32;
+
+    for (phrase, signal, indicator) in SCAM_PHRASES {
+        if lower.contains(phrase) {
+            screening.add(*signal, indicator.to_string(), points(*signal));
+        }
+    }
+    for domain in personal_email_domains(text) {
+        screening.add(
+            RiskSignal::PersonalEmail,
+            format!("Asks applicants to write to a personal {} address", domain),
+            points(RiskSignal::PersonalEmail),
+        );
+    }
+    if lower
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| CRYPTO_TERMS.contains(&word))
+    {
+        screening.add(
+            RiskSignal::CryptoPayment,
+            "Mentions payment in cryptocurrency".to_string(),
+            points(RiskSignal::CryptoPayment),
+        );
+    }
+
+    screening.risk_score = screening.reasons.iter().map(|r| r.points).sum::<i32>().min(100);
+    screening.threshold = Some(tuning.threshold);
+    screening
+}
+
+/// Pre-screens a job in the background, stores the result on the job, and
+/// quarantines it when the risk reaches the threshold.
+///
+/// Screening never blocks or fails the post itself; errors are logged.
+pub fn spawn_prescreen(db_pool: PgPool, ai_service: Option<Arc<AIService>>, job_id: i32) {
+    tokio::spawn(async move {
+        if let Err(e) = prescreen(&db_pool, ai_service.as_deref(), job_id).await {
+            warn!("Pre-screening failed for job {}: {}", job_id, e);
+        }
+    });
+}
+
+async fn prescreen(db_pool: &PgPool, ai_service: Option<&AIService>, job_id: i32) -> AppResult<()> {
+    let Some((title, company, description, salary_min, salary_max)) =
+        sqlx::query_as::<_, (String, String, String, Option<i32>, Option<i32>)>(
+            "SELECT job_title, company, job_description, salary_min, salary_max FROM jobs WHERE id = $1",
+        )
+        .bind(job_id)
+        .fetch_optional(db_pool)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let posting = format!(
+        "Title: {}\nCompany: {}\nSalary: {} - {}\n\n{}",
+        title,
+        company,
+        salary_min.map_or("-".to_string(), |s| s.to_string()),
+        salary_max.map_or("-".to_string(), |s| s.to_string()),
+        description
+    );
+
+    let tuning = load_tuning(db_pool).await?;
+    let mut screening = heuristic_screen(&posting, &tuning);
+    if let Some(ai) = ai_service {
+        merge_ai_assessment(ai, &posting, &tuning, &mut screening).await;
+    }
+    screening.quarantined = screening.risk_score >= tuning.threshold;
+
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET scam_risk_score = $2, screening = $3,
+            moderation_status = CASE WHEN $4 AND moderation_status = 'pending'
+                                     THEN 'quarantined'::moderation_status
+                                     ELSE moderation_status END
+        WHERE id = $1
+        "#,
+    )
+    .bind(job_id)
+    .bind(screening.risk_score)
+    .bind(serde_json::to_value(&screening).unwrap_or_default())
+    .bind(screening.quarantined)
+    .execute(db_pool)
+    .await?;
+
+    info!(
+        "Job {} pre-screened: risk_score={}, indicators={}, quarantined={}",
+        job_id,
+        screening.risk_score,
+        screening.indicators.len(),
+        screening.quarantined
+    );
+    Ok(())
+}
+
+/// Asks the AI classifier for its assessment and folds it into `screening`.
+///
+/// The higher of the two scores wins so the AI can raise but never hide a
+/// heuristic match.
+async fn merge_ai_assessment(ai: &AIService, posting: &str, tuning: &Tuning, screening: &mut Screening) {
+    let request = AIActionRequest {
+        action: ActionType::ScreenJobPosting,
+        provider: AIProvider::Gemini,
+        input: posting.to_string(),
+        parameters: None,
+        user_id: None,
+        capture: true,
+    };
+
+    let response = match ai.process_action(request).await {
+        Ok(response) if response.success => response.data,
+        Ok(response) => {
+            debug!("AI screening unavailable: {:?}", response.message);
+            return;
+        }
+        Err(e) => {
+            debug!("AI screening unavailable: {}", e);
+            return;
+        }
+    };
+
+    let ai_score = response
+        .get("risk_score")
+        .and_then(|v| v.as_i64())
+        .map(|v| (v.clamp(0, 100) as f64 * tuning.weight(RiskSignal::AiClassifier)).round() as i32)
+        .unwrap_or(0);
+    screening.summary = response
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    if ai_score > 0 {
+        let detail = format!("The AI classifier rated the post {}/100", ai_score);
+        screening.reasons.push(RiskReason {
+            signal: RiskSignal::AiClassifier,
+            detail,
+            points: ai_score,
+        });
+    }
+    screening.risk_score = screening.risk_score.max(ai_score);
+
+    if let Some(indicators) = response.get("indicators").and_then(|v| v.as_array()) {
+        for indicator in indicators.iter().filter_map(|v| v.as_str()) {
+            if !screening.indicators.iter().any(|i| i == indicator) {
+                screening.indicators.push(indicator.to_string());
+            }
+        }
+    }
+    screening.ai_assisted = true;
+}
+
+/// Records a moderator's verdict on a screened job or feed listing as
+/// tuning feedback. Posts never screened are skipped.
+///
+/// Feedback is a side effect, so failures are logged rather than failing
+/// the decision.
+pub async fn record_feedback(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    job_id: Option<i32>,
+    import_id: Option<i32>,
+    screening: Option<&serde_json::Value>,
+    confirmed_scam: bool,
+) {
+    let Some(screening) = screening.and_then(|s| serde_json::from_value::<Screening>(s.clone()).ok()) else {
+        return;
+    };
+    let signals: Vec<&str> = screening.signals().into_iter().map(RiskSignal::as_str).collect();
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO scam_screening_feedback
+            (job_id, import_id, risk_score, signals, quarantined, confirmed_scam, decided_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(job_id)
+    .bind(import_id)
+    .bind(screening.risk_score)
+    .bind(&signals)
+    .bind(screening.quarantined)
+    .bind(confirmed_scam)
+    .bind(admin_id)
+    .execute(db_pool)
+    .await
+    {
+        warn!("Failed to record screening feedback: {}", e);
+    }
+}
+
+/// A feed listing held back by screening.
+#[derive(Debug, Serialize, FromRow)]
+pub struct QuarantinedImport {
+    pub id: i32,
+    pub source: String,
+    pub external_id: String,
+    pub title: String,
+    pub company: String,
+    pub url: String,
+    pub risk_score: i32,
+    pub screening: serde_json::Value,
+    pub status: ModerationStatus,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+const IMPORT_COLUMNS: &str =
+    "id, source, external_id, title, company, url, risk_score, screening, status, created_at";
+
+/// Screens a listing from an external feed with the heuristics (the AI
+/// classifier is left out, as feeds are fetched on every request). Returns
+/// whether the listing may be shown: it scores below the threshold, or a
+/// moderator released it. Suspicious listings are added to the quarantine
+/// queue on first sight.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn screen_import(
+    db_pool: &PgPool,
+    tuning: &Tuning,
+    source: &str,
+    external_id: &str,
+    title: &str,
+    company: &str,
+    url: &str,
+    text: &str,
+) -> AppResult<bool> {
+    let mut screening = heuristic_screen(text, tuning);
+    if screening.risk_score < tuning.threshold {
+        return Ok(true);
+    }
+    screening.quarantined = true;
+
+    let status = sqlx::query_scalar::<_, ModerationStatus>(
+        r#"
+        INSERT INTO quarantined_imports (source, external_id, title, company, url, risk_score, screening)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (source, external_id) DO UPDATE SET source = EXCLUDED.source
+        RETURNING status
+        "#,
+    )
+    .bind(source)
+    .bind(external_id)
+    .bind(title.chars().take(255).collect::<String>())
+    .bind(company.chars().take(255).collect::<String>())
+    .bind(url)
+    .bind(screening.risk_score)
+    .bind(serde_json::to_value(&screening).unwrap_or_default())
+    .fetch_one(db_pool)
+    .await?;
+    if status == ModerationStatus::Quarantined {
+        debug!("Feed listing {}/{} quarantined (risk {})", source, external_id, screening.risk_score);
+    }
+    Ok(status == ModerationStatus::Approved)
+}
+
+/// Feed listings awaiting a moderator, riskiest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_quarantined_imports(db_pool: &PgPool) -> AppResult<Vec<QuarantinedImport>> {
+    let imports = sqlx::query_as::<_, QuarantinedImport>(&format!(
+        "SELECT {} FROM quarantined_imports WHERE status = 'quarantined' ORDER BY risk_score DESC, created_at",
+        IMPORT_COLUMNS
+    ))
+    .fetch_all(db_pool)
+    .await?;
+    Ok(imports)
+}
+
+/// Releases a quarantined feed listing (a false positive) or confirms it as
+/// a scam, and records the verdict as feedback.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the listing is not quarantined, or an
+/// error if the database operation fails.
+pub async fn decide_import(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    import_id: i32,
+    decision: ModerationStatus,
+) -> AppResult<QuarantinedImport> {
+    let import = sqlx::query_as::<_, QuarantinedImport>(&format!(
+        r#"
+        UPDATE quarantined_imports
+        SET status = $2, decided_by = $3, decided_at = NOW()
+        WHERE id = $1 AND status = 'quarantined'
+        RETURNING {}
+        "#,
+        IMPORT_COLUMNS
+    ))
+    .bind(import_id)
+    .bind(decision)
+    .bind(admin_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    info!("Feed listing {} {:?} by admin {}", import_id, decision, admin_id);
+    record_feedback(
+        db_pool,
+        admin_id,
+        None,
+        Some(import_id),
+        Some(&import.screening),
+        decision == ModerationStatus::Rejected,
+    )
+    .await;
+    Ok(import)
+}
+
+/// Tells the employer who posted a job about the moderation decision.
+pub async fn notify_employer(
+    db_pool: &PgPool,
+    employer_id: Uuid,
+    job_title: &str,
+    decision: ModerationStatus,
+    reason: Option<&str>,
+) -> AppResult<()> {
+    let (title, mut message) = match decision {
+        ModerationStatus::Approved => (
+            "Job posting approved",
+            format!("Your job posting \"{}\" is now live.", job_title),
+        ),
+        _ => (
+            "Job posting rejected",
+            format!("Your job posting \"{}\" was not approved.", job_title),
+        ),
+    };
+    if let Some(reason) = reason {
+        message.push_str(&format!(" Reason: {}", reason));
+    }
+
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'job_moderation')")
+        .bind(employer_id)
+        .bind(title)
+        .bind(message)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,347 @@
+//! Salary negotiation practice.
+//!
+//! A job seeker practises negotiating one of their offers with the AI
+//! playing the recruiter who made it. The recruiter works from the offer's
+//! terms, the posted salary range, and, when enough placements share the
+//! job title, the salaries they were placed at (suppressed below
+//! `PLACEMENT_MIN_GROUP_SIZE`, as in placement reports). These are captured
+//! in the session's scenario when it starts; the transcript is kept on the
+//! session, so each reply sees the whole negotiation.
+//!
+//! A session ends when the recruiter concludes it, the candidate ends it, or
+//! after [`MAX_CANDIDATE_TURNS`] candidate messages. Ending it produces a
+//! debrief with concrete phrasing suggestions.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType, ChatMessage, ChatRole};
+use crate::errors::{AppError, AppResult};
+use crate::models::{CompensationPeriod, NegotiationStatus};
+use crate::reporting;
+
+/// Candidate messages before a session ends with a debrief.
+pub const MAX_CANDIDATE_TURNS: usize = 12;
+
+/// A negotiation practice session.
+#[derive(Debug, Serialize, FromRow)]
+pub struct NegotiationSession {
+    pub id: i32,
+    pub offer_id: Option<i32>,
+    /// Offer details and benchmarks the recruiter works from
+    pub scenario: String,
+    /// Recruiter (`assistant`) and candidate (`user`) messages, recruiter first
+    pub transcript: Json<Vec<ChatMessage>>,
+    pub status: NegotiationStatus,
+    /// Coaching on the finished negotiation
+    pub debrief: Option<Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The session after a turn.
+#[derive(Debug, Serialize)]
+pub struct NegotiationTurn {
+    /// The recruiter's reply, unless the candidate ended the session
+    pub reply: Option<String>,
+    /// A change to the offer the recruiter agreed to in this reply
+    pub revised_offer: Option<String>,
+    /// Candidate messages left before the session ends
+    pub turns_left: usize,
+    pub session: NegotiationSession,
+}
+
+const SESSION_COLUMNS: &str = "id, offer_id, scenario, transcript, status, debrief, created_at, updated_at";
+
+#[derive(Debug, FromRow)]
+struct OfferTerms {
+    job_title: String,
+    company: String,
+    base_salary: i32,
+    currency: String,
+    compensation: Json<Vec<Component>>,
+    start_date: Option<chrono::NaiveDate>,
+    salary_min: Option<i32>,
+    salary_max: Option<i32>,
+}
+
+/// A compensation component of an offer.
+#[derive(Debug, Deserialize)]
+struct Component {
+    label: String,
+    amount: i64,
+    period: CompensationPeriod,
+}
+
+#[derive(Debug, FromRow)]
+struct PlacementBand {
+    placements: i64,
+    p25: Option<f64>,
+    median: Option<f64>,
+    p75: Option<f64>,
+}
+
+/// Describes one of the user's offers and its salary benchmarks.
+async fn scenario(db_pool: &PgPool, user_id: Uuid, offer_id: i32) -> AppResult<String> {
+    let offer = sqlx::query_as::<_, OfferTerms>(
+        r#"
+        SELECT j.job_title, j.company, o.base_salary, o.currency, o.compensation, o.start_date,
+               j.salary_min, j.salary_max
+        FROM job_offers o
+        JOIN application_tracking a ON a.id = o.application_id
+        JOIN jobs j ON j.id = a.job_id
+        WHERE o.id = $1 AND a.user_id = $2
+        "#,
+    )
+    .bind(offer_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let band = sqlx::query_as::<_, PlacementBand>(
+        r#"
+        SELECT COUNT(*) AS placements,
+               percentile_cont(0.25) WITHIN GROUP (ORDER BY base_salary) AS p25,
+               percentile_cont(0.5) WITHIN GROUP (ORDER BY base_salary) AS median,
+               percentile_cont(0.75) WITHIN GROUP (ORDER BY base_salary) AS p75
+        FROM placements
+        WHERE LOWER(job_title) = LOWER($1) AND currency = $2 AND offer_id IS DISTINCT FROM $3
+        "#,
+    )
+    .bind(&offer.job_title)
+    .bind(&offer.currency)
+    .bind(offer_id)
+    .fetch_one(db_pool)
+    .await?;
+
+    let mut lines = vec![
+        format!("Role: {} at {}", offer.job_title, offer.company),
+        format!("Base salary: {} {} per year", offer.base_salary, offer.currency),
+    ];
+    for component in offer.compensation.iter() {
+        let period = match component.period {
+            CompensationPeriod::Annual => "per year",
+            CompensationPeriod::Monthly => "per month",
+            CompensationPeriod::OneTime => "one time",
+        };
+        lines.push(format!("{}: {} {} {}", component.label, component.amount, offer.currency, period));
+    }
+    if let Some(start_date) = offer.start_date {
+        lines.push(format!("Start date: {}", start_date));
+    }
+    match (offer.salary_min, offer.salary_max) {
+        (Some(min), Some(max)) => lines.push(format!("Posted salary range: {} to {} {}", min, max, offer.currency)),
+        (Some(min), None) => lines.push(format!("Posted salary: from {} {}", min, offer.currency)),
+        (None, Some(max)) => lines.push(format!("Posted salary: up to {} {}", max, offer.currency)),
+        (None, None) => lines.push("Posted salary range: not published".to_string()),
+    }
+    if band.placements >= reporting::min_group_size()
+        && let (Some(p25), Some(median), Some(p75)) = (band.p25, band.median, band.p75)
+    {
+        lines.push(format!(
+            "Platform placements as {} ({}): 25th percentile {:.0}, median {:.0}, 75th percentile {:.0} {}",
+            offer.job_title, band.placements, p25, median, p75, offer.currency
+        ));
+    } else {
+        lines.push("Platform placements for this role: too few to report".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+/// The scenario and transcript as one prompt input.
+fn negotiation_text(scenario: &str, transcript: &[ChatMessage]) -> String {
+    let turns: Vec<String> = transcript
+        .iter()
+        .map(|message| match message.role {
+            ChatRole::User => format!("Candidate: {}", message.content.trim()),
+            _ => format!("Recruiter: {}", message.content.trim()),
+        })
+        .collect();
+    let conversation = if turns.is_empty() {
+        "The call is just starting. Open by presenting the offer and asking for the candidate's thoughts.".to_string()
+    } else {
+        turns.join("\n\n")
+    };
+    format!("Offer and benchmarks:\n{}\n\nConversation so far:\n{}", scenario, conversation)
+}
+
+fn candidate_turns(transcript: &[ChatMessage]) -> usize {
+    transcript.iter().filter(|m| matches!(m.role, ChatRole::User)).count()
+}
+
+/// Asks the recruiter for its next message: the reply, whether the
+/// negotiation has concluded, and any change to the offer.
+async fn recruiter_reply(
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    negotiation: String,
+) -> AppResult<(String, bool, Option<String>)> {
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::NegotiationReply,
+            provider,
+            input: negotiation,
+            parameters: None,
+            capture: true,
+        })
+        .await?;
+    let reply = response
+        .data
+        .get("reply")
+        .and_then(|r| r.as_str())
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| AppError::ExternalServiceError("The recruiter reply was empty".to_string()))?
+        .to_string();
+    let concluded = response.data.get("concluded").and_then(|c| c.as_bool()).unwrap_or(false);
+    let revised_offer = response
+        .data
+        .get("revised_offer")
+        .and_then(|r| r.as_str())
+        .map(str::to_string);
+    Ok((reply, concluded, revised_offer))
+}
+
+/// Starts a session on one of the user's offers with the recruiter's
+/// opening message.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the offer is not on one of the user's
+/// applications, or an error if the AI call or database operation fails.
+pub async fn start(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    offer_id: i32,
+) -> AppResult<NegotiationTurn> {
+    let scenario = scenario(db_pool, user_id, offer_id).await?;
+    let (reply, _, _) = recruiter_reply(ai, provider, user_id, negotiation_text(&scenario, &[])).await?;
+
+    let session = sqlx::query_as::<_, NegotiationSession>(&format!(
+        r#"
+        INSERT INTO negotiation_sessions (user_id, offer_id, scenario, transcript)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}
+        "#,
+        SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(offer_id)
+    .bind(&scenario)
+    .bind(Json(vec![ChatMessage::assistant(reply.clone())]))
+    .fetch_one(db_pool)
+    .await?;
+
+    info!("Negotiation session {} started on offer {}", session.id, offer_id);
+    Ok(NegotiationTurn {
+        reply: Some(reply),
+        revised_offer: None,
+        turns_left: MAX_CANDIDATE_TURNS,
+        session,
+    })
+}
+
+/// Adds the candidate's message to an active session and gets the
+/// recruiter's reply. When `end` is set, the recruiter concludes, or the
+/// turn limit is reached, the session is completed with a debrief.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such session,
+/// `AppError::BadRequest` if it has ended or there is nothing to send, or an
+/// error if the AI call or database operation fails.
+pub async fn reply(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    session_id: i32,
+    message: Option<&str>,
+    end: bool,
+) -> AppResult<NegotiationTurn> {
+    let session = sqlx::query_as::<_, NegotiationSession>(&format!(
+        "SELECT {} FROM negotiation_sessions WHERE id = $1 AND user_id = $2",
+        SESSION_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if session.status == NegotiationStatus::Completed {
+        return Err(AppError::BadRequest("This negotiation has ended; start a new one".to_string()));
+    }
+
+    let mut transcript = session.transcript.0;
+    let message = message.map(str::trim).filter(|m| !m.is_empty());
+    match message {
+        Some(message) => transcript.push(ChatMessage::user(message)),
+        None if !end => return Err(AppError::ValidationError("message is required".to_string())),
+        None => {}
+    }
+
+    let mut reply = None;
+    let mut revised_offer = None;
+    let mut concluded = end;
+    if !end {
+        let (text, done, revised) =
+            recruiter_reply(ai, provider.clone(), user_id, negotiation_text(&session.scenario, &transcript)).await?;
+        transcript.push(ChatMessage::assistant(text.clone()));
+        reply = Some(text);
+        revised_offer = revised;
+        concluded = done || candidate_turns(&transcript) >= MAX_CANDIDATE_TURNS;
+    }
+
+    let debrief = if concluded {
+        let response = ai
+            .process_action(AIActionRequest {
+                user_id: Some(user_id),
+                action: ActionType::NegotiationDebrief,
+                provider,
+                input: negotiation_text(&session.scenario, &transcript),
+                parameters: None,
+                capture: true,
+            })
+            .await?;
+        Some(response.data)
+    } else {
+        None
+    };
+
+    let session = sqlx::query_as::<_, NegotiationSession>(&format!(
+        r#"
+        UPDATE negotiation_sessions
+        SET transcript = $2,
+            status = CASE WHEN $3::jsonb IS NULL THEN status ELSE 'completed'::negotiation_status END,
+            debrief = $3, updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        SESSION_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(Json(&transcript))
+    .bind(debrief)
+    .fetch_one(db_pool)
+    .await?;
+
+    if session.status == NegotiationStatus::Completed {
+        info!("Negotiation session {} completed", session_id);
+    }
+    Ok(NegotiationTurn {
+        reply,
+        revised_offer,
+        turns_left: MAX_CANDIDATE_TURNS.saturating_sub(candidate_turns(&transcript)),
+        session,
+    })
+}
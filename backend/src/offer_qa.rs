@@ -0,0 +1,283 @@
+//! Question answering over uploaded job offer documents.
+//!
+//! A job seeker uploads an offer letter or contract (PDF) and asks questions
+//! about it: notice period, equity vesting, non-compete terms. The text is
+//! cut into chunks (see [`retrieval::chunk_text`]) and embedded, and each
+//! answer is grounded only in the closest chunks of that one document.
+//!
+//! Offer documents are sensitive, so they are handled apart from the
+//! mentor's documents:
+//! - the file and every chunk's text are encrypted at rest with the
+//!   [`DocumentCipher`]; only the embeddings are stored in the clear,
+//! - chunks live in `offer_qa_chunks`, never in `document_chunks`, so mentor
+//!   retrieval, the semantic cache, and conversations never see them,
+//! - questions and answers are not stored, and no activity events are
+//!   recorded; only content-free AI usage metering applies.
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::encryption::DocumentCipher;
+use crate::errors::{AppError, AppResult};
+use crate::retrieval;
+use crate::storage::Storage;
+
+/// Chunks used to answer a question.
+const CHUNKS_PER_QUESTION: i64 = 5;
+
+/// Lowest cosine similarity for a chunk to count as relevant.
+const MIN_SIMILARITY: f64 = 0.3;
+
+/// Characters of a chunk shown as its source excerpt.
+const EXCERPT_CHARS: usize = 240;
+
+/// An uploaded offer document.
+#[derive(Debug, Serialize, FromRow)]
+pub struct OfferQaDocument {
+    pub id: i32,
+    pub title: String,
+    pub file_name: String,
+    pub size_bytes: i64,
+    /// Chunks of text the document was cut into
+    pub chunk_count: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A passage of the document an answer is based on.
+#[derive(Debug, Serialize)]
+pub struct OfferSource {
+    /// Number the answer cites the passage by, e.g. `[2]`
+    pub reference: usize,
+    pub chunk_index: i16,
+    pub excerpt: String,
+    pub similarity: f64,
+}
+
+/// An answer from the document.
+#[derive(Debug, Serialize)]
+pub struct OfferAnswer {
+    pub document_id: i32,
+    pub answer: serde_json::Value,
+    /// Passages the answer is based on; empty when the document does not
+    /// cover the question
+    pub sources: Vec<OfferSource>,
+}
+
+#[derive(Debug, FromRow)]
+struct ChunkRow {
+    chunk_index: i16,
+    content_ciphertext: Vec<u8>,
+    similarity: f64,
+}
+
+/// Stores an uploaded offer document: the PDF is encrypted into storage and
+/// its extracted `text` is chunked, embedded, and encrypted into
+/// `offer_qa_chunks`.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the document has no text, or an error
+/// if embedding, storage, or the database operation fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn upload(
+    db_pool: &PgPool,
+    storage: &Storage,
+    cipher: &DocumentCipher,
+    ai: &AIService,
+    user_id: Uuid,
+    title: &str,
+    file_name: &str,
+    file: &[u8],
+    text: &str,
+) -> AppResult<OfferQaDocument> {
+    let chunks = retrieval::chunk_text(text);
+    if chunks.is_empty() {
+        return Err(AppError::BadRequest(
+            "No text could be extracted from the document; scanned PDFs are not supported".to_string(),
+        ));
+    }
+    let vectors = ai.embed(&chunks).await?;
+
+    let storage_key = format!("offer-qa/{}/{}.bin", user_id, Uuid::new_v4());
+    storage.put(&storage_key, &cipher.encrypt(file)?).await?;
+
+    let mut tx = db_pool.begin().await?;
+    let document = sqlx::query_as::<_, OfferQaDocument>(
+        r#"
+        INSERT INTO offer_qa_documents (user_id, title, file_name, size_bytes, storage_key, chunk_count)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, title, file_name, size_bytes, chunk_count, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(title.chars().take(255).collect::<String>())
+    .bind(file_name)
+    .bind(file.len() as i64)
+    .bind(&storage_key)
+    .bind(chunks.len() as i32)
+    .fetch_one(&mut *tx)
+    .await?;
+    for (index, (chunk, vector)) in chunks.iter().zip(&vectors).enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO offer_qa_chunks (document_id, chunk_index, content_ciphertext, embedding)
+            VALUES ($1, $2, $3, $4::vector)
+            "#,
+        )
+        .bind(document.id)
+        .bind(index as i16)
+        .bind(cipher.encrypt(chunk.as_bytes())?)
+        .bind(retrieval::vector_literal(vector))
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    info!("Offer document {} uploaded with {} chunks", document.id, chunks.len());
+    Ok(document)
+}
+
+/// The user's offer documents, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<OfferQaDocument>> {
+    let documents = sqlx::query_as::<_, OfferQaDocument>(
+        r#"
+        SELECT id, title, file_name, size_bytes, chunk_count, created_at
+        FROM offer_qa_documents
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(documents)
+}
+
+/// Deletes one of the user's offer documents, its chunks, and its file.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such document, or an
+/// error if storage or the database operation fails.
+pub async fn delete(db_pool: &PgPool, storage: &Storage, user_id: Uuid, document_id: i32) -> AppResult<()> {
+    let storage_key = sqlx::query_scalar::<_, String>(
+        "DELETE FROM offer_qa_documents WHERE id = $1 AND user_id = $2 RETURNING storage_key",
+    )
+    .bind(document_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    storage.delete(&storage_key).await?;
+    info!("Offer document {} deleted", document_id);
+    Ok(())
+}
+
+/// Answers a question from one of the user's offer documents only.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such document, or an
+/// error if embedding, decryption, the AI call, or the database operation
+/// fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn ask(
+    db_pool: &PgPool,
+    cipher: &DocumentCipher,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    document_id: i32,
+    question: &str,
+) -> AppResult<OfferAnswer> {
+    let title = sqlx::query_scalar::<_, String>("SELECT title FROM offer_qa_documents WHERE id = $1 AND user_id = $2")
+        .bind(document_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let Some(query_vector) = ai.embed(&[question.to_string()]).await?.pop() else {
+        return Err(AppError::InternalServerError);
+    };
+    let rows = sqlx::query_as::<_, ChunkRow>(
+        r#"
+        SELECT chunk_index, content_ciphertext, 1 - (embedding <=> $2::vector) AS similarity
+        FROM offer_qa_chunks
+        WHERE document_id = $1
+        ORDER BY embedding <=> $2::vector
+        LIMIT $3
+        "#,
+    )
+    .bind(document_id)
+    .bind(retrieval::vector_literal(&query_vector))
+    .bind(CHUNKS_PER_QUESTION)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut passages = Vec::new();
+    for row in rows.into_iter().filter(|r| r.similarity >= MIN_SIMILARITY) {
+        passages.push((row.chunk_index, cipher.decrypt_text(&row.content_ciphertext)?, row.similarity));
+    }
+
+    let excerpts: Vec<String> = passages
+        .iter()
+        .enumerate()
+        .map(|(i, (_, text, _))| format!("[{}] {}", i + 1, text))
+        .collect();
+    let context = if excerpts.is_empty() {
+        format!(
+            "No passage of the user's offer document \"{}\" matches the question. Say that the document does not appear to cover it and suggest asking the employer.",
+            title
+        )
+    } else {
+        format!(
+            "Answer only from these excerpts of the user's offer document \"{}\" and cite them as [n]. If they do not answer the question, say the document does not cover it; do not guess or use outside knowledge. This is not legal advice.\n{}",
+            title,
+            excerpts.join("\n\n")
+        )
+    };
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::AskQuestion,
+            provider,
+            input: question.to_string(),
+            parameters: Some(serde_json::json!({ "context": context })),
+            // The excerpts are decrypted offer document text
+            capture: false,
+        })
+        .await?;
+
+    let sources = passages
+        .into_iter()
+        .enumerate()
+        .map(|(i, (chunk_index, text, similarity))| {
+            let mut excerpt: String = text.chars().take(EXCERPT_CHARS).collect();
+            if text.chars().count() > EXCERPT_CHARS {
+                excerpt.push('…');
+            }
+            OfferSource {
+                reference: i + 1,
+                chunk_index,
+                excerpt,
+                similarity,
+            }
+        })
+        .collect();
+
+    Ok(OfferAnswer {
+        document_id,
+        answer: response.data,
+        sources,
+    })
+}
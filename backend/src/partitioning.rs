@@ -0,0 +1,160 @@
+//! Monthly partition management and archival for high-volume log tables.
+//!
+//! `activity_events`, `ai_usage_logs`, and `audit_logs` are range-partitioned
+//! by month on `created_at` (see `migrations/005_partitioned_logs.sql`).
+//! The scheduler calls [`ensure_partitions`] so inserts always land in a
+//! monthly partition, and [`archive_cold_partitions`] to export partitions
+//! older than the retention window to gzip-compressed JSON Lines files before
+//! detaching and dropping them.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use tracing::{debug, info, warn};
+
+use crate::errors::{AppError, AppResult};
+
+/// Tables that are partitioned by month.
+pub const PARTITIONED_TABLES: &[&str] = &["activity_events", "ai_usage_logs", "audit_logs"];
+
+/// Returns the first day of the month containing `date`.
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("day 1 is always valid")
+}
+
+/// Parses the month from a partition name of the form `<parent>_pYYYYMM`.
+fn partition_month(parent: &str, partition: &str) -> Option<NaiveDate> {
+    let suffix = partition.strip_prefix(parent)?.strip_prefix("_p")?;
+    if suffix.len() != 6 || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year = suffix[..4].parse().ok()?;
+    let month = suffix[4..].parse().ok()?;
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// Creates monthly partitions for the current month and `months_ahead`
+/// following months on every partitioned table.
+///
+/// Safe to call repeatedly; existing partitions are left untouched.
+pub async fn ensure_partitions(db_pool: &PgPool, months_ahead: u32) -> AppResult<()> {
+    let current = month_start(Utc::now().date_naive());
+
+    for table in PARTITIONED_TABLES {
+        for offset in 0..=months_ahead {
+            let start = current
+                .checked_add_months(Months::new(offset))
+                .ok_or(AppError::InternalServerError)?;
+            let name = sqlx::query_scalar::<_, String>("SELECT create_monthly_partition($1, $2)")
+                .bind(table)
+                .bind(start)
+                .fetch_one(db_pool)
+                .await?;
+            debug!("Ensured partition {}", name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Exports and drops monthly partitions older than `retain_months`.
+///
+/// Each cold partition is written to `<archive_dir>/<partition>.jsonl.gz`
+/// (one JSON object per row), then detached from its parent and dropped.
+/// Default partitions are never archived.
+///
+/// # Returns
+///
+/// The paths of the archive files written.
+pub async fn archive_cold_partitions(
+    db_pool: &PgPool,
+    archive_dir: &Path,
+    retain_months: u32,
+) -> AppResult<Vec<PathBuf>> {
+    let cutoff = month_start(Utc::now().date_naive())
+        .checked_sub_months(Months::new(retain_months))
+        .ok_or(AppError::InternalServerError)?;
+
+    std::fs::create_dir_all(archive_dir).map_err(|e| {
+        warn!("Failed to create archive directory {:?}: {}", archive_dir, e);
+        AppError::InternalServerError
+    })?;
+
+    let mut archived = Vec::new();
+
+    for table in PARTITIONED_TABLES {
+        let partitions = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT child.relname::TEXT
+            FROM pg_inherits
+            JOIN pg_class parent ON parent.oid = pg_inherits.inhparent
+            JOIN pg_class child ON child.oid = pg_inherits.inhrelid
+            WHERE parent.relname = $1
+            ORDER BY child.relname
+            "#,
+        )
+        .bind(table)
+        .fetch_all(db_pool)
+        .await?;
+
+        for partition in partitions {
+            let Some(month) = partition_month(table, &partition) else {
+                continue;
+            };
+            if month >= cutoff {
+                continue;
+            }
+
+            let path = archive_dir.join(format!("{}.jsonl.gz", partition));
+            let rows = export_partition(db_pool, &partition, &path).await?;
+
+            // Names come from pg_catalog and matched <parent>_pYYYYMM above
+            sqlx::query(&format!(
+                "ALTER TABLE \"{}\" DETACH PARTITION \"{}\"",
+                table, partition
+            ))
+            .execute(db_pool)
+            .await?;
+            sqlx::query(&format!("DROP TABLE \"{}\"", partition))
+                .execute(db_pool)
+                .await?;
+
+            info!("Archived partition {} ({} rows) to {:?}", partition, rows, path);
+            archived.push(path);
+        }
+    }
+
+    Ok(archived)
+}
+
+/// Streams every row of `partition` as JSON into a gzip file at `path`.
+async fn export_partition(db_pool: &PgPool, partition: &str, path: &Path) -> AppResult<u64> {
+    let file = std::fs::File::create(path).map_err(|e| {
+        warn!("Failed to create archive file {:?}: {}", path, e);
+        AppError::InternalServerError
+    })?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+
+    let query = format!("SELECT row_to_json(t)::TEXT FROM \"{}\" t", partition);
+    let mut rows = sqlx::query_scalar::<_, String>(&query).fetch(db_pool);
+    let mut count = 0u64;
+
+    let write_err = |e: std::io::Error| {
+        warn!("Failed to write archive file {:?}: {}", path, e);
+        AppError::InternalServerError
+    };
+
+    while let Some(row) = rows.try_next().await? {
+        encoder.write_all(row.as_bytes()).map_err(write_err)?;
+        encoder.write_all(b"\n").map_err(write_err)?;
+        count += 1;
+    }
+
+    encoder.finish().map_err(write_err)?;
+    Ok(count)
+}
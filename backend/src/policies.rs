@@ -0,0 +1,349 @@
+//! Versioned terms of service and privacy policy, and their acceptance.
+//!
+//! Admins publish a policy as the next version of its kind. The newest
+//! version of each kind is in force, and a signed-in user who has not
+//! accepted it is stopped by [`require_acceptance`] on every API route
+//! except the policy and sign-in routes until they do. Acceptances record the
+//! version, the time, the connection's address, any `X-Forwarded-For`
+//! header, and the user agent, and are never deleted with a policy.
+
+use axum::{
+    extract::{OptionalFromRequestParts, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::errors::{AppError, AppResult};
+use crate::models::PolicyKind;
+
+/// Routes usable while acceptance is pending: reading and accepting the
+/// policies, and signing in.
+const EXEMPT_PREFIXES: &[&str] = &["/policies", "/login", "/register", "/auth", "/demo"];
+
+/// A published policy version.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyDocument {
+    pub id: i32,
+    pub kind: PolicyKind,
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    /// What changed since the previous version
+    pub summary: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// A policy version awaiting the user's acceptance.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PendingPolicy {
+    pub kind: PolicyKind,
+    pub version: i32,
+    pub title: String,
+    pub summary: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// A recorded acceptance.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyAcceptance {
+    pub user_id: Uuid,
+    pub kind: PolicyKind,
+    pub version: i32,
+    pub accepted_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub forwarded_for: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// A policy version with how many users accepted it.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyVersion {
+    pub id: i32,
+    pub kind: PolicyKind,
+    pub version: i32,
+    pub title: String,
+    pub summary: Option<String>,
+    pub published_by: Option<Uuid>,
+    pub published_at: DateTime<Utc>,
+    /// Whether this is the version in force
+    pub current: bool,
+    pub acceptances: i64,
+}
+
+/// Where an acceptance came from.
+#[derive(Debug, Default)]
+pub struct AcceptanceSource {
+    pub ip_address: Option<String>,
+    pub forwarded_for: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+const DOCUMENT_COLUMNS: &str = "d.id, d.kind, d.version, d.title, d.body, d.summary, d.published_at";
+
+const ACCEPTANCE_COLUMNS: &str =
+    "a.user_id, a.kind, a.version, a.accepted_at, a.ip_address, a.forwarded_for, a.user_agent";
+
+/// The version in force of each kind.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn current(db_pool: &PgPool) -> AppResult<Vec<PolicyDocument>> {
+    let documents = sqlx::query_as::<_, PolicyDocument>(&format!(
+        "SELECT DISTINCT ON (d.kind) {} FROM policy_documents d ORDER BY d.kind, d.version DESC",
+        DOCUMENT_COLUMNS
+    ))
+    .fetch_all(db_pool)
+    .await?;
+    Ok(documents)
+}
+
+/// A policy's version in force, or a given earlier version.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no such version is published, or an
+/// error if the database operation fails.
+pub async fn get(db_pool: &PgPool, kind: PolicyKind, version: Option<i32>) -> AppResult<PolicyDocument> {
+    sqlx::query_as::<_, PolicyDocument>(&format!(
+        r#"
+        SELECT {} FROM policy_documents d
+        WHERE d.kind = $1 AND ($2::int IS NULL OR d.version = $2)
+        ORDER BY d.version DESC
+        LIMIT 1
+        "#,
+        DOCUMENT_COLUMNS
+    ))
+    .bind(kind)
+    .bind(version)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Versions in force the user has not accepted.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn pending(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<PendingPolicy>> {
+    let pending = sqlx::query_as::<_, PendingPolicy>(
+        r#"
+        SELECT d.kind, d.version, d.title, d.summary, d.published_at
+        FROM (
+            SELECT DISTINCT ON (kind) id, kind, version, title, summary, published_at
+            FROM policy_documents
+            ORDER BY kind, version DESC
+        ) d
+        WHERE NOT EXISTS (
+            SELECT 1 FROM policy_acceptances a WHERE a.user_id = $1 AND a.policy_id = d.id
+        )
+        ORDER BY d.kind
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(pending)
+}
+
+/// Records the user's acceptance of the version in force. `version` must
+/// be that version, so users accept the text they were shown; accepting
+/// again keeps the first record.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no version of the policy is published,
+/// `AppError::ValidationError` if `version` is not the one in force, or an
+/// error if the database operation fails.
+pub async fn accept(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    kind: PolicyKind,
+    version: i32,
+    source: AcceptanceSource,
+) -> AppResult<PolicyAcceptance> {
+    let document = get(db_pool, kind, None).await?;
+    if document.version != version {
+        return Err(AppError::ValidationError(format!(
+            "Version {} is not in force; accept version {}",
+            version, document.version
+        )));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO policy_acceptances
+            (user_id, policy_id, kind, version, ip_address, forwarded_for, user_agent)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (user_id, policy_id) DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(document.id)
+    .bind(kind)
+    .bind(version)
+    .bind(&source.ip_address)
+    .bind(&source.forwarded_for)
+    .bind(&source.user_agent)
+    .execute(db_pool)
+    .await?;
+    info!("User {} accepted {} version {}", user_id, kind.as_str(), version);
+
+    sqlx::query_as::<_, PolicyAcceptance>(&format!(
+        "SELECT {} FROM policy_acceptances a WHERE a.user_id = $1 AND a.policy_id = $2",
+        ACCEPTANCE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(document.id)
+    .fetch_one(db_pool)
+    .await
+    .map_err(Into::into)
+}
+
+/// The user's acceptances, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn acceptances(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<PolicyAcceptance>> {
+    let acceptances = sqlx::query_as::<_, PolicyAcceptance>(&format!(
+        "SELECT {} FROM policy_acceptances a WHERE a.user_id = $1 ORDER BY a.accepted_at DESC",
+        ACCEPTANCE_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(acceptances)
+}
+
+/// Acceptances of one policy version, oldest first, for compliance exports.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the version does not exist, or an error
+/// if the database operation fails.
+pub async fn acceptances_of(
+    db_pool: &PgPool,
+    policy_id: i32,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<PolicyAcceptance>> {
+    sqlx::query_scalar::<_, i32>("SELECT id FROM policy_documents WHERE id = $1")
+        .bind(policy_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let acceptances = sqlx::query_as::<_, PolicyAcceptance>(&format!(
+        r#"
+        SELECT {} FROM policy_acceptances a
+        WHERE a.policy_id = $1
+        ORDER BY a.accepted_at, a.id
+        LIMIT $2 OFFSET $3
+        "#,
+        ACCEPTANCE_COLUMNS
+    ))
+    .bind(policy_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(acceptances)
+}
+
+/// Every published version, newest first per kind, with acceptance counts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn versions(db_pool: &PgPool) -> AppResult<Vec<PolicyVersion>> {
+    let versions = sqlx::query_as::<_, PolicyVersion>(
+        r#"
+        SELECT d.id, d.kind, d.version, d.title, d.summary, d.published_by, d.published_at,
+               d.version = MAX(d.version) OVER (PARTITION BY d.kind) AS current,
+               (SELECT COUNT(*) FROM policy_acceptances a WHERE a.policy_id = d.id) AS acceptances
+        FROM policy_documents d
+        ORDER BY d.kind, d.version DESC
+        "#,
+    )
+    .fetch_all(db_pool)
+    .await?;
+    Ok(versions)
+}
+
+/// Publishes the next version of a policy. It is in force at once, so every
+/// user must accept it before using the API again.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn publish(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    kind: PolicyKind,
+    title: &str,
+    body: &str,
+    summary: Option<&str>,
+) -> AppResult<PolicyDocument> {
+    let document = sqlx::query_as::<_, PolicyDocument>(
+        r#"
+        INSERT INTO policy_documents (kind, version, title, body, summary, published_by)
+        SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5
+        FROM policy_documents WHERE kind = $1
+        RETURNING id, kind, version, title, body, summary, published_at
+        "#,
+    )
+    .bind(kind)
+    .bind(title.trim())
+    .bind(body)
+    .bind(summary.map(str::trim).filter(|s| !s.is_empty()))
+    .bind(admin_id)
+    .fetch_one(db_pool)
+    .await?;
+    info!("{} version {} published by {}", kind.as_str(), document.version, admin_id);
+    Ok(document)
+}
+
+/// Middleware that stops signed-in users who have not accepted the policies
+/// in force, with `403` and the pending versions. The routes in
+/// [`EXEMPT_PREFIXES`] pass through before the token is looked at, as do
+/// requests with an impersonation token and requests without a valid token,
+/// which the routes themselves reject if they need one.
+pub async fn require_acceptance(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> AppResult<Response> {
+    if EXEMPT_PREFIXES.iter().any(|prefix| request.uri().path().starts_with(prefix)) {
+        return Ok(next.run(request).await);
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let auth_user = <AuthUser as OptionalFromRequestParts<AppState>>::from_request_parts(&mut parts, &app_state)
+        .await
+        .ok()
+        .flatten();
+    let request = Request::from_parts(parts, body);
+    // Admins impersonating a user cannot accept for them, so they are let through
+    if let Some(auth_user) = auth_user
+        && auth_user.impersonation.is_none()
+    {
+        let pending = pending(&app_state.db_pool, auth_user.user_id).await?;
+        if !pending.is_empty() {
+            return Err(AppError::PolicyAcceptanceRequired {
+                pending: pending
+                    .into_iter()
+                    .map(|p| (p.kind.as_str().to_string(), p.version))
+                    .collect(),
+            });
+        }
+    }
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,284 @@
+//! Multi-roadmap portfolio.
+//!
+//! Users often follow several roadmaps at once. [`summary`] adds up the
+//! weekly hours their unfinished roadmaps ask for, compares that with the
+//! `weekly_learning_hours` they said they have, and warns about conflicts:
+//! too little time, no stated availability, or the same technologies left to
+//! learn in more than one roadmap. One roadmap can be marked primary with
+//! [`set_primary`]; it leads the weekly digest and the mentor questions the
+//! summary suggests.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Mentor questions suggested for the primary roadmap.
+const SUGGESTED_QUESTIONS: usize = 3;
+
+/// An unfinished roadmap in the portfolio.
+#[derive(Debug, Serialize)]
+pub struct PortfolioRoadmap {
+    pub id: i32,
+    pub title: String,
+    pub target_role: String,
+    pub learning_hours_per_week: i32,
+    pub progress_percentage: i32,
+    pub is_primary: bool,
+    /// The first phase not yet completed, as "Phase 3: APIs"
+    pub next_phase: Option<String>,
+}
+
+/// Kinds of portfolio warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// The roadmaps need more hours a week than the user has
+    OverCapacity,
+    /// The user has not said how many hours a week they have
+    AvailabilityUnset,
+    /// Roadmaps have technologies left to learn in common
+    Overlap,
+}
+
+/// A conflict between the user's roadmaps or with their availability.
+#[derive(Debug, Serialize)]
+pub struct PortfolioWarning {
+    pub kind: WarningKind,
+    pub message: String,
+    /// Roadmaps the warning is about
+    pub roadmap_ids: Vec<i32>,
+}
+
+/// The user's unfinished roadmaps taken together.
+#[derive(Debug, Serialize)]
+pub struct PortfolioSummary {
+    /// Unfinished roadmaps, primary first, then newest first
+    pub roadmaps: Vec<PortfolioRoadmap>,
+    pub primary_roadmap_id: Option<i32>,
+    /// Hours a week all unfinished roadmaps ask for
+    pub weekly_hours_required: i32,
+    /// Hours a week the user has for learning, if stated
+    pub weekly_hours_available: Option<i32>,
+    pub warnings: Vec<PortfolioWarning>,
+    /// Mentor questions about the primary roadmap's next phase
+    pub suggested_questions: Vec<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct RoadmapRow {
+    id: i32,
+    title: String,
+    target_role: String,
+    roadmap_data: Value,
+    learning_hours_per_week: Option<i32>,
+    progress_percentage: Option<i32>,
+    completed_phases: Option<Vec<i32>>,
+    is_primary: bool,
+}
+
+/// A roadmap's phases that are not completed yet.
+fn remaining_phases<'a>(roadmap_data: &'a Value, completed: &[i32]) -> Vec<(i32, &'a Value)> {
+    roadmap_data
+        .get("phases")
+        .and_then(Value::as_array)
+        .map(|phases| {
+            phases
+                .iter()
+                .enumerate()
+                .map(|(index, phase)| {
+                    let number = phase.get("phase").and_then(Value::as_i64).map_or(index as i32 + 1, |n| n as i32);
+                    (number, phase)
+                })
+                .filter(|(number, _)| !completed.contains(number))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string_list(value: Option<&Value>) -> Vec<&str> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Builds the user's portfolio summary.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn summary(db_pool: &PgPool, user_id: Uuid) -> AppResult<PortfolioSummary> {
+    let rows = sqlx::query_as::<_, RoadmapRow>(
+        r#"
+        SELECT id, title, target_role, roadmap_data, learning_hours_per_week,
+               progress_percentage, completed_phases, is_primary
+        FROM career_roadmaps
+        WHERE user_id = $1 AND COALESCE(progress_percentage, 0) < 100
+        ORDER BY is_primary DESC, created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    let available = sqlx::query_scalar::<_, Option<i32>>("SELECT weekly_learning_hours FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+
+    let mut roadmaps = Vec::with_capacity(rows.len());
+    // Technology (lowercased) -> display name and the roadmaps still teaching it
+    let mut remaining_technologies: BTreeMap<String, (String, BTreeSet<i32>)> = BTreeMap::new();
+    let mut suggested_questions = Vec::new();
+    for row in &rows {
+        let remaining = remaining_phases(&row.roadmap_data, row.completed_phases.as_deref().unwrap_or_default());
+        for (_, phase) in &remaining {
+            for technology in string_list(phase.get("technologies")) {
+                remaining_technologies
+                    .entry(technology.to_lowercase())
+                    .or_insert_with(|| (technology.to_string(), BTreeSet::new()))
+                    .1
+                    .insert(row.id);
+            }
+        }
+
+        let next = remaining.first();
+        if row.is_primary
+            && let Some((number, phase)) = next
+        {
+            suggested_questions = suggest_questions(&row.target_role, *number, phase);
+        }
+        roadmaps.push(PortfolioRoadmap {
+            id: row.id,
+            title: row.title.clone(),
+            target_role: row.target_role.clone(),
+            learning_hours_per_week: row.learning_hours_per_week.unwrap_or(0),
+            progress_percentage: row.progress_percentage.unwrap_or(0),
+            is_primary: row.is_primary,
+            next_phase: next.map(|(number, phase)| {
+                format!(
+                    "Phase {}: {}",
+                    number,
+                    phase.get("title").and_then(Value::as_str).unwrap_or_default()
+                )
+            }),
+        });
+    }
+
+    let required: i32 = roadmaps.iter().map(|r| r.learning_hours_per_week).sum();
+    let all_ids: Vec<i32> = roadmaps.iter().map(|r| r.id).collect();
+    let mut warnings = Vec::new();
+    match available {
+        Some(available) if required > available => warnings.push(PortfolioWarning {
+            kind: WarningKind::OverCapacity,
+            message: format!(
+                "Your roadmaps need {} hours a week, {} more than the {} you have. Pause one or lower its pace.",
+                required,
+                required - available,
+                available
+            ),
+            roadmap_ids: all_ids,
+        }),
+        None if roadmaps.len() > 1 => warnings.push(PortfolioWarning {
+            kind: WarningKind::AvailabilityUnset,
+            message: format!(
+                "Your roadmaps need {} hours a week. Set weekly_learning_hours in your profile to check that you have the time.",
+                required
+            ),
+            roadmap_ids: all_ids,
+        }),
+        _ => {}
+    }
+
+    // One warning per group of roadmaps sharing technologies
+    let mut overlaps: BTreeMap<Vec<i32>, Vec<String>> = BTreeMap::new();
+    for (name, ids) in remaining_technologies.into_values() {
+        if ids.len() > 1 {
+            overlaps.entry(ids.into_iter().collect()).or_default().push(name);
+        }
+    }
+    for (roadmap_ids, technologies) in overlaps {
+        warnings.push(PortfolioWarning {
+            kind: WarningKind::Overlap,
+            message: format!(
+                "{} roadmaps still cover {}. Learn them once in your primary roadmap.",
+                roadmap_ids.len(),
+                technologies.join(", ")
+            ),
+            roadmap_ids,
+        });
+    }
+
+    Ok(PortfolioSummary {
+        primary_roadmap_id: roadmaps.iter().find(|r| r.is_primary).map(|r| r.id),
+        roadmaps,
+        weekly_hours_required: required,
+        weekly_hours_available: available,
+        warnings,
+        suggested_questions,
+    })
+}
+
+/// Mentor questions about a roadmap's next phase.
+fn suggest_questions(target_role: &str, number: i32, phase: &Value) -> Vec<String> {
+    let title = phase.get("title").and_then(Value::as_str).unwrap_or_default();
+    let mut questions = vec![format!(
+        "What should I focus on first in Phase {} ({}) to become a {}?",
+        number, title, target_role
+    )];
+    if let Some(technology) = string_list(phase.get("technologies")).first() {
+        questions.push(format!("What is a good first project to practice {}?", technology));
+    }
+    if let Some(goal) = string_list(phase.get("learning_goals")).first() {
+        questions.push(format!("How do I know when I can \"{}\"?", goal));
+    }
+    questions.truncate(SUGGESTED_QUESTIONS);
+    questions
+}
+
+/// Marks one of the user's roadmaps primary, clearing any other.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the roadmap is not the user's, or an
+/// error if the database operation fails.
+pub async fn set_primary(db_pool: &PgPool, user_id: Uuid, roadmap_id: i32) -> AppResult<()> {
+    let mut tx = db_pool.begin().await?;
+    sqlx::query("UPDATE career_roadmaps SET is_primary = FALSE WHERE user_id = $1 AND is_primary AND id <> $2")
+        .bind(user_id)
+        .bind(roadmap_id)
+        .execute(&mut *tx)
+        .await?;
+    let updated = sqlx::query("UPDATE career_roadmaps SET is_primary = TRUE WHERE id = $1 AND user_id = $2")
+        .bind(roadmap_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Clears the primary mark from the user's roadmap.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the roadmap is not the user's, or an
+/// error if the database operation fails.
+pub async fn clear_primary(db_pool: &PgPool, user_id: Uuid, roadmap_id: i32) -> AppResult<()> {
+    let updated = sqlx::query("UPDATE career_roadmaps SET is_primary = FALSE WHERE id = $1 AND user_id = $2")
+        .bind(roadmap_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
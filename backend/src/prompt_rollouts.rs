@@ -0,0 +1,528 @@
+//! Blue/green rollouts of new prompt versions.
+//!
+//! A new prompt ships next to the current one as the action's
+//! [`candidate_prompt_version`](crate::ai::types::ActionType::candidate_prompt_version).
+//! An admin starts a rollout that sends a share of traffic to the candidate
+//! (see [`crate::ai::rollout`]), raises the share, and promotes it to all
+//! traffic; the next release makes it the current version, which completes
+//! the rollout.
+//!
+//! Every `PROMPT_ROLLOUT_CHECK_INTERVAL_SECS`, [`check`] compares the
+//! candidate's parse-failure rate (from `ai_usage_logs`) and thumbs-down
+//! rate (from `ai_feedback`) since the rollout started against the
+//! rollout's thresholds, and rolls it back to the baseline version when
+//! either is exceeded over enough samples.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ai::types::ActionType;
+use crate::errors::{AppError, AppResult};
+use crate::models::PromptRolloutStatus;
+
+/// Parse-failure rate above which a candidate is rolled back, unless the
+/// rollout sets its own.
+pub const DEFAULT_MAX_PARSE_FAILURE_RATE: f32 = 0.1;
+
+/// Thumbs-down rate above which a candidate is rolled back, unless the
+/// rollout sets its own.
+pub const DEFAULT_MAX_THUMBS_DOWN_RATE: f32 = 0.3;
+
+/// Calls or ratings of the candidate needed before its rates are judged,
+/// unless the rollout sets its own.
+pub const DEFAULT_MIN_SAMPLES: i32 = 30;
+
+/// A prompt rollout.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PromptRollout {
+    pub id: i32,
+    pub action: String,
+    /// Version served outside the rollout, and to everyone after a rollback
+    pub baseline_version: String,
+    pub candidate_version: String,
+    pub status: PromptRolloutStatus,
+    /// Share of requests sent to the candidate while active
+    pub traffic_percent: i32,
+    pub max_parse_failure_rate: f32,
+    pub max_thumbs_down_rate: f32,
+    pub min_samples: i32,
+    pub started_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub promoted_at: Option<DateTime<Utc>>,
+    pub rolled_back_at: Option<DateTime<Utc>>,
+    /// `None` for an automatic rollback
+    pub rolled_back_by: Option<Uuid>,
+    pub rollback_reason: Option<String>,
+    /// Candidate and baseline metrics when rolled back
+    pub rollback_metrics: Option<Value>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// How one prompt version did since a rollout started.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VersionMetrics {
+    pub prompt_version: String,
+    /// Provider calls that rendered this version
+    pub calls: i64,
+    /// Calls whose output could not be used
+    pub parse_failures: i64,
+    #[sqlx(skip)]
+    pub parse_failure_rate: Option<f64>,
+    /// Thumbs up and down given to results of this version
+    pub ratings: i64,
+    pub thumbs_down: i64,
+    #[sqlx(skip)]
+    pub thumbs_down_rate: Option<f64>,
+}
+
+/// A rollout with how its candidate and baseline are doing.
+#[derive(Debug, Serialize)]
+pub struct RolloutReport {
+    #[serde(flatten)]
+    pub rollout: PromptRollout,
+    pub candidate: VersionMetrics,
+    pub baseline: VersionMetrics,
+}
+
+/// Thresholds of a rollout; unset ones keep their current value, or the
+/// default for a new rollout.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RolloutThresholds {
+    pub max_parse_failure_rate: Option<f32>,
+    pub max_thumbs_down_rate: Option<f32>,
+    pub min_samples: Option<i32>,
+}
+
+/// A thumbs up or down on an AI result.
+#[derive(Debug, Serialize, FromRow)]
+pub struct AiFeedback {
+    pub id: i64,
+    pub action: String,
+    pub prompt_version: String,
+    pub helpful: bool,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Records a user's thumbs up or down on an AI result, under the prompt
+/// version the result reported.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the prompt version is not one of
+/// the action's, or an error if the database operation fails.
+pub async fn record_feedback(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    action: &ActionType,
+    prompt_version: &str,
+    helpful: bool,
+    comment: Option<&str>,
+) -> AppResult<AiFeedback> {
+    let prompt_version = prompt_version.trim();
+    if !prompt_version.starts_with(&format!("{}/", action.as_str())) {
+        return Err(AppError::ValidationError(format!(
+            "prompt_version must be a version of {}, e.g. {}",
+            action.as_str(),
+            action.prompt_version()
+        )));
+    }
+    let feedback = sqlx::query_as::<_, AiFeedback>(
+        r#"
+        INSERT INTO ai_feedback (user_id, action, prompt_version, helpful, comment)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, action, prompt_version, helpful, comment, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(action.as_str())
+    .bind(prompt_version)
+    .bind(helpful)
+    .bind(comment.map(str::trim).filter(|c| !c.is_empty()))
+    .fetch_one(db_pool)
+    .await?;
+    Ok(feedback)
+}
+
+/// Starts rolling out the action's candidate prompt to `traffic_percent` of
+/// requests. A live rollout of a candidate that has since become the
+/// current version is completed first.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for an invalid share or threshold,
+/// `AppError::BadRequest` if this release ships no candidate for the action
+/// or it already has a live rollout, or an error if the database operation
+/// fails.
+pub async fn start(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    action: &ActionType,
+    traffic_percent: i32,
+    thresholds: RolloutThresholds,
+) -> AppResult<PromptRollout> {
+    let Some(candidate) = action.candidate_prompt_version() else {
+        return Err(AppError::BadRequest(format!(
+            "No new prompt for {} ships in this release",
+            action.as_str()
+        )));
+    };
+    validate(Some(traffic_percent), &thresholds)?;
+
+    let mut tx = db_pool.begin().await?;
+    complete_current(&mut tx, action).await?;
+    let rollout = sqlx::query_as::<_, PromptRollout>(
+        r#"
+        INSERT INTO prompt_rollouts
+            (action, baseline_version, candidate_version, traffic_percent,
+             max_parse_failure_rate, max_thumbs_down_rate, min_samples, started_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(action.as_str())
+    .bind(action.prompt_version())
+    .bind(candidate)
+    .bind(traffic_percent)
+    .bind(thresholds.max_parse_failure_rate.unwrap_or(DEFAULT_MAX_PARSE_FAILURE_RATE))
+    .bind(thresholds.max_thumbs_down_rate.unwrap_or(DEFAULT_MAX_THUMBS_DOWN_RATE))
+    .bind(thresholds.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES))
+    .bind(admin_id)
+    .fetch_one(&mut *tx)
+    .await;
+    let rollout = match rollout {
+        Ok(rollout) => rollout,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(AppError::BadRequest(format!(
+                "{} already has a live prompt rollout",
+                action.as_str()
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    tx.commit().await?;
+    info!(
+        "Admin {} started rolling out {} to {}% of {} requests",
+        admin_id, rollout.candidate_version, rollout.traffic_percent, rollout.action
+    );
+    Ok(rollout)
+}
+
+/// Rollouts with their metrics, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool) -> AppResult<Vec<RolloutReport>> {
+    let rollouts = sqlx::query_as::<_, PromptRollout>(
+        "SELECT * FROM prompt_rollouts ORDER BY created_at DESC LIMIT 100",
+    )
+    .fetch_all(db_pool)
+    .await?;
+    let mut reports = Vec::with_capacity(rollouts.len());
+    for rollout in rollouts {
+        reports.push(report(db_pool, rollout).await?);
+    }
+    Ok(reports)
+}
+
+/// A rollout with its metrics.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if it does not exist, or an error if the
+/// database operation fails.
+pub async fn get(db_pool: &PgPool, rollout_id: i32) -> AppResult<RolloutReport> {
+    let rollout = sqlx::query_as::<_, PromptRollout>("SELECT * FROM prompt_rollouts WHERE id = $1")
+        .bind(rollout_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    report(db_pool, rollout).await
+}
+
+/// Changes an active rollout's share of traffic or thresholds.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for an invalid share or threshold,
+/// `AppError::NotFound` if the rollout does not exist,
+/// `AppError::BadRequest` if it is no longer active, or an error if the
+/// database operation fails.
+pub async fn update(
+    db_pool: &PgPool,
+    rollout_id: i32,
+    traffic_percent: Option<i32>,
+    thresholds: RolloutThresholds,
+) -> AppResult<PromptRollout> {
+    validate(traffic_percent, &thresholds)?;
+    let rollout = sqlx::query_as::<_, PromptRollout>(
+        r#"
+        UPDATE prompt_rollouts
+        SET traffic_percent = COALESCE($2, traffic_percent),
+            max_parse_failure_rate = COALESCE($3, max_parse_failure_rate),
+            max_thumbs_down_rate = COALESCE($4, max_thumbs_down_rate),
+            min_samples = COALESCE($5, min_samples),
+            updated_at = NOW()
+        WHERE id = $1 AND status = 'active'
+        RETURNING *
+        "#,
+    )
+    .bind(rollout_id)
+    .bind(traffic_percent)
+    .bind(thresholds.max_parse_failure_rate)
+    .bind(thresholds.max_thumbs_down_rate)
+    .bind(thresholds.min_samples)
+    .fetch_optional(db_pool)
+    .await?;
+    match rollout {
+        Some(rollout) => {
+            info!("Prompt rollout {} now at {}%", rollout.id, rollout.traffic_percent);
+            Ok(rollout)
+        }
+        None => Err(not_in_status(db_pool, rollout_id, "active").await),
+    }
+}
+
+/// Sends all traffic to an active rollout's candidate. It stays monitored,
+/// and can still be rolled back, until a release makes it the current
+/// version.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the rollout does not exist,
+/// `AppError::BadRequest` if it is not active, or an error if the database
+/// operation fails.
+pub async fn promote(db_pool: &PgPool, admin_id: Uuid, rollout_id: i32) -> AppResult<PromptRollout> {
+    let rollout = sqlx::query_as::<_, PromptRollout>(
+        r#"
+        UPDATE prompt_rollouts
+        SET status = 'promoted', traffic_percent = 100, promoted_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'active'
+        RETURNING *
+        "#,
+    )
+    .bind(rollout_id)
+    .fetch_optional(db_pool)
+    .await?;
+    match rollout {
+        Some(rollout) => {
+            info!("Admin {} promoted {} to all {} requests", admin_id, rollout.candidate_version, rollout.action);
+            Ok(rollout)
+        }
+        None => Err(not_in_status(db_pool, rollout_id, "active").await),
+    }
+}
+
+/// Sends everyone back to the baseline version of a live rollout, by an
+/// admin or, with no `admin_id`, automatically.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the rollout does not exist,
+/// `AppError::BadRequest` if it is not live, or an error if the database
+/// operation fails.
+pub async fn roll_back(
+    db_pool: &PgPool,
+    rollout_id: i32,
+    admin_id: Option<Uuid>,
+    reason: &str,
+) -> AppResult<PromptRollout> {
+    let current = get(db_pool, rollout_id).await?;
+    let metrics = serde_json::json!({
+        "candidate": current.candidate,
+        "baseline": current.baseline,
+    });
+    let rollout = sqlx::query_as::<_, PromptRollout>(
+        r#"
+        UPDATE prompt_rollouts
+        SET status = 'rolled_back', rolled_back_at = NOW(), rolled_back_by = $2,
+            rollback_reason = $3, rollback_metrics = $4, updated_at = NOW()
+        WHERE id = $1 AND status IN ('active', 'promoted')
+        RETURNING *
+        "#,
+    )
+    .bind(rollout_id)
+    .bind(admin_id)
+    .bind(reason.trim())
+    .bind(metrics)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("The prompt rollout is no longer live".to_string()))?;
+    warn!(
+        "Prompt rollout {} rolled back {} to {} ({}): {}",
+        rollout.id,
+        rollout.action,
+        rollout.baseline_version,
+        admin_id.map_or_else(|| "automatically".to_string(), |id| format!("by admin {}", id)),
+        reason
+    );
+    Ok(rollout)
+}
+
+/// Checks every live rollout: completes those whose candidate became the
+/// current version and rolls back those whose candidate exceeds a
+/// threshold. Returns the rollouts rolled back.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn check(db_pool: &PgPool) -> AppResult<Vec<PromptRollout>> {
+    let live = sqlx::query_as::<_, PromptRollout>(
+        "SELECT * FROM prompt_rollouts WHERE status IN ('active', 'promoted') ORDER BY id",
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut rolled_back = Vec::new();
+    for rollout in live {
+        let action = serde_json::from_value::<ActionType>(Value::String(rollout.action.clone())).ok();
+        if let Some(action) = action.filter(|a| a.prompt_version() == rollout.candidate_version) {
+            let mut tx = db_pool.begin().await?;
+            complete_current(&mut tx, &action).await?;
+            tx.commit().await?;
+            continue;
+        }
+
+        let report = report(db_pool, rollout).await?;
+        if let Some(reason) = breach(&report) {
+            rolled_back.push(roll_back(db_pool, report.rollout.id, None, &reason).await?);
+        }
+    }
+    Ok(rolled_back)
+}
+
+/// Why a rollout's candidate should be rolled back, if it should.
+fn breach(report: &RolloutReport) -> Option<String> {
+    let rollout = &report.rollout;
+    let candidate = &report.candidate;
+    let min_samples = i64::from(rollout.min_samples);
+    if candidate.calls >= min_samples
+        && let Some(rate) = candidate.parse_failure_rate
+        && rate > f64::from(rollout.max_parse_failure_rate)
+    {
+        return Some(format!(
+            "Parse failure rate {:.1}% exceeded {:.1}% over {} calls",
+            rate * 100.0,
+            f64::from(rollout.max_parse_failure_rate) * 100.0,
+            candidate.calls
+        ));
+    }
+    if candidate.ratings >= min_samples
+        && let Some(rate) = candidate.thumbs_down_rate
+        && rate > f64::from(rollout.max_thumbs_down_rate)
+    {
+        return Some(format!(
+            "Thumbs-down rate {:.1}% exceeded {:.1}% over {} ratings",
+            rate * 100.0,
+            f64::from(rollout.max_thumbs_down_rate) * 100.0,
+            candidate.ratings
+        ));
+    }
+    None
+}
+
+/// Completes the action's live rollout if its candidate is now the current
+/// version.
+async fn complete_current(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, action: &ActionType) -> AppResult<()> {
+    let completed = sqlx::query_scalar::<_, i32>(
+        r#"
+        UPDATE prompt_rollouts
+        SET status = 'completed', completed_at = NOW(), updated_at = NOW()
+        WHERE action = $1 AND candidate_version = $2 AND status IN ('active', 'promoted')
+        RETURNING id
+        "#,
+    )
+    .bind(action.as_str())
+    .bind(action.prompt_version())
+    .fetch_optional(&mut **tx)
+    .await?;
+    if let Some(id) = completed {
+        info!("Prompt rollout {} completed: {} is now current", id, action.prompt_version());
+    }
+    Ok(())
+}
+
+/// A rollout with its candidate's and baseline's metrics since it started,
+/// up to when it ended.
+async fn report(db_pool: &PgPool, rollout: PromptRollout) -> AppResult<RolloutReport> {
+    let ended_at = rollout.rolled_back_at.or(rollout.completed_at);
+    let mut metrics = sqlx::query_as::<_, VersionMetrics>(
+        r#"
+        SELECT v.version AS prompt_version,
+               COALESCE(u.calls, 0) AS calls, COALESCE(u.parse_failures, 0) AS parse_failures,
+               COALESCE(f.ratings, 0) AS ratings, COALESCE(f.thumbs_down, 0) AS thumbs_down
+        FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS v(version, position)
+        LEFT JOIN (
+            SELECT prompt_version, COUNT(*) AS calls, COUNT(*) FILTER (WHERE parse_failed) AS parse_failures
+            FROM ai_usage_logs
+            WHERE action = $1 AND created_at >= $3 AND created_at <= COALESCE($4, NOW())
+            GROUP BY prompt_version
+        ) u ON u.prompt_version = v.version
+        LEFT JOIN (
+            SELECT prompt_version, COUNT(*) AS ratings, COUNT(*) FILTER (WHERE NOT helpful) AS thumbs_down
+            FROM ai_feedback
+            WHERE action = $1 AND created_at >= $3 AND created_at <= COALESCE($4, NOW())
+            GROUP BY prompt_version
+        ) f ON f.prompt_version = v.version
+        ORDER BY v.position
+        "#,
+    )
+    .bind(&rollout.action)
+    .bind(vec![rollout.candidate_version.clone(), rollout.baseline_version.clone()])
+    .bind(rollout.created_at)
+    .bind(ended_at)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .map(|mut m| {
+        m.parse_failure_rate = rate(m.parse_failures, m.calls);
+        m.thumbs_down_rate = rate(m.thumbs_down, m.ratings);
+        m
+    });
+    let (Some(candidate), Some(baseline)) = (metrics.next(), metrics.next()) else {
+        return Err(AppError::InternalServerError);
+    };
+    Ok(RolloutReport {
+        rollout,
+        candidate,
+        baseline,
+    })
+}
+
+fn rate(count: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
+}
+
+/// Checks a share of traffic and thresholds.
+fn validate(traffic_percent: Option<i32>, thresholds: &RolloutThresholds) -> AppResult<()> {
+    if traffic_percent.is_some_and(|p| !(1..=100).contains(&p)) {
+        return Err(AppError::ValidationError("traffic_percent must be from 1 to 100".to_string()));
+    }
+    let rates = [thresholds.max_parse_failure_rate, thresholds.max_thumbs_down_rate];
+    if rates.into_iter().flatten().any(|r| !(0.0..=1.0).contains(&r)) {
+        return Err(AppError::ValidationError("Rate thresholds must be from 0 to 1".to_string()));
+    }
+    if thresholds.min_samples.is_some_and(|n| n < 1) {
+        return Err(AppError::ValidationError("min_samples must be at least 1".to_string()));
+    }
+    Ok(())
+}
+
+/// The error for a rollout that is missing or not in `status`.
+async fn not_in_status(db_pool: &PgPool, rollout_id: i32, status: &str) -> AppError {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM prompt_rollouts WHERE id = $1)")
+        .bind(rollout_id)
+        .fetch_one(db_pool)
+        .await;
+    match exists {
+        Ok(true) => AppError::BadRequest(format!("The prompt rollout is not {}", status)),
+        Ok(false) => AppError::NotFound,
+        Err(e) => e.into(),
+    }
+}
@@ -0,0 +1,187 @@
+//! Rate limit policy resolution.
+//!
+//! A user's daily AI quota comes from their plan (`plans.ai_daily_quota`),
+//! unless a tenant policy in `rate_limit_policies` overrides it. A policy
+//! belongs to an organization and applies to its staff and cohort students,
+//! for every plan or for one plan; a plan-specific policy wins over the
+//! organization's all-plans one. A user in several organizations gets the
+//! most generous of their policies.
+//!
+//! Policies are held in memory and reloaded from the database once they are
+//! older than `RATE_LIMIT_POLICY_TTL_SECS`, and at once after an admin edits
+//! them on this instance, so changes apply without a restart. The limit in
+//! effect is reported on AI responses in `X-RateLimit-*` headers (see
+//! [`crate::billing::enforce_ai_quota`]).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tokio::sync::RwLock;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::billing;
+use crate::errors::AppResult;
+
+/// Default time before policies are reloaded from the database.
+const DEFAULT_POLICY_TTL_SECS: u64 = 30;
+
+/// A tenant override of the plan quota.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RateLimitPolicy {
+    pub id: i32,
+    pub org_id: i32,
+    /// Plan the policy applies to; `None` for every plan
+    pub plan_id: Option<String>,
+    pub ai_daily_quota: i32,
+    pub note: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The AI quota in effect for a user.
+#[derive(Debug, Clone, Serialize)]
+pub struct AiLimit {
+    /// The user's plan
+    pub plan_id: String,
+    pub plan_name: String,
+    /// AI requests allowed per day
+    pub ai_daily_quota: i32,
+    /// The tenant policy that set the quota, if not the plan
+    pub policy_id: Option<i32>,
+}
+
+/// Loaded policies, by organization.
+#[derive(Debug, Default)]
+struct PolicySet {
+    by_org: HashMap<i32, Vec<RateLimitPolicy>>,
+    loaded_at: Option<Instant>,
+}
+
+/// In-memory rate limit policies, reloaded when stale.
+///
+/// Cloning is cheap; all clones share the same policies.
+#[derive(Debug, Clone)]
+pub struct RateLimitPolicies {
+    policies: Arc<RwLock<Arc<PolicySet>>>,
+    ttl: Duration,
+}
+
+impl RateLimitPolicies {
+    /// Creates an empty policy cache, loaded on first use.
+    ///
+    /// Reads `RATE_LIMIT_POLICY_TTL_SECS` (default: 30).
+    pub fn from_env() -> Self {
+        let ttl_secs = std::env::var("RATE_LIMIT_POLICY_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_POLICY_TTL_SECS);
+
+        Self {
+            policies: Arc::new(RwLock::new(Arc::new(PolicySet::default()))),
+            ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    /// Drops the loaded policies so the next lookup reloads them.
+    pub async fn invalidate(&self) {
+        *self.policies.write().await = Arc::new(PolicySet::default());
+    }
+
+    /// Current policies, reloading them if they are stale.
+    async fn current(&self, db_pool: &PgPool) -> AppResult<Arc<PolicySet>> {
+        {
+            let policies = self.policies.read().await;
+            if policies.loaded_at.is_some_and(|at| at.elapsed() < self.ttl) {
+                return Ok(policies.clone());
+            }
+        }
+
+        let mut by_org: HashMap<i32, Vec<RateLimitPolicy>> = HashMap::new();
+        for policy in list(db_pool).await? {
+            by_org.entry(policy.org_id).or_default().push(policy);
+        }
+        debug!("Loaded rate limit policies for {} organizations", by_org.len());
+        let policies = Arc::new(PolicySet {
+            by_org,
+            loaded_at: Some(Instant::now()),
+        });
+        *self.policies.write().await = policies.clone();
+        Ok(policies)
+    }
+
+    /// Resolves the AI quota in effect for `user_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plan, memberships, or policies cannot be loaded.
+    pub async fn resolve(&self, db_pool: &PgPool, user_id: Uuid) -> AppResult<AiLimit> {
+        let plan = billing::current_plan(db_pool, user_id).await?;
+        let org_ids = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT org_id FROM organization_members WHERE user_id = $1
+            UNION
+            SELECT c.org_id FROM cohort_members cm JOIN cohorts c ON c.id = cm.cohort_id WHERE cm.user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        let policies = self.current(db_pool).await?;
+        let policy = org_ids
+            .iter()
+            .filter_map(|org_id| {
+                let org_policies = policies.by_org.get(org_id)?;
+                org_policies
+                    .iter()
+                    .find(|p| p.plan_id.as_deref() == Some(plan.id.as_str()))
+                    .or_else(|| org_policies.iter().find(|p| p.plan_id.is_none()))
+            })
+            .max_by_key(|p| p.ai_daily_quota);
+
+        Ok(AiLimit {
+            ai_daily_quota: policy.map_or(plan.ai_daily_quota, |p| p.ai_daily_quota),
+            policy_id: policy.map(|p| p.id),
+            plan_id: plan.id,
+            plan_name: plan.name,
+        })
+    }
+}
+
+/// Lists every policy, by organization and then plan.
+pub async fn list(db_pool: &PgPool) -> AppResult<Vec<RateLimitPolicy>> {
+    let policies = sqlx::query_as::<_, RateLimitPolicy>(
+        r#"
+        SELECT id, org_id, plan_id, ai_daily_quota, note, updated_at
+        FROM rate_limit_policies
+        ORDER BY org_id, plan_id NULLS FIRST
+        "#,
+    )
+    .fetch_all(db_pool)
+    .await?;
+    Ok(policies)
+}
+
+/// Seconds until the daily quota resets at midnight UTC.
+pub fn seconds_until_reset(now: DateTime<Utc>) -> i64 {
+    let tomorrow = now.date_naive().succ_opt().unwrap_or(now.date_naive());
+    (tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc() - now)
+        .num_seconds()
+        .max(0)
+}
+
+/// Sets `X-RateLimit-Limit`, `X-RateLimit-Remaining`, and
+/// `X-RateLimit-Reset` (seconds until the quota resets).
+pub fn set_headers(headers: &mut HeaderMap, limit: &AiLimit, used: i32) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(limit.ai_daily_quota));
+    headers.insert(
+        "x-ratelimit-remaining",
+        HeaderValue::from((limit.ai_daily_quota - used).max(0)),
+    );
+    headers.insert("x-ratelimit-reset", HeaderValue::from(seconds_until_reset(Utc::now())));
+}
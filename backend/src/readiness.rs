@@ -0,0 +1,322 @@
+//! Role readiness.
+//!
+//! One explainable 0-100 score of how ready a user is to apply for one of
+//! their target roles, from up to four components, each reported with what
+//! it was computed from:
+//!
+//! | Component | Weight | From |
+//! |-----------|--------|------|
+//! | `roadmap` | 50 | The best roadmap to the role: progress toward its job application timing |
+//! | `credentials` | 15 | Certifications verified with their issuer; 3 count fully |
+//! | `portfolio` | 20 | Projects on the profile; 3 count fully |
+//! | `practice` | 15 | Coding practice volume and topic breadth, for engineering roles only |
+//!
+//! For other roles `practice` is left out and the others reweighted. Scores
+//! are stored in `role_readiness`, recalculated on every request and by
+//! [`recalculate_due`] on the scheduler; application timing nudges are sent
+//! once a roadmap's role reaches [`nudge_score`].
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::applicant_ranking::ScoreComponent;
+use crate::application_timing;
+use crate::benchmarking;
+use crate::code_practice;
+use crate::errors::{AppError, AppResult};
+
+/// Component weights, out of 100.
+const ROADMAP_WEIGHT: f64 = 50.0;
+const CREDENTIALS_WEIGHT: f64 = 15.0;
+const PORTFOLIO_WEIGHT: f64 = 20.0;
+const PRACTICE_WEIGHT: f64 = 15.0;
+
+/// Verified certifications that count as full credentials.
+const FULL_CREDENTIALS: usize = 3;
+
+/// Profile projects that count as a full portfolio.
+const FULL_PROJECTS: usize = 3;
+
+/// Default readiness score at which application timing nudges are sent.
+const DEFAULT_NUDGE_SCORE: f64 = 65.0;
+
+/// Hours before a stored score is recalculated in the background.
+const RECALCULATE_AFTER_HOURS: i32 = 6;
+
+/// Scores recalculated per run.
+const BATCH_SIZE: i64 = 500;
+
+/// A user's readiness for a role.
+#[derive(Debug, Serialize)]
+pub struct RoleReadiness {
+    pub role: String,
+    /// 0-100
+    pub score: f64,
+    /// Whether the score has reached [`nudge_score`]
+    pub ready: bool,
+    pub nudge_score: f64,
+    pub components: Vec<ScoreComponent>,
+    pub calculated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, FromRow)]
+struct RoleRoadmap {
+    title: String,
+    roadmap_data: Value,
+    job_application_timing: Option<String>,
+    progress_percentage: Option<i32>,
+    completed_phases: Option<Vec<i32>>,
+}
+
+#[derive(Debug, FromRow)]
+struct DueRole {
+    user_id: Uuid,
+    role: String,
+}
+
+/// Returns the score application timing nudges are sent at, from
+/// `READINESS_NUDGE_SCORE` (default: 65).
+pub fn nudge_score() -> f64 {
+    std::env::var("READINESS_NUDGE_SCORE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|s| (1.0..=100.0).contains(s))
+        .unwrap_or(DEFAULT_NUDGE_SCORE)
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Share of `count` out of `full`, in percent.
+fn capped_share(count: usize, full: usize) -> f64 {
+    count.min(full) as f64 / full as f64 * 100.0
+}
+
+/// Progress of the user's best roadmap to `role` toward its application
+/// timing.
+async fn roadmap_component(db_pool: &PgPool, user_id: Uuid, role: &str) -> AppResult<(f64, String)> {
+    let roadmaps = sqlx::query_as::<_, RoleRoadmap>(
+        r#"
+        SELECT title, roadmap_data, job_application_timing, progress_percentage, completed_phases
+        FROM career_roadmaps
+        WHERE user_id = $1 AND LOWER(target_role) = LOWER($2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(role)
+    .fetch_all(db_pool)
+    .await?;
+
+    let best = roadmaps
+        .iter()
+        .map(|roadmap| {
+            let (progress, threshold) = application_timing::progress_and_threshold(
+                &roadmap.roadmap_data,
+                roadmap.job_application_timing.as_deref(),
+                roadmap.progress_percentage,
+                roadmap.completed_phases.as_deref().unwrap_or_default(),
+            );
+            let score = (f64::from(progress) / f64::from(threshold.max(1)) * 100.0).min(100.0);
+            (score, roadmap, progress, threshold)
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    Ok(match best {
+        Some((score, roadmap, progress, threshold)) => (
+            score,
+            format!(
+                "\"{}\" is {}% complete; its timing says to start applying at {}%",
+                roadmap.title, progress, threshold
+            ),
+        ),
+        None => (0.0, "No roadmap to this role yet".to_string()),
+    })
+}
+
+/// Calculates and stores the user's readiness for `role`.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails.
+pub async fn calculate(db_pool: &PgPool, user_id: Uuid, role: &str) -> AppResult<RoleReadiness> {
+    let mut components: Vec<(&'static str, f64, f64, String)> = Vec::new();
+
+    let (score, detail) = roadmap_component(db_pool, user_id, role).await?;
+    components.push(("roadmap", score, ROADMAP_WEIGHT, detail));
+
+    let verified = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT name FROM user_certifications
+        WHERE user_id = $1 AND verification_status = 'verified'
+        ORDER BY verified_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    let detail = match verified.len() {
+        0 => "No certifications verified with their issuer yet".to_string(),
+        n => format!(
+            "{} verified certification{}: {}; {} count fully",
+            n,
+            if n == 1 { "" } else { "s" },
+            verified.join(", "),
+            FULL_CREDENTIALS
+        ),
+    };
+    components.push((
+        "credentials",
+        capped_share(verified.len(), FULL_CREDENTIALS),
+        CREDENTIALS_WEIGHT,
+        detail,
+    ));
+
+    let projects = sqlx::query_scalar::<_, Vec<String>>("SELECT projects FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .count();
+    components.push((
+        "portfolio",
+        capped_share(projects, FULL_PROJECTS),
+        PORTFOLIO_WEIGHT,
+        format!("{} projects on your profile; {} count fully", projects, FULL_PROJECTS),
+    ));
+
+    if let Some(practice) = code_practice::practice_readiness(db_pool, user_id, role).await? {
+        let detail = if practice.core_topics_missing.is_empty() {
+            format!("{} coding problems solved, across every core topic", practice.total_solved)
+        } else {
+            format!(
+                "{} coding problems solved; core topics to practise: {}",
+                practice.total_solved,
+                practice.core_topics_missing.join(", ")
+            )
+        };
+        components.push(("practice", f64::from(practice.score), PRACTICE_WEIGHT, detail));
+    }
+
+    let total_weight: f64 = components.iter().map(|(_, _, w, _)| w).sum();
+    let score = round1(components.iter().map(|(_, score, w, _)| score * w / total_weight).sum());
+    let components: Vec<ScoreComponent> = components
+        .into_iter()
+        .map(|(name, score, weight, detail)| {
+            let weight = weight / total_weight;
+            ScoreComponent {
+                name,
+                score: round1(score),
+                weight: (weight * 1000.0).round() / 1000.0,
+                points: round1(score * weight),
+                detail,
+            }
+        })
+        .collect();
+
+    let calculated_at = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
+        r#"
+        INSERT INTO role_readiness (user_id, role, score, components)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, LOWER(role)) DO UPDATE
+        SET role = EXCLUDED.role, score = EXCLUDED.score, components = EXCLUDED.components,
+            calculated_at = NOW()
+        RETURNING calculated_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(role)
+    .bind(score)
+    .bind(Json(&components))
+    .fetch_one(db_pool)
+    .await?;
+
+    let nudge_score = nudge_score();
+    Ok(RoleReadiness {
+        role: role.to_string(),
+        score,
+        ready: score >= nudge_score,
+        nudge_score,
+        components,
+        calculated_at,
+    })
+}
+
+/// The user's readiness for `role`, by default their first target role.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the user has no target role or
+/// roadmap, or `role` is not one of theirs, or an error if a database
+/// operation fails.
+pub async fn for_user(db_pool: &PgPool, user_id: Uuid, role: Option<&str>) -> AppResult<RoleReadiness> {
+    let roles = benchmarking::user_roles(db_pool, user_id).await?;
+    let role = match role.map(str::trim) {
+        Some(role) => roles
+            .into_iter()
+            .find(|r| r.eq_ignore_ascii_case(role))
+            .ok_or_else(|| {
+                AppError::ValidationError("role must be one of your target roles or roadmap roles".to_string())
+            })?,
+        None => roles.into_iter().next().ok_or_else(|| {
+            AppError::ValidationError("Add a target role or generate a roadmap to see your readiness".to_string())
+        })?,
+    };
+    calculate(db_pool, user_id, &role).await
+}
+
+/// Recalculates a batch of job seekers' readiness for their target roles and
+/// roadmap roles, those never calculated first. Returns how many were
+/// recalculated.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be loaded; failures for single
+/// roles are logged and retried on the next run.
+pub async fn recalculate_due(db_pool: &PgPool) -> AppResult<usize> {
+    let due = sqlx::query_as::<_, DueRole>(
+        r#"
+        SELECT roles.user_id, roles.role
+        FROM (
+            SELECT DISTINCT ON (user_id, LOWER(role)) user_id, role
+            FROM (
+                SELECT u.id AS user_id, t.role
+                FROM users u, unnest(u.target_roles) AS t(role)
+                WHERE u.role = 'job_seeker'
+                UNION ALL
+                SELECT r.user_id, r.target_role
+                FROM career_roadmaps r JOIN users u ON u.id = r.user_id
+                WHERE u.role = 'job_seeker'
+            ) all_roles
+            WHERE btrim(role) <> ''
+            ORDER BY user_id, LOWER(role)
+        ) roles
+        LEFT JOIN role_readiness rr ON rr.user_id = roles.user_id AND LOWER(rr.role) = LOWER(roles.role)
+        WHERE rr.calculated_at IS NULL OR rr.calculated_at < NOW() - make_interval(hours => $1)
+        ORDER BY rr.calculated_at NULLS FIRST
+        LIMIT $2
+        "#,
+    )
+    .bind(RECALCULATE_AFTER_HOURS)
+    .bind(BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut recalculated = 0;
+    for role in &due {
+        match calculate(db_pool, role.user_id, &role.role).await {
+            Ok(_) => recalculated += 1,
+            Err(e) => warn!("Failed to recalculate readiness of user {} for {}: {}", role.user_id, role.role, e),
+        }
+    }
+    if recalculated > 0 {
+        info!("Recalculated {} role readiness scores", recalculated);
+    }
+    Ok(recalculated)
+}
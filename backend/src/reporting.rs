@@ -0,0 +1,345 @@
+//! Placement outcome reporting for institutions.
+//!
+//! Aggregates the placements of an organization's cohort members: placement
+//! rate, median time from application to offer, top hiring companies, and
+//! salary bands. Reports are anonymized: any group smaller than the minimum
+//! group size (`PLACEMENT_MIN_GROUP_SIZE`, default 5) is suppressed, so no
+//! figure can be traced back to an individual student. Reports can be
+//! exported as CSV for accreditation reporting.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+
+use crate::errors::AppResult;
+
+/// Default minimum number of students behind any reported figure.
+const DEFAULT_MIN_GROUP_SIZE: i64 = 5;
+
+/// Number of hiring companies listed.
+const TOP_COMPANIES: i64 = 10;
+
+/// Placements of the organization's cohort members, one row per cohort a
+/// placed student belongs to.
+///
+/// Binds: `$1` org ID, `$2` optional cohort ID, `$3`/`$4` optional
+/// accepted-on date range (inclusive).
+const SCOPED_PLACEMENTS_CTE: &str = r#"
+    WITH scoped AS (
+        SELECT
+            m.cohort_id, p.id, p.user_id, p.company, p.base_salary, p.currency,
+            EXTRACT(EPOCH FROM (p.offered_at - p.applied_at))::FLOAT8 / 86400 AS days_to_offer
+        FROM placements p
+        JOIN cohort_members m ON m.user_id = p.user_id
+        JOIN cohorts c ON c.id = m.cohort_id
+        JOIN users u ON u.id = p.user_id
+        WHERE c.org_id = $1
+          AND u.sandbox_key_id IS NULL
+          AND ($2::INTEGER IS NULL OR c.id = $2)
+          AND ($3::DATE IS NULL OR p.accepted_at >= $3::DATE)
+          AND ($4::DATE IS NULL OR p.accepted_at < $4::DATE + 1)
+    )
+"#;
+
+/// Returns the minimum group size from `PLACEMENT_MIN_GROUP_SIZE` (default: 5).
+pub fn min_group_size() -> i64 {
+    std::env::var("PLACEMENT_MIN_GROUP_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|n| *n >= 1)
+        .unwrap_or(DEFAULT_MIN_GROUP_SIZE)
+}
+
+/// Which placements a report covers.
+#[derive(Debug, Clone, Copy)]
+pub struct ReportScope {
+    pub org_id: i32,
+    pub cohort_id: Option<i32>,
+    /// First acceptance date included
+    pub from: Option<NaiveDate>,
+    /// Last acceptance date included
+    pub to: Option<NaiveDate>,
+}
+
+/// Placement outcomes for a group of students.
+///
+/// Figures are `None` when the group is below the minimum group size.
+#[derive(Debug, Serialize)]
+pub struct PlacementOutcome {
+    /// Number of students
+    pub students: i64,
+    /// Number of students who accepted an offer
+    pub placed: Option<i64>,
+    /// Share of students placed, in percent
+    pub placement_rate: Option<f64>,
+    /// Median days from application to offer among placed students
+    pub median_days_to_offer: Option<f64>,
+    /// Whether figures were withheld because the group is too small
+    pub suppressed: bool,
+}
+
+impl PlacementOutcome {
+    fn new(students: i64, placed: i64, median_days_to_offer: Option<f64>, min_group_size: i64) -> Self {
+        if students < min_group_size {
+            return Self {
+                students,
+                placed: None,
+                placement_rate: None,
+                median_days_to_offer: None,
+                suppressed: true,
+            };
+        }
+        Self {
+            students,
+            placed: Some(placed),
+            placement_rate: Some(round1(placed as f64 * 100.0 / students as f64)),
+            // A median over fewer placements than the group size could reveal one student's timeline
+            median_days_to_offer: median_days_to_offer
+                .filter(|_| placed >= min_group_size)
+                .map(round1),
+            suppressed: false,
+        }
+    }
+}
+
+/// Outcomes for one cohort.
+#[derive(Debug, Serialize)]
+pub struct CohortOutcome {
+    pub cohort_id: i32,
+    pub name: String,
+    #[serde(flatten)]
+    pub outcome: PlacementOutcome,
+}
+
+/// A company that hired at least the minimum group size of students.
+#[derive(Debug, Serialize, FromRow)]
+pub struct HiringCompany {
+    pub company: String,
+    pub placements: i64,
+}
+
+/// Salary quartiles for placements paid in one currency.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SalaryBand {
+    pub currency: String,
+    pub placements: i64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+}
+
+/// An organization's anonymized placement report.
+#[derive(Debug, Serialize)]
+pub struct PlacementReport {
+    pub org_id: i32,
+    pub org_name: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Groups smaller than this are suppressed
+    pub min_group_size: i64,
+    /// All students across the reported cohorts, counted once each
+    pub overall: PlacementOutcome,
+    pub cohorts: Vec<CohortOutcome>,
+    pub top_companies: Vec<HiringCompany>,
+    /// Placements at companies too small to list
+    pub other_company_placements: i64,
+    pub salary_bands: Vec<SalaryBand>,
+}
+
+#[derive(FromRow)]
+struct OutcomeRow {
+    cohort_id: Option<i32>,
+    name: Option<String>,
+    students: i64,
+    placed: i64,
+    median_days_to_offer: Option<f64>,
+}
+
+/// Builds the placement report for an organization.
+///
+/// # Errors
+///
+/// Returns an error if a query fails.
+pub async fn placement_report(
+    db_pool: &PgPool,
+    org_name: String,
+    scope: ReportScope,
+    min_group_size: i64,
+) -> AppResult<PlacementReport> {
+    // Cohort rows plus an overall row (cohort_id NULL) that counts students
+    // in several cohorts once
+    let rows = sqlx::query_as::<_, OutcomeRow>(&format!(
+        r#"
+        {}
+        , students AS (
+            SELECT m.cohort_id, m.user_id
+            FROM cohort_members m
+            JOIN cohorts c ON c.id = m.cohort_id
+            JOIN users u ON u.id = m.user_id
+            WHERE c.org_id = $1 AND ($2::INTEGER IS NULL OR c.id = $2) AND u.sandbox_key_id IS NULL
+        )
+        SELECT
+            c.id AS cohort_id, c.name,
+            (SELECT COUNT(*) FROM students s WHERE s.cohort_id = c.id) AS students,
+            (SELECT COUNT(DISTINCT user_id) FROM scoped p WHERE p.cohort_id = c.id) AS placed,
+            (
+                SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY days_to_offer)
+                FROM scoped p WHERE p.cohort_id = c.id
+            ) AS median_days_to_offer
+        FROM cohorts c
+        WHERE c.org_id = $1 AND ($2::INTEGER IS NULL OR c.id = $2)
+        UNION ALL
+        SELECT
+            NULL, NULL,
+            (SELECT COUNT(DISTINCT user_id) FROM students),
+            (SELECT COUNT(DISTINCT user_id) FROM scoped),
+            (
+                SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY days_to_offer)
+                FROM (SELECT DISTINCT id, days_to_offer FROM scoped) p
+            )
+        ORDER BY 2 NULLS FIRST
+        "#,
+        SCOPED_PLACEMENTS_CTE
+    ))
+    .bind(scope.org_id)
+    .bind(scope.cohort_id)
+    .bind(scope.from)
+    .bind(scope.to)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut overall = PlacementOutcome::new(0, 0, None, min_group_size);
+    let mut cohorts = Vec::new();
+    for row in rows {
+        let outcome = PlacementOutcome::new(row.students, row.placed, row.median_days_to_offer, min_group_size);
+        match (row.cohort_id, row.name) {
+            (Some(cohort_id), Some(name)) => cohorts.push(CohortOutcome { cohort_id, name, outcome }),
+            _ => overall = outcome,
+        }
+    }
+
+    let companies = sqlx::query_as::<_, HiringCompany>(&format!(
+        r#"
+        {}
+        SELECT company, COUNT(DISTINCT id) AS placements
+        FROM scoped
+        GROUP BY company
+        ORDER BY placements DESC, company
+        "#,
+        SCOPED_PLACEMENTS_CTE
+    ))
+    .bind(scope.org_id)
+    .bind(scope.cohort_id)
+    .bind(scope.from)
+    .bind(scope.to)
+    .fetch_all(db_pool)
+    .await?;
+
+    let (listed, other): (Vec<_>, Vec<_>) = companies
+        .into_iter()
+        .partition(|c| c.placements >= min_group_size);
+    let top_companies = listed.into_iter().take(TOP_COMPANIES as usize).collect();
+    let other_company_placements = other.iter().map(|c| c.placements).sum();
+
+    let salary_bands = sqlx::query_as::<_, SalaryBand>(&format!(
+        r#"
+        {}
+        SELECT
+            currency::TEXT AS currency,
+            COUNT(*) AS placements,
+            percentile_cont(0.25) WITHIN GROUP (ORDER BY base_salary) AS p25,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY base_salary) AS median,
+            percentile_cont(0.75) WITHIN GROUP (ORDER BY base_salary) AS p75
+        FROM (SELECT DISTINCT id, currency, base_salary FROM scoped) p
+        GROUP BY currency
+        HAVING COUNT(*) >= $5
+        ORDER BY placements DESC
+        "#,
+        SCOPED_PLACEMENTS_CTE
+    ))
+    .bind(scope.org_id)
+    .bind(scope.cohort_id)
+    .bind(scope.from)
+    .bind(scope.to)
+    .bind(min_group_size)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(PlacementReport {
+        org_id: scope.org_id,
+        org_name,
+        from: scope.from,
+        to: scope.to,
+        min_group_size,
+        overall,
+        cohorts,
+        top_companies,
+        other_company_placements,
+        salary_bands,
+    })
+}
+
+/// Renders a report as CSV with `section,group,metric,value` rows.
+///
+/// Suppressed figures are written as `suppressed`.
+pub fn to_csv(report: &PlacementReport) -> String {
+    let mut rows: Vec<[String; 4]> = vec![["section".into(), "group".into(), "metric".into(), "value".into()]];
+    let mut push = |section: &str, group: &str, metric: &str, value: String| {
+        rows.push([section.to_string(), group.to_string(), metric.to_string(), value]);
+    };
+
+    push("report", &report.org_name, "from", report.from.map(|d| d.to_string()).unwrap_or_default());
+    push("report", &report.org_name, "to", report.to.map(|d| d.to_string()).unwrap_or_default());
+    push("report", &report.org_name, "min_group_size", report.min_group_size.to_string());
+
+    let outcomes = std::iter::once(("overall", "All cohorts", &report.overall))
+        .chain(report.cohorts.iter().map(|c| ("cohort", c.name.as_str(), &c.outcome)));
+    for (section, group, outcome) in outcomes {
+        push(section, group, "students", outcome.students.to_string());
+        push(section, group, "placed", suppressible(outcome.placed));
+        push(section, group, "placement_rate", suppressible(outcome.placement_rate));
+        push(section, group, "median_days_to_offer", suppressible(outcome.median_days_to_offer));
+    }
+
+    for company in &report.top_companies {
+        push("company", &company.company, "placements", company.placements.to_string());
+    }
+    push("company", "Other companies", "placements", report.other_company_placements.to_string());
+
+    for band in &report.salary_bands {
+        push("salary_band", &band.currency, "placements", band.placements.to_string());
+        push("salary_band", &band.currency, "p25", band.p25.round().to_string());
+        push("salary_band", &band.currency, "median", band.median.round().to_string());
+        push("salary_band", &band.currency, "p75", band.p75.round().to_string());
+    }
+
+    let mut csv = String::new();
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn suppressible<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "suppressed".to_string())
+}
+
+/// Quotes a field when needed (RFC 4180) and neutralizes spreadsheet formulas.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
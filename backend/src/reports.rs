@@ -0,0 +1,570 @@
+//! Reports of abusive jobs, accounts, companies, messages, and company
+//! reviews.
+//!
+//! Candidates and employers report spam postings, scams, inappropriate
+//! messages they received, or abusive company reviews. A report records who
+//! is answerable for the target (the job's poster, the message's sender, the
+//! review's author) and keeps a copy of a reported message or review. Open
+//! reports wait in the admin moderation queue; a live job or published
+//! review with [`REVIEW_THRESHOLD`] open reports is hidden until a moderator
+//! looks at it.
+//!
+//! Admins resolve a report with a [`ReportAction`]. The action closes every
+//! open report on the same target, and is written to `audit_logs`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{ModerationStatus, ReportCategory, ReportStatus, ReportTargetType};
+use crate::moderation;
+
+/// Open reports that send a live job back to moderation or hide a review.
+pub const REVIEW_THRESHOLD: i64 = 3;
+
+/// A report as its reporter sees it.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Report {
+    pub id: i32,
+    pub target_type: ReportTargetType,
+    pub target_id: String,
+    pub category: ReportCategory,
+    pub details: Option<String>,
+    pub status: ReportStatus,
+    pub resolution: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A report in the admin moderation queue.
+#[derive(Debug, Serialize, FromRow)]
+pub struct QueuedReport {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub report: Report,
+    pub reporter_id: Uuid,
+    pub reporter_email: String,
+    pub reported_user_id: Option<Uuid>,
+    pub reported_user_email: Option<String>,
+    pub reported_company_id: Option<i32>,
+    pub reported_company_name: Option<String>,
+    /// Copy of a reported message or review
+    pub excerpt: Option<String>,
+    /// Open reports on the same target, this one included
+    pub open_reports: i64,
+    pub resolution_note: Option<String>,
+    pub resolved_by: Option<Uuid>,
+}
+
+/// How an admin resolves a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportAction {
+    /// Close without action
+    Dismiss,
+    /// Notify the reported party with the admin's note
+    Warn,
+    /// Reject a reported job, delete a reported message, or take down a
+    /// reported review
+    Remove,
+}
+
+impl ReportAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReportAction::Dismiss => "dismiss",
+            ReportAction::Warn => "warn",
+            ReportAction::Remove => "remove",
+        }
+    }
+}
+
+const REPORT_COLUMNS: &str = r#"
+    r.id, r.target_type, r.target_id, r.category, r.details, r.status,
+    r.resolution, r.resolved_at, r.created_at
+"#;
+
+const QUEUE_COLUMNS: &str = r#"
+    r.reporter_id, reporter.email AS reporter_email,
+    r.reported_user_id, reported.email AS reported_user_email,
+    r.reported_company_id, c.name AS reported_company_name,
+    r.excerpt,
+    (SELECT COUNT(*) FROM reports o
+     WHERE o.target_type = r.target_type AND o.target_id = r.target_id AND o.status = 'open') AS open_reports,
+    r.resolution_note, r.resolved_by
+"#;
+
+const QUEUE_JOINS: &str = r#"
+    FROM reports r
+    JOIN users reporter ON reporter.id = r.reporter_id
+    LEFT JOIN users reported ON reported.id = r.reported_user_id
+    LEFT JOIN companies c ON c.id = r.reported_company_id
+"#;
+
+/// Who is answerable for a report's target.
+#[derive(Debug, Default)]
+struct ReportedParty {
+    user_id: Option<Uuid>,
+    company_id: Option<i32>,
+    excerpt: Option<String>,
+}
+
+fn bad_target() -> AppError {
+    AppError::ValidationError("target_id does not identify a target of that type".to_string())
+}
+
+async fn reported_party(
+    db_pool: &PgPool,
+    reporter_id: Uuid,
+    target_type: ReportTargetType,
+    target_id: &str,
+) -> AppResult<ReportedParty> {
+    let party = match target_type {
+        ReportTargetType::Job => {
+            let job_id: i32 = target_id.parse().map_err(|_| bad_target())?;
+            let (user_id, company_id) = sqlx::query_as::<_, (Option<Uuid>, Option<i32>)>(
+                "SELECT posted_by, company_id FROM jobs WHERE id = $1",
+            )
+            .bind(job_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+            ReportedParty {
+                user_id,
+                company_id,
+                excerpt: None,
+            }
+        }
+        ReportTargetType::User => {
+            let user_id: Uuid = target_id.parse().map_err(|_| bad_target())?;
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(db_pool)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            ReportedParty {
+                user_id: Some(user_id),
+                ..Default::default()
+            }
+        }
+        ReportTargetType::Company => {
+            let company_id: i32 = target_id.parse().map_err(|_| bad_target())?;
+            sqlx::query_scalar::<_, i32>("SELECT id FROM companies WHERE id = $1")
+                .bind(company_id)
+                .fetch_optional(db_pool)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            ReportedParty {
+                company_id: Some(company_id),
+                ..Default::default()
+            }
+        }
+        ReportTargetType::Message => {
+            // Only messages the reporter received
+            let notification_id: i32 = target_id.parse().map_err(|_| bad_target())?;
+            let (user_id, company_id, message) = sqlx::query_as::<_, (Option<Uuid>, Option<i32>, String)>(
+                "SELECT sender_id, sender_company_id, message FROM notifications WHERE id = $1 AND user_id = $2",
+            )
+            .bind(notification_id)
+            .bind(reporter_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+            if user_id.is_none() && company_id.is_none() {
+                return Err(AppError::ValidationError(
+                    "Only messages sent by other users can be reported".to_string(),
+                ));
+            }
+            ReportedParty {
+                user_id,
+                company_id,
+                excerpt: Some(message),
+            }
+        }
+        ReportTargetType::Review => {
+            let review_id: i32 = target_id.parse().map_err(|_| bad_target())?;
+            let (user_id, headline, body) = sqlx::query_as::<_, (Uuid, String, String)>(
+                "SELECT user_id, headline, body FROM company_reviews WHERE id = $1 AND status <> 'removed'",
+            )
+            .bind(review_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or(AppError::NotFound)?;
+            ReportedParty {
+                user_id: Some(user_id),
+                company_id: None,
+                excerpt: Some(format!("{}\n\n{}", headline, body)),
+            }
+        }
+    };
+
+    if party.user_id == Some(reporter_id) {
+        return Err(AppError::ValidationError("You cannot report yourself".to_string()));
+    }
+    Ok(party)
+}
+
+/// Files a report. A job reaching [`REVIEW_THRESHOLD`] open reports is
+/// sent back to moderation, and a review reaching it is hidden.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the target does not exist (or, for a
+/// message, was not sent to the reporter), `AppError::ValidationError` if
+/// the target cannot be reported or the reporter already has an open
+/// report on it, or an error if the database operation fails.
+pub async fn file(
+    db_pool: &PgPool,
+    reporter_id: Uuid,
+    target_type: ReportTargetType,
+    target_id: &str,
+    category: ReportCategory,
+    details: Option<&str>,
+) -> AppResult<Report> {
+    let target_id = target_id.trim();
+    let party = reported_party(db_pool, reporter_id, target_type, target_id).await?;
+
+    let report_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO reports
+            (reporter_id, target_type, target_id, reported_user_id, reported_company_id, excerpt, category, details)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(target_type)
+    .bind(target_id)
+    .bind(party.user_id)
+    .bind(party.company_id)
+    .bind(&party.excerpt)
+    .bind(category)
+    .bind(details.map(str::trim).filter(|d| !d.is_empty()))
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or_else(|| AppError::ValidationError("You have already reported this".to_string()))?;
+    info!("Report {} filed on {:?} {} by {}", report_id, target_type, target_id, reporter_id);
+
+    match target_type {
+        ReportTargetType::Job => requeue_job(db_pool, target_id).await?,
+        ReportTargetType::Review => hide_review(db_pool, target_id).await?,
+        _ => {}
+    }
+
+    sqlx::query_as::<_, Report>(&format!("SELECT {} FROM reports r WHERE r.id = $1", REPORT_COLUMNS))
+        .bind(report_id)
+        .fetch_one(db_pool)
+        .await
+        .map_err(Into::into)
+}
+
+/// Sends a live job back to moderation once it has enough open reports.
+async fn requeue_job(db_pool: &PgPool, target_id: &str) -> AppResult<()> {
+    let requeued = sqlx::query(
+        r#"
+        UPDATE jobs SET moderation_status = 'pending'
+        WHERE id::text = $1 AND moderation_status = 'approved'
+          AND (SELECT COUNT(*) FROM reports
+               WHERE target_type = 'job' AND target_id = $1 AND status = 'open') >= $2
+        "#,
+    )
+    .bind(target_id)
+    .bind(REVIEW_THRESHOLD)
+    .execute(db_pool)
+    .await?;
+    if requeued.rows_affected() > 0 {
+        info!("Job {} returned to moderation after {} reports", target_id, REVIEW_THRESHOLD);
+    }
+    Ok(())
+}
+
+/// Hides a published review once it has enough open reports.
+async fn hide_review(db_pool: &PgPool, target_id: &str) -> AppResult<()> {
+    let hidden = sqlx::query(
+        r#"
+        UPDATE company_reviews SET status = 'hidden', updated_at = NOW()
+        WHERE id::text = $1 AND status = 'published'
+          AND (SELECT COUNT(*) FROM reports
+               WHERE target_type = 'review' AND target_id = $1 AND status = 'open') >= $2
+        "#,
+    )
+    .bind(target_id)
+    .bind(REVIEW_THRESHOLD)
+    .execute(db_pool)
+    .await?;
+    if hidden.rows_affected() > 0 {
+        info!("Review {} hidden after {} reports", target_id, REVIEW_THRESHOLD);
+    }
+    Ok(())
+}
+
+/// The reporter's reports, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list_for_reporter(db_pool: &PgPool, reporter_id: Uuid) -> AppResult<Vec<Report>> {
+    let reports = sqlx::query_as::<_, Report>(&format!(
+        "SELECT {} FROM reports r WHERE r.reporter_id = $1 ORDER BY r.created_at DESC",
+        REPORT_COLUMNS
+    ))
+    .bind(reporter_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(reports)
+}
+
+/// Reports in the moderation queue. Open reports come first, most
+/// reported targets first, then oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn queue(db_pool: &PgPool, status: Option<ReportStatus>, limit: i64) -> AppResult<Vec<QueuedReport>> {
+    let reports = sqlx::query_as::<_, QueuedReport>(&format!(
+        r#"
+        SELECT {}, {} {}
+        WHERE $1::report_status IS NULL OR r.status = $1
+        ORDER BY r.status = 'open' DESC, open_reports DESC, r.created_at
+        LIMIT $2
+        "#,
+        REPORT_COLUMNS, QUEUE_COLUMNS, QUEUE_JOINS
+    ))
+    .bind(status)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(reports)
+}
+
+async fn fetch_queued(db_pool: &PgPool, report_id: i32) -> AppResult<QueuedReport> {
+    sqlx::query_as::<_, QueuedReport>(&format!(
+        "SELECT {}, {} {} WHERE r.id = $1",
+        REPORT_COLUMNS, QUEUE_COLUMNS, QUEUE_JOINS
+    ))
+    .bind(report_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Resolves an open report, closing every open report on the same target.
+/// Dismissing or warning on a review hidden by reports publishes it again.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the report does not exist,
+/// `AppError::ValidationError` if it is already closed, the action needs a
+/// note, or the target cannot be removed or warned, or an error if the
+/// database operation fails.
+pub async fn resolve(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    report_id: i32,
+    action: ReportAction,
+    note: Option<&str>,
+) -> AppResult<QueuedReport> {
+    let report = fetch_queued(db_pool, report_id).await?;
+    if report.report.status != ReportStatus::Open {
+        return Err(AppError::ValidationError("This report is already closed".to_string()));
+    }
+    let note = note.map(str::trim).filter(|n| !n.is_empty());
+    if action != ReportAction::Dismiss && note.is_none() {
+        return Err(AppError::ValidationError(
+            "A note is required when acting on a report".to_string(),
+        ));
+    }
+    let target_type = report.report.target_type;
+    let target_id = report.report.target_id.as_str();
+
+    match action {
+        ReportAction::Dismiss => {}
+        ReportAction::Warn => warn_party(db_pool, &report, note.unwrap_or_default()).await?,
+
+        ReportAction::Remove => match target_type {
+            ReportTargetType::Job => reject_job(db_pool, admin_id, target_id, note).await?,
+            ReportTargetType::Message => {
+                sqlx::query("DELETE FROM notifications WHERE id::text = $1")
+                    .bind(target_id)
+                    .execute(db_pool)
+                    .await?;
+            }
+            ReportTargetType::Review => {
+                sqlx::query("UPDATE company_reviews SET status = 'removed', updated_at = NOW() WHERE id::text = $1")
+                    .bind(target_id)
+                    .execute(db_pool)
+                    .await?;
+            }
+            ReportTargetType::User | ReportTargetType::Company => {
+                return Err(AppError::ValidationError(
+                    "Only jobs, messages, and reviews can be removed".to_string(),
+                ));
+            }
+        },
+    }
+    if target_type == ReportTargetType::Review && action != ReportAction::Remove {
+        sqlx::query(
+            "UPDATE company_reviews SET status = 'published', updated_at = NOW() WHERE id::text = $1 AND status = 'hidden'",
+        )
+        .bind(target_id)
+        .execute(db_pool)
+        .await?;
+    }
+
+    let status = if action == ReportAction::Dismiss {
+        ReportStatus::Dismissed
+    } else {
+        ReportStatus::Resolved
+    };
+    let closed = sqlx::query(
+        r#"
+        UPDATE reports
+        SET status = $3, resolution = $4, resolution_note = $5, resolved_by = $6, resolved_at = NOW()
+        WHERE target_type = $1 AND target_id = $2 AND status = 'open'
+        "#,
+    )
+    .bind(target_type)
+    .bind(target_id)
+    .bind(status)
+    .bind(action.as_str())
+    .bind(note)
+    .bind(admin_id)
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, company_id, action, target_type, target_id, metadata)
+        VALUES ($1, $2, $3, 'report', $4, $5)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(report.reported_company_id)
+    .bind(format!("report.{}", action.as_str()))
+    .bind(report_id.to_string())
+    .bind(json!({ "target_type": target_type, "target_id": target_id, "reports": closed, "note": note }))
+    .execute(db_pool)
+    .await?;
+
+    info!(
+        "Report {} resolved by admin {} with {}: {} reports closed",
+        report_id,
+        admin_id,
+        action.as_str(),
+        closed
+    );
+    fetch_queued(db_pool, report_id).await
+}
+
+/// Notifies the reported user, or the owners of the reported company.
+async fn warn_party(db_pool: &PgPool, report: &QueuedReport, note: &str) -> AppResult<()> {
+    let warned = sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, title, message, type)
+        SELECT DISTINCT recipient, 'Warning from CareerBridge moderators', $3, 'moderation_warning'
+        FROM (
+            SELECT $1::uuid AS recipient
+            UNION
+            SELECT m.user_id FROM company_members m
+            WHERE $1::uuid IS NULL AND m.company_id = $2 AND m.role = 'owner'
+        ) r
+        WHERE recipient IS NOT NULL
+        "#,
+    )
+    .bind(report.reported_user_id)
+    .bind(report.reported_company_id)
+    .bind(note)
+    .execute(db_pool)
+    .await?
+    .rows_affected();
+    if warned == 0 {
+        return Err(AppError::ValidationError(
+            "No one is answerable for this target to warn".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects a reported job and tells its poster why.
+async fn reject_job(db_pool: &PgPool, admin_id: Uuid, target_id: &str, note: Option<&str>) -> AppResult<()> {
+    let Some((job_title, posted_by)) = sqlx::query_as::<_, (String, Option<Uuid>)>(
+        r#"
+        UPDATE jobs
+        SET moderation_status = 'rejected', moderation_reason = $2,
+            moderated_by = $3, moderated_at = CURRENT_TIMESTAMP
+        WHERE id::text = $1
+        RETURNING job_title, posted_by
+        "#,
+    )
+    .bind(target_id)
+    .bind(note)
+    .bind(admin_id)
+    .fetch_optional(db_pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    if let Some(employer_id) = posted_by
+        && let Err(e) =
+            moderation::notify_employer(db_pool, employer_id, &job_title, ModerationStatus::Rejected, note).await
+    {
+        warn!("Failed to notify employer {} about job {}: {}", employer_id, target_id, e);
+    }
+    Ok(())
+}
+
+/// Closes a job's open reports once a moderator approves or rejects it.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn close_for_job(
+    db_pool: &PgPool,
+    admin_id: Uuid,
+    job_id: i32,
+    decision: ModerationStatus,
+    reason: Option<&str>,
+) -> AppResult<()> {
+    let (status, action) = match decision {
+        ModerationStatus::Rejected => (ReportStatus::Resolved, ReportAction::Remove),
+        _ => (ReportStatus::Dismissed, ReportAction::Dismiss),
+    };
+    sqlx::query(
+        r#"
+        UPDATE reports
+        SET status = $2, resolution = $3, resolution_note = $4, resolved_by = $5, resolved_at = NOW()
+        WHERE target_type = 'job' AND target_id = $1 AND status = 'open'
+        "#,
+    )
+    .bind(job_id.to_string())
+    .bind(status)
+    .bind(action.as_str())
+    .bind(reason)
+    .bind(admin_id)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+/// The user and company answerable for a report's target.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the report does not exist, or an error
+/// if the database operation fails.
+pub async fn reported_party_of(db_pool: &PgPool, report_id: i32) -> AppResult<(Option<Uuid>, Option<i32>)> {
+    sqlx::query_as::<_, (Option<Uuid>, Option<i32>)>(
+        "SELECT reported_user_id, reported_company_id FROM reports WHERE id = $1",
+    )
+    .bind(report_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
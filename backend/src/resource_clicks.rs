@@ -0,0 +1,204 @@
+//! Learning resource click tracking.
+//!
+//! Clients link to resources through `GET /api/resources/:id/go`, which
+//! [`follow`]s the link: it logs the click with the user and the roadmap it
+//! came from, then redirects to the resource. Admins can configure affiliate
+//! query parameters per resource ([`set_affiliate_params`]); they are added
+//! to the URL on redirect and never shown elsewhere. [`most_used`] ranks
+//! resources by recent clicks.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use url::Url;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Affiliate parameters a resource may carry.
+const MAX_AFFILIATE_PARAMS: usize = 10;
+
+/// Longest affiliate parameter name or value, in characters.
+const MAX_AFFILIATE_PARAM_CHARS: usize = 200;
+
+/// A resource's clicks over a period.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ResourceUsage {
+    pub resource_id: i32,
+    pub title: String,
+    pub platform: String,
+    pub url: String,
+    pub clicks: i64,
+    /// Distinct signed-in users who clicked
+    pub unique_users: i64,
+    /// Clicks followed from a roadmap
+    pub roadmap_clicks: i64,
+    /// Clicks redirected with affiliate parameters
+    pub affiliate_clicks: i64,
+    pub last_clicked_at: DateTime<Utc>,
+}
+
+/// A resource's affiliate parameters.
+#[derive(Debug, Serialize)]
+pub struct AffiliateParams {
+    pub resource_id: i32,
+    pub params: BTreeMap<String, String>,
+}
+
+/// The resource URL with the affiliate parameters set, replacing any
+/// query parameters of the same name. URLs that do not parse are returned
+/// unchanged.
+pub fn destination(url: &str, params: &BTreeMap<String, String>) -> String {
+    if params.is_empty() {
+        return url.to_string();
+    }
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| !params.contains_key(name.as_ref()))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(kept)
+        .extend_pairs(params);
+    parsed.to_string()
+}
+
+/// Affiliate parameters stored as a JSON object; values that are not
+/// strings are ignored.
+fn params_from_json(value: &Value) -> BTreeMap<String, String> {
+    value
+        .as_object()
+        .map(|object| {
+            object
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Logs a click on a resource and returns the URL to redirect to.
+///
+/// The roadmap is recorded only when it is the signed-in user's own. Click
+/// logging is a side effect, so failures are logged rather than failing the
+/// redirect.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the resource does not exist, or an error
+/// if it cannot be loaded.
+pub async fn follow(
+    db_pool: &PgPool,
+    resource_id: i32,
+    user_id: Option<Uuid>,
+    roadmap_id: Option<i32>,
+) -> AppResult<String> {
+    let (url, params) = sqlx::query_as::<_, (String, Value)>(
+        "SELECT url, affiliate_params FROM learning_resources WHERE id = $1",
+    )
+    .bind(resource_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let params = params_from_json(&params);
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO resource_clicks (resource_id, user_id, roadmap_id, affiliate)
+        SELECT $1, $2,
+               (SELECT id FROM career_roadmaps WHERE id = $3 AND user_id = $2),
+               $4
+        "#,
+    )
+    .bind(resource_id)
+    .bind(user_id)
+    .bind(roadmap_id)
+    .bind(!params.is_empty())
+    .execute(db_pool)
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to record click on resource {}: {}", resource_id, e);
+    }
+
+    Ok(destination(&url, &params))
+}
+
+/// The most clicked resources over the last `days` days.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn most_used(db_pool: &PgPool, days: i32, limit: i64) -> AppResult<Vec<ResourceUsage>> {
+    let usage = sqlx::query_as::<_, ResourceUsage>(
+        r#"
+        SELECT lr.id AS resource_id, lr.title, lr.platform, lr.url,
+               COUNT(*) AS clicks,
+               COUNT(DISTINCT rc.user_id) AS unique_users,
+               COUNT(rc.roadmap_id) AS roadmap_clicks,
+               COUNT(*) FILTER (WHERE rc.affiliate) AS affiliate_clicks,
+               MAX(rc.created_at) AS last_clicked_at
+        FROM resource_clicks rc
+        JOIN learning_resources lr ON lr.id = rc.resource_id
+        LEFT JOIN users u ON u.id = rc.user_id
+        WHERE rc.created_at > NOW() - make_interval(days => $1) AND u.sandbox_key_id IS NULL
+        GROUP BY lr.id
+        ORDER BY clicks DESC, unique_users DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(days)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(usage)
+}
+
+/// Replaces a resource's affiliate parameters; an empty map removes them.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for too many, empty, or overlong
+/// parameters, `AppError::NotFound` if the resource does not exist, or an
+/// error if the database operation fails.
+pub async fn set_affiliate_params(
+    db_pool: &PgPool,
+    resource_id: i32,
+    params: BTreeMap<String, String>,
+) -> AppResult<AffiliateParams> {
+    if params.len() > MAX_AFFILIATE_PARAMS {
+        return Err(AppError::ValidationError(format!(
+            "At most {} affiliate parameters are allowed",
+            MAX_AFFILIATE_PARAMS
+        )));
+    }
+    for (name, value) in &params {
+        if name.trim().is_empty() {
+            return Err(AppError::ValidationError("Affiliate parameter names cannot be empty".to_string()));
+        }
+        if name.chars().count() > MAX_AFFILIATE_PARAM_CHARS || value.chars().count() > MAX_AFFILIATE_PARAM_CHARS {
+            return Err(AppError::ValidationError(format!(
+                "Affiliate parameter names and values must be at most {} characters",
+                MAX_AFFILIATE_PARAM_CHARS
+            )));
+        }
+    }
+
+    let updated = sqlx::query("UPDATE learning_resources SET affiliate_params = $2 WHERE id = $1")
+        .bind(resource_id)
+        .bind(serde_json::to_value(&params).unwrap_or_default())
+        .execute(db_pool)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(AffiliateParams { resource_id, params })
+}
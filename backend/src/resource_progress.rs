@@ -0,0 +1,270 @@
+//! Per-resource progress in roadmap phases.
+//!
+//! Each resource listed in a roadmap phase has an id `"<phase>-<n>"`, where
+//! `n` is its 1-based position in the phase's resources. Users mark a
+//! resource completed or skipped with [`update`], optionally rating it 1-5
+//! and leaving a note. A mark only applies while the roadmap still lists the
+//! same resource at that position, so regenerated phases start clean.
+//!
+//! Marks are aggregated across users by resource key: the resource's first
+//! URL, or its lowercased text when it has none. [`quality_by_key`] returns
+//! the average rating and skip rate per key, and [`ResourceQuality::factor`]
+//! turns them into a weight the catalog recommendations are ranked by.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::links;
+use crate::models::ResourceStatus;
+
+/// Ratings a resource needs before they change its ranking.
+pub const MIN_RATINGS: i64 = 3;
+
+/// Skip rate at or above which a resource is ranked down.
+const HIGH_SKIP_RATE: f64 = 0.5;
+
+/// A resource in a roadmap phase and the user's progress with it.
+#[derive(Debug, Serialize)]
+pub struct RoadmapResource {
+    /// `"<phase>-<n>"`, used in `PUT /api/ai/roadmaps/:id/resources/:rid`
+    pub rid: String,
+    pub phase: i32,
+    pub resource: String,
+    /// The first URL in the resource, if any
+    pub url: Option<String>,
+    /// `None` until the user marks the resource
+    pub status: Option<ResourceStatus>,
+    pub rating: Option<i16>,
+    pub note: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Ratings and skips of one resource across all users.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ResourceQuality {
+    /// Average rating, `None` if nobody has rated it
+    pub average_rating: Option<f64>,
+    pub rating_count: i64,
+    /// Share of marks that were skips (0-1)
+    pub skip_rate: f64,
+    /// Users who marked the resource completed or skipped
+    pub mark_count: i64,
+}
+
+impl ResourceQuality {
+    /// Weight applied to a resource's relevance when ranking: 0.7 for an
+    /// average rating of 1 up to 1.3 for 5, once it has [`MIN_RATINGS`]
+    /// ratings, and a further 0.8 if most users who marked it skipped it.
+    pub fn factor(&self) -> f64 {
+        let mut factor = match self.average_rating {
+            Some(average) if self.rating_count >= MIN_RATINGS => 1.0 + (average - 3.0) * 0.15,
+            _ => 1.0,
+        };
+        if self.mark_count >= MIN_RATINGS && self.skip_rate >= HIGH_SKIP_RATE {
+            factor *= 0.8;
+        }
+        factor
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct ProgressRow {
+    phase: i32,
+    resource_index: i32,
+    resource: String,
+    status: ResourceStatus,
+    rating: Option<i16>,
+    note: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+/// The key marks of a resource are aggregated by.
+pub fn resource_key(resource: &str) -> String {
+    match links::extract_urls(resource).first() {
+        Some(url) => url.to_string(),
+        None => resource.trim().to_lowercase(),
+    }
+}
+
+/// Parses a resource id `"<phase>-<n>"`.
+fn parse_rid(rid: &str) -> Option<(i32, i32)> {
+    let (phase, index) = rid.split_once('-')?;
+    let index = index.parse::<i32>().ok().filter(|i| *i >= 1)?;
+    Some((phase.parse().ok()?, index))
+}
+
+/// The resources of each phase, as (phase number, resource index, text).
+fn roadmap_resources(roadmap_data: &Value) -> Vec<(i32, i32, String)> {
+    let Some(phases) = roadmap_data.get("phases").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    phases
+        .iter()
+        .enumerate()
+        .flat_map(|(index, phase)| {
+            let number = phase
+                .get("phase")
+                .and_then(Value::as_i64)
+                .map_or(index as i32 + 1, |n| n as i32);
+            phase
+                .get("resources")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .enumerate()
+                .map(move |(position, resource)| (number, position as i32 + 1, resource.to_string()))
+        })
+        .collect()
+}
+
+/// Loads the roadmap's data if it is the user's.
+async fn load_roadmap(db_pool: &PgPool, user_id: Uuid, roadmap_id: i32) -> AppResult<Value> {
+    sqlx::query_scalar::<_, Value>("SELECT roadmap_data FROM career_roadmaps WHERE id = $1 AND user_id = $2")
+        .bind(roadmap_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Lists every resource in the user's roadmap with their progress.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the roadmap is not the user's, or an
+/// error if the database operation fails.
+pub async fn list(db_pool: &PgPool, user_id: Uuid, roadmap_id: i32) -> AppResult<Vec<RoadmapResource>> {
+    let roadmap_data = load_roadmap(db_pool, user_id, roadmap_id).await?;
+    let rows = sqlx::query_as::<_, ProgressRow>(
+        r#"
+        SELECT phase, resource_index, resource, status, rating, note, updated_at
+        FROM roadmap_resource_progress
+        WHERE roadmap_id = $1
+        "#,
+    )
+    .bind(roadmap_id)
+    .fetch_all(db_pool)
+    .await?;
+    let mut marks: HashMap<(i32, i32), ProgressRow> =
+        rows.into_iter().map(|row| ((row.phase, row.resource_index), row)).collect();
+
+    Ok(roadmap_resources(&roadmap_data)
+        .into_iter()
+        .map(|(phase, index, resource)| {
+            // Marks of a resource since replaced by regeneration are stale
+            let mark = marks
+                .remove(&(phase, index))
+                .filter(|row| row.resource == resource);
+            to_resource(phase, index, resource, mark)
+        })
+        .collect())
+}
+
+fn to_resource(phase: i32, index: i32, resource: String, mark: Option<ProgressRow>) -> RoadmapResource {
+    RoadmapResource {
+        rid: format!("{}-{}", phase, index),
+        phase,
+        url: links::extract_urls(&resource).first().map(|url| url.to_string()),
+        resource,
+        status: mark.as_ref().map(|m| m.status),
+        rating: mark.as_ref().and_then(|m| m.rating),
+        note: mark.as_ref().and_then(|m| m.note.clone()),
+        updated_at: mark.map(|m| m.updated_at),
+    }
+}
+
+/// Marks a resource in the user's roadmap completed or skipped, replacing
+/// any earlier mark, rating, and note.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the roadmap is not the user's or has no
+/// resource `rid`, or an error if the database operation fails.
+pub async fn update(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    roadmap_id: i32,
+    rid: &str,
+    status: ResourceStatus,
+    rating: Option<i16>,
+    note: Option<&str>,
+) -> AppResult<RoadmapResource> {
+    let roadmap_data = load_roadmap(db_pool, user_id, roadmap_id).await?;
+    let (phase, index) = parse_rid(rid).ok_or(AppError::NotFound)?;
+    let resource = roadmap_resources(&roadmap_data)
+        .into_iter()
+        .find(|(p, i, _)| *p == phase && *i == index)
+        .map(|(_, _, resource)| resource)
+        .ok_or(AppError::NotFound)?;
+    let note = note.map(str::trim).filter(|n| !n.is_empty());
+
+    let row = sqlx::query_as::<_, ProgressRow>(
+        r#"
+        INSERT INTO roadmap_resource_progress
+            (roadmap_id, user_id, phase, resource_index, resource, resource_key, status, rating, note)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (roadmap_id, phase, resource_index) DO UPDATE
+        SET resource = EXCLUDED.resource, resource_key = EXCLUDED.resource_key,
+            status = EXCLUDED.status, rating = EXCLUDED.rating, note = EXCLUDED.note,
+            updated_at = NOW()
+        RETURNING phase, resource_index, resource, status, rating, note, updated_at
+        "#,
+    )
+    .bind(roadmap_id)
+    .bind(user_id)
+    .bind(phase)
+    .bind(index)
+    .bind(&resource)
+    .bind(resource_key(&resource))
+    .bind(status)
+    .bind(rating)
+    .bind(note)
+    .fetch_one(db_pool)
+    .await?;
+
+    Ok(to_resource(phase, index, resource, Some(row)))
+}
+
+/// Ratings and skip rates across all users for the given resource keys.
+/// Keys nobody has marked are missing from the map.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn quality_by_key(db_pool: &PgPool, keys: &[String]) -> AppResult<HashMap<String, ResourceQuality>> {
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    #[derive(FromRow)]
+    struct Row {
+        resource_key: String,
+        #[sqlx(flatten)]
+        quality: ResourceQuality,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT p.resource_key,
+               AVG(p.rating)::float8 AS average_rating,
+               COUNT(p.rating) AS rating_count,
+               AVG(CASE WHEN p.status = 'skipped' THEN 1.0 ELSE 0.0 END)::float8 AS skip_rate,
+               COUNT(*) AS mark_count
+        FROM roadmap_resource_progress p
+        JOIN users u ON u.id = p.user_id
+        WHERE p.resource_key = ANY($1) AND u.sandbox_key_id IS NULL
+        GROUP BY p.resource_key
+        "#,
+    )
+    .bind(keys)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(rows.into_iter().map(|row| (row.resource_key, row.quality)).collect())
+}
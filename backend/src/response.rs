@@ -0,0 +1,94 @@
+//! The response envelope shared by every JSON endpoint.
+//!
+//! Successful responses are wrapped in [`ApiResponse`]:
+//!
+//! ```json
+//! { "success": true, "data": { ... }, "meta": { ... }, "pagination": { ... } }
+//! ```
+//!
+//! `meta` (request-level details such as the AI provider used or warnings)
+//! and `pagination` are left out when empty. Errors use the same `success`
+//! flag with an `error` message instead of `data` (see
+//! [`AppError`](crate::errors::AppError)).
+//!
+//! The envelope is the `/api/v1` shape; the deprecated `/api` alias serves
+//! the earlier unwrapped bodies through a
+//! [legacy shim](crate::versioning::LEGACY_SHIMS).
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// A successful response.
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+/// Position of a page within a paginated list.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Pagination {
+    /// 1-based page number
+    pub page: u32,
+    pub per_page: u32,
+    /// Items across all pages
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+impl Pagination {
+    pub fn new(page: u32, per_page: u32, total: i64) -> Self {
+        let per_page = per_page.max(1);
+        Self {
+            page: page.max(1),
+            per_page,
+            total,
+            total_pages: (total.max(0) as u64).div_ceil(per_page as u64) as u32,
+        }
+    }
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    /// Wraps `data` in a successful response.
+    pub fn ok(data: T) -> Self {
+        Self::new(true, data)
+    }
+
+    /// Wraps `data`, with `success` reporting whether the work the request
+    /// asked for succeeded, e.g. an AI action whose provider call failed.
+    pub fn new(success: bool, data: T) -> Self {
+        Self {
+            success,
+            data,
+            meta: None,
+            pagination: None,
+        }
+    }
+
+    /// Adds a `meta` entry.
+    pub fn meta(mut self, key: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.meta.get_or_insert_with(Map::new).insert(key.to_string(), value);
+        self
+    }
+
+    /// Sets the pagination of a list response.
+    pub fn paginated(mut self, pagination: Pagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+}
+
+impl<T: Serialize> IntoResponse for ApiResponse<T> {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
@@ -0,0 +1,369 @@
+//! Retrieval over user documents for grounded mentor answers.
+//!
+//! The user's CV, roadmaps, and saved documents are cut into overlapping
+//! chunks of words, embedded, and stored in `document_chunks` (pgvector).
+//! [`retrieve`] first brings the index up to date — only sources whose text
+//! changed since they were embedded are re-embedded — then returns the
+//! chunks closest to the question by cosine similarity, together with the
+//! closest published knowledge base chunks (see [`crate::knowledge_base`]).
+//! The mentor answers from those chunks and returns them as numbered sources.
+//!
+//! Embeddings need Gemini (or the mock client); without it mentor answers
+//! are not grounded and no sources are returned.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::errors::AppResult;
+use crate::knowledge_base;
+use crate::models::DocumentSource;
+
+/// Words per chunk.
+const CHUNK_WORDS: usize = 200;
+
+/// Words repeated at the start of the next chunk, so a passage cut at a
+/// chunk boundary is still found whole in one of them.
+const CHUNK_OVERLAP_WORDS: usize = 40;
+
+/// Chunks returned per question.
+pub const DEFAULT_LIMIT: i64 = 5;
+
+/// Lowest cosine similarity for a chunk to count as relevant.
+const MIN_SIMILARITY: f64 = 0.35;
+
+/// Characters of a chunk shown as its source excerpt.
+const EXCERPT_CHARS: usize = 240;
+
+/// A document the user saved for the mentor.
+#[derive(Debug, Serialize, FromRow)]
+pub struct UserDocument {
+    pub id: i32,
+    pub title: String,
+    pub content: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Text of one source, ready to chunk.
+#[derive(Debug)]
+struct SourceText {
+    source: DocumentSource,
+    source_id: Option<i32>,
+    title: String,
+    text: String,
+}
+
+impl SourceText {
+    fn hash(&self) -> String {
+        hex::encode(Sha256::digest(self.text.as_bytes()))
+    }
+}
+
+/// A chunk retrieved for a question.
+#[derive(Debug, Clone, FromRow)]
+pub struct RetrievedChunk {
+    pub source: DocumentSource,
+    pub source_id: Option<i32>,
+    pub source_title: String,
+    pub chunk_index: i16,
+    pub content: String,
+    /// Cosine similarity to the question, from -1 to 1
+    pub similarity: f64,
+}
+
+/// A retrieved chunk as cited in a mentor answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceAttribution {
+    /// Number the answer cites the chunk by, e.g. `[2]`
+    pub reference: usize,
+    pub source: DocumentSource,
+    /// Roadmap, document, or knowledge base article id; `None` for the CV
+    pub source_id: Option<i32>,
+    pub title: String,
+    pub excerpt: String,
+    pub similarity: f64,
+}
+
+/// Cuts text into chunks of [`CHUNK_WORDS`] words overlapping by
+/// [`CHUNK_OVERLAP_WORDS`].
+pub fn chunk_text(text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        let end = (start + CHUNK_WORDS).min(words.len());
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP_WORDS;
+    }
+    chunks
+}
+
+/// Renders a roadmap as plain text for indexing.
+fn roadmap_text(title: &str, target_role: &str, completed_phases: &[i32], data: &Value) -> String {
+    let list = |value: &Value, key: &str| -> String {
+        value
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|i| i.as_str()).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default()
+    };
+
+    let mut lines = vec![format!("{} (target role: {})", title, target_role)];
+    for phase in data.get("phases").and_then(|p| p.as_array()).into_iter().flatten() {
+        let number = phase.get("phase").and_then(|n| n.as_i64()).unwrap_or_default();
+        let status = if completed_phases.contains(&(number as i32)) { "completed" } else { "not completed" };
+        lines.push(format!(
+            "Phase {}: {} ({}, {}). Topics: {}. Technologies: {}. Goals: {}. Resources: {}.",
+            number,
+            phase.get("title").and_then(|t| t.as_str()).unwrap_or_default(),
+            phase.get("duration").and_then(|d| d.as_str()).unwrap_or_default(),
+            status,
+            list(phase, "topics"),
+            list(phase, "technologies"),
+            list(phase, "learning_goals"),
+            list(phase, "resources"),
+        ));
+    }
+    for project in data.get("project_suggestions").and_then(|p| p.as_array()).into_iter().flatten() {
+        lines.push(format!(
+            "Project: {}. {}",
+            project.get("title").and_then(|t| t.as_str()).unwrap_or_default(),
+            project.get("description").and_then(|d| d.as_str()).unwrap_or_default(),
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Loads the text of every source of the user's index.
+async fn load_sources(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<SourceText>> {
+    let mut sources = Vec::new();
+
+    let cv = sqlx::query_scalar::<_, Option<String>>("SELECT raw_cv_text FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .flatten()
+        .filter(|text| !text.trim().is_empty());
+    if let Some(text) = cv {
+        sources.push(SourceText {
+            source: DocumentSource::Cv,
+            source_id: None,
+            title: "CV".to_string(),
+            text,
+        });
+    }
+
+    let roadmaps = sqlx::query_as::<_, (i32, String, String, Option<Vec<i32>>, Value)>(
+        r#"
+        SELECT id, title, target_role, completed_phases, roadmap_data
+        FROM career_roadmaps
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    sources.extend(roadmaps.into_iter().map(|(id, title, target_role, completed, data)| SourceText {
+        source: DocumentSource::Roadmap,
+        source_id: Some(id),
+        text: roadmap_text(&title, &target_role, &completed.unwrap_or_default(), &data),
+        title,
+    }));
+
+    let documents = sqlx::query_as::<_, (i32, String, String)>(
+        "SELECT id, title, content FROM user_documents WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    sources.extend(documents.into_iter().map(|(id, title, text)| SourceText {
+        source: DocumentSource::Document,
+        source_id: Some(id),
+        title,
+        text,
+    }));
+
+    Ok(sources)
+}
+
+/// Formats a vector as a pgvector literal, e.g. `[0.1,0.2]`.
+pub fn vector_literal(vector: &[f32]) -> String {
+    let values: Vec<String> = vector.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Brings the user's index up to date: embeds new and changed sources and
+/// drops chunks of deleted ones. Returns the number of chunks embedded.
+///
+/// # Errors
+///
+/// Returns an error if embedding or the database operation fails.
+pub async fn index_user_documents(db_pool: &PgPool, ai: &AIService, user_id: Uuid) -> AppResult<usize> {
+    let sources = load_sources(db_pool, user_id).await?;
+    let indexed: HashMap<(DocumentSource, Option<i32>), String> =
+        sqlx::query_as::<_, (DocumentSource, Option<i32>, String)>(
+            "SELECT DISTINCT source, source_id, source_hash FROM document_chunks WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?
+        .into_iter()
+        .map(|(source, source_id, hash)| ((source, source_id), hash))
+        .collect();
+
+    let mut embedded = 0;
+    for source in &sources {
+        let hash = source.hash();
+        if indexed.get(&(source.source, source.source_id)) == Some(&hash) {
+            continue;
+        }
+
+        let chunks = chunk_text(&source.text);
+        // The title is embedded with each chunk so it matches questions naming the source
+        let inputs: Vec<String> = chunks.iter().map(|c| format!("{}\n{}", source.title, c)).collect();
+        let vectors = ai.embed(&inputs).await?;
+
+        let mut tx = db_pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM document_chunks WHERE user_id = $1 AND source = $2 AND source_id IS NOT DISTINCT FROM $3",
+        )
+        .bind(user_id)
+        .bind(source.source)
+        .bind(source.source_id)
+        .execute(&mut *tx)
+        .await?;
+        for (index, (chunk, vector)) in chunks.iter().zip(&vectors).enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO document_chunks
+                    (user_id, source, source_id, source_title, source_hash, chunk_index, content, embedding)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8::vector)
+                "#,
+            )
+            .bind(user_id)
+            .bind(source.source)
+            .bind(source.source_id)
+            .bind(source.title.chars().take(255).collect::<String>())
+            .bind(&hash)
+            .bind(index as i16)
+            .bind(chunk)
+            .bind(vector_literal(vector))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        embedded += chunks.len();
+    }
+
+    let live: Vec<(DocumentSource, Option<i32>)> = sources.iter().map(|s| (s.source, s.source_id)).collect();
+    for key in indexed.keys().filter(|key| !live.contains(key)) {
+        sqlx::query(
+            "DELETE FROM document_chunks WHERE user_id = $1 AND source = $2 AND source_id IS NOT DISTINCT FROM $3",
+        )
+        .bind(user_id)
+        .bind(key.0)
+        .bind(key.1)
+        .execute(db_pool)
+        .await?;
+    }
+
+    if embedded > 0 {
+        info!("Embedded {} document chunks for user {}", embedded, user_id);
+    }
+    Ok(embedded)
+}
+
+/// Returns the chunks most relevant to a question, best first, from the
+/// user's documents (after bringing their index up to date) and the
+/// knowledge base.
+///
+/// # Errors
+///
+/// Returns an error if embedding or the database operation fails.
+pub async fn retrieve(
+    db_pool: &PgPool,
+    ai: &AIService,
+    user_id: Uuid,
+    question: &str,
+    limit: i64,
+) -> AppResult<Vec<RetrievedChunk>> {
+    index_user_documents(db_pool, ai, user_id).await?;
+
+    let Some(query_vector) = ai.embed(&[question.to_string()]).await?.pop() else {
+        return Ok(Vec::new());
+    };
+    let query_vector = vector_literal(&query_vector);
+    let mut chunks = sqlx::query_as::<_, RetrievedChunk>(
+        r#"
+        SELECT source, source_id, source_title, chunk_index, content,
+               1 - (embedding <=> $2::vector) AS similarity
+        FROM document_chunks
+        WHERE user_id = $1
+        ORDER BY embedding <=> $2::vector
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(&query_vector)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    chunks.extend(knowledge_base::nearest(db_pool, &query_vector, limit).await?);
+
+    chunks.retain(|c| c.similarity >= MIN_SIMILARITY);
+    chunks.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    chunks.truncate(limit.max(0) as usize);
+    Ok(chunks)
+}
+
+/// Renders retrieved chunks as numbered excerpts for the prompt context.
+pub fn to_context(chunks: &[RetrievedChunk]) -> String {
+    let excerpts: Vec<String> = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let origin = if chunk.source == DocumentSource::KnowledgeBase {
+                "Knowledge base article"
+            } else {
+                "User's"
+            };
+            format!("[{}] {} {}: {}", i + 1, origin, chunk.source_title, chunk.content)
+        })
+        .collect();
+    format!(
+        "Excerpts from the user's documents and the platform knowledge base (cite them as [n] when you use them):\n{}",
+        excerpts.join("\n\n")
+    )
+}
+
+/// The sources to return with an answer, numbered as in [`to_context`].
+pub fn attributions(chunks: &[RetrievedChunk]) -> Vec<SourceAttribution> {
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut excerpt: String = chunk.content.chars().take(EXCERPT_CHARS).collect();
+            if chunk.content.chars().count() > EXCERPT_CHARS {
+                excerpt.push('…');
+            }
+            SourceAttribution {
+                reference: i + 1,
+                source: chunk.source,
+                source_id: chunk.source_id,
+                title: chunk.source_title.clone(),
+                excerpt,
+                similarity: chunk.similarity,
+            }
+        })
+        .collect()
+}
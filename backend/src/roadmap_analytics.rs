@@ -0,0 +1,299 @@
+//! Roadmap progress analytics.
+//!
+//! Every progress update is snapshotted into `roadmap_progress_events` by
+//! [`record`]. [`analytics`] turns a roadmap's snapshots into a time series,
+//! a projected completion date compared with the roadmap's target (its
+//! creation date plus `timeframe_months`), and the time each phase took
+//! against the duration the roadmap planned for it, so slippage shows up
+//! before the target date has passed.
+//!
+//! The projection extrapolates the progress made over the last
+//! [`VELOCITY_WINDOW_DAYS`] days (or since the roadmap was created, if that
+//! is more recent), so a student who has stalled is projected late even if
+//! they started fast.
+
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// Days of recent progress the completion projection is based on.
+pub const VELOCITY_WINDOW_DAYS: i64 = 28;
+
+/// Progress snapshot at one point in time.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProgressPoint {
+    pub progress_percentage: i32,
+    pub completed_phases: Vec<i32>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// How long one phase took compared with its plan.
+#[derive(Debug, Serialize)]
+pub struct PhaseVelocity {
+    pub phase: i32,
+    pub title: String,
+    /// The phase's planned duration in days, parsed from its `duration`
+    pub planned_days: Option<f64>,
+    /// When the phase was first marked completed
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Days from the previous phase's completion (or the roadmap's
+    /// creation) to this one's
+    pub actual_days: Option<f64>,
+}
+
+/// Progress analytics for one roadmap.
+#[derive(Debug, Serialize)]
+pub struct RoadmapAnalytics {
+    pub roadmap_id: i32,
+    pub title: String,
+    pub progress_percentage: i32,
+    pub started_at: DateTime<Utc>,
+    /// Creation date plus the roadmap's timeframe
+    pub target_date: Option<DateTime<Utc>>,
+    /// Percentage points per week over the velocity window
+    pub weekly_velocity: f64,
+    /// When the roadmap will be finished at the current velocity; `None`
+    /// when progress has stalled
+    pub projected_completion: Option<DateTime<Utc>>,
+    /// Days the projection lies past the target (negative when ahead)
+    pub days_behind_target: Option<i64>,
+    pub on_track: Option<bool>,
+    /// Snapshots from every progress update, oldest first, starting at 0%
+    /// on creation
+    pub series: Vec<ProgressPoint>,
+    pub phases: Vec<PhaseVelocity>,
+}
+
+#[derive(Debug, FromRow)]
+struct RoadmapRow {
+    title: String,
+    roadmap_data: Value,
+    timeframe_months: Option<i32>,
+    progress_percentage: Option<i32>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// Snapshots a roadmap's current progress after an update.
+///
+/// Analytics are a side effect, so failures are logged rather than failing
+/// the update.
+pub async fn record(db_pool: &PgPool, roadmap_id: i32) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO roadmap_progress_events (roadmap_id, user_id, progress_percentage, completed_phases)
+        SELECT id, user_id, COALESCE(progress_percentage, 0), COALESCE(completed_phases, '{}')
+        FROM career_roadmaps
+        WHERE id = $1
+        "#,
+    )
+    .bind(roadmap_id)
+    .execute(db_pool)
+    .await;
+
+    if let Err(e) = result {
+        warn!("Failed to record progress event for roadmap {}: {}", roadmap_id, e);
+    }
+}
+
+/// Whether `viewer` may see `owner`'s roadmap analytics: it is their own,
+/// or they are staff of an organization with a cohort `owner` belongs to.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn may_view(db_pool: &PgPool, viewer: Uuid, owner: Uuid) -> AppResult<bool> {
+    if viewer == owner {
+        return Ok(true);
+    }
+    let advises = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM organization_members om
+            JOIN cohorts c ON c.org_id = om.org_id
+            JOIN cohort_members cm ON cm.cohort_id = c.id
+            WHERE om.user_id = $1 AND cm.user_id = $2
+        )
+        "#,
+    )
+    .bind(viewer)
+    .bind(owner)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(advises)
+}
+
+/// Builds the analytics for a roadmap the viewer may see.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the roadmap does not exist or the viewer
+/// may not see it, or an error if the database operation fails.
+pub async fn analytics(db_pool: &PgPool, viewer: Uuid, roadmap_id: i32) -> AppResult<RoadmapAnalytics> {
+    let owner = sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM career_roadmaps WHERE id = $1")
+        .bind(roadmap_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if !may_view(db_pool, viewer, owner).await? {
+        return Err(AppError::NotFound);
+    }
+
+    let roadmap = sqlx::query_as::<_, RoadmapRow>(
+        r#"
+        SELECT title, roadmap_data, timeframe_months, progress_percentage, created_at
+        FROM career_roadmaps
+        WHERE id = $1
+        "#,
+    )
+    .bind(roadmap_id)
+    .fetch_one(db_pool)
+    .await?;
+    let events = sqlx::query_as::<_, ProgressPoint>(
+        r#"
+        SELECT progress_percentage, completed_phases, created_at AS recorded_at
+        FROM roadmap_progress_events
+        WHERE roadmap_id = $1
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(roadmap_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    let started_at = roadmap.created_at.unwrap_or_else(Utc::now);
+    let mut series = vec![ProgressPoint {
+        progress_percentage: 0,
+        completed_phases: Vec::new(),
+        recorded_at: started_at,
+    }];
+    series.extend(events);
+
+    let now = Utc::now();
+    let progress = roadmap.progress_percentage.unwrap_or(0);
+    let target_date = roadmap
+        .timeframe_months
+        .and_then(|months| u32::try_from(months).ok())
+        .and_then(|months| started_at.checked_add_months(Months::new(months)));
+
+    let weekly_velocity = weekly_velocity(&series, progress, now);
+    let projected_completion = if progress >= 100 {
+        series
+            .iter()
+            .find(|p| p.progress_percentage >= 100)
+            .map(|p| p.recorded_at)
+    } else if weekly_velocity > 0.0 {
+        let days = f64::from(100 - progress) / weekly_velocity * 7.0;
+        Some(now + Duration::seconds((days * 86_400.0) as i64))
+    } else {
+        None
+    };
+    let days_behind_target = match (projected_completion, target_date) {
+        (Some(projected), Some(target)) => Some((projected - target).num_days()),
+        _ => None,
+    };
+    let on_track = match (projected_completion, target_date) {
+        (Some(projected), Some(target)) => Some(projected <= target),
+        // Stalled short of the goal
+        (None, Some(_)) if progress < 100 => Some(false),
+        _ => None,
+    };
+
+    Ok(RoadmapAnalytics {
+        roadmap_id,
+        title: roadmap.title,
+        progress_percentage: progress,
+        started_at,
+        target_date,
+        weekly_velocity,
+        projected_completion,
+        days_behind_target,
+        on_track,
+        phases: phase_velocities(&roadmap.roadmap_data, &series),
+        series,
+    })
+}
+
+/// Percentage points gained per week over the velocity window.
+fn weekly_velocity(series: &[ProgressPoint], progress: i32, now: DateTime<Utc>) -> f64 {
+    let window_start = now - Duration::days(VELOCITY_WINDOW_DAYS);
+    // Progress at the start of the window: the last snapshot before it, or
+    // the creation baseline if the roadmap is younger than the window
+    let (baseline, since) = series
+        .iter()
+        .rev()
+        .find(|p| p.recorded_at <= window_start)
+        .map(|p| (p.progress_percentage, window_start))
+        .unwrap_or((0, series[0].recorded_at));
+    let days = days_between(since, now);
+    if days < 1.0 {
+        return 0.0;
+    }
+    (f64::from(progress - baseline) / days * 7.0 * 10.0).round() / 10.0
+}
+
+/// Fractional days from `from` to `to`, at least 0.
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds().max(0) as f64 / 86_400.0
+}
+
+/// Planned and actual time per phase, in phase order.
+fn phase_velocities(roadmap_data: &Value, series: &[ProgressPoint]) -> Vec<PhaseVelocity> {
+    let Some(phases) = roadmap_data.get("phases").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let mut previous = series.first().map(|p| p.recorded_at);
+    phases
+        .iter()
+        .enumerate()
+        .map(|(index, phase)| {
+            let number = phase
+                .get("phase")
+                .and_then(Value::as_i64)
+                .map_or(index as i32 + 1, |n| n as i32);
+            let completed_at = series
+                .iter()
+                .find(|p| p.completed_phases.contains(&number))
+                .map(|p| p.recorded_at);
+            let actual_days = match (previous, completed_at) {
+                (Some(from), Some(to)) => Some((days_between(from, to) * 10.0).round() / 10.0),
+                _ => None,
+            };
+            if completed_at.is_some() {
+                previous = completed_at;
+            }
+            PhaseVelocity {
+                phase: number,
+                title: phase.get("title").and_then(Value::as_str).unwrap_or_default().to_string(),
+                planned_days: phase.get("duration").and_then(Value::as_str).and_then(planned_days),
+                completed_at,
+                actual_days,
+            }
+        })
+        .collect()
+}
+
+/// Days in a duration such as "4 weeks", "1-2 months", or "10 days"; ranges
+/// count by their upper bound.
+fn planned_days(duration: &str) -> Option<f64> {
+    let lower = duration.to_lowercase();
+    let amount = lower
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter_map(|n| n.parse::<f64>().ok())
+        .next_back()?;
+    let unit = if lower.contains("month") {
+        30.0
+    } else if lower.contains("week") {
+        7.0
+    } else if lower.contains("day") {
+        1.0
+    } else {
+        return None;
+    };
+    Some(amount * unit)
+}
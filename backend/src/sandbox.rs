@@ -0,0 +1,323 @@
+//! Sandbox mode for API integrators.
+//!
+//! An API key with the `sandbox` scope (see [`crate::api_keys`]) creates
+//! disposable test users, jobs, and applications tagged with the key. Test
+//! users sign in with the token returned when they are created and use the
+//! API like anyone else, but each sandbox is sealed off:
+//! - Test users only see and apply to their sandbox's jobs, and production
+//!   users never see sandbox jobs (the `same_sandbox` SQL function)
+//! - Benchmarks, salary benchmarks, market statistics, resource analytics,
+//!   and platform AI usage leave sandbox users and jobs out
+//! - Test users cannot join cohorts, so placement reports and at-risk lists
+//!   never include them
+//!
+//! All sandbox data is purged every `SANDBOX_PURGE_INTERVAL_SECS` (nightly
+//! by default), and on demand through `DELETE /api/sandbox`.
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{ApplicationTracking, ExperienceLevel, Job, JobType, User, UserRole};
+
+/// Domain of test users' email addresses, which never receive mail.
+pub const SANDBOX_EMAIL_DOMAIN: &str = "sandbox.careerbridge.invalid";
+
+/// Test users a sandbox can hold between purges.
+const MAX_USERS_PER_SANDBOX: i64 = 100;
+
+/// Jobs a sandbox can hold between purges.
+const MAX_JOBS_PER_SANDBOX: i64 = 200;
+
+/// A new test user.
+#[derive(Debug)]
+pub struct SandboxUserDraft<'a> {
+    pub role: UserRole,
+    pub full_name: Option<&'a str>,
+    pub skills: &'a [String],
+    pub experience_level: Option<ExperienceLevel>,
+}
+
+/// A new test job.
+#[derive(Debug)]
+pub struct SandboxJobDraft<'a> {
+    /// Test employer posting it
+    pub posted_by: Option<Uuid>,
+    pub job_title: &'a str,
+    pub company: &'a str,
+    pub location: &'a str,
+    pub job_description: &'a str,
+    pub required_skills: &'a [String],
+    pub experience_level: ExperienceLevel,
+    pub job_type: JobType,
+    pub salary_min: Option<i32>,
+    pub salary_max: Option<i32>,
+}
+
+/// What a sandbox holds.
+#[derive(Debug, Default, Serialize, FromRow)]
+pub struct SandboxSummary {
+    pub users: i64,
+    pub jobs: i64,
+    pub applications: i64,
+}
+
+const JOB_COLUMNS: &str = r#"
+    id, job_title, company, location, job_description, required_skills, experience_level, job_type,
+    salary_min, salary_max, responsibilities, requirements, benefits,
+    employer_verified(company_id) AS employer_verified
+"#;
+
+/// Creates a test user in the key's sandbox, with the policies in force
+/// accepted so it can use the API right away.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for the admin role,
+/// `AppError::BadRequest` if the sandbox is full, or an error if the
+/// database operation fails.
+pub async fn create_user(db_pool: &PgPool, key_id: i32, draft: SandboxUserDraft<'_>) -> AppResult<User> {
+    if draft.role == UserRole::Admin {
+        return Err(AppError::ValidationError("Test users are job seekers or employers".to_string()));
+    }
+    let users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE sandbox_key_id = $1")
+        .bind(key_id)
+        .fetch_one(db_pool)
+        .await?;
+    if users >= MAX_USERS_PER_SANDBOX {
+        return Err(AppError::BadRequest(format!(
+            "A sandbox holds at most {} test users; purge it first",
+            MAX_USERS_PER_SANDBOX
+        )));
+    }
+
+    let id = Uuid::new_v4();
+    let full_name = draft
+        .full_name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Test User {}", &id.simple().to_string()[..8]));
+    let mut tx = db_pool.begin().await?;
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (id, full_name, email, role, skills, experience_level, profile_completed, sandbox_key_id)
+        VALUES ($1, $2, $3, $4, $5, $6, TRUE, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&full_name)
+    .bind(format!("sandbox-{}@{}", id.simple(), SANDBOX_EMAIL_DOMAIN))
+    .bind(draft.role)
+    .bind(draft.skills)
+    .bind(draft.experience_level)
+    .bind(key_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO policy_acceptances (user_id, policy_id, kind, version)
+        SELECT DISTINCT ON (kind) $1, id, kind, version
+        FROM policy_documents
+        ORDER BY kind, version DESC
+        "#,
+    )
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    info!("Sandbox {} created test user {}", key_id, user.id);
+    Ok(user)
+}
+
+/// Creates an approved test job in the key's sandbox.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the poster is not a test employer
+/// of the sandbox, `AppError::BadRequest` if the sandbox is full, or an
+/// error if the database operation fails.
+pub async fn create_job(db_pool: &PgPool, key_id: i32, draft: SandboxJobDraft<'_>) -> AppResult<Job> {
+    if let Some(posted_by) = draft.posted_by {
+        let employer = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND sandbox_key_id = $2 AND role = 'employer')",
+        )
+        .bind(posted_by)
+        .bind(key_id)
+        .fetch_one(db_pool)
+        .await?;
+        if !employer {
+            return Err(AppError::ValidationError(
+                "posted_by must be a test employer of this sandbox".to_string(),
+            ));
+        }
+    }
+    let jobs = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM jobs WHERE sandbox_key_id = $1")
+        .bind(key_id)
+        .fetch_one(db_pool)
+        .await?;
+    if jobs >= MAX_JOBS_PER_SANDBOX {
+        return Err(AppError::BadRequest(format!(
+            "A sandbox holds at most {} jobs; purge it first",
+            MAX_JOBS_PER_SANDBOX
+        )));
+    }
+
+    let job = sqlx::query_as::<_, Job>(&format!(
+        r#"
+        INSERT INTO jobs (
+            job_title, company, location, job_description, required_skills,
+            experience_level, job_type, salary_min, salary_max, posted_by, sandbox_key_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING {}
+        "#,
+        JOB_COLUMNS
+    ))
+    .bind(draft.job_title.trim())
+    .bind(draft.company.trim())
+    .bind(draft.location.trim())
+    .bind(draft.job_description)
+    .bind(draft.required_skills)
+    .bind(draft.experience_level)
+    .bind(draft.job_type)
+    .bind(draft.salary_min)
+    .bind(draft.salary_max)
+    .bind(draft.posted_by)
+    .bind(key_id)
+    .fetch_one(db_pool)
+    .await?;
+    info!("Sandbox {} created test job {}", key_id, job.id);
+    Ok(job)
+}
+
+/// Records an application of a test user to a job of the same sandbox, in
+/// any status - to set up, say, a rejected application.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the user or job is not in the
+/// sandbox, `AppError::BadRequest` if the user already applied, or an error
+/// if the database operation fails.
+pub async fn create_application(
+    db_pool: &PgPool,
+    key_id: i32,
+    user_id: Uuid,
+    job_id: i32,
+    status: &str,
+    notes: Option<&str>,
+) -> AppResult<ApplicationTracking> {
+    let in_sandbox = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND sandbox_key_id = $3)
+           AND EXISTS (SELECT 1 FROM jobs WHERE id = $2 AND sandbox_key_id = $3)
+        "#,
+    )
+    .bind(user_id)
+    .bind(job_id)
+    .bind(key_id)
+    .fetch_one(db_pool)
+    .await?;
+    if !in_sandbox {
+        return Err(AppError::ValidationError(
+            "user_id and job_id must be a test user and job of this sandbox".to_string(),
+        ));
+    }
+
+    let application = sqlx::query_as::<_, ApplicationTracking>(
+        r#"
+        INSERT INTO application_tracking (user_id, job_id, status, notes)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, user_id, job_id, status, applied_at, notes
+        "#,
+    )
+    .bind(user_id)
+    .bind(job_id)
+    .bind(status.trim())
+    .bind(notes)
+    .fetch_one(db_pool)
+    .await;
+    match application {
+        Ok(application) => Ok(application),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(AppError::BadRequest(
+            "The test user already applied to this job".to_string(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// What the key's sandbox holds.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn summary(db_pool: &PgPool, key_id: i32) -> AppResult<SandboxSummary> {
+    let summary = sqlx::query_as::<_, SandboxSummary>(
+        r#"
+        SELECT (SELECT COUNT(*) FROM users WHERE sandbox_key_id = $1) AS users,
+               (SELECT COUNT(*) FROM jobs WHERE sandbox_key_id = $1) AS jobs,
+               (SELECT COUNT(*) FROM application_tracking a
+                JOIN users u ON u.id = a.user_id
+                WHERE u.sandbox_key_id = $1) AS applications
+        "#,
+    )
+    .bind(key_id)
+    .fetch_one(db_pool)
+    .await?;
+    Ok(summary)
+}
+
+/// Deletes the test users and jobs of one sandbox, or of all sandboxes;
+/// their applications and everything else they own go with them. Returns
+/// what was deleted.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn purge(db_pool: &PgPool, key_id: Option<i32>) -> AppResult<SandboxSummary> {
+    let mut tx = db_pool.begin().await?;
+    let applications = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM application_tracking a
+        JOIN users u ON u.id = a.user_id
+        WHERE u.sandbox_key_id = $1 OR ($1 IS NULL AND u.sandbox_key_id IS NOT NULL)
+        "#,
+    )
+    .bind(key_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let jobs = sqlx::query(
+        "DELETE FROM jobs WHERE sandbox_key_id = $1 OR ($1 IS NULL AND sandbox_key_id IS NOT NULL)",
+    )
+    .bind(key_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    let users = sqlx::query(
+        "DELETE FROM users WHERE sandbox_key_id = $1 OR ($1 IS NULL AND sandbox_key_id IS NOT NULL)",
+    )
+    .bind(key_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    tx.commit().await?;
+
+    let purged = SandboxSummary {
+        users: users as i64,
+        jobs: jobs as i64,
+        applications,
+    };
+    if purged.users > 0 || purged.jobs > 0 {
+        info!(
+            "Purged sandbox data ({}): {} users, {} jobs, {} applications",
+            key_id.map_or_else(|| "all sandboxes".to_string(), |id| format!("key {}", id)),
+            purged.users,
+            purged.jobs,
+            purged.applications
+        );
+    }
+    Ok(purged)
+}
@@ -0,0 +1,601 @@
+//! Background maintenance tasks.
+//!
+//! The scheduler runs periodic jobs on the Tokio runtime alongside the HTTP
+//! server. Each task runs on its own interval and logs (rather than panics
+//! on) failures so a bad run never takes the server down.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use crate::activity;
+use crate::ai::AIService;
+use crate::application_packages;
+use crate::application_timing;
+use crate::cohort_roadmaps;
+use crate::certifications::{self, CertificateVerifier};
+use crate::code_practice;
+use crate::debug_captures;
+use crate::prompt_rollouts;
+use crate::geo::{self, Geocoder};
+use crate::encryption::DocumentCipher;
+use crate::integrations::calendar::CalendarClient;
+use crate::integrations::leetcode::LeetCodeClient;
+use crate::meetings::MeetingProvider;
+use crate::mentor_calendars;
+use crate::mentor_payments;
+use crate::mentorship;
+use crate::knowledge_base;
+use crate::links::{self, LinkChecker};
+use crate::partitioning;
+use crate::readiness;
+use crate::sandbox;
+use crate::talent_pools;
+use crate::impersonation;
+use crate::interview_prep;
+
+/// Default interval between job application counter reconciliations.
+const DEFAULT_STATS_RECONCILE_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between log partition maintenance runs.
+const DEFAULT_PARTITION_MAINTENANCE_INTERVAL_SECS: u64 = 86_400;
+
+/// How many months of partitions to create ahead of the current month.
+const PARTITION_MONTHS_AHEAD: u32 = 2;
+
+/// Default interval between runs that close expired job postings.
+const DEFAULT_JOB_EXPIRY_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between job geocoding backfill runs.
+const DEFAULT_GEOCODE_BACKFILL_INTERVAL_SECS: u64 = 600;
+
+/// Default interval between resource link validation runs.
+const DEFAULT_LINK_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between runs that index pending knowledge base articles.
+const DEFAULT_KB_INDEX_INTERVAL_SECS: u64 = 300;
+
+/// Default interval between runs that work through queued application packages.
+const DEFAULT_APPLICATION_PACKAGE_INTERVAL_SECS: u64 = 60;
+
+/// Default interval between runs that work through queued cohort roadmap batches.
+const DEFAULT_COHORT_ROADMAP_INTERVAL_SECS: u64 = 60;
+
+/// Default interval between certificate verification runs.
+const DEFAULT_CERTIFICATE_VERIFY_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between coding practice sync runs. Each run only syncs
+/// handles not synced in the past day.
+const DEFAULT_CODE_PRACTICE_SYNC_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between weekly activity digest runs. Each run only sends
+/// digests to users who have not had one in the past week.
+const DEFAULT_WEEKLY_DIGEST_INTERVAL_SECS: u64 = 21600;
+
+/// Default interval between role readiness recalculation runs. Each run only
+/// recalculates scores older than six hours.
+const DEFAULT_READINESS_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between runs that send job application timing nudges.
+const DEFAULT_APPLICATION_NUDGE_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between runs that deliver interview prep packs.
+const DEFAULT_INTERVIEW_PREP_INTERVAL_SECS: u64 = 900;
+
+/// Default interval between mentor calendar sync runs.
+const DEFAULT_MENTOR_CALENDAR_SYNC_INTERVAL_SECS: u64 = 300;
+
+/// Default interval between mentor session reminder runs.
+const DEFAULT_MENTOR_REMINDER_INTERVAL_SECS: u64 = 300;
+
+/// Default interval between mentor payment settlement runs.
+const DEFAULT_MENTOR_PAYMENT_SETTLE_INTERVAL_SECS: u64 = 900;
+
+/// Default interval between sandbox purges (nightly).
+const DEFAULT_SANDBOX_PURGE_INTERVAL_SECS: u64 = 86_400;
+
+/// Default interval between purges of expired debug captures.
+const DEFAULT_DEBUG_CAPTURE_PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between prompt rollout checks.
+const DEFAULT_PROMPT_ROLLOUT_CHECK_INTERVAL_SECS: u64 = 300;
+
+/// Default interval between talent pool alert checks.
+const DEFAULT_TALENT_POOL_ALERT_INTERVAL_SECS: u64 = 3600;
+
+/// Default interval between notifying users of finished impersonation sessions.
+const DEFAULT_IMPERSONATION_NOTICE_INTERVAL_SECS: u64 = 300;
+
+/// Default number of months of log partitions kept in the database.
+const DEFAULT_ARCHIVE_RETENTION_MONTHS: u32 = 6;
+
+/// Reads an interval in seconds from the environment, falling back to `default`.
+fn interval_from_env(var: &str, default: u64) -> Duration {
+    let secs = std::env::var(var)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(default);
+    Duration::from_secs(secs)
+}
+
+/// Spawns all background maintenance tasks.
+///
+/// Intervals are configurable via environment variables:
+/// - `STATS_RECONCILE_INTERVAL_SECS` - job application counter reconciliation (default: 3600)
+/// - `PARTITION_MAINTENANCE_INTERVAL_SECS` - log partition creation and archival (default: 86400)
+/// - `JOB_EXPIRY_INTERVAL_SECS` - closing expired job postings (default: 3600)
+/// - `GEOCODE_BACKFILL_INTERVAL_SECS` - job location geocoding, when a geocoder is configured (default: 600)
+/// - `LINK_CHECK_INTERVAL_SECS` - resource link validation, with AI replacements when a provider is configured (default: 3600)
+/// - `KB_INDEX_INTERVAL_SECS` - embedding knowledge base articles still pending indexing, when embeddings are available (default: 300)
+/// - `APPLICATION_PACKAGE_INTERVAL_SECS` - running queued and stalled application packages, when an AI provider is configured (default: 60)
+/// - `COHORT_ROADMAP_INTERVAL_SECS` - running queued and stalled bulk cohort roadmap batches, when an AI provider is configured (default: 60)
+/// - `CERTIFICATE_VERIFY_INTERVAL_SECS` - verifying pending certifications and re-checking verified ones with their issuers (default: 3600)
+/// - `CODE_PRACTICE_SYNC_INTERVAL_SECS` - importing solved problems for linked coding practice handles (default: 3600)
+/// - `WEEKLY_DIGEST_INTERVAL_SECS` - sending weekly activity digest notifications (default: 21600)
+/// - `READINESS_INTERVAL_SECS` - recalculating job seekers' readiness for their target roles (default: 3600)
+/// - `APPLICATION_NUDGE_INTERVAL_SECS` - notifying users whose roadmap's role reached the readiness nudge score (default: 3600)
+/// - `INTERVIEW_PREP_INTERVAL_SECS` - preparing and delivering prep packs for interviews in the next 48 hours, when an AI provider is configured (default: 900)
+/// - `MENTOR_CALENDAR_SYNC_INTERVAL_SECS` - syncing mentors' calendar busy times into their session slots, when `DOCUMENT_ENCRYPTION_KEY` is set (default: 300)
+/// - `MENTOR_REMINDER_INTERVAL_SECS` - reminding both sides of mentor sessions starting within the hour, with the meeting link (default: 300)
+/// - `MENTOR_PAYMENT_SETTLE_INTERVAL_SECS` - releasing held payments of completed mentor sessions and expiring abandoned checkouts (default: 900)
+/// - `SANDBOX_PURGE_INTERVAL_SECS` - deleting all sandbox test users, jobs, and applications (default: 86400)
+/// - `DEBUG_CAPTURE_PURGE_INTERVAL_SECS` - deleting unflagged AI debug captures past `DEBUG_CAPTURE_RETENTION_HOURS` (default: 3600)
+/// - `PROMPT_ROLLOUT_CHECK_INTERVAL_SECS` - rolling back prompt rollouts over their thresholds and reloading routes (default: 300)
+/// - `TALENT_POOL_ALERT_INTERVAL_SECS` - alerting recruiters when pooled candidates gain a pool's skills or verified certifications (default: 3600)
+/// - `IMPERSONATION_NOTICE_INTERVAL_SECS` - notifying users after support finished impersonating them (default: 300)
+///
+/// Partition archival also reads `ARCHIVE_DIR` (default: `./archive`) and
+/// `ARCHIVE_RETENTION_MONTHS` (default: 6).
+pub fn spawn(db_pool: PgPool, geocoder: Option<Arc<Geocoder>>, ai_service: Option<Arc<AIService>>) {
+    let period = interval_from_env(
+        "STATS_RECONCILE_INTERVAL_SECS",
+        DEFAULT_STATS_RECONCILE_INTERVAL_SECS,
+    );
+    info!("Scheduling job stats reconciliation every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = reconcile_job_application_stats(&pool).await {
+                error!("Job stats reconciliation failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "PARTITION_MAINTENANCE_INTERVAL_SECS",
+        DEFAULT_PARTITION_MAINTENANCE_INTERVAL_SECS,
+    );
+    info!("Scheduling log partition maintenance every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            maintain_partitions(&pool).await;
+        }
+    });
+
+    let period = interval_from_env("JOB_EXPIRY_INTERVAL_SECS", DEFAULT_JOB_EXPIRY_INTERVAL_SECS);
+    info!("Scheduling job expiry every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = close_expired_jobs(&pool).await {
+                error!("Closing expired jobs failed: {}", e);
+            }
+        }
+    });
+
+    if let Some(geocoder) = geocoder {
+        let period = interval_from_env(
+            "GEOCODE_BACKFILL_INTERVAL_SECS",
+            DEFAULT_GEOCODE_BACKFILL_INTERVAL_SECS,
+        );
+        info!("Scheduling job geocoding backfill every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = geo::backfill_job_coordinates(&pool, &geocoder).await {
+                    error!("Job geocoding backfill failed: {}", e);
+                }
+            }
+        });
+    }
+
+    let period = interval_from_env(
+        "CERTIFICATE_VERIFY_INTERVAL_SECS",
+        DEFAULT_CERTIFICATE_VERIFY_INTERVAL_SECS,
+    );
+    info!("Scheduling certificate verification every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let verifier = CertificateVerifier::new();
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = certifications::verify_pending(&pool, &verifier).await {
+                error!("Certificate verification failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "CODE_PRACTICE_SYNC_INTERVAL_SECS",
+        DEFAULT_CODE_PRACTICE_SYNC_INTERVAL_SECS,
+    );
+    info!("Scheduling coding practice sync every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let client = LeetCodeClient::new();
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = code_practice::sync_due(&pool, &client).await {
+                error!("Coding practice sync failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env("WEEKLY_DIGEST_INTERVAL_SECS", DEFAULT_WEEKLY_DIGEST_INTERVAL_SECS);
+    info!("Scheduling weekly activity digests every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = activity::send_weekly_digests(&pool).await {
+                error!("Weekly activity digests failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env("READINESS_INTERVAL_SECS", DEFAULT_READINESS_INTERVAL_SECS);
+    info!("Scheduling role readiness recalculation every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = readiness::recalculate_due(&pool).await {
+                error!("Role readiness recalculation failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "APPLICATION_NUDGE_INTERVAL_SECS",
+        DEFAULT_APPLICATION_NUDGE_INTERVAL_SECS,
+    );
+    info!("Scheduling job application timing nudges every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = application_timing::send_nudges(&pool).await {
+                error!("Job application timing nudges failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "TALENT_POOL_ALERT_INTERVAL_SECS",
+        DEFAULT_TALENT_POOL_ALERT_INTERVAL_SECS,
+    );
+    info!("Scheduling talent pool alert checks every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = talent_pools::check_alerts(&pool).await {
+                error!("Talent pool alert check failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "IMPERSONATION_NOTICE_INTERVAL_SECS",
+        DEFAULT_IMPERSONATION_NOTICE_INTERVAL_SECS,
+    );
+    info!("Scheduling impersonation notices every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = impersonation::notify_finished(&pool).await {
+                error!("Impersonation notices failed: {}", e);
+            }
+        }
+    });
+
+    let link_period = interval_from_env("LINK_CHECK_INTERVAL_SECS", DEFAULT_LINK_CHECK_INTERVAL_SECS);
+    info!("Scheduling resource link validation every {:?}", link_period);
+
+    if let Some(ai) = ai_service.clone().filter(|ai| ai.embeddings_available()) {
+        let period = interval_from_env("KB_INDEX_INTERVAL_SECS", DEFAULT_KB_INDEX_INTERVAL_SECS);
+        info!("Scheduling knowledge base indexing every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = knowledge_base::index_pending(&pool, &ai).await {
+                    error!("Knowledge base indexing failed: {}", e);
+                }
+            }
+        });
+    }
+
+    if let Some(ai) = ai_service.clone() {
+        let period = interval_from_env(
+            "APPLICATION_PACKAGE_INTERVAL_SECS",
+            DEFAULT_APPLICATION_PACKAGE_INTERVAL_SECS,
+        );
+        info!("Scheduling application package runs every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = application_packages::run_pending(&pool, &ai).await {
+                    error!("Application package run failed: {}", e);
+                }
+            }
+        });
+    }
+
+    if let Some(ai) = ai_service.clone() {
+        let period = interval_from_env(
+            "COHORT_ROADMAP_INTERVAL_SECS",
+            DEFAULT_COHORT_ROADMAP_INTERVAL_SECS,
+        );
+        info!("Scheduling cohort roadmap batch runs every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = cohort_roadmaps::run_pending(&pool, &ai).await {
+                    error!("Cohort roadmap batch run failed: {}", e);
+                }
+            }
+        });
+    }
+
+    if let Some(ai) = ai_service.clone() {
+        let period = interval_from_env("INTERVIEW_PREP_INTERVAL_SECS", DEFAULT_INTERVIEW_PREP_INTERVAL_SECS);
+        info!("Scheduling interview prep pack delivery every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = interview_prep::deliver_due(&pool, &ai).await {
+                    error!("Interview prep pack delivery failed: {}", e);
+                }
+            }
+        });
+    }
+
+    if let Some(cipher) = DocumentCipher::from_env() {
+        let period = interval_from_env(
+            "MENTOR_CALENDAR_SYNC_INTERVAL_SECS",
+            DEFAULT_MENTOR_CALENDAR_SYNC_INTERVAL_SECS,
+        );
+        info!("Scheduling mentor calendar sync every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let client = CalendarClient::from_env();
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = mentor_calendars::sync_due(&pool, &client, &cipher).await {
+                    error!("Mentor calendar sync failed: {}", e);
+                }
+            }
+        });
+    }
+
+    let period = interval_from_env("MENTOR_REMINDER_INTERVAL_SECS", DEFAULT_MENTOR_REMINDER_INTERVAL_SECS);
+    info!("Scheduling mentor session reminders every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let meetings = MeetingProvider::from_env();
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = mentorship::send_reminders(&pool, &meetings).await {
+                error!("Mentor session reminders failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "MENTOR_PAYMENT_SETTLE_INTERVAL_SECS",
+        DEFAULT_MENTOR_PAYMENT_SETTLE_INTERVAL_SECS,
+    );
+    info!("Scheduling mentor payment settlement every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = mentor_payments::settle_due(&pool).await {
+                error!("Mentor payment settlement failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env("SANDBOX_PURGE_INTERVAL_SECS", DEFAULT_SANDBOX_PURGE_INTERVAL_SECS);
+    info!("Scheduling sandbox purges every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        // Wait a full period first, so a restart does not wipe sandboxes in use
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sandbox::purge(&pool, None).await {
+                error!("Sandbox purge failed: {}", e);
+            }
+        }
+    });
+
+    let period = interval_from_env(
+        "DEBUG_CAPTURE_PURGE_INTERVAL_SECS",
+        DEFAULT_DEBUG_CAPTURE_PURGE_INTERVAL_SECS,
+    );
+    info!("Scheduling debug capture purges every {:?}", period);
+
+    let pool = db_pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = debug_captures::purge_expired(&pool).await {
+                error!("Debug capture purge failed: {}", e);
+            }
+        }
+    });
+
+    if let Some(ai) = ai_service.clone().filter(|ai| ai.prompt_router().is_some()) {
+        let period = interval_from_env(
+            "PROMPT_ROLLOUT_CHECK_INTERVAL_SECS",
+            DEFAULT_PROMPT_ROLLOUT_CHECK_INTERVAL_SECS,
+        );
+        info!("Scheduling prompt rollout checks every {:?}", period);
+
+        let pool = db_pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = prompt_rollouts::check(&pool).await {
+                    error!("Prompt rollout check failed: {}", e);
+                }
+                // Picks up changes made on other instances, and the rollbacks above
+                if let Some(router) = ai.prompt_router()
+                    && let Err(e) = router.refresh().await
+                {
+                    error!("Failed to reload prompt rollouts: {}", e);
+                }
+            }
+        });
+    }
+
+    let pool = db_pool;
+    tokio::spawn(async move {
+        let checker = LinkChecker::new();
+        let mut interval = tokio::time::interval(link_period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = links::validate_links(&pool, ai_service.as_deref(), &checker).await {
+                error!("Resource link validation failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Creates upcoming monthly log partitions and archives cold ones.
+pub async fn maintain_partitions(db_pool: &PgPool) {
+    if let Err(e) = partitioning::ensure_partitions(db_pool, PARTITION_MONTHS_AHEAD).await {
+        error!("Partition creation failed: {}", e);
+    }
+
+    let archive_dir = std::env::var("ARCHIVE_DIR").unwrap_or_else(|_| "./archive".to_string());
+    let retain_months = std::env::var("ARCHIVE_RETENTION_MONTHS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_ARCHIVE_RETENTION_MONTHS);
+
+    match partitioning::archive_cold_partitions(db_pool, Path::new(&archive_dir), retain_months).await {
+        Ok(archived) if !archived.is_empty() => {
+            info!("Archived {} cold log partitions to {}", archived.len(), archive_dir);
+        }
+        Ok(_) => {}
+        Err(e) => error!("Partition archival failed: {}", e),
+    }
+}
+
+/// Recomputes the employer dashboard counters from `application_tracking`.
+///
+/// The counters are normally kept current by triggers; this corrects any
+/// drift (e.g. from manual data fixes or bulk imports).
+pub async fn reconcile_job_application_stats(db_pool: &PgPool) -> Result<i32, sqlx::Error> {
+    let corrected = sqlx::query_scalar::<_, i32>("SELECT reconcile_job_application_stats()")
+        .fetch_one(db_pool)
+        .await?;
+
+    if corrected > 0 {
+        info!("Reconciled job application stats: {} jobs corrected", corrected);
+    }
+
+    Ok(corrected)
+}
+
+/// Closes job postings past their `expires_at` and asks employers to renew.
+///
+/// Expired jobs are already hidden from search and matching; closing them
+/// records when that happened and sends the renewal notification exactly
+/// once. Returns the number of postings closed.
+pub async fn close_expired_jobs(db_pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let closed = sqlx::query_scalar::<_, i64>(
+        r#"
+        WITH closed AS (
+            UPDATE jobs
+            SET closed_at = CURRENT_TIMESTAMP
+            WHERE closed_at IS NULL AND expires_at <= CURRENT_TIMESTAMP
+            RETURNING id, job_title, posted_by
+        ),
+        notified AS (
+            INSERT INTO notifications (user_id, title, message, type)
+            SELECT posted_by,
+                   'Job posting expired',
+                   format('Your job posting "%s" has expired and is no longer shown to job seekers. '
+                          'Renew it from your dashboard to reopen it.', job_title),
+                   'job_expired'
+            FROM closed
+            WHERE posted_by IS NOT NULL
+        )
+        SELECT COUNT(*) FROM closed
+        "#,
+    )
+    .fetch_one(db_pool)
+    .await?;
+
+    if closed > 0 {
+        info!("Closed {} expired job postings", closed);
+    }
+
+    Ok(closed)
+}
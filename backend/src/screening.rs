@@ -0,0 +1,756 @@
+//! Screening questions on job postings.
+//!
+//! Employers attach up to [`MAX_QUESTIONS`] questions to a posting
+//! ([`replace`]), written by hand or suggested from the job description by
+//! [`generate`]. Candidates answer them when they apply:
+//!
+//! - `yes_no` answers are checked against the expected "yes" or "no"
+//! - `number` answers are checked against the minimum expected
+//! - `text` answers are scored 0-100 by the AI provider in the background
+//!   ([`spawn_evaluation`]), against what a strong answer covers
+//!
+//! A wrong answer to a knockout question flags the application, as does a
+//! knockout text answer scoring below [`KNOCKOUT_TEXT_SCORE`] or one the
+//! provider flags. The application's screening score is the average of its
+//! answer scores, shown in the employer's applicant list ([`applicants`]).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+use crate::models::ScreeningQuestionType;
+
+/// Questions a posting may have.
+pub const MAX_QUESTIONS: usize = 10;
+
+/// Score below which a knockout text answer flags the application.
+pub const KNOCKOUT_TEXT_SCORE: i16 = 40;
+
+/// Content type questions are generated with.
+const QUESTIONS_CONTENT_TYPE: &str = "screening_questions";
+
+/// Content type text answers are scored with.
+const EVALUATION_CONTENT_TYPE: &str = "screening_evaluation";
+
+/// A question as written by the employer or suggested by [`generate`].
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct QuestionInput {
+    #[validate(length(min = 1, max = 500, message = "question must be between 1 and 500 characters"))]
+    pub question: String,
+    pub question_type: ScreeningQuestionType,
+    /// `yes_no`: "yes" or "no"; `number`: the minimum accepted; `text`:
+    /// what a strong answer covers
+    #[validate(length(max = 1000))]
+    pub expected_answer: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// Whether a wrong answer flags the application
+    #[serde(default)]
+    pub knockout: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A question on a posting, as the employer sees it.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScreeningQuestion {
+    pub id: i32,
+    pub job_id: i32,
+    pub position: i32,
+    pub question: String,
+    pub question_type: ScreeningQuestionType,
+    pub expected_answer: Option<String>,
+    pub required: bool,
+    pub knockout: bool,
+}
+
+/// A question as candidates see it, without the expected answer.
+#[derive(Debug, Serialize)]
+pub struct CandidateQuestion {
+    pub id: i32,
+    pub question: String,
+    pub question_type: ScreeningQuestionType,
+    pub required: bool,
+}
+
+impl From<ScreeningQuestion> for CandidateQuestion {
+    fn from(q: ScreeningQuestion) -> Self {
+        CandidateQuestion {
+            id: q.id,
+            question: q.question,
+            question_type: q.question_type,
+            required: q.required,
+        }
+    }
+}
+
+/// A candidate's answer to one question.
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnswerInput {
+    pub question_id: i32,
+    #[validate(length(max = 5000, message = "answers must be at most 5000 characters"))]
+    pub answer: String,
+}
+
+/// An answer and its evaluation.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScreeningAnswer {
+    /// `None` if the question has since been removed
+    pub question_id: Option<i32>,
+    /// The question as it was when answered
+    pub question: String,
+    pub question_type: ScreeningQuestionType,
+    pub knockout: bool,
+    pub answer: String,
+    /// 0-100; `None` until evaluated, or when there was nothing to score against
+    pub score: Option<i16>,
+    pub flagged: bool,
+    pub reason: Option<String>,
+    pub evaluated_at: Option<DateTime<Utc>>,
+}
+
+/// An application in the employer's applicant list.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Applicant {
+    pub application_id: i32,
+    pub user_id: Uuid,
+    pub full_name: String,
+    pub email: String,
+    pub status: String,
+    pub applied_at: Option<DateTime<Utc>>,
+    /// Average answer score, `None` if no answer has been scored
+    pub screening_score: Option<i16>,
+    /// Whether any answer was flagged
+    pub screening_flagged: bool,
+    /// Whether text answers are still being scored
+    pub screening_pending: bool,
+    #[sqlx(skip)]
+    pub screening_answers: Vec<ScreeningAnswer>,
+}
+
+/// The outcome of checking one answer.
+struct Evaluation {
+    score: Option<i16>,
+    flagged: bool,
+    reason: Option<String>,
+}
+
+fn parse_yes_no(answer: &str) -> Option<bool> {
+    match answer.trim().to_lowercase().as_str() {
+        "yes" | "y" | "true" => Some(true),
+        "no" | "n" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_number(answer: &str) -> Option<f64> {
+    answer.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Checks a yes/no or number answer against the expected answer; text
+/// answers return `None` and are left to the AI provider.
+fn check_answer(question: &ScreeningQuestion, answer: &str) -> Option<Evaluation> {
+    let expected = question.expected_answer.as_deref();
+    let (passed, expectation) = match question.question_type {
+        ScreeningQuestionType::Text => return None,
+        ScreeningQuestionType::YesNo => {
+            let expected = expected.and_then(parse_yes_no);
+            (
+                expected.map(|e| parse_yes_no(answer) == Some(e)),
+                format!("Expected \"{}\"", if expected == Some(true) { "yes" } else { "no" }),
+            )
+        }
+        ScreeningQuestionType::Number => {
+            let minimum = expected.and_then(parse_number);
+            (
+                minimum.map(|m| parse_number(answer).is_some_and(|n| n >= m)),
+                format!("Below the minimum of {}", minimum.unwrap_or_default()),
+            )
+        }
+    };
+    Some(match passed {
+        None => Evaluation { score: None, flagged: false, reason: None },
+        Some(true) => Evaluation { score: Some(100), flagged: false, reason: None },
+        Some(false) => Evaluation {
+            score: Some(0),
+            flagged: question.knockout,
+            reason: Some(expectation),
+        },
+    })
+}
+
+/// Validates and normalizes questions before they are saved.
+fn validate_questions(questions: &mut [QuestionInput]) -> AppResult<()> {
+    if questions.len() > MAX_QUESTIONS {
+        return Err(AppError::ValidationError(format!(
+            "A posting can have at most {} screening questions",
+            MAX_QUESTIONS
+        )));
+    }
+    for q in questions.iter_mut() {
+        q.validate()?;
+        q.question = q.question.trim().to_string();
+        q.expected_answer = q
+            .expected_answer
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(str::to_string);
+        if q.question.is_empty() {
+            return Err(AppError::ValidationError("Screening questions cannot be empty".to_string()));
+        }
+        match (q.question_type, q.expected_answer.as_deref()) {
+            (ScreeningQuestionType::YesNo, Some(e)) => match parse_yes_no(e) {
+                Some(yes) => q.expected_answer = Some(if yes { "yes" } else { "no" }.to_string()),
+                None => {
+                    return Err(AppError::ValidationError(format!(
+                        "\"{}\": the expected answer must be \"yes\" or \"no\"",
+                        q.question
+                    )));
+                }
+            },
+            (ScreeningQuestionType::Number, Some(e)) if parse_number(e).is_none() => {
+                return Err(AppError::ValidationError(format!(
+                    "\"{}\": the expected answer must be the minimum number accepted",
+                    q.question
+                )));
+            }
+            (ScreeningQuestionType::YesNo | ScreeningQuestionType::Number, None) if q.knockout => {
+                return Err(AppError::ValidationError(format!(
+                    "\"{}\": knockout questions need an expected answer",
+                    q.question
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Ensures the user posted the job, holds a seat at its company
+/// (`company_id`), or is an admin.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the job does not exist or the user may
+/// not manage it, or an error if the database query fails.
+pub async fn ensure_job_access(
+    db_pool: &PgPool,
+    job_id: i32,
+    user_id: Uuid,
+    is_admin: bool,
+    company_id: Option<i32>,
+) -> AppResult<()> {
+    sqlx::query_scalar::<_, i32>("SELECT id FROM jobs WHERE id = $1 AND ($2 OR posted_by = $3 OR company_id = $4)")
+        .bind(job_id)
+        .bind(is_admin)
+        .bind(user_id)
+        .bind(company_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(())
+}
+
+/// A posting's questions in order.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, job_id: i32) -> AppResult<Vec<ScreeningQuestion>> {
+    let questions = sqlx::query_as::<_, ScreeningQuestion>(
+        r#"
+        SELECT id, job_id, position, question, question_type, expected_answer, required, knockout
+        FROM screening_questions
+        WHERE job_id = $1
+        ORDER BY position
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(questions)
+}
+
+/// The questions of a posting open for applications, without expected
+/// answers.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the job is not approved or has expired,
+/// or an error if the database operation fails.
+pub async fn for_candidates(db_pool: &PgPool, job_id: i32) -> AppResult<Vec<CandidateQuestion>> {
+    sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT id FROM jobs
+        WHERE id = $1 AND moderation_status = 'approved' AND sandbox_key_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+        "#,
+    )
+    .bind(job_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(list(db_pool, job_id).await?.into_iter().map(CandidateQuestion::from).collect())
+}
+
+/// Replaces a posting's questions. Answers already given keep their copy of
+/// the question they answered.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` for too many or invalid questions,
+/// or an error if the database operation fails.
+pub async fn replace(
+    db_pool: &PgPool,
+    job_id: i32,
+    mut questions: Vec<QuestionInput>,
+) -> AppResult<Vec<ScreeningQuestion>> {
+    validate_questions(&mut questions)?;
+
+    let mut tx = db_pool.begin().await?;
+    sqlx::query("DELETE FROM screening_questions WHERE job_id = $1")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+    for (position, q) in questions.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO screening_questions
+                (job_id, position, question, question_type, expected_answer, required, knockout)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(job_id)
+        .bind(position as i32 + 1)
+        .bind(&q.question)
+        .bind(q.question_type)
+        .bind(&q.expected_answer)
+        .bind(q.required)
+        .bind(q.knockout)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    list(db_pool, job_id).await
+}
+
+/// Suggests questions from the posting's description and requirements.
+/// Nothing is saved; the employer edits the suggestions and saves them
+/// with [`replace`].
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the job does not exist,
+/// `AppError::ExternalServiceError` if the provider fails or suggests no
+/// usable questions, or an error if a database query fails.
+pub async fn generate(
+    db_pool: &PgPool,
+    ai: &AIService,
+    user_id: Uuid,
+    job_id: i32,
+    count: usize,
+    provider: AIProvider,
+) -> AppResult<Vec<QuestionInput>> {
+    let (job_title, job_description, requirements) = sqlx::query_as::<_, (String, String, Vec<String>)>(
+        "SELECT job_title, job_description, requirements FROM jobs WHERE id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let posting = format!(
+        "Title: {}\n\n{}\n\nRequirements:\n{}",
+        job_title,
+        job_description,
+        requirements.iter().map(|r| format!("- {}", r)).collect::<Vec<_>>().join("\n")
+    );
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateContent,
+            provider,
+            input: posting,
+            parameters: Some(json!({
+                "content_type": QUESTIONS_CONTENT_TYPE,
+                "count": count,
+                "instructions": format!(
+                    "Suggest {} screening questions for applicants to this job, checking its must-have requirements. \
+                     Put a one-line summary in `content`. In `metadata.questions`, give {{\"question\", \"question_type\", \
+                     \"expected_answer\", \"knockout\"}} objects. `question_type` is \"yes_no\" (expected_answer \"yes\" or \
+                     \"no\"), \"number\" (expected_answer the minimum accepted, e.g. years of experience), or \"text\" \
+                     (expected_answer: what a strong answer covers). Set `knockout` only for hard requirements. Do not ask \
+                     about age, gender, marital status, religion, health, or nationality.",
+                    count
+                ),
+            })),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+
+    // Suggestions that would not pass validation are dropped
+    let questions: Vec<QuestionInput> = response
+        .data
+        .pointer("/metadata/questions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|q| {
+            let mut one = [serde_json::from_value::<QuestionInput>(q.clone()).ok()?];
+            validate_questions(&mut one).ok()?;
+            let [q] = one;
+            Some(q)
+        })
+        .take(count)
+        .collect();
+    if questions.is_empty() {
+        return Err(AppError::ExternalServiceError(
+            "AI provider suggested no usable screening questions".to_string(),
+        ));
+    }
+    Ok(questions)
+}
+
+/// Checks a candidate's answers before the application is created: every
+/// required question answered, answers only to the posting's questions,
+/// and yes/no and number answers in the right form.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` describing the first problem found.
+pub fn check_answers(questions: &[ScreeningQuestion], answers: &[AnswerInput]) -> AppResult<()> {
+    for answer in answers {
+        answer.validate()?;
+        let Some(question) = questions.iter().find(|q| q.id == answer.question_id) else {
+            return Err(AppError::ValidationError(format!(
+                "Question {} is not a screening question for this job",
+                answer.question_id
+            )));
+        };
+        let text = answer.answer.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let valid = match question.question_type {
+            ScreeningQuestionType::YesNo => parse_yes_no(text).is_some(),
+            ScreeningQuestionType::Number => parse_number(text).is_some(),
+            ScreeningQuestionType::Text => true,
+        };
+        if !valid {
+            let form = match question.question_type {
+                ScreeningQuestionType::YesNo => "\"yes\" or \"no\"",
+                _ => "a number",
+            };
+            return Err(AppError::ValidationError(format!("\"{}\" must be answered with {}", question.question, form)));
+        }
+    }
+    for question in questions.iter().filter(|q| q.required) {
+        let answered = answers
+            .iter()
+            .any(|a| a.question_id == question.id && !a.answer.trim().is_empty());
+        if !answered {
+            return Err(AppError::ValidationError(format!(
+                "Screening question \"{}\" is required",
+                question.question
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Stores a new application's answers, scoring yes/no and number answers
+/// right away. Returns whether text answers are left for
+/// [`spawn_evaluation`].
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn record_answers(
+    tx: &mut Transaction<'_, Postgres>,
+    application_id: i32,
+    questions: &[ScreeningQuestion],
+    answers: &[AnswerInput],
+) -> AppResult<bool> {
+    let mut text_answers = false;
+    for question in questions {
+        let Some(answer) = answers
+            .iter()
+            .find(|a| a.question_id == question.id)
+            .map(|a| a.answer.trim())
+            .filter(|a| !a.is_empty())
+        else {
+            continue;
+        };
+        let evaluation = check_answer(question, answer);
+        text_answers |= evaluation.is_none();
+        let evaluation = evaluation.unwrap_or(Evaluation { score: None, flagged: false, reason: None });
+        let evaluated = question.question_type != ScreeningQuestionType::Text;
+
+        sqlx::query(
+            r#"
+            INSERT INTO screening_answers
+                (application_id, question_id, question, question_type, knockout, answer,
+                 score, flagged, reason, evaluated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CASE WHEN $10 THEN NOW() END)
+            "#,
+        )
+        .bind(application_id)
+        .bind(question.id)
+        .bind(&question.question)
+        .bind(question.question_type)
+        .bind(question.knockout)
+        .bind(answer)
+        .bind(evaluation.score)
+        .bind(evaluation.flagged)
+        .bind(evaluation.reason)
+        .bind(evaluated)
+        .execute(&mut **tx)
+        .await?;
+    }
+    summarize(&mut **tx, application_id).await?;
+    Ok(text_answers)
+}
+
+/// Recomputes an application's screening score and flag from its answers.
+async fn summarize<'e, E>(executor: E, application_id: i32) -> AppResult<()>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        UPDATE application_tracking a
+        SET screening_score = s.score, screening_flagged = s.flagged
+        FROM (
+            SELECT ROUND(AVG(score))::smallint AS score, COALESCE(BOOL_OR(flagged), FALSE) AS flagged
+            FROM screening_answers WHERE application_id = $1
+        ) s
+        WHERE a.id = $1
+        "#,
+    )
+    .bind(application_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Scores an application's text answers in the background. Without an AI
+/// provider, or if it fails, the answers are left unscored for the
+/// employer to read.
+pub fn spawn_evaluation(db_pool: PgPool, ai_service: Option<Arc<AIService>>, application_id: i32) {
+    tokio::spawn(async move {
+        if let Err(e) = evaluate(&db_pool, ai_service.as_deref(), application_id).await {
+            warn!("Screening evaluation failed for application {}: {}", application_id, e);
+        }
+    });
+}
+
+#[derive(Debug, FromRow)]
+struct PendingAnswer {
+    id: i32,
+    question: String,
+    expected_answer: Option<String>,
+    knockout: bool,
+    answer: String,
+}
+
+/// The provider's score for one answer.
+#[derive(Debug, Deserialize)]
+struct AnswerScore {
+    id: i32,
+    score: i64,
+    #[serde(default)]
+    flagged: bool,
+    reason: Option<String>,
+}
+
+async fn evaluate(db_pool: &PgPool, ai_service: Option<&AIService>, application_id: i32) -> AppResult<()> {
+    let pending = sqlx::query_as::<_, PendingAnswer>(
+        r#"
+        SELECT sa.id, sa.question, q.expected_answer, sa.knockout, sa.answer
+        FROM screening_answers sa
+        LEFT JOIN screening_questions q ON q.id = sa.question_id
+        WHERE sa.application_id = $1 AND sa.evaluated_at IS NULL
+        "#,
+    )
+    .bind(application_id)
+    .fetch_all(db_pool)
+    .await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let scores = match ai_service {
+        Some(ai) => score_text_answers(ai, db_pool, application_id, &pending).await,
+        None => HashMap::new(),
+    };
+
+    for answer in &pending {
+        let evaluation = match scores.get(&answer.id) {
+            Some(s) => {
+                let score = s.score.clamp(0, 100) as i16;
+                Evaluation {
+                    score: Some(score),
+                    flagged: s.flagged || (answer.knockout && score < KNOCKOUT_TEXT_SCORE),
+                    reason: s.reason.clone().filter(|r| !r.trim().is_empty()),
+                }
+            }
+            None => Evaluation {
+                score: None,
+                flagged: false,
+                reason: Some("Could not be scored automatically".to_string()),
+            },
+        };
+        sqlx::query(
+            "UPDATE screening_answers SET score = $2, flagged = $3, reason = $4, evaluated_at = NOW() WHERE id = $1",
+        )
+        .bind(answer.id)
+        .bind(evaluation.score)
+        .bind(evaluation.flagged)
+        .bind(evaluation.reason)
+        .execute(db_pool)
+        .await?;
+    }
+    summarize(db_pool, application_id).await?;
+
+    info!(
+        "Screening answers evaluated: application_id={}, scored={}/{}",
+        application_id,
+        scores.len(),
+        pending.len()
+    );
+    Ok(())
+}
+
+/// Asks the provider to score text answers; answers it did not score are
+/// missing from the map.
+async fn score_text_answers(
+    ai: &AIService,
+    db_pool: &PgPool,
+    application_id: i32,
+    pending: &[PendingAnswer],
+) -> HashMap<i32, AnswerScore> {
+    let job_title = sqlx::query_scalar::<_, String>(
+        "SELECT j.job_title FROM application_tracking a JOIN jobs j ON j.id = a.job_id WHERE a.id = $1",
+    )
+    .bind(application_id)
+    .fetch_optional(db_pool)
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or_default();
+    let answers: Vec<Value> = pending
+        .iter()
+        .map(|a| json!({ "id": a.id, "question": a.question, "strong_answer_covers": a.expected_answer, "answer": a.answer }))
+        .collect();
+
+    let request = AIActionRequest {
+        user_id: None,
+        action: ActionType::GenerateContent,
+        provider: AIProvider::Gemini,
+        input: serde_json::to_string_pretty(&answers).unwrap_or_default(),
+        parameters: Some(json!({
+            "content_type": EVALUATION_CONTENT_TYPE,
+            "job_title": job_title,
+            "instructions": "Score each candidate answer to these screening questions from 0 to 100 for how directly and \
+                credibly it answers the question and covers what a strong answer covers. Put a one-line summary in \
+                `content`. In `metadata.evaluations`, give one {\"id\", \"score\", \"flagged\", \"reason\"} object per answer; \
+                set `flagged` for answers that are off-topic, evasive, or raise a concern, and keep `reason` to one sentence \
+                about the answer only.",
+        })),
+        capture: true,
+    };
+
+    let data = match ai.process_action(request).await {
+        Ok(response) if response.success => response.data,
+        Ok(response) => {
+            warn!("Screening evaluation unavailable for application {}: {:?}", application_id, response.message);
+            return HashMap::new();
+        }
+        Err(e) => {
+            warn!("Screening evaluation unavailable for application {}: {}", application_id, e);
+            return HashMap::new();
+        }
+    };
+
+    data.pointer("/metadata/evaluations")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| serde_json::from_value::<AnswerScore>(e.clone()).ok())
+        .filter(|s| pending.iter().any(|a| a.id == s.id))
+        .map(|s| (s.id, s))
+        .collect()
+}
+
+/// A posting's applications with their screening results, newest first.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn applicants(db_pool: &PgPool, job_id: i32) -> AppResult<Vec<Applicant>> {
+    let mut applicants = sqlx::query_as::<_, Applicant>(
+        r#"
+        SELECT a.id AS application_id, a.user_id, u.full_name, u.email, a.status, a.applied_at,
+               a.screening_score, a.screening_flagged,
+               EXISTS (
+                   SELECT 1 FROM screening_answers sa
+                   WHERE sa.application_id = a.id AND sa.evaluated_at IS NULL
+               ) AS screening_pending
+        FROM application_tracking a
+        JOIN users u ON u.id = a.user_id
+        WHERE a.job_id = $1
+        ORDER BY a.applied_at DESC
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    let ids: Vec<i32> = applicants.iter().map(|a| a.application_id).collect();
+
+    #[derive(FromRow)]
+    struct Row {
+        application_id: i32,
+        #[sqlx(flatten)]
+        answer: ScreeningAnswer,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT application_id, question_id, question, question_type, knockout, answer,
+               score, flagged, reason, evaluated_at
+        FROM screening_answers
+        WHERE application_id = ANY($1)
+        ORDER BY id
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(db_pool)
+    .await?;
+    let mut answers: HashMap<i32, Vec<ScreeningAnswer>> = HashMap::new();
+    for row in rows {
+        answers.entry(row.application_id).or_default().push(row.answer);
+    }
+    for applicant in &mut applicants {
+        applicant.screening_answers = answers.remove(&applicant.application_id).unwrap_or_default();
+    }
+    Ok(applicants)
+}
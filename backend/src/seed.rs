@@ -0,0 +1,424 @@
+//! Demo dataset seeding.
+//!
+//! Populates the database with a small, realistic dataset (job seekers, an
+//! employer, job postings, a career roadmap, applications, and a mentor
+//! conversation) so the app can be explored locally or demoed without any
+//! manual setup. Run it with:
+//!
+//! ```text
+//! cargo run -- seed
+//! ```
+//!
+//! Seeding is idempotent: all demo accounts live under [`DEMO_EMAIL_DOMAIN`]
+//! and are removed (along with everything they own) before being recreated.
+//! When `DEMO_MODE` is enabled, `POST /api/demo/login` issues a token for one
+//! of the demo personas without a password.
+
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::AppResult;
+use crate::models::{CareerTrack, ExperienceLevel, JobType, UserRole};
+use crate::security::hash_password;
+
+/// Email domain shared by every seeded account.
+pub const DEMO_EMAIL_DOMAIN: &str = "demo.careerbridge.dev";
+
+/// Password for every seeded account, for signing in through the normal login flow.
+pub const DEMO_PASSWORD: &str = "DemoPass123!";
+
+/// Email of the primary demo job seeker.
+pub const DEMO_JOB_SEEKER_EMAIL: &str = "ayesha@demo.careerbridge.dev";
+
+/// Email of the demo employer.
+pub const DEMO_EMPLOYER_EMAIL: &str = "hiring@demo.careerbridge.dev";
+
+/// Returns whether `DEMO_MODE` is enabled (`true`, `1`, or `yes`).
+pub fn demo_mode_enabled() -> bool {
+    std::env::var("DEMO_MODE")
+        .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Row counts written by a seed run.
+#[derive(Debug, Default, Serialize)]
+pub struct SeedSummary {
+    pub users: usize,
+    pub jobs: usize,
+    pub roadmaps: usize,
+    pub applications: usize,
+    pub conversations: usize,
+}
+
+struct DemoUser {
+    full_name: &'static str,
+    email: &'static str,
+    role: UserRole,
+    education_level: Option<&'static str>,
+    experience_level: Option<ExperienceLevel>,
+    preferred_track: Option<CareerTrack>,
+    skills: &'static [&'static str],
+    projects: &'static [&'static str],
+    target_roles: &'static [&'static str],
+}
+
+const DEMO_USERS: &[DemoUser] = &[
+    DemoUser {
+        full_name: "Ayesha Rahman",
+        email: DEMO_JOB_SEEKER_EMAIL,
+        role: UserRole::JobSeeker,
+        education_level: Some("BSc in Computer Science and Engineering"),
+        experience_level: Some(ExperienceLevel::Fresher),
+        preferred_track: Some(CareerTrack::WebDevelopment),
+        skills: &["HTML", "CSS", "JavaScript", "React", "Git", "SQL"],
+        projects: &[
+            "Campus Events Portal - React app listing university club events with search and RSVP",
+            "Expense Tracker - Personal finance tracker with charts built on JavaScript and LocalStorage",
+        ],
+        target_roles: &["Frontend Developer", "Junior Web Developer"],
+    },
+    DemoUser {
+        full_name: "Tanvir Hasan",
+        email: "tanvir@demo.careerbridge.dev",
+        role: UserRole::JobSeeker,
+        education_level: Some("BBA in Management Information Systems"),
+        experience_level: Some(ExperienceLevel::Junior),
+        preferred_track: Some(CareerTrack::Data),
+        skills: &["Excel", "SQL", "Python", "Power BI", "Statistics"],
+        projects: &["Retail Sales Dashboard - Power BI report tracking monthly sales by region"],
+        target_roles: &["Data Analyst", "Business Intelligence Analyst"],
+    },
+    DemoUser {
+        full_name: "Nusrat Jahan",
+        email: DEMO_EMPLOYER_EMAIL,
+        role: UserRole::Employer,
+        education_level: None,
+        experience_level: None,
+        preferred_track: None,
+        skills: &[],
+        projects: &[],
+        target_roles: &[],
+    },
+];
+
+struct DemoJob {
+    job_title: &'static str,
+    location: &'static str,
+    job_description: &'static str,
+    required_skills: &'static [&'static str],
+    experience_level: ExperienceLevel,
+    job_type: JobType,
+    salary_min: Option<i32>,
+    salary_max: Option<i32>,
+}
+
+const DEMO_COMPANY: &str = "Padma Digital Ltd.";
+
+const DEMO_JOBS: &[DemoJob] = &[
+    DemoJob {
+        job_title: "Junior Frontend Developer",
+        location: "Dhaka, Bangladesh",
+        job_description: "Build responsive customer-facing web pages with React and work closely with designers to ship features every sprint.",
+        required_skills: &["JavaScript", "React", "CSS", "Git"],
+        experience_level: ExperienceLevel::Fresher,
+        job_type: JobType::FullTime,
+        salary_min: Some(30000),
+        salary_max: Some(45000),
+    },
+    DemoJob {
+        job_title: "Frontend Engineering Intern",
+        location: "Remote",
+        job_description: "Three-month paid internship on the web team, fixing UI bugs and writing component tests under mentorship.",
+        required_skills: &["HTML", "CSS", "JavaScript"],
+        experience_level: ExperienceLevel::Fresher,
+        job_type: JobType::Internship,
+        salary_min: Some(12000),
+        salary_max: Some(15000),
+    },
+    DemoJob {
+        job_title: "Data Analyst",
+        location: "Chattogram, Bangladesh",
+        job_description: "Own weekly business reporting, build dashboards, and help product teams answer questions with SQL.",
+        required_skills: &["SQL", "Excel", "Power BI", "Statistics"],
+        experience_level: ExperienceLevel::Junior,
+        job_type: JobType::FullTime,
+        salary_min: Some(40000),
+        salary_max: Some(60000),
+    },
+    DemoJob {
+        job_title: "Full Stack Developer",
+        location: "Dhaka, Bangladesh",
+        job_description: "Develop and maintain Node.js APIs and React front ends for our logistics platform.",
+        required_skills: &["JavaScript", "React", "Node.js", "PostgreSQL", "Docker"],
+        experience_level: ExperienceLevel::Mid,
+        job_type: JobType::FullTime,
+        salary_min: Some(80000),
+        salary_max: Some(120000),
+    },
+    DemoJob {
+        job_title: "Freelance UI Designer",
+        location: "Remote",
+        job_description: "Design landing pages and marketing assets for product launches on a per-project basis.",
+        required_skills: &["Figma", "UI Design", "Prototyping"],
+        experience_level: ExperienceLevel::Junior,
+        job_type: JobType::Freelance,
+        salary_min: None,
+        salary_max: None,
+    },
+];
+
+/// Seeds the demo dataset, replacing any previously seeded demo data.
+///
+/// Everything is written in a single transaction, so a failed run leaves the
+/// database unchanged.
+///
+/// # Errors
+///
+/// Returns an error if password hashing or any database operation fails.
+pub async fn run(db_pool: &PgPool) -> AppResult<SeedSummary> {
+    let password_hash = hash_password(DEMO_PASSWORD.to_string()).await?;
+    let mut tx = db_pool.begin().await?;
+    let mut summary = SeedSummary::default();
+
+    clear_demo_data(&mut tx).await?;
+
+    let mut user_ids = Vec::with_capacity(DEMO_USERS.len());
+    for user in DEMO_USERS {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO users (
+                full_name, email, password_hash, role, education_level,
+                experience_level, preferred_track, skills, projects, target_roles,
+                profile_completed
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(user.full_name)
+        .bind(user.email)
+        .bind(&password_hash)
+        .bind(user.role)
+        .bind(user.education_level)
+        .bind(&user.experience_level)
+        .bind(&user.preferred_track)
+        .bind(user.skills)
+        .bind(user.projects)
+        .bind(user.target_roles)
+        .bind(user.role == UserRole::JobSeeker)
+        .fetch_one(&mut *tx)
+        .await?;
+        user_ids.push(id);
+        summary.users += 1;
+    }
+    let (ayesha, tanvir, employer) = (user_ids[0], user_ids[1], user_ids[2]);
+
+    let mut job_ids = Vec::with_capacity(DEMO_JOBS.len());
+    for job in DEMO_JOBS {
+        let id = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO jobs (
+                job_title, company, location, job_description, required_skills,
+                experience_level, job_type, salary_min, salary_max, posted_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(job.job_title)
+        .bind(DEMO_COMPANY)
+        .bind(job.location)
+        .bind(job.job_description)
+        .bind(job.required_skills)
+        .bind(&job.experience_level)
+        .bind(job.job_type)
+        .bind(job.salary_min)
+        .bind(job.salary_max)
+        .bind(employer)
+        .fetch_one(&mut *tx)
+        .await?;
+        job_ids.push(id);
+        summary.jobs += 1;
+    }
+
+    let applications = [
+        (ayesha, job_ids[0], "in_review", "Referred by a senior from university"),
+        (ayesha, job_ids[1], "applied", "Backup option if the full-time role falls through"),
+        (tanvir, job_ids[2], "interview", "Technical interview scheduled for next week"),
+    ];
+    for (user_id, job_id, status, notes) in applications {
+        sqlx::query(
+            "INSERT INTO application_tracking (user_id, job_id, status, notes) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(job_id)
+        .bind(status)
+        .bind(notes)
+        .execute(&mut *tx)
+        .await?;
+        summary.applications += 1;
+    }
+
+    seed_roadmap(&mut tx, ayesha).await?;
+    summary.roadmaps += 1;
+
+    seed_conversation(&mut tx, ayesha).await?;
+    summary.conversations += 1;
+
+    tx.commit().await?;
+
+    info!(
+        "Seeded demo data: {} users, {} jobs, {} roadmaps, {} applications, {} conversations",
+        summary.users, summary.jobs, summary.roadmaps, summary.applications, summary.conversations
+    );
+    Ok(summary)
+}
+
+/// Removes all demo accounts and the jobs they posted.
+///
+/// Jobs are deleted explicitly because `jobs.posted_by` is `ON DELETE SET NULL`;
+/// everything else owned by demo users cascades.
+async fn clear_demo_data(tx: &mut Transaction<'_, Postgres>) -> AppResult<()> {
+    let pattern = format!("%@{}", DEMO_EMAIL_DOMAIN);
+
+    sqlx::query("DELETE FROM jobs WHERE posted_by IN (SELECT id FROM users WHERE email LIKE $1)")
+        .bind(&pattern)
+        .execute(&mut **tx)
+        .await?;
+    let removed = sqlx::query("DELETE FROM users WHERE email LIKE $1")
+        .bind(&pattern)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+    if removed > 0 {
+        info!("Removed {} existing demo users", removed);
+    }
+    Ok(())
+}
+
+async fn seed_roadmap(tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> AppResult<()> {
+    let project_suggestions = json!([
+        {
+            "title": "Personal Portfolio Website",
+            "description": "Responsive portfolio showcasing projects with a contact form",
+            "technologies": ["HTML", "CSS", "JavaScript"],
+            "difficulty": "beginner",
+            "estimated_hours": 20,
+            "recommended_phase": 1
+        },
+        {
+            "title": "Job Board Clone",
+            "description": "React front end consuming a public jobs API with filters and saved searches",
+            "technologies": ["React", "TypeScript", "REST APIs"],
+            "difficulty": "intermediate",
+            "estimated_hours": 40,
+            "recommended_phase": 2
+        }
+    ]);
+    let roadmap_data = json!({
+        "target_role": "Frontend Developer",
+        "phases": [
+            {
+                "phase": 1,
+                "title": "JavaScript Foundations",
+                "timeline": "Month 1 (Weeks 1-4)",
+                "topics": ["ES6+ syntax", "Asynchronous programming", "DOM manipulation"],
+                "technologies": ["JavaScript", "Git"],
+                "duration": "4 weeks",
+                "learning_goals": ["Write modern JavaScript confidently", "Understand async/await"],
+                "resources": ["JavaScript.info", "MDN Web Docs - JavaScript Guide"]
+            },
+            {
+                "phase": 2,
+                "title": "React and TypeScript",
+                "timeline": "Months 2-3 (Weeks 5-12)",
+                "topics": ["Component architecture", "Hooks and state management", "Typing React components"],
+                "technologies": ["React", "TypeScript", "React Router"],
+                "duration": "8 weeks",
+                "learning_goals": ["Build multi-page React applications", "Adopt TypeScript in a React codebase"],
+                "resources": ["React Official Documentation", "TypeScript Handbook"]
+            },
+            {
+                "phase": 3,
+                "title": "Testing and Deployment",
+                "timeline": "Months 4-6 (Weeks 13-24)",
+                "topics": ["Unit and component testing", "CI pipelines", "Performance basics"],
+                "technologies": ["Jest", "React Testing Library", "GitHub Actions", "Vercel"],
+                "duration": "12 weeks",
+                "learning_goals": ["Ship a tested, deployed portfolio project"],
+                "resources": ["Testing Library Docs", "GitHub Actions Documentation"]
+            }
+        ],
+        "project_suggestions": project_suggestions,
+        "job_application_timing": "Start applying for internships after Phase 2 and full-time roles once Phase 3 begins"
+    });
+
+    sqlx::query(
+        r#"
+        INSERT INTO career_roadmaps (
+            user_id, title, target_role, roadmap_data, ai_provider,
+            timeframe_months, learning_hours_per_week, current_skills,
+            project_suggestions, job_application_timing, progress_percentage,
+            completed_phases
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+    )
+    .bind(user_id)
+    .bind("Roadmap to Frontend Developer")
+    .bind("Frontend Developer")
+    .bind(&roadmap_data)
+    .bind("gemini")
+    .bind(6)
+    .bind(12)
+    .bind(json!(["HTML", "CSS", "JavaScript", "React", "Git", "SQL"]))
+    .bind(&project_suggestions)
+    .bind(roadmap_data["job_application_timing"].as_str())
+    .bind(33)
+    .bind(vec![1i32])
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn seed_conversation(tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> AppResult<()> {
+    let conversation_id = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO mentor_conversations (user_id, title) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(user_id)
+    .bind("Preparing for frontend interviews")
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let messages = [
+        ("user", "I have an interview for a junior frontend role next week. What should I focus on?"),
+        (
+            "assistant",
+            "Focus on three areas: JavaScript fundamentals (closures, promises, array methods), \
+             React basics (state, props, hooks, rendering lists), and walking through one of your \
+             projects end to end. Practice explaining a bug you fixed and why you chose your approach.",
+        ),
+        ("user", "How should I talk about my Campus Events Portal project?"),
+        (
+            "assistant",
+            "Describe the problem it solved, your role, and the result. For example: students missed \
+             club events, so you built a searchable React portal with RSVP, and mention any usage \
+             numbers. Be ready to discuss how you managed state and what you would improve next.",
+        ),
+    ];
+    for (role, content) in messages {
+        sqlx::query("INSERT INTO mentor_messages (conversation_id, role, content) VALUES ($1, $2, $3)")
+            .bind(conversation_id)
+            .bind(role)
+            .bind(content)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Semantic cache of mentor answers.
+//!
+//! Users often ask the mentor the same thing in different words. Standalone
+//! questions (no conversation history, no tools) are embedded, and the answer
+//! is stored in `mentor_answer_cache` with the question's embedding and a
+//! hash of the profile context it was given for. A later question from the
+//! same user and context whose embedding has a cosine similarity of at least
+//! `MENTOR_CACHE_SIMILARITY` (default 0.95) to a cached question younger than
+//! `MENTOR_CACHE_TTL_SECS` (default one day) is answered from the cache
+//! without calling the AI provider. A change to the user's skills, target
+//! roles, or experience level changes the context hash, so answers given for
+//! the old profile are not reused.
+//!
+//! Embeddings need Gemini (or the mock client); without it nothing is cached.
+//! Setting `MENTOR_CACHE_TTL_SECS` to 0 disables the cache.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::errors::AppResult;
+use crate::retrieval;
+
+/// Default lowest cosine similarity for a cached question to match.
+const DEFAULT_SIMILARITY: f64 = 0.95;
+
+/// Default age after which a cached answer is no longer served.
+const DEFAULT_TTL_SECS: i64 = 86_400;
+
+/// An embedded question, ready to look up or store.
+#[derive(Debug)]
+pub struct CacheKey {
+    context_hash: String,
+    /// The question's embedding as a pgvector literal
+    embedding: String,
+}
+
+/// A cached answer close enough to the question asked.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CachedAnswer {
+    pub id: i64,
+    /// The question the answer was originally given for
+    pub question: String,
+    pub answer: Value,
+    pub sources: Value,
+    pub provider: String,
+    pub similarity: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returns the similarity threshold from `MENTOR_CACHE_SIMILARITY` (default: 0.95).
+pub fn similarity_threshold() -> f64 {
+    std::env::var("MENTOR_CACHE_SIMILARITY")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|t| (0.0..=1.0).contains(t))
+        .unwrap_or(DEFAULT_SIMILARITY)
+}
+
+/// Returns the cache lifetime from `MENTOR_CACHE_TTL_SECS` (default: 86400).
+pub fn ttl_secs() -> i64 {
+    std::env::var("MENTOR_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|n| *n >= 0)
+        .unwrap_or(DEFAULT_TTL_SECS)
+}
+
+/// Whether answers can be cached with this AI service.
+pub fn enabled(ai: &AIService) -> bool {
+    ttl_secs() > 0 && ai.embeddings_available()
+}
+
+/// Embeds `question` asked in `context`.
+///
+/// Returns `None` if the embedding service returned nothing.
+///
+/// # Errors
+///
+/// Returns an error if embedding fails.
+pub async fn key(ai: &AIService, context: &str, question: &str) -> AppResult<Option<CacheKey>> {
+    let Some(vector) = ai.embed(&[question.trim().to_string()]).await?.pop() else {
+        return Ok(None);
+    };
+    Ok(Some(CacheKey {
+        context_hash: hex::encode(Sha256::digest(context.as_bytes())),
+        embedding: retrieval::vector_literal(&vector),
+    }))
+}
+
+/// Finds the closest recent cached answer for the user and context, if it is
+/// within the similarity threshold, and counts the hit.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn lookup(db_pool: &PgPool, user_id: Uuid, key: &CacheKey) -> AppResult<Option<CachedAnswer>> {
+    let closest = sqlx::query_as::<_, CachedAnswer>(
+        r#"
+        SELECT id, question, answer, sources, provider,
+               1 - (embedding <=> $3::vector) AS similarity, created_at
+        FROM mentor_answer_cache
+        WHERE user_id = $1
+          AND context_hash = $2
+          AND created_at > NOW() - make_interval(secs => $4)
+        ORDER BY embedding <=> $3::vector
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(&key.context_hash)
+    .bind(&key.embedding)
+    .bind(ttl_secs() as f64)
+    .fetch_optional(db_pool)
+    .await?;
+
+    let Some(cached) = closest.filter(|c| c.similarity >= similarity_threshold()) else {
+        return Ok(None);
+    };
+    sqlx::query("UPDATE mentor_answer_cache SET hit_count = hit_count + 1 WHERE id = $1")
+        .bind(cached.id)
+        .execute(db_pool)
+        .await?;
+    Ok(Some(cached))
+}
+
+/// Stores an answer under `key`, dropping the user's expired entries.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn store(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    key: &CacheKey,
+    question: &str,
+    answer: &Value,
+    sources: &Value,
+    provider: &str,
+) -> AppResult<()> {
+    sqlx::query(
+        "DELETE FROM mentor_answer_cache WHERE user_id = $1 AND created_at <= NOW() - make_interval(secs => $2)",
+    )
+    .bind(user_id)
+    .bind(ttl_secs() as f64)
+    .execute(db_pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_answer_cache (user_id, context_hash, question, embedding, answer, sources, provider)
+        VALUES ($1, $2, $3, $4::vector, $5, $6, $7)
+        "#,
+    )
+    .bind(user_id)
+    .bind(&key.context_hash)
+    .bind(question)
+    .bind(&key.embedding)
+    .bind(answer)
+    .bind(sources)
+    .bind(provider)
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,384 @@
+//! Notes and action items from mentor sessions.
+//!
+//! Once a session has started its mentor can record notes, which the mentee
+//! sees too, and assign the mentee action items. Recording notes marks the
+//! booking completed. Each action item shows up in the mentee's activity
+//! feed, and [`add_to_roadmap`] turns one into a task on a roadmap phase: it
+//! is appended to the phase's resources, so it is checked off like any
+//! other resource (see [`crate::resource_progress`]).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::activity::{self, ActivityType};
+use crate::errors::{AppError, AppResult};
+use crate::mentorship::{self, MentorBooking};
+use crate::models::MentorBookingStatus;
+
+/// Prefix of the roadmap resource an action item becomes.
+const ROADMAP_TASK_PREFIX: &str = "Mentor action item: ";
+
+/// An action item a mentor assigned to a mentee.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ActionItem {
+    pub id: i32,
+    pub booking_id: i32,
+    pub mentor_id: Uuid,
+    pub mentor_name: String,
+    pub title: String,
+    pub details: Option<String>,
+    pub due_on: Option<NaiveDate>,
+    /// Roadmap the item was added to, if the mentee converted it
+    pub roadmap_id: Option<i32>,
+    /// Resource id (`"<phase>-<n>"`) of the task on that roadmap
+    pub roadmap_resource: Option<String>,
+    pub converted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields of a new action item.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionItemDraft<'a> {
+    pub title: &'a str,
+    pub details: Option<&'a str>,
+    pub due_on: Option<NaiveDate>,
+}
+
+/// A session's notes and action items.
+#[derive(Debug, Serialize)]
+pub struct SessionNotes {
+    pub booking_id: i32,
+    /// `None` until the mentor records notes
+    pub notes: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub action_items: Vec<ActionItem>,
+}
+
+const ACTION_ITEM_SELECT: &str = r#"
+    SELECT a.id, a.booking_id, b.mentor_id, u.full_name AS mentor_name, a.title, a.details,
+           a.due_on, a.roadmap_id, a.roadmap_resource, a.converted_at, a.created_at
+    FROM mentor_action_items a
+    JOIN mentor_bookings b ON b.id = a.booking_id
+    JOIN users u ON u.id = b.mentor_id
+"#;
+
+/// The caller's booking if they are its mentor and the session has started.
+async fn mentor_session(db_pool: &PgPool, mentor_id: Uuid, booking_id: i32) -> AppResult<MentorBooking> {
+    let booking = mentorship::booking(db_pool, mentor_id, booking_id).await?;
+    if booking.mentor_id != mentor_id {
+        return Err(AppError::Forbidden);
+    }
+    let held = matches!(booking.status, MentorBookingStatus::Confirmed | MentorBookingStatus::Completed);
+    if !held || booking.starts_at > Utc::now() {
+        return Err(AppError::BadRequest(
+            "Notes and action items can only be recorded once the session has started".to_string(),
+        ));
+    }
+    Ok(booking)
+}
+
+/// Notes and action items of one of the caller's sessions.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404) or the
+/// database operation fails.
+pub async fn session_notes(db_pool: &PgPool, user_id: Uuid, booking_id: i32) -> AppResult<SessionNotes> {
+    mentorship::booking(db_pool, user_id, booking_id).await?;
+    let notes = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+        "SELECT notes, updated_at FROM mentor_session_notes WHERE booking_id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(db_pool)
+    .await?;
+    let action_items = sqlx::query_as::<_, ActionItem>(&format!(
+        "{} WHERE a.booking_id = $1 ORDER BY a.id",
+        ACTION_ITEM_SELECT
+    ))
+    .bind(booking_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    let (notes, updated_at) = notes.unzip();
+    Ok(SessionNotes {
+        booking_id,
+        notes,
+        updated_at,
+        action_items,
+    })
+}
+
+/// Records the mentor's notes for a session, replacing earlier ones, and
+/// marks the session completed. The mentee is notified the first time.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404), the caller is
+/// not its mentor (403), the session has not started or was cancelled, or
+/// the database operation fails.
+pub async fn save_notes(db_pool: &PgPool, mentor_id: Uuid, booking_id: i32, notes: &str) -> AppResult<SessionNotes> {
+    let booking = mentor_session(db_pool, mentor_id, booking_id).await?;
+
+    let mut tx = db_pool.begin().await?;
+    let (first,) = sqlx::query_as::<_, (bool,)>(
+        r#"
+        INSERT INTO mentor_session_notes (booking_id, notes)
+        VALUES ($1, $2)
+        ON CONFLICT (booking_id) DO UPDATE SET notes = EXCLUDED.notes, updated_at = NOW()
+        RETURNING (xmax = 0)
+        "#,
+    )
+    .bind(booking_id)
+    .bind(notes.trim())
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE mentor_bookings SET status = 'completed', updated_at = NOW() WHERE id = $1 AND status = 'confirmed'")
+        .bind(booking_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    if first {
+        sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_booking')")
+            .bind(booking.mentee_id)
+            .bind("Session notes available")
+            .bind(format!(
+                "{} shared notes from your session on {}",
+                booking.mentor_name, booking.topic
+            ))
+            .execute(db_pool)
+            .await?;
+    }
+    info!("Mentor {} recorded notes for booking {}", mentor_id, booking_id);
+    session_notes(db_pool, mentor_id, booking_id).await
+}
+
+/// Assigns action items from a session to its mentee, each recorded in the
+/// mentee's activity feed.
+///
+/// # Errors
+///
+/// Returns an error if the booking is not the caller's (404), the caller is
+/// not its mentor (403), the session has not started or was cancelled, or
+/// the database operation fails.
+pub async fn add_action_items(
+    db_pool: &PgPool,
+    mentor_id: Uuid,
+    booking_id: i32,
+    items: &[ActionItemDraft<'_>],
+) -> AppResult<Vec<ActionItem>> {
+    let booking = mentor_session(db_pool, mentor_id, booking_id).await?;
+
+    let mut tx = db_pool.begin().await?;
+    let mut ids = Vec::with_capacity(items.len());
+    for item in items {
+        let (id,) = sqlx::query_as::<_, (i32,)>(
+            r#"
+            INSERT INTO mentor_action_items (booking_id, mentee_id, title, details, due_on)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(booking_id)
+        .bind(booking.mentee_id)
+        .bind(item.title.trim())
+        .bind(item.details.map(str::trim).filter(|d| !d.is_empty()))
+        .bind(item.due_on)
+        .fetch_one(&mut *tx)
+        .await?;
+        ids.push(id);
+    }
+    tx.commit().await?;
+
+    let created = sqlx::query_as::<_, ActionItem>(&format!("{} WHERE a.id = ANY($1) ORDER BY a.id", ACTION_ITEM_SELECT))
+        .bind(&ids)
+        .fetch_all(db_pool)
+        .await?;
+    for item in &created {
+        activity::record(
+            db_pool,
+            booking.mentee_id,
+            ActivityType::ActionItemAssigned,
+            json!({
+                "action_item_id": item.id,
+                "booking_id": booking_id,
+                "mentor_name": item.mentor_name,
+                "title": item.title,
+                "due_on": item.due_on,
+            }),
+        )
+        .await;
+    }
+    sqlx::query("INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'mentor_booking')")
+        .bind(booking.mentee_id)
+        .bind("New action items")
+        .bind(format!(
+            "{} assigned you {} action item{} from your session on {}",
+            booking.mentor_name,
+            created.len(),
+            if created.len() == 1 { "" } else { "s" },
+            booking.topic
+        ))
+        .execute(db_pool)
+        .await?;
+    info!("Mentor {} assigned {} action items on booking {}", mentor_id, created.len(), booking_id);
+    Ok(created)
+}
+
+/// Removes an action item the caller assigned, unless the mentee already
+/// added it to a roadmap.
+///
+/// # Errors
+///
+/// Returns an error if the item is not one the caller assigned (404), it
+/// was added to a roadmap, or the database operation fails.
+pub async fn delete_action_item(db_pool: &PgPool, mentor_id: Uuid, item_id: i32) -> AppResult<()> {
+    let converted = sqlx::query_as::<_, (bool,)>(
+        r#"
+        SELECT a.converted_at IS NOT NULL
+        FROM mentor_action_items a
+        JOIN mentor_bookings b ON b.id = a.booking_id
+        WHERE a.id = $1 AND b.mentor_id = $2
+        "#,
+    )
+    .bind(item_id)
+    .bind(mentor_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if converted.0 {
+        return Err(AppError::BadRequest(
+            "The mentee already added this action item to a roadmap".to_string(),
+        ));
+    }
+    sqlx::query("DELETE FROM mentor_action_items WHERE id = $1")
+        .bind(item_id)
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// The caller's action items, newest first; those not yet added to a
+/// roadmap only if `open_only`.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn action_items(db_pool: &PgPool, mentee_id: Uuid, open_only: bool) -> AppResult<Vec<ActionItem>> {
+    Ok(sqlx::query_as::<_, ActionItem>(&format!(
+        "{} WHERE a.mentee_id = $1 AND (NOT $2 OR a.converted_at IS NULL) ORDER BY a.created_at DESC, a.id DESC",
+        ACTION_ITEM_SELECT
+    ))
+    .bind(mentee_id)
+    .bind(open_only)
+    .fetch_all(db_pool)
+    .await?)
+}
+
+/// Adds one of the caller's action items to a roadmap as a task: a resource
+/// of `phase`, by default the first phase not completed yet. Without
+/// `roadmap_id` the primary roadmap is used, else the newest one.
+///
+/// # Errors
+///
+/// Returns an error if the item or roadmap is not the caller's (404), the
+/// item was already added, the roadmap has no such phase, or the database
+/// operation fails.
+pub async fn add_to_roadmap(
+    db_pool: &PgPool,
+    mentee_id: Uuid,
+    item_id: i32,
+    roadmap_id: Option<i32>,
+    phase: Option<i32>,
+) -> AppResult<ActionItem> {
+    let mut tx = db_pool.begin().await?;
+    let (title, converted_at) = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+        "SELECT title, converted_at FROM mentor_action_items WHERE id = $1 AND mentee_id = $2 FOR UPDATE",
+    )
+    .bind(item_id)
+    .bind(mentee_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if converted_at.is_some() {
+        return Err(AppError::BadRequest(
+            "This action item is already on a roadmap".to_string(),
+        ));
+    }
+
+    let (roadmap_id, mut roadmap_data, completed_phases) = sqlx::query_as::<_, (i32, Value, Option<Vec<i32>>)>(
+        r#"
+        SELECT id, roadmap_data, completed_phases
+        FROM career_roadmaps
+        WHERE user_id = $1 AND ($2::INTEGER IS NULL OR id = $2)
+        ORDER BY is_primary DESC, created_at DESC
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(mentee_id)
+    .bind(roadmap_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let completed_phases = completed_phases.unwrap_or_default();
+
+    let phases = roadmap_data
+        .get_mut("phases")
+        .and_then(Value::as_array_mut)
+        .filter(|phases| !phases.is_empty())
+        .ok_or_else(|| AppError::BadRequest("The roadmap has no phases".to_string()))?;
+    let numbered: Vec<i32> = phases
+        .iter()
+        .enumerate()
+        .map(|(index, p)| p.get("phase").and_then(Value::as_i64).map_or(index as i32 + 1, |n| n as i32))
+        .collect();
+    let index = match phase {
+        Some(phase) => numbered
+            .iter()
+            .position(|n| *n == phase)
+            .ok_or_else(|| AppError::ValidationError(format!("The roadmap has no phase {}", phase)))?,
+        None => numbered
+            .iter()
+            .position(|n| !completed_phases.contains(n))
+            .unwrap_or(numbered.len() - 1),
+    };
+
+    // Appending keeps the positions, and so the marks, of existing resources
+    let target = &mut phases[index];
+    let mut resources = target
+        .get("resources")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    resources.push(Value::String(format!("{}{}", ROADMAP_TASK_PREFIX, title)));
+    let rid = format!("{}-{}", numbered[index], resources.len());
+    target["resources"] = Value::Array(resources);
+
+    sqlx::query("UPDATE career_roadmaps SET roadmap_data = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(roadmap_id)
+        .bind(&roadmap_data)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "UPDATE mentor_action_items SET roadmap_id = $2, roadmap_resource = $3, converted_at = NOW() WHERE id = $1",
+    )
+    .bind(item_id)
+    .bind(roadmap_id)
+    .bind(&rid)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    info!("User {} added action item {} to roadmap {} as {}", mentee_id, item_id, roadmap_id, rid);
+    Ok(
+        sqlx::query_as::<_, ActionItem>(&format!("{} WHERE a.id = $1", ACTION_ITEM_SELECT))
+            .bind(item_id)
+            .fetch_one(db_pool)
+            .await?,
+    )
+}
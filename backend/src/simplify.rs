@@ -0,0 +1,108 @@
+//! Rewriting generated documents at a simpler reading level.
+//!
+//! [`simplify`] takes a document the AI generated earlier (a roadmap, a
+//! summary, a mentor answer) and asks for the same document in plainer
+//! words (`simplified_document` content type). Text stays text, keeping its
+//! Markdown headings and lists; for a JSON document only the prose string
+//! values are rewritten, and a rewrite whose keys, array lengths, or value
+//! types differ from the original is rejected rather than returned.
+
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType, ReadingLevel, Tone};
+use crate::errors::{AppError, AppResult};
+
+/// Content type documents are rewritten with.
+const CONTENT_TYPE: &str = "simplified_document";
+
+/// Whether two JSON values have the same structure: the same keys, array
+/// lengths, and value types, all the way down. Strings may differ.
+fn same_shape(original: &Value, rewritten: &Value) -> bool {
+    match (original, rewritten) {
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| same_shape(value, other)))
+        }
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_shape(a, b)),
+        (Value::String(_), Value::String(_)) => true,
+        (a, b) => a == b,
+    }
+}
+
+/// Rewrites `document` at `level`, keeping its structure.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the document has no text;
+/// `AppError::ExternalServiceError` if the provider fails or its rewrite
+/// does not keep the document's structure.
+pub async fn simplify(
+    ai: &AIService,
+    user_id: Uuid,
+    document: &Value,
+    level: ReadingLevel,
+    tone: Option<Tone>,
+    provider: AIProvider,
+) -> AppResult<Value> {
+    let (input, instructions) = match document {
+        Value::String(text) if !text.trim().is_empty() => (
+            text.clone(),
+            "Rewrite this document at the requested reading level. Keep its meaning and every fact, and keep \
+             its structure: the same headings, lists, and paragraphs in the same order, with the same Markdown. \
+             Put the rewritten document in `content`.",
+        ),
+        Value::Object(_) | Value::Array(_) => (
+            document.to_string(),
+            "This is a JSON document. Rewrite its prose at the requested reading level and return it in \
+             `content` as a JSON string of the same document: the same keys, the same number of items in every \
+             array, and the same numbers, booleans, and nulls. Only rewrite sentences meant for the reader; leave \
+             names, identifiers, dates, URLs, and single-word values as they are.",
+        ),
+        _ => return Err(AppError::ValidationError("content must be text or a JSON document".to_string())),
+    };
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::GenerateContent,
+            provider,
+            input,
+            parameters: Some(json!({
+                "content_type": CONTENT_TYPE,
+                "reading_level": level,
+                "tone": tone,
+                "instructions": instructions,
+            })),
+            capture: true,
+        })
+        .await?;
+    if !response.success {
+        return Err(AppError::ExternalServiceError(
+            response.message.unwrap_or_else(|| "AI provider call failed".to_string()),
+        ));
+    }
+
+    let content = response.data.get("content").cloned().unwrap_or(Value::Null);
+    if document.is_string() {
+        return content
+            .as_str()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| Value::String(c.to_string()))
+            .ok_or_else(|| AppError::ExternalServiceError("AI provider returned no rewrite".to_string()));
+    }
+
+    // Providers sometimes return the document itself rather than a string
+    let rewritten = match content {
+        Value::String(text) => serde_json::from_str(&text)
+            .map_err(|_| AppError::ExternalServiceError("AI provider returned a malformed document".to_string()))?,
+        other => other,
+    };
+    if !same_shape(document, &rewritten) {
+        return Err(AppError::ExternalServiceError(
+            "AI provider changed the document's structure".to_string(),
+        ));
+    }
+    Ok(rewritten)
+}
@@ -0,0 +1,336 @@
+//! Skill proficiency self-assessment and decay.
+//!
+//! Users rate their proficiency in a skill from 1 (beginner) to 5 (expert)
+//! and record when they last used it. Skills fade without practice, so the
+//! effective proficiency of a skill not used for a while is lowered: there
+//! is no decay for [`DECAY_GRACE_DAYS`], then the rating halves every
+//! [`DECAY_HALF_LIFE_DAYS`], never dropping below [`MIN_DECAY_FACTOR`] of
+//! the rating.
+//!
+//! Job matching and skill-gap analysis weight each skill by its effective
+//! proficiency through [`SkillWeights`]. Skills without a rating keep full
+//! weight, so users who never rate their skills see no change.
+//!
+//! Learning progress is also tracked per skill rather than per roadmap, in
+//! `user_skill_progress`. The skills a roadmap phase teaches are its
+//! technologies; completing the phase marks them known, so a skill learned
+//! in one roadmap counts as held in skill-gap analysis and is passed as a
+//! current skill when the next roadmap is generated. A proficiency rating
+//! overrides roadmap progress for that skill.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::errors::AppResult;
+use crate::models::{SkillProgressSource, SkillProgressStatus};
+
+/// Highest proficiency rating.
+pub const MAX_PROFICIENCY: i16 = 5;
+
+/// Effective proficiency that counts as a full match for a required skill
+/// (intermediate); also assumed for unrated skills.
+pub const FULL_MATCH_PROFICIENCY: f64 = 3.0;
+
+/// Days after last use before a skill starts to decay.
+pub const DECAY_GRACE_DAYS: i64 = 180;
+
+/// Days over which a decaying skill loses half its proficiency.
+pub const DECAY_HALF_LIFE_DAYS: f64 = 730.0;
+
+/// Lowest fraction of the rating a skill decays to.
+pub const MIN_DECAY_FACTOR: f64 = 0.5;
+
+/// Decay factor below which a skill is reported as stale.
+pub const STALE_FACTOR: f64 = 0.8;
+
+/// Fraction of a rating kept after `last_used_on`, as of `today`.
+///
+/// Skills without a last-used date are not decayed.
+pub fn decay_factor(last_used_on: Option<NaiveDate>, today: NaiveDate) -> f64 {
+    let Some(last_used_on) = last_used_on else {
+        return 1.0;
+    };
+    let idle_days = (today - last_used_on).num_days() - DECAY_GRACE_DAYS;
+    if idle_days <= 0 {
+        return 1.0;
+    }
+    0.5_f64
+        .powf(idle_days as f64 / DECAY_HALF_LIFE_DAYS)
+        .max(MIN_DECAY_FACTOR)
+}
+
+/// A user's rating of one skill.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SkillRating {
+    pub skill: String,
+    /// Self-assessed proficiency, 1 (beginner) to 5 (expert)
+    pub proficiency: i16,
+    pub last_used_on: Option<NaiveDate>,
+    /// Proficiency after decay for time since last use
+    #[sqlx(skip)]
+    pub effective_proficiency: f64,
+    /// Whether the skill has decayed enough to need a refresher
+    #[sqlx(skip)]
+    pub stale: bool,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SkillRating {
+    /// Derives the effective proficiency as of `today`.
+    pub fn with_decay(mut self, today: NaiveDate) -> Self {
+        let factor = decay_factor(self.last_used_on, today);
+        self.effective_proficiency = (self.proficiency as f64 * factor * 10.0).round() / 10.0;
+        self.stale = factor < STALE_FACTOR;
+        self
+    }
+}
+
+/// How much each of a user's skills counts toward a match, from 0 to 1.
+#[derive(Debug, Default)]
+pub struct SkillWeights {
+    /// Keyed by lowercase skill name
+    ratings: HashMap<String, SkillRating>,
+}
+
+impl SkillWeights {
+    /// Builds weights from ratings that already have decay applied.
+    pub fn new(ratings: Vec<SkillRating>) -> Self {
+        Self {
+            ratings: ratings
+                .into_iter()
+                .map(|r| (r.skill.to_lowercase(), r))
+                .collect(),
+        }
+    }
+
+    /// Weight of a skill; unrated skills count fully.
+    pub fn weight(&self, skill: &str) -> f64 {
+        self.ratings
+            .get(&skill.to_lowercase())
+            .map_or(1.0, |r| (r.effective_proficiency / FULL_MATCH_PROFICIENCY).min(1.0))
+    }
+
+    /// Whether a rated skill has gone stale.
+    pub fn is_stale(&self, skill: &str) -> bool {
+        self.ratings
+            .get(&skill.to_lowercase())
+            .is_some_and(|r| r.stale)
+    }
+}
+
+/// Loads a user's skill ratings with decay applied, alphabetically.
+pub async fn load_ratings(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<SkillRating>> {
+    let today = chrono::Utc::now().date_naive();
+    let ratings = sqlx::query_as::<_, SkillRating>(
+        r#"
+        SELECT skill, proficiency, last_used_on, updated_at
+        FROM user_skill_ratings
+        WHERE user_id = $1
+        ORDER BY LOWER(skill)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(ratings.into_iter().map(|r| r.with_decay(today)).collect())
+}
+
+/// Loads the weights used to score a user's skills in matching.
+pub async fn load_weights(db_pool: &PgPool, user_id: Uuid) -> AppResult<SkillWeights> {
+    Ok(SkillWeights::new(load_ratings(db_pool, user_id).await?))
+}
+
+/// A user's progress on one skill, across all roadmaps.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SkillProgress {
+    pub skill: String,
+    pub status: SkillProgressStatus,
+    pub source: SkillProgressSource,
+    /// Roadmap whose completed phase marked the skill known
+    pub roadmap_id: Option<i32>,
+    pub known_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Loads a user's progress on every skill, alphabetically.
+pub async fn load_progress(db_pool: &PgPool, user_id: Uuid) -> AppResult<Vec<SkillProgress>> {
+    let progress = sqlx::query_as::<_, SkillProgress>(
+        r#"
+        SELECT skill, status, source, roadmap_id, known_at, updated_at
+        FROM user_skill_progress
+        WHERE user_id = $1
+        ORDER BY LOWER(skill)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(progress)
+}
+
+/// Adds `extra` skills to `skills`, skipping ones already present in any case.
+pub fn merge_skills(skills: &[String], extra: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut seen: HashSet<String> = skills.iter().map(|s| s.to_lowercase()).collect();
+    let mut merged = skills.to_vec();
+    for skill in extra {
+        if seen.insert(skill.to_lowercase()) {
+            merged.push(skill);
+        }
+    }
+    merged
+}
+
+/// Skills taught by each phase of a roadmap, by phase number.
+fn phase_skills(roadmap: &Value) -> Vec<(i64, Vec<&str>)> {
+    roadmap
+        .get("phases")
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|phase| {
+            let number = phase.get("phase")?.as_i64()?;
+            let skills = phase
+                .get("technologies")?
+                .as_array()?
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(str::trim)
+                .filter(|t| !t.is_empty() && t.chars().count() <= 100)
+                .collect();
+            Some((number, skills))
+        })
+        .collect()
+}
+
+/// Recomputes roadmap-based skill progress from all of a user's roadmaps.
+///
+/// Skills of completed phases become known and skills of the remaining
+/// phases are recorded as being learned. A skill whose completing phase
+/// was unchecked goes back to learning unless another roadmap completed it;
+/// skills from deleted roadmaps stay known. Skills set by a proficiency
+/// rating are left alone.
+pub async fn sync_roadmap_progress(db_pool: &PgPool, user_id: Uuid) -> AppResult<()> {
+    let roadmaps = sqlx::query_as::<_, (i32, Value, Option<Vec<i32>>)>(
+        "SELECT id, roadmap_data, completed_phases FROM career_roadmaps WHERE user_id = $1 ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    // Keyed by lowercase skill; the first roadmap to complete a skill keeps it
+    let mut known: HashMap<String, (String, i32)> = HashMap::new();
+    let mut learning: HashMap<String, String> = HashMap::new();
+    for (roadmap_id, roadmap, completed_phases) in &roadmaps {
+        let completed = completed_phases.as_deref().unwrap_or_default();
+        for (phase, skills) in phase_skills(roadmap) {
+            let done = completed.iter().any(|&c| c as i64 == phase);
+            for skill in skills {
+                if done {
+                    known
+                        .entry(skill.to_lowercase())
+                        .or_insert_with(|| (skill.to_string(), *roadmap_id));
+                } else {
+                    learning
+                        .entry(skill.to_lowercase())
+                        .or_insert_with(|| skill.to_string());
+                }
+            }
+        }
+    }
+    learning.retain(|key, _| !known.contains_key(key));
+
+    let known_keys: Vec<String> = known.keys().cloned().collect();
+    let (known_skills, known_roadmaps): (Vec<String>, Vec<i32>) = known.into_values().unzip();
+    let learning_skills: Vec<String> = learning.into_values().collect();
+
+    let mut tx = db_pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_skill_progress
+        SET status = 'learning', roadmap_id = NULL, known_at = NULL, updated_at = NOW()
+        WHERE user_id = $1 AND source = 'roadmap' AND status = 'known'
+          AND roadmap_id IS NOT NULL
+          AND NOT (LOWER(skill) = ANY($2))
+        "#,
+    )
+    .bind(user_id)
+    .bind(&known_keys)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_skill_progress (user_id, skill, status, source, roadmap_id, known_at)
+        SELECT $1, skill, 'known', 'roadmap', roadmap_id, NOW()
+        FROM UNNEST($2::TEXT[], $3::INTEGER[]) AS k(skill, roadmap_id)
+        ON CONFLICT (user_id, LOWER(skill)) DO UPDATE
+        SET status = 'known',
+            roadmap_id = EXCLUDED.roadmap_id,
+            known_at = COALESCE(user_skill_progress.known_at, NOW()),
+            updated_at = NOW()
+        WHERE user_skill_progress.source = 'roadmap'
+        "#,
+    )
+    .bind(user_id)
+    .bind(&known_skills)
+    .bind(&known_roadmaps)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_skill_progress (user_id, skill, status, source)
+        SELECT $1, skill, 'learning', 'roadmap' FROM UNNEST($2::TEXT[]) AS l(skill)
+        ON CONFLICT (user_id, LOWER(skill)) DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(&learning_skills)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Records a proficiency rating as skill progress: known at intermediate
+/// or above, otherwise still being learned.
+pub async fn record_rating_progress(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    skill: &str,
+    proficiency: i16,
+) -> AppResult<()> {
+    let status = if proficiency as f64 >= FULL_MATCH_PROFICIENCY {
+        SkillProgressStatus::Known
+    } else {
+        SkillProgressStatus::Learning
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_skill_progress (user_id, skill, status, source, known_at)
+        VALUES ($1, $2, $3, 'self_rating', CASE WHEN $3 = 'known' THEN NOW() END)
+        ON CONFLICT (user_id, LOWER(skill)) DO UPDATE
+        SET status = EXCLUDED.status,
+            source = 'self_rating',
+            roadmap_id = NULL,
+            known_at = CASE WHEN EXCLUDED.status = 'known'
+                            THEN COALESCE(user_skill_progress.known_at, NOW()) END,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(skill)
+    .bind(status)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
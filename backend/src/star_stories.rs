@@ -0,0 +1,374 @@
+//! STAR stories for behavioral interviews.
+//!
+//! [`mine`] reads the user's projects and CV and saves the experiences that
+//! would make good Situation-Task-Action-Result stories as drafts, tagged by
+//! the competencies they show. Each draft comes with follow-up questions;
+//! [`refine`] revises a story with the user's answers, keeping their earlier
+//! inputs so every turn builds on the last. Once a story reads well, the user
+//! saves it as polished and can pull up their stories by competency before
+//! an interview.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::ai::AIService;
+use crate::ai::types::{AIActionRequest, AIProvider, ActionType};
+use crate::errors::{AppError, AppResult};
+use crate::models::{StarCompetency, StarStoryStatus};
+
+/// Characters of the CV sent when mining stories.
+const MAX_CV_CHARS: usize = 12_000;
+
+/// A STAR story.
+#[derive(Debug, Serialize, FromRow)]
+pub struct StarStory {
+    pub id: i32,
+    pub title: String,
+    pub competencies: Vec<StarCompetency>,
+    /// The project or role the story comes from
+    pub source: Option<String>,
+    pub situation: String,
+    pub task: String,
+    pub action: String,
+    pub result: String,
+    /// Questions whose answers would strengthen the story
+    pub follow_up_questions: Vec<String>,
+    /// The user's refinement inputs, oldest first
+    pub refinements: Vec<String>,
+    pub status: StarStoryStatus,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A story after a refinement turn.
+#[derive(Debug, Serialize)]
+pub struct RefinedStory {
+    /// Whether the coach considers the story ready to tell as is
+    pub ready: bool,
+    pub story: StarStory,
+}
+
+/// Changes the user makes to a story; `None` leaves a field as it is.
+#[derive(Debug, Default)]
+pub struct StoryEdit<'a> {
+    pub title: Option<&'a str>,
+    pub competencies: Option<&'a [StarCompetency]>,
+    pub situation: Option<&'a str>,
+    pub task: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub result: Option<&'a str>,
+    pub status: Option<StarStoryStatus>,
+}
+
+/// A story as the model returns it.
+#[derive(Debug, Deserialize)]
+struct StoryDraft {
+    title: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    competencies: Vec<String>,
+    situation: String,
+    task: String,
+    action: String,
+    result: String,
+    #[serde(default)]
+    follow_up_questions: Vec<String>,
+    #[serde(default)]
+    ready: bool,
+}
+
+impl StoryDraft {
+    /// Known competencies, in order, without duplicates.
+    fn competencies(&self) -> Vec<StarCompetency> {
+        let mut competencies = Vec::new();
+        for competency in self.competencies.iter().filter_map(|c| c.parse::<StarCompetency>().ok()) {
+            if !competencies.contains(&competency) {
+                competencies.push(competency);
+            }
+        }
+        competencies
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MinedStories {
+    stories: Vec<StoryDraft>,
+}
+
+const STORY_COLUMNS: &str = r#"
+    id, title, competencies, source, situation, task, action, result,
+    follow_up_questions, refinements, status, created_at, updated_at
+"#;
+
+fn competency_names(competencies: &[StarCompetency]) -> Vec<String> {
+    competencies
+        .iter()
+        .filter_map(|c| serde_json::to_value(c).ok())
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Mines the user's projects and CV for STAR stories, optionally focusing
+/// on `focus` competencies, and saves them as drafts.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the user has neither projects nor
+/// a CV, `AppError::ExternalServiceError` if the model's stories cannot be
+/// read, or an error if the AI call or database operation fails.
+pub async fn mine(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    focus: &[StarCompetency],
+) -> AppResult<Vec<StarStory>> {
+    let (projects, cv) = sqlx::query_as::<_, (Vec<String>, Option<String>)>(
+        "SELECT projects, raw_cv_text FROM users WHERE id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    let cv = cv.map(|cv| cv.trim().chars().take(MAX_CV_CHARS).collect::<String>()).filter(|cv| !cv.is_empty());
+    if projects.is_empty() && cv.is_none() {
+        return Err(AppError::ValidationError(
+            "Add projects to your profile or upload your CV so stories can be found".to_string(),
+        ));
+    }
+
+    let mut background = Vec::new();
+    if !projects.is_empty() {
+        let list: Vec<String> = projects.iter().map(|p| format!("- {}", p)).collect();
+        background.push(format!("Projects:\n{}", list.join("\n")));
+    }
+    if let Some(cv) = cv {
+        background.push(format!("CV:\n{}", cv));
+    }
+    if !focus.is_empty() {
+        background.push(format!("Focus on these competencies: {}", competency_names(focus).join(", ")));
+    }
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::MineStarStories,
+            provider,
+            input: background.join("\n\n"),
+            parameters: None,
+            capture: true,
+        })
+        .await?;
+    let mined: MinedStories = serde_json::from_value(response.data)
+        .map_err(|e| AppError::ExternalServiceError(format!("Could not read the mined stories: {}", e)))?;
+
+    let mut tx = db_pool.begin().await?;
+    let mut stories = Vec::with_capacity(mined.stories.len());
+    for draft in &mined.stories {
+        let story = sqlx::query_as::<_, StarStory>(&format!(
+            r#"
+            INSERT INTO star_stories
+                (user_id, title, competencies, source, situation, task, action, result, follow_up_questions)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING {}
+            "#,
+            STORY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(draft.title.chars().take(255).collect::<String>())
+        .bind(draft.competencies())
+        .bind(draft.source.as_ref().map(|s| s.chars().take(255).collect::<String>()))
+        .bind(&draft.situation)
+        .bind(&draft.task)
+        .bind(&draft.action)
+        .bind(&draft.result)
+        .bind(&draft.follow_up_questions)
+        .fetch_one(&mut *tx)
+        .await?;
+        stories.push(story);
+    }
+    tx.commit().await?;
+
+    info!("Mined {} STAR stories for user {}", stories.len(), user_id);
+    Ok(stories)
+}
+
+async fn fetch(db_pool: &PgPool, user_id: Uuid, story_id: i32) -> AppResult<StarStory> {
+    sqlx::query_as::<_, StarStory>(&format!(
+        "SELECT {} FROM star_stories WHERE id = $1 AND user_id = $2",
+        STORY_COLUMNS
+    ))
+    .bind(story_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Revises one of the user's stories with their latest `input`, such as
+/// answers to the follow-up questions or feedback on the wording.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such story,
+/// `AppError::ExternalServiceError` if the revised story cannot be read, or
+/// an error if the AI call or database operation fails.
+pub async fn refine(
+    db_pool: &PgPool,
+    ai: &AIService,
+    provider: AIProvider,
+    user_id: Uuid,
+    story_id: i32,
+    input: &str,
+) -> AppResult<RefinedStory> {
+    let story = fetch(db_pool, user_id, story_id).await?;
+
+    let mut text = format!(
+        "Title: {}\nCompetencies: {}\nSituation: {}\nTask: {}\nAction: {}\nResult: {}",
+        story.title,
+        competency_names(&story.competencies).join(", "),
+        story.situation,
+        story.task,
+        story.action,
+        story.result
+    );
+    if !story.follow_up_questions.is_empty() {
+        text.push_str(&format!("\n\nQuestions you asked last time:\n- {}", story.follow_up_questions.join("\n- ")));
+    }
+    if !story.refinements.is_empty() {
+        text.push_str(&format!("\n\nThe candidate's earlier input:\n- {}", story.refinements.join("\n- ")));
+    }
+    text.push_str(&format!("\n\nThe candidate's latest input:\n{}", input));
+
+    let response = ai
+        .process_action(AIActionRequest {
+            user_id: Some(user_id),
+            action: ActionType::RefineStarStory,
+            provider,
+            input: text,
+            parameters: None,
+            capture: true,
+        })
+        .await?;
+    let draft: StoryDraft = serde_json::from_value(response.data)
+        .map_err(|e| AppError::ExternalServiceError(format!("Could not read the revised story: {}", e)))?;
+
+    let competencies = draft.competencies();
+    let story = sqlx::query_as::<_, StarStory>(&format!(
+        r#"
+        UPDATE star_stories
+        SET title = $3, competencies = CASE WHEN cardinality($4::star_competency[]) = 0 THEN competencies ELSE $4 END,
+            situation = $5, task = $6, action = $7, result = $8, follow_up_questions = $9,
+            refinements = array_append(refinements, $10), updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING {}
+        "#,
+        STORY_COLUMNS
+    ))
+    .bind(story_id)
+    .bind(user_id)
+    .bind(draft.title.chars().take(255).collect::<String>())
+    .bind(&competencies)
+    .bind(&draft.situation)
+    .bind(&draft.task)
+    .bind(&draft.action)
+    .bind(&draft.result)
+    .bind(&draft.follow_up_questions)
+    .bind(input)
+    .fetch_one(db_pool)
+    .await?;
+
+    Ok(RefinedStory {
+        ready: draft.ready,
+        story,
+    })
+}
+
+/// The user's stories, polished first, optionally only those showing a
+/// competency or with a status.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    competency: Option<StarCompetency>,
+    status: Option<StarStoryStatus>,
+) -> AppResult<Vec<StarStory>> {
+    let stories = sqlx::query_as::<_, StarStory>(&format!(
+        r#"
+        SELECT {} FROM star_stories
+        WHERE user_id = $1
+          AND ($2::star_competency IS NULL OR $2 = ANY(competencies))
+          AND ($3::star_story_status IS NULL OR status = $3)
+        ORDER BY status DESC, updated_at DESC
+        "#,
+        STORY_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(competency)
+    .bind(status)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(stories)
+}
+
+/// Edits one of the user's stories, e.g. to save it as polished.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such story, or an error
+/// if the database operation fails.
+pub async fn update(db_pool: &PgPool, user_id: Uuid, story_id: i32, edit: StoryEdit<'_>) -> AppResult<StarStory> {
+    sqlx::query_as::<_, StarStory>(&format!(
+        r#"
+        UPDATE star_stories
+        SET title = COALESCE($3, title),
+            competencies = COALESCE($4, competencies),
+            situation = COALESCE($5, situation),
+            task = COALESCE($6, task),
+            action = COALESCE($7, action),
+            result = COALESCE($8, result),
+            status = COALESCE($9, status),
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING {}
+        "#,
+        STORY_COLUMNS
+    ))
+    .bind(story_id)
+    .bind(user_id)
+    .bind(edit.title)
+    .bind(edit.competencies)
+    .bind(edit.situation)
+    .bind(edit.task)
+    .bind(edit.action)
+    .bind(edit.result)
+    .bind(edit.status)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// Deletes one of the user's stories.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no such story, or an error
+/// if the database operation fails.
+pub async fn delete(db_pool: &PgPool, user_id: Uuid, story_id: i32) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM star_stories WHERE id = $1 AND user_id = $2")
+        .bind(story_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! File storage for uploaded documents.
+//!
+//! Files are written under a root directory on local disk, addressed by a
+//! slash-separated key such as `offers/12/<uuid>.pdf`. Only the key is stored
+//! in the database, so the backend can later be swapped for object storage
+//! without a data migration.
+
+use std::path::{Path, PathBuf};
+
+use tracing::error;
+
+use crate::errors::{AppError, AppResult};
+
+/// Default storage root, relative to the working directory.
+const DEFAULT_STORAGE_DIR: &str = "storage";
+
+/// Local-disk file storage.
+///
+/// Cloning is cheap; all clones write to the same directory.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    root: PathBuf,
+}
+
+impl Storage {
+    /// Creates storage rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Creates storage configured from the environment.
+    ///
+    /// Reads `STORAGE_DIR` (default: `storage`).
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("STORAGE_DIR").unwrap_or_else(|_| DEFAULT_STORAGE_DIR.to_string()))
+    }
+
+    /// Writes `bytes` under `key`, replacing any existing file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if the key is invalid or the
+    /// file cannot be written.
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> AppResult<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                error!("Failed to create storage directory {}: {}", parent.display(), e);
+                AppError::InternalServerError
+            })?;
+        }
+        tokio::fs::write(&path, bytes).await.map_err(|e| {
+            error!("Failed to write {}: {}", path.display(), e);
+            AppError::InternalServerError
+        })
+    }
+
+    /// Reads the file stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::NotFound` if no file is stored under the key, or
+    /// `AppError::InternalServerError` if it cannot be read.
+    pub async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AppError::NotFound
+            } else {
+                error!("Failed to read {}: {}", path.display(), e);
+                AppError::InternalServerError
+            }
+        })
+    }
+
+    /// Removes the file stored under `key`; a missing file is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if the key is invalid or the
+    /// file cannot be removed.
+    pub async fn delete(&self, key: &str) -> AppResult<()> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                error!("Failed to remove {}: {}", path.display(), e);
+                Err(AppError::InternalServerError)
+            }
+        }
+    }
+
+    /// The directory files are stored under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Checks that files can be written under the root and read back, using
+    /// a probe file that is removed afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InternalServerError` if the probe cannot be
+    /// written, read back, or removed.
+    pub async fn check(&self) -> AppResult<()> {
+        const PROBE_KEY: &str = "diagnostics/probe";
+        const PROBE: &[u8] = b"careerbridge storage probe";
+
+        self.put(PROBE_KEY, PROBE).await?;
+        let read = self.get(PROBE_KEY).await?;
+        let path = self.path_for(PROBE_KEY)?;
+        tokio::fs::remove_file(&path).await.map_err(|e| {
+            error!("Failed to remove {}: {}", path.display(), e);
+            AppError::InternalServerError
+        })?;
+        if read != PROBE {
+            error!("Storage probe {} read back different bytes", path.display());
+            return Err(AppError::InternalServerError);
+        }
+        Ok(())
+    }
+
+    /// Maps a key to a path under the root, rejecting keys that could escape it.
+    fn path_for(&self, key: &str) -> AppResult<PathBuf> {
+        let valid = !key.is_empty()
+            && key.split('/').all(|part| {
+                !part.is_empty()
+                    && part != "."
+                    && part != ".."
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            });
+        if !valid {
+            error!("Rejected invalid storage key: {:?}", key);
+            return Err(AppError::InternalServerError);
+        }
+        Ok(self.root.join(Path::new(key)))
+    }
+}
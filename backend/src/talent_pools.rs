@@ -0,0 +1,604 @@
+//! Talent pools: candidates a company team saves for later.
+//!
+//! Pools belong to a company and hold candidates with tags and notes. A
+//! pool may list skills it looks for; [`check_alerts`] runs on a schedule
+//! and alerts the recruiter who saved a candidate when the candidate gains
+//! one of those skills (listed on their profile or learned in a roadmap) or
+//! gets a certification verified. Candidates are compared against what was
+//! seen at the previous check, and when they are added, so only new gains
+//! are alerted.
+//!
+//! A whole pool can be invited to apply to one of the company's open
+//! postings with [`invite`]; members who already applied are skipped.
+//! Candidates hidden by a block (see [`crate::blocks`]) are left out of
+//! pools, alerts, and invitations.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+
+/// A company's talent pool.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TalentPool {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    /// Skills the pool looks for; gaining one triggers an alert
+    pub required_skills: Vec<String>,
+    pub member_count: i64,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A candidate saved in a pool.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PoolMember {
+    pub candidate_id: Uuid,
+    pub full_name: String,
+    pub email: String,
+    pub experience_level: Option<String>,
+    pub skills: Vec<String>,
+    pub verified_certifications: i64,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub added_by: Option<Uuid>,
+    pub added_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A pool with its members.
+#[derive(Debug, Serialize)]
+pub struct PoolDetail {
+    #[serde(flatten)]
+    pub pool: TalentPool,
+    pub members: Vec<PoolMember>,
+}
+
+/// A pooled candidate gained a skill the pool looks for or a verification.
+#[derive(Debug, Serialize, FromRow)]
+pub struct PoolAlert {
+    pub id: i32,
+    pub pool_id: i32,
+    pub pool_name: String,
+    pub candidate_id: Uuid,
+    pub candidate_name: String,
+    /// `skill` or `verification`
+    pub alert_type: String,
+    pub detail: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Pool fields to set; omitted fields are left unchanged on update.
+#[derive(Debug, Default)]
+pub struct PoolChanges {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub required_skills: Option<Vec<String>>,
+}
+
+/// Result of inviting a pool to a posting.
+#[derive(Debug, Serialize)]
+pub struct PoolInvitation {
+    pub job_id: i32,
+    pub job_title: String,
+    /// Members notified
+    pub invited: usize,
+    /// Members who had already applied
+    pub already_applied: usize,
+}
+
+/// Trims, drops empty entries, and removes case-insensitive duplicates.
+fn clean_list(items: Vec<String>, lowercase: bool) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .map(|i| i.trim().to_string())
+        .map(|i| if lowercase { i.to_lowercase() } else { i })
+        .filter(|i| !i.is_empty() && seen.insert(i.to_lowercase()))
+        .collect()
+}
+
+const POOL_COLUMNS: &str = r#"
+    p.id, p.name, p.description, p.required_skills,
+    (SELECT COUNT(*) FROM talent_pool_members m WHERE m.pool_id = p.id) AS member_count,
+    p.created_by, p.created_at, p.updated_at
+"#;
+
+/// The company's pools, by name.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn list(db_pool: &PgPool, company_id: i32) -> AppResult<Vec<TalentPool>> {
+    let pools = sqlx::query_as::<_, TalentPool>(&format!(
+        "SELECT {} FROM talent_pools p WHERE p.company_id = $1 ORDER BY LOWER(p.name)",
+        POOL_COLUMNS
+    ))
+    .bind(company_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(pools)
+}
+
+async fn load(db_pool: &PgPool, company_id: i32, pool_id: i32) -> AppResult<TalentPool> {
+    sqlx::query_as::<_, TalentPool>(&format!(
+        "SELECT {} FROM talent_pools p WHERE p.id = $1 AND p.company_id = $2",
+        POOL_COLUMNS
+    ))
+    .bind(pool_id)
+    .bind(company_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+/// A pool and its members, most recently added first, optionally only
+/// those with `tag`. Candidates hidden by a block are left out.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's, or an
+/// error if the database operation fails.
+pub async fn get(db_pool: &PgPool, company_id: i32, pool_id: i32, tag: Option<&str>) -> AppResult<PoolDetail> {
+    let pool = load(db_pool, company_id, pool_id).await?;
+    let members = sqlx::query_as::<_, PoolMember>(
+        r#"
+        SELECT m.candidate_id, u.full_name, u.email, u.experience_level::text AS experience_level, u.skills,
+               (SELECT COUNT(*) FROM user_certifications c
+                WHERE c.user_id = m.candidate_id AND c.verification_status = 'verified') AS verified_certifications,
+               m.tags, m.notes, m.added_by, m.added_at, m.updated_at
+        FROM talent_pool_members m
+        JOIN users u ON u.id = m.candidate_id
+        WHERE m.pool_id = $1 AND ($2::text IS NULL OR LOWER($2) = ANY(m.tags))
+          AND NOT blocked_between(m.candidate_id, m.added_by, $3)
+        ORDER BY m.added_at DESC
+        "#,
+    )
+    .bind(pool_id)
+    .bind(tag.map(str::trim))
+    .bind(company_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(PoolDetail { pool, members })
+}
+
+/// Creates a pool.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the company already has a pool
+/// with that name, or an error if the database operation fails.
+pub async fn create(
+    db_pool: &PgPool,
+    company_id: i32,
+    user_id: Uuid,
+    name: &str,
+    description: Option<&str>,
+    required_skills: Vec<String>,
+) -> AppResult<TalentPool> {
+    let pool_id = sqlx::query_scalar::<_, i32>(
+        r#"
+        INSERT INTO talent_pools (company_id, name, description, required_skills, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (company_id, name) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(company_id)
+    .bind(name.trim())
+    .bind(description.map(str::trim).filter(|d| !d.is_empty()))
+    .bind(clean_list(required_skills, false))
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or_else(|| AppError::ValidationError(format!("A pool named \"{}\" already exists", name.trim())))?;
+    load(db_pool, company_id, pool_id).await
+}
+
+/// Updates a pool. Changing its skills does not alert on skills members
+/// already hold.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's,
+/// `AppError::ValidationError` if the new name is taken, or an error if the
+/// database operation fails.
+pub async fn update(db_pool: &PgPool, company_id: i32, pool_id: i32, changes: PoolChanges) -> AppResult<TalentPool> {
+    let name = changes.name.as_deref().map(str::trim);
+    let result = sqlx::query(
+        r#"
+        UPDATE talent_pools
+        SET name = COALESCE($3, name),
+            description = CASE WHEN $4::text IS NULL THEN description ELSE NULLIF($4, '') END,
+            required_skills = COALESCE($5, required_skills),
+            updated_at = NOW()
+        WHERE id = $1 AND company_id = $2
+        "#,
+    )
+    .bind(pool_id)
+    .bind(company_id)
+    .bind(name)
+    .bind(changes.description.as_deref().map(str::trim))
+    .bind(changes.required_skills.map(|s| clean_list(s, false)))
+    .execute(db_pool)
+    .await;
+    match result {
+        Ok(done) if done.rows_affected() == 0 => return Err(AppError::NotFound),
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(AppError::ValidationError(format!(
+                "A pool named \"{}\" already exists",
+                name.unwrap_or_default()
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    // Skills added to the pool that members already hold are not news
+    sqlx::query(
+        r#"
+        UPDATE talent_pool_members m
+        SET seen_skills = ARRAY(
+            SELECT LOWER(s) FROM unnest(p.required_skills) s
+            WHERE LOWER(s) = ANY(held_skills(m.candidate_id))
+        )
+        FROM talent_pools p
+        WHERE p.id = m.pool_id AND m.pool_id = $1
+        "#,
+    )
+    .bind(pool_id)
+    .execute(db_pool)
+    .await?;
+
+    load(db_pool, company_id, pool_id).await
+}
+
+/// Deletes a pool with its members and alerts.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's, or an
+/// error if the database operation fails.
+pub async fn delete(db_pool: &PgPool, company_id: i32, pool_id: i32) -> AppResult<()> {
+    let deleted = sqlx::query("DELETE FROM talent_pools WHERE id = $1 AND company_id = $2")
+        .bind(pool_id)
+        .bind(company_id)
+        .execute(db_pool)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Saves a candidate in a pool, or replaces their tags and notes if they
+/// are already in it. What the candidate holds now is recorded as seen.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's, the
+/// candidate is not a job seeker, or a block hides them, or an error if the
+/// database operation fails.
+pub async fn add_member(
+    db_pool: &PgPool,
+    company_id: i32,
+    pool_id: i32,
+    candidate_id: Uuid,
+    tags: Vec<String>,
+    notes: Option<&str>,
+    added_by: Uuid,
+) -> AppResult<()> {
+    load(db_pool, company_id, pool_id).await?;
+    let added = sqlx::query(
+        r#"
+        INSERT INTO talent_pool_members
+            (pool_id, candidate_id, tags, notes, added_by, seen_skills, seen_certifications)
+        SELECT $1, u.id, $3, $4, $5,
+               ARRAY(SELECT LOWER(s) FROM unnest(p.required_skills) s WHERE LOWER(s) = ANY(held_skills(u.id))),
+               ARRAY(SELECT c.id FROM user_certifications c
+                     WHERE c.user_id = u.id AND c.verification_status = 'verified')
+        FROM users u, talent_pools p
+        WHERE u.id = $2 AND u.role = 'job_seeker' AND p.id = $1
+          AND NOT blocked_between(u.id, $5, p.company_id)
+        ON CONFLICT (pool_id, candidate_id) DO UPDATE
+        SET tags = EXCLUDED.tags, notes = EXCLUDED.notes, updated_at = NOW()
+        "#,
+    )
+    .bind(pool_id)
+    .bind(candidate_id)
+    .bind(clean_list(tags, true))
+    .bind(notes.map(str::trim).filter(|n| !n.is_empty()))
+    .bind(added_by)
+    .execute(db_pool)
+    .await?;
+    if added.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Changes a member's tags and notes; omitted fields are left unchanged.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's or the
+/// candidate is not in it, or an error if the database operation fails.
+pub async fn update_member(
+    db_pool: &PgPool,
+    company_id: i32,
+    pool_id: i32,
+    candidate_id: Uuid,
+    tags: Option<Vec<String>>,
+    notes: Option<&str>,
+) -> AppResult<()> {
+    load(db_pool, company_id, pool_id).await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE talent_pool_members
+        SET tags = COALESCE($3, tags),
+            notes = CASE WHEN $4::text IS NULL THEN notes ELSE NULLIF($4, '') END,
+            updated_at = NOW()
+        WHERE pool_id = $1 AND candidate_id = $2
+        "#,
+    )
+    .bind(pool_id)
+    .bind(candidate_id)
+    .bind(tags.map(|t| clean_list(t, true)))
+    .bind(notes.map(str::trim))
+    .execute(db_pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Removes a candidate from a pool.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool is not the company's or the
+/// candidate is not in it, or an error if the database operation fails.
+pub async fn remove_member(db_pool: &PgPool, company_id: i32, pool_id: i32, candidate_id: Uuid) -> AppResult<()> {
+    let removed = sqlx::query(
+        r#"
+        DELETE FROM talent_pool_members m
+        USING talent_pools p
+        WHERE p.id = m.pool_id AND m.pool_id = $1 AND p.company_id = $2 AND m.candidate_id = $3
+        "#,
+    )
+    .bind(pool_id)
+    .bind(company_id)
+    .bind(candidate_id)
+    .execute(db_pool)
+    .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Invites every member of a pool to apply to one of the company's open
+/// postings, skipping members who already applied or are hidden by a block.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the pool or job is not the company's,
+/// `AppError::ValidationError` if the job is not open for applications, or
+/// an error if the database operation fails.
+pub async fn invite(
+    db_pool: &PgPool,
+    company_id: i32,
+    company_name: &str,
+    pool_id: i32,
+    job_id: i32,
+    message: Option<&str>,
+    sender_id: Uuid,
+) -> AppResult<PoolInvitation> {
+    load(db_pool, company_id, pool_id).await?;
+    let (job_title, open) = sqlx::query_as::<_, (String, bool)>(
+        r#"
+        SELECT job_title,
+               moderation_status = 'approved' AND closed_at IS NULL
+                   AND (expires_at IS NULL OR expires_at > NOW()) AS open
+        FROM jobs WHERE id = $1 AND company_id = $2
+        "#,
+    )
+    .bind(job_id)
+    .bind(company_id)
+    .fetch_optional(db_pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+    if !open {
+        return Err(AppError::ValidationError(
+            "The posting must be approved and open before inviting candidates".to_string(),
+        ));
+    }
+
+    let mut text = format!("{} invites you to apply for \"{}\".", company_name, job_title);
+    if let Some(note) = message.map(str::trim).filter(|m| !m.is_empty()) {
+        text.push_str(&format!(" Message: {}", note));
+    }
+    let invited = sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, title, message, type, sender_id, sender_company_id)
+        SELECT m.candidate_id, 'You''re invited to apply', $3, 'talent_pool_invitation', $4, $5
+        FROM talent_pool_members m
+        WHERE m.pool_id = $1
+          AND NOT blocked_between(m.candidate_id, $4, $5)
+          AND NOT EXISTS (
+              SELECT 1 FROM application_tracking a WHERE a.user_id = m.candidate_id AND a.job_id = $2
+          )
+        "#,
+    )
+    .bind(pool_id)
+    .bind(job_id)
+    .bind(text)
+    .bind(sender_id)
+    .bind(company_id)
+    .execute(db_pool)
+    .await?
+    .rows_affected() as usize;
+    let already_applied = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM talent_pool_members m
+        JOIN application_tracking a ON a.user_id = m.candidate_id AND a.job_id = $2
+        WHERE m.pool_id = $1
+        "#,
+    )
+    .bind(pool_id)
+    .bind(job_id)
+    .fetch_one(db_pool)
+    .await? as usize;
+
+    info!("Talent pool {} invited to job {}: {} members", pool_id, job_id, invited);
+    Ok(PoolInvitation {
+        job_id,
+        job_title,
+        invited,
+        already_applied,
+    })
+}
+
+/// The company's most recent pool alerts.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn alerts(db_pool: &PgPool, company_id: i32, limit: i64) -> AppResult<Vec<PoolAlert>> {
+    let alerts = sqlx::query_as::<_, PoolAlert>(
+        r#"
+        SELECT a.id, a.pool_id, p.name AS pool_name, a.candidate_id, u.full_name AS candidate_name,
+               a.alert_type, a.detail, a.created_at
+        FROM talent_pool_alerts a
+        JOIN talent_pools p ON p.id = a.pool_id
+        JOIN users u ON u.id = a.candidate_id
+        WHERE p.company_id = $1
+        ORDER BY a.created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(company_id)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(alerts)
+}
+
+#[derive(Debug, FromRow)]
+struct MemberCheck {
+    pool_id: i32,
+    pool_name: String,
+    candidate_id: Uuid,
+    candidate_name: String,
+    notify: Option<Uuid>,
+    seen_skills: Vec<String>,
+    seen_certifications: Vec<i32>,
+    /// Required skills (lowercase) the candidate holds now
+    held_skills: Vec<String>,
+    verified_ids: Vec<i32>,
+    verified_names: Vec<String>,
+}
+
+/// Alerts on pooled candidates who gained a skill their pool looks for or
+/// a verified certification since the last check. Returns the number of
+/// alerts raised.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn check_alerts(db_pool: &PgPool) -> AppResult<usize> {
+    let members = sqlx::query_as::<_, MemberCheck>(
+        r#"
+        SELECT m.pool_id, p.name AS pool_name, m.candidate_id, u.full_name AS candidate_name,
+               COALESCE(m.added_by, p.created_by) AS notify,
+               m.seen_skills, m.seen_certifications,
+               ARRAY(SELECT LOWER(s) FROM unnest(p.required_skills) s
+                     WHERE LOWER(s) = ANY(held_skills(m.candidate_id))) AS held_skills,
+               ARRAY(SELECT c.id FROM user_certifications c
+                     WHERE c.user_id = m.candidate_id AND c.verification_status = 'verified'
+                     ORDER BY c.id) AS verified_ids,
+               ARRAY(SELECT c.name::text FROM user_certifications c
+                     WHERE c.user_id = m.candidate_id AND c.verification_status = 'verified'
+                     ORDER BY c.id) AS verified_names
+        FROM talent_pool_members m
+        JOIN talent_pools p ON p.id = m.pool_id
+        JOIN users u ON u.id = m.candidate_id
+        WHERE NOT blocked_between(m.candidate_id, m.added_by, p.company_id)
+        "#,
+    )
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut raised = 0;
+    for member in members {
+        let mut alerts: Vec<(&str, String)> = member
+            .held_skills
+            .iter()
+            .filter(|s| !member.seen_skills.contains(s))
+            .map(|s| ("skill", format!("Now has {}", s)))
+            .collect();
+        alerts.extend(
+            member
+                .verified_ids
+                .iter()
+                .zip(&member.verified_names)
+                .filter(|(id, _)| !member.seen_certifications.contains(id))
+                .map(|(_, name)| ("verification", format!("Verified certification: {}", name))),
+        );
+        let changed = !alerts.is_empty() || member.held_skills.len() != member.seen_skills.len();
+        if !changed {
+            continue;
+        }
+
+        let mut tx = db_pool.begin().await?;
+        for (alert_type, detail) in &alerts {
+            sqlx::query(
+                "INSERT INTO talent_pool_alerts (pool_id, candidate_id, alert_type, detail) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(member.pool_id)
+            .bind(member.candidate_id)
+            .bind(alert_type)
+            .bind(detail)
+            .execute(&mut *tx)
+            .await?;
+        }
+        if let (Some(user_id), false) = (member.notify, alerts.is_empty()) {
+            let details: Vec<&str> = alerts.iter().map(|(_, d)| d.as_str()).collect();
+            sqlx::query(
+                "INSERT INTO notifications (user_id, title, message, type) VALUES ($1, $2, $3, 'talent_pool_alert')",
+            )
+            .bind(user_id)
+            .bind(format!("{} in \"{}\"", member.candidate_name, member.pool_name))
+            .bind(details.join(". "))
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            r#"
+            UPDATE talent_pool_members SET seen_skills = $3, seen_certifications = $4
+            WHERE pool_id = $1 AND candidate_id = $2
+            "#,
+        )
+        .bind(member.pool_id)
+        .bind(member.candidate_id)
+        .bind(&member.held_skills)
+        .bind(&member.verified_ids)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        raised += alerts.len();
+    }
+
+    if raised > 0 {
+        info!("Raised {} talent pool alerts", raised);
+    }
+    Ok(raised)
+}
@@ -0,0 +1,115 @@
+//! Company recruiting teams and per-seat permissions.
+//!
+//! A company groups employer users who share one job pipeline. Every member
+//! holds exactly one seat in `company_members` with a [`SeatRole`]:
+//!
+//! - `owner` - invites and removes members, changes roles, and recruits
+//! - `recruiter` - posts jobs and works the applicant pipeline
+//! - `viewer` - read-only access to the company's jobs and dashboard
+//!
+//! New members join through single-use invitations bound to an email address.
+//! Team and pipeline changes are written to `audit_logs` with the acting
+//! seat holder as `actor_id` and the team as `company_id`, so activity can be
+//! attributed per seat. Actions taken while a support admin impersonates the
+//! seat holder also record the admin in `impersonated_by`.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::SeatRole;
+
+/// Days an invitation stays valid.
+pub const INVITATION_TTL_DAYS: i64 = 7;
+
+/// Roles allowed to change the job pipeline.
+pub const PIPELINE_ROLES: &[SeatRole] = &[SeatRole::Owner, SeatRole::Recruiter];
+
+/// A user's seat on a company team.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Seat {
+    pub company_id: i32,
+    pub company_name: String,
+    pub role: SeatRole,
+}
+
+/// Returns the user's seat, if they belong to a company.
+pub async fn seat_for(db_pool: &PgPool, user_id: Uuid) -> AppResult<Option<Seat>> {
+    let seat = sqlx::query_as::<_, Seat>(
+        r#"
+        SELECT m.company_id, c.name AS company_name, m.role
+        FROM company_members m
+        JOIN companies c ON c.id = m.company_id
+        WHERE m.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(seat)
+}
+
+/// Returns the user's seat if its role is one of `allowed`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the user has no seat and
+/// `AppError::Forbidden` if the seat's role is not allowed.
+pub async fn require_seat(db_pool: &PgPool, user_id: Uuid, allowed: &[SeatRole]) -> AppResult<Seat> {
+    let seat = seat_for(db_pool, user_id).await?.ok_or(AppError::NotFound)?;
+    if allowed.contains(&seat.role) {
+        Ok(seat)
+    } else {
+        Err(AppError::Forbidden)
+    }
+}
+
+/// Generates an invitation token and the hash stored for it.
+pub fn new_invitation_token() -> (String, String) {
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let hash = hash_token(&token);
+    (token, hash)
+}
+
+/// Hex SHA-256 of an invitation token.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Records a team or pipeline action in `audit_logs`.
+///
+/// Audit entries are a side effect, so failures are logged rather than
+/// failing the request that triggered them.
+pub async fn record_activity(
+    db_pool: &PgPool,
+    actor_id: Uuid,
+    company_id: i32,
+    action: &str,
+    target_type: &str,
+    target_id: &str,
+    metadata: Value,
+) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO audit_logs (actor_id, company_id, action, target_type, target_id, metadata, impersonated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(actor_id)
+    .bind(company_id)
+    .bind(action)
+    .bind(target_type)
+    .bind(target_id)
+    .bind(&metadata)
+    .bind(crate::impersonation::current())
+    .execute(db_pool)
+    .await;
+
+    if let Err(e) = result {
+        warn!("Failed to record team activity {} by {}: {}", action, actor_id, e);
+    }
+}
@@ -0,0 +1,217 @@
+//! Per-organization configuration.
+//!
+//! An organization's `tenant_configs` row holds its career services guidance:
+//! a mentor persona added to mentor prompts and guidance added to roadmap
+//! prompts (see [`crate::ai::prompt::persona`]) for its staff and cohort
+//! students. A user in several organizations gets the guidance of the one
+//! whose configuration changed most recently.
+//!
+//! Configurations are held in memory and reloaded from the database once they
+//! are older than `TENANT_CONFIG_TTL_SECS`, and at once after an org admin
+//! edits them on this instance, so changes apply without a restart.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppResult;
+
+/// Default time before configurations are reloaded from the database.
+const DEFAULT_CONFIG_TTL_SECS: u64 = 30;
+
+/// An organization's configuration.
+#[derive(Debug, Clone, Default, Serialize, FromRow)]
+pub struct TenantConfig {
+    pub org_id: i32,
+    /// Guidance added to mentor prompts
+    pub mentor_persona: Option<String>,
+    /// Guidance added to roadmap prompts
+    pub roadmap_guidance: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Which prompts a piece of guidance is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonaKind {
+    Mentor,
+    Roadmap,
+}
+
+impl TenantConfig {
+    /// The guidance for `kind`, if set.
+    pub fn persona(&self, kind: PersonaKind) -> Option<&str> {
+        match kind {
+            PersonaKind::Mentor => self.mentor_persona.as_deref(),
+            PersonaKind::Roadmap => self.roadmap_guidance.as_deref(),
+        }
+    }
+}
+
+/// Loaded configurations, by organization.
+#[derive(Debug, Default)]
+struct ConfigSet {
+    by_org: HashMap<i32, TenantConfig>,
+    loaded_at: Option<Instant>,
+}
+
+/// In-memory tenant configurations, reloaded when stale.
+///
+/// Cloning is cheap; all clones share the same configurations.
+#[derive(Debug, Clone)]
+pub struct TenantConfigs {
+    configs: Arc<RwLock<Arc<ConfigSet>>>,
+    ttl: Duration,
+}
+
+impl TenantConfigs {
+    /// Creates an empty configuration cache, loaded on first use.
+    ///
+    /// Reads `TENANT_CONFIG_TTL_SECS` (default: 30).
+    pub fn from_env() -> Self {
+        let ttl_secs = std::env::var("TENANT_CONFIG_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CONFIG_TTL_SECS);
+
+        Self {
+            configs: Arc::new(RwLock::new(Arc::new(ConfigSet::default()))),
+            ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    /// Drops the loaded configurations so the next lookup reloads them.
+    pub async fn invalidate(&self) {
+        *self.configs.write().await = Arc::new(ConfigSet::default());
+    }
+
+    /// Current configurations, reloading them if they are stale.
+    async fn current(&self, db_pool: &PgPool) -> AppResult<Arc<ConfigSet>> {
+        {
+            let configs = self.configs.read().await;
+            if configs.loaded_at.is_some_and(|at| at.elapsed() < self.ttl) {
+                return Ok(configs.clone());
+            }
+        }
+
+        let by_org: HashMap<i32, TenantConfig> = sqlx::query_as::<_, TenantConfig>(
+            "SELECT org_id, mentor_persona, roadmap_guidance, updated_by, updated_at FROM tenant_configs",
+        )
+        .fetch_all(db_pool)
+        .await?
+        .into_iter()
+        .map(|config| (config.org_id, config))
+        .collect();
+        debug!("Loaded tenant configuration for {} organizations", by_org.len());
+        let configs = Arc::new(ConfigSet {
+            by_org,
+            loaded_at: Some(Instant::now()),
+        });
+        *self.configs.write().await = configs.clone();
+        Ok(configs)
+    }
+
+    /// Resolves the configuration in effect for `user_id`, if any of their
+    /// organizations has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memberships or configurations cannot be loaded.
+    pub async fn resolve(&self, db_pool: &PgPool, user_id: Uuid) -> AppResult<Option<TenantConfig>> {
+        let org_ids = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT org_id FROM organization_members WHERE user_id = $1
+            UNION
+            SELECT c.org_id FROM cohort_members cm JOIN cohorts c ON c.id = cm.cohort_id WHERE cm.user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+        if org_ids.is_empty() {
+            return Ok(None);
+        }
+
+        let configs = self.current(db_pool).await?;
+        Ok(org_ids
+            .iter()
+            .filter_map(|org_id| configs.by_org.get(org_id))
+            .max_by_key(|config| (config.updated_at, config.org_id))
+            .cloned())
+    }
+
+    /// The user's institution guidance for `kind`. Guidance only shapes
+    /// prompts, so failures are logged and the prompt goes without it.
+    pub async fn persona_for(&self, db_pool: &PgPool, user_id: Uuid, kind: PersonaKind) -> Option<String> {
+        match self.resolve(db_pool, user_id).await {
+            Ok(config) => config.and_then(|c| c.persona(kind).map(str::to_string)),
+            Err(e) => {
+                warn!("Could not load tenant guidance for user {}: {}", user_id, e);
+                None
+            }
+        }
+    }
+}
+
+/// An organization's configuration; empty when it has none yet.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn get(db_pool: &PgPool, org_id: i32) -> AppResult<TenantConfig> {
+    let config = sqlx::query_as::<_, TenantConfig>(
+        "SELECT org_id, mentor_persona, roadmap_guidance, updated_by, updated_at FROM tenant_configs WHERE org_id = $1",
+    )
+    .bind(org_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(config.unwrap_or(TenantConfig {
+        org_id,
+        ..TenantConfig::default()
+    }))
+}
+
+/// Saves an organization's guidance; blank text clears it. Call
+/// [`TenantConfigs::invalidate`] afterwards so this instance applies it at
+/// once.
+///
+/// # Errors
+///
+/// Returns an error if the database operation fails.
+pub async fn save(
+    db_pool: &PgPool,
+    org_id: i32,
+    updated_by: Uuid,
+    mentor_persona: Option<&str>,
+    roadmap_guidance: Option<&str>,
+) -> AppResult<TenantConfig> {
+    let clean = |text: Option<&str>| text.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+    let config = sqlx::query_as::<_, TenantConfig>(
+        r#"
+        INSERT INTO tenant_configs (org_id, mentor_persona, roadmap_guidance, updated_by, updated_at)
+        VALUES ($1, $2, $3, $4, NOW())
+        ON CONFLICT (org_id) DO UPDATE SET
+            mentor_persona = EXCLUDED.mentor_persona,
+            roadmap_guidance = EXCLUDED.roadmap_guidance,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = NOW()
+        RETURNING org_id, mentor_persona, roadmap_guidance, updated_by, updated_at
+        "#,
+    )
+    .bind(org_id)
+    .bind(clean(mentor_persona))
+    .bind(clean(roadmap_guidance))
+    .bind(updated_by)
+    .fetch_one(db_pool)
+    .await?;
+    info!("Tenant configuration of organization {} updated by {}", org_id, updated_by);
+    Ok(config)
+}
@@ -0,0 +1,65 @@
+//! Typed JSON request bodies that answer malformed input with JSON errors.
+//!
+//! Axum's `Json` extractor rejects bodies with a plain-text message. The
+//! [`ValidatedJson`] extractor reads the body the same way, but a body that
+//! is not JSON, a missing field, or a value of the wrong type becomes an
+//! [`AppError::InvalidJson`] naming the field, and a body that parses but
+//! fails its `validator` rules becomes [`AppError::UnprocessableFields`]
+//! (`422 Unprocessable Entity`).
+
+use axum::{
+    Json,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use validator::Validate;
+
+use crate::errors::AppError;
+
+/// A JSON request body deserialized into `T` and validated.
+pub struct ValidatedJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(body) = Json::<Value>::from_request(request, state).await.map_err(|rejection| {
+            AppError::InvalidJson {
+                status: rejection.status(),
+                message: rejection.body_text(),
+                field: None,
+            }
+        })?;
+
+        let payload: T = serde_path_to_error::deserialize(body).map_err(|e| {
+            let message = e.inner().to_string();
+            AppError::InvalidJson {
+                status: StatusCode::UNPROCESSABLE_ENTITY,
+                field: field_of(&e.path().to_string(), &message),
+                message,
+            }
+        })?;
+        payload.validate().map_err(AppError::UnprocessableFields)?;
+        Ok(ValidatedJson(payload))
+    }
+}
+
+/// The field a deserialization error at `path` is about. A missing field is
+/// reported at its parent, so its name is taken from the message.
+fn field_of(path: &str, message: &str) -> Option<String> {
+    let parent = Some(path).filter(|p| *p != ".");
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next());
+    match (parent, missing) {
+        (Some(parent), Some(name)) => Some(format!("{}.{}", parent, name)),
+        (None, Some(name)) => Some(name.to_string()),
+        (parent, None) => parent.map(str::to_string),
+    }
+}
@@ -0,0 +1,198 @@
+//! API versioning.
+//!
+//! Every endpoint is served under `/api/v1`, and under the unversioned
+//! `/api` prefix as a deprecated alias of v1. Responses on the alias carry
+//! `Deprecation`, `Sunset`, and `Link: rel="successor-version"` headers so
+//! clients can find and move to the versioned path.
+//!
+//! The alias keeps serving the legacy response shapes. When a response
+//! changes shape in a breaking way, the change is made for v1 and a
+//! [`Shim`] is added to [`LEGACY_SHIMS`] that turns the new JSON shape back
+//! into the old one for `/api` callers. Handlers that need to branch on the
+//! version can read the [`ApiVersion`] request extension.
+
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+/// Prefix of the current API version.
+pub const V1_PREFIX: &str = "/api/v1";
+
+/// Prefix of the deprecated unversioned alias.
+pub const LEGACY_PREFIX: &str = "/api";
+
+/// When the unversioned alias was deprecated.
+const LEGACY_DEPRECATED_ON: &str = "2026-10-17";
+
+/// Default date after which the unversioned alias may be removed.
+const DEFAULT_LEGACY_SUNSET: &str = "2027-06-30";
+
+/// Largest response body a shim will rewrite; bigger ones pass through.
+const MAX_SHIM_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Which API version a request was routed through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// The unversioned `/api` alias
+    Legacy,
+    /// `/api/v1`
+    V1,
+}
+
+/// Turns a v1 JSON response back into its legacy shape.
+pub struct Shim {
+    /// What changed, for logs
+    pub name: &'static str,
+    /// Whether the shim applies to a response
+    pub applies: fn(StatusCode, &Value) -> bool,
+    /// Rewrites the body in place
+    pub downgrade: fn(&mut Value),
+}
+
+/// Shims applied, in order, to JSON responses served on the `/api` alias.
+pub const LEGACY_SHIMS: &[Shim] = &[Shim {
+    name: "response_envelope",
+    applies: is_envelope,
+    downgrade: unwrap_envelope,
+}];
+
+/// A successful [`ApiResponse`](crate::response::ApiResponse) envelope.
+fn is_envelope(status: StatusCode, body: &Value) -> bool {
+    status.is_success()
+        && body.get("success").is_some_and(Value::is_boolean)
+        && body.get("data").is_some()
+}
+
+/// Legacy bodies were the data itself. Objects get `success` and the `meta`
+/// entries back at the top level, where the handlers that set them used to
+/// put them; other objects simply gain a `success` key.
+fn unwrap_envelope(body: &mut Value) {
+    let Value::Object(mut envelope) = body.take() else {
+        return;
+    };
+    let data = envelope.remove("data").unwrap_or(Value::Null);
+    *body = match data {
+        Value::Object(data) => {
+            let mut legacy = serde_json::Map::new();
+            legacy.insert("success".to_string(), envelope.remove("success").unwrap_or(Value::Bool(true)));
+            if let Some(Value::Object(meta)) = envelope.remove("meta") {
+                legacy.extend(meta);
+            }
+            legacy.extend(data);
+            Value::Object(legacy)
+        }
+        data => data,
+    };
+}
+
+/// Headers announcing the alias's deprecation.
+#[derive(Debug, Clone)]
+pub struct LegacyApi {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+}
+
+impl LegacyApi {
+    /// Reads the sunset date (`YYYY-MM-DD`) from `LEGACY_API_SUNSET`,
+    /// falling back to 2027-06-30.
+    pub fn from_env() -> Self {
+        let sunset = std::env::var("LEGACY_API_SUNSET")
+            .ok()
+            .and_then(|v| parse_date(&v))
+            .or_else(|| parse_date(DEFAULT_LEGACY_SUNSET))
+            .unwrap_or_default();
+        let deprecated = parse_date(LEGACY_DEPRECATED_ON).unwrap_or_default();
+
+        // RFC 9745 structured date, and an RFC 8594 HTTP-date
+        Self {
+            deprecation: HeaderValue::from_str(&format!("@{}", deprecated.timestamp()))
+                .unwrap_or(HeaderValue::from_static("true")),
+            sunset: HeaderValue::from_str(&sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+                .unwrap_or(HeaderValue::from_static("")),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+}
+
+/// Middleware for `/api/v1` routes.
+pub async fn v1(mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(ApiVersion::V1);
+    next.run(request).await
+}
+
+/// Middleware for the `/api` alias: applies the legacy shims and adds the
+/// deprecation headers.
+pub async fn legacy(State(config): State<Arc<LegacyApi>>, mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(ApiVersion::Legacy);
+    // Nested routers see the path without the prefix
+    let successor = format!("{}{}", V1_PREFIX, request.uri().path());
+
+    let mut response = downgrade(next.run(request).await).await;
+
+    let headers = response.headers_mut();
+    headers.insert("Deprecation", config.deprecation.clone());
+    headers.insert("Sunset", config.sunset.clone());
+    if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor)) {
+        headers.append(header::LINK, link);
+    }
+    response
+}
+
+/// Applies [`LEGACY_SHIMS`] to a JSON response.
+async fn downgrade(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let fits = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= MAX_SHIM_BODY_BYTES as u64);
+    if LEGACY_SHIMS.is_empty() || !is_json || !fits {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_SHIM_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Could not buffer response for legacy shims: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let mut changed = false;
+    for shim in LEGACY_SHIMS {
+        if (shim.applies)(parts.status, &value) {
+            (shim.downgrade)(&mut value);
+            debug!("Applied legacy shim {}", shim.name);
+            changed = true;
+        }
+    }
+    if !changed {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(value.to_string()))
+}
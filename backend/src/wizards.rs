@@ -0,0 +1,163 @@
+//! Resumable multi-step wizards.
+//!
+//! A wizard walks a user through a fixed sequence of steps, such as the CV
+//! review: upload → extract → confirm skills → generate summary → build
+//! resume. The client runs each step (calling the AI endpoints as needed)
+//! and stores the step's status and output on the wizard, so the flow can be
+//! resumed on another device without repeating AI calls.
+//!
+//! A step can only start once every earlier step is completed or skipped.
+//! Changing a finished step's output, or reopening it, resets the later
+//! steps to `pending`, since their outputs were derived from it.
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{WizardKind, WizardStepStatus};
+
+/// Steps of the CV review wizard, in order.
+pub const CV_REVIEW_STEPS: &[&str] = &[
+    "upload",
+    "extract",
+    "confirm_skills",
+    "generate_summary",
+    "build_resume",
+];
+
+/// Largest output stored for one step, in bytes of JSON.
+pub const MAX_OUTPUT_BYTES: usize = 256 * 1024;
+
+/// Returns the steps of a wizard kind, in order.
+pub fn steps_for(kind: WizardKind) -> &'static [&'static str] {
+    match kind {
+        WizardKind::CvReview => CV_REVIEW_STEPS,
+    }
+}
+
+/// One step of a wizard.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WizardStep {
+    #[serde(skip)]
+    pub wizard_id: i32,
+    /// Step name (e.g. `extract`)
+    pub step: String,
+    /// Position in the flow, starting at 0
+    pub position: i16,
+    pub status: WizardStepStatus,
+    /// Intermediate result stored by the client
+    pub output: Option<sqlx::types::Json<Value>>,
+    /// Why the step failed
+    pub error: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the step changed and needs to be saved
+    #[sqlx(skip)]
+    #[serde(skip)]
+    pub changed: bool,
+}
+
+/// A wizard with its steps.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Wizard {
+    pub id: i32,
+    pub kind: WizardKind,
+    /// First step not yet completed or skipped; `None` once finished
+    #[sqlx(skip)]
+    pub current_step: Option<String>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[sqlx(skip)]
+    pub steps: Vec<WizardStep>,
+}
+
+impl Wizard {
+    /// Attaches steps (ordered by position) and derives the current step.
+    pub fn with_steps(mut self, steps: Vec<WizardStep>) -> Self {
+        self.current_step = current_step(&steps).map(str::to_string);
+        self.steps = steps;
+        self
+    }
+}
+
+/// Returns the first step that is not completed or skipped.
+pub fn current_step(steps: &[WizardStep]) -> Option<&str> {
+    steps
+        .iter()
+        .find(|s| !s.status.is_done())
+        .map(|s| s.step.as_str())
+}
+
+/// Applies a client's update to one step, marking changed steps.
+///
+/// `output` of `None` keeps the stored output, unless the step is reset to
+/// `pending`, which clears it. `error` is only kept on failed steps.
+///
+/// # Errors
+///
+/// Returns `AppError::ValidationError` if the step does not exist, an
+/// earlier step is not yet completed or skipped, or the output is too large.
+pub fn apply_update(
+    steps: &mut [WizardStep],
+    step: &str,
+    status: WizardStepStatus,
+    output: Option<Value>,
+    error: Option<String>,
+) -> AppResult<()> {
+    let index = steps.iter().position(|s| s.step == step).ok_or_else(|| {
+        let names: Vec<&str> = steps.iter().map(|s| s.step.as_str()).collect();
+        AppError::ValidationError(format!(
+            "Unknown step '{}'; steps are {}",
+            step,
+            names.join(", ")
+        ))
+    })?;
+
+    if status != WizardStepStatus::Pending
+        && let Some(blocking) = steps[..index].iter().find(|s| !s.status.is_done())
+    {
+        return Err(AppError::ValidationError(format!(
+            "Complete or skip '{}' before '{}'",
+            blocking.step, step
+        )));
+    }
+
+    if let Some(output) = &output {
+        let size = serde_json::to_vec(output).map(|b| b.len()).unwrap_or(0);
+        if size > MAX_OUTPUT_BYTES {
+            return Err(AppError::ValidationError(format!(
+                "Output for '{}' is too large ({} bytes; the limit is {})",
+                step, size, MAX_OUTPUT_BYTES
+            )));
+        }
+    }
+
+    let target = &mut steps[index];
+    let was_done = target.status.is_done();
+    let output_changed = output
+        .as_ref()
+        .is_some_and(|new| target.output.as_ref().map(|old| &old.0) != Some(new));
+
+    target.status = status;
+    if status == WizardStepStatus::Pending {
+        target.output = None;
+    } else if let Some(output) = output {
+        target.output = Some(sqlx::types::Json(output));
+    }
+    target.error = if status == WizardStepStatus::Failed { error } else { None };
+    target.changed = true;
+
+    // Later steps were built from this step's old result
+    if was_done && (output_changed || !status.is_done()) {
+        for later in steps[index + 1..].iter_mut() {
+            if later.status != WizardStepStatus::Pending || later.output.is_some() {
+                later.status = WizardStepStatus::Pending;
+                later.output = None;
+                later.error = None;
+                later.changed = true;
+            }
+        }
+    }
+    Ok(())
+}
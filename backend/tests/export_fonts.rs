@@ -0,0 +1,150 @@
+//! Multilingual PDF exports: CVs in Bengali, Arabic, and Chinese are
+//! rendered through the export path handlers use, and the PDFs checked for
+//! the fonts that cover each script and for glyphs that are not `.notdef`
+//! (the empty box of a missing character).
+//!
+//! The default DejaVu fonts cover Arabic. Bengali and Chinese need fallback
+//! fonts: those in `EXPORT_FONT_FALLBACKS`, or else the Noto Sans Bengali and
+//! Droid Sans Fallback files Debian and Ubuntu install. A script no
+//! available font covers is skipped.
+
+use std::path::Path;
+
+use backend::export::{self, Block, Document, ExportFormat, Typeface};
+use pdf_extract::content::Content;
+use pdf_extract::{Document as Pdf, Object};
+use ttf_parser::Face;
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+const FALLBACKS: &[&str] = &[
+    "/usr/share/fonts/truetype/noto/NotoSansBengali-Regular.ttf",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+];
+
+fn cv(name: &str, heading: &str, summary: &str, skills: &[&str]) -> Document {
+    Document {
+        title: name.to_string(),
+        subtitle: Some("cv@example.com".to_string()),
+        blocks: vec![
+            Block::Heading(heading.to_string()),
+            Block::Paragraph(summary.to_string()),
+            Block::List(skills.iter().map(|s| s.to_string()).collect()),
+        ],
+    }
+}
+
+/// The first of `fonts` with a glyph for every letter of `text`.
+fn covering_font<'a>(fonts: &[&'a str], text: &str) -> Option<&'a str> {
+    fonts.iter().copied().find(|path| {
+        std::fs::read(path).is_ok_and(|data| {
+            Face::parse(&data, 0).is_ok_and(|face| {
+                text.chars().filter(|c| c.is_alphabetic()).all(|c| face.glyph_index(c).is_some())
+            })
+        })
+    })
+}
+
+/// Embedded font files of `pdf`.
+fn embedded_fonts(pdf: &Pdf) -> Vec<Vec<u8>> {
+    pdf.objects
+        .values()
+        .filter_map(|object| object.as_dict().ok()?.get(b"FontFile2").ok()?.as_reference().ok())
+        .filter_map(|id| Some(pdf.get_object(id).ok()?.as_stream().ok()?.content.clone()))
+        .collect()
+}
+
+/// Glyph IDs written by the text operators of `pdf`, which uses two-byte
+/// Identity-H encoded fonts.
+fn glyph_ids(pdf: &Pdf) -> Vec<u16> {
+    let mut glyphs = Vec::new();
+    for page in pdf.get_pages().into_values() {
+        let content = Content::decode(&pdf.get_page_content(page).unwrap()).unwrap();
+        for operation in content.operations.iter().filter(|op| op.operator == "Tj" || op.operator == "TJ") {
+            let strings = operation.operands.iter().flat_map(|operand| match operand {
+                Object::Array(items) => items.iter().collect(),
+                other => vec![other],
+            });
+            for string in strings {
+                if let Object::String(bytes, _) = string {
+                    glyphs.extend(bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])));
+                }
+            }
+        }
+    }
+    glyphs
+}
+
+#[tokio::test]
+async fn multilingual_cvs_embed_fonts_with_their_glyphs() {
+    if !Path::new(DEJAVU_SANS).exists() && std::env::var("EXPORT_FONT_SANS").is_err() {
+        eprintln!("DejaVu Sans is not installed and EXPORT_FONT_SANS is unset; skipping");
+        return;
+    }
+    let configured = std::env::var("EXPORT_FONT_FALLBACKS").ok();
+    let fallbacks: Vec<String> = match &configured {
+        Some(paths) => paths.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect(),
+        None => FALLBACKS.iter().filter(|p| Path::new(p).exists()).map(|p| p.to_string()).collect(),
+    };
+    if configured.is_none() {
+        // Fonts are read on the first PDF, which this test renders
+        unsafe { std::env::set_var("EXPORT_FONT_FALLBACKS", fallbacks.join(",")) };
+    }
+    let regular = std::env::var("EXPORT_FONT_SANS").unwrap_or_else(|_| DEJAVU_SANS.to_string());
+    let fonts: Vec<&str> = std::iter::once(regular.as_str()).chain(fallbacks.iter().map(String::as_str)).collect();
+
+    let cvs = [
+        (
+            "Bengali",
+            cv(
+                "রহিম উদ্দিন",
+                "দক্ষতা",
+                "ঢাকার একজন ডেটা বিশ্লেষক, পাঁচ বছরের অভিজ্ঞতা",
+                &["পাইথন প্রোগ্রামিং", "তথ্য বিশ্লেষণ"],
+            ),
+        ),
+        (
+            "Arabic",
+            cv(
+                "أحمد الخطيب",
+                "المهارات",
+                "مهندس برمجيات من عمّان بخبرة ست سنوات",
+                &["تطوير الواجهات", "قواعد البيانات"],
+            ),
+        ),
+        (
+            "Chinese",
+            cv("王小明", "技能", "上海的软件工程师，拥有五年经验", &["后端开发", "数据分析"]),
+        ),
+    ];
+
+    let mut checked = 0;
+    for (script, document) in cvs {
+        let body = match &document.blocks[1] {
+            Block::Paragraph(text) => text.clone(),
+            _ => unreachable!(),
+        };
+        let Some(font) = covering_font(&fonts, &body) else {
+            eprintln!("No font covers {}; set EXPORT_FONT_FALLBACKS to test it", script);
+            continue;
+        };
+
+        let pdf = export::render_in_background(document, ExportFormat::Pdf, Typeface::Sans)
+            .await
+            .unwrap_or_else(|e| panic!("{} CV failed to render: {}", script, e));
+        let pdf = Pdf::load_mem(&pdf.body).unwrap();
+
+        let expected = std::fs::read(font).unwrap();
+        assert!(
+            embedded_fonts(&pdf).contains(&expected),
+            "{} CV does not embed {}",
+            script,
+            font
+        );
+        let glyphs = glyph_ids(&pdf);
+        assert!(!glyphs.is_empty(), "{} CV has no text", script);
+        assert!(!glyphs.contains(&0), "{} CV has .notdef glyphs", script);
+        checked += 1;
+    }
+    assert!(checked > 0, "no script could be checked");
+}
@@ -0,0 +1,95 @@
+//! Checkout webhooks for paid mentor sessions, run against the migrated
+//! database at `DATABASE_URL`. Skipped when it is unset.
+
+use backend::mentor_payments;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn db_pool() -> Option<PgPool> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    Some(PgPool::connect(&url).await.expect("DATABASE_URL is reachable"))
+}
+
+async fn create_user(db_pool: &PgPool, name: &str) -> Uuid {
+    sqlx::query_scalar("INSERT INTO users (full_name, email) VALUES ($1, $2) RETURNING id")
+        .bind(name)
+        .bind(format!("{}@example.com", Uuid::new_v4()))
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn failed_refund_for_cancelled_booking_is_left_for_the_webhook_retry() {
+    let Some(db_pool) = db_pool().await else {
+        eprintln!("DATABASE_URL is not set; skipping");
+        return;
+    };
+    let mentor_id = create_user(&db_pool, "Test Mentor").await;
+    let mentee_id = create_user(&db_pool, "Test Mentee").await;
+    sqlx::query("INSERT INTO mentor_profiles (user_id, headline, session_price_cents) VALUES ($1, 'Mentor', 5000)")
+        .bind(mentor_id)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+    let slot_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mentor_slots (mentor_id, starts_at, ends_at) VALUES ($1, NOW() + INTERVAL '2 days', NOW() + INTERVAL '2 days 30 minutes') RETURNING id",
+    )
+    .bind(mentor_id)
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+    // The mentee cancelled while the checkout page was still open
+    let booking_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mentor_bookings (slot_id, mentor_id, mentee_id, topic, status) VALUES ($1, $2, $3, 'Interviews', 'cancelled') RETURNING id",
+    )
+    .bind(slot_id)
+    .bind(mentor_id)
+    .bind(mentee_id)
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+    let session_id = format!("cs_test_{}", Uuid::new_v4().simple());
+    sqlx::query(
+        r#"
+        INSERT INTO mentor_payments
+            (booking_id, mentor_id, mentee_id, amount_cents, platform_fee_cents, currency, status, stripe_checkout_session_id)
+        VALUES ($1, $2, $3, 5000, 750, 'usd', 'expired', $4)
+        "#,
+    )
+    .bind(booking_id)
+    .bind(mentor_id)
+    .bind(mentee_id)
+    .bind(&session_id)
+    .execute(&db_pool)
+    .await
+    .unwrap();
+
+    // Without billing configured the refund fails
+    let session = json!({ "id": session_id, "payment_status": "paid", "payment_intent": "pi_test" });
+    let result = mentor_payments::checkout_completed(&db_pool, None, &session).await;
+    assert!(result.is_err());
+
+    // The payment is untouched, so the retried webhook picks it up again
+    let (status, refunded_cents, intent): (String, i32, Option<String>) = sqlx::query_as(
+        "SELECT status::TEXT, refunded_cents, stripe_payment_intent_id FROM mentor_payments WHERE booking_id = $1",
+    )
+    .bind(booking_id)
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+    assert_eq!((status.as_str(), refunded_cents, intent), ("expired", 0, None));
+    let booking_status: String = sqlx::query_scalar("SELECT status::TEXT FROM mentor_bookings WHERE id = $1")
+        .bind(booking_id)
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+    assert_eq!(booking_status, "cancelled");
+
+    sqlx::query("DELETE FROM users WHERE id = ANY($1)")
+        .bind(vec![mentor_id, mentee_id])
+        .execute(&db_pool)
+        .await
+        .unwrap();
+}